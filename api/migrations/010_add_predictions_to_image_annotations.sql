@@ -0,0 +1,11 @@
+-- Add model prediction fields to image_annotations
+ALTER TABLE image_annotations
+ADD COLUMN is_prediction BOOLEAN NOT NULL DEFAULT FALSE,
+ADD COLUMN confidence FLOAT;
+
+-- Create index for finding unreviewed suggestions
+CREATE INDEX idx_image_annotations_is_prediction ON image_annotations(is_prediction) WHERE is_prediction;
+
+-- Add comments for documentation
+COMMENT ON COLUMN image_annotations.is_prediction IS 'True for model suggestions that have not been accepted by an annotator';
+COMMENT ON COLUMN image_annotations.confidence IS 'Model confidence score (0.0 - 1.0) for predicted annotations';
@@ -0,0 +1,21 @@
+-- Create project_models table for optional ONNX auto-annotation
+CREATE TABLE project_models (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL UNIQUE REFERENCES projects(id) ON DELETE CASCADE,
+    model_url TEXT NOT NULL,
+    input_width INTEGER NOT NULL DEFAULT 640,
+    input_height INTEGER NOT NULL DEFAULT 640,
+    class_names TEXT[] NOT NULL DEFAULT '{}',
+    score_threshold FLOAT NOT NULL DEFAULT 0.25,
+    iou_threshold FLOAT NOT NULL DEFAULT 0.45,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Create index for project lookups
+CREATE INDEX idx_project_models_project_id ON project_models(project_id);
+
+-- Add comments for documentation
+COMMENT ON TABLE project_models IS 'ONNX detection model used to pre-annotate tasks of a project';
+COMMENT ON COLUMN project_models.model_url IS 'Location of the .onnx file (storage:// key in project storage or http(s) URL)';
+COMMENT ON COLUMN project_models.class_names IS 'Category name for each model output class, in model class order';
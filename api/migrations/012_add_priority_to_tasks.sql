@@ -0,0 +1,8 @@
+-- Add active-learning priority score to tasks
+ALTER TABLE tasks ADD COLUMN priority FLOAT;
+
+-- Create index for priority ordering within a project
+CREATE INDEX idx_tasks_project_priority ON tasks(project_id, priority DESC NULLS LAST);
+
+-- Add comments for documentation
+COMMENT ON COLUMN tasks.priority IS 'Uploaded per-task score (e.g. model uncertainty); higher scores are labeled first';
@@ -0,0 +1,16 @@
+-- Create task_assignments table for assigning blind copies of a task to several annotators
+CREATE TABLE task_assignments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    assigned_by UUID REFERENCES users(id),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    UNIQUE(task_id, user_id)
+);
+
+-- Create indexes for better performance
+CREATE INDEX idx_task_assignments_task_id ON task_assignments(task_id);
+CREATE INDEX idx_task_assignments_user_id ON task_assignments(user_id);
+
+-- Add comments for documentation
+COMMENT ON TABLE task_assignments IS 'Annotators assigned to a task; each assignee labels an independent (blind) copy';
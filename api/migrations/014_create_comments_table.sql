@@ -0,0 +1,29 @@
+-- Create comments table for review discussions on tasks
+CREATE TABLE comments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    parent_id UUID REFERENCES comments(id) ON DELETE CASCADE,
+    author_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+
+    -- Optional anchor on a bounding box, stored by value since annotations are versioned
+    anchor_bbox FLOAT[],
+
+    -- Users mentioned with @email in the body
+    mentions UUID[] NOT NULL DEFAULT '{}',
+
+    resolved BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+
+    CONSTRAINT check_anchor_bbox_format CHECK (anchor_bbox IS NULL OR array_length(anchor_bbox, 1) = 4)
+);
+
+-- Create indexes for better performance
+CREATE INDEX idx_comments_task_id ON comments(task_id);
+CREATE INDEX idx_comments_parent_id ON comments(parent_id);
+CREATE INDEX idx_comments_mentions ON comments USING GIN(mentions);
+
+-- Add comments for documentation
+COMMENT ON TABLE comments IS 'Reviewer feedback on tasks, optionally anchored to a bounding box';
+COMMENT ON COLUMN comments.anchor_bbox IS 'COCO [x, y, width, height] of the box the comment refers to';
@@ -0,0 +1,14 @@
+-- Add issue flags to tasks so annotators can report problematic images
+ALTER TABLE tasks ADD COLUMN flag_reason VARCHAR(50);
+ALTER TABLE tasks ADD COLUMN flag_note TEXT;
+ALTER TABLE tasks ADD COLUMN flagged_by UUID REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE tasks ADD COLUMN flagged_at TIMESTAMP WITH TIME ZONE;
+
+ALTER TABLE tasks ADD CONSTRAINT check_flag_reason
+    CHECK (flag_reason IS NULL OR flag_reason IN ('corrupted', 'wrong_dataset', 'cant_tell', 'other'));
+
+-- Create index for filtering flagged tasks within a project
+CREATE INDEX idx_tasks_project_flag_reason ON tasks(project_id, flag_reason) WHERE flag_reason IS NOT NULL;
+
+-- Add comments for documentation
+COMMENT ON COLUMN tasks.flag_reason IS 'Reason code of an open issue flag; flagged tasks are skipped by the next-task queue';
@@ -0,0 +1,131 @@
+-- Create project templates table for predefined category sets
+CREATE TABLE project_templates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+
+    -- Array of {"name", "supercategory", "color", "coco_id"} objects
+    categories JSONB NOT NULL DEFAULT '[]'::jsonb,
+
+    -- NULL for built-in templates
+    created_by UUID REFERENCES users(id) ON DELETE CASCADE,
+
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+
+    UNIQUE(created_by, name)
+);
+
+-- Create indexes for project_templates
+CREATE INDEX idx_project_templates_created_by ON project_templates(created_by);
+
+-- Seed built-in templates
+INSERT INTO project_templates (name, description, categories) VALUES
+('COCO-80', 'The 80 object categories of MS COCO with their original category IDs', '[
+        {"name": "person", "supercategory": "person", "color": null, "coco_id": 1},
+        {"name": "bicycle", "supercategory": "vehicle", "color": null, "coco_id": 2},
+        {"name": "car", "supercategory": "vehicle", "color": null, "coco_id": 3},
+        {"name": "motorcycle", "supercategory": "vehicle", "color": null, "coco_id": 4},
+        {"name": "airplane", "supercategory": "vehicle", "color": null, "coco_id": 5},
+        {"name": "bus", "supercategory": "vehicle", "color": null, "coco_id": 6},
+        {"name": "train", "supercategory": "vehicle", "color": null, "coco_id": 7},
+        {"name": "truck", "supercategory": "vehicle", "color": null, "coco_id": 8},
+        {"name": "boat", "supercategory": "vehicle", "color": null, "coco_id": 9},
+        {"name": "traffic light", "supercategory": "outdoor", "color": null, "coco_id": 10},
+        {"name": "fire hydrant", "supercategory": "outdoor", "color": null, "coco_id": 11},
+        {"name": "stop sign", "supercategory": "outdoor", "color": null, "coco_id": 13},
+        {"name": "parking meter", "supercategory": "outdoor", "color": null, "coco_id": 14},
+        {"name": "bench", "supercategory": "outdoor", "color": null, "coco_id": 15},
+        {"name": "bird", "supercategory": "animal", "color": null, "coco_id": 16},
+        {"name": "cat", "supercategory": "animal", "color": null, "coco_id": 17},
+        {"name": "dog", "supercategory": "animal", "color": null, "coco_id": 18},
+        {"name": "horse", "supercategory": "animal", "color": null, "coco_id": 19},
+        {"name": "sheep", "supercategory": "animal", "color": null, "coco_id": 20},
+        {"name": "cow", "supercategory": "animal", "color": null, "coco_id": 21},
+        {"name": "elephant", "supercategory": "animal", "color": null, "coco_id": 22},
+        {"name": "bear", "supercategory": "animal", "color": null, "coco_id": 23},
+        {"name": "zebra", "supercategory": "animal", "color": null, "coco_id": 24},
+        {"name": "giraffe", "supercategory": "animal", "color": null, "coco_id": 25},
+        {"name": "backpack", "supercategory": "accessory", "color": null, "coco_id": 27},
+        {"name": "umbrella", "supercategory": "accessory", "color": null, "coco_id": 28},
+        {"name": "handbag", "supercategory": "accessory", "color": null, "coco_id": 31},
+        {"name": "tie", "supercategory": "accessory", "color": null, "coco_id": 32},
+        {"name": "suitcase", "supercategory": "accessory", "color": null, "coco_id": 33},
+        {"name": "frisbee", "supercategory": "sports", "color": null, "coco_id": 34},
+        {"name": "skis", "supercategory": "sports", "color": null, "coco_id": 35},
+        {"name": "snowboard", "supercategory": "sports", "color": null, "coco_id": 36},
+        {"name": "sports ball", "supercategory": "sports", "color": null, "coco_id": 37},
+        {"name": "kite", "supercategory": "sports", "color": null, "coco_id": 38},
+        {"name": "baseball bat", "supercategory": "sports", "color": null, "coco_id": 39},
+        {"name": "baseball glove", "supercategory": "sports", "color": null, "coco_id": 40},
+        {"name": "skateboard", "supercategory": "sports", "color": null, "coco_id": 41},
+        {"name": "surfboard", "supercategory": "sports", "color": null, "coco_id": 42},
+        {"name": "tennis racket", "supercategory": "sports", "color": null, "coco_id": 43},
+        {"name": "bottle", "supercategory": "kitchen", "color": null, "coco_id": 44},
+        {"name": "wine glass", "supercategory": "kitchen", "color": null, "coco_id": 46},
+        {"name": "cup", "supercategory": "kitchen", "color": null, "coco_id": 47},
+        {"name": "fork", "supercategory": "kitchen", "color": null, "coco_id": 48},
+        {"name": "knife", "supercategory": "kitchen", "color": null, "coco_id": 49},
+        {"name": "spoon", "supercategory": "kitchen", "color": null, "coco_id": 50},
+        {"name": "bowl", "supercategory": "kitchen", "color": null, "coco_id": 51},
+        {"name": "banana", "supercategory": "food", "color": null, "coco_id": 52},
+        {"name": "apple", "supercategory": "food", "color": null, "coco_id": 53},
+        {"name": "sandwich", "supercategory": "food", "color": null, "coco_id": 54},
+        {"name": "orange", "supercategory": "food", "color": null, "coco_id": 55},
+        {"name": "broccoli", "supercategory": "food", "color": null, "coco_id": 56},
+        {"name": "carrot", "supercategory": "food", "color": null, "coco_id": 57},
+        {"name": "hot dog", "supercategory": "food", "color": null, "coco_id": 58},
+        {"name": "pizza", "supercategory": "food", "color": null, "coco_id": 59},
+        {"name": "donut", "supercategory": "food", "color": null, "coco_id": 60},
+        {"name": "cake", "supercategory": "food", "color": null, "coco_id": 61},
+        {"name": "chair", "supercategory": "furniture", "color": null, "coco_id": 62},
+        {"name": "couch", "supercategory": "furniture", "color": null, "coco_id": 63},
+        {"name": "potted plant", "supercategory": "furniture", "color": null, "coco_id": 64},
+        {"name": "bed", "supercategory": "furniture", "color": null, "coco_id": 65},
+        {"name": "dining table", "supercategory": "furniture", "color": null, "coco_id": 67},
+        {"name": "toilet", "supercategory": "furniture", "color": null, "coco_id": 70},
+        {"name": "tv", "supercategory": "electronic", "color": null, "coco_id": 72},
+        {"name": "laptop", "supercategory": "electronic", "color": null, "coco_id": 73},
+        {"name": "mouse", "supercategory": "electronic", "color": null, "coco_id": 74},
+        {"name": "remote", "supercategory": "electronic", "color": null, "coco_id": 75},
+        {"name": "keyboard", "supercategory": "electronic", "color": null, "coco_id": 76},
+        {"name": "cell phone", "supercategory": "electronic", "color": null, "coco_id": 77},
+        {"name": "microwave", "supercategory": "appliance", "color": null, "coco_id": 78},
+        {"name": "oven", "supercategory": "appliance", "color": null, "coco_id": 79},
+        {"name": "toaster", "supercategory": "appliance", "color": null, "coco_id": 80},
+        {"name": "sink", "supercategory": "appliance", "color": null, "coco_id": 81},
+        {"name": "refrigerator", "supercategory": "appliance", "color": null, "coco_id": 82},
+        {"name": "book", "supercategory": "indoor", "color": null, "coco_id": 84},
+        {"name": "clock", "supercategory": "indoor", "color": null, "coco_id": 85},
+        {"name": "vase", "supercategory": "indoor", "color": null, "coco_id": 86},
+        {"name": "scissors", "supercategory": "indoor", "color": null, "coco_id": 87},
+        {"name": "teddy bear", "supercategory": "indoor", "color": null, "coco_id": 88},
+        {"name": "hair drier", "supercategory": "indoor", "color": null, "coco_id": 89},
+        {"name": "toothbrush", "supercategory": "indoor", "color": null, "coco_id": 90}
+    ]'::jsonb),
+('Vehicles', 'Road traffic: vehicle types, pedestrians and license plates', '[
+        {"name": "car", "supercategory": "vehicle", "color": "#E6194B", "coco_id": 1},
+        {"name": "truck", "supercategory": "vehicle", "color": "#3CB44B", "coco_id": 2},
+        {"name": "bus", "supercategory": "vehicle", "color": "#FFE119", "coco_id": 3},
+        {"name": "motorcycle", "supercategory": "vehicle", "color": "#4363D8", "coco_id": 4},
+        {"name": "bicycle", "supercategory": "vehicle", "color": "#F58231", "coco_id": 5},
+        {"name": "van", "supercategory": "vehicle", "color": "#911EB4", "coco_id": 6},
+        {"name": "trailer", "supercategory": "vehicle", "color": "#42D4F4", "coco_id": 7},
+        {"name": "pedestrian", "supercategory": "person", "color": "#F032E6", "coco_id": 8},
+        {"name": "license plate", "supercategory": "vehicle part", "color": "#BFEF45", "coco_id": 9}
+    ]'::jsonb),
+('Retail', 'Store shelves: products, price tags and shelf fixtures', '[
+        {"name": "product", "supercategory": "item", "color": "#E6194B", "coco_id": 1},
+        {"name": "price tag", "supercategory": "label", "color": "#3CB44B", "coco_id": 2},
+        {"name": "shelf", "supercategory": "fixture", "color": "#4363D8", "coco_id": 3},
+        {"name": "empty shelf space", "supercategory": "fixture", "color": "#F58231", "coco_id": 4},
+        {"name": "promotional sign", "supercategory": "label", "color": "#911EB4", "coco_id": 5},
+        {"name": "shopping cart", "supercategory": "equipment", "color": "#42D4F4", "coco_id": 6},
+        {"name": "shopping basket", "supercategory": "equipment", "color": "#F032E6", "coco_id": 7},
+        {"name": "person", "supercategory": "person", "color": "#FFE119", "coco_id": 8}
+    ]'::jsonb);
+
+-- Add comments for documentation
+COMMENT ON TABLE project_templates IS 'Predefined category sets that can be applied when creating a project';
+COMMENT ON COLUMN project_templates.categories IS 'Category definitions copied into image_annotation_categories on project creation';
+COMMENT ON COLUMN project_templates.created_by IS 'Owner of a user-defined template, NULL for built-in templates';
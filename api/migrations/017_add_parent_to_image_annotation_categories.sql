@@ -0,0 +1,12 @@
+-- Add parent/child relationships between image annotation categories
+ALTER TABLE image_annotation_categories
+    ADD COLUMN parent_id UUID REFERENCES image_annotation_categories(id) ON DELETE SET NULL;
+
+ALTER TABLE image_annotation_categories ADD CONSTRAINT check_parent_not_self
+    CHECK (parent_id IS NULL OR parent_id <> id);
+
+-- Create index for looking up children of a category
+CREATE INDEX idx_image_annotation_categories_parent_id ON image_annotation_categories(parent_id);
+
+-- Add comments for documentation
+COMMENT ON COLUMN image_annotation_categories.parent_id IS 'Parent category in the class hierarchy, NULL for root categories';
@@ -0,0 +1,10 @@
+-- Add custom attribute schemas to categories and attribute values to annotations
+ALTER TABLE image_annotation_categories
+    ADD COLUMN attribute_schema JSONB NOT NULL DEFAULT '[]';
+
+ALTER TABLE image_annotations
+    ADD COLUMN attributes JSONB NOT NULL DEFAULT '{}';
+
+-- Add comments for documentation
+COMMENT ON COLUMN image_annotation_categories.attribute_schema IS 'Attribute definitions for annotations of this category: [{name, type (bool|enum|string|number), options, required, default}]';
+COMMENT ON COLUMN image_annotations.attributes IS 'Attribute values keyed by name, validated against the category attribute_schema';
@@ -0,0 +1,6 @@
+-- Add rotation to image annotations for oriented bounding boxes
+ALTER TABLE image_annotations
+    ADD COLUMN rotation FLOAT NOT NULL DEFAULT 0;
+
+-- Add comments for documentation
+COMMENT ON COLUMN image_annotations.rotation IS 'Clockwise rotation in degrees around the bbox center (image coordinates); 0 for axis-aligned boxes';
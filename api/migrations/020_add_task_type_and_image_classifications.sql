@@ -0,0 +1,22 @@
+-- Add task type to projects and whole-image labels for classification projects
+ALTER TABLE projects
+    ADD COLUMN task_type VARCHAR(50) NOT NULL DEFAULT 'detection'
+    CHECK (task_type IN ('detection', 'classification'));
+
+-- Create table for image-level labels, one row per label of an annotation
+CREATE TABLE image_classifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    annotation_id UUID NOT NULL REFERENCES annotations(id) ON DELETE CASCADE,
+    category_id UUID NOT NULL REFERENCES image_annotation_categories(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+
+    UNIQUE(annotation_id, category_id)
+);
+
+-- Create indexes for better performance
+CREATE INDEX idx_image_classifications_annotation_id ON image_classifications(annotation_id);
+CREATE INDEX idx_image_classifications_category_id ON image_classifications(category_id);
+
+-- Add comments for documentation
+COMMENT ON COLUMN projects.task_type IS 'What tasks are annotated with: detection (bounding boxes) or classification (whole-image labels)';
+COMMENT ON TABLE image_classifications IS 'Whole-image category labels of an annotation in classification projects';
@@ -0,0 +1,32 @@
+-- Add video tasks with extracted frames and frame-indexed boxes
+ALTER TABLE tasks
+    ADD COLUMN media_type VARCHAR(20) NOT NULL DEFAULT 'image'
+    CHECK (media_type IN ('image', 'video')),
+    ADD COLUMN frame_count INTEGER,
+    ADD COLUMN frame_rate FLOAT;
+
+-- Create table for the frames extracted from video tasks
+CREATE TABLE task_frames (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    frame_index INTEGER NOT NULL CHECK (frame_index >= 0),
+    resource_url TEXT NOT NULL,
+    timestamp_ms BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+
+    UNIQUE(task_id, frame_index)
+);
+
+ALTER TABLE image_annotations
+    ADD COLUMN frame_index INTEGER;
+
+-- Create indexes for better performance
+CREATE INDEX idx_task_frames_task_id ON task_frames(task_id);
+CREATE INDEX idx_image_annotations_frame_index ON image_annotations(frame_index) WHERE frame_index IS NOT NULL;
+
+-- Add comments for documentation
+COMMENT ON COLUMN tasks.media_type IS 'image for still images, video for tasks whose frames are stored in task_frames';
+COMMENT ON COLUMN tasks.frame_count IS 'Number of extracted frames of a video task';
+COMMENT ON COLUMN tasks.frame_rate IS 'Frames per second the video was sampled at during extraction';
+COMMENT ON TABLE task_frames IS 'Still frames extracted from a video task, stored next to the video';
+COMMENT ON COLUMN image_annotations.frame_index IS 'Frame of a video task the box is drawn on; NULL for still images';
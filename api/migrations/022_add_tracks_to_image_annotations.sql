@@ -0,0 +1,11 @@
+-- Add track IDs to image annotations so boxes of a video can be interpolated between keyframes
+ALTER TABLE image_annotations
+    ADD COLUMN track_id UUID,
+    ADD COLUMN is_interpolated BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Create indexes for better performance
+CREATE INDEX idx_image_annotations_track_id ON image_annotations(track_id) WHERE track_id IS NOT NULL;
+
+-- Add comments for documentation
+COMMENT ON COLUMN image_annotations.track_id IS 'Object followed across the frames of a video task; boxes sharing it belong to one track';
+COMMENT ON COLUMN image_annotations.is_interpolated IS 'TRUE for boxes generated between two keyframes of a track, FALSE for boxes drawn by an annotator';
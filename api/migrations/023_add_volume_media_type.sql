@@ -0,0 +1,8 @@
+-- Allow slice stacks (multi-page TIFF, DICOM) as multi-frame tasks next to videos
+ALTER TABLE tasks
+    DROP CONSTRAINT tasks_media_type_check,
+    ADD CONSTRAINT tasks_media_type_check CHECK (media_type IN ('image', 'video', 'volume'));
+
+-- Add comments for documentation
+COMMENT ON COLUMN tasks.media_type IS 'image for still images, video or volume for tasks whose frames (or slices) are stored in task_frames';
+COMMENT ON COLUMN image_annotations.frame_index IS 'Frame of a video task or slice of a volume task the box is drawn on; NULL for still images';
@@ -0,0 +1,6 @@
+-- Mark tasks whose image has a deep-zoom tile pyramid next to it in storage
+ALTER TABLE tasks
+    ADD COLUMN tiled BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Add comments for documentation
+COMMENT ON COLUMN tasks.tiled IS 'Whether tiles of the image are stored under <key>.tiles/<level>/<col>_<row>.jpg and served by the tiles endpoints';
@@ -0,0 +1,9 @@
+-- Let projects bind a keyboard shortcut to each category
+ALTER TABLE image_annotation_categories
+    ADD COLUMN hotkey VARCHAR(32);
+
+-- Create indexes for better performance
+CREATE UNIQUE INDEX idx_image_annotation_categories_project_hotkey ON image_annotation_categories(project_id, hotkey) WHERE hotkey IS NOT NULL;
+
+-- Add comments for documentation
+COMMENT ON COLUMN image_annotation_categories.hotkey IS 'Key that picks the category while labeling, as a key code name such as Digit1 or KeyQ; unique within a project';
@@ -0,0 +1,8 @@
+-- Add a dataset split to tasks so they can be grouped into train, validation and test sets
+ALTER TABLE tasks ADD COLUMN split VARCHAR(16);
+
+ALTER TABLE tasks ADD CONSTRAINT check_split
+    CHECK (split IS NULL OR split IN ('train', 'val', 'test'));
+
+-- Add comments for documentation
+COMMENT ON COLUMN tasks.split IS 'Dataset split of the task: train, val or test; NULL when not assigned to one';
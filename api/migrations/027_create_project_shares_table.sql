@@ -0,0 +1,17 @@
+-- Create table for read-only share links of projects
+CREATE TABLE project_shares (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    token VARCHAR(64) NOT NULL UNIQUE,
+    name VARCHAR(255),
+    created_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Create indexes for better performance
+CREATE INDEX idx_project_shares_project_id ON project_shares(project_id);
+
+-- Add comments for documentation
+COMMENT ON TABLE project_shares IS 'Expiring links that let anyone with the token read the tasks, annotations and exports of a project';
+COMMENT ON COLUMN project_shares.token IS 'Secret part of the share URL, 64 random hex characters';
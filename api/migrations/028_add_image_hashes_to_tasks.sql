@@ -0,0 +1,10 @@
+-- Add image hashes to tasks so duplicates within a project can be found
+ALTER TABLE tasks
+    ADD COLUMN content_hash VARCHAR(64),
+    ADD COLUMN perceptual_hash BIGINT;
+
+CREATE INDEX idx_tasks_content_hash ON tasks(project_id, content_hash) WHERE content_hash IS NOT NULL;
+
+-- Add comments for documentation
+COMMENT ON COLUMN tasks.content_hash IS 'SHA-256 of the file in hex, equal for byte-identical files; NULL for tasks not created by a sync';
+COMMENT ON COLUMN tasks.perceptual_hash IS '64-bit difference hash of the image, close in Hamming distance for near-identical images';
@@ -0,0 +1,8 @@
+-- Add a review mark to tasks so quality control can sample annotated tasks for a second look
+ALTER TABLE tasks ADD COLUMN review_requested_at TIMESTAMP WITH TIME ZONE;
+
+-- Create index for listing the tasks waiting for review within a project
+CREATE INDEX idx_tasks_project_review_requested ON tasks(project_id) WHERE review_requested_at IS NOT NULL;
+
+-- Add comments for documentation
+COMMENT ON COLUMN tasks.review_requested_at IS 'When a quality-control sample marked the task for review; NULL when no review is pending';
@@ -0,0 +1,23 @@
+-- Add gold tasks: tasks with a reference annotation every annotator's copy is scored against
+ALTER TABLE tasks ADD COLUMN gold_annotation_id UUID REFERENCES annotations(id) ON DELETE SET NULL;
+
+-- Create gold_scores table for the accuracy of annotators on gold tasks
+CREATE TABLE gold_scores (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    annotation_id UUID NOT NULL REFERENCES annotations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    mean_iou DOUBLE PRECISION NOT NULL,
+    accuracy DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    UNIQUE(annotation_id)
+);
+
+-- Create indexes for better performance
+CREATE INDEX idx_tasks_project_gold ON tasks(project_id) WHERE gold_annotation_id IS NOT NULL;
+CREATE INDEX idx_gold_scores_task_id ON gold_scores(task_id);
+CREATE INDEX idx_gold_scores_user_id ON gold_scores(user_id);
+
+-- Add comments for documentation
+COMMENT ON COLUMN tasks.gold_annotation_id IS 'Reference annotation of a gold (honeypot) task; NULL for regular tasks';
+COMMENT ON TABLE gold_scores IS 'Agreement of an annotation on a gold task with the reference, written when the annotation is saved';
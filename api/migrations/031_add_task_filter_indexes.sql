@@ -0,0 +1,3 @@
+-- Indexes for filtering tasks by whether they were annotated and by the categories of their latest annotation
+CREATE INDEX idx_annotations_task_id_created_at ON annotations(task_id, created_at DESC);
+CREATE INDEX idx_image_annotations_category_annotation ON image_annotations(category_id, annotation_id) WHERE NOT is_prediction;
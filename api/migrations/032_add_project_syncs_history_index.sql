@@ -0,0 +1,2 @@
+-- Index for the sync history of a project, newest first
+CREATE INDEX idx_project_syncs_project_started_at ON project_syncs(project_id, started_at DESC);
@@ -0,0 +1,21 @@
+-- Create table for named export settings of a project
+CREATE TABLE export_presets (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    format VARCHAR(50) NOT NULL,
+    options JSONB NOT NULL DEFAULT '{}'::jsonb,
+    is_default BOOLEAN NOT NULL DEFAULT FALSE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    UNIQUE(project_id, name)
+);
+
+-- At most one default preset per project
+CREATE UNIQUE INDEX idx_export_presets_project_default ON export_presets(project_id) WHERE is_default;
+
+-- Add comments for documentation
+COMMENT ON TABLE export_presets IS 'Saved export settings so the same export can be run again without picking every option';
+COMMENT ON COLUMN export_presets.options IS 'Query parameters of the export endpoint: include_images, include_metadata, splits';
+COMMENT ON COLUMN export_presets.is_default IS 'Preset the export section of the app starts with';
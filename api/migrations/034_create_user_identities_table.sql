@@ -0,0 +1,33 @@
+-- Sign-in providers of each user, so one account can be reached from Google and GitHub
+CREATE TABLE user_identities (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    provider VARCHAR(50) NOT NULL,
+    provider_id VARCHAR(255) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    UNIQUE(provider, provider_id),
+    UNIQUE(user_id, provider)
+);
+
+CREATE INDEX idx_user_identities_user_id ON user_identities(user_id);
+
+-- Every existing account signs in with the provider it was created with
+INSERT INTO user_identities (user_id, provider, provider_id, email)
+SELECT id, provider, provider_id, email FROM users
+ON CONFLICT DO NOTHING;
+
+-- Logins started from an account link the provider to it instead of signing in
+ALTER TABLE pending_auths ADD COLUMN link_user_id UUID REFERENCES users(id) ON DELETE CASCADE;
+
+-- Deleted accounts leave their work in the projects that remain
+ALTER TABLE annotations
+    DROP CONSTRAINT annotations_annotated_by_fkey,
+    ADD CONSTRAINT annotations_annotated_by_fkey FOREIGN KEY (annotated_by) REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE task_assignments
+    DROP CONSTRAINT task_assignments_assigned_by_fkey,
+    ADD CONSTRAINT task_assignments_assigned_by_fkey FOREIGN KEY (assigned_by) REFERENCES users(id) ON DELETE SET NULL;
+
+-- Add comments for documentation
+COMMENT ON TABLE user_identities IS 'OAuth accounts a user signs in with, users.provider is the one the account was created with';
+COMMENT ON COLUMN pending_auths.link_user_id IS 'User the provider gets linked to when the login completes, NULL for a plain login';
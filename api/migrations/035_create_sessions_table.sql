@@ -0,0 +1,27 @@
+-- Sign-ins of each user, one per device, so the access of a single device can be revoked
+CREATE TABLE sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    refresh_token_hash VARCHAR(64) NOT NULL UNIQUE,
+    device_name VARCHAR(255),
+    user_agent TEXT,
+    ip_address VARCHAR(64),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX idx_sessions_user_id ON sessions(user_id);
+
+-- Logins hand the refresh token out with the JWT, and remember the device they were started on
+ALTER TABLE pending_auths
+    ADD COLUMN refresh_token VARCHAR(64),
+    ADD COLUMN device_name VARCHAR(255),
+    ADD COLUMN completed_at TIMESTAMP WITH TIME ZONE;
+
+-- Add comments for documentation
+COMMENT ON TABLE sessions IS 'Issued JWT and refresh token pairs, JWTs carry the session id and stop working once it is revoked';
+COMMENT ON COLUMN sessions.refresh_token_hash IS 'SHA-256 of the current refresh token, replaced on every refresh';
+COMMENT ON COLUMN sessions.user_agent IS 'Browser the login was completed in';
+COMMENT ON COLUMN pending_auths.completed_at IS 'Set when the login completed, logins that link a provider complete without a JWT';
@@ -0,0 +1,4 @@
+-- Projects that reject boxes leaving the image instead of clipping them
+ALTER TABLE projects ADD COLUMN strict_bounds BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN projects.strict_bounds IS 'Reject bounding boxes outside the image dimensions of the task instead of clipping them';
@@ -0,0 +1,4 @@
+-- Rules every saved set of boxes has to follow, kept as one object so rules can be added later
+ALTER TABLE projects ADD COLUMN labeling_rules JSONB NOT NULL DEFAULT '{}';
+
+COMMENT ON COLUMN projects.labeling_rules IS 'Minimum box size, maximum boxes per image and required categories, checked when annotations are saved';
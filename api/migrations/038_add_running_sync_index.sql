@@ -0,0 +1,7 @@
+-- Syncs run in the background, a project runs one at a time. Runs left 'running' by a
+-- stopped server would never finish, so they are failed first.
+UPDATE project_syncs
+SET status = 'failed', errors = '["Interrupted by a server restart"]'::jsonb, completed_at = NOW()
+WHERE status = 'running';
+
+CREATE UNIQUE INDEX idx_project_syncs_one_running ON project_syncs(project_id) WHERE status = 'running';
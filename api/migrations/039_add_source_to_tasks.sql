@@ -0,0 +1,16 @@
+-- Remember which file in storage each task was made from, so a changed file can be detected
+ALTER TABLE tasks
+    ADD COLUMN source_key TEXT GENERATED ALWAYS AS (
+        CASE WHEN resource_url LIKE 'storage://%' THEN substring(resource_url FROM 11) END
+    ) STORED,
+    ADD COLUMN source_etag TEXT,
+    ADD COLUMN source_size BIGINT,
+    ADD COLUMN stale_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX idx_tasks_stale ON tasks(project_id) WHERE stale_at IS NOT NULL;
+
+-- Add comments for documentation
+COMMENT ON COLUMN tasks.source_key IS 'Storage key of the file behind a storage:// resource_url, NULL for other URLs';
+COMMENT ON COLUMN tasks.source_etag IS 'ETag of the source file when the task was created from it, or when its file was last accepted';
+COMMENT ON COLUMN tasks.source_size IS 'Size in bytes of the source file when the task was created from it, or when its file was last accepted';
+COMMENT ON COLUMN tasks.stale_at IS 'When a refresh found the source file changed or gone; NULL while the file matches the task';
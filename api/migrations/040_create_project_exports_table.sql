@@ -0,0 +1,24 @@
+-- Record exports written to storage, running or done, so they can be followed and found again
+CREATE TABLE project_exports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    format VARCHAR(50) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'running',
+    storage_key TEXT,
+    bucket TEXT,
+    location TEXT,
+    content_type TEXT,
+    size_bytes BIGINT,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX idx_project_exports_project ON project_exports(project_id, created_at DESC);
+
+-- Add comments for documentation
+COMMENT ON TABLE project_exports IS 'Exports written to storage, the ones run in the background included';
+COMMENT ON COLUMN project_exports.status IS 'running, completed or failed';
+COMMENT ON COLUMN project_exports.storage_key IS 'Object key of the export file once completed';
+COMMENT ON COLUMN project_exports.bucket IS 'Other bucket the export went to, NULL for the project storage';
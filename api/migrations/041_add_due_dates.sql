@@ -0,0 +1,10 @@
+-- Due dates of tasks, and the deadline settings of labeling campaigns
+ALTER TABLE tasks ADD COLUMN due_at TIMESTAMP WITH TIME ZONE;
+
+ALTER TABLE projects ADD COLUMN deadline_settings JSONB NOT NULL DEFAULT '{}';
+
+CREATE INDEX idx_tasks_due ON tasks(project_id, due_at) WHERE due_at IS NOT NULL;
+
+-- Add comments for documentation
+COMMENT ON COLUMN tasks.due_at IS 'When the task has to be done, NULL to follow the deadline settings of the project';
+COMMENT ON COLUMN projects.deadline_settings IS 'Deadline of the project and the days new tasks get, for tasks without their own due date';
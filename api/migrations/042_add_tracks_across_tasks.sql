@@ -0,0 +1,2 @@
+-- Tracks also link the boxes of an image sequence, where every frame is a task of its own
+COMMENT ON COLUMN image_annotations.track_id IS 'Object followed across the frames of a video task or the tasks of an image sequence; boxes sharing it belong to one track';
@@ -0,0 +1,4 @@
+-- Archived projects are read-only and left out of the project list by default
+ALTER TABLE projects ADD COLUMN archived_at TIMESTAMP WITH TIME ZONE;
+
+COMMENT ON COLUMN projects.archived_at IS 'When the project was archived; archived projects refuse every write until unarchived';
@@ -0,0 +1,4 @@
+-- Pixel masks of the objects, imported from the run-length encoded segmentations of COCO files
+ALTER TABLE image_annotations ADD COLUMN mask JSONB;
+
+COMMENT ON COLUMN image_annotations.mask IS 'COCO RLE mask {"size": [height, width], "counts": "..."} with compressed counts, NULL for plain boxes';
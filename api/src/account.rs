@@ -0,0 +1,926 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+use crate::auth::{JwtManager, Claims, User, UserInfoResponse, AuthStorage, PROVIDERS};
+use crate::storage::config::StorageConfig;
+use crate::storage::factory::create_storage_provider;
+
+/// Largest avatar image accepted
+const MAX_AVATAR_BYTES: usize = 2 * 1024 * 1024;
+/// Folder of the avatar storage the images go to, one subfolder per user
+const AVATAR_PREFIX: &str = "avatars";
+
+/// Where uploaded avatars are kept, a storage configuration in the format of the project ones,
+/// e.g. `{"type": "local", "base_path": "./avatars"}`. Uploads are disabled when unset.
+#[derive(Debug, Clone, Default)]
+pub struct AvatarConfig {
+    pub storage: Option<StorageConfig>,
+}
+
+impl AvatarConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let storage = match std::env::var("AVATAR_STORAGE").ok().filter(|value| !value.trim().is_empty()) {
+            Some(value) => {
+                let config: StorageConfig = serde_json::from_str(&value)
+                    .map_err(|e| format!("AVATAR_STORAGE is not a storage configuration: {}", e))?;
+                config.validate()?;
+                Some(config)
+            }
+            None => None,
+        };
+        Ok(Self { storage })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateProfileRequest {
+    /// Name shown to the other members of your projects
+    pub name: String,
+}
+
+/// The updated account, with a token carrying the new name
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProfileResponse {
+    pub user: User,
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct LinkedProvider {
+    pub provider: String,
+    /// Email of the provider account
+    pub email: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LinkedProvidersResponse {
+    pub providers: Vec<LinkedProvider>,
+    /// Every provider accounts can be linked from
+    pub available: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OwnershipTransfer {
+    pub project_id: Uuid,
+    /// A member of the project who becomes its owner
+    pub new_owner_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeleteAccountRequest {
+    /// Email of the account, typed again to confirm the deletion
+    pub confirm_email: String,
+    /// New owners for the owned projects other members work in. Owned projects without other
+    /// members are deleted with the account.
+    #[serde(default)]
+    pub transfers: Vec<OwnershipTransfer>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct SharedProject {
+    pub id: Uuid,
+    pub name: String,
+    pub other_members: i64,
+}
+
+/// Answer to a deletion that would leave projects of other members without an owner
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AccountDeletionBlocked {
+    pub message: String,
+    /// Owned projects that need a new owner first
+    pub projects: Vec<SharedProject>,
+}
+
+enum AccountDeletion {
+    Deleted {
+        avatar_url: Option<String>,
+        /// Projects the user was a member of, and their members
+        project_ids: Vec<Uuid>,
+        member_ids: Vec<Uuid>,
+        /// Projects the user owned alone, deleted with the account
+        deleted_project_ids: Vec<Uuid>,
+    },
+    Blocked(Vec<SharedProject>),
+    InvalidTransfer(String),
+}
+
+enum Unlink {
+    Unlinked,
+    NotLinked,
+    LastProvider,
+}
+
+#[utoipa::path(
+    put,
+    path = "/me",
+    tag = "auth",
+    request_body = UpdateProfileRequest,
+    responses(
+        (status = 200, body = ProfileResponse),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "User not found", body = String),
+    ),
+)]
+pub async fn update_profile(
+    req: HttpRequest,
+    payload: web::Json<UpdateProfileRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return HttpResponse::BadRequest().json("Name cannot be empty");
+    }
+    if name.len() > 255 {
+        return HttpResponse::BadRequest().json("Name too long (max 255 characters)");
+    }
+
+    let user = match update_user_name(&pool, user_id, name).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return HttpResponse::NotFound().json("User not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to update profile"),
+    };
+
+    // Tokens carry the name, so the old one would keep showing the previous name
+    let session_id = claims.sid.as_deref().and_then(|sid| Uuid::parse_str(sid).ok());
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.generate_session_token(&user.id.to_string(), &user.email, &user.name, session_id) {
+        Ok(token) => HttpResponse::Ok().json(ProfileResponse { user, token }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to generate token"),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/me/avatar",
+    tag = "auth",
+    request_body(content = [u8], content_type = "application/octet-stream", description = "PNG, JPEG, WebP or GIF image of at most 2 MiB"),
+    responses(
+        (status = 200, body = UserInfoResponse),
+        (status = 400, description = "Not a supported image, or too large", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 503, description = "Avatar uploads are not configured", body = String),
+    ),
+)]
+pub async fn upload_avatar(
+    req: HttpRequest,
+    payload: web::Bytes,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+    avatar_config: web::Data<AvatarConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let Some(storage_config) = &avatar_config.storage else {
+        return HttpResponse::ServiceUnavailable().json("Avatar uploads are not configured");
+    };
+
+    if payload.len() > MAX_AVATAR_BYTES {
+        return HttpResponse::BadRequest().json(format!("Avatar too large (max {} bytes)", MAX_AVATAR_BYTES));
+    }
+    let (extension, content_type) = match image::guess_format(&payload) {
+        Ok(format @ (image::ImageFormat::Png | image::ImageFormat::Jpeg | image::ImageFormat::WebP | image::ImageFormat::Gif)) => {
+            (format.extensions_str()[0], format.to_mime_type())
+        }
+        _ => return HttpResponse::BadRequest().json("Avatar must be a PNG, JPEG, WebP or GIF image"),
+    };
+
+    let storage = match create_storage_provider(storage_config).await {
+        Ok(storage) => storage,
+        Err(e) => return HttpResponse::InternalServerError().json(format!("Storage error: {}", e)),
+    };
+
+    // A new key per upload, so clients holding the previous image notice the change
+    let key = format!("{}/{}/{}.{}", AVATAR_PREFIX, user_id, Uuid::new_v4(), extension);
+    if let Err(e) = storage.upload(&key, &payload, Some(content_type)).await {
+        return HttpResponse::InternalServerError().json(format!("Upload failed: {}", e));
+    }
+
+    match set_user_avatar(&pool, user_id, Some(&format!("storage://{}", key))).await {
+        Ok(Some((user, previous))) => {
+            delete_stored_avatar(&avatar_config, previous.as_deref()).await;
+            HttpResponse::Ok().json(UserInfoResponse { user })
+        }
+        Ok(None) => HttpResponse::NotFound().json("User not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to update avatar"),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/me/avatar",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Account without an avatar", body = UserInfoResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "User not found", body = String),
+    ),
+)]
+pub async fn delete_avatar(
+    req: HttpRequest,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+    avatar_config: web::Data<AvatarConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    match set_user_avatar(&pool, user_id, None).await {
+        Ok(Some((user, previous))) => {
+            delete_stored_avatar(&avatar_config, previous.as_deref()).await;
+            HttpResponse::Ok().json(UserInfoResponse { user })
+        }
+        Ok(None) => HttpResponse::NotFound().json("User not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to remove avatar"),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/avatar",
+    tag = "auth",
+    params(("user_id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "Uploaded avatar image", body = [u8], content_type = "image/*"),
+        (status = 302, description = "Redirect to the avatar of the sign-in provider"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "User has no avatar", body = String),
+    ),
+)]
+pub async fn get_user_avatar(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+    avatar_config: web::Data<AvatarConfig>,
+) -> impl Responder {
+    if let Err(response) = extract_user_claims(&req, &config) {
+        return response;
+    }
+
+    let user_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let avatar_url = match crate::auth::get_user_by_id(&pool, user_id).await {
+        Ok(Some(user)) => user.avatar_url,
+        Ok(None) => return HttpResponse::NotFound().json("User not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Database error"),
+    };
+
+    match avatar_url.as_deref().map(|url| url.strip_prefix("storage://").ok_or(url)) {
+        Some(Ok(key)) => {
+            let Some(storage_config) = &avatar_config.storage else {
+                return HttpResponse::NotFound().json("Avatar not available");
+            };
+            let storage = match create_storage_provider(storage_config).await {
+                Ok(storage) => storage,
+                Err(e) => return HttpResponse::InternalServerError().json(format!("Storage error: {}", e)),
+            };
+            match storage.download(key).await {
+                Ok(data) => {
+                    let content_type = image::guess_format(&data)
+                        .map(|format| format.to_mime_type())
+                        .unwrap_or("application/octet-stream");
+                    HttpResponse::Ok()
+                        .content_type(content_type)
+                        .insert_header(("Cache-Control", "private, max-age=3600"))
+                        .body(data)
+                }
+                Err(crate::storage::StorageError::NotFound) => HttpResponse::NotFound().json("Avatar not available"),
+                Err(e) => HttpResponse::InternalServerError().json(format!("Download failed: {}", e)),
+            }
+        }
+        // Pictures of the sign-in provider stay where they are
+        Some(Err(url)) => HttpResponse::Found().insert_header(("Location", url)).finish(),
+        None => HttpResponse::NotFound().json("User has no avatar"),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/me",
+    tag = "auth",
+    request_body = DeleteAccountRequest,
+    responses(
+        (status = 204, description = "Account and its personal data deleted"),
+        (status = 400, description = "Email does not match, or invalid transfer", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "User not found", body = String),
+        (status = 409, description = "Owned projects of other members need a new owner", body = AccountDeletionBlocked),
+    ),
+)]
+pub async fn delete_account(
+    req: HttpRequest,
+    payload: web::Json<DeleteAccountRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+    avatar_config: web::Data<AvatarConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let user = match crate::auth::get_user_by_id(&pool, user_id).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return HttpResponse::NotFound().json("User not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Database error"),
+    };
+
+    if !payload.confirm_email.trim().eq_ignore_ascii_case(&user.email) {
+        return HttpResponse::BadRequest().json("Email does not match the account");
+    }
+
+    match delete_account_from_db(&pool, user_id, &payload.transfers).await {
+        Ok(AccountDeletion::Deleted { avatar_url, project_ids, member_ids, deleted_project_ids }) => {
+            delete_stored_avatar(&avatar_config, avatar_url.as_deref()).await;
+            crate::cache::invalidate_user_projects(&member_ids).await;
+            // The user left every project, and the ones with other members changed owner
+            for project_id in project_ids {
+                if deleted_project_ids.contains(&project_id) {
+                    crate::cache::invalidate_project(project_id).await;
+                } else {
+                    crate::cache::invalidate_access(project_id).await;
+                }
+            }
+            HttpResponse::NoContent().finish()
+        }
+        Ok(AccountDeletion::Blocked(projects)) => HttpResponse::Conflict().json(AccountDeletionBlocked {
+            message: "Transfer the ownership of these projects to one of their members first".to_string(),
+            projects,
+        }),
+        Ok(AccountDeletion::InvalidTransfer(e)) => HttpResponse::BadRequest().json(e),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to delete account"),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/me/providers",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Providers the account signs in with", body = LinkedProvidersResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+    ),
+)]
+pub async fn list_linked_providers(
+    req: HttpRequest,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    match get_linked_providers(&pool, user_id).await {
+        Ok(providers) => HttpResponse::Ok().json(LinkedProvidersResponse {
+            providers,
+            available: PROVIDERS.iter().map(|provider| provider.to_string()).collect(),
+        }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch linked providers"),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/me/providers/{provider}",
+    tag = "auth",
+    params(("provider" = String, Path, description = "`google` or `github`")),
+    responses(
+        (status = 200, description = "URL to sign in to the provider account with, which then gets linked", body = crate::auth::AuthUrlResponse),
+        (status = 400, description = "Unknown provider", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+    ),
+)]
+pub async fn link_provider(
+    req: HttpRequest,
+    path: web::Path<String>,
+    config: web::Data<crate::auth::OAuthConfig>,
+    auth_storage: web::Data<AuthStorage>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    crate::auth::start_login(&config, &auth_storage, &path.into_inner(), Some(user_id), None).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/me/providers/{provider}",
+    tag = "auth",
+    params(("provider" = String, Path, description = "`google` or `github`")),
+    responses(
+        (status = 204, description = "Provider unlinked"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Provider not linked", body = String),
+        (status = 409, description = "The only provider left to sign in with", body = String),
+    ),
+)]
+pub async fn unlink_provider(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    match unlink_provider_from_db(&pool, user_id, &path.into_inner()).await {
+        Ok(Unlink::Unlinked) => HttpResponse::NoContent().finish(),
+        Ok(Unlink::NotLinked) => HttpResponse::NotFound().json("Provider not linked"),
+        Ok(Unlink::LastProvider) => HttpResponse::Conflict().json("Link another provider before removing the last one"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to unlink provider"),
+    }
+}
+
+async fn update_user_name(pool: &Pool<Postgres>, user_id: Uuid, name: &str) -> Result<Option<User>, sqlx::Error> {
+    sqlx::query_as::<_, User>(
+        r#"
+        UPDATE users SET name = $2, updated_at = NOW()
+        WHERE id = $1
+        RETURNING id, email, name, avatar_url, provider, provider_id, created_at, updated_at
+        "#
+    )
+    .bind(user_id)
+    .bind(name)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Replaces the avatar URL, returning the user and the URL it had before
+async fn set_user_avatar(
+    pool: &Pool<Postgres>,
+    user_id: Uuid,
+    avatar_url: Option<&str>,
+) -> Result<Option<(User, Option<String>)>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let previous = sqlx::query_scalar::<_, Option<String>>("SELECT avatar_url FROM users WHERE id = $1 FOR UPDATE")
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+    let Some(previous) = previous else {
+        return Ok(None);
+    };
+
+    let user = sqlx::query_as::<_, User>(
+        r#"
+        UPDATE users SET avatar_url = $2, updated_at = NOW()
+        WHERE id = $1
+        RETURNING id, email, name, avatar_url, provider, provider_id, created_at, updated_at
+        "#
+    )
+    .bind(user_id)
+    .bind(avatar_url)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some((user, previous)))
+}
+
+/// Removes an uploaded avatar from storage. Provider pictures and failures are left alone, the
+/// account no longer points at the image either way.
+async fn delete_stored_avatar(avatar_config: &AvatarConfig, avatar_url: Option<&str>) {
+    let (Some(key), Some(storage_config)) = (avatar_url.and_then(|url| url.strip_prefix("storage://")), &avatar_config.storage) else {
+        return;
+    };
+    match create_storage_provider(storage_config).await {
+        Ok(storage) => {
+            if let Err(e) = storage.delete(key).await {
+                eprintln!("Failed to delete avatar {}: {}", key, e);
+            }
+        }
+        Err(e) => eprintln!("Failed to open avatar storage: {}", e),
+    }
+}
+
+async fn get_linked_providers(pool: &Pool<Postgres>, user_id: Uuid) -> Result<Vec<LinkedProvider>, sqlx::Error> {
+    sqlx::query_as::<_, LinkedProvider>(
+        "SELECT provider, email, created_at FROM user_identities WHERE user_id = $1 ORDER BY created_at"
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn unlink_provider_from_db(pool: &Pool<Postgres>, user_id: Uuid, provider: &str) -> Result<Unlink, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let providers = sqlx::query_scalar::<_, String>("SELECT provider FROM user_identities WHERE user_id = $1 FOR UPDATE")
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+    if !providers.iter().any(|linked| linked == provider) {
+        return Ok(Unlink::NotLinked);
+    }
+    if providers.len() == 1 {
+        return Ok(Unlink::LastProvider);
+    }
+
+    sqlx::query("DELETE FROM user_identities WHERE user_id = $1 AND provider = $2")
+        .bind(user_id)
+        .bind(provider)
+        .execute(&mut *tx)
+        .await?;
+
+    // Logins fall back on the provider the account was created with, so it has to stay linked
+    sqlx::query(
+        r#"
+        UPDATE users u SET provider = ui.provider, provider_id = ui.provider_id, updated_at = NOW()
+        FROM (
+            SELECT provider, provider_id FROM user_identities
+            WHERE user_id = $1
+            ORDER BY created_at
+            LIMIT 1
+        ) ui
+        WHERE u.id = $1 AND u.provider = $2
+        "#
+    )
+    .bind(user_id)
+    .bind(provider)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Unlink::Unlinked)
+}
+
+/// Hands the shared projects of the user over to their new owners and deletes the account.
+/// Memberships, comments and owned projects nobody else works in go with it, annotations stay in
+/// their projects without an author.
+async fn delete_account_from_db(
+    pool: &Pool<Postgres>,
+    user_id: Uuid,
+    transfers: &[OwnershipTransfer],
+) -> Result<AccountDeletion, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let shared_projects = sqlx::query_as::<_, SharedProject>(
+        r#"
+        SELECT p.id, p.name, COUNT(pm.user_id) AS other_members
+        FROM projects p
+        JOIN project_members pm ON pm.project_id = p.id AND pm.user_id <> $1
+        WHERE p.owner_id = $1
+        GROUP BY p.id, p.name
+        ORDER BY p.name
+        "#
+    )
+    .bind(user_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for transfer in transfers {
+        let Some(project) = shared_projects.iter().find(|project| project.id == transfer.project_id) else {
+            return Ok(AccountDeletion::InvalidTransfer(format!(
+                "Project {} is not a project you own with other members",
+                transfer.project_id
+            )));
+        };
+        let is_member = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM project_members WHERE project_id = $1 AND user_id = $2)"
+        )
+        .bind(transfer.project_id)
+        .bind(transfer.new_owner_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if !is_member || transfer.new_owner_id == user_id {
+            return Ok(AccountDeletion::InvalidTransfer(format!(
+                "The new owner of '{}' has to be another member of the project",
+                project.name
+            )));
+        }
+    }
+
+    let blocked: Vec<SharedProject> = shared_projects
+        .into_iter()
+        .filter(|project| !transfers.iter().any(|transfer| transfer.project_id == project.id))
+        .collect();
+    if !blocked.is_empty() {
+        return Ok(AccountDeletion::Blocked(blocked));
+    }
+
+    for transfer in transfers {
+        sqlx::query("UPDATE projects SET owner_id = $2, updated_at = NOW() WHERE id = $1")
+            .bind(transfer.project_id)
+            .bind(transfer.new_owner_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE project_members SET role = 'owner' WHERE project_id = $1 AND user_id = $2")
+            .bind(transfer.project_id)
+            .bind(transfer.new_owner_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let project_ids = sqlx::query_scalar::<_, Uuid>("SELECT project_id FROM project_members WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+    let member_ids = sqlx::query_scalar::<_, Uuid>(
+        "SELECT DISTINCT user_id FROM project_members WHERE project_id = ANY($1)"
+    )
+    .bind(&project_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+    let deleted_project_ids = sqlx::query_scalar::<_, Uuid>("SELECT id FROM projects WHERE owner_id = $1")
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+    let avatar_url = sqlx::query_scalar::<_, Option<String>>("DELETE FROM users WHERE id = $1 RETURNING avatar_url")
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(AccountDeletion::Deleted { avatar_url, project_ids, member_ids, deleted_project_ids })
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{OAuthConfig, ProviderAccount};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    fn provider_account(provider: &'static str, provider_id: &str, email: &str) -> ProviderAccount {
+        ProviderAccount {
+            provider,
+            provider_id: provider_id.to_string(),
+            email: email.to_string(),
+            name: "Alice".to_string(),
+            avatar_url: None,
+        }
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_update_profile_and_linked_providers() {
+        let pool = test_utils::setup_test_db().await;
+        let oauth_config = create_test_oauth_config();
+        let user = crate::auth::create_or_get_user(&pool, &provider_account("google", "google-1", "alice@example.com")).await.unwrap();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config.clone()))
+                .route("/me", web::put().to(update_profile))
+                .route("/me/providers", web::get().to(list_linked_providers))
+                .route("/me/providers/{provider}", web::delete().to(unlink_provider))
+        ).await;
+
+        let req = test::TestRequest::put()
+            .uri("/me")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(UpdateProfileRequest { name: "  Alice Smith ".to_string() })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let profile: ProfileResponse = test::read_body_json(resp).await;
+        assert_eq!(profile.user.name, "Alice Smith");
+        let claims = JwtManager::new(&oauth_config.jwt_secret).verify_token(&profile.token).unwrap();
+        assert_eq!(claims.name, "Alice Smith");
+
+        let req = test::TestRequest::put()
+            .uri("/me")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(UpdateProfileRequest { name: " ".to_string() })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+
+        // GitHub linked to the Google account signs in to the same user
+        crate::auth::link_identity(&pool, user.id, &provider_account("github", "github-1", "alice@users.github.com")).await.unwrap().unwrap();
+        let github_user = crate::auth::create_or_get_user(&pool, &provider_account("github", "github-1", "alice@users.github.com")).await.unwrap();
+        assert_eq!(github_user.id, user.id);
+
+        // A GitHub account of another user cannot be linked
+        let other = crate::auth::create_or_get_user(&pool, &provider_account("github", "github-2", "bob@example.com")).await.unwrap();
+        assert!(crate::auth::link_identity(&pool, user.id, &provider_account("github", "github-2", "bob@example.com")).await.unwrap().is_none());
+        assert_ne!(other.id, user.id);
+
+        let req = test::TestRequest::get()
+            .uri("/me/providers")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let linked: LinkedProvidersResponse = test::read_body_json(resp).await;
+        let providers: Vec<&str> = linked.providers.iter().map(|linked| linked.provider.as_str()).collect();
+        assert_eq!(providers, vec!["google", "github"]);
+        assert_eq!(linked.available, vec!["google", "github"]);
+
+        // Unlinking the provider the account was created with moves sign-in to the one left
+        let req = test::TestRequest::delete()
+            .uri("/me/providers/google")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 204);
+        let user = crate::auth::get_user_by_id(&pool, user.id).await.unwrap().unwrap();
+        assert_eq!((user.provider.as_str(), user.provider_id.as_str()), ("github", "github-1"));
+
+        let req = test::TestRequest::delete()
+            .uri("/me/providers/google")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+
+        let req = test::TestRequest::delete()
+            .uri("/me/providers/github")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 409);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_delete_account_requires_ownership_transfer() {
+        let pool = test_utils::setup_test_db().await;
+        let oauth_config = create_test_oauth_config();
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let token = create_auth_token(&oauth_config, &user);
+        let member_id = test_utils::create_test_user(&pool).await;
+
+        let shared = crate::projects::create_project_in_db(&pool, "Shared", None, None, user.id).await.unwrap();
+        let private = crate::projects::create_project_in_db(&pool, "Private", None, None, user.id).await.unwrap();
+        sqlx::query("INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, 'member')")
+            .bind(shared.id)
+            .bind(member_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .app_data(web::Data::new(AvatarConfig::default()))
+                .route("/me", web::delete().to(delete_account))
+        ).await;
+
+        let delete_request = |confirm_email: &str, transfers: Vec<OwnershipTransfer>| {
+            test::TestRequest::delete()
+                .uri("/me")
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(DeleteAccountRequest { confirm_email: confirm_email.to_string(), transfers })
+                .to_request()
+        };
+
+        let resp = test::call_service(&app, delete_request("someone@example.com", vec![])).await;
+        assert_eq!(resp.status(), 400);
+
+        let resp = test::call_service(&app, delete_request("test@example.com", vec![])).await;
+        assert_eq!(resp.status(), 409);
+        let blocked: AccountDeletionBlocked = test::read_body_json(resp).await;
+        assert_eq!(blocked.projects.len(), 1);
+        assert_eq!(blocked.projects[0].id, shared.id);
+        assert_eq!(blocked.projects[0].other_members, 1);
+
+        // Only members of the project can take it over
+        let transfer = vec![OwnershipTransfer { project_id: shared.id, new_owner_id: Uuid::new_v4() }];
+        let resp = test::call_service(&app, delete_request("test@example.com", transfer)).await;
+        assert_eq!(resp.status(), 400);
+
+        let transfer = vec![OwnershipTransfer { project_id: shared.id, new_owner_id: member_id }];
+        let resp = test::call_service(&app, delete_request("Test@Example.com", transfer)).await;
+        assert_eq!(resp.status(), 204);
+
+        assert!(crate::auth::get_user_by_id(&pool, user.id).await.unwrap().is_none());
+        let owner_id = sqlx::query_scalar::<_, Uuid>("SELECT owner_id FROM projects WHERE id = $1")
+            .bind(shared.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(owner_id, member_id);
+        let role = sqlx::query_scalar::<_, String>("SELECT role FROM project_members WHERE project_id = $1 AND user_id = $2")
+            .bind(shared.id)
+            .bind(member_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(role, "owner");
+        let private_exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM projects WHERE id = $1)")
+            .bind(private.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(!private_exists);
+    }
+}
@@ -1,10 +1,13 @@
 use actix_web::{web, HttpResponse, Responder, HttpRequest};
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Postgres};
+use std::collections::HashMap;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
 
 use crate::auth::{JwtManager, Claims};
+use crate::coco::types::CocoRle;
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Annotation {
@@ -26,11 +29,19 @@ pub struct ImageAnnotation {
     pub area: Option<f64>,
     pub iscrowd: bool,
     pub image_metadata: serde_json::Value,
+    pub is_prediction: bool,
+    pub confidence: Option<f64>,
+    pub attributes: serde_json::Value,
+    pub rotation: f64,
+    pub frame_index: Option<i32>,
+    pub track_id: Option<Uuid>,
+    pub is_interpolated: bool,
+    pub mask: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct AnnotationWithCategory {
     pub id: Uuid,
     pub task_id: Uuid,
@@ -45,40 +56,101 @@ pub struct AnnotationWithCategory {
     pub area: Option<f64>,
     pub iscrowd: bool,
     pub image_metadata: serde_json::Value,
+    pub is_prediction: bool,
+    pub confidence: Option<f64>,
+    pub attributes: serde_json::Value,
+    pub rotation: f64,
+    pub frame_index: Option<i32>,
+    pub track_id: Option<Uuid>,
+    pub is_interpolated: bool,
+    /// Pixel mask of the object, a COCO RLE with compressed counts
+    #[schema(value_type = Option<crate::coco::types::CocoRle>)]
+    pub mask: Option<serde_json::Value>,
     pub category_name: String,
     pub category_color: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BoundingBox {
     pub category_id: Uuid,
     pub bbox: Vec<f64>, // [x, y, width, height]
-    pub area: Option<f64>,
+    pub area: Option<f64>, // Ignored on save, the server computes it from the box
     pub iscrowd: Option<bool>,
+    pub is_prediction: Option<bool>, // Unreviewed model suggestion
+    pub confidence: Option<f64>,
+    pub attributes: Option<serde_json::Value>, // Values for the category's attribute schema
+    pub rotation: Option<f64>, // Clockwise degrees around the box center, for oriented boxes
+    pub frame_index: Option<i32>, // Frame of a video task, None for still images
+    pub track_id: Option<Uuid>, // Object followed across video frames
+    pub is_interpolated: Option<bool>, // Generated between two keyframes of the track
+    #[serde(default)]
+    pub mask: Option<CocoRle>, // Pixel mask of the object, stored with compressed counts
+}
+
+/// Area stored with a box: the pixels of its mask, or the box itself
+pub(crate) fn stored_area(bbox: &[f64], mask: Option<&CocoRle>) -> f64 {
+    mask.and_then(|mask| crate::coco::types::rle_area(mask).ok())
+        .map_or_else(|| crate::image_bounds::box_area(bbox), |area| area as f64)
+}
+
+/// The mask of a box as it is stored, with compressed counts
+pub(crate) fn stored_mask(mask: Option<&CocoRle>) -> Option<serde_json::Value> {
+    mask.and_then(|mask| crate::coco::types::compress_rle(mask).ok())
+        .and_then(|mask| serde_json::to_value(mask).ok())
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateAnnotationRequest {
     pub bboxes: Vec<BoundingBox>,
     pub metadata: Option<serde_json::Value>,
+    /// Latest annotation the boxes were edited from, the nil UUID when the task had none.
+    /// The save is refused with a conflict when someone saved the task since.
+    #[serde(default)]
+    pub base_annotation_id: Option<Uuid>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateAnnotationRequest {
     pub bboxes: Vec<BoundingBox>,
     pub metadata: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AnnotationResponse {
     pub annotations: Vec<AnnotationWithCategory>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AnnotationsListResponse {
     pub annotations: Vec<AnnotationWithCategory>,
 }
 
+/// Answer to a save whose base is no longer the latest annotation of the task
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AnnotationConflict {
+    /// None when the task has no annotations anymore
+    pub latest_annotation_id: Option<Uuid>,
+    /// Boxes of the latest annotation, to merge the save with
+    pub annotations: Vec<AnnotationWithCategory>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/tasks/{task_id}/annotations",
+    tag = "annotations",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    request_body = CreateAnnotationRequest,
+    responses(
+        (status = 201, description = "Boxes of the new annotation", body = AnnotationResponse),
+        (status = 400, description = "Invalid boxes, attributes or categories. Boxes leaving the image answer an `OutOfBoundsError`, boxes breaking the labeling rules `RuleViolations`.", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+        (status = 409, description = "Someone saved the task since `base_annotation_id`", body = AnnotationConflict),
+    ),
+)]
 pub async fn create_annotation(
     req: HttpRequest,
     path: web::Path<(String, String)>,
@@ -123,40 +195,170 @@ pub async fn create_annotation(
                 return HttpResponse::BadRequest().json("bbox values must be non-negative");
             }
         }
+
+        if let Some(rotation) = bbox.rotation {
+            if !crate::rotated_box::is_valid_rotation(rotation) {
+                return HttpResponse::BadRequest().json("rotation must be between -360 and 360 degrees");
+            }
+        }
+
+        if let Some(Err(message)) = bbox.mask.as_ref().map(crate::coco::types::rle_runs) {
+            return HttpResponse::BadRequest().json(format!("Invalid mask: {}", message));
+        }
     }
 
-    // Check if user has access to this project
-    if !user_has_project_access(&pool, project_id, user_id).await {
-        return HttpResponse::NotFound().json("Project not found or access denied");
+    if let Err(message) = crate::interpolation::validate_tracks(&payload.bboxes) {
+        return HttpResponse::BadRequest().json(message);
     }
 
+    // Access, task, project type and categories are checked from one query
+    let category_ids: Vec<Uuid> = payload.bboxes.iter().map(|bbox| bbox.category_id).collect();
+    let context = match resolve_save_context(&pool, project_id, task_id, user_id, &category_ids).await {
+        Ok(Some(context)) if context.has_access => context,
+        Ok(_) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check project access"),
+    };
+
     // Verify task belongs to the project
-    if !task_belongs_to_project(&pool, task_id, project_id).await {
+    if !context.task_exists {
         return HttpResponse::BadRequest().json("Task does not belong to the specified project");
     }
 
+    // Boxes of video tasks must sit on an extracted frame
+    if payload.bboxes.iter().any(|bbox| !crate::video::is_valid_frame_index(bbox.frame_index, context.frame_count)) {
+        return HttpResponse::BadRequest().json("frame_index must name a frame of video tasks and be omitted for images");
+    }
+
+    // Classification projects take whole-image labels instead of boxes
+    if context.task_type == crate::projects::TASK_TYPE_CLASSIFICATION {
+        return HttpResponse::BadRequest().json("Classification projects take labels instead of bounding boxes");
+    }
+
     // Verify all categories belong to the project
-    for bbox in &payload.bboxes {
-        if !category_belongs_to_project(&pool, bbox.category_id, project_id).await {
-            return HttpResponse::BadRequest().json("One or more categories do not belong to the specified project");
+    if category_ids.iter().any(|category_id| !context.category_schemas.contains_key(category_id)) {
+        return HttpResponse::BadRequest().json("One or more categories do not belong to the specified project");
+    }
+
+    let mut payload = payload.into_inner();
+
+    // Boxes are fitted to the image once its size is known, and masks have to cover it
+    if let Some((width, height)) = context.image_size {
+        for mask in payload.bboxes.iter().filter_map(|bbox| bbox.mask.as_ref()) {
+            if let Err(message) = crate::coco::types::check_mask_size(mask, width, height) {
+                return HttpResponse::BadRequest().json(format!("Invalid mask: {}", message));
+            }
+        }
+        if let Err(error) = crate::image_bounds::fit_boxes(&mut payload.bboxes, width, height, context.strict_bounds) {
+            return HttpResponse::BadRequest().json(error);
+        }
+    }
+
+    // Boxes are checked against the project's labeling rules once they are fitted
+    if let Err(violations) = crate::labeling_rules::check(&context.labeling_rules, &payload.bboxes) {
+        return HttpResponse::BadRequest().json(violations);
+    }
+
+    // Validate custom attributes against each category's schema, filling in defaults
+    for bbox in &mut payload.bboxes {
+        let schema = &context.category_schemas[&bbox.category_id];
+
+        match crate::attributes::validate_attributes(schema, bbox.attributes.as_ref()) {
+            Ok(attributes) => bbox.attributes = Some(attributes),
+            Err(message) => return HttpResponse::BadRequest().json(message),
+        }
+    }
+
+    // Saves of the task queue up on its row, so no other save lands between the check of the
+    // base annotation and the insert
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to create annotation"),
+    };
+    if sqlx::query("SELECT id FROM tasks WHERE id = $1 FOR UPDATE")
+        .bind(task_id)
+        .execute(&mut *tx)
+        .await
+        .is_err()
+    {
+        return HttpResponse::InternalServerError().json("Failed to create annotation");
+    }
+
+    // A save edited from an older annotation would silently drop someone else's boxes
+    if let Some(base_annotation_id) = payload.base_annotation_id {
+        let own_copy_only = if user_works_on_blind_copy(&pool, task_id, user_id).await {
+            Some(user_id)
+        } else {
+            None
+        };
+        let latest_annotation_id = match sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT id FROM annotations
+            WHERE task_id = $1 AND ($2::uuid IS NULL OR annotated_by = $2)
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#
+        )
+        .bind(task_id)
+        .bind(own_copy_only)
+        .fetch_optional(&mut *tx)
+        .await
+        {
+            Ok(latest_annotation_id) => latest_annotation_id,
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotations"),
+        };
+        if latest_annotation_id.unwrap_or(Uuid::nil()) != base_annotation_id {
+            drop(tx);
+            let latest = match get_task_annotations(&pool, task_id, true, own_copy_only).await {
+                Ok(latest) => latest,
+                Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotations"),
+            };
+            return HttpResponse::Conflict().json(AnnotationConflict {
+                latest_annotation_id,
+                annotations: latest,
+            });
         }
     }
 
     // Create annotation with multiple bounding boxes
-    match create_annotation_in_db(
-        &pool,
+    let created = match insert_annotation(
+        &mut tx,
         task_id,
         &payload.bboxes,
         payload.metadata.as_ref().unwrap_or(&serde_json::json!({})),
         user_id,
     ).await {
-        Ok(annotations) => HttpResponse::Created().json(AnnotationResponse { 
-            annotations 
-        }),
+        Ok(annotations) => tx.commit().await.map(|_| annotations),
+        Err(err) => Err(err),
+    };
+    match created {
+        Ok(annotations) => {
+            if let Some(annotation) = annotations.first() {
+                score_gold_annotation(&pool, task_id, annotation.annotation_id, user_id).await;
+            }
+            HttpResponse::Created().json(AnnotationResponse { 
+                annotations 
+            })
+        }
         Err(_) => HttpResponse::InternalServerError().json("Failed to create annotation"),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/tasks/{task_id}/annotations",
+    tag = "annotations",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        ("latest_only" = Option<bool>, Query, description = "Only the boxes of the latest annotation"),
+    ),
+    responses(
+        (status = 200, body = AnnotationsListResponse),
+        (status = 400, description = "Task doesn't belong to the project", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
 pub async fn list_annotations(
     req: HttpRequest,
     path: web::Path<(String, String)>,
@@ -186,26 +388,49 @@ pub async fn list_annotations(
         Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
     };
 
-    // Check if user has access to this project
-    if !user_has_project_access(&pool, project_id, user_id).await {
-        return HttpResponse::NotFound().json("Project not found or access denied");
-    }
-
-    // Verify task belongs to the project
-    if !task_belongs_to_project(&pool, task_id, project_id).await {
-        return HttpResponse::BadRequest().json("Task does not belong to the specified project");
+    // Check project access and that the task belongs to the project
+    match resolve_task_access(&pool, project_id, task_id, user_id).await {
+        Ok(TaskAccess::Granted) => {}
+        Ok(TaskAccess::NoProjectAccess) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Ok(TaskAccess::TaskNotInProject) => {
+            return HttpResponse::BadRequest().json("Task does not belong to the specified project");
+        }
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check project access"),
     }
 
     // Check if latest_only flag is set
     let latest_only = query.get("latest_only").map(|v| v == "true").unwrap_or(false);
 
+    // Assigned annotators work on blind copies and only see their own annotations
+    let own_copy_only = if user_works_on_blind_copy(&pool, task_id, user_id).await {
+        Some(user_id)
+    } else {
+        None
+    };
+
     // Get task's annotations
-    match get_task_annotations(&pool, task_id, latest_only).await {
+    match get_task_annotations(&pool, task_id, latest_only, own_copy_only).await {
         Ok(annotations) => HttpResponse::Ok().json(AnnotationsListResponse { annotations }),
         Err(_) => HttpResponse::InternalServerError().json("Failed to fetch annotations"),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/tasks/{task_id}/annotations/{annotation_id}",
+    tag = "annotations",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        ("annotation_id" = Uuid, Path, description = "Annotation ID"),
+    ),
+    responses(
+        (status = 200, body = AnnotationResponse),
+        (status = 400, description = "Task doesn't belong to the project", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or annotation not found", body = String),
+    ),
+)]
 pub async fn get_annotation(
     req: HttpRequest,
     path: web::Path<(String, String, String)>,
@@ -239,24 +464,55 @@ pub async fn get_annotation(
         Err(_) => return HttpResponse::BadRequest().json("Invalid annotation ID"),
     };
 
-    // Check if user has access to this project
-    if !user_has_project_access(&pool, project_id, user_id).await {
-        return HttpResponse::NotFound().json("Project not found or access denied");
+    // Check project access and that the task belongs to the project
+    match resolve_task_access(&pool, project_id, task_id, user_id).await {
+        Ok(TaskAccess::Granted) => {}
+        Ok(TaskAccess::NoProjectAccess) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Ok(TaskAccess::TaskNotInProject) => {
+            return HttpResponse::BadRequest().json("Task does not belong to the specified project");
+        }
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check project access"),
     }
 
-    // Verify task belongs to the project
-    if !task_belongs_to_project(&pool, task_id, project_id).await {
-        return HttpResponse::BadRequest().json("Task does not belong to the specified project");
-    }
+    // Assigned annotators work on blind copies and can't open anyone else's annotation
+    let own_copy_only = if user_works_on_blind_copy(&pool, task_id, user_id).await {
+        Some(user_id)
+    } else {
+        None
+    };
 
     // Get annotation
     match get_annotation_by_id(&pool, annotation_id, task_id).await {
+        Ok(Some(annotations))
+            if own_copy_only.is_some_and(|user_id| {
+                annotations.iter().any(|annotation| annotation.annotated_by != Some(user_id))
+            }) =>
+        {
+            HttpResponse::NotFound().json("Annotation not found")
+        }
         Ok(Some(annotations)) => HttpResponse::Ok().json(AnnotationResponse { annotations }),
         Ok(None) => HttpResponse::NotFound().json("Annotation not found"),
         Err(_) => HttpResponse::InternalServerError().json("Failed to fetch annotation"),
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/projects/{project_id}/tasks/{task_id}/annotations/{annotation_id}",
+    tag = "annotations",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        ("annotation_id" = Uuid, Path, description = "Annotation ID"),
+    ),
+    request_body = UpdateAnnotationRequest,
+    responses(
+        (status = 200, body = AnnotationResponse),
+        (status = 400, description = "Invalid boxes, attributes or categories. Boxes leaving the image answer an `OutOfBoundsError`, boxes breaking the labeling rules `RuleViolations`.", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or annotation not found", body = String),
+    ),
+)]
 pub async fn update_annotation(
     req: HttpRequest,
     path: web::Path<(String, String, String)>,
@@ -306,22 +562,76 @@ pub async fn update_annotation(
                 return HttpResponse::BadRequest().json("bbox values must be non-negative");
             }
         }
+
+        if let Some(rotation) = bbox.rotation {
+            if !crate::rotated_box::is_valid_rotation(rotation) {
+                return HttpResponse::BadRequest().json("rotation must be between -360 and 360 degrees");
+            }
+        }
+
+        if let Some(Err(message)) = bbox.mask.as_ref().map(crate::coco::types::rle_runs) {
+            return HttpResponse::BadRequest().json(format!("Invalid mask: {}", message));
+        }
     }
 
-    // Check if user has access to this project
-    if !user_has_project_access(&pool, project_id, user_id).await {
-        return HttpResponse::NotFound().json("Project not found or access denied");
+    if let Err(message) = crate::interpolation::validate_tracks(&payload.bboxes) {
+        return HttpResponse::BadRequest().json(message);
     }
 
+    // Access, task, project type and categories are checked from one query
+    let category_ids: Vec<Uuid> = payload.bboxes.iter().map(|bbox| bbox.category_id).collect();
+    let context = match resolve_save_context(&pool, project_id, task_id, user_id, &category_ids).await {
+        Ok(Some(context)) if context.has_access => context,
+        Ok(_) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check project access"),
+    };
+
     // Verify task belongs to the project
-    if !task_belongs_to_project(&pool, task_id, project_id).await {
+    if !context.task_exists {
         return HttpResponse::BadRequest().json("Task does not belong to the specified project");
     }
 
+    // Boxes of video tasks must sit on an extracted frame
+    if payload.bboxes.iter().any(|bbox| !crate::video::is_valid_frame_index(bbox.frame_index, context.frame_count)) {
+        return HttpResponse::BadRequest().json("frame_index must name a frame of video tasks and be omitted for images");
+    }
+
+    // Classification projects take whole-image labels instead of boxes
+    if context.task_type == crate::projects::TASK_TYPE_CLASSIFICATION {
+        return HttpResponse::BadRequest().json("Classification projects take labels instead of bounding boxes");
+    }
+
     // Verify all categories belong to the project
-    for bbox in &payload.bboxes {
-        if !category_belongs_to_project(&pool, bbox.category_id, project_id).await {
-            return HttpResponse::BadRequest().json("One or more categories do not belong to the specified project");
+    if category_ids.iter().any(|category_id| !context.category_schemas.contains_key(category_id)) {
+        return HttpResponse::BadRequest().json("One or more categories do not belong to the specified project");
+    }
+
+    let mut payload = payload.into_inner();
+
+    // Boxes are fitted to the image once its size is known, and masks have to cover it
+    if let Some((width, height)) = context.image_size {
+        for mask in payload.bboxes.iter().filter_map(|bbox| bbox.mask.as_ref()) {
+            if let Err(message) = crate::coco::types::check_mask_size(mask, width, height) {
+                return HttpResponse::BadRequest().json(format!("Invalid mask: {}", message));
+            }
+        }
+        if let Err(error) = crate::image_bounds::fit_boxes(&mut payload.bboxes, width, height, context.strict_bounds) {
+            return HttpResponse::BadRequest().json(error);
+        }
+    }
+
+    // Boxes are checked against the project's labeling rules once they are fitted
+    if let Err(violations) = crate::labeling_rules::check(&context.labeling_rules, &payload.bboxes) {
+        return HttpResponse::BadRequest().json(violations);
+    }
+
+    // Validate custom attributes against each category's schema, filling in defaults
+    for bbox in &mut payload.bboxes {
+        let schema = &context.category_schemas[&bbox.category_id];
+
+        match crate::attributes::validate_attributes(schema, bbox.attributes.as_ref()) {
+            Ok(attributes) => bbox.attributes = Some(attributes),
+            Err(message) => return HttpResponse::BadRequest().json(message),
         }
     }
 
@@ -333,12 +643,33 @@ pub async fn update_annotation(
         &payload.bboxes,
         payload.metadata.as_ref().unwrap_or(&serde_json::json!({})),
     ).await {
-        Ok(Some(annotations)) => HttpResponse::Ok().json(AnnotationResponse { annotations }),
+        Ok(Some(annotations)) => {
+            if let Some(annotated_by) = annotations.first().and_then(|annotation| annotation.annotated_by) {
+                score_gold_annotation(&pool, task_id, annotation_id, annotated_by).await;
+            }
+            HttpResponse::Ok().json(AnnotationResponse { annotations })
+        }
         Ok(None) => HttpResponse::NotFound().json("Annotation not found"),
         Err(_) => HttpResponse::InternalServerError().json("Failed to update annotation"),
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/projects/{project_id}/tasks/{task_id}/annotations/{annotation_id}",
+    tag = "annotations",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        ("annotation_id" = Uuid, Path, description = "Annotation ID"),
+    ),
+    responses(
+        (status = 204, description = "Annotation deleted"),
+        (status = 400, description = "Task doesn't belong to the project", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or annotation not found", body = String),
+    ),
+)]
 pub async fn delete_annotation(
     req: HttpRequest,
     path: web::Path<(String, String, String)>,
@@ -372,14 +703,14 @@ pub async fn delete_annotation(
         Err(_) => return HttpResponse::BadRequest().json("Invalid annotation ID"),
     };
 
-    // Check if user has access to this project
-    if !user_has_project_access(&pool, project_id, user_id).await {
-        return HttpResponse::NotFound().json("Project not found or access denied");
-    }
-
-    // Verify task belongs to the project
-    if !task_belongs_to_project(&pool, task_id, project_id).await {
-        return HttpResponse::BadRequest().json("Task does not belong to the specified project");
+    // Check project access and that the task belongs to the project
+    match resolve_task_access(&pool, project_id, task_id, user_id).await {
+        Ok(TaskAccess::Granted) => {}
+        Ok(TaskAccess::NoProjectAccess) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Ok(TaskAccess::TaskNotInProject) => {
+            return HttpResponse::BadRequest().json("Task does not belong to the specified project");
+        }
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check project access"),
     }
 
     // Delete annotation
@@ -396,6 +727,20 @@ pub async fn create_annotation_in_db(
     bboxes: &[BoundingBox],
     metadata: &serde_json::Value,
     annotated_by: Uuid,
+) -> Result<Vec<AnnotationWithCategory>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let annotations = insert_annotation(&mut tx, task_id, bboxes, metadata, annotated_by).await?;
+    tx.commit().await?;
+    Ok(annotations)
+}
+
+/// Saves the boxes as a new annotation of the task within the caller's transaction
+async fn insert_annotation(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    task_id: Uuid,
+    bboxes: &[BoundingBox],
+    metadata: &serde_json::Value,
+    annotated_by: Uuid,
 ) -> Result<Vec<AnnotationWithCategory>, sqlx::Error> {
     if bboxes.is_empty() {
         return Ok(Vec::new());
@@ -419,7 +764,7 @@ pub async fn create_annotation_in_db(
     .bind(now)
     .bind(now)
     .bind(now)
-    .fetch_one(pool)
+    .fetch_one(&mut **tx)
     .await?;
 
     let mut result = Vec::new();
@@ -428,22 +773,14 @@ pub async fn create_annotation_in_db(
     for bbox in bboxes {
         let image_annotation_id = Uuid::new_v4();
         
-        // Calculate area if not provided
-        let calculated_area = bbox.area.unwrap_or_else(|| {
-            if bbox.bbox.len() >= 4 {
-                let width = bbox.bbox[2];
-                let height = bbox.bbox[3];
-                width * height
-            } else {
-                0.0
-            }
-        });
+        // Computed rather than taken from the client, so it always matches the box or its mask
+        let calculated_area = stored_area(&bbox.bbox, bbox.mask.as_ref());
 
         let image_annotation = sqlx::query_as::<_, ImageAnnotation>(
             r#"
-            INSERT INTO image_annotations (id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-            RETURNING id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, created_at, updated_at
+            INSERT INTO image_annotations (id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, is_prediction, confidence, attributes, rotation, frame_index, track_id, is_interpolated, mask, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+            RETURNING id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, is_prediction, confidence, attributes, rotation, frame_index, track_id, is_interpolated, mask, created_at, updated_at
             "#
         )
         .bind(image_annotation_id)
@@ -453,9 +790,17 @@ pub async fn create_annotation_in_db(
         .bind(Some(calculated_area))
         .bind(bbox.iscrowd.unwrap_or(false))
         .bind(serde_json::json!({}))
+        .bind(bbox.is_prediction.unwrap_or(false))
+        .bind(bbox.confidence)
+        .bind(bbox.attributes.clone().unwrap_or_else(|| serde_json::json!({})))
+        .bind(crate::rotated_box::normalize_rotation(bbox.rotation.unwrap_or(0.0)))
+        .bind(bbox.frame_index)
+        .bind(bbox.track_id)
+        .bind(bbox.is_interpolated.unwrap_or(false))
+        .bind(stored_mask(bbox.mask.as_ref()))
         .bind(now)
         .bind(now)
-        .fetch_one(pool)
+        .fetch_one(&mut **tx)
         .await?;
 
         // Get category info from image_annotation_categories
@@ -463,7 +808,7 @@ pub async fn create_annotation_in_db(
             "SELECT name, color FROM image_annotation_categories WHERE id = $1",
             bbox.category_id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut **tx)
         .await?;
 
         result.push(AnnotationWithCategory {
@@ -480,6 +825,14 @@ pub async fn create_annotation_in_db(
             area: image_annotation.area,
             iscrowd: image_annotation.iscrowd,
             image_metadata: image_annotation.image_metadata,
+            is_prediction: image_annotation.is_prediction,
+            confidence: image_annotation.confidence,
+            attributes: image_annotation.attributes,
+            rotation: image_annotation.rotation,
+            frame_index: image_annotation.frame_index,
+            track_id: image_annotation.track_id,
+            is_interpolated: image_annotation.is_interpolated,
+            mask: image_annotation.mask,
             category_name: category.name,
             category_color: category.color,
         });
@@ -488,22 +841,31 @@ pub async fn create_annotation_in_db(
     Ok(result)
 }
 
-async fn get_task_annotations(
+pub(crate) async fn get_task_annotations(
     pool: &Pool<Postgres>,
     task_id: Uuid,
     latest_only: bool,
+    annotated_by: Option<Uuid>,
 ) -> Result<Vec<AnnotationWithCategory>, sqlx::Error> {
     let mut where_clause = "WHERE a.task_id = $1".to_string();
     
+    if annotated_by.is_some() {
+        where_clause.push_str(" AND a.annotated_by = $2");
+    }
+
     if latest_only {
-        where_clause.push_str(" AND a.id = (SELECT id FROM annotations WHERE task_id = $1 ORDER BY created_at DESC LIMIT 1)");
+        let author_filter = if annotated_by.is_some() { " AND annotated_by = $2" } else { "" };
+        where_clause.push_str(&format!(
+            " AND a.id = (SELECT id FROM annotations WHERE task_id = $1{} ORDER BY created_at DESC LIMIT 1)",
+            author_filter
+        ));
     }
     
     let query = format!(
         r#"
         SELECT 
             a.id, a.task_id, a.metadata, a.annotated_by, a.annotated_at, a.created_at, a.updated_at,
-            ia.id as image_id, ia.annotation_id, ia.category_id, ia.bbox, ia.area, ia.iscrowd, ia.image_metadata, ia.created_at as image_created_at, ia.updated_at as image_updated_at,
+            ia.id as image_id, ia.annotation_id, ia.category_id, ia.bbox, ia.area, ia.iscrowd, ia.image_metadata, ia.is_prediction, ia.confidence, ia.attributes, ia.rotation, ia.frame_index, ia.track_id, ia.is_interpolated, ia.mask, ia.created_at as image_created_at, ia.updated_at as image_updated_at,
             COALESCE(iac.name, 'Unknown') as category_name,
             iac.color as category_color
         FROM annotations a
@@ -515,10 +877,11 @@ async fn get_task_annotations(
         where_clause
     );
 
-    let rows = sqlx::query(&query)
-        .bind(task_id)
-        .fetch_all(pool)
-        .await?;
+    let mut query = sqlx::query(&query).bind(task_id);
+    if let Some(user_id) = annotated_by {
+        query = query.bind(user_id);
+    }
+    let rows = query.fetch_all(pool).await?;
 
     let mut result = Vec::new();
     for row in rows {
@@ -542,6 +905,14 @@ async fn get_task_annotations(
             area: row.get("area"),
             iscrowd: row.get::<Option<bool>, _>("iscrowd").unwrap_or(false),
             image_metadata: row.get::<Option<serde_json::Value>, _>("image_metadata").unwrap_or_else(|| serde_json::json!({})),
+            is_prediction: row.get("is_prediction"),
+            confidence: row.get("confidence"),
+            attributes: row.get("attributes"),
+            rotation: row.get("rotation"),
+            frame_index: row.get("frame_index"),
+            track_id: row.get("track_id"),
+            is_interpolated: row.get("is_interpolated"),
+            mask: row.get("mask"),
             created_at: row.get::<Option<chrono::DateTime<chrono::Utc>>, _>("image_created_at").unwrap_or_else(|| row.get("created_at")),
             updated_at: row.get::<Option<chrono::DateTime<chrono::Utc>>, _>("image_updated_at").unwrap_or_else(|| row.get("updated_at")),
         };
@@ -560,6 +931,14 @@ async fn get_task_annotations(
             area: image_annotation.area,
             iscrowd: image_annotation.iscrowd,
             image_metadata: image_annotation.image_metadata,
+            is_prediction: image_annotation.is_prediction,
+            confidence: image_annotation.confidence,
+            attributes: image_annotation.attributes,
+            rotation: image_annotation.rotation,
+            frame_index: image_annotation.frame_index,
+            track_id: image_annotation.track_id,
+            is_interpolated: image_annotation.is_interpolated,
+            mask: image_annotation.mask,
             category_name: row.get::<Option<String>, _>("category_name").unwrap_or_else(|| "Unknown".to_string()),
             category_color: row.get("category_color"),
         });
@@ -577,7 +956,7 @@ async fn get_annotation_by_id(
         r#"
         SELECT 
             a.id, a.task_id, a.metadata, a.annotated_by, a.annotated_at, a.created_at, a.updated_at,
-            ia.id as image_id, ia.annotation_id, ia.category_id, ia.bbox, ia.area, ia.iscrowd, ia.image_metadata, ia.created_at as image_created_at, ia.updated_at as image_updated_at,
+            ia.id as image_id, ia.annotation_id, ia.category_id, ia.bbox, ia.area, ia.iscrowd, ia.image_metadata, ia.is_prediction, ia.confidence, ia.attributes, ia.rotation, ia.frame_index, ia.track_id, ia.is_interpolated, ia.mask, ia.created_at as image_created_at, ia.updated_at as image_updated_at,
             COALESCE(iac.name, 'Unknown') as category_name,
             iac.color as category_color
         FROM annotations a
@@ -616,6 +995,14 @@ async fn get_annotation_by_id(
             area: row.area,
             iscrowd: row.iscrowd.unwrap_or(false),
             image_metadata: row.image_metadata.unwrap_or_else(|| serde_json::json!({})),
+            is_prediction: row.is_prediction,
+            confidence: row.confidence,
+            attributes: row.attributes,
+            rotation: row.rotation,
+            frame_index: row.frame_index,
+            track_id: row.track_id,
+            is_interpolated: row.is_interpolated,
+            mask: row.mask,
             created_at: row.image_created_at.unwrap_or_else(|| row.created_at.unwrap()),
             updated_at: row.image_updated_at.unwrap_or_else(|| row.updated_at.unwrap()),
         };
@@ -634,6 +1021,14 @@ async fn get_annotation_by_id(
             area: image_annotation.area,
             iscrowd: image_annotation.iscrowd,
             image_metadata: image_annotation.image_metadata,
+            is_prediction: image_annotation.is_prediction,
+            confidence: image_annotation.confidence,
+            attributes: image_annotation.attributes,
+            rotation: image_annotation.rotation,
+            frame_index: image_annotation.frame_index,
+            track_id: image_annotation.track_id,
+            is_interpolated: image_annotation.is_interpolated,
+            mask: image_annotation.mask,
             category_name: row.category_name.unwrap_or("Unknown".to_string()),
             category_color: row.category_color,
         });
@@ -681,22 +1076,14 @@ async fn update_annotation_in_db(
     for bbox in bboxes {
         let image_annotation_id = Uuid::new_v4();
         
-        // Calculate area if not provided
-        let calculated_area = bbox.area.unwrap_or_else(|| {
-            if bbox.bbox.len() >= 4 {
-                let width = bbox.bbox[2];
-                let height = bbox.bbox[3];
-                width * height
-            } else {
-                0.0
-            }
-        });
+        // Computed rather than taken from the client, so it always matches the box or its mask
+        let calculated_area = stored_area(&bbox.bbox, bbox.mask.as_ref());
 
         let image_annotation = sqlx::query_as::<_, ImageAnnotation>(
             r#"
-            INSERT INTO image_annotations (id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-            RETURNING id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, created_at, updated_at
+            INSERT INTO image_annotations (id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, is_prediction, confidence, attributes, rotation, frame_index, track_id, is_interpolated, mask, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+            RETURNING id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, is_prediction, confidence, attributes, rotation, frame_index, track_id, is_interpolated, mask, created_at, updated_at
             "#
         )
         .bind(image_annotation_id)
@@ -706,6 +1093,14 @@ async fn update_annotation_in_db(
         .bind(Some(calculated_area))
         .bind(bbox.iscrowd.unwrap_or(false))
         .bind(serde_json::json!({}))
+        .bind(bbox.is_prediction.unwrap_or(false))
+        .bind(bbox.confidence)
+        .bind(bbox.attributes.clone().unwrap_or_else(|| serde_json::json!({})))
+        .bind(crate::rotated_box::normalize_rotation(bbox.rotation.unwrap_or(0.0)))
+        .bind(bbox.frame_index)
+        .bind(bbox.track_id)
+        .bind(bbox.is_interpolated.unwrap_or(false))
+        .bind(stored_mask(bbox.mask.as_ref()))
         .bind(now)
         .bind(now)
         .fetch_one(pool)
@@ -733,6 +1128,14 @@ async fn update_annotation_in_db(
             area: image_annotation.area,
             iscrowd: image_annotation.iscrowd,
             image_metadata: image_annotation.image_metadata,
+            is_prediction: image_annotation.is_prediction,
+            confidence: image_annotation.confidence,
+            attributes: image_annotation.attributes,
+            rotation: image_annotation.rotation,
+            frame_index: image_annotation.frame_index,
+            track_id: image_annotation.track_id,
+            is_interpolated: image_annotation.is_interpolated,
+            mask: image_annotation.mask,
             category_name: category.name,
             category_color: category.color,
         });
@@ -755,44 +1158,159 @@ async fn delete_annotation_from_db(
     Ok(result.rows_affected() > 0)
 }
 
-async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
-    sqlx::query_scalar::<_, bool>(
+/// Whether the user may work on the task, answered by a single query
+enum TaskAccess {
+    Granted,
+    NoProjectAccess,
+    TaskNotInProject,
+}
+
+async fn resolve_task_access(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    task_id: Uuid,
+    user_id: Uuid,
+) -> Result<TaskAccess, sqlx::Error> {
+    let (has_access, task_exists) = sqlx::query_as::<_, (bool, bool)>(
         r#"
-        SELECT EXISTS(
-            SELECT 1 FROM project_members pm
-            WHERE pm.project_id = $1 AND pm.user_id = $2
-        )
+        SELECT
+            EXISTS(SELECT 1 FROM project_members WHERE project_id = $1 AND user_id = $3),
+            EXISTS(SELECT 1 FROM tasks WHERE id = $2 AND project_id = $1)
         "#
     )
     .bind(project_id)
+    .bind(task_id)
     .bind(user_id)
     .fetch_one(pool)
-    .await
-    .unwrap_or(false)
+    .await?;
+
+    Ok(match (has_access, task_exists) {
+        (false, _) => TaskAccess::NoProjectAccess,
+        (true, false) => TaskAccess::TaskNotInProject,
+        (true, true) => TaskAccess::Granted,
+    })
 }
 
-async fn task_belongs_to_project(pool: &Pool<Postgres>, task_id: Uuid, project_id: Uuid) -> bool {
-    sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM tasks WHERE id = $1 AND project_id = $2)"
+/// Everything saving boxes checks before writing them
+struct SaveContext {
+    has_access: bool,
+    task_exists: bool,
+    /// Extracted frames of a video task, `None` for images
+    frame_count: Option<i32>,
+    task_type: String,
+    /// Image size of the task, once it is known
+    image_size: Option<(i32, i32)>,
+    /// Boxes leaving the image are rejected instead of clipped
+    strict_bounds: bool,
+    labeling_rules: crate::labeling_rules::LabelingRules,
+    /// Attribute schemas of the requested categories that belong to the project
+    category_schemas: HashMap<Uuid, Vec<crate::attributes::AttributeDefinition>>,
+}
+
+#[derive(sqlx::FromRow)]
+struct SaveContextRow {
+    has_access: bool,
+    task_exists: bool,
+    frame_count: Option<i32>,
+    task_type: String,
+    width: Option<i32>,
+    height: Option<i32>,
+    strict_bounds: bool,
+    labeling_rules: serde_json::Value,
+    category_schemas: serde_json::Value,
+}
+
+/// Resolves access, the task, the project type and the categories of a save in one round
+/// trip instead of one query per check and category. `None` when the project doesn't exist.
+async fn resolve_save_context(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    task_id: Uuid,
+    user_id: Uuid,
+    category_ids: &[Uuid],
+) -> Result<Option<SaveContext>, sqlx::Error> {
+    let row = sqlx::query_as::<_, SaveContextRow>(
+        r#"
+        SELECT
+            EXISTS(SELECT 1 FROM project_members pm WHERE pm.project_id = p.id AND pm.user_id = $3) AS has_access,
+            t.id IS NOT NULL AS task_exists,
+            t.frame_count,
+            p.task_type,
+            t.width,
+            t.height,
+            p.strict_bounds,
+            p.labeling_rules,
+            COALESCE(
+                (
+                    SELECT jsonb_object_agg(c.id::text, c.attribute_schema)
+                    FROM image_annotation_categories c
+                    WHERE c.project_id = p.id AND c.id = ANY($4)
+                ),
+                '{}'::jsonb
+            ) AS category_schemas
+        FROM projects p
+        LEFT JOIN tasks t ON t.id = $2 AND t.project_id = p.id
+        WHERE p.id = $1
+        "#
     )
-    .bind(task_id)
     .bind(project_id)
-    .fetch_one(pool)
-    .await
-    .unwrap_or(false)
+    .bind(task_id)
+    .bind(user_id)
+    .bind(category_ids)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| {
+        let category_schemas = match row.category_schemas {
+            serde_json::Value::Object(schemas) => schemas
+                .into_iter()
+                .filter_map(|(id, schema)| {
+                    // Schemas are validated when they are saved, so anything unreadable is treated as empty
+                    Some((Uuid::parse_str(&id).ok()?, serde_json::from_value(schema).unwrap_or_default()))
+                })
+                .collect(),
+            _ => HashMap::new(),
+        };
+
+        SaveContext {
+            has_access: row.has_access,
+            task_exists: row.task_exists,
+            frame_count: row.frame_count,
+            task_type: row.task_type,
+            image_size: row.width.zip(row.height),
+            strict_bounds: row.strict_bounds,
+            labeling_rules: crate::labeling_rules::from_column(row.labeling_rules),
+            category_schemas,
+        }
+    }))
 }
 
-async fn category_belongs_to_project(pool: &Pool<Postgres>, category_id: Uuid, project_id: Uuid) -> bool {
+/// Whether the user is assigned to the task, or the task is a gold task whose reference
+/// someone else made and has to stay hidden from them
+async fn user_works_on_blind_copy(pool: &Pool<Postgres>, task_id: Uuid, user_id: Uuid) -> bool {
     sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM image_annotation_categories WHERE id = $1 AND project_id = $2)"
+        r#"
+        SELECT EXISTS(SELECT 1 FROM task_assignments WHERE task_id = $1 AND user_id = $2)
+            OR EXISTS(
+                SELECT 1 FROM tasks t JOIN annotations g ON g.id = t.gold_annotation_id
+                WHERE t.id = $1 AND g.annotated_by IS DISTINCT FROM $2
+            )
+        "#
     )
-    .bind(category_id)
-    .bind(project_id)
+    .bind(task_id)
+    .bind(user_id)
     .fetch_one(pool)
     .await
     .unwrap_or(false)
 }
 
+/// Scores a save on a gold task. Failures are only logged, the annotation is saved either way.
+async fn score_gold_annotation(pool: &Pool<Postgres>, task_id: Uuid, annotation_id: Uuid, user_id: Uuid) {
+    if let Err(err) = crate::gold::score_annotation(pool, task_id, annotation_id, user_id).await {
+        eprintln!("Gold scoring error: {:?}", err);
+    }
+}
+
 fn extract_user_claims(
     req: &HttpRequest,
     config: &crate::auth::OAuthConfig,
@@ -876,8 +1394,17 @@ mod tests {
                 bbox: vec![100.0, 50.0, 200.0, 150.0], // [x, y, width, height]
                 area: Some(30000.0),
                 iscrowd: Some(false),
+                is_prediction: None,
+                confidence: None,
+                attributes: None,
+                rotation: None,
+                frame_index: None,
+                track_id: None,
+                is_interpolated: None,
+                mask: None,
             }],
             metadata: Some(serde_json::json!({"confidence": 0.95})),
+            base_annotation_id: None,
         };
 
         let req = test::TestRequest::post()
@@ -897,6 +1424,162 @@ mod tests {
         assert_eq!(body["annotations"][0]["iscrowd"], false);
     }
 
+    #[actix_web::test]
+    #[serial]
+    async fn test_boxes_are_fitted_to_the_image() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+        let auth_storage = AuthStorage::new(pool.clone());
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, None, None, None).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "Test Task", Some("test.jpg")).await.unwrap();
+        sqlx::query("UPDATE tasks SET width = 100, height = 80 WHERE id = $1")
+            .bind(task.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .app_data(web::Data::new(auth_storage))
+                .route("/projects/{project_id}/tasks/{task_id}/annotations", web::post().to(create_annotation))
+        ).await;
+
+        let request = |bbox: Vec<f64>| CreateAnnotationRequest {
+            bboxes: vec![BoundingBox {
+                category_id: category.id,
+                bbox,
+                // Wrong on purpose, the server computes its own
+                area: Some(1.0),
+                iscrowd: None,
+                is_prediction: None,
+                confidence: None,
+                attributes: None,
+                rotation: None,
+                frame_index: None,
+                track_id: None,
+                is_interpolated: None,
+                mask: None,
+            }],
+            metadata: None,
+            base_annotation_id: None,
+        };
+        let uri = format!("/projects/{}/tasks/{}/annotations", project.id, task.id);
+
+        // Clipped to the 100x80 image
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(request(vec![60.0, 40.0, 60.0, 50.0]))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["annotations"][0]["bbox"], serde_json::json!([60.0, 40.0, 40.0, 40.0]));
+        assert_eq!(body["annotations"][0]["area"], 1600.0);
+
+        // Nothing of it is on the image
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(request(vec![150.0, 0.0, 10.0, 10.0]))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+
+        sqlx::query("UPDATE projects SET strict_bounds = TRUE WHERE id = $1")
+            .bind(project.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(request(vec![60.0, 40.0, 60.0, 50.0]))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["image_width"], 100);
+        assert_eq!(body["image_height"], 80);
+        assert_eq!(body["boxes"][0]["index"], 0);
+        assert_eq!(body["boxes"][0]["bbox"], serde_json::json!([60.0, 40.0, 60.0, 50.0]));
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_saves_follow_the_labeling_rules() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+        let auth_storage = AuthStorage::new(pool.clone());
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, user.id).await.unwrap();
+        let person = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, None, None, None).await.unwrap();
+        let car = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "car", None, None, None, None).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "Test Task", Some("test.jpg")).await.unwrap();
+        sqlx::query("UPDATE projects SET labeling_rules = $1 WHERE id = $2")
+            .bind(serde_json::json!({ "min_box_width": 10.0, "max_boxes_per_image": 2, "required_category_ids": [car.id] }))
+            .bind(project.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .app_data(web::Data::new(auth_storage))
+                .route("/projects/{project_id}/tasks/{task_id}/annotations", web::post().to(create_annotation))
+        ).await;
+
+        let request = |boxes: Vec<(Uuid, f64)>| CreateAnnotationRequest {
+            bboxes: boxes.into_iter().map(|(category_id, width)| BoundingBox {
+                category_id,
+                bbox: vec![0.0, 0.0, width, 20.0],
+                area: None,
+                iscrowd: None,
+                is_prediction: None,
+                confidence: None,
+                attributes: None,
+                rotation: None,
+                frame_index: None,
+                track_id: None,
+                is_interpolated: None,
+                mask: None,
+            }).collect(),
+            metadata: None,
+            base_annotation_id: None,
+        };
+        let uri = format!("/projects/{}/tasks/{}/annotations", project.id, task.id);
+
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(request(vec![(person.id, 4.0), (person.id, 20.0), (person.id, 20.0)]))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let rules: Vec<_> = body["violations"].as_array().unwrap().iter().map(|violation| violation["rule"].clone()).collect();
+        assert_eq!(rules, vec!["box_too_small", "too_many_boxes", "missing_category"]);
+        assert_eq!(body["violations"][0]["index"], 0);
+
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(request(vec![(person.id, 20.0), (car.id, 10.0)]))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
     #[actix_web::test]
     #[serial]
     async fn test_create_annotation_invalid_bbox() {
@@ -924,8 +1607,17 @@ mod tests {
                 bbox: vec![100.0, 50.0, 200.0], // Invalid: only 3 values
                 area: None,
                 iscrowd: None,
+                is_prediction: None,
+                confidence: None,
+                attributes: None,
+                rotation: None,
+                frame_index: None,
+                track_id: None,
+                is_interpolated: None,
+                mask: None,
             }],
             metadata: None,
+            base_annotation_id: None,
         };
 
         let req = test::TestRequest::post()
@@ -965,8 +1657,17 @@ mod tests {
                 bbox: vec![-10.0, 50.0, 200.0, 150.0], // Invalid: negative value
                 area: None,
                 iscrowd: None,
+                is_prediction: None,
+                confidence: None,
+                attributes: None,
+                rotation: None,
+                frame_index: None,
+                track_id: None,
+                is_interpolated: None,
+                mask: None,
             }],
             metadata: None,
+            base_annotation_id: None,
         };
 
         let req = test::TestRequest::post()
@@ -993,8 +1694,8 @@ mod tests {
         let task = crate::tasks::create_task_in_db(&pool, project.id, "Test Task", Some("test.jpg")).await.unwrap();
 
         // Create test annotations
-        let bbox1 = BoundingBox { category_id: category.id, bbox: vec![100.0, 50.0, 200.0, 150.0], area: Some(30000.0), iscrowd: Some(false) };
-        let bbox2 = BoundingBox { category_id: category.id, bbox: vec![300.0, 100.0, 150.0, 100.0], area: Some(15000.0), iscrowd: Some(false) };
+        let bbox1 = BoundingBox { category_id: category.id, bbox: vec![100.0, 50.0, 200.0, 150.0], area: Some(30000.0), iscrowd: Some(false), is_prediction: None, confidence: None, attributes: None, rotation: None, frame_index: None, track_id: None, is_interpolated: None, mask: None };
+        let bbox2 = BoundingBox { category_id: category.id, bbox: vec![300.0, 100.0, 150.0, 100.0], area: Some(15000.0), iscrowd: Some(false), is_prediction: None, confidence: None, attributes: None, rotation: None, frame_index: None, track_id: None, is_interpolated: None, mask: None };
         create_annotation_in_db(&pool, task.id, &[bbox1], &serde_json::json!({}), user.id).await.unwrap();
         create_annotation_in_db(&pool, task.id, &[bbox2], &serde_json::json!({}), user.id).await.unwrap();
 
@@ -1031,7 +1732,7 @@ mod tests {
         let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, Some("human"), Some("#FF0000"), Some(1)).await.unwrap();
         let task = crate::tasks::create_task_in_db(&pool, project.id, "Test Task", Some("test.jpg")).await.unwrap();
 
-        let bbox = BoundingBox { category_id: category.id, bbox: vec![100.0, 50.0, 200.0, 150.0], area: Some(30000.0), iscrowd: Some(false) };
+        let bbox = BoundingBox { category_id: category.id, bbox: vec![100.0, 50.0, 200.0, 150.0], area: Some(30000.0), iscrowd: Some(false), is_prediction: None, confidence: None, attributes: None, rotation: None, frame_index: None, track_id: None, is_interpolated: None, mask: None };
         let annotations = create_annotation_in_db(&pool, task.id, &[bbox], &serde_json::json!({}), user.id).await.unwrap();
         let annotation = &annotations[0];
 
@@ -1056,6 +1757,59 @@ mod tests {
         assert_eq!(body["annotations"][0]["bbox"], serde_json::json!([100.0, 50.0, 200.0, 150.0]));
     }
 
+    #[actix_web::test]
+    #[serial]
+    async fn test_get_annotation_keeps_blind_copies_apart() {
+        let pool = test_utils::setup_test_db().await;
+        let owner = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let auth_storage = AuthStorage::new(pool.clone());
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, owner.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, None, None, None).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "Test Task", Some("test.jpg")).await.unwrap();
+
+        let annotator_id = test_utils::create_test_user(&pool).await;
+        sqlx::query("INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, 'member')")
+            .bind(project.id)
+            .bind(annotator_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO task_assignments (task_id, user_id) VALUES ($1, $2)")
+            .bind(task.id)
+            .bind(annotator_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let annotator_token = JwtManager::new(&oauth_config.jwt_secret)
+            .generate_token(&annotator_id.to_string(), &format!("test-{}@example.com", annotator_id), "Test User")
+            .unwrap();
+
+        let bbox = BoundingBox { category_id: category.id, bbox: vec![10.0, 10.0, 20.0, 20.0], area: None, iscrowd: None, is_prediction: None, confidence: None, attributes: None, rotation: None, frame_index: None, track_id: None, is_interpolated: None, mask: None };
+        let others = create_annotation_in_db(&pool, task.id, std::slice::from_ref(&bbox), &serde_json::json!({}), owner.id).await.unwrap();
+        let own = create_annotation_in_db(&pool, task.id, &[bbox], &serde_json::json!({}), annotator_id).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .app_data(web::Data::new(auth_storage))
+                .route("/projects/{project_id}/tasks/{task_id}/annotations/{annotation_id}", web::get().to(get_annotation))
+        ).await;
+
+        let get = |annotation_id: Uuid| test::TestRequest::get()
+            .uri(&format!("/projects/{}/tasks/{}/annotations/{}", project.id, task.id, annotation_id))
+            .insert_header(("Authorization", format!("Bearer {}", annotator_token)))
+            .to_request();
+
+        let resp = test::call_service(&app, get(others[0].annotation_id)).await;
+        assert_eq!(resp.status(), 404);
+
+        let resp = test::call_service(&app, get(own[0].annotation_id)).await;
+        assert_eq!(resp.status(), 200);
+    }
+
     #[actix_web::test]
     #[serial]
     async fn test_update_annotation_success() {
@@ -1069,7 +1823,7 @@ mod tests {
         let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, Some("human"), Some("#FF0000"), Some(1)).await.unwrap();
         let task = crate::tasks::create_task_in_db(&pool, project.id, "Test Task", Some("test.jpg")).await.unwrap();
 
-        let bbox = BoundingBox { category_id: category.id, bbox: vec![100.0, 50.0, 200.0, 150.0], area: Some(30000.0), iscrowd: Some(false) };
+        let bbox = BoundingBox { category_id: category.id, bbox: vec![100.0, 50.0, 200.0, 150.0], area: Some(30000.0), iscrowd: Some(false), is_prediction: None, confidence: None, attributes: None, rotation: None, frame_index: None, track_id: None, is_interpolated: None, mask: None };
         let annotations = create_annotation_in_db(&pool, task.id, &[bbox], &serde_json::json!({}), user.id).await.unwrap();
         let annotation = &annotations[0];
 
@@ -1087,6 +1841,14 @@ mod tests {
                 bbox: vec![120.0, 60.0, 180.0, 140.0], // Updated bbox
                 area: Some(25200.0),
                 iscrowd: Some(true),
+                is_prediction: None,
+                confidence: None,
+                attributes: None,
+                rotation: None,
+                frame_index: None,
+                track_id: None,
+                is_interpolated: None,
+                mask: None,
             }],
             metadata: Some(serde_json::json!({"confidence": 0.85, "updated": true})),
         };
@@ -1119,7 +1881,7 @@ mod tests {
         let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, Some("human"), Some("#FF0000"), Some(1)).await.unwrap();
         let task = crate::tasks::create_task_in_db(&pool, project.id, "Test Task", Some("test.jpg")).await.unwrap();
 
-        let bbox = BoundingBox { category_id: category.id, bbox: vec![100.0, 50.0, 200.0, 150.0], area: Some(30000.0), iscrowd: Some(false) };
+        let bbox = BoundingBox { category_id: category.id, bbox: vec![100.0, 50.0, 200.0, 150.0], area: Some(30000.0), iscrowd: Some(false), is_prediction: None, confidence: None, attributes: None, rotation: None, frame_index: None, track_id: None, is_interpolated: None, mask: None };
         let annotations = create_annotation_in_db(&pool, task.id, &[bbox], &serde_json::json!({}), user.id).await.unwrap();
         let annotation = &annotations[0];
 
@@ -1169,8 +1931,17 @@ mod tests {
                 bbox: vec![100.0, 50.0, 200.0, 150.0],
                 area: None,
                 iscrowd: None,
+                is_prediction: None,
+                confidence: None,
+                attributes: None,
+                rotation: None,
+                frame_index: None,
+                track_id: None,
+                is_interpolated: None,
+                mask: None,
             }],
             metadata: None,
+            base_annotation_id: None,
         };
 
         let req = test::TestRequest::post()
@@ -1209,8 +1980,17 @@ mod tests {
                 bbox: vec![100.0, 50.0, 200.0, 150.0],
                 area: None,
                 iscrowd: None,
+                is_prediction: None,
+                confidence: None,
+                attributes: None,
+                rotation: None,
+                frame_index: None,
+                track_id: None,
+                is_interpolated: None,
+                mask: None,
             }],
             metadata: None,
+            base_annotation_id: None,
         };
 
         let req = test::TestRequest::post()
@@ -1221,4 +2001,404 @@ mod tests {
         let resp = test::call_service(&app, req).await;
         assert_eq!(resp.status(), 401);
     }
-}
\ No newline at end of file
+    #[actix_web::test]
+    #[serial]
+    async fn test_create_annotation_validates_attributes() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "car", None, None, Some("#FF0000"), Some(1)).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "Test Task", Some("test.jpg")).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/image-annotation-categories/{category_id}/attribute-schema", web::put().to(crate::image_annotation_categories::update_image_annotation_category_attribute_schema))
+                .route("/projects/{project_id}/tasks/{task_id}/annotations", web::post().to(create_annotation))
+        ).await;
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/projects/{}/image-annotation-categories/{}/attribute-schema", project.id, category.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(serde_json::json!({"attribute_schema": [
+                {"name": "occluded", "type": "bool", "default": false},
+                {"name": "color", "type": "enum", "options": ["red", "blue"], "required": true}
+            ]}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let annotation_request = |attributes: serde_json::Value| CreateAnnotationRequest {
+            bboxes: vec![BoundingBox {
+                category_id: category.id,
+                bbox: vec![10.0, 10.0, 50.0, 50.0],
+                area: None,
+                iscrowd: None,
+                is_prediction: None,
+                confidence: None,
+                attributes: Some(attributes),
+                rotation: None,
+                frame_index: None,
+                track_id: None,
+                is_interpolated: None,
+                mask: None,
+            }],
+            metadata: None,
+            base_annotation_id: None,
+        };
+
+        // Missing required enum value
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/tasks/{}/annotations", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(annotation_request(serde_json::json!({"occluded": true})))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/tasks/{}/annotations", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(annotation_request(serde_json::json!({"color": "blue"})))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["annotations"][0]["attributes"], serde_json::json!({"color": "blue", "occluded": false}));
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_create_annotation_with_rotation() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "ship", None, None, Some("#FF0000"), Some(1)).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "Test Task", Some("test.jpg")).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/tasks/{task_id}/annotations", web::post().to(create_annotation))
+        ).await;
+
+        let annotation_request = |rotation: f64| CreateAnnotationRequest {
+            bboxes: vec![BoundingBox {
+                category_id: category.id,
+                bbox: vec![10.0, 10.0, 50.0, 20.0],
+                area: None,
+                iscrowd: None,
+                is_prediction: None,
+                confidence: None,
+                attributes: None,
+                rotation: Some(rotation),
+                frame_index: None,
+                track_id: None,
+                is_interpolated: None,
+                mask: None,
+            }],
+            metadata: None,
+            base_annotation_id: None,
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/tasks/{}/annotations", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(annotation_request(400.0))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+
+        // Rotations are stored normalized to (-180, 180]
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/tasks/{}/annotations", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(annotation_request(270.0))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["annotations"][0]["rotation"], serde_json::json!(-90.0));
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_create_annotation_with_mask() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, None, None, None).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "Test Task", Some("test.jpg")).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/tasks/{task_id}/annotations", web::post().to(create_annotation))
+                .route("/projects/{project_id}/tasks/{task_id}/annotations", web::get().to(list_annotations))
+        ).await;
+
+        // A 2x2 square in the middle of a 4x4 image
+        let annotation_request = |counts: Vec<u32>| CreateAnnotationRequest {
+            bboxes: vec![BoundingBox {
+                category_id: category.id,
+                bbox: vec![1.0, 1.0, 2.0, 2.0],
+                area: None,
+                iscrowd: Some(true),
+                is_prediction: None,
+                confidence: None,
+                attributes: None,
+                rotation: None,
+                frame_index: None,
+                track_id: None,
+                is_interpolated: None,
+                mask: Some(CocoRle { size: [4, 4], counts: crate::coco::types::RleCounts::Uncompressed(counts) }),
+            }],
+            metadata: None,
+            base_annotation_id: None,
+        };
+        let uri = format!("/projects/{}/tasks/{}/annotations", project.id, task.id);
+
+        // The runs have to cover the image
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(annotation_request(vec![5, 2, 2]))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+
+        // And the mask has to be as large as the image, once its size is known
+        let set_image_size = |width: i32, height: i32| {
+            sqlx::query("UPDATE tasks SET width = $2, height = $3 WHERE id = $1")
+                .bind(task.id)
+                .bind(width)
+                .bind(height)
+                .execute(&pool)
+        };
+        set_image_size(6, 4).await.unwrap();
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(annotation_request(vec![5, 2, 2, 2, 5]))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+        set_image_size(4, 4).await.unwrap();
+
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(annotation_request(vec![5, 2, 2, 2, 5]))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+
+        // Stored with compressed counts, the area counts the pixels of the mask
+        let req = test::TestRequest::get()
+            .uri(&format!("{}?latest_only=true", uri))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["annotations"][0]["mask"], serde_json::json!({"size": [4, 4], "counts": "52203"}));
+        assert_eq!(body["annotations"][0]["area"], 4.0);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_saves_from_an_outdated_base_conflict() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, None, Some("#FF0000"), Some(1)).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "Test Task", Some("test.jpg")).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/tasks/{task_id}/annotations", web::post().to(create_annotation))
+        ).await;
+
+        let annotation_request = |base_annotation_id: Option<Uuid>| CreateAnnotationRequest {
+            bboxes: vec![BoundingBox {
+                category_id: category.id,
+                bbox: vec![10.0, 10.0, 50.0, 20.0],
+                area: None,
+                iscrowd: None,
+                is_prediction: None,
+                confidence: None,
+                attributes: None,
+                rotation: None,
+                frame_index: None,
+                track_id: None,
+                is_interpolated: None,
+                mask: None,
+            }],
+            metadata: None,
+            base_annotation_id,
+        };
+        let uri = format!("/projects/{}/tasks/{}/annotations", project.id, task.id);
+
+        // The nil UUID is the base of a task without annotations
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(annotation_request(Some(Uuid::nil())))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let first_id = body["annotations"][0]["annotation_id"].clone();
+
+        // Someone else saved meanwhile
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(annotation_request(Some(Uuid::nil())))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 409);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["latest_annotation_id"], first_id);
+        assert_eq!(body["annotations"][0]["bbox"], serde_json::json!([10.0, 10.0, 50.0, 20.0]));
+
+        let first_id: Uuid = serde_json::from_value(first_id).unwrap();
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(annotation_request(Some(first_id)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+
+        // Saves without a base are not checked
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(annotation_request(None))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_create_annotation_on_video_frames() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "car", None, None, Some("#FF0000"), Some(1)).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "drive.mp4", Some("storage://drive.mp4")).await.unwrap();
+        let frames: Vec<crate::video::ExtractedFrame> = (0..2)
+            .map(|frame_index| crate::video::ExtractedFrame {
+                frame_index,
+                storage_key: crate::video::frame_storage_key("drive.mp4", frame_index),
+                timestamp_ms: crate::video::frame_timestamp_ms(frame_index, 1.0),
+            })
+            .collect();
+        crate::video::save_task_frames(&pool, task.id, crate::video::MEDIA_TYPE_VIDEO, &frames, Some(1.0)).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/tasks/{task_id}/annotations", web::post().to(create_annotation))
+        ).await;
+
+        let annotation_request = |frame_index: Option<i32>| CreateAnnotationRequest {
+            bboxes: vec![BoundingBox {
+                category_id: category.id,
+                bbox: vec![10.0, 10.0, 50.0, 20.0],
+                area: None,
+                iscrowd: None,
+                is_prediction: None,
+                confidence: None,
+                attributes: None,
+                rotation: None,
+                frame_index,
+                track_id: None,
+                is_interpolated: None,
+                mask: None,
+            }],
+            metadata: None,
+            base_annotation_id: None,
+        };
+
+        // Video boxes need a frame that was extracted
+        for frame_index in [None, Some(2)] {
+            let req = test::TestRequest::post()
+                .uri(&format!("/projects/{}/tasks/{}/annotations", project.id, task.id))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(annotation_request(frame_index))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), 400);
+        }
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/tasks/{}/annotations", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(annotation_request(Some(1)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["annotations"][0]["frame_index"], serde_json::json!(1));
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_resolve_save_context() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, None, None, Some(1)).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "Test Task", Some("test.jpg")).await.unwrap();
+
+        let other_project = crate::projects::create_project_in_db(&pool, "Other Project", None, None, user.id).await.unwrap();
+        let other_category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, other_project.id, "car", None, None, None, Some(1)).await.unwrap();
+
+        let context = resolve_save_context(&pool, project.id, task.id, user.id, &[category.id, other_category.id])
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(context.has_access);
+        assert!(context.task_exists);
+        assert_eq!(context.frame_count, None);
+        assert_eq!(context.task_type, crate::projects::TASK_TYPE_DETECTION);
+        assert!(context.category_schemas.contains_key(&category.id));
+        assert!(!context.category_schemas.contains_key(&other_category.id));
+
+        // A stranger and the task of another project are told apart from a missing project
+        let context = resolve_save_context(&pool, other_project.id, task.id, Uuid::new_v4(), &[]).await.unwrap().unwrap();
+        assert!(!context.has_access);
+        assert!(!context.task_exists);
+        assert!(resolve_save_context(&pool, Uuid::new_v4(), task.id, user.id, &[]).await.unwrap().is_none());
+
+        assert!(matches!(resolve_task_access(&pool, project.id, task.id, user.id).await.unwrap(), TaskAccess::Granted));
+        assert!(matches!(resolve_task_access(&pool, other_project.id, task.id, user.id).await.unwrap(), TaskAccess::TaskNotInProject));
+        assert!(matches!(resolve_task_access(&pool, project.id, task.id, Uuid::new_v4()).await.unwrap(), TaskAccess::NoProjectAccess));
+    }
+}
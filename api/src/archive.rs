@@ -0,0 +1,361 @@
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::Utc;
+
+use crate::auth::{JwtManager, Claims};
+use crate::projects::Project;
+
+/// Project routes that still take writes while the project is archived: archiving itself,
+/// cloning it into a new project and sharing it read-only
+const OPEN_WHILE_ARCHIVED: [&str; 3] = ["archive", "clone", "shares"];
+
+/// The project a request would change, for writes under `/projects/{id}` that archived
+/// projects refuse. Deleting the project itself is left open, archived projects are often the
+/// ones on their way out.
+fn written_project(method: &Method, path: &str) -> Option<Uuid> {
+    if [Method::GET, Method::HEAD, Method::OPTIONS].contains(method) {
+        return None;
+    }
+    let mut segments = path.trim_start_matches('/').split('/');
+    if segments.next() != Some("projects") {
+        return None;
+    }
+    let project_id = Uuid::parse_str(segments.next()?).ok()?;
+    match segments.next() {
+        None if *method == Method::DELETE => None,
+        Some(resource) if OPEN_WHILE_ARCHIVED.contains(&resource) => None,
+        _ => Some(project_id),
+    }
+}
+
+/// Answers writes to archived projects with 423 Locked before they reach the handlers, so
+/// every endpoint of a project is read-only until it is unarchived
+pub async fn enforce_archived<B: MessageBody>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, actix_web::Error> {
+    let project_id = written_project(req.method(), req.path());
+    let pool = req.app_data::<web::Data<Pool<Postgres>>>().cloned();
+
+    if let (Some(project_id), Some(pool)) = (project_id, pool) {
+        if crate::cache::project_is_archived(&pool, project_id).await {
+            let response = HttpResponse::Locked().json("Project is archived");
+            return Ok(req.into_response(response).map_into_right_body());
+        }
+    }
+
+    next.call(req).await.map(ServiceResponse::map_into_left_body)
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{id}/archive",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 200, description = "The archived project, read-only and left out of the project list by default", body = crate::projects::ProjectResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found, or the user is not an owner or admin of it", body = String),
+    ),
+)]
+pub async fn archive_project(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    set_archived(req, path, pool, config, true).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/projects/{id}/archive",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 200, description = "The project, writable again", body = crate::projects::ProjectResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found, or the user is not an owner or admin of it", body = String),
+    ),
+)]
+pub async fn unarchive_project(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    set_archived(req, path, pool, config, false).await
+}
+
+async fn set_archived(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+    archived: bool,
+) -> HttpResponse {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Owners and admins archive a project, members only read it while it is archived
+    match crate::projects::user_can_manage_storage(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check project access"),
+    }
+
+    match set_archived_in_db(&pool, project_id, archived).await {
+        Ok(Some(project)) => {
+            crate::cache::invalidate_archived(project_id).await;
+            crate::projects::invalidate_member_project_lists(&pool, project_id).await;
+            HttpResponse::Ok().json(crate::projects::ProjectResponse { project })
+        }
+        Ok(None) => HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to update project"),
+    }
+}
+
+/// Archives the project, keeping when it was first archived, or unarchives it
+async fn set_archived_in_db(pool: &Pool<Postgres>, project_id: Uuid, archived: bool) -> Result<Option<Project>, sqlx::Error> {
+    let now = Utc::now();
+    sqlx::query_as::<_, Project>(
+        r#"
+        UPDATE projects
+        SET archived_at = CASE WHEN $2 THEN COALESCE(archived_at, $3) END,
+            updated_at = $3
+        WHERE id = $1
+        RETURNING id, name, description, storage_config, owner_id, task_type, strict_bounds, archived_at, created_at, updated_at
+        "#
+    )
+    .bind(project_id)
+    .bind(archived)
+    .bind(now)
+    .fetch_optional(pool)
+    .await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{middleware, test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    #[actix_web::test]
+    async fn test_written_project() {
+        let id = Uuid::parse_str("9b2f0c1e-3f7a-4a59-8f3e-1c2d3e4f5a6b").unwrap();
+
+        assert_eq!(written_project(&Method::PUT, &format!("/projects/{}", id)), Some(id));
+        assert_eq!(written_project(&Method::POST, &format!("/projects/{}/tasks/x/annotations", id)), Some(id));
+        assert_eq!(written_project(&Method::DELETE, &format!("/projects/{}/image-annotation-categories/x", id)), Some(id));
+
+        // Reads, the archive itself, clones, shares and deleting the project stay open
+        assert_eq!(written_project(&Method::GET, &format!("/projects/{}/tasks", id)), None);
+        assert_eq!(written_project(&Method::DELETE, &format!("/projects/{}", id)), None);
+        assert_eq!(written_project(&Method::DELETE, &format!("/projects/{}/archive", id)), None);
+        assert_eq!(written_project(&Method::POST, &format!("/projects/{}/clone", id)), None);
+        assert_eq!(written_project(&Method::POST, &format!("/projects/{}/shares", id)), None);
+
+        // Not a project of its own
+        assert_eq!(written_project(&Method::POST, "/projects"), None);
+        assert_eq!(written_project(&Method::POST, "/projects/not-a-uuid"), None);
+        assert_eq!(written_project(&Method::POST, "/templates"), None);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_archived_projects_are_read_only_and_hidden() {
+        let pool = test_utils::setup_test_db().await;
+        let owner = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &owner);
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, owner.id).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .wrap(middleware::from_fn(enforce_archived))
+                .route("/projects", web::get().to(crate::projects::list_projects))
+                .route("/projects/{id}", web::put().to(crate::projects::update_project))
+                .route("/projects/{id}/archive", web::post().to(archive_project))
+                .route("/projects/{id}/archive", web::delete().to(unarchive_project))
+        ).await;
+        let rename = |name: &str| {
+            test::TestRequest::put()
+                .uri(&format!("/projects/{}", project.id))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(serde_json::json!({ "name": name }))
+                .to_request()
+        };
+        let list = |include_archived: bool| {
+            test::TestRequest::get()
+                .uri(&format!("/projects?include_archived={}", include_archived))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request()
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/archive", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert!(body["project"]["archived_at"].is_string());
+
+        assert_eq!(test::call_service(&app, rename("Renamed")).await.status(), 423);
+
+        let body: serde_json::Value = test::call_and_read_body_json(&app, list(false)).await;
+        assert!(body["projects"].as_array().unwrap().is_empty());
+        let body: serde_json::Value = test::call_and_read_body_json(&app, list(true)).await;
+        assert_eq!(body["projects"].as_array().unwrap().len(), 1);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/projects/{}/archive", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert!(body["project"]["archived_at"].is_null());
+
+        assert_eq!(test::call_service(&app, rename("Renamed")).await.status(), 200);
+        let body: serde_json::Value = test::call_and_read_body_json(&app, list(false)).await;
+        assert_eq!(body["projects"].as_array().unwrap().len(), 1);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_members_cannot_archive() {
+        let pool = test_utils::setup_test_db().await;
+        let owner = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, owner.id).await.unwrap();
+
+        let member_id = test_utils::create_test_user(&pool).await;
+        sqlx::query("INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, 'member')")
+            .bind(project.id)
+            .bind(member_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let member_token = JwtManager::new(&oauth_config.jwt_secret)
+            .generate_token(&member_id.to_string(), &format!("test-{}@example.com", member_id), "Test User")
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{id}/archive", web::post().to(archive_project))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/archive", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", member_token)))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+        assert!(!crate::cache::project_is_archived(&pool, project.id).await);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_archived_projects_can_be_deleted() {
+        let pool = test_utils::setup_test_db().await;
+        let owner = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &owner);
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, owner.id).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .wrap(middleware::from_fn(enforce_archived))
+                .route("/projects/{id}", web::delete().to(crate::projects::delete_project))
+                .route("/projects/{id}/archive", web::post().to(archive_project))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/archive", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/projects/{}", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+        let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM projects WHERE id = $1)")
+            .bind(project.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(!exists);
+    }
+}
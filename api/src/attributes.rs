@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use utoipa::ToSchema;
+
+// Custom attributes that categories can attach to their annotations,
+// e.g. `occluded: bool`, `color: enum` or `plate_text: string`
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeType {
+    Bool,
+    Enum,
+    String,
+    Number,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AttributeDefinition {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub attribute_type: AttributeType,
+    /// Allowed values for `enum` attributes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub required: bool,
+    /// Value used when an annotation omits the attribute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+}
+
+pub fn validate_attribute_schema(schema: &[AttributeDefinition]) -> Result<(), String> {
+    let mut names = HashSet::new();
+
+    for definition in schema {
+        let name = definition.name.as_str();
+        if name.trim().is_empty() {
+            return Err("Attribute name cannot be empty".to_string());
+        }
+        if name.len() > 100 {
+            return Err(format!("Attribute name '{}' too long (max 100 characters)", name));
+        }
+        if !names.insert(name) {
+            return Err(format!("Duplicate attribute name '{}'", name));
+        }
+
+        match definition.attribute_type {
+            AttributeType::Enum if definition.options.is_empty() => {
+                return Err(format!("Enum attribute '{}' must define at least one option", name));
+            }
+            AttributeType::Enum => {}
+            _ if !definition.options.is_empty() => {
+                return Err(format!("Only enum attributes can define options ('{}')", name));
+            }
+            _ => {}
+        }
+
+        if let Some(default) = &definition.default {
+            check_value(definition, default)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks an annotation's attribute payload against a category schema and returns
+/// the normalized object, with defaults filled in for omitted attributes.
+pub fn validate_attributes(
+    schema: &[AttributeDefinition],
+    attributes: Option<&serde_json::Value>,
+) -> Result<serde_json::Value, String> {
+    let mut values = match attributes {
+        None | Some(serde_json::Value::Null) => serde_json::Map::new(),
+        Some(serde_json::Value::Object(values)) => values.clone(),
+        Some(_) => return Err("attributes must be a JSON object".to_string()),
+    };
+
+    if let Some(unknown) = values.keys().find(|key| !schema.iter().any(|definition| &definition.name == *key)) {
+        return Err(format!("Unknown attribute '{}'", unknown));
+    }
+
+    for definition in schema {
+        match values.get(&definition.name) {
+            Some(value) => check_value(definition, value)?,
+            None => match &definition.default {
+                Some(default) => {
+                    values.insert(definition.name.clone(), default.clone());
+                }
+                None if definition.required => {
+                    return Err(format!("Missing required attribute '{}'", definition.name));
+                }
+                None => {}
+            },
+        }
+    }
+
+    Ok(serde_json::Value::Object(values))
+}
+
+fn check_value(definition: &AttributeDefinition, value: &serde_json::Value) -> Result<(), String> {
+    let valid = match definition.attribute_type {
+        AttributeType::Bool => value.is_boolean(),
+        AttributeType::String => value.is_string(),
+        AttributeType::Number => value.is_number(),
+        AttributeType::Enum => value
+            .as_str()
+            .is_some_and(|value| definition.options.iter().any(|option| option == value)),
+    };
+
+    if valid {
+        return Ok(());
+    }
+
+    match definition.attribute_type {
+        AttributeType::Enum => Err(format!(
+            "Attribute '{}' must be one of: {}",
+            definition.name,
+            definition.options.join(", ")
+        )),
+        AttributeType::Bool => Err(format!("Attribute '{}' must be a boolean", definition.name)),
+        AttributeType::String => Err(format!("Attribute '{}' must be a string", definition.name)),
+        AttributeType::Number => Err(format!("Attribute '{}' must be a number", definition.name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn vehicle_schema() -> Vec<AttributeDefinition> {
+        serde_json::from_value(json!([
+            {"name": "occluded", "type": "bool", "default": false},
+            {"name": "color", "type": "enum", "options": ["red", "blue"], "required": true},
+            {"name": "plate_text", "type": "string"},
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate_attributes_fills_defaults() {
+        let schema = vehicle_schema();
+        let attributes = validate_attributes(&schema, Some(&json!({"color": "red"}))).unwrap();
+        assert_eq!(attributes, json!({"color": "red", "occluded": false}));
+    }
+
+    #[test]
+    fn test_validate_attributes_rejects_invalid_payloads() {
+        let schema = vehicle_schema();
+        assert!(validate_attributes(&schema, None).is_err()); // color is required
+        assert!(validate_attributes(&schema, Some(&json!({"color": "green"}))).is_err());
+        assert!(validate_attributes(&schema, Some(&json!({"color": "red", "occluded": "yes"}))).is_err());
+        assert!(validate_attributes(&schema, Some(&json!({"color": "red", "speed": 3}))).is_err());
+        assert!(validate_attributes(&schema, Some(&json!(["red"]))).is_err());
+        assert_eq!(validate_attributes(&[], None).unwrap(), json!({}));
+    }
+
+    #[test]
+    fn test_validate_attribute_schema() {
+        assert!(validate_attribute_schema(&vehicle_schema()).is_ok());
+
+        let enum_without_options: Vec<AttributeDefinition> =
+            serde_json::from_value(json!([{"name": "color", "type": "enum"}])).unwrap();
+        assert!(validate_attribute_schema(&enum_without_options).is_err());
+
+        let duplicates: Vec<AttributeDefinition> =
+            serde_json::from_value(json!([{"name": "a", "type": "bool"}, {"name": "a", "type": "string"}])).unwrap();
+        assert!(validate_attribute_schema(&duplicates).is_err());
+
+        let bad_default: Vec<AttributeDefinition> =
+            serde_json::from_value(json!([{"name": "occluded", "type": "bool", "default": "no"}])).unwrap();
+        assert!(validate_attribute_schema(&bad_default).is_err());
+    }
+}
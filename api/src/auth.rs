@@ -8,8 +8,9 @@ use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use jsonwebtoken::{encode, decode, Header, EncodingKey, DecodingKey, Validation};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
 pub struct User {
     pub id: Uuid,
     pub email: String,
@@ -21,6 +22,9 @@ pub struct User {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Providers users sign in with
+pub const PROVIDERS: [&str; 2] = ["google", "github"];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
@@ -28,6 +32,10 @@ pub struct Claims {
     pub name: String,
     pub exp: usize,
     pub iat: usize,
+    /// Session the token was issued for, checked on every request so revoked sessions stop
+    /// working before the token expires. Missing from tokens issued outside a login.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -60,19 +68,22 @@ pub struct AuthResponse {
     pub user: User,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AuthUrlResponse {
     pub poll_token: String,
     pub auth_url: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct PollResponse {
     pub status: String,
     pub jwt: Option<String>,
+    /// Trades for a new JWT at `/auth/refresh` once this one expires
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UserInfoResponse {
     pub user: User,
 }
@@ -83,11 +94,22 @@ struct PendingAuth {
     #[allow(dead_code)]
     pub auth_key: String,
     pub jwt: Option<String>,
+    pub refresh_token: Option<String>,
     #[allow(dead_code)]
     pub csrf_token: String,
     pub expires_at: DateTime<Utc>,
     #[allow(dead_code)]
     pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// What a login was started for, read back when the provider calls back
+#[derive(Debug, Default, sqlx::FromRow)]
+pub(crate) struct PendingLogin {
+    /// User the provider account gets linked to, `None` for a plain login
+    pub link_user_id: Option<Uuid>,
+    /// Device the login was started on, named by the client
+    pub device_name: Option<String>,
 }
 
 #[derive(Clone)]
@@ -100,27 +122,48 @@ impl AuthStorage {
         Self { pool }
     }
     
-    pub async fn create_pending_auth(&self, csrf_token: String) -> Result<String, sqlx::Error> {
+    /// Starts a login; with `link_user_id` the provider account gets linked to that user instead
+    pub async fn create_pending_auth(
+        &self,
+        csrf_token: String,
+        link_user_id: Option<Uuid>,
+        device_name: Option<&str>,
+    ) -> Result<String, sqlx::Error> {
         let auth_key = Uuid::new_v4().to_string();
         let expires_at = Utc::now() + chrono::Duration::minutes(5);
         
         sqlx::query(
-            "INSERT INTO pending_auths (auth_key, csrf_token, expires_at) VALUES ($1, $2, $3)"
+            "INSERT INTO pending_auths (auth_key, csrf_token, expires_at, link_user_id, device_name) VALUES ($1, $2, $3, $4, $5)"
         )
         .bind(&auth_key)
         .bind(&csrf_token)
         .bind(expires_at)
+        .bind(link_user_id)
+        .bind(device_name)
         .execute(&self.pool)
         .await?;
         
         Ok(auth_key)
     }
+
+    /// What the login of `csrf_token` was started for, `None` once it expired
+    pub(crate) async fn pending_login(&self, csrf_token: &str) -> Result<Option<PendingLogin>, sqlx::Error> {
+        sqlx::query_as::<_, PendingLogin>(
+            "SELECT link_user_id, device_name FROM pending_auths WHERE csrf_token = $1 AND expires_at > NOW()"
+        )
+        .bind(csrf_token)
+        .fetch_optional(&self.pool)
+        .await
+    }
     
-    pub async fn complete_auth(&self, csrf_token: &str, jwt: String) -> Result<bool, sqlx::Error> {
+    /// Marks the login completed, with the tokens the poll hands out. Logins that link a
+    /// provider complete without any.
+    pub async fn complete_auth(&self, csrf_token: &str, jwt: Option<String>, refresh_token: Option<String>) -> Result<bool, sqlx::Error> {
         let result = sqlx::query(
-            "UPDATE pending_auths SET jwt = $1 WHERE csrf_token = $2 AND expires_at > NOW()"
+            "UPDATE pending_auths SET jwt = $1, refresh_token = $2, completed_at = NOW() WHERE csrf_token = $3 AND expires_at > NOW()"
         )
         .bind(&jwt)
+        .bind(&refresh_token)
         .bind(csrf_token)
         .execute(&self.pool)
         .await?;
@@ -130,7 +173,7 @@ impl AuthStorage {
     
     pub async fn get_auth_status(&self, auth_key: &str) -> Result<Option<PollResponse>, sqlx::Error> {
         let pending = sqlx::query_as::<_, PendingAuth>(
-            "SELECT id, auth_key, jwt, csrf_token, expires_at, created_at FROM pending_auths WHERE auth_key = $1"
+            "SELECT id, auth_key, jwt, refresh_token, csrf_token, expires_at, created_at, completed_at FROM pending_auths WHERE auth_key = $1"
         )
         .bind(auth_key)
         .fetch_optional(&self.pool)
@@ -148,8 +191,9 @@ impl AuthStorage {
                     Ok(Some(PollResponse {
                         status: "expired".to_string(),
                         jwt: None,
+                        refresh_token: None,
                     }))
-                } else if let Some(jwt) = auth.jwt {
+                } else if auth.completed_at.is_some() {
                     // Clean up completed record
                     let _ = sqlx::query("DELETE FROM pending_auths WHERE id = $1")
                         .bind(auth.id)
@@ -158,12 +202,14 @@ impl AuthStorage {
                         
                     Ok(Some(PollResponse {
                         status: "completed".to_string(),
-                        jwt: Some(jwt),
+                        jwt: auth.jwt,
+                        refresh_token: auth.refresh_token,
                     }))
                 } else {
                     Ok(Some(PollResponse {
                         status: "pending".to_string(),
                         jwt: None,
+                        refresh_token: None,
                     }))
                 }
             }
@@ -180,7 +226,15 @@ impl AuthStorage {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LoginQuery {
+    /// Name of the device the login is for, shown in the list of sessions
+    pub device: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct AuthCallback {
     pub code: String,
     #[allow(dead_code)]
@@ -227,6 +281,17 @@ impl JwtManager {
     }
 
     pub fn generate_token(&self, user_id: &str, email: &str, name: &str) -> Result<String, jsonwebtoken::errors::Error> {
+        self.generate_session_token(user_id, email, name, None)
+    }
+
+    /// Token of a signed-in session, which stops working once the session is revoked
+    pub fn generate_session_token(
+        &self,
+        user_id: &str,
+        email: &str,
+        name: &str,
+        session_id: Option<Uuid>,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
         let now = Utc::now();
         let exp = now + chrono::Duration::hours(24);
 
@@ -236,6 +301,7 @@ impl JwtManager {
             name: name.to_owned(),
             iat: now.timestamp() as usize,
             exp: exp.timestamp() as usize,
+            sid: session_id.map(|id| id.to_string()),
         };
 
         encode(&Header::default(), &claims, &self.encoding_key)
@@ -251,53 +317,86 @@ impl JwtManager {
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/auth/google",
+    tag = "auth",
+    params(LoginQuery),
+    security(()),
+    responses(
+        (status = 200, description = "URL to open in a browser and the token to poll the login with", body = AuthUrlResponse),
+    ),
+)]
 pub async fn google_login(
+    query: web::Query<LoginQuery>,
+    config: web::Data<OAuthConfig>,
+    auth_storage: web::Data<AuthStorage>,
+) -> impl Responder {
+    start_login(&config, &auth_storage, "google", None, query.device.as_deref()).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/github",
+    tag = "auth",
+    params(LoginQuery),
+    security(()),
+    responses(
+        (status = 200, description = "URL to open in a browser and the token to poll the login with", body = AuthUrlResponse),
+    ),
+)]
+pub async fn github_login(
+    query: web::Query<LoginQuery>,
     config: web::Data<OAuthConfig>,
     auth_storage: web::Data<AuthStorage>,
 ) -> impl Responder {
-    let client = BasicClient::new(
+    start_login(&config, &auth_storage, "github", None, query.device.as_deref()).await
+}
+
+fn google_client(config: &OAuthConfig) -> BasicClient {
+    BasicClient::new(
         ClientId::new(config.google_client_id.clone()),
         Some(ClientSecret::new(config.google_client_secret.clone())),
         AuthUrl::new("https://accounts.google.com/o/oauth2/v2/auth".to_string()).unwrap(),
         Some(TokenUrl::new("https://www.googleapis.com/oauth2/v4/token".to_string()).unwrap()),
     )
-    .set_redirect_uri(RedirectUrl::new(config.google_redirect_url.clone()).unwrap());
-
-    let (auth_url, csrf_token) = client
-        .authorize_url(CsrfToken::new_random)
-        .add_scope(Scope::new("email".to_string()))
-        .add_scope(Scope::new("profile".to_string()))
-        .url();
-
-    let poll_token = match auth_storage.create_pending_auth(csrf_token.secret().clone()).await {
-        Ok(token) => token,
-        Err(_) => return HttpResponse::InternalServerError().json("Failed to create auth session"),
-    };
-
-    HttpResponse::Ok().json(AuthUrlResponse {
-        poll_token,
-        auth_url: auth_url.to_string(),
-    })
+    .set_redirect_uri(RedirectUrl::new(config.google_redirect_url.clone()).unwrap())
 }
 
-pub async fn github_login(
-    config: web::Data<OAuthConfig>,
-    auth_storage: web::Data<AuthStorage>,
-) -> impl Responder {
-    let client = BasicClient::new(
+fn github_client(config: &OAuthConfig) -> BasicClient {
+    BasicClient::new(
         ClientId::new(config.github_client_id.clone()),
         Some(ClientSecret::new(config.github_client_secret.clone())),
         AuthUrl::new("https://github.com/login/oauth/authorize".to_string()).unwrap(),
         Some(TokenUrl::new("https://github.com/login/oauth/access_token".to_string()).unwrap()),
     )
-    .set_redirect_uri(RedirectUrl::new(config.github_redirect_url.clone()).unwrap());
+    .set_redirect_uri(RedirectUrl::new(config.github_redirect_url.clone()).unwrap())
+}
 
-    let (auth_url, csrf_token) = client
-        .authorize_url(CsrfToken::new_random)
-        .add_scope(Scope::new("user:email".to_string()))
-        .url();
+/// Login URL of the provider and the token to poll the result with. With `link_user_id` the
+/// provider account is linked to that user when the login completes.
+pub(crate) async fn start_login(
+    config: &OAuthConfig,
+    auth_storage: &AuthStorage,
+    provider: &str,
+    link_user_id: Option<Uuid>,
+    device_name: Option<&str>,
+) -> HttpResponse {
+    let (auth_url, csrf_token) = match provider {
+        "google" => google_client(config)
+            .authorize_url(CsrfToken::new_random)
+            .add_scope(Scope::new("email".to_string()))
+            .add_scope(Scope::new("profile".to_string()))
+            .url(),
+        "github" => github_client(config)
+            .authorize_url(CsrfToken::new_random)
+            .add_scope(Scope::new("user:email".to_string()))
+            .url(),
+        _ => return HttpResponse::BadRequest().json(format!("Invalid provider. Must be one of: {}", PROVIDERS.join(", "))),
+    };
 
-    let poll_token = match auth_storage.create_pending_auth(csrf_token.secret().clone()).await {
+    let device_name = device_name.map(str::trim).filter(|name| !name.is_empty() && name.len() <= 255);
+    let poll_token = match auth_storage.create_pending_auth(csrf_token.secret().clone(), link_user_id, device_name).await {
         Ok(token) => token,
         Err(_) => return HttpResponse::InternalServerError().json("Failed to create auth session"),
     };
@@ -308,21 +407,25 @@ pub async fn github_login(
     })
 }
 
+#[utoipa::path(
+    get,
+    path = "/auth/google/callback",
+    tag = "auth",
+    params(AuthCallback),
+    security(()),
+    responses(
+        (status = 200, description = "Login completed, the JWT is handed out through polling", body = String),
+        (status = 400, description = "Invalid request", body = String),
+    ),
+)]
 pub async fn google_callback(
+    req: HttpRequest,
     query: web::Query<AuthCallback>,
     pool: web::Data<Pool<Postgres>>,
     config: web::Data<OAuthConfig>,
     auth_storage: web::Data<AuthStorage>,
 ) -> impl Responder {
-    let client = BasicClient::new(
-        ClientId::new(config.google_client_id.clone()),
-        Some(ClientSecret::new(config.google_client_secret.clone())),
-        AuthUrl::new("https://accounts.google.com/o/oauth2/v2/auth".to_string()).unwrap(),
-        Some(TokenUrl::new("https://www.googleapis.com/oauth2/v4/token".to_string()).unwrap()),
-    )
-    .set_redirect_uri(RedirectUrl::new(config.google_redirect_url.clone()).unwrap());
-
-    let token = match client.exchange_code(AuthorizationCode::new(query.code.clone())).request_async(oauth2::reqwest::async_http_client).await {
+    let token = match google_client(&config).exchange_code(AuthorizationCode::new(query.code.clone())).request_async(oauth2::reqwest::async_http_client).await {
         Ok(token) => token,
         Err(_) => return HttpResponse::BadRequest().json("Failed to exchange code for token"),
     };
@@ -340,41 +443,35 @@ pub async fn google_callback(
         Err(_) => return HttpResponse::BadRequest().json("Failed to request user info"),
     };
 
-    match create_or_get_user(&pool, &user_info.email, &user_info.name, user_info.picture.as_deref(), "google", &user_info.id).await {
-        Ok(user) => {
-            let jwt_manager = JwtManager::new(&config.jwt_secret);
-            
-            match jwt_manager.generate_token(&user.id.to_string(), &user.email, &user.name) {
-                Ok(token) => {
-                    // Save JWT using CSRF token
-                    match auth_storage.complete_auth(&query.state, token.clone()).await {
-                        Ok(true) => HttpResponse::Ok().json("Authentication completed. You can close this window."),
-                        Ok(false) => HttpResponse::BadRequest().json("Invalid or expired authentication session"),
-                        Err(_) => HttpResponse::InternalServerError().json("Failed to complete authentication"),
-                    }
-                }
-                Err(_) => HttpResponse::InternalServerError().json("Failed to generate token"),
-            }
-        }
-        Err(_) => HttpResponse::InternalServerError().json("Failed to create user"),
-    }
+    let account = ProviderAccount {
+        provider: "google",
+        provider_id: user_info.id,
+        email: user_info.email,
+        name: user_info.name,
+        avatar_url: user_info.picture,
+    };
+    complete_login(&req, &pool, &config, &auth_storage, &query.state, &account).await
 }
 
+#[utoipa::path(
+    get,
+    path = "/auth/github/callback",
+    tag = "auth",
+    params(AuthCallback),
+    security(()),
+    responses(
+        (status = 200, description = "Login completed, the JWT is handed out through polling", body = String),
+        (status = 400, description = "Invalid request", body = String),
+    ),
+)]
 pub async fn github_callback(
+    req: HttpRequest,
     query: web::Query<AuthCallback>,
     pool: web::Data<Pool<Postgres>>,
     config: web::Data<OAuthConfig>,
     auth_storage: web::Data<AuthStorage>,
 ) -> impl Responder {
-    let client = BasicClient::new(
-        ClientId::new(config.github_client_id.clone()),
-        Some(ClientSecret::new(config.github_client_secret.clone())),
-        AuthUrl::new("https://github.com/login/oauth/authorize".to_string()).unwrap(),
-        Some(TokenUrl::new("https://github.com/login/oauth/access_token".to_string()).unwrap()),
-    )
-    .set_redirect_uri(RedirectUrl::new(config.github_redirect_url.clone()).unwrap());
-
-    let token = match client.exchange_code(AuthorizationCode::new(query.code.clone())).request_async(oauth2::reqwest::async_http_client).await {
+    let token = match github_client(&config).exchange_code(AuthorizationCode::new(query.code.clone())).request_async(oauth2::reqwest::async_http_client).await {
         Ok(token) => token,
         Err(_) => return HttpResponse::BadRequest().json("Failed to exchange code for token"),
     };
@@ -417,78 +514,185 @@ pub async fn github_callback(
         }
     };
 
-    let name = user_info.name.unwrap_or(user_info.login.clone());
+    let account = ProviderAccount {
+        provider: "github",
+        provider_id: user_info.id.to_string(),
+        email,
+        name: user_info.name.unwrap_or(user_info.login),
+        avatar_url: user_info.avatar_url,
+    };
+    complete_login(&req, &pool, &config, &auth_storage, &query.state, &account).await
+}
 
-    match create_or_get_user(&pool, &email, &name, user_info.avatar_url.as_deref(), "github", &user_info.id.to_string()).await {
-        Ok(user) => {
-            let jwt_manager = JwtManager::new(&config.jwt_secret);
-            
-            match jwt_manager.generate_token(&user.id.to_string(), &user.email, &user.name) {
-                Ok(token) => {
-                    // Save JWT using CSRF token
-                    match auth_storage.complete_auth(&query.state, token.clone()).await {
-                        Ok(true) => HttpResponse::Ok().json("Authentication completed. You can close this window."),
-                        Ok(false) => HttpResponse::BadRequest().json("Invalid or expired authentication session"),
-                        Err(_) => HttpResponse::InternalServerError().json("Failed to complete authentication"),
-                    }
-                }
-                Err(_) => HttpResponse::InternalServerError().json("Failed to generate token"),
+/// Who the provider says signed in
+#[derive(Debug)]
+pub(crate) struct ProviderAccount {
+    pub provider: &'static str,
+    pub provider_id: String,
+    pub email: String,
+    pub name: String,
+    pub avatar_url: Option<String>,
+}
+
+/// Hands the JWT of the account out to the poll token. A login started from an account links the
+/// provider account to it rather than signing in with it.
+async fn complete_login(
+    req: &HttpRequest,
+    pool: &Pool<Postgres>,
+    config: &OAuthConfig,
+    auth_storage: &AuthStorage,
+    state: &str,
+    account: &ProviderAccount,
+) -> HttpResponse {
+    let pending = match auth_storage.pending_login(state).await {
+        Ok(pending) => pending.unwrap_or_default(),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to complete authentication"),
+    };
+
+    // Linking happens from a signed-in session, which keeps its tokens
+    if let Some(user_id) = pending.link_user_id {
+        return match link_identity(pool, user_id, account).await {
+            Ok(Some(_)) => match auth_storage.complete_auth(state, None, None).await {
+                Ok(true) => HttpResponse::Ok().json("Account linked. You can close this window."),
+                Ok(false) => HttpResponse::BadRequest().json("Invalid or expired authentication session"),
+                Err(_) => HttpResponse::InternalServerError().json("Failed to complete authentication"),
+            },
+            Ok(None) => HttpResponse::Conflict().json("This account is already linked to another user"),
+            Err(_) => HttpResponse::InternalServerError().json("Failed to link account"),
+        };
+    }
+
+    let user = match create_or_get_user(pool, account).await {
+        Ok(user) => user,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to create user"),
+    };
+
+    let device = crate::sessions::SessionDevice::from_request(req, pending.device_name);
+    let (session_id, refresh_token) = match crate::sessions::create_session(pool, user.id, &device).await {
+        Ok(session) => session,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to create session"),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.generate_session_token(&user.id.to_string(), &user.email, &user.name, Some(session_id)) {
+        Ok(token) => {
+            // Save JWT using CSRF token
+            match auth_storage.complete_auth(state, Some(token), Some(refresh_token)).await {
+                Ok(true) => HttpResponse::Ok().json("Authentication completed. You can close this window."),
+                Ok(false) => HttpResponse::BadRequest().json("Invalid or expired authentication session"),
+                Err(_) => HttpResponse::InternalServerError().json("Failed to complete authentication"),
             }
         }
-        Err(_) => HttpResponse::InternalServerError().json("Failed to create user"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to generate token"),
     }
 }
 
-async fn create_or_get_user(
-    pool: &Pool<Postgres>,
-    email: &str,
-    name: &str,
-    avatar_url: Option<&str>,
-    provider: &str,
-    provider_id: &str,
-) -> Result<User, sqlx::Error> {
+pub(crate) async fn create_or_get_user(pool: &Pool<Postgres>, account: &ProviderAccount) -> Result<User, sqlx::Error> {
+    let linked_user = sqlx::query_as::<_, User>(
+        r#"
+        SELECT u.id, u.email, u.name, u.avatar_url, u.provider, u.provider_id, u.created_at, u.updated_at
+        FROM user_identities ui
+        JOIN users u ON u.id = ui.user_id
+        WHERE ui.provider = $1 AND ui.provider_id = $2
+        "#
+    )
+    .bind(account.provider)
+    .bind(&account.provider_id)
+    .fetch_optional(pool)
+    .await?;
+
+    if let Some(user) = linked_user {
+        return Ok(user);
+    }
+
+    // Accounts created with this provider before identities were recorded
     let existing_user = sqlx::query_as::<_, User>(
         "SELECT id, email, name, avatar_url, provider, provider_id, created_at, updated_at FROM users WHERE email = $1 AND provider = $2"
     )
-    .bind(email)
-    .bind(provider)
+    .bind(&account.email)
+    .bind(account.provider)
     .fetch_optional(pool)
     .await?;
 
-    match existing_user {
-        Some(user) => Ok(user),
+    let user = match existing_user {
+        Some(user) => user,
         None => {
-            let user_id = Uuid::new_v4();
-            let now = Utc::now();
-
-            sqlx::query(
-                "INSERT INTO users (id, email, name, avatar_url, provider, provider_id, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
+            sqlx::query_as::<_, User>(
+                r#"
+                INSERT INTO users (id, email, name, avatar_url, provider, provider_id, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, NOW(), NOW())
+                RETURNING id, email, name, avatar_url, provider, provider_id, created_at, updated_at
+                "#
             )
-            .bind(user_id)
-            .bind(email)
-            .bind(name)
-            .bind(avatar_url)
-            .bind(provider)
-            .bind(provider_id)
-            .bind(now)
-            .bind(now)
-            .execute(pool)
-            .await?;
-
-            Ok(User {
-                id: user_id,
-                email: email.to_string(),
-                name: name.to_string(),
-                avatar_url: avatar_url.map(String::from),
-                provider: provider.to_string(),
-                provider_id: provider_id.to_string(),
-                created_at: now,
-                updated_at: now,
-            })
+            .bind(Uuid::new_v4())
+            .bind(&account.email)
+            .bind(&account.name)
+            .bind(account.avatar_url.as_deref())
+            .bind(account.provider)
+            .bind(&account.provider_id)
+            .fetch_one(pool)
+            .await?
         }
+    };
+
+    sqlx::query(
+        "INSERT INTO user_identities (user_id, provider, provider_id, email) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING"
+    )
+    .bind(user.id)
+    .bind(account.provider)
+    .bind(&account.provider_id)
+    .bind(&account.email)
+    .execute(pool)
+    .await?;
+
+    Ok(user)
+}
+
+/// Lets the user sign in with the provider account too, replacing the account of the same
+/// provider linked before. `None` when the provider account belongs to another user.
+pub(crate) async fn link_identity(pool: &Pool<Postgres>, user_id: Uuid, account: &ProviderAccount) -> Result<Option<User>, sqlx::Error> {
+    let owner = sqlx::query_scalar::<_, Uuid>(
+        "SELECT user_id FROM user_identities WHERE provider = $1 AND provider_id = $2"
+    )
+    .bind(account.provider)
+    .bind(&account.provider_id)
+    .fetch_optional(pool)
+    .await?;
+
+    if owner.is_some_and(|owner| owner != user_id) {
+        return Ok(None);
     }
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_identities (user_id, provider, provider_id, email)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id, provider) DO UPDATE SET provider_id = EXCLUDED.provider_id, email = EXCLUDED.email
+        "#
+    )
+    .bind(user_id)
+    .bind(account.provider)
+    .bind(&account.provider_id)
+    .bind(&account.email)
+    .execute(pool)
+    .await?;
+
+    get_user_by_id(pool, user_id).await
 }
 
+#[utoipa::path(
+    get,
+    path = "/auth/poll/{poll_token}",
+    tag = "auth",
+    params(
+        ("poll_token" = String, Path, description = "Token returned with the login URL"),
+    ),
+    security(()),
+    responses(
+        (status = 200, description = "`pending` until the login completes, then `completed` with the JWT", body = PollResponse),
+        (status = 404, description = "Unknown or expired poll token", body = PollResponse),
+    ),
+)]
 pub async fn poll_auth(
     path: web::Path<String>,
     auth_storage: web::Data<AuthStorage>,
@@ -500,14 +704,26 @@ pub async fn poll_auth(
         Ok(None) => HttpResponse::NotFound().json(PollResponse {
             status: "not_found".to_string(),
             jwt: None,
+            refresh_token: None,
         }),
         Err(_) => HttpResponse::InternalServerError().json(PollResponse {
             status: "error".to_string(),
             jwt: None,
+            refresh_token: None,
         }),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/me",
+    tag = "auth",
+    responses(
+        (status = 200, body = UserInfoResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "User not found", body = String),
+    ),
+)]
 pub async fn get_user_info(
     req: HttpRequest,
     pool: web::Data<Pool<Postgres>>,
@@ -546,7 +762,7 @@ pub async fn get_user_info(
     }
 }
 
-async fn get_user_by_id(pool: &Pool<Postgres>, user_id: Uuid) -> Result<Option<User>, sqlx::Error> {
+pub(crate) async fn get_user_by_id(pool: &Pool<Postgres>, user_id: Uuid) -> Result<Option<User>, sqlx::Error> {
     sqlx::query_as::<_, User>(
         "SELECT id, email, name, avatar_url, provider, provider_id, created_at, updated_at FROM users WHERE id = $1"
     )
@@ -0,0 +1,370 @@
+use redis::AsyncCommands;
+use redis::aio::ConnectionManager;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+const KEY_PREFIX: &str = "fast-tag";
+
+/// Settings of the optional Redis cache of hot read paths
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheConfig {
+    /// Caching is off when unset and every read goes to the database
+    pub redis_url: Option<String>,
+    /// Upper bound of how stale an entry can get if an invalidation is missed
+    pub ttl_secs: u64,
+}
+
+impl CacheConfig {
+    pub fn from_env() -> Self {
+        Self {
+            redis_url: std::env::var("REDIS_URL").ok().filter(|url| !url.is_empty()),
+            ttl_secs: std::env::var("CACHE_TTL_SECS")
+                .ok()
+                .and_then(|ttl| ttl.parse().ok())
+                .unwrap_or(60),
+        }
+    }
+}
+
+struct Cache {
+    connection: ConnectionManager,
+    ttl_secs: u64,
+}
+
+static CACHE: OnceLock<Cache> = OnceLock::new();
+
+/// Connects to Redis when configured. Like the metrics, the cache is global because the access
+/// checks that use it are spread over every handler module.
+pub async fn init(config: &CacheConfig) {
+    let Some(url) = &config.redis_url else {
+        return;
+    };
+
+    let connection = match redis::Client::open(url.as_str()) {
+        Ok(client) => client.get_connection_manager().await,
+        Err(e) => Err(e),
+    };
+    match connection {
+        Ok(connection) => {
+            let _ = CACHE.set(Cache { connection, ttl_secs: config.ttl_secs });
+            println!("Caching in Redis for {} seconds", config.ttl_secs);
+        }
+        Err(e) => eprintln!("Failed to connect to Redis, caching is disabled: {}", e),
+    }
+}
+
+fn access_key(project_id: Uuid) -> String {
+    format!("{}:access:{}", KEY_PREFIX, project_id)
+}
+
+fn categories_key(project_id: Uuid) -> String {
+    format!("{}:categories:{}", KEY_PREFIX, project_id)
+}
+
+fn projects_key(user_id: Uuid) -> String {
+    format!("{}:projects:{}", KEY_PREFIX, user_id)
+}
+
+fn presigned_urls_key(project_id: Uuid) -> String {
+    format!("{}:presigned:{}", KEY_PREFIX, project_id)
+}
+
+fn session_key(session_id: Uuid) -> String {
+    format!("{}:session:{}", KEY_PREFIX, session_id)
+}
+
+fn archived_key(project_id: Uuid) -> String {
+    format!("{}:archived:{}", KEY_PREFIX, project_id)
+}
+
+/// A presigned URL and when to stop handing it out, in seconds since the epoch
+#[derive(Serialize, Deserialize)]
+struct CachedUrl {
+    url: String,
+    refresh_at: i64,
+}
+
+/// Whether the user is a member of the project. Answers of a project live in one hash so
+/// `invalidate_access` drops them together after a write to the members of the project.
+pub async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    let key = access_key(project_id);
+    let field = user_id.to_string();
+
+    if let Some(cache) = CACHE.get() {
+        let mut connection = cache.connection.clone();
+        match connection.hget::<_, _, Option<bool>>(&key, &field).await {
+            Ok(Some(has_access)) => return has_access,
+            Ok(None) => {}
+            Err(e) => eprintln!("Failed to read project access from Redis: {}", e),
+        }
+    }
+
+    let has_access = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM project_members pm
+            WHERE pm.project_id = $1 AND pm.user_id = $2
+        )
+        "#
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await;
+
+    // Database errors deny access without being remembered
+    let Ok(has_access) = has_access else {
+        return false;
+    };
+
+    if let Some(cache) = CACHE.get() {
+        let mut connection = cache.connection.clone();
+        let result: redis::RedisResult<()> = redis::pipe()
+            .hset(&key, &field, has_access)
+            .expire(&key, cache.ttl_secs as i64)
+            .query_async(&mut connection)
+            .await;
+        if let Err(e) = result {
+            eprintln!("Failed to cache project access in Redis: {}", e);
+        }
+    }
+
+    has_access
+}
+
+/// Whether the session is neither revoked nor expired. Checked on every authenticated request,
+/// so revocations drop the cached answer right away.
+pub async fn session_is_active(pool: &Pool<Postgres>, session_id: Uuid) -> bool {
+    let key = session_key(session_id);
+    if let Some(active) = get_json::<bool>(&key).await {
+        return active;
+    }
+
+    let active = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM sessions WHERE id = $1 AND revoked_at IS NULL AND expires_at > NOW())"
+    )
+    .bind(session_id)
+    .fetch_one(pool)
+    .await;
+
+    // Database errors deny access without being remembered
+    let Ok(active) = active else {
+        return false;
+    };
+    set_json(&key, &active).await;
+    active
+}
+
+/// Whether the project is archived and read-only. Checked on every write to a project, so
+/// archiving and unarchiving drop the cached answer right away.
+pub async fn project_is_archived(pool: &Pool<Postgres>, project_id: Uuid) -> bool {
+    let key = archived_key(project_id);
+    if let Some(archived) = get_json::<bool>(&key).await {
+        return archived;
+    }
+
+    let archived = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM projects WHERE id = $1 AND archived_at IS NOT NULL)"
+    )
+    .bind(project_id)
+    .fetch_one(pool)
+    .await;
+
+    // Database errors let the write through to the handler without being remembered
+    let Ok(archived) = archived else {
+        return false;
+    };
+    set_json(&key, &archived).await;
+    archived
+}
+
+async fn get_json<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let cache = CACHE.get()?;
+    let mut connection = cache.connection.clone();
+    match connection.get::<_, Option<String>>(key).await {
+        Ok(value) => value.and_then(|value| serde_json::from_str(&value).ok()),
+        Err(e) => {
+            eprintln!("Failed to read {} from Redis: {}", key, e);
+            None
+        }
+    }
+}
+
+async fn set_json<T: Serialize>(key: &str, value: &T) {
+    let Some(cache) = CACHE.get() else {
+        return;
+    };
+    let Ok(value) = serde_json::to_string(value) else {
+        return;
+    };
+    let mut connection = cache.connection.clone();
+    if let Err(e) = connection.set_ex::<_, _, ()>(key, value, cache.ttl_secs).await {
+        eprintln!("Failed to cache {} in Redis: {}", key, e);
+    }
+}
+
+async fn delete(keys: Vec<String>) {
+    let Some(cache) = CACHE.get() else {
+        return;
+    };
+    if keys.is_empty() {
+        return;
+    }
+    let mut connection = cache.connection.clone();
+    if let Err(e) = connection.del::<_, ()>(keys).await {
+        eprintln!("Failed to invalidate Redis cache: {}", e);
+    }
+}
+
+/// Returns the cached value of `key`, or loads it and caches it when loading succeeds
+async fn get_or_load<T, F>(key: &str, load: F) -> Result<T, sqlx::Error>
+where
+    T: Serialize + DeserializeOwned,
+    F: Future<Output = Result<T, sqlx::Error>>,
+{
+    if let Some(value) = get_json(key).await {
+        return Ok(value);
+    }
+    let value = load.await?;
+    set_json(key, &value).await;
+    Ok(value)
+}
+
+/// Categories of a project, cached until they change
+pub async fn project_categories<T, F>(project_id: Uuid, load: F) -> Result<T, sqlx::Error>
+where
+    T: Serialize + DeserializeOwned,
+    F: Future<Output = Result<T, sqlx::Error>>,
+{
+    get_or_load(&categories_key(project_id), load).await
+}
+
+/// Projects the user is a member of, cached until one of them changes
+pub async fn user_projects<T, F>(user_id: Uuid, load: F) -> Result<T, sqlx::Error>
+where
+    T: Serialize + DeserializeOwned,
+    F: Future<Output = Result<T, sqlx::Error>>,
+{
+    get_or_load(&projects_key(user_id), load).await
+}
+
+/// Presigned URL of an object in the project's storage that was handed out before, as long as
+/// it is not due for a refresh
+pub async fn presigned_url(project_id: Uuid, storage_key: &str) -> Option<String> {
+    let cache = CACHE.get()?;
+    let mut connection = cache.connection.clone();
+    let cached = match connection.hget::<_, _, Option<String>>(presigned_urls_key(project_id), storage_key).await {
+        Ok(cached) => cached?,
+        Err(e) => {
+            eprintln!("Failed to read presigned URL from Redis: {}", e);
+            return None;
+        }
+    };
+
+    let cached: CachedUrl = serde_json::from_str(&cached).ok()?;
+    (cached.refresh_at > chrono::Utc::now().timestamp()).then_some(cached.url)
+}
+
+/// Remembers a presigned URL for `valid_secs`, which must end before the URL itself expires.
+/// URLs of a project share one hash so a storage change drops them together.
+pub async fn set_presigned_url(project_id: Uuid, storage_key: &str, url: &str, valid_secs: u64) {
+    let Some(cache) = CACHE.get() else {
+        return;
+    };
+    let cached = CachedUrl {
+        url: url.to_string(),
+        refresh_at: chrono::Utc::now().timestamp() + valid_secs as i64,
+    };
+    let Ok(cached) = serde_json::to_string(&cached) else {
+        return;
+    };
+
+    let key = presigned_urls_key(project_id);
+    let mut connection = cache.connection.clone();
+    let result: redis::RedisResult<()> = redis::pipe()
+        .hset(&key, storage_key, cached)
+        .expire(&key, valid_secs as i64)
+        .query_async(&mut connection)
+        .await;
+    if let Err(e) = result {
+        eprintln!("Failed to cache presigned URL in Redis: {}", e);
+    }
+}
+
+/// Drops the presigned URLs of a project after its storage configuration changed
+pub async fn invalidate_presigned_urls(project_id: Uuid) {
+    delete(vec![presigned_urls_key(project_id)]).await;
+}
+
+/// Drops the cached categories after any write to the categories of the project
+pub async fn invalidate_categories(project_id: Uuid) {
+    delete(vec![categories_key(project_id)]).await;
+}
+
+/// Drops the cached project lists of users, after a project of theirs is created or changed
+pub async fn invalidate_user_projects(user_ids: &[Uuid]) {
+    delete(user_ids.iter().map(|user_id| projects_key(*user_id)).collect()).await;
+}
+
+/// Drops the cached state of a session after it was revoked
+pub async fn invalidate_session(session_id: Uuid) {
+    delete(vec![session_key(session_id)]).await;
+}
+
+/// Drops the cached access checks of a project after a member was added or removed, or their
+/// role changed
+pub async fn invalidate_access(project_id: Uuid) {
+    delete(vec![access_key(project_id)]).await;
+}
+
+/// Drops the cached archive state after the project was archived or unarchived
+pub async fn invalidate_archived(project_id: Uuid) {
+    delete(vec![archived_key(project_id)]).await;
+}
+
+/// Drops everything cached about a deleted project
+pub async fn invalidate_project(project_id: Uuid) {
+    delete(vec![
+        access_key(project_id),
+        categories_key(project_id),
+        presigned_urls_key(project_id),
+        archived_key(project_id),
+    ])
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{create_test_project, create_test_user, setup_test_db};
+    use serial_test::serial;
+
+    #[test]
+    fn test_keys_are_scoped() {
+        let id = Uuid::parse_str("9b2f0c1e-3f7a-4a59-8f3e-1c2d3e4f5a6b").unwrap();
+        assert_eq!(access_key(id), "fast-tag:access:9b2f0c1e-3f7a-4a59-8f3e-1c2d3e4f5a6b");
+        assert_eq!(categories_key(id), "fast-tag:categories:9b2f0c1e-3f7a-4a59-8f3e-1c2d3e4f5a6b");
+        assert_eq!(projects_key(id), "fast-tag:projects:9b2f0c1e-3f7a-4a59-8f3e-1c2d3e4f5a6b");
+        assert_eq!(presigned_urls_key(id), "fast-tag:presigned:9b2f0c1e-3f7a-4a59-8f3e-1c2d3e4f5a6b");
+        assert_eq!(session_key(id), "fast-tag:session:9b2f0c1e-3f7a-4a59-8f3e-1c2d3e4f5a6b");
+        assert_eq!(archived_key(id), "fast-tag:archived:9b2f0c1e-3f7a-4a59-8f3e-1c2d3e4f5a6b");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_reads_through_without_redis() {
+        let pool = setup_test_db().await;
+        let user_id = create_test_user(&pool).await;
+        let project_id = create_test_project(&pool, user_id).await;
+
+        assert!(user_has_project_access(&pool, project_id, user_id).await);
+        assert!(!user_has_project_access(&pool, project_id, Uuid::new_v4()).await);
+
+        let loaded = user_projects(user_id, async { Ok(vec![project_id]) }).await.unwrap();
+        assert_eq!(loaded, vec![project_id]);
+        invalidate_user_projects(&[user_id]).await;
+    }
+}
@@ -0,0 +1,754 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres, Transaction};
+use std::collections::HashSet;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::auth::{JwtManager, Claims};
+use crate::coco::bundle::{self, BundleEntry};
+use crate::projects::TASK_TYPE_CLASSIFICATION;
+use crate::storage::factory::create_storage_provider_from_project;
+
+const CSV_HEADER: &str = "task_id,task_name,file_name,labels,annotator";
+
+/// Name of the label listing written next to the class directories of an ImageFolder export.
+const IMAGEFOLDER_LABELS_FILE: &str = "labels.csv";
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct ClassificationLabel {
+    pub category_id: Uuid,
+    pub category_name: String,
+    pub category_color: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskClassification {
+    pub task_id: Uuid,
+    /// Annotation holding the labels, `None` while the task has not been labeled
+    pub annotation_id: Option<Uuid>,
+    pub annotated_by: Option<Uuid>,
+    pub annotated_at: Option<DateTime<Utc>>,
+    pub labels: Vec<ClassificationLabel>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SetClassificationRequest {
+    pub category_ids: Vec<Uuid>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ClassificationExportQuery {
+    /// `csv` (default) or `imagefolder`, a ZIP with one directory of images per category
+    pub format: Option<String>,
+}
+
+/// One task of a classification export with the labels of its latest annotation.
+#[derive(Debug, sqlx::FromRow)]
+pub struct ClassificationExportRow {
+    pub task_id: Uuid,
+    pub task_name: String,
+    pub resource_url: Option<String>,
+    pub labels: Vec<String>,
+    pub annotator: Option<String>,
+}
+
+#[utoipa::path(
+    put,
+    path = "/projects/{project_id}/tasks/{task_id}/classification",
+    tag = "annotations",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    request_body = SetClassificationRequest,
+    responses(
+        (status = 201, description = "Labels saved as a new annotation", body = TaskClassification),
+        (status = 400, description = "Not a classification project or unknown categories", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn set_task_classification(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    payload: web::Json<SetClassificationRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    if let Err(message) = validate_category_ids(&payload.category_ids) {
+        return HttpResponse::BadRequest().json(message);
+    }
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match crate::projects::get_project_task_type(&pool, project_id).await {
+        Ok(Some(task_type)) if task_type == TASK_TYPE_CLASSIFICATION => {}
+        Ok(Some(_)) => return HttpResponse::BadRequest().json("Labels can only be set on classification projects"),
+        Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    }
+
+    // Verify task belongs to the project
+    if !task_belongs_to_project(&pool, task_id, project_id).await {
+        return HttpResponse::BadRequest().json("Task does not belong to the specified project");
+    }
+
+    // Verify all categories belong to the project
+    match count_project_categories(&pool, project_id, &payload.category_ids).await {
+        Ok(count) if count == payload.category_ids.len() as i64 => {}
+        Ok(_) => return HttpResponse::BadRequest().json("One or more categories do not belong to the specified project"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to verify categories"),
+    }
+
+    match set_classification_in_db(
+        &pool,
+        task_id,
+        &payload.category_ids,
+        payload.metadata.as_ref().unwrap_or(&serde_json::json!({})),
+        user_id,
+    ).await {
+        Ok(classification) => HttpResponse::Created().json(classification),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to save labels"),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/tasks/{task_id}/classification",
+    tag = "annotations",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    responses(
+        (status = 200, body = TaskClassification),
+        (status = 400, description = "Task doesn't belong to the project", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn get_task_classification(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    // Verify task belongs to the project
+    if !task_belongs_to_project(&pool, task_id, project_id).await {
+        return HttpResponse::BadRequest().json("Task does not belong to the specified project");
+    }
+
+    match get_task_classification_from_db(&pool, task_id).await {
+        Ok(classification) => HttpResponse::Ok().json(classification),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch labels"),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/export/classification",
+    tag = "export",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ClassificationExportQuery,
+    ),
+    responses(
+        (status = 200, description = "CSV of the labels, or a ZIP with one directory per category for `imagefolder`", body = String, content_type = "text/csv"),
+        (status = 400, description = "Not a classification project or unknown format", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn export_project_classifications(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<ClassificationExportQuery>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let imagefolder = match query.format.as_deref().unwrap_or("csv") {
+        "csv" => false,
+        "imagefolder" => true,
+        _ => return HttpResponse::BadRequest().json("Invalid export format (expected 'csv' or 'imagefolder')"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let project = match get_project_by_id(&pool, project_id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    };
+
+    if project.task_type != TASK_TYPE_CLASSIFICATION {
+        return HttpResponse::BadRequest().json("Only classification projects can be exported as labels");
+    }
+
+    let rows = match get_project_rows_for_export(&pool, project_id).await {
+        Ok(rows) => rows,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch labels"),
+    };
+
+    let file_stem = format!("{}_labels_{}",
+        project.name.replace(" ", "_").to_lowercase(),
+        Utc::now().format("%Y%m%d_%H%M%S")
+    );
+    let csv = build_classification_csv(&rows);
+
+    if imagefolder {
+        let storage_provider = match create_storage_provider_from_project(&project).await {
+            Ok(provider) => provider,
+            Err(e) => return HttpResponse::BadRequest().json(format!("Storage not available: {}", e)),
+        };
+
+        return bundle::stream_export_bundle(
+            IMAGEFOLDER_LABELS_FILE.to_string(),
+            csv.into_bytes(),
+            build_imagefolder_entries(&rows),
+            storage_provider,
+            format!("{}.zip", file_stem),
+        );
+    }
+
+    HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}.csv\"", file_stem)))
+        .body(csv)
+}
+
+pub fn validate_category_ids(category_ids: &[Uuid]) -> Result<(), String> {
+    if category_ids.is_empty() {
+        return Err("At least one category label is required".to_string());
+    }
+
+    let mut seen = HashSet::new();
+    if !category_ids.iter().all(|id| seen.insert(id)) {
+        return Err("Category labels must be unique".to_string());
+    }
+
+    Ok(())
+}
+
+/// Renders one CSV line per task; multiple labels are joined with `;`.
+pub fn build_classification_csv(rows: &[ClassificationExportRow]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+
+    for row in rows {
+        let fields = [
+            row.task_id.to_string(),
+            escape_csv_field(&row.task_name),
+            escape_csv_field(file_name(row)),
+            escape_csv_field(&row.labels.join(";")),
+            escape_csv_field(row.annotator.as_deref().unwrap_or("")),
+        ];
+
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Places every labeled image stored in project storage under `<category>/<file name>`.
+/// Images with several labels are copied into each of their category directories.
+pub fn build_imagefolder_entries(rows: &[ClassificationExportRow]) -> Vec<BundleEntry> {
+    let mut used_paths = HashSet::new();
+    let mut entries = Vec::new();
+
+    for row in rows {
+        let storage_key = match row.resource_url.as_deref().and_then(|url| url.strip_prefix("storage://")) {
+            Some(key) => key,
+            None => continue,
+        };
+
+        for label in &row.labels {
+            let directory = label.replace(['/', '\\'], "_");
+
+            // Images from different folders can share a base name, so prefix duplicates with the task ID
+            let mut archive_path = format!("{}/{}", directory, file_name(row));
+            if !used_paths.insert(archive_path.clone()) {
+                archive_path = format!("{}/{}_{}", directory, row.task_id, file_name(row));
+                used_paths.insert(archive_path.clone());
+            }
+
+            entries.push(BundleEntry {
+                storage_key: storage_key.to_string(),
+                archive_path,
+            });
+        }
+    }
+
+    entries
+}
+
+/// Moves the labels of one category to another, e.g. when the category is merged or deleted.
+/// Labels the annotation already carries under the target are dropped instead of duplicated.
+pub async fn reassign_classification_labels(
+    tx: &mut Transaction<'_, Postgres>,
+    from_category_id: Uuid,
+    to_category_id: Uuid,
+) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        r#"
+        DELETE FROM image_classifications ic
+        WHERE ic.category_id = $1
+          AND EXISTS(SELECT 1 FROM image_classifications other WHERE other.annotation_id = ic.annotation_id AND other.category_id = $2)
+        "#
+    )
+    .bind(from_category_id)
+    .bind(to_category_id)
+    .execute(&mut **tx)
+    .await?;
+
+    let result = sqlx::query("UPDATE image_classifications SET category_id = $1 WHERE category_id = $2")
+        .bind(to_category_id)
+        .bind(from_category_id)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+fn file_name(row: &ClassificationExportRow) -> &str {
+    row.resource_url
+        .as_deref()
+        .and_then(|url| url.split('/').next_back())
+        .unwrap_or(&row.task_name)
+}
+
+fn escape_csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+async fn set_classification_in_db(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
+    category_ids: &[Uuid],
+    metadata: &serde_json::Value,
+    annotated_by: Uuid,
+) -> Result<TaskClassification, sqlx::Error> {
+    let annotation_id = Uuid::new_v4();
+    let now = Utc::now();
+
+    let mut tx = pool.begin().await?;
+
+    // Always creates a new annotation to preserve history, like bounding box annotations
+    sqlx::query(
+        r#"
+        INSERT INTO annotations (id, task_id, metadata, annotated_by, annotated_at, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#
+    )
+    .bind(annotation_id)
+    .bind(task_id)
+    .bind(metadata)
+    .bind(annotated_by)
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO image_classifications (id, annotation_id, category_id, created_at)
+        SELECT gen_random_uuid(), $1, category_id, $3
+        FROM UNNEST($2::UUID[]) AS c(category_id)
+        "#
+    )
+    .bind(annotation_id)
+    .bind(category_ids)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    get_task_classification_from_db(pool, task_id).await
+}
+
+async fn get_task_classification_from_db(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
+) -> Result<TaskClassification, sqlx::Error> {
+    let latest = sqlx::query_as::<_, (Uuid, Option<Uuid>, Option<DateTime<Utc>>)>(
+        "SELECT id, annotated_by, annotated_at FROM annotations WHERE task_id = $1 ORDER BY created_at DESC LIMIT 1"
+    )
+    .bind(task_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let (annotation_id, annotated_by, annotated_at) = match latest {
+        Some(latest) => latest,
+        None => {
+            return Ok(TaskClassification {
+                task_id,
+                annotation_id: None,
+                annotated_by: None,
+                annotated_at: None,
+                labels: Vec::new(),
+            });
+        }
+    };
+
+    let labels = sqlx::query_as::<_, ClassificationLabel>(
+        r#"
+        SELECT iac.id as category_id, iac.name as category_name, iac.color as category_color
+        FROM image_classifications ic
+        JOIN image_annotation_categories iac ON iac.id = ic.category_id
+        WHERE ic.annotation_id = $1
+        ORDER BY iac.name
+        "#
+    )
+    .bind(annotation_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(TaskClassification {
+        task_id,
+        annotation_id: Some(annotation_id),
+        annotated_by,
+        annotated_at,
+        labels,
+    })
+}
+
+async fn get_project_rows_for_export(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Vec<ClassificationExportRow>, sqlx::Error> {
+    // Only the latest annotation of each task is exported, matching the COCO exporter
+    sqlx::query_as::<_, ClassificationExportRow>(
+        r#"
+        WITH latest_annotations AS (
+            SELECT DISTINCT ON (task_id) id, task_id, annotated_by
+            FROM annotations
+            WHERE task_id IN (SELECT id FROM tasks WHERE project_id = $1)
+            ORDER BY task_id, created_at DESC
+        )
+        SELECT
+            t.id as task_id,
+            t.name as task_name,
+            t.resource_url,
+            COALESCE(ARRAY_AGG(iac.name::TEXT ORDER BY iac.name) FILTER (WHERE iac.id IS NOT NULL), '{}'::TEXT[]) as labels,
+            u.email as annotator
+        FROM tasks t
+        LEFT JOIN latest_annotations la ON la.task_id = t.id
+        LEFT JOIN image_classifications ic ON ic.annotation_id = la.id
+        LEFT JOIN image_annotation_categories iac ON iac.id = ic.category_id
+        LEFT JOIN users u ON u.id = la.annotated_by
+        WHERE t.project_id = $1
+        GROUP BY t.id, t.name, t.resource_url, t.created_at, u.email
+        ORDER BY t.created_at, t.id
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_project_by_id(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Option<crate::projects::Project>, sqlx::Error> {
+    sqlx::query_as::<_, crate::projects::Project>(
+        "SELECT id, name, description, storage_config, owner_id, task_type, created_at, updated_at FROM projects WHERE id = $1"
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn count_project_categories(pool: &Pool<Postgres>, project_id: Uuid, category_ids: &[Uuid]) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM image_annotation_categories WHERE project_id = $1 AND id = ANY($2)"
+    )
+    .bind(project_id)
+    .bind(category_ids)
+    .fetch_one(pool)
+    .await
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+async fn task_belongs_to_project(pool: &Pool<Postgres>, task_id: Uuid, project_id: Uuid) -> bool {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM tasks WHERE id = $1 AND project_id = $2)"
+    )
+    .bind(task_id)
+    .bind(project_id)
+    .fetch_one(pool)
+    .await
+    .unwrap_or(false)
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    fn export_row(resource_url: &str, labels: &[&str]) -> ClassificationExportRow {
+        ClassificationExportRow {
+            task_id: Uuid::new_v4(),
+            task_name: "task".to_string(),
+            resource_url: Some(resource_url.to_string()),
+            labels: labels.iter().map(|label| label.to_string()).collect(),
+            annotator: Some("a@example.com".to_string()),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_validate_category_ids() {
+        let id = Uuid::new_v4();
+        assert!(validate_category_ids(&[id, Uuid::new_v4()]).is_ok());
+        assert!(validate_category_ids(&[]).is_err());
+        assert!(validate_category_ids(&[id, id]).is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_build_classification_exports() {
+        let rows = vec![
+            export_row("storage://a/cat1.jpg", &["cat", "indoor"]),
+            export_row("storage://b/cat1.jpg", &["cat"]),
+            export_row("https://example.com/dog.jpg", &["dog"]),
+        ];
+
+        let csv = build_classification_csv(&rows);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[1], format!("{},task,cat1.jpg,cat;indoor,a@example.com", rows[0].task_id));
+
+        // External images cannot be bundled, and duplicate names are prefixed with the task ID
+        let entries = build_imagefolder_entries(&rows);
+        let paths: Vec<&str> = entries.iter().map(|entry| entry.archive_path.as_str()).collect();
+        assert_eq!(paths, vec![
+            "cat/cat1.jpg".to_string(),
+            "indoor/cat1.jpg".to_string(),
+            format!("cat/{}_cat1.jpg", rows[1].task_id),
+        ]);
+        assert_eq!(entries[2].storage_key, "b/cat1.jpg");
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_set_and_get_task_classification() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_with_template_in_db(&pool, "Classification Project", None, None, user.id, TASK_TYPE_CLASSIFICATION, None).await.unwrap();
+        let cat = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "cat", None, None, None, None).await.unwrap();
+        let indoor = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "indoor", None, None, None, None).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "image1.jpg", Some("https://example.com/image1.jpg")).await.unwrap();
+
+        let detection_project = crate::projects::create_project_in_db(&pool, "Detection Project", None, None, user.id).await.unwrap();
+        let detection_category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, detection_project.id, "cat", None, None, None, None).await.unwrap();
+        let detection_task = crate::tasks::create_task_in_db(&pool, detection_project.id, "image1.jpg", None).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/tasks/{task_id}/classification", web::put().to(set_task_classification))
+                .route("/projects/{project_id}/tasks/{task_id}/classification", web::get().to(get_task_classification))
+                .route("/projects/{project_id}/export/classification", web::get().to(export_project_classifications))
+        ).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/tasks/{}/classification", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let body: serde_json::Value = test::read_body_json(test::call_service(&app, req).await).await;
+        assert!(body["annotation_id"].is_null());
+        assert_eq!(body["labels"].as_array().unwrap().len(), 0);
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/projects/{}/tasks/{}/classification", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(SetClassificationRequest { category_ids: vec![indoor.id, cat.id], metadata: None })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["labels"][0]["category_name"], "cat");
+        assert_eq!(body["labels"][1]["category_name"], "indoor");
+
+        // Relabeling replaces the labels of the latest annotation
+        let req = test::TestRequest::put()
+            .uri(&format!("/projects/{}/tasks/{}/classification", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(SetClassificationRequest { category_ids: vec![cat.id], metadata: None })
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/tasks/{}/classification", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let body: serde_json::Value = test::read_body_json(test::call_service(&app, req).await).await;
+        assert_eq!(body["labels"].as_array().unwrap().len(), 1);
+        assert_eq!(body["labels"][0]["category_id"], cat.id.to_string());
+
+        // Categories of other projects are rejected
+        let req = test::TestRequest::put()
+            .uri(&format!("/projects/{}/tasks/{}/classification", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(SetClassificationRequest { category_ids: vec![detection_category.id], metadata: None })
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        // Detection projects do not take labels
+        let req = test::TestRequest::put()
+            .uri(&format!("/projects/{}/tasks/{}/classification", detection_project.id, detection_task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(SetClassificationRequest { category_ids: vec![detection_category.id], metadata: None })
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/export/classification", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body = test::read_body(resp).await;
+        let csv = std::str::from_utf8(&body).unwrap();
+        assert_eq!(
+            csv.lines().nth(1).unwrap(),
+            format!("{},image1.jpg,image1.jpg,cat,{}", task.id, user.email)
+        );
+    }
+}
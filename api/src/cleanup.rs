@@ -0,0 +1,400 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use std::collections::HashSet;
+use utoipa::ToSchema;
+
+use crate::auth::{JwtManager, Claims};
+use crate::storage::config::StorageConfig;
+use crate::storage::factory::create_storage_provider;
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct CleanupRequest {
+    /// Only report what would be deleted, true when not given
+    pub dry_run: Option<bool>,
+    /// Also look for files in the project storage that no task uses
+    pub include_storage: Option<bool>,
+    /// Only look at the storage files under this prefix
+    pub prefix: Option<String>,
+}
+
+/// What a cleanup found, and deleted unless it was a dry run
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CleanupReport {
+    pub dry_run: bool,
+    /// Annotations left without boxes or labels. Annotations of deleted tasks are deleted with
+    /// the task, these are the ones whose content went away.
+    pub empty_annotations: Vec<Uuid>,
+    /// Boxes whose category was deleted
+    pub uncategorized_boxes: Vec<Uuid>,
+    /// Images, videos and slice stacks no task uses, with the frames and tiles written for them
+    pub unused_objects: Vec<String>,
+    /// Storage files that could not be deleted
+    pub errors: Vec<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{id}/cleanup",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    request_body = CleanupRequest,
+    responses(
+        (status = 200, description = "What was found, and deleted unless `dry_run`", body = CleanupReport),
+        (status = 400, description = "Storage files were asked for but the project has no storage", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found, or the user is not an owner or admin of it", body = String),
+    ),
+)]
+pub async fn cleanup_project(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<CleanupRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Cleanups delete data of every member, and read the storage credentials
+    match crate::projects::user_can_manage_storage(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check project access"),
+    }
+
+    let dry_run = payload.dry_run.unwrap_or(true);
+    let (empty_annotations, uncategorized_boxes) = match find_annotation_garbage(&pool, project_id).await {
+        Ok(garbage) => garbage,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to look for unused annotations"),
+    };
+
+    let mut report = CleanupReport {
+        dry_run,
+        empty_annotations,
+        uncategorized_boxes,
+        unused_objects: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    let storage_provider = if payload.include_storage.unwrap_or(false) {
+        let storage_config = match get_storage_config(&pool, project_id).await {
+            Ok(Some(storage_config)) => storage_config,
+            Ok(None) => return HttpResponse::BadRequest().json("Project has no storage configuration"),
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+        };
+        let provider = match create_storage_provider(&storage_config).await {
+            Ok(provider) => provider,
+            Err(e) => return HttpResponse::InternalServerError().json(format!("Storage error: {}", e)),
+        };
+        let keys = match provider.list_objects(payload.prefix.as_deref()).await {
+            Ok(keys) => keys,
+            Err(e) => return HttpResponse::InternalServerError().json(format!("Failed to list storage objects: {}", e)),
+        };
+        report.unused_objects = match find_unused_objects(&pool, keys).await {
+            Ok(unused) => unused,
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to look for unused storage objects"),
+        };
+        Some(provider)
+    } else {
+        None
+    };
+
+    if dry_run {
+        return HttpResponse::Ok().json(report);
+    }
+
+    if delete_annotation_garbage(&pool, &report.empty_annotations, &report.uncategorized_boxes).await.is_err() {
+        return HttpResponse::InternalServerError().json("Failed to delete unused annotations");
+    }
+
+    // Files go after the database, a failed file is reported and left for the next cleanup
+    if let Some(provider) = storage_provider {
+        for key in &report.unused_objects {
+            if let Err(e) = provider.delete(key).await {
+                report.errors.push(format!("Failed to delete {}: {}", key, e));
+            }
+        }
+    }
+
+    HttpResponse::Ok().json(report)
+}
+
+/// File the storage key was written for: the video of a frame, the image of a tile, the key
+/// itself otherwise
+fn source_key(key: &str) -> &str {
+    crate::video::frame_source_key(key)
+        .or_else(|| crate::tiles::tile_source_key(key))
+        .unwrap_or(key)
+}
+
+/// Files a sync turns into tasks. Anything else in the bucket, like exports or models, is
+/// never reported.
+fn is_task_media(key: &str) -> bool {
+    crate::sync::is_image_file(key) || crate::video::is_video_file(key) || crate::slices::is_slice_stack_file(key)
+}
+
+/// Keys whose source file is task media that no task of any project uses. Buckets can be shared
+/// between projects, so tasks of other projects count as uses too.
+async fn find_unused_objects(pool: &Pool<Postgres>, keys: Vec<String>) -> Result<Vec<String>, sqlx::Error> {
+    let keys: Vec<String> = keys.into_iter().filter(|key| is_task_media(source_key(key))).collect();
+    let resource_urls: Vec<String> = keys.iter()
+        .map(|key| format!("storage://{}", source_key(key)))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    let used: HashSet<String> = sqlx::query_scalar::<_, String>(
+        "SELECT DISTINCT resource_url FROM tasks WHERE resource_url = ANY($1)"
+    )
+    .bind(&resource_urls)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    let mut unused: Vec<String> = keys.into_iter()
+        .filter(|key| !used.contains(&format!("storage://{}", source_key(key))))
+        .collect();
+    unused.sort();
+    Ok(unused)
+}
+
+/// Annotations without boxes of a live category or labels, and boxes whose category is gone.
+/// Annotations a gold task refers to are kept even when empty.
+async fn find_annotation_garbage(pool: &Pool<Postgres>, project_id: Uuid) -> Result<(Vec<Uuid>, Vec<Uuid>), sqlx::Error> {
+    let empty_annotations = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT a.id
+        FROM annotations a
+        INNER JOIN tasks t ON t.id = a.task_id
+        WHERE t.project_id = $1
+          AND NOT EXISTS(SELECT 1 FROM image_annotations ia WHERE ia.annotation_id = a.id AND ia.category_id IS NOT NULL)
+          AND NOT EXISTS(SELECT 1 FROM image_classifications ic WHERE ic.annotation_id = a.id)
+          AND NOT EXISTS(SELECT 1 FROM tasks gold WHERE gold.gold_annotation_id = a.id)
+        ORDER BY a.created_at
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    let uncategorized_boxes = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT ia.id
+        FROM image_annotations ia
+        INNER JOIN annotations a ON a.id = ia.annotation_id
+        INNER JOIN tasks t ON t.id = a.task_id
+        WHERE t.project_id = $1 AND ia.category_id IS NULL
+        ORDER BY ia.created_at
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok((empty_annotations, uncategorized_boxes))
+}
+
+/// Deletes what a report listed, nothing that was added or changed since
+async fn delete_annotation_garbage(
+    pool: &Pool<Postgres>,
+    empty_annotations: &[Uuid],
+    uncategorized_boxes: &[Uuid],
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM image_annotations WHERE id = ANY($1) AND category_id IS NULL")
+        .bind(uncategorized_boxes)
+        .execute(&mut *tx)
+        .await?;
+
+    // Boxes saved into an annotation since the report keep it
+    sqlx::query(
+        r#"
+        DELETE FROM annotations a
+        WHERE a.id = ANY($1)
+          AND NOT EXISTS(SELECT 1 FROM image_annotations ia WHERE ia.annotation_id = a.id)
+          AND NOT EXISTS(SELECT 1 FROM image_classifications ic WHERE ic.annotation_id = a.id)
+        "#
+    )
+    .bind(empty_annotations)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+async fn get_storage_config(pool: &Pool<Postgres>, project_id: Uuid) -> Result<Option<StorageConfig>, sqlx::Error> {
+    let storage_config = sqlx::query_scalar::<_, Option<serde_json::Value>>("SELECT storage_config FROM projects WHERE id = $1")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+
+    Ok(storage_config.and_then(|storage_config| serde_json::from_value(storage_config).ok()))
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    #[actix_web::test]
+    async fn test_source_key_and_task_media() {
+        assert_eq!(source_key("clips/drive.mp4.frames/000012.jpg"), "clips/drive.mp4");
+        assert_eq!(source_key("maps/area.tif.tiles/12/3_4.jpg"), "maps/area.tif");
+        assert_eq!(source_key("images/cat.jpg"), "images/cat.jpg");
+        assert!(is_task_media("images/cat.jpg"));
+        assert!(is_task_media("clips/drive.mp4"));
+        assert!(!is_task_media("exports/weekly.json"));
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_cleanup_reports_then_deletes() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        for file in ["used.png", "unused.png", "unused.png.tiles/thumbnail.jpg", "exports/weekly.json"] {
+            let path = temp_dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"bytes").unwrap();
+        }
+        let storage_config = serde_json::json!({
+            "type": "local",
+            "base_path": temp_dir.path().to_str().unwrap()
+        });
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, Some(&storage_config), user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, None, None, None).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "used.png", Some("storage://used.png")).await.unwrap();
+
+        // One annotation keeps a live box next to a box of a deleted category, the other has no boxes left
+        let mut annotation_ids = Vec::new();
+        for _ in 0..2 {
+            let annotation_id = sqlx::query_scalar::<_, Uuid>("INSERT INTO annotations (task_id) VALUES ($1) RETURNING id")
+                .bind(task.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            annotation_ids.push(annotation_id);
+        }
+        let (kept, empty) = (annotation_ids[0], annotation_ids[1]);
+        for category_id in [Some(category.id), None] {
+            sqlx::query("INSERT INTO image_annotations (annotation_id, category_id, bbox) VALUES ($1, $2, ARRAY[0, 0, 10, 10]::FLOAT[])")
+                .bind(kept)
+                .bind(category_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{id}/cleanup", web::post().to(cleanup_project))
+        ).await;
+        let uri = format!("/projects/{}/cleanup", project.id);
+
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(CleanupRequest { include_storage: Some(true), ..Default::default() })
+            .to_request();
+        let report: CleanupReport = test::call_and_read_body_json(&app, req).await;
+        assert!(report.dry_run);
+        assert_eq!(report.empty_annotations, vec![empty]);
+        assert_eq!(report.uncategorized_boxes.len(), 1);
+        assert_eq!(report.unused_objects, vec!["unused.png".to_string(), "unused.png.tiles/thumbnail.jpg".to_string()]);
+        // Nothing is deleted on a dry run
+        assert!(temp_dir.path().join("unused.png").exists());
+
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(CleanupRequest { dry_run: Some(false), include_storage: Some(true), prefix: None })
+            .to_request();
+        let report: CleanupReport = test::call_and_read_body_json(&app, req).await;
+        assert!(report.errors.is_empty());
+        assert!(!temp_dir.path().join("unused.png").exists());
+        assert!(temp_dir.path().join("used.png").exists());
+        assert!(temp_dir.path().join("exports/weekly.json").exists());
+
+        let (empty_annotations, uncategorized_boxes) = find_annotation_garbage(&pool, project.id).await.unwrap();
+        assert!(empty_annotations.is_empty() && uncategorized_boxes.is_empty());
+        let boxes = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM image_annotations WHERE annotation_id = $1")
+            .bind(kept)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(boxes, 1);
+    }
+}
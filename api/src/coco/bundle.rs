@@ -0,0 +1,191 @@
+use actix_web::HttpResponse;
+use bytes::Bytes;
+use std::collections::HashSet;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::storage::StorageProvider;
+use super::types::CocoImage;
+
+/// Directory inside the archive that holds the downloaded images.
+pub const IMAGES_DIR: &str = "images";
+/// Chunks of a streamed bundle waiting for the client. Building the archive pauses when they
+/// pile up, so a slow download doesn't hold the whole dataset in memory.
+const STREAM_BUFFER_CHUNKS: usize = 16;
+
+/// An image that should be copied from project storage into the bundle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleEntry {
+    pub storage_key: String,
+    pub archive_path: String,
+}
+
+/// Assigns a unique file name to every image stored in project storage and
+/// rewrites `file_name` so the annotation file points at the bundled copy.
+/// Images that do not live in project storage are left untouched.
+pub fn assign_archive_paths(images: &mut [CocoImage]) -> Vec<BundleEntry> {
+    let mut used_names = HashSet::new();
+    let mut entries = Vec::new();
+
+    for image in images.iter_mut() {
+        let storage_key = match image.coco_url.as_deref().and_then(|url| url.strip_prefix("storage://")) {
+            Some(key) => key.to_string(),
+            None => continue,
+        };
+
+        // Images from different folders can share a base name, so prefix duplicates with the image ID
+        let base_name = archive_file_name(&image.file_name, image.id);
+        let mut file_name = base_name.clone();
+        if !used_names.insert(file_name.clone()) {
+            file_name = format!("{}_{}", image.id, base_name);
+            used_names.insert(file_name.clone());
+        }
+
+        image.file_name = file_name.clone();
+        entries.push(BundleEntry {
+            storage_key,
+            archive_path: format!("{}/{}", IMAGES_DIR, file_name),
+        });
+    }
+
+    entries
+}
+
+/// The last component of an image's `file_name`, so names from an imported file such as
+/// `../../etc/passwd` or `C:\images\a.jpg` can't place entries outside the images directory
+fn archive_file_name(file_name: &str, image_id: i64) -> String {
+    match file_name.rsplit(['/', '\\']).next() {
+        Some(name) if !matches!(name, "" | "." | "..") => name.to_string(),
+        _ => format!("image_{}", image_id),
+    }
+}
+
+/// Streams a ZIP archive containing the annotation file and every referenced
+/// image. Images are downloaded one at a time and sent before the next one,
+/// so memory use stays bounded by the largest single image rather than the
+/// whole dataset.
+pub fn stream_export_bundle(
+    annotation_file_name: String,
+    annotation_json: Vec<u8>,
+    entries: Vec<BundleEntry>,
+    storage_provider: Arc<dyn StorageProvider>,
+    bundle_file_name: String,
+) -> HttpResponse {
+    let (sender, mut receiver) = mpsc::channel::<Result<Bytes, std::io::Error>>(STREAM_BUFFER_CHUNKS);
+    let chunks = ChunkSender { buffer: Arc::default(), sender };
+
+    actix_web::rt::spawn(async move {
+        let mut zip = ZipWriter::new_stream(ChunkWriter { buffer: chunks.buffer.clone() });
+
+        if let Err(e) = write_bundle(&mut zip, &annotation_file_name, &annotation_json, &entries, storage_provider.as_ref(), Some(&chunks)).await {
+            eprintln!("Failed to build export bundle: {}", e);
+            let _ = chunks.sender.send(Err(std::io::Error::other(e.to_string()))).await;
+            return;
+        }
+
+        let finished = match zip.finish() {
+            Ok(_) => chunks.send().await,
+            Err(e) => Err(std::io::Error::other(e.to_string())),
+        };
+        if let Err(e) = finished {
+            eprintln!("Failed to finalize export bundle: {}", e);
+            let _ = chunks.sender.send(Err(e)).await;
+        }
+    });
+
+    let body = futures_util::stream::poll_fn(move |cx| receiver.poll_recv(cx));
+
+    HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", bundle_file_name)))
+        .streaming(body)
+}
+
+/// The same archive as `stream_export_bundle`, built in memory to be written to storage
+pub async fn build_export_bundle(
+    annotation_file_name: &str,
+    annotation_json: &[u8],
+    entries: &[BundleEntry],
+    storage_provider: &dyn StorageProvider,
+) -> zip::result::ZipResult<Vec<u8>> {
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    write_bundle(&mut zip, annotation_file_name, annotation_json, entries, storage_provider, None).await?;
+    Ok(zip.finish()?.into_inner())
+}
+
+async fn write_bundle<W: Write>(
+    zip: &mut ZipWriter<W>,
+    annotation_file_name: &str,
+    annotation_json: &[u8],
+    entries: &[BundleEntry],
+    storage_provider: &dyn StorageProvider,
+    chunks: Option<&ChunkSender>,
+) -> zip::result::ZipResult<()> {
+    let json_options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file(annotation_file_name, json_options)?;
+    zip.write_all(annotation_json)?;
+    if let Some(chunks) = chunks {
+        chunks.send().await?;
+    }
+
+    // Images are already compressed, so store them as-is
+    let image_options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+    for entry in entries {
+        let data = match storage_provider.download(&entry.storage_key).await {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("Skipping {} in export bundle: {}", entry.storage_key, e);
+                continue;
+            }
+        };
+
+        zip.start_file(entry.archive_path.as_str(), image_options)?;
+        zip.write_all(&data)?;
+        if let Some(chunks) = chunks {
+            chunks.send().await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects what the ZIP encoder writes until `ChunkSender` passes it on. The encoder writes
+/// synchronously, so it can't wait for the client itself.
+struct ChunkWriter {
+    buffer: Arc<Mutex<Vec<u8>>>,
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.lock().unwrap_or_else(|e| e.into_inner()).extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Sends what the ZIP encoder wrote so far to the response stream, waiting while the client
+/// is behind.
+struct ChunkSender {
+    buffer: Arc<Mutex<Vec<u8>>>,
+    sender: mpsc::Sender<Result<Bytes, std::io::Error>>,
+}
+
+impl ChunkSender {
+    async fn send(&self) -> std::io::Result<()> {
+        let chunk = std::mem::take(&mut *self.buffer.lock().unwrap_or_else(|e| e.into_inner()));
+        if chunk.is_empty() {
+            return Ok(());
+        }
+        self.sender
+            .send(Ok(Bytes::from(chunk)))
+            .await
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::BrokenPipe, "Client disconnected"))
+    }
+}
@@ -2,14 +2,102 @@ use actix_web::{web, HttpResponse, Responder, HttpRequest};
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 use chrono::{Datelike, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use crate::auth::{JwtManager, Claims};
-use super::types::{CocoExport, CocoInfo, CocoLicense, CocoImage, CocoAnnotation, CocoCategory};
+use crate::export_delivery::{deliver_export, ExportDelivery, ExportDestination};
+use crate::export_jobs::{finish_export_job, record_export_start, ExportJob, ExportNotifyConfig};
+use crate::storage::factory::create_storage_provider_from_project;
+use super::bundle;
+use super::types::{CocoExport, CocoInfo, CocoLicense, CocoImage, CocoAnnotation, CocoCategory, CocoRle, CocoSegmentation, FastTagAnnotation, FastTagInfo};
+
+/// Version of the `x-fasttag` extension fields written by the export
+const FASTTAG_SCHEMA_VERSION: i32 = 1;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportQuery {
+    /// Package the annotation file together with the referenced images as a ZIP
+    pub include_images: Option<bool>,
+    /// Add the `x-fasttag` extension fields: the project in the info block, and the task, save,
+    /// annotator, timestamps and review state of every annotation
+    pub include_metadata: Option<bool>,
+    /// Only export the tasks of these comma-separated splits, e.g. `train,val`
+    pub splits: Option<String>,
+}
+
+/// Export written to storage by the server instead of downloaded. Writing to storage is part of
+/// managing it, so only owners and admins deliver exports.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CocoDeliveryRequest {
+    #[serde(default)]
+    pub include_images: bool,
+    #[serde(default)]
+    pub include_metadata: bool,
+    /// Only export the tasks of these splits, every task when empty
+    #[serde(default)]
+    pub splits: Vec<String>,
+    /// Folder of the project storage to write to, e.g. `exports/weekly`. Empty for the root of
+    /// the bucket.
+    pub destination: String,
+    /// Another bucket (or Azure container) reachable with the project storage credentials
+    pub destination_bucket: Option<String>,
+    /// Answer right away and write the export in the background, to be followed on
+    /// `GET /projects/{project_id}/exports`
+    #[serde(default)]
+    pub background: bool,
+}
+
+/// What goes into a COCO export besides the annotations
+#[derive(Debug, Default)]
+pub(crate) struct CocoExportOptions {
+    pub include_images: bool,
+    pub include_metadata: bool,
+    /// Tasks of these splits only, every task when `None`
+    pub splits: Option<Vec<String>>,
+    /// Written to storage instead of downloaded when set
+    pub destination: Option<ExportDestination>,
+    /// Leaves out tasks annotated on blind copies and gold tasks, whose annotations members
+    /// who don't manage the project mustn't see
+    pub hide_blind_tasks: bool,
+}
+
+/// Splits of a comma-separated `splits` parameter, checked against `tasks::SPLITS`
+pub(crate) fn parse_splits(splits: &str) -> Result<Vec<String>, String> {
+    splits
+        .split(',')
+        .map(str::trim)
+        .filter(|split| !split.is_empty())
+        .map(|split| {
+            if crate::tasks::SPLITS.contains(&split) {
+                Ok(split.to_string())
+            } else {
+                Err(format!("Invalid split. Must be one of: {}", crate::tasks::SPLITS.join(", ")))
+            }
+        })
+        .collect()
+}
 
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/export/coco",
+    tag = "export",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ExportQuery,
+    ),
+    responses(
+        (status = 200, description = "COCO annotation file, or a ZIP with the images as well when `include_images` is set", body = CocoExport, content_type = "application/json"),
+        (status = 400, description = "Invalid split", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
 pub async fn export_project_coco(
     req: HttpRequest,
     path: web::Path<String>,
+    query: web::Query<ExportQuery>,
     pool: web::Data<Pool<Postgres>>,
     config: web::Data<crate::auth::OAuthConfig>,
 ) -> impl Responder {
@@ -34,34 +122,162 @@ pub async fn export_project_coco(
         return HttpResponse::NotFound().json("Project not found or access denied");
     }
 
+    let splits = match query.splits.as_deref().map(parse_splits).transpose() {
+        Ok(splits) => splits,
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+
+    // Plain members could read the blind copies of other annotators and gold references
+    let hide_blind_tasks = match crate::projects::user_can_manage_storage(&pool, project_id, user_id).await {
+        Ok(can_manage) => !can_manage,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check project access"),
+    };
+
+    let options = CocoExportOptions {
+        include_images: query.include_images.unwrap_or(false),
+        include_metadata: query.include_metadata.unwrap_or(false),
+        splits,
+        destination: None,
+        hide_blind_tasks,
+    };
+    coco_export_response(&pool, project_id, claims.email, &options).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/export/coco",
+    tag = "export",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+    ),
+    request_body = CocoDeliveryRequest,
+    responses(
+        (status = 201, description = "Export written to `destination`", body = ExportDelivery),
+        (status = 202, description = "Export started in the background", body = ExportJob),
+        (status = 400, description = "Invalid split or destination, or no storage to write to", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found, or the user is no owner or admin of it", body = String),
+        (status = 502, description = "The storage refused the export", body = String),
+    ),
+)]
+pub async fn deliver_project_coco(
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<CocoDeliveryRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // The export is written with the storage credentials, into any bucket they reach
+    match crate::projects::user_can_manage_storage(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check project access"),
+    }
+
+    let body = body.into_inner();
+    let splits = if body.splits.is_empty() {
+        None
+    } else {
+        match parse_splits(&body.splits.join(",")) {
+            Ok(splits) => Some(splits),
+            Err(e) => return HttpResponse::BadRequest().json(e),
+        }
+    };
+
+    let destination = match ExportDestination::from_query(Some(&body.destination), body.destination_bucket.as_deref()) {
+        Ok(destination) => destination,
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+
+    let options = CocoExportOptions {
+        include_images: body.include_images,
+        include_metadata: body.include_metadata,
+        splits,
+        destination,
+        hide_blind_tasks: false,
+    };
+
+    // Exports written to storage are recorded, so they can be followed and found again
+    let job = match record_export_start(&pool, project_id, user_id, "coco").await {
+        Ok(job) => job,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to record the export"),
+    };
+    let notify_config = req
+        .app_data::<web::Data<ExportNotifyConfig>>()
+        .map(|config| config.get_ref().clone())
+        .unwrap_or_default();
+
+    if body.background {
+        let pool = pool.get_ref().clone();
+        let job_id = job.id;
+        actix_web::rt::spawn(async move {
+            let response = coco_export_response(&pool, project_id, claims.email, &options).await;
+            finish_export_job(&pool, &notify_config, job_id, response).await;
+        });
+        return HttpResponse::Accepted().json(job);
+    }
+
+    let response = coco_export_response(&pool, project_id, claims.email, &options).await;
+    finish_export_job(&pool, &notify_config, job.id, response).await
+}
+
+/// COCO export of a project as a download, shared by the member and the share link endpoints.
+/// `contributor` ends up in the info block of the file. `include_metadata` adds the `x-fasttag`
+/// fields, which name the annotators.
+pub(crate) async fn coco_export_response(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    contributor: String,
+    options: &CocoExportOptions,
+) -> HttpResponse {
+    let include_metadata = options.include_metadata;
     // Get project info
-    let project = match get_project_info(&pool, project_id).await {
+    let project = match get_project_info(pool, project_id).await {
         Ok(Some(project)) => project,
         Ok(None) => return HttpResponse::NotFound().json("Project not found"),
         Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
     };
 
     // Get categories
-    let categories = match get_project_categories_for_export(&pool, project_id).await {
+    let categories = match get_project_categories_for_export(pool, project_id).await {
         Ok(cats) => cats,
         Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch categories"),
     };
 
     // Get tasks with annotations
-    let (images, annotations) = match get_project_annotations_for_export(&pool, project_id).await {
+    let (images, annotations) = match get_project_annotations_for_export(pool, project_id, include_metadata, options.splits.as_deref(), options.hide_blind_tasks).await {
         Ok(data) => data,
         Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotations"),
     };
 
     // Build COCO format export
-    let coco_export = CocoExport {
+    let mut coco_export = CocoExport {
         info: CocoInfo {
             year: Utc::now().year(),
             version: "1.0".to_string(),
             description: project.description.unwrap_or_else(|| project.name.clone()),
-            contributor: claims.email,
+            contributor,
             url: "https://fast-tag.com".to_string(),
             date_created: Utc::now().to_rfc3339(),
+            x_fasttag: include_metadata.then(|| FastTagInfo {
+                project_id: project_id.to_string(),
+                schema_version: FASTTAG_SCHEMA_VERSION,
+            }),
         },
         licenses: vec![CocoLicense {
             id: 1,
@@ -74,10 +290,18 @@ pub async fn export_project_coco(
     };
 
     // Generate filename
-    let filename = format!("{}_coco_export_{}.json", 
-        project.name.replace(" ", "_").to_lowercase(), 
+    let file_stem = format!("{}_coco_export_{}",
+        project.name.replace(" ", "_").to_lowercase(),
         Utc::now().format("%Y%m%d_%H%M%S")
     );
+    let filename = format!("{}.json", file_stem);
+
+    // Rewrite image file names to point at the bundled copies before serializing
+    let bundle_entries = if options.include_images {
+        Some(bundle::assign_archive_paths(&mut coco_export.images))
+    } else {
+        None
+    };
 
     // Return JSON file as download with 2-space indentation
     let pretty_json = {
@@ -90,6 +314,31 @@ pub async fn export_project_coco(
             Err(_) => return HttpResponse::InternalServerError().json("Failed to serialize JSON"),
         }
     };
+
+    if let Some(destination) = &options.destination {
+        return deliver_coco_export(pool, project_id, destination, &file_stem, filename, pretty_json.into_bytes(), bundle_entries).await;
+    }
+
+    if let Some(entries) = bundle_entries {
+        let full_project = match get_project_by_id(pool, project_id).await {
+            Ok(Some(project)) => project,
+            Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+        };
+
+        let storage_provider = match create_storage_provider_from_project(&full_project).await {
+            Ok(provider) => provider,
+            Err(e) => return HttpResponse::BadRequest().json(format!("Storage not available: {}", e)),
+        };
+
+        return bundle::stream_export_bundle(
+            filename,
+            pretty_json.into_bytes(),
+            entries,
+            storage_provider,
+            format!("{}.zip", file_stem),
+        );
+    }
     
     HttpResponse::Ok()
         .content_type("application/json")
@@ -97,6 +346,43 @@ pub async fn export_project_coco(
         .body(pretty_json)
 }
 
+/// Writes the annotation file, or the ZIP with the images, to the destination
+async fn deliver_coco_export(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    destination: &ExportDestination,
+    file_stem: &str,
+    annotation_file_name: String,
+    annotation_json: Vec<u8>,
+    bundle_entries: Option<Vec<bundle::BundleEntry>>,
+) -> HttpResponse {
+    let project = match get_project_by_id(pool, project_id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    };
+
+    let storage_provider = match create_storage_provider_from_project(&project).await {
+        Ok(provider) => provider,
+        Err(e) => return HttpResponse::BadRequest().json(format!("Storage not available: {}", e)),
+    };
+
+    let (file_name, data, content_type) = match bundle_entries {
+        Some(entries) => {
+            match bundle::build_export_bundle(&annotation_file_name, &annotation_json, &entries, storage_provider.as_ref()).await {
+                Ok(archive) => (format!("{}.zip", file_stem), archive, "application/zip"),
+                Err(e) => {
+                    eprintln!("Failed to build export bundle: {}", e);
+                    return HttpResponse::InternalServerError().json("Failed to build export bundle");
+                }
+            }
+        }
+        None => (annotation_file_name, annotation_json, "application/json"),
+    };
+
+    deliver_export(&project, storage_provider, destination, &file_name, data, content_type).await
+}
+
 // Helper structures
 #[derive(Debug)]
 struct ProjectInfo {
@@ -121,6 +407,18 @@ async fn get_project_info(
     }))
 }
 
+pub(super) async fn get_project_by_id(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Option<crate::projects::Project>, sqlx::Error> {
+    sqlx::query_as::<_, crate::projects::Project>(
+        "SELECT id, name, description, storage_config, owner_id, task_type, created_at, updated_at FROM projects WHERE id = $1"
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
 async fn get_project_categories_for_export(
     pool: &Pool<Postgres>,
     project_id: Uuid,
@@ -160,16 +458,25 @@ async fn get_project_categories_for_export(
 async fn get_project_annotations_for_export(
     pool: &Pool<Postgres>,
     project_id: Uuid,
+    include_metadata: bool,
+    splits: Option<&[String]>,
+    hide_blind_tasks: bool,
 ) -> Result<(Vec<CocoImage>, Vec<CocoAnnotation>), sqlx::Error> {
-    // First, get all tasks for the project
+    // First, get the tasks of the project, the annotations of the others are left out below
     let tasks = sqlx::query!(
         r#"
         SELECT id, name, resource_url, created_at, width, height
-        FROM tasks
-        WHERE project_id = $1
-        ORDER BY created_at
+        FROM tasks t
+        WHERE project_id = $1 AND ($2::text[] IS NULL OR split = ANY($2))
+            AND NOT ($3::bool AND (
+                gold_annotation_id IS NOT NULL
+                OR EXISTS(SELECT 1 FROM task_assignments ta WHERE ta.task_id = t.id)
+            ))
+        ORDER BY created_at, name, id
         "#,
-        project_id
+        project_id,
+        splits.map(<[String]>::to_vec),
+        hide_blind_tasks
     )
     .fetch_all(pool)
     .await?;
@@ -191,14 +498,23 @@ async fn get_project_annotations_for_export(
             ia.bbox,
             ia.area,
             ia.iscrowd,
+            ia.attributes,
+            ia.rotation,
+            ia.mask,
             iac.coco_id as category_coco_id,
-            iac.id as category_id
+            iac.id as category_id,
+            u.email as "annotated_by_email?",
+            a.annotated_at,
+            t.status as task_status,
+            t.review_requested_at
         FROM latest_annotations la
         JOIN annotations a ON la.id = a.id
+        JOIN tasks t ON a.task_id = t.id
         JOIN image_annotations ia ON a.id = ia.annotation_id
         JOIN image_annotation_categories iac ON ia.category_id = iac.id
-        WHERE la.rn = 1
-        ORDER BY a.created_at
+        LEFT JOIN users u ON a.annotated_by = u.id
+        WHERE la.rn = 1 AND NOT ia.is_prediction
+        ORDER BY t.created_at, t.name, t.id, ia.created_at, ia.bbox, ia.id
         "#,
         project_id
     )
@@ -264,14 +580,37 @@ async fn get_project_annotations_for_export(
                 }
             }) as i32;
 
+            // Masks are exported as RLE, rotated boxes as a 4-point polygon with the enclosing box as bbox
+            let mask = row.mask.and_then(|mask| serde_json::from_value::<CocoRle>(mask).ok());
+            let (segmentation, bbox_vec) = if let Some(mask) = mask {
+                (CocoSegmentation::Rle(mask), bbox_vec)
+            } else if row.rotation != 0.0 && bbox_vec.len() >= 4 {
+                let corners = crate::rotated_box::corners(&bbox_vec, row.rotation);
+                (CocoSegmentation::Polygons(vec![crate::rotated_box::polygon(&corners)]), crate::rotated_box::enclosing_bbox(&corners))
+            } else {
+                (CocoSegmentation::default(), bbox_vec) // Bounding box format doesn't use segmentation
+            };
+
             annotations.push(CocoAnnotation {
                 id: annotation_id_counter,
                 image_id,
                 category_id: category_coco_id,
-                segmentation: vec![], // Bounding box format doesn't use segmentation
+                segmentation,
                 area,
                 bbox: bbox_vec,
                 iscrowd: if row.iscrowd.unwrap_or(false) { 1 } else { 0 },
+                // Custom attributes are only written when the category defines some
+                attributes: row.attributes.as_object()
+                    .is_some_and(|attributes| !attributes.is_empty())
+                    .then_some(row.attributes),
+                x_fasttag: include_metadata.then(|| FastTagAnnotation {
+                    task_id: row.task_id.to_string(),
+                    annotation_id: row.annotation_id.to_string(),
+                    annotated_by: row.annotated_by_email,
+                    annotated_at: row.annotated_at.map(|at| at.to_rfc3339()),
+                    task_status: row.task_status,
+                    review_requested_at: row.review_requested_at.map(|at| at.to_rfc3339()),
+                }),
             });
 
             annotation_id_counter += 1;
@@ -282,19 +621,7 @@ async fn get_project_annotations_for_export(
 }
 
 pub(super) async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
-    sqlx::query_scalar::<_, bool>(
-        r#"
-        SELECT EXISTS(
-            SELECT 1 FROM project_members pm
-            WHERE pm.project_id = $1 AND pm.user_id = $2
-        )
-        "#
-    )
-    .bind(project_id)
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .unwrap_or(false)
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
 }
 
 pub(super) fn extract_user_claims(
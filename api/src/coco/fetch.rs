@@ -0,0 +1,286 @@
+use futures_util::StreamExt;
+use sha2::{Digest, Sha256};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::types::CocoImage;
+use crate::storage::StorageProvider;
+
+/// Images downloaded at the same time during an import
+const PARALLEL_DOWNLOADS: usize = 4;
+/// Time allowed for one image, from the request to the last byte
+const DOWNLOAD_TIMEOUT_SECS: u64 = 60;
+/// Folder of the project storage the fetched images go to
+const IMPORT_PREFIX: &str = "imports";
+/// Redirects followed for one image
+const MAX_REDIRECTS: usize = 10;
+/// Hex digits of the query hash kept in a storage key
+const QUERY_HASH_LENGTH: usize = 16;
+
+/// Where an image of a COCO file can be downloaded from: its `coco_url`, or its `file_name`
+/// when that is a URL itself
+pub(super) fn source_url(image: &CocoImage) -> Option<reqwest::Url> {
+    [image.coco_url.as_deref(), Some(image.file_name.as_str())]
+        .into_iter()
+        .flatten()
+        .filter_map(|candidate| reqwest::Url::parse(candidate).ok())
+        .find(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some())
+}
+
+/// Storage key of a downloaded image, made of the host and path of its URL so importing the
+/// same file again finds the images it already fetched. A query picks another file on many
+/// hosts, so its hash goes in front of the extension.
+fn storage_key(url: &reqwest::Url) -> String {
+    let mut path = url.path().trim_matches('/').to_string();
+    if let Some(query) = url.query().filter(|query| !query.is_empty()) {
+        let hash: String = Sha256::digest(query.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect();
+        let hash = &hash[..QUERY_HASH_LENGTH];
+        let name_start = path.rfind('/').map_or(0, |slash| slash + 1);
+        match path[name_start..].rfind('.').filter(|&dot| dot > 0) {
+            Some(dot) => path.insert_str(name_start + dot, &format!("-{}", hash)),
+            None => path.push_str(&format!("-{}", hash)),
+        }
+    }
+    format!("{}/{}/{}", IMPORT_PREFIX, url.host_str().unwrap_or("unknown"), path)
+}
+
+/// Whether an address is reachable from the internet. Images come from URLs anyone with write
+/// access to a project can put in a COCO file, so the server must not fetch from itself or its
+/// private network on their behalf.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let shared = ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64;
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || shared)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(mapped));
+            }
+            let unique_local = (ip.segments()[0] & 0xfe00) == 0xfc00;
+            let link_local = (ip.segments()[0] & 0xffc0) == 0xfe80;
+            !(ip.is_loopback() || ip.is_unspecified() || ip.is_multicast() || unique_local || link_local)
+        }
+    }
+}
+
+/// A host in a URL given as an address that isn't public. Host names are checked when they
+/// are resolved, by `PublicResolver`.
+pub(crate) fn private_host(url: &reqwest::Url) -> Option<IpAddr> {
+    let ip = match url.host()? {
+        url::Host::Ipv4(ip) => IpAddr::V4(ip),
+        url::Host::Ipv6(ip) => IpAddr::V6(ip),
+        url::Host::Domain(_) => return None,
+    };
+    (!is_public(ip)).then_some(ip)
+}
+
+/// Resolves host names to their public addresses only, so a name pointing into the private
+/// network can't be fetched from, also not after a redirect
+pub(crate) struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|address| is_public(address.ip()))
+                .collect();
+            if addresses.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+            let addresses: reqwest::dns::Addrs = Box::new(addresses.into_iter());
+            Ok(addresses)
+        })
+    }
+}
+
+/// Follows redirects to public hosts only
+pub(crate) fn redirect_policy() -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(|attempt| {
+        if attempt.previous().len() > MAX_REDIRECTS {
+            return attempt.error("too many redirects");
+        }
+        if let Some(ip) = private_host(attempt.url()) {
+            let error = format!("redirected to the private address {}", ip);
+            return attempt.error(error);
+        }
+        attempt.follow()
+    })
+}
+
+/// Downloads the images of the file into the project storage and points them at the stored
+/// copies, so the tasks created for them don't depend on the original host. Images that can't
+/// be fetched keep their URL. Returns how many were stored and what went wrong.
+pub(super) async fn fetch_images(
+    images: &mut [CocoImage],
+    storage: Arc<dyn StorageProvider>,
+    max_bytes: usize,
+) -> (usize, Vec<String>) {
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(DOWNLOAD_TIMEOUT_SECS))
+        .dns_resolver(Arc::new(PublicResolver))
+        .redirect(redirect_policy())
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => return (0, vec![format!("Failed to set up image downloads: {}", e)]),
+    };
+
+    let downloads = images
+        .iter()
+        .enumerate()
+        .filter_map(|(index, image)| source_url(image).map(|url| (index, url)))
+        .map(|(index, url)| {
+            let client = client.clone();
+            let storage = storage.clone();
+            async move {
+                let key = storage_key(&url);
+                let result = store_image(&client, storage.as_ref(), &url, &key, max_bytes).await;
+                (index, url, key, result)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let results: Vec<_> = futures_util::stream::iter(downloads)
+        .buffer_unordered(PARALLEL_DOWNLOADS)
+        .collect()
+        .await;
+
+    let mut fetched = 0;
+    let mut errors = Vec::new();
+    for (index, url, key, result) in results {
+        let image = &mut images[index];
+        match result {
+            Ok(()) => {
+                // Tasks are named after the file, not the URL it came from
+                if image.file_name == url.as_str() {
+                    image.file_name = key.rsplit('/').next().unwrap_or(&key).to_string();
+                }
+                image.coco_url = Some(format!("storage://{}", key));
+                fetched += 1;
+            }
+            Err(e) => errors.push(format!("Failed to fetch image '{}' from {}: {}", image.file_name, url, e)),
+        }
+    }
+
+    (fetched, errors)
+}
+
+async fn store_image(
+    client: &reqwest::Client,
+    storage: &dyn StorageProvider,
+    url: &reqwest::Url,
+    key: &str,
+    max_bytes: usize,
+) -> Result<(), String> {
+    if let Some(ip) = private_host(url) {
+        return Err(format!("{} is a private address", ip));
+    }
+    if storage.exists(key).await.unwrap_or(false) {
+        return Ok(());
+    }
+
+    let mut response = client
+        .get(url.clone())
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| e.to_string())?;
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let mut data = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        if data.len() + chunk.len() > max_bytes {
+            return Err(format!("larger than the {} byte limit", max_bytes));
+        }
+        data.extend_from_slice(&chunk);
+    }
+
+    storage
+        .upload(key, &data, content_type.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(file_name: &str, coco_url: Option<&str>) -> CocoImage {
+        CocoImage {
+            id: 1,
+            width: 640,
+            height: 480,
+            file_name: file_name.to_string(),
+            license: 1,
+            flickr_url: None,
+            coco_url: coco_url.map(str::to_string),
+            date_captured: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_source_url_prefers_coco_url_and_skips_other_schemes() {
+        let url = source_url(&image("a.jpg", Some("http://images.example.com/val/a.jpg"))).unwrap();
+        assert_eq!(url.as_str(), "http://images.example.com/val/a.jpg");
+
+        let url = source_url(&image("https://cdn.example.com/b.jpg", Some("storage://b.jpg"))).unwrap();
+        assert_eq!(url.as_str(), "https://cdn.example.com/b.jpg");
+
+        assert!(source_url(&image("c.jpg", None)).is_none());
+        assert!(source_url(&image("c.jpg", Some("file:///etc/passwd"))).is_none());
+    }
+
+    #[test]
+    fn test_storage_key_keeps_host_and_path() {
+        let url = reqwest::Url::parse("http://images.example.com/val2017/000001.jpg").unwrap();
+        assert_eq!(storage_key(&url), "imports/images.example.com/val2017/000001.jpg");
+    }
+
+    #[test]
+    fn test_storage_key_tells_queries_apart() {
+        let key = |url: &str| storage_key(&reqwest::Url::parse(url).unwrap());
+
+        let full = key("http://images.example.com/val2017/000001.jpg?size=full");
+        let thumbnail = key("http://images.example.com/val2017/000001.jpg?size=thumbnail");
+        assert_ne!(full, thumbnail);
+        assert_ne!(full, key("http://images.example.com/val2017/000001.jpg"));
+        assert_eq!(full, key("http://images.example.com/val2017/000001.jpg?size=full"));
+
+        // The hash goes in front of the extension, so the file keeps its type
+        assert!(full.starts_with("imports/images.example.com/val2017/000001-"));
+        assert!(full.ends_with(".jpg"));
+        assert_eq!(full.len(), thumbnail.len());
+        assert!(key("http://images.example.com/render?id=1").starts_with("imports/images.example.com/render-"));
+    }
+
+    #[test]
+    fn test_private_addresses_are_refused() {
+        for address in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "0.0.0.0", "100.64.0.1", "::1", "::", "fd00::1", "fe80::1", "::ffff:127.0.0.1"] {
+            assert!(!is_public(address.parse().unwrap()), "{} should be refused", address);
+        }
+        for address in ["93.184.216.34", "8.8.8.8", "2606:4700::1111"] {
+            assert!(is_public(address.parse().unwrap()), "{} should be allowed", address);
+        }
+
+        let url = |url: &str| reqwest::Url::parse(url).unwrap();
+        assert!(private_host(&url("http://169.254.169.254/latest/meta-data")).is_some());
+        assert!(private_host(&url("http://[::1]:8080/a.jpg")).is_some());
+        assert!(private_host(&url("http://images.example.com/a.jpg")).is_none());
+        assert!(private_host(&url("http://8.8.8.8/a.jpg")).is_none());
+    }
+}
@@ -1,15 +1,49 @@
 use actix_web::{web, HttpResponse, Responder, HttpRequest};
 use actix_multipart::Multipart;
 use futures_util::TryStreamExt;
+use serde::Deserialize;
 use sqlx::{Pool, Postgres};
+use std::collections::HashSet;
+use utoipa::IntoParams;
 use uuid::Uuid;
 
-use super::types::{CocoImport, CocoCategory, CocoImage, CocoAnnotation, ImportResult, ImportStats};
-use super::export::{user_has_project_access, extract_user_claims};
+use super::types::{CocoImport, CocoCategory, CocoImage, CocoAnnotation, CocoRle, ImportDryRun, ImportResult, ImportStats};
+use super::export::{get_project_by_id, user_has_project_access, extract_user_claims};
+use super::fetch;
+use crate::limits::LimitsConfig;
+use crate::storage::factory::create_storage_provider_from_project;
+use crate::openapi::FileUpload;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportQuery {
+    /// Only validate the file and report what the import would do, without writing anything
+    pub dry_run: Option<bool>,
+    /// Download the images from their `coco_url`, or `file_name` when it is a URL, into the
+    /// project storage and point the tasks at the stored copies
+    pub fetch_images: Option<bool>,
+}
 
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/import/coco",
+    tag = "import",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ImportQuery,
+    ),
+    request_body(content = FileUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Outcome of the import, or an `ImportDryRun` report with `dry_run`", body = ImportResult),
+        (status = 400, description = "Invalid COCO file", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
 pub async fn import_project_coco(
     req: HttpRequest,
     path: web::Path<String>,
+    query: web::Query<ImportQuery>,
     mut payload: Multipart,
     pool: web::Data<Pool<Postgres>>,
     config: web::Data<crate::auth::OAuthConfig>,
@@ -36,24 +70,57 @@ pub async fn import_project_coco(
     }
 
     // Extract JSON data from multipart upload
-    let json_data = match extract_json_from_multipart(&mut payload).await {
+    let max_bytes = req.app_data::<web::Data<LimitsConfig>>()
+        .map(|limits| limits.max_upload_bytes)
+        .unwrap_or_else(|| LimitsConfig::default().max_upload_bytes);
+    let json_data = match extract_json_from_multipart(&mut payload, max_bytes).await {
         Ok(data) => data,
         Err(err) => return HttpResponse::BadRequest().json(format!("Failed to read file: {}", err)),
     };
 
-    // Parse COCO JSON
-    let coco_data: CocoImport = match serde_json::from_str(&json_data) {
-        Ok(data) => data,
-        Err(err) => return HttpResponse::BadRequest().json(format!("Invalid COCO JSON: {}", err)),
+    // Parse COCO JSON straight from the uploaded bytes, off the async workers since files of
+    // hundreds of MB take a while
+    let parsed = web::block(move || serde_json::from_slice::<CocoImport>(&json_data)).await;
+    let mut coco_data = match parsed {
+        Ok(Ok(data)) => data,
+        Ok(Err(err)) => return HttpResponse::BadRequest().json(format!("Invalid COCO JSON: {}", err)),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to parse COCO JSON"),
     };
 
+    if query.dry_run.unwrap_or(false) {
+        return match plan_coco_import(&pool, project_id, coco_data).await {
+            Ok(dry_run) => HttpResponse::Ok().json(dry_run),
+            Err(_) => HttpResponse::InternalServerError().json("Failed to check the project"),
+        };
+    }
+
     // Validate COCO data
-    if let Err(validation_error) = validate_coco_data(&coco_data) {
+    if let Err(validation_error) = fast_tag_formats::coco::validate(&coco_data) {
         return HttpResponse::BadRequest().json(format!("Invalid COCO data: {}", validation_error));
     }
 
+    // Bring the images over before anything is written, the tasks then refer to the stored copies
+    let (images_fetched, fetch_errors) = if query.fetch_images.unwrap_or(false) {
+        let project = match get_project_by_id(&pool, project_id).await {
+            Ok(Some(project)) => project,
+            Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+        };
+        let storage_provider = match create_storage_provider_from_project(&project).await {
+            Ok(provider) => provider,
+            Err(e) => return HttpResponse::BadRequest().json(format!("Storage not available: {}", e)),
+        };
+        fetch::fetch_images(&mut coco_data.images, storage_provider, max_bytes).await
+    } else {
+        (0, Vec::new())
+    };
+
     // Import the data
-    match import_coco_data(&pool, project_id, user_id, coco_data).await {
+    let result = import_coco_data(&pool, project_id, user_id, coco_data, images_fetched, fetch_errors).await;
+    // Categories are created one by one, so some may exist even when the import failed
+    crate::cache::invalidate_categories(project_id).await;
+
+    match result {
         Ok(result) => HttpResponse::Ok().json(result),
         Err(err) => {
             eprintln!("Import error: {:?}", err);
@@ -62,66 +129,86 @@ pub async fn import_project_coco(
     }
 }
 
-async fn extract_json_from_multipart(payload: &mut Multipart) -> Result<String, Box<dyn std::error::Error>> {
+/// Reads the `file` field, stopping at `max_bytes` for uploads sent without a `Content-Length`
+/// that the limits middleware could not reject up front
+async fn extract_json_from_multipart(
+    payload: &mut Multipart,
+    max_bytes: usize,
+) -> Result<bytes::Bytes, Box<dyn std::error::Error>> {
     while let Some(mut field) = payload.try_next().await? {
         let field_name = field.name();
         
         if field_name == Some("file") {
             let mut data = bytes::BytesMut::new();
             while let Some(chunk) = field.try_next().await? {
+                if data.len() + chunk.len() > max_bytes {
+                    return Err(format!("File is larger than the {} byte limit", max_bytes).into());
+                }
                 data.extend_from_slice(&chunk);
             }
             
-            return Ok(String::from_utf8(data.to_vec())?);
+            return Ok(data.freeze());
         }
     }
     
     Err("No file field found in multipart data".into())
 }
 
-fn validate_coco_data(coco_data: &CocoImport) -> Result<(), String> {
-    // Check for required fields
-    if coco_data.categories.is_empty() {
-        return Err("No categories found in COCO data".to_string());
-    }
+/// Dry run of an import: every problem of the file, and what importing it would create and
+/// update in the project
+async fn plan_coco_import(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    coco_data: CocoImport,
+) -> Result<ImportDryRun, sqlx::Error> {
+    let existing_categories: Vec<(String, Option<i32>)> = sqlx::query_as(
+        "SELECT name, coco_id FROM image_annotation_categories WHERE project_id = $1"
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+    let existing_tasks: Vec<String> = sqlx::query_scalar("SELECT name FROM tasks WHERE project_id = $1")
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
 
-    if coco_data.images.is_empty() {
-        return Err("No images found in COCO data".to_string());
-    }
+    let category_names: HashSet<&str> = existing_categories.iter().map(|(name, _)| name.as_str()).collect();
+    let task_names: HashSet<&str> = existing_tasks.iter().map(String::as_str).collect();
 
-    // Validate category IDs are unique
-    let mut category_ids = std::collections::HashSet::new();
+    // Imported categories take the COCO ID of the file, which another category may already have
+    let mut coco_id_conflicts = Vec::new();
     for category in &coco_data.categories {
-        if !category_ids.insert(category.id) {
-            return Err(format!("Duplicate category ID: {}", category.id));
+        let taken_by = existing_categories
+            .iter()
+            .find(|(name, coco_id)| *coco_id == Some(category.id) && *name != category.name);
+        if let Some((name, _)) = taken_by {
+            coco_id_conflicts.push(format!(
+                "Category '{}' would get COCO ID {}, which '{}' already has",
+                category.name, category.id, name
+            ));
         }
     }
 
-    // Validate image IDs are unique
-    let mut image_ids = std::collections::HashSet::new();
-    for image in &coco_data.images {
-        if !image_ids.insert(image.id) {
-            return Err(format!("Duplicate image ID: {}", image.id));
-        }
-    }
-
-    // Validate annotations reference valid categories and images
-    let category_id_set: std::collections::HashSet<_> = coco_data.categories.iter().map(|c| c.id).collect();
-    let image_id_set: std::collections::HashSet<_> = coco_data.images.iter().map(|i| i.id).collect();
-
-    for annotation in &coco_data.annotations {
-        if !category_id_set.contains(&annotation.category_id) {
-            return Err(format!("Annotation {} references invalid category ID: {}", annotation.id, annotation.category_id));
-        }
-        if !image_id_set.contains(&annotation.image_id) {
-            return Err(format!("Annotation {} references invalid image ID: {}", annotation.id, annotation.image_id));
-        }
-        if annotation.bbox.len() != 4 {
-            return Err(format!("Annotation {} has invalid bbox format", annotation.id));
-        }
-    }
-
-    Ok(())
+    let categories_to_update = coco_data.categories.iter()
+        .filter(|category| category_names.contains(category.name.as_str()))
+        .count();
+    let tasks_to_update = coco_data.images.iter()
+        .filter(|image| task_names.contains(image.file_name.as_str()))
+        .count();
+    let annotated_images: HashSet<i64> = coco_data.annotations.iter().map(|annotation| annotation.image_id).collect();
+
+    let validation = fast_tag_formats::coco::validation_report(&coco_data);
+    Ok(ImportDryRun {
+        valid: validation.is_valid(),
+        categories_to_create: coco_data.categories.len() - categories_to_update,
+        categories_to_update,
+        tasks_to_create: coco_data.images.len() - tasks_to_update,
+        tasks_to_update,
+        tasks_to_annotate: coco_data.images.iter().filter(|image| annotated_images.contains(&image.id)).count(),
+        images_to_fetch: coco_data.images.iter().filter(|image| fetch::source_url(image).is_some()).count(),
+        coco_id_conflicts,
+        validation,
+    })
 }
 
 async fn import_coco_data(
@@ -129,13 +216,16 @@ async fn import_coco_data(
     project_id: Uuid,
     user_id: Uuid,
     coco_data: CocoImport,
+    images_fetched: usize,
+    fetch_errors: Vec<String>,
 ) -> Result<ImportResult, sqlx::Error> {
     let mut stats = ImportStats {
         categories_created: 0,
         categories_updated: 0,
         tasks_created: 0,
         annotations_created: 0,
-        errors: Vec::new(),
+        images_fetched,
+        errors: fetch_errors,
     };
 
     // Start transaction
@@ -174,14 +264,28 @@ async fn import_coco_data(
     }
 
     // Group annotations by task to ensure only one annotation per task
-    let mut task_annotations: std::collections::HashMap<Uuid, Vec<(&CocoAnnotation, Uuid)>> = std::collections::HashMap::new();
+    let mut task_annotations: std::collections::HashMap<Uuid, Vec<(&CocoAnnotation, Uuid, Option<&CocoRle>)>> = std::collections::HashMap::new();
+    let image_sizes: std::collections::HashMap<i64, (i32, i32)> = coco_data.images
+        .iter()
+        .map(|image| (image.id, (image.width, image.height)))
+        .collect();
     
     for coco_annotation in &coco_data.annotations {
         if let (Some(&category_id), Some(&task_id)) = (
             category_mapping.get(&coco_annotation.category_id),
             image_mapping.get(&coco_annotation.image_id),
         ) {
-            task_annotations.entry(task_id).or_default().push((coco_annotation, category_id));
+            // The box is still imported, without the mask
+            let mask = coco_annotation.segmentation.rle().filter(|mask| {
+                let (width, height) = image_sizes[&coco_annotation.image_id];
+                let checked = super::types::rle_runs(mask)
+                    .and_then(|_| super::types::check_mask_size(mask, width, height));
+                if let Err(message) = &checked {
+                    stats.errors.push(format!("Annotation {} has an invalid mask: {}", coco_annotation.id, message));
+                }
+                checked.is_ok()
+            });
+            task_annotations.entry(task_id).or_default().push((coco_annotation, category_id, mask));
         } else {
             stats.errors.push(format!("Annotation {} references invalid category or image", coco_annotation.id));
         }
@@ -285,6 +389,10 @@ async fn import_image_as_task(
     .fetch_optional(&mut **tx)
     .await?;
 
+    // Files without the image sizes give 0, which leaves the size of the task unknown
+    let width = (coco_image.width > 0).then_some(coco_image.width);
+    let height = (coco_image.height > 0).then_some(coco_image.height);
+
     match existing_task {
         Some(existing) => {
             // Update existing task with resource URL if available
@@ -297,6 +405,14 @@ async fn import_image_as_task(
                 .execute(&mut **tx)
                 .await?;
             }
+            sqlx::query!(
+                "UPDATE tasks SET width = COALESCE($1, width), height = COALESCE($2, height) WHERE id = $3",
+                width,
+                height,
+                existing.id
+            )
+            .execute(&mut **tx)
+            .await?;
             Ok(existing.id)
         }
         None => {
@@ -304,13 +420,15 @@ async fn import_image_as_task(
             let new_id = Uuid::new_v4();
             sqlx::query!(
                 r#"
-                INSERT INTO tasks (id, project_id, name, resource_url, created_at, updated_at)
-                VALUES ($1, $2, $3, $4, NOW(), NOW())
+                INSERT INTO tasks (id, project_id, name, resource_url, width, height, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, NOW(), NOW())
                 "#,
                 new_id,
                 project_id,
                 coco_image.file_name,
-                coco_image.coco_url
+                coco_image.coco_url,
+                width,
+                height
             )
             .execute(&mut **tx)
             .await?;
@@ -323,14 +441,14 @@ async fn import_image_as_task(
 async fn import_task_annotations(
     tx: &mut sqlx::Transaction<'_, Postgres>,
     task_id: Uuid,
-    annotations: &[(&CocoAnnotation, Uuid)],
+    annotations: &[(&CocoAnnotation, Uuid, Option<&CocoRle>)],
     user_id: Uuid,
 ) -> Result<usize, sqlx::Error> {
     let annotation_id = Uuid::new_v4();
     let now = chrono::Utc::now();
 
     // Collect all original COCO IDs for metadata
-    let coco_ids: Vec<i64> = annotations.iter().map(|(ann, _)| ann.id).collect();
+    let coco_ids: Vec<i64> = annotations.iter().map(|(ann, _, _)| ann.id).collect();
     
     println!("import_task_annotations: Creating annotation {} for task {}", annotation_id, task_id);
     println!("import_task_annotations: Will create {} image_annotations", annotations.len());
@@ -352,14 +470,14 @@ async fn import_task_annotations(
     .execute(&mut **tx)
     .await?;
 
-    // Create image annotations for each bounding box
-    for (coco_annotation, category_id) in annotations {
+    // Create image annotations for each bounding box, with the mask of RLE segmentations
+    for (coco_annotation, category_id, mask) in annotations {
         let image_annotation_id = Uuid::new_v4();
         
         sqlx::query!(
             r#"
-            INSERT INTO image_annotations (id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            INSERT INTO image_annotations (id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, mask, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             "#,
             image_annotation_id,
             annotation_id,
@@ -368,6 +486,7 @@ async fn import_task_annotations(
             Some(coco_annotation.area as f64),
             coco_annotation.iscrowd == 1,
             serde_json::json!({"imported_from_coco": true, "original_coco_id": coco_annotation.id}),
+            crate::annotations::stored_mask(*mask),
             now,
             now
         )
@@ -1,8 +1,10 @@
 pub mod types;
+pub mod bundle;
 pub mod export;
+pub mod fetch;
 pub mod import;
 
-pub use export::export_project_coco;
+pub use export::{deliver_project_coco, export_project_coco};
 pub use import::import_project_coco;
 
 #[cfg(test)]
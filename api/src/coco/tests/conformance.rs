@@ -0,0 +1,154 @@
+//! Conformance suite of the COCO import and export. Every `fixtures/<name>.json` is imported into
+//! a new project and exported again, and the export has to match `fixtures/<name>.expected.json`
+//! byte for byte, apart from the fields that depend on when and by whom it was made. Importing
+//! that export into another project has to give the same file again.
+//!
+//! Ignored by default. Run it with
+//! `cargo test -p api coco::tests::conformance -- --ignored`, and with `UPDATE_FIXTURES=1` to
+//! rewrite the expected files after an intended change of the export.
+
+use super::*;
+use std::path::{Path, PathBuf};
+
+const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/coco/tests/fixtures");
+
+/// Fields of the export that change with every run
+const VOLATILE_FIELDS: [&str; 4] = ["year", "contributor", "date_created", "date_captured"];
+
+/// Description of the fixture projects, which the export writes into its info block
+const PROJECT_DESCRIPTION: &str = "Conformance fixture";
+
+/// Files to import, in name order
+fn fixture_inputs() -> Vec<PathBuf> {
+    let mut inputs: Vec<PathBuf> = std::fs::read_dir(FIXTURES_DIR)
+        .expect("Failed to read the fixtures directory")
+        .map(|entry| entry.expect("Failed to read a fixture").path())
+        .filter(|path| {
+            let name = path.to_string_lossy();
+            name.ends_with(".json") && !name.ends_with(".expected.json")
+        })
+        .collect();
+    inputs.sort();
+    inputs
+}
+
+fn expected_path(input: &Path) -> PathBuf {
+    input.with_extension("expected.json")
+}
+
+/// Replaces the values of the volatile fields, which the pretty-printed export puts on lines of
+/// their own, and ends the file with a newline like the expected files
+fn mask_volatile_fields(export: &str) -> String {
+    let mut masked = String::with_capacity(export.len());
+    for line in export.lines() {
+        let value = line.trim_start();
+        let indent = &line[..line.len() - value.len()];
+        let field = VOLATILE_FIELDS
+            .iter()
+            .find(|field| value.starts_with(&format!("\"{}\": ", field)));
+        match field {
+            Some(field) => {
+                let comma = if value.ends_with(',') { "," } else { "" };
+                masked.push_str(&format!("{}\"{}\": \"<volatile>\"{}", indent, field, comma));
+            }
+            None => masked.push_str(line),
+        }
+        masked.push('\n');
+    }
+    masked
+}
+
+fn multipart_body(json: &[u8], boundary: &str) -> Vec<u8> {
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"fixture.json\"\r\nContent-Type: application/json\r\n\r\n",
+        boundary
+    )
+    .into_bytes();
+    body.extend_from_slice(json);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    body
+}
+
+#[actix_web::test]
+async fn test_mask_volatile_fields() {
+    let export = "{\n  \"info\": {\n    \"year\": 2026,\n    \"date_created\": \"2026-10-16T00:00:00+00:00\"\n  },\n  \"name\": \"year\"\n}";
+    assert_eq!(
+        mask_volatile_fields(export),
+        "{\n  \"info\": {\n    \"year\": \"<volatile>\",\n    \"date_created\": \"<volatile>\"\n  },\n  \"name\": \"year\"\n}\n"
+    );
+}
+
+#[actix_web::test]
+#[serial]
+#[ignore = "conformance suite, run with --ignored"]
+async fn test_coco_fixtures_round_trip() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+    let auth_storage = AuthStorage::new(pool.clone());
+    let update = std::env::var("UPDATE_FIXTURES").is_ok_and(|value| value == "1");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/import/coco", web::post().to(import_project_coco))
+            .route("/projects/{project_id}/export/coco", web::get().to(export_project_coco))
+    ).await;
+
+    let inputs = fixture_inputs();
+    assert!(!inputs.is_empty(), "No fixtures in {}", FIXTURES_DIR);
+
+    for input in inputs {
+        let name = input.file_stem().unwrap().to_string_lossy().to_string();
+        let mut file = std::fs::read(&input).expect("Failed to read the fixture");
+        let mut exports = Vec::new();
+
+        // The fixture, then its own export
+        for round in ["import", "round trip"] {
+            let project = crate::projects::create_project_in_db(
+                &pool,
+                &format!("{} ({})", name, round),
+                Some(PROJECT_DESCRIPTION),
+                None,
+                user.id,
+            )
+            .await
+            .unwrap();
+
+            let boundary = "----conformance-boundary";
+            let req = test::TestRequest::post()
+                .uri(&format!("/projects/{}/import/coco", project.id))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+                .set_payload(multipart_body(&file, boundary))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), 200, "{}: the {} was refused", name, round);
+            let result: types::ImportResult = test::read_body_json(resp).await;
+            assert!(result.success, "{}: the {} failed: {:?}", name, round, result.stats.errors);
+
+            let req = test::TestRequest::get()
+                .uri(&format!("/projects/{}/export/coco", project.id))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), 200, "{}: the export after the {} failed", name, round);
+            file = test::read_body(resp).await.to_vec();
+            exports.push(mask_volatile_fields(&String::from_utf8(file.clone()).unwrap()));
+        }
+
+        assert_eq!(exports[0], exports[1], "{}: exporting the export again changed it", name);
+
+        let expected_path = expected_path(&input);
+        if update {
+            std::fs::write(&expected_path, &exports[0]).expect("Failed to write the expected export");
+            continue;
+        }
+        let expected = std::fs::read_to_string(&expected_path)
+            .unwrap_or_else(|_| panic!("{}: no {}, run with UPDATE_FIXTURES=1 to write it", name, expected_path.display()));
+        assert_eq!(exports[0], expected, "{}: the export differs from the expected one", name);
+    }
+}
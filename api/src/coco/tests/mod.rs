@@ -0,0 +1,958 @@
+use super::*;
+use crate::auth::{User, OAuthConfig, AuthStorage, JwtManager};
+use crate::test_utils;
+use actix_web::{test, App, web};
+use serial_test::serial;
+
+mod conformance;
+
+fn create_test_oauth_config() -> OAuthConfig {
+    OAuthConfig {
+        google_client_id: "test_google_id".to_string(),
+        google_client_secret: "test_google_secret".to_string(),
+        google_redirect_url: "http://localhost/callback".to_string(),
+        github_client_id: "test_github_id".to_string(),
+        github_client_secret: "test_github_secret".to_string(),
+        github_redirect_url: "http://localhost/callback".to_string(),
+        jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+    }
+}
+
+fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+    let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+    jwt_manager.generate_token(
+        &user.id.to_string(),
+        &user.email,
+        &user.name
+    ).expect("Failed to generate token")
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_export_project_coco_empty() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+    let auth_storage = AuthStorage::new(pool.clone());
+
+    // Create a test project
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/export/coco", web::get().to(export_project_coco))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/export/coco", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let headers = resp.headers();
+    assert!(headers.get("Content-Disposition").is_some());
+    assert!(headers.get("Content-Type").unwrap().to_str().unwrap().contains("application/json"));
+
+    let body: types::CocoExport = test::read_body_json(resp).await;
+    assert_eq!(body.images.len(), 0);
+    assert_eq!(body.annotations.len(), 0);
+    assert_eq!(body.categories.len(), 0);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_export_project_coco_with_data() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+    let auth_storage = AuthStorage::new(pool.clone());
+
+    // Create test data
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+    let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, Some("human"), Some("#FF0000"), Some(1)).await.unwrap();
+    let task = crate::tasks::create_task_in_db(&pool, project.id, "image1.jpg", Some("https://example.com/image1.jpg")).await.unwrap();
+    
+    // Create annotation
+    let bbox = crate::annotations::BoundingBox {
+        category_id: category.id,
+        bbox: vec![100.0, 50.0, 200.0, 150.0],
+        area: Some(30000.0),
+        iscrowd: Some(false),
+        is_prediction: None,
+        confidence: None,
+        attributes: None,
+        rotation: None,
+        frame_index: None,
+        track_id: None,
+        is_interpolated: None,
+        mask: None,
+    };
+    crate::annotations::create_annotation_in_db(&pool, task.id, &[bbox], &serde_json::json!({}), user.id).await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/export/coco", web::get().to(export_project_coco))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/export/coco", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let body: types::CocoExport = test::read_body_json(resp).await;
+    assert_eq!(body.images.len(), 1);
+    assert_eq!(body.annotations.len(), 1);
+    assert_eq!(body.categories.len(), 1);
+
+    // Check category
+    assert_eq!(body.categories[0].id, 1);
+    assert_eq!(body.categories[0].name, "person");
+    assert_eq!(body.categories[0].supercategory, "human");
+
+    // Check image
+    assert_eq!(body.images[0].file_name, "image1.jpg");
+    assert_eq!(body.images[0].coco_url, Some("https://example.com/image1.jpg".to_string()));
+
+    // Check annotation
+    assert_eq!(body.annotations[0].category_id, 1);
+    assert_eq!(body.annotations[0].bbox, vec![100.0, 50.0, 200.0, 150.0]);
+    assert_eq!(body.annotations[0].area, 30000);
+    assert_eq!(body.annotations[0].iscrowd, 0);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_export_project_coco_latest_annotations_only() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+    let auth_storage = AuthStorage::new(pool.clone());
+
+    // Create test data
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+    let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, Some("human"), Some("#FF0000"), Some(1)).await.unwrap();
+    let task = crate::tasks::create_task_in_db(&pool, project.id, "image1.jpg", Some("https://example.com/image1.jpg")).await.unwrap();
+    
+    // Create first annotation (older)
+    let bbox1 = crate::annotations::BoundingBox {
+        category_id: category.id,
+        bbox: vec![100.0, 50.0, 200.0, 150.0],
+        area: Some(30000.0),
+        iscrowd: Some(false),
+        is_prediction: None,
+        confidence: None,
+        attributes: None,
+        rotation: None,
+        frame_index: None,
+        track_id: None,
+        is_interpolated: None,
+        mask: None,
+    };
+    crate::annotations::create_annotation_in_db(&pool, task.id, &[bbox1], &serde_json::json!({"version": "old"}), user.id).await.unwrap();
+
+    // Wait a moment to ensure different timestamps
+    tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+    // Create second annotation (newer) - this should be the one exported
+    let bbox2 = crate::annotations::BoundingBox {
+        category_id: category.id,
+        bbox: vec![150.0, 75.0, 250.0, 175.0],
+        area: Some(43750.0),
+        iscrowd: Some(false),
+        is_prediction: None,
+        confidence: None,
+        attributes: None,
+        rotation: None,
+        frame_index: None,
+        track_id: None,
+        is_interpolated: None,
+        mask: None,
+    };
+    crate::annotations::create_annotation_in_db(&pool, task.id, &[bbox2], &serde_json::json!({"version": "new"}), user.id).await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/export/coco", web::get().to(export_project_coco))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/export/coco", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let body: types::CocoExport = test::read_body_json(resp).await;
+    
+    // Should have 1 image and only 1 annotation (the latest one)
+    assert_eq!(body.images.len(), 1);
+    assert_eq!(body.annotations.len(), 1);
+    assert_eq!(body.categories.len(), 1);
+
+    // Check that the exported annotation is the latest one (bbox2 coordinates)
+    assert_eq!(body.annotations[0].bbox, vec![150.0, 75.0, 250.0, 175.0]);
+    assert_eq!(body.annotations[0].area, 43750);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_export_project_coco_with_metadata() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+    let auth_storage = AuthStorage::new(pool.clone());
+
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+    let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, Some("human"), Some("#FF0000"), Some(1)).await.unwrap();
+    let task = crate::tasks::create_task_in_db(&pool, project.id, "image1.jpg", Some("https://example.com/image1.jpg")).await.unwrap();
+    let bbox = crate::annotations::BoundingBox {
+        category_id: category.id,
+        bbox: vec![100.0, 50.0, 200.0, 150.0],
+        area: Some(30000.0),
+        iscrowd: Some(false),
+        is_prediction: None,
+        confidence: None,
+        attributes: None,
+        rotation: None,
+        frame_index: None,
+        track_id: None,
+        is_interpolated: None,
+        mask: None,
+    };
+    let saved = crate::annotations::create_annotation_in_db(&pool, task.id, &[bbox], &serde_json::json!({}), user.id).await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/export/coco", web::get().to(export_project_coco))
+    ).await;
+
+    // Plain exports stay free of the extension fields
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/export/coco", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(body["info"].get("x-fasttag").is_none());
+    assert!(body["annotations"][0].get("x-fasttag").is_none());
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/export/coco?include_metadata=true", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["info"]["x-fasttag"]["project_id"], project.id.to_string());
+
+    let extension = &body["annotations"][0]["x-fasttag"];
+    assert_eq!(extension["task_id"], task.id.to_string());
+    assert_eq!(extension["annotation_id"], saved[0].annotation_id.to_string());
+    assert_eq!(extension["annotated_by"], user.email);
+    assert!(extension["annotated_at"].is_string());
+    assert_eq!(extension["task_status"], "pending");
+    assert!(extension["review_requested_at"].is_null());
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_export_project_coco_filtered_by_split() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+    let auth_storage = AuthStorage::new(pool.clone());
+
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, user.id).await.unwrap();
+    let train = crate::tasks::create_task_in_db(&pool, project.id, "train.jpg", Some("https://example.com/train.jpg")).await.unwrap();
+    crate::tasks::create_task_in_db(&pool, project.id, "val.jpg", Some("https://example.com/val.jpg")).await.unwrap();
+    crate::tasks::create_task_in_db(&pool, project.id, "unsplit.jpg", Some("https://example.com/unsplit.jpg")).await.unwrap();
+    sqlx::query("UPDATE tasks SET split = CASE name WHEN 'train.jpg' THEN 'train' WHEN 'val.jpg' THEN 'val' END WHERE project_id = $1")
+        .bind(project.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/export/coco", web::get().to(export_project_coco))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/export/coco?splits=train", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let images = body["images"].as_array().unwrap();
+    assert_eq!(images.len(), 1);
+    assert_eq!(images[0]["coco_url"], train.resource_url.unwrap());
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/export/coco?splits=train,val", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["images"].as_array().unwrap().len(), 2);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/export/coco?splits=holdout", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_export_project_coco_unauthorized() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let auth_storage = AuthStorage::new(pool.clone());
+
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/export/coco", web::get().to(export_project_coco))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/export/coco", project.id))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_export_project_coco_bundle_with_images() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+    let auth_storage = AuthStorage::new(pool.clone());
+
+    // Create a project backed by local storage containing one image
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    std::fs::write(temp_dir.path().join("image1.png"), b"fake image bytes").expect("Failed to write test image");
+    let storage_config = serde_json::json!({
+        "type": "local",
+        "base_path": temp_dir.path().to_str().unwrap()
+    });
+    let project = crate::projects::create_project_in_db(&pool, "Bundle Project", None, Some(&storage_config), user.id).await.unwrap();
+    crate::tasks::create_task_in_db(&pool, project.id, "image1.png", Some("storage://image1.png")).await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/export/coco", web::get().to(export_project_coco))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/export/coco?include_images=true", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let headers = resp.headers();
+    assert!(headers.get("Content-Type").unwrap().to_str().unwrap().contains("application/zip"));
+    assert!(headers.get("Content-Disposition").unwrap().to_str().unwrap().contains(".zip"));
+
+    let body = test::read_body(resp).await;
+    assert!(body.starts_with(b"PK"));
+    assert!(body.windows(b"images/image1.png".len()).any(|w| w == b"images/image1.png"));
+    assert!(body.windows(b"fake image bytes".len()).any(|w| w == b"fake image bytes"));
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_export_project_coco_delivered_to_storage() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+    let auth_storage = AuthStorage::new(pool.clone());
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    std::fs::write(temp_dir.path().join("image1.png"), b"fake image bytes").expect("Failed to write test image");
+    let storage_config = serde_json::json!({
+        "type": "local",
+        "base_path": temp_dir.path().to_str().unwrap()
+    });
+    let project = crate::projects::create_project_in_db(&pool, "Delivery Project", None, Some(&storage_config), user.id).await.unwrap();
+    crate::tasks::create_task_in_db(&pool, project.id, "image1.png", Some("storage://image1.png")).await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/export/coco", web::post().to(deliver_project_coco))
+    ).await;
+    let deliver = |body: serde_json::Value| test::TestRequest::post()
+        .uri(&format!("/projects/{}/export/coco", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(body)
+        .to_request();
+
+    let req = deliver(serde_json::json!({ "destination": "exports/weekly" }));
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let delivery: crate::export_delivery::ExportDelivery = test::read_body_json(resp).await;
+    assert!(delivery.key.starts_with("exports/weekly/delivery_project_coco_export_"));
+    assert!(delivery.key.ends_with(".json"));
+    assert_eq!(delivery.content_type, "application/json");
+
+    let written = std::fs::read(temp_dir.path().join(&delivery.key)).expect("Export was not written");
+    assert_eq!(written.len() as u64, delivery.size_bytes);
+    let coco: serde_json::Value = serde_json::from_slice(&written).unwrap();
+    assert_eq!(coco["images"].as_array().unwrap().len(), 1);
+
+    let req = deliver(serde_json::json!({ "include_images": true, "destination": "exports" }));
+    let delivery: crate::export_delivery::ExportDelivery = test::call_and_read_body_json(&app, req).await;
+    assert!(delivery.key.ends_with(".zip"));
+    let archive = std::fs::read(temp_dir.path().join(&delivery.key)).expect("Bundle was not written");
+    assert!(archive.starts_with(b"PK"));
+
+    // Local storage has no other buckets to write to
+    let req = deliver(serde_json::json!({ "destination": "exports", "destination_bucket": "datasets" }));
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    let req = deliver(serde_json::json!({ "destination": "../outside" }));
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_members_cannot_deliver_exports() {
+    let pool = test_utils::setup_test_db().await;
+    let owner = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let storage_config = serde_json::json!({
+        "type": "local",
+        "base_path": temp_dir.path().to_str().unwrap()
+    });
+    let project = crate::projects::create_project_in_db(&pool, "Delivery Project", None, Some(&storage_config), owner.id).await.unwrap();
+
+    let member_id = test_utils::create_test_user(&pool).await;
+    sqlx::query("INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, 'member')")
+        .bind(project.id)
+        .bind(member_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let member_token = JwtManager::new(&oauth_config.jwt_secret)
+        .generate_token(&member_id.to_string(), &format!("test-{}@example.com", member_id), "Test User")
+        .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .route("/projects/{project_id}/export/coco", web::get().to(export_project_coco))
+            .route("/projects/{project_id}/export/coco", web::post().to(deliver_project_coco))
+    ).await;
+
+    // Members still download the export
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/export/coco", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", member_token)))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/projects/{}/export/coco", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", member_token)))
+        .set_json(serde_json::json!({ "destination": "exports" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+    assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_member_exports_leave_out_blind_and_gold_tasks() {
+    let pool = test_utils::setup_test_db().await;
+    let owner = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let owner_token = create_auth_token(&oauth_config, &owner);
+
+    let project = crate::projects::create_project_in_db(&pool, "Blind Project", None, None, owner.id).await.unwrap();
+    let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, None, None, None).await.unwrap();
+    let open_task = crate::tasks::create_task_in_db(&pool, project.id, "open.jpg", None).await.unwrap();
+    let assigned_task = crate::tasks::create_task_in_db(&pool, project.id, "assigned.jpg", None).await.unwrap();
+    let gold_task = crate::tasks::create_task_in_db(&pool, project.id, "gold.jpg", None).await.unwrap();
+
+    let member_id = test_utils::create_test_user(&pool).await;
+    sqlx::query("INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, 'member')")
+        .bind(project.id)
+        .bind(member_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let member_token = JwtManager::new(&oauth_config.jwt_secret)
+        .generate_token(&member_id.to_string(), &format!("test-{}@example.com", member_id), "Test User")
+        .unwrap();
+
+    let bbox = crate::annotations::BoundingBox {
+        category_id: category.id,
+        bbox: vec![10.0, 10.0, 20.0, 20.0],
+        area: None,
+        iscrowd: None,
+        is_prediction: None,
+        confidence: None,
+        attributes: None,
+        rotation: None,
+        frame_index: None,
+        track_id: None,
+        is_interpolated: None,
+        mask: None,
+    };
+    for task in [&open_task, &assigned_task, &gold_task] {
+        crate::annotations::create_annotation_in_db(&pool, task.id, std::slice::from_ref(&bbox), &serde_json::json!({}), owner.id).await.unwrap();
+    }
+    sqlx::query("INSERT INTO task_assignments (task_id, user_id) VALUES ($1, $2)")
+        .bind(assigned_task.id)
+        .bind(owner.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE tasks SET gold_annotation_id = (SELECT id FROM annotations WHERE task_id = $1) WHERE id = $1")
+        .bind(gold_task.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .route("/projects/{project_id}/export/coco", web::get().to(export_project_coco))
+    ).await;
+
+    let export = |token: &str| test::TestRequest::get()
+        .uri(&format!("/projects/{}/export/coco", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+
+    let body: types::CocoExport = test::read_body_json(test::call_service(&app, export(&member_token)).await).await;
+    let names: Vec<&str> = body.images.iter().map(|image| image.file_name.as_str()).collect();
+    assert_eq!(names, vec!["open.jpg"]);
+    assert_eq!(body.annotations.len(), 1);
+
+    let body: types::CocoExport = test::read_body_json(test::call_service(&app, export(&owner_token)).await).await;
+    assert_eq!(body.images.len(), 3);
+    assert_eq!(body.annotations.len(), 3);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_export_jobs_are_recorded_and_listed() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let storage_config = serde_json::json!({
+        "type": "local",
+        "base_path": temp_dir.path().to_str().unwrap()
+    });
+    let project = crate::projects::create_project_in_db(&pool, "Jobs Project", None, Some(&storage_config), user.id).await.unwrap();
+    crate::tasks::create_task_in_db(&pool, project.id, "image1.png", Some("storage://image1.png")).await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .route("/projects/{project_id}/export/coco", web::get().to(export_project_coco))
+            .route("/projects/{project_id}/export/coco", web::post().to(deliver_project_coco))
+            .route("/projects/{project_id}/exports", web::get().to(crate::export_jobs::list_project_exports))
+    ).await;
+    let deliver = |body: serde_json::Value| test::TestRequest::post()
+        .uri(&format!("/projects/{}/export/coco", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(body)
+        .to_request();
+
+    // Downloads aren't jobs
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/export/coco", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    // Failed deliveries are kept with their error
+    let req = deliver(serde_json::json!({ "destination": "exports", "destination_bucket": "datasets" }));
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    let req = deliver(serde_json::json!({ "destination": "exports", "background": true }));
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 202);
+    let job: crate::export_jobs::ExportJob = test::read_body_json(resp).await;
+    assert_eq!(job.status, "running");
+
+    let mut exports = serde_json::Value::Null;
+    for _ in 0..50 {
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/exports", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        exports = test::call_and_read_body_json(&app, req).await;
+        if exports["exports"][0]["status"] != "running" {
+            break;
+        }
+        actix_web::rt::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+
+    let exports = exports["exports"].as_array().unwrap();
+    assert_eq!(exports.len(), 2);
+    assert_eq!(exports[0]["id"], job.id.to_string());
+    assert_eq!(exports[0]["status"], "completed");
+    let key = exports[0]["storage_key"].as_str().unwrap();
+    assert!(key.starts_with("exports/jobs_project_coco_export_"));
+    let written = std::fs::metadata(temp_dir.path().join(key)).expect("Export was not written");
+    assert_eq!(exports[0]["size_bytes"], written.len());
+    assert_eq!(exports[1]["status"], "failed");
+    assert!(exports[1]["error"].as_str().is_some_and(|error| !error.is_empty()));
+}
+
+#[actix_web::test]
+async fn test_assign_archive_paths_deduplicates_names() {
+    let make_image = |id: i64, url: Option<&str>| types::CocoImage {
+        id,
+        width: 10,
+        height: 10,
+        file_name: "image.png".to_string(),
+        license: 1,
+        flickr_url: None,
+        coco_url: url.map(|u| u.to_string()),
+        date_captured: String::new(),
+    };
+
+    let mut images = vec![
+        make_image(1, Some("storage://a/image.png")),
+        make_image(2, Some("storage://b/image.png")),
+        make_image(3, Some("https://example.com/image.png")),
+    ];
+
+    let entries = bundle::assign_archive_paths(&mut images);
+
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].storage_key, "a/image.png");
+    assert_eq!(entries[0].archive_path, "images/image.png");
+    assert_eq!(entries[1].storage_key, "b/image.png");
+    assert_eq!(entries[1].archive_path, "images/2_image.png");
+    assert_eq!(images[1].file_name, "2_image.png");
+    assert_eq!(images[2].file_name, "image.png");
+}
+
+#[actix_web::test]
+async fn test_assign_archive_paths_keeps_entries_in_the_images_directory() {
+    let make_image = |id: i64, file_name: &str| types::CocoImage {
+        id,
+        width: 10,
+        height: 10,
+        file_name: file_name.to_string(),
+        license: 1,
+        flickr_url: None,
+        coco_url: Some(format!("storage://imports/{}.png", id)),
+        date_captured: String::new(),
+    };
+
+    let mut images = vec![
+        make_image(1, "../../etc/passwd"),
+        make_image(2, "C:\\images\\passwd"),
+        make_image(3, "/tmp/.."),
+        make_image(4, "nested/dir/photo.png"),
+    ];
+
+    let entries = bundle::assign_archive_paths(&mut images);
+
+    let paths: Vec<_> = entries.iter().map(|entry| entry.archive_path.as_str()).collect();
+    assert_eq!(paths, ["images/passwd", "images/2_passwd", "images/image_3", "images/photo.png"]);
+    assert_eq!(images[0].file_name, "passwd");
+    assert_eq!(images[1].file_name, "2_passwd");
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_import_project_coco_success() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+    let auth_storage = AuthStorage::new(pool.clone());
+
+    // Create a test project
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+
+    // Create test COCO data
+    let coco_data = serde_json::json!({
+        "info": {
+            "year": 2024,
+            "version": "1.0",
+            "description": "Test dataset",
+            "contributor": "test@example.com",
+            "url": "https://example.com",
+            "date_created": "2024-01-01T00:00:00Z"
+        },
+        "licenses": [
+            {
+                "id": 1,
+                "name": "Test License",
+                "url": "https://example.com/license"
+            }
+        ],
+        "images": [
+            {
+                "id": 1,
+                "width": 640,
+                "height": 480,
+                "file_name": "test_image.jpg",
+                "license": 1,
+                "flickr_url": null,
+                "coco_url": "https://example.com/test_image.jpg",
+                "date_captured": "2024-01-01T00:00:00Z"
+            }
+        ],
+        "annotations": [
+            {
+                "id": 1,
+                "image_id": 1,
+                "category_id": 1,
+                "segmentation": [],
+                "area": 30000,
+                "bbox": [100.0, 50.0, 200.0, 150.0],
+                "iscrowd": 0
+            }
+        ],
+        "categories": [
+            {
+                "id": 1,
+                "name": "person",
+                "supercategory": "human"
+            }
+        ]
+    });
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/import/coco", web::post().to(import_project_coco))
+    ).await;
+
+    // Create multipart form data
+    let json_str = serde_json::to_string(&coco_data).unwrap();
+    let boundary = "----formdata-test-boundary";
+    let body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"test.json\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+        boundary, json_str, boundary
+    );
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/projects/{}/import/coco", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+        .set_payload(body)
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let body: types::ImportResult = test::read_body_json(resp).await;
+    assert!(body.success);
+    assert_eq!(body.stats.categories_created, 1);
+    assert_eq!(body.stats.tasks_created, 1);
+    assert_eq!(body.stats.annotations_created, 1);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_import_project_coco_dry_run() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+    let auth_storage = AuthStorage::new(pool.clone());
+
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+    crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "dog", None, None, None, Some(1)).await.unwrap();
+    crate::tasks::create_task_in_db(&pool, project.id, "a.jpg", None).await.unwrap();
+
+    // An unknown category, a missing image, a malformed box and a reused annotation ID
+    let coco_data = serde_json::json!({
+        "images": [
+            {"id": 1, "width": 640, "height": 480, "file_name": "a.jpg", "license": 1, "flickr_url": null, "coco_url": null, "date_captured": ""},
+            {"id": 2, "width": 640, "height": 480, "file_name": "b.jpg", "license": 1, "flickr_url": null, "coco_url": null, "date_captured": ""}
+        ],
+        "annotations": [
+            {"id": 1, "image_id": 1, "category_id": 1, "segmentation": [], "area": 100, "bbox": [0.0, 0.0, 10.0, 10.0], "iscrowd": 0},
+            {"id": 1, "image_id": 3, "category_id": 1, "segmentation": [], "area": 100, "bbox": [0.0, 0.0, 10.0, 10.0], "iscrowd": 0},
+            {"id": 2, "image_id": 2, "category_id": 5, "segmentation": [], "area": 100, "bbox": [0.0, 0.0, -10.0, 10.0], "iscrowd": 0}
+        ],
+        "categories": [
+            {"id": 1, "name": "person", "supercategory": "human"}
+        ]
+    });
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/import/coco", web::post().to(import_project_coco))
+    ).await;
+
+    let json_str = serde_json::to_string(&coco_data).unwrap();
+    let boundary = "----formdata-test-boundary";
+    let body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"test.json\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+        boundary, json_str, boundary
+    );
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/projects/{}/import/coco?dry_run=true", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+        .set_payload(body)
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let body: types::ImportDryRun = test::read_body_json(resp).await;
+    assert!(!body.valid);
+    assert_eq!(body.validation.issues.len(), 4);
+    assert_eq!(body.categories_to_create, 1);
+    assert_eq!(body.tasks_to_create, 1);
+    assert_eq!(body.tasks_to_update, 1);
+    assert_eq!(body.tasks_to_annotate, 2);
+    assert_eq!(body.images_to_fetch, 0);
+    // "dog" already has COCO ID 1
+    assert_eq!(body.coco_id_conflicts.len(), 1);
+
+    // Nothing was written
+    let categories: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM image_annotation_categories WHERE project_id = $1")
+        .bind(project.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let tasks: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tasks WHERE project_id = $1")
+        .bind(project.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!((categories, tasks), (1, 1));
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_import_project_coco_invalid_json() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+    let auth_storage = AuthStorage::new(pool.clone());
+
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/import/coco", web::post().to(import_project_coco))
+    ).await;
+
+    let boundary = "----formdata-test-boundary";
+    let body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"test.json\"\r\nContent-Type: application/json\r\n\r\n{{\r\n--{}--\r\n",
+        boundary, boundary
+    );
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/projects/{}/import/coco", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+        .set_payload(body)
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_import_project_coco_unauthorized() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let auth_storage = AuthStorage::new(pool.clone());
+
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/import/coco", web::post().to(import_project_coco))
+    ).await;
+
+    let coco_data = serde_json::json!({
+        "images": [],
+        "annotations": [],
+        "categories": []
+    });
+
+    let json_str = serde_json::to_string(&coco_data).unwrap();
+    let boundary = "----formdata-test-boundary";
+    let body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"test.json\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+        boundary, json_str, boundary
+    );
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/projects/{}/import/coco", project.id))
+        .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+        .set_payload(body)
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
\ No newline at end of file
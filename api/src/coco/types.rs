@@ -1,86 +1,116 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-// COCO format data structures
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CocoExport {
-    pub info: CocoInfo,
-    pub licenses: Vec<CocoLicense>,
-    pub images: Vec<CocoImage>,
-    pub annotations: Vec<CocoAnnotation>,
-    pub categories: Vec<CocoCategory>,
-}
+// COCO format data structures, shared with the CLI and the Python bindings
+pub use fast_tag_formats::coco::{CocoExport, CocoInfo, CocoLicense, CocoImage, CocoAnnotation, CocoCategory, CocoImport, CocoRle, CocoSegmentation, FastTagAnnotation, FastTagInfo, RleCounts, ValidationReport};
+use fast_tag_formats::coco::{decode_rle_counts, encode_rle_counts};
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CocoInfo {
-    pub year: i32,
-    pub version: String,
-    pub description: String,
-    pub contributor: String,
-    pub url: String,
-    pub date_created: String,
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportResult {
+    pub success: bool,
+    pub message: String,
+    pub stats: ImportStats,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CocoLicense {
-    pub id: i32,
-    pub name: String,
-    pub url: String,
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportStats {
+    pub categories_created: usize,
+    pub categories_updated: usize,
+    pub tasks_created: usize,
+    pub annotations_created: usize,
+    /// Images downloaded into the project storage by a COCO import with `fetch_images`
+    #[serde(default)]
+    pub images_fetched: usize,
+    pub errors: Vec<String>,
 }
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CocoImage {
-    pub id: i64,
-    pub width: i32,
-    pub height: i32,
-    pub file_name: String,
-    pub license: i32,
-    pub flickr_url: Option<String>,
-    pub coco_url: Option<String>,
-    pub date_captured: String,
+/// What a COCO import would do, answered by a dry run without writing anything
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportDryRun {
+    /// Whether the file passes validation, the import is refused otherwise
+    pub valid: bool,
+    pub validation: ValidationReport,
+    pub categories_to_create: usize,
+    /// Categories of the file whose name the project already has
+    pub categories_to_update: usize,
+    pub tasks_to_create: usize,
+    /// Images of the file whose name matches an existing task
+    pub tasks_to_update: usize,
+    /// Tasks that would get an annotation with the boxes of their image
+    pub tasks_to_annotate: usize,
+    /// Images with a URL to download them from, fetched into the project storage when the import
+    /// is run with `fetch_images`
+    pub images_to_fetch: usize,
+    /// Categories that would end up sharing a COCO ID with another one of the project
+    pub coco_id_conflicts: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CocoAnnotation {
-    pub id: i64,
-    pub image_id: i64,
-    pub category_id: i32,
-    pub segmentation: Vec<Vec<f64>>,
-    pub area: i32,
-    pub bbox: Vec<f64>,
-    pub iscrowd: i32,
+/// Run lengths of the mask, checked to cover its image exactly
+pub fn rle_runs(rle: &CocoRle) -> Result<Vec<u32>, String> {
+    let runs = match &rle.counts {
+        RleCounts::Uncompressed(counts) => counts.clone(),
+        RleCounts::Compressed(counts) => decode_rle_counts(counts)?,
+    };
+
+    let [height, width] = rle.size;
+    let pixels: u64 = runs.iter().map(|&run| run as u64).sum();
+    if pixels != height as u64 * width as u64 {
+        return Err(format!(
+            "RLE runs cover {} pixels but the {}x{} mask has {}",
+            pixels, width, height, height as u64 * width as u64
+        ));
+    }
+    Ok(runs)
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CocoCategory {
-    pub id: i32,
-    pub name: String,
-    pub supercategory: String,
+/// Checks that the mask is as large as the image it belongs to
+pub fn check_mask_size(rle: &CocoRle, width: i32, height: i32) -> Result<(), String> {
+    let [mask_height, mask_width] = rle.size;
+    if i64::from(mask_width) != i64::from(width) || i64::from(mask_height) != i64::from(height) {
+        return Err(format!(
+            "The {}x{} mask doesn't match the {}x{} image",
+            mask_width, mask_height, width, height
+        ));
+    }
+    Ok(())
 }
 
-// Import specific structures
-#[derive(Debug, Deserialize)]
-pub struct CocoImport {
-    #[allow(dead_code)]
-    pub info: Option<CocoInfo>,
-    #[allow(dead_code)]
-    pub licenses: Option<Vec<CocoLicense>>,
-    pub images: Vec<CocoImage>,
-    pub annotations: Vec<CocoAnnotation>,
-    pub categories: Vec<CocoCategory>,
+/// The mask with its runs compressed, the form masks are stored in
+pub fn compress_rle(rle: &CocoRle) -> Result<CocoRle, String> {
+    Ok(CocoRle {
+        size: rle.size,
+        counts: RleCounts::Compressed(encode_rle_counts(&rle_runs(rle)?)),
+    })
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ImportResult {
-    pub success: bool,
-    pub message: String,
-    pub stats: ImportStats,
+/// Pixels of the object, which every second run covers
+pub fn rle_area(rle: &CocoRle) -> Result<u64, String> {
+    Ok(rle_runs(rle)?.iter().skip(1).step_by(2).map(|&run| run as u64).sum())
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ImportStats {
-    pub categories_created: usize,
-    pub categories_updated: usize,
-    pub tasks_created: usize,
-    pub annotations_created: usize,
-    pub errors: Vec<String>,
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressed_and_uncompressed_masks_agree() {
+        let uncompressed = CocoRle { size: [4, 4], counts: RleCounts::Uncompressed(vec![5, 2, 2, 2, 5]) };
+        let compressed = compress_rle(&uncompressed).unwrap();
+        assert_eq!(compressed.counts, RleCounts::Compressed("52203".to_string()));
+        assert_eq!(rle_runs(&compressed).unwrap(), vec![5, 2, 2, 2, 5]);
+        assert_eq!(rle_area(&uncompressed).unwrap(), 4);
+        assert_eq!(rle_area(&compressed).unwrap(), 4);
+
+        // Runs have to cover the whole image
+        let short = CocoRle { size: [4, 4], counts: RleCounts::Uncompressed(vec![5, 2]) };
+        assert!(compress_rle(&short).is_err());
+    }
+
+    #[test]
+    fn test_mask_size_has_to_match_the_image() {
+        // `size` is `[height, width]`
+        let mask = |size| CocoRle { size, counts: RleCounts::Uncompressed(vec![24]) };
+        assert!(check_mask_size(&mask([6, 4]), 4, 6).is_ok());
+        assert!(check_mask_size(&mask([4, 6]), 4, 6).is_err());
+        assert!(check_mask_size(&mask([6, 4]), 4, 7).is_err());
+    }
+}
@@ -0,0 +1,867 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::auth::{JwtManager, Claims};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct Comment {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub author_id: Uuid,
+    pub author_name: String,
+    pub author_email: String,
+    pub body: String,
+    pub anchor_bbox: Option<Vec<f64>>, // [x, y, width, height]
+    pub mentions: Vec<Uuid>,
+    pub resolved: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateCommentRequest {
+    pub body: String,
+    pub parent_id: Option<Uuid>,
+    pub anchor_bbox: Option<Vec<f64>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateCommentRequest {
+    pub body: Option<String>,
+    pub resolved: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MentionsQuery {
+    /// Only return comments that are not resolved yet
+    pub unresolved: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CommentsListResponse {
+    pub comments: Vec<Comment>,
+}
+
+const COMMENT_COLUMNS: &str = r#"
+    c.id, c.task_id, c.parent_id, c.author_id, u.name AS author_name, u.email AS author_email,
+    c.body, c.anchor_bbox, c.mentions, c.resolved, c.created_at, c.updated_at
+"#;
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/tasks/{task_id}/comments",
+    tag = "comments",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    request_body = CreateCommentRequest,
+    responses(
+        (status = 201, body = Comment),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn create_comment(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    payload: web::Json<CreateCommentRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    let payload = payload.into_inner();
+    let body = payload.body.trim();
+    if body.is_empty() {
+        return HttpResponse::BadRequest().json("Comment body cannot be empty");
+    }
+
+    if let Some(anchor) = &payload.anchor_bbox {
+        if let Err(message) = validate_anchor_bbox(anchor) {
+            return HttpResponse::BadRequest().json(message);
+        }
+    }
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    // Verify task belongs to the project
+    if !task_belongs_to_project(&pool, task_id, project_id).await {
+        return HttpResponse::BadRequest().json("Task does not belong to the specified project");
+    }
+
+    // Replies must stay in the same thread
+    if let Some(parent_id) = payload.parent_id {
+        if !comment_belongs_to_task(&pool, parent_id, task_id).await {
+            return HttpResponse::BadRequest().json("Parent comment does not belong to the specified task");
+        }
+    }
+
+    let mentions = match resolve_mentions(&pool, project_id, &parse_mentions(body)).await {
+        Ok(mentions) => mentions,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to resolve mentions"),
+    };
+
+    match create_comment_in_db(&pool, task_id, user_id, body, payload.parent_id, payload.anchor_bbox.as_deref(), &mentions).await {
+        Ok(comment) => HttpResponse::Created().json(comment),
+        Err(err) => {
+            eprintln!("Comment create error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to create comment")
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/tasks/{task_id}/comments",
+    tag = "comments",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    responses(
+        (status = 200, body = CommentsListResponse),
+        (status = 400, description = "Task doesn't belong to the project", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn list_comments(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    // Verify task belongs to the project
+    if !task_belongs_to_project(&pool, task_id, project_id).await {
+        return HttpResponse::BadRequest().json("Task does not belong to the specified project");
+    }
+
+    match get_task_comments(&pool, task_id).await {
+        Ok(comments) => HttpResponse::Ok().json(CommentsListResponse { comments }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch comments"),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/projects/{project_id}/tasks/{task_id}/comments/{comment_id}",
+    tag = "comments",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        ("comment_id" = Uuid, Path, description = "Comment ID"),
+    ),
+    request_body = UpdateCommentRequest,
+    responses(
+        (status = 200, body = Comment),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or comment not found, or the comment isn't the user's", body = String),
+    ),
+)]
+pub async fn update_comment(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    payload: web::Json<UpdateCommentRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str, comment_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    let comment_id = match Uuid::parse_str(&comment_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid comment ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    // Verify task belongs to the project
+    if !task_belongs_to_project(&pool, task_id, project_id).await {
+        return HttpResponse::BadRequest().json("Task does not belong to the specified project");
+    }
+
+    let comment = match get_comment_by_id(&pool, comment_id, task_id).await {
+        Ok(Some(comment)) => comment,
+        Ok(None) => return HttpResponse::NotFound().json("Comment not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch comment"),
+    };
+
+    let payload = payload.into_inner();
+
+    // Only the author may edit the text; any member may resolve or reopen a thread
+    let mut body = None;
+    let mut mentions = None;
+    if let Some(new_body) = payload.body.as_deref().map(str::trim) {
+        if comment.author_id != user_id {
+            return HttpResponse::NotFound().json("Comment not found or permission denied");
+        }
+        if new_body.is_empty() {
+            return HttpResponse::BadRequest().json("Comment body cannot be empty");
+        }
+
+        match resolve_mentions(&pool, project_id, &parse_mentions(new_body)).await {
+            Ok(resolved) => mentions = Some(resolved),
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to resolve mentions"),
+        }
+        body = Some(new_body);
+    }
+
+    match update_comment_in_db(&pool, comment_id, body, mentions.as_deref(), payload.resolved).await {
+        Ok(()) => match get_comment_by_id(&pool, comment_id, task_id).await {
+            Ok(Some(comment)) => HttpResponse::Ok().json(comment),
+            Ok(None) => HttpResponse::NotFound().json("Comment not found"),
+            Err(_) => HttpResponse::InternalServerError().json("Failed to fetch comment"),
+        },
+        Err(_) => HttpResponse::InternalServerError().json("Failed to update comment"),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/projects/{project_id}/tasks/{task_id}/comments/{comment_id}",
+    tag = "comments",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        ("comment_id" = Uuid, Path, description = "Comment ID"),
+    ),
+    responses(
+        (status = 204, description = "Comment deleted"),
+        (status = 400, description = "Task doesn't belong to the project", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or comment not found, or the comment isn't the user's", body = String),
+    ),
+)]
+pub async fn delete_comment(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str, comment_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    let comment_id = match Uuid::parse_str(&comment_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid comment ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    // Verify task belongs to the project
+    if !task_belongs_to_project(&pool, task_id, project_id).await {
+        return HttpResponse::BadRequest().json("Task does not belong to the specified project");
+    }
+
+    let comment = match get_comment_by_id(&pool, comment_id, task_id).await {
+        Ok(Some(comment)) => comment,
+        Ok(None) => return HttpResponse::NotFound().json("Comment not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch comment"),
+    };
+
+    // Authors can delete their own comments, owners can moderate any of them
+    if comment.author_id != user_id {
+        match user_is_project_owner(&pool, project_id, user_id).await {
+            Ok(true) => {}
+            Ok(false) => return HttpResponse::NotFound().json("Comment not found or permission denied"),
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to check permissions"),
+        }
+    }
+
+    match delete_comment_from_db(&pool, comment_id, task_id).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => HttpResponse::NotFound().json("Comment not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to delete comment"),
+    }
+}
+
+/// Lists the comments of a project that mention the current user, newest first.
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/mentions",
+    tag = "comments",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        MentionsQuery,
+    ),
+    responses(
+        (status = 200, description = "Comments of the project that mention the user", body = CommentsListResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn list_my_mentions(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<MentionsQuery>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match get_mentions_for_user(&pool, project_id, user_id, query.unresolved.unwrap_or(false)).await {
+        Ok(comments) => HttpResponse::Ok().json(CommentsListResponse { comments }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch mentions"),
+    }
+}
+
+fn validate_anchor_bbox(anchor: &[f64]) -> Result<(), &'static str> {
+    if anchor.len() != 4 {
+        return Err("anchor_bbox must have exactly 4 values [x, y, width, height]");
+    }
+
+    if anchor.iter().any(|value| !value.is_finite() || *value < 0.0) {
+        return Err("anchor_bbox values must be non-negative");
+    }
+
+    Ok(())
+}
+
+/// Extracts the lowercased emails mentioned as `@alice@example.com` in a comment body.
+pub fn parse_mentions(body: &str) -> Vec<String> {
+    let mut emails: Vec<String> = Vec::new();
+
+    for word in body.split_whitespace() {
+        let Some(candidate) = word.strip_prefix('@') else {
+            continue;
+        };
+
+        let email = candidate
+            .trim_end_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+
+        let valid = match email.split_once('@') {
+            Some((local, domain)) => !local.is_empty() && domain.contains('.'),
+            None => false,
+        };
+
+        if valid && !emails.contains(&email) {
+            emails.push(email);
+        }
+    }
+
+    emails
+}
+
+/// Resolves mentioned emails to users, ignoring anyone who is not a project member.
+async fn resolve_mentions(pool: &Pool<Postgres>, project_id: Uuid, emails: &[String]) -> Result<Vec<Uuid>, sqlx::Error> {
+    if emails.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT DISTINCT u.id FROM users u
+        INNER JOIN project_members pm ON pm.user_id = u.id
+        WHERE pm.project_id = $1 AND LOWER(u.email) = ANY($2)
+        "#
+    )
+    .bind(project_id)
+    .bind(emails)
+    .fetch_all(pool)
+    .await
+}
+
+async fn create_comment_in_db(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
+    author_id: Uuid,
+    body: &str,
+    parent_id: Option<Uuid>,
+    anchor_bbox: Option<&[f64]>,
+    mentions: &[Uuid],
+) -> Result<Comment, sqlx::Error> {
+    let comment_id = Uuid::new_v4();
+
+    sqlx::query(
+        r#"
+        INSERT INTO comments (id, task_id, parent_id, author_id, body, anchor_bbox, mentions, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, NOW(), NOW())
+        "#
+    )
+    .bind(comment_id)
+    .bind(task_id)
+    .bind(parent_id)
+    .bind(author_id)
+    .bind(body)
+    .bind(anchor_bbox)
+    .bind(mentions)
+    .execute(pool)
+    .await?;
+
+    get_comment_by_id(pool, comment_id, task_id)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)
+}
+
+async fn get_task_comments(pool: &Pool<Postgres>, task_id: Uuid) -> Result<Vec<Comment>, sqlx::Error> {
+    sqlx::query_as::<_, Comment>(&format!(
+        r#"
+        SELECT {}
+        FROM comments c
+        INNER JOIN users u ON c.author_id = u.id
+        WHERE c.task_id = $1
+        ORDER BY c.created_at ASC
+        "#,
+        COMMENT_COLUMNS
+    ))
+    .bind(task_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_comment_by_id(pool: &Pool<Postgres>, comment_id: Uuid, task_id: Uuid) -> Result<Option<Comment>, sqlx::Error> {
+    sqlx::query_as::<_, Comment>(&format!(
+        r#"
+        SELECT {}
+        FROM comments c
+        INNER JOIN users u ON c.author_id = u.id
+        WHERE c.id = $1 AND c.task_id = $2
+        "#,
+        COMMENT_COLUMNS
+    ))
+    .bind(comment_id)
+    .bind(task_id)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn get_mentions_for_user(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    user_id: Uuid,
+    unresolved_only: bool,
+) -> Result<Vec<Comment>, sqlx::Error> {
+    sqlx::query_as::<_, Comment>(&format!(
+        r#"
+        SELECT {}
+        FROM comments c
+        INNER JOIN users u ON c.author_id = u.id
+        INNER JOIN tasks t ON c.task_id = t.id
+        WHERE t.project_id = $1 AND $2 = ANY(c.mentions) AND (NOT $3 OR NOT c.resolved)
+        ORDER BY c.created_at DESC
+        "#,
+        COMMENT_COLUMNS
+    ))
+    .bind(project_id)
+    .bind(user_id)
+    .bind(unresolved_only)
+    .fetch_all(pool)
+    .await
+}
+
+async fn update_comment_in_db(
+    pool: &Pool<Postgres>,
+    comment_id: Uuid,
+    body: Option<&str>,
+    mentions: Option<&[Uuid]>,
+    resolved: Option<bool>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE comments
+        SET body = COALESCE($2, body),
+            mentions = COALESCE($3, mentions),
+            resolved = COALESCE($4, resolved),
+            updated_at = NOW()
+        WHERE id = $1
+        "#
+    )
+    .bind(comment_id)
+    .bind(body)
+    .bind(mentions)
+    .bind(resolved)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+async fn delete_comment_from_db(pool: &Pool<Postgres>, comment_id: Uuid, task_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM comments WHERE id = $1 AND task_id = $2")
+        .bind(comment_id)
+        .bind(task_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+async fn comment_belongs_to_task(pool: &Pool<Postgres>, comment_id: Uuid, task_id: Uuid) -> bool {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM comments WHERE id = $1 AND task_id = $2)"
+    )
+    .bind(comment_id)
+    .bind(task_id)
+    .fetch_one(pool)
+    .await
+    .unwrap_or(false)
+}
+
+async fn task_belongs_to_project(pool: &Pool<Postgres>, task_id: Uuid, project_id: Uuid) -> bool {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM tasks WHERE id = $1 AND project_id = $2)"
+    )
+    .bind(task_id)
+    .bind(project_id)
+    .fetch_one(pool)
+    .await
+    .unwrap_or(false)
+}
+
+async fn user_is_project_owner(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM project_members pm
+            INNER JOIN projects p ON pm.project_id = p.id
+            WHERE p.id = $1 AND pm.user_id = $2 AND (pm.role = 'owner' OR p.owner_id = $2)
+        )
+        "#
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    async fn add_project_member(pool: &sqlx::PgPool, project_id: Uuid, email: &str) -> User {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            INSERT INTO users (id, email, name, provider, provider_id)
+            VALUES ($1, $2, 'Reviewer', 'google', $3)
+            RETURNING id, email, name, avatar_url, provider, provider_id, created_at, updated_at
+            "#
+        )
+        .bind(Uuid::new_v4())
+        .bind(email)
+        .bind(Uuid::new_v4().to_string())
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+        sqlx::query("INSERT INTO project_members (id, project_id, user_id, role, joined_at) VALUES ($1, $2, $3, 'member', NOW())")
+            .bind(Uuid::new_v4())
+            .bind(project_id)
+            .bind(user.id)
+            .execute(pool)
+            .await
+            .unwrap();
+
+        user
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_comment_thread_with_mentions() {
+        let pool = test_utils::setup_test_db().await;
+        let owner = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let owner_token = create_auth_token(&oauth_config, &owner);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, owner.id).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "image1.jpg", None).await.unwrap();
+        let reviewer = add_project_member(&pool, project.id, "reviewer@example.com").await;
+        let reviewer_token = create_auth_token(&oauth_config, &reviewer);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/tasks/{task_id}/comments", web::post().to(create_comment))
+                .route("/projects/{project_id}/tasks/{task_id}/comments", web::get().to(list_comments))
+                .route("/projects/{project_id}/tasks/{task_id}/comments/{comment_id}", web::put().to(update_comment))
+                .route("/projects/{project_id}/mentions", web::get().to(list_my_mentions))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/tasks/{}/comments", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+            .set_json(serde_json::json!({
+                "body": "@Reviewer@example.com is this box too loose? @nobody@example.com",
+                "anchor_bbox": [10.0, 20.0, 30.0, 40.0]
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+
+        let comment: Comment = test::read_body_json(resp).await;
+        assert_eq!(comment.mentions, vec![reviewer.id]);
+        assert_eq!(comment.anchor_bbox, Some(vec![10.0, 20.0, 30.0, 40.0]));
+        assert_eq!(comment.author_id, owner.id);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/tasks/{}/comments", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", reviewer_token)))
+            .set_json(serde_json::json!({"body": "Fixed", "parent_id": comment.id}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+
+        // Only the author can edit the text
+        let req = test::TestRequest::put()
+            .uri(&format!("/projects/{}/tasks/{}/comments/{}", project.id, task.id, comment.id))
+            .insert_header(("Authorization", format!("Bearer {}", reviewer_token)))
+            .set_json(serde_json::json!({"body": "Rewritten"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+
+        // ... but anyone in the project can resolve the thread
+        let req = test::TestRequest::put()
+            .uri(&format!("/projects/{}/tasks/{}/comments/{}", project.id, task.id, comment.id))
+            .insert_header(("Authorization", format!("Bearer {}", reviewer_token)))
+            .set_json(serde_json::json!({"resolved": true}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let updated: Comment = test::read_body_json(resp).await;
+        assert!(updated.resolved);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/tasks/{}/comments", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let list: CommentsListResponse = test::read_body_json(resp).await;
+        assert_eq!(list.comments.len(), 2);
+        assert_eq!(list.comments[1].parent_id, Some(comment.id));
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/mentions", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", reviewer_token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let mentions: CommentsListResponse = test::read_body_json(resp).await;
+        assert_eq!(mentions.comments.len(), 1);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/mentions?unresolved=true", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", reviewer_token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let mentions: CommentsListResponse = test::read_body_json(resp).await;
+        assert!(mentions.comments.is_empty());
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_delete_comment_by_owner() {
+        let pool = test_utils::setup_test_db().await;
+        let owner = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let owner_token = create_auth_token(&oauth_config, &owner);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, owner.id).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "image1.jpg", None).await.unwrap();
+        let reviewer = add_project_member(&pool, project.id, "reviewer@example.com").await;
+        let comment = create_comment_in_db(&pool, task.id, reviewer.id, "Blurry image", None, None, &[]).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/tasks/{task_id}/comments/{comment_id}", web::delete().to(delete_comment))
+        ).await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/projects/{}/tasks/{}/comments/{}", project.id, task.id, comment.id))
+            .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 204);
+
+        assert!(get_task_comments(&pool, task.id).await.unwrap().is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_parse_mentions() {
+        assert_eq!(
+            parse_mentions("@Alice@Example.com, please check. cc @bob@example.org! @alice@example.com"),
+            vec!["alice@example.com".to_string(), "bob@example.org".to_string()]
+        );
+        assert!(parse_mentions("email me at alice@example.com or @here").is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::types::{ConsensusBox, PairAgreement};
+
+/// One box of an annotator's copy, COCO `[x, y, width, height]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgreementBox {
+    pub category_id: Option<Uuid>,
+    pub bbox: [f64; 4],
+}
+
+/// Per-category counters accumulated over all annotator pairs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CategoryCounts {
+    pub boxes: usize,
+    pub matched: usize,
+    pub iou_sum: f64,
+}
+
+pub fn iou(a: &[f64; 4], b: &[f64; 4]) -> f64 {
+    let x1 = a[0].max(b[0]);
+    let y1 = a[1].max(b[1]);
+    let x2 = (a[0] + a[2]).min(b[0] + b[2]);
+    let y2 = (a[1] + a[3]).min(b[1] + b[3]);
+
+    let intersection = (x2 - x1).max(0.0) * (y2 - y1).max(0.0);
+    let union = a[2] * a[3] + b[2] * b[3] - intersection;
+
+    if union <= 0.0 { 0.0 } else { intersection / union }
+}
+
+/// Greedily matches boxes of two annotators by IoU, ignoring labels.
+/// Returns `(index_a, index_b, iou)` triples, best overlaps first.
+pub fn match_boxes(a: &[AgreementBox], b: &[AgreementBox], iou_threshold: f64) -> Vec<(usize, usize, f64)> {
+    let mut candidates = Vec::new();
+    for (i, box_a) in a.iter().enumerate() {
+        for (j, box_b) in b.iter().enumerate() {
+            let overlap = iou(&box_a.bbox, &box_b.bbox);
+            if overlap >= iou_threshold {
+                candidates.push((i, j, overlap));
+            }
+        }
+    }
+    candidates.sort_by(|x, y| y.2.total_cmp(&x.2));
+
+    let mut used_a = vec![false; a.len()];
+    let mut used_b = vec![false; b.len()];
+    let mut matches = Vec::new();
+    for (i, j, overlap) in candidates {
+        if !used_a[i] && !used_b[j] {
+            used_a[i] = true;
+            used_b[j] = true;
+            matches.push((i, j, overlap));
+        }
+    }
+
+    matches
+}
+
+/// Compares two annotators' copies and adds their boxes to the per-category counters.
+pub fn compare_pair(
+    annotator_a: Uuid,
+    boxes_a: &[AgreementBox],
+    annotator_b: Uuid,
+    boxes_b: &[AgreementBox],
+    iou_threshold: f64,
+    categories: &mut HashMap<Option<Uuid>, CategoryCounts>,
+) -> PairAgreement {
+    let matches = match_boxes(boxes_a, boxes_b, iou_threshold);
+
+    for item in boxes_a.iter().chain(boxes_b.iter()) {
+        categories.entry(item.category_id).or_default().boxes += 1;
+    }
+
+    let mut same_label = 0;
+    let mut iou_sum = 0.0;
+    for &(i, j, overlap) in &matches {
+        iou_sum += overlap;
+        if boxes_a[i].category_id == boxes_b[j].category_id {
+            same_label += 1;
+            let counts = categories.entry(boxes_a[i].category_id).or_default();
+            counts.matched += 1;
+            counts.iou_sum += overlap;
+        }
+    }
+
+    let total = boxes_a.len() + boxes_b.len();
+    PairAgreement {
+        annotator_a,
+        annotator_b,
+        matched: matches.len(),
+        unmatched_a: boxes_a.len() - matches.len(),
+        unmatched_b: boxes_b.len() - matches.len(),
+        mean_iou: if matches.is_empty() { 0.0 } else { iou_sum / matches.len() as f64 },
+        label_agreement: if matches.is_empty() { 0.0 } else { same_label as f64 / matches.len() as f64 },
+        // Two empty copies agree perfectly
+        f1: if total == 0 { 1.0 } else { 2.0 * matches.len() as f64 / total as f64 },
+    }
+}
+
+/// Merges the annotators' copies by majority vote.
+///
+/// Boxes are clustered greedily (at most one box per annotator per cluster); a cluster
+/// becomes a consensus box when it has at least `min_votes` members. The merged box is the
+/// mean of its members and the category is the most voted one (earliest wins ties).
+pub fn merge_by_majority(copies: &[Vec<AgreementBox>], iou_threshold: f64, min_votes: usize) -> Vec<ConsensusBox> {
+    struct Cluster {
+        members: Vec<(usize, AgreementBox)>,
+    }
+
+    impl Cluster {
+        fn mean_box(&self) -> [f64; 4] {
+            let mut sum = [0.0; 4];
+            for (_, member) in &self.members {
+                for (total, value) in sum.iter_mut().zip(member.bbox) {
+                    *total += value;
+                }
+            }
+            sum.map(|total| total / self.members.len() as f64)
+        }
+    }
+
+    let mut clusters: Vec<Cluster> = Vec::new();
+    for (annotator, boxes) in copies.iter().enumerate() {
+        for item in boxes {
+            let best = clusters
+                .iter()
+                .enumerate()
+                .filter(|(_, cluster)| cluster.members.iter().all(|(member, _)| *member != annotator))
+                .map(|(index, cluster)| (index, iou(&cluster.mean_box(), &item.bbox)))
+                .filter(|(_, overlap)| *overlap >= iou_threshold)
+                .max_by(|x, y| x.1.total_cmp(&y.1));
+
+            match best {
+                Some((index, _)) => clusters[index].members.push((annotator, item.clone())),
+                None => clusters.push(Cluster { members: vec![(annotator, item.clone())] }),
+            }
+        }
+    }
+
+    clusters
+        .into_iter()
+        .filter(|cluster| cluster.members.len() >= min_votes)
+        .map(|cluster| {
+            let mut votes: Vec<(Option<Uuid>, usize)> = Vec::new();
+            for (_, member) in &cluster.members {
+                match votes.iter_mut().find(|(category, _)| *category == member.category_id) {
+                    Some((_, count)) => *count += 1,
+                    None => votes.push((member.category_id, 1)),
+                }
+            }
+            let category_id = votes
+                .iter()
+                .fold(None, |best: Option<&(Option<Uuid>, usize)>, vote| match best {
+                    Some(current) if current.1 >= vote.1 => Some(current),
+                    _ => Some(vote),
+                })
+                .and_then(|(category, _)| *category);
+
+            ConsensusBox {
+                category_id,
+                bbox: cluster.mean_box().to_vec(),
+                votes: cluster.members.len(),
+            }
+        })
+        .collect()
+}
+
+/// Default quorum for `n` annotators: a strict majority.
+pub fn majority(annotators: usize) -> usize {
+    annotators / 2 + 1
+}
@@ -0,0 +1,610 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use std::collections::HashMap;
+
+use crate::auth::{JwtManager, Claims};
+use super::agreement::{self, AgreementBox, CategoryCounts};
+use super::types::{
+    TaskAssignment, UpdateAssignmentsRequest, AssignmentsResponse, AgreementQuery, ConsensusQuery,
+    TaskAgreement, CategoryAgreement, AgreementReport, ConsensusResult, DEFAULT_IOU_THRESHOLD,
+};
+
+/// Latest box of one annotator's copy of a task. Annotators with an empty copy
+/// appear once with no bbox.
+#[derive(Debug, sqlx::FromRow)]
+struct CopyBoxRow {
+    task_id: Uuid,
+    task_name: String,
+    annotated_by: Uuid,
+    category_id: Option<Uuid>,
+    bbox: Option<Vec<f64>>,
+}
+
+/// All annotator copies of one task, in annotator order.
+struct TaskCopies {
+    task_id: Uuid,
+    task_name: String,
+    copies: Vec<(Uuid, Vec<AgreementBox>)>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/tasks/{task_id}/assignments",
+    tag = "consensus",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    responses(
+        (status = 200, body = AssignmentsResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn get_task_assignments(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id, task_id) = match parse_task_path(path.into_inner()) {
+        Ok(ids) => ids,
+        Err(response) => return response,
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match get_task_assignments_from_db(&pool, project_id, task_id).await {
+        Ok(assignments) => HttpResponse::Ok().json(AssignmentsResponse { assignments }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch assignments"),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/projects/{project_id}/tasks/{task_id}/assignments",
+    tag = "consensus",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    request_body = UpdateAssignmentsRequest,
+    responses(
+        (status = 200, body = AssignmentsResponse),
+        (status = 400, description = "A user isn't a member of the project", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found, or the user isn't the project owner", body = String),
+    ),
+)]
+pub async fn update_task_assignments(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+    assignments_req: web::Json<UpdateAssignmentsRequest>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id, task_id) = match parse_task_path(path.into_inner()) {
+        Ok(ids) => ids,
+        Err(response) => return response,
+    };
+
+    // Only project owners may assign annotators
+    match user_is_project_owner(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or permission denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check permissions"),
+    }
+
+    match task_belongs_to_project(&pool, task_id, project_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Task not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch task"),
+    }
+
+    let mut user_ids = assignments_req.into_inner().user_ids;
+    user_ids.sort();
+    user_ids.dedup();
+    for assignee in &user_ids {
+        if !user_has_project_access(&pool, project_id, *assignee).await {
+            return HttpResponse::BadRequest().json(format!("User {} is not a member of this project", assignee));
+        }
+    }
+
+    if let Err(err) = replace_task_assignments_in_db(&pool, task_id, &user_ids, user_id).await {
+        eprintln!("Assignment update error: {:?}", err);
+        return HttpResponse::InternalServerError().json("Failed to update assignments");
+    }
+
+    match get_task_assignments_from_db(&pool, project_id, task_id).await {
+        Ok(assignments) => HttpResponse::Ok().json(AssignmentsResponse { assignments }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch assignments"),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/agreement",
+    tag = "consensus",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        AgreementQuery,
+    ),
+    responses(
+        (status = 200, body = AgreementReport),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn get_agreement_report(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<AgreementQuery>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let iou_threshold = match validate_iou_threshold(query.iou_threshold) {
+        Ok(threshold) => threshold,
+        Err(response) => return response,
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let tasks = match get_task_copies(&pool, project_id, None).await {
+        Ok(tasks) => tasks,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotations"),
+    };
+
+    let category_names = match get_category_names(&pool, project_id).await {
+        Ok(names) => names,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch categories"),
+    };
+
+    HttpResponse::Ok().json(build_agreement_report(&tasks, &category_names, iou_threshold))
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/tasks/{task_id}/consensus",
+    tag = "consensus",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        ConsensusQuery,
+    ),
+    responses(
+        (status = 201, description = "Boxes enough annotators agree on, saved as a new annotation", body = ConsensusResult),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or the user isn't its owner", body = String),
+    ),
+)]
+pub async fn create_consensus_annotation(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    query: web::Query<ConsensusQuery>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id, task_id) = match parse_task_path(path.into_inner()) {
+        Ok(ids) => ids,
+        Err(response) => return response,
+    };
+
+    let iou_threshold = match validate_iou_threshold(query.iou_threshold) {
+        Ok(threshold) => threshold,
+        Err(response) => return response,
+    };
+
+    // Only project owners may publish the final annotation
+    match user_is_project_owner(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or permission denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check permissions"),
+    }
+
+    let copies = match get_task_copies(&pool, project_id, Some(task_id)).await {
+        Ok(mut tasks) => match tasks.pop() {
+            Some(task) => task.copies,
+            None => Vec::new(),
+        },
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotations"),
+    };
+
+    if copies.len() < 2 {
+        return HttpResponse::BadRequest().json("Consensus needs annotations from at least two annotators");
+    }
+
+    let min_votes = query.min_votes.unwrap_or_else(|| agreement::majority(copies.len()));
+    if min_votes == 0 || min_votes > copies.len() {
+        return HttpResponse::BadRequest().json(format!("min_votes must be between 1 and {}", copies.len()));
+    }
+
+    let boxes: Vec<Vec<AgreementBox>> = copies.into_iter().map(|(_, boxes)| boxes).collect();
+    let merged = agreement::merge_by_majority(&boxes, iou_threshold, min_votes);
+
+    match create_consensus_annotation_in_db(&pool, task_id, &merged, boxes.len(), iou_threshold, user_id).await {
+        Ok(annotation_id) => HttpResponse::Created().json(ConsensusResult {
+            annotation_id,
+            annotators: boxes.len(),
+            min_votes,
+            boxes: merged,
+        }),
+        Err(err) => {
+            eprintln!("Consensus error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to create consensus annotation")
+        }
+    }
+}
+
+fn parse_task_path((project_id_str, task_id_str): (String, String)) -> Result<(Uuid, Uuid), HttpResponse> {
+    let project_id = Uuid::parse_str(&project_id_str)
+        .map_err(|_| HttpResponse::BadRequest().json("Invalid project ID"))?;
+    let task_id = Uuid::parse_str(&task_id_str)
+        .map_err(|_| HttpResponse::BadRequest().json("Invalid task ID"))?;
+    Ok((project_id, task_id))
+}
+
+fn validate_iou_threshold(threshold: Option<f64>) -> Result<f64, HttpResponse> {
+    let threshold = threshold.unwrap_or(DEFAULT_IOU_THRESHOLD);
+    if threshold > 0.0 && threshold <= 1.0 {
+        Ok(threshold)
+    } else {
+        Err(HttpResponse::BadRequest().json("iou_threshold must be in (0, 1]"))
+    }
+}
+
+fn build_agreement_report(
+    tasks: &[TaskCopies],
+    category_names: &HashMap<Uuid, String>,
+    iou_threshold: f64,
+) -> AgreementReport {
+    let mut category_counts: HashMap<Option<Uuid>, CategoryCounts> = HashMap::new();
+    let mut task_reports = Vec::new();
+
+    for task in tasks.iter().filter(|task| task.copies.len() >= 2) {
+        let mut pairs = Vec::new();
+        for (index, (annotator_a, boxes_a)) in task.copies.iter().enumerate() {
+            for (annotator_b, boxes_b) in &task.copies[index + 1..] {
+                pairs.push(agreement::compare_pair(
+                    *annotator_a,
+                    boxes_a,
+                    *annotator_b,
+                    boxes_b,
+                    iou_threshold,
+                    &mut category_counts,
+                ));
+            }
+        }
+
+        let count = pairs.len() as f64;
+        task_reports.push(TaskAgreement {
+            task_id: task.task_id,
+            task_name: task.task_name.clone(),
+            annotators: task.copies.iter().map(|(annotator, _)| *annotator).collect(),
+            mean_iou: pairs.iter().map(|pair| pair.mean_iou).sum::<f64>() / count,
+            label_agreement: pairs.iter().map(|pair| pair.label_agreement).sum::<f64>() / count,
+            f1: pairs.iter().map(|pair| pair.f1).sum::<f64>() / count,
+            pairs,
+        });
+    }
+
+    let mut categories: Vec<CategoryAgreement> = category_counts
+        .into_iter()
+        .map(|(category_id, counts)| CategoryAgreement {
+            category_id,
+            category_name: category_id
+                .and_then(|id| category_names.get(&id).cloned())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            boxes: counts.boxes,
+            matched: counts.matched,
+            agreement: if counts.boxes == 0 { 0.0 } else { 2.0 * counts.matched as f64 / counts.boxes as f64 },
+            mean_iou: if counts.matched == 0 { 0.0 } else { counts.iou_sum / counts.matched as f64 },
+        })
+        .collect();
+    categories.sort_by(|a, b| a.category_name.cmp(&b.category_name));
+
+    let average = |value: fn(&TaskAgreement) -> f64| {
+        if task_reports.is_empty() {
+            0.0
+        } else {
+            task_reports.iter().map(value).sum::<f64>() / task_reports.len() as f64
+        }
+    };
+
+    AgreementReport {
+        iou_threshold,
+        tasks_compared: task_reports.len(),
+        mean_iou: average(|task| task.mean_iou),
+        label_agreement: average(|task| task.label_agreement),
+        f1: average(|task| task.f1),
+        tasks: task_reports,
+        categories,
+    }
+}
+
+/// Loads the latest annotation of every annotator per task. Assigned tasks only count
+/// their assignees, and earlier consensus results are left out.
+async fn get_task_copies(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    task_id: Option<Uuid>,
+) -> Result<Vec<TaskCopies>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, CopyBoxRow>(
+        r#"
+        WITH latest_copies AS (
+            SELECT DISTINCT ON (a.task_id, a.annotated_by) a.id, a.task_id, a.annotated_by
+            FROM annotations a
+            JOIN tasks t ON t.id = a.task_id
+            WHERE t.project_id = $1
+              AND ($2::uuid IS NULL OR a.task_id = $2)
+              AND a.annotated_by IS NOT NULL
+              AND COALESCE(a.metadata->>'consensus', 'false') <> 'true'
+              AND (
+                  NOT EXISTS (SELECT 1 FROM task_assignments ta WHERE ta.task_id = a.task_id)
+                  OR EXISTS (SELECT 1 FROM task_assignments ta WHERE ta.task_id = a.task_id AND ta.user_id = a.annotated_by)
+              )
+            ORDER BY a.task_id, a.annotated_by, a.created_at DESC
+        )
+        SELECT lc.task_id, t.name as task_name, lc.annotated_by, ia.category_id, ia.bbox
+        FROM latest_copies lc
+        JOIN tasks t ON t.id = lc.task_id
+        LEFT JOIN image_annotations ia ON ia.annotation_id = lc.id AND NOT ia.is_prediction
+        ORDER BY t.created_at, lc.task_id, lc.annotated_by, ia.created_at
+        "#
+    )
+    .bind(project_id)
+    .bind(task_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut tasks: Vec<TaskCopies> = Vec::new();
+    for row in rows {
+        if tasks.last().map(|task| task.task_id) != Some(row.task_id) {
+            tasks.push(TaskCopies { task_id: row.task_id, task_name: row.task_name.clone(), copies: Vec::new() });
+        }
+        let task = tasks.last_mut().unwrap();
+
+        if task.copies.last().map(|(annotator, _)| *annotator) != Some(row.annotated_by) {
+            task.copies.push((row.annotated_by, Vec::new()));
+        }
+        let boxes = &mut task.copies.last_mut().unwrap().1;
+
+        if let Some(bbox) = row.bbox.filter(|bbox| bbox.len() == 4) {
+            boxes.push(AgreementBox {
+                category_id: row.category_id,
+                bbox: [bbox[0], bbox[1], bbox[2], bbox[3]],
+            });
+        }
+    }
+
+    Ok(tasks)
+}
+
+async fn get_category_names(pool: &Pool<Postgres>, project_id: Uuid) -> Result<HashMap<Uuid, String>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, (Uuid, String)>(
+        "SELECT id, name FROM image_annotation_categories WHERE project_id = $1"
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+async fn create_consensus_annotation_in_db(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
+    boxes: &[super::types::ConsensusBox],
+    annotators: usize,
+    iou_threshold: f64,
+    user_id: Uuid,
+) -> Result<Uuid, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let annotation_id = Uuid::new_v4();
+    let now = chrono::Utc::now();
+
+    sqlx::query(
+        r#"
+        INSERT INTO annotations (id, task_id, metadata, annotated_by, annotated_at, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#
+    )
+    .bind(annotation_id)
+    .bind(task_id)
+    .bind(serde_json::json!({"consensus": true, "annotators": annotators, "iou_threshold": iou_threshold}))
+    .bind(user_id)
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    for item in boxes {
+        sqlx::query(
+            r#"
+            INSERT INTO image_annotations (id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, FALSE, $6, $7, $8)
+            "#
+        )
+        .bind(Uuid::new_v4())
+        .bind(annotation_id)
+        .bind(item.category_id)
+        .bind(&item.bbox)
+        .bind(item.bbox[2] * item.bbox[3])
+        .bind(serde_json::json!({"votes": item.votes}))
+        .bind(now)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(annotation_id)
+}
+
+async fn get_task_assignments_from_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    task_id: Uuid,
+) -> Result<Vec<TaskAssignment>, sqlx::Error> {
+    sqlx::query_as::<_, TaskAssignment>(
+        r#"
+        SELECT ta.task_id, ta.user_id, u.email, u.name, ta.created_at
+        FROM task_assignments ta
+        JOIN tasks t ON t.id = ta.task_id
+        JOIN users u ON u.id = ta.user_id
+        WHERE ta.task_id = $1 AND t.project_id = $2
+        ORDER BY ta.created_at, u.email
+        "#
+    )
+    .bind(task_id)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn replace_task_assignments_in_db(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
+    user_ids: &[Uuid],
+    assigned_by: Uuid,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM task_assignments WHERE task_id = $1 AND NOT (user_id = ANY($2))")
+        .bind(task_id)
+        .bind(user_ids)
+        .execute(&mut *tx)
+        .await?;
+
+    for user_id in user_ids {
+        sqlx::query(
+            r#"
+            INSERT INTO task_assignments (id, task_id, user_id, assigned_by, created_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (task_id, user_id) DO NOTHING
+            "#
+        )
+        .bind(Uuid::new_v4())
+        .bind(task_id)
+        .bind(user_id)
+        .bind(assigned_by)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
+}
+
+async fn task_belongs_to_project(pool: &Pool<Postgres>, task_id: Uuid, project_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM tasks WHERE id = $1 AND project_id = $2)"
+    )
+    .bind(task_id)
+    .bind(project_id)
+    .fetch_one(pool)
+    .await
+}
+
+async fn user_is_project_owner(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM project_members pm
+            INNER JOIN projects p ON pm.project_id = p.id
+            WHERE p.id = $1 AND pm.user_id = $2 AND (pm.role = 'owner' OR p.owner_id = $2)
+        )
+        "#
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
@@ -0,0 +1,8 @@
+pub mod types;
+pub mod agreement;
+pub mod handlers;
+
+pub use handlers::{get_task_assignments, update_task_assignments, get_agreement_report, create_consensus_annotation};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,205 @@
+use super::*;
+use super::agreement::{AgreementBox, CategoryCounts, compare_pair, match_boxes, merge_by_majority, majority};
+use super::types::{AgreementReport, ConsensusResult};
+use crate::annotations::{create_annotation_in_db, BoundingBox};
+use crate::auth::{User, OAuthConfig, JwtManager};
+use crate::test_utils;
+use actix_web::{test, App, web};
+use serial_test::serial;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn create_test_oauth_config() -> OAuthConfig {
+    OAuthConfig {
+        google_client_id: "test_google_id".to_string(),
+        google_client_secret: "test_google_secret".to_string(),
+        google_redirect_url: "http://localhost/callback".to_string(),
+        github_client_id: "test_github_id".to_string(),
+        github_client_secret: "test_github_secret".to_string(),
+        github_redirect_url: "http://localhost/callback".to_string(),
+        jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+    }
+}
+
+fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+    let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+    jwt_manager.generate_token(
+        &user.id.to_string(),
+        &user.email,
+        &user.name
+    ).expect("Failed to generate token")
+}
+
+fn bbox(category_id: Uuid, coords: [f64; 4]) -> BoundingBox {
+    BoundingBox { category_id, bbox: coords.to_vec(), area: None, iscrowd: Some(false), is_prediction: None, confidence: None, attributes: None, rotation: None, frame_index: None, track_id: None, is_interpolated: None, mask: None }
+}
+
+async fn add_project_member(pool: &sqlx::PgPool, project_id: Uuid) -> Uuid {
+    let user_id = test_utils::create_test_user(pool).await;
+    sqlx::query("INSERT INTO project_members (id, project_id, user_id, role, joined_at) VALUES ($1, $2, $3, 'member', NOW())")
+        .bind(Uuid::new_v4())
+        .bind(project_id)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    user_id
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_agreement_report_and_consensus() {
+    let pool = test_utils::setup_test_db().await;
+    let owner = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &owner);
+
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, owner.id).await.unwrap();
+    let person = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, None, None, Some(1)).await.unwrap();
+    let car = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "car", None, None, None, Some(2)).await.unwrap();
+    let task = crate::tasks::create_task_in_db(&pool, project.id, "image1.jpg", None).await.unwrap();
+
+    let annotator_a = add_project_member(&pool, project.id).await;
+    let annotator_b = add_project_member(&pool, project.id).await;
+    let annotator_c = add_project_member(&pool, project.id).await;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .route("/projects/{project_id}/tasks/{task_id}/assignments", web::put().to(update_task_assignments))
+            .route("/projects/{project_id}/agreement", web::get().to(get_agreement_report))
+            .route("/projects/{project_id}/tasks/{task_id}/consensus", web::post().to(create_consensus_annotation))
+    ).await;
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/projects/{}/tasks/{}/assignments", project.id, task.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(serde_json::json!({"user_ids": [annotator_a, annotator_b, annotator_c]}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    // Two annotators agree on a person, the third labels the same box as a car
+    create_annotation_in_db(&pool, task.id, &[bbox(person.id, [10.0, 10.0, 100.0, 100.0])], &serde_json::json!({}), annotator_a).await.unwrap();
+    create_annotation_in_db(&pool, task.id, &[bbox(person.id, [12.0, 10.0, 100.0, 100.0])], &serde_json::json!({}), annotator_b).await.unwrap();
+    create_annotation_in_db(&pool, task.id, &[bbox(car.id, [10.0, 12.0, 100.0, 100.0])], &serde_json::json!({}), annotator_c).await.unwrap();
+    // The owner is not assigned, so this copy is ignored
+    create_annotation_in_db(&pool, task.id, &[bbox(car.id, [300.0, 300.0, 10.0, 10.0])], &serde_json::json!({}), owner.id).await.unwrap();
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/agreement", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let report: AgreementReport = test::read_body_json(resp).await;
+    assert_eq!(report.tasks_compared, 1);
+    assert_eq!(report.tasks[0].annotators.len(), 3);
+    assert_eq!(report.tasks[0].pairs.len(), 3);
+    assert_eq!(report.tasks[0].f1, 1.0);
+    assert!((report.tasks[0].label_agreement - 1.0 / 3.0).abs() < 1e-9);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/projects/{}/tasks/{}/consensus", project.id, task.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+
+    let result: ConsensusResult = test::read_body_json(resp).await;
+    assert_eq!(result.annotators, 3);
+    assert_eq!(result.min_votes, 2);
+    assert_eq!(result.boxes.len(), 1);
+    assert_eq!(result.boxes[0].category_id, Some(person.id));
+    assert_eq!(result.boxes[0].votes, 3);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_update_assignments_rejects_non_members() {
+    let pool = test_utils::setup_test_db().await;
+    let owner = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &owner);
+
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, owner.id).await.unwrap();
+    let task = crate::tasks::create_task_in_db(&pool, project.id, "image1.jpg", None).await.unwrap();
+    let outsider = test_utils::create_test_user(&pool).await;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .route("/projects/{project_id}/tasks/{task_id}/assignments", web::put().to(update_task_assignments))
+    ).await;
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/projects/{}/tasks/{}/assignments", project.id, task.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(serde_json::json!({"user_ids": [outsider]}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[actix_web::test]
+async fn test_compare_pair_counts_labels_per_category() {
+    let person = Some(Uuid::new_v4());
+    let car = Some(Uuid::new_v4());
+    let a = vec![
+        AgreementBox { category_id: person, bbox: [0.0, 0.0, 10.0, 10.0] },
+        AgreementBox { category_id: car, bbox: [50.0, 50.0, 10.0, 10.0] },
+    ];
+    let b = vec![
+        AgreementBox { category_id: person, bbox: [0.0, 0.0, 10.0, 10.0] },
+        AgreementBox { category_id: person, bbox: [50.0, 50.0, 10.0, 10.0] },
+        AgreementBox { category_id: car, bbox: [200.0, 200.0, 10.0, 10.0] },
+    ];
+
+    let mut categories: HashMap<Option<Uuid>, CategoryCounts> = HashMap::new();
+    let pair = compare_pair(Uuid::new_v4(), &a, Uuid::new_v4(), &b, 0.5, &mut categories);
+
+    assert_eq!(pair.matched, 2);
+    assert_eq!(pair.unmatched_a, 0);
+    assert_eq!(pair.unmatched_b, 1);
+    assert_eq!(pair.mean_iou, 1.0);
+    assert_eq!(pair.label_agreement, 0.5);
+    assert_eq!(pair.f1, 0.8);
+    assert_eq!(categories[&person], CategoryCounts { boxes: 3, matched: 1, iou_sum: 1.0 });
+    assert_eq!(categories[&car].matched, 0);
+}
+
+#[actix_web::test]
+async fn test_match_boxes_is_one_to_one() {
+    let category_id = Some(Uuid::new_v4());
+    let a = vec![AgreementBox { category_id, bbox: [0.0, 0.0, 10.0, 10.0] }];
+    let b = vec![
+        AgreementBox { category_id, bbox: [1.0, 0.0, 10.0, 10.0] },
+        AgreementBox { category_id, bbox: [0.0, 0.0, 10.0, 10.0] },
+    ];
+
+    let matches = match_boxes(&a, &b, 0.5);
+    assert_eq!(matches.len(), 1);
+    assert_eq!((matches[0].0, matches[0].1), (0, 1));
+}
+
+#[actix_web::test]
+async fn test_merge_by_majority_drops_minority_boxes() {
+    let person = Some(Uuid::new_v4());
+    let copies = vec![
+        vec![AgreementBox { category_id: person, bbox: [0.0, 0.0, 10.0, 10.0] }],
+        vec![
+            AgreementBox { category_id: person, bbox: [2.0, 0.0, 10.0, 10.0] },
+            AgreementBox { category_id: person, bbox: [100.0, 100.0, 10.0, 10.0] },
+        ],
+        Vec::new(),
+    ];
+
+    let merged = merge_by_majority(&copies, 0.5, majority(copies.len()));
+    assert_eq!(merged.len(), 1);
+    assert_eq!(merged[0].votes, 2);
+    assert_eq!(merged[0].bbox, vec![1.0, 0.0, 10.0, 10.0]);
+    assert_eq!(merged[0].category_id, person);
+}
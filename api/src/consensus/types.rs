@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
+
+pub const DEFAULT_IOU_THRESHOLD: f64 = 0.5;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct TaskAssignment {
+    pub task_id: Uuid,
+    pub user_id: Uuid,
+    pub email: String,
+    pub name: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateAssignmentsRequest {
+    pub user_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AssignmentsResponse {
+    pub assignments: Vec<TaskAssignment>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AgreementQuery {
+    pub iou_threshold: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ConsensusQuery {
+    pub iou_threshold: Option<f64>,
+    /// Minimum number of annotators that must agree on a box, defaults to a strict majority
+    pub min_votes: Option<usize>,
+}
+
+/// Agreement between the latest copies of two annotators of the same task.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PairAgreement {
+    pub annotator_a: Uuid,
+    pub annotator_b: Uuid,
+    pub matched: usize,
+    pub unmatched_a: usize,
+    pub unmatched_b: usize,
+    pub mean_iou: f64,
+    pub label_agreement: f64,
+    pub f1: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TaskAgreement {
+    pub task_id: Uuid,
+    pub task_name: String,
+    pub annotators: Vec<Uuid>,
+    pub mean_iou: f64,
+    pub label_agreement: f64,
+    pub f1: f64,
+    pub pairs: Vec<PairAgreement>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CategoryAgreement {
+    pub category_id: Option<Uuid>,
+    pub category_name: String,
+    pub boxes: usize,
+    pub matched: usize,
+    /// Share of this category's boxes that were matched with the same label
+    pub agreement: f64,
+    pub mean_iou: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AgreementReport {
+    pub iou_threshold: f64,
+    pub tasks_compared: usize,
+    pub mean_iou: f64,
+    pub label_agreement: f64,
+    pub f1: f64,
+    pub tasks: Vec<TaskAgreement>,
+    pub categories: Vec<CategoryAgreement>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct ConsensusBox {
+    pub category_id: Option<Uuid>,
+    pub bbox: Vec<f64>,
+    pub votes: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ConsensusResult {
+    pub annotation_id: Uuid,
+    pub annotators: usize,
+    pub min_votes: usize,
+    pub boxes: Vec<ConsensusBox>,
+}
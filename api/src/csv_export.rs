@@ -0,0 +1,419 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::Utc;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::auth::{JwtManager, Claims};
+use crate::coco::export::parse_splits;
+
+const CSV_HEADER: &str = "task_id,task_name,file_name,category,x,y,width,height,area,annotator,reviewed";
+
+/// Tasks to export, the filters of the COCO export plus the task status
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CsvExportQuery {
+    /// Only export the tasks of these comma-separated splits, e.g. `train,val`
+    pub splits: Option<String>,
+    /// Only export the tasks with these comma-separated statuses, e.g. `completed`
+    pub status: Option<String>,
+}
+
+/// Statuses of a comma-separated `status` parameter, checked against `tasks::TASK_STATUSES`
+fn parse_statuses(statuses: &str) -> Result<Vec<String>, String> {
+    statuses
+        .split(',')
+        .map(str::trim)
+        .filter(|status| !status.is_empty())
+        .map(|status| {
+            if crate::tasks::TASK_STATUSES.contains(&status) {
+                Ok(status.to_string())
+            } else {
+                Err(format!("Invalid status. Must be one of: {}", crate::tasks::TASK_STATUSES.join(", ")))
+            }
+        })
+        .collect()
+}
+
+/// One exported bounding box joined with its task, category and annotator.
+#[derive(Debug, sqlx::FromRow)]
+pub struct CsvAnnotationRow {
+    pub task_id: Uuid,
+    pub task_name: String,
+    pub resource_url: Option<String>,
+    pub task_status: String,
+    pub category_name: Option<String>,
+    pub bbox: Vec<f64>,
+    pub area: Option<f64>,
+    pub annotator: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/export/csv",
+    tag = "export",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        CsvExportQuery,
+    ),
+    responses(
+        (status = 200, description = "One row per box", body = String, content_type = "text/csv"),
+        (status = 400, description = "Invalid split or status", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn export_project_csv(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<CsvExportQuery>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let splits = match query.splits.as_deref().map(parse_splits).transpose() {
+        Ok(splits) => splits,
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+
+    let statuses = match query.status.as_deref().map(parse_statuses).transpose() {
+        Ok(statuses) => statuses,
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+
+    // Plain members could read the blind copies of other annotators and gold references
+    let hide_blind_tasks = match crate::projects::user_can_manage_storage(&pool, project_id, user_id).await {
+        Ok(can_manage) => !can_manage,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check project access"),
+    };
+
+    let project_name = match get_project_name(&pool, project_id).await {
+        Ok(Some(name)) => name,
+        Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    };
+
+    let rows = match get_project_rows_for_csv(&pool, project_id, splits.as_deref(), statuses.as_deref(), hide_blind_tasks).await {
+        Ok(rows) => rows,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotations"),
+    };
+
+    let filename = format!("{}_annotations_{}.csv",
+        project_name.replace(" ", "_").to_lowercase(),
+        Utc::now().format("%Y%m%d_%H%M%S")
+    );
+
+    HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", filename)))
+        .body(build_csv(&rows))
+}
+
+/// Renders the rows as CSV, one line per bounding box.
+/// A task counts as reviewed once its status is `completed`.
+pub fn build_csv(rows: &[CsvAnnotationRow]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+
+    for row in rows {
+        if row.bbox.len() < 4 {
+            continue;
+        }
+
+        let file_name = row.resource_url
+            .as_deref()
+            .and_then(|url| url.split('/').next_back())
+            .unwrap_or(&row.task_name);
+        let area = row.area.unwrap_or(row.bbox[2] * row.bbox[3]);
+
+        let fields = [
+            row.task_id.to_string(),
+            escape_csv_field(&row.task_name),
+            escape_csv_field(file_name),
+            escape_csv_field(row.category_name.as_deref().unwrap_or("")),
+            row.bbox[0].to_string(),
+            row.bbox[1].to_string(),
+            row.bbox[2].to_string(),
+            row.bbox[3].to_string(),
+            area.to_string(),
+            escape_csv_field(row.annotator.as_deref().unwrap_or("")),
+            (row.task_status == "completed").to_string(),
+        ];
+
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+fn escape_csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+async fn get_project_name(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>("SELECT name FROM projects WHERE id = $1")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+}
+
+async fn get_project_rows_for_csv(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    splits: Option<&[String]>,
+    statuses: Option<&[String]>,
+    hide_blind_tasks: bool,
+) -> Result<Vec<CsvAnnotationRow>, sqlx::Error> {
+    // Only the latest annotation of each task is exported, matching the COCO exporter
+    sqlx::query_as::<_, CsvAnnotationRow>(
+        r#"
+        WITH latest_annotations AS (
+            SELECT DISTINCT ON (task_id) id, task_id, annotated_by
+            FROM annotations
+            WHERE task_id IN (SELECT id FROM tasks WHERE project_id = $1)
+            ORDER BY task_id, created_at DESC
+        )
+        SELECT
+            t.id as task_id,
+            t.name as task_name,
+            t.resource_url,
+            t.status as task_status,
+            iac.name as category_name,
+            ia.bbox,
+            ia.area,
+            u.email as annotator
+        FROM latest_annotations la
+        JOIN tasks t ON t.id = la.task_id
+        JOIN image_annotations ia ON ia.annotation_id = la.id
+        LEFT JOIN image_annotation_categories iac ON iac.id = ia.category_id
+        LEFT JOIN users u ON u.id = la.annotated_by
+        WHERE NOT ia.is_prediction
+            AND ($2::text[] IS NULL OR t.split = ANY($2))
+            AND ($3::text[] IS NULL OR t.status = ANY($3))
+            AND NOT ($4::bool AND (
+                t.gold_annotation_id IS NOT NULL
+                OR EXISTS(SELECT 1 FROM task_assignments ta WHERE ta.task_id = t.id)
+            ))
+        ORDER BY t.created_at, ia.created_at
+        "#
+    )
+    .bind(project_id)
+    .bind(splits)
+    .bind(statuses)
+    .bind(hide_blind_tasks)
+    .fetch_all(pool)
+    .await
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::annotations::{create_annotation_in_db, BoundingBox};
+    use crate::auth::{User, OAuthConfig, AuthStorage, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_export_project_csv_with_data() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+        let auth_storage = AuthStorage::new(pool.clone());
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, Some("human"), Some("#FF0000"), Some(1)).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "image1.jpg", Some("https://example.com/image1.jpg")).await.unwrap();
+
+        let bbox = BoundingBox { category_id: category.id, bbox: vec![10.0, 20.0, 30.0, 40.0], area: None, iscrowd: Some(false), is_prediction: None, confidence: None, attributes: None, rotation: None, frame_index: None, track_id: None, is_interpolated: None, mask: None };
+        create_annotation_in_db(&pool, task.id, &[bbox], &serde_json::json!({}), user.id).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .app_data(web::Data::new(auth_storage))
+                .route("/projects/{project_id}/export/csv", web::get().to(export_project_csv))
+        ).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/export/csv", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert!(resp.headers().get("Content-Type").unwrap().to_str().unwrap().contains("text/csv"));
+
+        let body = test::read_body(resp).await;
+        let csv = std::str::from_utf8(&body).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            format!("{},image1.jpg,image1.jpg,person,10,20,30,40,1200,{},false", task.id, user.email)
+        );
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_export_project_csv_filtered_by_split_and_status() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, None, None, Some(1)).await.unwrap();
+        let bbox = BoundingBox { category_id: category.id, bbox: vec![10.0, 20.0, 30.0, 40.0], area: None, iscrowd: Some(false), is_prediction: None, confidence: None, attributes: None, rotation: None, frame_index: None, track_id: None, is_interpolated: None, mask: None };
+        for (name, split, status) in [("train_done.jpg", "train", "completed"), ("train_open.jpg", "train", "pending"), ("val_done.jpg", "val", "completed")] {
+            let task = crate::tasks::create_task_in_db(&pool, project.id, name, None).await.unwrap();
+            sqlx::query("UPDATE tasks SET split = $1, status = $2 WHERE id = $3")
+                .bind(split)
+                .bind(status)
+                .bind(task.id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            create_annotation_in_db(&pool, task.id, std::slice::from_ref(&bbox), &serde_json::json!({}), user.id).await.unwrap();
+        }
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/export/csv", web::get().to(export_project_csv))
+        ).await;
+
+        let export = |query: &str| test::TestRequest::get()
+            .uri(&format!("/projects/{}/export/csv{}", project.id, query))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let task_names = |csv: &[u8]| -> Vec<String> {
+            std::str::from_utf8(csv).unwrap().lines().skip(1).map(|line| line.split(',').nth(1).unwrap().to_string()).collect()
+        };
+
+        let body = test::read_body(test::call_service(&app, export("?splits=train")).await).await;
+        assert_eq!(task_names(&body), vec!["train_done.jpg", "train_open.jpg"]);
+
+        let body = test::read_body(test::call_service(&app, export("?splits=train&status=completed")).await).await;
+        assert_eq!(task_names(&body), vec!["train_done.jpg"]);
+
+        let body = test::read_body(test::call_service(&app, export("?status=completed")).await).await;
+        assert_eq!(task_names(&body), vec!["train_done.jpg", "val_done.jpg"]);
+
+        assert_eq!(test::call_service(&app, export("?splits=holdout")).await.status(), 400);
+        assert_eq!(test::call_service(&app, export("?status=archived")).await.status(), 400);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_export_project_csv_unauthorized() {
+        let pool = test_utils::setup_test_db().await;
+        let oauth_config = create_test_oauth_config();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/export/csv", web::get().to(export_project_csv))
+        ).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/export/csv", Uuid::new_v4()))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 401);
+    }
+
+    #[actix_web::test]
+    async fn test_escape_csv_field() {
+        assert_eq!(escape_csv_field("plain"), "plain");
+        assert_eq!(escape_csv_field("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
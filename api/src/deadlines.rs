@@ -0,0 +1,386 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::{DateTime, Duration, Utc};
+use utoipa::ToSchema;
+
+use crate::auth::{JwtManager, Claims};
+use crate::tasks::Task;
+
+/// Most days a new task may get to be done
+const MAX_DUE_DAYS: u32 = 3650;
+
+/// When the tasks of a project are due, for tasks without a due date of their own. Settings
+/// left out give no due date.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DeadlineSettings {
+    /// End of the labeling campaign, every task is due then at the latest
+    #[serde(default)]
+    pub deadline: Option<DateTime<Utc>>,
+    /// Days a task has to be done in, counted from its creation
+    #[serde(default)]
+    pub default_due_days: Option<u32>,
+}
+
+impl DeadlineSettings {
+    /// The task's own due date, else the earlier of its default one and the project deadline
+    pub fn due_date(&self, task: &Task) -> Option<DateTime<Utc>> {
+        if task.due_at.is_some() {
+            return task.due_at;
+        }
+        let by_default = self.default_due_days.map(|days| task.created_at + Duration::days(days as i64));
+        match (by_default, self.deadline) {
+            (Some(by_default), Some(deadline)) => Some(by_default.min(deadline)),
+            (by_default, deadline) => by_default.or(deadline),
+        }
+    }
+
+    /// The task is past its due date and still waits to be done
+    pub fn is_overdue(&self, task: &Task, now: DateTime<Utc>) -> bool {
+        !matches!(task.status.as_str(), "completed" | "cancelled") && self.due_date(task).is_some_and(|due| due < now)
+    }
+}
+
+/// Reads the settings column, settings that can't be read give no due dates
+pub fn from_column(value: serde_json::Value) -> DeadlineSettings {
+    serde_json::from_value(value).unwrap_or_default()
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{id}/deadlines",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 200, body = DeadlineSettings),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn get_deadline_settings(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    if !crate::cache::user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match get_deadline_settings_from_db(&pool, project_id).await {
+        Ok(Some(settings)) => HttpResponse::Ok().json(settings),
+        Ok(None) => HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch deadline settings"),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/projects/{id}/deadlines",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    request_body = DeadlineSettings,
+    responses(
+        (status = 200, body = DeadlineSettings),
+        (status = 400, description = "Invalid settings", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found, or the user is not an owner or admin of it", body = String),
+    ),
+)]
+pub async fn update_deadline_settings(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<DeadlineSettings>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let settings = payload.into_inner();
+    if let Err(e) = validate_settings(&settings) {
+        return HttpResponse::BadRequest().json(e);
+    }
+
+    match user_can_manage_deadlines(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check project access"),
+    }
+
+    match update_deadline_settings_in_db(&pool, project_id, &settings).await {
+        Ok(()) => HttpResponse::Ok().json(settings),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to update deadline settings"),
+    }
+}
+
+fn validate_settings(settings: &DeadlineSettings) -> Result<(), String> {
+    if settings.default_due_days.is_some_and(|days| days == 0 || days > MAX_DUE_DAYS) {
+        return Err(format!("default_due_days must be between 1 and {}", MAX_DUE_DAYS));
+    }
+    Ok(())
+}
+
+pub async fn get_deadline_settings_from_db(pool: &Pool<Postgres>, project_id: Uuid) -> Result<Option<DeadlineSettings>, sqlx::Error> {
+    let settings = sqlx::query_scalar::<_, serde_json::Value>("SELECT deadline_settings FROM projects WHERE id = $1")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(settings.map(from_column))
+}
+
+async fn update_deadline_settings_in_db(pool: &Pool<Postgres>, project_id: Uuid, settings: &DeadlineSettings) -> Result<(), sqlx::Error> {
+    let settings = serde_json::to_value(settings).unwrap_or_else(|_| serde_json::json!({}));
+    sqlx::query("UPDATE projects SET deadline_settings = $1, updated_at = $2 WHERE id = $3")
+        .bind(&settings)
+        .bind(Utc::now())
+        .bind(project_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Owners and admins plan the campaign, every member sees the due dates
+async fn user_can_manage_deadlines(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM project_members pm
+            INNER JOIN projects p ON pm.project_id = p.id
+            WHERE p.id = $1 AND pm.user_id = $2 AND (pm.role IN ('owner', 'admin') OR p.owner_id = $2)
+        )
+        "#
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use chrono::TimeZone;
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    fn task(created_at: DateTime<Utc>, due_at: Option<DateTime<Utc>>, status: &str) -> Task {
+        Task {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            name: "task".to_string(),
+            resource_url: None,
+            status: status.to_string(),
+            created_at,
+            updated_at: created_at,
+            completed_at: None,
+            priority: None,
+            flag_reason: None,
+            flag_note: None,
+            flagged_by: None,
+            flagged_at: None,
+            media_type: "image".to_string(),
+            frame_count: None,
+            split: None,
+            review_requested_at: None,
+            source_key: None,
+            source_etag: None,
+            source_size: None,
+            stale_at: None,
+            due_at,
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_due_date_prefers_the_task_then_the_earliest_setting() {
+        let created_at = Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+        let deadline = Utc.with_ymd_and_hms(2026, 3, 5, 0, 0, 0).unwrap();
+        let own = Utc.with_ymd_and_hms(2026, 4, 1, 0, 0, 0).unwrap();
+
+        assert_eq!(DeadlineSettings::default().due_date(&task(created_at, None, "pending")), None);
+
+        let settings = DeadlineSettings { deadline: Some(deadline), default_due_days: Some(2) };
+        assert_eq!(settings.due_date(&task(created_at, None, "pending")), Some(created_at + Duration::days(2)));
+        assert_eq!(settings.due_date(&task(created_at, Some(own), "pending")), Some(own));
+
+        // Tasks made close to the end of the campaign are due with it
+        let late = Utc.with_ymd_and_hms(2026, 3, 4, 0, 0, 0).unwrap();
+        assert_eq!(settings.due_date(&task(late, None, "pending")), Some(deadline));
+        let only_deadline = DeadlineSettings { deadline: Some(deadline), default_due_days: None };
+        assert_eq!(only_deadline.due_date(&task(created_at, None, "pending")), Some(deadline));
+    }
+
+    #[actix_web::test]
+    async fn test_done_tasks_are_never_overdue() {
+        let created_at = Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 0, 0, 0).unwrap();
+        let settings = DeadlineSettings { deadline: None, default_due_days: Some(3) };
+
+        assert!(settings.is_overdue(&task(created_at, None, "pending"), now));
+        assert!(settings.is_overdue(&task(created_at, None, "in_progress"), now));
+        assert!(!settings.is_overdue(&task(created_at, None, "completed"), now));
+        assert!(!settings.is_overdue(&task(created_at, None, "cancelled"), now));
+        assert!(!settings.is_overdue(&task(created_at, Some(now + Duration::days(1)), "pending"), now));
+        assert!(!DeadlineSettings::default().is_overdue(&task(created_at, None, "pending"), now));
+    }
+
+    #[actix_web::test]
+    async fn test_validate_settings() {
+        assert!(validate_settings(&DeadlineSettings::default()).is_ok());
+        assert!(validate_settings(&DeadlineSettings { deadline: None, default_due_days: Some(14) }).is_ok());
+        assert!(validate_settings(&DeadlineSettings { deadline: None, default_due_days: Some(0) }).is_err());
+        assert!(validate_settings(&DeadlineSettings { deadline: None, default_due_days: Some(MAX_DUE_DAYS + 1) }).is_err());
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_deadline_settings_are_set_by_owners_and_admins() {
+        let pool = test_utils::setup_test_db().await;
+        let owner = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let owner_token = create_auth_token(&oauth_config, &owner);
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, owner.id).await.unwrap();
+
+        let member_id = test_utils::create_test_user(&pool).await;
+        sqlx::query("INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, 'member')")
+            .bind(project.id)
+            .bind(member_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let member_token = JwtManager::new(&oauth_config.jwt_secret)
+            .generate_token(&member_id.to_string(), &format!("test-{}@example.com", member_id), "Test User")
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{id}/deadlines", web::get().to(get_deadline_settings))
+                .route("/projects/{id}/deadlines", web::put().to(update_deadline_settings))
+        ).await;
+        let uri = format!("/projects/{}/deadlines", project.id);
+
+        // No deadlines until they are set
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", member_token)))
+            .to_request();
+        let settings: DeadlineSettings = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(settings, DeadlineSettings::default());
+
+        let settings = DeadlineSettings {
+            deadline: Some(Utc.with_ymd_and_hms(2026, 12, 31, 0, 0, 0).unwrap()),
+            default_due_days: Some(7),
+        };
+        let req = test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", member_token)))
+            .set_json(&settings)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+        let req = test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+            .set_json(DeadlineSettings { default_due_days: Some(0), ..settings.clone() })
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        let req = test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+            .set_json(&settings)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", member_token)))
+            .to_request();
+        let saved: DeadlineSettings = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(saved, settings);
+    }
+}
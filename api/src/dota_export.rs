@@ -0,0 +1,297 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use sqlx::{Pool, Postgres};
+use std::collections::HashSet;
+use std::io::{Cursor, Write};
+use uuid::Uuid;
+use chrono::Utc;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::auth::{JwtManager, Claims};
+
+/// Directory inside the archive that holds one label file per image, as in the DOTA dataset.
+pub const LABELS_DIR: &str = "labelTxt";
+
+/// One task joined with one of its (possibly rotated) boxes.
+/// Tasks without boxes appear once with `bbox` set to `None`.
+#[derive(Debug, sqlx::FromRow)]
+pub struct DotaAnnotationRow {
+    pub task_id: Uuid,
+    pub task_name: String,
+    pub resource_url: Option<String>,
+    pub category_name: Option<String>,
+    pub bbox: Option<Vec<f64>>,
+    pub rotation: Option<f64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/export/dota",
+    tag = "export",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 200, description = "ZIP with one DOTA label file per task", body = [u8], content_type = "application/zip"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn export_project_dota(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let project_name = match get_project_name(&pool, project_id).await {
+        Ok(Some(name)) => name,
+        Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    };
+
+    let rows = match get_project_rows_for_dota(&pool, project_id).await {
+        Ok(rows) => rows,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotations"),
+    };
+
+    let archive = match write_archive(&build_dota_files(&rows)) {
+        Ok(archive) => archive,
+        Err(e) => {
+            eprintln!("Failed to build DOTA archive: {}", e);
+            return HttpResponse::InternalServerError().json("Failed to build export archive");
+        }
+    };
+
+    let filename = format!("{}_dota_{}.zip",
+        project_name.replace(" ", "_").to_lowercase(),
+        Utc::now().format("%Y%m%d_%H%M%S")
+    );
+
+    HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", filename)))
+        .body(archive)
+}
+
+/// Groups the rows into one `labelTxt/<image>.txt` file per task. Every box becomes a
+/// `x1 y1 x2 y2 x3 y3 x4 y4 category difficult` line with its corners in clockwise order.
+pub fn build_dota_files(rows: &[DotaAnnotationRow]) -> Vec<(String, String)> {
+    let mut files: Vec<(Uuid, String, String)> = Vec::new();
+    let mut used_names = HashSet::new();
+
+    for row in rows {
+        if files.last().is_none_or(|(task_id, _, _)| *task_id != row.task_id) {
+            let file_name = row.resource_url
+                .as_deref()
+                .and_then(|url| url.split('/').next_back())
+                .unwrap_or(&row.task_name);
+            let stem = file_name.rsplit_once('.').map_or(file_name, |(stem, _)| stem);
+
+            // Images from different folders can share a base name, so prefix duplicates with the task ID
+            let mut label_name = format!("{}.txt", stem);
+            if !used_names.insert(label_name.clone()) {
+                label_name = format!("{}_{}.txt", row.task_id, stem);
+                used_names.insert(label_name.clone());
+            }
+
+            files.push((row.task_id, format!("{}/{}", LABELS_DIR, label_name), String::new()));
+        }
+
+        let bbox = match &row.bbox {
+            Some(bbox) if bbox.len() >= 4 => bbox,
+            _ => continue,
+        };
+
+        let corners = crate::rotated_box::corners(bbox, row.rotation.unwrap_or(0.0));
+        let coordinates: Vec<String> = corners.iter()
+            .flat_map(|(x, y)| [format!("{:.1}", x), format!("{:.1}", y)])
+            .collect();
+        // DOTA separates fields with spaces, so category names cannot contain any
+        let category = row.category_name.as_deref().unwrap_or("unknown").replace(char::is_whitespace, "-");
+
+        let (_, _, content) = files.last_mut().expect("file was pushed above");
+        content.push_str(&format!("{} {} 0\n", coordinates.join(" "), category));
+    }
+
+    files.into_iter().map(|(_, path, content)| (path, content)).collect()
+}
+
+fn write_archive(files: &[(String, String)]) -> zip::result::ZipResult<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (path, content) in files {
+        zip.start_file(path.as_str(), options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+async fn get_project_name(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>("SELECT name FROM projects WHERE id = $1")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+}
+
+async fn get_project_rows_for_dota(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Vec<DotaAnnotationRow>, sqlx::Error> {
+    // Only the latest annotation of each task is exported, matching the COCO exporter
+    sqlx::query_as::<_, DotaAnnotationRow>(
+        r#"
+        WITH latest_annotations AS (
+            SELECT DISTINCT ON (task_id) id, task_id
+            FROM annotations
+            WHERE task_id IN (SELECT id FROM tasks WHERE project_id = $1)
+            ORDER BY task_id, created_at DESC
+        )
+        SELECT
+            t.id as task_id,
+            t.name as task_name,
+            t.resource_url,
+            iac.name as category_name,
+            ia.bbox,
+            ia.rotation
+        FROM tasks t
+        LEFT JOIN latest_annotations la ON la.task_id = t.id
+        LEFT JOIN image_annotations ia ON ia.annotation_id = la.id AND NOT ia.is_prediction
+        LEFT JOIN image_annotation_categories iac ON iac.id = ia.category_id
+        WHERE t.project_id = $1
+        ORDER BY t.created_at, t.id, ia.created_at
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::OAuthConfig;
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn row(task_id: Uuid, resource_url: &str, category: &str, bbox: Option<Vec<f64>>, rotation: f64) -> DotaAnnotationRow {
+        DotaAnnotationRow {
+            task_id,
+            task_name: "task".to_string(),
+            resource_url: Some(resource_url.to_string()),
+            category_name: Some(category.to_string()),
+            bbox,
+            rotation: Some(rotation),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_build_dota_files() {
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let third = Uuid::new_v4();
+        let rows = vec![
+            row(first, "storage://a/img1.jpg", "small vehicle", Some(vec![0.0, 0.0, 4.0, 2.0]), 0.0),
+            row(first, "storage://a/img1.jpg", "plane", Some(vec![0.0, 0.0, 4.0, 2.0]), 90.0),
+            row(second, "storage://b/img1.jpg", "plane", None, 0.0),
+            row(third, "storage://c/img2.png", "plane", Some(vec![1.0, 1.0, 1.0, 1.0]), 0.0),
+        ];
+
+        let files = build_dota_files(&rows);
+        assert_eq!(files.len(), 3);
+
+        assert_eq!(files[0].0, "labelTxt/img1.txt");
+        assert_eq!(
+            files[0].1,
+            "0.0 0.0 4.0 0.0 4.0 2.0 0.0 2.0 small-vehicle 0\n3.0 -1.0 3.0 3.0 1.0 3.0 1.0 -1.0 plane 0\n"
+        );
+
+        // Duplicate image names are disambiguated and tasks without boxes get an empty file
+        assert_eq!(files[1].0, format!("labelTxt/{}_img1.txt", second));
+        assert_eq!(files[1].1, "");
+        assert_eq!(files[2].0, "labelTxt/img2.txt");
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_export_project_dota_unauthorized() {
+        let pool = test_utils::setup_test_db().await;
+        let oauth_config = OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/export/dota", web::get().to(export_project_dota))
+        ).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/export/dota", Uuid::new_v4()))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 401);
+    }
+}
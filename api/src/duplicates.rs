@@ -0,0 +1,415 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use image::imageops::FilterType;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Postgres};
+use std::collections::HashMap;
+use uuid::Uuid;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::auth::{JwtManager, Claims};
+
+/// Bits two perceptual hashes may differ in for the images to count as near-identical
+const DEFAULT_MAX_DISTANCE: u32 = 4;
+const MAX_DISTANCE_LIMIT: u32 = 16;
+
+/// Hashes of an image file, stored on its task
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageHashes {
+    /// SHA-256 of the file in hex
+    pub content_hash: String,
+    /// Difference hash of the decoded image
+    pub perceptual_hash: i64,
+}
+
+impl ImageHashes {
+    pub fn new(data: &[u8], image: &image::DynamicImage) -> Self {
+        Self {
+            content_hash: content_hash(data),
+            perceptual_hash: perceptual_hash(image),
+        }
+    }
+}
+
+pub fn content_hash(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// 64-bit difference hash: the image is shrunk to 9x8 grays and every bit tells whether a
+/// pixel is darker than its right neighbour. Re-encoding, resizing and small edits flip only a
+/// few bits, so near-identical images end up a small Hamming distance apart.
+pub fn perceptual_hash(image: &image::DynamicImage) -> i64 {
+    let small = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y)[0] < small.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash as i64
+}
+
+pub fn hamming_distance(a: i64, b: i64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DuplicatesQuery {
+    /// Bits the perceptual hashes of near-identical images may differ in, 4 by default and at most 16
+    pub max_distance: Option<u32>,
+}
+
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct DuplicateTask {
+    pub id: Uuid,
+    pub name: String,
+    pub resource_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DuplicateGroup {
+    pub tasks: Vec<DuplicateTask>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DuplicatesReport {
+    /// Tasks of byte-identical files
+    pub exact: Vec<DuplicateGroup>,
+    /// Tasks of different files showing near-identical images, one task per set of exact duplicates
+    pub similar: Vec<DuplicateGroup>,
+    pub max_distance: u32,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct HashedTask {
+    id: Uuid,
+    name: String,
+    resource_url: Option<String>,
+    content_hash: Option<String>,
+    perceptual_hash: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/duplicates",
+    tag = "tasks",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        DuplicatesQuery,
+    ),
+    responses(
+        (status = 200, description = "Groups of duplicate tasks among the ones created by a sync", body = DuplicatesReport),
+        (status = 400, description = "Invalid max_distance", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn get_duplicates(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<DuplicatesQuery>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let max_distance = query.max_distance.unwrap_or(DEFAULT_MAX_DISTANCE);
+    if max_distance > MAX_DISTANCE_LIMIT {
+        return HttpResponse::BadRequest().json(format!("max_distance must be at most {}", MAX_DISTANCE_LIMIT));
+    }
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let tasks = match get_hashed_tasks(&pool, project_id).await {
+        Ok(tasks) => tasks,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch tasks"),
+    };
+
+    let (exact, similar) = group_duplicates(&tasks, max_distance);
+    let to_groups = |groups: Vec<Vec<usize>>| -> Vec<DuplicateGroup> {
+        groups
+            .into_iter()
+            .map(|group| DuplicateGroup {
+                tasks: group
+                    .into_iter()
+                    .map(|index| DuplicateTask {
+                        id: tasks[index].id,
+                        name: tasks[index].name.clone(),
+                        resource_url: tasks[index].resource_url.clone(),
+                    })
+                    .collect(),
+            })
+            .collect()
+    };
+
+    HttpResponse::Ok().json(DuplicatesReport {
+        exact: to_groups(exact),
+        similar: to_groups(similar),
+        max_distance,
+    })
+}
+
+/// Indices of exact duplicates grouped by content hash, and of near-identical images grouped
+/// by perceptual hash. Images are compared pairwise, which stays fast up to tens of thousands
+/// of distinct images.
+fn group_duplicates(tasks: &[HashedTask], max_distance: u32) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+    let mut by_content: HashMap<&str, Vec<usize>> = HashMap::new();
+    // One task per distinct file takes part in the near-identical comparison
+    let mut representatives: Vec<(usize, i64)> = Vec::new();
+    for (index, task) in tasks.iter().enumerate() {
+        let is_first = match &task.content_hash {
+            Some(hash) => {
+                let group = by_content.entry(hash.as_str()).or_default();
+                group.push(index);
+                group.len() == 1
+            }
+            None => true,
+        };
+        if let (true, Some(perceptual_hash)) = (is_first, task.perceptual_hash) {
+            representatives.push((index, perceptual_hash));
+        }
+    }
+
+    let mut exact: Vec<Vec<usize>> = by_content.into_values().filter(|group| group.len() > 1).collect();
+    exact.sort();
+
+    // Union-find over the representatives, so chains of close images form one group
+    let mut parents: Vec<usize> = (0..representatives.len()).collect();
+    fn root(parents: &mut [usize], mut node: usize) -> usize {
+        while parents[node] != node {
+            parents[node] = parents[parents[node]];
+            node = parents[node];
+        }
+        node
+    }
+    for (a, (_, hash_a)) in representatives.iter().enumerate() {
+        for (b, (_, hash_b)) in representatives.iter().enumerate().skip(a + 1) {
+            if hamming_distance(*hash_a, *hash_b) <= max_distance {
+                let (root_a, root_b) = (root(&mut parents, a), root(&mut parents, b));
+                parents[root_b] = root_a;
+            }
+        }
+    }
+
+    let mut by_root: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (node, (index, _)) in representatives.iter().enumerate() {
+        let group_root = root(&mut parents, node);
+        by_root.entry(group_root).or_default().push(*index);
+    }
+    let mut similar: Vec<Vec<usize>> = by_root.into_values().filter(|group| group.len() > 1).collect();
+    similar.sort();
+
+    (exact, similar)
+}
+
+async fn get_hashed_tasks(pool: &Pool<Postgres>, project_id: Uuid) -> Result<Vec<HashedTask>, sqlx::Error> {
+    sqlx::query_as::<_, HashedTask>(
+        r#"
+        SELECT id, name, resource_url, content_hash, perceptual_hash
+        FROM tasks
+        WHERE project_id = $1 AND (content_hash IS NOT NULL OR perceptual_hash IS NOT NULL)
+        ORDER BY created_at ASC, name ASC
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Whether a task of the project was already created from a file with these exact bytes
+pub async fn content_hash_exists(pool: &Pool<Postgres>, project_id: Uuid, content_hash: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM tasks WHERE project_id = $1 AND content_hash = $2)"
+    )
+    .bind(project_id)
+    .bind(content_hash)
+    .fetch_one(pool)
+    .await
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use image::{DynamicImage, GrayImage, Luma};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    fn gradient(width: u32, height: u32, offset: u8) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(width, height, |x, _| {
+            Luma([((x * 255 / width) as u8).saturating_add(offset)])
+        }))
+    }
+
+    fn hashed(name: &str, content_hash: Option<&str>, perceptual_hash: Option<i64>) -> HashedTask {
+        HashedTask {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            resource_url: None,
+            content_hash: content_hash.map(str::to_string),
+            perceptual_hash,
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_perceptual_hash_survives_resizing() {
+        let original = perceptual_hash(&gradient(640, 480, 0));
+        let resized = perceptual_hash(&gradient(320, 240, 0));
+        let brighter = perceptual_hash(&gradient(640, 480, 10));
+        let mirrored = perceptual_hash(&gradient(640, 480, 0).fliph());
+
+        assert!(hamming_distance(original, resized) <= DEFAULT_MAX_DISTANCE);
+        assert!(hamming_distance(original, brighter) <= DEFAULT_MAX_DISTANCE);
+        assert!(hamming_distance(original, mirrored) > DEFAULT_MAX_DISTANCE);
+    }
+
+    #[actix_web::test]
+    async fn test_content_hash() {
+        assert_eq!(content_hash(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+
+    #[actix_web::test]
+    async fn test_group_duplicates() {
+        let tasks = vec![
+            hashed("a.jpg", Some("aaa"), Some(0b0000)),
+            hashed("a_copy.jpg", Some("aaa"), Some(0b0000)),
+            hashed("a_resized.jpg", Some("bbb"), Some(0b0011)),
+            hashed("other.jpg", Some("ccc"), Some(-1)),
+            hashed("unhashed.jpg", None, None),
+        ];
+
+        let (exact, similar) = group_duplicates(&tasks, 2);
+        assert_eq!(exact, vec![vec![0, 1]]);
+        // The copy is reported as an exact duplicate only
+        assert_eq!(similar, vec![vec![0, 2]]);
+
+        let (_, similar) = group_duplicates(&tasks, 0);
+        assert!(similar.is_empty());
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_get_duplicates() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, user.id).await.unwrap();
+        for (name, content_hash, perceptual_hash) in [
+            ("a.jpg", "aaa", 0i64),
+            ("a_copy.jpg", "aaa", 0),
+            ("a_resized.jpg", "bbb", 1),
+            ("other.jpg", "ccc", -1),
+        ] {
+            let task = crate::tasks::create_task_in_db(&pool, project.id, name, None).await.unwrap();
+            sqlx::query("UPDATE tasks SET content_hash = $1, perceptual_hash = $2 WHERE id = $3")
+                .bind(content_hash)
+                .bind(perceptual_hash)
+                .bind(task.id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        assert!(content_hash_exists(&pool, project.id, "aaa").await.unwrap());
+        assert!(!content_hash_exists(&pool, project.id, "ddd").await.unwrap());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/duplicates", web::get().to(get_duplicates))
+        ).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/duplicates", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let report: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(report["exact"].as_array().unwrap().len(), 1);
+        assert_eq!(report["exact"][0]["tasks"].as_array().unwrap().len(), 2);
+        assert_eq!(report["similar"][0]["tasks"][1]["name"], "a_resized.jpg");
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/duplicates?max_distance=64", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+}
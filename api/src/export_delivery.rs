@@ -0,0 +1,137 @@
+use actix_web::HttpResponse;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+use crate::storage::config::StorageConfig;
+use crate::storage::factory::create_storage_provider;
+use crate::storage::StorageProvider;
+
+/// Where an export is written instead of being sent back: a folder of the project storage, or of
+/// another bucket the project credentials can write to
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportDestination {
+    /// Folder of the object, empty for the root of the bucket
+    pub prefix: String,
+    pub bucket: Option<String>,
+}
+
+impl ExportDestination {
+    /// From the `destination` and `destination_bucket` of a delivery request, `None` when the
+    /// export is a plain download
+    pub fn from_query(destination: Option<&str>, bucket: Option<&str>) -> Result<Option<Self>, String> {
+        let Some(destination) = destination else {
+            return match bucket {
+                Some(_) => Err("destination_bucket requires a destination".to_string()),
+                None => Ok(None),
+            };
+        };
+
+        let prefix = destination.trim().trim_matches('/');
+        let invalid_segment = !prefix.is_empty() && prefix.split('/').any(|segment| matches!(segment, "" | "." | ".."));
+        if invalid_segment || prefix.contains('\\') {
+            return Err("Invalid destination. Use a folder path such as exports/weekly".to_string());
+        }
+
+        let bucket = match bucket.map(str::trim) {
+            Some(bucket) if bucket.is_empty() || bucket.contains('/') => {
+                return Err("Invalid destination bucket".to_string());
+            }
+            bucket => bucket.map(str::to_string),
+        };
+
+        Ok(Some(Self { prefix: prefix.to_string(), bucket }))
+    }
+
+    /// Storage key of the export file
+    pub fn key(&self, file_name: &str) -> String {
+        if self.prefix.is_empty() {
+            file_name.to_string()
+        } else {
+            format!("{}/{}", self.prefix, file_name)
+        }
+    }
+}
+
+/// Export written to storage
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ExportDelivery {
+    /// Object key in the bucket
+    pub key: String,
+    /// Location reported by the storage provider, e.g. `s3://bucket/key`
+    pub location: String,
+    /// The other bucket written to, `None` for the project storage
+    pub bucket: Option<String>,
+    pub content_type: String,
+    pub size_bytes: u64,
+}
+
+/// Uploads the export to its destination and answers with where it went. `project_storage` is
+/// used as is when the destination is in the project bucket.
+pub async fn deliver_export(
+    project: &crate::projects::Project,
+    project_storage: Arc<dyn StorageProvider>,
+    destination: &ExportDestination,
+    file_name: &str,
+    data: Vec<u8>,
+    content_type: &str,
+) -> HttpResponse {
+    let storage = match &destination.bucket {
+        Some(bucket) => match storage_for_bucket(project, bucket).await {
+            Ok(storage) => storage,
+            Err(e) => return HttpResponse::BadRequest().json(e),
+        },
+        None => project_storage,
+    };
+
+    let key = destination.key(file_name);
+    match storage.upload(&key, &data, Some(content_type)).await {
+        Ok(location) => HttpResponse::Created().json(ExportDelivery {
+            key,
+            location,
+            bucket: destination.bucket.clone(),
+            content_type: content_type.to_string(),
+            size_bytes: data.len() as u64,
+        }),
+        Err(e) => {
+            eprintln!("Failed to deliver export to {}: {}", key, e);
+            HttpResponse::BadGateway().json(format!("Failed to write the export to storage: {}", e))
+        }
+    }
+}
+
+/// Provider for another bucket with the credentials of the project storage
+pub(crate) async fn storage_for_bucket(project: &crate::projects::Project, bucket: &str) -> Result<Arc<dyn StorageProvider>, String> {
+    let config: StorageConfig = project
+        .storage_config
+        .clone()
+        .ok_or_else(|| "No storage configuration found for project".to_string())
+        .and_then(|config| serde_json::from_value(config).map_err(|e| format!("Invalid storage configuration: {}", e)))?;
+
+    let config = config.with_bucket(bucket)?;
+    create_storage_provider(&config)
+        .await
+        .map_err(|e| format!("Storage not available: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_destination_from_query() {
+        assert_eq!(ExportDestination::from_query(None, None).unwrap(), None);
+
+        let destination = ExportDestination::from_query(Some("/exports/weekly/"), None).unwrap().unwrap();
+        assert_eq!(destination.key("coco.json"), "exports/weekly/coco.json");
+
+        let destination = ExportDestination::from_query(Some(""), Some("datasets")).unwrap().unwrap();
+        assert_eq!(destination.key("coco.json"), "coco.json");
+        assert_eq!(destination.bucket.as_deref(), Some("datasets"));
+
+        assert!(ExportDestination::from_query(Some("exports/../secrets"), None).is_err());
+        assert!(ExportDestination::from_query(Some("exports//weekly"), None).is_err());
+        assert!(ExportDestination::from_query(Some("exports"), Some("a/b")).is_err());
+        assert!(ExportDestination::from_query(None, Some("datasets")).is_err());
+    }
+}
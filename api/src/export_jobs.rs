@@ -0,0 +1,355 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use actix_web::body::MessageBody;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::auth::{JwtManager, Claims};
+use crate::export_delivery::{storage_for_bucket, ExportDelivery};
+use crate::storage::StorageProvider;
+use crate::storage::factory::create_storage_provider_from_project;
+
+/// Seconds the download links of the list stay valid
+const DOWNLOAD_LINK_SECS: u64 = 60 * 60;
+
+/// Who hears about finished exports besides the app that started them, read from the environment
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportNotifyConfig {
+    /// Receives a JSON `POST` for every large export that completes, e.g. a chat or mail relay
+    pub webhook_url: Option<String>,
+    /// Exports from this size on count as large
+    pub min_bytes: u64,
+}
+
+impl Default for ExportNotifyConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            min_bytes: 100 * 1024 * 1024,
+        }
+    }
+}
+
+impl ExportNotifyConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.trim().is_empty());
+
+        let min_bytes = match var("EXPORT_NOTIFY_MIN_BYTES") {
+            Some(bytes) => bytes
+                .trim()
+                .parse()
+                .map_err(|_| format!("EXPORT_NOTIFY_MIN_BYTES must be a number, got {}", bytes))?,
+            None => Self::default().min_bytes,
+        };
+
+        Ok(Self {
+            webhook_url: var("EXPORT_WEBHOOK_URL"),
+            min_bytes,
+        })
+    }
+}
+
+/// An export written to storage, in the background or while its request waited
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct ExportJob {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub requested_by: Option<Uuid>,
+    /// One of `EXPORT_FORMATS`
+    pub format: String,
+    /// `running`, `completed` or `failed`
+    pub status: String,
+    /// Object key of the file once completed
+    pub storage_key: Option<String>,
+    /// The other bucket written to, `None` for the project storage
+    pub bucket: Option<String>,
+    /// Location reported by the storage provider, e.g. `s3://bucket/key`
+    pub location: Option<String>,
+    pub content_type: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Presigned link to the file of a completed export, valid for an hour
+    #[sqlx(skip)]
+    pub download_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExportJobsListResponse {
+    pub exports: Vec<ExportJob>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportJobsQuery {
+    /// Newest exports to list, 20 by default and 100 at most
+    pub limit: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/exports",
+    tag = "export",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ExportJobsQuery,
+    ),
+    responses(
+        (status = 200, description = "Exports written to storage, newest first, the running ones included", body = ExportJobsListResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn list_project_exports(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<ExportJobsQuery>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    if !crate::cache::user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let mut exports = match get_project_exports(&pool, project_id, limit).await {
+        Ok(exports) => exports,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch exports"),
+    };
+    add_download_links(&pool, project_id, user_id, &mut exports).await;
+
+    HttpResponse::Ok().json(ExportJobsListResponse { exports })
+}
+
+/// Presigned links to the files of the completed exports. A storage that can't be reached
+/// leaves the links out rather than failing the list. Links into other buckets are only for
+/// owners and admins, every member reads the project bucket anyway.
+async fn add_download_links(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid, exports: &mut [ExportJob]) {
+    if !exports.iter().any(|export| export.storage_key.is_some()) {
+        return;
+    }
+    let project = match get_project_by_id(pool, project_id).await {
+        Ok(Some(project)) => project,
+        _ => return,
+    };
+    let manages_storage = crate::projects::user_can_manage_storage(pool, project_id, user_id).await.unwrap_or(false);
+
+    let mut storages: HashMap<Option<String>, Option<Arc<dyn StorageProvider>>> = HashMap::new();
+    for export in exports.iter_mut() {
+        let Some(key) = export.storage_key.as_deref() else {
+            continue;
+        };
+        if export.bucket.is_some() && !manages_storage {
+            continue;
+        }
+        if !storages.contains_key(&export.bucket) {
+            let storage = match &export.bucket {
+                Some(bucket) => storage_for_bucket(&project, bucket).await.ok(),
+                None => create_storage_provider_from_project(&project).await.ok(),
+            };
+            storages.insert(export.bucket.clone(), storage);
+        }
+        if let Some(Some(storage)) = storages.get(&export.bucket) {
+            export.download_url = storage.get_presigned_url(key, DOWNLOAD_LINK_SECS).await.ok();
+        }
+    }
+}
+
+/// Records an export that is about to be written to storage
+pub(crate) async fn record_export_start(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    user_id: Uuid,
+    format: &str,
+) -> Result<ExportJob, sqlx::Error> {
+    sqlx::query_as::<_, ExportJob>(
+        r#"
+        INSERT INTO project_exports (project_id, requested_by, format)
+        VALUES ($1, $2, $3)
+        RETURNING id, project_id, requested_by, format, status, storage_key, bucket, location, content_type, size_bytes, error, created_at, completed_at
+        "#
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .bind(format)
+    .fetch_one(pool)
+    .await
+}
+
+/// Records how the export of `job_id` went from the response of its export endpoint, which is
+/// handed back for the request that waited for it. Large exports are announced to the webhook.
+pub(crate) async fn finish_export_job(
+    pool: &Pool<Postgres>,
+    notify_config: &ExportNotifyConfig,
+    job_id: Uuid,
+    response: HttpResponse,
+) -> HttpResponse {
+    let status = response.status();
+    let content_type = response.headers().get(actix_web::http::header::CONTENT_TYPE).cloned();
+    let body = response.into_body().try_into_bytes().unwrap_or_default();
+
+    let outcome = if status.is_success() {
+        serde_json::from_slice::<ExportDelivery>(&body).map_err(|e| format!("Unreadable export result: {}", e))
+    } else {
+        // Export endpoints answer failures with a JSON string
+        Err(serde_json::from_slice::<String>(&body).unwrap_or_else(|_| String::from_utf8_lossy(&body).to_string()))
+    };
+
+    match record_export_finish(pool, job_id, &outcome).await {
+        Ok(job) if job.status == "completed" => notify_export_completed(notify_config, &job).await,
+        Ok(_) => {}
+        Err(e) => eprintln!("Failed to record the end of export {}: {}", job_id, e),
+    }
+
+    let mut rebuilt = HttpResponse::build(status);
+    if let Some(content_type) = content_type {
+        rebuilt.insert_header((actix_web::http::header::CONTENT_TYPE, content_type));
+    }
+    rebuilt.body(body)
+}
+
+async fn record_export_finish(
+    pool: &Pool<Postgres>,
+    job_id: Uuid,
+    outcome: &Result<ExportDelivery, String>,
+) -> Result<ExportJob, sqlx::Error> {
+    let query = match outcome {
+        Ok(delivery) => sqlx::query_as::<_, ExportJob>(
+            r#"
+            UPDATE project_exports
+            SET status = 'completed', storage_key = $2, bucket = $3, location = $4, content_type = $5, size_bytes = $6, completed_at = NOW()
+            WHERE id = $1
+            RETURNING id, project_id, requested_by, format, status, storage_key, bucket, location, content_type, size_bytes, error, created_at, completed_at
+            "#
+        )
+        .bind(job_id)
+        .bind(&delivery.key)
+        .bind(&delivery.bucket)
+        .bind(&delivery.location)
+        .bind(&delivery.content_type)
+        .bind(delivery.size_bytes as i64),
+        Err(error) => sqlx::query_as::<_, ExportJob>(
+            r#"
+            UPDATE project_exports
+            SET status = 'failed', error = $2, completed_at = NOW()
+            WHERE id = $1
+            RETURNING id, project_id, requested_by, format, status, storage_key, bucket, location, content_type, size_bytes, error, created_at, completed_at
+            "#
+        )
+        .bind(job_id)
+        .bind(error),
+    };
+    query.fetch_one(pool).await
+}
+
+/// Posts a completed large export to the webhook. A webhook that fails is logged, the export
+/// is done either way.
+async fn notify_export_completed(config: &ExportNotifyConfig, job: &ExportJob) {
+    let Some(url) = &config.webhook_url else {
+        return;
+    };
+    if job.size_bytes.unwrap_or(0) < config.min_bytes as i64 {
+        return;
+    }
+
+    let payload = json!({
+        "event": "export.completed",
+        "export": job,
+    });
+    match reqwest::Client::new().post(url).json(&payload).send().await {
+        Ok(response) if response.status().is_success() => {}
+        Ok(response) => eprintln!("Export webhook answered {} for export {}", response.status(), job.id),
+        Err(e) => eprintln!("Failed to call the export webhook for export {}: {}", job.id, e),
+    }
+}
+
+/// Fails the exports a stopped server left 'running', their jobs are gone
+pub async fn fail_interrupted_exports(pool: &Pool<Postgres>) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE project_exports
+        SET status = 'failed', error = 'Interrupted by a server restart', completed_at = NOW()
+        WHERE status = 'running'
+        "#
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+async fn get_project_exports(pool: &Pool<Postgres>, project_id: Uuid, limit: i64) -> Result<Vec<ExportJob>, sqlx::Error> {
+    sqlx::query_as::<_, ExportJob>(
+        r#"
+        SELECT id, project_id, requested_by, format, status, storage_key, bucket, location, content_type, size_bytes, error, created_at, completed_at
+        FROM project_exports
+        WHERE project_id = $1
+        ORDER BY created_at DESC
+        LIMIT $2
+        "#
+    )
+    .bind(project_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_project_by_id(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Option<crate::projects::Project>, sqlx::Error> {
+    sqlx::query_as::<_, crate::projects::Project>(
+        "SELECT id, name, description, storage_config, owner_id, task_type, created_at, updated_at FROM projects WHERE id = $1"
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
@@ -0,0 +1,591 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+use crate::auth::{JwtManager, Claims};
+
+/// Formats with an `/export/{format}` endpoint
+pub const EXPORT_FORMATS: [&str; 7] = ["coco", "csv", "labelstudio", "dota", "classification", "mot", "kitti"];
+
+/// Formats writing the boxes of detection projects
+const DETECTION_FORMATS: [&str; 6] = ["coco", "csv", "labelstudio", "dota", "mot", "kitti"];
+/// Formats writing the labels of classification projects
+const CLASSIFICATION_FORMATS: [&str; 1] = ["classification"];
+
+/// Formats that can export projects of `task_type`
+pub fn formats_for_task_type(task_type: &str) -> &'static [&'static str] {
+    if task_type == crate::projects::TASK_TYPE_CLASSIFICATION {
+        &CLASSIFICATION_FORMATS
+    } else {
+        &DETECTION_FORMATS
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct ExportPreset {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    /// One of `EXPORT_FORMATS`
+    pub format: String,
+    /// An `ExportOptions` object
+    pub options: serde_json::Value,
+    /// The preset the export section starts with, at most one per project
+    pub is_default: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Settings of an export, sent as the query parameters of its endpoint. Formats ignore the
+/// settings they don't have.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExportOptions {
+    /// Package the images with the annotations as a ZIP
+    #[serde(default)]
+    pub include_images: bool,
+    /// Add the annotator, timestamps and review state of each annotation
+    #[serde(default)]
+    pub include_metadata: bool,
+    /// Only export the tasks of these splits, every task when empty
+    #[serde(default)]
+    pub splits: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ExportPresetRequest {
+    pub name: String,
+    pub format: String,
+    #[serde(default)]
+    pub options: ExportOptions,
+    /// Make this the project's default preset, replacing the previous one
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExportPresetsListResponse {
+    pub presets: Vec<ExportPreset>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{id}/export-presets",
+    tag = "export",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 200, description = "Presets of the project by name", body = ExportPresetsListResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn list_export_presets(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    if !crate::cache::user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match get_export_presets(&pool, project_id).await {
+        Ok(presets) => HttpResponse::Ok().json(ExportPresetsListResponse { presets }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch export presets"),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{id}/export-presets",
+    tag = "export",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    request_body = ExportPresetRequest,
+    responses(
+        (status = 201, body = ExportPreset),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+        (status = 409, description = "Preset name already exists", body = String),
+    ),
+)]
+pub async fn create_export_preset(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<ExportPresetRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    if let Err(e) = validate_preset(&payload) {
+        return HttpResponse::BadRequest().json(e);
+    }
+
+    if !crate::cache::user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    if let Err(response) = check_format_fits_project(&pool, project_id, &payload.format).await {
+        return response;
+    }
+
+    match save_export_preset_in_db(&pool, project_id, None, &payload, user_id).await {
+        Ok(Some(preset)) => HttpResponse::Created().json(preset),
+        Ok(None) => HttpResponse::InternalServerError().json("Failed to create export preset"),
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+            HttpResponse::Conflict().json("Preset name already exists")
+        }
+        Err(_) => HttpResponse::InternalServerError().json("Failed to create export preset"),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/projects/{id}/export-presets/{preset_id}",
+    tag = "export",
+    params(
+        ("id" = Uuid, Path, description = "Project ID"),
+        ("preset_id" = Uuid, Path, description = "Export preset ID"),
+    ),
+    request_body = ExportPresetRequest,
+    responses(
+        (status = 200, body = ExportPreset),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or preset not found", body = String),
+        (status = 409, description = "Preset name already exists", body = String),
+    ),
+)]
+pub async fn update_export_preset(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    payload: web::Json<ExportPresetRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, preset_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let preset_id = match Uuid::parse_str(&preset_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid preset ID"),
+    };
+
+    if let Err(e) = validate_preset(&payload) {
+        return HttpResponse::BadRequest().json(e);
+    }
+
+    if !crate::cache::user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    if let Err(response) = check_format_fits_project(&pool, project_id, &payload.format).await {
+        return response;
+    }
+
+    match save_export_preset_in_db(&pool, project_id, Some(preset_id), &payload, user_id).await {
+        Ok(Some(preset)) => HttpResponse::Ok().json(preset),
+        Ok(None) => HttpResponse::NotFound().json("Export preset not found"),
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+            HttpResponse::Conflict().json("Preset name already exists")
+        }
+        Err(_) => HttpResponse::InternalServerError().json("Failed to update export preset"),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/projects/{id}/export-presets/{preset_id}",
+    tag = "export",
+    params(
+        ("id" = Uuid, Path, description = "Project ID"),
+        ("preset_id" = Uuid, Path, description = "Export preset ID"),
+    ),
+    responses(
+        (status = 204, description = "Export preset deleted"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or preset not found", body = String),
+    ),
+)]
+pub async fn delete_export_preset(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, preset_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let preset_id = match Uuid::parse_str(&preset_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid preset ID"),
+    };
+
+    if !crate::cache::user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match delete_export_preset_from_db(&pool, preset_id, project_id).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => HttpResponse::NotFound().json("Export preset not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to delete export preset"),
+    }
+}
+
+/// Checks the name, the format and the splits of the options
+fn validate_preset(payload: &ExportPresetRequest) -> Result<(), String> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err("Preset name cannot be empty".to_string());
+    }
+    if name.len() > 255 {
+        return Err("Preset name too long (max 255 characters)".to_string());
+    }
+    if !EXPORT_FORMATS.contains(&payload.format.as_str()) {
+        return Err(format!("Invalid format. Must be one of: {}", EXPORT_FORMATS.join(", ")));
+    }
+    if let Some(split) = payload.options.splits.iter().find(|split| !crate::tasks::SPLITS.contains(&split.as_str())) {
+        return Err(format!("Invalid split '{}'. Must be one of: {}", split, crate::tasks::SPLITS.join(", ")));
+    }
+    Ok(())
+}
+
+/// Refuses formats that can't export the project's task type
+async fn check_format_fits_project(pool: &Pool<Postgres>, project_id: Uuid, format: &str) -> Result<(), HttpResponse> {
+    match crate::projects::get_project_task_type(pool, project_id).await {
+        Ok(Some(task_type)) => {
+            let formats = formats_for_task_type(&task_type);
+            if formats.contains(&format) {
+                Ok(())
+            } else {
+                Err(HttpResponse::BadRequest().json(format!(
+                    "Format '{}' can't export {} projects. Must be one of: {}",
+                    format, task_type, formats.join(", ")
+                )))
+            }
+        }
+        Ok(None) => Err(HttpResponse::NotFound().json("Project not found")),
+        Err(_) => Err(HttpResponse::InternalServerError().json("Failed to fetch project")),
+    }
+}
+
+async fn get_export_presets(pool: &Pool<Postgres>, project_id: Uuid) -> Result<Vec<ExportPreset>, sqlx::Error> {
+    sqlx::query_as::<_, ExportPreset>(
+        r#"
+        SELECT id, project_id, name, format, options, is_default, created_by, created_at, updated_at
+        FROM export_presets
+        WHERE project_id = $1
+        ORDER BY name
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Creates the preset, or replaces `preset_id` of the project. A new default preset takes over
+/// from the previous one. `None` when `preset_id` is not a preset of the project.
+async fn save_export_preset_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    preset_id: Option<Uuid>,
+    preset: &ExportPresetRequest,
+    user_id: Uuid,
+) -> Result<Option<ExportPreset>, sqlx::Error> {
+    let options = serde_json::to_value(&preset.options).unwrap_or_else(|_| serde_json::json!({}));
+    let mut tx = pool.begin().await?;
+
+    if preset.is_default {
+        sqlx::query("UPDATE export_presets SET is_default = FALSE, updated_at = NOW() WHERE project_id = $1 AND is_default")
+            .bind(project_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let saved = match preset_id {
+        Some(preset_id) => {
+            sqlx::query_as::<_, ExportPreset>(
+                r#"
+                UPDATE export_presets
+                SET name = $3, format = $4, options = $5, is_default = $6, updated_at = NOW()
+                WHERE id = $1 AND project_id = $2
+                RETURNING id, project_id, name, format, options, is_default, created_by, created_at, updated_at
+                "#
+            )
+            .bind(preset_id)
+            .bind(project_id)
+            .bind(preset.name.trim())
+            .bind(&preset.format)
+            .bind(&options)
+            .bind(preset.is_default)
+            .fetch_optional(&mut *tx)
+            .await?
+        }
+        None => {
+            sqlx::query_as::<_, ExportPreset>(
+                r#"
+                INSERT INTO export_presets (id, project_id, name, format, options, is_default, created_by, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, NOW(), NOW())
+                RETURNING id, project_id, name, format, options, is_default, created_by, created_at, updated_at
+                "#
+            )
+            .bind(Uuid::new_v4())
+            .bind(project_id)
+            .bind(preset.name.trim())
+            .bind(&preset.format)
+            .bind(&options)
+            .bind(preset.is_default)
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?
+        }
+    };
+
+    // Leave the previous default alone when there was nothing to update
+    if saved.is_some() {
+        tx.commit().await?;
+    }
+    Ok(saved)
+}
+
+async fn delete_export_preset_from_db(pool: &Pool<Postgres>, preset_id: Uuid, project_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM export_presets WHERE id = $1 AND project_id = $2")
+        .bind(preset_id)
+        .bind(project_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    fn preset(name: &str, is_default: bool) -> ExportPresetRequest {
+        ExportPresetRequest {
+            name: name.to_string(),
+            format: "coco".to_string(),
+            options: ExportOptions { include_images: true, include_metadata: false, splits: vec!["train".to_string()] },
+            is_default,
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_formats_for_task_type() {
+        assert_eq!(formats_for_task_type(crate::projects::TASK_TYPE_CLASSIFICATION), ["classification"]);
+        let detection = formats_for_task_type(crate::projects::TASK_TYPE_DETECTION);
+        assert!(detection.contains(&"mot") && detection.contains(&"kitti"));
+        assert!(!detection.contains(&"classification"));
+        // Every format belongs to some task type
+        assert!(EXPORT_FORMATS.iter().all(|format| detection.contains(format) || *format == "classification"));
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_export_presets_crud_keeps_one_default() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, user.id).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{id}/export-presets", web::get().to(list_export_presets))
+                .route("/projects/{id}/export-presets", web::post().to(create_export_preset))
+                .route("/projects/{id}/export-presets/{preset_id}", web::put().to(update_export_preset))
+                .route("/projects/{id}/export-presets/{preset_id}", web::delete().to(delete_export_preset))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/export-presets", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(preset("Weekly training set", true))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let weekly: ExportPreset = test::read_body_json(resp).await;
+        assert_eq!(weekly.options["splits"][0], "train");
+
+        // Same name again
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/export-presets", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(preset("Weekly training set", false))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+        // Unknown splits are refused
+        let mut invalid = preset("Holdout", false);
+        invalid.options.splits = vec!["holdout".to_string()];
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/export-presets", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(invalid)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        // Classification labels can't be exported from a detection project
+        let mut classification = preset("Labels", false);
+        classification.format = "classification".to_string();
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/export-presets", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(classification)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        // A new default replaces the previous one
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/export-presets", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(preset("Audit", true))
+            .to_request();
+        let audit: ExportPreset = test::call_and_read_body_json(&app, req).await;
+        let presets = get_export_presets(&pool, project.id).await.unwrap();
+        let defaults: Vec<_> = presets.iter().filter(|preset| preset.is_default).map(|preset| preset.id).collect();
+        assert_eq!(defaults, vec![audit.id]);
+
+        let mut renamed = preset("Weekly train split", false);
+        renamed.options.include_metadata = true;
+        let req = test::TestRequest::put()
+            .uri(&format!("/projects/{}/export-presets/{}", project.id, weekly.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(renamed)
+            .to_request();
+        let updated: ExportPreset = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(updated.name, "Weekly train split");
+        assert_eq!(updated.options["include_metadata"], true);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/projects/{}/export-presets/{}", project.id, weekly.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/export-presets", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let list: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(list["presets"].as_array().unwrap().len(), 1);
+        assert_eq!(list["presets"][0]["name"], "Audit");
+    }
+}
@@ -0,0 +1,565 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+use crate::auth::{JwtManager, Claims};
+use crate::consensus::agreement::{self, AgreementBox};
+use crate::consensus::types::DEFAULT_IOU_THRESHOLD;
+
+/// Makes a task a gold task, its reference is one of the task's annotations
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetGoldRequest {
+    /// Reference annotation, defaults to the latest annotation of the task
+    pub annotation_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct GoldTask {
+    pub task_id: Uuid,
+    pub task_name: String,
+    pub annotation_id: Uuid,
+    /// Author of the reference annotation, their own saves aren't scored
+    pub reference_by: Option<Uuid>,
+    /// Annotators scored on the task
+    pub annotators: i64,
+    /// Mean of their latest scores, `None` before anyone was scored
+    pub mean_accuracy: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GoldTasksResponse {
+    pub tasks: Vec<GoldTask>,
+}
+
+/// Agreement of an annotation with the reference of its gold task
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoldScore {
+    /// Mean IoU of the boxes matched to a reference box
+    pub mean_iou: f64,
+    /// F1 of the boxes matched to a reference box with the same category, 1 when both are empty
+    pub accuracy: f64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/gold",
+    tag = "consensus",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+    ),
+    responses(
+        (status = 200, body = GoldTasksResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or the user doesn't lead it", body = String),
+    ),
+)]
+pub async fn list_gold_tasks(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Which tasks are gold is kept from annotators, so only leads of the project may see it
+    match user_is_project_lead(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or permission denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check permissions"),
+    }
+
+    match get_gold_tasks(&pool, project_id, None).await {
+        Ok(tasks) => HttpResponse::Ok().json(GoldTasksResponse { tasks }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch gold tasks"),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/projects/{project_id}/tasks/{task_id}/gold",
+    tag = "consensus",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    request_body = SetGoldRequest,
+    responses(
+        (status = 200, body = GoldTask),
+        (status = 400, description = "The task has no such annotation", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found, or the user doesn't lead the project", body = String),
+    ),
+)]
+pub async fn set_gold_task(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    payload: web::Json<SetGoldRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id, task_id) = match parse_task_path(path.into_inner()) {
+        Ok(ids) => ids,
+        Err(response) => return response,
+    };
+
+    match user_is_project_lead(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or permission denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check permissions"),
+    }
+
+    match set_gold_annotation_in_db(&pool, project_id, task_id, payload.annotation_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::BadRequest().json("The task has no such annotation"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to update task"),
+    }
+
+    match get_gold_tasks(&pool, project_id, Some(task_id)).await {
+        Ok(mut tasks) if !tasks.is_empty() => HttpResponse::Ok().json(tasks.remove(0)),
+        Ok(_) => HttpResponse::NotFound().json("Task not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch gold task"),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/projects/{project_id}/tasks/{task_id}/gold",
+    tag = "consensus",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    responses(
+        (status = 204, description = "The task is a regular task again, its scores are kept"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found, or the user doesn't lead the project", body = String),
+    ),
+)]
+pub async fn unset_gold_task(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id, task_id) = match parse_task_path(path.into_inner()) {
+        Ok(ids) => ids,
+        Err(response) => return response,
+    };
+
+    match user_is_project_lead(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or permission denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check permissions"),
+    }
+
+    let result = sqlx::query("UPDATE tasks SET gold_annotation_id = NULL, updated_at = NOW() WHERE id = $1 AND project_id = $2")
+        .bind(task_id)
+        .bind(project_id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => HttpResponse::NoContent().finish(),
+        Ok(_) => HttpResponse::NotFound().json("Task not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to update task"),
+    }
+}
+
+/// Scores boxes against the reference of a gold task. Boxes are matched greedily by IoU like
+/// the agreement report does, and only matches with the reference's category count as correct.
+pub fn score_against_reference(reference: &[AgreementBox], boxes: &[AgreementBox]) -> GoldScore {
+    let matches = agreement::match_boxes(reference, boxes, DEFAULT_IOU_THRESHOLD);
+    let correct = matches
+        .iter()
+        .filter(|&&(i, j, _)| reference[i].category_id == boxes[j].category_id)
+        .count();
+
+    let total = reference.len() + boxes.len();
+    GoldScore {
+        mean_iou: if matches.is_empty() { 0.0 } else { matches.iter().map(|&(_, _, overlap)| overlap).sum::<f64>() / matches.len() as f64 },
+        accuracy: if total == 0 { 1.0 } else { 2.0 * correct as f64 / total as f64 },
+    }
+}
+
+/// Scores a saved annotation when its task is a gold task, replacing an earlier score of the
+/// same annotation. Saves of the reference's author aren't scored.
+pub async fn score_annotation(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
+    annotation_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<GoldScore>, sqlx::Error> {
+    let reference = sqlx::query_as::<_, (Uuid, Option<Uuid>)>(
+        r#"
+        SELECT g.id, g.annotated_by
+        FROM tasks t
+        JOIN annotations g ON g.id = t.gold_annotation_id
+        WHERE t.id = $1
+        "#
+    )
+    .bind(task_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let reference_id = match reference {
+        Some((reference_id, reference_by)) if reference_id != annotation_id && reference_by != Some(user_id) => reference_id,
+        _ => return Ok(None),
+    };
+
+    let reference_boxes = get_annotation_boxes(pool, reference_id).await?;
+    let boxes = get_annotation_boxes(pool, annotation_id).await?;
+    let score = score_against_reference(&reference_boxes, &boxes);
+
+    sqlx::query(
+        r#"
+        INSERT INTO gold_scores (task_id, annotation_id, user_id, mean_iou, accuracy)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (annotation_id) DO UPDATE SET mean_iou = EXCLUDED.mean_iou, accuracy = EXCLUDED.accuracy
+        "#
+    )
+    .bind(task_id)
+    .bind(annotation_id)
+    .bind(user_id)
+    .bind(score.mean_iou)
+    .bind(score.accuracy)
+    .execute(pool)
+    .await?;
+
+    Ok(Some(score))
+}
+
+/// Boxes of an annotation, model suggestions left out
+async fn get_annotation_boxes(pool: &Pool<Postgres>, annotation_id: Uuid) -> Result<Vec<AgreementBox>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, (Option<Uuid>, Vec<f64>)>(
+        "SELECT category_id, bbox FROM image_annotations WHERE annotation_id = $1 AND NOT is_prediction ORDER BY created_at"
+    )
+    .bind(annotation_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter(|(_, bbox)| bbox.len() == 4)
+        .map(|(category_id, bbox)| AgreementBox { category_id, bbox: [bbox[0], bbox[1], bbox[2], bbox[3]] })
+        .collect())
+}
+
+/// Makes the annotation, or the latest one of the task, its reference. `false` when the task
+/// isn't in the project or has no such annotation.
+async fn set_gold_annotation_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    task_id: Uuid,
+    annotation_id: Option<Uuid>,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE tasks t
+        SET gold_annotation_id = (
+            SELECT a.id FROM annotations a
+            WHERE a.task_id = t.id AND ($3::uuid IS NULL OR a.id = $3)
+            ORDER BY a.created_at DESC
+            LIMIT 1
+        ),
+        updated_at = NOW()
+        WHERE t.id = $1 AND t.project_id = $2
+          AND EXISTS (SELECT 1 FROM annotations a WHERE a.task_id = t.id AND ($3::uuid IS NULL OR a.id = $3))
+        "#
+    )
+    .bind(task_id)
+    .bind(project_id)
+    .bind(annotation_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+async fn get_gold_tasks(pool: &Pool<Postgres>, project_id: Uuid, task_id: Option<Uuid>) -> Result<Vec<GoldTask>, sqlx::Error> {
+    sqlx::query_as::<_, GoldTask>(
+        r#"
+        SELECT
+            t.id AS task_id,
+            t.name AS task_name,
+            t.gold_annotation_id AS annotation_id,
+            g.annotated_by AS reference_by,
+            COUNT(latest.user_id) AS annotators,
+            AVG(latest.accuracy) AS mean_accuracy
+        FROM tasks t
+        JOIN annotations g ON g.id = t.gold_annotation_id
+        LEFT JOIN (
+            SELECT DISTINCT ON (gs.task_id, gs.user_id) gs.task_id, gs.user_id, gs.accuracy
+            FROM gold_scores gs
+            ORDER BY gs.task_id, gs.user_id, gs.created_at DESC
+        ) latest ON latest.task_id = t.id
+        WHERE t.project_id = $1 AND ($2::uuid IS NULL OR t.id = $2)
+        GROUP BY t.id, t.name, t.gold_annotation_id, g.annotated_by
+        ORDER BY t.created_at
+        "#
+    )
+    .bind(project_id)
+    .bind(task_id)
+    .fetch_all(pool)
+    .await
+}
+
+fn parse_task_path((project_id, task_id): (String, String)) -> Result<(Uuid, Uuid), HttpResponse> {
+    let project_id = Uuid::parse_str(&project_id).map_err(|_| HttpResponse::BadRequest().json("Invalid project ID"))?;
+    let task_id = Uuid::parse_str(&task_id).map_err(|_| HttpResponse::BadRequest().json("Invalid task ID"))?;
+    Ok((project_id, task_id))
+}
+
+async fn user_is_project_lead(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM project_members pm
+            INNER JOIN projects p ON pm.project_id = p.id
+            WHERE p.id = $1 AND pm.user_id = $2 AND (pm.role IN ('owner', 'admin') OR p.owner_id = $2)
+        )
+        "#
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serde_json::json;
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    fn labeled(category_id: Uuid, bbox: [f64; 4]) -> AgreementBox {
+        AgreementBox { category_id: Some(category_id), bbox }
+    }
+
+    async fn add_project_member(pool: &sqlx::PgPool, project_id: Uuid, email: &str) -> User {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            INSERT INTO users (id, email, name, provider, provider_id)
+            VALUES ($1, $2, 'Annotator', 'google', $3)
+            RETURNING id, email, name, avatar_url, provider, provider_id, created_at, updated_at
+            "#
+        )
+        .bind(Uuid::new_v4())
+        .bind(email)
+        .bind(Uuid::new_v4().to_string())
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+        sqlx::query("INSERT INTO project_members (id, project_id, user_id, role, joined_at) VALUES ($1, $2, $3, 'member', NOW())")
+            .bind(Uuid::new_v4())
+            .bind(project_id)
+            .bind(user.id)
+            .execute(pool)
+            .await
+            .unwrap();
+
+        user
+    }
+
+    #[actix_web::test]
+    async fn test_score_against_reference() {
+        let (car, bike) = (Uuid::new_v4(), Uuid::new_v4());
+        let reference = vec![labeled(car, [0.0, 0.0, 10.0, 10.0]), labeled(bike, [50.0, 50.0, 10.0, 10.0])];
+
+        let perfect = score_against_reference(&reference, &reference);
+        assert_eq!(perfect, GoldScore { mean_iou: 1.0, accuracy: 1.0 });
+
+        // One box right, one with the wrong category and a missed one
+        let boxes = vec![labeled(car, [0.0, 0.0, 10.0, 10.0]), labeled(car, [50.0, 50.0, 10.0, 10.0])];
+        let score = score_against_reference(&reference, &boxes);
+        assert_eq!(score.mean_iou, 1.0);
+        assert_eq!(score.accuracy, 0.5);
+
+        let score = score_against_reference(&reference, &[labeled(car, [0.0, 0.0, 10.0, 20.0])]);
+        assert_eq!(score.mean_iou, 0.5);
+        assert!((score.accuracy - 2.0 / 3.0).abs() < 1e-9);
+
+        assert_eq!(score_against_reference(&reference, &[]).accuracy, 0.0);
+        assert_eq!(score_against_reference(&[], &[]).accuracy, 1.0);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_gold_task_scores_annotators() {
+        let pool = test_utils::setup_test_db().await;
+        let lead = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &lead);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, lead.id).await.unwrap();
+        let annotator = add_project_member(&pool, project.id, "annotator@example.com").await;
+        let annotator_token = create_auth_token(&oauth_config, &annotator);
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "car", None, None, None, None).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "gold.jpg", None).await.unwrap();
+
+        let boxes = |bbox: Vec<f64>| crate::annotations::BoundingBox {
+            category_id: category.id,
+            bbox,
+            area: None,
+            iscrowd: None,
+            is_prediction: None,
+            confidence: None,
+            attributes: None,
+            rotation: None,
+            frame_index: None,
+            track_id: None,
+            is_interpolated: None,
+            mask: None,
+        };
+        let reference = crate::annotations::create_annotation_in_db(&pool, task.id, &[boxes(vec![0.0, 0.0, 10.0, 10.0])], &json!({}), lead.id).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/gold", web::get().to(list_gold_tasks))
+                .route("/projects/{project_id}/tasks/{task_id}/gold", web::put().to(set_gold_task))
+                .route("/projects/{project_id}/tasks/{task_id}/gold", web::delete().to(unset_gold_task))
+        ).await;
+
+        // Annotators can't see or change which tasks are gold
+        let req = test::TestRequest::put()
+            .uri(&format!("/projects/{}/tasks/{}/gold", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", annotator_token)))
+            .set_json(json!({}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/projects/{}/tasks/{}/gold", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(json!({}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let gold: GoldTask = test::read_body_json(resp).await;
+        assert_eq!(gold.annotation_id, reference[0].annotation_id);
+        assert_eq!(gold.annotators, 0);
+
+        // The reference itself isn't scored, the annotator's copy is
+        assert!(score_annotation(&pool, task.id, reference[0].annotation_id, lead.id).await.unwrap().is_none());
+        let copy = crate::annotations::create_annotation_in_db(&pool, task.id, &[boxes(vec![0.0, 0.0, 10.0, 20.0])], &json!({}), annotator.id).await.unwrap();
+        let score = score_annotation(&pool, task.id, copy[0].annotation_id, annotator.id).await.unwrap().unwrap();
+        assert_eq!(score, GoldScore { mean_iou: 0.5, accuracy: 1.0 });
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/gold", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let listed: GoldTasksResponse = test::read_body_json(resp).await;
+        assert_eq!(listed.tasks.len(), 1);
+        assert_eq!(listed.tasks[0].annotators, 1);
+        assert_eq!(listed.tasks[0].mean_accuracy, Some(1.0));
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/projects/{}/tasks/{}/gold", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(json!({ "annotation_id": Uuid::new_v4() }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/projects/{}/tasks/{}/gold", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 204);
+        assert!(score_annotation(&pool, task.id, copy[0].annotation_id, annotator.id).await.unwrap().is_none());
+    }
+}
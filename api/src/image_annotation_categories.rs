@@ -1,12 +1,17 @@
 use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use actix_multipart::Multipart;
+use futures_util::TryStreamExt;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::auth::{JwtManager, Claims};
+use crate::openapi::FileUpload;
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
 pub struct ImageAnnotationCategory {
     pub id: Uuid,
     pub project_id: Uuid,
@@ -15,41 +20,173 @@ pub struct ImageAnnotationCategory {
     pub supercategory: Option<String>,
     pub color: Option<String>,
     pub coco_id: Option<i32>,
+    pub parent_id: Option<Uuid>,
+    pub attribute_schema: serde_json::Value,
+    /// Key that picks the category in the labeling UI, e.g. `Digit1` or `KeyQ`
+    pub hotkey: Option<String>,
     pub image_metadata: serde_json::Value,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateImageAnnotationCategoryRequest {
     pub name: String,
     pub description: Option<String>,
     pub supercategory: Option<String>,
     pub color: Option<String>,
     pub coco_id: Option<i32>,
+    pub parent_id: Option<Uuid>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateImageAnnotationCategoryRequest {
     pub name: String,
     pub description: Option<String>,
     pub supercategory: Option<String>,
     pub color: Option<String>,
     pub coco_id: Option<i32>,
+    /// `None` makes the category a root
+    pub parent_id: Option<Uuid>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateAttributeSchemaRequest {
+    pub attribute_schema: Vec<crate::attributes::AttributeDefinition>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CategoryHotkey {
+    pub category_id: Uuid,
+    pub hotkey: Option<String>,
+}
+
+/// Full set of keyboard shortcuts of a project, categories left out lose their key.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateHotkeysRequest {
+    pub hotkeys: Vec<CategoryHotkey>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ImageAnnotationCategoryResponse {
     pub category: ImageAnnotationCategory,
 }
 
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ImageAnnotationCategoriesListResponse {
     pub categories: Vec<ImageAnnotationCategory>,
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CategoryTreeNode {
+    pub category: ImageAnnotationCategory,
+    #[schema(no_recursion)]
+    pub children: Vec<CategoryTreeNode>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CategoryTreeResponse {
+    pub tree: Vec<CategoryTreeNode>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CategoryTreeQuery {
+    /// Only return the subtree below this category
+    pub root_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BulkCategoryImportQuery {
+    /// What to do with names that already exist: `skip` (default), `update` or `error`
+    pub on_duplicate: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DuplicateMode {
+    Skip,
+    Update,
+    Error,
+}
+
+impl DuplicateMode {
+    pub fn parse(value: Option<&str>) -> Option<Self> {
+        match value {
+            None | Some("skip") => Some(DuplicateMode::Skip),
+            Some("update") => Some(DuplicateMode::Update),
+            Some("error") => Some(DuplicateMode::Error),
+            Some(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkCategoryImportResult {
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeleteCategoryQuery {
+    /// Move the category's annotations to this category before deleting it
+    pub reassign_to: Option<Uuid>,
+    /// Delete even though annotations still use the category, leaving them uncategorized
+    pub force: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CategoryDeleteResult {
+    pub affected_annotations: i64,
+    pub reassigned_to: Option<Uuid>,
+}
+
+enum CategoryDeleteOutcome {
+    Deleted(CategoryDeleteResult),
+    InUse(i64),
+    NotFound,
+    ReassignTargetNotFound,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CategoryMergeResult {
+    pub target: ImageAnnotationCategory,
+    pub reassigned_annotations: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkRelabelRequest {
+    pub from_category_ids: Vec<Uuid>,
+    pub to_category_id: Uuid,
+    /// Only relabel annotations of these tasks, all project tasks when omitted
+    pub task_ids: Option<Vec<Uuid>>,
+    pub min_area: Option<f64>,
+    pub max_area: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkRelabelResult {
+    pub relabeled: u64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/image-annotation-categories",
+    tag = "categories",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    request_body = CreateImageAnnotationCategoryRequest,
+    responses(
+        (status = 201, body = ImageAnnotationCategoryResponse),
+        (status = 400, description = "Invalid name, color or parent", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+        (status = 409, description = "Category name already exists in this project", body = String),
+    ),
+)]
 pub async fn create_image_annotation_category(
     req: HttpRequest,
     path: web::Path<String>,
@@ -94,8 +231,16 @@ pub async fn create_image_annotation_category(
         return HttpResponse::NotFound().json("Project not found or access denied");
     }
 
+    if let Some(parent_id) = payload.parent_id {
+        match get_image_annotation_category_by_id(&pool, parent_id, project_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => return HttpResponse::BadRequest().json("Parent category not found in this project"),
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch parent category"),
+        }
+    }
+
     // Create annotation category
-    match create_image_annotation_category_in_db(
+    match create_image_annotation_category_with_parent_in_db(
         &pool,
         project_id,
         &payload.name,
@@ -103,8 +248,10 @@ pub async fn create_image_annotation_category(
         payload.supercategory.as_deref(),
         payload.color.as_deref(),
         payload.coco_id,
+        payload.parent_id,
     ).await {
         Ok(category) => {
+            crate::cache::invalidate_categories(project_id).await;
             HttpResponse::Created().json(ImageAnnotationCategoryResponse {
                 category,
             })
@@ -116,6 +263,17 @@ pub async fn create_image_annotation_category(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/image-annotation-categories",
+    tag = "categories",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 200, body = ImageAnnotationCategoriesListResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
 pub async fn list_image_annotation_categories(
     req: HttpRequest,
     path: web::Path<String>,
@@ -150,6 +308,20 @@ pub async fn list_image_annotation_categories(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/image-annotation-categories/{category_id}",
+    tag = "categories",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("category_id" = Uuid, Path, description = "Category ID"),
+    ),
+    responses(
+        (status = 200, body = ImageAnnotationCategoryResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or category not found", body = String),
+    ),
+)]
 pub async fn get_image_annotation_category(
     req: HttpRequest,
     path: web::Path<(String, String)>,
@@ -195,6 +367,120 @@ pub async fn get_image_annotation_category(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/image-annotation-categories/tree",
+    tag = "categories",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        CategoryTreeQuery,
+    ),
+    responses(
+        (status = 200, description = "Root categories with their descendants", body = CategoryTreeResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or category not found", body = String),
+    ),
+)]
+pub async fn get_image_annotation_category_tree(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<CategoryTreeQuery>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let categories = match get_project_image_annotation_categories(&pool, project_id).await {
+        Ok(categories) => categories,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotation categories"),
+    };
+
+    let tree = match query.root_id {
+        Some(root_id) => match build_category_tree(categories).into_iter().find_map(|node| find_subtree(node, root_id)) {
+            Some(node) => vec![node],
+            None => return HttpResponse::NotFound().json("Annotation category not found"),
+        },
+        None => build_category_tree(categories),
+    };
+
+    HttpResponse::Ok().json(CategoryTreeResponse { tree })
+}
+
+/// Nests categories under their parents. Categories whose parent is missing become
+/// roots; siblings keep the input order.
+pub fn build_category_tree(categories: Vec<ImageAnnotationCategory>) -> Vec<CategoryTreeNode> {
+    let ids: HashSet<Uuid> = categories.iter().map(|category| category.id).collect();
+    let mut children: std::collections::HashMap<Uuid, Vec<ImageAnnotationCategory>> = std::collections::HashMap::new();
+    let mut roots = Vec::new();
+
+    for category in categories {
+        match category.parent_id {
+            Some(parent_id) if ids.contains(&parent_id) && parent_id != category.id => {
+                children.entry(parent_id).or_default().push(category)
+            }
+            _ => roots.push(category),
+        }
+    }
+
+    fn attach(
+        category: ImageAnnotationCategory,
+        children: &mut std::collections::HashMap<Uuid, Vec<ImageAnnotationCategory>>,
+    ) -> CategoryTreeNode {
+        let nodes = children
+            .remove(&category.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| attach(child, children))
+            .collect();
+        CategoryTreeNode { category, children: nodes }
+    }
+
+    roots.into_iter().map(|root| attach(root, &mut children)).collect()
+}
+
+fn find_subtree(node: CategoryTreeNode, id: Uuid) -> Option<CategoryTreeNode> {
+    if node.category.id == id {
+        return Some(node);
+    }
+    node.children.into_iter().find_map(|child| find_subtree(child, id))
+}
+
+#[utoipa::path(
+    put,
+    path = "/projects/{project_id}/image-annotation-categories/{category_id}",
+    tag = "categories",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("category_id" = Uuid, Path, description = "Category ID"),
+    ),
+    request_body = UpdateImageAnnotationCategoryRequest,
+    responses(
+        (status = 200, body = ImageAnnotationCategoryResponse),
+        (status = 400, description = "Invalid name, color or parent", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or category not found", body = String),
+        (status = 409, description = "Category name already exists in this project", body = String),
+    ),
+)]
 pub async fn update_image_annotation_category(
     req: HttpRequest,
     path: web::Path<(String, String)>,
@@ -245,6 +531,20 @@ pub async fn update_image_annotation_category(
         return HttpResponse::NotFound().json("Project not found or access denied");
     }
 
+    if let Some(parent_id) = payload.parent_id {
+        match get_image_annotation_category_by_id(&pool, parent_id, project_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => return HttpResponse::BadRequest().json("Parent category not found in this project"),
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch parent category"),
+        }
+
+        match would_create_cycle(&pool, category_id, parent_id).await {
+            Ok(false) => {}
+            Ok(true) => return HttpResponse::BadRequest().json("A category cannot be its own ancestor"),
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to validate category hierarchy"),
+        }
+    }
+
     // Update annotation category
     match update_image_annotation_category_in_db(
         &pool,
@@ -255,8 +555,10 @@ pub async fn update_image_annotation_category(
         payload.supercategory.as_deref(),
         payload.color.as_deref(),
         payload.coco_id,
+        payload.parent_id,
     ).await {
         Ok(Some(category)) => {
+            crate::cache::invalidate_categories(project_id).await;
             HttpResponse::Ok().json(ImageAnnotationCategoryResponse {
                 category,
             })
@@ -269,9 +571,99 @@ pub async fn update_image_annotation_category(
     }
 }
 
+/// Replaces the custom attribute definitions annotations of this category must follow.
+#[utoipa::path(
+    put,
+    path = "/projects/{project_id}/image-annotation-categories/{category_id}/attribute-schema",
+    tag = "categories",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("category_id" = Uuid, Path, description = "Category ID"),
+    ),
+    request_body = UpdateAttributeSchemaRequest,
+    responses(
+        (status = 200, body = ImageAnnotationCategoryResponse),
+        (status = 400, description = "Invalid attribute schema", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or category not found", body = String),
+    ),
+)]
+pub async fn update_image_annotation_category_attribute_schema(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    payload: web::Json<UpdateAttributeSchemaRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, category_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let category_id = match Uuid::parse_str(&category_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid category ID"),
+    };
+
+    if let Err(message) = crate::attributes::validate_attribute_schema(&payload.attribute_schema) {
+        return HttpResponse::BadRequest().json(message);
+    }
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let attribute_schema = match serde_json::to_value(&payload.attribute_schema) {
+        Ok(value) => value,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to serialize attribute schema"),
+    };
+
+    match update_attribute_schema_in_db(&pool, category_id, project_id, &attribute_schema).await {
+        Ok(Some(category)) => {
+            crate::cache::invalidate_categories(project_id).await;
+            HttpResponse::Ok().json(ImageAnnotationCategoryResponse { category })
+        }
+        Ok(None) => HttpResponse::NotFound().json("Annotation category not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to update attribute schema"),
+    }
+}
+
+/// Deletes a category. When annotations still use it, the caller must either pass
+/// `reassign_to` to move them to another category or `force=true` to orphan them.
+#[utoipa::path(
+    delete,
+    path = "/projects/{project_id}/image-annotation-categories/{category_id}",
+    tag = "categories",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("category_id" = Uuid, Path, description = "Category ID"),
+        DeleteCategoryQuery,
+    ),
+    responses(
+        (status = 200, body = CategoryDeleteResult),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or category not found", body = String),
+        (status = 409, description = "Annotations still use the category", body = String),
+    ),
+)]
 pub async fn delete_image_annotation_category(
     req: HttpRequest,
     path: web::Path<(String, String)>,
+    query: web::Query<DeleteCategoryQuery>,
     pool: web::Data<Pool<Postgres>>,
     config: web::Data<crate::auth::OAuthConfig>,
 ) -> impl Responder {
@@ -297,19 +689,480 @@ pub async fn delete_image_annotation_category(
         Err(_) => return HttpResponse::BadRequest().json("Invalid category ID"),
     };
 
+    let force = query.force.unwrap_or(false);
+    if query.reassign_to.is_some() && force {
+        return HttpResponse::BadRequest().json("Use either reassign_to or force, not both");
+    }
+
+    if query.reassign_to == Some(category_id) {
+        return HttpResponse::BadRequest().json("Cannot reassign annotations to the category being deleted");
+    }
+
     // Check if user has access to this project
     if !user_has_project_access(&pool, project_id, user_id).await {
         return HttpResponse::NotFound().json("Project not found or access denied");
     }
 
     // Delete annotation category
-    match delete_image_annotation_category_from_db(&pool, category_id, project_id).await {
-        Ok(true) => HttpResponse::NoContent().finish(),
-        Ok(false) => HttpResponse::NotFound().json("Annotation category not found"),
+    match delete_image_annotation_category_from_db(&pool, category_id, project_id, query.reassign_to, force).await {
+        Ok(CategoryDeleteOutcome::Deleted(result)) => {
+            crate::cache::invalidate_categories(project_id).await;
+            HttpResponse::Ok().json(result)
+        }
+        Ok(CategoryDeleteOutcome::InUse(count)) => HttpResponse::Conflict().json(format!(
+            "Category is used by {} annotations; pass reassign_to=<category_id> or force=true",
+            count
+        )),
+        Ok(CategoryDeleteOutcome::NotFound) => HttpResponse::NotFound().json("Annotation category not found"),
+        Ok(CategoryDeleteOutcome::ReassignTargetNotFound) => {
+            HttpResponse::BadRequest().json("Reassignment category not found in this project")
+        }
         Err(_) => HttpResponse::InternalServerError().json("Failed to delete annotation category"),
     }
 }
 
+/// Moves every annotation of a category to another one and deletes the source,
+/// all in one transaction.
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/image-annotation-categories/{category_id}/merge-into/{target_id}",
+    tag = "categories",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("category_id" = Uuid, Path, description = "Category ID"),
+        ("target_id" = Uuid, Path, description = "Category the annotations are moved to"),
+    ),
+    responses(
+        (status = 200, body = CategoryMergeResult),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or category not found", body = String),
+    ),
+)]
+pub async fn merge_image_annotation_category(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, category_id_str, target_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let category_id = match Uuid::parse_str(&category_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid category ID"),
+    };
+
+    let target_id = match Uuid::parse_str(&target_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid target category ID"),
+    };
+
+    if category_id == target_id {
+        return HttpResponse::BadRequest().json("Cannot merge a category into itself");
+    }
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match merge_categories_in_db(&pool, project_id, category_id, target_id).await {
+        Ok(Some(result)) => {
+            crate::cache::invalidate_categories(project_id).await;
+            HttpResponse::Ok().json(result)
+        }
+        Ok(None) => HttpResponse::NotFound().json("Annotation category not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to merge annotation categories"),
+    }
+}
+
+/// Reassigns the annotations matching a filter (source categories, tasks, area range)
+/// to another category.
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/image-annotation-categories/relabel",
+    tag = "categories",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    request_body = BulkRelabelRequest,
+    responses(
+        (status = 200, body = BulkRelabelResult),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn relabel_image_annotations(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<BulkRelabelRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Validate input
+    let from_category_ids: Vec<Uuid> = payload.from_category_ids.iter().copied()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    if from_category_ids.is_empty() {
+        return HttpResponse::BadRequest().json("At least one source category is required");
+    }
+
+    if from_category_ids.contains(&payload.to_category_id) {
+        return HttpResponse::BadRequest().json("Target category cannot also be a source category");
+    }
+
+    if let (Some(min_area), Some(max_area)) = (payload.min_area, payload.max_area) {
+        if min_area > max_area {
+            return HttpResponse::BadRequest().json("min_area cannot be greater than max_area");
+        }
+    }
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let mut category_ids = from_category_ids.clone();
+    category_ids.push(payload.to_category_id);
+    match count_project_categories(&pool, project_id, &category_ids).await {
+        Ok(count) if count == category_ids.len() as i64 => {}
+        Ok(_) => return HttpResponse::BadRequest().json("One or more categories do not belong to the specified project"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotation categories"),
+    }
+
+    match relabel_annotations_in_db(&pool, project_id, &from_category_ids, &payload).await {
+        Ok(relabeled) => HttpResponse::Ok().json(BulkRelabelResult { relabeled }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to relabel annotations"),
+    }
+}
+
+/// Replaces the keyboard shortcuts of all categories of a project in one go, so keys can be
+/// swapped between categories without tripping over the one-key-per-category rule.
+#[utoipa::path(
+    put,
+    path = "/projects/{project_id}/image-annotation-categories/hotkeys",
+    tag = "categories",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    request_body = UpdateHotkeysRequest,
+    responses(
+        (status = 200, body = ImageAnnotationCategoriesListResponse),
+        (status = 400, description = "Unknown or duplicate hotkey", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn update_image_annotation_category_hotkeys(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<UpdateHotkeysRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    if let Err(message) = validate_hotkeys(&payload.hotkeys) {
+        return HttpResponse::BadRequest().json(message);
+    }
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let category_ids: Vec<Uuid> = payload.hotkeys.iter().map(|hotkey| hotkey.category_id).collect();
+    match count_project_categories(&pool, project_id, &category_ids).await {
+        Ok(count) if count == category_ids.len() as i64 => {}
+        Ok(_) => return HttpResponse::BadRequest().json("One or more categories do not belong to the specified project"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotation categories"),
+    }
+
+    if let Err(e) = replace_hotkeys_in_db(&pool, project_id, &payload.hotkeys).await {
+        eprintln!("Failed to update hotkeys: {}", e);
+        return HttpResponse::InternalServerError().json("Failed to update hotkeys");
+    }
+    crate::cache::invalidate_categories(project_id).await;
+
+    match get_project_image_annotation_categories(&pool, project_id).await {
+        Ok(categories) => HttpResponse::Ok().json(ImageAnnotationCategoriesListResponse { categories }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch annotation categories"),
+    }
+}
+
+/// Checks that every category and every key appears at most once. Key names are the
+/// client's business, the server only bounds their length.
+pub fn validate_hotkeys(hotkeys: &[CategoryHotkey]) -> Result<(), String> {
+    let mut categories = HashSet::new();
+    let mut keys = HashSet::new();
+    for hotkey in hotkeys {
+        if !categories.insert(hotkey.category_id) {
+            return Err(format!("Category {} is listed more than once", hotkey.category_id));
+        }
+        if let Some(key) = &hotkey.hotkey {
+            if key.trim().is_empty() || key.len() > 32 {
+                return Err("Hotkeys must be between 1 and 32 characters".to_string());
+            }
+            if !keys.insert(key.as_str()) {
+                return Err(format!("Hotkey {} is assigned to more than one category", key));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Creates many categories at once from an uploaded JSON array or CSV file.
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/image-annotation-categories/bulk",
+    tag = "categories",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        BulkCategoryImportQuery,
+    ),
+    request_body(content = FileUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, body = BulkCategoryImportResult),
+        (status = 400, description = "Invalid category file", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+        (status = 409, description = "Categories already exist and `on_duplicate` is `error`", body = String),
+    ),
+)]
+pub async fn bulk_import_image_annotation_categories(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<BulkCategoryImportQuery>,
+    mut payload: Multipart,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let mode = match DuplicateMode::parse(query.on_duplicate.as_deref()) {
+        Some(mode) => mode,
+        None => return HttpResponse::BadRequest().json("on_duplicate must be one of: skip, update, error"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let data = match extract_file_from_multipart(&mut payload).await {
+        Ok(data) => data,
+        Err(err) => return HttpResponse::BadRequest().json(format!("Failed to read file: {}", err)),
+    };
+
+    let categories = match parse_categories_file(&data) {
+        Ok(categories) => categories,
+        Err(err) => return HttpResponse::BadRequest().json(format!("Invalid category file: {}", err)),
+    };
+
+    if mode == DuplicateMode::Error {
+        let existing = match get_existing_category_names(&pool, project_id).await {
+            Ok(existing) => existing,
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotation categories"),
+        };
+        let duplicates: Vec<&str> = categories
+            .iter()
+            .map(|category| category.name.as_str())
+            .filter(|name| existing.contains(*name))
+            .collect();
+        if !duplicates.is_empty() {
+            return HttpResponse::Conflict().json(format!("Categories already exist: {}", duplicates.join(", ")));
+        }
+    }
+
+    match bulk_import_categories_in_db(&pool, project_id, &categories, mode).await {
+        Ok(result) => {
+            crate::cache::invalidate_categories(project_id).await;
+            HttpResponse::Ok().json(result)
+        }
+        Err(err) => {
+            eprintln!("Category import error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to import annotation categories")
+        }
+    }
+}
+
+async fn extract_file_from_multipart(payload: &mut Multipart) -> Result<String, Box<dyn std::error::Error>> {
+    while let Some(mut field) = payload.try_next().await? {
+        if field.name() == Some("file") {
+            let mut data = bytes::BytesMut::new();
+            while let Some(chunk) = field.try_next().await? {
+                data.extend_from_slice(&chunk);
+            }
+
+            return Ok(String::from_utf8(data.to_vec())?);
+        }
+    }
+
+    Err("No file field found in multipart data".into())
+}
+
+/// Parses either a JSON array of category objects or a CSV file with a `name` column and
+/// optional `description`, `supercategory`, `color` and `coco_id` columns, then validates
+/// the entries the same way single category creation does.
+pub fn parse_categories_file(data: &str) -> Result<Vec<CreateImageAnnotationCategoryRequest>, String> {
+    let trimmed = data.trim_start_matches('\u{feff}').trim();
+
+    let categories: Vec<CreateImageAnnotationCategoryRequest> = if trimmed.starts_with('[') {
+        serde_json::from_str(trimmed).map_err(|e| e.to_string())?
+    } else {
+        parse_categories_csv(trimmed)?
+    };
+
+    if categories.is_empty() {
+        return Err("No categories found".to_string());
+    }
+
+    let mut names = HashSet::new();
+    for (index, category) in categories.iter().enumerate() {
+        if category.name.trim().is_empty() {
+            return Err(format!("Entry {} has an empty name", index + 1));
+        }
+        if category.name.len() > 255 {
+            return Err(format!("Entry {} has a name longer than 255 characters", index + 1));
+        }
+        if let Some(color) = &category.color {
+            if !color.starts_with('#') || color.len() != 7 {
+                return Err(format!("Entry {} has a color that is not in HEX format (#RRGGBB)", index + 1));
+            }
+        }
+        if !names.insert(category.name.as_str()) {
+            return Err(format!("Duplicate category name in file: {}", category.name));
+        }
+        if category.parent_id.is_some() {
+            return Err(format!("Entry {} sets parent_id, which bulk import does not support", index + 1));
+        }
+    }
+
+    Ok(categories)
+}
+
+fn parse_categories_csv(data: &str) -> Result<Vec<CreateImageAnnotationCategoryRequest>, String> {
+    let mut lines = data.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<String> = split_csv_line(lines.next().ok_or("Empty CSV file")?)
+        .into_iter()
+        .map(|column| column.to_lowercase())
+        .collect();
+
+    let column = |name: &str| header.iter().position(|column| column == name);
+    let name_column = column("name").ok_or("CSV header must contain a name column")?;
+    let description_column = column("description");
+    let supercategory_column = column("supercategory");
+    let color_column = column("color");
+    let coco_id_column = column("coco_id");
+
+    let mut categories = Vec::new();
+    for (line_number, line) in lines.enumerate() {
+        let fields = split_csv_line(line);
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|i| fields.get(i))
+                .filter(|value| !value.is_empty())
+                .cloned()
+        };
+
+        let coco_id = match field(coco_id_column) {
+            Some(value) => Some(value.parse::<i32>().map_err(|_| format!("Invalid coco_id on line {}", line_number + 2))?),
+            None => None,
+        };
+
+        categories.push(CreateImageAnnotationCategoryRequest {
+            name: field(Some(name_column)).unwrap_or_default(),
+            description: field(description_column),
+            supercategory: field(supercategory_column),
+            color: field(color_column),
+            coco_id,
+            parent_id: None,
+        });
+    }
+
+    Ok(categories)
+}
+
+/// Splits one CSV line, honouring double-quoted fields with `""` escapes.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current).trim().to_string()),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current.trim().to_string());
+
+    fields
+}
+
 pub async fn create_image_annotation_category_in_db(
     pool: &Pool<Postgres>,
     project_id: Uuid,
@@ -318,6 +1171,20 @@ pub async fn create_image_annotation_category_in_db(
     supercategory: Option<&str>,
     color: Option<&str>,
     coco_id: Option<i32>,
+) -> Result<ImageAnnotationCategory, sqlx::Error> {
+    create_image_annotation_category_with_parent_in_db(pool, project_id, name, description, supercategory, color, coco_id, None).await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn create_image_annotation_category_with_parent_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    name: &str,
+    description: Option<&str>,
+    supercategory: Option<&str>,
+    color: Option<&str>,
+    coco_id: Option<i32>,
+    parent_id: Option<Uuid>,
 ) -> Result<ImageAnnotationCategory, sqlx::Error> {
     let category_id = Uuid::new_v4();
     let now = Utc::now();
@@ -325,9 +1192,9 @@ pub async fn create_image_annotation_category_in_db(
     // Create annotation category in image_annotation_categories table
     let category = sqlx::query_as::<_, ImageAnnotationCategory>(
         r#"
-        INSERT INTO image_annotation_categories (id, project_id, name, description, supercategory, color, coco_id, image_metadata, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-        RETURNING id, project_id, name, description, supercategory, color, coco_id, image_metadata, created_at, updated_at
+        INSERT INTO image_annotation_categories (id, project_id, name, description, supercategory, color, coco_id, parent_id, image_metadata, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING id, project_id, name, description, supercategory, color, coco_id, parent_id, attribute_schema, hotkey, image_metadata, created_at, updated_at
         "#
     )
     .bind(category_id)
@@ -337,115 +1204,430 @@ pub async fn create_image_annotation_category_in_db(
     .bind(supercategory)
     .bind(color)
     .bind(coco_id)
+    .bind(parent_id)
     .bind(serde_json::json!({}))
     .bind(now)
     .bind(now)
     .fetch_one(pool)
     .await?;
 
-    Ok(category)
-}
+    Ok(category)
+}
+
+pub(crate) async fn get_project_image_annotation_categories(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Vec<ImageAnnotationCategory>, sqlx::Error> {
+    crate::cache::project_categories(project_id, fetch_project_image_annotation_categories(pool, project_id)).await
+}
+
+async fn fetch_project_image_annotation_categories(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Vec<ImageAnnotationCategory>, sqlx::Error> {
+    sqlx::query_as::<_, ImageAnnotationCategory>(
+        r#"
+        SELECT id, project_id, name, description, supercategory, color, coco_id, parent_id, attribute_schema, hotkey, image_metadata, created_at, updated_at
+        FROM image_annotation_categories
+        WHERE project_id = $1
+        ORDER BY name ASC
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_image_annotation_category_by_id(
+    pool: &Pool<Postgres>,
+    category_id: Uuid,
+    project_id: Uuid,
+) -> Result<Option<ImageAnnotationCategory>, sqlx::Error> {
+    sqlx::query_as::<_, ImageAnnotationCategory>(
+        r#"
+        SELECT id, project_id, name, description, supercategory, color, coco_id, parent_id, attribute_schema, hotkey, image_metadata, created_at, updated_at
+        FROM image_annotation_categories
+        WHERE id = $1 AND project_id = $2
+        "#
+    )
+    .bind(category_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn update_image_annotation_category_in_db(
+    pool: &Pool<Postgres>,
+    category_id: Uuid,
+    project_id: Uuid,
+    name: &str,
+    description: Option<&str>,
+    supercategory: Option<&str>,
+    color: Option<&str>,
+    coco_id: Option<i32>,
+    parent_id: Option<Uuid>,
+) -> Result<Option<ImageAnnotationCategory>, sqlx::Error> {
+    let now = Utc::now();
+
+    // Update annotation category in image_annotation_categories table
+    sqlx::query_as::<_, ImageAnnotationCategory>(
+        r#"
+        UPDATE image_annotation_categories
+        SET name = $1, description = $2, supercategory = $3, color = $4, coco_id = $5, parent_id = $6, updated_at = $7
+        WHERE id = $8 AND project_id = $9
+        RETURNING id, project_id, name, description, supercategory, color, coco_id, parent_id, attribute_schema, hotkey, image_metadata, created_at, updated_at
+        "#
+    )
+    .bind(name)
+    .bind(description)
+    .bind(supercategory)
+    .bind(color)
+    .bind(coco_id)
+    .bind(parent_id)
+    .bind(now)
+    .bind(category_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn update_attribute_schema_in_db(
+    pool: &Pool<Postgres>,
+    category_id: Uuid,
+    project_id: Uuid,
+    attribute_schema: &serde_json::Value,
+) -> Result<Option<ImageAnnotationCategory>, sqlx::Error> {
+    sqlx::query_as::<_, ImageAnnotationCategory>(
+        r#"
+        UPDATE image_annotation_categories
+        SET attribute_schema = $1, updated_at = $2
+        WHERE id = $3 AND project_id = $4
+        RETURNING id, project_id, name, description, supercategory, color, coco_id, parent_id, attribute_schema, hotkey, image_metadata, created_at, updated_at
+        "#
+    )
+    .bind(attribute_schema)
+    .bind(Utc::now())
+    .bind(category_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Whether making `parent_id` the parent of `category_id` would close a loop, i.e. the
+/// category is the proposed parent itself or one of its ancestors.
+async fn would_create_cycle(pool: &Pool<Postgres>, category_id: Uuid, parent_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        r#"
+        WITH RECURSIVE ancestors AS (
+            SELECT id, parent_id FROM image_annotation_categories WHERE id = $1
+            UNION
+            SELECT c.id, c.parent_id FROM image_annotation_categories c
+            INNER JOIN ancestors a ON c.id = a.parent_id
+        )
+        SELECT EXISTS(SELECT 1 FROM ancestors WHERE id = $2)
+        "#
+    )
+    .bind(parent_id)
+    .bind(category_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Counts, reassigns and deletes in one transaction so no annotation can pick up the
+/// category between the check and the delete.
+async fn delete_image_annotation_category_from_db(
+    pool: &Pool<Postgres>,
+    category_id: Uuid,
+    project_id: Uuid,
+    reassign_to: Option<Uuid>,
+    force: bool,
+) -> Result<CategoryDeleteOutcome, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let exists = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM image_annotation_categories WHERE id = $1 AND project_id = $2 FOR UPDATE"
+    )
+    .bind(category_id)
+    .bind(project_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .is_some();
+    if !exists {
+        return Ok(CategoryDeleteOutcome::NotFound);
+    }
+
+    // Boxes and whole-image classification labels both count as uses
+    let affected_annotations = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT (SELECT COUNT(*) FROM image_annotations WHERE category_id = $1)
+             + (SELECT COUNT(*) FROM image_classifications WHERE category_id = $1)
+        "#
+    )
+    .bind(category_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if let Some(target_id) = reassign_to {
+        let target_exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM image_annotation_categories WHERE id = $1 AND project_id = $2)"
+        )
+        .bind(target_id)
+        .bind(project_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if !target_exists {
+            return Ok(CategoryDeleteOutcome::ReassignTargetNotFound);
+        }
+
+        sqlx::query("UPDATE image_annotations SET category_id = $1, updated_at = NOW() WHERE category_id = $2")
+            .bind(target_id)
+            .bind(category_id)
+            .execute(&mut *tx)
+            .await?;
+        crate::classifications::reassign_classification_labels(&mut tx, category_id, target_id).await?;
+    } else if affected_annotations > 0 && !force {
+        return Ok(CategoryDeleteOutcome::InUse(affected_annotations));
+    }
+
+    // Remaining boxes (force) and child categories are set to NULL by the foreign keys, labels are deleted
+    sqlx::query("DELETE FROM image_annotation_categories WHERE id = $1")
+        .bind(category_id)
+        .execute(&mut *tx)
+        .await?;
+    crate::labeling_rules::forget_required_category(&mut tx, project_id, category_id).await?;
+
+    tx.commit().await?;
+
+    Ok(CategoryDeleteOutcome::Deleted(CategoryDeleteResult {
+        affected_annotations,
+        reassigned_to: reassign_to,
+    }))
+}
+
+async fn get_existing_category_names(pool: &Pool<Postgres>, project_id: Uuid) -> Result<HashSet<String>, sqlx::Error> {
+    let names = sqlx::query_scalar::<_, String>("SELECT name FROM image_annotation_categories WHERE project_id = $1")
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(names.into_iter().collect())
+}
+
+/// Inserts the categories in one transaction. Existing names are skipped, or have the
+/// provided fields overwritten in `Update` mode.
+async fn bulk_import_categories_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    categories: &[CreateImageAnnotationCategoryRequest],
+    mode: DuplicateMode,
+) -> Result<BulkCategoryImportResult, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut result = BulkCategoryImportResult {
+        created: 0,
+        updated: 0,
+        skipped: Vec::new(),
+    };
+
+    for category in categories {
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO image_annotation_categories (id, project_id, name, description, supercategory, color, coco_id, image_metadata, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, '{}'::jsonb, NOW(), NOW())
+            ON CONFLICT (project_id, name) DO NOTHING
+            "#
+        )
+        .bind(Uuid::new_v4())
+        .bind(project_id)
+        .bind(&category.name)
+        .bind(&category.description)
+        .bind(&category.supercategory)
+        .bind(&category.color)
+        .bind(category.coco_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if inserted.rows_affected() > 0 {
+            result.created += 1;
+            continue;
+        }
+
+        if mode != DuplicateMode::Update {
+            result.skipped.push(category.name.clone());
+            continue;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE image_annotation_categories
+            SET description = COALESCE($1, description),
+                supercategory = COALESCE($2, supercategory),
+                color = COALESCE($3, color),
+                coco_id = COALESCE($4, coco_id),
+                updated_at = NOW()
+            WHERE project_id = $5 AND name = $6
+            "#
+        )
+        .bind(&category.description)
+        .bind(&category.supercategory)
+        .bind(&category.color)
+        .bind(category.coco_id)
+        .bind(project_id)
+        .bind(&category.name)
+        .execute(&mut *tx)
+        .await?;
+        result.updated += 1;
+    }
+
+    tx.commit().await?;
+
+    Ok(result)
+}
+
+/// Returns `None` when either category is not part of the project. Children of the
+/// merged category move up to its own parent, which can never introduce a cycle.
+async fn merge_categories_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    category_id: Uuid,
+    target_id: Uuid,
+) -> Result<Option<CategoryMergeResult>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let source_parent_id = match sqlx::query_scalar::<_, Option<Uuid>>(
+        "SELECT parent_id FROM image_annotation_categories WHERE id = $1 AND project_id = $2 FOR UPDATE"
+    )
+    .bind(category_id)
+    .bind(project_id)
+    .fetch_optional(&mut *tx)
+    .await? {
+        Some(parent_id) => parent_id,
+        None => return Ok(None),
+    };
+
+    let target_exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM image_annotation_categories WHERE id = $1 AND project_id = $2)"
+    )
+    .bind(target_id)
+    .bind(project_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if !target_exists {
+        return Ok(None);
+    }
+
+    let reassigned = sqlx::query(
+        "UPDATE image_annotations SET category_id = $1, updated_at = NOW() WHERE category_id = $2"
+    )
+    .bind(target_id)
+    .bind(category_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected()
+        + crate::classifications::reassign_classification_labels(&mut tx, category_id, target_id).await?;
+
+    sqlx::query(
+        "UPDATE image_annotation_categories SET parent_id = $1, updated_at = NOW() WHERE parent_id = $2"
+    )
+    .bind(source_parent_id)
+    .bind(category_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM image_annotation_categories WHERE id = $1")
+        .bind(category_id)
+        .execute(&mut *tx)
+        .await?;
+    crate::labeling_rules::forget_required_category(&mut tx, project_id, category_id).await?;
 
-async fn get_project_image_annotation_categories(
-    pool: &Pool<Postgres>,
-    project_id: Uuid,
-) -> Result<Vec<ImageAnnotationCategory>, sqlx::Error> {
-    sqlx::query_as::<_, ImageAnnotationCategory>(
+    let target = sqlx::query_as::<_, ImageAnnotationCategory>(
         r#"
-        SELECT id, project_id, name, description, supercategory, color, coco_id, image_metadata, created_at, updated_at
+        SELECT id, project_id, name, description, supercategory, color, coco_id, parent_id, attribute_schema, hotkey, image_metadata, created_at, updated_at
         FROM image_annotation_categories
-        WHERE project_id = $1
-        ORDER BY name ASC
+        WHERE id = $1
         "#
     )
-    .bind(project_id)
-    .fetch_all(pool)
-    .await
+    .bind(target_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Some(CategoryMergeResult {
+        target,
+        reassigned_annotations: reassigned,
+    }))
 }
 
-async fn get_image_annotation_category_by_id(
-    pool: &Pool<Postgres>,
-    category_id: Uuid,
-    project_id: Uuid,
-) -> Result<Option<ImageAnnotationCategory>, sqlx::Error> {
-    sqlx::query_as::<_, ImageAnnotationCategory>(
-        r#"
-        SELECT id, project_id, name, description, supercategory, color, coco_id, image_metadata, created_at, updated_at
-        FROM image_annotation_categories
-        WHERE id = $1 AND project_id = $2
-        "#
+async fn count_project_categories(pool: &Pool<Postgres>, project_id: Uuid, category_ids: &[Uuid]) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM image_annotation_categories WHERE project_id = $1 AND id = ANY($2)"
     )
-    .bind(category_id)
     .bind(project_id)
-    .fetch_optional(pool)
+    .bind(category_ids)
+    .fetch_one(pool)
     .await
 }
 
-#[allow(clippy::too_many_arguments)]
-async fn update_image_annotation_category_in_db(
+async fn relabel_annotations_in_db(
     pool: &Pool<Postgres>,
-    category_id: Uuid,
     project_id: Uuid,
-    name: &str,
-    description: Option<&str>,
-    supercategory: Option<&str>,
-    color: Option<&str>,
-    coco_id: Option<i32>,
-) -> Result<Option<ImageAnnotationCategory>, sqlx::Error> {
-    let now = Utc::now();
-
-    // Update annotation category in image_annotation_categories table
-    sqlx::query_as::<_, ImageAnnotationCategory>(
+    from_category_ids: &[Uuid],
+    filter: &BulkRelabelRequest,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
         r#"
-        UPDATE image_annotation_categories
-        SET name = $1, description = $2, supercategory = $3, color = $4, coco_id = $5, updated_at = $6
-        WHERE id = $7 AND project_id = $8
-        RETURNING id, project_id, name, description, supercategory, color, coco_id, image_metadata, created_at, updated_at
+        UPDATE image_annotations ia
+        SET category_id = $1, updated_at = NOW()
+        FROM annotations a
+        JOIN tasks t ON a.task_id = t.id
+        WHERE ia.annotation_id = a.id
+            AND t.project_id = $2
+            AND ia.category_id = ANY($3)
+            AND ($4::uuid[] IS NULL OR a.task_id = ANY($4))
+            AND ($5::float8 IS NULL OR ia.area >= $5)
+            AND ($6::float8 IS NULL OR ia.area <= $6)
         "#
     )
-    .bind(name)
-    .bind(description)
-    .bind(supercategory)
-    .bind(color)
-    .bind(coco_id)
-    .bind(now)
-    .bind(category_id)
+    .bind(filter.to_category_id)
     .bind(project_id)
-    .fetch_optional(pool)
-    .await
+    .bind(from_category_ids)
+    .bind(&filter.task_ids)
+    .bind(filter.min_area)
+    .bind(filter.max_area)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
 }
 
-async fn delete_image_annotation_category_from_db(
+async fn replace_hotkeys_in_db(
     pool: &Pool<Postgres>,
-    category_id: Uuid,
     project_id: Uuid,
-) -> Result<bool, sqlx::Error> {
-    // Delete from image_annotation_categories
-    let result = sqlx::query(
-        "DELETE FROM image_annotation_categories WHERE id = $1 AND project_id = $2"
-    )
-    .bind(category_id)
-    .bind(project_id)
-    .execute(pool)
-    .await?;
+    hotkeys: &[CategoryHotkey],
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // Clear first, the unique index would reject swapping keys one category at a time
+    sqlx::query("UPDATE image_annotation_categories SET hotkey = NULL, updated_at = NOW() WHERE project_id = $1 AND hotkey IS NOT NULL")
+        .bind(project_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for hotkey in hotkeys.iter().filter(|hotkey| hotkey.hotkey.is_some()) {
+        sqlx::query("UPDATE image_annotation_categories SET hotkey = $1, updated_at = NOW() WHERE id = $2 AND project_id = $3")
+            .bind(&hotkey.hotkey)
+            .bind(hotkey.category_id)
+            .bind(project_id)
+            .execute(&mut *tx)
+            .await?;
+    }
 
-    Ok(result.rows_affected() > 0)
+    tx.commit().await
 }
 
 async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
-    sqlx::query_scalar::<_, bool>(
-        r#"
-        SELECT EXISTS(
-            SELECT 1 FROM project_members pm
-            WHERE pm.project_id = $1 AND pm.user_id = $2
-        )
-        "#
-    )
-    .bind(project_id)
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .unwrap_or(false)
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
 }
 
 fn extract_user_claims(
@@ -529,6 +1711,7 @@ mod tests {
             color: Some("#FF0000".to_string()),
             description: Some("Human person category".to_string()),
             coco_id: Some(1),
+            parent_id: None,
         };
 
         let req = test::TestRequest::post()
@@ -572,6 +1755,7 @@ mod tests {
             color: None,
             description: None,
             coco_id: None,
+            parent_id: None,
         };
 
         let req = test::TestRequest::post()
@@ -609,6 +1793,7 @@ mod tests {
             color: Some("invalid_color".to_string()),
             description: None,
             coco_id: None,
+            parent_id: None,
         };
 
         let req = test::TestRequest::post()
@@ -715,6 +1900,7 @@ mod tests {
             color: Some("#0000FF".to_string()),
             description: Some("Updated description".to_string()),
             coco_id: Some(10),
+            parent_id: None,
         };
 
         let req = test::TestRequest::put()
@@ -758,7 +1944,64 @@ mod tests {
             .to_request();
 
         let resp = test::call_service(&app, req).await;
-        assert_eq!(resp.status(), 204);
+        assert_eq!(resp.status(), 200);
+
+        let result: CategoryDeleteResult = test::read_body_json(resp).await;
+        assert_eq!(result.affected_annotations, 0);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_delete_category_in_use_requires_reassign_or_force() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        let car = create_image_annotation_category_in_db(&pool, project.id, "car", None, None, None, None).await.unwrap();
+        let vehicle = create_image_annotation_category_in_db(&pool, project.id, "vehicle", None, None, None, None).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "image1.jpg", None).await.unwrap();
+        let bbox = crate::annotations::BoundingBox { category_id: car.id, bbox: vec![1.0, 2.0, 3.0, 4.0], area: None, iscrowd: Some(false), is_prediction: None, confidence: None, attributes: None, rotation: None, frame_index: None, track_id: None, is_interpolated: None, mask: None };
+        crate::annotations::create_annotation_in_db(&pool, task.id, &[bbox], &serde_json::json!({}), user.id).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/image-annotation-categories/{category_id}", web::delete().to(delete_image_annotation_category))
+        ).await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/projects/{}/image-annotation-categories/{}", project.id, car.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 409);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/projects/{}/image-annotation-categories/{}?reassign_to={}", project.id, car.id, vehicle.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let result: CategoryDeleteResult = test::read_body_json(resp).await;
+        assert_eq!(result.affected_annotations, 1);
+        assert_eq!(result.reassigned_to, Some(vehicle.id));
+
+        let category_id = sqlx::query_scalar::<_, Option<Uuid>>("SELECT category_id FROM image_annotations")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(category_id, Some(vehicle.id));
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/projects/{}/image-annotation-categories/{}?force=true", project.id, vehicle.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
     }
 
     #[actix_web::test]
@@ -785,6 +2028,7 @@ mod tests {
             color: None,
             description: None,
             coco_id: None,
+            parent_id: None,
         };
 
         let req = test::TestRequest::post()
@@ -795,4 +2039,306 @@ mod tests {
         let resp = test::call_service(&app, req).await;
         assert_eq!(resp.status(), 401);
     }
-}
\ No newline at end of file
+
+    fn multipart_body(boundary: &str, file_name: &str, content: &str) -> String {
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: application/octet-stream\r\n\r\n{}\r\n--{}--\r\n",
+            boundary, file_name, content, boundary
+        )
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_bulk_import_categories_with_duplicates() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        create_image_annotation_category_in_db(&pool, project.id, "person", None, None, Some("#000000"), Some(1)).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/image-annotation-categories/bulk", web::post().to(bulk_import_image_annotation_categories))
+        ).await;
+
+        let boundary = "----formdata-test-boundary";
+        let csv = "name,supercategory,color,coco_id\nperson,human,#FF0000,1\ncar,vehicle,,3\n\"bus, large\",vehicle,#00FF00,6\n";
+
+        // Existing names are rejected in error mode
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/image-annotation-categories/bulk?on_duplicate=error", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+            .set_payload(multipart_body(boundary, "categories.csv", csv))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 409);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/image-annotation-categories/bulk", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+            .set_payload(multipart_body(boundary, "categories.csv", csv))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let result: BulkCategoryImportResult = test::read_body_json(resp).await;
+        assert_eq!(result.created, 2);
+        assert_eq!(result.updated, 0);
+        assert_eq!(result.skipped, vec!["person".to_string()]);
+
+        let json = r##"[{"name": "person", "color": "#FF0000"}, {"name": "truck", "coco_id": 8}]"##;
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/image-annotation-categories/bulk?on_duplicate=update", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+            .set_payload(multipart_body(boundary, "categories.json", json))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let result: BulkCategoryImportResult = test::read_body_json(resp).await;
+        assert_eq!(result.created, 1);
+        assert_eq!(result.updated, 1);
+
+        let categories = get_project_image_annotation_categories(&pool, project.id).await.unwrap();
+        let names: Vec<&str> = categories.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["bus, large", "car", "person", "truck"]);
+        let person = &categories[2];
+        assert_eq!(person.color.as_deref(), Some("#FF0000"));
+        assert_eq!(person.coco_id, Some(1));
+    }
+
+    #[actix_web::test]
+    async fn test_parse_categories_file_rejects_invalid_entries() {
+        assert!(parse_categories_file("name\n").is_err());
+        assert!(parse_categories_file("label,color\ncat,#FFFFFF\n").is_err());
+        assert!(parse_categories_file("name,coco_id\ncat,abc\n").is_err());
+        assert!(parse_categories_file("name\ncat\ncat\n").is_err());
+        assert!(parse_categories_file(r#"[{"name": "cat", "color": "red"}]"#).is_err());
+
+        let categories = parse_categories_file("\u{feff}Name,Description\ncat,\"small, \"\"furry\"\"\"\n").unwrap();
+        assert_eq!(categories.len(), 1);
+        assert_eq!(categories[0].name, "cat");
+        assert_eq!(categories[0].description.as_deref(), Some("small, \"furry\""));
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_category_hierarchy_rejects_cycles_and_returns_tree() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        let vehicle = create_image_annotation_category_in_db(&pool, project.id, "vehicle", None, None, None, None).await.unwrap();
+        let car = create_image_annotation_category_with_parent_in_db(&pool, project.id, "car", None, None, None, None, Some(vehicle.id)).await.unwrap();
+        let sedan = create_image_annotation_category_with_parent_in_db(&pool, project.id, "sedan", None, None, None, None, Some(car.id)).await.unwrap();
+        create_image_annotation_category_in_db(&pool, project.id, "person", None, None, None, None).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/image-annotation-categories/tree", web::get().to(get_image_annotation_category_tree))
+                .route("/projects/{project_id}/image-annotation-categories/{category_id}", web::put().to(update_image_annotation_category))
+        ).await;
+
+        // vehicle -> car -> sedan, so sedan can't become the parent of vehicle
+        let req = test::TestRequest::put()
+            .uri(&format!("/projects/{}/image-annotation-categories/{}", project.id, vehicle.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(serde_json::json!({"name": "vehicle", "parent_id": sedan.id}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/image-annotation-categories/tree", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let tree = body["tree"].as_array().unwrap();
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0]["category"]["name"], "person");
+        assert_eq!(tree[1]["category"]["name"], "vehicle");
+        assert_eq!(tree[1]["children"][0]["category"]["name"], "car");
+        assert_eq!(tree[1]["children"][0]["children"][0]["category"]["name"], "sedan");
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/image-annotation-categories/tree?root_id={}", project.id, car.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["tree"][0]["category"]["name"], "car");
+        assert_eq!(body["tree"][0]["children"].as_array().unwrap().len(), 1);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_merge_and_relabel_categories() {
+        use crate::annotations::{create_annotation_in_db, BoundingBox};
+
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        let car = create_image_annotation_category_in_db(&pool, project.id, "car", None, None, None, None).await.unwrap();
+        let automobile = create_image_annotation_category_in_db(&pool, project.id, "automobile", None, None, None, None).await.unwrap();
+        let sedan = create_image_annotation_category_with_parent_in_db(&pool, project.id, "sedan", None, None, None, None, Some(automobile.id)).await.unwrap();
+        let truck = create_image_annotation_category_in_db(&pool, project.id, "truck", None, None, None, None).await.unwrap();
+        let task1 = crate::tasks::create_task_in_db(&pool, project.id, "image1.jpg", None).await.unwrap();
+        let task2 = crate::tasks::create_task_in_db(&pool, project.id, "image2.jpg", None).await.unwrap();
+
+        let bbox = |category_id: Uuid, size: f64| BoundingBox {
+            category_id,
+            bbox: vec![0.0, 0.0, size, size],
+            area: None,
+            iscrowd: Some(false),
+            is_prediction: None,
+            confidence: None,
+            attributes: None,
+            rotation: None,
+            frame_index: None,
+            track_id: None,
+            is_interpolated: None,
+            mask: None,
+        };
+        create_annotation_in_db(&pool, task1.id, &[bbox(car.id, 10.0), bbox(automobile.id, 100.0)], &serde_json::json!({}), user.id).await.unwrap();
+        create_annotation_in_db(&pool, task2.id, &[bbox(automobile.id, 10.0)], &serde_json::json!({}), user.id).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/image-annotation-categories/relabel", web::post().to(relabel_image_annotations))
+                .route("/projects/{project_id}/image-annotation-categories/{category_id}/merge-into/{target_id}", web::post().to(merge_image_annotation_category))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/image-annotation-categories/{}/merge-into/{}", project.id, automobile.id, car.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let result: CategoryMergeResult = test::read_body_json(resp).await;
+        assert_eq!(result.target.id, car.id);
+        assert_eq!(result.reassigned_annotations, 2);
+        assert!(get_image_annotation_category_by_id(&pool, automobile.id, project.id).await.unwrap().is_none());
+        let sedan = get_image_annotation_category_by_id(&pool, sedan.id, project.id).await.unwrap().unwrap();
+        assert_eq!(sedan.parent_id, None);
+
+        // Only the large box on the first task becomes a truck
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/image-annotation-categories/relabel", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(serde_json::json!({
+                "from_category_ids": [car.id],
+                "to_category_id": truck.id,
+                "task_ids": [task1.id],
+                "min_area": 1000.0
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let result: BulkRelabelResult = test::read_body_json(resp).await;
+        assert_eq!(result.relabeled, 1);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/image-annotation-categories/{}/merge-into/{}", project.id, car.id, car.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_validate_hotkeys() {
+        let car = Uuid::new_v4();
+        let truck = Uuid::new_v4();
+        let hotkey = |category_id: Uuid, key: Option<&str>| CategoryHotkey {
+            category_id,
+            hotkey: key.map(str::to_string),
+        };
+
+        assert!(validate_hotkeys(&[hotkey(car, Some("Digit1")), hotkey(truck, None)]).is_ok());
+        assert!(validate_hotkeys(&[hotkey(car, Some("KeyQ")), hotkey(truck, Some("KeyQ"))]).is_err());
+        assert!(validate_hotkeys(&[hotkey(car, Some("Digit1")), hotkey(car, Some("Digit2"))]).is_err());
+        assert!(validate_hotkeys(&[hotkey(car, Some(" "))]).is_err());
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_update_hotkeys_swaps_keys() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        let car = create_image_annotation_category_in_db(&pool, project.id, "car", None, None, None, None).await.unwrap();
+        let truck = create_image_annotation_category_in_db(&pool, project.id, "truck", None, None, None, None).await.unwrap();
+        let other_project = crate::projects::create_project_in_db(&pool, "Other Project", None, None, user.id).await.unwrap();
+        let bus = create_image_annotation_category_in_db(&pool, other_project.id, "bus", None, None, None, None).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/image-annotation-categories/hotkeys", web::put().to(update_image_annotation_category_hotkeys))
+        ).await;
+
+        // The second request swaps the keys of the first one
+        for (car_key, truck_key) in [("Digit1", "Digit2"), ("Digit2", "Digit1")] {
+            let req = test::TestRequest::put()
+                .uri(&format!("/projects/{}/image-annotation-categories/hotkeys", project.id))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(serde_json::json!({"hotkeys": [
+                    {"category_id": car.id, "hotkey": car_key},
+                    {"category_id": truck.id, "hotkey": truck_key}
+                ]}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), 200);
+        }
+
+        let categories = get_project_image_annotation_categories(&pool, project.id).await.unwrap();
+        let hotkeys: Vec<(&str, Option<&str>)> = categories.iter().map(|c| (c.name.as_str(), c.hotkey.as_deref())).collect();
+        assert_eq!(hotkeys, vec![("car", Some("Digit2")), ("truck", Some("Digit1"))]);
+
+        // Leaving a category out clears its key, categories of other projects are rejected
+        let req = test::TestRequest::put()
+            .uri(&format!("/projects/{}/image-annotation-categories/hotkeys", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(serde_json::json!({"hotkeys": [{"category_id": car.id, "hotkey": "KeyQ"}]}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let truck = get_image_annotation_category_by_id(&pool, truck.id, project.id).await.unwrap().unwrap();
+        assert_eq!(truck.hotkey, None);
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/projects/{}/image-annotation-categories/hotkeys", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(serde_json::json!({"hotkeys": [{"category_id": bus.id, "hotkey": "Digit3"}]}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+}
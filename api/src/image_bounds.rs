@@ -0,0 +1,150 @@
+// Checks boxes against the image they are drawn on. Boxes are COCO [x, y, width, height] in
+// pixels, rotated boxes are checked by their corners. Projects in strict mode reject boxes
+// that leave the image, the others get axis-aligned boxes clipped to it.
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::annotations::BoundingBox;
+
+/// Slack for coordinates that leave the image by rounding only
+const TOLERANCE: f64 = 1e-6;
+
+/// Area of a COCO box, also the area of the box rotated around its center
+pub fn box_area(bbox: &[f64]) -> f64 {
+    if bbox.len() >= 4 {
+        bbox[2] * bbox[3]
+    } else {
+        0.0
+    }
+}
+
+/// Whether the box, with its rotation, lies within a `width` x `height` image
+pub fn is_within_image(bbox: &[f64], rotation: f64, width: f64, height: f64) -> bool {
+    crate::rotated_box::corners(bbox, rotation).iter().all(|(x, y)| {
+        *x >= -TOLERANCE && *y >= -TOLERANCE && *x <= width + TOLERANCE && *y <= height + TOLERANCE
+    })
+}
+
+/// The part of an axis-aligned box inside the image, `None` when nothing of it is
+pub fn clip_to_image(bbox: &[f64], width: f64, height: f64) -> Option<Vec<f64>> {
+    let (min_x, min_y) = (bbox[0].clamp(0.0, width), bbox[1].clamp(0.0, height));
+    let (max_x, max_y) = ((bbox[0] + bbox[2]).clamp(0.0, width), (bbox[1] + bbox[3]).clamp(0.0, height));
+    (max_x > min_x && max_y > min_y).then(|| vec![min_x, min_y, max_x - min_x, max_y - min_y])
+}
+
+/// A box of the request that leaves the image
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OutOfBoundsBox {
+    /// Position of the box in `bboxes`
+    pub index: usize,
+    pub bbox: Vec<f64>,
+    pub rotation: f64,
+}
+
+/// Body of the 400 answered when boxes leave the image
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OutOfBoundsError {
+    pub error: String,
+    pub image_width: i32,
+    pub image_height: i32,
+    pub boxes: Vec<OutOfBoundsBox>,
+}
+
+/// Fits the boxes into a `width` x `height` image. Strict projects get every box leaving the
+/// image back as an error. Otherwise axis-aligned boxes are clipped, rotated boxes are kept as
+/// drawn, and only boxes entirely outside the image are errors.
+pub fn fit_boxes(bboxes: &mut [BoundingBox], width: i32, height: i32, strict: bool) -> Result<(), OutOfBoundsError> {
+    let (image_width, image_height) = (width as f64, height as f64);
+    let mut out_of_bounds = Vec::new();
+
+    for (index, bbox) in bboxes.iter_mut().enumerate() {
+        let rotation = bbox.rotation.unwrap_or(0.0);
+        if is_within_image(&bbox.bbox, rotation, image_width, image_height) {
+            continue;
+        }
+
+        if !strict && rotation != 0.0 {
+            continue;
+        }
+        match clip_to_image(&bbox.bbox, image_width, image_height).filter(|_| !strict) {
+            Some(clipped) => bbox.bbox = clipped,
+            None => out_of_bounds.push(OutOfBoundsBox { index, bbox: bbox.bbox.clone(), rotation }),
+        }
+    }
+
+    if out_of_bounds.is_empty() {
+        return Ok(());
+    }
+    let error = if strict {
+        "Bounding boxes must lie within the image"
+    } else {
+        "Bounding boxes lie entirely outside the image"
+    };
+    Err(OutOfBoundsError {
+        error: error.to_string(),
+        image_width: width,
+        image_height: height,
+        boxes: out_of_bounds,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn bbox(values: [f64; 4], rotation: Option<f64>) -> BoundingBox {
+        BoundingBox {
+            category_id: Uuid::new_v4(),
+            bbox: values.to_vec(),
+            area: None,
+            iscrowd: None,
+            is_prediction: None,
+            confidence: None,
+            attributes: None,
+            rotation,
+            frame_index: None,
+            track_id: None,
+            is_interpolated: None,
+            mask: None,
+        }
+    }
+
+    #[test]
+    fn test_is_within_image() {
+        assert!(is_within_image(&[0.0, 0.0, 100.0, 50.0], 0.0, 100.0, 50.0));
+        assert!(!is_within_image(&[10.0, 0.0, 100.0, 50.0], 0.0, 100.0, 50.0));
+        // Turned upright the 40x10 box pokes out above and below a 20 pixel high image
+        assert!(!is_within_image(&[0.0, 5.0, 40.0, 10.0], 90.0, 100.0, 20.0));
+    }
+
+    #[test]
+    fn test_clip_to_image() {
+        assert_eq!(clip_to_image(&[-10.0, 20.0, 50.0, 100.0], 100.0, 80.0), Some(vec![0.0, 20.0, 40.0, 60.0]));
+        assert_eq!(clip_to_image(&[120.0, 0.0, 10.0, 10.0], 100.0, 80.0), None);
+    }
+
+    #[test]
+    fn test_fit_boxes_clips_unless_strict() {
+        let mut boxes = vec![bbox([90.0, 0.0, 20.0, 10.0], None), bbox([0.0, 5.0, 40.0, 10.0], Some(90.0))];
+        fit_boxes(&mut boxes, 100, 20, false).unwrap();
+        assert_eq!(boxes[0].bbox, vec![90.0, 0.0, 10.0, 10.0]);
+        assert_eq!(boxes[1].bbox, vec![0.0, 5.0, 40.0, 10.0]);
+
+        let mut boxes = vec![bbox([0.0, 0.0, 10.0, 10.0], None), bbox([90.0, 0.0, 20.0, 10.0], None)];
+        let error = fit_boxes(&mut boxes, 100, 20, true).unwrap_err();
+        assert_eq!(error.boxes.len(), 1);
+        assert_eq!(error.boxes[0].index, 1);
+        assert_eq!((error.image_width, error.image_height), (100, 20));
+
+        let mut boxes = vec![bbox([200.0, 0.0, 20.0, 10.0], None)];
+        assert!(fit_boxes(&mut boxes, 100, 20, false).is_err());
+    }
+
+    #[test]
+    fn test_box_area() {
+        assert_eq!(box_area(&[5.0, 5.0, 20.0, 3.0]), 60.0);
+        assert_eq!(box_area(&[]), 0.0);
+    }
+}
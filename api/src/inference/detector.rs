@@ -0,0 +1,211 @@
+/// A single detection in original image pixels, with a COCO `[x, y, width, height]` box.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detection {
+    pub class_index: usize,
+    pub score: f32,
+    pub bbox: [f32; 4],
+}
+
+#[derive(Debug, Clone)]
+pub struct DetectorOptions {
+    pub input_width: u32,
+    pub input_height: u32,
+    pub score_threshold: f32,
+    pub iou_threshold: f32,
+}
+
+/// Decodes a YOLOv8-style output tensor of shape `[1, 4 + num_classes, num_anchors]`.
+/// Each anchor holds a center-format box followed by one score per class; boxes are
+/// scaled from model input space back to the original image.
+pub fn decode_yolo_output(
+    output: &[f32],
+    num_channels: usize,
+    num_anchors: usize,
+    scale_x: f32,
+    scale_y: f32,
+    score_threshold: f32,
+) -> Vec<Detection> {
+    if num_channels <= 4 || output.len() < num_channels * num_anchors {
+        return Vec::new();
+    }
+
+    let value = |channel: usize, anchor: usize| output[channel * num_anchors + anchor];
+    let mut detections = Vec::new();
+
+    for anchor in 0..num_anchors {
+        let (class_index, score) = (4..num_channels)
+            .map(|channel| (channel - 4, value(channel, anchor)))
+            .fold((0, f32::MIN), |best, current| if current.1 > best.1 { current } else { best });
+
+        if score < score_threshold {
+            continue;
+        }
+
+        let center_x = value(0, anchor) * scale_x;
+        let center_y = value(1, anchor) * scale_y;
+        let width = value(2, anchor) * scale_x;
+        let height = value(3, anchor) * scale_y;
+
+        detections.push(Detection {
+            class_index,
+            score,
+            bbox: [center_x - width / 2.0, center_y - height / 2.0, width, height],
+        });
+    }
+
+    detections
+}
+
+/// Greedy per-class non-maximum suppression, highest score first.
+pub fn non_max_suppression(mut detections: Vec<Detection>, iou_threshold: f32) -> Vec<Detection> {
+    detections.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    let mut kept: Vec<Detection> = Vec::new();
+    for detection in detections {
+        let suppressed = kept.iter().any(|other| {
+            other.class_index == detection.class_index && iou(&other.bbox, &detection.bbox) > iou_threshold
+        });
+        if !suppressed {
+            kept.push(detection);
+        }
+    }
+
+    kept
+}
+
+/// Clips a box to the image bounds, returning `None` when nothing is left of it.
+pub fn clamp_to_image(bbox: [f32; 4], image_width: f32, image_height: f32) -> Option<[f32; 4]> {
+    let x1 = bbox[0].clamp(0.0, image_width);
+    let y1 = bbox[1].clamp(0.0, image_height);
+    let x2 = (bbox[0] + bbox[2]).clamp(0.0, image_width);
+    let y2 = (bbox[1] + bbox[3]).clamp(0.0, image_height);
+
+    if x2 - x1 < 1.0 || y2 - y1 < 1.0 {
+        return None;
+    }
+
+    Some([x1, y1, x2 - x1, y2 - y1])
+}
+
+fn iou(a: &[f32; 4], b: &[f32; 4]) -> f32 {
+    let x1 = a[0].max(b[0]);
+    let y1 = a[1].max(b[1]);
+    let x2 = (a[0] + a[2]).min(b[0] + b[2]);
+    let y2 = (a[1] + a[3]).min(b[1] + b[3]);
+
+    let intersection = (x2 - x1).max(0.0) * (y2 - y1).max(0.0);
+    let union = a[2] * a[3] + b[2] * b[3] - intersection;
+
+    if union <= 0.0 { 0.0 } else { intersection / union }
+}
+
+/// Resizes the image to the model input size and lays it out as a normalized RGB `[1, 3, H, W]` tensor.
+/// Returns the tensor data together with the original image size.
+#[cfg_attr(not(feature = "inference"), allow(dead_code))]
+pub fn preprocess_image(
+    image_bytes: &[u8],
+    input_width: u32,
+    input_height: u32,
+) -> Result<(Vec<f32>, u32, u32), String> {
+    let image = image::load_from_memory(image_bytes)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let (original_width, original_height) = (image.width(), image.height());
+
+    let resized = image
+        .resize_exact(input_width, input_height, image::imageops::FilterType::Triangle)
+        .to_rgb8();
+
+    let plane = (input_width * input_height) as usize;
+    let mut tensor = vec![0.0f32; 3 * plane];
+    for (index, pixel) in resized.pixels().enumerate() {
+        for channel in 0..3 {
+            tensor[channel * plane + index] = pixel[channel] as f32 / 255.0;
+        }
+    }
+
+    Ok((tensor, original_width, original_height))
+}
+
+/// A loaded ONNX model. Building the session is much slower than running it, so models are
+/// kept between requests; a run needs the session to itself.
+#[cfg(feature = "inference")]
+pub struct Model {
+    session: std::sync::Mutex<ort::session::Session>,
+}
+
+/// Without the `inference` feature no model can be loaded.
+#[cfg(not(feature = "inference"))]
+pub enum Model {}
+
+/// Builds an inference session from the ONNX model bytes.
+#[cfg(feature = "inference")]
+pub fn load_model(model_bytes: &[u8]) -> Result<Model, String> {
+    let session = ort::session::Session::builder()
+        .and_then(|builder| builder.commit_from_memory(model_bytes))
+        .map_err(|e| format!("Failed to load model: {}", e))?;
+    Ok(Model { session: std::sync::Mutex::new(session) })
+}
+
+/// Fallback used when the server was built without the `inference` feature.
+#[cfg(not(feature = "inference"))]
+pub fn load_model(_model_bytes: &[u8]) -> Result<Model, String> {
+    Err("Inference support is not enabled on this server".to_string())
+}
+
+/// Runs the ONNX model over the image and returns the filtered detections.
+#[cfg(feature = "inference")]
+pub fn run_detection(
+    model: &Model,
+    image_bytes: &[u8],
+    options: &DetectorOptions,
+) -> Result<Vec<Detection>, String> {
+    use ort::value::Tensor;
+
+    let (input, original_width, original_height) =
+        preprocess_image(image_bytes, options.input_width, options.input_height)?;
+
+    let mut session = model.session.lock().unwrap_or_else(|e| e.into_inner());
+
+    let shape = [1usize, 3, options.input_height as usize, options.input_width as usize];
+    let tensor = Tensor::from_array((shape, input.into_boxed_slice()))
+        .map_err(|e| format!("Failed to build input tensor: {}", e))?;
+
+    let outputs = session
+        .run(ort::inputs![tensor])
+        .map_err(|e| format!("Model inference failed: {}", e))?;
+
+    let (output_shape, data) = outputs[0]
+        .try_extract_tensor::<f32>()
+        .map_err(|e| format!("Unexpected model output: {}", e))?;
+
+    if output_shape.len() != 3 {
+        return Err(format!("Expected a 3-dimensional output, got {:?}", output_shape));
+    }
+
+    let detections = decode_yolo_output(
+        data,
+        output_shape[1] as usize,
+        output_shape[2] as usize,
+        original_width as f32 / options.input_width as f32,
+        original_height as f32 / options.input_height as f32,
+        options.score_threshold,
+    );
+
+    Ok(non_max_suppression(detections, options.iou_threshold)
+        .into_iter()
+        .filter_map(|detection| {
+            clamp_to_image(detection.bbox, original_width as f32, original_height as f32)
+                .map(|bbox| Detection { bbox, ..detection })
+        })
+        .collect())
+}
+
+/// Fallback used when the server was built without the `inference` feature.
+#[cfg(not(feature = "inference"))]
+pub fn run_detection(
+    model: &Model,
+    _image_bytes: &[u8],
+    _options: &DetectorOptions,
+) -> Result<Vec<Detection>, String> {
+    match *model {}
+}
@@ -0,0 +1,543 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::auth::{JwtManager, Claims};
+use crate::coco::fetch::{private_host, redirect_policy, PublicResolver};
+use crate::predictions::{PredictionInput, import_task_predictions};
+use crate::storage::factory::create_storage_provider_from_project;
+use super::detector::{self, Detection, DetectorOptions};
+use super::types::{ProjectModel, UpdateProjectModelRequest, AutoAnnotateResult};
+
+const DEFAULT_INPUT_SIZE: i32 = 640;
+const DEFAULT_SCORE_THRESHOLD: f64 = 0.25;
+const DEFAULT_IOU_THRESHOLD: f64 = 0.45;
+/// How long to wait for the host of a model or image to accept the connection
+const CONNECT_TIMEOUT_SECS: u64 = 10;
+/// How long a whole model or image download may take
+const REQUEST_TIMEOUT_SECS: u64 = 300;
+/// Largest model file that is loaded
+const MAX_MODEL_BYTES: usize = 512 * 1024 * 1024;
+/// Largest image that is run through a model
+const MAX_IMAGE_BYTES: usize = 100 * 1024 * 1024;
+
+/// A project's loaded model and the URL it came from
+struct CachedModel {
+    model_url: String,
+    model: Arc<detector::Model>,
+}
+
+/// Loaded models by project. A model is downloaded and built again only when the project's
+/// model URL changes or its settings are saved.
+fn model_cache() -> &'static Mutex<HashMap<Uuid, CachedModel>> {
+    static MODELS: OnceLock<Mutex<HashMap<Uuid, CachedModel>>> = OnceLock::new();
+    MODELS.get_or_init(Default::default)
+}
+
+/// Model and image URLs are set by project members, so downloads only reach public hosts.
+fn download_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .dns_resolver(Arc::new(PublicResolver))
+            .redirect(redirect_policy())
+            .build()
+            .expect("Failed to build the model download client")
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/model",
+    tag = "inference",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 200, body = ProjectModel),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or no model configured", body = String),
+    ),
+)]
+pub async fn get_project_model(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match get_project_model_from_db(&pool, project_id).await {
+        Ok(Some(model)) => HttpResponse::Ok().json(model),
+        Ok(None) => HttpResponse::NotFound().json("No model configured for this project"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch model"),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/projects/{project_id}/model",
+    tag = "inference",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    request_body = UpdateProjectModelRequest,
+    responses(
+        (status = 200, body = ProjectModel),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or the user isn't its owner", body = String),
+    ),
+)]
+pub async fn update_project_model(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+    model_req: web::Json<UpdateProjectModelRequest>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    if let Err(message) = validate_model_request(&model_req) {
+        return HttpResponse::BadRequest().json(message);
+    }
+
+    // Only project owners may change the model
+    match user_is_project_owner(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or permission denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check permissions"),
+    }
+
+    match upsert_project_model_in_db(&pool, project_id, &model_req).await {
+        Ok(model) => {
+            // The file behind an unchanged URL may have been replaced
+            model_cache().lock().unwrap_or_else(|e| e.into_inner()).remove(&project_id);
+            HttpResponse::Ok().json(model)
+        }
+        Err(_) => HttpResponse::InternalServerError().json("Failed to update model"),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/tasks/{task_id}/auto-annotate",
+    tag = "inference",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    responses(
+        (status = 200, description = "Detections saved as predictions", body = AutoAnnotateResult),
+        (status = 400, description = "No model configured, or the model or image can't be loaded", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found", body = String),
+        (status = 501, description = "Server built without inference support", body = String),
+    ),
+)]
+pub async fn auto_annotate_task(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str) = path.into_inner();
+
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    if !cfg!(feature = "inference") {
+        return HttpResponse::NotImplemented().json("Inference support is not enabled on this server");
+    }
+
+    let model = match get_project_model_from_db(&pool, project_id).await {
+        Ok(Some(model)) => model,
+        Ok(None) => return HttpResponse::BadRequest().json("No model configured for this project"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch model"),
+    };
+
+    let resource_url = match get_task_resource_url(&pool, project_id, task_id).await {
+        Ok(Some(Some(url))) => url,
+        Ok(Some(None)) => return HttpResponse::BadRequest().json("Task has no image"),
+        Ok(None) => return HttpResponse::NotFound().json("Task not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch task"),
+    };
+
+    let project = match get_project_by_id(&pool, project_id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    };
+
+    let detector_model = match load_project_model(&project, &model.model_url).await {
+        Ok(detector_model) => detector_model,
+        Err(err) => return HttpResponse::BadRequest().json(err),
+    };
+
+    let image_bytes = match fetch_resource(&project, &resource_url, MAX_IMAGE_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(err) => return HttpResponse::BadRequest().json(format!("Failed to load image: {}", err)),
+    };
+
+    let options = DetectorOptions {
+        input_width: model.input_width as u32,
+        input_height: model.input_height as u32,
+        score_threshold: model.score_threshold as f32,
+        iou_threshold: model.iou_threshold as f32,
+    };
+
+    // Inference is CPU bound, keep it off the async workers
+    let detections = match web::block(move || detector::run_detection(&detector_model, &image_bytes, &options)).await {
+        Ok(Ok(detections)) => detections,
+        Ok(Err(err)) => {
+            eprintln!("Auto-annotation error: {}", err);
+            return HttpResponse::UnprocessableEntity().json(err);
+        }
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to run model"),
+    };
+
+    match store_detections(&pool, project_id, task_id, user_id, &model.class_names, &detections).await {
+        Ok(result) => HttpResponse::Ok().json(result),
+        Err(err) => {
+            eprintln!("Auto-annotation error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to store predictions")
+        }
+    }
+}
+
+fn validate_model_request(request: &UpdateProjectModelRequest) -> Result<(), String> {
+    let url = request.model_url.trim();
+    if !(url.starts_with("storage://") || url.starts_with("http://") || url.starts_with("https://")) {
+        return Err("model_url must be a storage:// key or an http(s) URL".to_string());
+    }
+
+    if request.class_names.is_empty() {
+        return Err("class_names must list at least one class".to_string());
+    }
+
+    for size in [request.input_width, request.input_height].into_iter().flatten() {
+        if !(32..=4096).contains(&size) {
+            return Err("Model input size must be between 32 and 4096".to_string());
+        }
+    }
+
+    for threshold in [request.score_threshold, request.iou_threshold].into_iter().flatten() {
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err("Thresholds must be between 0 and 1".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Maps model classes to project categories by name and stores the detections
+/// as prediction boxes on a new annotation version of the task.
+async fn store_detections(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    task_id: Uuid,
+    user_id: Uuid,
+    class_names: &[String],
+    detections: &[Detection],
+) -> Result<AutoAnnotateResult, sqlx::Error> {
+    let categories: HashMap<String, Uuid> = sqlx::query_as::<_, (String, Uuid)>(
+        "SELECT name, id FROM image_annotation_categories WHERE project_id = $1"
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    let mut skipped_classes = Vec::new();
+    let mut inputs = Vec::new();
+    for detection in detections {
+        let class_name = class_names
+            .get(detection.class_index)
+            .cloned()
+            .unwrap_or_else(|| format!("class_{}", detection.class_index));
+
+        match categories.get(&class_name) {
+            Some(&category_id) => inputs.push((
+                PredictionInput {
+                    image_id: None,
+                    file_name: None,
+                    task_id: Some(task_id),
+                    category_id: None,
+                    category_name: Some(class_name),
+                    bbox: detection.bbox.iter().map(|&v| v as f64).collect(),
+                    score: detection.score as f64,
+                },
+                category_id,
+            )),
+            None => {
+                if !skipped_classes.contains(&class_name) {
+                    skipped_classes.push(class_name);
+                }
+            }
+        }
+    }
+
+    let predictions_created = if inputs.is_empty() {
+        0
+    } else {
+        let predictions: Vec<(&PredictionInput, Uuid)> = inputs.iter().map(|(input, id)| (input, *id)).collect();
+        let mut tx = pool.begin().await?;
+        let created = import_task_predictions(&mut tx, task_id, &predictions, user_id).await?;
+        tx.commit().await?;
+        created
+    };
+
+    Ok(AutoAnnotateResult {
+        detections: detections.len(),
+        predictions_created,
+        skipped_classes,
+    })
+}
+
+/// Returns the project's model, downloading and building it only when none is cached for the
+/// configured URL.
+async fn load_project_model(
+    project: &crate::projects::Project,
+    model_url: &str,
+) -> Result<Arc<detector::Model>, String> {
+    let cached = model_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&project.id)
+        .filter(|cached| cached.model_url == model_url)
+        .map(|cached| cached.model.clone());
+    if let Some(model) = cached {
+        return Ok(model);
+    }
+
+    let model_bytes = fetch_resource(project, model_url, MAX_MODEL_BYTES)
+        .await
+        .map_err(|e| format!("Failed to load model: {}", e))?;
+    // Building the session is CPU bound too
+    let model = match web::block(move || detector::load_model(&model_bytes)).await {
+        Ok(result) => Arc::new(result?),
+        Err(e) => return Err(format!("Failed to load model: {}", e)),
+    };
+
+    model_cache().lock().unwrap_or_else(|e| e.into_inner()).insert(
+        project.id,
+        CachedModel { model_url: model_url.to_string(), model: model.clone() },
+    );
+    Ok(model)
+}
+
+/// Loads a `storage://` key from the project storage or downloads an http(s) URL, refusing
+/// anything larger than `max_bytes`.
+async fn fetch_resource(project: &crate::projects::Project, url: &str, max_bytes: usize) -> Result<Vec<u8>, String> {
+    if let Some(key) = url.strip_prefix("storage://") {
+        let provider = create_storage_provider_from_project(project)
+            .await
+            .map_err(|e| e.to_string())?;
+        let data = provider.download(key).await.map_err(|e| e.to_string())?;
+        if data.len() > max_bytes {
+            return Err(format!("larger than the {} byte limit", max_bytes));
+        }
+        return Ok(data);
+    }
+
+    let url = reqwest::Url::parse(url).map_err(|e| e.to_string())?;
+    if let Some(ip) = private_host(&url) {
+        return Err(format!("{} is a private address", ip));
+    }
+
+    let mut response = download_client()
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| e.to_string())?;
+
+    let mut data = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        if data.len() + chunk.len() > max_bytes {
+            return Err(format!("larger than the {} byte limit", max_bytes));
+        }
+        data.extend_from_slice(&chunk);
+    }
+    Ok(data)
+}
+
+async fn get_project_model_from_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Option<ProjectModel>, sqlx::Error> {
+    sqlx::query_as::<_, ProjectModel>(
+        r#"
+        SELECT id, project_id, model_url, input_width, input_height, class_names,
+               score_threshold, iou_threshold, created_at, updated_at
+        FROM project_models
+        WHERE project_id = $1
+        "#
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn upsert_project_model_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    request: &UpdateProjectModelRequest,
+) -> Result<ProjectModel, sqlx::Error> {
+    sqlx::query_as::<_, ProjectModel>(
+        r#"
+        INSERT INTO project_models (id, project_id, model_url, input_width, input_height, class_names, score_threshold, iou_threshold, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW(), NOW())
+        ON CONFLICT (project_id) DO UPDATE SET
+            model_url = EXCLUDED.model_url,
+            input_width = EXCLUDED.input_width,
+            input_height = EXCLUDED.input_height,
+            class_names = EXCLUDED.class_names,
+            score_threshold = EXCLUDED.score_threshold,
+            iou_threshold = EXCLUDED.iou_threshold,
+            updated_at = NOW()
+        RETURNING id, project_id, model_url, input_width, input_height, class_names,
+                  score_threshold, iou_threshold, created_at, updated_at
+        "#
+    )
+    .bind(Uuid::new_v4())
+    .bind(project_id)
+    .bind(request.model_url.trim())
+    .bind(request.input_width.unwrap_or(DEFAULT_INPUT_SIZE))
+    .bind(request.input_height.unwrap_or(DEFAULT_INPUT_SIZE))
+    .bind(&request.class_names)
+    .bind(request.score_threshold.unwrap_or(DEFAULT_SCORE_THRESHOLD))
+    .bind(request.iou_threshold.unwrap_or(DEFAULT_IOU_THRESHOLD))
+    .fetch_one(pool)
+    .await
+}
+
+async fn get_task_resource_url(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    task_id: Uuid,
+) -> Result<Option<Option<String>>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<String>>(
+        "SELECT resource_url FROM tasks WHERE id = $1 AND project_id = $2"
+    )
+    .bind(task_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn get_project_by_id(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Option<crate::projects::Project>, sqlx::Error> {
+    sqlx::query_as::<_, crate::projects::Project>(
+        "SELECT id, name, description, storage_config, owner_id, task_type, created_at, updated_at FROM projects WHERE id = $1"
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn user_is_project_owner(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM project_members pm
+            INNER JOIN projects p ON pm.project_id = p.id
+            WHERE p.id = $1 AND pm.user_id = $2 AND (pm.role = 'owner' OR p.owner_id = $2)
+        )
+        "#
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
@@ -0,0 +1,8 @@
+pub mod types;
+pub mod detector;
+pub mod handlers;
+
+pub use handlers::{get_project_model, update_project_model, auto_annotate_task};
+
+#[cfg(test)]
+mod tests;
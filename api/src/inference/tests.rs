@@ -0,0 +1,172 @@
+use super::*;
+use super::detector::{Detection, decode_yolo_output, non_max_suppression, clamp_to_image};
+use super::types::ProjectModel;
+use crate::auth::{User, OAuthConfig, AuthStorage, JwtManager};
+use crate::test_utils;
+use actix_web::{test, App, web};
+use serial_test::serial;
+
+fn create_test_oauth_config() -> OAuthConfig {
+    OAuthConfig {
+        google_client_id: "test_google_id".to_string(),
+        google_client_secret: "test_google_secret".to_string(),
+        google_redirect_url: "http://localhost/callback".to_string(),
+        github_client_id: "test_github_id".to_string(),
+        github_client_secret: "test_github_secret".to_string(),
+        github_redirect_url: "http://localhost/callback".to_string(),
+        jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+    }
+}
+
+fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+    let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+    jwt_manager.generate_token(
+        &user.id.to_string(),
+        &user.email,
+        &user.name
+    ).expect("Failed to generate token")
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_update_and_get_project_model() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+    let auth_storage = AuthStorage::new(pool.clone());
+
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/model", web::get().to(get_project_model))
+            .route("/projects/{project_id}/model", web::put().to(update_project_model))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/model", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/projects/{}/model", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(serde_json::json!({
+            "model_url": "storage://models/yolov8n.onnx",
+            "class_names": ["person", "car"],
+            "score_threshold": 0.4
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/model", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let model: ProjectModel = test::read_body_json(resp).await;
+    assert_eq!(model.model_url, "storage://models/yolov8n.onnx");
+    assert_eq!(model.class_names, vec!["person".to_string(), "car".to_string()]);
+    assert_eq!(model.input_width, 640);
+    assert_eq!(model.score_threshold, 0.4);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_update_project_model_rejects_invalid_url() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .route("/projects/{project_id}/model", web::put().to(update_project_model))
+    ).await;
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/projects/{}/model", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(serde_json::json!({
+            "model_url": "/etc/passwd",
+            "class_names": ["person"]
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_auto_annotate_unauthorized() {
+    let pool = test_utils::setup_test_db().await;
+    let oauth_config = create_test_oauth_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .route("/projects/{project_id}/tasks/{task_id}/auto-annotate", web::post().to(auto_annotate_task))
+    ).await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/projects/{}/tasks/{}/auto-annotate", uuid::Uuid::new_v4(), uuid::Uuid::new_v4()))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_web::test]
+async fn test_decode_yolo_output() {
+    // Two anchors, two classes: channels are cx, cy, w, h, class0, class1
+    let output = vec![
+        50.0, 10.0,  // cx
+        40.0, 10.0,  // cy
+        20.0, 4.0,   // w
+        10.0, 4.0,   // h
+        0.1, 0.05,   // class 0
+        0.9, 0.1,    // class 1
+    ];
+
+    let detections = decode_yolo_output(&output, 6, 2, 2.0, 1.0, 0.25);
+    assert_eq!(detections.len(), 1);
+    assert_eq!(detections[0].class_index, 1);
+    assert_eq!(detections[0].score, 0.9);
+    assert_eq!(detections[0].bbox, [80.0, 35.0, 40.0, 10.0]);
+}
+
+#[actix_web::test]
+async fn test_non_max_suppression_is_per_class() {
+    let detection = |class_index, score, x| Detection { class_index, score, bbox: [x, 0.0, 10.0, 10.0] };
+
+    let kept = non_max_suppression(
+        vec![detection(0, 0.5, 1.0), detection(0, 0.9, 0.0), detection(1, 0.7, 0.0), detection(0, 0.6, 50.0)],
+        0.45,
+    );
+
+    assert_eq!(kept.len(), 3);
+    assert_eq!(kept[0].score, 0.9);
+    assert!(kept.iter().any(|d| d.class_index == 1));
+    assert!(!kept.iter().any(|d| d.score == 0.5));
+}
+
+#[actix_web::test]
+async fn test_clamp_to_image() {
+    assert_eq!(clamp_to_image([-5.0, -5.0, 20.0, 20.0], 100.0, 100.0), Some([0.0, 0.0, 15.0, 15.0]));
+    assert_eq!(clamp_to_image([95.0, 10.0, 20.0, 20.0], 100.0, 100.0), Some([95.0, 10.0, 5.0, 20.0]));
+    assert_eq!(clamp_to_image([150.0, 10.0, 20.0, 20.0], 100.0, 100.0), None);
+}
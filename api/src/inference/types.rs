@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+/// Detection model attached to a project for auto-annotation.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, sqlx::FromRow)]
+pub struct ProjectModel {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub model_url: String,
+    pub input_width: i32,
+    pub input_height: i32,
+    /// Category name for each model output class, in class index order
+    pub class_names: Vec<String>,
+    pub score_threshold: f64,
+    pub iou_threshold: f64,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateProjectModelRequest {
+    pub model_url: String,
+    pub input_width: Option<i32>,
+    pub input_height: Option<i32>,
+    pub class_names: Vec<String>,
+    pub score_threshold: Option<f64>,
+    pub iou_threshold: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AutoAnnotateResult {
+    pub detections: usize,
+    pub predictions_created: usize,
+    pub skipped_classes: Vec<String>,
+}
@@ -0,0 +1,435 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use std::collections::{BTreeMap, HashSet};
+use uuid::Uuid;
+use chrono::Utc;
+use utoipa::ToSchema;
+
+use crate::annotations::BoundingBox;
+use crate::auth::{JwtManager, Claims};
+
+/// One box of a track. Keyframes are read in this shape and interpolated boxes written out in it.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct TrackBox {
+    pub track_id: Uuid,
+    pub frame_index: i32,
+    pub category_id: Option<Uuid>,
+    pub bbox: Vec<f64>,
+    pub rotation: f64,
+    pub attributes: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InterpolationResponse {
+    pub annotation_id: Uuid,
+    pub track_count: usize,
+    pub interpolated_count: usize,
+}
+
+/// Fills the frames between the keyframes of every track in the task's latest annotation.
+/// Boxes from an earlier run are replaced, so calling it again after moving a keyframe is safe.
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/tasks/{task_id}/interpolate",
+    tag = "annotations",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    responses(
+        (status = 200, body = InterpolationResponse),
+        (status = 400, description = "Not a video task or nothing to interpolate", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found", body = String),
+    ),
+)]
+pub async fn interpolate_task(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match get_task_frame_count(&pool, task_id, project_id).await {
+        Ok(Some(Some(_))) => {}
+        Ok(Some(None)) => return HttpResponse::BadRequest().json("Only video tasks can be interpolated"),
+        Ok(None) => return HttpResponse::NotFound().json("Task not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch task"),
+    }
+
+    let annotation_id = match get_latest_annotation_id(&pool, task_id).await {
+        Ok(Some(id)) => id,
+        Ok(None) => return HttpResponse::BadRequest().json("Task has no annotations to interpolate"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotations"),
+    };
+
+    let keyframes = match get_keyframes(&pool, annotation_id).await {
+        Ok(keyframes) => keyframes,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotations"),
+    };
+
+    let interpolated = interpolate_tracks(&keyframes);
+    if let Err(e) = replace_interpolated_boxes(&pool, annotation_id, &interpolated).await {
+        eprintln!("Failed to save interpolated boxes: {}", e);
+        return HttpResponse::InternalServerError().json("Failed to save interpolated boxes");
+    }
+
+    let track_count = keyframes.iter().map(|keyframe| keyframe.track_id).collect::<HashSet<_>>().len();
+    HttpResponse::Ok().json(InterpolationResponse {
+        annotation_id,
+        track_count,
+        interpolated_count: interpolated.len(),
+    })
+}
+
+/// Checks the track fields of submitted boxes: a track has at most one box per frame, or per
+/// image when the task isn't a video (its other boxes are on the tasks that follow). Only video
+/// frames are interpolated.
+pub fn validate_tracks(bboxes: &[BoundingBox]) -> Result<(), &'static str> {
+    let mut seen = HashSet::new();
+    for bbox in bboxes {
+        match bbox.track_id {
+            Some(track_id) => {
+                if !seen.insert((track_id, bbox.frame_index)) {
+                    return Err("A track can only have one box per frame");
+                }
+                if bbox.is_interpolated == Some(true) && bbox.frame_index.is_none() {
+                    return Err("Interpolated boxes must be on a video frame");
+                }
+            }
+            None => {
+                if bbox.is_interpolated == Some(true) {
+                    return Err("Interpolated boxes must belong to a track");
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Linearly interpolates every track between consecutive keyframes. Generated boxes take the
+/// category and attributes of the keyframe before them; rotation turns the shorter way round.
+pub fn interpolate_tracks(keyframes: &[TrackBox]) -> Vec<TrackBox> {
+    let mut tracks: BTreeMap<Uuid, Vec<&TrackBox>> = BTreeMap::new();
+    for keyframe in keyframes.iter().filter(|keyframe| keyframe.bbox.len() == 4) {
+        tracks.entry(keyframe.track_id).or_default().push(keyframe);
+    }
+
+    let mut interpolated = Vec::new();
+    for track in tracks.values_mut() {
+        track.sort_by_key(|keyframe| keyframe.frame_index);
+
+        for pair in track.windows(2) {
+            let (start, end) = (pair[0], pair[1]);
+            let span = end.frame_index - start.frame_index;
+            let rotation_delta = crate::rotated_box::normalize_rotation(end.rotation - start.rotation);
+
+            for frame_index in start.frame_index + 1..end.frame_index {
+                let t = (frame_index - start.frame_index) as f64 / span as f64;
+                interpolated.push(TrackBox {
+                    track_id: start.track_id,
+                    frame_index,
+                    category_id: start.category_id,
+                    bbox: start.bbox.iter().zip(&end.bbox).map(|(a, b)| a + (b - a) * t).collect(),
+                    rotation: crate::rotated_box::normalize_rotation(start.rotation + rotation_delta * t),
+                    attributes: start.attributes.clone(),
+                });
+            }
+        }
+    }
+
+    interpolated
+}
+
+/// `None` when the task doesn't exist in the project, `Some(None)` for image tasks.
+async fn get_task_frame_count(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
+    project_id: Uuid,
+) -> Result<Option<Option<i32>>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<i32>>("SELECT frame_count FROM tasks WHERE id = $1 AND project_id = $2")
+        .bind(task_id)
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+}
+
+async fn get_latest_annotation_id(pool: &Pool<Postgres>, task_id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+    sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM annotations WHERE task_id = $1 ORDER BY created_at DESC LIMIT 1"
+    )
+    .bind(task_id)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn get_keyframes(pool: &Pool<Postgres>, annotation_id: Uuid) -> Result<Vec<TrackBox>, sqlx::Error> {
+    sqlx::query_as::<_, TrackBox>(
+        r#"
+        SELECT track_id, frame_index, category_id, bbox, rotation, attributes
+        FROM image_annotations
+        WHERE annotation_id = $1
+            AND track_id IS NOT NULL
+            AND frame_index IS NOT NULL
+            AND NOT is_interpolated
+            AND NOT is_prediction
+        "#
+    )
+    .bind(annotation_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn replace_interpolated_boxes(
+    pool: &Pool<Postgres>,
+    annotation_id: Uuid,
+    boxes: &[TrackBox],
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM image_annotations WHERE annotation_id = $1 AND is_interpolated")
+        .bind(annotation_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for track_box in boxes {
+        sqlx::query(
+            r#"
+            INSERT INTO image_annotations (id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, is_prediction, confidence, attributes, rotation, frame_index, track_id, is_interpolated, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, FALSE, '{}', FALSE, NULL, $6, $7, $8, $9, TRUE, $10, $10)
+            "#
+        )
+        .bind(Uuid::new_v4())
+        .bind(annotation_id)
+        .bind(track_box.category_id)
+        .bind(&track_box.bbox)
+        .bind(track_box.bbox[2] * track_box.bbox[3])
+        .bind(&track_box.attributes)
+        .bind(track_box.rotation)
+        .bind(track_box.frame_index)
+        .bind(track_box.track_id)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::OAuthConfig;
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn keyframe(track_id: Uuid, frame_index: i32, bbox: [f64; 4], rotation: f64) -> TrackBox {
+        TrackBox {
+            track_id,
+            frame_index,
+            category_id: None,
+            bbox: bbox.to_vec(),
+            rotation,
+            attributes: serde_json::json!({}),
+        }
+    }
+
+    fn track_bbox(track_id: Option<Uuid>, frame_index: Option<i32>, is_interpolated: Option<bool>) -> BoundingBox {
+        BoundingBox {
+            category_id: Uuid::new_v4(),
+            bbox: vec![0.0, 0.0, 1.0, 1.0],
+            area: None,
+            iscrowd: None,
+            is_prediction: None,
+            confidence: None,
+            attributes: None,
+            rotation: None,
+            frame_index,
+            track_id,
+            is_interpolated,
+            mask: None,
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_interpolate_tracks() {
+        let car = Uuid::new_v4();
+        let person = Uuid::new_v4();
+        let keyframes = vec![
+            keyframe(car, 4, [40.0, 20.0, 20.0, 40.0], -170.0),
+            keyframe(car, 0, [0.0, 0.0, 10.0, 20.0], 170.0),
+            keyframe(car, 5, [0.0, 0.0, 1.0, 1.0], 0.0),
+            keyframe(person, 0, [0.0, 0.0, 1.0, 1.0], 0.0),
+        ];
+
+        let interpolated = interpolate_tracks(&keyframes);
+
+        // Only the gap between frames 0 and 4 of the car needs boxes
+        assert_eq!(interpolated.len(), 3);
+        assert!(interpolated.iter().all(|track_box| track_box.track_id == car));
+        assert_eq!(interpolated[0].frame_index, 1);
+        assert_eq!(interpolated[0].bbox, vec![10.0, 5.0, 12.5, 25.0]);
+        assert_eq!(interpolated[1].bbox, vec![20.0, 10.0, 15.0, 30.0]);
+        // 170° to -170° turns 20° through 180°, not 340° back through 0°
+        assert!((interpolated[1].rotation - 180.0).abs() < 1e-9);
+        assert!((interpolated[2].rotation + 175.0).abs() < 1e-9);
+    }
+
+    #[actix_web::test]
+    async fn test_validate_tracks() {
+        let track = Uuid::new_v4();
+        assert!(validate_tracks(&[track_bbox(None, None, None), track_bbox(Some(track), Some(0), None)]).is_ok());
+        assert!(validate_tracks(&[track_bbox(Some(track), Some(0), None), track_bbox(Some(track), Some(1), Some(true))]).is_ok());
+        // Image tasks carry a track with one box each
+        assert!(validate_tracks(&[track_bbox(Some(track), None, None), track_bbox(Some(Uuid::new_v4()), None, None)]).is_ok());
+
+        assert!(validate_tracks(&[track_bbox(Some(track), None, None), track_bbox(Some(track), None, None)]).is_err());
+        assert!(validate_tracks(&[track_bbox(Some(track), None, Some(true))]).is_err());
+        assert!(validate_tracks(&[track_bbox(None, Some(0), Some(true))]).is_err());
+        assert!(validate_tracks(&[track_bbox(Some(track), Some(0), None), track_bbox(Some(track), Some(0), None)]).is_err());
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_interpolate_task() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        };
+        let token = JwtManager::new(&oauth_config.jwt_secret)
+            .generate_token(&user.id.to_string(), &user.email, &user.name)
+            .unwrap();
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "car", None, None, None, None).await.unwrap();
+        let image_task = crate::tasks::create_task_in_db(&pool, project.id, "image.jpg", None).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "drive.mp4", Some("storage://drive.mp4")).await.unwrap();
+        let frames: Vec<crate::video::ExtractedFrame> = (0..5)
+            .map(|frame_index| crate::video::ExtractedFrame {
+                frame_index,
+                storage_key: crate::video::frame_storage_key("drive.mp4", frame_index),
+                timestamp_ms: crate::video::frame_timestamp_ms(frame_index, 1.0),
+            })
+            .collect();
+        crate::video::save_task_frames(&pool, task.id, crate::video::MEDIA_TYPE_VIDEO, &frames, Some(1.0)).await.unwrap();
+
+        let track_id = Uuid::new_v4();
+        let keyframe_box = |frame_index: i32, x: f64| BoundingBox {
+            category_id: category.id,
+            track_id: Some(track_id),
+            frame_index: Some(frame_index),
+            bbox: vec![x, 0.0, 10.0, 10.0],
+            ..track_bbox(None, None, None)
+        };
+        crate::annotations::create_annotation_in_db(
+            &pool,
+            task.id,
+            &[keyframe_box(0, 0.0), keyframe_box(4, 40.0)],
+            &serde_json::json!({}),
+            user.id,
+        ).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/tasks/{task_id}/interpolate", web::post().to(interpolate_task))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/tasks/{}/interpolate", project.id, image_task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+
+        // Running twice replaces the boxes of the first run instead of stacking them
+        for _ in 0..2 {
+            let req = test::TestRequest::post()
+                .uri(&format!("/projects/{}/tasks/{}/interpolate", project.id, task.id))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), 200);
+
+            let body: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(body["track_count"], 1);
+            assert_eq!(body["interpolated_count"], 3);
+        }
+
+        let bboxes = sqlx::query_scalar::<_, Vec<f64>>(
+            "SELECT ia.bbox FROM image_annotations ia JOIN annotations a ON a.id = ia.annotation_id WHERE a.task_id = $1 AND ia.is_interpolated ORDER BY ia.frame_index"
+        )
+        .bind(task.id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(bboxes, vec![vec![10.0, 0.0, 10.0, 10.0], vec![20.0, 0.0, 10.0, 10.0], vec![30.0, 0.0, 10.0, 10.0]]);
+    }
+}
@@ -0,0 +1,364 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use sqlx::{Pool, Postgres};
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Write};
+use uuid::Uuid;
+use chrono::Utc;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::auth::{JwtManager, Claims};
+
+/// Directory with one label file per image, as in the KITTI object detection benchmark.
+pub const LABELS_DIR: &str = "label_2";
+/// Directory with one label file per video, as in the KITTI tracking benchmark.
+pub const TRACKING_LABELS_DIR: &str = "label_02";
+
+/// One task joined with one of its (possibly rotated) boxes.
+/// Tasks without boxes appear once with `bbox` set to `None`.
+#[derive(Debug, sqlx::FromRow)]
+pub struct KittiAnnotationRow {
+    pub task_id: Uuid,
+    pub task_name: String,
+    pub resource_url: Option<String>,
+    /// Set for video tasks
+    pub frame_count: Option<i32>,
+    pub category_name: Option<String>,
+    pub bbox: Option<Vec<f64>>,
+    pub rotation: Option<f64>,
+    pub frame_index: Option<i32>,
+    pub track_id: Option<Uuid>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/export/kitti",
+    tag = "export",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 200, description = "ZIP with one KITTI label file per image and one KITTI tracking label file per video", body = [u8], content_type = "application/zip"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn export_project_kitti(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let project_name = match get_project_name(&pool, project_id).await {
+        Ok(Some(name)) => name,
+        Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    };
+
+    let rows = match get_project_rows_for_kitti(&pool, project_id).await {
+        Ok(rows) => rows,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotations"),
+    };
+
+    let archive = match write_archive(&build_kitti_files(&rows)) {
+        Ok(archive) => archive,
+        Err(e) => {
+            eprintln!("Failed to build KITTI archive: {}", e);
+            return HttpResponse::InternalServerError().json("Failed to build export archive");
+        }
+    };
+
+    let filename = format!("{}_kitti_{}.zip",
+        project_name.replace(" ", "_").to_lowercase(),
+        Utc::now().format("%Y%m%d_%H%M%S")
+    );
+
+    HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", filename)))
+        .body(archive)
+}
+
+/// Groups the rows into one `label_2/<image>.txt` file per image task and one
+/// `label_02/<video>.txt` file per video task. Every box becomes a KITTI object line with only
+/// the 2D box filled in (`type 0.00 0 -10.00 left top right bottom` and the 3D fields unset);
+/// video lines start with the frame and a track number counted from 0 within the video.
+/// Rotated boxes are exported as the upright box enclosing them.
+pub fn build_kitti_files(rows: &[KittiAnnotationRow]) -> Vec<(String, String)> {
+    let mut files: Vec<(Uuid, String, String)> = Vec::new();
+    let mut used_names = HashSet::new();
+    let mut tracks: HashMap<Uuid, usize> = HashMap::new();
+    let mut track_count = 0;
+
+    for row in rows {
+        if files.last().is_none_or(|(task_id, _, _)| *task_id != row.task_id) {
+            let file_name = row.resource_url
+                .as_deref()
+                .and_then(|url| url.split('/').next_back())
+                .unwrap_or(&row.task_name);
+            let stem = file_name.rsplit_once('.').map_or(file_name, |(stem, _)| stem).replace(char::is_whitespace, "_");
+            let dir = if row.frame_count.is_some() { TRACKING_LABELS_DIR } else { LABELS_DIR };
+
+            // Files from different folders can share a base name, so prefix duplicates with the task ID
+            let mut label_path = format!("{}/{}.txt", dir, stem);
+            if !used_names.insert(label_path.clone()) {
+                label_path = format!("{}/{}_{}.txt", dir, row.task_id, stem);
+                used_names.insert(label_path.clone());
+            }
+
+            files.push((row.task_id, label_path, String::new()));
+            tracks.clear();
+            track_count = 0;
+        }
+
+        let bbox = match &row.bbox {
+            Some(bbox) if bbox.len() >= 4 => bbox,
+            _ => continue,
+        };
+
+        let corners = crate::rotated_box::corners(bbox, row.rotation.unwrap_or(0.0));
+        let enclosing = crate::rotated_box::enclosing_bbox(&corners);
+        // KITTI separates fields with spaces, so category names cannot contain any
+        let category = row.category_name.as_deref().unwrap_or("unknown").replace(char::is_whitespace, "_");
+        let object = format!(
+            "{} 0.00 0 -10.00 {:.2} {:.2} {:.2} {:.2} -1.00 -1.00 -1.00 -1000.00 -1000.00 -1000.00 -10.00\n",
+            category,
+            enclosing[0],
+            enclosing[1],
+            enclosing[0] + enclosing[2],
+            enclosing[1] + enclosing[3],
+        );
+
+        let line = match (row.frame_count, row.frame_index) {
+            (None, _) => object,
+            (Some(_), Some(frame_index)) => {
+                // Boxes of one track share a number, boxes without a track are objects of their own
+                let track = match row.track_id.and_then(|track_id| tracks.get(&track_id)) {
+                    Some(track) => *track,
+                    None => {
+                        if let Some(track_id) = row.track_id {
+                            tracks.insert(track_id, track_count);
+                        }
+                        track_count += 1;
+                        track_count - 1
+                    }
+                };
+                format!("{} {} {}", frame_index, track, object)
+            }
+            (Some(_), None) => continue,
+        };
+
+        let (_, _, content) = files.last_mut().expect("file was pushed above");
+        content.push_str(&line);
+    }
+
+    files.into_iter().map(|(_, path, content)| (path, content)).collect()
+}
+
+fn write_archive(files: &[(String, String)]) -> zip::result::ZipResult<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (path, content) in files {
+        zip.start_file(path.as_str(), options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+async fn get_project_name(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>("SELECT name FROM projects WHERE id = $1")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+}
+
+async fn get_project_rows_for_kitti(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Vec<KittiAnnotationRow>, sqlx::Error> {
+    // Only the latest annotation of each task is exported, matching the COCO exporter.
+    // KITTI tracking files list the frames in order.
+    sqlx::query_as::<_, KittiAnnotationRow>(
+        r#"
+        WITH latest_annotations AS (
+            SELECT DISTINCT ON (task_id) id, task_id
+            FROM annotations
+            WHERE task_id IN (SELECT id FROM tasks WHERE project_id = $1)
+            ORDER BY task_id, created_at DESC
+        )
+        SELECT
+            t.id as task_id,
+            t.name as task_name,
+            t.resource_url,
+            t.frame_count,
+            iac.name as category_name,
+            ia.bbox,
+            ia.rotation,
+            ia.frame_index,
+            ia.track_id
+        FROM tasks t
+        LEFT JOIN latest_annotations la ON la.task_id = t.id
+        LEFT JOIN image_annotations ia ON ia.annotation_id = la.id AND NOT ia.is_prediction
+        LEFT JOIN image_annotation_categories iac ON iac.id = ia.category_id
+        WHERE t.project_id = $1
+        ORDER BY t.created_at, t.id, ia.frame_index, ia.created_at
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::OAuthConfig;
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn image_row(task_id: Uuid, resource_url: &str, category: &str, bbox: Option<Vec<f64>>, rotation: f64) -> KittiAnnotationRow {
+        KittiAnnotationRow {
+            task_id,
+            task_name: "task".to_string(),
+            resource_url: Some(resource_url.to_string()),
+            frame_count: None,
+            category_name: Some(category.to_string()),
+            bbox,
+            rotation: Some(rotation),
+            frame_index: None,
+            track_id: None,
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_build_kitti_files() {
+        let (first, second, video) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let car = Uuid::new_v4();
+        let video_row = |frame_index: i32, track_id: Option<Uuid>| KittiAnnotationRow {
+            task_id: video,
+            task_name: "drive cam.mp4".to_string(),
+            resource_url: None,
+            frame_count: Some(10),
+            category_name: Some("Car".to_string()),
+            bbox: Some(vec![5.0, 5.0, 2.0, 4.0]),
+            rotation: None,
+            frame_index: Some(frame_index),
+            track_id,
+        };
+        let rows = vec![
+            image_row(first, "storage://a/000001.png", "Person sitting", Some(vec![10.0, 20.0, 30.0, 40.0]), 0.0),
+            image_row(first, "storage://a/000001.png", "Car", Some(vec![0.0, 0.0, 4.0, 2.0]), 90.0),
+            image_row(second, "storage://b/000001.png", "Car", None, 0.0),
+            video_row(0, Some(car)),
+            video_row(0, None),
+            video_row(1, Some(car)),
+        ];
+
+        let files = build_kitti_files(&rows);
+        assert_eq!(files.len(), 3);
+
+        assert_eq!(files[0].0, "label_2/000001.txt");
+        assert_eq!(
+            files[0].1,
+            "Person_sitting 0.00 0 -10.00 10.00 20.00 40.00 60.00 -1.00 -1.00 -1.00 -1000.00 -1000.00 -1000.00 -10.00\n\
+             Car 0.00 0 -10.00 1.00 -1.00 3.00 3.00 -1.00 -1.00 -1.00 -1000.00 -1000.00 -1000.00 -10.00\n"
+        );
+
+        // Duplicate image names are disambiguated and tasks without boxes get an empty file
+        assert_eq!(files[1].0, format!("label_2/{}_000001.txt", second));
+        assert_eq!(files[1].1, "");
+
+        // The tracked car keeps its number, the untracked box gets the next one
+        assert_eq!(files[2].0, "label_02/drive_cam.txt");
+        assert_eq!(
+            files[2].1,
+            "0 0 Car 0.00 0 -10.00 5.00 5.00 7.00 9.00 -1.00 -1.00 -1.00 -1000.00 -1000.00 -1000.00 -10.00\n\
+             0 1 Car 0.00 0 -10.00 5.00 5.00 7.00 9.00 -1.00 -1.00 -1.00 -1000.00 -1000.00 -1000.00 -10.00\n\
+             1 0 Car 0.00 0 -10.00 5.00 5.00 7.00 9.00 -1.00 -1.00 -1.00 -1000.00 -1000.00 -1000.00 -10.00\n"
+        );
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_export_project_kitti_unauthorized() {
+        let pool = test_utils::setup_test_db().await;
+        let oauth_config = OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/export/kitti", web::get().to(export_project_kitti))
+        ).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/export/kitti", Uuid::new_v4()))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 401);
+    }
+}
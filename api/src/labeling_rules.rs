@@ -0,0 +1,481 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+use chrono::Utc;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::annotations::BoundingBox;
+use crate::auth::{JwtManager, Claims};
+
+/// Rules every save of a task's boxes has to follow. Rules left out are not checked.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LabelingRules {
+    /// Narrowest box in pixels, measured along the box before rotation
+    #[serde(default)]
+    pub min_box_width: Option<f64>,
+    /// Lowest box in pixels, measured along the box before rotation
+    #[serde(default)]
+    pub min_box_height: Option<f64>,
+    /// Most boxes on one image, or on one frame of a video
+    #[serde(default)]
+    pub max_boxes_per_image: Option<u32>,
+    /// Categories each task needs at least one box of
+    #[serde(default)]
+    pub required_category_ids: Vec<Uuid>,
+}
+
+/// A rule the boxes of a save break
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum RuleViolation {
+    /// The box at `index` of the request is below the minimum size
+    BoxTooSmall { index: usize, width: f64, height: f64 },
+    /// More boxes than allowed, on the frame for videos
+    TooManyBoxes { frame_index: Option<i32>, count: usize },
+    /// No box has this required category
+    MissingCategory { category_id: Uuid },
+}
+
+/// Body of the 400 answered when boxes break the labeling rules
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RuleViolations {
+    pub error: String,
+    pub violations: Vec<RuleViolation>,
+}
+
+/// Checks the boxes saved for one task against the rules, every violation is reported
+pub fn check(rules: &LabelingRules, bboxes: &[BoundingBox]) -> Result<(), RuleViolations> {
+    let mut violations = Vec::new();
+
+    for (index, bbox) in bboxes.iter().enumerate() {
+        let (width, height) = (bbox.bbox[2], bbox.bbox[3]);
+        let too_narrow = rules.min_box_width.is_some_and(|min| width < min);
+        let too_low = rules.min_box_height.is_some_and(|min| height < min);
+        if too_narrow || too_low {
+            violations.push(RuleViolation::BoxTooSmall { index, width, height });
+        }
+    }
+
+    if let Some(max) = rules.max_boxes_per_image {
+        let mut counts: HashMap<Option<i32>, usize> = HashMap::new();
+        for bbox in bboxes {
+            *counts.entry(bbox.frame_index).or_default() += 1;
+        }
+        let mut crowded: Vec<_> = counts.into_iter().filter(|(_, count)| *count > max as usize).collect();
+        crowded.sort();
+        violations.extend(crowded.into_iter().map(|(frame_index, count)| RuleViolation::TooManyBoxes { frame_index, count }));
+    }
+
+    for category_id in &rules.required_category_ids {
+        if !bboxes.iter().any(|bbox| bbox.category_id == *category_id) {
+            violations.push(RuleViolation::MissingCategory { category_id: *category_id });
+        }
+    }
+
+    if violations.is_empty() {
+        return Ok(());
+    }
+    Err(RuleViolations {
+        error: "Annotations break the labeling rules of the project".to_string(),
+        violations,
+    })
+}
+
+/// Reads the rules column, rules that can't be read are not checked
+pub fn from_column(value: serde_json::Value) -> LabelingRules {
+    serde_json::from_value(value).unwrap_or_default()
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{id}/labeling-rules",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 200, body = LabelingRules),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn get_labeling_rules(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    if !crate::cache::user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match get_labeling_rules_from_db(&pool, project_id).await {
+        Ok(Some(rules)) => HttpResponse::Ok().json(rules),
+        Ok(None) => HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch labeling rules"),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/projects/{id}/labeling-rules",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    request_body = LabelingRules,
+    responses(
+        (status = 200, body = LabelingRules),
+        (status = 400, description = "Invalid rules", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found, or the user is not an owner or admin of it", body = String),
+    ),
+)]
+pub async fn update_labeling_rules(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<LabelingRules>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let mut rules = payload.into_inner();
+    if let Err(e) = validate_rules(&mut rules) {
+        return HttpResponse::BadRequest().json(e);
+    }
+
+    match user_can_manage_rules(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check project access"),
+    }
+
+    match categories_belong_to_project(&pool, project_id, &rules.required_category_ids).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::BadRequest().json("One or more categories do not belong to the specified project"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check categories"),
+    }
+
+    match update_labeling_rules_in_db(&pool, project_id, &rules).await {
+        Ok(()) => HttpResponse::Ok().json(rules),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to update labeling rules"),
+    }
+}
+
+/// Checks the limits are positive and drops repeated categories
+fn validate_rules(rules: &mut LabelingRules) -> Result<(), String> {
+    for (name, value) in [("min_box_width", rules.min_box_width), ("min_box_height", rules.min_box_height)] {
+        if value.is_some_and(|value| !value.is_finite() || value <= 0.0) {
+            return Err(format!("{} must be a positive number of pixels", name));
+        }
+    }
+    if rules.max_boxes_per_image == Some(0) {
+        return Err("max_boxes_per_image must be at least 1".to_string());
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    rules.required_category_ids.retain(|category_id| seen.insert(*category_id));
+    Ok(())
+}
+
+async fn get_labeling_rules_from_db(pool: &Pool<Postgres>, project_id: Uuid) -> Result<Option<LabelingRules>, sqlx::Error> {
+    let rules = sqlx::query_scalar::<_, serde_json::Value>("SELECT labeling_rules FROM projects WHERE id = $1")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(rules.map(from_column))
+}
+
+async fn update_labeling_rules_in_db(pool: &Pool<Postgres>, project_id: Uuid, rules: &LabelingRules) -> Result<(), sqlx::Error> {
+    let rules = serde_json::to_value(rules).unwrap_or_else(|_| serde_json::json!({}));
+    sqlx::query("UPDATE projects SET labeling_rules = $1, updated_at = $2 WHERE id = $3")
+        .bind(&rules)
+        .bind(Utc::now())
+        .bind(project_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Owners and admins set the rules, every member saves under them
+async fn user_can_manage_rules(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM project_members pm
+            INNER JOIN projects p ON pm.project_id = p.id
+            WHERE p.id = $1 AND pm.user_id = $2 AND (pm.role IN ('owner', 'admin') OR p.owner_id = $2)
+        )
+        "#
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+async fn categories_belong_to_project(pool: &Pool<Postgres>, project_id: Uuid, category_ids: &[Uuid]) -> Result<bool, sqlx::Error> {
+    let count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM image_annotation_categories WHERE project_id = $1 AND id = ANY($2)"
+    )
+    .bind(project_id)
+    .bind(category_ids)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count == category_ids.len() as i64)
+}
+
+/// Stops requiring a category that is deleted or merged into another
+pub async fn forget_required_category(
+    tx: &mut Transaction<'_, Postgres>,
+    project_id: Uuid,
+    category_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE projects
+        SET labeling_rules = jsonb_set(labeling_rules, '{required_category_ids}', (labeling_rules->'required_category_ids') - $2)
+        WHERE id = $1 AND labeling_rules->'required_category_ids' ? $2
+        "#
+    )
+    .bind(project_id)
+    .bind(category_id.to_string())
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    fn bbox(category_id: Uuid, values: [f64; 4], frame_index: Option<i32>) -> BoundingBox {
+        BoundingBox {
+            category_id,
+            bbox: values.to_vec(),
+            area: None,
+            iscrowd: None,
+            is_prediction: None,
+            confidence: None,
+            attributes: None,
+            rotation: None,
+            frame_index,
+            track_id: None,
+            is_interpolated: None,
+            mask: None,
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_check_reports_every_violation() {
+        let (person, car) = (Uuid::new_v4(), Uuid::new_v4());
+        let rules = LabelingRules {
+            min_box_width: Some(10.0),
+            min_box_height: None,
+            max_boxes_per_image: Some(2),
+            required_category_ids: vec![person, car],
+        };
+
+        let boxes = vec![
+            bbox(person, [0.0, 0.0, 20.0, 2.0], None),
+            bbox(person, [0.0, 0.0, 5.0, 20.0], None),
+            bbox(person, [0.0, 0.0, 20.0, 20.0], None),
+        ];
+        let violations = check(&rules, &boxes).unwrap_err().violations;
+        assert_eq!(violations, vec![
+            RuleViolation::BoxTooSmall { index: 1, width: 5.0, height: 20.0 },
+            RuleViolation::TooManyBoxes { frame_index: None, count: 3 },
+            RuleViolation::MissingCategory { category_id: car },
+        ]);
+
+        // Boxes are counted per video frame
+        let boxes = vec![
+            bbox(person, [0.0, 0.0, 20.0, 20.0], Some(0)),
+            bbox(car, [0.0, 0.0, 20.0, 20.0], Some(0)),
+            bbox(person, [0.0, 0.0, 20.0, 20.0], Some(1)),
+        ];
+        assert!(check(&rules, &boxes).is_ok());
+        assert!(check(&LabelingRules::default(), &[]).is_ok());
+    }
+
+    #[actix_web::test]
+    async fn test_validate_rules() {
+        let category_id = Uuid::new_v4();
+        let mut rules = LabelingRules { required_category_ids: vec![category_id, category_id], ..Default::default() };
+        assert!(validate_rules(&mut rules).is_ok());
+        assert_eq!(rules.required_category_ids, vec![category_id]);
+
+        assert!(validate_rules(&mut LabelingRules { min_box_width: Some(0.0), ..Default::default() }).is_err());
+        assert!(validate_rules(&mut LabelingRules { min_box_height: Some(f64::NAN), ..Default::default() }).is_err());
+        assert!(validate_rules(&mut LabelingRules { max_boxes_per_image: Some(0), ..Default::default() }).is_err());
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_labeling_rules_are_set_by_owners_and_admins() {
+        let pool = test_utils::setup_test_db().await;
+        let owner = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let owner_token = create_auth_token(&oauth_config, &owner);
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, owner.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, None, None, None).await.unwrap();
+
+        let member_id = test_utils::create_test_user(&pool).await;
+        sqlx::query("INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, 'member')")
+            .bind(project.id)
+            .bind(member_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let member_token = JwtManager::new(&oauth_config.jwt_secret)
+            .generate_token(&member_id.to_string(), &format!("test-{}@example.com", member_id), "Test User")
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{id}/labeling-rules", web::get().to(get_labeling_rules))
+                .route("/projects/{id}/labeling-rules", web::put().to(update_labeling_rules))
+        ).await;
+        let uri = format!("/projects/{}/labeling-rules", project.id);
+
+        // No rules until they are set
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", member_token)))
+            .to_request();
+        let rules: LabelingRules = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(rules, LabelingRules::default());
+
+        let rules = LabelingRules {
+            min_box_width: Some(8.0),
+            min_box_height: Some(8.0),
+            max_boxes_per_image: Some(50),
+            required_category_ids: vec![category.id],
+        };
+        let req = test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", member_token)))
+            .set_json(&rules)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+        // Categories of other projects can't be required
+        let other_project = crate::projects::create_project_in_db(&pool, "Other Project", None, None, owner.id).await.unwrap();
+        let other_category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, other_project.id, "car", None, None, None, None).await.unwrap();
+        let req = test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+            .set_json(LabelingRules { required_category_ids: vec![other_category.id], ..rules.clone() })
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        let req = test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+            .set_json(&rules)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", member_token)))
+            .to_request();
+        let saved: LabelingRules = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(saved, rules);
+
+        // Deleted categories stop being required
+        let mut tx = pool.begin().await.unwrap();
+        forget_required_category(&mut tx, project.id, category.id).await.unwrap();
+        tx.commit().await.unwrap();
+        let saved = get_labeling_rules_from_db(&pool, project.id).await.unwrap().unwrap();
+        assert!(saved.required_category_ids.is_empty());
+        assert_eq!(saved.max_boxes_per_image, Some(50));
+    }
+}
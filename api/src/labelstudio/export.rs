@@ -0,0 +1,225 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use crate::auth::{JwtManager, Claims};
+use super::types::{
+    LabelStudioTask, LabelStudioData, LabelStudioAnnotation, LabelStudioResult, RECTANGLE_LABELS,
+    coco_bbox_to_value,
+};
+
+#[derive(Debug, sqlx::FromRow)]
+struct TaskRow {
+    id: Uuid,
+    name: String,
+    resource_url: Option<String>,
+    width: Option<i32>,
+    height: Option<i32>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct BoxRow {
+    task_id: Uuid,
+    created_at: Option<DateTime<Utc>>,
+    annotator: Option<String>,
+    bbox: Vec<f64>,
+    category_name: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/export/labelstudio",
+    tag = "export",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 200, description = "Label Studio JSON tasks", body = Vec<LabelStudioTask>),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn export_project_labelstudio(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let project_name = match sqlx::query_scalar::<_, String>("SELECT name FROM projects WHERE id = $1")
+        .bind(project_id)
+        .fetch_optional(pool.get_ref())
+        .await
+    {
+        Ok(Some(name)) => name,
+        Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    };
+
+    let tasks = match build_labelstudio_tasks(&pool, project_id).await {
+        Ok(tasks) => tasks,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotations"),
+    };
+
+    let filename = format!("{}_labelstudio_export_{}.json",
+        project_name.replace(" ", "_").to_lowercase(),
+        Utc::now().format("%Y%m%d_%H%M%S")
+    );
+
+    let json = match serde_json::to_string_pretty(&tasks) {
+        Ok(json) => json,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to serialize JSON"),
+    };
+
+    HttpResponse::Ok()
+        .content_type("application/json")
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", filename)))
+        .body(json)
+}
+
+async fn build_labelstudio_tasks(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Vec<LabelStudioTask>, sqlx::Error> {
+    let tasks = sqlx::query_as::<_, TaskRow>(
+        "SELECT id, name, resource_url, width, height FROM tasks WHERE project_id = $1 ORDER BY created_at"
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    // Only the latest annotation of each task is exported, matching the COCO exporter
+    let boxes = sqlx::query_as::<_, BoxRow>(
+        r#"
+        WITH latest_annotations AS (
+            SELECT DISTINCT ON (task_id) id, task_id, annotated_by, created_at
+            FROM annotations
+            WHERE task_id IN (SELECT id FROM tasks WHERE project_id = $1)
+            ORDER BY task_id, created_at DESC
+        )
+        SELECT
+            la.task_id,
+            la.created_at,
+            u.email as annotator,
+            ia.bbox,
+            iac.name as category_name
+        FROM latest_annotations la
+        JOIN image_annotations ia ON ia.annotation_id = la.id
+        LEFT JOIN image_annotation_categories iac ON iac.id = ia.category_id
+        LEFT JOIN users u ON u.id = la.annotated_by
+        WHERE NOT ia.is_prediction
+        ORDER BY ia.created_at
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut boxes_by_task: HashMap<Uuid, Vec<BoxRow>> = HashMap::new();
+    for row in boxes {
+        boxes_by_task.entry(row.task_id).or_default().push(row);
+    }
+
+    let mut result = Vec::new();
+    for (index, task) in tasks.into_iter().enumerate() {
+        let annotations = match (boxes_by_task.remove(&task.id), task.width, task.height) {
+            // Label Studio stores percentages, so boxes can only be converted when the image size is known
+            (Some(rows), Some(width), Some(height)) if width > 0 && height > 0 => {
+                let first = &rows[0];
+                let completed_by = first.annotator.clone().map(serde_json::Value::String);
+                let created_at = first.created_at.map(|dt| dt.to_rfc3339());
+
+                let results = rows
+                    .iter()
+                    .filter(|row| row.bbox.len() == 4)
+                    .enumerate()
+                    .map(|(box_index, row)| LabelStudioResult {
+                        id: Some(format!("{}_{}", index + 1, box_index + 1)),
+                        result_type: RECTANGLE_LABELS.to_string(),
+                        from_name: "label".to_string(),
+                        to_name: "image".to_string(),
+                        original_width: Some(width),
+                        original_height: Some(height),
+                        image_rotation: 0.0,
+                        value: coco_bbox_to_value(
+                            &row.bbox,
+                            width as f64,
+                            height as f64,
+                            row.category_name.clone().unwrap_or_default(),
+                        ),
+                    })
+                    .collect();
+
+                vec![LabelStudioAnnotation {
+                    id: Some(index as i64 + 1),
+                    completed_by,
+                    was_cancelled: false,
+                    created_at,
+                    result: results,
+                }]
+            }
+            _ => Vec::new(),
+        };
+
+        result.push(LabelStudioTask {
+            id: Some(index as i64 + 1),
+            data: LabelStudioData {
+                image: task.resource_url.unwrap_or(task.name),
+            },
+            annotations,
+            predictions: Vec::new(),
+        });
+    }
+
+    Ok(result)
+}
+
+pub(super) async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+pub(super) fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
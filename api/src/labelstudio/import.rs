@@ -0,0 +1,348 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use actix_multipart::Multipart;
+use futures_util::TryStreamExt;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use std::collections::HashMap;
+
+use crate::coco::types::{ImportResult, ImportStats};
+use super::types::{LabelStudioTask, LabelStudioAnnotation, RECTANGLE_LABELS, value_to_coco_bbox};
+use super::export::{user_has_project_access, extract_user_claims};
+use crate::openapi::FileUpload;
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/import/labelstudio",
+    tag = "import",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    request_body(content = FileUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, body = ImportResult),
+        (status = 400, description = "Invalid Label Studio file", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn import_project_labelstudio(
+    req: HttpRequest,
+    path: web::Path<String>,
+    mut payload: Multipart,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    // Extract JSON data from multipart upload
+    let json_data = match extract_json_from_multipart(&mut payload).await {
+        Ok(data) => data,
+        Err(err) => return HttpResponse::BadRequest().json(format!("Failed to read file: {}", err)),
+    };
+
+    // Parse Label Studio JSON
+    let tasks: Vec<LabelStudioTask> = match serde_json::from_str(&json_data) {
+        Ok(data) => data,
+        Err(err) => return HttpResponse::BadRequest().json(format!("Invalid Label Studio JSON: {}", err)),
+    };
+
+    if tasks.is_empty() {
+        return HttpResponse::BadRequest().json("No tasks found in Label Studio data");
+    }
+
+    match import_labelstudio_data(&pool, project_id, user_id, &tasks).await {
+        Ok(result) => {
+            crate::cache::invalidate_categories(project_id).await;
+            HttpResponse::Ok().json(result)
+        }
+        Err(err) => {
+            eprintln!("Label Studio import error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to import Label Studio data")
+        }
+    }
+}
+
+async fn extract_json_from_multipart(payload: &mut Multipart) -> Result<String, Box<dyn std::error::Error>> {
+    while let Some(mut field) = payload.try_next().await? {
+        if field.name() == Some("file") {
+            let mut data = bytes::BytesMut::new();
+            while let Some(chunk) = field.try_next().await? {
+                data.extend_from_slice(&chunk);
+            }
+
+            return Ok(String::from_utf8(data.to_vec())?);
+        }
+    }
+
+    Err("No file field found in multipart data".into())
+}
+
+/// Picks the annotation to import: the most recent one that was not skipped.
+/// Label Studio appends annotations, so the last entry is the newest.
+fn select_annotation(task: &LabelStudioTask) -> Option<&LabelStudioAnnotation> {
+    task.annotations.iter().rev().find(|annotation| !annotation.was_cancelled)
+}
+
+async fn import_labelstudio_data(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    user_id: Uuid,
+    tasks: &[LabelStudioTask],
+) -> Result<ImportResult, sqlx::Error> {
+    let mut stats = ImportStats {
+        categories_created: 0,
+        categories_updated: 0,
+        tasks_created: 0,
+        annotations_created: 0,
+        images_fetched: 0,
+        errors: Vec::new(),
+    };
+
+    // Start transaction
+    let mut tx = pool.begin().await?;
+
+    let mut category_mapping: HashMap<String, Uuid> = HashMap::new();
+
+    for (index, task) in tasks.iter().enumerate() {
+        let file_name = task.data.image
+            .split('/')
+            .next_back()
+            .filter(|name| !name.is_empty())
+            .unwrap_or(&task.data.image)
+            .to_string();
+
+        let annotation = select_annotation(task);
+        let dimensions = annotation
+            .and_then(|a| a.result.iter().find_map(|r| r.original_width.zip(r.original_height)));
+
+        let task_id = match import_task(&mut tx, project_id, &file_name, &task.data.image, dimensions).await {
+            Ok(task_id) => {
+                stats.tasks_created += 1;
+                task_id
+            }
+            Err(err) => {
+                stats.errors.push(format!("Failed to import task '{}': {}", file_name, err));
+                continue;
+            }
+        };
+
+        let annotation = match annotation {
+            Some(annotation) => annotation,
+            None => continue,
+        };
+
+        let mut bboxes = Vec::new();
+        for result in annotation.result.iter().filter(|r| r.result_type == RECTANGLE_LABELS) {
+            let (width, height) = match result.original_width.zip(result.original_height) {
+                Some(size) => size,
+                None => {
+                    stats.errors.push(format!("Task {} has a rectangle without original image size", index + 1));
+                    continue;
+                }
+            };
+
+            let label = match result.value.rectanglelabels.first() {
+                Some(label) => label,
+                None => {
+                    stats.errors.push(format!("Task {} has a rectangle without a label", index + 1));
+                    continue;
+                }
+            };
+
+            let category_id = match category_mapping.get(label) {
+                Some(&id) => id,
+                None => match import_category(&mut tx, project_id, label).await {
+                    Ok((id, was_created)) => {
+                        if was_created {
+                            stats.categories_created += 1;
+                        }
+                        category_mapping.insert(label.clone(), id);
+                        id
+                    }
+                    Err(err) => {
+                        stats.errors.push(format!("Failed to import category '{}': {}", label, err));
+                        continue;
+                    }
+                },
+            };
+
+            bboxes.push((value_to_coco_bbox(&result.value, width as f64, height as f64), category_id));
+        }
+
+        if bboxes.is_empty() {
+            continue;
+        }
+
+        match import_task_annotation(&mut tx, task_id, &bboxes, user_id).await {
+            Ok(_) => stats.annotations_created += 1,
+            Err(err) => stats.errors.push(format!("Failed to import annotations for task '{}': {}", file_name, err)),
+        }
+    }
+
+    // Commit transaction
+    tx.commit().await?;
+
+    Ok(ImportResult {
+        success: stats.errors.is_empty(),
+        message: if stats.errors.is_empty() {
+            "Import completed successfully".to_string()
+        } else {
+            format!("Import completed with {} errors", stats.errors.len())
+        },
+        stats,
+    })
+}
+
+async fn import_category(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    project_id: Uuid,
+    name: &str,
+) -> Result<(Uuid, bool), sqlx::Error> {
+    let existing = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM image_annotation_categories WHERE project_id = $1 AND name = $2"
+    )
+    .bind(project_id)
+    .bind(name)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    if let Some(id) = existing {
+        return Ok((id, false));
+    }
+
+    let new_id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO image_annotation_categories (id, project_id, name, created_at, updated_at)
+        VALUES ($1, $2, $3, NOW(), NOW())
+        "#
+    )
+    .bind(new_id)
+    .bind(project_id)
+    .bind(name)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok((new_id, true))
+}
+
+async fn import_task(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    project_id: Uuid,
+    name: &str,
+    resource_url: &str,
+    dimensions: Option<(i32, i32)>,
+) -> Result<Uuid, sqlx::Error> {
+    let (width, height) = dimensions.unzip();
+
+    // Reuse an existing task with the same file name
+    let existing = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM tasks WHERE project_id = $1 AND name = $2"
+    )
+    .bind(project_id)
+    .bind(name)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    if let Some(id) = existing {
+        sqlx::query(
+            r#"
+            UPDATE tasks
+            SET resource_url = $1, width = COALESCE($2, width), height = COALESCE($3, height), updated_at = NOW()
+            WHERE id = $4
+            "#
+        )
+        .bind(resource_url)
+        .bind(width)
+        .bind(height)
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+        return Ok(id);
+    }
+
+    let new_id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO tasks (id, project_id, name, resource_url, width, height, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, NOW(), NOW())
+        "#
+    )
+    .bind(new_id)
+    .bind(project_id)
+    .bind(name)
+    .bind(resource_url)
+    .bind(width)
+    .bind(height)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(new_id)
+}
+
+async fn import_task_annotation(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    task_id: Uuid,
+    bboxes: &[(Vec<f64>, Uuid)],
+    user_id: Uuid,
+) -> Result<usize, sqlx::Error> {
+    let annotation_id = Uuid::new_v4();
+    let now = chrono::Utc::now();
+
+    // Create single annotation for this task
+    sqlx::query(
+        r#"
+        INSERT INTO annotations (id, task_id, metadata, annotated_by, annotated_at, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#
+    )
+    .bind(annotation_id)
+    .bind(task_id)
+    .bind(serde_json::json!({"imported_from_labelstudio": true}))
+    .bind(user_id)
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .execute(&mut **tx)
+    .await?;
+
+    // Create image annotations for each bounding box
+    for (bbox, category_id) in bboxes {
+        sqlx::query(
+            r#"
+            INSERT INTO image_annotations (id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#
+        )
+        .bind(Uuid::new_v4())
+        .bind(annotation_id)
+        .bind(category_id)
+        .bind(bbox)
+        .bind(bbox[2] * bbox[3])
+        .bind(false)
+        .bind(serde_json::json!({"imported_from_labelstudio": true}))
+        .bind(now)
+        .bind(now)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(bboxes.len())
+}
@@ -0,0 +1,9 @@
+pub mod types;
+pub mod export;
+pub mod import;
+
+pub use export::export_project_labelstudio;
+pub use import::import_project_labelstudio;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,196 @@
+use super::*;
+use super::types::{LabelStudioValue, coco_bbox_to_value, value_to_coco_bbox};
+use crate::auth::{User, OAuthConfig, AuthStorage, JwtManager};
+use crate::test_utils;
+use actix_web::{test, App, web};
+use serial_test::serial;
+
+fn create_test_oauth_config() -> OAuthConfig {
+    OAuthConfig {
+        google_client_id: "test_google_id".to_string(),
+        google_client_secret: "test_google_secret".to_string(),
+        google_redirect_url: "http://localhost/callback".to_string(),
+        github_client_id: "test_github_id".to_string(),
+        github_client_secret: "test_github_secret".to_string(),
+        github_redirect_url: "http://localhost/callback".to_string(),
+        jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+    }
+}
+
+fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+    let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+    jwt_manager.generate_token(
+        &user.id.to_string(),
+        &user.email,
+        &user.name
+    ).expect("Failed to generate token")
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_export_project_labelstudio_with_data() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+    let auth_storage = AuthStorage::new(pool.clone());
+
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+    let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, Some("human"), Some("#FF0000"), Some(1)).await.unwrap();
+    let task = crate::tasks::create_task_in_db(&pool, project.id, "image1.jpg", Some("https://example.com/image1.jpg")).await.unwrap();
+    sqlx::query("UPDATE tasks SET width = 200, height = 100 WHERE id = $1")
+        .bind(task.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let bbox = crate::annotations::BoundingBox { category_id: category.id, bbox: vec![20.0, 10.0, 100.0, 50.0], area: None, iscrowd: Some(false), is_prediction: None, confidence: None, attributes: None, rotation: None, frame_index: None, track_id: None, is_interpolated: None, mask: None };
+    crate::annotations::create_annotation_in_db(&pool, task.id, &[bbox], &serde_json::json!({}), user.id).await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/export/labelstudio", web::get().to(export_project_labelstudio))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/export/labelstudio", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get("Content-Disposition").is_some());
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let tasks = body.as_array().unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0]["data"]["image"], "https://example.com/image1.jpg");
+
+    let result = &tasks[0]["annotations"][0]["result"][0];
+    assert_eq!(result["type"], "rectanglelabels");
+    assert_eq!(result["original_width"], 200);
+    assert_eq!(result["value"]["x"], 10.0);
+    assert_eq!(result["value"]["y"], 10.0);
+    assert_eq!(result["value"]["width"], 50.0);
+    assert_eq!(result["value"]["height"], 50.0);
+    assert_eq!(result["value"]["rectanglelabels"][0], "person");
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_import_project_labelstudio_success() {
+    let pool = test_utils::setup_test_db().await;
+    let user = test_utils::create_test_user_with_details(&pool).await;
+    let oauth_config = create_test_oauth_config();
+    let token = create_auth_token(&oauth_config, &user);
+    let auth_storage = AuthStorage::new(pool.clone());
+
+    let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+
+    let labelstudio_data = serde_json::json!([
+        {
+            "id": 1,
+            "data": {"image": "https://example.com/images/cat.jpg"},
+            "annotations": [{
+                "id": 1,
+                "was_cancelled": false,
+                "result": [{
+                    "id": "a1",
+                    "type": "rectanglelabels",
+                    "from_name": "label",
+                    "to_name": "image",
+                    "original_width": 400,
+                    "original_height": 200,
+                    "image_rotation": 0,
+                    "value": {"x": 25, "y": 50, "width": 50, "height": 25, "rotation": 0, "rectanglelabels": ["cat"]}
+                }]
+            }],
+            "predictions": []
+        }
+    ]);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .app_data(web::Data::new(auth_storage))
+            .route("/projects/{project_id}/import/labelstudio", web::post().to(import_project_labelstudio))
+    ).await;
+
+    let json_str = serde_json::to_string(&labelstudio_data).unwrap();
+    let boundary = "----formdata-test-boundary";
+    let body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"test.json\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+        boundary, json_str, boundary
+    );
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/projects/{}/import/labelstudio", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+        .set_payload(body)
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let result: crate::coco::types::ImportResult = test::read_body_json(resp).await;
+    assert!(result.success);
+    assert_eq!(result.stats.categories_created, 1);
+    assert_eq!(result.stats.tasks_created, 1);
+    assert_eq!(result.stats.annotations_created, 1);
+
+    let bbox = sqlx::query_scalar::<_, Vec<f64>>(
+        r#"
+        SELECT ia.bbox FROM image_annotations ia
+        JOIN annotations a ON a.id = ia.annotation_id
+        JOIN tasks t ON t.id = a.task_id
+        WHERE t.project_id = $1 AND t.name = 'cat.jpg'
+        "#
+    )
+    .bind(project.id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(bbox, vec![100.0, 100.0, 200.0, 50.0]);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_import_project_labelstudio_unauthorized() {
+    let pool = test_utils::setup_test_db().await;
+    let oauth_config = create_test_oauth_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(oauth_config))
+            .route("/projects/{project_id}/import/labelstudio", web::post().to(import_project_labelstudio))
+    ).await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/projects/{}/import/labelstudio", uuid::Uuid::new_v4()))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_web::test]
+async fn test_bbox_conversion_round_trip() {
+    let value = coco_bbox_to_value(&[64.0, 48.0, 128.0, 96.0], 640.0, 480.0, "dog".to_string());
+    assert_eq!(value.x, 10.0);
+    assert_eq!(value.y, 10.0);
+    assert_eq!(value.width, 20.0);
+    assert_eq!(value.height, 20.0);
+    assert_eq!(value.rectanglelabels, vec!["dog".to_string()]);
+
+    let bbox = value_to_coco_bbox(&value, 640.0, 480.0);
+    assert_eq!(bbox, vec![64.0, 48.0, 128.0, 96.0]);
+
+    let empty = LabelStudioValue::default();
+    assert_eq!(value_to_coco_bbox(&empty, 640.0, 480.0), vec![0.0, 0.0, 0.0, 0.0]);
+}
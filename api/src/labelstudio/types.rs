@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Label Studio task format (JSON export of an image labeling project)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LabelStudioTask {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    pub data: LabelStudioData,
+    #[serde(default)]
+    pub annotations: Vec<LabelStudioAnnotation>,
+    #[serde(default)]
+    pub predictions: Vec<LabelStudioAnnotation>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LabelStudioData {
+    pub image: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LabelStudioAnnotation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_by: Option<serde_json::Value>,
+    #[serde(default)]
+    pub was_cancelled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub result: Vec<LabelStudioResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LabelStudioResult {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub result_type: String,
+    pub from_name: String,
+    pub to_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_width: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_height: Option<i32>,
+    #[serde(default)]
+    pub image_rotation: f64,
+    pub value: LabelStudioValue,
+}
+
+/// Rectangle values are percentages of the original image size
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct LabelStudioValue {
+    #[serde(default)]
+    pub x: f64,
+    #[serde(default)]
+    pub y: f64,
+    #[serde(default)]
+    pub width: f64,
+    #[serde(default)]
+    pub height: f64,
+    #[serde(default)]
+    pub rotation: f64,
+    #[serde(default)]
+    pub rectanglelabels: Vec<String>,
+}
+
+pub const RECTANGLE_LABELS: &str = "rectanglelabels";
+
+/// Converts a COCO `[x, y, width, height]` pixel bbox to Label Studio percentages
+pub fn coco_bbox_to_value(bbox: &[f64], image_width: f64, image_height: f64, label: String) -> LabelStudioValue {
+    LabelStudioValue {
+        x: bbox[0] / image_width * 100.0,
+        y: bbox[1] / image_height * 100.0,
+        width: bbox[2] / image_width * 100.0,
+        height: bbox[3] / image_height * 100.0,
+        rotation: 0.0,
+        rectanglelabels: vec![label],
+    }
+}
+
+/// Converts Label Studio percentages back to a COCO `[x, y, width, height]` pixel bbox
+pub fn value_to_coco_bbox(value: &LabelStudioValue, image_width: f64, image_height: f64) -> Vec<f64> {
+    vec![
+        value.x * image_width / 100.0,
+        value.y * image_height / 100.0,
+        value.width * image_width / 100.0,
+        value.height * image_height / 100.0,
+    ]
+}
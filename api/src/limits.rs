@@ -0,0 +1,359 @@
+use crate::auth::{JwtManager, OAuthConfig};
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{Payload, ServiceRequest, ServiceResponse};
+use actix_web::error::{InternalError, JsonPayloadError, PayloadError};
+use actix_web::http::header;
+use actix_web::middleware::Next;
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, web};
+use futures_util::StreamExt;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Buckets kept before full ones are dropped, so idle clients don't pile up
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Request rate and body size limits, read from the environment
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitsConfig {
+    /// Sustained requests per minute of one user or IP, 0 turns rate limiting off
+    pub requests_per_minute: u32,
+    /// Requests a client may send at once before the per minute rate applies
+    pub burst: u32,
+    pub max_json_bytes: usize,
+    /// Largest multipart form or raw file upload
+    pub max_upload_bytes: usize,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: 600,
+            burst: 120,
+            max_json_bytes: 2 * 1024 * 1024,
+            max_upload_bytes: 100 * 1024 * 1024,
+        }
+    }
+}
+
+impl LimitsConfig {
+    pub fn from_env() -> Result<Self, String> {
+        fn var<T: std::str::FromStr>(name: &str, default: T) -> Result<T, String> {
+            match std::env::var(name).ok().filter(|value| !value.trim().is_empty()) {
+                Some(value) => value
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("{} must be a number, got {}", name, value)),
+                None => Ok(default),
+            }
+        }
+
+        let defaults = Self::default();
+        Ok(Self {
+            requests_per_minute: var("RATE_LIMIT_PER_MINUTE", defaults.requests_per_minute)?,
+            burst: var("RATE_LIMIT_BURST", defaults.burst)?,
+            max_json_bytes: var("MAX_JSON_BYTES", defaults.max_json_bytes)?,
+            max_upload_bytes: var("MAX_UPLOAD_BYTES", defaults.max_upload_bytes)?,
+        })
+    }
+
+    /// Limit of JSON bodies, answering bodies sent without a `Content-Length` that turn out too
+    /// large like the ones the middleware rejects up front
+    pub fn json_config(&self) -> web::JsonConfig {
+        let limit = self.max_json_bytes;
+        web::JsonConfig::default()
+            .limit(limit)
+            .error_handler(move |err, _req: &HttpRequest| match err {
+                JsonPayloadError::Overflow { .. } | JsonPayloadError::OverflowKnownLength { .. } => {
+                    InternalError::from_response(err, payload_too_large(limit)).into()
+                }
+                err => err.into(),
+            })
+    }
+
+    /// Limit of raw bodies such as storage uploads
+    pub fn payload_config(&self) -> web::PayloadConfig {
+        web::PayloadConfig::new(self.max_upload_bytes)
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token buckets of every client, shared by all workers
+pub struct RateLimiter {
+    /// Tokens added per second
+    rate: f64,
+    capacity: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: &LimitsConfig) -> Self {
+        Self {
+            rate: config.requests_per_minute as f64 / 60.0,
+            capacity: config.burst.max(1) as f64,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn enabled(&self) -> bool {
+        self.rate > 0.0
+    }
+
+    /// Takes a token from the bucket of `key`, or tells how long until the next one is available
+    pub fn check(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() >= PRUNE_THRESHOLD {
+            buckets.retain(|_, bucket| {
+                bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * self.rate < self.capacity
+            });
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket { tokens: self.capacity, updated: now });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate))
+        }
+    }
+}
+
+fn payload_too_large(limit: usize) -> HttpResponse {
+    HttpResponse::PayloadTooLarge().json(json!({
+        "error": "payload_too_large",
+        "message": format!("Request body is larger than the limit of {} bytes", limit),
+        "limit_bytes": limit,
+    }))
+}
+
+fn too_many_requests(retry_after: Duration) -> HttpResponse {
+    let retry_after_secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    HttpResponse::TooManyRequests()
+        .insert_header((header::RETRY_AFTER, retry_after_secs.to_string()))
+        .json(json!({
+            "error": "rate_limited",
+            "message": format!("Too many requests, retry in {} seconds", retry_after_secs),
+            "retry_after_secs": retry_after_secs,
+        }))
+}
+
+/// Who a request counts against: the user of a valid token, otherwise the connecting address.
+/// The peer address rather than `X-Forwarded-For`, which clients could set to dodge the limit.
+fn client_key(req: &ServiceRequest) -> String {
+    let sub = req
+        .app_data::<web::Data<OAuthConfig>>()
+        .zip(
+            req.headers()
+                .get("Authorization")
+                .and_then(|header| header.to_str().ok())
+                .and_then(|header| header.strip_prefix("Bearer ")),
+        )
+        .and_then(|(config, token)| JwtManager::new(&config.jwt_secret).verify_token(token).ok())
+        .map(|claims| claims.sub);
+
+    match sub {
+        Some(sub) => format!("user:{}", sub),
+        None => format!(
+            "ip:{}",
+            req.peer_addr().map(|addr| addr.ip().to_string()).unwrap_or_else(|| "unknown".to_string())
+        ),
+    }
+}
+
+/// Cuts the body off once more than `limit` bytes were read, for bodies without a
+/// `Content-Length` and readers such as `Multipart` that no extractor config covers. The flag
+/// tells the middleware to answer with the structured error rather than the handler's own.
+fn limit_payload(req: &mut ServiceRequest, limit: usize, exceeded: Arc<AtomicBool>) {
+    let mut read = 0;
+    let payload = req.take_payload().map(move |chunk| {
+        let chunk = chunk?;
+        read += chunk.len();
+        if read > limit {
+            exceeded.store(true, Ordering::Relaxed);
+            return Err(PayloadError::Overflow);
+        }
+        Ok(chunk)
+    });
+    req.set_payload(Payload::from(payload.boxed_local()));
+}
+
+/// Rejects bodies over the size limits, by their `Content-Length` before they are read or
+/// once the bytes read pass the limit, and clients over their request rate. Health checks and
+/// metrics scrapes are never limited.
+pub async fn enforce_limits<B: MessageBody>(
+    mut req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, actix_web::Error> {
+    let config = req.app_data::<web::Data<LimitsConfig>>().cloned();
+    let limiter = req.app_data::<web::Data<RateLimiter>>().cloned();
+
+    let exceeded = Arc::new(AtomicBool::new(false));
+    let mut body_limit = None;
+    if let Some(config) = &config {
+        let content_length = req
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<usize>().ok());
+        let is_json = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("application/json"));
+        let limit = if is_json { config.max_json_bytes } else { config.max_upload_bytes };
+
+        if content_length.is_some_and(|length| length > limit) {
+            return Ok(req.into_response(payload_too_large(limit)).map_into_right_body());
+        }
+        limit_payload(&mut req, limit, exceeded.clone());
+        body_limit = Some(limit);
+    }
+
+    if let Some(limiter) = limiter.filter(|limiter| limiter.enabled()) {
+        let exempt = matches!(req.path(), "/health" | "/metrics");
+        if !exempt
+            && let Err(retry_after) = limiter.check(&client_key(&req), Instant::now())
+        {
+            return Ok(req.into_response(too_many_requests(retry_after)).map_into_right_body());
+        }
+    }
+
+    let response = next.call(req).await?;
+    match body_limit {
+        Some(limit) if exceeded.load(Ordering::Relaxed) => {
+            let (req, _) = response.into_parts();
+            Ok(ServiceResponse::new(req, payload_too_large(limit)).map_into_right_body())
+        }
+        _ => Ok(response.map_into_left_body()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::middleware::from_fn;
+    use actix_web::{App, test};
+
+    fn limiter(requests_per_minute: u32, burst: u32) -> RateLimiter {
+        RateLimiter::new(&LimitsConfig { requests_per_minute, burst, ..Default::default() })
+    }
+
+    #[actix_web::test]
+    async fn test_bucket_refills_over_time() {
+        let limiter = limiter(60, 2);
+        let start = Instant::now();
+
+        assert!(limiter.check("user:a", start).is_ok());
+        assert!(limiter.check("user:a", start).is_ok());
+        let retry_after = limiter.check("user:a", start).unwrap_err();
+        assert!(retry_after <= Duration::from_secs(1));
+
+        // Other clients have their own bucket
+        assert!(limiter.check("user:b", start).is_ok());
+
+        assert!(limiter.check("user:a", start + Duration::from_secs(1)).is_ok());
+        assert!(limiter.check("user:a", start + Duration::from_secs(1)).is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_bucket_never_exceeds_burst() {
+        let limiter = limiter(60, 2);
+        let start = Instant::now();
+
+        assert!(limiter.check("ip:127.0.0.1", start).is_ok());
+        let later = start + Duration::from_secs(3600);
+        assert!(limiter.check("ip:127.0.0.1", later).is_ok());
+        assert!(limiter.check("ip:127.0.0.1", later).is_ok());
+        assert!(limiter.check("ip:127.0.0.1", later).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_enforce_limits() {
+        let config = LimitsConfig { requests_per_minute: 60, burst: 2, max_json_bytes: 16, max_upload_bytes: 1024 };
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(RateLimiter::new(&config)))
+                .app_data(web::Data::new(config))
+                .wrap(from_fn(enforce_limits))
+                .route("/projects", web::post().to(|| async { HttpResponse::Ok().finish() }))
+                .route("/health", web::get().to(|| async { HttpResponse::Ok().finish() }))
+        ).await;
+
+        let body = r#"{"name": "a project that is too long"}"#;
+        let req = test::TestRequest::post()
+            .uri("/projects")
+            .insert_header((header::CONTENT_TYPE, "application/json"))
+            .insert_header((header::CONTENT_LENGTH, body.len()))
+            .set_payload(body)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 413);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["error"], "payload_too_large");
+        assert_eq!(body["limit_bytes"], 16);
+
+        for _ in 0..2 {
+            let req = test::TestRequest::post().uri("/projects").to_request();
+            let resp = test::call_service(&app, req).await;
+            assert!(resp.status().is_success());
+        }
+
+        let req = test::TestRequest::post().uri("/projects").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 429);
+        assert_eq!(resp.headers().get(header::RETRY_AFTER).unwrap(), "1");
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["error"], "rate_limited");
+        assert_eq!(body["retry_after_secs"], 1);
+
+        let req = test::TestRequest::get().uri("/health").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+    }
+
+    #[tokio::test]
+    async fn test_enforce_limits_counts_bodies_without_length() {
+        let config = LimitsConfig { requests_per_minute: 0, max_upload_bytes: 8, ..Default::default() };
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config))
+                .wrap(from_fn(enforce_limits))
+                // Reads the body itself like the multipart handlers, answering read errors its own way
+                .route("/upload", web::post().to(|mut payload: web::Payload| async move {
+                    while let Some(chunk) = payload.next().await {
+                        if chunk.is_err() {
+                            return HttpResponse::BadRequest().json("Failed to read upload");
+                        }
+                    }
+                    HttpResponse::Ok().finish()
+                }))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri("/upload")
+            .insert_header((header::CONTENT_TYPE, "multipart/form-data; boundary=x"))
+            .set_payload("more than eight bytes")
+            .to_request();
+        assert!(req.headers().get(header::CONTENT_LENGTH).is_none());
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 413);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["error"], "payload_too_large");
+        assert_eq!(body["limit_bytes"], 8);
+
+        let req = test::TestRequest::post().uri("/upload").set_payload("small").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+    }
+}
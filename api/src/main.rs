@@ -1,18 +1,75 @@
-use actix_web::{App, HttpResponse, HttpServer, Responder, web};
+use actix_web::{App, HttpResponse, HttpServer, Responder, middleware, web};
 use sqlx::{Pool, Postgres};
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
 
 mod auth;
+mod account;
+mod sessions;
 mod projects;
 mod tasks;
 mod storage;
 mod sync;
 mod image_annotation_categories;
 mod annotations;
+mod classifications;
+mod attributes;
+mod rotated_box;
+mod image_bounds;
+mod labeling_rules;
+mod deadlines;
+mod cleanup;
+mod snapshots;
+mod archive;
+mod video;
+mod interpolation;
+mod tracks;
+mod slices;
+mod tiles;
+mod thumbnails;
+mod stats;
+mod reports;
 mod coco;
+mod csv_export;
+mod dota_export;
+mod mot_export;
+mod kitti_export;
+mod labelstudio;
+mod predictions;
+mod inference;
+mod segmentation;
+mod priorities;
+mod duplicates;
+mod qc;
+mod consensus;
+mod gold;
+mod comments;
+mod shares;
+mod export_presets;
+mod export_delivery;
+mod export_jobs;
+mod project_clone;
+mod templates;
+mod metrics;
+mod openapi;
+mod server_config;
+mod limits;
+mod cache;
+mod database;
 
 #[cfg(test)]
 mod test_utils;
 
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "ops",
+    security(()),
+    responses(
+        (status = 200, description = "API and database are up"),
+        (status = 503, description = "Database unreachable"),
+    ),
+)]
 async fn health_check(pool: web::Data<Pool<Postgres>>) -> impl Responder {
     match sqlx::query("SELECT 1").fetch_one(pool.get_ref()).await {
         Ok(_) => HttpResponse::Ok().json(serde_json::json!({
@@ -30,9 +87,19 @@ async fn health_check(pool: web::Data<Pool<Postgres>>) -> impl Responder {
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    dotenvy::from_filename("api/.env").ok();
+    let env_file = std::env::var("API_ENV_FILE").unwrap_or_else(|_| "api/.env".to_string());
+    dotenvy::from_filename(&env_file).ok();
+    // sqlx reports slow statements and slow connection acquires as warnings
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
 
-    println!("Starting API server on http://localhost:8080");
+    let server_config = match server_config::ServerConfig::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid server configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+    println!("Starting API server on {}", server_config.url());
 
     let database_url = std::env::var("DATABASE_URL").unwrap();
     let oauth_config = match auth::OAuthConfig::from_env() {
@@ -44,7 +111,73 @@ async fn main() -> std::io::Result<()> {
         }
     };
 
-    let pool = sqlx::PgPool::connect(&database_url)
+    let segmentation_config = segmentation::SegmentationConfig::from_env();
+    if segmentation_config.server_url.is_none() {
+        println!("SAM_SERVER_URL not set, assisted segmentation is disabled");
+    }
+
+    let avatar_config = match account::AvatarConfig::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid avatar configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if avatar_config.storage.is_none() {
+        println!("AVATAR_STORAGE not set, avatar uploads are disabled");
+    }
+
+    let limits_config = match limits::LimitsConfig::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid limits configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if limits_config.requests_per_minute == 0 {
+        println!("RATE_LIMIT_PER_MINUTE is 0, requests are not rate limited");
+    }
+    let rate_limiter = web::Data::new(limits::RateLimiter::new(&limits_config));
+
+    let export_notify_config = match export_jobs::ExportNotifyConfig::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid export notification configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let snapshot_config = match snapshots::SnapshotConfig::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid snapshot configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if snapshot_config.interval_hours.is_none() {
+        println!("SNAPSHOT_INTERVAL_HOURS not set, annotation snapshots are disabled");
+    }
+
+    let metrics_config = metrics::MetricsConfig::from_env();
+    if metrics_config.token.is_none() {
+        println!("METRICS_TOKEN not set, /metrics is open to anyone who can reach the server");
+    }
+
+    let cache_config = cache::CacheConfig::from_env();
+    if cache_config.redis_url.is_none() {
+        println!("REDIS_URL not set, caching is disabled");
+    }
+    cache::init(&cache_config).await;
+
+    let database_config = match database::DatabaseConfig::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid database configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let pool = database_config
+        .connect(&database_url)
         .await
         .expect("Failed to connect to database");
     
@@ -61,6 +194,18 @@ async fn main() -> std::io::Result<()> {
         }
     }
     
+    // Syncs run in the background of the server, the ones it was running when it stopped are lost
+    match sync::fail_interrupted_syncs(&pool).await {
+        Ok(0) => {}
+        Ok(count) => println!("Marked {} interrupted sync(s) as failed", count),
+        Err(e) => eprintln!("Failed to mark interrupted syncs as failed: {}", e),
+    }
+    match export_jobs::fail_interrupted_exports(&pool).await {
+        Ok(0) => {}
+        Ok(count) => println!("Marked {} interrupted export(s) as failed", count),
+        Err(e) => eprintln!("Failed to mark interrupted exports as failed: {}", e),
+    }
+
     // Start cleanup task for expired auth requests
     let auth_storage_cleanup = auth_storage.clone();
     tokio::spawn(async move {
@@ -73,12 +218,33 @@ async fn main() -> std::io::Result<()> {
         }
     });
 
-    HttpServer::new(move || {
+    // Snapshot the annotations of every project with storage
+    tokio::spawn(snapshots::run_snapshot_job(pool.clone(), snapshot_config));
+
+    let openapi = openapi::ApiDoc::openapi();
+    let cors_config = server_config.clone();
+
+    let server = HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(pool.clone()))
             .app_data(web::Data::new(oauth_config.clone()))
             .app_data(web::Data::new(auth_storage.clone()))
+            .app_data(web::Data::new(segmentation_config.clone()))
+            .app_data(web::Data::new(avatar_config.clone()))
+            .app_data(web::Data::new(metrics_config.clone()))
+            .app_data(web::Data::new(limits_config.clone()))
+            .app_data(web::Data::new(export_notify_config.clone()))
+            .app_data(rate_limiter.clone())
+            .app_data(limits_config.json_config())
+            .app_data(limits_config.payload_config())
+            .wrap(middleware::from_fn(archive::enforce_archived))
+            .wrap(middleware::from_fn(sessions::enforce_sessions))
+            .wrap(middleware::from_fn(limits::enforce_limits))
+            .wrap(middleware::from_fn(metrics::track_requests))
+            .wrap(middleware::Condition::new(cors_config.cors_enabled(), cors_config.cors()))
+            .service(SwaggerUi::new("/swagger-ui/{_:.*}").url("/api-docs/openapi.json", openapi.clone()))
             .route("/health", web::get().to(health_check))
+            .route("/metrics", web::get().to(metrics::get_metrics))
             .route("/auth/google", web::get().to(auth::google_login))
             .route(
                 "/auth/google/callback",
@@ -90,44 +256,166 @@ async fn main() -> std::io::Result<()> {
                 web::get().to(auth::github_callback),
             )
             .route("/auth/poll/{poll_token}", web::get().to(auth::poll_auth))
+            .route("/auth/refresh", web::post().to(sessions::refresh_token))
             .route("/me", web::get().to(auth::get_user_info))
+            .route("/me", web::put().to(account::update_profile))
+            .route("/me", web::delete().to(account::delete_account))
+            .route("/me/avatar", web::put().to(account::upload_avatar))
+            .route("/me/avatar", web::delete().to(account::delete_avatar))
+            .route("/me/providers", web::get().to(account::list_linked_providers))
+            .route("/me/providers/{provider}", web::post().to(account::link_provider))
+            .route("/me/providers/{provider}", web::delete().to(account::unlink_provider))
+            .route("/me/sessions", web::get().to(sessions::list_sessions))
+            .route("/me/sessions/{session_id}", web::delete().to(sessions::revoke_session))
+            .route("/users/{user_id}/avatar", web::get().to(account::get_user_avatar))
             .route("/projects", web::post().to(projects::create_project))
             .route("/projects", web::get().to(projects::list_projects))
             .route("/projects/{id}", web::get().to(projects::get_project))
             .route("/projects/{id}", web::put().to(projects::update_project))
             .route("/projects/{id}", web::delete().to(projects::delete_project))
             .route("/projects/{id}/storage-config", web::put().to(projects::update_storage_config))
+            .route("/projects/{id}/strict-bounds", web::put().to(projects::update_strict_bounds))
+            .route("/projects/{id}/labeling-rules", web::get().to(labeling_rules::get_labeling_rules))
+            .route("/projects/{id}/labeling-rules", web::put().to(labeling_rules::update_labeling_rules))
+            .route("/projects/{id}/deadlines", web::get().to(deadlines::get_deadline_settings))
+            .route("/projects/{id}/deadlines", web::put().to(deadlines::update_deadline_settings))
+            .route("/projects/{id}/cleanup", web::post().to(cleanup::cleanup_project))
+            .route("/projects/{id}/snapshots", web::get().to(snapshots::list_snapshots))
+            .route("/projects/{id}/snapshots/restore", web::post().to(snapshots::restore_snapshot))
+            .route("/projects/{id}/archive", web::post().to(archive::archive_project))
+            .route("/projects/{id}/archive", web::delete().to(archive::unarchive_project))
+            .route("/projects/{id}/clone", web::post().to(project_clone::clone_project))
+            .route("/projects/{id}/members", web::get().to(projects::list_project_members))
+            // Project template endpoints
+            .route("/templates", web::get().to(templates::list_templates))
+            .route("/templates", web::post().to(templates::create_template))
+            .route("/templates/{id}", web::delete().to(templates::delete_template))
             .route("/projects/{project_id}/tasks", web::post().to(tasks::create_task))
             .route("/projects/{project_id}/tasks", web::get().to(tasks::list_tasks))
+            .route("/projects/{project_id}/stats", web::get().to(stats::get_project_stats))
+            .route("/projects/{project_id}/stats/annotations", web::get().to(stats::get_annotation_stats))
+            .route("/projects/{project_id}/reports/annotators", web::get().to(reports::get_annotators_report))
+            .route("/projects/{project_id}/tasks/priorities", web::post().to(priorities::upload_task_priorities))
+            .route("/projects/{project_id}/duplicates", web::get().to(duplicates::get_duplicates))
+            .route("/projects/{project_id}/tasks/batch/delete", web::post().to(tasks::batch_delete_tasks))
+            .route("/projects/{project_id}/tasks/batch/status", web::post().to(tasks::batch_update_task_status))
+            .route("/projects/{project_id}/tasks/batch/assign", web::post().to(tasks::batch_assign_tasks))
+            .route("/projects/{project_id}/tasks/batch/split", web::post().to(tasks::batch_set_task_split))
+            .route("/projects/{project_id}/tasks/batch/due", web::post().to(tasks::batch_set_task_due))
             .route("/projects/{project_id}/tasks/{task_id}", web::get().to(tasks::get_task))
             .route("/projects/{project_id}/tasks/{task_id}", web::put().to(tasks::update_task))
             .route("/projects/{project_id}/tasks/{task_id}", web::delete().to(tasks::delete_task))
+            .route("/projects/{project_id}/tasks/{task_id}/image", web::get().to(tasks::get_task_image))
+            .route("/projects/{project_id}/tasks/{task_id}/refresh", web::post().to(tasks::refresh_task))
+            .route("/projects/{project_id}/tasks/{task_id}/flag", web::put().to(tasks::flag_task))
+            .route("/projects/{project_id}/tasks/{task_id}/flag", web::delete().to(tasks::unflag_task))
+            .route("/projects/{project_id}/tasks/{task_id}/review", web::delete().to(qc::complete_review))
+            .route("/projects/{project_id}/qc/sample", web::post().to(qc::sample_tasks_for_review))
             .route("/projects/{project_id}/storage/upload", web::post().to(storage::handlers::upload_file))
             .route("/projects/{project_id}/storage/{key}", web::get().to(storage::handlers::download_file))
             .route("/projects/{project_id}/storage/{key}/url", web::get().to(storage::handlers::get_presigned_url))
             .route("/projects/{project_id}/storage", web::get().to(storage::handlers::list_objects))
             .route("/projects/{project_id}/sync", web::post().to(sync::sync_storage_to_tasks))
             .route("/projects/{project_id}/sync/{sync_id}", web::get().to(sync::get_sync_status))
+            .route("/projects/{project_id}/syncs", web::get().to(sync::list_syncs))
             // Image annotation categories endpoints
             .route("/projects/{project_id}/image-annotation-categories", web::post().to(image_annotation_categories::create_image_annotation_category))
             .route("/projects/{project_id}/image-annotation-categories", web::get().to(image_annotation_categories::list_image_annotation_categories))
+            .route("/projects/{project_id}/image-annotation-categories/bulk", web::post().to(image_annotation_categories::bulk_import_image_annotation_categories))
+            .route("/projects/{project_id}/image-annotation-categories/tree", web::get().to(image_annotation_categories::get_image_annotation_category_tree))
+            .route("/projects/{project_id}/image-annotation-categories/relabel", web::post().to(image_annotation_categories::relabel_image_annotations))
+            .route("/projects/{project_id}/image-annotation-categories/hotkeys", web::put().to(image_annotation_categories::update_image_annotation_category_hotkeys))
             .route("/projects/{project_id}/image-annotation-categories/{category_id}", web::get().to(image_annotation_categories::get_image_annotation_category))
             .route("/projects/{project_id}/image-annotation-categories/{category_id}", web::put().to(image_annotation_categories::update_image_annotation_category))
             .route("/projects/{project_id}/image-annotation-categories/{category_id}", web::delete().to(image_annotation_categories::delete_image_annotation_category))
+            .route("/projects/{project_id}/image-annotation-categories/{category_id}/attribute-schema", web::put().to(image_annotation_categories::update_image_annotation_category_attribute_schema))
+            .route("/projects/{project_id}/image-annotation-categories/{category_id}/merge-into/{target_id}", web::post().to(image_annotation_categories::merge_image_annotation_category))
             // Annotations endpoints
             .route("/projects/{project_id}/tasks/{task_id}/annotations", web::post().to(annotations::create_annotation))
             .route("/projects/{project_id}/tasks/{task_id}/annotations", web::get().to(annotations::list_annotations))
             .route("/projects/{project_id}/tasks/{task_id}/annotations/{annotation_id}", web::get().to(annotations::get_annotation))
             .route("/projects/{project_id}/tasks/{task_id}/annotations/{annotation_id}", web::put().to(annotations::update_annotation))
             .route("/projects/{project_id}/tasks/{task_id}/annotations/{annotation_id}", web::delete().to(annotations::delete_annotation))
+            // Video frame endpoints
+            .route("/projects/{project_id}/tasks/{task_id}/frames", web::get().to(video::list_task_frames))
+            .route("/projects/{project_id}/tasks/{task_id}/interpolate", web::post().to(interpolation::interpolate_task))
+            // Track endpoints
+            .route("/projects/{project_id}/tracks", web::post().to(tracks::link_track))
+            .route("/projects/{project_id}/tracks/{track_id}", web::get().to(tracks::get_track))
+            .route("/projects/{project_id}/tracks/{track_id}/boxes/{box_id}", web::delete().to(tracks::unlink_track_box))
+            .route("/projects/{project_id}/tasks/{task_id}/tiles", web::get().to(tiles::get_task_tile_info))
+            .route("/projects/{project_id}/tasks/{task_id}/tiles/{level}/{col}/{row}", web::get().to(tiles::get_task_tile))
+            .route("/projects/{project_id}/tasks/{task_id}/thumbnail", web::get().to(thumbnails::get_task_thumbnail))
+            // Classification label endpoints
+            .route("/projects/{project_id}/tasks/{task_id}/classification", web::get().to(classifications::get_task_classification))
+            .route("/projects/{project_id}/tasks/{task_id}/classification", web::put().to(classifications::set_task_classification))
             // Export endpoints
             .route("/projects/{project_id}/export/coco", web::get().to(coco::export_project_coco))
+            .route("/projects/{project_id}/export/coco", web::post().to(coco::deliver_project_coco))
+            .route("/projects/{project_id}/export/csv", web::get().to(csv_export::export_project_csv))
+            .route("/projects/{project_id}/export/labelstudio", web::get().to(labelstudio::export_project_labelstudio))
+            .route("/projects/{project_id}/export/dota", web::get().to(dota_export::export_project_dota))
+            .route("/projects/{project_id}/export/mot", web::get().to(mot_export::export_project_mot))
+            .route("/projects/{project_id}/export/kitti", web::get().to(kitti_export::export_project_kitti))
+            .route("/projects/{project_id}/export/classification", web::get().to(classifications::export_project_classifications))
+            .route("/projects/{project_id}/exports", web::get().to(export_jobs::list_project_exports))
+            .route("/projects/{id}/export-presets", web::get().to(export_presets::list_export_presets))
+            .route("/projects/{id}/export-presets", web::post().to(export_presets::create_export_preset))
+            .route("/projects/{id}/export-presets/{preset_id}", web::put().to(export_presets::update_export_preset))
+            .route("/projects/{id}/export-presets/{preset_id}", web::delete().to(export_presets::delete_export_preset))
             // Import endpoints  
             .route("/projects/{project_id}/import/coco", web::post().to(coco::import_project_coco))
-    })
-    .bind("127.0.0.1:8080")?
-    .run()
-    .await
+            .route("/projects/{project_id}/import/labelstudio", web::post().to(labelstudio::import_project_labelstudio))
+            .route("/projects/{project_id}/import/predictions", web::post().to(predictions::import_predictions))
+            // Auto-annotation endpoints
+            .route("/projects/{project_id}/model", web::get().to(inference::get_project_model))
+            .route("/projects/{project_id}/model", web::put().to(inference::update_project_model))
+            .route("/projects/{project_id}/tasks/{task_id}/auto-annotate", web::post().to(inference::auto_annotate_task))
+            .route("/projects/{project_id}/tasks/{task_id}/segment", web::post().to(segmentation::segment_task))
+            // Multi-annotator agreement endpoints
+            .route("/projects/{project_id}/tasks/{task_id}/assignments", web::get().to(consensus::get_task_assignments))
+            .route("/projects/{project_id}/tasks/{task_id}/assignments", web::put().to(consensus::update_task_assignments))
+            .route("/projects/{project_id}/tasks/{task_id}/consensus", web::post().to(consensus::create_consensus_annotation))
+            .route("/projects/{project_id}/agreement", web::get().to(consensus::get_agreement_report))
+            .route("/projects/{project_id}/gold", web::get().to(gold::list_gold_tasks))
+            .route("/projects/{project_id}/tasks/{task_id}/gold", web::put().to(gold::set_gold_task))
+            .route("/projects/{project_id}/tasks/{task_id}/gold", web::delete().to(gold::unset_gold_task))
+            // Comment endpoints
+            .route("/projects/{project_id}/tasks/{task_id}/comments", web::post().to(comments::create_comment))
+            .route("/projects/{project_id}/tasks/{task_id}/comments", web::get().to(comments::list_comments))
+            .route("/projects/{project_id}/tasks/{task_id}/comments/{comment_id}", web::put().to(comments::update_comment))
+            .route("/projects/{project_id}/tasks/{task_id}/comments/{comment_id}", web::delete().to(comments::delete_comment))
+            .route("/projects/{project_id}/mentions", web::get().to(comments::list_my_mentions))
+            // Share link endpoints
+            .route("/projects/{id}/shares", web::post().to(shares::create_share))
+            .route("/projects/{id}/shares", web::get().to(shares::list_shares))
+            .route("/projects/{id}/shares/{share_id}", web::delete().to(shares::delete_share))
+            // Public read-only endpoints opened by a share link
+            .route("/shared/{token}", web::get().to(shares::get_shared_project))
+            .route("/shared/{token}/tasks", web::get().to(shares::list_shared_tasks))
+            .route("/shared/{token}/tasks/{task_id}/annotations", web::get().to(shares::list_shared_annotations))
+            .route("/shared/{token}/export/coco", web::get().to(shares::export_shared_coco))
+    });
+
+    let server = match server_config.workers {
+        Some(workers) => server.workers(workers),
+        None => server,
+    };
+    let server = match &server_config.tls {
+        Some(tls) => {
+            let rustls_config = match server_config::load_rustls_config(tls) {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            };
+            server.bind_rustls_0_23(&server_config.bind_address, rustls_config)?
+        }
+        None => server.bind(&server_config.bind_address)?,
+    };
+
+    server.run().await
 }
 
 #[cfg(test)]
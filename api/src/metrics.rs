@@ -0,0 +1,244 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use sqlx::{Pool, Postgres};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+/// Settings of the `/metrics` endpoint
+#[derive(Debug, Clone, Default)]
+pub struct MetricsConfig {
+    /// Bearer token scrapers have to send, the endpoint is open when unset
+    pub token: Option<String>,
+}
+
+impl MetricsConfig {
+    pub fn from_env() -> Self {
+        Self {
+            token: std::env::var("METRICS_TOKEN").ok().filter(|token| !token.is_empty()),
+        }
+    }
+}
+
+pub struct Metrics {
+    registry: Registry,
+    pub http_requests: IntCounterVec,
+    pub http_request_duration: HistogramVec,
+    pub db_pool_connections: IntGaugeVec,
+    pub db_pool_max_connections: IntGauge,
+    pub storage_calls: IntCounterVec,
+    pub storage_errors: IntCounterVec,
+    pub sync_jobs_running: IntGauge,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests by route pattern and status"),
+            &["method", "path", "status"],
+        )
+        .unwrap();
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "Time to answer HTTP requests by route pattern"),
+            &["method", "path"],
+        )
+        .unwrap();
+        let db_pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Database connections of the pool by state"),
+            &["state"],
+        )
+        .unwrap();
+        let db_pool_max_connections =
+            IntGauge::new("db_pool_max_connections", "Connections the database pool may open").unwrap();
+        let storage_calls = IntCounterVec::new(
+            Opts::new("storage_calls_total", "Calls to storage providers"),
+            &["provider", "operation"],
+        )
+        .unwrap();
+        let storage_errors = IntCounterVec::new(
+            Opts::new("storage_errors_total", "Failed calls to storage providers, missing objects not included"),
+            &["provider", "operation"],
+        )
+        .unwrap();
+        let sync_jobs_running =
+            IntGauge::new("sync_jobs_running", "Storage syncs that haven't finished yet").unwrap();
+
+        registry.register(Box::new(http_requests.clone())).unwrap();
+        registry.register(Box::new(http_request_duration.clone())).unwrap();
+        registry.register(Box::new(db_pool_connections.clone())).unwrap();
+        registry.register(Box::new(db_pool_max_connections.clone())).unwrap();
+        registry.register(Box::new(storage_calls.clone())).unwrap();
+        registry.register(Box::new(storage_errors.clone())).unwrap();
+        registry.register(Box::new(sync_jobs_running.clone())).unwrap();
+
+        Self {
+            registry,
+            http_requests,
+            http_request_duration,
+            db_pool_connections,
+            db_pool_max_connections,
+            storage_calls,
+            storage_errors,
+            sync_jobs_running,
+        }
+    }
+}
+
+/// Metrics of the whole process. Storage providers are created deep inside handlers and
+/// background syncs, so they are counted here rather than through app data.
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::new)
+}
+
+/// Counts every request and its latency under the route pattern, so `/projects/{id}` stays one
+/// series however many projects there are
+pub async fn track_requests(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let started = Instant::now();
+    let method = req.method().to_string();
+    let path = req.match_pattern().unwrap_or_else(|| "unmatched".to_string());
+
+    let result = next.call(req).await;
+
+    let status = match &result {
+        Ok(res) => res.status(),
+        Err(error) => error.as_response_error().status_code(),
+    };
+    let metrics = metrics();
+    metrics
+        .http_requests
+        .with_label_values(&[method.as_str(), path.as_str(), status.as_str()])
+        .inc();
+    metrics
+        .http_request_duration
+        .with_label_values(&[method.as_str(), path.as_str()])
+        .observe(started.elapsed().as_secs_f64());
+
+    result
+}
+
+/// Prometheus text format of all metrics, with the pool and sync gauges read at scrape time
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "ops",
+    security(()),
+    responses(
+        (status = 200, description = "Prometheus text format", body = String, content_type = "text/plain"),
+        (status = 401, description = "`METRICS_TOKEN` is set and wasn't sent as bearer token", body = String),
+    ),
+)]
+pub async fn get_metrics(
+    req: HttpRequest,
+    pool: web::Data<Pool<Postgres>>,
+    metrics_config: web::Data<MetricsConfig>,
+) -> impl Responder {
+    if let Some(token) = &metrics_config.token {
+        let authorized = req
+            .headers()
+            .get("Authorization")
+            .and_then(|header| header.to_str().ok())
+            .and_then(|header| header.strip_prefix("Bearer "))
+            .is_some_and(|bearer| bearer == token);
+        if !authorized {
+            return HttpResponse::Unauthorized().json("Invalid metrics token");
+        }
+    }
+
+    let metrics = metrics();
+    let idle = pool.num_idle() as i64;
+    metrics.db_pool_connections.with_label_values(&["idle"]).set(idle);
+    metrics.db_pool_connections.with_label_values(&["in_use"]).set(pool.size() as i64 - idle);
+    metrics.db_pool_max_connections.set(pool.options().get_max_connections() as i64);
+
+    match sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM project_syncs WHERE status = 'running'")
+        .fetch_one(pool.get_ref())
+        .await
+    {
+        Ok(running) => metrics.sync_jobs_running.set(running),
+        Err(e) => eprintln!("Failed to count running syncs: {}", e),
+    }
+
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    if let Err(e) = encoder.encode(&metrics.registry.gather(), &mut buffer) {
+        eprintln!("Failed to encode metrics: {}", e);
+        return HttpResponse::InternalServerError().json("Failed to encode metrics");
+    }
+
+    HttpResponse::Ok()
+        .content_type(encoder.format_type())
+        .body(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+    use actix_web::middleware::from_fn;
+    use actix_web::{App, test};
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_metrics_endpoint() {
+        let pool = setup_test_db().await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(MetricsConfig::default()))
+                .wrap(from_fn(track_requests))
+                .route("/health", web::get().to(crate::health_check))
+                .route("/metrics", web::get().to(get_metrics))
+        ).await;
+
+        let req = test::TestRequest::get().uri("/health").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let req = test::TestRequest::get().uri("/metrics").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains(r#"http_requests_total{method="GET",path="/health",status="200"}"#));
+        assert!(body.contains("http_request_duration_seconds_bucket"));
+        assert!(body.contains(r#"db_pool_connections{state="idle"}"#));
+        assert!(body.contains("db_pool_max_connections 5"));
+        assert!(body.contains("sync_jobs_running 0"));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_metrics_endpoint_requires_token() {
+        let pool = setup_test_db().await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(MetricsConfig { token: Some("scrape-secret".to_string()) }))
+                .route("/metrics", web::get().to(get_metrics))
+        ).await;
+
+        let req = test::TestRequest::get().uri("/metrics").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 401);
+
+        let req = test::TestRequest::get()
+            .uri("/metrics")
+            .insert_header(("Authorization", "Bearer scrape-secret"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+    }
+}
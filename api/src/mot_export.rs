@@ -0,0 +1,421 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use sqlx::{Pool, Postgres};
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Write};
+use uuid::Uuid;
+use chrono::Utc;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::auth::{JwtManager, Claims};
+
+/// File at the root of the archive naming the classes, the class of a box is its line number
+pub const LABELS_FILE: &str = "labels.txt";
+
+/// One task joined with one of its boxes. Tasks without boxes appear once with `bbox` set to `None`.
+#[derive(Debug, sqlx::FromRow)]
+pub struct MotAnnotationRow {
+    pub task_id: Uuid,
+    pub task_name: String,
+    pub resource_url: Option<String>,
+    /// Set for video tasks
+    pub frame_count: Option<i32>,
+    pub frame_rate: Option<f64>,
+    pub category_name: Option<String>,
+    pub bbox: Option<Vec<f64>>,
+    pub frame_index: Option<i32>,
+    pub track_id: Option<Uuid>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/export/mot",
+    tag = "export",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 200, description = "ZIP with one MOTChallenge sequence per video and one for the images", body = [u8], content_type = "application/zip"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn export_project_mot(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let project_name = match get_project_name(&pool, project_id).await {
+        Ok(Some(name)) => name,
+        Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    };
+
+    let rows = match get_project_rows_for_mot(&pool, project_id).await {
+        Ok(rows) => rows,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotations"),
+    };
+
+    let sequence_name = project_name.replace(" ", "_").to_lowercase();
+    let archive = match write_archive(&build_mot_files(&sequence_name, &rows)) {
+        Ok(archive) => archive,
+        Err(e) => {
+            eprintln!("Failed to build MOT archive: {}", e);
+            return HttpResponse::InternalServerError().json("Failed to build export archive");
+        }
+    };
+
+    let filename = format!("{}_mot_{}.zip", sequence_name, Utc::now().format("%Y%m%d_%H%M%S"));
+
+    HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", filename)))
+        .body(archive)
+}
+
+/// One `<name>/` folder of the archive
+struct Sequence {
+    name: String,
+    frame_rate: f64,
+    length: i32,
+    /// Image of every frame of an image sequence, in frame order
+    images: Vec<String>,
+    /// `(frame, object, line)` of every box
+    lines: Vec<(i32, usize, String)>,
+    objects: HashMap<Uuid, usize>,
+    object_count: usize,
+}
+
+impl Sequence {
+    fn new(name: String, frame_rate: f64, length: i32) -> Self {
+        Self { name, frame_rate, length, images: Vec::new(), lines: Vec::new(), objects: HashMap::new(), object_count: 0 }
+    }
+
+    /// MOT numbers objects from 1 within a sequence. Boxes of one track share a number,
+    /// boxes without a track are objects of their own.
+    fn object_id(&mut self, track_id: Option<Uuid>) -> usize {
+        if let Some(id) = track_id.and_then(|track_id| self.objects.get(&track_id)) {
+            return *id;
+        }
+        self.object_count += 1;
+        if let Some(track_id) = track_id {
+            self.objects.insert(track_id, self.object_count);
+        }
+        self.object_count
+    }
+}
+
+/// Lays the rows out as MOTChallenge sequences. Every video task is a sequence of its own;
+/// the image tasks, ordered by name, are the frames of one sequence called `image_sequence`.
+/// Each sequence has a `gt/gt.txt` with `frame,id,left,top,width,height,1,class,1` lines and a
+/// `seqinfo.ini`, image sequences also an `images.txt` naming the image of every frame.
+pub fn build_mot_files(image_sequence: &str, rows: &[MotAnnotationRow]) -> Vec<(String, String)> {
+    let mut classes: Vec<&str> = rows
+        .iter()
+        .filter(|row| row.bbox.is_some())
+        .map(|row| row.category_name.as_deref().unwrap_or("unknown"))
+        .collect();
+    classes.sort();
+    classes.dedup();
+
+    let mut sequences: Vec<Sequence> = Vec::new();
+    let mut images_index = None;
+    let mut used_names = HashSet::new();
+    // Task of the previous row, its sequence and its frame in an image sequence
+    let mut current: Option<(Uuid, usize, Option<i32>)> = None;
+
+    for row in rows {
+        if current.is_none_or(|(task_id, _, _)| task_id != row.task_id) {
+            let file_name = row.resource_url
+                .as_deref()
+                .and_then(|url| url.split('/').next_back())
+                .unwrap_or(&row.task_name);
+
+            current = Some(match row.frame_count {
+                Some(frame_count) => {
+                    let stem = file_name.rsplit_once('.').map_or(file_name, |(stem, _)| stem);
+                    let name = unique_name(&mut used_names, stem.replace(char::is_whitespace, "_"), row.task_id);
+                    sequences.push(Sequence::new(name, row.frame_rate.unwrap_or(1.0), frame_count));
+                    (row.task_id, sequences.len() - 1, None)
+                }
+                None => {
+                    let index = match images_index {
+                        Some(index) => index,
+                        None => {
+                            let name = unique_name(&mut used_names, image_sequence.to_string(), row.task_id);
+                            sequences.push(Sequence::new(name, 1.0, 0));
+                            images_index = Some(sequences.len() - 1);
+                            sequences.len() - 1
+                        }
+                    };
+                    let sequence = &mut sequences[index];
+                    sequence.length += 1;
+                    sequence.images.push(file_name.to_string());
+                    (row.task_id, index, Some(sequence.length))
+                }
+            });
+        }
+
+        let Some((_, index, image_frame)) = current else {
+            continue;
+        };
+        let bbox = match &row.bbox {
+            Some(bbox) if bbox.len() >= 4 => bbox,
+            _ => continue,
+        };
+        // MOT frames count from 1
+        let Some(frame) = image_frame.or(row.frame_index.map(|frame_index| frame_index + 1)) else {
+            continue;
+        };
+
+        let category = row.category_name.as_deref().unwrap_or("unknown");
+        let class = classes.iter().position(|class| *class == category).map_or(0, |position| position + 1);
+        let sequence = &mut sequences[index];
+        let id = sequence.object_id(row.track_id);
+        sequence.lines.push((
+            frame,
+            id,
+            format!("{},{},{:.2},{:.2},{:.2},{:.2},1,{},1\n", frame, id, bbox[0], bbox[1], bbox[2], bbox[3], class),
+        ));
+    }
+
+    let mut files = vec![(LABELS_FILE.to_string(), classes.iter().map(|class| format!("{}\n", class)).collect())];
+    for mut sequence in sequences {
+        sequence.lines.sort_by_key(|(frame, id, _)| (*frame, *id));
+        files.push((
+            format!("{}/gt/gt.txt", sequence.name),
+            sequence.lines.into_iter().map(|(_, _, line)| line).collect(),
+        ));
+        files.push((
+            format!("{}/seqinfo.ini", sequence.name),
+            format!(
+                "[Sequence]\nname={}\nframeRate={}\nseqLength={}\n",
+                sequence.name, sequence.frame_rate, sequence.length
+            ),
+        ));
+        if !sequence.images.is_empty() {
+            files.push((
+                format!("{}/images.txt", sequence.name),
+                sequence.images.iter().enumerate().map(|(index, image)| format!("{},{}\n", index + 1, image)).collect(),
+            ));
+        }
+    }
+    files
+}
+
+/// Folders of different tasks can share a name, so duplicates are prefixed with the task ID
+fn unique_name(used_names: &mut HashSet<String>, name: String, task_id: Uuid) -> String {
+    if used_names.insert(name.clone()) {
+        return name;
+    }
+    let name = format!("{}_{}", task_id, name);
+    used_names.insert(name.clone());
+    name
+}
+
+fn write_archive(files: &[(String, String)]) -> zip::result::ZipResult<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (path, content) in files {
+        zip.start_file(path.as_str(), options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+async fn get_project_name(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>("SELECT name FROM projects WHERE id = $1")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+}
+
+async fn get_project_rows_for_mot(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Vec<MotAnnotationRow>, sqlx::Error> {
+    // Only the latest annotation of each task is exported, matching the COCO exporter.
+    // Image tasks are frames in name order, so the rows come sorted by name.
+    sqlx::query_as::<_, MotAnnotationRow>(
+        r#"
+        WITH latest_annotations AS (
+            SELECT DISTINCT ON (task_id) id, task_id
+            FROM annotations
+            WHERE task_id IN (SELECT id FROM tasks WHERE project_id = $1)
+            ORDER BY task_id, created_at DESC
+        )
+        SELECT
+            t.id as task_id,
+            t.name as task_name,
+            t.resource_url,
+            t.frame_count,
+            t.frame_rate,
+            iac.name as category_name,
+            ia.bbox,
+            ia.frame_index,
+            ia.track_id
+        FROM tasks t
+        LEFT JOIN latest_annotations la ON la.task_id = t.id
+        LEFT JOIN image_annotations ia ON ia.annotation_id = la.id AND NOT ia.is_prediction
+        LEFT JOIN image_annotation_categories iac ON iac.id = ia.category_id
+        WHERE t.project_id = $1
+        ORDER BY t.name, t.id, ia.frame_index, ia.created_at
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::OAuthConfig;
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn image_row(task_id: Uuid, name: &str, category: &str, bbox: Option<Vec<f64>>, track_id: Option<Uuid>) -> MotAnnotationRow {
+        MotAnnotationRow {
+            task_id,
+            task_name: name.to_string(),
+            resource_url: Some(format!("storage://seq/{}", name)),
+            frame_count: None,
+            frame_rate: None,
+            category_name: Some(category.to_string()),
+            bbox,
+            frame_index: None,
+            track_id,
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_build_mot_files() {
+        let car = Uuid::new_v4();
+        let (first, second, third, video) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let video_row = |frame_index: i32, track_id: Option<Uuid>| MotAnnotationRow {
+            task_id: video,
+            task_name: "drive cam.mp4".to_string(),
+            resource_url: None,
+            frame_count: Some(10),
+            frame_rate: Some(2.0),
+            category_name: Some("person".to_string()),
+            bbox: Some(vec![5.0, 5.0, 2.0, 4.0]),
+            frame_index: Some(frame_index),
+            track_id,
+        };
+        let rows = vec![
+            image_row(first, "001.jpg", "car", Some(vec![10.0, 20.0, 30.0, 40.0]), Some(car)),
+            image_row(first, "001.jpg", "car", Some(vec![0.0, 0.0, 5.0, 5.0]), None),
+            image_row(second, "002.jpg", "car", None, None),
+            image_row(third, "003.jpg", "car", Some(vec![12.5, 20.0, 30.0, 40.0]), Some(car)),
+            video_row(0, Some(car)),
+            video_row(3, Some(car)),
+        ];
+
+        let files = build_mot_files("street", &rows);
+        let file = |path: &str| files.iter().find(|(file_path, _)| file_path == path).map(|(_, content)| content.as_str());
+
+        assert_eq!(file("labels.txt"), Some("car\nperson\n"));
+
+        // The car keeps its number across the images, the untracked box gets the next one
+        assert_eq!(
+            file("street/gt/gt.txt"),
+            Some("1,1,10.00,20.00,30.00,40.00,1,1,1\n1,2,0.00,0.00,5.00,5.00,1,1,1\n3,1,12.50,20.00,30.00,40.00,1,1,1\n")
+        );
+        assert_eq!(file("street/seqinfo.ini"), Some("[Sequence]\nname=street\nframeRate=1\nseqLength=3\n"));
+        assert_eq!(file("street/images.txt"), Some("1,001.jpg\n2,002.jpg\n3,003.jpg\n"));
+
+        // Videos number their objects on their own and count frames from 1
+        assert_eq!(
+            file("drive_cam/gt/gt.txt"),
+            Some("1,1,5.00,5.00,2.00,4.00,1,2,1\n4,1,5.00,5.00,2.00,4.00,1,2,1\n")
+        );
+        assert_eq!(file("drive_cam/seqinfo.ini"), Some("[Sequence]\nname=drive_cam\nframeRate=2\nseqLength=10\n"));
+        assert_eq!(file("drive_cam/images.txt"), None);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_export_project_mot_unauthorized() {
+        let pool = test_utils::setup_test_db().await;
+        let oauth_config = OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/export/mot", web::get().to(export_project_mot))
+        ).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/export/mot", Uuid::new_v4()))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 401);
+    }
+}
@@ -0,0 +1,256 @@
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi, ToSchema};
+
+/// Multipart form of the upload and import endpoints, the file goes in the `file` field
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct FileUpload {
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
+
+/// Spec of every route in `main.rs`, served at `/api-docs/openapi.json` with Swagger UI at
+/// `/swagger-ui/`. Schemas are collected from the request and response bodies of the paths.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "fast-tag API",
+        description = "Projects, tasks and annotations of the fast-tag labeling tool. Log in through \
+                       one of the OAuth flows and send the JWT as bearer token.",
+    ),
+    paths(
+        crate::health_check,
+        crate::metrics::get_metrics,
+        crate::auth::google_login,
+        crate::auth::google_callback,
+        crate::auth::github_login,
+        crate::auth::github_callback,
+        crate::auth::poll_auth,
+        crate::sessions::refresh_token,
+        crate::auth::get_user_info,
+        crate::account::update_profile,
+        crate::account::upload_avatar,
+        crate::account::delete_avatar,
+        crate::account::get_user_avatar,
+        crate::account::delete_account,
+        crate::account::list_linked_providers,
+        crate::account::link_provider,
+        crate::account::unlink_provider,
+        crate::sessions::list_sessions,
+        crate::sessions::revoke_session,
+        crate::projects::create_project,
+        crate::projects::list_projects,
+        crate::projects::get_project,
+        crate::projects::update_project,
+        crate::projects::delete_project,
+        crate::projects::update_storage_config,
+        crate::projects::update_strict_bounds,
+        crate::labeling_rules::get_labeling_rules,
+        crate::labeling_rules::update_labeling_rules,
+        crate::deadlines::get_deadline_settings,
+        crate::deadlines::update_deadline_settings,
+        crate::cleanup::cleanup_project,
+        crate::snapshots::list_snapshots,
+        crate::snapshots::restore_snapshot,
+        crate::archive::archive_project,
+        crate::archive::unarchive_project,
+        crate::projects::list_project_members,
+        crate::project_clone::clone_project,
+        crate::templates::list_templates,
+        crate::templates::create_template,
+        crate::templates::delete_template,
+        crate::tasks::create_task,
+        crate::tasks::list_tasks,
+        crate::tasks::get_task,
+        crate::tasks::get_task_image,
+        crate::tasks::refresh_task,
+        crate::tasks::update_task,
+        crate::tasks::delete_task,
+        crate::tasks::flag_task,
+        crate::tasks::unflag_task,
+        crate::qc::sample_tasks_for_review,
+        crate::qc::complete_review,
+        crate::tasks::batch_delete_tasks,
+        crate::tasks::batch_update_task_status,
+        crate::tasks::batch_assign_tasks,
+        crate::tasks::batch_set_task_split,
+        crate::tasks::batch_set_task_due,
+        crate::priorities::upload_task_priorities,
+        crate::duplicates::get_duplicates,
+        crate::stats::get_project_stats,
+        crate::stats::get_annotation_stats,
+        crate::reports::get_annotators_report,
+        crate::storage::handlers::upload_file,
+        crate::storage::handlers::download_file,
+        crate::storage::handlers::get_presigned_url,
+        crate::storage::handlers::list_objects,
+        crate::sync::sync_storage_to_tasks,
+        crate::sync::get_sync_status,
+        crate::sync::list_syncs,
+        crate::image_annotation_categories::create_image_annotation_category,
+        crate::image_annotation_categories::list_image_annotation_categories,
+        crate::image_annotation_categories::bulk_import_image_annotation_categories,
+        crate::image_annotation_categories::get_image_annotation_category_tree,
+        crate::image_annotation_categories::relabel_image_annotations,
+        crate::image_annotation_categories::update_image_annotation_category_hotkeys,
+        crate::image_annotation_categories::get_image_annotation_category,
+        crate::image_annotation_categories::update_image_annotation_category,
+        crate::image_annotation_categories::delete_image_annotation_category,
+        crate::image_annotation_categories::update_image_annotation_category_attribute_schema,
+        crate::image_annotation_categories::merge_image_annotation_category,
+        crate::annotations::create_annotation,
+        crate::annotations::list_annotations,
+        crate::annotations::get_annotation,
+        crate::annotations::update_annotation,
+        crate::annotations::delete_annotation,
+        crate::interpolation::interpolate_task,
+        crate::tracks::link_track,
+        crate::tracks::get_track,
+        crate::tracks::unlink_track_box,
+        crate::classifications::get_task_classification,
+        crate::classifications::set_task_classification,
+        crate::video::list_task_frames,
+        crate::tiles::get_task_tile_info,
+        crate::tiles::get_task_tile,
+        crate::thumbnails::get_task_thumbnail,
+        crate::coco::export::export_project_coco,
+        crate::coco::export::deliver_project_coco,
+        crate::csv_export::export_project_csv,
+        crate::labelstudio::export::export_project_labelstudio,
+        crate::dota_export::export_project_dota,
+        crate::mot_export::export_project_mot,
+        crate::kitti_export::export_project_kitti,
+        crate::classifications::export_project_classifications,
+        crate::export_jobs::list_project_exports,
+        crate::export_presets::list_export_presets,
+        crate::export_presets::create_export_preset,
+        crate::export_presets::update_export_preset,
+        crate::export_presets::delete_export_preset,
+        crate::coco::import::import_project_coco,
+        crate::labelstudio::import::import_project_labelstudio,
+        crate::predictions::import_predictions,
+        crate::inference::handlers::get_project_model,
+        crate::inference::handlers::update_project_model,
+        crate::inference::handlers::auto_annotate_task,
+        crate::segmentation::segment_task,
+        crate::consensus::handlers::get_task_assignments,
+        crate::consensus::handlers::update_task_assignments,
+        crate::consensus::handlers::create_consensus_annotation,
+        crate::consensus::handlers::get_agreement_report,
+        crate::gold::list_gold_tasks,
+        crate::gold::set_gold_task,
+        crate::gold::unset_gold_task,
+        crate::comments::create_comment,
+        crate::comments::list_comments,
+        crate::comments::update_comment,
+        crate::comments::delete_comment,
+        crate::comments::list_my_mentions,
+        crate::shares::create_share,
+        crate::shares::list_shares,
+        crate::shares::delete_share,
+        crate::shares::get_shared_project,
+        crate::shares::list_shared_tasks,
+        crate::shares::list_shared_annotations,
+        crate::shares::export_shared_coco,
+    ),
+    // Answered in place of the documented body, so no path refers to it
+    components(schemas(crate::coco::types::ImportDryRun, crate::image_bounds::OutOfBoundsError, crate::labeling_rules::RuleViolations)),
+    modifiers(&BearerAuth),
+    security(("bearer_auth" = [])),
+    tags(
+        (name = "ops", description = "Health check and metrics for operators"),
+        (name = "auth", description = "OAuth login and the logged in user"),
+        (name = "projects", description = "Projects and their members"),
+        (name = "templates", description = "Category sets new projects can start from"),
+        (name = "tasks", description = "Images and videos to annotate"),
+        (name = "storage", description = "Files in the storage of a project"),
+        (name = "sync", description = "Creating tasks from the files in storage"),
+        (name = "categories", description = "Annotation categories of a project"),
+        (name = "annotations", description = "Boxes and labels of tasks"),
+        (name = "media", description = "Frames, tiles and thumbnails of task images"),
+        (name = "reports", description = "Progress and productivity of a project"),
+        (name = "export", description = "Annotations in other tools' formats"),
+        (name = "import", description = "Annotations and predictions from other tools"),
+        (name = "inference", description = "Model-assisted annotation"),
+        (name = "consensus", description = "Multi-annotator assignments and agreement"),
+        (name = "comments", description = "Review comments on tasks"),
+        (name = "shares", description = "Expiring read-only links to a project"),
+    ),
+)]
+pub struct ApiDoc;
+
+/// The JWT handed out by the OAuth flows, required unless a path clears `security`
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utoipa::openapi::PathItem;
+
+    fn operation_exists(item: &PathItem, method: &str) -> bool {
+        match method {
+            "get" => item.get.is_some(),
+            "post" => item.post.is_some(),
+            "put" => item.put.is_some(),
+            "delete" => item.delete.is_some(),
+            "patch" => item.patch.is_some(),
+            _ => false,
+        }
+    }
+
+    /// Every `.route(path, web::method()...)` registered by the server
+    fn registered_routes() -> Vec<(String, String)> {
+        let main = include_str!("main.rs");
+        let server = &main[..main.find("#[cfg(test)]\nmod tests").unwrap_or(main.len())];
+
+        let mut routes = Vec::new();
+        for route in server.split(".route(").skip(1) {
+            let path = route.split('"').nth(1).unwrap().to_string();
+            let method = route.split("web::").nth(1).unwrap().split('(').next().unwrap().to_string();
+            routes.push((path, method));
+        }
+        routes
+    }
+
+    #[test]
+    fn test_every_route_is_documented() {
+        let spec = ApiDoc::openapi();
+        let routes = registered_routes();
+        assert!(routes.len() > 80);
+
+        for (path, method) in routes {
+            let item = spec.paths.paths.get(&path);
+            assert!(
+                item.is_some_and(|item| operation_exists(item, &method)),
+                "{} {} is missing from the OpenAPI spec",
+                method.to_uppercase(),
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_spec_has_schemas_and_auth() {
+        let spec = ApiDoc::openapi();
+        let components = spec.components.expect("components");
+
+        assert!(components.schemas.contains_key("TaskWithResolvedUrl"));
+        assert!(components.schemas.contains_key("CategoryTreeNode"));
+        assert!(components.security_schemes.contains_key("bearer_auth"));
+
+        let json = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        // Login endpoints work without a token
+        assert_eq!(json["paths"]["/auth/google"]["get"]["security"], serde_json::json!([{}]));
+        assert!(json["security"][0].get("bearer_auth").is_some());
+    }
+}
@@ -0,0 +1,502 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use actix_multipart::Multipart;
+use futures_util::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use std::collections::HashMap;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::auth::{JwtManager, Claims};
+use crate::openapi::FileUpload;
+
+/// A single detection in COCO results format, e.g. the output of a detector's
+/// evaluation script. Images are matched by `task_id`, `file_name` or the COCO
+/// `image_id` assigned by the COCO exporter (tasks ordered by creation time).
+#[derive(Debug, Deserialize)]
+pub struct PredictionInput {
+    pub image_id: Option<i64>,
+    pub file_name: Option<String>,
+    pub task_id: Option<Uuid>,
+    pub category_id: Option<i32>,
+    pub category_name: Option<String>,
+    pub bbox: Vec<f64>,
+    pub score: f64,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PredictionImportQuery {
+    /// Predictions scoring below this threshold are skipped
+    pub min_score: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PredictionImportResult {
+    pub tasks_updated: usize,
+    pub predictions_created: usize,
+    pub predictions_skipped: usize,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct TaskRow {
+    id: Uuid,
+    name: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct CategoryRow {
+    id: Uuid,
+    name: String,
+    coco_id: Option<i32>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ExistingBoxRow {
+    category_id: Option<Uuid>,
+    bbox: Vec<f64>,
+    area: Option<f64>,
+    iscrowd: Option<bool>,
+    image_metadata: Option<serde_json::Value>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/import/predictions",
+    tag = "import",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        PredictionImportQuery,
+    ),
+    request_body(content = FileUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, body = PredictionImportResult),
+        (status = 400, description = "Invalid predictions file", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn import_predictions(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<PredictionImportQuery>,
+    mut payload: Multipart,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    // Extract JSON data from multipart upload
+    let json_data = match extract_json_from_multipart(&mut payload).await {
+        Ok(data) => data,
+        Err(err) => return HttpResponse::BadRequest().json(format!("Failed to read file: {}", err)),
+    };
+
+    let predictions: Vec<PredictionInput> = match serde_json::from_str(&json_data) {
+        Ok(data) => data,
+        Err(err) => return HttpResponse::BadRequest().json(format!("Invalid predictions JSON: {}", err)),
+    };
+
+    if let Err(validation_error) = validate_predictions(&predictions) {
+        return HttpResponse::BadRequest().json(format!("Invalid predictions: {}", validation_error));
+    }
+
+    match import_predictions_in_db(&pool, project_id, user_id, &predictions, query.min_score.unwrap_or(0.0)).await {
+        Ok(result) => HttpResponse::Ok().json(result),
+        Err(err) => {
+            eprintln!("Prediction import error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to import predictions")
+        }
+    }
+}
+
+async fn extract_json_from_multipart(payload: &mut Multipart) -> Result<String, Box<dyn std::error::Error>> {
+    while let Some(mut field) = payload.try_next().await? {
+        if field.name() == Some("file") {
+            let mut data = bytes::BytesMut::new();
+            while let Some(chunk) = field.try_next().await? {
+                data.extend_from_slice(&chunk);
+            }
+
+            return Ok(String::from_utf8(data.to_vec())?);
+        }
+    }
+
+    Err("No file field found in multipart data".into())
+}
+
+fn validate_predictions(predictions: &[PredictionInput]) -> Result<(), String> {
+    if predictions.is_empty() {
+        return Err("No predictions found".to_string());
+    }
+
+    for (index, prediction) in predictions.iter().enumerate() {
+        if prediction.bbox.len() != 4 {
+            return Err(format!("Prediction {} has invalid bbox format", index));
+        }
+        if prediction.bbox.iter().any(|&value| value < 0.0) {
+            return Err(format!("Prediction {} has negative bbox values", index));
+        }
+        if !(0.0..=1.0).contains(&prediction.score) {
+            return Err(format!("Prediction {} has a score outside 0.0 - 1.0", index));
+        }
+        if prediction.image_id.is_none() && prediction.file_name.is_none() && prediction.task_id.is_none() {
+            return Err(format!("Prediction {} does not reference an image", index));
+        }
+        if prediction.category_id.is_none() && prediction.category_name.is_none() {
+            return Err(format!("Prediction {} does not reference a category", index));
+        }
+    }
+
+    Ok(())
+}
+
+async fn import_predictions_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    user_id: Uuid,
+    predictions: &[PredictionInput],
+    min_score: f64,
+) -> Result<PredictionImportResult, sqlx::Error> {
+    let mut result = PredictionImportResult {
+        tasks_updated: 0,
+        predictions_created: 0,
+        predictions_skipped: 0,
+        errors: Vec::new(),
+    };
+
+    // Tasks are ordered the same way as the COCO exporter so exported image IDs resolve
+    let tasks = sqlx::query_as::<_, TaskRow>(
+        "SELECT id, name FROM tasks WHERE project_id = $1 ORDER BY created_at"
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    let categories = sqlx::query_as::<_, CategoryRow>(
+        "SELECT id, name, coco_id FROM image_annotation_categories WHERE project_id = $1"
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut predictions_by_task: HashMap<Uuid, Vec<(&PredictionInput, Uuid)>> = HashMap::new();
+
+    for (index, prediction) in predictions.iter().enumerate() {
+        if prediction.score < min_score {
+            result.predictions_skipped += 1;
+            continue;
+        }
+
+        let task_id = if let Some(task_id) = prediction.task_id {
+            tasks.iter().find(|t| t.id == task_id).map(|t| t.id)
+        } else if let Some(file_name) = &prediction.file_name {
+            tasks.iter().find(|t| &t.name == file_name).map(|t| t.id)
+        } else {
+            prediction.image_id
+                .and_then(|image_id| usize::try_from(image_id - 1).ok())
+                .and_then(|position| tasks.get(position))
+                .map(|t| t.id)
+        };
+
+        let category_id = if let Some(coco_id) = prediction.category_id {
+            categories.iter().find(|c| c.coco_id == Some(coco_id)).map(|c| c.id)
+        } else {
+            categories.iter().find(|c| Some(&c.name) == prediction.category_name.as_ref()).map(|c| c.id)
+        };
+
+        match (task_id, category_id) {
+            (Some(task_id), Some(category_id)) => {
+                predictions_by_task.entry(task_id).or_default().push((prediction, category_id));
+            }
+            (None, _) => {
+                result.predictions_skipped += 1;
+                result.errors.push(format!("Prediction {} references an unknown image", index));
+            }
+            (_, None) => {
+                result.predictions_skipped += 1;
+                result.errors.push(format!("Prediction {} references an unknown category", index));
+            }
+        }
+    }
+
+    // Start transaction
+    let mut tx = pool.begin().await?;
+
+    for (task_id, task_predictions) in predictions_by_task {
+        let created = import_task_predictions(&mut tx, task_id, &task_predictions, user_id).await?;
+        result.tasks_updated += 1;
+        result.predictions_created += created;
+    }
+
+    // Commit transaction
+    tx.commit().await?;
+
+    Ok(result)
+}
+
+/// Creates a new annotation version for the task that keeps the boxes accepted so far
+/// and replaces any earlier unreviewed suggestions with the new predictions.
+pub(crate) async fn import_task_predictions(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    task_id: Uuid,
+    predictions: &[(&PredictionInput, Uuid)],
+    user_id: Uuid,
+) -> Result<usize, sqlx::Error> {
+    let existing_boxes = sqlx::query_as::<_, ExistingBoxRow>(
+        r#"
+        SELECT ia.category_id, ia.bbox, ia.area, ia.iscrowd, ia.image_metadata
+        FROM image_annotations ia
+        WHERE ia.annotation_id = (SELECT id FROM annotations WHERE task_id = $1 ORDER BY created_at DESC LIMIT 1)
+          AND NOT ia.is_prediction
+        ORDER BY ia.created_at
+        "#
+    )
+    .bind(task_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let annotation_id = Uuid::new_v4();
+    let now = chrono::Utc::now();
+
+    sqlx::query(
+        r#"
+        INSERT INTO annotations (id, task_id, metadata, annotated_by, annotated_at, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#
+    )
+    .bind(annotation_id)
+    .bind(task_id)
+    .bind(serde_json::json!({"imported_predictions": predictions.len()}))
+    .bind(user_id)
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .execute(&mut **tx)
+    .await?;
+
+    // Carry over the boxes annotators already accepted
+    for existing in &existing_boxes {
+        sqlx::query(
+            r#"
+            INSERT INTO image_annotations (id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#
+        )
+        .bind(Uuid::new_v4())
+        .bind(annotation_id)
+        .bind(existing.category_id)
+        .bind(&existing.bbox)
+        .bind(existing.area)
+        .bind(existing.iscrowd.unwrap_or(false))
+        .bind(existing.image_metadata.clone().unwrap_or_else(|| serde_json::json!({})))
+        .bind(now)
+        .bind(now)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    for (prediction, category_id) in predictions {
+        sqlx::query(
+            r#"
+            INSERT INTO image_annotations (id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, is_prediction, confidence, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, FALSE, $6, TRUE, $7, $8, $9)
+            "#
+        )
+        .bind(Uuid::new_v4())
+        .bind(annotation_id)
+        .bind(category_id)
+        .bind(&prediction.bbox)
+        .bind(prediction.bbox[2] * prediction.bbox[3])
+        .bind(serde_json::json!({}))
+        .bind(prediction.score)
+        .bind(now)
+        .bind(now)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(predictions.len())
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, AuthStorage, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    fn multipart_body(json: &serde_json::Value, boundary: &str) -> String {
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"predictions.json\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary, json, boundary
+        )
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_import_predictions_keeps_accepted_boxes() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+        let auth_storage = AuthStorage::new(pool.clone());
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, Some("human"), Some("#FF0000"), Some(1)).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "image1.jpg", Some("https://example.com/image1.jpg")).await.unwrap();
+
+        let bbox = crate::annotations::BoundingBox { category_id: category.id, bbox: vec![1.0, 2.0, 3.0, 4.0], area: None, iscrowd: Some(false), is_prediction: None, confidence: None, attributes: None, rotation: None, frame_index: None, track_id: None, is_interpolated: None, mask: None };
+        crate::annotations::create_annotation_in_db(&pool, task.id, &[bbox], &serde_json::json!({}), user.id).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .app_data(web::Data::new(auth_storage))
+                .route("/projects/{project_id}/import/predictions", web::post().to(import_predictions))
+        ).await;
+
+        let predictions = serde_json::json!([
+            {"image_id": 1, "category_id": 1, "bbox": [10.0, 20.0, 30.0, 40.0], "score": 0.9},
+            {"file_name": "image1.jpg", "category_name": "person", "bbox": [50.0, 60.0, 10.0, 10.0], "score": 0.2}
+        ]);
+        let boundary = "----formdata-test-boundary";
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/import/predictions?min_score=0.5", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+            .set_payload(multipart_body(&predictions, boundary))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let result: PredictionImportResult = test::read_body_json(resp).await;
+        assert_eq!(result.tasks_updated, 1);
+        assert_eq!(result.predictions_created, 1);
+        assert_eq!(result.predictions_skipped, 1);
+        assert!(result.errors.is_empty());
+
+        let rows = sqlx::query_as::<_, (bool, Option<f64>)>(
+            r#"
+            SELECT ia.is_prediction, ia.confidence FROM image_annotations ia
+            WHERE ia.annotation_id = (SELECT id FROM annotations WHERE task_id = $1 ORDER BY created_at DESC LIMIT 1)
+            ORDER BY ia.is_prediction
+            "#
+        )
+        .bind(task.id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(rows, vec![(false, None), (true, Some(0.9))]);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_import_predictions_invalid_score() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+        let auth_storage = AuthStorage::new(pool.clone());
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .app_data(web::Data::new(auth_storage))
+                .route("/projects/{project_id}/import/predictions", web::post().to(import_predictions))
+        ).await;
+
+        let predictions = serde_json::json!([
+            {"image_id": 1, "category_id": 1, "bbox": [10.0, 20.0, 30.0, 40.0], "score": 1.5}
+        ]);
+        let boundary = "----formdata-test-boundary";
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/import/predictions", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+            .set_payload(multipart_body(&predictions, boundary))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+}
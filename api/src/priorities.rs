@@ -0,0 +1,369 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use actix_multipart::Multipart;
+use futures_util::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::auth::{JwtManager, Claims};
+use crate::openapi::FileUpload;
+
+/// Score for one task, matched by `task_id` or by task name (`file_name`).
+/// Typically produced by an active-learning script, e.g. model uncertainty.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PriorityInput {
+    pub task_id: Option<Uuid>,
+    pub file_name: Option<String>,
+    pub score: f64,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PriorityUploadQuery {
+    /// Clear existing scores of the project before applying the file
+    pub reset: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PriorityUploadResult {
+    pub tasks_updated: usize,
+    pub not_found: Vec<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/tasks/priorities",
+    tag = "tasks",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        PriorityUploadQuery,
+    ),
+    request_body(content = FileUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, body = PriorityUploadResult),
+        (status = 400, description = "Invalid priority file", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn upload_task_priorities(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<PriorityUploadQuery>,
+    mut payload: Multipart,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let data = match extract_file_from_multipart(&mut payload).await {
+        Ok(data) => data,
+        Err(err) => return HttpResponse::BadRequest().json(format!("Failed to read file: {}", err)),
+    };
+
+    let priorities = match parse_priorities(&data) {
+        Ok(priorities) => priorities,
+        Err(err) => return HttpResponse::BadRequest().json(format!("Invalid priority file: {}", err)),
+    };
+
+    if priorities.is_empty() {
+        return HttpResponse::BadRequest().json("No scores found in priority file");
+    }
+
+    match apply_priorities_in_db(&pool, project_id, &priorities, query.reset.unwrap_or(false)).await {
+        Ok(result) => HttpResponse::Ok().json(result),
+        Err(err) => {
+            eprintln!("Priority upload error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to update task priorities")
+        }
+    }
+}
+
+async fn extract_file_from_multipart(payload: &mut Multipart) -> Result<String, Box<dyn std::error::Error>> {
+    while let Some(mut field) = payload.try_next().await? {
+        if field.name() == Some("file") {
+            let mut data = bytes::BytesMut::new();
+            while let Some(chunk) = field.try_next().await? {
+                data.extend_from_slice(&chunk);
+            }
+
+            return Ok(String::from_utf8(data.to_vec())?);
+        }
+    }
+
+    Err("No file field found in multipart data".into())
+}
+
+/// Parses either a JSON array of `{task_id | file_name, score}` objects or a CSV file
+/// whose header has a `score` column and a `task_id` or `file_name` column.
+pub fn parse_priorities(data: &str) -> Result<Vec<PriorityInput>, String> {
+    let trimmed = data.trim_start_matches('\u{feff}').trim();
+
+    let priorities: Vec<PriorityInput> = if trimmed.starts_with('[') {
+        serde_json::from_str(trimmed).map_err(|e| e.to_string())?
+    } else {
+        parse_priorities_csv(trimmed)?
+    };
+
+    for (index, priority) in priorities.iter().enumerate() {
+        if priority.task_id.is_none() && priority.file_name.is_none() {
+            return Err(format!("Entry {} has neither task_id nor file_name", index + 1));
+        }
+        if !priority.score.is_finite() {
+            return Err(format!("Entry {} has an invalid score", index + 1));
+        }
+    }
+
+    Ok(priorities)
+}
+
+fn parse_priorities_csv(data: &str) -> Result<Vec<PriorityInput>, String> {
+    let mut lines = data.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<String> = lines
+        .next()
+        .ok_or("Empty CSV file")?
+        .split(',')
+        .map(|column| column.trim().to_lowercase())
+        .collect();
+
+    let column = |name: &str| header.iter().position(|column| column == name);
+    let score_column = column("score").ok_or("CSV header must contain a score column")?;
+    let task_id_column = column("task_id");
+    let file_name_column = column("file_name");
+    if task_id_column.is_none() && file_name_column.is_none() {
+        return Err("CSV header must contain a task_id or file_name column".to_string());
+    }
+
+    let mut priorities = Vec::new();
+    for (line_number, line) in lines.enumerate() {
+        let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+        let field = |index: Option<usize>| index.and_then(|i| fields.get(i)).filter(|value| !value.is_empty());
+
+        let score = field(Some(score_column))
+            .and_then(|value| value.parse::<f64>().ok())
+            .ok_or_else(|| format!("Invalid score on line {}", line_number + 2))?;
+        let task_id = match field(task_id_column) {
+            Some(value) => Some(Uuid::parse_str(value).map_err(|_| format!("Invalid task_id on line {}", line_number + 2))?),
+            None => None,
+        };
+
+        priorities.push(PriorityInput {
+            task_id,
+            file_name: field(file_name_column).map(|value| value.to_string()),
+            score,
+        });
+    }
+
+    Ok(priorities)
+}
+
+async fn apply_priorities_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    priorities: &[PriorityInput],
+    reset: bool,
+) -> Result<PriorityUploadResult, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    if reset {
+        sqlx::query("UPDATE tasks SET priority = NULL WHERE project_id = $1")
+            .bind(project_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let mut tasks_updated = 0;
+    let mut not_found = Vec::new();
+
+    for priority in priorities {
+        let result = sqlx::query(
+            r#"
+            UPDATE tasks SET priority = $1, updated_at = NOW()
+            WHERE project_id = $2 AND (id = $3 OR ($3 IS NULL AND name = $4))
+            "#
+        )
+        .bind(priority.score)
+        .bind(project_id)
+        .bind(priority.task_id)
+        .bind(&priority.file_name)
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            not_found.push(match (&priority.task_id, &priority.file_name) {
+                (Some(task_id), _) => task_id.to_string(),
+                (None, Some(file_name)) => file_name.clone(),
+                (None, None) => String::new(),
+            });
+        } else {
+            tasks_updated += result.rows_affected() as usize;
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(PriorityUploadResult { tasks_updated, not_found })
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_upload_priorities_orders_tasks() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        crate::tasks::create_task_in_db(&pool, project.id, "a.jpg", None).await.unwrap();
+        crate::tasks::create_task_in_db(&pool, project.id, "b.jpg", None).await.unwrap();
+        crate::tasks::create_task_in_db(&pool, project.id, "c.jpg", None).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/tasks/priorities", web::post().to(upload_task_priorities))
+                .route("/projects/{project_id}/tasks", web::get().to(crate::tasks::list_tasks))
+        ).await;
+
+        let csv = "file_name,score\nb.jpg,0.9\nc.jpg,0.4\nmissing.jpg,0.1\n";
+        let boundary = "----formdata-test-boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"scores.csv\"\r\nContent-Type: text/csv\r\n\r\n{}\r\n--{}--\r\n",
+            boundary, csv, boundary
+        );
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/tasks/priorities", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+            .set_payload(body)
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let result: PriorityUploadResult = test::read_body_json(resp).await;
+        assert_eq!(result.tasks_updated, 2);
+        assert_eq!(result.not_found, vec!["missing.jpg".to_string()]);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/tasks?order=priority", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let names: Vec<&str> = body["tasks"].as_array().unwrap().iter().map(|t| t["name"].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["b.jpg", "c.jpg", "a.jpg"]);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/tasks?next_unannotated=true&order=priority", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["tasks"][0]["name"], "b.jpg");
+    }
+
+    #[actix_web::test]
+    async fn test_parse_priorities_json_and_csv() {
+        let task_id = Uuid::new_v4();
+        let json = format!(r#"[{{"task_id": "{}", "score": 0.5}}, {{"file_name": "x.jpg", "score": 2}}]"#, task_id);
+        let parsed = parse_priorities(&json).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].task_id, Some(task_id));
+        assert_eq!(parsed[1].file_name.as_deref(), Some("x.jpg"));
+        assert_eq!(parsed[1].score, 2.0);
+
+        let csv = format!("task_id,score\n{},0.75\n", task_id);
+        let parsed = parse_priorities(&csv).unwrap();
+        assert_eq!(parsed, vec![PriorityInput { task_id: Some(task_id), file_name: None, score: 0.75 }]);
+
+        assert!(parse_priorities("name,value\na,1\n").is_err());
+        assert!(parse_priorities("file_name,score\na.jpg,high\n").is_err());
+        assert!(parse_priorities(r#"[{"score": 1.0}]"#).is_err());
+    }
+}
@@ -0,0 +1,633 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+use crate::auth::{JwtManager, Claims};
+use crate::projects::Project;
+
+/// Storage config keys holding secrets, per storage type
+const CREDENTIAL_KEYS: [&str; 4] = ["access_key", "secret_key", "account_key", "service_account_key"];
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct CloneProjectRequest {
+    /// Defaults to "<source name> (copy)"
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub include_tasks: Option<bool>,
+    /// Requires `include_tasks`
+    pub include_annotations: Option<bool>,
+    pub include_storage_config: Option<bool>,
+    /// Copy storage secrets as well, only allowed for project owners and admins
+    pub include_credentials: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CloneOptions {
+    tasks: bool,
+    annotations: bool,
+    storage_config: bool,
+    credentials: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CloneProjectResponse {
+    pub project: Project,
+    pub categories_copied: u64,
+    pub tasks_copied: u64,
+    pub annotations_copied: u64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{id}/clone",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project to clone")),
+    request_body = CloneProjectRequest,
+    responses(
+        (status = 201, body = CloneProjectResponse),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn clone_project(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<CloneProjectRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let payload = payload.into_inner();
+    let options = CloneOptions {
+        tasks: payload.include_tasks.unwrap_or(false),
+        annotations: payload.include_annotations.unwrap_or(false),
+        storage_config: payload.include_storage_config.unwrap_or(true),
+        credentials: payload.include_credentials.unwrap_or(false),
+    };
+
+    if options.annotations && !options.tasks {
+        return HttpResponse::BadRequest().json("include_annotations requires include_tasks");
+    }
+
+    if let Some(name) = &payload.name {
+        if name.trim().is_empty() {
+            return HttpResponse::BadRequest().json("Project name cannot be empty");
+        }
+        if name.len() > 255 {
+            return HttpResponse::BadRequest().json("Project name too long (max 255 characters)");
+        }
+    }
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    // Storage secrets stay with those who manage the storage
+    if options.storage_config && options.credentials {
+        match crate::projects::user_can_manage_storage(&pool, project_id, user_id).await {
+            Ok(true) => {}
+            Ok(false) => return HttpResponse::NotFound().json("Project not found or permission denied"),
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to check permissions"),
+        }
+    }
+
+    match clone_project_in_db(&pool, project_id, user_id, payload.name.as_deref(), payload.description.as_deref(), options).await {
+        Ok(response) => HttpResponse::Created().json(response),
+        Err(err) => {
+            eprintln!("Project clone error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to clone project")
+        }
+    }
+}
+
+/// Blanks the secrets of a storage config so the copy keeps bucket and paths but
+/// needs its own credentials before it can be used.
+pub fn strip_storage_credentials(storage_config: &serde_json::Value) -> serde_json::Value {
+    let mut stripped = storage_config.clone();
+    if let Some(object) = stripped.as_object_mut() {
+        for key in CREDENTIAL_KEYS {
+            if let Some(value) = object.get_mut(key) {
+                *value = serde_json::Value::String(String::new());
+            }
+        }
+    }
+    stripped
+}
+
+async fn clone_project_in_db(
+    pool: &Pool<Postgres>,
+    source_id: Uuid,
+    owner_id: Uuid,
+    name: Option<&str>,
+    description: Option<&str>,
+    options: CloneOptions,
+) -> Result<CloneProjectResponse, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let source = sqlx::query_as::<_, Project>(
+        "SELECT id, name, description, storage_config, owner_id, task_type, created_at, updated_at FROM projects WHERE id = $1"
+    )
+    .bind(source_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let storage_config = match (&source.storage_config, options.storage_config) {
+        (Some(storage_config), true) if options.credentials => Some(storage_config.clone()),
+        (Some(storage_config), true) => Some(strip_storage_credentials(storage_config)),
+        _ => None,
+    };
+
+    let project_id = Uuid::new_v4();
+    let project = sqlx::query_as::<_, Project>(
+        r#"
+        INSERT INTO projects (id, name, description, storage_config, owner_id, annotation_type, task_type, strict_bounds, labeling_rules, deadline_settings, created_at, updated_at)
+        SELECT $1, $2, $3, $4, $5, annotation_type, task_type, strict_bounds, labeling_rules, deadline_settings, NOW(), NOW() FROM projects WHERE id = $6
+        RETURNING id, name, description, storage_config, owner_id, task_type, strict_bounds, created_at, updated_at
+        "#
+    )
+    .bind(project_id)
+    .bind(name.map(String::from).unwrap_or_else(|| format!("{} (copy)", source.name)))
+    .bind(description.or(source.description.as_deref()))
+    .bind(&storage_config)
+    .bind(owner_id)
+    .bind(source_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // Add owner as project member with 'owner' role
+    sqlx::query(
+        "INSERT INTO project_members (id, project_id, user_id, role, joined_at) VALUES ($1, $2, $3, 'owner', NOW())"
+    )
+    .bind(Uuid::new_v4())
+    .bind(project_id)
+    .bind(owner_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let categories_copied = sqlx::query(
+        r#"
+        INSERT INTO image_annotation_categories (id, project_id, name, description, supercategory, color, coco_id, attribute_schema, hotkey, image_metadata, created_at, updated_at)
+        SELECT gen_random_uuid(), $1, name, description, supercategory, color, coco_id, attribute_schema, hotkey, image_metadata, NOW(), NOW()
+        FROM image_annotation_categories WHERE project_id = $2
+        "#
+    )
+    .bind(project_id)
+    .bind(source_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    // Re-link the copied hierarchy, parents are matched by name
+    sqlx::query(
+        r#"
+        UPDATE image_annotation_categories nc
+        SET parent_id = np.id
+        FROM image_annotation_categories oc
+        INNER JOIN image_annotation_categories op ON oc.parent_id = op.id
+        INNER JOIN image_annotation_categories np ON np.project_id = $1 AND np.name = op.name
+        WHERE nc.project_id = $1 AND oc.project_id = $2 AND oc.name = nc.name
+        "#
+    )
+    .bind(project_id)
+    .bind(source_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // The labeling rules name categories by id, point them at the copies
+    sqlx::query(
+        r#"
+        UPDATE projects
+        SET labeling_rules = jsonb_set(labeling_rules, '{required_category_ids}', COALESCE((
+            SELECT jsonb_agg(nc.id ORDER BY required.position)
+            FROM jsonb_array_elements_text(labeling_rules->'required_category_ids') WITH ORDINALITY AS required(id, position)
+            INNER JOIN image_annotation_categories oc ON oc.id = required.id::uuid AND oc.project_id = $2
+            INNER JOIN image_annotation_categories nc ON nc.project_id = $1 AND nc.name = oc.name
+        ), '[]'::jsonb))
+        WHERE id = $1 AND labeling_rules ? 'required_category_ids'
+        "#
+    )
+    .bind(project_id)
+    .bind(source_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO export_presets (id, project_id, name, format, options, is_default, created_by, created_at, updated_at)
+        SELECT gen_random_uuid(), $1, name, format, options, is_default, $3, NOW(), NOW()
+        FROM export_presets WHERE project_id = $2
+        "#
+    )
+    .bind(project_id)
+    .bind(source_id)
+    .bind(owner_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let mut tasks_copied = 0;
+    let mut annotations_copied = 0;
+    if options.tasks {
+        let task_map = copy_tasks(&mut tx, source_id, project_id, options.annotations).await?;
+        tasks_copied = task_map.0.len() as u64;
+
+        if options.annotations {
+            annotations_copied = copy_annotations(&mut tx, source_id, project_id, &task_map).await?;
+        }
+    }
+
+    tx.commit().await?;
+    crate::cache::invalidate_user_projects(&[owner_id]).await;
+
+    Ok(CloneProjectResponse {
+        project,
+        categories_copied,
+        tasks_copied,
+        annotations_copied,
+    })
+}
+
+/// Copies the tasks of a project and returns the `(old ids, new ids)` mapping.
+/// Without annotations the copies start over as pending tasks.
+async fn copy_tasks(
+    tx: &mut Transaction<'_, Postgres>,
+    source_id: Uuid,
+    project_id: Uuid,
+    keep_status: bool,
+) -> Result<(Vec<Uuid>, Vec<Uuid>), sqlx::Error> {
+    let old_ids = sqlx::query_scalar::<_, Uuid>("SELECT id FROM tasks WHERE project_id = $1")
+        .bind(source_id)
+        .fetch_all(&mut **tx)
+        .await?;
+    let new_ids: Vec<Uuid> = old_ids.iter().map(|_| Uuid::new_v4()).collect();
+
+    sqlx::query(
+        r#"
+        INSERT INTO tasks (id, project_id, name, resource_url, status, completed_at, priority, width, height, media_type, frame_count, frame_rate, source_etag, source_size, stale_at, due_at, created_at, updated_at)
+        SELECT m.new_id, $1, t.name, t.resource_url,
+               CASE WHEN $2 THEN t.status ELSE 'pending' END,
+               CASE WHEN $2 THEN t.completed_at ELSE NULL END,
+               t.priority, t.width, t.height, t.media_type, t.frame_count, t.frame_rate, t.source_etag, t.source_size, t.stale_at, t.due_at, t.created_at, NOW()
+        FROM tasks t
+        INNER JOIN UNNEST($3::UUID[], $4::UUID[]) AS m(old_id, new_id) ON t.id = m.old_id
+        "#
+    )
+    .bind(project_id)
+    .bind(keep_status)
+    .bind(&old_ids)
+    .bind(&new_ids)
+    .execute(&mut **tx)
+    .await?;
+
+    // Video copies point at the same extracted frames as the source
+    sqlx::query(
+        r#"
+        INSERT INTO task_frames (task_id, frame_index, resource_url, timestamp_ms)
+        SELECT m.new_id, f.frame_index, f.resource_url, f.timestamp_ms
+        FROM task_frames f
+        INNER JOIN UNNEST($1::UUID[], $2::UUID[]) AS m(old_id, new_id) ON f.task_id = m.old_id
+        "#
+    )
+    .bind(&old_ids)
+    .bind(&new_ids)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok((old_ids, new_ids))
+}
+
+/// Copies every annotation version of the copied tasks, remapping categories by name.
+async fn copy_annotations(
+    tx: &mut Transaction<'_, Postgres>,
+    source_id: Uuid,
+    project_id: Uuid,
+    task_map: &(Vec<Uuid>, Vec<Uuid>),
+) -> Result<u64, sqlx::Error> {
+    let old_ids = sqlx::query_scalar::<_, Uuid>(
+        "SELECT a.id FROM annotations a INNER JOIN tasks t ON a.task_id = t.id WHERE t.project_id = $1"
+    )
+    .bind(source_id)
+    .fetch_all(&mut **tx)
+    .await?;
+    let new_ids: Vec<Uuid> = old_ids.iter().map(|_| Uuid::new_v4()).collect();
+
+    // Keep the original timestamps so "latest annotation" stays the same in the copy
+    sqlx::query(
+        r#"
+        INSERT INTO annotations (id, task_id, metadata, annotated_by, annotated_at, created_at, updated_at)
+        SELECT am.new_id, tm.new_id, a.metadata, a.annotated_by, a.annotated_at, a.created_at, a.updated_at
+        FROM annotations a
+        INNER JOIN UNNEST($1::UUID[], $2::UUID[]) AS am(old_id, new_id) ON a.id = am.old_id
+        INNER JOIN UNNEST($3::UUID[], $4::UUID[]) AS tm(old_id, new_id) ON a.task_id = tm.old_id
+        "#
+    )
+    .bind(&old_ids)
+    .bind(&new_ids)
+    .bind(&task_map.0)
+    .bind(&task_map.1)
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO image_annotations (id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, is_prediction, confidence, attributes, rotation, frame_index, track_id, is_interpolated, mask, created_at, updated_at)
+        SELECT gen_random_uuid(), am.new_id, nc.id, ia.bbox, ia.area, ia.iscrowd, ia.image_metadata, ia.is_prediction, ia.confidence, ia.attributes, ia.rotation, ia.frame_index, ia.track_id, ia.is_interpolated, ia.mask, ia.created_at, ia.updated_at
+        FROM image_annotations ia
+        INNER JOIN UNNEST($1::UUID[], $2::UUID[]) AS am(old_id, new_id) ON ia.annotation_id = am.old_id
+        LEFT JOIN image_annotation_categories oc ON ia.category_id = oc.id
+        LEFT JOIN image_annotation_categories nc ON nc.project_id = $3 AND nc.name = oc.name
+        "#
+    )
+    .bind(&old_ids)
+    .bind(&new_ids)
+    .bind(project_id)
+    .execute(&mut **tx)
+    .await?;
+
+    // Classification labels need a category, so labels of categories that were not copied are dropped
+    sqlx::query(
+        r#"
+        INSERT INTO image_classifications (id, annotation_id, category_id, created_at)
+        SELECT gen_random_uuid(), am.new_id, nc.id, ic.created_at
+        FROM image_classifications ic
+        INNER JOIN UNNEST($1::UUID[], $2::UUID[]) AS am(old_id, new_id) ON ic.annotation_id = am.old_id
+        INNER JOIN image_annotation_categories oc ON ic.category_id = oc.id
+        INNER JOIN image_annotation_categories nc ON nc.project_id = $3 AND nc.name = oc.name
+        "#
+    )
+    .bind(&old_ids)
+    .bind(&new_ids)
+    .bind(project_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(old_ids.len() as u64)
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::annotations::{create_annotation_in_db, BoundingBox};
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_clone_project_with_tasks_and_annotations() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let storage_config = serde_json::json!({
+            "type": "s3",
+            "bucket": "images",
+            "region": "us-east-1",
+            "access_key": "AKIA",
+            "secret_key": "secret",
+        });
+        let project = crate::projects::create_project_in_db(&pool, "Source", Some("Description"), Some(&storage_config), user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, None, None, Some(1)).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "image1.jpg", None).await.unwrap();
+        crate::tasks::create_task_in_db(&pool, project.id, "image2.jpg", None).await.unwrap();
+        let bbox = BoundingBox { category_id: category.id, bbox: vec![1.0, 2.0, 3.0, 4.0], area: None, iscrowd: Some(false), is_prediction: None, confidence: None, attributes: None, rotation: None, frame_index: None, track_id: None, is_interpolated: None, mask: None };
+        create_annotation_in_db(&pool, task.id, &[bbox], &serde_json::json!({}), user.id).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{id}/clone", web::post().to(clone_project))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/clone", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(serde_json::json!({"include_tasks": true, "include_annotations": true}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+
+        let result: CloneProjectResponse = test::read_body_json(resp).await;
+        assert_eq!(result.project.name, "Source (copy)");
+        assert_ne!(result.project.id, project.id);
+        assert_eq!(result.categories_copied, 1);
+        assert_eq!(result.tasks_copied, 2);
+        assert_eq!(result.annotations_copied, 1);
+
+        // Credentials are not copied unless asked for
+        let copied_config = result.project.storage_config.unwrap();
+        assert_eq!(copied_config["bucket"], "images");
+        assert_eq!(copied_config["secret_key"], "");
+
+        // The copied box points at the copied category
+        let copied_category_id = sqlx::query_scalar::<_, Option<Uuid>>(
+            r#"
+            SELECT ia.category_id FROM image_annotations ia
+            INNER JOIN annotations a ON ia.annotation_id = a.id
+            INNER JOIN tasks t ON a.task_id = t.id
+            WHERE t.project_id = $1
+            "#
+        )
+        .bind(result.project.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .unwrap();
+        let copied_category_project = sqlx::query_scalar::<_, Uuid>("SELECT project_id FROM image_annotation_categories WHERE id = $1")
+            .bind(copied_category_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(copied_category_project, result.project.id);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_clone_project_copies_settings() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Source", None, None, user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, None, None, Some(1)).await.unwrap();
+        sqlx::query("UPDATE image_annotation_categories SET hotkey = 'Digit1' WHERE id = $1")
+            .bind(category.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"
+            UPDATE projects
+            SET strict_bounds = TRUE,
+                labeling_rules = jsonb_build_object('max_boxes_per_image', 5, 'required_category_ids', jsonb_build_array($2::uuid)),
+                deadline_settings = '{"default_due_days": 3}'
+            WHERE id = $1
+            "#
+        )
+        .bind(project.id)
+        .bind(category.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO export_presets (project_id, name, format, options, is_default) VALUES ($1, 'Train only', 'coco', '{\"splits\": [\"train\"]}', TRUE)")
+            .bind(project.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{id}/clone", web::post().to(clone_project))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/clone", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(serde_json::json!({}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let result: CloneProjectResponse = test::read_body_json(resp).await;
+        assert!(result.project.strict_bounds);
+
+        let (copied_category_id, hotkey) = sqlx::query_as::<_, (Uuid, Option<String>)>(
+            "SELECT id, hotkey FROM image_annotation_categories WHERE project_id = $1"
+        )
+        .bind(result.project.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(hotkey.as_deref(), Some("Digit1"));
+
+        let (labeling_rules, deadline_settings) = sqlx::query_as::<_, (serde_json::Value, serde_json::Value)>(
+            "SELECT labeling_rules, deadline_settings FROM projects WHERE id = $1"
+        )
+        .bind(result.project.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(labeling_rules["max_boxes_per_image"], 5);
+        assert_eq!(labeling_rules["required_category_ids"], serde_json::json!([copied_category_id]));
+        assert_eq!(deadline_settings["default_due_days"], 3);
+
+        let (preset_name, is_default, created_by) = sqlx::query_as::<_, (String, bool, Option<Uuid>)>(
+            "SELECT name, is_default, created_by FROM export_presets WHERE project_id = $1"
+        )
+        .bind(result.project.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(preset_name, "Train only");
+        assert!(is_default);
+        assert_eq!(created_by, Some(user.id));
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_clone_project_rejects_annotations_without_tasks() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+        let project = crate::projects::create_project_in_db(&pool, "Source", None, None, user.id).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{id}/clone", web::post().to(clone_project))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/clone", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(serde_json::json!({"include_annotations": true}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_strip_storage_credentials() {
+        let config = serde_json::json!({"type": "azure", "account_name": "acct", "account_key": "key", "container_name": "images"});
+        let stripped = strip_storage_credentials(&config);
+        assert_eq!(stripped["account_name"], "acct");
+        assert_eq!(stripped["account_key"], "");
+        assert_eq!(stripped["container_name"], "images");
+    }
+}
@@ -3,16 +3,33 @@ use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
 
 use crate::auth::{JwtManager, Claims};
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+/// Tasks are annotated with bounding boxes
+pub const TASK_TYPE_DETECTION: &str = "detection";
+/// Tasks are annotated with one or more whole-image category labels
+pub const TASK_TYPE_CLASSIFICATION: &str = "classification";
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
 pub struct Project {
     pub id: Uuid,
     pub name: String,
     pub description: Option<String>,
     pub owner_id: Uuid,
+    /// Holds the storage credentials, so only owners and admins of the project read it. Other
+    /// members get `null` and reach the files through presigned URLs.
     pub storage_config: Option<serde_json::Value>,
+    pub task_type: String,
+    /// Boxes leaving the image are rejected instead of clipped to it
+    #[sqlx(default)]
+    #[serde(default)]
+    pub strict_bounds: bool,
+    /// When the project was archived, archived projects are read-only
+    #[sqlx(default)]
+    #[serde(default)]
+    pub archived_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -26,35 +43,79 @@ pub struct ProjectMember {
     pub joined_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Member of a project with the user details shown when picking assignees
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct ProjectMemberWithUser {
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub role: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProjectMembersResponse {
+    pub members: Vec<ProjectMemberWithUser>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateProjectRequest {
     pub name: String,
     pub description: Option<String>,
     pub storage_config: Option<serde_json::Value>,
+    /// Project template whose categories are created with the project
+    pub template_id: Option<Uuid>,
+    /// `detection` (default) or `classification`, fixed once the project is created
+    pub task_type: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateProjectRequest {
     pub name: String,
     pub description: Option<String>,
     pub storage_config: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateStorageConfigRequest {
     pub storage_config: serde_json::Value,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateStrictBoundsRequest {
+    pub strict_bounds: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ProjectResponse {
     pub project: Project,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListProjectsQuery {
+    /// Also list archived projects, which are left out by default
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ProjectsListResponse {
     pub projects: Vec<Project>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/projects",
+    tag = "projects",
+    request_body = CreateProjectRequest,
+    responses(
+        (status = 201, body = ProjectResponse),
+        (status = 400, description = "Invalid name, storage configuration or task type", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Template not found", body = String),
+        (status = 409, description = "Project name already exists for this user", body = String),
+    ),
+)]
 pub async fn create_project(
     req: HttpRequest,
     payload: web::Json<CreateProjectRequest>,
@@ -88,8 +149,22 @@ pub async fn create_project(
         }
     }
 
+    let task_type = payload.task_type.as_deref().unwrap_or(TASK_TYPE_DETECTION);
+    if task_type != TASK_TYPE_DETECTION && task_type != TASK_TYPE_CLASSIFICATION {
+        return HttpResponse::BadRequest().json("Invalid task type (expected 'detection' or 'classification')");
+    }
+
+    // Templates must be built-in or owned by the user
+    if let Some(template_id) = payload.template_id {
+        match crate::templates::user_can_use_template(&pool, template_id, user_id).await {
+            Ok(true) => {}
+            Ok(false) => return HttpResponse::NotFound().json("Template not found"),
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to check template"),
+        }
+    }
+
     // Create project
-    match create_project_in_db(&pool, &payload.name, payload.description.as_deref(), payload.storage_config.as_ref(), user_id).await {
+    match create_project_with_template_in_db(&pool, &payload.name, payload.description.as_deref(), payload.storage_config.as_ref(), user_id, task_type, payload.template_id).await {
         Ok(project) => HttpResponse::Created().json(ProjectResponse { project }),
         Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
             HttpResponse::Conflict().json("Project name already exists for this user")
@@ -98,8 +173,19 @@ pub async fn create_project(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/projects",
+    tag = "projects",
+    params(ListProjectsQuery),
+    responses(
+        (status = 200, description = "Projects the user owns or is a member of, with the storage configuration of those the user owns or administers", body = ProjectsListResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+    ),
+)]
 pub async fn list_projects(
     req: HttpRequest,
+    query: web::Query<ListProjectsQuery>,
     pool: web::Data<Pool<Postgres>>,
     config: web::Data<crate::auth::OAuthConfig>,
 ) -> impl Responder {
@@ -115,12 +201,28 @@ pub async fn list_projects(
     };
 
     // Get user's projects (owned + member of)
-    match get_user_projects(&pool, user_id).await {
-        Ok(projects) => HttpResponse::Ok().json(ProjectsListResponse { projects }),
+    match crate::cache::user_projects(user_id, get_user_projects(&pool, user_id)).await {
+        Ok(mut projects) => {
+            if !query.include_archived {
+                projects.retain(|project: &Project| project.archived_at.is_none());
+            }
+            HttpResponse::Ok().json(ProjectsListResponse { projects })
+        }
         Err(_) => HttpResponse::InternalServerError().json("Failed to fetch projects"),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/projects/{id}",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 200, description = "The project, its storage configuration only for owners and admins", body = ProjectResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
 pub async fn get_project(
     req: HttpRequest,
     path: web::Path<String>,
@@ -151,6 +253,66 @@ pub async fn get_project(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/projects/{id}/members",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 200, body = ProjectMembersResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn list_project_members(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Check if user has access to this project
+    match get_project_by_id(&pool, project_id, user_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    }
+
+    match get_project_members(&pool, project_id).await {
+        Ok(members) => HttpResponse::Ok().json(ProjectMembersResponse { members }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch project members"),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/projects/{id}",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    request_body = UpdateProjectRequest,
+    responses(
+        (status = 200, body = ProjectResponse),
+        (status = 400, description = "Invalid name or storage configuration", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or not owned by the user", body = String),
+        (status = 409, description = "Project name already exists for this user", body = String),
+    ),
+)]
 pub async fn update_project(
     req: HttpRequest,
     path: web::Path<String>,
@@ -192,7 +354,10 @@ pub async fn update_project(
 
     // Update project
     match update_project_in_db(&pool, project_id, &payload.name, payload.description.as_deref(), payload.storage_config.as_ref(), user_id).await {
-        Ok(Some(project)) => HttpResponse::Ok().json(ProjectResponse { project }),
+        Ok(Some(project)) => {
+            invalidate_member_project_lists(&pool, project_id).await;
+            HttpResponse::Ok().json(ProjectResponse { project })
+        }
         Ok(None) => HttpResponse::NotFound().json("Project not found or access denied"),
         Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
             HttpResponse::Conflict().json("Project name already exists for this user")
@@ -201,6 +366,17 @@ pub async fn update_project(
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/projects/{id}",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 204, description = "Project deleted"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or not owned by the user", body = String),
+    ),
+)]
 pub async fn delete_project(
     req: HttpRequest,
     path: web::Path<String>,
@@ -223,14 +399,34 @@ pub async fn delete_project(
         Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
     };
 
+    // Members whose cached project lists go stale, read before the memberships are deleted
+    let member_ids = get_project_member_ids(&pool, project_id).await.unwrap_or_default();
+
     // Delete project (only owner can delete)
     match delete_project_from_db(&pool, project_id, user_id).await {
-        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(true) => {
+            crate::cache::invalidate_user_projects(&member_ids).await;
+            crate::cache::invalidate_project(project_id).await;
+            HttpResponse::NoContent().finish()
+        }
         Ok(false) => HttpResponse::NotFound().json("Project not found or access denied"),
         Err(_) => HttpResponse::InternalServerError().json("Failed to delete project"),
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/projects/{id}/storage-config",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    request_body = UpdateStorageConfigRequest,
+    responses(
+        (status = 200, body = ProjectResponse),
+        (status = 400, description = "Invalid storage configuration", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found, or the user is not an owner or admin of it", body = String),
+    ),
+)]
 pub async fn update_storage_config(
     req: HttpRequest,
     path: web::Path<String>,
@@ -261,18 +457,81 @@ pub async fn update_storage_config(
 
     // Update storage config
     match update_storage_config_in_db(&pool, project_id, &payload.storage_config, user_id).await {
-        Ok(Some(project)) => HttpResponse::Ok().json(ProjectResponse { project }),
+        Ok(Some(project)) => {
+            invalidate_member_project_lists(&pool, project_id).await;
+            // URLs signed for the old storage no longer work
+            crate::cache::invalidate_presigned_urls(project_id).await;
+            HttpResponse::Ok().json(ProjectResponse { project })
+        }
         Ok(None) => HttpResponse::NotFound().json("Project not found or access denied"),
         Err(_) => HttpResponse::InternalServerError().json("Failed to update storage configuration"),
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/projects/{id}/strict-bounds",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    request_body = UpdateStrictBoundsRequest,
+    responses(
+        (status = 200, body = ProjectResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found, or the user is not an owner or admin of it", body = String),
+    ),
+)]
+pub async fn update_strict_bounds(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<UpdateStrictBoundsRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    match update_strict_bounds_in_db(&pool, project_id, payload.strict_bounds, user_id).await {
+        Ok(Some(project)) => {
+            invalidate_member_project_lists(&pool, project_id).await;
+            HttpResponse::Ok().json(ProjectResponse { project })
+        }
+        Ok(None) => HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to update the bounds check"),
+    }
+}
+
 pub async fn create_project_in_db(
     pool: &Pool<Postgres>,
     name: &str,
     description: Option<&str>,
     storage_config: Option<&serde_json::Value>,
     owner_id: Uuid,
+) -> Result<Project, sqlx::Error> {
+    create_project_with_template_in_db(pool, name, description, storage_config, owner_id, TASK_TYPE_DETECTION, None).await
+}
+
+/// Creates a project and, when a template is given, its categories in the same transaction.
+pub async fn create_project_with_template_in_db(
+    pool: &Pool<Postgres>,
+    name: &str,
+    description: Option<&str>,
+    storage_config: Option<&serde_json::Value>,
+    owner_id: Uuid,
+    task_type: &str,
+    template_id: Option<Uuid>,
 ) -> Result<Project, sqlx::Error> {
     let project_id = Uuid::new_v4();
     let now = Utc::now();
@@ -282,13 +541,14 @@ pub async fn create_project_in_db(
 
     // Insert project
     sqlx::query(
-        "INSERT INTO projects (id, name, description, storage_config, owner_id, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7)"
+        "INSERT INTO projects (id, name, description, storage_config, owner_id, task_type, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
     )
     .bind(project_id)
     .bind(name)
     .bind(description)
     .bind(storage_config)
     .bind(owner_id)
+    .bind(task_type)
     .bind(now)
     .bind(now)
     .execute(&mut *tx)
@@ -307,8 +567,14 @@ pub async fn create_project_in_db(
     .execute(&mut *tx)
     .await?;
 
+    // Create the template's categories
+    if let Some(template_id) = template_id {
+        crate::templates::apply_template(&mut tx, template_id, project_id).await?;
+    }
+
     // Commit transaction
     tx.commit().await?;
+    crate::cache::invalidate_user_projects(&[owner_id]).await;
 
     Ok(Project {
         id: project_id,
@@ -316,15 +582,27 @@ pub async fn create_project_in_db(
         description: description.map(String::from),
         owner_id,
         storage_config: storage_config.cloned(),
+        task_type: task_type.to_string(),
+        strict_bounds: false,
+        archived_at: None,
         created_at: now,
         updated_at: now,
     })
 }
 
+pub async fn get_project_task_type(pool: &Pool<Postgres>, project_id: Uuid) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>("SELECT task_type FROM projects WHERE id = $1")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+}
+
 async fn get_user_projects(pool: &Pool<Postgres>, user_id: Uuid) -> Result<Vec<Project>, sqlx::Error> {
     sqlx::query_as::<_, Project>(
         r#"
-        SELECT DISTINCT p.id, p.name, p.description, p.storage_config, p.owner_id, p.created_at, p.updated_at
+        SELECT DISTINCT p.id, p.name, p.description,
+               CASE WHEN pm.role IN ('owner', 'admin') OR p.owner_id = $1 THEN p.storage_config END AS storage_config,
+               p.owner_id, p.task_type, p.strict_bounds, p.archived_at, p.created_at, p.updated_at
         FROM projects p
         INNER JOIN project_members pm ON p.id = pm.project_id
         WHERE pm.user_id = $1
@@ -343,7 +621,9 @@ async fn get_project_by_id(
 ) -> Result<Option<Project>, sqlx::Error> {
     sqlx::query_as::<_, Project>(
         r#"
-        SELECT DISTINCT p.id, p.name, p.description, p.storage_config, p.owner_id, p.created_at, p.updated_at
+        SELECT DISTINCT p.id, p.name, p.description,
+               CASE WHEN pm.role IN ('owner', 'admin') OR p.owner_id = $2 THEN p.storage_config END AS storage_config,
+               p.owner_id, p.task_type, p.strict_bounds, p.archived_at, p.created_at, p.updated_at
         FROM projects p
         INNER JOIN project_members pm ON p.id = pm.project_id
         WHERE p.id = $1 AND pm.user_id = $2
@@ -355,6 +635,36 @@ async fn get_project_by_id(
     .await
 }
 
+async fn get_project_members(pool: &Pool<Postgres>, project_id: Uuid) -> Result<Vec<ProjectMemberWithUser>, sqlx::Error> {
+    sqlx::query_as::<_, ProjectMemberWithUser>(
+        r#"
+        SELECT pm.user_id, u.name, u.email, pm.role
+        FROM project_members pm
+        INNER JOIN users u ON u.id = pm.user_id
+        WHERE pm.project_id = $1
+        ORDER BY u.name
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_project_member_ids(pool: &Pool<Postgres>, project_id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+    sqlx::query_scalar::<_, Uuid>("SELECT user_id FROM project_members WHERE project_id = $1")
+        .bind(project_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Every member lists the project, so all their cached lists are stale after it changes
+pub(crate) async fn invalidate_member_project_lists(pool: &Pool<Postgres>, project_id: Uuid) {
+    match get_project_member_ids(pool, project_id).await {
+        Ok(member_ids) => crate::cache::invalidate_user_projects(&member_ids).await,
+        Err(e) => eprintln!("Failed to fetch members to invalidate cached project lists: {}", e),
+    }
+}
+
 async fn update_project_in_db(
     pool: &Pool<Postgres>,
     project_id: Uuid,
@@ -390,7 +700,7 @@ async fn update_project_in_db(
         UPDATE projects 
         SET name = $1, description = $2, storage_config = $3, updated_at = $4
         WHERE id = $5
-        RETURNING id, name, description, storage_config, owner_id, created_at, updated_at
+        RETURNING id, name, description, storage_config, owner_id, task_type, strict_bounds, archived_at, created_at, updated_at
         "#
     )
     .bind(name)
@@ -451,13 +761,41 @@ async fn update_storage_config_in_db(
 ) -> Result<Option<Project>, sqlx::Error> {
     let now = Utc::now();
 
-    // Check if user has owner role for this project
+    if !user_can_manage_storage(pool, project_id, user_id).await? {
+        return Ok(None);
+    }
+
+    // Update only the storage config
+    let updated_project = sqlx::query_as::<_, Project>(
+        r#"
+        UPDATE projects 
+        SET storage_config = $1, updated_at = $2
+        WHERE id = $3
+        RETURNING id, name, description, storage_config, owner_id, task_type, strict_bounds, archived_at, created_at, updated_at
+        "#
+    )
+    .bind(storage_config)
+    .bind(now)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(updated_project)
+}
+
+async fn update_strict_bounds_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    strict_bounds: bool,
+    user_id: Uuid,
+) -> Result<Option<Project>, sqlx::Error> {
+    // Owners and admins decide how boxes are checked
     let has_permission = sqlx::query_scalar::<_, bool>(
         r#"
         SELECT EXISTS(
             SELECT 1 FROM project_members pm
             INNER JOIN projects p ON pm.project_id = p.id
-            WHERE p.id = $1 AND pm.user_id = $2 AND (pm.role = 'owner' OR p.owner_id = $2)
+            WHERE p.id = $1 AND pm.user_id = $2 AND (pm.role IN ('owner', 'admin') OR p.owner_id = $2)
         )
         "#
     )
@@ -470,22 +808,37 @@ async fn update_storage_config_in_db(
         return Ok(None);
     }
 
-    // Update only the storage config
-    let updated_project = sqlx::query_as::<_, Project>(
+    sqlx::query_as::<_, Project>(
         r#"
-        UPDATE projects 
-        SET storage_config = $1, updated_at = $2
+        UPDATE projects
+        SET strict_bounds = $1, updated_at = $2
         WHERE id = $3
-        RETURNING id, name, description, storage_config, owner_id, created_at, updated_at
+        RETURNING id, name, description, storage_config, owner_id, task_type, strict_bounds, archived_at, created_at, updated_at
         "#
     )
-    .bind(storage_config)
-    .bind(now)
+    .bind(strict_bounds)
+    .bind(Utc::now())
     .bind(project_id)
     .fetch_optional(pool)
-    .await?;
+    .await
+}
 
-    Ok(updated_project)
+/// Owners and admins of a project read and change its storage configuration, which holds the
+/// credentials. Every member reads the files themselves.
+pub(crate) async fn user_can_manage_storage(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM project_members pm
+            INNER JOIN projects p ON pm.project_id = p.id
+            WHERE p.id = $1 AND pm.user_id = $2 AND (pm.role IN ('owner', 'admin') OR p.owner_id = $2)
+        )
+        "#
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
 }
 
 fn extract_user_claims(
@@ -574,6 +927,8 @@ mod tests {
             name: "Test Project".to_string(),
             description: Some("A test project".to_string()),
             storage_config: None,
+            template_id: None,
+            task_type: None,
         };
 
         let req = test::TestRequest::post()
@@ -589,6 +944,7 @@ mod tests {
         assert_eq!(body["project"]["name"], "Test Project");
         assert_eq!(body["project"]["description"], "A test project");
         assert_eq!(body["project"]["owner_id"], user.id.to_string());
+        assert_eq!(body["project"]["task_type"], "detection");
     }
 
     #[actix_web::test]
@@ -610,6 +966,8 @@ mod tests {
             name: "Test Project".to_string(),
             description: None,
             storage_config: None,
+            template_id: None,
+            task_type: None,
         };
 
         let req = test::TestRequest::post()
@@ -642,6 +1000,8 @@ mod tests {
             name: "".to_string(),
             description: None,
             storage_config: None,
+            template_id: None,
+            task_type: None,
         };
 
         let req = test::TestRequest::post()
@@ -745,6 +1105,49 @@ mod tests {
         assert_eq!(resp.status(), 404);
     }
 
+    #[actix_web::test]
+    #[serial]
+    async fn test_list_project_members() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+        let auth_storage = AuthStorage::new(pool.clone());
+
+        let project = create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .app_data(web::Data::new(auth_storage))
+                .route("/projects/{id}/members", web::get().to(list_project_members))
+        ).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/members", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let members = body["members"].as_array().unwrap();
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0]["user_id"], user.id.to_string());
+        assert_eq!(members[0]["name"], user.name);
+        assert_eq!(members[0]["role"], "owner");
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/members", Uuid::new_v4()))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+    }
+
     #[actix_web::test]
     #[serial]
     async fn test_update_project_success() {
@@ -999,4 +1402,77 @@ mod tests {
         let resp = test::call_service(&app, req).await;
         assert_eq!(resp.status(), 404);
     }
-}
\ No newline at end of file
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_storage_config_role_matrix() {
+        let pool = test_utils::setup_test_db().await;
+        let owner = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let auth_storage = AuthStorage::new(pool.clone());
+
+        let storage_config = serde_json::json!({
+            "type": "s3",
+            "region": "us-east-1",
+            "bucket": "test-bucket",
+            "access_key": "test-access-key",
+            "secret_key": "test-secret-key"
+        });
+        let project = create_project_in_db(&pool, "Test Project", None, Some(&storage_config), owner.id).await.unwrap();
+
+        let mut tokens = vec![("owner", create_auth_token(&oauth_config, &owner), true)];
+        for (role, can_manage) in [("admin", true), ("member", false), ("viewer", false)] {
+            let user_id = test_utils::create_test_user(&pool).await;
+            sqlx::query("INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, $3)")
+                .bind(project.id)
+                .bind(user_id)
+                .bind(role)
+                .execute(&pool)
+                .await
+                .unwrap();
+            let token = JwtManager::new(&oauth_config.jwt_secret)
+                .generate_token(&user_id.to_string(), &format!("test-{}@example.com", user_id), "Test User")
+                .unwrap();
+            tokens.push((role, token, can_manage));
+        }
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .app_data(web::Data::new(auth_storage))
+                .route("/projects", web::get().to(list_projects))
+                .route("/projects/{id}", web::get().to(get_project))
+                .route("/projects/{id}/storage-config", web::put().to(update_storage_config))
+        ).await;
+
+        for (role, token, can_manage) in &tokens {
+            let expected = if *can_manage { storage_config.clone() } else { serde_json::Value::Null };
+
+            let req = test::TestRequest::get()
+                .uri(&format!("/projects/{}", project.id))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), 200, "{} reads the project", role);
+            let body: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(body["project"]["storage_config"], expected, "{} reads the storage config", role);
+
+            let req = test::TestRequest::get()
+                .uri("/projects")
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request();
+            let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(body["projects"][0]["storage_config"], expected, "{} lists the storage config", role);
+
+            let req = test::TestRequest::put()
+                .uri(&format!("/projects/{}/storage-config", project.id))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(UpdateStorageConfigRequest { storage_config: storage_config.clone() })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            let expected_status = if *can_manage { 200 } else { 404 };
+            assert_eq!(resp.status(), expected_status, "{} changes the storage config", role);
+        }
+    }
+}
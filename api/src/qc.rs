@@ -0,0 +1,366 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+use crate::auth::{JwtManager, Claims};
+
+/// What a quality-control sample can be stratified by
+pub const STRATA: [&str; 2] = ["annotator", "category"];
+
+/// Marks a random share of the annotated tasks for review
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct QcSampleRequest {
+    /// Percentage of the annotated tasks to sample, more than 0 and at most 100
+    pub percent: f64,
+    /// `annotator` or `category` to sample that percentage of every annotator's or category's
+    /// tasks, so rarely seen annotators and categories are reviewed too
+    pub stratify_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct QcSampleResponse {
+    /// Tasks the sample marked for review
+    pub task_ids: Vec<Uuid>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/qc/sample",
+    tag = "tasks",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+    ),
+    request_body = QcSampleRequest,
+    responses(
+        (status = 200, body = QcSampleResponse),
+        (status = 400, description = "Invalid percentage or stratum", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn sample_tasks_for_review(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<QcSampleRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    if !(payload.percent > 0.0 && payload.percent <= 100.0) {
+        return HttpResponse::BadRequest().json("percent must be more than 0 and at most 100");
+    }
+
+    if let Some(stratum) = &payload.stratify_by {
+        if !STRATA.contains(&stratum.as_str()) {
+            return HttpResponse::BadRequest().json(format!("Invalid stratify_by. Must be one of: {}", STRATA.join(", ")));
+        }
+    }
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match mark_sample_in_db(&pool, project_id, payload.percent, payload.stratify_by.as_deref()).await {
+        Ok(task_ids) => HttpResponse::Ok().json(QcSampleResponse { task_ids }),
+        Err(err) => {
+            eprintln!("QC sampling error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to sample tasks")
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/projects/{project_id}/tasks/{task_id}/review",
+    tag = "tasks",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    responses(
+        (status = 204, description = "Review mark cleared"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found", body = String),
+    ),
+)]
+pub async fn complete_review(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let result = sqlx::query("UPDATE tasks SET review_requested_at = NULL, updated_at = NOW() WHERE id = $1 AND project_id = $2")
+        .bind(task_id)
+        .bind(project_id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => HttpResponse::NoContent().finish(),
+        Ok(_) => HttpResponse::NotFound().json("Task not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to clear review mark"),
+    }
+}
+
+/// Marks a random `percent` of the annotated tasks that aren't waiting for review yet, rounded
+/// up so every stratum gets at least one task. With a stratum, a task with annotations of
+/// several annotators or categories can be drawn by each of them.
+pub async fn mark_sample_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    percent: f64,
+    stratify_by: Option<&str>,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let (stratum, join) = match stratify_by {
+        Some("annotator") => ("a.annotated_by::TEXT", ""),
+        Some("category") => ("ia.category_id::TEXT", "JOIN image_annotations ia ON ia.annotation_id = a.id"),
+        _ => ("NULL::TEXT", ""),
+    };
+
+    sqlx::query_scalar::<_, Uuid>(&format!(
+        r#"
+        WITH candidates AS (
+            SELECT DISTINCT a.task_id, {} AS stratum
+            FROM annotations a
+            JOIN tasks t ON t.id = a.task_id
+            {}
+            WHERE t.project_id = $1 AND t.review_requested_at IS NULL
+        ),
+        ranked AS (
+            SELECT
+                task_id,
+                ROW_NUMBER() OVER (PARTITION BY stratum ORDER BY RANDOM()) AS position,
+                COUNT(*) OVER (PARTITION BY stratum) AS stratum_size
+            FROM candidates
+        )
+        UPDATE tasks
+        SET review_requested_at = NOW(), updated_at = NOW()
+        WHERE id IN (SELECT task_id FROM ranked WHERE position <= CEIL(stratum_size * $2::FLOAT8 / 100.0))
+        RETURNING id
+        "#,
+        stratum, join
+    ))
+    .bind(project_id)
+    .bind(percent)
+    .fetch_all(pool)
+    .await
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serde_json::json;
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    /// Annotates a task with one box of the category
+    async fn annotate(pool: &Pool<Postgres>, task_id: Uuid, user_id: Uuid, category_id: Option<Uuid>) {
+        let annotation_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO annotations (id, task_id, annotated_by) VALUES ($1, $2, $3)")
+            .bind(annotation_id)
+            .bind(task_id)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO image_annotations (annotation_id, category_id, bbox) VALUES ($1, $2, ARRAY[0, 0, 10, 10]::FLOAT[])")
+            .bind(annotation_id)
+            .bind(category_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_sample_tasks_for_review() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, user.id).await.unwrap();
+        let mut annotated = Vec::new();
+        for index in 0..6 {
+            let task = crate::tasks::create_task_in_db(&pool, project.id, &format!("{}.jpg", index), None).await.unwrap();
+            // Only the first four tasks are annotated
+            if index < 4 {
+                annotate(&pool, task.id, user.id, None).await;
+                annotated.push(task.id);
+            }
+        }
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/qc/sample", web::post().to(sample_tasks_for_review))
+                .route("/projects/{project_id}/tasks/{task_id}/review", web::delete().to(complete_review))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/qc/sample", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(json!({ "percent": 50.0 }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let first: QcSampleResponse = test::read_body_json(resp).await;
+        assert_eq!(first.task_ids.len(), 2);
+        assert!(first.task_ids.iter().all(|id| annotated.contains(id)));
+
+        // Marked tasks are left out of the next sample
+        let second = mark_sample_in_db(&pool, project.id, 100.0, None).await.unwrap();
+        assert_eq!(second.len(), 2);
+        assert!(second.iter().all(|id| !first.task_ids.contains(id)));
+        assert!(mark_sample_in_db(&pool, project.id, 100.0, None).await.unwrap().is_empty());
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/projects/{}/tasks/{}/review", project.id, first.task_ids[0]))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 204);
+        assert_eq!(mark_sample_in_db(&pool, project.id, 100.0, None).await.unwrap(), vec![first.task_ids[0]]);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/qc/sample", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(json!({ "percent": 0.0 }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/qc/sample", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(json!({ "percent": 10.0, "stratify_by": "weekday" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_sample_stratified_by_category() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, user.id).await.unwrap();
+        let car = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "car", None, None, None, None).await.unwrap();
+        let bike = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "bike", None, None, None, None).await.unwrap();
+
+        let mut bike_task = None;
+        for index in 0..4 {
+            let task = crate::tasks::create_task_in_db(&pool, project.id, &format!("{}.jpg", index), None).await.unwrap();
+            let category = if index == 0 { bike.id } else { car.id };
+            annotate(&pool, task.id, user.id, Some(category)).await;
+            if index == 0 {
+                bike_task = Some(task.id);
+            }
+        }
+
+        // 25% of three car tasks and of the one bike task rounds up to one of each
+        let sampled = mark_sample_in_db(&pool, project.id, 25.0, Some("category")).await.unwrap();
+        assert_eq!(sampled.len(), 2);
+        assert!(sampled.contains(&bike_task.unwrap()));
+    }
+}
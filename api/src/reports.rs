@@ -0,0 +1,450 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::auth::{JwtManager, Claims};
+
+/// Days covered when the request has no `from`
+const DEFAULT_REPORT_DAYS: i64 = 30;
+/// Longest range a report may cover
+const MAX_REPORT_DAYS: i64 = 366;
+/// Gaps between two saves longer than this are breaks, not time spent on the next task
+const IDLE_GAP_SECS: i64 = 10 * 60;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AnnotatorsReportQuery {
+    /// First day of the report, inclusive. Defaults to 30 days before `to`.
+    pub from: Option<NaiveDate>,
+    /// Last day of the report, inclusive. Defaults to today (UTC).
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct AnnotatorDay {
+    /// UTC day
+    pub date: NaiveDate,
+    /// Annotations saved, every save of a task counts
+    pub annotations: i64,
+    /// Boxes in those annotations
+    pub boxes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AnnotatorReport {
+    pub user_id: Uuid,
+    pub name: String,
+    /// Distinct tasks the annotator saved
+    pub tasks: i64,
+    pub annotations: i64,
+    pub boxes: i64,
+    /// Time between consecutive saves, breaks left out, per task worked on. `None` with fewer
+    /// than two saves close enough together.
+    pub average_seconds_per_task: Option<f64>,
+    /// Days with at least one save, in order
+    pub days: Vec<AnnotatorDay>,
+    /// Gold tasks the annotator was scored on, by their latest save
+    pub gold_tasks: i64,
+    /// Mean accuracy on those gold tasks against the reference, `None` without any
+    pub gold_accuracy: Option<f64>,
+    /// Mean IoU of the boxes matched to a reference box
+    pub gold_mean_iou: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AnnotatorsReportResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub annotators: Vec<AnnotatorReport>,
+}
+
+/// One saved annotation of the project
+#[derive(Debug, sqlx::FromRow)]
+struct AnnotationRow {
+    user_id: Uuid,
+    name: String,
+    task_id: Uuid,
+    created_at: DateTime<Utc>,
+    boxes: i64,
+}
+
+/// Scores of one annotator on the gold tasks saved in the range
+#[derive(Debug, sqlx::FromRow)]
+struct GoldSummaryRow {
+    user_id: Uuid,
+    tasks: i64,
+    accuracy: Option<f64>,
+    mean_iou: Option<f64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/reports/annotators",
+    tag = "reports",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        AnnotatorsReportQuery,
+    ),
+    responses(
+        (status = 200, body = AnnotatorsReportResponse),
+        (status = 400, description = "Invalid date range", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or the user doesn't lead it", body = String),
+    ),
+)]
+pub async fn get_annotators_report(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<AnnotatorsReportQuery>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query.from.unwrap_or(to - Duration::days(DEFAULT_REPORT_DAYS - 1));
+    if from > to {
+        return HttpResponse::BadRequest().json("from must not be after to");
+    }
+    if (to - from).num_days() >= MAX_REPORT_DAYS {
+        return HttpResponse::BadRequest().json(format!("Reports cover at most {} days", MAX_REPORT_DAYS));
+    }
+
+    // Reports show the work of everyone, so only leads of the project may see them
+    match user_is_project_lead(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or permission denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check permissions"),
+    }
+
+    let start = from.and_hms_opt(0, 0, 0).unwrap().and_utc();
+    let end = (to + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap().and_utc();
+    let rows = match get_annotation_rows(&pool, project_id, start, end).await {
+        Ok(rows) => rows,
+        Err(err) => {
+            eprintln!("Annotators report error: {:?}", err);
+            return HttpResponse::InternalServerError().json("Failed to build report");
+        }
+    };
+
+    match get_gold_summaries(&pool, project_id, start, end).await {
+        Ok(gold) => HttpResponse::Ok().json(AnnotatorsReportResponse {
+            from,
+            to,
+            annotators: build_annotator_reports(rows, gold),
+        }),
+        Err(err) => {
+            eprintln!("Annotators report error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to build report")
+        }
+    }
+}
+
+/// Groups the saves, ordered by annotator and time, into one report per annotator, the most
+/// productive first.
+fn build_annotator_reports(rows: Vec<AnnotationRow>, gold: Vec<GoldSummaryRow>) -> Vec<AnnotatorReport> {
+    let mut gold_by_user: HashMap<Uuid, GoldSummaryRow> = gold.into_iter().map(|row| (row.user_id, row)).collect();
+
+    let mut rows_by_user: HashMap<Uuid, Vec<AnnotationRow>> = HashMap::new();
+    for row in rows {
+        rows_by_user.entry(row.user_id).or_default().push(row);
+    }
+
+    let mut reports: Vec<AnnotatorReport> = rows_by_user
+        .into_iter()
+        .map(|(user_id, rows)| {
+            let mut days: BTreeMap<NaiveDate, AnnotatorDay> = BTreeMap::new();
+            for row in &rows {
+                let date = row.created_at.date_naive();
+                let day = days.entry(date).or_insert(AnnotatorDay { date, annotations: 0, boxes: 0 });
+                day.annotations += 1;
+                day.boxes += row.boxes;
+            }
+            let saves: Vec<(Uuid, DateTime<Utc>)> = rows.iter().map(|row| (row.task_id, row.created_at)).collect();
+            let gold = gold_by_user.remove(&user_id);
+
+            AnnotatorReport {
+                user_id,
+                name: rows[0].name.clone(),
+                tasks: rows.iter().map(|row| row.task_id).collect::<HashSet<_>>().len() as i64,
+                annotations: rows.len() as i64,
+                boxes: rows.iter().map(|row| row.boxes).sum(),
+                average_seconds_per_task: average_seconds_per_task(&saves),
+                days: days.into_values().collect(),
+                gold_tasks: gold.as_ref().map_or(0, |gold| gold.tasks),
+                gold_accuracy: gold.as_ref().and_then(|gold| gold.accuracy),
+                gold_mean_iou: gold.and_then(|gold| gold.mean_iou),
+            }
+        })
+        .collect();
+
+    reports.sort_by(|a, b| b.annotations.cmp(&a.annotations).then_with(|| a.name.cmp(&b.name)));
+    reports
+}
+
+/// Average time spent per task from the save times of one annotator, in order. The time
+/// between two saves goes to the task of the later one, unless it is long enough to be a break.
+pub fn average_seconds_per_task(saves: &[(Uuid, DateTime<Utc>)]) -> Option<f64> {
+    let mut total_seconds = 0;
+    let mut tasks = HashSet::new();
+    for pair in saves.windows(2) {
+        let ((_, previous_at), (task_id, at)) = (pair[0], pair[1]);
+        let gap = (at - previous_at).num_seconds();
+        if gap <= IDLE_GAP_SECS {
+            total_seconds += gap;
+            tasks.insert(task_id);
+        }
+    }
+
+    if tasks.is_empty() {
+        None
+    } else {
+        Some(total_seconds as f64 / tasks.len() as f64)
+    }
+}
+
+async fn get_annotation_rows(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<AnnotationRow>, sqlx::Error> {
+    sqlx::query_as::<_, AnnotationRow>(
+        r#"
+        SELECT
+            a.annotated_by AS user_id,
+            u.name,
+            a.task_id,
+            a.created_at,
+            (SELECT COUNT(*) FROM image_annotations ia WHERE ia.annotation_id = a.id) AS boxes
+        FROM annotations a
+        INNER JOIN tasks t ON t.id = a.task_id
+        INNER JOIN users u ON u.id = a.annotated_by
+        WHERE t.project_id = $1 AND a.created_at >= $2 AND a.created_at < $3
+        ORDER BY a.annotated_by, a.created_at
+        "#
+    )
+    .bind(project_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_gold_summaries(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<GoldSummaryRow>, sqlx::Error> {
+    sqlx::query_as::<_, GoldSummaryRow>(
+        r#"
+        SELECT user_id, COUNT(*) AS tasks, AVG(accuracy) AS accuracy, AVG(mean_iou) AS mean_iou
+        FROM (
+            SELECT DISTINCT ON (gs.user_id, gs.task_id) gs.user_id, gs.accuracy, gs.mean_iou
+            FROM gold_scores gs
+            INNER JOIN tasks t ON t.id = gs.task_id
+            WHERE t.project_id = $1 AND gs.created_at >= $2 AND gs.created_at < $3
+            ORDER BY gs.user_id, gs.task_id, gs.created_at DESC
+        ) latest
+        GROUP BY user_id
+        "#
+    )
+    .bind(project_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await
+}
+
+async fn user_is_project_lead(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM project_members pm
+            INNER JOIN projects p ON pm.project_id = p.id
+            WHERE p.id = $1 AND pm.user_id = $2 AND (pm.role IN ('owner', 'admin') OR p.owner_id = $2)
+        )
+        "#
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_average_seconds_per_task() {
+        let (first, second, third) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        // 60 s on the second task, saved twice 30 s apart, then a break before the third
+        let saves = [(first, at(0)), (second, at(60)), (second, at(90)), (third, at(90 + IDLE_GAP_SECS + 1))];
+        assert_eq!(average_seconds_per_task(&saves), Some(90.0));
+
+        let saves = [(first, at(0)), (second, at(40)), (third, at(100))];
+        assert_eq!(average_seconds_per_task(&saves), Some(50.0));
+    }
+
+    #[actix_web::test]
+    async fn test_average_seconds_per_task_without_pairs() {
+        assert_eq!(average_seconds_per_task(&[]), None);
+        assert_eq!(average_seconds_per_task(&[(Uuid::new_v4(), at(0))]), None);
+        assert_eq!(average_seconds_per_task(&[(Uuid::new_v4(), at(0)), (Uuid::new_v4(), at(IDLE_GAP_SECS + 1))]), None);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_annotators_report() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        let first = crate::tasks::create_task_in_db(&pool, project.id, "a.jpg", None).await.unwrap();
+        let second = crate::tasks::create_task_in_db(&pool, project.id, "b.jpg", None).await.unwrap();
+
+        // Two boxes on the first task, then one on the second two minutes later
+        for (task_id, boxes, age) in [(first.id, 2, "3 minutes"), (second.id, 1, "1 minute")] {
+            let annotation_id = Uuid::new_v4();
+            sqlx::query(&format!(
+                "INSERT INTO annotations (id, task_id, annotated_by, created_at) VALUES ($1, $2, $3, NOW() - INTERVAL '{}')",
+                age
+            ))
+            .bind(annotation_id)
+            .bind(task_id)
+            .bind(user.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+            for _ in 0..boxes {
+                sqlx::query("INSERT INTO image_annotations (annotation_id, bbox) VALUES ($1, ARRAY[0, 0, 10, 10]::FLOAT[])")
+                    .bind(annotation_id)
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{id}/reports/annotators", web::get().to(get_annotators_report))
+        ).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/reports/annotators", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let report: AnnotatorsReportResponse = test::read_body_json(resp).await;
+        assert_eq!((report.to - report.from).num_days(), DEFAULT_REPORT_DAYS - 1);
+        assert_eq!(report.annotators.len(), 1);
+        let annotator = &report.annotators[0];
+        assert_eq!(annotator.user_id, user.id);
+        assert_eq!(annotator.tasks, 2);
+        assert_eq!(annotator.annotations, 2);
+        assert_eq!(annotator.boxes, 3);
+        assert_eq!(annotator.average_seconds_per_task.map(|seconds| seconds.round()), Some(120.0));
+        assert_eq!(annotator.days.iter().map(|day| day.boxes).sum::<i64>(), 3);
+        assert_eq!(annotator.gold_tasks, 0);
+        assert_eq!(annotator.gold_accuracy, None);
+
+        // Ranges before any annotation are empty, reversed ones are rejected
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/reports/annotators?from=2020-01-01&to=2020-01-31", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let report: AnnotatorsReportResponse = test::read_body_json(resp).await;
+        assert!(report.annotators.is_empty());
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/reports/annotators?from=2020-02-01&to=2020-01-01", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+}
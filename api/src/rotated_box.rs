@@ -0,0 +1,85 @@
+// Geometry for oriented bounding boxes. A box is stored as its unrotated COCO bbox
+// [x, y, width, height] plus a clockwise rotation in degrees around the box center,
+// in image coordinates (y pointing down).
+
+pub fn is_valid_rotation(rotation: f64) -> bool {
+    rotation.is_finite() && (-360.0..=360.0).contains(&rotation)
+}
+
+/// Maps any angle into (-180, 180].
+pub fn normalize_rotation(rotation: f64) -> f64 {
+    let normalized = rotation.rem_euclid(360.0);
+    if normalized > 180.0 {
+        normalized - 360.0
+    } else {
+        normalized
+    }
+}
+
+/// Corners of the rotated box, starting at the (unrotated) top-left and going clockwise.
+pub fn corners(bbox: &[f64], rotation: f64) -> [(f64, f64); 4] {
+    let (x, y, width, height) = (bbox[0], bbox[1], bbox[2], bbox[3]);
+    let (cx, cy) = (x + width / 2.0, y + height / 2.0);
+    let (sin, cos) = rotation.to_radians().sin_cos();
+
+    let rotate = |dx: f64, dy: f64| (cx + dx * cos - dy * sin, cy + dx * sin + dy * cos);
+    let (half_width, half_height) = (width / 2.0, height / 2.0);
+
+    [
+        rotate(-half_width, -half_height),
+        rotate(half_width, -half_height),
+        rotate(half_width, half_height),
+        rotate(-half_width, half_height),
+    ]
+}
+
+/// Axis-aligned [x, y, width, height] box enclosing the corners.
+pub fn enclosing_bbox(corners: &[(f64, f64); 4]) -> Vec<f64> {
+    let min_x = corners.iter().map(|(x, _)| *x).fold(f64::INFINITY, f64::min);
+    let max_x = corners.iter().map(|(x, _)| *x).fold(f64::NEG_INFINITY, f64::max);
+    let min_y = corners.iter().map(|(_, y)| *y).fold(f64::INFINITY, f64::min);
+    let max_y = corners.iter().map(|(_, y)| *y).fold(f64::NEG_INFINITY, f64::max);
+
+    vec![min_x, min_y, max_x - min_x, max_y - min_y]
+}
+
+/// COCO segmentation polygon `[x1, y1, x2, y2, ...]`.
+pub fn polygon(corners: &[(f64, f64); 4]) -> Vec<f64> {
+    corners.iter().flat_map(|(x, y)| [*x, *y]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: (f64, f64), b: (f64, f64)) {
+        assert!((a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_corners_rotate_clockwise_around_center() {
+        let unrotated = corners(&[0.0, 0.0, 4.0, 2.0], 0.0);
+        assert_eq!(unrotated, [(0.0, 0.0), (4.0, 0.0), (4.0, 2.0), (0.0, 2.0)]);
+
+        // A quarter turn clockwise (y down) makes the 4x2 box stand upright around (2, 1)
+        let rotated = corners(&[0.0, 0.0, 4.0, 2.0], 90.0);
+        assert_close(rotated[0], (3.0, -1.0));
+        assert_close(rotated[1], (3.0, 3.0));
+        assert_close(rotated[2], (1.0, 3.0));
+        assert_close(rotated[3], (1.0, -1.0));
+
+        let bbox = enclosing_bbox(&rotated);
+        assert!((bbox[0] - 1.0).abs() < 1e-9 && (bbox[1] + 1.0).abs() < 1e-9);
+        assert!((bbox[2] - 2.0).abs() < 1e-9 && (bbox[3] - 4.0).abs() < 1e-9);
+        assert_eq!(polygon(&unrotated), vec![0.0, 0.0, 4.0, 0.0, 4.0, 2.0, 0.0, 2.0]);
+    }
+
+    #[test]
+    fn test_normalize_rotation() {
+        assert_eq!(normalize_rotation(270.0), -90.0);
+        assert_eq!(normalize_rotation(-180.0), 180.0);
+        assert_eq!(normalize_rotation(45.0), 45.0);
+        assert!(!is_valid_rotation(f64::NAN));
+        assert!(!is_valid_rotation(400.0));
+    }
+}
@@ -0,0 +1,422 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use std::time::Duration;
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+use crate::auth::{JwtManager, Claims};
+use crate::storage::factory::create_storage_provider_from_project;
+
+/// Presigned image URLs handed to the segmentation server stay valid for this long.
+const IMAGE_URL_EXPIRY_SECS: u64 = 300;
+/// Time allowed to reach the segmentation server
+const CONNECT_TIMEOUT_SECS: u64 = 5;
+/// Time allowed for one segmentation, from the request to the last byte of the answer. The
+/// editor waits for it, so a stuck server must not hold the request open.
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Location of the SAM-compatible inference server. Segmentation is disabled when unset.
+#[derive(Debug, Clone)]
+pub struct SegmentationConfig {
+    pub server_url: Option<String>,
+    /// Shared by all requests, so connections to the server are reused
+    pub client: reqwest::Client,
+}
+
+impl Default for SegmentationConfig {
+    fn default() -> Self {
+        Self {
+            server_url: None,
+            client: segmentation_client(),
+        }
+    }
+}
+
+impl SegmentationConfig {
+    pub fn from_env() -> Self {
+        Self {
+            server_url: std::env::var("SAM_SERVER_URL")
+                .ok()
+                .map(|url| url.trim_end_matches('/').to_string())
+                .filter(|url| !url.is_empty()),
+            client: segmentation_client(),
+        }
+    }
+}
+
+fn segmentation_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .expect("Failed to build the segmentation server client")
+}
+
+/// A click prompt in image pixels. `label` is 1 for foreground and 0 for background.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PromptPoint {
+    pub x: f64,
+    pub y: f64,
+    pub label: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SegmentRequest {
+    #[serde(default)]
+    pub points: Vec<PromptPoint>,
+    /// Optional COCO `[x, y, width, height]` box prompt
+    #[serde(rename = "box", default)]
+    pub prompt_box: Option<Vec<f64>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SegmentResponse {
+    #[schema(value_type = Vec<Vec<f64>>)]
+    pub polygon: Vec<[f64; 2]>,
+    pub bbox: Vec<f64>,
+    pub area: f64,
+    pub score: Option<f64>,
+}
+
+/// Request body sent to the segmentation server.
+#[derive(Debug, Serialize)]
+struct SamServerRequest<'a> {
+    image_url: &'a str,
+    points: &'a [PromptPoint],
+    #[serde(rename = "box")]
+    prompt_box: Option<&'a [f64]>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SamServerResponse {
+    polygon: Vec<[f64; 2]>,
+    #[serde(default)]
+    score: Option<f64>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/tasks/{task_id}/segment",
+    tag = "inference",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    request_body = SegmentRequest,
+    responses(
+        (status = 200, description = "Polygon of the segmented object", body = SegmentResponse),
+        (status = 400, description = "Invalid prompt or task without image", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found", body = String),
+        (status = 422, description = "The segmentation server returned an empty mask", body = String),
+        (status = 501, description = "No segmentation server configured", body = String),
+        (status = 502, description = "The segmentation server failed", body = String),
+    ),
+)]
+pub async fn segment_task(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+    segmentation_config: web::Data<SegmentationConfig>,
+    segment_req: web::Json<SegmentRequest>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str) = path.into_inner();
+
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    if let Err(message) = validate_segment_request(&segment_req) {
+        return HttpResponse::BadRequest().json(message);
+    }
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let server_url = match &segmentation_config.server_url {
+        Some(url) => url,
+        None => return HttpResponse::NotImplemented().json("Segmentation server is not configured"),
+    };
+
+    let resource_url = match get_task_resource_url(&pool, project_id, task_id).await {
+        Ok(Some(Some(url))) => url,
+        Ok(Some(None)) => return HttpResponse::BadRequest().json("Task has no image"),
+        Ok(None) => return HttpResponse::NotFound().json("Task not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch task"),
+    };
+
+    let image_url = match resolve_image_url(&pool, project_id, &resource_url).await {
+        Ok(url) => url,
+        Err(err) => return HttpResponse::BadRequest().json(format!("Failed to resolve image URL: {}", err)),
+    };
+
+    let server_request = SamServerRequest {
+        image_url: &image_url,
+        points: &segment_req.points,
+        prompt_box: segment_req.prompt_box.as_deref(),
+    };
+
+    let response = match segmentation_config.client
+        .post(format!("{}/predict", server_url))
+        .json(&server_request)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+    {
+        Ok(response) => response,
+        Err(err) => {
+            eprintln!("Segmentation server error: {}", err);
+            return HttpResponse::BadGateway().json("Segmentation server request failed");
+        }
+    };
+
+    let sam_response: SamServerResponse = match response.json().await {
+        Ok(body) => body,
+        Err(err) => {
+            eprintln!("Segmentation server error: {}", err);
+            return HttpResponse::BadGateway().json("Invalid response from segmentation server");
+        }
+    };
+
+    match build_segment_response(sam_response.polygon, sam_response.score) {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::UnprocessableEntity().json("Segmentation server returned an empty mask"),
+    }
+}
+
+fn validate_segment_request(request: &SegmentRequest) -> Result<(), String> {
+    if request.points.is_empty() && request.prompt_box.is_none() {
+        return Err("At least one point or a box prompt is required".to_string());
+    }
+
+    if request.points.iter().any(|point| point.label != 0 && point.label != 1) {
+        return Err("Point labels must be 0 (background) or 1 (foreground)".to_string());
+    }
+
+    if let Some(prompt_box) = &request.prompt_box {
+        if prompt_box.len() != 4 || prompt_box[2] <= 0.0 || prompt_box[3] <= 0.0 {
+            return Err("Box prompt must be [x, y, width, height] with a positive size".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Computes the tight bounding box and the area (shoelace formula) of the polygon.
+pub fn build_segment_response(polygon: Vec<[f64; 2]>, score: Option<f64>) -> Option<SegmentResponse> {
+    if polygon.len() < 3 {
+        return None;
+    }
+
+    let (mut min_x, mut min_y) = (f64::MAX, f64::MAX);
+    let (mut max_x, mut max_y) = (f64::MIN, f64::MIN);
+    let mut twice_area = 0.0;
+
+    for (index, [x, y]) in polygon.iter().copied().enumerate() {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+
+        let [next_x, next_y] = polygon[(index + 1) % polygon.len()];
+        twice_area += x * next_y - next_x * y;
+    }
+
+    Some(SegmentResponse {
+        bbox: vec![min_x, min_y, max_x - min_x, max_y - min_y],
+        area: twice_area.abs() / 2.0,
+        polygon,
+        score,
+    })
+}
+
+/// The segmentation server fetches the image itself, so storage keys are turned into presigned URLs.
+async fn resolve_image_url(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    resource_url: &str,
+) -> Result<String, String> {
+    let key = match resource_url.strip_prefix("storage://") {
+        Some(key) => key,
+        None => return Ok(resource_url.to_string()),
+    };
+
+    let project = sqlx::query_as::<_, crate::projects::Project>(
+        "SELECT id, name, description, storage_config, owner_id, task_type, created_at, updated_at FROM projects WHERE id = $1"
+    )
+    .bind(project_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let provider = create_storage_provider_from_project(&project)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    provider
+        .get_presigned_url(key, IMAGE_URL_EXPIRY_SECS)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn get_task_resource_url(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    task_id: Uuid,
+) -> Result<Option<Option<String>>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<String>>(
+        "SELECT resource_url FROM tasks WHERE id = $1 AND project_id = $2"
+    )
+    .bind(task_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_segment_task_without_server_configured() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "image1.jpg", Some("https://example.com/image1.jpg")).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .app_data(web::Data::new(SegmentationConfig::default()))
+                .route("/projects/{project_id}/tasks/{task_id}/segment", web::post().to(segment_task))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/tasks/{}/segment", project.id, task.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(serde_json::json!({"points": [{"x": 10.0, "y": 20.0, "label": 1}]}))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 501);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_segment_task_requires_prompt() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .app_data(web::Data::new(SegmentationConfig::default()))
+                .route("/projects/{project_id}/tasks/{task_id}/segment", web::post().to(segment_task))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/tasks/{}/segment", Uuid::new_v4(), Uuid::new_v4()))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(serde_json::json!({"points": []}))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_build_segment_response() {
+        let polygon = vec![[10.0, 20.0], [50.0, 20.0], [50.0, 40.0], [10.0, 40.0]];
+        let response = build_segment_response(polygon, Some(0.93)).unwrap();
+
+        assert_eq!(response.bbox, vec![10.0, 20.0, 40.0, 20.0]);
+        assert_eq!(response.area, 800.0);
+        assert_eq!(response.score, Some(0.93));
+
+        assert!(build_segment_response(vec![[0.0, 0.0], [1.0, 1.0]], None).is_none());
+    }
+}
@@ -0,0 +1,197 @@
+use actix_cors::Cors;
+use actix_web::http::{Method, header};
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+
+const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1:8080";
+
+/// Certificate chain and private key to serve HTTPS with
+#[derive(Debug, Clone, PartialEq)]
+pub struct TlsConfig {
+    pub cert_path: String,
+    pub key_path: String,
+}
+
+/// Where and how the server listens, read from the environment (and so from `api/.env`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    /// `host:port`, `0.0.0.0:8080` to accept remote clients
+    pub bind_address: String,
+    /// Worker threads, one per CPU core when unset
+    pub workers: Option<usize>,
+    pub tls: Option<TlsConfig>,
+    /// Origins browsers may call the API from, `*` for any. Empty disables CORS, which the
+    /// desktop app doesn't need.
+    pub cors_allowed_origins: Vec<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: DEFAULT_BIND_ADDRESS.to_string(),
+            workers: None,
+            tls: None,
+            cors_allowed_origins: Vec::new(),
+        }
+    }
+}
+
+impl ServerConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.trim().is_empty());
+
+        let workers = match var("SERVER_WORKERS") {
+            Some(workers) => match workers.trim().parse::<usize>() {
+                Ok(workers) if workers > 0 => Some(workers),
+                _ => return Err(format!("SERVER_WORKERS must be a positive number, got {}", workers)),
+            },
+            None => None,
+        };
+
+        let tls = match (var("TLS_CERT_PATH"), var("TLS_KEY_PATH")) {
+            (Some(cert_path), Some(key_path)) => Some(TlsConfig { cert_path, key_path }),
+            (None, None) => None,
+            _ => return Err("TLS_CERT_PATH and TLS_KEY_PATH have to be set together".to_string()),
+        };
+
+        Ok(Self {
+            bind_address: var("BIND_ADDRESS").unwrap_or_else(|| DEFAULT_BIND_ADDRESS.to_string()),
+            workers,
+            tls,
+            cors_allowed_origins: var("CORS_ALLOWED_ORIGINS")
+                .map(|origins| parse_origins(&origins))
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Base URL clients reach the server at, for the startup message
+    pub fn url(&self) -> String {
+        let scheme = if self.tls.is_some() { "https" } else { "http" };
+        format!("{}://{}", scheme, self.bind_address)
+    }
+
+    pub fn cors_enabled(&self) -> bool {
+        !self.cors_allowed_origins.is_empty()
+    }
+
+    /// CORS middleware answering the allowed origins only. Every route authenticates with the
+    /// `Authorization` header rather than cookies, so credentials are never allowed.
+    pub fn cors(&self) -> Cors {
+        let mut cors = Cors::default()
+            .allowed_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+            .allowed_headers([header::AUTHORIZATION, header::CONTENT_TYPE, header::ACCEPT])
+            .allowed_header("x-prefix")
+            .expose_headers([header::CONTENT_DISPOSITION])
+            .max_age(3600);
+
+        if self.cors_allowed_origins.iter().any(|origin| origin == "*") {
+            cors = cors.allow_any_origin();
+        } else {
+            for origin in &self.cors_allowed_origins {
+                cors = cors.allowed_origin(origin);
+            }
+        }
+        cors
+    }
+}
+
+/// Comma separated origins, trailing slashes dropped since browsers never send them
+fn parse_origins(origins: &str) -> Vec<String> {
+    origins
+        .split(',')
+        .map(|origin| origin.trim().trim_end_matches('/').to_string())
+        .filter(|origin| !origin.is_empty())
+        .collect()
+}
+
+/// Reads the PEM certificate chain and private key of `tls`
+pub fn load_rustls_config(tls: &TlsConfig) -> Result<rustls::ServerConfig, String> {
+    let cert_file = File::open(&tls.cert_path)
+        .map_err(|e| format!("Failed to open TLS certificate {}: {}", tls.cert_path, e))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(cert_file))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read TLS certificate {}: {}", tls.cert_path, e))?;
+    if certs.is_empty() {
+        return Err(format!("No certificate found in {}", tls.cert_path));
+    }
+
+    let key_file = File::open(&tls.key_path)
+        .map_err(|e| format!("Failed to open TLS key {}: {}", tls.key_path, e))?;
+    let key = rustls_pemfile::private_key(&mut BufReader::new(key_file))
+        .map_err(|e| format!("Failed to read TLS key {}: {}", tls.key_path, e))?
+        .ok_or_else(|| format!("No private key found in {}", tls.key_path))?;
+
+    // Explicit provider, the default one is ambiguous when dependencies enable several
+    rustls::ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("Invalid TLS protocol versions: {}", e))?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| format!("Invalid TLS certificate or key: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{App, HttpResponse, test, web};
+
+    #[actix_web::test]
+    async fn test_parse_origins() {
+        assert_eq!(
+            parse_origins(" https://label.example.com/, http://localhost:3000 ,,"),
+            vec!["https://label.example.com", "http://localhost:3000"]
+        );
+        assert!(parse_origins("").is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_url() {
+        let mut config = ServerConfig::default();
+        assert_eq!(config.url(), "http://127.0.0.1:8080");
+
+        config.tls = Some(TlsConfig { cert_path: "cert.pem".to_string(), key_path: "key.pem".to_string() });
+        assert_eq!(config.url(), "https://127.0.0.1:8080");
+    }
+
+    #[actix_web::test]
+    async fn test_load_rustls_config_missing_files() {
+        let tls = TlsConfig { cert_path: "/nonexistent/cert.pem".to_string(), key_path: "/nonexistent/key.pem".to_string() };
+        let error = load_rustls_config(&tls).unwrap_err();
+        assert!(error.contains("/nonexistent/cert.pem"));
+    }
+
+    #[tokio::test]
+    async fn test_cors_allow_list() {
+        let config = ServerConfig {
+            cors_allowed_origins: vec!["https://label.example.com".to_string()],
+            ..Default::default()
+        };
+        let app = test::init_service(
+            App::new()
+                .wrap(config.cors())
+                .route("/projects", web::get().to(|| async { HttpResponse::Ok().finish() }))
+        ).await;
+
+        let req = test::TestRequest::default()
+            .method(Method::OPTIONS)
+            .uri("/projects")
+            .insert_header((header::ORIGIN, "https://label.example.com"))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_METHOD, "GET"))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        assert_eq!(
+            resp.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://label.example.com"
+        );
+
+        let req = test::TestRequest::get()
+            .uri("/projects")
+            .insert_header((header::ORIGIN, "https://evil.example.com"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    }
+}
@@ -0,0 +1,465 @@
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Postgres};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::auth::{JwtManager, Claims, OAuthConfig, User};
+
+/// How long a refresh token stays valid without being used
+const REFRESH_TOKEN_DAYS: i64 = 30;
+
+/// Where a login came from, shown in the list of sessions
+pub(crate) struct SessionDevice {
+    pub name: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl SessionDevice {
+    /// `req` is the provider callback, so the user agent and address are those of the browser
+    /// the login was completed in
+    pub fn from_request(req: &HttpRequest, name: Option<String>) -> Self {
+        Self {
+            name,
+            user_agent: req
+                .headers()
+                .get("User-Agent")
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+            ip_address: req.peer_addr().map(|addr| addr.ip().to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct Session {
+    pub id: Uuid,
+    /// Name the client gave the device, e.g. the desktop app and its OS
+    pub device_name: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Last sign-in or refresh
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// Whether this is the session of the token the list was requested with
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub current: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SessionsResponse {
+    pub sessions: Vec<Session>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+/// A new JWT, and the refresh token to use next time. The one sent is no longer valid.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RefreshResponse {
+    pub token: String,
+    pub refresh_token: String,
+}
+
+/// Rejects tokens of revoked or expired sessions before they reach the handlers, which only
+/// check the signature. Requests without a token or with an invalid one pass, the handlers
+/// answer those.
+pub async fn enforce_sessions<B: MessageBody>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, actix_web::Error> {
+    let session_id = req
+        .app_data::<web::Data<OAuthConfig>>()
+        .zip(
+            req.headers()
+                .get("Authorization")
+                .and_then(|header| header.to_str().ok())
+                .and_then(|header| header.strip_prefix("Bearer ")),
+        )
+        .and_then(|(config, token)| JwtManager::new(&config.jwt_secret).verify_token(token).ok())
+        .and_then(|claims| claims.sid)
+        .and_then(|sid| Uuid::parse_str(&sid).ok());
+    let pool = req.app_data::<web::Data<Pool<Postgres>>>().cloned();
+
+    if let (Some(session_id), Some(pool)) = (session_id, pool) {
+        if !crate::cache::session_is_active(&pool, session_id).await {
+            let response = HttpResponse::Unauthorized().json("Session has been revoked");
+            return Ok(req.into_response(response).map_into_right_body());
+        }
+    }
+
+    next.call(req).await.map(ServiceResponse::map_into_left_body)
+}
+
+#[utoipa::path(
+    get,
+    path = "/me/sessions",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Sessions that can still be used, most recently used first", body = SessionsResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+    ),
+)]
+pub async fn list_sessions(
+    req: HttpRequest,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    match get_active_sessions(&pool, user_id).await {
+        Ok(mut sessions) => {
+            for session in &mut sessions {
+                session.current = claims.sid.as_deref() == Some(session.id.to_string().as_str());
+            }
+            HttpResponse::Ok().json(SessionsResponse { sessions })
+        }
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch sessions"),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/me/sessions/{session_id}",
+    tag = "auth",
+    params(("session_id" = Uuid, Path, description = "Session ID")),
+    responses(
+        (status = 204, description = "Session revoked, its tokens stop working right away"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Session not found", body = String),
+    ),
+)]
+pub async fn revoke_session(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let session_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid session ID"),
+    };
+
+    match revoke_session_in_db(&pool, user_id, session_id).await {
+        Ok(true) => {
+            crate::cache::invalidate_session(session_id).await;
+            HttpResponse::NoContent().finish()
+        }
+        Ok(false) => HttpResponse::NotFound().json("Session not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to revoke session"),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/refresh",
+    tag = "auth",
+    request_body = RefreshRequest,
+    security(()),
+    responses(
+        (status = 200, body = RefreshResponse),
+        (status = 401, description = "Unknown, used, expired or revoked refresh token", body = String),
+    ),
+)]
+pub async fn refresh_token(
+    payload: web::Json<RefreshRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<OAuthConfig>,
+) -> impl Responder {
+    let (session_id, user, refresh_token) = match refresh_session(&pool, &payload.refresh_token).await {
+        Ok(Some(refreshed)) => refreshed,
+        Ok(None) => return HttpResponse::Unauthorized().json("Invalid or expired refresh token"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to refresh session"),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.generate_session_token(&user.id.to_string(), &user.email, &user.name, Some(session_id)) {
+        Ok(token) => HttpResponse::Ok().json(RefreshResponse { token, refresh_token }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to generate token"),
+    }
+}
+
+fn generate_refresh_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// Only hashes are stored, so a leaked database does not hand out sessions
+fn hash_refresh_token(refresh_token: &str) -> String {
+    Sha256::digest(refresh_token.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Starts a session of the user, returning its ID and refresh token
+pub(crate) async fn create_session(
+    pool: &Pool<Postgres>,
+    user_id: Uuid,
+    device: &SessionDevice,
+) -> Result<(Uuid, String), sqlx::Error> {
+    let refresh_token = generate_refresh_token();
+    let session_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO sessions (user_id, refresh_token_hash, device_name, user_agent, ip_address, expires_at)
+        VALUES ($1, $2, $3, $4, $5, NOW() + make_interval(days => $6))
+        RETURNING id
+        "#
+    )
+    .bind(user_id)
+    .bind(hash_refresh_token(&refresh_token))
+    .bind(device.name.as_deref())
+    .bind(device.user_agent.as_deref())
+    .bind(device.ip_address.as_deref())
+    .bind(REFRESH_TOKEN_DAYS as i32)
+    .fetch_one(pool)
+    .await?;
+
+    Ok((session_id, refresh_token))
+}
+
+/// Swaps a refresh token for a new one, so every token works once. `None` when the token is
+/// unknown or its session is over.
+async fn refresh_session(
+    pool: &Pool<Postgres>,
+    refresh_token: &str,
+) -> Result<Option<(Uuid, User, String)>, sqlx::Error> {
+    let new_refresh_token = generate_refresh_token();
+    let session = sqlx::query_as::<_, (Uuid, Uuid)>(
+        r#"
+        UPDATE sessions
+        SET refresh_token_hash = $2, last_used_at = NOW(), expires_at = NOW() + make_interval(days => $3)
+        WHERE refresh_token_hash = $1 AND revoked_at IS NULL AND expires_at > NOW()
+        RETURNING id, user_id
+        "#
+    )
+    .bind(hash_refresh_token(refresh_token))
+    .bind(hash_refresh_token(&new_refresh_token))
+    .bind(REFRESH_TOKEN_DAYS as i32)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some((session_id, user_id)) = session else {
+        return Ok(None);
+    };
+    let user = crate::auth::get_user_by_id(pool, user_id).await?;
+    Ok(user.map(|user| (session_id, user, new_refresh_token)))
+}
+
+async fn get_active_sessions(pool: &Pool<Postgres>, user_id: Uuid) -> Result<Vec<Session>, sqlx::Error> {
+    sqlx::query_as::<_, Session>(
+        r#"
+        SELECT id, device_name, user_agent, ip_address, created_at, last_used_at, expires_at
+        FROM sessions
+        WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+        ORDER BY last_used_at DESC
+        "#
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn revoke_session_in_db(pool: &Pool<Postgres>, user_id: Uuid, session_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE sessions SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL"
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils;
+    use actix_web::middleware::from_fn;
+    use actix_web::{test, App};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn device(name: &str) -> SessionDevice {
+        SessionDevice { name: Some(name.to_string()), user_agent: None, ip_address: None }
+    }
+
+    #[actix_web::test]
+    async fn test_refresh_tokens_are_stored_hashed() {
+        let token = generate_refresh_token();
+        assert_eq!(token.len(), 64);
+        assert_eq!(hash_refresh_token(&token).len(), 64);
+        assert_ne!(hash_refresh_token(&token), token);
+        assert_eq!(hash_refresh_token(&token), hash_refresh_token(&token));
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_revoked_session_is_cut_off() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+
+        let (laptop_id, laptop_refresh) = create_session(&pool, user.id, &device("Laptop")).await.unwrap();
+        let (desktop_id, _) = create_session(&pool, user.id, &device("Desktop")).await.unwrap();
+        let laptop_token = jwt_manager.generate_session_token(&user.id.to_string(), &user.email, &user.name, Some(laptop_id)).unwrap();
+        let desktop_token = jwt_manager.generate_session_token(&user.id.to_string(), &user.email, &user.name, Some(desktop_id)).unwrap();
+        // Tokens issued outside a login carry no session and stay valid until they expire
+        let plain_token = jwt_manager.generate_token(&user.id.to_string(), &user.email, &user.name).unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .wrap(from_fn(enforce_sessions))
+                .route("/me", web::get().to(crate::auth::get_user_info))
+                .route("/me/sessions", web::get().to(list_sessions))
+                .route("/me/sessions/{session_id}", web::delete().to(revoke_session))
+                .route("/auth/refresh", web::post().to(refresh_token))
+        ).await;
+
+        let get_me = |token: &str| {
+            test::TestRequest::get()
+                .uri("/me")
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request()
+        };
+
+        let req = test::TestRequest::get()
+            .uri("/me/sessions")
+            .insert_header(("Authorization", format!("Bearer {}", desktop_token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let listed: SessionsResponse = test::read_body_json(resp).await;
+        assert_eq!(listed.sessions.len(), 2);
+        let current: Vec<_> = listed.sessions.iter().filter(|session| session.current).collect();
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0].device_name.as_deref(), Some("Desktop"));
+
+        // The stolen laptop is signed out from the desktop
+        let req = test::TestRequest::delete()
+            .uri(&format!("/me/sessions/{}", laptop_id))
+            .insert_header(("Authorization", format!("Bearer {}", desktop_token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 204);
+
+        assert_eq!(test::call_service(&app, get_me(&laptop_token)).await.status(), 401);
+        assert_eq!(test::call_service(&app, get_me(&desktop_token)).await.status(), 200);
+        assert_eq!(test::call_service(&app, get_me(&plain_token)).await.status(), 200);
+
+        // Nor can it sign in again with its refresh token
+        let req = test::TestRequest::post()
+            .uri("/auth/refresh")
+            .set_json(RefreshRequest { refresh_token: laptop_refresh })
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+        // Sessions are only found among those of the user
+        let req = test::TestRequest::delete()
+            .uri(&format!("/me/sessions/{}", Uuid::new_v4()))
+            .insert_header(("Authorization", format!("Bearer {}", desktop_token)))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_refresh_token_works_once() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let (session_id, refresh) = create_session(&pool, user.id, &device("Laptop")).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config.clone()))
+                .route("/auth/refresh", web::post().to(refresh_token))
+        ).await;
+
+        let refresh_request = |refresh_token: &str| {
+            test::TestRequest::post()
+                .uri("/auth/refresh")
+                .set_json(RefreshRequest { refresh_token: refresh_token.to_string() })
+                .to_request()
+        };
+
+        let resp = test::call_service(&app, refresh_request(&refresh)).await;
+        assert_eq!(resp.status(), 200);
+        let refreshed: RefreshResponse = test::read_body_json(resp).await;
+        assert_ne!(refreshed.refresh_token, refresh);
+        let claims = JwtManager::new(&oauth_config.jwt_secret).verify_token(&refreshed.token).unwrap();
+        assert_eq!(claims.sid, Some(session_id.to_string()));
+        assert_eq!(claims.sub, user.id.to_string());
+
+        assert_eq!(test::call_service(&app, refresh_request(&refresh)).await.status(), 401);
+        assert_eq!(test::call_service(&app, refresh_request(&refreshed.refresh_token)).await.status(), 200);
+    }
+}
@@ -0,0 +1,700 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use chrono::{DateTime, Duration, Utc};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::annotations::{AnnotationWithCategory, AnnotationsListResponse};
+use crate::auth::{JwtManager, Claims};
+use crate::image_annotation_categories::ImageAnnotationCategory;
+
+/// Lifetime of a share link when the request doesn't set one
+const DEFAULT_EXPIRY_DAYS: i64 = 30;
+const MAX_EXPIRY_DAYS: i64 = 365;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct ProjectShare {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// Secret of the link, anyone holding it can read the project until it expires
+    pub token: String,
+    pub name: Option<String>,
+    pub created_by: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateShareRequest {
+    /// Who or what the link is for, e.g. `External review`
+    pub name: Option<String>,
+    /// Days until the link stops working, 30 by default and at most 365
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SharesListResponse {
+    pub shares: Vec<ProjectShare>,
+}
+
+/// What a share link shows of its project
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SharedProject {
+    pub name: String,
+    pub description: Option<String>,
+    pub task_type: String,
+    pub categories: Vec<ImageAnnotationCategory>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Read-only view of a task, without status, flags or assignees
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct SharedTask {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(skip)]
+    pub resource_url: Option<String>,
+    pub media_type: String,
+    pub split: Option<String>,
+    #[sqlx(skip)]
+    pub resolved_resource_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SharedTasksListResponse {
+    pub tasks: Vec<SharedTask>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SharedExportQuery {
+    /// Package the annotation file together with the referenced images as a ZIP
+    pub include_images: Option<bool>,
+}
+
+/// The share a token opens, `None` once it expired or was revoked
+#[derive(Debug, sqlx::FromRow)]
+struct ActiveShare {
+    project_id: Uuid,
+    name: Option<String>,
+    expires_at: DateTime<Utc>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{id}/shares",
+    tag = "shares",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    request_body = CreateShareRequest,
+    responses(
+        (status = 201, body = ProjectShare),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or not owned by the user", body = String),
+    ),
+)]
+pub async fn create_share(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<CreateShareRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let expires_in_days = payload.expires_in_days.unwrap_or(DEFAULT_EXPIRY_DAYS);
+    if !(1..=MAX_EXPIRY_DAYS).contains(&expires_in_days) {
+        return HttpResponse::BadRequest().json(format!("expires_in_days must be between 1 and {}", MAX_EXPIRY_DAYS));
+    }
+
+    let name = payload.name.as_deref().map(str::trim).filter(|name| !name.is_empty());
+    if name.is_some_and(|name| name.len() > 255) {
+        return HttpResponse::BadRequest().json("Share name too long (max 255 characters)");
+    }
+
+    // Publishing data is up to the owner
+    match user_is_project_owner(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    }
+
+    let expires_at = Utc::now() + Duration::days(expires_in_days);
+    match create_share_in_db(&pool, project_id, name, user_id, expires_at).await {
+        Ok(share) => HttpResponse::Created().json(share),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to create share link"),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{id}/shares",
+    tag = "shares",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 200, body = SharesListResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn list_shares(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Tokens are secrets, only the owner who hands them out sees them
+    match user_is_project_owner(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    }
+
+    match get_project_shares(&pool, project_id).await {
+        Ok(shares) => HttpResponse::Ok().json(SharesListResponse { shares }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch share links"),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/projects/{id}/shares/{share_id}",
+    tag = "shares",
+    params(
+        ("id" = Uuid, Path, description = "Project ID"),
+        ("share_id" = Uuid, Path, description = "Share link ID"),
+    ),
+    responses(
+        (status = 204, description = "Share link revoked"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or share link not found", body = String),
+    ),
+)]
+pub async fn delete_share(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, share_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let share_id = match Uuid::parse_str(&share_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid share ID"),
+    };
+
+    match user_is_project_owner(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    }
+
+    match delete_share_from_db(&pool, share_id, project_id).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => HttpResponse::NotFound().json("Share link not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to revoke share link"),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/shared/{token}",
+    tag = "shares",
+    security(()),
+    params(("token" = String, Path, description = "Token of the share link")),
+    responses(
+        (status = 200, body = SharedProject),
+        (status = 404, description = "Share link not found or expired", body = String),
+    ),
+)]
+pub async fn get_shared_project(
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+) -> impl Responder {
+    let share = match get_active_share(&pool, &path.into_inner()).await {
+        Ok(Some(share)) => share,
+        Ok(None) => return HttpResponse::NotFound().json("Share link not found or expired"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch share link"),
+    };
+
+    let project = match get_project_by_id(&pool, share.project_id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => return HttpResponse::NotFound().json("Share link not found or expired"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    };
+
+    let categories = match crate::image_annotation_categories::get_project_image_annotation_categories(&pool, share.project_id).await {
+        Ok(categories) => categories,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotation categories"),
+    };
+
+    HttpResponse::Ok().json(SharedProject {
+        name: project.name,
+        description: project.description,
+        task_type: project.task_type,
+        categories,
+        expires_at: share.expires_at,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/shared/{token}/tasks",
+    tag = "shares",
+    security(()),
+    params(("token" = String, Path, description = "Token of the share link")),
+    responses(
+        (status = 200, body = SharedTasksListResponse),
+        (status = 404, description = "Share link not found or expired", body = String),
+    ),
+)]
+pub async fn list_shared_tasks(
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+) -> impl Responder {
+    let share = match get_active_share(&pool, &path.into_inner()).await {
+        Ok(Some(share)) => share,
+        Ok(None) => return HttpResponse::NotFound().json("Share link not found or expired"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch share link"),
+    };
+
+    let mut tasks = match get_shared_tasks(&pool, share.project_id).await {
+        Ok(tasks) => tasks,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch tasks"),
+    };
+
+    for task in &mut tasks {
+        if let Some(url) = &task.resource_url {
+            task.resolved_resource_url = crate::tasks::resolve_storage_url(&pool, share.project_id, url).await;
+        }
+    }
+
+    HttpResponse::Ok().json(SharedTasksListResponse { tasks })
+}
+
+#[utoipa::path(
+    get,
+    path = "/shared/{token}/tasks/{task_id}/annotations",
+    tag = "shares",
+    security(()),
+    params(
+        ("token" = String, Path, description = "Token of the share link"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    responses(
+        (status = 200, description = "Boxes of the latest annotation of the task", body = AnnotationsListResponse),
+        (status = 404, description = "Share link or task not found", body = String),
+    ),
+)]
+pub async fn list_shared_annotations(
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+) -> impl Responder {
+    let (token, task_id_str) = path.into_inner();
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    let share = match get_active_share(&pool, &token).await {
+        Ok(Some(share)) => share,
+        Ok(None) => return HttpResponse::NotFound().json("Share link not found or expired"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch share link"),
+    };
+
+    match task_belongs_to_project(&pool, task_id, share.project_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Task not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch task"),
+    }
+
+    // Readers see the current state of each task, not its history
+    let annotations: Vec<AnnotationWithCategory> = match crate::annotations::get_task_annotations(&pool, task_id, true, None).await {
+        Ok(annotations) => annotations,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotations"),
+    };
+
+    HttpResponse::Ok().json(AnnotationsListResponse { annotations })
+}
+
+#[utoipa::path(
+    get,
+    path = "/shared/{token}/export/coco",
+    tag = "shares",
+    security(()),
+    params(
+        ("token" = String, Path, description = "Token of the share link"),
+        SharedExportQuery,
+    ),
+    responses(
+        (status = 200, description = "COCO annotation file, or a ZIP with the images as well when `include_images` is set", body = crate::coco::types::CocoExport, content_type = "application/json"),
+        (status = 404, description = "Share link not found or expired", body = String),
+    ),
+)]
+pub async fn export_shared_coco(
+    path: web::Path<String>,
+    query: web::Query<SharedExportQuery>,
+    pool: web::Data<Pool<Postgres>>,
+) -> impl Responder {
+    let share = match get_active_share(&pool, &path.into_inner()).await {
+        Ok(Some(share)) => share,
+        Ok(None) => return HttpResponse::NotFound().json("Share link not found or expired"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch share link"),
+    };
+
+    let contributor = share.name.unwrap_or_else(|| "Shared dataset".to_string());
+    // Share links are public, they never carry the annotators' emails
+    let options = crate::coco::export::CocoExportOptions {
+        include_images: query.include_images.unwrap_or(false),
+        ..Default::default()
+    };
+    crate::coco::export::coco_export_response(&pool, share.project_id, contributor, &options).await
+}
+
+/// 64 hex characters from two random UUIDs, 244 random bits
+fn generate_share_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+async fn create_share_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    name: Option<&str>,
+    created_by: Uuid,
+    expires_at: DateTime<Utc>,
+) -> Result<ProjectShare, sqlx::Error> {
+    sqlx::query_as::<_, ProjectShare>(
+        r#"
+        INSERT INTO project_shares (id, project_id, token, name, created_by, expires_at, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, NOW())
+        RETURNING id, project_id, token, name, created_by, expires_at, created_at
+        "#
+    )
+    .bind(Uuid::new_v4())
+    .bind(project_id)
+    .bind(generate_share_token())
+    .bind(name)
+    .bind(created_by)
+    .bind(expires_at)
+    .fetch_one(pool)
+    .await
+}
+
+async fn get_project_shares(pool: &Pool<Postgres>, project_id: Uuid) -> Result<Vec<ProjectShare>, sqlx::Error> {
+    sqlx::query_as::<_, ProjectShare>(
+        r#"
+        SELECT id, project_id, token, name, created_by, expires_at, created_at
+        FROM project_shares
+        WHERE project_id = $1
+        ORDER BY created_at DESC
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn delete_share_from_db(pool: &Pool<Postgres>, share_id: Uuid, project_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM project_shares WHERE id = $1 AND project_id = $2")
+        .bind(share_id)
+        .bind(project_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+async fn get_active_share(pool: &Pool<Postgres>, token: &str) -> Result<Option<ActiveShare>, sqlx::Error> {
+    sqlx::query_as::<_, ActiveShare>(
+        "SELECT project_id, name, expires_at FROM project_shares WHERE token = $1 AND expires_at > NOW()"
+    )
+    .bind(token)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn get_shared_tasks(pool: &Pool<Postgres>, project_id: Uuid) -> Result<Vec<SharedTask>, sqlx::Error> {
+    sqlx::query_as::<_, SharedTask>(
+        r#"
+        SELECT id, name, resource_url, media_type, split
+        FROM tasks
+        WHERE project_id = $1
+        ORDER BY created_at ASC
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_project_by_id(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Option<crate::projects::Project>, sqlx::Error> {
+    sqlx::query_as::<_, crate::projects::Project>(
+        "SELECT id, name, description, storage_config, owner_id, task_type, created_at, updated_at FROM projects WHERE id = $1"
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn task_belongs_to_project(pool: &Pool<Postgres>, task_id: Uuid, project_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM tasks WHERE id = $1 AND project_id = $2)"
+    )
+    .bind(task_id)
+    .bind(project_id)
+    .fetch_one(pool)
+    .await
+}
+
+async fn user_is_project_owner(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM project_members pm
+            INNER JOIN projects p ON pm.project_id = p.id
+            WHERE p.id = $1 AND pm.user_id = $2 AND (pm.role = 'owner' OR p.owner_id = $2)
+        )
+        "#
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    #[actix_web::test]
+    async fn test_generate_share_token() {
+        let token = generate_share_token();
+        assert_eq!(token.len(), 64);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token, generate_share_token());
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_share_link_grants_read_only_access() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Benchmark", Some("Public benchmark"), None, user.id).await.unwrap();
+        crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "person", None, None, None, Some(1)).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "image_001.jpg", Some("https://example.com/image_001.jpg")).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{id}/shares", web::post().to(create_share))
+                .route("/projects/{id}/shares", web::get().to(list_shares))
+                .route("/projects/{id}/shares/{share_id}", web::delete().to(delete_share))
+                .route("/shared/{token}", web::get().to(get_shared_project))
+                .route("/shared/{token}/tasks", web::get().to(list_shared_tasks))
+                .route("/shared/{token}/tasks/{task_id}/annotations", web::get().to(list_shared_annotations))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/shares", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(CreateShareRequest { name: Some("Reviewers".to_string()), expires_in_days: Some(7) })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let share: serde_json::Value = test::read_body_json(resp).await;
+        let share_token = share["token"].as_str().unwrap().to_string();
+        let share_id = share["id"].as_str().unwrap().to_string();
+
+        // No account needed to read through the link
+        let req = test::TestRequest::get().uri(&format!("/shared/{}", share_token)).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let shared: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(shared["name"], "Benchmark");
+        assert_eq!(shared["categories"][0]["name"], "person");
+
+        let req = test::TestRequest::get().uri(&format!("/shared/{}/tasks", share_token)).to_request();
+        let resp = test::call_service(&app, req).await;
+        let tasks: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(tasks["tasks"][0]["name"], "image_001.jpg");
+        assert_eq!(tasks["tasks"][0]["resolved_resource_url"], "https://example.com/image_001.jpg");
+        assert!(tasks["tasks"][0].get("status").is_none());
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/shared/{}/tasks/{}/annotations", share_token, task.id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        // Tasks of other projects stay hidden
+        let other_project = crate::projects::create_project_in_db(&pool, "Private", None, None, user.id).await.unwrap();
+        let other_task = crate::tasks::create_task_in_db(&pool, other_project.id, "secret.jpg", None).await.unwrap();
+        let req = test::TestRequest::get()
+            .uri(&format!("/shared/{}/tasks/{}/annotations", share_token, other_task.id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+
+        // Revoked links stop working
+        let req = test::TestRequest::delete()
+            .uri(&format!("/projects/{}/shares/{}", project.id, share_id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 204);
+
+        let req = test::TestRequest::get().uri(&format!("/shared/{}", share_token)).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_expired_share_link_is_rejected() {
+        let pool = test_utils::setup_test_db().await;
+        let (user_id, project_id) = test_utils::setup_test_user_and_project(&pool).await;
+
+        let share = create_share_in_db(&pool, project_id, None, user_id, Utc::now() - Duration::minutes(1)).await.unwrap();
+        assert!(get_active_share(&pool, &share.token).await.unwrap().is_none());
+
+        let share = create_share_in_db(&pool, project_id, None, user_id, Utc::now() + Duration::days(1)).await.unwrap();
+        assert_eq!(get_active_share(&pool, &share.token).await.unwrap().unwrap().project_id, project_id);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_create_share_validation() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+        let project = crate::projects::create_project_in_db(&pool, "Benchmark", None, None, user.id).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{id}/shares", web::post().to(create_share))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/shares", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(CreateShareRequest { name: None, expires_in_days: Some(0) })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/shares", Uuid::new_v4()))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(CreateShareRequest { name: None, expires_in_days: None })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+    }
+}
@@ -0,0 +1,209 @@
+// Multi-slice images: multi-page TIFFs and DICOM series are split into one PNG per slice,
+// stored like video frames so the slices are annotated through the frame index of boxes.
+
+use image::{DynamicImage, GenericImageView, GrayImage, ImageFormat, RgbImage, RgbaImage};
+use std::io::Cursor;
+use std::path::Path;
+
+use crate::storage::StorageProvider;
+use crate::video::{ExtractedFrame, FRAMES_DIR_SUFFIX};
+
+pub const MEDIA_TYPE_VOLUME: &str = "volume";
+
+const TIFF_EXTENSIONS: [&str; 2] = ["tif", "tiff"];
+const DICOM_EXTENSIONS: [&str; 2] = ["dcm", "dicom"];
+
+/// Upper bound of slices kept from one file, matching the frame limit of videos.
+pub const MAX_SLICES: usize = crate::video::MAX_FRAMES;
+
+fn has_extension(file_key: &str, extensions: &[&str]) -> bool {
+    Path::new(file_key)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.iter().any(|known| known.eq_ignore_ascii_case(ext)))
+}
+
+pub fn is_dicom_file(file_key: &str) -> bool {
+    has_extension(file_key, &DICOM_EXTENSIONS)
+}
+
+/// Files that may hold more than one slice. Single-page TIFFs still become plain image tasks.
+pub fn is_slice_stack_file(file_key: &str) -> bool {
+    is_dicom_file(file_key) || has_extension(file_key, &TIFF_EXTENSIONS)
+}
+
+pub fn slice_storage_key(file_key: &str, slice_index: i32) -> String {
+    format!("{}{}{:06}.png", file_key, FRAMES_DIR_SUFFIX, slice_index)
+}
+
+/// Decodes the slices of the file and uploads them as PNGs next to it. Returns `None` for
+/// single-page TIFFs, which browsers and the viewer can show as they are.
+pub async fn extract_slices(
+    storage_provider: &dyn StorageProvider,
+    file_key: &str,
+) -> Result<Option<(Vec<ExtractedFrame>, Option<(u32, u32)>)>, String> {
+    let data = storage_provider.download(file_key)
+        .await
+        .map_err(|e| format!("Failed to download file: {}", e))?;
+
+    let is_dicom = is_dicom_file(file_key);
+    let slices = tokio::task::spawn_blocking(move || {
+        if is_dicom {
+            decode_dicom_slices(&data)
+        } else {
+            decode_tiff_pages(&data)
+        }
+    })
+    .await
+    .map_err(|e| format!("Slice decoding panicked: {}", e))??;
+
+    if slices.is_empty() {
+        return Err("File contains no slices".to_string());
+    }
+    if !is_dicom && slices.len() == 1 {
+        return Ok(None);
+    }
+
+    let dimensions = slices.first().map(|slice| slice.dimensions());
+    let mut frames = Vec::with_capacity(slices.len());
+    for (index, slice) in slices.iter().enumerate() {
+        let mut png = Cursor::new(Vec::new());
+        slice.write_to(&mut png, ImageFormat::Png)
+            .map_err(|e| format!("Failed to encode slice {}: {}", index, e))?;
+
+        let frame_index = index as i32;
+        let storage_key = slice_storage_key(file_key, frame_index);
+        storage_provider.upload(&storage_key, png.get_ref(), Some("image/png"))
+            .await
+            .map_err(|e| format!("Failed to upload slice {}: {}", frame_index, e))?;
+
+        // Slices have no time, so the timestamp stays at zero
+        frames.push(ExtractedFrame {
+            frame_index,
+            storage_key,
+            timestamp_ms: 0,
+        });
+    }
+
+    Ok(Some((frames, dimensions)))
+}
+
+/// Reads every page of a TIFF. 16-bit grayscale pages are stretched to 8 bits over their
+/// own value range, which is the usual default window for scans.
+pub fn decode_tiff_pages(data: &[u8]) -> Result<Vec<DynamicImage>, String> {
+    use tiff::ColorType;
+    use tiff::decoder::{Decoder, DecodingResult};
+
+    let mut decoder = Decoder::new(Cursor::new(data))
+        .map_err(|e| format!("Failed to read TIFF: {}", e))?;
+
+    let mut pages = Vec::new();
+    loop {
+        let (width, height) = decoder.dimensions().map_err(|e| format!("Failed to read TIFF page: {}", e))?;
+        let color_type = decoder.colortype().map_err(|e| format!("Failed to read TIFF page: {}", e))?;
+        let page = decoder.read_image().map_err(|e| format!("Failed to read TIFF page: {}", e))?;
+
+        let image = match (color_type, page) {
+            (ColorType::Gray(8), DecodingResult::U8(pixels)) => {
+                GrayImage::from_raw(width, height, pixels).map(DynamicImage::ImageLuma8)
+            }
+            (ColorType::Gray(16), DecodingResult::U16(pixels)) => {
+                GrayImage::from_raw(width, height, stretch_to_u8(&pixels)).map(DynamicImage::ImageLuma8)
+            }
+            (ColorType::RGB(8), DecodingResult::U8(pixels)) => {
+                RgbImage::from_raw(width, height, pixels).map(DynamicImage::ImageRgb8)
+            }
+            (ColorType::RGBA(8), DecodingResult::U8(pixels)) => {
+                RgbaImage::from_raw(width, height, pixels).map(DynamicImage::ImageRgba8)
+            }
+            (color_type, _) => return Err(format!("Unsupported TIFF color type {:?}", color_type)),
+        };
+        pages.push(image.ok_or("TIFF page size does not match its pixel data")?);
+
+        if pages.len() >= MAX_SLICES || !decoder.more_images() {
+            break;
+        }
+        decoder.next_image().map_err(|e| format!("Failed to read TIFF page: {}", e))?;
+    }
+
+    Ok(pages)
+}
+
+/// Maps the range between the smallest and largest value onto 0..=255.
+pub fn stretch_to_u8(values: &[u16]) -> Vec<u8> {
+    let (Some(&min), Some(&max)) = (values.iter().min(), values.iter().max()) else {
+        return Vec::new();
+    };
+    let range = (max - min).max(1) as f32;
+    values.iter()
+        .map(|&value| ((value - min) as f32 / range * 255.0).round() as u8)
+        .collect()
+}
+
+/// Decodes every frame of a DICOM file with the window stored in the file, falling back to
+/// the value range of the pixels.
+#[cfg(feature = "dicom")]
+pub fn decode_dicom_slices(data: &[u8]) -> Result<Vec<DynamicImage>, String> {
+    use dicom_pixeldata::PixelDecoder;
+
+    // The reader of dicom-object expects a file on disk to handle the preamble
+    let path = std::env::temp_dir().join(format!("fast-tag-dicom-{}.dcm", uuid::Uuid::new_v4()));
+    std::fs::write(&path, data).map_err(|e| format!("Failed to write DICOM file: {}", e))?;
+    let object = dicom_object::open_file(&path);
+    let _ = std::fs::remove_file(&path);
+
+    let object = object.map_err(|e| format!("Failed to read DICOM file: {}", e))?;
+    let pixel_data = object.decode_pixel_data()
+        .map_err(|e| format!("Failed to decode DICOM pixel data: {}", e))?;
+
+    let frame_count = (pixel_data.number_of_frames() as usize).min(MAX_SLICES);
+    (0..frame_count)
+        .map(|frame| {
+            pixel_data.to_dynamic_image(frame as u32)
+                .map_err(|e| format!("Failed to convert DICOM frame {}: {}", frame, e))
+        })
+        .collect()
+}
+
+/// Fallback used when the server was built without the `dicom` feature.
+#[cfg(not(feature = "dicom"))]
+pub fn decode_dicom_slices(_data: &[u8]) -> Result<Vec<DynamicImage>, String> {
+    Err("DICOM support is not enabled on this server".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slice_stack_files() {
+        assert!(is_slice_stack_file("scans/ct.dcm"));
+        assert!(is_slice_stack_file("scans/stack.TIF"));
+        assert!(!is_slice_stack_file("images/photo.png"));
+        assert_eq!(slice_storage_key("scans/ct.dcm", 3), "scans/ct.dcm.frames/000003.png");
+        assert!(crate::video::is_extracted_frame(&slice_storage_key("scans/ct.dcm", 3)));
+    }
+
+    #[test]
+    fn test_stretch_to_u8() {
+        assert_eq!(stretch_to_u8(&[1000, 1500, 2000]), vec![0, 128, 255]);
+        // A flat slice must not divide by zero
+        assert_eq!(stretch_to_u8(&[7, 7]), vec![0, 0]);
+        assert!(stretch_to_u8(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_decode_tiff_pages() {
+        let mut data = Cursor::new(Vec::new());
+        {
+            let mut encoder = tiff::encoder::TiffEncoder::new(&mut data).unwrap();
+            encoder.write_image::<tiff::encoder::colortype::Gray8>(2, 1, &[0, 255]).unwrap();
+            encoder.write_image::<tiff::encoder::colortype::Gray16>(2, 1, &[100, 300]).unwrap();
+        }
+
+        let pages = decode_tiff_pages(data.get_ref()).unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].dimensions(), (2, 1));
+        assert_eq!(pages[1].to_luma8().into_raw(), vec![0, 255]);
+    }
+}
@@ -0,0 +1,953 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read, Write};
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use utoipa::ToSchema;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::auth::{JwtManager, Claims};
+use crate::coco::types::CocoRle;
+use crate::storage::factory::create_storage_provider_from_project;
+
+/// Folder of the project storage the snapshots of a project go into, one subfolder per project
+/// since projects can share a bucket
+pub const SNAPSHOT_PREFIX: &str = "fast-tag-snapshots";
+/// File inside a snapshot archive holding the annotations
+const SNAPSHOT_FILE: &str = "annotations.json";
+/// Layout of the snapshot file, snapshots of another layout are not restored
+const SNAPSHOT_VERSION: u32 = 1;
+/// Seconds between two looks at which projects are due for a snapshot
+const CHECK_INTERVAL_SECS: u64 = 15 * 60;
+const KEY_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// How often the annotations of every project with storage are snapshotted, read from the
+/// environment
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotConfig {
+    /// Hours between two snapshots of a project, snapshots are off when `None`
+    pub interval_hours: Option<u32>,
+    /// Newest snapshots kept per project, older ones are deleted
+    pub retention: usize,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            interval_hours: None,
+            retention: 7,
+        }
+    }
+}
+
+impl SnapshotConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.trim().is_empty());
+
+        let interval_hours = match var("SNAPSHOT_INTERVAL_HOURS") {
+            Some(hours) => match hours.trim().parse::<u32>() {
+                Ok(0) => None,
+                Ok(hours) => Some(hours),
+                Err(_) => return Err(format!("SNAPSHOT_INTERVAL_HOURS must be a number, got {}", hours)),
+            },
+            None => None,
+        };
+        let retention = match var("SNAPSHOT_RETENTION") {
+            Some(count) => match count.trim().parse::<usize>() {
+                Ok(count) if count > 0 => count,
+                _ => return Err(format!("SNAPSHOT_RETENTION must be a positive number, got {}", count)),
+            },
+            None => Self::default().retention,
+        };
+
+        Ok(Self { interval_hours, retention })
+    }
+}
+
+/// The latest annotation of every annotated task of a project, as written to storage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnnotationSnapshot {
+    pub version: u32,
+    pub project_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    /// Categories of the project at the time, to find the boxes' categories by name again
+    pub categories: Vec<SnapshotCategory>,
+    pub tasks: Vec<SnapshotTask>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SnapshotCategory {
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotTask {
+    pub task_id: Uuid,
+    pub task_name: String,
+    pub annotation_id: Uuid,
+    pub annotated_by: Option<Uuid>,
+    pub annotated_at: DateTime<Utc>,
+    pub metadata: serde_json::Value,
+    pub boxes: Vec<SnapshotBox>,
+    /// Whole-image labels of classification projects
+    pub labels: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotBox {
+    pub category_id: Option<Uuid>,
+    pub bbox: Vec<f64>,
+    pub iscrowd: bool,
+    pub is_prediction: bool,
+    pub confidence: Option<f64>,
+    pub attributes: serde_json::Value,
+    pub rotation: f64,
+    pub frame_index: Option<i32>,
+    pub track_id: Option<Uuid>,
+    pub is_interpolated: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mask: Option<CocoRle>,
+}
+
+/// Snapshot file in the project storage
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SnapshotInfo {
+    pub key: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SnapshotsListResponse {
+    pub snapshots: Vec<SnapshotInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RestoreSnapshotRequest {
+    /// Key of one of the project's snapshots, as listed
+    pub key: String,
+}
+
+/// What a restore did
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RestoreReport {
+    /// Tasks that got the snapshot's annotation back as their new latest one
+    pub restored_tasks: usize,
+    /// Tasks whose latest annotation is still the one in the snapshot
+    pub unchanged_tasks: usize,
+    /// Tasks of the snapshot that were deleted since
+    pub missing_tasks: usize,
+    /// Boxes and labels left out because their category is gone
+    pub skipped_boxes: usize,
+}
+
+#[derive(sqlx::FromRow)]
+struct LatestAnnotationRow {
+    id: Uuid,
+    task_id: Uuid,
+    task_name: String,
+    metadata: serde_json::Value,
+    annotated_by: Option<Uuid>,
+    annotated_at: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow)]
+struct SnapshotBoxRow {
+    annotation_id: Uuid,
+    category_id: Option<Uuid>,
+    bbox: Vec<f64>,
+    iscrowd: bool,
+    is_prediction: bool,
+    confidence: Option<f64>,
+    attributes: serde_json::Value,
+    rotation: f64,
+    frame_index: Option<i32>,
+    track_id: Option<Uuid>,
+    is_interpolated: bool,
+    mask: Option<serde_json::Value>,
+}
+
+/// The project as it is now, what a snapshot is restored against
+#[derive(Debug, Default)]
+pub struct RestoreTarget {
+    pub category_ids: HashSet<Uuid>,
+    pub categories_by_name: HashMap<String, Uuid>,
+    /// Latest annotation of every task, `None` for tasks without one
+    pub latest_annotations: HashMap<Uuid, Option<Uuid>>,
+}
+
+/// Annotation to save on a task, its categories those of the project now
+#[derive(Debug, PartialEq)]
+pub struct RestoredTask {
+    pub task_id: Uuid,
+    pub source: Uuid,
+    pub metadata: serde_json::Value,
+    pub boxes: Vec<SnapshotBox>,
+    pub labels: Vec<Uuid>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{id}/snapshots",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    responses(
+        (status = 200, description = "Snapshots in the project storage, newest first", body = SnapshotsListResponse),
+        (status = 400, description = "The project has no storage", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found, or the user is not an owner or admin of it", body = String),
+    ),
+)]
+pub async fn list_snapshots(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Snapshots are read with the storage credentials
+    match crate::projects::user_can_manage_storage(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check project access"),
+    }
+
+    let project = match get_project_by_id(&pool, project_id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    };
+    if project.storage_config.is_none() {
+        return HttpResponse::BadRequest().json("Project has no storage configuration");
+    }
+
+    let storage = match create_storage_provider_from_project(&project).await {
+        Ok(storage) => storage,
+        Err(e) => return HttpResponse::InternalServerError().json(format!("Storage error: {}", e)),
+    };
+    match storage.list_objects(Some(&snapshot_prefix(project_id))).await {
+        Ok(keys) => HttpResponse::Ok().json(SnapshotsListResponse { snapshots: snapshots_newest_first(project_id, keys) }),
+        Err(e) => HttpResponse::InternalServerError().json(format!("Failed to list snapshots: {}", e)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{id}/snapshots/restore",
+    tag = "projects",
+    params(("id" = Uuid, Path, description = "Project ID")),
+    request_body = RestoreSnapshotRequest,
+    responses(
+        (status = 200, description = "The snapshot's annotations are the latest ones of their tasks again", body = RestoreReport),
+        (status = 400, description = "Not a snapshot of the project, or the project has no storage", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or snapshot not found, or the user is not an owner or admin of it", body = String),
+    ),
+)]
+pub async fn restore_snapshot(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<RestoreSnapshotRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Only keys the snapshot job writes, so no other file of the storage is read
+    if snapshot_time(project_id, &payload.key).is_none() {
+        return HttpResponse::BadRequest().json("Not a snapshot of this project");
+    }
+
+    // A restore replaces the work of every member
+    match crate::projects::user_can_manage_storage(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check project access"),
+    }
+
+    let project = match get_project_by_id(&pool, project_id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    };
+    if project.storage_config.is_none() {
+        return HttpResponse::BadRequest().json("Project has no storage configuration");
+    }
+
+    let storage = match create_storage_provider_from_project(&project).await {
+        Ok(storage) => storage,
+        Err(e) => return HttpResponse::InternalServerError().json(format!("Storage error: {}", e)),
+    };
+    match storage.exists(&payload.key).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Snapshot not found"),
+        Err(e) => return HttpResponse::InternalServerError().json(format!("Storage error: {}", e)),
+    }
+    let data = match storage.download(&payload.key).await {
+        Ok(data) => data,
+        Err(e) => return HttpResponse::InternalServerError().json(format!("Failed to read snapshot: {}", e)),
+    };
+    let snapshot = match read_snapshot(&data) {
+        Ok(snapshot) if snapshot.project_id == project_id => snapshot,
+        Ok(_) => return HttpResponse::BadRequest().json("Not a snapshot of this project"),
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+
+    match restore_snapshot_in_db(&pool, project_id, &snapshot, &payload.key, user_id).await {
+        Ok(report) => HttpResponse::Ok().json(report),
+        Err(e) => {
+            eprintln!("Failed to restore snapshot {}: {}", payload.key, e);
+            HttpResponse::InternalServerError().json("Failed to restore snapshot")
+        }
+    }
+}
+
+/// Snapshots every project with storage whose annotations changed since its last snapshot
+/// once `interval_hours` passed, and deletes the snapshots past the retention. Runs until the
+/// server stops; the newest snapshot in storage tells when a project was last done, so
+/// restarts don't snapshot early.
+pub async fn run_snapshot_job(pool: Pool<Postgres>, config: SnapshotConfig) {
+    let Some(interval_hours) = config.interval_hours else {
+        return;
+    };
+    let interval = chrono::Duration::hours(interval_hours as i64);
+    let mut ticker = tokio::time::interval(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS));
+
+    loop {
+        ticker.tick().await;
+        let projects = match get_projects_with_storage(&pool).await {
+            Ok(projects) => projects,
+            Err(e) => {
+                eprintln!("Failed to list projects to snapshot: {}", e);
+                continue;
+            }
+        };
+        for project in projects {
+            if let Err(e) = snapshot_project_if_due(&pool, &project, interval, config.retention).await {
+                eprintln!("Failed to snapshot project {}: {}", project.id, e);
+            }
+        }
+    }
+}
+
+async fn snapshot_project_if_due(
+    pool: &Pool<Postgres>,
+    project: &crate::projects::Project,
+    interval: chrono::Duration,
+    retention: usize,
+) -> Result<(), String> {
+    let storage = create_storage_provider_from_project(project).await.map_err(|e| e.to_string())?;
+    let keys = storage
+        .list_objects(Some(&snapshot_prefix(project.id)))
+        .await
+        .map_err(|e| e.to_string())?;
+    let snapshots = snapshots_newest_first(project.id, keys);
+
+    let last_change = get_last_annotation_time(pool, project.id).await.map_err(|e| e.to_string())?;
+    let now = Utc::now();
+    if snapshot_is_due(snapshots.first().map(|snapshot| snapshot.created_at), last_change, now, interval) {
+        let snapshot = build_snapshot(pool, project.id, now).await.map_err(|e| e.to_string())?;
+        let data = write_snapshot(&snapshot).map_err(|e| e.to_string())?;
+        storage
+            .upload(&snapshot_key(project.id, now), &data, Some("application/zip"))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        // Only prune once the new snapshot is safe
+        for old in snapshots.iter().skip(retention.saturating_sub(1)) {
+            if let Err(e) = storage.delete(&old.key).await {
+                eprintln!("Failed to delete old snapshot {}: {}", old.key, e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A project is snapshotted when it has annotations, its last snapshot is at least `interval`
+/// old and annotations were saved after it
+pub fn snapshot_is_due(
+    last_snapshot: Option<DateTime<Utc>>,
+    last_change: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    interval: chrono::Duration,
+) -> bool {
+    let Some(last_change) = last_change else {
+        return false;
+    };
+    match last_snapshot {
+        Some(last_snapshot) => now - last_snapshot >= interval && last_change > last_snapshot,
+        None => true,
+    }
+}
+
+pub fn snapshot_prefix(project_id: Uuid) -> String {
+    format!("{}/{}/", SNAPSHOT_PREFIX, project_id)
+}
+
+pub fn snapshot_key(project_id: Uuid, created_at: DateTime<Utc>) -> String {
+    format!("{}{}.zip", snapshot_prefix(project_id), created_at.format(KEY_TIME_FORMAT))
+}
+
+/// When the snapshot at `key` was taken, `None` when `key` isn't a snapshot of the project
+pub fn snapshot_time(project_id: Uuid, key: &str) -> Option<DateTime<Utc>> {
+    let stem = key.strip_prefix(&snapshot_prefix(project_id))?.strip_suffix(".zip")?;
+    NaiveDateTime::parse_from_str(stem, KEY_TIME_FORMAT).ok().map(|time| time.and_utc())
+}
+
+/// The snapshots among `keys`, other files of the folder left out
+pub fn snapshots_newest_first(project_id: Uuid, keys: Vec<String>) -> Vec<SnapshotInfo> {
+    let mut snapshots: Vec<SnapshotInfo> = keys
+        .into_iter()
+        .filter_map(|key| snapshot_time(project_id, &key).map(|created_at| SnapshotInfo { key, created_at }))
+        .collect();
+    snapshots.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    snapshots
+}
+
+/// Zips the snapshot as JSON
+pub fn write_snapshot(snapshot: &AnnotationSnapshot) -> Result<Vec<u8>, String> {
+    let json = serde_json::to_vec(snapshot).map_err(|e| format!("Failed to serialize snapshot: {}", e))?;
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file(SNAPSHOT_FILE, options).map_err(|e| e.to_string())?;
+    zip.write_all(&json).map_err(|e| e.to_string())?;
+    Ok(zip.finish().map_err(|e| e.to_string())?.into_inner())
+}
+
+pub fn read_snapshot(data: &[u8]) -> Result<AnnotationSnapshot, String> {
+    let mut archive = ZipArchive::new(Cursor::new(data)).map_err(|e| format!("Invalid snapshot archive: {}", e))?;
+    let mut file = archive
+        .by_name(SNAPSHOT_FILE)
+        .map_err(|_| format!("Snapshot archive has no {}", SNAPSHOT_FILE))?;
+    let mut json = Vec::new();
+    file.read_to_end(&mut json).map_err(|e| format!("Invalid snapshot archive: {}", e))?;
+
+    let snapshot: AnnotationSnapshot = serde_json::from_slice(&json).map_err(|e| format!("Invalid snapshot: {}", e))?;
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(format!("Unsupported snapshot version {}", snapshot.version));
+    }
+    Ok(snapshot)
+}
+
+/// What restoring `snapshot` onto the project does. Tasks whose latest annotation is the
+/// snapshot's are left alone, deleted tasks are skipped. Categories are found by ID, then by
+/// name for categories that were recreated; boxes and labels of categories that are gone are
+/// left out.
+pub fn plan_restore(snapshot: &AnnotationSnapshot, target: &RestoreTarget) -> (Vec<RestoredTask>, RestoreReport) {
+    let snapshot_names: HashMap<Uuid, &str> = snapshot
+        .categories
+        .iter()
+        .map(|category| (category.id, category.name.as_str()))
+        .collect();
+    let resolve = |category_id: Uuid| -> Option<Uuid> {
+        if target.category_ids.contains(&category_id) {
+            return Some(category_id);
+        }
+        snapshot_names
+            .get(&category_id)
+            .and_then(|name| target.categories_by_name.get(*name))
+            .copied()
+    };
+
+    let mut report = RestoreReport::default();
+    let mut restored = Vec::new();
+    for task in &snapshot.tasks {
+        let Some(latest) = target.latest_annotations.get(&task.task_id) else {
+            report.missing_tasks += 1;
+            continue;
+        };
+        if *latest == Some(task.annotation_id) {
+            report.unchanged_tasks += 1;
+            continue;
+        }
+
+        let mut boxes = Vec::new();
+        for snapshot_box in &task.boxes {
+            match snapshot_box.category_id.and_then(resolve) {
+                Some(category_id) => boxes.push(SnapshotBox { category_id: Some(category_id), ..snapshot_box.clone() }),
+                None => report.skipped_boxes += 1,
+            }
+        }
+        let mut labels = Vec::new();
+        for label in &task.labels {
+            match resolve(*label) {
+                Some(category_id) => labels.push(category_id),
+                None => report.skipped_boxes += 1,
+            }
+        }
+
+        report.restored_tasks += 1;
+        restored.push(RestoredTask {
+            task_id: task.task_id,
+            source: task.annotation_id,
+            metadata: task.metadata.clone(),
+            boxes,
+            labels,
+        });
+    }
+    (restored, report)
+}
+
+/// Reads the latest annotation of every annotated task of the project
+pub async fn build_snapshot(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    created_at: DateTime<Utc>,
+) -> Result<AnnotationSnapshot, sqlx::Error> {
+    let categories = sqlx::query_as::<_, SnapshotCategory>(
+        "SELECT id, name FROM image_annotation_categories WHERE project_id = $1 ORDER BY name"
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    let annotations = sqlx::query_as::<_, LatestAnnotationRow>(
+        r#"
+        SELECT DISTINCT ON (a.task_id)
+            a.id, a.task_id, t.name as task_name, COALESCE(a.metadata, '{}'::jsonb) as metadata, a.annotated_by,
+            COALESCE(a.annotated_at, a.created_at, NOW()) as annotated_at
+        FROM annotations a
+        INNER JOIN tasks t ON t.id = a.task_id
+        WHERE t.project_id = $1
+        ORDER BY a.task_id, a.created_at DESC
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+    let annotation_ids: Vec<Uuid> = annotations.iter().map(|annotation| annotation.id).collect();
+
+    let box_rows = sqlx::query_as::<_, SnapshotBoxRow>(
+        r#"
+        SELECT annotation_id, category_id, bbox, COALESCE(iscrowd, FALSE) as iscrowd, is_prediction, confidence,
+            attributes, rotation, frame_index, track_id, is_interpolated, mask
+        FROM image_annotations
+        WHERE annotation_id = ANY($1)
+        ORDER BY created_at
+        "#
+    )
+    .bind(&annotation_ids)
+    .fetch_all(pool)
+    .await?;
+    let mut boxes: HashMap<Uuid, Vec<SnapshotBox>> = HashMap::new();
+    for row in box_rows {
+        boxes.entry(row.annotation_id).or_default().push(SnapshotBox {
+            category_id: row.category_id,
+            bbox: row.bbox,
+            iscrowd: row.iscrowd,
+            is_prediction: row.is_prediction,
+            confidence: row.confidence,
+            attributes: row.attributes,
+            rotation: row.rotation,
+            frame_index: row.frame_index,
+            track_id: row.track_id,
+            is_interpolated: row.is_interpolated,
+            mask: row.mask.and_then(|mask| serde_json::from_value(mask).ok()),
+        });
+    }
+
+    let label_rows = sqlx::query_as::<_, (Uuid, Uuid)>(
+        "SELECT annotation_id, category_id FROM image_classifications WHERE annotation_id = ANY($1) ORDER BY created_at"
+    )
+    .bind(&annotation_ids)
+    .fetch_all(pool)
+    .await?;
+    let mut labels: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for (annotation_id, category_id) in label_rows {
+        labels.entry(annotation_id).or_default().push(category_id);
+    }
+
+    let tasks = annotations
+        .into_iter()
+        .map(|annotation| SnapshotTask {
+            task_id: annotation.task_id,
+            task_name: annotation.task_name,
+            annotation_id: annotation.id,
+            annotated_by: annotation.annotated_by,
+            annotated_at: annotation.annotated_at,
+            metadata: annotation.metadata,
+            boxes: boxes.remove(&annotation.id).unwrap_or_default(),
+            labels: labels.remove(&annotation.id).unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(AnnotationSnapshot {
+        version: SNAPSHOT_VERSION,
+        project_id,
+        created_at,
+        categories,
+        tasks,
+    })
+}
+
+/// Saves the snapshot's annotations as new latest annotations of their tasks, by `user_id`,
+/// keeping the annotations made since in the history. Everything is restored or nothing.
+pub async fn restore_snapshot_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    snapshot: &AnnotationSnapshot,
+    key: &str,
+    user_id: Uuid,
+) -> Result<RestoreReport, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let categories = sqlx::query_as::<_, SnapshotCategory>(
+        "SELECT id, name FROM image_annotation_categories WHERE project_id = $1"
+    )
+    .bind(project_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    let latest_annotations = sqlx::query_as::<_, (Uuid, Option<Uuid>)>(
+        r#"
+        SELECT t.id, (SELECT a.id FROM annotations a WHERE a.task_id = t.id ORDER BY a.created_at DESC LIMIT 1)
+        FROM tasks t
+        WHERE t.project_id = $1
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let target = RestoreTarget {
+        category_ids: categories.iter().map(|category| category.id).collect(),
+        categories_by_name: categories.into_iter().map(|category| (category.name, category.id)).collect(),
+        latest_annotations: latest_annotations.into_iter().collect(),
+    };
+    let (tasks, report) = plan_restore(snapshot, &target);
+
+    for task in tasks {
+        let annotation_id = Uuid::new_v4();
+        let now = Utc::now();
+        let mut metadata = match task.metadata {
+            serde_json::Value::Object(metadata) => metadata,
+            _ => serde_json::Map::new(),
+        };
+        metadata.insert(
+            "restored_from".to_string(),
+            serde_json::json!({ "snapshot": key, "annotation_id": task.source }),
+        );
+
+        sqlx::query(
+            r#"
+            INSERT INTO annotations (id, task_id, metadata, annotated_by, annotated_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $5, $5)
+            "#
+        )
+        .bind(annotation_id)
+        .bind(task.task_id)
+        .bind(serde_json::Value::Object(metadata))
+        .bind(user_id)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        for restored_box in &task.boxes {
+            sqlx::query(
+                r#"
+                INSERT INTO image_annotations (id, annotation_id, category_id, bbox, area, iscrowd, image_metadata, is_prediction, confidence, attributes, rotation, frame_index, track_id, is_interpolated, mask, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, '{}'::jsonb, $7, $8, $9, $10, $11, $12, $13, $14, $15, $15)
+                "#
+            )
+            .bind(Uuid::new_v4())
+            .bind(annotation_id)
+            .bind(restored_box.category_id)
+            .bind(&restored_box.bbox)
+            .bind(crate::annotations::stored_area(&restored_box.bbox, restored_box.mask.as_ref()))
+            .bind(restored_box.iscrowd)
+            .bind(restored_box.is_prediction)
+            .bind(restored_box.confidence)
+            .bind(&restored_box.attributes)
+            .bind(restored_box.rotation)
+            .bind(restored_box.frame_index)
+            .bind(restored_box.track_id)
+            .bind(restored_box.is_interpolated)
+            .bind(crate::annotations::stored_mask(restored_box.mask.as_ref()))
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if !task.labels.is_empty() {
+            sqlx::query(
+                r#"
+                INSERT INTO image_classifications (id, annotation_id, category_id, created_at)
+                SELECT gen_random_uuid(), $1, category_id, $3
+                FROM UNNEST($2::UUID[]) AS c(category_id)
+                "#
+            )
+            .bind(annotation_id)
+            .bind(&task.labels)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(report)
+}
+
+async fn get_last_annotation_time(pool: &Pool<Postgres>, project_id: Uuid) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+        r#"
+        SELECT MAX(a.created_at)
+        FROM annotations a
+        INNER JOIN tasks t ON t.id = a.task_id
+        WHERE t.project_id = $1
+        "#
+    )
+    .bind(project_id)
+    .fetch_one(pool)
+    .await
+}
+
+async fn get_projects_with_storage(pool: &Pool<Postgres>) -> Result<Vec<crate::projects::Project>, sqlx::Error> {
+    sqlx::query_as::<_, crate::projects::Project>(
+        "SELECT id, name, description, storage_config, owner_id, task_type, created_at, updated_at FROM projects WHERE storage_config IS NOT NULL"
+    )
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_project_by_id(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Option<crate::projects::Project>, sqlx::Error> {
+    sqlx::query_as::<_, crate::projects::Project>(
+        "SELECT id, name, description, storage_config, owner_id, task_type, created_at, updated_at FROM projects WHERE id = $1"
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::annotations::BoundingBox;
+    use crate::test_utils;
+    use chrono::{Duration, TimeZone};
+    use serial_test::serial;
+
+    fn snapshot_box(category_id: Uuid) -> SnapshotBox {
+        SnapshotBox {
+            category_id: Some(category_id),
+            bbox: vec![1.0, 2.0, 3.0, 4.0],
+            iscrowd: false,
+            is_prediction: false,
+            confidence: None,
+            attributes: serde_json::json!({}),
+            rotation: 0.0,
+            frame_index: None,
+            track_id: None,
+            is_interpolated: false,
+            mask: None,
+        }
+    }
+
+    fn snapshot_task(task_id: Uuid, annotation_id: Uuid, boxes: Vec<SnapshotBox>) -> SnapshotTask {
+        SnapshotTask {
+            task_id,
+            task_name: "task.jpg".to_string(),
+            annotation_id,
+            annotated_by: None,
+            annotated_at: Utc.with_ymd_and_hms(2026, 5, 1, 0, 0, 0).unwrap(),
+            metadata: serde_json::json!({}),
+            boxes,
+            labels: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_keys() {
+        let project_id = Uuid::new_v4();
+        let taken = Utc.with_ymd_and_hms(2026, 5, 1, 3, 4, 5).unwrap();
+        let key = snapshot_key(project_id, taken);
+        assert_eq!(key, format!("fast-tag-snapshots/{}/20260501T030405Z.zip", project_id));
+        assert_eq!(snapshot_time(project_id, &key), Some(taken));
+
+        // Other projects' snapshots and other files are not snapshots of the project
+        assert_eq!(snapshot_time(Uuid::new_v4(), &key), None);
+        assert_eq!(snapshot_time(project_id, &format!("{}notes.zip", snapshot_prefix(project_id))), None);
+        assert_eq!(snapshot_time(project_id, "images/20260501T030405Z.zip"), None);
+
+        let older = snapshot_key(project_id, taken - Duration::days(1));
+        let snapshots = snapshots_newest_first(project_id, vec![older.clone(), "readme.txt".to_string(), key.clone()]);
+        let keys: Vec<&str> = snapshots.iter().map(|snapshot| snapshot.key.as_str()).collect();
+        assert_eq!(keys, vec![key.as_str(), older.as_str()]);
+    }
+
+    #[test]
+    fn test_snapshot_is_due() {
+        let now = Utc.with_ymd_and_hms(2026, 5, 2, 12, 0, 0).unwrap();
+        let day = Duration::hours(24);
+
+        assert!(!snapshot_is_due(None, None, now, day));
+        assert!(snapshot_is_due(None, Some(now - Duration::days(30)), now, day));
+        // Too soon after the last one
+        assert!(!snapshot_is_due(Some(now - Duration::hours(2)), Some(now - Duration::hours(1)), now, day));
+        // Nothing saved since the last one
+        assert!(!snapshot_is_due(Some(now - Duration::days(2)), Some(now - Duration::days(3)), now, day));
+        assert!(snapshot_is_due(Some(now - Duration::days(2)), Some(now - Duration::hours(1)), now, day));
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let category = Uuid::new_v4();
+        let snapshot = AnnotationSnapshot {
+            version: SNAPSHOT_VERSION,
+            project_id: Uuid::new_v4(),
+            created_at: Utc.with_ymd_and_hms(2026, 5, 1, 0, 0, 0).unwrap(),
+            categories: vec![SnapshotCategory { id: category, name: "car".to_string() }],
+            tasks: vec![snapshot_task(Uuid::new_v4(), Uuid::new_v4(), vec![snapshot_box(category)])],
+        };
+
+        let data = write_snapshot(&snapshot).unwrap();
+        assert_eq!(read_snapshot(&data).unwrap(), snapshot);
+        assert!(read_snapshot(b"not a zip").is_err());
+
+        let future = AnnotationSnapshot { version: SNAPSHOT_VERSION + 1, ..snapshot };
+        assert!(read_snapshot(&write_snapshot(&future).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_plan_restore() {
+        let (car, person, recreated_person) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let (changed, unchanged, deleted) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let gone = Uuid::new_v4();
+        let kept_annotation = Uuid::new_v4();
+
+        let snapshot = AnnotationSnapshot {
+            version: SNAPSHOT_VERSION,
+            project_id: Uuid::new_v4(),
+            created_at: Utc.with_ymd_and_hms(2026, 5, 1, 0, 0, 0).unwrap(),
+            categories: vec![
+                SnapshotCategory { id: car, name: "car".to_string() },
+                SnapshotCategory { id: person, name: "person".to_string() },
+            ],
+            tasks: vec![
+                snapshot_task(changed, Uuid::new_v4(), vec![snapshot_box(car), snapshot_box(person), snapshot_box(gone)]),
+                snapshot_task(unchanged, kept_annotation, vec![snapshot_box(car)]),
+                snapshot_task(deleted, Uuid::new_v4(), vec![snapshot_box(car)]),
+            ],
+        };
+        let target = RestoreTarget {
+            category_ids: HashSet::from([car, recreated_person]),
+            categories_by_name: HashMap::from([("car".to_string(), car), ("person".to_string(), recreated_person)]),
+            latest_annotations: HashMap::from([(changed, Some(Uuid::new_v4())), (unchanged, Some(kept_annotation))]),
+        };
+
+        let (tasks, report) = plan_restore(&snapshot, &target);
+        assert_eq!(report, RestoreReport { restored_tasks: 1, unchanged_tasks: 1, missing_tasks: 1, skipped_boxes: 1 });
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].task_id, changed);
+        // The deleted person category was recreated under the same name
+        let categories: Vec<Option<Uuid>> = tasks[0].boxes.iter().map(|restored| restored.category_id).collect();
+        assert_eq!(categories, vec![Some(car), Some(recreated_person)]);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_restore_snapshot_brings_back_saved_boxes() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "car", None, None, None, None).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "street.jpg", None).await.unwrap();
+        let metadata = serde_json::json!({});
+        let bbox = |x: f64| BoundingBox {
+            category_id: category.id,
+            bbox: vec![x, 10.0, 20.0, 20.0],
+            area: None,
+            iscrowd: None,
+            is_prediction: None,
+            confidence: None,
+            attributes: None,
+            rotation: None,
+            frame_index: None,
+            track_id: None,
+            is_interpolated: None,
+            mask: None,
+        };
+        crate::annotations::create_annotation_in_db(&pool, task.id, &[bbox(1.0), bbox(50.0)], &metadata, user.id).await.unwrap();
+
+        let snapshot = build_snapshot(&pool, project.id, Utc::now()).await.unwrap();
+        assert_eq!(snapshot.tasks.len(), 1);
+        assert_eq!(snapshot.tasks[0].boxes.len(), 2);
+
+        // Someone saves over the boxes by mistake
+        crate::annotations::create_annotation_in_db(&pool, task.id, &[bbox(5.0)], &metadata, user.id).await.unwrap();
+
+        let report = restore_snapshot_in_db(&pool, project.id, &snapshot, "fast-tag-snapshots/key.zip", user.id).await.unwrap();
+        assert_eq!(report.restored_tasks, 1);
+
+        let latest = crate::annotations::get_task_annotations(&pool, task.id, true, None).await.unwrap();
+        let mut xs: Vec<f64> = latest.iter().map(|restored| restored.bbox[0]).collect();
+        xs.sort_by(f64::total_cmp);
+        assert_eq!(xs, vec![1.0, 50.0]);
+        assert_eq!(latest[0].metadata["restored_from"]["annotation_id"], serde_json::json!(snapshot.tasks[0].annotation_id));
+
+        // A snapshot of the restored state has nothing to restore
+        let again = build_snapshot(&pool, project.id, Utc::now()).await.unwrap();
+        let report = restore_snapshot_in_db(&pool, project.id, &again, "fast-tag-snapshots/key.zip", user.id).await.unwrap();
+        assert_eq!(report.unchanged_tasks, 1);
+    }
+}
@@ -0,0 +1,544 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::auth::{JwtManager, Claims};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProjectStatsQuery {
+    /// Start of "today" for the daily counts, the client's local midnight. Defaults to midnight UTC.
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Completion of a project, shown while annotating
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct ProjectStats {
+    pub total_tasks: i64,
+    /// Tasks with at least one annotation
+    pub annotated_tasks: i64,
+    pub completed_tasks: i64,
+    /// Tasks whose first annotation was saved since `since`
+    pub annotated_today: i64,
+    /// Tasks the requesting user saved an annotation of since `since`
+    pub annotated_today_by_me: i64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/stats",
+    tag = "reports",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ProjectStatsQuery,
+    ),
+    responses(
+        (status = 200, body = ProjectStats),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn get_project_stats(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<ProjectStatsQuery>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let since = query.since.unwrap_or_else(start_of_today);
+    match get_project_stats_from_db(&pool, project_id, user_id, since).await {
+        Ok(stats) => HttpResponse::Ok().json(stats),
+        Err(err) => {
+            eprintln!("Project stats error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to fetch project stats")
+        }
+    }
+}
+
+fn start_of_today() -> DateTime<Utc> {
+    Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc()
+}
+
+async fn get_project_stats_from_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    user_id: Uuid,
+    since: DateTime<Utc>,
+) -> Result<ProjectStats, sqlx::Error> {
+    sqlx::query_as::<_, ProjectStats>(
+        r#"
+        SELECT
+            COUNT(*) AS total_tasks,
+            COUNT(*) FILTER (WHERE EXISTS (SELECT 1 FROM annotations a WHERE a.task_id = t.id)) AS annotated_tasks,
+            COUNT(*) FILTER (WHERE t.status = 'completed') AS completed_tasks,
+            COUNT(*) FILTER (WHERE (SELECT MIN(a.created_at) FROM annotations a WHERE a.task_id = t.id) >= $2) AS annotated_today,
+            COUNT(*) FILTER (WHERE EXISTS (
+                SELECT 1 FROM annotations a WHERE a.task_id = t.id AND a.annotated_by = $3 AND a.created_at >= $2
+            )) AS annotated_today_by_me
+        FROM tasks t
+        WHERE t.project_id = $1
+        "#
+    )
+    .bind(project_id)
+    .bind(since)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Cells along each side of the box center heatmap
+const HEATMAP_CELLS: i32 = 20;
+/// Bins of the box size histograms, over 0 to 1 of the image side
+const SIZE_BINS: i32 = 20;
+
+/// Where boxes are and how large, and which categories they are, over the latest save of every
+/// task, to spot a biased dataset before training
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AnnotationStats {
+    /// Tasks with a saved annotation
+    pub annotated_tasks: i64,
+    pub total_boxes: i64,
+    /// Boxes on images whose size isn't known, left out of the heatmap and the histograms
+    pub boxes_without_dimensions: i64,
+    /// Box centers counted on a `heatmap_cells` by `heatmap_cells` grid over the image, row by row
+    /// from the top left
+    pub heatmap_cells: i32,
+    pub center_heatmap: Vec<i64>,
+    /// Boxes counted by width relative to the image width, in equal bins from 0 to 1
+    pub width_histogram: Vec<i64>,
+    /// Boxes counted by height relative to the image height, in equal bins from 0 to 1
+    pub height_histogram: Vec<i64>,
+    /// Every category of the project, most used first
+    pub categories: Vec<CategoryDistribution>,
+    /// Boxes without a category, or whose category was deleted
+    pub uncategorized_boxes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct CategoryDistribution {
+    pub category_id: Uuid,
+    pub name: String,
+    pub color: Option<String>,
+    pub boxes: i64,
+    /// Tasks with at least one box of the category
+    pub tasks: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct BoxTotals {
+    annotated_tasks: i64,
+    total_boxes: i64,
+    boxes_without_dimensions: i64,
+}
+
+/// A count of `GROUPING SETS ((center_column, center_row), (width_bin), (height_bin))`, with the
+/// columns of the other sets NULL
+#[derive(sqlx::FromRow)]
+struct BinCount {
+    center_column: Option<i32>,
+    center_row: Option<i32>,
+    width_bin: Option<i32>,
+    height_bin: Option<i32>,
+    boxes: i64,
+}
+
+/// Boxes of the latest save of every task of `$1`, predictions left out as in the exports
+const LATEST_BOXES: &str = r#"
+    WITH latest_annotations AS (
+        SELECT DISTINCT ON (task_id) id, task_id
+        FROM annotations
+        WHERE task_id IN (SELECT id FROM tasks WHERE project_id = $1)
+        ORDER BY task_id, created_at DESC
+    ),
+    boxes AS (
+        SELECT la.task_id, ia.category_id, ia.bbox, t.width, t.height
+        FROM latest_annotations la
+        JOIN tasks t ON t.id = la.task_id
+        JOIN image_annotations ia ON ia.annotation_id = la.id
+        WHERE NOT ia.is_prediction
+    )
+"#;
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/stats/annotations",
+    tag = "reports",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+    ),
+    responses(
+        (status = 200, body = AnnotationStats),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn get_annotation_stats(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match get_annotation_stats_from_db(&pool, project_id).await {
+        Ok(stats) => HttpResponse::Ok().json(stats),
+        Err(err) => {
+            eprintln!("Annotation stats error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to fetch annotation stats")
+        }
+    }
+}
+
+async fn get_annotation_stats_from_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<AnnotationStats, sqlx::Error> {
+    let totals = sqlx::query_as::<_, BoxTotals>(&format!(
+        r#"{}
+        SELECT
+            (SELECT COUNT(*) FROM latest_annotations) AS annotated_tasks,
+            COUNT(*) AS total_boxes,
+            COUNT(*) FILTER (WHERE COALESCE(width, 0) <= 0 OR COALESCE(height, 0) <= 0) AS boxes_without_dimensions
+        FROM boxes
+        "#,
+        LATEST_BOXES
+    ))
+    .bind(project_id)
+    .fetch_one(pool)
+    .await?;
+
+    // width_bucket puts values outside [0, 1) in bin 0 or count + 1, such as boxes reaching the
+    // right edge, so they are clamped into the first and last bins
+    let bins = sqlx::query_as::<_, BinCount>(&format!(
+        r#"{}
+        , binned AS (
+            SELECT
+                LEAST(GREATEST(width_bucket((bbox[1] + bbox[3] / 2) / width, 0, 1, $2), 1), $2) - 1 AS center_column,
+                LEAST(GREATEST(width_bucket((bbox[2] + bbox[4] / 2) / height, 0, 1, $2), 1), $2) - 1 AS center_row,
+                LEAST(GREATEST(width_bucket(bbox[3] / width, 0, 1, $3), 1), $3) - 1 AS width_bin,
+                LEAST(GREATEST(width_bucket(bbox[4] / height, 0, 1, $3), 1), $3) - 1 AS height_bin
+            FROM boxes
+            WHERE width > 0 AND height > 0
+        )
+        SELECT center_column, center_row, width_bin, height_bin, COUNT(*) AS boxes
+        FROM binned
+        GROUP BY GROUPING SETS ((center_column, center_row), (width_bin), (height_bin))
+        "#,
+        LATEST_BOXES
+    ))
+    .bind(project_id)
+    .bind(HEATMAP_CELLS)
+    .bind(SIZE_BINS)
+    .fetch_all(pool)
+    .await?;
+
+    let categories = sqlx::query_as::<_, CategoryDistribution>(&format!(
+        r#"{}
+        SELECT
+            c.id AS category_id,
+            c.name,
+            c.color,
+            COUNT(b.task_id) AS boxes,
+            COUNT(DISTINCT b.task_id) AS tasks
+        FROM image_annotation_categories c
+        LEFT JOIN boxes b ON b.category_id = c.id
+        WHERE c.project_id = $1
+        GROUP BY c.id, c.name, c.color
+        ORDER BY boxes DESC, c.name
+        "#,
+        LATEST_BOXES
+    ))
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut center_heatmap = vec![0; (HEATMAP_CELLS * HEATMAP_CELLS) as usize];
+    let mut width_histogram = vec![0; SIZE_BINS as usize];
+    let mut height_histogram = vec![0; SIZE_BINS as usize];
+    for bin in bins {
+        match (bin.center_column, bin.center_row, bin.width_bin, bin.height_bin) {
+            (Some(column), Some(row), _, _) => center_heatmap[(row * HEATMAP_CELLS + column) as usize] = bin.boxes,
+            (_, _, Some(width), _) => width_histogram[width as usize] = bin.boxes,
+            (_, _, _, Some(height)) => height_histogram[height as usize] = bin.boxes,
+            _ => {}
+        }
+    }
+
+    let categorized: i64 = categories.iter().map(|category| category.boxes).sum();
+    Ok(AnnotationStats {
+        annotated_tasks: totals.annotated_tasks,
+        total_boxes: totals.total_boxes,
+        boxes_without_dimensions: totals.boxes_without_dimensions,
+        heatmap_cells: HEATMAP_CELLS,
+        center_heatmap,
+        width_histogram,
+        height_histogram,
+        uncategorized_boxes: totals.total_boxes - categorized,
+        categories,
+    })
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_project_stats_counts_tasks() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        let yesterday = crate::tasks::create_task_in_db(&pool, project.id, "a.jpg", None).await.unwrap();
+        let today = crate::tasks::create_task_in_db(&pool, project.id, "b.jpg", None).await.unwrap();
+        crate::tasks::create_task_in_db(&pool, project.id, "c.jpg", None).await.unwrap();
+
+        // One task first annotated yesterday and edited today, one first annotated today
+        for (task_id, age) in [(yesterday.id, "1 day"), (yesterday.id, "0 seconds"), (today.id, "0 seconds")] {
+            sqlx::query(&format!(
+                "INSERT INTO annotations (task_id, annotated_by, created_at) VALUES ($1, $2, NOW() - INTERVAL '{}')",
+                age
+            ))
+            .bind(task_id)
+            .bind(user.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query("UPDATE tasks SET status = 'completed' WHERE id = $1")
+            .bind(yesterday.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/stats", web::get().to(get_project_stats))
+        ).await;
+
+        let since = (Utc::now() - chrono::Duration::hours(1)).to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/stats?since={}", project.id, since))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let stats: ProjectStats = test::read_body_json(resp).await;
+        assert_eq!(stats.total_tasks, 3);
+        assert_eq!(stats.annotated_tasks, 2);
+        assert_eq!(stats.completed_tasks, 1);
+        assert_eq!(stats.annotated_today, 1);
+        assert_eq!(stats.annotated_today_by_me, 2);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/stats", Uuid::new_v4()))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_annotation_stats_aggregates_latest_boxes() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", Some("Description"), None, user.id).await.unwrap();
+        let task = crate::tasks::create_task_in_db(&pool, project.id, "a.jpg", None).await.unwrap();
+        sqlx::query("UPDATE tasks SET width = 100, height = 200 WHERE id = $1")
+            .bind(task.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let car = Uuid::new_v4();
+        for (id, name) in [(car, "car"), (Uuid::new_v4(), "person")] {
+            sqlx::query("INSERT INTO image_annotation_categories (id, project_id, name) VALUES ($1, $2, $3)")
+                .bind(id)
+                .bind(project.id)
+                .bind(name)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        // The older save is left out, so is the prediction of the latest one
+        let boxes: [(&str, Vec<(Option<Uuid>, [f64; 4], bool)>); 2] = [
+            ("1 day", vec![(Some(car), [0.0, 0.0, 10.0, 10.0], false)]),
+            ("0 seconds", vec![
+                (Some(car), [0.0, 0.0, 8.0, 14.0], false),
+                (None, [92.0, 190.0, 12.0, 14.0], false),
+                (Some(car), [50.0, 50.0, 10.0, 10.0], true),
+            ]),
+        ];
+        for (age, boxes) in boxes {
+            let annotation_id = Uuid::new_v4();
+            sqlx::query(&format!(
+                "INSERT INTO annotations (id, task_id, annotated_by, created_at) VALUES ($1, $2, $3, NOW() - INTERVAL '{}')",
+                age
+            ))
+            .bind(annotation_id)
+            .bind(task.id)
+            .bind(user.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+            for (category_id, bbox, is_prediction) in boxes {
+                sqlx::query("INSERT INTO image_annotations (annotation_id, category_id, bbox, is_prediction) VALUES ($1, $2, $3, $4)")
+                    .bind(annotation_id)
+                    .bind(category_id)
+                    .bind(bbox.to_vec())
+                    .bind(is_prediction)
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/stats/annotations", web::get().to(get_annotation_stats))
+        ).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/stats/annotations", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let stats: AnnotationStats = test::read_body_json(resp).await;
+        assert_eq!(stats.annotated_tasks, 1);
+        assert_eq!(stats.total_boxes, 2);
+        assert_eq!(stats.boxes_without_dimensions, 0);
+
+        // Centers at (4, 7) and (98, 197) of a 100 by 200 image, in the first and the last cell
+        let cells = stats.heatmap_cells as usize;
+        assert_eq!(stats.center_heatmap.len(), cells * cells);
+        assert_eq!(stats.center_heatmap[0], 1);
+        assert_eq!(stats.center_heatmap[cells * cells - 1], 1);
+        assert_eq!(stats.center_heatmap.iter().sum::<i64>(), 2);
+
+        // 8% and 12% of the image wide, both 7% of it high
+        assert_eq!(stats.width_histogram[1], 1);
+        assert_eq!(stats.width_histogram[2], 1);
+        assert_eq!(stats.height_histogram[1], 2);
+
+        assert_eq!(stats.categories.len(), 2);
+        assert_eq!(stats.categories[0].name, "car");
+        assert_eq!(stats.categories[0].boxes, 1);
+        assert_eq!(stats.categories[0].tasks, 1);
+        assert_eq!(stats.categories[1].boxes, 0);
+        assert_eq!(stats.uncategorized_boxes, 1);
+    }
+}
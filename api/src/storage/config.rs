@@ -55,4 +55,17 @@ impl StorageConfig {
         }
         Ok(())
     }
+
+    /// The same account with another bucket, or container for Azure
+    pub fn with_bucket(&self, bucket: &str) -> Result<StorageConfig, String> {
+        let mut config = self.clone();
+        match &mut config {
+            StorageConfig::S3 { bucket: current, .. } | StorageConfig::GoogleCloudStorage { bucket: current, .. } => {
+                *current = bucket.to_string();
+            }
+            StorageConfig::Azure { container_name, .. } => *container_name = bucket.to_string(),
+            StorageConfig::Local { .. } => return Err("Local storage has no buckets".to_string()),
+        }
+        Ok(config)
+    }
 }
\ No newline at end of file
@@ -1,10 +1,11 @@
 use crate::storage::{StorageProvider, StorageError};
+use crate::storage::instrumented::InstrumentedStorageProvider;
 use crate::storage::config::StorageConfig;
 use crate::storage::providers::{S3StorageProvider, AzureStorageProvider, GcsStorageProvider, LocalStorageProvider};
 use std::sync::Arc;
 
 pub async fn create_storage_provider(config: &StorageConfig) -> Result<Arc<dyn StorageProvider>, StorageError> {
-    match config {
+    let (provider, name): (Arc<dyn StorageProvider>, &'static str) = match config {
         StorageConfig::S3 { bucket, region, access_key, secret_key, endpoint } => {
             let provider = S3StorageProvider::new(
                 bucket.clone(),
@@ -13,7 +14,7 @@ pub async fn create_storage_provider(config: &StorageConfig) -> Result<Arc<dyn S
                 secret_key.clone(),
                 endpoint.clone(),
             ).await?;
-            Ok(Arc::new(provider))
+            (Arc::new(provider), "s3")
         }
         StorageConfig::Azure { account_name, account_key, container_name } => {
             let provider = AzureStorageProvider::new(
@@ -21,7 +22,7 @@ pub async fn create_storage_provider(config: &StorageConfig) -> Result<Arc<dyn S
                 account_key.clone(),
                 container_name.clone(),
             )?;
-            Ok(Arc::new(provider))
+            (Arc::new(provider), "azure")
         }
         StorageConfig::GoogleCloudStorage { bucket, project_id, service_account_key } => {
             let provider = GcsStorageProvider::new(
@@ -29,13 +30,15 @@ pub async fn create_storage_provider(config: &StorageConfig) -> Result<Arc<dyn S
                 project_id.clone(),
                 service_account_key.clone(),
             ).await?;
-            Ok(Arc::new(provider))
+            (Arc::new(provider), "gcs")
         }
         StorageConfig::Local { base_path } => {
             let provider = LocalStorageProvider::new(base_path.clone()).await?;
-            Ok(Arc::new(provider))
+            (Arc::new(provider), "local")
         }
-    }
+    };
+
+    Ok(Arc::new(InstrumentedStorageProvider::new(provider, name)))
 }
 
 pub async fn create_storage_provider_from_project(
@@ -2,32 +2,63 @@ use actix_web::{web, HttpResponse, Responder, HttpRequest};
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::auth::{JwtManager, Claims};
 use crate::storage::factory::create_storage_provider_from_project;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct UploadRequest {
     pub key: String,
     pub content_type: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UploadResponse {
     pub upload_url: String,
     pub key: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct DownloadResponse {
     pub download_url: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListObjectsQuery {
+    /// Only keys starting with this prefix, takes over from the `x-prefix` header
+    pub prefix: Option<String>,
+    /// Lists one level below the prefix, like a folder, with the deeper keys grouped into
+    /// `prefixes` at the first `delimiter`, usually `/`
+    pub delimiter: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ListObjectsResponse {
     pub objects: Vec<String>,
+    /// Subfolders of the prefix when listed with a delimiter, each ending with it. Frames of
+    /// videos and tiles of large images are left out, syncs create no tasks from them.
+    pub prefixes: Vec<String>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/storage/upload",
+    tag = "storage",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        UploadRequest,
+    ),
+    request_body(content = [u8], content_type = "application/octet-stream"),
+    responses(
+        (status = 200, body = UploadResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+        (status = 500, description = "Storage error", body = String),
+    ),
+)]
 pub async fn upload_file(
     req: HttpRequest,
     path: web::Path<String>,
@@ -67,14 +98,40 @@ pub async fn upload_file(
     };
 
     match storage_provider.upload(&query.key, &payload, query.content_type.as_deref()).await {
-        Ok(url) => HttpResponse::Ok().json(UploadResponse {
-            upload_url: url,
-            key: query.key.clone(),
-        }),
+        Ok(url) => {
+            // Large images get their tile pyramid right away; sync reuses it and only falls back
+            // to generating it for files that were put into storage directly
+            if let Some((width, height)) = crate::tiles::image_dimensions(&payload) {
+                if crate::tiles::needs_tiles(width, height) {
+                    if let Err(e) = crate::tiles::generate_pyramid(&*storage_provider, &query.key, payload.to_vec()).await {
+                        eprintln!("Failed to generate tiles for {}: {}", query.key, e);
+                    }
+                }
+            }
+
+            HttpResponse::Ok().json(UploadResponse {
+                upload_url: url,
+                key: query.key.clone(),
+            })
+        }
         Err(e) => HttpResponse::InternalServerError().json(format!("Upload failed: {}", e)),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/storage/{key}",
+    tag = "storage",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("key" = String, Path, description = "Object key"),
+    ),
+    responses(
+        (status = 200, description = "Object content with its stored content type", body = [u8], content_type = "application/octet-stream"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or file not found", body = String),
+    ),
+)]
 pub async fn download_file(
     req: HttpRequest,
     path: web::Path<(String, String)>,
@@ -130,6 +187,20 @@ pub async fn download_file(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/storage/{key}/url",
+    tag = "storage",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("key" = String, Path, description = "Object key"),
+    ),
+    responses(
+        (status = 200, body = DownloadResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or file not found", body = String),
+    ),
+)]
 pub async fn get_presigned_url(
     req: HttpRequest,
     path: web::Path<(String, String)>,
@@ -183,9 +254,26 @@ pub async fn get_presigned_url(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/storage",
+    tag = "storage",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("x-prefix" = Option<String>, Header, description = "Only keys starting with this prefix"),
+        ListObjectsQuery,
+    ),
+    responses(
+        (status = 200, body = ListObjectsResponse),
+        (status = 400, description = "Empty delimiter", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found, or the user is not an owner or admin of it", body = String),
+    ),
+)]
 pub async fn list_objects(
     req: HttpRequest,
     path: web::Path<String>,
+    query: web::Query<ListObjectsQuery>,
     pool: web::Data<Pool<Postgres>>,
     config: web::Data<crate::auth::OAuthConfig>,
 ) -> impl Responder {
@@ -204,8 +292,12 @@ pub async fn list_objects(
         Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
     };
 
-    if !user_has_project_access(&pool, project_id, user_id).await {
-        return HttpResponse::NotFound().json("Project not found or access denied");
+    // Browsing the whole bucket is part of setting the storage up, annotators only open the
+    // files of their tasks
+    match crate::projects::user_can_manage_storage(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or access denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check permissions"),
     }
 
     let project = match get_project_by_id(&pool, project_id).await {
@@ -219,39 +311,47 @@ pub async fn list_objects(
         Err(e) => return HttpResponse::InternalServerError().json(format!("Storage error: {}", e)),
     };
 
-    let prefix = req
-        .headers()
-        .get("x-prefix")
-        .and_then(|h| h.to_str().ok());
+    let prefix = query.prefix.as_deref().or_else(|| {
+        req
+            .headers()
+            .get("x-prefix")
+            .and_then(|h| h.to_str().ok())
+    });
+
+    let Some(delimiter) = query.delimiter.as_deref() else {
+        return match storage_provider.list_objects(prefix).await {
+            Ok(objects) => HttpResponse::Ok().json(ListObjectsResponse { objects, prefixes: Vec::new() }),
+            Err(e) => HttpResponse::InternalServerError().json(format!("Failed to list objects: {}", e)),
+        };
+    };
+    if delimiter.is_empty() {
+        return HttpResponse::BadRequest().json("delimiter must not be empty");
+    }
 
-    match storage_provider.list_objects(prefix).await {
-        Ok(objects) => HttpResponse::Ok().json(ListObjectsResponse { objects }),
+    match storage_provider.list_objects_with_delimiter(prefix, delimiter).await {
+        Ok(listing) => HttpResponse::Ok().json(ListObjectsResponse {
+            objects: listing.objects,
+            prefixes: listing.prefixes
+                .into_iter()
+                .filter(|prefix| !crate::video::is_extracted_frame(prefix) && !crate::tiles::is_tile_key(prefix))
+                .collect(),
+        }),
         Err(e) => HttpResponse::InternalServerError().json(format!("Failed to list objects: {}", e)),
     }
 }
 
 async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
-    sqlx::query_scalar::<_, bool>(
-        r#"
-        SELECT EXISTS(
-            SELECT 1 FROM project_members pm
-            WHERE pm.project_id = $1 AND pm.user_id = $2
-        )
-        "#
-    )
-    .bind(project_id)
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .unwrap_or(false)
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
 }
 
+/// The whole project with its storage credentials, used only to reach the storage and never
+/// returned to the client
 async fn get_project_by_id(
     pool: &Pool<Postgres>,
     project_id: Uuid,
 ) -> Result<Option<crate::projects::Project>, sqlx::Error> {
     sqlx::query_as::<_, crate::projects::Project>(
-        "SELECT id, name, description, storage_config, owner_id, created_at, updated_at FROM projects WHERE id = $1"
+        "SELECT id, name, description, storage_config, owner_id, task_type, created_at, updated_at FROM projects WHERE id = $1"
     )
     .bind(project_id)
     .fetch_optional(pool)
@@ -0,0 +1,114 @@
+use crate::metrics::metrics;
+use crate::storage::{ObjectListing, StorageError, StorageMetadata, StorageProvider};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Counts the calls and failures of the provider it wraps for `/metrics`
+pub struct InstrumentedStorageProvider {
+    inner: Arc<dyn StorageProvider>,
+    provider: &'static str,
+}
+
+impl InstrumentedStorageProvider {
+    pub fn new(inner: Arc<dyn StorageProvider>, provider: &'static str) -> Self {
+        Self { inner, provider }
+    }
+
+    fn record<T>(&self, operation: &str, result: &Result<T, StorageError>) {
+        let metrics = metrics();
+        metrics.storage_calls.with_label_values(&[self.provider, operation]).inc();
+        // A missing object is an answer, not a failing provider
+        if matches!(result, Err(error) if !matches!(error, StorageError::NotFound)) {
+            metrics.storage_errors.with_label_values(&[self.provider, operation]).inc();
+        }
+    }
+}
+
+#[async_trait]
+impl StorageProvider for InstrumentedStorageProvider {
+    async fn upload(
+        &self,
+        key: &str,
+        data: &[u8],
+        content_type: Option<&str>,
+    ) -> Result<String, StorageError> {
+        let result = self.inner.upload(key, data, content_type).await;
+        self.record("upload", &result);
+        result
+    }
+
+    async fn download(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let result = self.inner.download(key).await;
+        self.record("download", &result);
+        result
+    }
+
+    async fn get_presigned_url(
+        &self,
+        key: &str,
+        expires_in_secs: u64,
+    ) -> Result<String, StorageError> {
+        let result = self.inner.get_presigned_url(key, expires_in_secs).await;
+        self.record("get_presigned_url", &result);
+        result
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        let result = self.inner.delete(key).await;
+        self.record("delete", &result);
+        result
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, StorageError> {
+        let result = self.inner.exists(key).await;
+        self.record("exists", &result);
+        result
+    }
+
+    async fn get_metadata(&self, key: &str) -> Result<StorageMetadata, StorageError> {
+        let result = self.inner.get_metadata(key).await;
+        self.record("get_metadata", &result);
+        result
+    }
+
+    async fn list_objects(&self, prefix: Option<&str>) -> Result<Vec<String>, StorageError> {
+        let result = self.inner.list_objects(prefix).await;
+        self.record("list_objects", &result);
+        result
+    }
+
+    async fn list_objects_with_delimiter(
+        &self,
+        prefix: Option<&str>,
+        delimiter: &str,
+    ) -> Result<ObjectListing, StorageError> {
+        let result = self.inner.list_objects_with_delimiter(prefix, delimiter).await;
+        self.record("list_objects", &result);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::providers::LocalStorageProvider;
+
+    #[tokio::test]
+    async fn test_counts_calls_and_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = LocalStorageProvider::new(dir.path().to_string_lossy().to_string()).await.unwrap();
+        let provider = InstrumentedStorageProvider::new(Arc::new(local), "local");
+
+        let calls = |operation: &str| metrics().storage_calls.with_label_values(&["local", operation]).get();
+        let errors = |operation: &str| metrics().storage_errors.with_label_values(&["local", operation]).get();
+        let (uploads, downloads, download_errors) = (calls("upload"), calls("download"), errors("download"));
+
+        provider.upload("a.txt", b"hello", Some("text/plain")).await.unwrap();
+        assert_eq!(provider.download("a.txt").await.unwrap(), b"hello");
+        assert!(matches!(provider.download("missing.txt").await, Err(StorageError::NotFound)));
+
+        assert_eq!(calls("upload"), uploads + 1);
+        assert_eq!(calls("download"), downloads + 2);
+        assert_eq!(errors("download"), download_errors);
+    }
+}
@@ -2,6 +2,7 @@ pub mod providers;
 pub mod config;
 pub mod factory;
 pub mod handlers;
+pub mod instrumented;
 
 #[cfg(test)]
 pub mod tests;
@@ -47,6 +48,35 @@ pub struct StorageMetadata {
     pub last_modified: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// One level of the keys under a prefix, like the files and folders of a directory
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ObjectListing {
+    /// Keys right under the prefix
+    pub objects: Vec<String>,
+    /// Prefixes the deeper keys share, each ending with the delimiter
+    pub prefixes: Vec<String>,
+}
+
+impl ObjectListing {
+    /// Splits a flat list of keys under `prefix` into the level below it
+    pub fn from_keys(keys: Vec<String>, prefix: &str, delimiter: &str) -> Self {
+        let mut listing = Self::default();
+        for key in keys {
+            let Some(rest) = key.strip_prefix(prefix) else {
+                continue;
+            };
+            match rest.find(delimiter) {
+                Some(end) => listing.prefixes.push(format!("{}{}", prefix, &rest[..end + delimiter.len()])),
+                None => listing.objects.push(key),
+            }
+        }
+        listing.objects.sort();
+        listing.prefixes.sort();
+        listing.prefixes.dedup();
+        listing
+    }
+}
+
 #[async_trait]
 pub trait StorageProvider: Send + Sync {
     async fn upload(
@@ -67,10 +97,20 @@ pub trait StorageProvider: Send + Sync {
     #[allow(dead_code)]
     async fn delete(&self, key: &str) -> Result<(), StorageError>;
 
-    #[allow(dead_code)]
     async fn exists(&self, key: &str) -> Result<bool, StorageError>;
 
     async fn get_metadata(&self, key: &str) -> Result<StorageMetadata, StorageError>;
 
     async fn list_objects(&self, prefix: Option<&str>) -> Result<Vec<String>, StorageError>;
+
+    /// The level of keys right under `prefix`, with the deeper ones grouped by `delimiter`.
+    /// Providers that can group keys themselves override this, the others list every key.
+    async fn list_objects_with_delimiter(
+        &self,
+        prefix: Option<&str>,
+        delimiter: &str,
+    ) -> Result<ObjectListing, StorageError> {
+        let keys = self.list_objects(prefix).await?;
+        Ok(ObjectListing::from_keys(keys, prefix.unwrap_or(""), delimiter))
+    }
 }
\ No newline at end of file
@@ -136,10 +136,17 @@ impl StorageProvider for LocalStorageProvider {
                 time.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs() as i64, 0
             ));
 
+        // Like the ETags of static file servers, it changes whenever the file is written
+        let etag = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|since_epoch| format!("\"{:x}-{:x}\"", since_epoch.as_nanos(), metadata.len()));
+
         Ok(StorageMetadata {
             content_type,
             content_length: Some(metadata.len()),
-            etag: None,
+            etag,
             last_modified,
         })
     }
@@ -1,4 +1,4 @@
-use crate::storage::{StorageProvider, StorageError, StorageMetadata};
+use crate::storage::{ObjectListing, StorageProvider, StorageError, StorageMetadata};
 use async_trait::async_trait;
 use rusoto_core::{Region, RusotoError};
 use rusoto_credential::{StaticProvider, ProvideAwsCredentials};
@@ -242,4 +242,42 @@ impl StorageProvider for S3StorageProvider {
 
         Ok(keys)
     }
+
+    async fn list_objects_with_delimiter(
+        &self,
+        prefix: Option<&str>,
+        delimiter: &str,
+    ) -> Result<ObjectListing, StorageError> {
+        let mut listing = ObjectListing::default();
+        let mut continuation_token = None;
+
+        // S3 groups the keys itself, a folder of a large bucket takes a page or two rather than
+        // every key below it
+        loop {
+            let request = ListObjectsV2Request {
+                bucket: self.bucket.clone(),
+                prefix: prefix.map(|p| p.to_string()),
+                delimiter: Some(delimiter.to_string()),
+                continuation_token: continuation_token.take(),
+                ..Default::default()
+            };
+
+            let response = self.client
+                .list_objects_v2(request)
+                .await
+                .map_err(|e| StorageError::NetworkError(e.to_string()))?;
+
+            listing.objects.extend(response.contents.unwrap_or_default().into_iter().filter_map(|obj| obj.key));
+            listing.prefixes.extend(response.common_prefixes.unwrap_or_default().into_iter().filter_map(|p| p.prefix));
+
+            match response.next_continuation_token {
+                Some(token) if response.is_truncated == Some(true) => continuation_token = Some(token),
+                _ => break,
+            }
+        }
+
+        listing.objects.sort();
+        listing.prefixes.sort();
+        Ok(listing)
+    }
 }
\ No newline at end of file
@@ -434,4 +434,143 @@ async fn test_storage_unauthorized_access() {
     assert_eq!(resp.status(), 401);
 
     cleanup_test_data(&pool, user_id, project_id).await;
-}
\ No newline at end of file
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_storage_access_by_role() {
+    let pool = test_utils::setup_test_db().await;
+    let (owner_id, project_id) = test_utils::setup_test_user_and_project_with_storage(&pool).await;
+    let config = get_test_config();
+
+    let test_dir = "/tmp/fast_tag_test";
+    std::fs::create_dir_all(test_dir).unwrap();
+    std::fs::write(format!("{}/image.jpg", test_dir), "image").unwrap();
+
+    let mut users = vec![("owner", owner_id, true)];
+    for (role, can_manage) in [("admin", true), ("member", false), ("viewer", false)] {
+        let user_id = test_utils::create_test_user(&pool).await;
+        sqlx::query("INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, $3)")
+            .bind(project_id)
+            .bind(user_id)
+            .bind(role)
+            .execute(&pool)
+            .await
+            .unwrap();
+        users.push((role, user_id, can_manage));
+    }
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config.clone()))
+            .route("/projects/{project_id}/storage", web::get().to(list_objects))
+            .route("/projects/{project_id}/storage/{key}/url", web::get().to(get_presigned_url))
+    ).await;
+
+    for (role, user_id, can_manage) in &users {
+        let token = create_test_jwt_token(*user_id, &config);
+
+        // Every member opens the images of the project
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/storage/image.jpg/url", project_id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200, "{} gets a presigned URL", role);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/storage", project_id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let expected_status = if *can_manage { 200 } else { 404 };
+        assert_eq!(resp.status(), expected_status, "{} lists the bucket", role);
+    }
+
+    for (_, user_id, _) in users.iter().skip(1) {
+        let _ = sqlx::query("DELETE FROM project_members WHERE user_id = $1").bind(user_id).execute(&pool).await;
+        let _ = sqlx::query("DELETE FROM users WHERE id = $1").bind(user_id).execute(&pool).await;
+    }
+    cleanup_test_data(&pool, owner_id, project_id).await;
+}
+
+#[actix_web::test]
+async fn test_object_listing_from_keys() {
+    let keys = ["images/a.jpg", "images/2024/b.jpg", "images/2024/c.jpg", "images/raw/d.png", "other/e.jpg"]
+        .map(String::from)
+        .to_vec();
+
+    let listing = crate::storage::ObjectListing::from_keys(keys.clone(), "images/", "/");
+    assert_eq!(listing.objects, vec!["images/a.jpg"]);
+    assert_eq!(listing.prefixes, vec!["images/2024/", "images/raw/"]);
+
+    let listing = crate::storage::ObjectListing::from_keys(keys, "", "/");
+    assert!(listing.objects.is_empty());
+    assert_eq!(listing.prefixes, vec!["images/", "other/"]);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_list_objects_by_folder() {
+    let pool = test_utils::setup_test_db().await;
+    let (user_id, project_id) = test_utils::setup_test_user_and_project_with_storage(&pool).await;
+    let config = get_test_config();
+    let token = create_test_jwt_token(user_id, &config);
+
+    let test_dir = "/tmp/fast_tag_test";
+    std::fs::create_dir_all(format!("{}/images/2024", test_dir)).unwrap();
+    std::fs::create_dir_all(format!("{}/images/clip.mp4.frames", test_dir)).unwrap();
+    std::fs::write(format!("{}/readme.txt", test_dir), "docs").unwrap();
+    std::fs::write(format!("{}/images/photo1.jpg", test_dir), "image1").unwrap();
+    std::fs::write(format!("{}/images/2024/photo2.jpg", test_dir), "image2").unwrap();
+    std::fs::write(format!("{}/images/clip.mp4", test_dir), "video").unwrap();
+    std::fs::write(format!("{}/images/clip.mp4.frames/000000.jpg", test_dir), "frame").unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config))
+            .route("/projects/{project_id}/storage", web::get().to(list_objects))
+            .route("/projects/{project_id}/storage/{key}", web::get().to(download_file))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/storage?delimiter=/", project_id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["objects"], serde_json::json!(["readme.txt"]));
+    assert_eq!(body["prefixes"], serde_json::json!(["images/"]));
+
+    // The frames of the video are no folder of their own
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/storage?prefix=images/&delimiter=/", project_id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["objects"], serde_json::json!(["images/clip.mp4", "images/photo1.jpg"]));
+    assert_eq!(body["prefixes"], serde_json::json!(["images/2024/"]));
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/storage?delimiter=", project_id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    // Keys in folders are previewed with their slashes encoded
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/storage/images%2F2024%2Fphoto2.jpg", project_id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(test::read_body(resp).await, Bytes::from("image2"));
+
+    cleanup_test_data(&pool, user_id, project_id).await;
+}
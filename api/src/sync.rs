@@ -1,11 +1,16 @@
 use actix_web::{web, HttpResponse, Responder, HttpRequest};
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Postgres, Row};
+use sqlx::postgres::PgRow;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use image::GenericImageView;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use utoipa::{IntoParams, ToSchema};
 
 use crate::auth::{JwtManager, Claims};
+use crate::storage::StorageProvider;
 use crate::storage::factory::create_storage_provider_from_project;
 
 #[cfg(test)]
@@ -58,7 +63,7 @@ mod unit_tests {
     }
 
     #[tokio::test]
-    async fn test_get_image_dimensions() {
+    async fn test_inspect_image() {
         use crate::storage::{StorageProvider, StorageError, StorageMetadata};
         use async_trait::async_trait;
         use image::{ImageBuffer, RgbImage};
@@ -110,42 +115,35 @@ mod unit_tests {
 
         // Test successful dimension retrieval
         let provider = MockStorageProvider { should_fail: false };
-        let result = get_image_dimensions(&provider, "test.png").await;
+        let result = inspect_image(&provider, "test.png").await;
         if let Err(e) = &result {
             println!("Error getting dimensions: {}", e);
         }
         assert!(result.is_ok());
-        let (width, height) = result.unwrap();
+        let (width, height) = result.unwrap().dimensions;
         assert_eq!(width, 5);
         assert_eq!(height, 5);
 
         // Test download failure
         let provider = MockStorageProvider { should_fail: true };
-        let result = get_image_dimensions(&provider, "test.png").await;
+        let result = inspect_image(&provider, "test.png").await;
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Failed to download image"));
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct SyncRequest {
     pub prefix: Option<String>,
     pub file_extensions: Option<Vec<String>>,
     pub overwrite_existing: Option<bool>,
+    /// Frames per second extracted from video files, defaults to `video::DEFAULT_FRAME_RATE`
+    pub video_frame_rate: Option<f64>,
+    /// Skip images whose exact bytes a task of the project already shows, counted as skipped
+    pub skip_duplicates: Option<bool>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct SyncResponse {
-    pub sync_id: Uuid,
-    pub total_files: usize,
-    pub tasks_created: usize,
-    pub tasks_skipped: usize,
-    pub errors: Vec<String>,
-    pub started_at: DateTime<Utc>,
-    pub completed_at: DateTime<Utc>,
-}
-
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SyncStatus {
     pub sync_id: Uuid,
     pub project_id: Uuid,
@@ -159,6 +157,48 @@ pub struct SyncStatus {
     pub completed_at: Option<DateTime<Utc>>,
 }
 
+/// A running sync writes its progress after this many files at the latest
+const PROGRESS_EVERY_FILES: usize = 10;
+/// ...or once a file kept it busy this long, like a long video being split into frames
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Sync runs per page of the history when the request doesn't say
+const DEFAULT_SYNCS_PER_PAGE: i64 = 20;
+const MAX_SYNCS_PER_PAGE: i64 = 100;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SyncHistoryQuery {
+    /// Page of the history, from 1
+    pub page: Option<i64>,
+    /// Sync runs per page, at most 100
+    pub per_page: Option<i64>,
+}
+
+/// Past and running syncs of a project, newest first
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SyncHistory {
+    pub syncs: Vec<SyncStatus>,
+    /// Sync runs of the project over all pages
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/sync",
+    tag = "sync",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    request_body = SyncRequest,
+    responses(
+        (status = 202, description = "Sync started, follow its progress through its status", body = SyncStatus),
+        (status = 400, description = "Invalid frame rate or no storage configured", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+        (status = 409, description = "A sync of the project is already running", body = String),
+    ),
+)]
 pub async fn sync_storage_to_tasks(
     req: HttpRequest,
     path: web::Path<String>,
@@ -181,6 +221,11 @@ pub async fn sync_storage_to_tasks(
         Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
     };
 
+    let frame_rate = payload.video_frame_rate.unwrap_or(crate::video::DEFAULT_FRAME_RATE);
+    if !crate::video::is_valid_frame_rate(frame_rate) {
+        return HttpResponse::BadRequest().json("video_frame_rate must be greater than 0 and at most 120");
+    }
+
     if !user_has_project_access(&pool, project_id, user_id).await {
         return HttpResponse::NotFound().json("Project not found or access denied");
     }
@@ -203,22 +248,73 @@ pub async fn sync_storage_to_tasks(
     let sync_id = Uuid::new_v4();
     let started_at = Utc::now();
 
-    // Record sync start
-    if let Err(e) = record_sync_start(&pool, sync_id, project_id, &started_at).await {
-        return HttpResponse::InternalServerError().json(format!("Failed to record sync start: {}", e));
+    // A project runs one sync at a time, two would create the same tasks twice
+    match record_sync_start(&pool, sync_id, project_id, &started_at).await {
+        Ok(()) => {}
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+            return HttpResponse::Conflict().json("A sync of this project is already running");
+        }
+        Err(e) => return HttpResponse::InternalServerError().json(format!("Failed to record sync start: {}", e)),
     }
 
+    let options = SyncOptions {
+        prefix: payload.prefix.clone(),
+        file_extensions: payload.file_extensions.clone(),
+        overwrite_existing: payload.overwrite_existing.unwrap_or(false),
+        skip_duplicates: payload.skip_duplicates.unwrap_or(false),
+        frame_rate,
+    };
+    // The files are worked through after answering, clients follow the run through its status
+    actix_web::rt::spawn(run_sync(pool.get_ref().clone(), storage_provider, sync_id, project_id, options));
+
+    HttpResponse::Accepted().json(SyncStatus {
+        sync_id,
+        project_id,
+        status: "running".to_string(),
+        total_files: 0,
+        processed_files: 0,
+        tasks_created: 0,
+        tasks_skipped: 0,
+        errors: Vec::new(),
+        started_at,
+        completed_at: None,
+    })
+}
+
+/// What a sync run was asked to do
+struct SyncOptions {
+    prefix: Option<String>,
+    file_extensions: Option<Vec<String>>,
+    overwrite_existing: bool,
+    skip_duplicates: bool,
+    frame_rate: f64,
+}
+
+/// Creates the tasks of a sync run started by `sync_storage_to_tasks`, writing its progress to
+/// the run's row as it goes
+async fn run_sync(
+    pool: Pool<Postgres>,
+    storage_provider: Arc<dyn StorageProvider>,
+    sync_id: Uuid,
+    project_id: Uuid,
+    options: SyncOptions,
+) {
     // Get files from storage
-    let files = match storage_provider.list_objects(payload.prefix.as_deref()).await {
+    let files = match storage_provider.list_objects(options.prefix.as_deref()).await {
         Ok(files) => files,
         Err(e) => {
             let _ = record_sync_error(&pool, sync_id, &format!("Failed to list storage objects: {}", e)).await;
-            return HttpResponse::InternalServerError().json(format!("Failed to list storage objects: {}", e));
+            return;
         }
     };
 
+    // Frames extracted from videos and tiles of large images belong to the task of their source file
+    let files: Vec<String> = files.into_iter()
+        .filter(|file| !crate::video::is_extracted_frame(file) && !crate::tiles::is_tile_key(file))
+        .collect();
+
     // Filter files by extension if specified
-    let filtered_files = if let Some(extensions) = &payload.file_extensions {
+    let filtered_files = if let Some(extensions) = &options.file_extensions {
         files.into_iter()
             .filter(|file| {
                 if let Some(ext) = std::path::Path::new(file).extension() {
@@ -236,9 +332,11 @@ pub async fn sync_storage_to_tasks(
     };
 
     let total_files = filtered_files.len();
+    let frame_rate = options.frame_rate;
     let mut tasks_created = 0;
     let mut tasks_skipped = 0;
     let mut errors = Vec::new();
+    let mut last_reported = Instant::now();
 
     // Update sync status with total files
     if let Err(e) = update_sync_progress(&pool, sync_id, total_files, 0, 0, 0).await {
@@ -246,33 +344,100 @@ pub async fn sync_storage_to_tasks(
     }
 
     // Create tasks for each file
+    let mut processed_files = 0;
     for (index, file_key) in filtered_files.iter().enumerate() {
+        // Report the files done so far, every few files and whenever a slow one held things up
+        if index > 0 && (index % PROGRESS_EVERY_FILES == 0 || last_reported.elapsed() >= PROGRESS_INTERVAL) {
+            if let Err(e) = update_sync_progress(&pool, sync_id, total_files, index, tasks_created, tasks_skipped).await {
+                errors.push(format!("Failed to update sync progress: {}", e));
+            }
+            last_reported = Instant::now();
+        }
+        processed_files = index + 1;
+
         let task_name = extract_task_name_from_file(file_key);
         let resource_url = format!("storage://{}", file_key);
 
         // Check if task already exists (unless overwrite is enabled)
-        if !payload.overwrite_existing.unwrap_or(false) {
+        if !options.overwrite_existing {
             if let Ok(true) = task_exists_for_resource(&pool, project_id, &resource_url).await {
                 tasks_skipped += 1;
                 continue;
             }
         }
+        // Videos and slice stacks are split into frames up front, without frames they can't be annotated
+        let extracted = if crate::video::is_video_file(file_key) {
+            match crate::video::extract_frames(&*storage_provider, file_key, frame_rate).await {
+                Ok((frames, dimensions)) => Some((crate::video::MEDIA_TYPE_VIDEO, Some(frame_rate), frames, dimensions)),
+                Err(e) => {
+                    errors.push(format!("Failed to extract frames for {}: {}", file_key, e));
+                    continue;
+                }
+            }
+        } else if crate::slices::is_slice_stack_file(file_key) {
+            match crate::slices::extract_slices(&*storage_provider, file_key).await {
+                Ok(slices) => slices.map(|(frames, dimensions)| (crate::slices::MEDIA_TYPE_VOLUME, None, frames, dimensions)),
+                Err(e) => {
+                    errors.push(format!("Failed to extract slices for {}: {}", file_key, e));
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
 
-        // Get image dimensions if it's an image file
-        let dimensions = if is_image_file(file_key) {
-            match get_image_dimensions(&*storage_provider, file_key).await {
-                Ok(dims) => Some(dims),
+        // Multi-frame tasks use the size of their frames, otherwise read it from the image
+        let (dimensions, hashes) = if let Some((_, _, _, frame_dimensions)) = &extracted {
+            (*frame_dimensions, None)
+        } else if is_image_file(file_key) {
+            match inspect_image(&*storage_provider, file_key).await {
+                Ok(info) => (Some(info.dimensions), Some(info.hashes)),
                 Err(e) => {
                     errors.push(format!("Failed to get dimensions for {}: {}", file_key, e));
-                    None
+                    (None, None)
                 }
             }
         } else {
-            None
+            (None, None)
         };
 
-        match create_task_for_file(&pool, project_id, &task_name, &resource_url, dimensions).await {
-            Ok(_) => tasks_created += 1,
+        // Copies of an image under another key, including ones earlier in this sync
+        if let (true, Some(hashes)) = (options.skip_duplicates, &hashes) {
+            if let Ok(true) = crate::duplicates::content_hash_exists(&pool, project_id, &hashes.content_hash).await {
+                tasks_skipped += 1;
+                continue;
+            }
+        }
+
+        // Very large images are viewed through a tile pyramid, which is kept across syncs
+        let tiled = match (&extracted, dimensions) {
+            (None, Some(dims)) => match crate::tiles::ensure_pyramid(&*storage_provider, file_key, dims).await {
+                Ok(tiled) => tiled,
+                Err(e) => {
+                    errors.push(format!("Failed to generate tiles for {}: {}", file_key, e));
+                    false
+                }
+            },
+            _ => false,
+        };
+
+        // The file as the task was made from it, for refreshes to tell when it changed
+        let source = storage_provider.get_metadata(file_key).await.ok();
+
+        match create_task_for_file(&pool, project_id, &task_name, &resource_url, dimensions, hashes.as_ref(), source.as_ref()).await {
+            Ok(task_id) => {
+                tasks_created += 1;
+                if tiled {
+                    if let Err(e) = crate::tiles::mark_task_tiled(&pool, task_id).await {
+                        errors.push(format!("Failed to mark {} as tiled: {}", file_key, e));
+                    }
+                }
+                if let Some((media_type, frame_rate, frames, _)) = &extracted {
+                    if let Err(e) = crate::video::save_task_frames(&pool, task_id, media_type, frames, *frame_rate).await {
+                        errors.push(format!("Failed to save frames for {}: {}", file_key, e));
+                    }
+                }
+            }
             Err(e) => {
                 errors.push(format!("Failed to create task for {}: {}", file_key, e));
                 if errors.len() > 10 { // Limit error collection
@@ -281,33 +446,31 @@ pub async fn sync_storage_to_tasks(
                 }
             }
         }
-
-        // Update progress periodically
-        if index % 10 == 0 || index == filtered_files.len() - 1 {
-            if let Err(e) = update_sync_progress(&pool, sync_id, total_files, index + 1, tasks_created, tasks_skipped).await {
-                errors.push(format!("Failed to update sync progress: {}", e));
-            }
-        }
     }
 
     let completed_at = Utc::now();
 
     // Record sync completion
-    if let Err(e) = record_sync_completion(&pool, sync_id, tasks_created, tasks_skipped, &errors, &completed_at).await {
-        return HttpResponse::InternalServerError().json(format!("Failed to record sync completion: {}", e));
+    if let Err(e) = record_sync_completion(&pool, sync_id, processed_files, tasks_created, tasks_skipped, &errors, &completed_at).await {
+        eprintln!("Failed to record completion of sync {}: {}", sync_id, e);
+        let _ = record_sync_error(&pool, sync_id, &format!("Failed to record sync completion: {}", e)).await;
     }
-
-    HttpResponse::Ok().json(SyncResponse {
-        sync_id,
-        total_files,
-        tasks_created,
-        tasks_skipped,
-        errors,
-        started_at,
-        completed_at,
-    })
 }
 
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/sync/{sync_id}",
+    tag = "sync",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("sync_id" = Uuid, Path, description = "Sync ID"),
+    ),
+    responses(
+        (status = 200, body = SyncStatus),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or sync not found", body = String),
+    ),
+)]
 pub async fn get_sync_status(
     req: HttpRequest,
     path: web::Path<(String, String)>,
@@ -346,7 +509,63 @@ pub async fn get_sync_status(
     }
 }
 
-fn is_image_file(file_key: &str) -> bool {
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/syncs",
+    tag = "sync",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        SyncHistoryQuery,
+    ),
+    responses(
+        (status = 200, body = SyncHistory),
+        (status = 400, description = "Invalid page", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn list_syncs(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<SyncHistoryQuery>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let page = query.page.unwrap_or(1);
+    let per_page = query.per_page.unwrap_or(DEFAULT_SYNCS_PER_PAGE);
+    if page < 1 {
+        return HttpResponse::BadRequest().json("page must be at least 1");
+    }
+    if !(1..=MAX_SYNCS_PER_PAGE).contains(&per_page) {
+        return HttpResponse::BadRequest().json(format!("per_page must be between 1 and {}", MAX_SYNCS_PER_PAGE));
+    }
+
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match get_sync_history_from_db(&pool, project_id, page, per_page).await {
+        Ok(history) => HttpResponse::Ok().json(history),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch sync history"),
+    }
+}
+
+pub fn is_image_file(file_key: &str) -> bool {
     if let Some(ext) = std::path::Path::new(file_key).extension() {
         if let Some(ext_str) = ext.to_str() {
             let image_extensions = ["jpg", "jpeg", "png", "gif", "bmp", "webp", "svg", "ico", "tiff", "tif"];
@@ -356,10 +575,15 @@ fn is_image_file(file_key: &str) -> bool {
     false
 }
 
-async fn get_image_dimensions(
+struct ImageInfo {
+    dimensions: (u32, u32),
+    hashes: crate::duplicates::ImageHashes,
+}
+
+async fn inspect_image(
     storage_provider: &dyn crate::storage::StorageProvider,
     file_key: &str,
-) -> Result<(u32, u32), String> {
+) -> Result<ImageInfo, String> {
     // Download the image
     let image_data = storage_provider.download(file_key)
         .await
@@ -369,8 +593,10 @@ async fn get_image_dimensions(
     let img = image::load_from_memory(&image_data)
         .map_err(|e| format!("Failed to parse image: {}", e))?;
     
-    let (width, height) = img.dimensions();
-    Ok((width, height))
+    Ok(ImageInfo {
+        dimensions: img.dimensions(),
+        hashes: crate::duplicates::ImageHashes::new(&image_data, &img),
+    })
 }
 
 async fn record_sync_start(
@@ -423,6 +649,7 @@ async fn update_sync_progress(
 async fn record_sync_completion(
     pool: &Pool<Postgres>,
     sync_id: Uuid,
+    processed_files: usize,
     tasks_created: usize,
     tasks_skipped: usize,
     errors: &[String],
@@ -434,11 +661,12 @@ async fn record_sync_completion(
     sqlx::query(
         r#"
         UPDATE project_syncs 
-        SET status = $1, tasks_created = $2, tasks_skipped = $3, errors = $4, completed_at = $5
-        WHERE id = $6
+        SET status = $1, processed_files = $2, tasks_created = $3, tasks_skipped = $4, errors = $5, completed_at = $6
+        WHERE id = $7
         "#
     )
     .bind(status)
+    .bind(processed_files as i32)
     .bind(tasks_created as i32)
     .bind(tasks_skipped as i32)
     .bind(errors_json)
@@ -470,6 +698,22 @@ async fn record_sync_error(
     Ok(())
 }
 
+/// Fails the runs a stopped server left 'running'. Their jobs are gone, and the project could
+/// never be synced again while one of its runs is still marked as running.
+pub async fn fail_interrupted_syncs(pool: &Pool<Postgres>) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE project_syncs
+        SET status = 'failed', errors = '["Interrupted by a server restart"]'::jsonb, completed_at = NOW()
+        WHERE status = 'running'
+        "#
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
 async fn get_sync_status_from_db(
     pool: &Pool<Postgres>,
     sync_id: Uuid,
@@ -487,23 +731,57 @@ async fn get_sync_status_from_db(
     .fetch_optional(pool)
     .await?;
 
-    if let Some(row) = row {
-        let errors: Vec<String> = serde_json::from_value(row.get("errors")).unwrap_or_default();
-        
-        Ok(Some(SyncStatus {
-            sync_id: row.get("id"),
-            project_id: row.get("project_id"),
-            status: row.get("status"),
-            total_files: row.get::<i32, _>("total_files") as usize,
-            processed_files: row.get::<i32, _>("processed_files") as usize,
-            tasks_created: row.get::<i32, _>("tasks_created") as usize,
-            tasks_skipped: row.get::<i32, _>("tasks_skipped") as usize,
-            errors,
-            started_at: row.get("started_at"),
-            completed_at: row.get("completed_at"),
-        }))
-    } else {
-        Ok(None)
+    Ok(row.as_ref().map(sync_status_from_row))
+}
+
+async fn get_sync_history_from_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    page: i64,
+    per_page: i64,
+) -> Result<SyncHistory, sqlx::Error> {
+    let total = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM project_syncs WHERE project_id = $1")
+        .bind(project_id)
+        .fetch_one(pool)
+        .await?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT id, project_id, status, total_files, processed_files, tasks_created, tasks_skipped, errors, started_at, completed_at
+        FROM project_syncs
+        WHERE project_id = $1
+        ORDER BY started_at DESC
+        LIMIT $2 OFFSET $3
+        "#
+    )
+    .bind(project_id)
+    .bind(per_page)
+    .bind((page - 1) * per_page)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(SyncHistory {
+        syncs: rows.iter().map(sync_status_from_row).collect(),
+        total,
+        page,
+        per_page,
+    })
+}
+
+fn sync_status_from_row(row: &PgRow) -> SyncStatus {
+    let errors: Vec<String> = serde_json::from_value(row.get("errors")).unwrap_or_default();
+
+    SyncStatus {
+        sync_id: row.get("id"),
+        project_id: row.get("project_id"),
+        status: row.get("status"),
+        total_files: row.get::<i32, _>("total_files") as usize,
+        processed_files: row.get::<i32, _>("processed_files") as usize,
+        tasks_created: row.get::<i32, _>("tasks_created") as usize,
+        tasks_skipped: row.get::<i32, _>("tasks_skipped") as usize,
+        errors,
+        started_at: row.get("started_at"),
+        completed_at: row.get("completed_at"),
     }
 }
 
@@ -529,14 +807,16 @@ async fn create_task_for_file(
     name: &str,
     resource_url: &str,
     dimensions: Option<(u32, u32)>,
-) -> Result<(), sqlx::Error> {
+    hashes: Option<&crate::duplicates::ImageHashes>,
+    source: Option<&crate::storage::StorageMetadata>,
+) -> Result<Uuid, sqlx::Error> {
     let task_id = Uuid::new_v4();
     let now = Utc::now();
 
     sqlx::query(
         r#"
-        INSERT INTO tasks (id, project_id, name, resource_url, status, width, height, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, 'pending', $5, $6, $7, $8)
+        INSERT INTO tasks (id, project_id, name, resource_url, status, width, height, content_hash, perceptual_hash, source_etag, source_size, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, 'pending', $5, $6, $7, $8, $9, $10, $11, $12)
         "#
     )
     .bind(task_id)
@@ -545,12 +825,16 @@ async fn create_task_for_file(
     .bind(resource_url)
     .bind(dimensions.map(|(w, _)| w as i32))
     .bind(dimensions.map(|(_, h)| h as i32))
+    .bind(hashes.map(|hashes| hashes.content_hash.clone()))
+    .bind(hashes.map(|hashes| hashes.perceptual_hash))
+    .bind(source.and_then(|source| source.etag.clone()))
+    .bind(source.and_then(|source| source.content_length).map(|size| size as i64))
     .bind(now)
     .bind(now)
     .execute(pool)
     .await?;
 
-    Ok(())
+    Ok(task_id)
 }
 
 fn extract_task_name_from_file(file_key: &str) -> String {
@@ -562,19 +846,7 @@ fn extract_task_name_from_file(file_key: &str) -> String {
 }
 
 async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
-    sqlx::query_scalar::<_, bool>(
-        r#"
-        SELECT EXISTS(
-            SELECT 1 FROM project_members pm
-            WHERE pm.project_id = $1 AND pm.user_id = $2
-        )
-        "#
-    )
-    .bind(project_id)
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .unwrap_or(false)
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
 }
 
 async fn get_project_by_id(
@@ -582,7 +854,7 @@ async fn get_project_by_id(
     project_id: Uuid,
 ) -> Result<Option<crate::projects::Project>, sqlx::Error> {
     sqlx::query_as::<_, crate::projects::Project>(
-        "SELECT id, name, description, storage_config, owner_id, created_at, updated_at FROM projects WHERE id = $1"
+        "SELECT id, name, description, storage_config, owner_id, task_type, created_at, updated_at FROM projects WHERE id = $1"
     )
     .bind(project_id)
     .fetch_optional(pool)
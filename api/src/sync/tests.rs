@@ -3,11 +3,27 @@ use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 use serde_json::json;
 use serial_test::serial;
-use crate::sync::{sync_storage_to_tasks, get_sync_status};
+use crate::sync::{sync_storage_to_tasks, get_sync_status, list_syncs};
 use crate::test_utils;
 
 
 
+/// Waits for the background job of a sync run and returns the status it ended with
+async fn wait_for_sync(pool: &Pool<Postgres>, sync_id: Uuid) -> String {
+    for _ in 0..100 {
+        let status = sqlx::query_scalar::<_, String>("SELECT status FROM project_syncs WHERE id = $1")
+            .bind(sync_id)
+            .fetch_one(pool)
+            .await
+            .expect("Failed to fetch sync status");
+        if status != "running" {
+            return status;
+        }
+        actix_web::rt::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    panic!("Sync {} is still running", sync_id);
+}
+
 fn create_test_jwt_token(user_id: Uuid, config: &crate::auth::OAuthConfig) -> String {
     let jwt_manager = crate::auth::JwtManager::new(&config.jwt_secret);
     let unique_email = format!("test-{}@example.com", user_id);
@@ -127,20 +143,34 @@ async fn test_sync_with_image_dimensions() {
 
     let resp = test::call_service(&app, req).await;
     let status = resp.status();
-    if status != 200 {
+    if status != 202 {
         let body = test::read_body(resp).await;
         let body_str = std::str::from_utf8(&body).unwrap_or("Invalid UTF-8");
         println!("Error response: {}", body_str);
-        panic!("Expected status 200, got {}", status);
+        panic!("Expected status 202, got {}", status);
     }
-    assert_eq!(status, 200);
+    assert_eq!(status, 202);
 
+    // The sync answers right away and creates the tasks in the background
     let body: serde_json::Value = test::read_body_json(resp).await;
-    assert!(body["tasks_created"].as_u64().unwrap() >= 1);
+    assert_eq!(body["status"], "running");
+    let sync_id = Uuid::parse_str(body["sync_id"].as_str().unwrap()).unwrap();
+    wait_for_sync(&pool, sync_id).await;
+
+    let (total_files, processed_files, tasks_created) = sqlx::query_as::<_, (i32, i32, i32)>(
+        "SELECT total_files, processed_files, tasks_created FROM project_syncs WHERE id = $1"
+    )
+    .bind(sync_id)
+    .fetch_one(&pool)
+    .await
+    .expect("Failed to fetch sync");
+    assert_eq!(total_files, 2);
+    assert_eq!(processed_files, total_files);
+    assert!(tasks_created >= 1);
 
     // Check that the image task has dimensions
     let image_task = sqlx::query!(
-        "SELECT width, height FROM tasks WHERE project_id = $1 AND name = 'test_image.png'",
+        "SELECT width, height, source_key, source_size FROM tasks WHERE project_id = $1 AND name = 'test_image.png'",
         project_id
     )
     .fetch_optional(&pool)
@@ -151,6 +181,9 @@ async fn test_sync_with_image_dimensions() {
     let task = image_task.unwrap();
     assert_eq!(task.width, Some(10));
     assert_eq!(task.height, Some(10));
+    // The file it was made from is remembered for refreshes
+    assert!(task.source_key.is_some_and(|key| key.ends_with("test_image.png")));
+    assert!(task.source_size.is_some());
 
     // Check that the text file task doesn't have dimensions
     let text_task = sqlx::query!(
@@ -218,12 +251,18 @@ async fn test_sync_storage_to_tasks_success() {
     if status.is_success() {
         let body: serde_json::Value = test::read_body_json(resp).await;
         assert!(body["sync_id"].is_string());
+        assert_eq!(body["status"], "running");
         assert!(body["total_files"].is_number());
+        assert!(body["processed_files"].is_number());
         assert!(body["tasks_created"].is_number());
         assert!(body["tasks_skipped"].is_number());
         assert!(body["errors"].is_array());
         assert!(body["started_at"].is_string());
-        assert!(body["completed_at"].is_string());
+        assert!(body["completed_at"].is_null());
+
+        let sync_id = Uuid::parse_str(body["sync_id"].as_str().unwrap()).unwrap();
+        let final_status = wait_for_sync(&pool, sync_id).await;
+        assert!(["completed", "completed_with_errors", "failed"].contains(&final_status.as_str()));
     } else {
         // For mock testing, we might get storage errors which is expected
         let error_body = test::read_body(resp).await;
@@ -318,6 +357,80 @@ async fn test_sync_storage_to_tasks_no_storage_config() {
     cleanup_test_data(&pool, user_id, project_id).await;
 }
 
+#[actix_web::test]
+#[serial]
+async fn test_sync_storage_to_tasks_one_run_at_a_time() {
+    let pool = test_utils::setup_test_db().await;
+    let (user_id, project_id) = test_utils::setup_test_user_and_project_with_sync_storage(&pool).await;
+
+    let config = crate::auth::OAuthConfig {
+        google_client_id: "test".to_string(),
+        google_client_secret: "test".to_string(),
+        google_redirect_url: "test".to_string(),
+        github_client_id: "test".to_string(),
+        github_client_secret: "test".to_string(),
+        github_redirect_url: "test".to_string(),
+        jwt_secret: "test_secret".to_string(),
+    };
+
+    let token = create_test_jwt_token(user_id, &config);
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    sqlx::query("UPDATE projects SET storage_config = $1 WHERE id = $2")
+        .bind(json!({ "type": "local", "base_path": temp_dir.path().to_str().unwrap() }))
+        .bind(project_id)
+        .execute(&pool)
+        .await
+        .expect("Failed to configure storage");
+
+    // A run of the project that hasn't finished yet
+    let running_id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO project_syncs (id, project_id, status, total_files, processed_files, tasks_created, tasks_skipped, errors, started_at)
+        VALUES ($1, $2, 'running', 4200, 137, 130, 7, '[]'::jsonb, NOW())
+        "#
+    )
+    .bind(running_id)
+    .bind(project_id)
+    .execute(&pool)
+    .await
+    .expect("Failed to create test sync");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config))
+            .route("/projects/{project_id}/sync", web::post().to(sync_storage_to_tasks))
+    ).await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/projects/{}/sync", project_id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 409);
+
+    let runs = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM project_syncs WHERE project_id = $1")
+        .bind(project_id)
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to count syncs");
+    assert_eq!(runs, 1);
+
+    // A restarted server fails the run it lost, after which the project syncs again
+    crate::sync::fail_interrupted_syncs(&pool).await.expect("Failed to fail interrupted syncs");
+    let status = sqlx::query_scalar::<_, String>("SELECT status FROM project_syncs WHERE id = $1")
+        .bind(running_id)
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch sync status");
+    assert_eq!(status, "failed");
+
+    cleanup_test_data(&pool, user_id, project_id).await;
+}
+
 #[actix_web::test]
 #[serial]
 async fn test_sync_storage_to_tasks_unauthorized() {
@@ -458,6 +571,88 @@ async fn test_get_sync_status_success() {
     cleanup_test_data(&pool, user_id, project_id).await;
 }
 
+#[actix_web::test]
+#[serial]
+async fn test_list_syncs_paginates_newest_first() {
+    let pool = test_utils::setup_test_db().await;
+    let (user_id, project_id) = test_utils::setup_test_user_and_project_with_sync_storage(&pool).await;
+
+    let config = crate::auth::OAuthConfig {
+        google_client_id: "test".to_string(),
+        google_client_secret: "test".to_string(),
+        google_redirect_url: "test".to_string(),
+        github_client_id: "test".to_string(),
+        github_client_secret: "test".to_string(),
+        github_redirect_url: "test".to_string(),
+        jwt_secret: "test_secret".to_string(),
+    };
+
+    let token = create_test_jwt_token(user_id, &config);
+
+    // A failed run two days ago, a run with errors yesterday and one still running
+    for (status, errors, age) in [
+        ("failed", json!(["Failed to list storage objects: timeout"]), "2 days"),
+        ("completed_with_errors", json!(["Failed to parse image: broken.jpg"]), "1 day"),
+        ("running", json!([]), "0 seconds"),
+    ] {
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO project_syncs (project_id, status, total_files, processed_files, tasks_created, tasks_skipped, errors, started_at)
+            VALUES ($1, $2, 3, 3, 2, 1, $3, NOW() - INTERVAL '{}')
+            "#,
+            age
+        ))
+        .bind(project_id)
+        .bind(status)
+        .bind(errors)
+        .execute(&pool)
+        .await
+        .expect("Failed to create test sync");
+    }
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config))
+            .route("/projects/{project_id}/syncs", web::get().to(list_syncs))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/syncs?per_page=2", project_id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["total"], 3);
+    assert_eq!(body["page"], 1);
+    let syncs = body["syncs"].as_array().unwrap();
+    assert_eq!(syncs.len(), 2);
+    assert_eq!(syncs[0]["status"], "running");
+    assert_eq!(syncs[1]["status"], "completed_with_errors");
+    assert_eq!(syncs[1]["errors"], json!(["Failed to parse image: broken.jpg"]));
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/syncs?page=2&per_page=2", project_id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let syncs = body["syncs"].as_array().unwrap();
+    assert_eq!(syncs.len(), 1);
+    assert_eq!(syncs[0]["status"], "failed");
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/syncs?per_page=0", project_id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    cleanup_test_data(&pool, user_id, project_id).await;
+}
+
 #[actix_web::test]
 #[serial]
 async fn test_get_sync_status_not_found() {
@@ -3,14 +3,16 @@ use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
 
 use crate::auth::{JwtManager, Claims};
+use crate::storage::{StorageError, StorageMetadata};
 use crate::storage::factory::create_storage_provider_from_project;
 
 #[cfg(test)]
 mod tests;
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
 pub struct Task {
     pub id: Uuid,
     pub project_id: Uuid,
@@ -20,39 +22,167 @@ pub struct Task {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
+    /// Active-learning score, higher values are labeled first
+    pub priority: Option<f64>,
+    /// Reason code of an open issue flag, one of `FLAG_REASONS`
+    pub flag_reason: Option<String>,
+    pub flag_note: Option<String>,
+    pub flagged_by: Option<Uuid>,
+    pub flagged_at: Option<DateTime<Utc>>,
+    /// `image`, or `video` for tasks whose frames are listed by the frames endpoint
+    pub media_type: String,
+    pub frame_count: Option<i32>,
+    /// Dataset split, one of `SPLITS`
+    pub split: Option<String>,
+    /// Set while a quality-control sample has the task waiting for review
+    pub review_requested_at: Option<DateTime<Utc>>,
+    /// Storage key of the file the task shows, for `storage://` resource URLs
+    pub source_key: Option<String>,
+    /// ETag of the source file the task was made from, compared by a refresh
+    pub source_etag: Option<String>,
+    /// Size in bytes of the source file the task was made from
+    pub source_size: Option<i64>,
+    /// Set once a refresh found the source file changed or gone
+    pub stale_at: Option<DateTime<Utc>>,
+    /// When the task has to be done, else the deadline settings of the project apply
+    pub due_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Reason codes annotators can flag a problematic image with
+pub const FLAG_REASONS: [&str; 4] = ["corrupted", "wrong_dataset", "cant_tell", "other"];
+
+/// Dataset splits tasks can be put in
+pub const SPLITS: [&str; 3] = ["train", "val", "test"];
+
+pub(crate) const TASK_STATUSES: [&str; 4] = ["pending", "in_progress", "completed", "cancelled"];
+
+/// Lifetime of the presigned URLs handed out for task images
+const PRESIGNED_URL_EXPIRY_SECS: u64 = 3600;
+/// Cached presigned URLs are replaced this long before they expire, so every URL handed out
+/// stays valid at least this long
+const PRESIGNED_URL_REFRESH_MARGIN_SECS: u64 = 600;
+
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateTaskRequest {
     pub name: String,
     pub resource_url: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateTaskRequest {
     pub name: String,
     pub resource_url: Option<String>,
     pub status: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FlagTaskRequest {
+    pub reason: String,
+    pub note: Option<String>,
+}
+
+/// Tasks a batch operation applies to; IDs outside of the project are ignored
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchTasksRequest {
+    pub task_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchStatusRequest {
+    pub task_ids: Vec<Uuid>,
+    pub status: String,
+}
+
+/// Replaces the assignees of every task, an empty `user_ids` unassigns them
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchAssignRequest {
+    pub task_ids: Vec<Uuid>,
+    pub user_ids: Vec<Uuid>,
+}
+
+/// Puts tasks in a split, or takes them out of theirs with `split = None`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchSplitRequest {
+    pub task_ids: Vec<Uuid>,
+    pub split: Option<String>,
+}
+
+/// Gives tasks a due date, or leaves them to the deadline settings with `due_at = None`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchDueRequest {
+    pub task_ids: Vec<Uuid>,
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchTasksResponse {
+    /// Tasks of the project the operation changed
+    pub affected: u64,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RefreshTaskQuery {
+    /// Takes the source file as it is now for the task and clears its stale mark
+    pub accept: Option<bool>,
+}
+
+/// What a refresh found out about the source file of a task
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RefreshTaskResponse {
+    pub task: Task,
+    /// The file in storage differs from the one the task was made from
+    pub changed: bool,
+    /// The file is no longer in storage
+    pub missing: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct TaskResponse {
     pub task: Task,
     pub resolved_resource_url: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct TaskWithResolvedUrl {
     #[serde(flatten)]
     pub task: Task,
     pub resolved_resource_url: Option<String>,
+    /// Boxes in the latest annotation of the task
+    pub annotation_count: i64,
+    /// Names of the annotators the task is assigned to
+    pub assignees: Vec<String>,
+    /// Own due date of the task, or the one the deadline settings of the project give it
+    pub due_date: Option<DateTime<Utc>>,
+    /// Past its due date and neither completed nor cancelled
+    pub overdue: bool,
 }
 
-#[derive(Debug, Serialize)]
+/// Annotation count and assignees of a task, shown on the cards of the task list
+#[derive(Debug, sqlx::FromRow)]
+struct TaskSummary {
+    task_id: Uuid,
+    annotation_count: i64,
+    assignees: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct TasksListResponse {
     pub tasks: Vec<TaskWithResolvedUrl>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/tasks",
+    tag = "tasks",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    request_body = CreateTaskRequest,
+    responses(
+        (status = 201, body = TaskResponse),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
 pub async fn create_task(
     req: HttpRequest,
     path: web::Path<String>,
@@ -107,6 +237,29 @@ pub async fn create_task(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/tasks",
+    tag = "tasks",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("next_unannotated" = Option<bool>, Query, description = "Only the next task the user hasn't annotated yet"),
+        ("random" = Option<bool>, Query, description = "Pick the next unannotated task at random"),
+        ("order" = Option<String>, Query, description = "`priority` to follow the uploaded priority scores"),
+        ("flag" = Option<String>, Query, description = "A flag reason, `any` for all flagged tasks or `none` for unflagged ones"),
+        ("review" = Option<bool>, Query, description = "Only tasks a quality-control sample marked for review"),
+        ("has_annotations" = Option<bool>, Query, description = "Only tasks with (`true`) or without (`false`) a saved annotation"),
+        ("contains_category" = Option<Uuid>, Query, description = "Only tasks whose latest annotation has a box or label of the category"),
+        ("stale" = Option<bool>, Query, description = "Only tasks whose source file a refresh found changed (`true`) or not (`false`)"),
+        ("overdue" = Option<bool>, Query, description = "Only tasks past their due date (`true`) or the others (`false`)"),
+    ),
+    responses(
+        (status = 200, body = TasksListResponse),
+        (status = 400, description = "Invalid flag, annotation, category, stale or overdue filter", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
 pub async fn list_tasks(
     req: HttpRequest,
     path: web::Path<String>,
@@ -139,20 +292,79 @@ pub async fn list_tasks(
     let next_unannotated = query.get("next_unannotated").map(|v| v == "true").unwrap_or(false);
     // Check if random flag is set (only used with next_unannotated)
     let random = query.get("random").map(|v| v == "true").unwrap_or(false);
+    // Check if tasks should follow the uploaded priority scores
+    let by_priority = query.get("order").map(|v| v == "priority").unwrap_or(false);
+    // Filter by flag: a reason code, "any" for all flagged tasks or "none" for unflagged ones
+    let flag = query.get("flag").map(|v| v.as_str());
+    if let Some(flag) = flag {
+        if flag != "any" && flag != "none" && !FLAG_REASONS.contains(&flag) {
+            return HttpResponse::BadRequest().json(format!("Invalid flag filter. Must be one of: any, none, {}", FLAG_REASONS.join(", ")));
+        }
+    }
+
+    // Only tasks waiting for review
+    let review = query.get("review").map(|v| v == "true").unwrap_or(false);
+
+    // Only tasks with or without a saved annotation
+    let has_annotations = match query.get("has_annotations").map(|v| v.as_str()) {
+        None => None,
+        Some("true") => Some(true),
+        Some("false") => Some(false),
+        Some(_) => return HttpResponse::BadRequest().json("Invalid annotation filter. Must be true or false"),
+    };
+
+    // Only tasks whose latest annotation has the category
+    let contains_category = match query.get("contains_category").map(|v| Uuid::parse_str(v)) {
+        None => None,
+        Some(Ok(id)) => Some(id),
+        Some(Err(_)) => return HttpResponse::BadRequest().json("Invalid category ID"),
+    };
+
+    // Only tasks whose source file changed, or the ones still matching it
+    let stale = match query.get("stale").map(|v| v.as_str()) {
+        None => None,
+        Some("true") => Some(true),
+        Some("false") => Some(false),
+        Some(_) => return HttpResponse::BadRequest().json("Invalid stale filter. Must be true or false"),
+    };
+
+    // Only tasks past their due date, or the ones still in time
+    let overdue = match query.get("overdue").map(|v| v.as_str()) {
+        None => None,
+        Some("true") => Some(true),
+        Some("false") => Some(false),
+        Some(_) => return HttpResponse::BadRequest().json("Invalid overdue filter. Must be true or false"),
+    };
+
+    // Due dates follow the project's settings for tasks without their own
+    let deadlines = match crate::deadlines::get_deadline_settings_from_db(&pool, project_id).await {
+        Ok(settings) => settings.unwrap_or_default(),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch tasks"),
+    };
+    let now = Utc::now();
 
     // Get project tasks
     let tasks_result = if next_unannotated {
         if random {
-            get_random_unannotated_task(&pool, project_id).await
+            get_random_unannotated_task(&pool, project_id, user_id, by_priority).await
         } else {
-            get_next_unannotated_task(&pool, project_id).await
+            get_next_unannotated_task(&pool, project_id, user_id, by_priority).await
         }
     } else {
-        get_project_tasks(&pool, project_id).await
+        get_project_tasks(&pool, project_id, by_priority, flag, review, has_annotations, contains_category, stale).await
     };
 
     match tasks_result {
-        Ok(tasks) => {
+        Ok(mut tasks) => {
+            if let Some(overdue) = overdue {
+                tasks.retain(|task| deadlines.is_overdue(task, now) == overdue);
+            }
+            let task_ids: Vec<Uuid> = tasks.iter().map(|task| task.id).collect();
+            let mut summaries: std::collections::HashMap<Uuid, TaskSummary> = match get_task_summaries(&pool, &task_ids).await {
+                Ok(summaries) => summaries.into_iter().map(|summary| (summary.task_id, summary)).collect(),
+                Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch tasks"),
+            };
+
             let mut tasks_with_urls = Vec::new();
             for task in tasks {
                 let resolved_url = if let Some(ref url) = task.resource_url {
@@ -160,9 +372,16 @@ pub async fn list_tasks(
                 } else {
                     None
                 };
+                let summary = summaries.remove(&task.id);
+                let due_date = deadlines.due_date(&task);
+                let overdue = deadlines.is_overdue(&task, now);
                 tasks_with_urls.push(TaskWithResolvedUrl {
                     task,
                     resolved_resource_url: resolved_url,
+                    annotation_count: summary.as_ref().map_or(0, |summary| summary.annotation_count),
+                    assignees: summary.map(|summary| summary.assignees).unwrap_or_default(),
+                    due_date,
+                    overdue,
                 });
             }
             HttpResponse::Ok().json(TasksListResponse { tasks: tasks_with_urls })
@@ -171,6 +390,20 @@ pub async fn list_tasks(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/tasks/{task_id}",
+    tag = "tasks",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    responses(
+        (status = 200, body = TaskResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found", body = String),
+    ),
+)]
 pub async fn get_task(
     req: HttpRequest,
     path: web::Path<(String, String)>,
@@ -222,6 +455,205 @@ pub async fn get_task(
     }
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TaskImageQuery {
+    /// Sign a new URL instead of reusing the cached one, after the previous URL was rejected
+    pub refresh: Option<bool>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/tasks/{task_id}/image",
+    tag = "tasks",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        TaskImageQuery,
+    ),
+    responses(
+        (status = 302, description = "Redirect to a presigned URL of the image that is valid for at least 10 more minutes"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found, or the task has no image", body = String),
+    ),
+)]
+pub async fn get_task_image(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    query: web::Query<TaskImageQuery>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let resource_url = match get_task_by_id(&pool, task_id, project_id).await {
+        Ok(Some(task)) => match task.resource_url {
+            Some(url) => url,
+            None => return HttpResponse::NotFound().json("Task has no image"),
+        },
+        Ok(None) => return HttpResponse::NotFound().json("Task not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch task"),
+    };
+
+    match presign_storage_url(&pool, project_id, &resource_url, query.refresh.unwrap_or(false)).await {
+        // Clients may reuse the redirect as long as the URL behind it is guaranteed to work
+        Some(url) => HttpResponse::Found()
+            .insert_header(("Location", url))
+            .insert_header(("Cache-Control", format!("private, max-age={}", PRESIGNED_URL_REFRESH_MARGIN_SECS)))
+            .finish(),
+        None => HttpResponse::InternalServerError().json("Failed to generate image URL"),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/tasks/{task_id}/refresh",
+    tag = "tasks",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        RefreshTaskQuery,
+    ),
+    responses(
+        (status = 200, body = RefreshTaskResponse),
+        (status = 400, description = "The task has no source file in storage", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found", body = String),
+    ),
+)]
+pub async fn refresh_task(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    query: web::Query<RefreshTaskQuery>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let task = match get_task_by_id(&pool, task_id, project_id).await {
+        Ok(Some(task)) => task,
+        Ok(None) => return HttpResponse::NotFound().json("Task not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch task"),
+    };
+    let Some(source_key) = task.source_key.clone() else {
+        return HttpResponse::BadRequest().json("Task has no source file in storage");
+    };
+
+    let storage_provider = match get_project_by_id(&pool, project_id).await {
+        Ok(Some(project)) => match create_storage_provider_from_project(&project).await {
+            Ok(provider) => provider,
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to access project storage"),
+        },
+        Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    };
+
+    let metadata = match storage_provider.get_metadata(&source_key).await {
+        Ok(metadata) => Some(metadata),
+        Err(StorageError::NotFound) => None,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check the source file"),
+    };
+
+    let changed = metadata.as_ref().is_none_or(|metadata| {
+        source_changed(task.source_etag.as_deref(), task.source_size, metadata)
+    });
+    let result = match &metadata {
+        // A matching file also fills in what tasks from before sources were recorded lack
+        Some(metadata) if !changed || query.accept.unwrap_or(false) => {
+            record_task_source_in_db(&pool, task_id, project_id, metadata).await
+        }
+        _ => mark_task_stale_in_db(&pool, task_id, project_id).await,
+    };
+
+    match result {
+        Ok(Some(task)) => HttpResponse::Ok().json(RefreshTaskResponse {
+            task,
+            changed,
+            missing: metadata.is_none(),
+        }),
+        Ok(None) => HttpResponse::NotFound().json("Task not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to update task"),
+    }
+}
+
+/// Whether the file in storage differs from the one the task was made from. ETags decide when
+/// both sides have one, sizes otherwise; with nothing recorded there is nothing to compare.
+pub fn source_changed(recorded_etag: Option<&str>, recorded_size: Option<i64>, current: &StorageMetadata) -> bool {
+    if let (Some(recorded), Some(current)) = (recorded_etag, current.etag.as_deref()) {
+        return recorded != current;
+    }
+    match (recorded_size, current.content_length) {
+        (Some(recorded), Some(current)) => recorded as u64 != current,
+        _ => false,
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/projects/{project_id}/tasks/{task_id}",
+    tag = "tasks",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    request_body = UpdateTaskRequest,
+    responses(
+        (status = 200, body = TaskResponse),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found", body = String),
+    ),
+)]
 pub async fn update_task(
     req: HttpRequest,
     path: web::Path<(String, String)>,
@@ -261,7 +693,7 @@ pub async fn update_task(
     }
 
     // Validate status
-    if !["pending", "in_progress", "completed", "cancelled"].contains(&payload.status.as_str()) {
+    if !TASK_STATUSES.contains(&payload.status.as_str()) {
         return HttpResponse::BadRequest().json("Invalid status");
     }
 
@@ -288,9 +720,26 @@ pub async fn update_task(
     }
 }
 
-pub async fn delete_task(
+#[utoipa::path(
+    put,
+    path = "/projects/{project_id}/tasks/{task_id}/flag",
+    tag = "tasks",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    request_body = FlagTaskRequest,
+    responses(
+        (status = 200, body = TaskResponse),
+        (status = 400, description = "Unknown flag reason", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found", body = String),
+    ),
+)]
+pub async fn flag_task(
     req: HttpRequest,
     path: web::Path<(String, String)>,
+    payload: web::Json<FlagTaskRequest>,
     pool: web::Data<Pool<Postgres>>,
     config: web::Data<crate::auth::OAuthConfig>,
 ) -> impl Responder {
@@ -316,109 +765,622 @@ pub async fn delete_task(
         Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
     };
 
+    // Validate reason code
+    if !FLAG_REASONS.contains(&payload.reason.as_str()) {
+        return HttpResponse::BadRequest().json(format!("Invalid flag reason. Must be one of: {}", FLAG_REASONS.join(", ")));
+    }
+
     // Check if user has access to this project
     if !user_has_project_access(&pool, project_id, user_id).await {
         return HttpResponse::NotFound().json("Project not found or access denied");
     }
 
-    // Delete task
-    match delete_task_from_db(&pool, task_id, project_id).await {
-        Ok(true) => HttpResponse::NoContent().finish(),
-        Ok(false) => HttpResponse::NotFound().json("Task not found"),
-        Err(_) => HttpResponse::InternalServerError().json("Failed to delete task"),
+    let note = payload.note.as_deref().map(str::trim).filter(|note| !note.is_empty());
+
+    match set_task_flag_in_db(&pool, task_id, project_id, Some(&payload.reason), note, Some(user_id)).await {
+        Ok(Some(task)) => {
+            let resolved_url = if let Some(ref url) = task.resource_url {
+                resolve_storage_url(&pool, project_id, url).await
+            } else {
+                None
+            };
+            HttpResponse::Ok().json(TaskResponse {
+                task,
+                resolved_resource_url: resolved_url,
+            })
+        },
+        Ok(None) => HttpResponse::NotFound().json("Task not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to flag task"),
     }
 }
 
-pub async fn create_task_in_db(
-    pool: &Pool<Postgres>,
-    project_id: Uuid,
-    name: &str,
-    resource_url: Option<&str>,
-) -> Result<Task, sqlx::Error> {
-    let task_id = Uuid::new_v4();
-    let now = Utc::now();
+#[utoipa::path(
+    delete,
+    path = "/projects/{project_id}/tasks/{task_id}/flag",
+    tag = "tasks",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    responses(
+        (status = 204, description = "Flag cleared"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found", body = String),
+    ),
+)]
+pub async fn unflag_task(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
 
-    sqlx::query_as::<_, Task>(
-        r#"
-        INSERT INTO tasks (id, project_id, name, resource_url, status, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, 'pending', $5, $6)
-        RETURNING id, project_id, name, resource_url, status, created_at, updated_at, completed_at
-        "#
-    )
-    .bind(task_id)
-    .bind(project_id)
-    .bind(name)
-    .bind(resource_url)
-    .bind(now)
-    .bind(now)
-    .fetch_one(pool)
-    .await
-}
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
 
-async fn get_project_tasks(pool: &Pool<Postgres>, project_id: Uuid) -> Result<Vec<Task>, sqlx::Error> {
-    sqlx::query_as::<_, Task>(
-        "SELECT id, project_id, name, resource_url, status, created_at, updated_at, completed_at FROM tasks WHERE project_id = $1 ORDER BY created_at DESC"
-    )
-    .bind(project_id)
-    .fetch_all(pool)
-    .await
-}
+    let (project_id_str, task_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
 
-async fn get_next_unannotated_task(pool: &Pool<Postgres>, project_id: Uuid) -> Result<Vec<Task>, sqlx::Error> {
-    sqlx::query_as::<_, Task>(
-        r#"
-        SELECT t.id, t.project_id, t.name, t.resource_url, t.status, t.created_at, t.updated_at, t.completed_at 
-        FROM tasks t
-        WHERE t.project_id = $1 
-        AND t.status != 'completed'
-        AND NOT EXISTS (
-            SELECT 1 FROM annotations a WHERE a.task_id = t.id
-        )
-        ORDER BY t.created_at ASC
-        LIMIT 1
-        "#
-    )
-    .bind(project_id)
-    .fetch_all(pool)
-    .await
-}
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
 
-async fn get_random_unannotated_task(pool: &Pool<Postgres>, project_id: Uuid) -> Result<Vec<Task>, sqlx::Error> {
-    sqlx::query_as::<_, Task>(
-        r#"
-        SELECT t.id, t.project_id, t.name, t.resource_url, t.status, t.created_at, t.updated_at, t.completed_at 
-        FROM tasks t
-        WHERE t.project_id = $1 
-        AND t.status != 'completed'
-        AND NOT EXISTS (
-            SELECT 1 FROM annotations a WHERE a.task_id = t.id
-        )
-        ORDER BY RANDOM()
-        LIMIT 1
-        "#
-    )
-    .bind(project_id)
-    .fetch_all(pool)
-    .await
-}
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
 
-async fn get_task_by_id(
-    pool: &Pool<Postgres>,
-    task_id: Uuid,
-    project_id: Uuid,
-) -> Result<Option<Task>, sqlx::Error> {
-    sqlx::query_as::<_, Task>(
-        "SELECT id, project_id, name, resource_url, status, created_at, updated_at, completed_at FROM tasks WHERE id = $1 AND project_id = $2"
-    )
-    .bind(task_id)
-    .bind(project_id)
-    .fetch_optional(pool)
-    .await
+    match set_task_flag_in_db(&pool, task_id, project_id, None, None, None).await {
+        Ok(Some(_)) => HttpResponse::NoContent().finish(),
+        Ok(None) => HttpResponse::NotFound().json("Task not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to clear task flag"),
+    }
 }
 
-async fn update_task_in_db(
-    pool: &Pool<Postgres>,
-    task_id: Uuid,
+#[utoipa::path(
+    delete,
+    path = "/projects/{project_id}/tasks/{task_id}",
+    tag = "tasks",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    responses(
+        (status = 204, description = "Task deleted"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found", body = String),
+    ),
+)]
+pub async fn delete_task(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    // Delete task
+    match delete_task_from_db(&pool, task_id, project_id).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => HttpResponse::NotFound().json("Task not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to delete task"),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/tasks/batch/delete",
+    tag = "tasks",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    request_body = BatchTasksRequest,
+    responses(
+        (status = 200, body = BatchTasksResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn batch_delete_tasks(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<BatchTasksRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    if payload.task_ids.is_empty() {
+        return HttpResponse::BadRequest().json("No tasks given");
+    }
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match delete_tasks_from_db(&pool, project_id, &payload.task_ids).await {
+        Ok(affected) => HttpResponse::Ok().json(BatchTasksResponse { affected }),
+        Err(err) => {
+            eprintln!("Batch delete error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to delete tasks")
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/tasks/batch/status",
+    tag = "tasks",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    request_body = BatchStatusRequest,
+    responses(
+        (status = 200, body = BatchTasksResponse),
+        (status = 400, description = "Unknown status", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn batch_update_task_status(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<BatchStatusRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    if payload.task_ids.is_empty() {
+        return HttpResponse::BadRequest().json("No tasks given");
+    }
+
+    // Validate status
+    if !TASK_STATUSES.contains(&payload.status.as_str()) {
+        return HttpResponse::BadRequest().json("Invalid status");
+    }
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match set_tasks_status_in_db(&pool, project_id, &payload.task_ids, &payload.status).await {
+        Ok(affected) => HttpResponse::Ok().json(BatchTasksResponse { affected }),
+        Err(err) => {
+            eprintln!("Batch status update error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to update tasks")
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/tasks/batch/assign",
+    tag = "tasks",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    request_body = BatchAssignRequest,
+    responses(
+        (status = 200, body = BatchTasksResponse),
+        (status = 400, description = "An assignee isn't a member of the project", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or the user isn't its owner", body = String),
+    ),
+)]
+pub async fn batch_assign_tasks(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<BatchAssignRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    if payload.task_ids.is_empty() {
+        return HttpResponse::BadRequest().json("No tasks given");
+    }
+
+    // Only project owners may assign annotators, like for a single task
+    match user_is_project_owner(&pool, project_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json("Project not found or permission denied"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to check permissions"),
+    }
+
+    let mut user_ids = payload.user_ids.clone();
+    user_ids.sort();
+    user_ids.dedup();
+    for assignee in &user_ids {
+        if !user_has_project_access(&pool, project_id, *assignee).await {
+            return HttpResponse::BadRequest().json(format!("User {} is not a member of this project", assignee));
+        }
+    }
+
+    match replace_tasks_assignments_in_db(&pool, project_id, &payload.task_ids, &user_ids, user_id).await {
+        Ok(affected) => HttpResponse::Ok().json(BatchTasksResponse { affected }),
+        Err(err) => {
+            eprintln!("Batch assignment error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to update assignments")
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/tasks/batch/split",
+    tag = "tasks",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    request_body = BatchSplitRequest,
+    responses(
+        (status = 200, body = BatchTasksResponse),
+        (status = 400, description = "Unknown split", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn batch_set_task_split(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<BatchSplitRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    if payload.task_ids.is_empty() {
+        return HttpResponse::BadRequest().json("No tasks given");
+    }
+
+    if let Some(split) = &payload.split {
+        if !SPLITS.contains(&split.as_str()) {
+            return HttpResponse::BadRequest().json(format!("Invalid split. Must be one of: {}", SPLITS.join(", ")));
+        }
+    }
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match set_tasks_split_in_db(&pool, project_id, &payload.task_ids, payload.split.as_deref()).await {
+        Ok(affected) => HttpResponse::Ok().json(BatchTasksResponse { affected }),
+        Err(err) => {
+            eprintln!("Batch split update error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to update tasks")
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/tasks/batch/due",
+    tag = "tasks",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    request_body = BatchDueRequest,
+    responses(
+        (status = 200, body = BatchTasksResponse),
+        (status = 400, description = "No tasks given", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn batch_set_task_due(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<BatchDueRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    if payload.task_ids.is_empty() {
+        return HttpResponse::BadRequest().json("No tasks given");
+    }
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match set_tasks_due_in_db(&pool, project_id, &payload.task_ids, payload.due_at).await {
+        Ok(affected) => HttpResponse::Ok().json(BatchTasksResponse { affected }),
+        Err(err) => {
+            eprintln!("Batch due date update error: {:?}", err);
+            HttpResponse::InternalServerError().json("Failed to update tasks")
+        }
+    }
+}
+
+pub async fn create_task_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    name: &str,
+    resource_url: Option<&str>,
+) -> Result<Task, sqlx::Error> {
+    let task_id = Uuid::new_v4();
+    let now = Utc::now();
+
+    sqlx::query_as::<_, Task>(
+        r#"
+        INSERT INTO tasks (id, project_id, name, resource_url, status, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, 'pending', $5, $6)
+        RETURNING id, project_id, name, resource_url, status, created_at, updated_at, completed_at, priority, flag_reason, flag_note, flagged_by, flagged_at, media_type, frame_count, split, review_requested_at, source_key, source_etag, source_size, stale_at, due_at
+        "#
+    )
+    .bind(task_id)
+    .bind(project_id)
+    .bind(name)
+    .bind(resource_url)
+    .bind(now)
+    .bind(now)
+    .fetch_one(pool)
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn get_project_tasks(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    by_priority: bool,
+    flag: Option<&str>,
+    review: bool,
+    has_annotations: Option<bool>,
+    contains_category: Option<Uuid>,
+    stale: Option<bool>,
+) -> Result<Vec<Task>, sqlx::Error> {
+    let order_by = if by_priority { "priority DESC NULLS LAST, created_at ASC" } else { "created_at DESC" };
+    sqlx::query_as::<_, Task>(&format!(
+        r#"
+        SELECT id, project_id, name, resource_url, status, created_at, updated_at, completed_at, priority, flag_reason, flag_note, flagged_by, flagged_at, media_type, frame_count, split, review_requested_at, source_key, source_etag, source_size, stale_at, due_at
+        FROM tasks
+        WHERE project_id = $1
+        AND (
+            $2::TEXT IS NULL
+            OR ($2 = 'any' AND flag_reason IS NOT NULL)
+            OR ($2 = 'none' AND flag_reason IS NULL)
+            OR flag_reason = $2
+        )
+        AND (NOT $3 OR review_requested_at IS NOT NULL)
+        AND ($4::BOOLEAN IS NULL OR EXISTS (SELECT 1 FROM annotations a WHERE a.task_id = tasks.id) = $4)
+        -- Boxes of detection projects, labels of classification ones, in the latest save like the exports
+        AND (
+            $5::UUID IS NULL
+            OR EXISTS (
+                SELECT 1 FROM image_annotations ia
+                WHERE ia.annotation_id = (SELECT a.id FROM annotations a WHERE a.task_id = tasks.id ORDER BY a.created_at DESC LIMIT 1)
+                AND ia.category_id = $5
+                AND NOT ia.is_prediction
+            )
+            OR EXISTS (
+                SELECT 1 FROM image_classifications ic
+                WHERE ic.annotation_id = (SELECT a.id FROM annotations a WHERE a.task_id = tasks.id ORDER BY a.created_at DESC LIMIT 1)
+                AND ic.category_id = $5
+            )
+        )
+        AND ($6::BOOLEAN IS NULL OR (stale_at IS NOT NULL) = $6)
+        ORDER BY {}
+        "#,
+        order_by
+    ))
+    .bind(project_id)
+    .bind(flag)
+    .bind(review)
+    .bind(has_annotations)
+    .bind(contains_category)
+    .bind(stale)
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_task_summaries(pool: &Pool<Postgres>, task_ids: &[Uuid]) -> Result<Vec<TaskSummary>, sqlx::Error> {
+    sqlx::query_as::<_, TaskSummary>(
+        r#"
+        SELECT
+            t.id AS task_id,
+            (
+                SELECT COUNT(*) FROM image_annotations ia
+                WHERE ia.annotation_id = (SELECT a.id FROM annotations a WHERE a.task_id = t.id ORDER BY a.created_at DESC LIMIT 1)
+            ) AS annotation_count,
+            COALESCE(
+                (SELECT ARRAY_AGG(u.name::TEXT ORDER BY u.name) FROM task_assignments ta JOIN users u ON u.id = ta.user_id WHERE ta.task_id = t.id),
+                ARRAY[]::TEXT[]
+            ) AS assignees
+        FROM tasks t
+        WHERE t.id = ANY($1)
+        "#
+    )
+    .bind(task_ids)
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_next_unannotated_task(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid, by_priority: bool) -> Result<Vec<Task>, sqlx::Error> {
+    let order_by = if by_priority { "t.priority DESC NULLS LAST, t.created_at ASC" } else { "t.created_at ASC" };
+    sqlx::query_as::<_, Task>(&format!(
+        r#"
+        SELECT t.id, t.project_id, t.name, t.resource_url, t.status, t.created_at, t.updated_at, t.completed_at, t.priority, t.flag_reason, t.flag_note, t.flagged_by, t.flagged_at, t.media_type, t.frame_count, t.split, t.review_requested_at, t.source_key, t.source_etag, t.source_size, t.stale_at, t.due_at
+        FROM tasks t
+        WHERE t.project_id = $1 
+        AND (t.status != 'completed' OR t.gold_annotation_id IS NOT NULL)
+        AND t.flag_reason IS NULL
+        -- Tasks with assignments stay open for each assignee until they annotated their own copy,
+        -- gold tasks for every annotator
+        AND NOT EXISTS (
+            SELECT 1 FROM annotations a WHERE a.task_id = t.id
+            AND (
+                a.annotated_by = $2
+                OR (t.gold_annotation_id IS NULL AND NOT EXISTS (SELECT 1 FROM task_assignments ta WHERE ta.task_id = t.id))
+            )
+        )
+        AND (
+            NOT EXISTS (SELECT 1 FROM task_assignments ta WHERE ta.task_id = t.id)
+            OR EXISTS (SELECT 1 FROM task_assignments ta WHERE ta.task_id = t.id AND ta.user_id = $2)
+        )
+        ORDER BY {}
+        LIMIT 1
+        "#,
+        order_by
+    ))
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_random_unannotated_task(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid, by_priority: bool) -> Result<Vec<Task>, sqlx::Error> {
+    // With priorities, scored tasks still come first and only ties are broken randomly
+    let order_by = if by_priority { "t.priority DESC NULLS LAST, RANDOM()" } else { "RANDOM()" };
+    sqlx::query_as::<_, Task>(&format!(
+        r#"
+        SELECT t.id, t.project_id, t.name, t.resource_url, t.status, t.created_at, t.updated_at, t.completed_at, t.priority, t.flag_reason, t.flag_note, t.flagged_by, t.flagged_at, t.media_type, t.frame_count, t.split, t.review_requested_at, t.source_key, t.source_etag, t.source_size, t.stale_at, t.due_at
+        FROM tasks t
+        WHERE t.project_id = $1 
+        AND (t.status != 'completed' OR t.gold_annotation_id IS NOT NULL)
+        AND t.flag_reason IS NULL
+        -- Tasks with assignments stay open for each assignee until they annotated their own copy,
+        -- gold tasks for every annotator
+        AND NOT EXISTS (
+            SELECT 1 FROM annotations a WHERE a.task_id = t.id
+            AND (
+                a.annotated_by = $2
+                OR (t.gold_annotation_id IS NULL AND NOT EXISTS (SELECT 1 FROM task_assignments ta WHERE ta.task_id = t.id))
+            )
+        )
+        AND (
+            NOT EXISTS (SELECT 1 FROM task_assignments ta WHERE ta.task_id = t.id)
+            OR EXISTS (SELECT 1 FROM task_assignments ta WHERE ta.task_id = t.id AND ta.user_id = $2)
+        )
+        ORDER BY {}
+        LIMIT 1
+        "#,
+        order_by
+    ))
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_task_by_id(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
+    project_id: Uuid,
+) -> Result<Option<Task>, sqlx::Error> {
+    sqlx::query_as::<_, Task>(
+        "SELECT id, project_id, name, resource_url, status, created_at, updated_at, completed_at, priority, flag_reason, flag_note, flagged_by, flagged_at, media_type, frame_count, split, review_requested_at, source_key, source_etag, source_size, stale_at, due_at FROM tasks WHERE id = $1 AND project_id = $2"
+    )
+    .bind(task_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn update_task_in_db(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
     project_id: Uuid,
     name: &str,
     resource_url: Option<&str>,
@@ -430,9 +1392,13 @@ async fn update_task_in_db(
     sqlx::query_as::<_, Task>(
         r#"
         UPDATE tasks 
-        SET name = $1, resource_url = $2, status = $3, updated_at = $4, completed_at = $5
+        SET name = $1, resource_url = $2, status = $3, updated_at = $4, completed_at = $5,
+            -- What was known about the old file says nothing about a new one
+            source_etag = CASE WHEN resource_url IS NOT DISTINCT FROM $2 THEN source_etag END,
+            source_size = CASE WHEN resource_url IS NOT DISTINCT FROM $2 THEN source_size END,
+            stale_at = CASE WHEN resource_url IS NOT DISTINCT FROM $2 THEN stale_at END
         WHERE id = $6 AND project_id = $7
-        RETURNING id, project_id, name, resource_url, status, created_at, updated_at, completed_at
+        RETURNING id, project_id, name, resource_url, status, created_at, updated_at, completed_at, priority, flag_reason, flag_note, flagged_by, flagged_at, media_type, frame_count, split, review_requested_at, source_key, source_etag, source_size, stale_at, due_at
         "#
     )
     .bind(name)
@@ -446,6 +1412,48 @@ async fn update_task_in_db(
     .await
 }
 
+async fn record_task_source_in_db(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
+    project_id: Uuid,
+    metadata: &StorageMetadata,
+) -> Result<Option<Task>, sqlx::Error> {
+    sqlx::query_as::<_, Task>(
+        r#"
+        UPDATE tasks
+        SET source_etag = $1, source_size = $2, stale_at = NULL
+        WHERE id = $3 AND project_id = $4
+        RETURNING id, project_id, name, resource_url, status, created_at, updated_at, completed_at, priority, flag_reason, flag_note, flagged_by, flagged_at, media_type, frame_count, split, review_requested_at, source_key, source_etag, source_size, stale_at, due_at
+        "#
+    )
+    .bind(&metadata.etag)
+    .bind(metadata.content_length.map(|size| size as i64))
+    .bind(task_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Keeps the first time the change was noticed
+async fn mark_task_stale_in_db(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
+    project_id: Uuid,
+) -> Result<Option<Task>, sqlx::Error> {
+    sqlx::query_as::<_, Task>(
+        r#"
+        UPDATE tasks
+        SET stale_at = COALESCE(stale_at, NOW())
+        WHERE id = $1 AND project_id = $2
+        RETURNING id, project_id, name, resource_url, status, created_at, updated_at, completed_at, priority, flag_reason, flag_note, flagged_by, flagged_at, media_type, frame_count, split, review_requested_at, source_key, source_etag, source_size, stale_at, due_at
+        "#
+    )
+    .bind(task_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
 async fn delete_task_from_db(
     pool: &Pool<Postgres>,
     task_id: Uuid,
@@ -460,12 +1468,155 @@ async fn delete_task_from_db(
     Ok(result.rows_affected() > 0)
 }
 
+async fn delete_tasks_from_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    task_ids: &[Uuid],
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM tasks WHERE project_id = $1 AND id = ANY($2)")
+        .bind(project_id)
+        .bind(task_ids)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+async fn set_tasks_status_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    task_ids: &[Uuid],
+    status: &str,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE tasks
+        SET status = $1, updated_at = NOW(),
+            completed_at = CASE WHEN $1 = 'completed' THEN NOW() ELSE NULL END
+        WHERE project_id = $2 AND id = ANY($3)
+        "#
+    )
+    .bind(status)
+    .bind(project_id)
+    .bind(task_ids)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+async fn set_tasks_split_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    task_ids: &[Uuid],
+    split: Option<&str>,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("UPDATE tasks SET split = $1, updated_at = NOW() WHERE project_id = $2 AND id = ANY($3)")
+        .bind(split)
+        .bind(project_id)
+        .bind(task_ids)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+async fn set_tasks_due_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    task_ids: &[Uuid],
+    due_at: Option<DateTime<Utc>>,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("UPDATE tasks SET due_at = $1, updated_at = NOW() WHERE project_id = $2 AND id = ANY($3)")
+        .bind(due_at)
+        .bind(project_id)
+        .bind(task_ids)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Gives every task of the project among `task_ids` exactly the assignees in `user_ids`.
+/// Returns the number of tasks changed.
+async fn replace_tasks_assignments_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    task_ids: &[Uuid],
+    user_ids: &[Uuid],
+    assigned_by: Uuid,
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let project_task_ids: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM tasks WHERE project_id = $1 AND id = ANY($2)")
+        .bind(project_id)
+        .bind(task_ids)
+        .fetch_all(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM task_assignments WHERE task_id = ANY($1) AND NOT (user_id = ANY($2))")
+        .bind(&project_task_ids)
+        .bind(user_ids)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO task_assignments (task_id, user_id, assigned_by, created_at)
+        SELECT task_id, user_id, $3, NOW()
+        FROM UNNEST($1::UUID[]) AS task_id CROSS JOIN UNNEST($2::UUID[]) AS user_id
+        ON CONFLICT (task_id, user_id) DO NOTHING
+        "#
+    )
+    .bind(&project_task_ids)
+    .bind(user_ids)
+    .bind(assigned_by)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(project_task_ids.len() as u64)
+}
+
+/// Sets or clears (with `reason = None`) the issue flag of a task.
+async fn set_task_flag_in_db(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
+    project_id: Uuid,
+    reason: Option<&str>,
+    note: Option<&str>,
+    flagged_by: Option<Uuid>,
+) -> Result<Option<Task>, sqlx::Error> {
+    sqlx::query_as::<_, Task>(
+        r#"
+        UPDATE tasks
+        SET flag_reason = $1, flag_note = $2, flagged_by = $3,
+            flagged_at = CASE WHEN $1::TEXT IS NULL THEN NULL ELSE NOW() END,
+            updated_at = NOW()
+        WHERE id = $4 AND project_id = $5
+        RETURNING id, project_id, name, resource_url, status, created_at, updated_at, completed_at, priority, flag_reason, flag_note, flagged_by, flagged_at, media_type, frame_count, split, review_requested_at, source_key, source_etag, source_size, stale_at, due_at
+        "#
+    )
+    .bind(reason)
+    .bind(note)
+    .bind(flagged_by)
+    .bind(task_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
 async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+async fn user_is_project_owner(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
     sqlx::query_scalar::<_, bool>(
         r#"
         SELECT EXISTS(
             SELECT 1 FROM project_members pm
-            WHERE pm.project_id = $1 AND pm.user_id = $2
+            INNER JOIN projects p ON pm.project_id = p.id
+            WHERE p.id = $1 AND pm.user_id = $2 AND (pm.role = 'owner' OR p.owner_id = $2)
         )
         "#
     )
@@ -473,7 +1624,6 @@ async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_i
     .bind(user_id)
     .fetch_one(pool)
     .await
-    .unwrap_or(false)
 }
 
 fn extract_user_claims(
@@ -502,18 +1652,33 @@ fn extract_user_claims(
     }
 }
 
-async fn resolve_storage_url(
+pub(crate) async fn resolve_storage_url(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    storage_url: &str,
+) -> Option<String> {
+    presign_storage_url(pool, project_id, storage_url, false).await
+}
+
+/// Presigned URL of a `storage://` URL, reused from the cache until it is due for a refresh.
+/// `refresh` signs a new one regardless, for clients whose URL was rejected.
+async fn presign_storage_url(
     pool: &Pool<Postgres>,
     project_id: Uuid,
     storage_url: &str,
+    refresh: bool,
 ) -> Option<String> {
     // Only process storage:// URLs
-    if !storage_url.starts_with("storage://") {
+    let Some(key) = storage_url.strip_prefix("storage://") else {
         return Some(storage_url.to_string());
+    };
+
+    if !refresh {
+        if let Some(url) = crate::cache::presigned_url(project_id, key).await {
+            return Some(url);
+        }
     }
     
-    let key = &storage_url[10..]; // Remove "storage://" prefix
-    
     // Get project to access storage configuration
     let project = match get_project_by_id(pool, project_id).await {
         Ok(Some(project)) => project,
@@ -526,8 +1691,14 @@ async fn resolve_storage_url(
         _ => return None,
     };
     
-    // Generate presigned URL with 1 hour expiry
-    storage_provider.get_presigned_url(key, 3600).await.ok()
+    let url = storage_provider.get_presigned_url(key, PRESIGNED_URL_EXPIRY_SECS).await.ok()?;
+    crate::cache::set_presigned_url(
+        project_id,
+        key,
+        &url,
+        PRESIGNED_URL_EXPIRY_SECS - PRESIGNED_URL_REFRESH_MARGIN_SECS,
+    ).await;
+    Some(url)
 }
 
 async fn get_project_by_id(
@@ -535,7 +1706,7 @@ async fn get_project_by_id(
     project_id: Uuid,
 ) -> Result<Option<crate::projects::Project>, sqlx::Error> {
     sqlx::query_as::<_, crate::projects::Project>(
-        "SELECT id, name, description, storage_config, owner_id, created_at, updated_at FROM projects WHERE id = $1"
+        "SELECT id, name, description, storage_config, owner_id, task_type, created_at, updated_at FROM projects WHERE id = $1"
     )
     .bind(project_id)
     .fetch_optional(pool)
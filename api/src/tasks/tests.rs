@@ -3,7 +3,7 @@ use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 use serde_json::json;
 use serial_test::serial;
-use crate::tasks::{create_task, list_tasks, get_task, update_task, delete_task, create_task_in_db, get_task_by_id};
+use crate::tasks::{create_task, list_tasks, get_task, get_task_image, update_task, delete_task, flag_task, unflag_task, batch_delete_tasks, batch_update_task_status, batch_assign_tasks, batch_set_task_split, batch_set_task_due, create_task_in_db, get_task_by_id, refresh_task, source_changed};
 use crate::test_utils;
 
 
@@ -699,4 +699,647 @@ async fn test_list_tasks_random_without_next_unannotated() {
     assert_eq!(tasks.len(), 2);
 
     cleanup_test_data(&pool, user_id, project_id).await;
-}
\ No newline at end of file
+}
+#[actix_web::test]
+#[serial]
+async fn test_flag_task_and_filter() {
+    let pool = test_utils::setup_test_db().await;
+    let (user_id, project_id) = test_utils::setup_test_user_and_project(&pool).await;
+    
+    let config = crate::auth::OAuthConfig {
+        google_client_id: "test".to_string(),
+        google_client_secret: "test".to_string(),
+        google_redirect_url: "test".to_string(),
+        github_client_id: "test".to_string(),
+        github_client_secret: "test".to_string(),
+        github_redirect_url: "test".to_string(),
+        jwt_secret: "test_secret".to_string(),
+    };
+    
+    let token = create_test_jwt_token(user_id, &config);
+
+    let task1 = create_task_in_db(&pool, project_id, "Task 1", None).await.unwrap();
+    let task2 = create_task_in_db(&pool, project_id, "Task 2", None).await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config))
+            .route("/projects/{project_id}/tasks", web::get().to(list_tasks))
+            .route("/projects/{project_id}/tasks/{task_id}/flag", web::put().to(flag_task))
+            .route("/projects/{project_id}/tasks/{task_id}/flag", web::delete().to(unflag_task))
+    ).await;
+
+    // Unknown reason codes are rejected
+    let req = test::TestRequest::put()
+        .uri(&format!("/projects/{}/tasks/{}/flag", project_id, task1.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({"reason": "ugly"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/projects/{}/tasks/{}/flag", project_id, task1.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({"reason": "corrupted", "note": "Half of the image is grey"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["task"]["flag_reason"], "corrupted");
+    assert_eq!(body["task"]["flagged_by"], user_id.to_string());
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/tasks?flag=corrupted", project_id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let tasks = body["tasks"].as_array().unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0]["id"], task1.id.to_string());
+
+    // Flagged tasks are skipped by the next-task queue
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/tasks?next_unannotated=true", project_id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["tasks"][0]["id"], task2.id.to_string());
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/projects/{}/tasks/{}/flag", project_id, task1.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 204);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/tasks?flag=any", project_id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body["tasks"].as_array().unwrap().is_empty());
+
+    cleanup_test_data(&pool, user_id, project_id).await;
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_list_tasks_summaries() {
+    let pool = test_utils::setup_test_db().await;
+    let (user_id, project_id) = test_utils::setup_test_user_and_project(&pool).await;
+
+    let config = crate::auth::OAuthConfig {
+        google_client_id: "test".to_string(),
+        google_client_secret: "test".to_string(),
+        google_redirect_url: "test".to_string(),
+        github_client_id: "test".to_string(),
+        github_client_secret: "test".to_string(),
+        github_redirect_url: "test".to_string(),
+        jwt_secret: "test_secret".to_string(),
+    };
+
+    let token = create_test_jwt_token(user_id, &config);
+
+    let annotated = create_task_in_db(&pool, project_id, "Annotated", None).await.unwrap();
+    let _empty = create_task_in_db(&pool, project_id, "Empty", None).await.unwrap();
+
+    // An older annotation with one box, then the latest one with two
+    for (boxes, age) in [(1, "1 hour"), (2, "0 seconds")] {
+        let annotation_id = Uuid::new_v4();
+        sqlx::query(&format!(
+            "INSERT INTO annotations (id, task_id, annotated_by, created_at) VALUES ($1, $2, $3, NOW() - INTERVAL '{}')",
+            age
+        ))
+        .bind(annotation_id)
+        .bind(annotated.id)
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        for _ in 0..boxes {
+            sqlx::query("INSERT INTO image_annotations (annotation_id, bbox) VALUES ($1, ARRAY[0, 0, 10, 10]::FLOAT[])")
+                .bind(annotation_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+    }
+    sqlx::query("INSERT INTO task_assignments (task_id, user_id) VALUES ($1, $2)")
+        .bind(annotated.id)
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config))
+            .route("/projects/{project_id}/tasks", web::get().to(list_tasks))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/projects/{}/tasks", project_id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let tasks = body["tasks"].as_array().unwrap();
+    let task = |name: &str| tasks.iter().find(|task| task["name"] == name).unwrap().clone();
+    assert_eq!(task("Annotated")["annotation_count"], 2);
+    assert_eq!(task("Annotated")["assignees"], json!(["Test User"]));
+    assert_eq!(task("Empty")["annotation_count"], 0);
+    assert_eq!(task("Empty")["assignees"], json!([]));
+
+    cleanup_test_data(&pool, user_id, project_id).await;
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_filter_tasks_by_annotations_and_category() {
+    let pool = test_utils::setup_test_db().await;
+    let (user_id, project_id) = test_utils::setup_test_user_and_project(&pool).await;
+
+    let config = crate::auth::OAuthConfig {
+        google_client_id: "test".to_string(),
+        google_client_secret: "test".to_string(),
+        google_redirect_url: "test".to_string(),
+        github_client_id: "test".to_string(),
+        github_client_secret: "test".to_string(),
+        github_redirect_url: "test".to_string(),
+        jwt_secret: "test_secret".to_string(),
+    };
+
+    let token = create_test_jwt_token(user_id, &config);
+
+    let forklift = Uuid::new_v4();
+    let person = Uuid::new_v4();
+    for (id, name) in [(forklift, "forklift"), (person, "person")] {
+        sqlx::query("INSERT INTO image_annotation_categories (id, project_id, name) VALUES ($1, $2, $3)")
+            .bind(id)
+            .bind(project_id)
+            .bind(name)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let with_forklift = create_task_in_db(&pool, project_id, "With forklift", None).await.unwrap();
+    let forklift_removed = create_task_in_db(&pool, project_id, "Forklift removed", None).await.unwrap();
+    let unlabeled = create_task_in_db(&pool, project_id, "Unlabeled", None).await.unwrap();
+
+    // The second task had a forklift in an older save only
+    for (task_id, category_id, age) in [
+        (with_forklift.id, forklift, "0 seconds"),
+        (forklift_removed.id, forklift, "1 hour"),
+        (forklift_removed.id, person, "0 seconds"),
+    ] {
+        let annotation_id = Uuid::new_v4();
+        sqlx::query(&format!(
+            "INSERT INTO annotations (id, task_id, annotated_by, created_at) VALUES ($1, $2, $3, NOW() - INTERVAL '{}')",
+            age
+        ))
+        .bind(annotation_id)
+        .bind(task_id)
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO image_annotations (annotation_id, category_id, bbox) VALUES ($1, $2, ARRAY[0, 0, 10, 10]::FLOAT[])")
+            .bind(annotation_id)
+            .bind(category_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config))
+            .route("/projects/{project_id}/tasks", web::get().to(list_tasks))
+    ).await;
+
+    let list = |filter: String| {
+        test::TestRequest::get()
+            .uri(&format!("/projects/{}/tasks?{}", project_id, filter))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+    let ids = |body: serde_json::Value| -> Vec<String> {
+        body["tasks"].as_array().unwrap().iter().map(|task| task["id"].as_str().unwrap().to_string()).collect()
+    };
+
+    let resp = test::call_service(&app, list("has_annotations=false".to_string())).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(ids(test::read_body_json(resp).await), vec![unlabeled.id.to_string()]);
+
+    let resp = test::call_service(&app, list("has_annotations=true".to_string())).await;
+    assert_eq!(ids(test::read_body_json(resp).await).len(), 2);
+
+    let resp = test::call_service(&app, list(format!("contains_category={}", forklift))).await;
+    assert_eq!(ids(test::read_body_json(resp).await), vec![with_forklift.id.to_string()]);
+
+    let resp = test::call_service(&app, list(format!("has_annotations=false&contains_category={}", person))).await;
+    assert!(ids(test::read_body_json(resp).await).is_empty());
+
+    let resp = test::call_service(&app, list("has_annotations=maybe".to_string())).await;
+    assert_eq!(resp.status(), 400);
+
+    let resp = test::call_service(&app, list("contains_category=forklift".to_string())).await;
+    assert_eq!(resp.status(), 400);
+
+    cleanup_test_data(&pool, user_id, project_id).await;
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_batch_task_operations() {
+    let pool = test_utils::setup_test_db().await;
+    let (user_id, project_id) = test_utils::setup_test_user_and_project(&pool).await;
+    let (other_user_id, other_project_id) = test_utils::setup_test_user_and_project(&pool).await;
+
+    let config = crate::auth::OAuthConfig {
+        google_client_id: "test".to_string(),
+        google_client_secret: "test".to_string(),
+        google_redirect_url: "test".to_string(),
+        github_client_id: "test".to_string(),
+        github_client_secret: "test".to_string(),
+        github_redirect_url: "test".to_string(),
+        jwt_secret: "test_secret".to_string(),
+    };
+
+    let token = create_test_jwt_token(user_id, &config);
+
+    let first = create_task_in_db(&pool, project_id, "First", None).await.unwrap();
+    let second = create_task_in_db(&pool, project_id, "Second", None).await.unwrap();
+    let untouched = create_task_in_db(&pool, project_id, "Untouched", None).await.unwrap();
+    // Tasks of other projects are ignored
+    let foreign = create_task_in_db(&pool, other_project_id, "Foreign", None).await.unwrap();
+    let task_ids = json!([first.id, second.id, foreign.id]);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config))
+            .route("/projects/{project_id}/tasks/batch/delete", web::post().to(batch_delete_tasks))
+            .route("/projects/{project_id}/tasks/batch/status", web::post().to(batch_update_task_status))
+            .route("/projects/{project_id}/tasks/batch/assign", web::post().to(batch_assign_tasks))
+            .route("/projects/{project_id}/tasks/batch/split", web::post().to(batch_set_task_split))
+    ).await;
+
+    let post = |operation: &str, body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&format!("/projects/{}/tasks/batch/{}", project_id, operation))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(body)
+            .to_request()
+    };
+
+    // Status
+    let resp = test::call_service(&app, post("status", json!({ "task_ids": task_ids, "status": "completed" }))).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["affected"], 2);
+    let task = get_task_by_id(&pool, first.id, project_id).await.unwrap().unwrap();
+    assert_eq!(task.status, "completed");
+    assert!(task.completed_at.is_some());
+    let task = get_task_by_id(&pool, untouched.id, project_id).await.unwrap().unwrap();
+    assert_eq!(task.status, "pending");
+    let task = get_task_by_id(&pool, foreign.id, other_project_id).await.unwrap().unwrap();
+    assert_eq!(task.status, "pending");
+
+    let resp = test::call_service(&app, post("status", json!({ "task_ids": task_ids, "status": "done" }))).await;
+    assert_eq!(resp.status(), 400);
+
+    // Split
+    let resp = test::call_service(&app, post("split", json!({ "task_ids": task_ids, "split": "val" }))).await;
+    assert_eq!(resp.status(), 200);
+    let task = get_task_by_id(&pool, second.id, project_id).await.unwrap().unwrap();
+    assert_eq!(task.split.as_deref(), Some("val"));
+
+    let resp = test::call_service(&app, post("split", json!({ "task_ids": task_ids, "split": "holdout" }))).await;
+    assert_eq!(resp.status(), 400);
+
+    let resp = test::call_service(&app, post("split", json!({ "task_ids": task_ids, "split": null }))).await;
+    assert_eq!(resp.status(), 200);
+    let task = get_task_by_id(&pool, second.id, project_id).await.unwrap().unwrap();
+    assert_eq!(task.split, None);
+
+    // Assign, only members of the project can be assignees
+    let resp = test::call_service(&app, post("assign", json!({ "task_ids": task_ids, "user_ids": [other_user_id] }))).await;
+    assert_eq!(resp.status(), 400);
+
+    let resp = test::call_service(&app, post("assign", json!({ "task_ids": task_ids, "user_ids": [user_id] }))).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["affected"], 2);
+    let assigned: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM task_assignments WHERE task_id = ANY($1) AND user_id = $2")
+        .bind(vec![first.id, second.id, untouched.id, foreign.id])
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(assigned, 2);
+
+    let resp = test::call_service(&app, post("assign", json!({ "task_ids": task_ids, "user_ids": [] }))).await;
+    assert_eq!(resp.status(), 200);
+    let assigned: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM task_assignments WHERE task_id = ANY($1)")
+        .bind(vec![first.id, second.id])
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(assigned, 0);
+
+    // Delete
+    let resp = test::call_service(&app, post("delete", json!({ "task_ids": [] }))).await;
+    assert_eq!(resp.status(), 400);
+
+    let resp = test::call_service(&app, post("delete", json!({ "task_ids": task_ids }))).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["affected"], 2);
+    assert!(get_task_by_id(&pool, first.id, project_id).await.unwrap().is_none());
+    assert!(get_task_by_id(&pool, untouched.id, project_id).await.unwrap().is_some());
+    assert!(get_task_by_id(&pool, foreign.id, other_project_id).await.unwrap().is_some());
+
+    cleanup_test_data(&pool, user_id, project_id).await;
+    cleanup_test_data(&pool, other_user_id, other_project_id).await;
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_get_task_image_redirects() {
+    let pool = test_utils::setup_test_db().await;
+    let (user_id, project_id) = test_utils::setup_test_user_and_project_with_storage(&pool).await;
+
+    let config = crate::auth::OAuthConfig {
+        google_client_id: "test".to_string(),
+        google_client_secret: "test".to_string(),
+        google_redirect_url: "test".to_string(),
+        github_client_id: "test".to_string(),
+        github_client_secret: "test".to_string(),
+        github_redirect_url: "test".to_string(),
+        jwt_secret: "test_secret".to_string(),
+    };
+
+    let token = create_test_jwt_token(user_id, &config);
+
+    std::fs::create_dir_all("/tmp/fast_tag_test").unwrap();
+    std::fs::write("/tmp/fast_tag_test/task-image.jpg", "not really a jpeg").unwrap();
+    let stored = create_task_in_db(&pool, project_id, "Stored", Some("storage://task-image.jpg")).await.unwrap();
+    let external = create_task_in_db(&pool, project_id, "External", Some("https://example.com/image.jpg")).await.unwrap();
+    let empty = create_task_in_db(&pool, project_id, "Empty", None).await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config))
+            .route("/projects/{project_id}/tasks/{task_id}/image", web::get().to(get_task_image))
+    ).await;
+
+    let get = |task_id: Uuid, query: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/projects/{}/tasks/{}/image{}", project_id, task_id, query))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+
+    let resp = test::call_service(&app, get(stored.id, "")).await;
+    assert_eq!(resp.status(), 302);
+    let location = resp.headers().get("Location").unwrap().to_str().unwrap();
+    assert!(location.contains("task-image.jpg"));
+    assert!(resp.headers().get("Cache-Control").unwrap().to_str().unwrap().starts_with("private"));
+
+    let resp = test::call_service(&app, get(stored.id, "?refresh=true")).await;
+    assert_eq!(resp.status(), 302);
+
+    // URLs outside the project's storage are passed through
+    let resp = test::call_service(&app, get(external.id, "")).await;
+    assert_eq!(resp.status(), 302);
+    assert_eq!(resp.headers().get("Location").unwrap(), "https://example.com/image.jpg");
+
+    let resp = test::call_service(&app, get(empty.id, "")).await;
+    assert_eq!(resp.status(), 404);
+
+    let resp = test::call_service(&app, get(Uuid::new_v4(), "")).await;
+    assert_eq!(resp.status(), 404);
+
+    cleanup_test_data(&pool, user_id, project_id).await;
+}
+
+#[actix_web::test]
+async fn test_source_changed() {
+    let metadata = |etag: Option<&str>, size: Option<u64>| crate::storage::StorageMetadata {
+        content_type: None,
+        content_length: size,
+        etag: etag.map(str::to_string),
+        last_modified: None,
+    };
+
+    assert!(!source_changed(Some("\"a\""), Some(10), &metadata(Some("\"a\""), Some(10))));
+    assert!(source_changed(Some("\"a\""), Some(10), &metadata(Some("\"b\""), Some(10))));
+    // ETags win over sizes when both sides have one
+    assert!(!source_changed(Some("\"a\""), Some(10), &metadata(Some("\"a\""), Some(12))));
+    assert!(source_changed(None, Some(10), &metadata(Some("\"a\""), Some(12))));
+    assert!(!source_changed(None, Some(10), &metadata(None, Some(10))));
+    // Nothing recorded, nothing to compare
+    assert!(!source_changed(None, None, &metadata(Some("\"a\""), Some(12))));
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_refresh_task_marks_changed_source_stale() {
+    let pool = test_utils::setup_test_db().await;
+    let (user_id, project_id) = test_utils::setup_test_user_and_project_with_storage(&pool).await;
+
+    let config = crate::auth::OAuthConfig {
+        google_client_id: "test".to_string(),
+        google_client_secret: "test".to_string(),
+        google_redirect_url: "test".to_string(),
+        github_client_id: "test".to_string(),
+        github_client_secret: "test".to_string(),
+        github_redirect_url: "test".to_string(),
+        jwt_secret: "test_secret".to_string(),
+    };
+
+    let token = create_test_jwt_token(user_id, &config);
+
+    std::fs::create_dir_all("/tmp/fast_tag_test").unwrap();
+    std::fs::write("/tmp/fast_tag_test/refresh-source.jpg", "first version").unwrap();
+    let _ = std::fs::remove_file("/tmp/fast_tag_test/refresh-missing.jpg");
+    let stored = create_task_in_db(&pool, project_id, "Stored", Some("storage://refresh-source.jpg")).await.unwrap();
+    let missing = create_task_in_db(&pool, project_id, "Missing", Some("storage://refresh-missing.jpg")).await.unwrap();
+    let external = create_task_in_db(&pool, project_id, "External", Some("https://example.com/image.jpg")).await.unwrap();
+    assert_eq!(stored.source_key.as_deref(), Some("refresh-source.jpg"));
+    assert_eq!(external.source_key, None);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config))
+            .route("/projects/{project_id}/tasks/{task_id}/refresh", web::post().to(refresh_task))
+            .route("/projects/{project_id}/tasks", web::get().to(list_tasks))
+    ).await;
+
+    let refresh = |task_id: Uuid, query: &str| {
+        test::TestRequest::post()
+            .uri(&format!("/projects/{}/tasks/{}/refresh{}", project_id, task_id, query))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+    let list_stale = |stale: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/projects/{}/tasks?stale={}", project_id, stale))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+    let names = |body: serde_json::Value| {
+        let mut names: Vec<String> = body["tasks"].as_array().unwrap().iter()
+            .map(|task| task["name"].as_str().unwrap().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+
+    // The first refresh records the file the task was made from
+    let body: serde_json::Value = test::call_and_read_body_json(&app, refresh(stored.id, "")).await;
+    assert_eq!(body["changed"], false);
+    assert_eq!(body["missing"], false);
+    assert_eq!(body["task"]["source_size"], 13);
+    assert!(body["task"]["stale_at"].is_null());
+
+    std::fs::write("/tmp/fast_tag_test/refresh-source.jpg", "second, longer version").unwrap();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, refresh(stored.id, "")).await;
+    assert_eq!(body["changed"], true);
+    assert!(!body["task"]["stale_at"].is_null());
+    assert_eq!(body["task"]["source_size"], 13);
+
+    let body: serde_json::Value = test::call_and_read_body_json(&app, refresh(missing.id, "")).await;
+    assert_eq!(body["missing"], true);
+    assert!(!body["task"]["stale_at"].is_null());
+
+    assert_eq!(names(test::call_and_read_body_json(&app, list_stale("true")).await), vec!["Missing", "Stored"]);
+    assert_eq!(names(test::call_and_read_body_json(&app, list_stale("false")).await), vec!["External"]);
+
+    // Accepting the new file clears the mark
+    let body: serde_json::Value = test::call_and_read_body_json(&app, refresh(stored.id, "?accept=true")).await;
+    assert_eq!(body["changed"], true);
+    assert!(body["task"]["stale_at"].is_null());
+    assert_eq!(body["task"]["source_size"], 22);
+    assert_eq!(names(test::call_and_read_body_json(&app, list_stale("true")).await), vec!["Missing"]);
+
+    let resp = test::call_service(&app, refresh(external.id, "")).await;
+    assert_eq!(resp.status(), 400);
+
+    let resp = test::call_service(&app, refresh(Uuid::new_v4(), "")).await;
+    assert_eq!(resp.status(), 404);
+
+    let resp = test::call_service(&app, list_stale("maybe")).await;
+    assert_eq!(resp.status(), 400);
+
+    cleanup_test_data(&pool, user_id, project_id).await;
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_due_dates_and_overdue_filter() {
+    let pool = test_utils::setup_test_db().await;
+    let (user_id, project_id) = test_utils::setup_test_user_and_project(&pool).await;
+
+    let config = crate::auth::OAuthConfig {
+        google_client_id: "test".to_string(),
+        google_client_secret: "test".to_string(),
+        google_redirect_url: "test".to_string(),
+        github_client_id: "test".to_string(),
+        github_client_secret: "test".to_string(),
+        github_redirect_url: "test".to_string(),
+        jwt_secret: "test_secret".to_string(),
+    };
+
+    let token = create_test_jwt_token(user_id, &config);
+
+    let late = create_task_in_db(&pool, project_id, "Late", None).await.unwrap();
+    let done = create_task_in_db(&pool, project_id, "Done", None).await.unwrap();
+    let upcoming = create_task_in_db(&pool, project_id, "Upcoming", None).await.unwrap();
+    let open = create_task_in_db(&pool, project_id, "Open", None).await.unwrap();
+    sqlx::query("UPDATE tasks SET status = 'completed' WHERE id = $1")
+        .bind(done.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config))
+            .route("/projects/{project_id}/tasks/batch/due", web::post().to(batch_set_task_due))
+            .route("/projects/{project_id}/tasks", web::get().to(list_tasks))
+    ).await;
+
+    let set_due = |task_ids: Vec<Uuid>, due_at: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&format!("/projects/{}/tasks/batch/due", project_id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(json!({ "task_ids": task_ids, "due_at": due_at }))
+            .to_request()
+    };
+    let list_overdue = |overdue: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/projects/{}/tasks?overdue={}", project_id, overdue))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+    let names = |body: serde_json::Value| {
+        let mut names: Vec<String> = body["tasks"].as_array().unwrap().iter()
+            .map(|task| task["name"].as_str().unwrap().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+
+    let yesterday = chrono::Utc::now() - chrono::Duration::days(1);
+    let resp = test::call_service(&app, set_due(vec![late.id, done.id], json!(yesterday))).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["affected"], 2);
+    let resp = test::call_service(&app, set_due(vec![upcoming.id], json!(chrono::Utc::now() + chrono::Duration::days(3)))).await;
+    assert_eq!(resp.status(), 200);
+    assert!(get_task_by_id(&pool, late.id, project_id).await.unwrap().unwrap().due_at.is_some());
+
+    // Completed tasks are never overdue, tasks without a due date neither
+    let body: serde_json::Value = test::call_and_read_body_json(&app, list_overdue("true")).await;
+    assert_eq!(body["tasks"][0]["overdue"], true);
+    assert_eq!(names(body), vec!["Late"]);
+    assert_eq!(names(test::call_and_read_body_json(&app, list_overdue("false")).await), vec!["Done", "Open", "Upcoming"]);
+
+    // The project deadline applies to tasks without their own due date
+    sqlx::query("UPDATE projects SET deadline_settings = $1 WHERE id = $2")
+        .bind(json!({ "deadline": yesterday }))
+        .bind(project_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(names(test::call_and_read_body_json(&app, list_overdue("true")).await), vec!["Late", "Open"]);
+
+    // Clearing the due date leaves the task to the deadline settings
+    let resp = test::call_service(&app, set_due(vec![upcoming.id], serde_json::Value::Null)).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(names(test::call_and_read_body_json(&app, list_overdue("true")).await), vec!["Late", "Open", "Upcoming"]);
+
+    let resp = test::call_service(&app, set_due(vec![], serde_json::Value::Null)).await;
+    assert_eq!(resp.status(), 400);
+
+    let resp = test::call_service(&app, list_overdue("soon")).await;
+    assert_eq!(resp.status(), 400);
+
+    cleanup_test_data(&pool, user_id, project_id).await;
+}
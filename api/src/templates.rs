@@ -0,0 +1,498 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use utoipa::ToSchema;
+
+use crate::auth::{JwtManager, Claims};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct ProjectTemplate {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    /// Array of `TemplateCategory` objects
+    pub categories: serde_json::Value,
+    /// `None` for built-in templates
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct TemplateCategory {
+    pub name: String,
+    pub supercategory: Option<String>,
+    pub color: Option<String>,
+    pub coco_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateTemplateRequest {
+    pub name: String,
+    pub description: Option<String>,
+    /// Explicit category set, mutually exclusive with `from_project_id`
+    pub categories: Option<Vec<TemplateCategory>>,
+    /// Snapshot the categories of an existing project
+    pub from_project_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TemplateResponse {
+    pub template: ProjectTemplate,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TemplatesListResponse {
+    pub templates: Vec<ProjectTemplate>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/templates",
+    tag = "templates",
+    responses(
+        (status = 200, description = "Built-in templates and the user's own", body = TemplatesListResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+    ),
+)]
+pub async fn list_templates(
+    req: HttpRequest,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    match get_user_templates(&pool, user_id).await {
+        Ok(templates) => HttpResponse::Ok().json(TemplatesListResponse { templates }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch templates"),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/templates",
+    tag = "templates",
+    request_body = CreateTemplateRequest,
+    responses(
+        (status = 201, body = TemplateResponse),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Source project not found or access denied", body = String),
+        (status = 409, description = "Template name already exists", body = String),
+    ),
+)]
+pub async fn create_template(
+    req: HttpRequest,
+    payload: web::Json<CreateTemplateRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    // Validate input
+    if payload.name.trim().is_empty() {
+        return HttpResponse::BadRequest().json("Template name cannot be empty");
+    }
+
+    if payload.name.len() > 255 {
+        return HttpResponse::BadRequest().json("Template name too long (max 255 characters)");
+    }
+
+    let categories = match (&payload.categories, payload.from_project_id) {
+        (Some(categories), None) => categories.clone(),
+        (None, Some(project_id)) => {
+            if !user_has_project_access(&pool, project_id, user_id).await {
+                return HttpResponse::NotFound().json("Project not found or access denied");
+            }
+            match get_project_categories(&pool, project_id).await {
+                Ok(categories) => categories,
+                Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project categories"),
+            }
+        }
+        _ => return HttpResponse::BadRequest().json("Provide either categories or from_project_id"),
+    };
+
+    if let Err(e) = validate_template_categories(&categories) {
+        return HttpResponse::BadRequest().json(e);
+    }
+
+    match create_template_in_db(&pool, payload.name.trim(), payload.description.as_deref(), &categories, user_id).await {
+        Ok(template) => HttpResponse::Created().json(TemplateResponse { template }),
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+            HttpResponse::Conflict().json("Template name already exists")
+        }
+        Err(_) => HttpResponse::InternalServerError().json("Failed to create template"),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/templates/{id}",
+    tag = "templates",
+    params(("id" = Uuid, Path, description = "Template ID")),
+    responses(
+        (status = 204, description = "Template deleted"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Template not found or not created by the user", body = String),
+    ),
+)]
+pub async fn delete_template(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let template_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid template ID"),
+    };
+
+    // Built-in templates have no owner and can't be deleted
+    match delete_template_from_db(&pool, template_id, user_id).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => HttpResponse::NotFound().json("Template not found or permission denied"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to delete template"),
+    }
+}
+
+/// Checks names are present and unique and colors are `#RRGGBB`.
+pub fn validate_template_categories(categories: &[TemplateCategory]) -> Result<(), String> {
+    if categories.is_empty() {
+        return Err("Template must contain at least one category".to_string());
+    }
+
+    let mut names = HashSet::new();
+    for category in categories {
+        if category.name.trim().is_empty() {
+            return Err("Category name cannot be empty".to_string());
+        }
+        if category.name.len() > 255 {
+            return Err(format!("Category name too long (max 255 characters): {}", category.name));
+        }
+        if !names.insert(category.name.as_str()) {
+            return Err(format!("Duplicate category name: {}", category.name));
+        }
+        if let Some(color) = &category.color {
+            if !color.starts_with('#') || color.len() != 7 {
+                return Err(format!("Color must be in HEX format (#RRGGBB): {}", category.name));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the template is built-in or owned by the user.
+pub async fn user_can_use_template(pool: &Pool<Postgres>, template_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM project_templates WHERE id = $1 AND (created_by IS NULL OR created_by = $2))"
+    )
+    .bind(template_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Creates the template's categories in a project, skipping names the project already has.
+pub async fn apply_template(
+    tx: &mut Transaction<'_, Postgres>,
+    template_id: Uuid,
+    project_id: Uuid,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO image_annotation_categories (id, project_id, name, supercategory, color, coco_id, image_metadata, created_at, updated_at)
+        SELECT gen_random_uuid(), $1, c.name, c.supercategory, c.color, c.coco_id, '{}'::jsonb, NOW(), NOW()
+        FROM project_templates t
+        CROSS JOIN LATERAL jsonb_to_recordset(t.categories) AS c(name TEXT, supercategory TEXT, color TEXT, coco_id INTEGER)
+        WHERE t.id = $2
+        ON CONFLICT (project_id, name) DO NOTHING
+        "#
+    )
+    .bind(project_id)
+    .bind(template_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+async fn get_user_templates(pool: &Pool<Postgres>, user_id: Uuid) -> Result<Vec<ProjectTemplate>, sqlx::Error> {
+    sqlx::query_as::<_, ProjectTemplate>(
+        r#"
+        SELECT id, name, description, categories, created_by, created_at, updated_at
+        FROM project_templates
+        WHERE created_by IS NULL OR created_by = $1
+        ORDER BY created_by NULLS FIRST, name ASC
+        "#
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_project_categories(pool: &Pool<Postgres>, project_id: Uuid) -> Result<Vec<TemplateCategory>, sqlx::Error> {
+    sqlx::query_as::<_, TemplateCategory>(
+        r#"
+        SELECT name, supercategory, color, coco_id
+        FROM image_annotation_categories
+        WHERE project_id = $1
+        ORDER BY coco_id ASC NULLS LAST, name ASC
+        "#
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn create_template_in_db(
+    pool: &Pool<Postgres>,
+    name: &str,
+    description: Option<&str>,
+    categories: &[TemplateCategory],
+    user_id: Uuid,
+) -> Result<ProjectTemplate, sqlx::Error> {
+    sqlx::query_as::<_, ProjectTemplate>(
+        r#"
+        INSERT INTO project_templates (id, name, description, categories, created_by, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, NOW(), NOW())
+        RETURNING id, name, description, categories, created_by, created_at, updated_at
+        "#
+    )
+    .bind(Uuid::new_v4())
+    .bind(name)
+    .bind(description)
+    .bind(serde_json::to_value(categories).unwrap_or_default())
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+async fn delete_template_from_db(pool: &Pool<Postgres>, template_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM project_templates WHERE id = $1 AND created_by = $2")
+        .bind(template_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{User, OAuthConfig, JwtManager};
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn create_test_oauth_config() -> OAuthConfig {
+        OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        }
+    }
+
+    fn create_auth_token(oauth_config: &OAuthConfig, user: &User) -> String {
+        let jwt_manager = JwtManager::new(&oauth_config.jwt_secret);
+        jwt_manager.generate_token(
+            &user.id.to_string(),
+            &user.email,
+            &user.name
+        ).expect("Failed to generate token")
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_create_project_from_template() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/templates", web::get().to(list_templates))
+                .route("/templates", web::post().to(create_template))
+                .route("/projects", web::post().to(crate::projects::create_project))
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri("/templates")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(serde_json::json!({
+                "name": "Fruit",
+                "categories": [
+                    {"name": "apple", "color": "#FF0000"},
+                    {"name": "pear", "supercategory": "fruit", "coco_id": 7},
+                ],
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let template_id = body["template"]["id"].as_str().unwrap().to_string();
+
+        let req = test::TestRequest::get()
+            .uri("/templates")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let templates = body["templates"].as_array().unwrap();
+        // Three built-in templates come first
+        assert_eq!(templates.len(), 4);
+        assert_eq!(templates[0]["name"], "COCO-80");
+        assert_eq!(templates[0]["categories"].as_array().unwrap().len(), 80);
+        assert_eq!(templates[3]["name"], "Fruit");
+
+        let req = test::TestRequest::post()
+            .uri("/projects")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(serde_json::json!({"name": "Orchard", "template_id": template_id}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let project_id = Uuid::parse_str(body["project"]["id"].as_str().unwrap()).unwrap();
+
+        let categories = get_project_categories(&pool, project_id).await.unwrap();
+        assert_eq!(categories.len(), 2);
+        assert_eq!(categories[0].name, "pear");
+        assert_eq!(categories[0].coco_id, Some(7));
+        assert_eq!(categories[1].name, "apple");
+        assert_eq!(categories[1].color.as_deref(), Some("#FF0000"));
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_template_visibility_and_delete() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let other_user = test_utils::create_test_user(&pool).await;
+        let oauth_config = create_test_oauth_config();
+        let token = create_auth_token(&oauth_config, &user);
+
+        let categories = vec![TemplateCategory { name: "widget".to_string(), supercategory: None, color: None, coco_id: None }];
+        let private = create_template_in_db(&pool, "Private", None, &categories, other_user).await.unwrap();
+        let builtin_id = sqlx::query_scalar::<_, Uuid>("SELECT id FROM project_templates WHERE created_by IS NULL LIMIT 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        assert!(user_can_use_template(&pool, builtin_id, user.id).await.unwrap());
+        assert!(!user_can_use_template(&pool, private.id, user.id).await.unwrap());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/templates/{id}", web::delete().to(delete_template))
+                .route("/projects", web::post().to(crate::projects::create_project))
+        ).await;
+
+        // Another user's template can't be used or deleted
+        let req = test::TestRequest::post()
+            .uri("/projects")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(serde_json::json!({"name": "Project", "template_id": private.id}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+
+        for template_id in [private.id, builtin_id] {
+            let req = test::TestRequest::delete()
+                .uri(&format!("/templates/{}", template_id))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), 404);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_validate_template_categories() {
+        let category = |name: &str, color: Option<&str>| TemplateCategory {
+            name: name.to_string(),
+            supercategory: None,
+            color: color.map(String::from),
+            coco_id: None,
+        };
+
+        assert!(validate_template_categories(&[category("a", Some("#00FF00")), category("b", None)]).is_ok());
+        assert!(validate_template_categories(&[]).is_err());
+        assert!(validate_template_categories(&[category(" ", None)]).is_err());
+        assert!(validate_template_categories(&[category("a", None), category("a", None)]).is_err());
+        assert!(validate_template_categories(&[category("a", Some("red"))]).is_err());
+    }
+}
@@ -0,0 +1,227 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use image::codecs::jpeg::JpegEncoder;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::auth::{JwtManager, Claims};
+use crate::storage::StorageError;
+use crate::storage::factory::create_storage_provider_from_project;
+use crate::tiles::thumbnail_storage_key;
+
+/// Longer side of a thumbnail in pixels
+pub const THUMBNAIL_SIZE: u32 = 256;
+
+const THUMBNAIL_JPEG_QUALITY: u8 = 80;
+
+/// Scales an image down to fit in `THUMBNAIL_SIZE` and encodes it as JPEG. Smaller images keep
+/// their size.
+pub fn make_thumbnail(image_data: &[u8]) -> Result<Vec<u8>, String> {
+    let image = image::load_from_memory(image_data)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let thumbnail = if image.width().max(image.height()) > THUMBNAIL_SIZE {
+        image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+    } else {
+        image
+    };
+
+    // JPEG has no alpha channel
+    let mut data = Vec::new();
+    image::DynamicImage::ImageRgb8(thumbnail.to_rgb8())
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut data, THUMBNAIL_JPEG_QUALITY))
+        .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+    Ok(data)
+}
+
+/// Small JPEG of a task's image, or of the first frame of a video or volume, for the task list.
+/// It is generated on the first request and kept in the project storage after that. Only images
+/// in the project storage get thumbnails.
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/tasks/{task_id}/thumbnail",
+    tag = "media",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    responses(
+        (status = 200, description = "JPEG thumbnail", body = [u8], content_type = "image/jpeg"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project, task or image not found", body = String),
+        (status = 422, description = "The image can't be decoded", body = String),
+    ),
+)]
+pub async fn get_task_thumbnail(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let resource_url = match get_thumbnail_source(&pool, task_id, project_id).await {
+        Ok(Some(url)) => url,
+        Ok(None) => return HttpResponse::NotFound().json("Task not found or has no image"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch task"),
+    };
+    let Some(image_key) = resource_url.strip_prefix("storage://") else {
+        return HttpResponse::NotFound().json("Thumbnails are only available for images in project storage");
+    };
+
+    let storage_provider = match get_project_by_id(&pool, project_id).await {
+        Ok(Some(project)) => match create_storage_provider_from_project(&project).await {
+            Ok(provider) => provider,
+            Err(e) => return HttpResponse::InternalServerError().json(format!("Storage error: {}", e)),
+        },
+        Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    };
+
+    let thumbnail_key = thumbnail_storage_key(image_key);
+    let thumbnail = match storage_provider.download(&thumbnail_key).await {
+        Ok(data) => data,
+        Err(StorageError::NotFound) => {
+            let image_data = match storage_provider.download(image_key).await {
+                Ok(data) => data,
+                Err(StorageError::NotFound) => return HttpResponse::NotFound().json("Image not found"),
+                Err(e) => return HttpResponse::InternalServerError().json(format!("Download failed: {}", e)),
+            };
+            let thumbnail = match tokio::task::spawn_blocking(move || make_thumbnail(&image_data)).await {
+                Ok(Ok(data)) => data,
+                Ok(Err(e)) => return HttpResponse::UnprocessableEntity().json(e),
+                Err(e) => return HttpResponse::InternalServerError().json(format!("Thumbnail generation panicked: {}", e)),
+            };
+            // A failed upload only means the next request generates it again
+            if let Err(e) = storage_provider.upload(&thumbnail_key, &thumbnail, Some("image/jpeg")).await {
+                eprintln!("Failed to store thumbnail {}: {}", thumbnail_key, e);
+            }
+            thumbnail
+        }
+        Err(e) => return HttpResponse::InternalServerError().json(format!("Download failed: {}", e)),
+    };
+
+    HttpResponse::Ok()
+        .content_type("image/jpeg")
+        .insert_header(("Cache-Control", "private, max-age=86400"))
+        .body(thumbnail)
+}
+
+/// Image a task's thumbnail is made from: its own, or its first frame for videos and volumes
+async fn get_thumbnail_source(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
+    project_id: Uuid,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<String>>(
+        r#"
+        SELECT CASE
+            WHEN t.media_type = 'image' THEN t.resource_url
+            ELSE (SELECT f.resource_url FROM task_frames f WHERE f.task_id = t.id ORDER BY f.frame_index LIMIT 1)
+        END
+        FROM tasks t
+        WHERE t.id = $1 AND t.project_id = $2
+        "#
+    )
+    .bind(task_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+    .map(Option::flatten)
+}
+
+async fn get_project_by_id(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Option<crate::projects::Project>, sqlx::Error> {
+    sqlx::query_as::<_, crate::projects::Project>(
+        "SELECT id, name, description, storage_config, owner_id, task_type, created_at, updated_at FROM projects WHERE id = $1"
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::DynamicImage;
+
+    fn encode_png(image: DynamicImage) -> Vec<u8> {
+        let mut data = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut data, image::ImageFormat::Png).unwrap();
+        data.into_inner()
+    }
+
+    #[test]
+    fn test_make_thumbnail_scales_down() {
+        let thumbnail = make_thumbnail(&encode_png(DynamicImage::new_rgba8(1024, 512))).unwrap();
+        let thumbnail = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (256, 128));
+    }
+
+    #[test]
+    fn test_make_thumbnail_keeps_small_images() {
+        let thumbnail = make_thumbnail(&encode_png(DynamicImage::new_rgb8(100, 60))).unwrap();
+        let thumbnail = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (100, 60));
+    }
+
+    #[test]
+    fn test_make_thumbnail_rejects_garbage() {
+        assert!(make_thumbnail(b"not an image").is_err());
+    }
+}
@@ -0,0 +1,498 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+use crate::auth::{JwtManager, Claims};
+use crate::storage::StorageProvider;
+use crate::storage::factory::create_storage_provider_from_project;
+
+/// Edge length of a tile in pixels, as in Deep Zoom pyramids
+pub const TILE_SIZE: u32 = 256;
+
+/// Images with a longer side than this get a tile pyramid, smaller ones are served whole.
+pub const TILING_THRESHOLD: u32 = 4096;
+
+/// Tiles are stored under `<image key>.tiles/<level>/<col>_<row>.jpg`, next to the image.
+const TILES_DIR_SUFFIX: &str = ".tiles/";
+
+const TILE_JPEG_QUALITY: u8 = 85;
+
+/// Levels of a Deep Zoom pyramid: level `max_level` is the full image and every level below
+/// halves it, down to `min_level`, the first level that fits in a single tile.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, ToSchema)]
+pub struct TileInfo {
+    pub width: u32,
+    pub height: u32,
+    pub tile_size: u32,
+    pub min_level: u32,
+    pub max_level: u32,
+}
+
+impl TileInfo {
+    pub fn new(width: u32, height: u32) -> Self {
+        let max_level = max_level(width, height);
+        let mut min_level = max_level;
+        while min_level > 0 {
+            let (level_width, level_height) = level_dimensions(width, height, min_level, max_level);
+            if level_width.max(level_height) <= TILE_SIZE {
+                break;
+            }
+            min_level -= 1;
+        }
+
+        Self { width, height, tile_size: TILE_SIZE, min_level, max_level }
+    }
+
+    pub fn level_dimensions(&self, level: u32) -> (u32, u32) {
+        level_dimensions(self.width, self.height, level, self.max_level)
+    }
+
+    /// Number of tile columns and rows of a level
+    pub fn tile_grid(&self, level: u32) -> (u32, u32) {
+        let (level_width, level_height) = self.level_dimensions(level);
+        (level_width.div_ceil(TILE_SIZE), level_height.div_ceil(TILE_SIZE))
+    }
+
+    pub fn contains_tile(&self, level: u32, col: u32, row: u32) -> bool {
+        let (cols, rows) = self.tile_grid(level);
+        (self.min_level..=self.max_level).contains(&level) && col < cols && row < rows
+    }
+}
+
+/// Level of the full image: the number of halvings until the longer side is one pixel.
+pub fn max_level(width: u32, height: u32) -> u32 {
+    let size = width.max(height).max(1);
+    u32::BITS - (size - 1).leading_zeros()
+}
+
+pub fn level_dimensions(width: u32, height: u32, level: u32, max_level: u32) -> (u32, u32) {
+    let scale = 1u32 << (max_level - level);
+    (width.div_ceil(scale).max(1), height.div_ceil(scale).max(1))
+}
+
+pub fn needs_tiles(width: u32, height: u32) -> bool {
+    width.max(height) > TILING_THRESHOLD
+}
+
+/// Reads the size from the image header without decoding the pixels.
+pub fn image_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    image::ImageReader::new(std::io::Cursor::new(data))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+pub fn tile_storage_key(image_key: &str, level: u32, col: u32, row: u32) -> String {
+    format!("{}{}{}/{}_{}.jpg", image_key, TILES_DIR_SUFFIX, level, col, row)
+}
+
+/// Thumbnail of the task list, kept with the tiles so syncs skip it too
+pub fn thumbnail_storage_key(image_key: &str) -> String {
+    format!("{}{}thumbnail.jpg", image_key, TILES_DIR_SUFFIX)
+}
+
+/// Tiles written for an image must not come back as image tasks on the next sync.
+pub fn is_tile_key(file_key: &str) -> bool {
+    file_key.contains(TILES_DIR_SUFFIX)
+}
+
+/// Image a tile or thumbnail was written for
+pub fn tile_source_key(file_key: &str) -> Option<&str> {
+    file_key.split_once(TILES_DIR_SUFFIX).map(|(source_key, _)| source_key)
+}
+
+/// Makes sure a large image has its pyramid, generating it when missing. Returns whether the
+/// image is tiled; images below `TILING_THRESHOLD` never are.
+pub async fn ensure_pyramid(
+    storage_provider: &dyn StorageProvider,
+    image_key: &str,
+    dimensions: (u32, u32),
+) -> Result<bool, String> {
+    let (width, height) = dimensions;
+    if !needs_tiles(width, height) {
+        return Ok(false);
+    }
+
+    // The single tile of the lowest level is uploaded last, so it marks a complete pyramid
+    let info = TileInfo::new(width, height);
+    let last_tile = tile_storage_key(image_key, info.min_level, 0, 0);
+    if storage_provider.exists(&last_tile).await.unwrap_or(false) {
+        return Ok(true);
+    }
+
+    let image_data = storage_provider.download(image_key)
+        .await
+        .map_err(|e| format!("Failed to download image: {}", e))?;
+    generate_pyramid(storage_provider, image_key, image_data).await?;
+    Ok(true)
+}
+
+/// Cuts the image into JPEG tiles for every level of its pyramid and uploads them,
+/// from the full-size level down to the single-tile level.
+pub async fn generate_pyramid(
+    storage_provider: &dyn StorageProvider,
+    image_key: &str,
+    image_data: Vec<u8>,
+) -> Result<TileInfo, String> {
+    let key = image_key.to_string();
+    let (info, tiles) = tokio::task::spawn_blocking(move || cut_tiles(&key, &image_data))
+        .await
+        .map_err(|e| format!("Tile generation panicked: {}", e))??;
+
+    for (tile_key, tile_data) in tiles {
+        storage_provider.upload(&tile_key, &tile_data, Some("image/jpeg"))
+            .await
+            .map_err(|e| format!("Failed to upload tile {}: {}", tile_key, e))?;
+    }
+
+    Ok(info)
+}
+
+fn cut_tiles(image_key: &str, image_data: &[u8]) -> Result<(TileInfo, Vec<(String, Vec<u8>)>), String> {
+    let image = image::load_from_memory(image_data)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let info = TileInfo::new(image.width(), image.height());
+
+    let mut tiles = Vec::new();
+    let mut level_image = DynamicImage::ImageRgb8(image.to_rgb8());
+    for level in (info.min_level..=info.max_level).rev() {
+        let (level_width, level_height) = info.level_dimensions(level);
+        if level_image.width() != level_width || level_image.height() != level_height {
+            // Each level is scaled from the one above, which is much cheaper than from the original
+            level_image = level_image.resize_exact(level_width, level_height, FilterType::Triangle);
+        }
+
+        let (cols, rows) = info.tile_grid(level);
+        for row in 0..rows {
+            for col in 0..cols {
+                let x = col * TILE_SIZE;
+                let y = row * TILE_SIZE;
+                let tile = level_image.crop_imm(x, y, TILE_SIZE.min(level_width - x), TILE_SIZE.min(level_height - y));
+
+                let mut tile_data = Vec::new();
+                tile.write_with_encoder(JpegEncoder::new_with_quality(&mut tile_data, TILE_JPEG_QUALITY))
+                    .map_err(|e| format!("Failed to encode tile: {}", e))?;
+                tiles.push((tile_storage_key(image_key, level, col, row), tile_data));
+            }
+        }
+    }
+
+    Ok((info, tiles))
+}
+
+pub async fn mark_task_tiled(pool: &Pool<Postgres>, task_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE tasks SET tiled = TRUE, updated_at = NOW() WHERE id = $1")
+        .bind(task_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct TiledTask {
+    resource_url: Option<String>,
+    width: Option<i32>,
+    height: Option<i32>,
+    tiled: bool,
+}
+
+impl TiledTask {
+    /// Pyramid layout and storage key of the image, `None` when the task has no pyramid
+    fn pyramid(&self) -> Option<(TileInfo, &str)> {
+        let key = self.resource_url.as_deref()?.strip_prefix("storage://")?;
+        match (self.tiled, self.width, self.height) {
+            (true, Some(width), Some(height)) => Some((TileInfo::new(width as u32, height as u32), key)),
+            _ => None,
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/tasks/{task_id}/tiles",
+    tag = "media",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    responses(
+        (status = 200, body = TileInfo),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found, or the task has no tile pyramid", body = String),
+    ),
+)]
+pub async fn get_task_tile_info(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match get_tiled_task(&pool, task_id, project_id).await {
+        Ok(Some(task)) => match task.pyramid() {
+            Some((info, _)) => HttpResponse::Ok().json(info),
+            None => HttpResponse::NotFound().json("Task has no tile pyramid"),
+        },
+        Ok(None) => HttpResponse::NotFound().json("Task not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch task"),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/tasks/{task_id}/tiles/{level}/{col}/{row}",
+    tag = "media",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        ("level" = u32, Path, description = "Pyramid level, `max_level` is the full image"),
+        ("col" = u32, Path, description = "Tile column"),
+        ("row" = u32, Path, description = "Tile row"),
+    ),
+    responses(
+        (status = 200, description = "JPEG tile", body = [u8], content_type = "image/jpeg"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project, task or tile not found", body = String),
+    ),
+)]
+pub async fn get_task_tile(
+    req: HttpRequest,
+    path: web::Path<(String, String, u32, u32, u32)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str, level, col, row) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let task = match get_tiled_task(&pool, task_id, project_id).await {
+        Ok(Some(task)) => task,
+        Ok(None) => return HttpResponse::NotFound().json("Task not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch task"),
+    };
+
+    let Some((info, image_key)) = task.pyramid() else {
+        return HttpResponse::NotFound().json("Task has no tile pyramid");
+    };
+    if !info.contains_tile(level, col, row) {
+        return HttpResponse::NotFound().json("Tile not found");
+    }
+
+    let storage_provider = match get_project_by_id(&pool, project_id).await {
+        Ok(Some(project)) => match create_storage_provider_from_project(&project).await {
+            Ok(provider) => provider,
+            Err(e) => return HttpResponse::InternalServerError().json(format!("Storage error: {}", e)),
+        },
+        Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    };
+
+    match storage_provider.download(&tile_storage_key(image_key, level, col, row)).await {
+        // Tiles never change once written, so the viewer may keep them around
+        Ok(data) => HttpResponse::Ok()
+            .content_type("image/jpeg")
+            .insert_header(("Cache-Control", "private, max-age=86400"))
+            .body(data),
+        Err(crate::storage::StorageError::NotFound) => HttpResponse::NotFound().json("Tile not found"),
+        Err(e) => HttpResponse::InternalServerError().json(format!("Download failed: {}", e)),
+    }
+}
+
+async fn get_tiled_task(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
+    project_id: Uuid,
+) -> Result<Option<TiledTask>, sqlx::Error> {
+    sqlx::query_as::<_, TiledTask>(
+        "SELECT resource_url, width, height, tiled FROM tasks WHERE id = $1 AND project_id = $2"
+    )
+    .bind(task_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn get_project_by_id(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Option<crate::projects::Project>, sqlx::Error> {
+    sqlx::query_as::<_, crate::projects::Project>(
+        "SELECT id, name, description, storage_config, owner_id, task_type, created_at, updated_at FROM projects WHERE id = $1"
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::OAuthConfig;
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    #[actix_web::test]
+    async fn test_tile_info() {
+        let info = TileInfo::new(8000, 6000);
+        // 8000 needs 13 halvings to reach one pixel, 8000 / 2^5 = 250 fits in a tile
+        assert_eq!(info.max_level, 13);
+        assert_eq!(info.min_level, 8);
+        assert_eq!(info.level_dimensions(13), (8000, 6000));
+        assert_eq!(info.level_dimensions(12), (4000, 3000));
+        assert_eq!(info.level_dimensions(8), (250, 188));
+        assert_eq!(info.tile_grid(13), (32, 24));
+        assert_eq!(info.tile_grid(8), (1, 1));
+
+        assert!(info.contains_tile(13, 31, 23));
+        assert!(!info.contains_tile(13, 32, 0));
+        assert!(!info.contains_tile(7, 0, 0));
+
+        assert_eq!(max_level(1, 1), 0);
+        assert_eq!(max_level(256, 100), 8);
+        assert_eq!(TileInfo::new(200, 100).min_level, TileInfo::new(200, 100).max_level);
+    }
+
+    #[actix_web::test]
+    async fn test_tile_keys() {
+        let key = tile_storage_key("maps/area.tif", 12, 3, 4);
+        assert_eq!(key, "maps/area.tif.tiles/12/3_4.jpg");
+        assert!(is_tile_key(&key));
+        assert!(!is_tile_key("maps/area.tif"));
+        assert!(is_tile_key(&thumbnail_storage_key("maps/area.tif")));
+        assert_eq!(tile_source_key(&key), Some("maps/area.tif"));
+        assert_eq!(tile_source_key("maps/area.tif"), None);
+        assert!(needs_tiles(8000, 100));
+        assert!(!needs_tiles(4096, 4096));
+    }
+
+    #[actix_web::test]
+    async fn test_cut_tiles() {
+        let image = DynamicImage::new_rgb8(300, 200);
+        let mut image_data = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut image_data, image::ImageFormat::Png).unwrap();
+
+        let (info, tiles) = cut_tiles("big.png", image_data.get_ref()).unwrap();
+        assert_eq!((info.min_level, info.max_level), (8, 9));
+        // 2x1 tiles at full size, then the 150x100 level in one tile, which comes last
+        let keys: Vec<&str> = tiles.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["big.png.tiles/9/0_0.jpg", "big.png.tiles/9/1_0.jpg", "big.png.tiles/8/0_0.jpg"]);
+
+        let edge_tile = image::load_from_memory(&tiles[1].1).unwrap();
+        assert_eq!((edge_tile.width(), edge_tile.height()), (44, 200));
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_get_task_tile_info_unauthorized() {
+        let pool = test_utils::setup_test_db().await;
+        let oauth_config = OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/tasks/{task_id}/tiles", web::get().to(get_task_tile_info))
+        ).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/tasks/{}/tiles", Uuid::new_v4(), Uuid::new_v4()))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 401);
+    }
+}
@@ -0,0 +1,489 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+use crate::auth::{JwtManager, Claims};
+
+/// One box of a track in the latest annotation of its task
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct TrackedBox {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub task_name: String,
+    /// Frame of a video task, `None` for images
+    pub frame_index: Option<i32>,
+    pub category_id: Option<Uuid>,
+    pub bbox: Vec<f64>,
+    pub rotation: f64,
+    pub is_interpolated: bool,
+}
+
+/// Boxes of a track across the frames and tasks of the project, in task name and frame order
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TrackResponse {
+    pub track_id: Uuid,
+    pub boxes: Vec<TrackedBox>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LinkTrackRequest {
+    /// Saved boxes to put on the track, at most one per task and frame
+    pub box_ids: Vec<Uuid>,
+    /// Track to add the boxes to, a new one when omitted
+    #[serde(default)]
+    pub track_id: Option<Uuid>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/tracks/{track_id}",
+    tag = "annotations",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("track_id" = Uuid, Path, description = "Track ID"),
+    ),
+    responses(
+        (status = 200, body = TrackResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn get_track(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, track_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let track_id = match Uuid::parse_str(&track_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid track ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match get_track_boxes(&pool, project_id, track_id).await {
+        Ok(boxes) => HttpResponse::Ok().json(TrackResponse { track_id, boxes }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch track"),
+    }
+}
+
+/// Puts saved boxes on one track, e.g. the same car on consecutive images of a sequence.
+/// Boxes already on the track stay on it, boxes of other tracks move over.
+#[utoipa::path(
+    post,
+    path = "/projects/{project_id}/tracks",
+    tag = "annotations",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    request_body = LinkTrackRequest,
+    responses(
+        (status = 200, description = "The track with the linked boxes", body = TrackResponse),
+        (status = 400, description = "Unknown or interpolated boxes, or two boxes on one frame", body = String),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project not found or access denied", body = String),
+    ),
+)]
+pub async fn link_track(
+    req: HttpRequest,
+    path: web::Path<String>,
+    payload: web::Json<LinkTrackRequest>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let project_id = match Uuid::parse_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let box_ids: Vec<Uuid> = payload.box_ids.iter().copied().collect::<HashSet<_>>().into_iter().collect();
+    if box_ids.is_empty() {
+        return HttpResponse::BadRequest().json("At least one box is required");
+    }
+
+    // Only boxes of the latest annotations can be linked, older ones are history
+    let boxes = match get_latest_boxes(&pool, project_id, &box_ids).await {
+        Ok(boxes) => boxes,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch annotations"),
+    };
+    if boxes.len() != box_ids.len() {
+        return HttpResponse::BadRequest().json("Boxes must be in the latest annotations of the project's tasks");
+    }
+    if boxes.iter().any(|tracked_box| tracked_box.is_interpolated) {
+        return HttpResponse::BadRequest().json("Interpolated boxes follow their keyframes and can't be linked");
+    }
+
+    let track_id = payload.track_id.unwrap_or_else(Uuid::new_v4);
+    let mut track = match get_track_boxes(&pool, project_id, track_id).await {
+        Ok(track) => track,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch track"),
+    };
+    track.retain(|tracked_box| !box_ids.contains(&tracked_box.id));
+    track.extend(boxes);
+    if has_frame_collision(&track) {
+        return HttpResponse::BadRequest().json("A track can only have one box per frame");
+    }
+
+    if let Err(e) = set_track_in_db(&pool, &box_ids, track_id).await {
+        eprintln!("Failed to link boxes to track {}: {}", track_id, e);
+        return HttpResponse::InternalServerError().json("Failed to link boxes");
+    }
+
+    match get_track_boxes(&pool, project_id, track_id).await {
+        Ok(boxes) => HttpResponse::Ok().json(TrackResponse { track_id, boxes }),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch track"),
+    }
+}
+
+/// Takes a box off its track. Interpolated boxes go with their keyframes instead.
+#[utoipa::path(
+    delete,
+    path = "/projects/{project_id}/tracks/{track_id}/boxes/{box_id}",
+    tag = "annotations",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("track_id" = Uuid, Path, description = "Track ID"),
+        ("box_id" = Uuid, Path, description = "Box ID"),
+    ),
+    responses(
+        (status = 204, description = "Box unlinked"),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Box not on the track, or project not found", body = String),
+    ),
+)]
+pub async fn unlink_track_box(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, track_id_str, box_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let track_id = match Uuid::parse_str(&track_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid track ID"),
+    };
+
+    let box_id = match Uuid::parse_str(&box_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid box ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    match unlink_box_in_db(&pool, project_id, track_id, box_id).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => HttpResponse::NotFound().json("Box not found on the track"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to unlink box"),
+    }
+}
+
+/// Whether two boxes would sit on the same frame of a task, or on the same image
+pub fn has_frame_collision(boxes: &[TrackedBox]) -> bool {
+    let mut frames: HashMap<(Uuid, Option<i32>), Uuid> = HashMap::new();
+    boxes.iter().any(|tracked_box| {
+        frames
+            .insert((tracked_box.task_id, tracked_box.frame_index), tracked_box.id)
+            .is_some_and(|other| other != tracked_box.id)
+    })
+}
+
+const LATEST_BOXES_QUERY: &str = r#"
+    WITH latest_annotations AS (
+        SELECT DISTINCT ON (task_id) id, task_id
+        FROM annotations
+        WHERE task_id IN (SELECT id FROM tasks WHERE project_id = $1)
+        ORDER BY task_id, created_at DESC
+    )
+    SELECT ia.id, t.id as task_id, t.name as task_name, ia.frame_index, ia.category_id, ia.bbox, ia.rotation, ia.is_interpolated
+    FROM image_annotations ia
+    JOIN latest_annotations la ON la.id = ia.annotation_id
+    JOIN tasks t ON t.id = la.task_id
+"#;
+
+pub async fn get_track_boxes(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    track_id: Uuid,
+) -> Result<Vec<TrackedBox>, sqlx::Error> {
+    sqlx::query_as::<_, TrackedBox>(&format!(
+        "{} WHERE ia.track_id = $2 ORDER BY t.name, t.id, ia.frame_index",
+        LATEST_BOXES_QUERY
+    ))
+    .bind(project_id)
+    .bind(track_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_latest_boxes(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    box_ids: &[Uuid],
+) -> Result<Vec<TrackedBox>, sqlx::Error> {
+    sqlx::query_as::<_, TrackedBox>(&format!("{} WHERE ia.id = ANY($2)", LATEST_BOXES_QUERY))
+        .bind(project_id)
+        .bind(box_ids)
+        .fetch_all(pool)
+        .await
+}
+
+async fn set_track_in_db(pool: &Pool<Postgres>, box_ids: &[Uuid], track_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE image_annotations SET track_id = $1, updated_at = NOW() WHERE id = ANY($2)")
+        .bind(track_id)
+        .bind(box_ids)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+async fn unlink_box_in_db(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+    track_id: Uuid,
+    box_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE image_annotations ia
+        SET track_id = NULL, updated_at = NOW()
+        FROM annotations a
+        JOIN tasks t ON t.id = a.task_id
+        WHERE ia.annotation_id = a.id
+            AND t.project_id = $1
+            AND ia.track_id = $2
+            AND ia.id = $3
+            AND NOT ia.is_interpolated
+        "#
+    )
+    .bind(project_id)
+    .bind(track_id)
+    .bind(box_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::annotations::BoundingBox;
+    use crate::auth::OAuthConfig;
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    fn tracked_box(task_id: Uuid, frame_index: Option<i32>) -> TrackedBox {
+        TrackedBox {
+            id: Uuid::new_v4(),
+            task_id,
+            task_name: "task".to_string(),
+            frame_index,
+            category_id: None,
+            bbox: vec![0.0, 0.0, 1.0, 1.0],
+            rotation: 0.0,
+            is_interpolated: false,
+        }
+    }
+
+    fn bbox(category_id: Uuid, track_id: Option<Uuid>) -> BoundingBox {
+        BoundingBox {
+            category_id,
+            bbox: vec![10.0, 10.0, 20.0, 20.0],
+            area: None,
+            iscrowd: Some(false),
+            is_prediction: None,
+            confidence: None,
+            attributes: None,
+            rotation: None,
+            frame_index: None,
+            track_id,
+            is_interpolated: None,
+            mask: None,
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_has_frame_collision() {
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let image = tracked_box(first, None);
+
+        assert!(!has_frame_collision(&[image.clone(), tracked_box(second, None)]));
+        assert!(!has_frame_collision(&[tracked_box(first, Some(0)), tracked_box(first, Some(1))]));
+        // The same box listed twice is still one box
+        assert!(!has_frame_collision(&[image.clone(), image.clone()]));
+
+        assert!(has_frame_collision(&[image, tracked_box(first, None)]));
+        assert!(has_frame_collision(&[tracked_box(second, Some(3)), tracked_box(second, Some(3))]));
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_link_and_unlink_boxes_across_tasks() {
+        let pool = test_utils::setup_test_db().await;
+        let user = test_utils::create_test_user_with_details(&pool).await;
+        let oauth_config = OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        };
+        let token = JwtManager::new(&oauth_config.jwt_secret)
+            .generate_token(&user.id.to_string(), &user.email, &user.name)
+            .unwrap();
+
+        let project = crate::projects::create_project_in_db(&pool, "Test Project", None, None, user.id).await.unwrap();
+        let category = crate::image_annotation_categories::create_image_annotation_category_in_db(&pool, project.id, "car", None, None, None, None).await.unwrap();
+        let first = crate::tasks::create_task_in_db(&pool, project.id, "frame_001.jpg", None).await.unwrap();
+        let second = crate::tasks::create_task_in_db(&pool, project.id, "frame_002.jpg", None).await.unwrap();
+        let metadata = serde_json::json!({});
+        let first_boxes = crate::annotations::create_annotation_in_db(&pool, first.id, &[bbox(category.id, None), bbox(category.id, None)], &metadata, user.id).await.unwrap();
+        let second_boxes = crate::annotations::create_annotation_in_db(&pool, second.id, &[bbox(category.id, None)], &metadata, user.id).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/tracks", web::post().to(link_track))
+                .route("/projects/{project_id}/tracks/{track_id}", web::get().to(get_track))
+                .route("/projects/{project_id}/tracks/{track_id}/boxes/{box_id}", web::delete().to(unlink_track_box))
+        ).await;
+
+        // Two boxes of one image can't be the same object
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/tracks", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(LinkTrackRequest { box_ids: vec![first_boxes[0].id, first_boxes[1].id], track_id: None })
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/tracks", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(LinkTrackRequest { box_ids: vec![first_boxes[0].id, second_boxes[0].id], track_id: None })
+            .to_request();
+        let track: TrackResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(track.boxes.len(), 2);
+        assert_eq!(track.boxes[0].task_id, first.id);
+        assert_eq!(track.boxes[1].task_id, second.id);
+
+        // The other box of the first image would be a second box of the track there
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/tracks", project.id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(LinkTrackRequest { box_ids: vec![first_boxes[1].id], track_id: Some(track.track_id) })
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/projects/{}/tracks/{}/boxes/{}", project.id, track.track_id, second_boxes[0].id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/tracks/{}", project.id, track.track_id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let track: TrackResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(track.boxes.len(), 1);
+        assert_eq!(track.boxes[0].id, first_boxes[0].id);
+
+        // Unlinking twice finds nothing to unlink
+        let req = test::TestRequest::delete()
+            .uri(&format!("/projects/{}/tracks/{}/boxes/{}", project.id, track.track_id, second_boxes[0].id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+    }
+}
@@ -0,0 +1,453 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use std::path::Path;
+use uuid::Uuid;
+use image::GenericImageView;
+use utoipa::ToSchema;
+
+use crate::auth::{JwtManager, Claims};
+use crate::storage::StorageProvider;
+use crate::storage::factory::create_storage_provider_from_project;
+
+pub const MEDIA_TYPE_VIDEO: &str = "video";
+
+/// Extensions synced as video tasks instead of images
+const VIDEO_EXTENSIONS: [&str; 6] = ["mp4", "mov", "avi", "mkv", "webm", "m4v"];
+
+/// Extracted frames are stored under `<video key>.frames/`, next to the video.
+pub const FRAMES_DIR_SUFFIX: &str = ".frames/";
+
+/// Frames per second extracted when the sync request doesn't pick a rate.
+pub const DEFAULT_FRAME_RATE: f64 = 1.0;
+
+/// Upper bound of frames extracted from one video, so a long recording can't fill the bucket.
+pub const MAX_FRAMES: usize = 10_000;
+
+/// Presigned frame URLs stay valid as long as the ones of task images.
+const FRAME_URL_EXPIRY_SECS: u64 = 3600;
+
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct TaskFrame {
+    pub frame_index: i32,
+    pub resource_url: String,
+    pub timestamp_ms: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskFrameWithResolvedUrl {
+    #[serde(flatten)]
+    pub frame: TaskFrame,
+    pub resolved_resource_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskFramesResponse {
+    /// Frames per second the video was sampled at, `None` for image tasks
+    pub frame_rate: Option<f64>,
+    pub frames: Vec<TaskFrameWithResolvedUrl>,
+}
+
+/// A frame uploaded to storage during extraction.
+#[derive(Debug)]
+pub struct ExtractedFrame {
+    pub frame_index: i32,
+    pub storage_key: String,
+    pub timestamp_ms: i64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{project_id}/tasks/{task_id}/frames",
+    tag = "media",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("task_id" = Uuid, Path, description = "Task ID"),
+    ),
+    responses(
+        (status = 200, description = "Frames of a video task in order, none for image tasks", body = TaskFramesResponse),
+        (status = 401, description = "Missing or invalid token", body = String),
+        (status = 404, description = "Project or task not found", body = String),
+    ),
+)]
+pub async fn list_task_frames(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    pool: web::Data<Pool<Postgres>>,
+    config: web::Data<crate::auth::OAuthConfig>,
+) -> impl Responder {
+    // Extract and verify JWT token
+    let claims = match extract_user_claims(&req, &config) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user ID"),
+    };
+
+    let (project_id_str, task_id_str) = path.into_inner();
+    let project_id = match Uuid::parse_str(&project_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid project ID"),
+    };
+
+    let task_id = match Uuid::parse_str(&task_id_str) {
+        Ok(id) => id,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid task ID"),
+    };
+
+    // Check if user has access to this project
+    if !user_has_project_access(&pool, project_id, user_id).await {
+        return HttpResponse::NotFound().json("Project not found or access denied");
+    }
+
+    let frame_rate = match get_task_frame_rate(&pool, task_id, project_id).await {
+        Ok(Some(frame_rate)) => frame_rate,
+        Ok(None) => return HttpResponse::NotFound().json("Task not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch task"),
+    };
+
+    let frames = match get_task_frames(&pool, task_id).await {
+        Ok(frames) => frames,
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch frames"),
+    };
+
+    // One provider presigns every frame instead of rebuilding it per URL
+    let storage_provider = match get_project_by_id(&pool, project_id).await {
+        Ok(Some(project)) => create_storage_provider_from_project(&project).await.ok(),
+        Ok(None) => return HttpResponse::NotFound().json("Project not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch project"),
+    };
+
+    let mut resolved_frames = Vec::with_capacity(frames.len());
+    for frame in frames {
+        let resolved_resource_url = match (frame.resource_url.strip_prefix("storage://"), &storage_provider) {
+            (Some(key), Some(provider)) => provider.get_presigned_url(key, FRAME_URL_EXPIRY_SECS).await.ok(),
+            (Some(_), None) => None,
+            (None, _) => Some(frame.resource_url.clone()),
+        };
+        resolved_frames.push(TaskFrameWithResolvedUrl { frame, resolved_resource_url });
+    }
+
+    HttpResponse::Ok().json(TaskFramesResponse {
+        frame_rate,
+        frames: resolved_frames,
+    })
+}
+
+pub fn is_video_file(file_key: &str) -> bool {
+    Path::new(file_key)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| VIDEO_EXTENSIONS.iter().any(|video_ext| video_ext.eq_ignore_ascii_case(ext)))
+}
+
+/// Frames written by an earlier sync must not come back as image tasks.
+pub fn is_extracted_frame(file_key: &str) -> bool {
+    file_key.contains(FRAMES_DIR_SUFFIX)
+}
+
+/// Video or slice stack an extracted frame was written for
+pub fn frame_source_key(file_key: &str) -> Option<&str> {
+    file_key.split_once(FRAMES_DIR_SUFFIX).map(|(source_key, _)| source_key)
+}
+
+pub fn is_valid_frame_rate(frame_rate: f64) -> bool {
+    frame_rate.is_finite() && frame_rate > 0.0 && frame_rate <= 120.0
+}
+
+/// Boxes of video tasks must sit on one of the extracted frames, boxes of still images on none.
+pub fn is_valid_frame_index(frame_index: Option<i32>, frame_count: Option<i32>) -> bool {
+    match (frame_index, frame_count) {
+        (None, None) => true,
+        (Some(index), Some(count)) => (0..count).contains(&index),
+        _ => false,
+    }
+}
+
+pub fn frame_storage_key(video_key: &str, frame_index: i32) -> String {
+    format!("{}{}{:06}.jpg", video_key, FRAMES_DIR_SUFFIX, frame_index)
+}
+
+pub fn frame_timestamp_ms(frame_index: i32, frame_rate: f64) -> i64 {
+    (frame_index as f64 * 1000.0 / frame_rate).round() as i64
+}
+
+/// Samples the video at `frame_rate` frames per second with `ffmpeg` and uploads the frames
+/// as JPEGs next to it. Returns the frames in order and the size of the first one.
+pub async fn extract_frames(
+    storage_provider: &dyn StorageProvider,
+    video_key: &str,
+    frame_rate: f64,
+) -> Result<(Vec<ExtractedFrame>, Option<(u32, u32)>), String> {
+    let video_data = storage_provider.download(video_key)
+        .await
+        .map_err(|e| format!("Failed to download video: {}", e))?;
+
+    let work_dir = std::env::temp_dir().join(format!("fast-tag-frames-{}", Uuid::new_v4()));
+    let result = extract_frames_in(&work_dir, storage_provider, video_key, &video_data, frame_rate).await;
+    let _ = tokio::fs::remove_dir_all(&work_dir).await;
+    result
+}
+
+async fn extract_frames_in(
+    work_dir: &Path,
+    storage_provider: &dyn StorageProvider,
+    video_key: &str,
+    video_data: &[u8],
+    frame_rate: f64,
+) -> Result<(Vec<ExtractedFrame>, Option<(u32, u32)>), String> {
+    tokio::fs::create_dir_all(work_dir)
+        .await
+        .map_err(|e| format!("Failed to create working directory: {}", e))?;
+
+    let input_path = work_dir.join("input");
+    tokio::fs::write(&input_path, video_data)
+        .await
+        .map_err(|e| format!("Failed to write video: {}", e))?;
+
+    let output = tokio::process::Command::new(ffmpeg_binary())
+        .args(["-hide_banner", "-loglevel", "error", "-i"])
+        .arg(&input_path)
+        .arg("-vf")
+        .arg(format!("fps={}", frame_rate))
+        .arg("-frames:v")
+        .arg(MAX_FRAMES.to_string())
+        .args(["-q:v", "2"])
+        .arg(work_dir.join("frame_%06d.jpg"))
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg (set FFMPEG_PATH if it is not on PATH): {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("ffmpeg failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    let mut frame_paths = Vec::new();
+    let mut entries = tokio::fs::read_dir(work_dir)
+        .await
+        .map_err(|e| format!("Failed to read frames: {}", e))?;
+    while let Some(entry) = entries.next_entry().await.map_err(|e| format!("Failed to read frames: {}", e))? {
+        let path = entry.path();
+        if path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with("frame_")) {
+            frame_paths.push(path);
+        }
+    }
+    // The numbers are zero-padded, so name order is frame order
+    frame_paths.sort();
+
+    if frame_paths.is_empty() {
+        return Err("ffmpeg produced no frames".to_string());
+    }
+
+    let mut frames = Vec::with_capacity(frame_paths.len());
+    let mut dimensions = None;
+    for (index, path) in frame_paths.iter().enumerate() {
+        let frame_data = tokio::fs::read(path)
+            .await
+            .map_err(|e| format!("Failed to read frame: {}", e))?;
+
+        if dimensions.is_none() {
+            dimensions = image::load_from_memory(&frame_data).ok().map(|img| img.dimensions());
+        }
+
+        let frame_index = index as i32;
+        let storage_key = frame_storage_key(video_key, frame_index);
+        storage_provider.upload(&storage_key, &frame_data, Some("image/jpeg"))
+            .await
+            .map_err(|e| format!("Failed to upload frame {}: {}", frame_index, e))?;
+
+        frames.push(ExtractedFrame {
+            frame_index,
+            storage_key,
+            timestamp_ms: frame_timestamp_ms(frame_index, frame_rate),
+        });
+    }
+
+    Ok((frames, dimensions))
+}
+
+fn ffmpeg_binary() -> String {
+    std::env::var("FFMPEG_PATH").unwrap_or_else(|_| "ffmpeg".to_string())
+}
+
+/// Turns the task into a multi-frame task of `media_type` holding `frames`, replacing frames
+/// of an earlier extraction. Slice stacks have no frame rate.
+pub async fn save_task_frames(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
+    media_type: &str,
+    frames: &[ExtractedFrame],
+    frame_rate: Option<f64>,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM task_frames WHERE task_id = $1")
+        .bind(task_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let frame_indices: Vec<i32> = frames.iter().map(|frame| frame.frame_index).collect();
+    let resource_urls: Vec<String> = frames.iter().map(|frame| format!("storage://{}", frame.storage_key)).collect();
+    let timestamps: Vec<i64> = frames.iter().map(|frame| frame.timestamp_ms).collect();
+
+    sqlx::query(
+        r#"
+        INSERT INTO task_frames (task_id, frame_index, resource_url, timestamp_ms)
+        SELECT $1, f.frame_index, f.resource_url, f.timestamp_ms
+        FROM UNNEST($2::INTEGER[], $3::TEXT[], $4::BIGINT[]) AS f(frame_index, resource_url, timestamp_ms)
+        "#
+    )
+    .bind(task_id)
+    .bind(&frame_indices)
+    .bind(&resource_urls)
+    .bind(&timestamps)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "UPDATE tasks SET media_type = $2, frame_count = $3, frame_rate = $4, updated_at = NOW() WHERE id = $1"
+    )
+    .bind(task_id)
+    .bind(media_type)
+    .bind(frames.len() as i32)
+    .bind(frame_rate)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+/// `None` when the task doesn't exist in the project, `Some(None)` for image tasks.
+async fn get_task_frame_rate(
+    pool: &Pool<Postgres>,
+    task_id: Uuid,
+    project_id: Uuid,
+) -> Result<Option<Option<f64>>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<f64>>("SELECT frame_rate FROM tasks WHERE id = $1 AND project_id = $2")
+        .bind(task_id)
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+}
+
+async fn get_task_frames(pool: &Pool<Postgres>, task_id: Uuid) -> Result<Vec<TaskFrame>, sqlx::Error> {
+    sqlx::query_as::<_, TaskFrame>(
+        "SELECT frame_index, resource_url, timestamp_ms FROM task_frames WHERE task_id = $1 ORDER BY frame_index"
+    )
+    .bind(task_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_project_by_id(
+    pool: &Pool<Postgres>,
+    project_id: Uuid,
+) -> Result<Option<crate::projects::Project>, sqlx::Error> {
+    sqlx::query_as::<_, crate::projects::Project>(
+        "SELECT id, name, description, storage_config, owner_id, task_type, created_at, updated_at FROM projects WHERE id = $1"
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn user_has_project_access(pool: &Pool<Postgres>, project_id: Uuid, user_id: Uuid) -> bool {
+    crate::cache::user_has_project_access(pool, project_id, user_id).await
+}
+
+fn extract_user_claims(
+    req: &HttpRequest,
+    config: &crate::auth::OAuthConfig,
+) -> Result<Claims, HttpResponse> {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return Err(HttpResponse::Unauthorized().json("Authorization header missing")),
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(str) => str,
+        Err(_) => return Err(HttpResponse::Unauthorized().json("Invalid authorization header")),
+    };
+
+    let token = match auth_str.strip_prefix("Bearer ") {
+        Some(token) => token,
+        None => return Err(HttpResponse::Unauthorized().json("Invalid authorization format")),
+    };
+
+    let jwt_manager = JwtManager::new(&config.jwt_secret);
+    match jwt_manager.verify_token(token) {
+        Ok(claims) => Ok(claims),
+        Err(_) => Err(HttpResponse::Unauthorized().json("Invalid or expired token")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::OAuthConfig;
+    use crate::test_utils;
+    use actix_web::{test, App, web};
+    use serial_test::serial;
+
+    #[actix_web::test]
+    async fn test_video_files_and_frame_keys() {
+        assert!(is_video_file("clips/drive.mp4"));
+        assert!(is_video_file("clips/drive.MOV"));
+        assert!(!is_video_file("images/frame.jpg"));
+        assert!(!is_video_file("mp4"));
+
+        let key = frame_storage_key("clips/drive.mp4", 12);
+        assert_eq!(key, "clips/drive.mp4.frames/000012.jpg");
+        assert!(is_extracted_frame(&key));
+        assert!(!is_extracted_frame("clips/drive.mp4"));
+        assert_eq!(frame_source_key(&key), Some("clips/drive.mp4"));
+
+        assert_eq!(frame_timestamp_ms(3, 2.0), 1500);
+        assert!(!is_valid_frame_rate(0.0));
+        assert!(!is_valid_frame_rate(f64::NAN));
+    }
+
+    #[actix_web::test]
+    async fn test_is_valid_frame_index() {
+        assert!(is_valid_frame_index(None, None));
+        assert!(is_valid_frame_index(Some(0), Some(10)));
+        assert!(is_valid_frame_index(Some(9), Some(10)));
+        assert!(!is_valid_frame_index(Some(10), Some(10)));
+        assert!(!is_valid_frame_index(Some(-1), Some(10)));
+        // Video boxes need a frame, image boxes can't have one
+        assert!(!is_valid_frame_index(None, Some(10)));
+        assert!(!is_valid_frame_index(Some(0), None));
+    }
+
+    #[actix_web::test]
+    #[serial]
+    async fn test_list_task_frames_unauthorized() {
+        let pool = test_utils::setup_test_db().await;
+        let oauth_config = OAuthConfig {
+            google_client_id: "test_google_id".to_string(),
+            google_client_secret: "test_google_secret".to_string(),
+            google_redirect_url: "http://localhost/callback".to_string(),
+            github_client_id: "test_github_id".to_string(),
+            github_client_secret: "test_github_secret".to_string(),
+            github_redirect_url: "http://localhost/callback".to_string(),
+            jwt_secret: "test_jwt_secret_key_that_is_long_enough".to_string(),
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(oauth_config))
+                .route("/projects/{project_id}/tasks/{task_id}/frames", web::get().to(list_task_frames))
+        ).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}/tasks/{}/frames", Uuid::new_v4(), Uuid::new_v4()))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 401);
+    }
+}
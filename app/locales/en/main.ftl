@@ -0,0 +1,795 @@
+# UI strings of the desktop app in English, the language every other locale falls back to.
+# Keep the messages of each page together, in the order they show up on the page.
+
+## Shared
+
+common-cancel = Cancel
+common-save = 💾 Save
+common-edit = Edit
+common-ok = OK
+common-reset = Reset
+common-error = Error: { $error }
+common-not-authenticated = Not authenticated
+common-invalid-project-id = Invalid project ID
+
+## Top bar
+
+nav-projects = 📁 Projects
+nav-settings = 🔧 Settings
+nav-tasks = ✨ Tasks
+nav-detail = 🕑 Detail
+nav-reports = 📊 Reports
+nav-stats = 📈 Stats
+nav-storage = 🗄 Storage
+nav-review = 🔍 Review
+nav-profile = 👤 Profile
+nav-log-out = 🚪 Log out
+
+## Login
+
+login-title = Login
+login-server = Server:
+login-custom-server = Custom
+login-profile-name = Profile name
+login-save-profile = 💾 Save profile
+login-delete-profile = 🗑 Delete profile
+login-check-connection = 🩺 Check connection
+login-connected = Connected to { $service } (database { $database })
+login-server-status = Server reports { $status } (database { $database })
+login-with-github = 🚀 Login with GitHub
+login-with-google = 🔍 Login with Google
+login-remember-me = Remember me
+login-waiting = 🔄 Waiting for authentication...
+login-restoring = 🔄 Restoring your session...
+login-complete-in-browser = Please complete the authentication in your browser.
+login-open-sign-in-page = Open the sign-in page again
+login-error = ❌ Error: { $error }
+login-try-again = Try Again
+login-success = ✅ Login successful! Redirecting...
+login-skip-development = Skip (Development)
+login-timeout = Authentication timeout
+login-open-browser-failed = Failed to open browser: { $error }
+login-start-failed = Failed to call auth API: { $error }
+login-expired = Authentication session expired
+login-failed = Authentication failed
+login-unknown-status = Unknown status: { $status }
+login-poll-failed = Failed to poll for JWT: { $error }
+
+## Projects
+
+projects-title = Projects
+projects-new = ➕ New Project
+projects-templates-failed = Failed to load templates: { $error }
+projects-refresh = 🔄 Refresh
+projects-loading = Loading projects...
+projects-empty = No projects found
+projects-empty-hint = Create your first project to get started!
+projects-no-description = No description
+projects-classification = Classification
+projects-created = Created: { $date }
+projects-open = Open
+projects-recent = Recent projects
+projects-resume = Resume
+projects-resume-hint = Open the task you last worked on in this project
+projects-upload = ⬆ Upload
+projects-archive = Archive
+projects-archive-hint = Make the project read-only and move it to the archived projects
+projects-archive-failed = Failed to change the archive state: { $error }
+projects-archived = Archived ({ $count })
+projects-archived-on = Archived: { $date }
+projects-unarchive = Unarchive
+projects-create-title = Create New Project
+projects-name = Project Name:
+projects-description = Description (optional):
+projects-template = Template:
+projects-blank-template = Blank project
+projects-builtin-template = { $name } ({ $count } { $count ->
+        [one] category
+       *[other] categories
+    })
+projects-custom-template = { $name } ({ $count } { $count ->
+        [one] category
+       *[other] categories
+    }, custom)
+projects-template-more = { $names }, +{ $count } more
+projects-classification-option = Image classification (whole-image labels, no boxes)
+projects-create = Create
+
+## Reports
+
+reports-title = Annotator Reports
+reports-no-project = Open the reports of a project from the projects page
+reports-from = From
+reports-to = to
+reports-last-days = Last { $days } days
+reports-invalid-date = Dates must look like 2025-01-31
+reports-empty = Nothing was annotated between { $from } and { $to }
+reports-annotator = Annotator
+reports-tasks = Tasks
+reports-annotations = Annotations
+reports-boxes = Boxes
+reports-time-per-task = Time per task
+reports-boxes-per-day = Boxes per day
+reports-per-day = Per day
+reports-total = Total: { $count }
+
+## Dataset stats
+
+stats-title = Dataset Statistics
+stats-no-project = Open the stats of a project from the projects page
+stats-summary = { $boxes } boxes on { $tasks } annotated tasks, latest saves only
+stats-empty = No boxes were saved in this project yet
+stats-without-dimensions = { $count } boxes are on images of unknown size and are left out of the heatmap and the sizes
+stats-centers = Box centers
+stats-cell = x { $x }, y { $y }
+stats-boxes = { $count } boxes
+stats-sizes = Box sizes, relative to the image
+stats-width = Width
+stats-height = Height
+stats-categories = Categories
+stats-category = Category
+stats-share = Share
+stats-uncategorized = No category
+stats-unused = Unused
+
+## Storage browser
+
+storage-title = Storage
+storage-no-project = Open the storage of a project from the projects page
+storage-root = Bucket
+storage-up = ⬆ Up
+storage-preview = Preview
+storage-preview-hint = Pick a file to preview it
+storage-preview-unsupported = No preview for this kind of file
+storage-empty = This folder is empty
+storage-sync-folder = 🔄 Sync this folder
+storage-sync-folder-description = Creates tasks for the images in { $folder } and its subfolders
+
+## Review
+
+review-no-project = Open the review of a project from the projects page
+review-queue = Waiting for review ({ $count })
+review-queue-empty = No tasks are waiting for review. Tasks show up here once a quality-control sample marks them.
+review-queue-item = { $task } · { $count ->
+        [one] { $count } box
+       *[other] { $count } boxes
+    }
+review-before = Before the last save
+review-after = Last save
+review-box-count = { $count ->
+        [one] { $count } box
+       *[other] { $count } boxes
+    }
+review-image-failed = Failed to load the image: { $error }
+review-frames-in-editor = Video and volume tasks are reviewed frame by frame in the editor.
+review-comment = Reviewer comment
+review-comment-hint = What is right or needs fixing, posted on the task
+review-approve = ✔ Approve (A)
+review-reject = ✖ Reject (R)
+review-open-in-editor = ✏ Open in editor
+review-keys-hint = ↑ and ↓ move through the queue
+review-reject-needs-comment = Say in the comment what needs fixing before rejecting
+review-approved = Approved { $task }
+review-rejected = Sent { $task } back to the annotators
+review-decision-failed = Failed to save the review: { $error }
+
+## Tasks
+
+tasks-title = Tasks
+tasks-new = ➕ New Task
+tasks-paste-image = 📋 Paste Image
+tasks-paste-image-hint = Creates a task from the image in the clipboard ({ $shortcut })
+tasks-start-annotation = 🎯 Start Annotation
+tasks-invalid-resource-url = Task has no valid resource URL
+tasks-no-resource-url = Task has no resource URL
+tasks-none-unannotated = No unannotated tasks available
+tasks-next-failed = Failed to fetch next task: { $error }
+tasks-filter-all = All tasks
+tasks-filter-flagged = 🚩 Flagged
+tasks-filter-not-flagged = Not flagged
+tasks-filter-any-annotation = Annotated or not
+tasks-filter-annotated = Annotated
+tasks-filter-unannotated = Not annotated
+tasks-filter-any-category = Any category
+tasks-filter-category = Contains { $category }
+tasks-filter-any-source = Any source file
+tasks-filter-stale = ⚠ Source changed
+tasks-filter-not-stale = Source unchanged
+tasks-filter-any-due = Any due date
+tasks-filter-overdue = Overdue
+tasks-filter-in-time = Not overdue
+tasks-back-to-projects = ← Back to Projects
+tasks-loading = Loading tasks...
+tasks-empty = No tasks found
+tasks-empty-hint = Create your first task to get started!
+tasks-selected = { $count } selected
+tasks-select-all = Select all
+tasks-clear-selection = Clear selection
+tasks-set-status = Set status
+tasks-add-to-split = Add to split
+tasks-remove-from-split = Remove from split
+tasks-set-due = Set due date
+tasks-set-due-apply = Set
+tasks-clear-due = Clear due date
+tasks-clear-due-hint = The tasks follow the deadline settings of the project again
+tasks-assign = Assign
+tasks-unassign = Unassign
+tasks-assign-hint = Replaces the current assignees of the selected tasks
+tasks-no-members = No project members loaded
+tasks-check-sources = 🔎 Check source files
+tasks-check-sources-hint = Compares the files in storage with the ones the selected tasks were made from
+tasks-delete = 🗑 Delete
+tasks-delete-title = Delete tasks
+tasks-delete-confirm = Delete { $count ->
+        [one] { $count } task
+       *[other] { $count } tasks
+    }? Their annotations and comments are deleted with them.
+tasks-box-count-hint = Boxes in the latest annotation
+tasks-priority = Priority: { $priority }
+tasks-stale = ⚠ Source changed
+tasks-stale-hint = { $key } changed or was removed since the task was made from it
+tasks-due-hint = Due date
+tasks-overdue-hint = Past its due date and not done yet
+tasks-batch-deleted = Deleted { $count ->
+        [one] { $count } task
+       *[other] { $count } tasks
+    }
+tasks-batch-status-set = Set { $count ->
+        [one] { $count } task
+       *[other] { $count } tasks
+    } to { $status }
+tasks-batch-unassigned = Unassigned { $count ->
+        [one] { $count } task
+       *[other] { $count } tasks
+    }
+tasks-batch-assigned = Assigned { $count ->
+        [one] { $count } task
+       *[other] { $count } tasks
+    } to { $annotators ->
+        [one] { $annotators } annotator
+       *[other] { $annotators } annotators
+    }
+tasks-batch-split-set = Added { $count ->
+        [one] { $count } task
+       *[other] { $count } tasks
+    } to { $split }
+tasks-batch-split-removed = Removed { $count ->
+        [one] { $count } task
+       *[other] { $count } tasks
+    } from their split
+tasks-batch-due-set = Made { $count ->
+        [one] { $count } task
+       *[other] { $count } tasks
+    } due on { $date }
+tasks-batch-due-cleared = Cleared the due date of { $count ->
+        [one] { $count } task
+       *[other] { $count } tasks
+    }
+tasks-batch-sources-checked = { $count ->
+        [one] { $count } source file changed
+       *[other] { $count } source files changed
+    }
+tasks-batch-failed = Bulk operation failed: { $error }
+tasks-clipboard-open-failed = Failed to open the clipboard: { $error }
+tasks-clipboard-no-image = The clipboard holds no image
+tasks-clipboard-malformed = The clipboard image is malformed
+tasks-clipboard-encode-failed = Failed to encode the clipboard image: { $error }
+tasks-pasted = Created task { $name } from the clipboard
+tasks-paste-failed = Failed to paste image: { $error }
+
+status-pending = 📋 Pending
+status-in-progress = 🔄 In Progress
+status-completed = ✅ Completed
+status-cancelled = ❌ Cancelled
+
+## Settings
+
+settings-title = Project Settings
+settings-language = Language:
+settings-no-projects = No projects available
+settings-no-projects-hint = Go to Projects page to create a project first.
+settings-project-details = Project Details
+settings-cancel-editing = ❌ Cancel
+settings-name = Name:
+settings-description = Description:
+settings-created = Created:
+settings-strict-bounds = Reject boxes outside the image
+settings-strict-bounds-hint = Otherwise boxes drawn past the edge of the image are clipped to it
+settings-strict-bounds-saved = Bounds check saved
+settings-rules-title = Labeling Rules
+settings-rules-description = Boxes that break these rules are not saved. Leave a limit empty to not check it.
+settings-rules-min-width = Minimum box width (px)
+settings-rules-min-height = Minimum box height (px)
+settings-rules-max-boxes = Maximum boxes per image
+settings-rules-no-limit = No limit
+settings-rules-required-categories = Categories every task needs a box of:
+settings-rules-invalid = Limits must be positive numbers, and the maximum boxes a whole number
+settings-rules-saved = Labeling rules saved
+settings-deadlines-title = Deadlines
+settings-deadlines-description = When tasks without a due date of their own are due. Tasks past it that aren't completed or cancelled show up as overdue.
+settings-deadlines-deadline = Campaign deadline:
+settings-deadlines-due-days = Days to finish a new task:
+settings-deadlines-none = None
+settings-deadlines-invalid = The deadline must be a day like 2026-12-31, the days a whole number above 0
+settings-deadlines-saved = Deadlines saved
+settings-project-saved = Project saved
+settings-project-save-failed = Failed to save the project: { $error }
+settings-sync-title = Storage Sync
+settings-sync-description = Sync files from storage to create annotation tasks.
+settings-sync-skip-duplicates = Skip images identical to ones already in the project
+settings-sync-start = 🔄 Start Sync
+settings-sync-starting = Starting sync...
+settings-syncing = Syncing...
+settings-sync-progress = Progress: { $processed } / { $total } files
+settings-sync-done = Sync completed! Created { $created } tasks, skipped { $skipped } tasks.
+settings-sync-errors = Completed with { $count ->
+        [one] { $count } error
+       *[other] { $count } errors
+    }: { $errors }
+settings-sync-failed = Sync failed: { $error }
+settings-sync-history = History
+settings-sync-history-empty = This project was never synced
+settings-sync-history-page = Page { $page } of { $pages }
+settings-sync-status-running = Running
+settings-sync-status-completed = Completed
+settings-sync-status-completed-with-errors = Completed with errors
+settings-sync-status-failed = Failed
+settings-sync-run-counts = { $files } files, { $created } created, { $skipped } skipped
+settings-sync-run-errors = { $count ->
+        [one] { $count } error
+       *[other] { $count } errors
+    }
+settings-storage-title = Storage Configuration
+settings-storage-configure = Configure
+settings-storage-provider = Provider:
+settings-storage-select-provider-short = Select provider
+settings-storage-select-provider = Please select a storage provider.
+settings-storage-local = Local Storage
+settings-storage-bucket = Bucket:
+settings-storage-region = Region:
+settings-storage-access-key = Access Key:
+settings-storage-secret-key = Secret Key:
+settings-storage-endpoint = Endpoint (optional):
+settings-storage-account-name = Account Name:
+settings-storage-account-key = Account Key:
+settings-storage-container-name = Container Name:
+settings-storage-container = Container:
+settings-storage-project-id = Project ID:
+settings-storage-service-account-key = Service Account Key (JSON):
+settings-storage-base-path = Base Path:
+settings-storage-none = No storage configuration set.
+settings-storage-invalid = Invalid storage configuration
+settings-storage-saved = Storage configuration saved
+settings-storage-save-failed = Failed to save the storage configuration: { $error }
+settings-categories-title = Annotation Categories
+settings-categories-existing = Existing Categories:
+settings-categories-empty = No categories created yet.
+settings-categories-create-title = Create New Category:
+settings-categories-color = Color:
+settings-categories-parent = Parent:
+settings-categories-top-level = None (top level)
+settings-categories-create = ➕ Create Category
+settings-creating = Creating...
+settings-categories-import-title = Import Categories:
+settings-categories-import-formats = JSON array of categories, or CSV with name, description, supercategory, color and coco_id columns
+settings-categories-import-update = Update existing categories with the same name
+settings-categories-import = 📁 Import categories from file
+settings-categories-imported = Imported { $created } categories, updated { $updated }{ $skipped ->
+        [0] {""}
+       *[other] , skipped { $skipped } existing
+    }
+settings-categories-import-failed = Failed to import categories: { $error }
+settings-read-file-failed = Failed to read file: { $error }
+settings-importing = Importing...
+settings-export-title = Export Data
+settings-export-description = Download annotation data in various formats:
+settings-export-coco = 📥 Download COCO Format
+settings-export-coco-hint = Export annotations in COCO format (JSON)
+settings-export-include-metadata = Include annotator and review details
+settings-export-include-metadata-hint = Adds who saved each annotation, when, and the review state of its task under "x-fasttag"
+settings-export-include-images = Include images
+settings-export-include-images-hint = Downloads a ZIP with the images next to the annotation file
+settings-export-splits = Splits:
+settings-export-splits-hint = Only export the tasks of the checked splits, or every task when none is checked
+settings-export-labels = Label files:
+settings-export-labels-download = 📥 Download
+settings-export-format-mot = MOTChallenge
+settings-export-format-kitti = KITTI
+settings-export-mot-hint = ZIP with the object tracks as MOTChallenge sequences, one per video and one for the images in name order
+settings-export-kitti-hint = ZIP with a KITTI label file per image and a KITTI tracking file per video, rotated boxes as their upright envelope
+settings-export-preset = Preset:
+settings-export-preset-none = (none)
+settings-export-preset-default-name = { $name } (default)
+settings-export-preset-name = Preset name
+settings-export-preset-make-default = Default for this project
+settings-export-preset-save = 💾 Save preset
+settings-export-preset-delete = 🗑 Delete preset
+settings-export-preset-saved = Saved export preset "{ $name }"
+settings-export-preset-failed = Export preset request failed: { $error }
+settings-downloading = Downloading...
+settings-export-done = Export completed! File saved to: { $path }
+settings-save-file-failed = Failed to save file: { $error }
+settings-export-destination = Storage folder:
+settings-export-to-storage = ☁ Export to storage
+settings-export-to-storage-hint = Needs a storage configuration, and waits for the running export
+settings-export-recent = Recent exports
+settings-export-recent-empty = No exports to storage yet
+settings-export-size = { $size } MB
+settings-export-download = Download
+settings-download-failed = Failed to download: { $error }
+settings-import-title = Import Data
+settings-import-description = Import annotation data from various formats:
+settings-import-coco = 📁 Import COCO Format
+settings-import-coco-hint = Import categories, tasks, and annotations from COCO format (JSON)
+settings-import-fetch-images = Download images into project storage
+settings-import-fetch-images-hint = Fetches the images from the URLs in the file (coco_url, or file_name when it is a URL) so the tasks keep working when the original host goes away
+settings-import-images-fetched = { $count ->
+        [one] Downloaded { $count } image into the project storage
+       *[other] Downloaded { $count } images into the project storage
+    }
+settings-import-done = Import completed! Created { $categories } categories, { $tasks } tasks, { $annotations } annotations. Updated { $updated } categories.
+settings-import-done-with-errors = { $summary } Note: { $count ->
+        [one] { $count } error
+       *[other] { $count } errors
+    } occurred during import.
+settings-import-failed = Failed to import: { $error }
+settings-duplicate-title = 📄 Duplicate Project
+settings-duplicate-description = Copy the label schema into a new project, optionally with tasks and annotations.
+settings-duplicate-default-name = { $name } (copy)
+settings-duplicate-include-tasks = Include tasks
+settings-duplicate-include-annotations = Include annotations
+settings-duplicate-include-credentials = Include storage credentials
+settings-duplicate-include-credentials-hint = Without credentials the copy keeps the storage location, enter new keys before syncing
+settings-duplicate = 📄 Duplicate Project
+settings-duplicating = Duplicating...
+settings-duplicate-done = Created '{ $name }' with { $categories } categories, { $tasks } tasks and { $annotations } annotations
+settings-duplicate-failed = Failed to duplicate the project: { $error }
+settings-danger-zone = ⚡ Danger Zone
+settings-delete-project = ❌ Delete Project
+settings-delete-irreversible = This action cannot be undone.
+settings-delete-confirm-title = ⚠️ Confirm Delete
+settings-delete-confirm = Are you sure you want to delete this project?
+settings-delete-project-name = Project: { $name }
+settings-delete = ❌ Delete
+settings-delete-error-title = ❌ Delete Error
+settings-delete-failed = Failed to delete project: { $error }
+
+## Uploads
+
+upload-title = Upload images to { $project }
+upload-choose-files = 📂 Choose files...
+upload-images-filter = Images
+upload-drop-hint = or drop images and folders onto this window
+upload-empty-hint = Each image becomes a task as soon as it is uploaded.
+upload-waiting = Waiting
+upload-done = ✔ Task created
+upload-failed = ✖ Failed
+upload-not-an-image = Not an image
+upload-progress = { $finished } of { $total } files uploaded
+upload-summary = { $created ->
+        [one] { $created } task
+       *[other] { $created } tasks
+    } created, { $failed ->
+        [one] { $failed } file
+       *[other] { $failed } files
+    } failed
+upload-close = Close
+
+## Annotation progress
+
+progress-task = Task { $current } / { $total } — { $percent }% annotated today
+progress-details = { $annotated } annotated, { $completed } completed
+    { $today } first annotated today, { $mine } saved by you today
+progress-session = · { $count } this session
+
+## Offline mode
+
+offline-banner = ⚠ Can't reach the server for { $duration }, changes are kept on this computer
+offline-next-check = Next try in { $seconds }s
+offline-retry-now = Retry now
+offline-status-title = Offline status
+offline-waiting = { $count ->
+        [one] { $count } task
+       *[other] { $count } tasks
+    } waiting to be sent
+offline-conflict = { $task } was changed on the server while you were offline
+offline-keep-mine = Keep mine
+offline-keep-server = Keep the server's
+
+## Task links
+
+deep-link-open-failed = Failed to open the linked task: { $error }
+
+## Updates
+
+updates-title = Update available
+updates-available = fast-tag { $version } is available, you have { $current }
+updates-published = Released { $date }
+updates-changelog = What's new
+updates-download = ⬇ Download installer
+updates-no-installer = No installer for this system, ask your administrator
+updates-later = Later
+updates-skip = Skip this version
+updates-downloaded = Installer saved to { $path }
+updates-open-failed = Failed to open the installer: { $error }
+updates-download-failed = Failed to download the update: { $error }
+
+## Exports
+
+exports-started = Export started, you'll be told when it's done
+exports-start-failed = Failed to start the export: { $error }
+exports-completed = Export finished: { $size } MB at { $location }
+exports-failed = Export failed: { $error }
+
+## Notifications
+
+notifications-title = Notifications
+notifications-dismiss = Dismiss
+notifications-empty = No notifications yet
+notifications-clear = Clear
+
+## Annotation editor
+
+detail-sort-by = Sort by:
+detail-sort-x-asc = X Asc
+detail-sort-x-desc = X Desc
+detail-sort-y-asc = Y Asc
+detail-sort-y-desc = Y Desc
+detail-element = element { $index }
+detail-element-suggestion = element { $index } (suggestion { $percent }%)
+detail-class = Class: { $class }
+detail-nudge-hint = Arrow keys nudge by 1 px, Shift + arrows by 10 px
+detail-rotation-hint = Position and size of the box before rotation
+detail-rotation = Rotation:
+detail-interpolated-hint = Interpolated, editing makes it a keyframe
+detail-selected-not-found = Selected rectangle not found
+detail-nothing-selected = No rectangle selected
+detail-attributes = Attributes ({ $category })
+detail-suggestion = Model suggestion ({ $percent }% confidence)
+detail-accept = ✔ Accept
+detail-delete = 🗑 Delete
+detail-selected = Selected
+detail-annotations = Annotations
+detail-categories = Categories
+detail-available-categories = Available Categories:
+detail-class-mapping = Class → Category Mapping
+detail-class-mapping-hint = Rectangle classes will map to categories as follows:
+detail-class-number = Class { $class }:
+detail-save-annotations = 💾 Save Annotations
+detail-save-next-task = 💾️ Save & Next Task
+detail-save-interpolate = 🎞 Save & Interpolate
+detail-interpolate-failed = Failed to interpolate: { $error }
+detail-reload-annotations = 🔄 Reload Annotations
+detail-saving = ⏳ Saving annotations...
+detail-loading-next-task = 🔍 Loading next task...
+detail-loading-task = Loading task...
+detail-right-panel = Right Panel
+detail-previous-frame = ◀ Prev (,)
+detail-next-frame = Next (.) ▶
+detail-carry-boxes = Carry boxes
+detail-carry-boxes-hint = Copy the boxes of the last frame onto frames without boxes
+detail-propagate = ⏭ Propagate to next frame
+detail-propagate-hint = Copy the box onto the next video frame, or the next image task by name, as the same tracked object (Ctrl/Cmd + P)
+detail-propagate-last-frame = This is the last frame of the video
+detail-propagate-last-task = There is no image task after this one
+detail-propagated = Box propagated to { $task }
+detail-propagate-failed = Failed to propagate the box: { $error }
+detail-propagate-conflict = Someone changed the same boxes of the next task, open it to propagate the box
+detail-frame = Frame { $frame }/{ $count } · { $seconds } s
+detail-slice = Slice { $slice }/{ $count }
+detail-window = Window:
+detail-level = Level:
+detail-frame-no-url = Frame { $frame } has no image URL
+detail-frame-failed = Failed to load frame { $frame }: { $error }
+detail-unsaved-title = Unsaved changes
+detail-unsaved-question = You have unsaved changes. Save them before leaving?
+detail-save-and-leave = 💾 Save and leave
+detail-discard-changes = Discard changes
+detail-auto-save = Auto-save
+detail-unsaved-changes = ● Unsaved changes
+detail-all-saved = All changes saved
+detail-save-failed = Failed to save: { $error }
+detail-merge-title = Someone else saved this task
+detail-merge-question = Their changes to other boxes were merged with yours ({ $merged } boxes). { $conflicts } boxes were changed on both sides, pick the version to keep:
+detail-merge-conflict = Box { $number }
+detail-merge-mine = Yours: { $version }
+detail-merge-theirs = Theirs: { $version }
+detail-merge-box = { $category } at ({ $x }, { $y }), { $width } × { $height } px
+detail-merge-box-on-frame = { $version } on frame { $frame }
+detail-merge-deleted = deleted
+detail-merge-save = 💾 Save merge
+detail-merge-keep-theirs = Discard my changes
+detail-merge-saved = Merged boxes saved
+detail-rules-broken = Not saved, the boxes break the labeling rules of the project:
+detail-rule-box-too-small = Box { $number } ({ $width } × { $height } px) is below the minimum size
+detail-rule-too-many-boxes = { $count } boxes, at most { $max } are allowed per image
+detail-rule-too-many-boxes-frame = Frame { $frame }: { $count } boxes, at most { $max } are allowed per frame
+detail-rule-missing-category = At least one "{ $category }" box is required
+detail-selection = Selection
+detail-selection-hint = { $key } + click adds or removes a box, { $key } + drag selects the boxes inside a frame
+detail-boxes-selected = { $count ->
+        [one] { $count } box selected
+       *[other] { $count } boxes selected
+    }
+detail-mixed = (mixed)
+detail-class-field = Class:
+detail-delete-all = 🗑 Delete all
+detail-clear-selection = Clear selection
+detail-classes = Classes
+detail-current-class = Current: { $class }
+detail-shortcuts-hint = F1 lists the shortcuts
+detail-other-group = Other
+detail-view = 🎚 View
+detail-brightness = Brightness
+detail-contrast = Contrast
+detail-gamma = Gamma
+detail-grayscale = Grayscale
+detail-view-hint = Only changes how the image is shown
+detail-drawing-aids = 📐 Drawing aids
+detail-crosshair = Crosshair
+detail-snapping = Snap to box edges and image borders
+detail-loupe = Loupe
+input-settings = Mouse & trackpad
+input-grab-radius = Grab radius
+input-zoom-sensitivity = Zoom sensitivity
+input-pan-button = Pan with
+input-pan-right = Right button
+input-pan-middle = Middle button
+input-scroll-pans = Scrolling pans
+input-scroll-pans-hint = Two-finger scrolling moves the view, pinch or Ctrl/Cmd + scroll to zoom
+input-select-modifier = Selection key
+input-mouse-defaults = Mouse defaults
+input-trackpad-defaults = Trackpad defaults
+detail-minimap = 🗺 Minimap
+detail-layers = 🗂 Layers
+detail-show-boxes = Show boxes
+detail-show-all = Show all
+detail-hide-all = Hide all
+detail-label-opacity = Label opacity
+detail-mask-opacity = Mask opacity
+detail-attributes-in-labels = Attributes in labels
+detail-layers-hint = Hidden boxes are still saved
+detail-tools = Tools
+detail-magic-select = 🪄 Magic select (M)
+detail-magic-select-hint = Click on an object to get a tight box from the segmentation server
+detail-magic-select-login = Open a task while logged in to use magic select
+detail-mask = Mask
+detail-mask-off = Off
+detail-mask-brush = Brush
+detail-mask-eraser = Eraser
+detail-brush-size = Brush size
+detail-mask-hint = Paint on the selected box, or start a new box of the current class with the brush
+detail-labels = Labels
+detail-no-categories = No categories defined for this project
+detail-labels-hint = Shortcut keys toggle labels, Enter saves and opens the next task, F1 lists the keys
+detail-save-next = 💾 Save & Next
+detail-pick-label = Pick at least one label
+detail-labels-saved = { $count ->
+        [one] Saved { $count } label
+       *[other] Saved { $count } labels
+    }
+detail-flagged = 🚩 Flagged: { $reason }
+detail-clear-flag = Clear
+detail-flag-image = 🚩 Flag image
+detail-flag-note = Optional note
+
+## Shortcuts
+
+shortcuts-title = ⌨ Shortcuts
+shortcuts-classes = Classes
+shortcuts-no-class-shortcuts = No class shortcuts
+shortcuts-edit-classes = ✏ Edit class shortcuts…
+shortcuts-editor-title = Edit class shortcuts
+shortcuts-editor-hint = Click a key, then press the new key for the category. Esc cancels.
+shortcuts-press-key = Press a key…
+shortcuts-remove = Remove the shortcut
+shortcuts-clear-all = Clear all
+shortcuts-clear-all-hint = Without any shortcuts the digit keys pick the first nine categories
+shortcuts-modes = Annotation modes
+shortcuts-take-tour = ▶ Take the tour
+shortcut-cheat-sheet = Show or hide this cheat sheet
+shortcut-undo = Undo
+shortcut-redo = Redo
+shortcut-delete = Delete the selected boxes
+shortcut-nudge = Nudge the selected boxes by 1 px
+shortcut-nudge-far = Nudge the selected boxes by 10 px
+shortcut-toggle-selection = Add a box to the selection or remove it
+shortcut-select-frame = Select the boxes inside a frame
+shortcut-cancel = Deselect and cancel the current action
+shortcut-magic-select = Toggle magic select
+shortcut-frames = Previous / next video frame
+shortcut-propagate = Propagate the selected box to the next frame
+shortcut-save-next = Save and open the next task (classification)
+shortcut-zoom = Zoom
+shortcut-fit = Fit the image to the window
+shortcut-actual-size = Zoom to 100%
+shortcut-zoom-selection = Zoom to the selected boxes
+shortcut-pan = Pan
+shortcut-touch = Pan, or pinch to zoom, on touchscreens
+mode-draw = Draw
+mode-draw-how = Drag on the image outside of any box
+mode-move = Move
+mode-move-how = Drag a box, or one of the selected boxes to move them all
+mode-resize = Resize
+mode-resize-how = Drag a corner handle of a box
+mode-rotate = Rotate
+mode-rotate-how = Drag the handle above the selected box
+mode-select = Select
+mode-select-how = { $key } + drag a frame around the boxes
+mode-magic-select = Magic select
+mode-magic-select-how = Press M, then click on an object
+mode-paint-mask = Paint mask
+mode-paint-mask-how = Pick the brush or eraser in the tools window, then drag on the image
+
+## Tour
+
+tour-step = Step { $step } of { $count }
+tour-back = Back
+tour-next = Next
+tour-done = Done
+tour-skip = Skip the tour
+tour-welcome-title = Welcome to fast-tag
+tour-welcome = This short tour shows where to find what you need to annotate. It only takes a minute.
+tour-toolbar-title = Top bar
+tour-toolbar = Switch between projects, settings, tasks and reports here. Log out on the right.
+tour-classes-title = Classes
+tour-classes = Pick the class of new boxes here, or with the shortcut keys next to the names.
+tour-drawing-title = Drawing boxes
+tour-drawing = Drag on the image to draw a box. Drag a box to move it and its corners to resize it. Zoom with the mouse wheel and pan with a right drag.
+tour-save-title = Saving
+tour-save = Save your boxes here. Changes are also saved automatically after a few seconds.
+tour-help-title = Shortcuts
+tour-help = Press F1 at any time for the list of shortcuts and annotation modes. The tour can be taken again from there.
+
+## Comments
+
+comments-title = Comments
+comments-login = Open a task while logged in to see comments
+comments-empty = No comments yet
+comments-show-box = 📍 Show box
+comments-reply = Reply
+comments-reopen = ↺ Reopen
+comments-resolve = ✔ Resolve
+comments-box-gone = The commented box is no longer on this image
+comments-replying = Replying to thread
+comments-hint = Leave feedback, mention with @email
+comments-attach = 📍 Attach to selected box
+comments-post = 💬 Post
+
+## Profile
+
+profile-title = Profile
+profile-unavailable = Your account could not be loaded. Log in again to edit it
+profile-avatar-upload = 🖼 Upload picture
+profile-avatar-remove = Remove picture
+profile-avatar-hint = PNG, JPEG, WebP or GIF, up to 2 MB
+profile-avatar-saved = Profile picture updated
+profile-read-failed = Could not read the image: { $error }
+profile-name = Display name:
+profile-saved = Display name saved
+profile-providers = Sign-in accounts
+profile-providers-hint = Link another account to sign in to this one with it too
+profile-linked-as = Linked as { $email }
+profile-not-linked = Not linked
+profile-link = 🔗 Link
+profile-link-waiting = Finish signing in in your browser…
+profile-link-timeout = Linking timed out. Try again
+profile-linked = { $provider } account linked
+profile-unlink = Unlink
+profile-unlink-last = Link another account before removing the last one
+profile-unlinked = { $provider } account unlinked
+profile-sessions = Signed-in devices
+profile-sessions-hint = Signing a device out stops its access right away, for instance of a lost laptop.
+profile-session-unknown-device = Unknown device
+profile-session-last-used = Last used { $time }
+profile-session-current = This device
+profile-session-revoke = Sign out
+profile-session-revoked = The device was signed out
+profile-delete-title = ⚠ Delete account
+profile-delete-hint = Your account, memberships, comments and the projects only you work in are deleted for good. Annotations you made stay in their projects.
+profile-delete-transfer = Pick a new owner for the projects other members work in:
+profile-delete-project = { $name } ({ $members } other members)
+profile-delete-pick-owner = New owner…
+profile-delete-confirm = Type { $email } to confirm:
+profile-delete = 🗑 Delete my account
+profile-deleted = Your account was deleted
@@ -0,0 +1,732 @@
+# デスクトップアプリの日本語の UI 文字列。ここにないメッセージは英語で表示されます。
+# en/main.ftl と同じ順序で並べてください。
+
+## 共通
+
+common-cancel = キャンセル
+common-save = 💾 保存
+common-edit = 編集
+common-ok = OK
+common-reset = リセット
+common-error = エラー: { $error }
+common-not-authenticated = ログインしていません
+common-invalid-project-id = プロジェクト ID が正しくありません
+
+## トップバー
+
+nav-projects = 📁 プロジェクト
+nav-settings = 🔧 設定
+nav-tasks = ✨ タスク
+nav-detail = 🕑 詳細
+nav-reports = 📊 レポート
+nav-stats = 📈 統計
+nav-storage = 🗄 ストレージ
+nav-review = 🔍 レビュー
+nav-profile = 👤 プロフィール
+nav-log-out = 🚪 ログアウト
+
+## ログイン
+
+login-title = ログイン
+login-server = サーバー:
+login-custom-server = カスタム
+login-profile-name = プロファイル名
+login-save-profile = 💾 プロファイルを保存
+login-delete-profile = 🗑 プロファイルを削除
+login-check-connection = 🩺 接続を確認
+login-connected = { $service } に接続しました (データベース { $database })
+login-server-status = サーバーの状態: { $status } (データベース { $database })
+login-with-github = 🚀 GitHub でログイン
+login-with-google = 🔍 Google でログイン
+login-remember-me = ログイン状態を保持する
+login-waiting = 🔄 認証を待っています...
+login-restoring = 🔄 セッションを復元しています...
+login-complete-in-browser = ブラウザで認証を完了してください。
+login-open-sign-in-page = ログインページをもう一度開く
+login-error = ❌ エラー: { $error }
+login-try-again = もう一度試す
+login-success = ✅ ログインしました。移動しています...
+login-skip-development = スキップ (開発用)
+login-timeout = 認証がタイムアウトしました
+login-open-browser-failed = ブラウザを開けませんでした: { $error }
+login-start-failed = 認証 API の呼び出しに失敗しました: { $error }
+login-expired = 認証セッションの有効期限が切れました
+login-failed = 認証に失敗しました
+login-unknown-status = 不明な状態: { $status }
+login-poll-failed = 認証結果の取得に失敗しました: { $error }
+
+## プロジェクト
+
+projects-title = プロジェクト
+projects-new = ➕ 新規プロジェクト
+projects-templates-failed = テンプレートを読み込めませんでした: { $error }
+projects-refresh = 🔄 更新
+projects-loading = プロジェクトを読み込んでいます...
+projects-empty = プロジェクトがありません
+projects-empty-hint = 最初のプロジェクトを作成しましょう
+projects-no-description = 説明なし
+projects-classification = 分類
+projects-created = 作成日: { $date }
+projects-open = 開く
+projects-recent = 最近のプロジェクト
+projects-resume = 再開
+projects-resume-hint = このプロジェクトで最後に作業したタスクを開きます
+projects-upload = ⬆ アップロード
+projects-archive = アーカイブ
+projects-archive-hint = プロジェクトを読み取り専用にしてアーカイブ済みに移します
+projects-archive-failed = アーカイブの状態を変更できませんでした: { $error }
+projects-archived = アーカイブ済み ({ $count })
+projects-archived-on = アーカイブ日: { $date }
+projects-unarchive = アーカイブを解除
+projects-create-title = 新規プロジェクトの作成
+projects-name = プロジェクト名:
+projects-description = 説明 (任意):
+projects-template = テンプレート:
+projects-blank-template = 空のプロジェクト
+projects-builtin-template = { $name } ({ $count } カテゴリ)
+projects-custom-template = { $name } ({ $count } カテゴリ, カスタム)
+projects-template-more = { $names } ほか { $count } 件
+projects-classification-option = 画像分類 (画像全体にラベルを付け、ボックスは使わない)
+projects-create = 作成
+
+## レポート
+
+reports-title = アノテーターレポート
+reports-no-project = プロジェクト一覧からプロジェクトのレポートを開いてください
+reports-from = 開始
+reports-to = 終了
+reports-last-days = 過去 { $days } 日
+reports-invalid-date = 日付は 2025-01-31 の形式で入力してください
+reports-empty = { $from } から { $to } の間にアノテーションはありません
+reports-annotator = アノテーター
+reports-tasks = タスク
+reports-annotations = アノテーション
+reports-boxes = ボックス
+reports-time-per-task = タスクあたりの時間
+reports-boxes-per-day = 1 日あたりのボックス
+reports-per-day = 日別
+reports-total = 合計: { $count }
+
+## データセット統計
+
+stats-title = データセット統計
+stats-no-project = プロジェクト一覧からプロジェクトの統計を開いてください
+stats-summary = アノテーション済み { $tasks } タスクのボックス { $boxes } 個（最新の保存のみ）
+stats-empty = このプロジェクトにはまだボックスが保存されていません
+stats-without-dimensions = 画像サイズが不明なボックス { $count } 個はヒートマップとサイズから除外されています
+stats-centers = ボックスの中心
+stats-cell = x { $x }、y { $y }
+stats-boxes = ボックス { $count } 個
+stats-sizes = ボックスのサイズ（画像に対する割合）
+stats-width = 幅
+stats-height = 高さ
+stats-categories = カテゴリ
+stats-category = カテゴリ
+stats-share = 割合
+stats-uncategorized = カテゴリなし
+stats-unused = 未使用
+
+## ストレージブラウザ
+
+storage-title = ストレージ
+storage-no-project = プロジェクト一覧からプロジェクトのストレージを開いてください
+storage-root = バケット
+storage-up = ⬆ 上へ
+storage-preview = プレビュー
+storage-preview-hint = ファイルを選ぶとプレビューが表示されます
+storage-preview-unsupported = この種類のファイルはプレビューできません
+storage-empty = このフォルダは空です
+storage-sync-folder = 🔄 このフォルダを同期
+storage-sync-folder-description = { $folder } とそのサブフォルダの画像からタスクを作成します
+
+## レビュー
+
+review-no-project = プロジェクト一覧からプロジェクトのレビューを開いてください
+review-queue = レビュー待ち（{ $count }）
+review-queue-empty = レビュー待ちのタスクはありません。品質管理のサンプルに選ばれたタスクがここに表示されます。
+review-queue-item = { $task } · ボックス { $count } 個
+review-before = 前回の保存
+review-after = 最新の保存
+review-box-count = ボックス { $count } 個
+review-image-failed = 画像を読み込めませんでした: { $error }
+review-frames-in-editor = 動画とボリュームのタスクはエディターでフレームごとにレビューします。
+review-comment = レビューコメント
+review-comment-hint = 良い点や修正が必要な点。タスクにコメントとして投稿されます
+review-approve = ✔ 承認 (A)
+review-reject = ✖ 差し戻し (R)
+review-open-in-editor = ✏ エディターで開く
+review-keys-hint = ↑ と ↓ でキューを移動します
+review-reject-needs-comment = 差し戻す前に、修正が必要な点をコメントに書いてください
+review-approved = { $task } を承認しました
+review-rejected = { $task } をアノテーターに差し戻しました
+review-decision-failed = レビューを保存できませんでした: { $error }
+
+## タスク
+
+tasks-title = タスク
+tasks-new = ➕ 新規タスク
+tasks-paste-image = 📋 画像を貼り付け
+tasks-paste-image-hint = クリップボードの画像からタスクを作成します ({ $shortcut })
+tasks-start-annotation = 🎯 アノテーションを開始
+tasks-invalid-resource-url = タスクのリソース URL が正しくありません
+tasks-no-resource-url = タスクにリソース URL がありません
+tasks-none-unannotated = 未アノテーションのタスクはありません
+tasks-next-failed = 次のタスクを取得できませんでした: { $error }
+tasks-filter-all = すべてのタスク
+tasks-filter-flagged = 🚩 フラグあり
+tasks-filter-not-flagged = フラグなし
+tasks-filter-any-annotation = アノテーションの有無: すべて
+tasks-filter-annotated = アノテーション済み
+tasks-filter-unannotated = 未アノテーション
+tasks-filter-any-category = すべてのカテゴリ
+tasks-filter-category = { $category } を含む
+tasks-filter-any-source = ソースファイル: すべて
+tasks-filter-stale = ⚠ ソース変更あり
+tasks-filter-not-stale = ソース変更なし
+tasks-filter-any-due = 期限を問わない
+tasks-filter-overdue = 期限切れ
+tasks-filter-in-time = 期限内
+tasks-back-to-projects = ← プロジェクト一覧へ
+tasks-loading = タスクを読み込んでいます...
+tasks-empty = タスクがありません
+tasks-empty-hint = 最初のタスクを作成しましょう
+tasks-selected = { $count } 件選択中
+tasks-select-all = すべて選択
+tasks-clear-selection = 選択を解除
+tasks-set-status = 状態を変更
+tasks-add-to-split = スプリットに追加
+tasks-remove-from-split = スプリットから外す
+tasks-set-due = 期限を設定
+tasks-set-due-apply = 設定
+tasks-clear-due = 期限を解除
+tasks-clear-due-hint = タスクはプロジェクトの期限設定に従うようになります
+tasks-assign = 割り当て
+tasks-unassign = 割り当てを解除
+tasks-assign-hint = 選択したタスクの担当者を置き換えます
+tasks-no-members = プロジェクトメンバーが読み込まれていません
+tasks-check-sources = 🔎 ソースファイルを確認
+tasks-check-sources-hint = ストレージのファイルを、選択したタスクの作成元のファイルと比べます
+tasks-delete = 🗑 削除
+tasks-delete-title = タスクの削除
+tasks-delete-confirm = { $count } 件のタスクを削除しますか？アノテーションとコメントも削除されます。
+tasks-box-count-hint = 最新のアノテーションのボックス数
+tasks-priority = 優先度: { $priority }
+tasks-stale = ⚠ ソース変更あり
+tasks-stale-hint = タスクの作成後に { $key } が変更または削除されました
+tasks-due-hint = 期限
+tasks-overdue-hint = 期限を過ぎていて、まだ完了していません
+tasks-batch-deleted = { $count } 件のタスクを削除しました
+tasks-batch-status-set = { $count } 件のタスクを { $status } にしました
+tasks-batch-unassigned = { $count } 件のタスクの割り当てを解除しました
+tasks-batch-assigned = { $count } 件のタスクを { $annotators } 人のアノテーターに割り当てました
+tasks-batch-split-set = { $count } 件のタスクを { $split } に追加しました
+tasks-batch-split-removed = { $count } 件のタスクをスプリットから外しました
+tasks-batch-due-set = { $count } 件のタスクの期限を { $date } にしました
+tasks-batch-due-cleared = { $count } 件のタスクの期限を解除しました
+tasks-batch-sources-checked = { $count } 件のソースファイルが変更されていました
+tasks-batch-failed = 一括操作に失敗しました: { $error }
+tasks-clipboard-open-failed = クリップボードを開けませんでした: { $error }
+tasks-clipboard-no-image = クリップボードに画像がありません
+tasks-clipboard-malformed = クリップボードの画像が壊れています
+tasks-clipboard-encode-failed = クリップボードの画像を変換できませんでした: { $error }
+tasks-pasted = クリップボードからタスク { $name } を作成しました
+tasks-paste-failed = 画像を貼り付けられませんでした: { $error }
+
+status-pending = 📋 未着手
+status-in-progress = 🔄 作業中
+status-completed = ✅ 完了
+status-cancelled = ❌ 中止
+
+split-train = 学習
+split-val = 検証
+split-test = テスト
+
+flag-reason-corrupted = 画像が壊れている
+flag-reason-wrong-dataset = データセットが違う
+flag-reason-cant-tell = 判断できない
+flag-reason-other = その他
+
+## 設定
+
+settings-title = プロジェクト設定
+settings-language = 言語:
+settings-no-projects = プロジェクトがありません
+settings-no-projects-hint = 先にプロジェクト一覧でプロジェクトを作成してください。
+settings-project-details = プロジェクトの詳細
+settings-cancel-editing = ❌ キャンセル
+settings-name = 名前:
+settings-description = 説明:
+settings-created = 作成日:
+settings-strict-bounds = 画像の外にはみ出すボックスを拒否
+settings-strict-bounds-hint = オフの場合、画像の端を越えて描かれたボックスは画像内に切り詰められます
+settings-strict-bounds-saved = 範囲チェックの設定を保存しました
+settings-rules-title = ラベリングルール
+settings-rules-description = ルールに違反するボックスは保存されません。制限を空欄にするとチェックしません。
+settings-rules-min-width = ボックスの最小幅 (px)
+settings-rules-min-height = ボックスの最小高さ (px)
+settings-rules-max-boxes = 画像ごとの最大ボックス数
+settings-rules-no-limit = 制限なし
+settings-rules-required-categories = すべてのタスクに必要なカテゴリ:
+settings-rules-invalid = 制限には正の数を、最大ボックス数には整数を入力してください
+settings-rules-saved = ラベリングルールを保存しました
+settings-deadlines-title = 期限
+settings-deadlines-description = 個別の期限がないタスクの期限です。期限を過ぎても完了・キャンセルされていないタスクは期限切れとして表示されます。
+settings-deadlines-deadline = キャンペーンの締め切り:
+settings-deadlines-due-days = 新しいタスクの作業日数:
+settings-deadlines-none = なし
+settings-deadlines-invalid = 締め切りは 2026-12-31 のような日付、日数は 1 以上の整数で入力してください
+settings-deadlines-saved = 期限を保存しました
+settings-project-saved = プロジェクトを保存しました
+settings-project-save-failed = プロジェクトを保存できませんでした: { $error }
+settings-sync-title = ストレージ同期
+settings-sync-description = ストレージのファイルからアノテーションタスクを作成します。
+settings-sync-skip-duplicates = プロジェクトにある画像と同じ画像はスキップする
+settings-sync-start = 🔄 同期を開始
+settings-sync-starting = 同期を開始しています...
+settings-syncing = 同期しています...
+settings-sync-progress = 進捗: { $processed } / { $total } ファイル
+settings-sync-done = 同期が完了しました。{ $created } 件のタスクを作成し、{ $skipped } 件をスキップしました。
+settings-sync-errors = { $count } 件のエラーがありました: { $errors }
+settings-sync-failed = 同期に失敗しました: { $error }
+settings-sync-history = 履歴
+settings-sync-history-empty = このプロジェクトはまだ同期されていません
+settings-sync-history-page = { $page } / { $pages } ページ
+settings-sync-status-running = 実行中
+settings-sync-status-completed = 完了
+settings-sync-status-completed-with-errors = エラーありで完了
+settings-sync-status-failed = 失敗
+settings-sync-run-counts = { $files } ファイル、作成 { $created } 件、スキップ { $skipped } 件
+settings-sync-run-errors = エラー { $count } 件
+settings-storage-title = ストレージ設定
+settings-storage-configure = 設定する
+settings-storage-provider = プロバイダー:
+settings-storage-select-provider-short = プロバイダーを選択
+settings-storage-select-provider = ストレージのプロバイダーを選択してください。
+settings-storage-local = ローカルストレージ
+settings-storage-bucket = バケット:
+settings-storage-region = リージョン:
+settings-storage-access-key = アクセスキー:
+settings-storage-secret-key = シークレットキー:
+settings-storage-endpoint = エンドポイント (任意):
+settings-storage-account-name = アカウント名:
+settings-storage-account-key = アカウントキー:
+settings-storage-container-name = コンテナー名:
+settings-storage-container = コンテナー:
+settings-storage-project-id = プロジェクト ID:
+settings-storage-service-account-key = サービスアカウントキー (JSON):
+settings-storage-base-path = ベースパス:
+settings-storage-none = ストレージが設定されていません。
+settings-storage-invalid = ストレージの設定が正しくありません
+settings-storage-saved = ストレージの設定を保存しました
+settings-storage-save-failed = ストレージの設定を保存できませんでした: { $error }
+settings-categories-title = アノテーションカテゴリ
+settings-categories-existing = 既存のカテゴリ:
+settings-categories-empty = カテゴリはまだありません。
+settings-categories-create-title = 新しいカテゴリ:
+settings-categories-color = 色:
+settings-categories-parent = 親:
+settings-categories-top-level = なし (最上位)
+settings-categories-create = ➕ カテゴリを作成
+settings-creating = 作成しています...
+settings-categories-import-title = カテゴリのインポート:
+settings-categories-import-formats = カテゴリの JSON 配列、または name, description, supercategory, color, coco_id 列を持つ CSV
+settings-categories-import-update = 同じ名前の既存カテゴリを更新する
+settings-categories-import = 📁 ファイルからカテゴリをインポート
+settings-categories-imported = { $created } 件のカテゴリをインポートし、{ $updated } 件を更新しました{ $skipped ->
+        [0] {""}
+       *[other] 。既存の { $skipped } 件はスキップしました
+    }
+settings-categories-import-failed = カテゴリをインポートできませんでした: { $error }
+settings-read-file-failed = ファイルを読み込めませんでした: { $error }
+settings-importing = インポートしています...
+settings-export-title = データのエクスポート
+settings-export-description = アノテーションデータを各形式でダウンロードします:
+settings-export-coco = 📥 COCO 形式でダウンロード
+settings-export-coco-hint = アノテーションを COCO 形式 (JSON) でエクスポートします
+settings-export-include-metadata = アノテーターとレビューの情報を含める
+settings-export-include-metadata-hint = 各アノテーションの保存者、保存日時、タスクのレビュー状態を「x-fasttag」に追加します
+settings-export-include-images = 画像を含める
+settings-export-include-images-hint = アノテーションファイルと画像をまとめた ZIP をダウンロードします
+settings-export-splits = スプリット:
+settings-export-splits-hint = チェックしたスプリットのタスクだけをエクスポートします。チェックがなければすべてのタスクが対象です
+settings-export-labels = ラベルファイル:
+settings-export-labels-download = 📥 ダウンロード
+settings-export-format-mot = MOTChallenge
+settings-export-format-kitti = KITTI
+settings-export-mot-hint = オブジェクトのトラックを MOTChallenge のシーケンスとして ZIP に書き出します。動画ごとに 1 つ、画像は名前順に 1 つです
+settings-export-kitti-hint = 画像ごとの KITTI ラベルファイルと動画ごとの KITTI トラッキングファイルを ZIP に書き出します。回転したボックスはそれを囲む矩形になります
+settings-export-preset = プリセット:
+settings-export-preset-none = (なし)
+settings-export-preset-default-name = { $name } (デフォルト)
+settings-export-preset-name = プリセット名
+settings-export-preset-make-default = このプロジェクトのデフォルトにする
+settings-export-preset-save = 💾 プリセットを保存
+settings-export-preset-delete = 🗑 プリセットを削除
+settings-export-preset-saved = エクスポートプリセット「{ $name }」を保存しました
+settings-export-preset-failed = エクスポートプリセットの処理に失敗しました: { $error }
+settings-downloading = ダウンロードしています...
+settings-export-done = エクスポートが完了しました。保存先: { $path }
+settings-save-file-failed = ファイルを保存できませんでした: { $error }
+settings-export-destination = ストレージのフォルダ:
+settings-export-to-storage = ☁ ストレージへエクスポート
+settings-export-to-storage-hint = ストレージの設定が必要です。実行中のエクスポートがあれば終わるまで待ちます
+settings-export-recent = 最近のエクスポート
+settings-export-recent-empty = ストレージへのエクスポートはまだありません
+settings-export-size = { $size } MB
+settings-export-download = ダウンロード
+settings-download-failed = ダウンロードに失敗しました: { $error }
+settings-import-title = データのインポート
+settings-import-description = 各形式のアノテーションデータをインポートします:
+settings-import-coco = 📁 COCO 形式をインポート
+settings-import-coco-hint = COCO 形式 (JSON) からカテゴリ、タスク、アノテーションをインポートします
+settings-import-fetch-images = 画像をプロジェクトのストレージにダウンロード
+settings-import-fetch-images-hint = ファイル内の URL (coco_url、または URL になっている file_name) から画像を取得し、元のホストがなくなってもタスクを使えるようにします
+settings-import-images-fetched = 画像 { $count } 件をプロジェクトのストレージにダウンロードしました
+settings-import-done = インポートが完了しました。カテゴリ { $categories } 件、タスク { $tasks } 件、アノテーション { $annotations } 件を作成し、カテゴリ { $updated } 件を更新しました。
+settings-import-done-with-errors = { $summary } なお、インポート中に { $count } 件のエラーがありました。
+settings-import-failed = インポートに失敗しました: { $error }
+settings-duplicate-title = 📄 プロジェクトの複製
+settings-duplicate-description = ラベル構成を新しいプロジェクトにコピーします。タスクとアノテーションも含められます。
+settings-duplicate-default-name = { $name } (コピー)
+settings-duplicate-include-tasks = タスクを含める
+settings-duplicate-include-annotations = アノテーションを含める
+settings-duplicate-include-credentials = ストレージの認証情報を含める
+settings-duplicate-include-credentials-hint = 認証情報を含めない場合、ストレージの場所だけがコピーされます。同期の前に新しいキーを入力してください
+settings-duplicate = 📄 プロジェクトを複製
+settings-duplicating = 複製しています...
+settings-duplicate-done = 「{ $name }」を作成しました (カテゴリ { $categories } 件、タスク { $tasks } 件、アノテーション { $annotations } 件)
+settings-duplicate-failed = プロジェクトを複製できませんでした: { $error }
+settings-danger-zone = ⚡ 危険な操作
+settings-delete-project = ❌ プロジェクトを削除
+settings-delete-irreversible = この操作は取り消せません。
+settings-delete-confirm-title = ⚠️ 削除の確認
+settings-delete-confirm = このプロジェクトを削除してもよろしいですか？
+settings-delete-project-name = プロジェクト: { $name }
+settings-delete = ❌ 削除
+settings-delete-error-title = ❌ 削除エラー
+settings-delete-failed = プロジェクトを削除できませんでした: { $error }
+
+## アップロード
+
+upload-title = { $project } に画像をアップロード
+upload-choose-files = 📂 ファイルを選択...
+upload-images-filter = 画像
+upload-drop-hint = または画像やフォルダーをこのウィンドウにドロップ
+upload-empty-hint = アップロードされた画像はそれぞれすぐにタスクになります。
+upload-waiting = 待機中
+upload-done = ✔ タスクを作成しました
+upload-failed = ✖ 失敗
+upload-not-an-image = 画像ではありません
+upload-progress = { $total } 件中 { $finished } 件のファイルをアップロードしました
+upload-summary = { $created } 件のタスクを作成、{ $failed } 件のファイルが失敗
+upload-close = 閉じる
+
+## アノテーションの進捗
+
+progress-task = タスク { $current } / { $total } — 本日 { $percent }% アノテーション済み
+progress-details = アノテーション済み { $annotated } 件、完了 { $completed } 件
+    本日初めてアノテーションされたもの { $today } 件、本日あなたが保存したもの { $mine } 件
+progress-session = · このセッションで { $count } 件
+
+## オフライン
+
+offline-banner = ⚠ サーバーに { $duration } 接続できていません。変更はこのコンピューターに保存されています
+offline-next-check = { $seconds } 秒後に再試行
+offline-retry-now = 今すぐ再試行
+offline-status-title = オフラインの状態
+offline-waiting = 送信待ちのタスクが { $count } 件あります
+offline-conflict = オフラインの間に { $task } がサーバーで変更されました
+offline-keep-mine = 自分の変更を残す
+offline-keep-server = サーバーの変更を残す
+
+## タスクへのリンク
+
+deep-link-open-failed = リンクされたタスクを開けませんでした: { $error }
+
+## アップデート
+
+updates-title = アップデートがあります
+updates-available = fast-tag { $version } が利用できます（現在 { $current }）
+updates-published = { $date } リリース
+updates-changelog = 変更内容
+updates-download = ⬇ インストーラーをダウンロード
+updates-no-installer = このシステム向けのインストーラーはありません。管理者に問い合わせてください
+updates-later = 後で
+updates-skip = このバージョンをスキップ
+updates-downloaded = インストーラーを { $path } に保存しました
+updates-open-failed = インストーラーを開けませんでした: { $error }
+updates-download-failed = アップデートをダウンロードできませんでした: { $error }
+
+## エクスポート
+
+exports-started = エクスポートを開始しました。終わったらお知らせします
+exports-start-failed = エクスポートを開始できませんでした: { $error }
+exports-completed = エクスポートが完了しました: { $size } MB、保存先 { $location }
+exports-failed = エクスポートに失敗しました: { $error }
+
+## 通知
+
+notifications-title = 通知
+notifications-dismiss = 閉じる
+notifications-empty = 通知はまだありません
+notifications-clear = クリア
+
+## アノテーション編集
+
+detail-sort-by = 並べ替え:
+detail-sort-x-asc = X 昇順
+detail-sort-x-desc = X 降順
+detail-sort-y-asc = Y 昇順
+detail-sort-y-desc = Y 降順
+detail-element = 要素 { $index }
+detail-element-suggestion = 要素 { $index }（提案 { $percent }%）
+detail-class = クラス: { $class }
+detail-nudge-hint = 矢印キーで 1 px、Shift + 矢印キーで 10 px 移動します
+detail-rotation-hint = 回転前のボックスの位置とサイズです
+detail-rotation = 回転:
+detail-interpolated-hint = 補間されたボックスです。編集するとキーフレームになります
+detail-selected-not-found = 選択したボックスが見つかりません
+detail-nothing-selected = ボックスが選択されていません
+detail-attributes = 属性（{ $category }）
+detail-suggestion = モデルの提案（信頼度 { $percent }%）
+detail-accept = ✔ 採用
+detail-delete = 🗑 削除
+detail-selected = 選択中
+detail-annotations = アノテーション
+detail-categories = カテゴリ
+detail-available-categories = 使用できるカテゴリ:
+detail-class-mapping = クラス → カテゴリの対応
+detail-class-mapping-hint = ボックスのクラスは次のカテゴリに対応します:
+detail-class-number = クラス { $class }:
+detail-save-annotations = 💾 アノテーションを保存
+detail-save-next-task = 💾️ 保存して次のタスクへ
+detail-save-interpolate = 🎞 保存して補間
+detail-interpolate-failed = 補間に失敗しました: { $error }
+detail-reload-annotations = 🔄 アノテーションを再読み込み
+detail-saving = ⏳ アノテーションを保存しています...
+detail-loading-next-task = 🔍 次のタスクを読み込んでいます...
+detail-loading-task = タスクを読み込んでいます...
+detail-right-panel = 右パネル
+detail-previous-frame = ◀ 前へ (,)
+detail-next-frame = 次へ (.) ▶
+detail-carry-boxes = ボックスを引き継ぐ
+detail-carry-boxes-hint = ボックスのないフレームに直前のフレームのボックスをコピーします
+detail-propagate = ⏭ 次のフレームへ伝播
+detail-propagate-hint = 同じ追跡オブジェクトとして、次の動画フレームか名前順で次の画像タスクにボックスをコピーします (Ctrl/Cmd + P)
+detail-propagate-last-frame = 動画の最後のフレームです
+detail-propagate-last-task = この後に画像タスクはありません
+detail-propagated = { $task } にボックスを伝播しました
+detail-propagate-failed = ボックスの伝播に失敗しました: { $error }
+detail-propagate-conflict = 次のタスクの同じボックスが他のユーザーに変更されています。タスクを開いて伝播してください
+detail-frame = フレーム { $frame }/{ $count } · { $seconds } 秒
+detail-slice = スライス { $slice }/{ $count }
+detail-window = ウィンドウ幅:
+detail-level = ウィンドウレベル:
+detail-frame-no-url = フレーム { $frame } に画像の URL がありません
+detail-frame-failed = フレーム { $frame } を読み込めませんでした: { $error }
+detail-unsaved-title = 未保存の変更
+detail-unsaved-question = 保存していない変更があります。移動する前に保存しますか？
+detail-save-and-leave = 💾 保存して移動
+detail-discard-changes = 変更を破棄
+detail-auto-save = 自動保存
+detail-unsaved-changes = ● 未保存の変更があります
+detail-all-saved = すべて保存済みです
+detail-save-failed = 保存に失敗しました: { $error }
+detail-merge-title = 他のユーザーがこのタスクを保存しました
+detail-merge-question = 他のボックスへの変更はあなたの変更と統合されました ({ $merged } 個)。{ $conflicts } 個のボックスが双方で変更されています。残す方を選んでください:
+detail-merge-conflict = ボックス { $number }
+detail-merge-mine = あなた: { $version }
+detail-merge-theirs = 相手: { $version }
+detail-merge-box = { $category } ({ $x }, { $y })、{ $width } × { $height } px
+detail-merge-box-on-frame = フレーム { $frame } の { $version }
+detail-merge-deleted = 削除
+detail-merge-save = 💾 統合して保存
+detail-merge-keep-theirs = 自分の変更を破棄
+detail-merge-saved = 統合したボックスを保存しました
+detail-rules-broken = ボックスがプロジェクトのラベリングルールに違反しているため保存していません:
+detail-rule-box-too-small = ボックス { $number } ({ $width } × { $height } px) が最小サイズを下回っています
+detail-rule-too-many-boxes = ボックスが { $count } 個あります。1 枚の画像に置けるのは { $max } 個までです
+detail-rule-too-many-boxes-frame = フレーム { $frame }: ボックスが { $count } 個あります。1 フレームに置けるのは { $max } 個までです
+detail-rule-missing-category = 「{ $category }」のボックスが少なくとも 1 つ必要です
+detail-selection = 選択
+detail-selection-hint = { $key } + クリックでボックスを選択に追加・解除し、{ $key } + ドラッグで枠内のボックスを選択します
+detail-boxes-selected = { $count } 個のボックスを選択中
+detail-mixed = （混在）
+detail-class-field = クラス:
+detail-delete-all = 🗑 すべて削除
+detail-clear-selection = 選択を解除
+detail-classes = クラス
+detail-current-class = 現在: { $class }
+detail-shortcuts-hint = F1 でショートカットを表示します
+detail-other-group = その他
+detail-view = 🎚 表示
+detail-brightness = 明るさ
+detail-contrast = コントラスト
+detail-gamma = ガンマ
+detail-grayscale = グレースケール
+detail-view-hint = 画像の表示だけが変わります
+detail-drawing-aids = 📐 描画補助
+detail-crosshair = 十字線
+detail-snapping = ボックスの端と画像の境界に吸着
+detail-loupe = ルーペ
+input-settings = マウスとトラックパッド
+input-grab-radius = つかむ範囲
+input-zoom-sensitivity = ズームの感度
+input-pan-button = 移動のボタン
+input-pan-right = 右ボタン
+input-pan-middle = 中ボタン
+input-scroll-pans = スクロールで移動
+input-scroll-pans-hint = 2 本指のスクロールで表示を移動し、ピンチか Ctrl/Cmd + スクロールでズームします
+input-select-modifier = 選択のキー
+input-mouse-defaults = マウス向けの既定値
+input-trackpad-defaults = トラックパッド向けの既定値
+detail-minimap = 🗺 ミニマップ
+detail-layers = 🗂 レイヤー
+detail-show-boxes = ボックスを表示
+detail-show-all = すべて表示
+detail-hide-all = すべて非表示
+detail-label-opacity = ラベルの不透明度
+detail-mask-opacity = マスクの不透明度
+detail-attributes-in-labels = ラベルに属性を表示
+detail-layers-hint = 非表示のボックスも保存されます
+detail-tools = ツール
+detail-magic-select = 🪄 マジック選択 (M)
+detail-magic-select-hint = 物体をクリックすると、セグメンテーションサーバーからぴったりのボックスを取得します
+detail-magic-select-login = マジック選択を使うにはログインしてタスクを開いてください
+detail-mask = マスク
+detail-mask-off = オフ
+detail-mask-brush = ブラシ
+detail-mask-eraser = 消しゴム
+detail-brush-size = ブラシの大きさ
+detail-mask-hint = 選択したボックスに塗るか、ブラシで現在のクラスの新しいボックスを始めます
+detail-labels = ラベル
+detail-no-categories = このプロジェクトにはカテゴリがありません
+detail-labels-hint = ショートカットキーでラベルを切り替え、Enter で保存して次のタスクを開きます。F1 でキーを一覧できます
+detail-save-next = 💾 保存して次へ
+detail-pick-label = ラベルを 1 つ以上選んでください
+detail-labels-saved = { $count } 個のラベルを保存しました
+detail-flagged = 🚩 フラグ: { $reason }
+detail-clear-flag = 解除
+detail-flag-image = 🚩 画像にフラグを立てる
+detail-flag-note = メモ（任意）
+
+## ショートカット
+
+shortcuts-title = ⌨ ショートカット
+shortcuts-classes = クラス
+shortcuts-no-class-shortcuts = クラスのショートカットはありません
+shortcuts-edit-classes = ✏ クラスのショートカットを編集…
+shortcuts-editor-title = クラスのショートカットを編集
+shortcuts-editor-hint = キーをクリックしてから、カテゴリの新しいキーを押してください。Esc で取り消します。
+shortcuts-press-key = キーを押してください…
+shortcuts-remove = ショートカットを削除
+shortcuts-clear-all = すべてクリア
+shortcuts-clear-all-hint = ショートカットがないときは、数字キーで最初の 9 個のカテゴリを選びます
+shortcuts-modes = アノテーションのモード
+shortcuts-take-tour = ▶ ツアーを見る
+shortcut-cheat-sheet = この一覧を表示・非表示
+shortcut-undo = 元に戻す
+shortcut-redo = やり直す
+shortcut-delete = 選択したボックスを削除
+shortcut-nudge = 選択したボックスを 1 px 移動
+shortcut-nudge-far = 選択したボックスを 10 px 移動
+shortcut-toggle-selection = ボックスを選択に追加・解除
+shortcut-select-frame = 枠内のボックスを選択
+shortcut-cancel = 選択を解除して操作を取り消す
+shortcut-magic-select = マジック選択の切り替え
+shortcut-frames = 前 / 次の動画フレーム
+shortcut-propagate = 選択したボックスを次のフレームへ伝播
+shortcut-save-next = 保存して次のタスクを開く（分類）
+shortcut-zoom = ズーム
+shortcut-fit = 画像をウィンドウに合わせる
+shortcut-actual-size = 100% で表示
+shortcut-zoom-selection = 選択したボックスにズーム
+shortcut-pan = 移動
+shortcut-touch = タッチスクリーンで移動、ピンチでズーム
+mode-draw = 描画
+mode-draw-how = ボックスのない場所で画像をドラッグします
+mode-move = 移動
+mode-move-how = ボックスをドラッグします。選択中のボックスをドラッグするとすべて動きます
+mode-resize = サイズ変更
+mode-resize-how = ボックスの角のハンドルをドラッグします
+mode-rotate = 回転
+mode-rotate-how = 選択したボックスの上のハンドルをドラッグします
+mode-select = 選択
+mode-select-how = { $key } + ドラッグでボックスを枠で囲みます
+mode-magic-select = マジック選択
+mode-magic-select-how = M を押してから物体をクリックします
+mode-paint-mask = マスクの塗りつぶし
+mode-paint-mask-how = ツールウィンドウでブラシか消しゴムを選び、画像上をドラッグします
+
+## ツアー
+
+tour-step = { $step } / { $count }
+tour-back = 戻る
+tour-next = 次へ
+tour-done = 完了
+tour-skip = ツアーをスキップ
+tour-welcome-title = fast-tag へようこそ
+tour-welcome = アノテーションに必要な機能の場所を紹介します。1 分ほどで終わります。
+tour-toolbar-title = トップバー
+tour-toolbar = プロジェクト、設定、タスク、レポートをここで切り替えます。右端からログアウトできます。
+tour-classes-title = クラス
+tour-classes = 新しいボックスのクラスをここで選びます。名前の横のショートカットキーでも選べます。
+tour-drawing-title = ボックスの描画
+tour-drawing = 画像をドラッグするとボックスを描けます。ボックスをドラッグすると移動し、角をドラッグするとサイズが変わります。マウスホイールでズーム、右ドラッグで移動します。
+tour-save-title = 保存
+tour-save = ボックスはここで保存します。変更は数秒後に自動でも保存されます。
+tour-help-title = ショートカット
+tour-help = F1 を押すといつでもショートカットとアノテーションのモードを一覧できます。ツアーもそこからもう一度見られます。
+
+## コメント
+
+comments-title = コメント
+comments-login = コメントを見るにはログインしてタスクを開いてください
+comments-empty = コメントはまだありません
+comments-show-box = 📍 ボックスを表示
+comments-reply = 返信
+comments-reopen = ↺ 再開
+comments-resolve = ✔ 解決
+comments-box-gone = コメントされたボックスはこの画像にもうありません
+comments-replying = スレッドに返信中
+comments-hint = フィードバックを書いてください。@メールアドレスでメンションできます
+comments-attach = 📍 選択したボックスに紐付ける
+comments-post = 💬 投稿
+
+## プロフィール
+
+profile-title = プロフィール
+profile-unavailable = アカウントを読み込めませんでした。編集するには再度ログインしてください
+profile-avatar-upload = 🖼 画像をアップロード
+profile-avatar-remove = 画像を削除
+profile-avatar-hint = PNG・JPEG・WebP・GIF、2 MB まで
+profile-avatar-saved = プロフィール画像を更新しました
+profile-read-failed = 画像を読み込めませんでした: { $error }
+profile-name = 表示名:
+profile-saved = 表示名を保存しました
+profile-providers = ログインに使うアカウント
+profile-providers-hint = 別のアカウントを連携すると、そのアカウントでもこのアカウントにログインできます
+profile-linked-as = { $email } で連携済み
+profile-not-linked = 未連携
+profile-link = 🔗 連携
+profile-link-waiting = ブラウザでログインを完了してください…
+profile-link-timeout = 連携がタイムアウトしました。もう一度お試しください
+profile-linked = { $provider } アカウントを連携しました
+profile-unlink = 連携解除
+profile-unlink-last = 最後のアカウントを外す前に、別のアカウントを連携してください
+profile-unlinked = { $provider } アカウントの連携を解除しました
+profile-sessions = サインイン中のデバイス
+profile-sessions-hint = デバイスをサインアウトするとすぐにアクセスできなくなります。紛失したノート PC などに使います。
+profile-session-unknown-device = 不明なデバイス
+profile-session-last-used = 最終使用 { $time }
+profile-session-current = このデバイス
+profile-session-revoke = サインアウト
+profile-session-revoked = デバイスをサインアウトしました
+profile-delete-title = ⚠ アカウントを削除
+profile-delete-hint = アカウント、メンバーシップ、コメント、あなただけが参加しているプロジェクトは完全に削除されます。作成したアノテーションはプロジェクトに残ります。
+profile-delete-transfer = 他のメンバーがいるプロジェクトの新しいオーナーを選んでください:
+profile-delete-project = { $name } (他のメンバー { $members } 人)
+profile-delete-pick-owner = 新しいオーナー…
+profile-delete-confirm = 確認のため { $email } と入力してください:
+profile-delete = 🗑 アカウントを削除する
+profile-deleted = アカウントを削除しました
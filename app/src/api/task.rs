@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+use bevy::tasks::ConditionalSendFuture;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+use crate::platform::{self, Task};
+
+/// Runs API requests in the background and hands their results back as events. Add one plugin
+/// per result type, start requests through `ApiTasks<T>` and read `ApiTaskSucceeded<T>` and
+/// `ApiTaskFailed<T>` to pick up what came back. Pages cancel the requests they started with
+/// `ApiTasks::cancel_all` when they close, so results meant for them don't land on the next one.
+pub struct ApiTaskPlugin<T>(PhantomData<T>);
+
+impl<T> Default for ApiTaskPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Send + Sync + 'static> Plugin for ApiTaskPlugin<T> {
+    fn build(&self, app: &mut App) {
+        let (tx, rx) = channel::<(u64, Result<T, String>)>();
+
+        app
+            .insert_resource(ApiTasks {
+                sender: Mutex::new(tx),
+                receiver: Mutex::new(rx),
+                generation: AtomicU64::new(0),
+                running: Mutex::new(Vec::new()),
+            })
+            .add_event::<ApiTaskSucceeded<T>>()
+            .add_event::<ApiTaskFailed<T>>()
+            // Before Update, so results are readable in the frame they arrive
+            .add_systems(PreUpdate, process_api_tasks::<T>);
+    }
+}
+
+/// Starts requests whose results come back as `ApiTaskSucceeded<T>` or `ApiTaskFailed<T>`
+#[derive(Resource)]
+pub struct ApiTasks<T: Send + 'static> {
+    /// Results tagged with the generation their request was started in
+    sender: Mutex<Sender<(u64, Result<T, String>)>>,
+    receiver: Mutex<Receiver<(u64, Result<T, String>)>>,
+    /// Bumped by every `cancel_all`, results of older generations are dropped unread
+    generation: AtomicU64,
+    running: Mutex<Vec<Task>>,
+}
+
+impl<T: Send + 'static> ApiTasks<T> {
+    /// Runs `task` in the background, see `platform::spawn`. Blocking work inside it, like a
+    /// file dialog, belongs in `platform::unblock`.
+    pub fn spawn(&self, task: impl ConditionalSendFuture<Output = Result<T, String>> + 'static) {
+        let Ok(tx) = self.sender.lock() else {
+            return;
+        };
+        let tx = tx.clone();
+        let generation = self.generation.load(Ordering::Relaxed);
+
+        let handle = platform::spawn(async move {
+            let _ = tx.send((generation, task.await));
+        });
+        if let Ok(mut running) = self.running.lock() {
+            running.retain(|task| !task.is_finished());
+            running.push(handle);
+        }
+    }
+
+    /// Aborts the requests still running, so their downloads stop where they are, and drops
+    /// the results that came back but haven't been read yet. Requests that change data on the
+    /// server are better left to finish, this is for the ones that only load something.
+    pub fn cancel_all(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut running) = self.running.lock() {
+            for handle in running.drain(..) {
+                handle.abort();
+            }
+        }
+    }
+}
+
+#[derive(Event)]
+pub struct ApiTaskSucceeded<T: Send + Sync + 'static>(pub T);
+
+#[derive(Event)]
+pub struct ApiTaskFailed<T: Send + Sync + 'static> {
+    pub error: String,
+    _result: PhantomData<fn() -> T>,
+}
+
+fn process_api_tasks<T: Send + Sync + 'static>(
+    tasks: Res<ApiTasks<T>>,
+    mut succeeded_events: EventWriter<ApiTaskSucceeded<T>>,
+    mut failed_events: EventWriter<ApiTaskFailed<T>>,
+) {
+    let Ok(rx) = tasks.receiver.lock() else {
+        return;
+    };
+    let generation = tasks.generation.load(Ordering::Relaxed);
+    while let Ok((result_generation, result)) = rx.try_recv() {
+        // Started before the last `cancel_all`
+        if result_generation != generation {
+            continue;
+        }
+        match result {
+            Ok(value) => {
+                succeeded_events.write(ApiTaskSucceeded(value));
+            }
+            Err(error) => {
+                failed_events.write(ApiTaskFailed { error, _result: PhantomData });
+            }
+        }
+    }
+}
@@ -8,4 +8,9 @@ pub enum AppState {
     Projects,
     ProjectSettings,
     Detail,
+    Reports,
+    Review,
+    Stats,
+    StorageBrowser,
+    Profile,
 }
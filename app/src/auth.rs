@@ -1,5 +1,6 @@
 use bevy::prelude::*;
-use crate::api::{auth::AuthApi, projects::ProjectsApi, tasks::TasksApi};
+use crate::api::{auth::AuthApi, projects::{ProjectsApi, CloneProjectRequest, CloneProjectResponse}, tasks::TasksApi, templates::{TemplatesApi, ProjectTemplate}};
+use crate::io::offline_store;
 
 #[derive(Resource, Default)]
 pub struct AuthState {
@@ -15,7 +16,6 @@ impl AuthState {
         self.jwt = Some(jwt);
     }
     
-    #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.jwt = None;
     }
@@ -36,7 +36,6 @@ pub struct UserState {
 }
 
 impl UserState {
-    #[allow(dead_code)]
     pub fn set_user(&mut self, user: User) {
         self.user = Some(user);
         self.fetch_error = None;
@@ -54,7 +53,6 @@ impl UserState {
         self.fetch_error = None;
     }
     
-    #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.user = None;
         self.fetch_error = None;
@@ -109,12 +107,18 @@ impl ProjectsState {
 
 pub async fn fetch_projects(jwt: &str) -> Result<Vec<Project>, String> {
     let projects_api = ProjectsApi::new();
-    projects_api.list_projects(jwt).await.map_err(|e| e.to_string())
+    let result = projects_api.list_projects_with_archived(jwt).await;
+    offline_store::store().projects(result).map_err(|e| e.to_string())
 }
 
-pub async fn create_project(jwt: &str, name: &str, description: Option<&str>) -> Result<Project, String> {
+pub async fn create_project(jwt: &str, name: &str, description: Option<&str>, template_id: Option<&str>, task_type: Option<&str>) -> Result<Project, String> {
     let projects_api = ProjectsApi::new();
-    projects_api.create_project(jwt, name, description).await.map_err(|e| e.to_string())
+    projects_api.create_project(jwt, name, description, template_id, task_type).await.map_err(|e| e.to_string())
+}
+
+pub async fn fetch_templates(jwt: &str) -> Result<Vec<ProjectTemplate>, String> {
+    let templates_api = TemplatesApi::new();
+    templates_api.list_templates(jwt).await.map_err(|e| e.to_string())
 }
 
 pub async fn update_project(jwt: &str, project_id: &str, name: &str, description: Option<&str>) -> Result<Project, String> {
@@ -127,6 +131,23 @@ pub async fn delete_project(jwt: &str, project_id: &str) -> Result<(), String> {
     projects_api.delete_project(jwt, project_id).await.map_err(|e| e.to_string())
 }
 
+pub async fn clone_project(jwt: &str, project_id: &str, request: &CloneProjectRequest) -> Result<CloneProjectResponse, String> {
+    let projects_api = ProjectsApi::new();
+    projects_api.clone_project(jwt, project_id, request).await.map_err(|e| e.to_string())
+}
+
+/// Archives or unarchives the project, answering when it was archived
+pub async fn set_project_archived(jwt: &str, project_id: &str, archived: bool) -> Result<Option<String>, String> {
+    let projects_api = ProjectsApi::new();
+    if archived {
+        let project = projects_api.archive_project(jwt, project_id).await.map_err(|e| e.to_string())?;
+        Ok(project.archived_at)
+    } else {
+        projects_api.unarchive_project(jwt, project_id).await.map_err(|e| e.to_string())?;
+        Ok(None)
+    }
+}
+
 pub async fn update_project_storage_config(jwt: &str, project_id: &str, storage_config: serde_json::Value) -> Result<Project, String> {
     let projects_api = ProjectsApi::new();
     projects_api.update_storage_config(jwt, project_id, storage_config).await.map_err(|e| e.to_string())
@@ -1,7 +1,15 @@
 use bevy::prelude::*;
 use bevy::input::ButtonState;
-use bevy::input::mouse::{MouseButtonInput, MouseWheel};
+use bevy::input::gestures::PinchGesture;
+use bevy::input::mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel};
+use bevy::input::touch::Touches;
 use bevy::window::PrimaryWindow;
+use crate::core::input_settings::InputSettings;
+
+/// Zoom change per wheel step at a zoom sensitivity of 1
+const ZOOM_PER_STEP: f32 = 0.001;
+/// Screen pixels a wheel line scrolls when scrolling pans
+const PIXELS_PER_LINE: f32 = 20.0;
 
 pub struct CameraController {
     pub zoom_level: f32,
@@ -10,6 +18,8 @@ pub struct CameraController {
     pub is_panning: bool,
     pub panning_start_screen_position: Option<Vec2>,
     pub camera_start_position: Option<Vec3>,
+    /// Distance between and midpoint of two fingers on the screen at the last frame of a gesture
+    pub touch_gesture: Option<(f32, Vec2)>,
 }
 
 impl Default for CameraController {
@@ -21,31 +31,56 @@ impl Default for CameraController {
             is_panning: false,
             panning_start_screen_position: None,
             camera_start_position: None,
+            touch_gesture: None,
         }
     }
 }
 
 impl CameraController {
+    /// Zooms with the wheel, or pans with it when `settings` say scrolling pans. Pinching always
+    /// zooms, and so does scrolling while `zoom_modifier` is held.
     pub fn process_zoom(
         &mut self,
         mouse_wheel_events: &mut EventReader<MouseWheel>,
+        pinch_events: &mut EventReader<PinchGesture>,
         cameras: &mut Query<&mut Transform, With<Camera>>,
+        settings: &InputSettings,
+        zoom_modifier: bool,
         egui_input_use: bool,
     ) {
         if egui_input_use {
+            mouse_wheel_events.clear();
+            pinch_events.clear();
             return;
         }
 
         for event in mouse_wheel_events.read() {
-            let zoom_delta = event.y * 0.001;
-            let new_zoom = (self.zoom_level + zoom_delta).clamp(self.min_zoom, self.max_zoom);
-
-            if new_zoom != self.zoom_level {
-                self.zoom_level = new_zoom;
-
+            if settings.scroll_pans && !zoom_modifier {
+                let scroll = match event.unit {
+                    MouseScrollUnit::Line => Vec2::new(event.x, event.y) * PIXELS_PER_LINE,
+                    MouseScrollUnit::Pixel => Vec2::new(event.x, event.y),
+                };
                 if let Ok(mut camera_transform) = cameras.single_mut() {
-                    camera_transform.scale = Vec3::splat(1.0 / self.zoom_level);
+                    camera_transform.translation.x -= scroll.x / self.zoom_level;
+                    camera_transform.translation.y += scroll.y / self.zoom_level;
                 }
+                continue;
+            }
+            self.zoom_to(self.zoom_level + event.y * ZOOM_PER_STEP * settings.zoom_sensitivity, cameras);
+        }
+
+        for event in pinch_events.read() {
+            self.zoom_to(self.zoom_level * (1.0 + event.0 * settings.zoom_sensitivity), cameras);
+        }
+    }
+
+    fn zoom_to(&mut self, zoom: f32, cameras: &mut Query<&mut Transform, With<Camera>>) {
+        let new_zoom = zoom.clamp(self.min_zoom, self.max_zoom);
+        if new_zoom != self.zoom_level {
+            self.zoom_level = new_zoom;
+
+            if let Ok(mut camera_transform) = cameras.single_mut() {
+                camera_transform.scale = Vec3::splat(1.0 / self.zoom_level);
             }
         }
     }
@@ -55,6 +90,7 @@ impl CameraController {
         mouse_button_events: &[MouseButtonInput],
         cameras: &mut Query<&mut Transform, With<Camera>>,
         q_window: Query<&Window, With<PrimaryWindow>>,
+        pan_button: MouseButton,
         egui_input_use: bool,
     ) {
         if egui_input_use {
@@ -65,7 +101,7 @@ impl CameraController {
         let current_screen_pos = window.cursor_position();
 
         for event in mouse_button_events.iter() {
-            if event.button == MouseButton::Right {
+            if event.button == pan_button {
                 match event.state {
                     ButtonState::Pressed => {
                         if !self.is_panning {
@@ -101,6 +137,63 @@ impl CameraController {
         }
     }
 
+    /// Pans the view with two fingers on a touchscreen and zooms it by pinching them
+    pub fn process_touch_gesture(
+        &mut self,
+        touches: &Touches,
+        cameras: &mut Query<&mut Transform, With<Camera>>,
+    ) {
+        let fingers: Vec<Vec2> = touches.iter().map(|touch| touch.position()).take(3).collect();
+        let [first, second] = fingers[..] else {
+            self.touch_gesture = None;
+            return;
+        };
+        let distance = first.distance(second);
+        let midpoint = (first + second) / 2.0;
+
+        if let Some((last_distance, last_midpoint)) = self.touch_gesture {
+            if let Ok(mut camera_transform) = cameras.single_mut() {
+                // Screen y grows downwards, world y upwards
+                let screen_delta = midpoint - last_midpoint;
+                camera_transform.translation.x -= screen_delta.x / self.zoom_level;
+                camera_transform.translation.y += screen_delta.y / self.zoom_level;
+            }
+            if last_distance > 0.0 {
+                let zoom = (self.zoom_level * distance / last_distance).clamp(self.min_zoom, self.max_zoom);
+                if zoom != self.zoom_level {
+                    self.zoom_level = zoom;
+                    if let Ok(mut camera_transform) = cameras.single_mut() {
+                        camera_transform.scale = Vec3::splat(1.0 / self.zoom_level);
+                    }
+                }
+            }
+        }
+        self.touch_gesture = Some((distance, midpoint));
+    }
+
+    /// Zoom at which `size` world units take up `fraction` of the window in both directions
+    pub fn zoom_to_fit(&self, window_size: Vec2, size: Vec2, fraction: f32) -> f32 {
+        let zoom = (window_size * fraction / size.max(Vec2::ONE)).min_element();
+        zoom.clamp(self.min_zoom, self.max_zoom)
+    }
+
+    /// Centers the camera on `center` at `zoom`
+    pub fn look_at(
+        &mut self,
+        center: Vec2,
+        zoom: f32,
+        cameras: &mut Query<&mut Transform, With<Camera>>,
+    ) {
+        self.zoom_level = zoom.clamp(self.min_zoom, self.max_zoom);
+        self.reset_panning();
+
+        if let Ok(mut camera_transform) = cameras.single_mut() {
+            camera_transform.translation.x = center.x;
+            camera_transform.translation.y = center.y;
+            camera_transform.scale = Vec3::splat(1.0 / self.zoom_level);
+        }
+    }
+
     pub fn reset_panning(&mut self) {
         self.is_panning = false;
         self.panning_start_screen_position = None;
@@ -8,6 +8,8 @@ pub enum Command {
     DeleteRectangle { index: usize, rectangle: Rectangle },
     MoveRectangle { index: usize, old_position: (f32, f32), new_position: (f32, f32) },
     ResizeRectangle { index: usize, old_rect: Rectangle, new_rect: Rectangle },
+    /// Several commands undone and redone as one step, such as a group move
+    Batch { commands: Vec<Command> },
 }
 
 impl Command {
@@ -34,6 +36,11 @@ impl Command {
                     *rect = new_rect.clone();
                 }
             }
+            Command::Batch { commands } => {
+                for command in commands {
+                    command.execute(rectangles);
+                }
+            }
         }
     }
 
@@ -58,6 +65,11 @@ impl Command {
                     *rect = old_rect.clone();
                 }
             }
+            Command::Batch { commands } => {
+                for command in commands.iter().rev() {
+                    command.undo(rectangles);
+                }
+            }
         }
     }
 }
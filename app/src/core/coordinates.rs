@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use bevy::math::DVec2;
+use bevy_egui::egui;
+use crate::core::rectangle::Rectangle;
+
+/// Saved coordinates keep this many steps per image pixel. Finer than any zoom can place a
+/// point, and coarse enough to drop the noise of the f32 world coordinates.
+const SUBPIXEL_STEPS: f64 = 1000.0;
+
+/// Image pixels from the top left corner with y down, as the server stores them, of a world
+/// point. World space has the image centered on the origin with y up.
+pub fn world_to_image(point: Vec2, image_dimensions: Vec2) -> Vec2 {
+    Vec2::new(point.x + image_dimensions.x / 2.0, image_dimensions.y / 2.0 - point.y)
+}
+
+/// Whether an image space point lies on the image
+pub fn is_on_image(point: Vec2, image_dimensions: Vec2) -> bool {
+    point.cmpge(Vec2::ZERO).all() && point.cmple(image_dimensions).all()
+}
+
+/// Converts a COCO `[x, y, width, height]` box in image pixels to a world space rectangle. The
+/// sums are done in f64, so the box edges land on the nearest f32 instead of collecting the
+/// rounding of every step.
+pub fn bbox_to_rectangle(bbox: &[f64; 4], class: usize, image_dimensions: Vec2) -> Rectangle {
+    let half = image_dimensions.as_dvec2() / 2.0;
+    let start = DVec2::new(bbox[0] - half.x, half.y - (bbox[1] + bbox[3]));
+    let end = DVec2::new(bbox[0] + bbox[2] - half.x, half.y - bbox[1]);
+    Rectangle::new(class, start.as_vec2(), end.as_vec2())
+}
+
+/// Converts a world space rectangle to a COCO `[x, y, width, height]` box in image pixels,
+/// rounded to `SUBPIXEL_STEPS`. Rotation is left out, as in the COCO export.
+pub fn rectangle_to_bbox(rect: &Rectangle, image_dimensions: Vec2) -> [f64; 4] {
+    let (pos1, pos2) = rect.position;
+    let half = image_dimensions.as_dvec2() / 2.0;
+    let (min, max) = (pos1.min(pos2).as_dvec2(), pos1.max(pos2).as_dvec2());
+    [
+        subpixel(min.x + half.x),
+        subpixel(half.y - max.y),
+        subpixel(max.x - min.x),
+        subpixel(max.y - min.y),
+    ]
+}
+
+/// Rounds an image space value to `SUBPIXEL_STEPS`
+pub fn subpixel(value: f64) -> f64 {
+    (value * SUBPIXEL_STEPS).round() / SUBPIXEL_STEPS
+}
+
+/// Window positions from Bevy, such as the cursor and touches, are in logical pixels, egui
+/// positions in points. The two only agree while egui has no scale of its own on top of the
+/// window scale factor.
+pub fn window_to_egui(position: Vec2, window: &Window, ctx: &egui::Context) -> egui::Pos2 {
+    let scale = window.scale_factor() / ctx.pixels_per_point();
+    egui::pos2(position.x * scale, position.y * scale)
+}
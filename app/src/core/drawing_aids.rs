@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use crate::core::rectangle::Rectangle;
+
+/// Distance in screen pixels within which the cursor snaps to an edge
+pub const SNAP_DISTANCE: f32 = 8.0;
+
+/// Helpers for placing box corners precisely, kept from task to task
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct DrawingAids {
+    /// Lines through the cursor across the whole view
+    pub crosshair: bool,
+    /// Pull new corners onto nearby box edges and image borders
+    pub snapping: bool,
+    /// Magnified view of the image around the cursor
+    pub loupe: bool,
+}
+
+impl Default for DrawingAids {
+    fn default() -> Self {
+        Self {
+            crosshair: true,
+            snapping: false,
+            loupe: false,
+        }
+    }
+}
+
+/// Cursor position after snapping, with the edges it snapped to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Snap {
+    pub point: Vec2,
+    /// X of the vertical edge the point snapped to
+    pub x: Option<f32>,
+    /// Y of the horizontal edge the point snapped to
+    pub y: Option<f32>,
+}
+
+/// Moves `point` onto the closest vertical and horizontal edge within `max_distance`, looking
+/// at the image borders and the bounds of `rectangles`. Each axis snaps on its own, so a corner
+/// can line up with one box horizontally and another vertically.
+pub fn snap_point(point: Vec2, rectangles: &[&Rectangle], image_bounds: Rect, max_distance: f32) -> Snap {
+    let mut xs = vec![image_bounds.min.x, image_bounds.max.x];
+    let mut ys = vec![image_bounds.min.y, image_bounds.max.y];
+    for rect in rectangles {
+        let bounds = rect.world_bounds();
+        xs.extend([bounds.min.x, bounds.max.x]);
+        ys.extend([bounds.min.y, bounds.max.y]);
+    }
+
+    let closest = |edges: &[f32], value: f32| {
+        edges
+            .iter()
+            .copied()
+            .filter(|edge| (edge - value).abs() <= max_distance)
+            .min_by(|a, b| (a - value).abs().total_cmp(&(b - value).abs()))
+    };
+    let x = closest(&xs, point.x);
+    let y = closest(&ys, point.y);
+
+    Snap {
+        point: Vec2::new(x.unwrap_or(point.x), y.unwrap_or(point.y)),
+        x,
+        y,
+    }
+}
@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::io::preferences::Preferences;
+
+/// Range of the grab radius in image pixels and of the zoom sensitivity multiplier
+pub const GRAB_RADII: std::ops::RangeInclusive<f32> = 2.0..=20.0;
+pub const ZOOM_SENSITIVITIES: std::ops::RangeInclusive<f32> = 0.1..=5.0;
+
+/// Mouse button that drags the view around
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PanButton {
+    #[default]
+    Right,
+    Middle,
+}
+
+impl PanButton {
+    pub const ALL: [PanButton; 2] = [PanButton::Right, PanButton::Middle];
+
+    pub fn mouse_button(self) -> MouseButton {
+        match self {
+            PanButton::Right => MouseButton::Right,
+            PanButton::Middle => MouseButton::Middle,
+        }
+    }
+
+    /// Name of the button for the cheat sheet, which names keys in English
+    pub fn name(self) -> &'static str {
+        match self {
+            PanButton::Right => "Right",
+            PanButton::Middle => "Middle",
+        }
+    }
+
+    pub fn label_key(self) -> &'static str {
+        match self {
+            PanButton::Right => "input-pan-right",
+            PanButton::Middle => "input-pan-middle",
+        }
+    }
+}
+
+/// Key held to shift-click boxes into the selection and to drag a selection frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SelectModifier {
+    #[default]
+    Shift,
+    Alt,
+    Control,
+}
+
+impl SelectModifier {
+    pub const ALL: [SelectModifier; 3] = [SelectModifier::Shift, SelectModifier::Alt, SelectModifier::Control];
+
+    pub fn is_pressed(self, keyboard: &ButtonInput<KeyCode>) -> bool {
+        let (left, right) = match self {
+            SelectModifier::Shift => (KeyCode::ShiftLeft, KeyCode::ShiftRight),
+            SelectModifier::Alt => (KeyCode::AltLeft, KeyCode::AltRight),
+            SelectModifier::Control => (KeyCode::ControlLeft, KeyCode::ControlRight),
+        };
+        keyboard.pressed(left) || keyboard.pressed(right)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SelectModifier::Shift => "Shift",
+            SelectModifier::Alt => "Alt",
+            SelectModifier::Control => "Ctrl",
+        }
+    }
+}
+
+/// How the mouse and trackpad drive the editor, kept in the preferences. Mice get the wheel to
+/// zoom and the right button to pan, trackpads two-finger scrolling to pan and pinching to zoom.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputSettings {
+    /// Distance in image pixels within which an edge or a corner of a box can be grabbed
+    pub grab_radius: f32,
+    pub pan_button: PanButton,
+    /// Multiplier of how far a wheel step or a pinch zooms
+    pub zoom_sensitivity: f32,
+    /// Scrolling pans the view, zooming takes a pinch or Ctrl/Cmd + scroll
+    pub scroll_pans: bool,
+    pub select_modifier: SelectModifier,
+}
+
+impl Default for InputSettings {
+    fn default() -> Self {
+        Self {
+            grab_radius: 5.0,
+            pan_button: PanButton::Right,
+            zoom_sensitivity: 1.0,
+            scroll_pans: false,
+            select_modifier: SelectModifier::Shift,
+        }
+    }
+}
+
+impl InputSettings {
+    /// Larger targets for the less precise pointer, and no need for a second button to pan
+    pub fn trackpad() -> Self {
+        Self {
+            grab_radius: 8.0,
+            zoom_sensitivity: 2.0,
+            scroll_pans: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn load() -> Self {
+        Preferences::load().input
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let mut preferences = Preferences::load();
+        preferences.input = self.clone();
+        preferences.save()
+    }
+
+    /// Keys of a fixed shortcut of the cheat sheet as they are with these settings
+    pub fn shortcut_keys(&self, keys: &str) -> String {
+        match keys {
+            "Shift + Click" | "Shift + Drag" => keys.replacen("Shift", self.select_modifier.name(), 1),
+            "Right drag" if self.scroll_pans => format!("{} drag, scroll", self.pan_button.name()),
+            "Right drag" => format!("{} drag", self.pan_button.name()),
+            "Mouse wheel" if self.scroll_pans => "Pinch, Ctrl/Cmd + scroll".to_string(),
+            keys => keys.to_string(),
+        }
+    }
+}
@@ -2,7 +2,9 @@ use bevy::prelude::*;
 use bevy::input::ButtonState;
 use bevy::input::mouse::MouseButtonInput;
 use bevy_egui::EguiContexts;
+use crate::core::layers::LayerState;
 use crate::core::rectangle::{Rectangle, Corner};
+use crate::core::spatial_index::SpatialIndex;
 use crate::core::commands::{Command, CommandHistory};
 
 #[derive(PartialEq, Default)]
@@ -10,8 +12,97 @@ pub enum InteractionMode {
     #[default]
     Default,
     Resizing,
+    Rotating,
     Drawing,
     Grabbing,
+    /// Shift-dragging a frame around the boxes to select
+    Selecting,
+    /// Dragging every box of a multi-box selection
+    GroupMoving,
+}
+
+#[derive(Default)]
+pub struct RotatingHandler {
+    pub rectangle_index: Option<usize>,
+    pub original_rect: Option<Rectangle>,
+}
+
+impl RotatingHandler {
+
+    #[allow(clippy::too_many_arguments, clippy::ptr_arg)]
+    pub fn process(
+        &mut self,
+        rectangles: &mut Vec<Rectangle>,
+        cursor_position: Option<Vec2>,
+        mouse_events: &[MouseButtonInput],
+        mode: &mut InteractionMode,
+        selected_index: &mut Option<usize>,
+        egui_contexts: &mut EguiContexts,
+        command_history: &mut CommandHistory,
+    ) {
+        const MARGIN: f32 = 6.0;
+        let ctx = egui_contexts.ctx_mut();
+
+        // Only the selected rectangle shows a rotation handle
+        if *mode == InteractionMode::Default {
+            let hovering_index = selected_index.filter(|&index| {
+                match (rectangles.get(index), cursor_position) {
+                    (Some(rect), Some(pos)) => (pos - rect.rotation_handle()).length() <= MARGIN,
+                    _ => false,
+                }
+            });
+
+            if hovering_index.is_some() {
+                ctx.set_cursor_icon(bevy_egui::egui::CursorIcon::Grab);
+
+                for event in mouse_events.iter() {
+                    if event.button == MouseButton::Left && event.state == ButtonState::Pressed {
+                        self.rectangle_index = hovering_index;
+                        if let Some(idx) = hovering_index {
+                            self.original_rect = rectangles.get(idx).cloned();
+                        }
+                        *mode = InteractionMode::Rotating;
+                    }
+                }
+            }
+        }
+
+        if *mode == InteractionMode::Rotating {
+            if let (Some(pos), Some(rect_idx)) = (cursor_position, self.rectangle_index) {
+                if let Some(rectangle) = rectangles.get_mut(rect_idx) {
+                    rectangle.rotate_towards(pos);
+                }
+            }
+            ctx.set_cursor_icon(bevy_egui::egui::CursorIcon::Grabbing);
+
+            for event in mouse_events.iter() {
+                if event.button == MouseButton::Left && event.state == ButtonState::Released {
+                    if let Some(rect_idx) = self.rectangle_index {
+                        *selected_index = Some(rect_idx);
+
+                        // Rotation only changes the rectangle in place, so it is recorded like a resize
+                        if let (Some(old_rect), Some(new_rect)) = (self.original_rect.as_ref(), rectangles.get(rect_idx)) {
+                            if old_rect.rotation != new_rect.rotation {
+                                let command = Command::ResizeRectangle {
+                                    index: rect_idx,
+                                    old_rect: old_rect.clone(),
+                                    new_rect: new_rect.clone(),
+                                };
+                                command_history.push(command);
+                            }
+                        }
+                    }
+                    self.clear();
+                    *mode = InteractionMode::Default;
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.rectangle_index = None;
+        self.original_rect = None;
+    }
 }
 
 #[derive(Default)]
@@ -27,6 +118,9 @@ impl ResizingHandler {
     pub fn process(
         &mut self,
         rectangles: &mut Vec<Rectangle>,
+        layers: &LayerState,
+        spatial_index: &SpatialIndex,
+        grab_radius: f32,
         cursor_position: Option<Vec2>,
         mouse_events: &[MouseButtonInput],
         mode: &mut InteractionMode,
@@ -34,16 +128,18 @@ impl ResizingHandler {
         egui_contexts: &mut EguiContexts,
         command_history: &mut CommandHistory,
     ) {
-        const MARGIN: f32 = 5.0;
         let ctx = egui_contexts.ctx_mut();
 
         if *mode == InteractionMode::Default {
             let mut hovering_index = None;
             let mut corner_option = None;
 
-            for (index, rect) in rectangles.iter().enumerate() {
-                if let Some(pos) = cursor_position {
-                    if let Some(corner) = rect.get_corner_at_point(pos, MARGIN) {
+            if let Some(pos) = cursor_position {
+                for index in spatial_index.near(pos, grab_radius) {
+                    let Some(rect) = rectangles.get(index).filter(|rect| layers.is_visible(rect)) else {
+                        continue;
+                    };
+                    if let Some(corner) = rect.get_corner_at_point(pos, grab_radius) {
                         hovering_index = Some(index);
                         corner_option = Some(corner);
                         break;
@@ -120,6 +216,9 @@ impl GrabbingHandler {
     pub fn process(
         &mut self,
         rectangles: &mut Vec<Rectangle>,
+        layers: &LayerState,
+        spatial_index: &SpatialIndex,
+        grab_radius: f32,
         cursor_position: Option<Vec2>,
         mouse_events: &[MouseButtonInput],
         mode: &mut InteractionMode,
@@ -127,19 +226,15 @@ impl GrabbingHandler {
         egui_contexts: &mut EguiContexts,
         command_history: &mut CommandHistory,
     ) {
-        const MARGIN: f32 = 5.0;
         let ctx = egui_contexts.ctx_mut();
 
         if *mode == InteractionMode::Default {
             let mut hovering_index = None;
 
-            for (index, rect) in rectangles.iter().enumerate() {
-                if let Some(pos) = cursor_position {
-                    if rect.contains_point(pos, MARGIN) {
-                        hovering_index = Some(index);
-                        break;
-                    }
-                }
+            if let Some(pos) = cursor_position {
+                hovering_index = spatial_index.near(pos, grab_radius)
+                    .into_iter()
+                    .find(|&index| rectangles.get(index).is_some_and(|rect| layers.is_visible(rect) && rect.contains_point(pos, grab_radius)));
             }
 
             if hovering_index.is_some() {
@@ -203,6 +298,255 @@ impl GrabbingHandler {
     }
 }
 
+/// Boxes selected together with shift-click or a shift-drag frame, which move, get deleted
+/// and change class as one. The primary selection in `selected_index` is always a member, and
+/// the group goes away as soon as the primary selection changes to a box outside of it.
+#[derive(Default)]
+pub struct GroupHandler {
+    pub indices: Vec<usize>,
+    pub frame_start: Option<Vec2>,
+    pub last_position: Option<Vec2>,
+    pub original_centers: Vec<(usize, Vec2)>,
+    pub pressed_index: Option<usize>,
+}
+
+impl GroupHandler {
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn process(
+        &mut self,
+        rectangles: &mut [Rectangle],
+        layers: &LayerState,
+        spatial_index: &SpatialIndex,
+        grab_radius: f32,
+        cursor_position: Option<Vec2>,
+        mouse_events: &[MouseButtonInput],
+        shift_pressed: bool,
+        mode: &mut InteractionMode,
+        selected_index: &mut Option<usize>,
+        egui_input_use: bool,
+        egui_contexts: &mut EguiContexts,
+        gizmos: &mut Gizmos,
+        command_history: &mut CommandHistory,
+    ) {
+        const ROTATION_HANDLE_MARGIN: f32 = 6.0;
+        const CLICK_DISTANCE: f32 = 3.0;
+        let ctx = egui_contexts.ctx_mut();
+
+        self.indices.retain(|&index| index < rectangles.len());
+        if selected_index.is_none_or(|index| !self.indices.contains(&index)) {
+            self.indices.clear();
+        }
+
+        if *mode == InteractionMode::Default && !egui_input_use {
+            if let Some(pos) = cursor_position {
+                let nearby: Vec<usize> = spatial_index.near(pos, grab_radius)
+                    .into_iter()
+                    .filter(|&index| rectangles.get(index).is_some_and(|rect| layers.is_visible(rect)))
+                    .collect();
+                let hovering_index = nearby.iter()
+                    .copied()
+                    .find(|&index| rectangles[index].contains_point(pos, grab_radius));
+                // Corners and the rotation handle keep resizing and rotating a single box
+                let on_handle = nearby.iter()
+                    .any(|&index| rectangles[index].get_corner_at_point(pos, grab_radius).is_some())
+                    || selected_index
+                        .and_then(|index| rectangles.get(index))
+                        .is_some_and(|rect| (pos - rect.rotation_handle()).length() <= ROTATION_HANDLE_MARGIN);
+
+                for event in mouse_events.iter() {
+                    if event.button != MouseButton::Left || event.state != ButtonState::Pressed {
+                        continue;
+                    }
+                    if shift_pressed {
+                        self.frame_start = Some(pos);
+                        *mode = InteractionMode::Selecting;
+                    } else if self.indices.len() > 1
+                        && !on_handle
+                        && hovering_index.is_some_and(|index| self.indices.contains(&index))
+                    {
+                        self.last_position = Some(pos);
+                        self.pressed_index = hovering_index;
+                        self.original_centers = self.indices.iter()
+                            .map(|&index| (index, rectangles[index].center()))
+                            .collect();
+                        *mode = InteractionMode::GroupMoving;
+                    }
+                }
+            }
+        }
+
+        if *mode == InteractionMode::Selecting {
+            if let (Some(start), Some(end)) = (self.frame_start, cursor_position) {
+                gizmos.rect_2d((start + end) / 2.0, end - start, Color::WHITE);
+
+                for event in mouse_events.iter() {
+                    if event.button == MouseButton::Left && event.state == ButtonState::Released {
+                        if (end - start).length() < CLICK_DISTANCE {
+                            let clicked = spatial_index.near(end, grab_radius)
+                                .into_iter()
+                                .find(|&index| rectangles.get(index).is_some_and(|rect| layers.is_visible(rect) && rect.contains_point(end, grab_radius)));
+                            if let Some(index) = clicked {
+                                self.toggle(index, selected_index);
+                            }
+                        } else {
+                            let frame = Rect::from_corners(start, end);
+                            let inside: Vec<usize> = spatial_index.query(frame)
+                                .into_iter()
+                                .filter(|&index| {
+                                    rectangles.get(index).is_some_and(|rect| {
+                                        layers.is_visible(rect) && corners(rect).iter().all(|corner| frame.contains(*corner))
+                                    })
+                                })
+                                .collect();
+                            self.add(&inside, selected_index);
+                        }
+                        self.frame_start = None;
+                        *mode = InteractionMode::Default;
+                    }
+                }
+            }
+        }
+
+        if *mode == InteractionMode::GroupMoving {
+            if let (Some(last_pos), Some(current_pos)) = (self.last_position, cursor_position) {
+                let delta = current_pos - last_pos;
+                for &index in &self.indices {
+                    if let Some(rectangle) = rectangles.get_mut(index) {
+                        rectangle.move_by(delta);
+                    }
+                }
+                self.last_position = Some(current_pos);
+            }
+            ctx.set_cursor_icon(bevy_egui::egui::CursorIcon::Grabbing);
+
+            for event in mouse_events.iter() {
+                if event.button == MouseButton::Left && event.state == ButtonState::Released {
+                    let moves: Vec<Command> = self.original_centers.iter()
+                        .filter_map(|&(index, old_center)| {
+                            let new_center = rectangles.get(index)?.center();
+                            (old_center != new_center).then_some(Command::MoveRectangle {
+                                index,
+                                old_position: (old_center.x, old_center.y),
+                                new_position: (new_center.x, new_center.y),
+                            })
+                        })
+                        .collect();
+
+                    if moves.is_empty() {
+                        // A click without dragging narrows the selection down to the clicked box
+                        *selected_index = self.pressed_index;
+                        self.indices.clear();
+                    } else {
+                        command_history.push(Command::Batch { commands: moves });
+                    }
+                    self.last_position = None;
+                    self.pressed_index = None;
+                    self.original_centers.clear();
+                    *mode = InteractionMode::Default;
+                }
+            }
+        }
+    }
+
+    /// Adds `index` to the selection or takes it out, keeping the primary selection a member.
+    pub fn toggle(&mut self, index: usize, selected_index: &mut Option<usize>) {
+        if self.indices.is_empty() {
+            self.indices.extend(*selected_index);
+        }
+        if let Some(position) = self.indices.iter().position(|&member| member == index) {
+            self.indices.remove(position);
+        } else {
+            self.indices.push(index);
+        }
+        *selected_index = self.indices.last().copied();
+    }
+
+    pub fn add(&mut self, indices: &[usize], selected_index: &mut Option<usize>) {
+        if self.indices.is_empty() {
+            self.indices.extend(*selected_index);
+        }
+        for &index in indices {
+            if !self.indices.contains(&index) {
+                self.indices.push(index);
+            }
+        }
+        *selected_index = selected_index.or(self.indices.first().copied());
+    }
+
+    /// Boxes the group operations apply to: the group, or the primary selection on its own.
+    pub fn targets(&self, selected_index: Option<usize>) -> Vec<usize> {
+        if self.indices.len() > 1 {
+            self.indices.clone()
+        } else {
+            selected_index.into_iter().collect()
+        }
+    }
+
+    /// Deletes every selected box as one undo step.
+    pub fn delete(&mut self, rectangles: &mut Vec<Rectangle>, selected_index: &mut Option<usize>, command_history: &mut CommandHistory) {
+        let mut indices = self.targets(*selected_index);
+        indices.retain(|&index| index < rectangles.len());
+        if indices.is_empty() {
+            return;
+        }
+
+        // From the back, so the indices of the boxes still to delete stay valid
+        indices.sort_unstable_by(|a, b| b.cmp(a));
+        let commands = indices.into_iter()
+            .map(|index| Command::DeleteRectangle { index, rectangle: rectangles[index].clone() })
+            .collect();
+        let command = Command::Batch { commands };
+        command.execute(rectangles);
+        command_history.push(command);
+
+        *selected_index = None;
+        self.clear();
+    }
+
+    /// Gives every selected box `class` as one undo step.
+    pub fn set_class(&self, rectangles: &mut Vec<Rectangle>, selected_index: Option<usize>, class: usize, command_history: &mut CommandHistory) {
+        let commands: Vec<Command> = self.targets(selected_index).into_iter()
+            .filter_map(|index| {
+                let old_rect = rectangles.get(index)?.clone();
+                if old_rect.class == class {
+                    return None;
+                }
+                let new_rect = Rectangle { class, interpolated: false, ..old_rect.clone() };
+                Some(Command::ResizeRectangle { index, old_rect, new_rect })
+            })
+            .collect();
+        if commands.is_empty() {
+            return;
+        }
+
+        let command = Command::Batch { commands };
+        command.execute(rectangles);
+        command_history.push(command);
+    }
+
+    pub fn clear(&mut self) {
+        self.indices.clear();
+        self.frame_start = None;
+        self.last_position = None;
+        self.original_centers.clear();
+        self.pressed_index = None;
+    }
+}
+
+/// Corners of a rectangle in world space, rotation included
+fn corners(rect: &Rectangle) -> [Vec2; 4] {
+    let (pos1, pos2) = rect.position;
+    let min = pos1.min(pos2);
+    let max = pos1.max(pos2);
+    [
+        rect.to_world(min),
+        rect.to_world(Vec2::new(max.x, min.y)),
+        rect.to_world(max),
+        rect.to_world(Vec2::new(min.x, max.y)),
+    ]
+}
+
 #[derive(Default)]
 pub struct DrawingHandler {
     pub start_position: Option<Vec2>,
@@ -268,22 +612,3 @@ impl DrawingHandler {
         self.start_position = None;
     }
 }
-
-pub fn key_code_to_class(keyboard: &Res<ButtonInput<KeyCode>>) -> Option<usize> {
-    for (key, class) in [
-        (KeyCode::Digit1, 1),
-        (KeyCode::Digit2, 2),
-        (KeyCode::Digit3, 3),
-        (KeyCode::Digit4, 4),
-        (KeyCode::Digit5, 5),
-        (KeyCode::Digit6, 6),
-        (KeyCode::Digit7, 7),
-        (KeyCode::Digit8, 8),
-        (KeyCode::Digit9, 9),
-    ] {
-        if keyboard.pressed(key) {
-            return Some(class);
-        }
-    }
-    None
-}
\ No newline at end of file
@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+use crate::core::rectangle::Rectangle;
+
+/// Font size of box labels on screen, whatever the zoom
+pub const LABEL_FONT_SIZE: f32 = 16.0;
+/// Rough advance of a character and height of a line, relative to the font size. Labels are
+/// placed before their text is laid out, so their size is estimated from these.
+const CHAR_WIDTH: f32 = 0.6;
+const LINE_HEIGHT: f32 = 1.25;
+/// Gap between a label and its box, in screen pixels
+const LABEL_GAP: f32 = 2.0;
+
+/// Tag shown next to a box: its index and category, the model confidence of a suggestion and,
+/// on request, its attribute values on a second line
+pub fn label_text(index: usize, rect: &Rectangle, category_name: Option<&str>, show_attributes: bool) -> String {
+    let mut text = match category_name {
+        Some(name) => format!("{} {}", index, name),
+        None => index.to_string(),
+    };
+    if let Some(score) = rect.suggestion_score {
+        text.push_str(&format!(" {:.0}%", score * 100.0));
+    }
+    if show_attributes && !rect.attributes.is_empty() {
+        let attributes: Vec<String> = rect.attributes
+            .iter()
+            .map(|(name, value)| match value {
+                serde_json::Value::String(value) => format!("{}: {}", name, value),
+                value => format!("{}: {}", name, value),
+            })
+            .collect();
+        text.push('\n');
+        text.push_str(&attributes.join(", "));
+    }
+    text
+}
+
+/// Size of a label in world units when the camera is scaled by `camera_scale`
+pub fn label_size(text: &str, camera_scale: f32) -> Vec2 {
+    let lines = text.lines().count().max(1);
+    let longest = text.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+    Vec2::new(
+        longest as f32 * LABEL_FONT_SIZE * CHAR_WIDTH,
+        lines as f32 * LABEL_FONT_SIZE * LINE_HEIGHT,
+    ) * camera_scale
+}
+
+/// Bottom-left corner of each label, trying above the box, inside its top edge, below it and
+/// above its right end until one doesn't cover a label placed before. Labels that fit nowhere
+/// go above the box anyway. `None` entries are boxes without a shown label.
+pub fn place_labels(labels: &[Option<(Rect, Vec2)>], camera_scale: f32) -> Vec<Option<Vec2>> {
+    let gap = LABEL_GAP * camera_scale;
+    let mut placed: Vec<Rect> = Vec::new();
+
+    labels
+        .iter()
+        .map(|label| {
+            let (bounds, size) = (*label)?;
+            let candidates = [
+                Vec2::new(bounds.min.x, bounds.max.y + gap),
+                Vec2::new(bounds.min.x + gap, bounds.max.y - size.y - gap),
+                Vec2::new(bounds.min.x, bounds.min.y - size.y - gap),
+                Vec2::new(bounds.max.x - size.x, bounds.max.y + gap),
+            ];
+            let position = candidates
+                .iter()
+                .copied()
+                .find(|&position| {
+                    let area = Rect::from_corners(position, position + size);
+                    placed.iter().all(|other| other.intersect(area).is_empty())
+                })
+                .unwrap_or(candidates[0]);
+            placed.push(Rect::from_corners(position, position + size));
+            Some(position)
+        })
+        .collect()
+}
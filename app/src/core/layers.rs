@@ -0,0 +1,48 @@
+use std::collections::HashSet;
+use crate::core::rectangle::Rectangle;
+
+/// Which boxes are drawn over the image and how strongly their labels show, to look at the
+/// image itself in crowded scenes. Hidden boxes are still saved, they just can't be seen or
+/// picked on the canvas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerState {
+    pub show_boxes: bool,
+    /// Classes (category index + 1) whose boxes are hidden
+    pub hidden_classes: HashSet<usize>,
+    /// Alpha of the box labels, from 0 (hidden) to 1
+    pub label_opacity: f32,
+    /// Alpha of the mask overlays, from 0 (hidden) to 1
+    pub mask_opacity: f32,
+    /// Add the attribute values of each box to its label
+    pub show_attributes: bool,
+}
+
+impl Default for LayerState {
+    fn default() -> Self {
+        Self {
+            show_boxes: true,
+            hidden_classes: HashSet::new(),
+            label_opacity: 1.0,
+            mask_opacity: 0.45,
+            show_attributes: false,
+        }
+    }
+}
+
+impl LayerState {
+    pub fn is_visible(&self, rect: &Rectangle) -> bool {
+        self.show_boxes && !self.hidden_classes.contains(&rect.class)
+    }
+
+    pub fn set_class_visible(&mut self, class: usize, visible: bool) {
+        if visible {
+            self.hidden_classes.remove(&class);
+        } else {
+            self.hidden_classes.insert(class);
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
@@ -0,0 +1,232 @@
+use bevy::prelude::*;
+use bevy::asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use fast_tag_formats::coco::{decode_rle_counts, encode_rle_counts};
+
+/// Mask overlays sit between the image and the box labels, the mask being painted above them
+pub const MASK_Z: f32 = 1.0;
+pub const PAINTED_MASK_Z: f32 = 1.5;
+/// Diameters of the brush in image pixels
+pub const BRUSH_SIZES: std::ops::RangeInclusive<f32> = 1.0..=200.0;
+const DEFAULT_BRUSH_SIZE: f32 = 20.0;
+
+/// What a stroke in mask mode does to the mask of the selected box
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskTool {
+    Brush,
+    Eraser,
+}
+
+/// Mask mode of the editor, off while `tool` is `None`. Strokes then paint masks instead of
+/// drawing and editing boxes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaskPainting {
+    pub tool: Option<MaskTool>,
+    /// Diameter of the brush in image pixels
+    pub brush_size: f32,
+}
+
+impl Default for MaskPainting {
+    fn default() -> Self {
+        Self {
+            tool: None,
+            brush_size: DEFAULT_BRUSH_SIZE,
+        }
+    }
+}
+
+/// Pixels of a mask while it is painted, in rows from the top left
+#[derive(Debug, Clone)]
+pub struct MaskBitmap {
+    pub size: UVec2,
+    pixels: Vec<bool>,
+}
+
+impl MaskBitmap {
+    pub fn new(size: UVec2) -> Self {
+        Self {
+            size,
+            pixels: vec![false; size.x as usize * size.y as usize],
+        }
+    }
+
+    /// The pixels of a mask decoded by `decode_mask`
+    pub fn from_runs(size: UVec2, runs: &[u32]) -> Self {
+        let mut bitmap = Self::new(size);
+        let height = size.y as usize;
+        let mut position = 0;
+        for (index, &run) in runs.iter().enumerate() {
+            let run = run as usize;
+            if index % 2 == 1 {
+                for pixel in position..position + run {
+                    let index = bitmap.index(pixel / height, pixel % height);
+                    bitmap.pixels[index] = true;
+                }
+            }
+            position += run;
+        }
+        bitmap
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        y * self.size.x as usize + x
+    }
+
+    /// Sets the pixels whose center lies within `radius` of the segment from `from` to `to`, both
+    /// in mask pixels. Returns the pixels that changed.
+    pub fn paint_line(&mut self, from: Vec2, to: Vec2, radius: f32, value: bool) -> Vec<usize> {
+        let min = (from.min(to) - radius).floor().max(Vec2::ZERO);
+        let max = (from.max(to) + radius).ceil().min(self.size.as_vec2());
+        let mut changed = Vec::new();
+
+        for y in min.y as usize..max.y as usize {
+            for x in min.x as usize..max.x as usize {
+                let center = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let index = self.index(x, y);
+                if self.pixels[index] != value && distance_to_segment(center, from, to) <= radius {
+                    self.pixels[index] = value;
+                    changed.push(index);
+                }
+            }
+        }
+        changed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.pixels.contains(&true)
+    }
+
+    /// `[x, y, width, height]` of the painted pixels, `None` when there are none
+    pub fn bounds(&self) -> Option<[u32; 4]> {
+        let width = self.size.x as usize;
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (usize::MAX, usize::MAX, 0, 0);
+        for (index, _) in self.pixels.iter().enumerate().filter(|(_, painted)| **painted) {
+            let (x, y) = (index % width, index / width);
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        (min_x != usize::MAX).then(|| {
+            [min_x as u32, min_y as u32, (max_x - min_x + 1) as u32, (max_y - min_y + 1) as u32]
+        })
+    }
+
+    /// The mask as the server stores it, a COCO RLE with compressed counts
+    pub fn to_rle(&self) -> serde_json::Value {
+        let (width, height) = (self.size.x as usize, self.size.y as usize);
+        let mut runs = Vec::new();
+        let (mut current, mut run) = (false, 0u32);
+        for x in 0..width {
+            for y in 0..height {
+                if self.pixels[y * width + x] != current {
+                    runs.push(run);
+                    current = !current;
+                    run = 0;
+                }
+                run += 1;
+            }
+        }
+        runs.push(run);
+
+        serde_json::json!({
+            "size": [self.size.y, self.size.x],
+            "counts": encode_rle_counts(&runs),
+        })
+    }
+
+    /// Texture of the mask, as `masks_image` draws it
+    pub fn to_image(&self, color: Color) -> Image {
+        let pixel = mask_pixel(color);
+        let data = self.pixels
+            .iter()
+            .flat_map(|&painted| if painted { pixel } else { [0; 4] })
+            .collect();
+        texture(self.size, data)
+    }
+}
+
+fn distance_to_segment(point: Vec2, from: Vec2, to: Vec2) -> f32 {
+    let segment = to - from;
+    let length_squared = segment.length_squared();
+    if length_squared == 0.0 {
+        return point.distance(from);
+    }
+    let along = ((point - from).dot(segment) / length_squared).clamp(0.0, 1.0);
+    point.distance(from + segment * along)
+}
+
+/// Writes the pixels of a stroke into the texture of the mask being painted
+pub fn update_image(image: &mut Image, pixels: &[usize], painted: bool, color: Color) {
+    let pixel = if painted { mask_pixel(color) } else { [0; 4] };
+    if let Some(data) = image.data.as_mut() {
+        for &index in pixels {
+            data[index * 4..index * 4 + 4].copy_from_slice(&pixel);
+        }
+    }
+}
+
+/// Size and run lengths of a COCO RLE mask `{"size": [height, width], "counts": ...}`, whose
+/// counts are either numbers or compressed the way pycocotools does. `None` when the mask is
+/// malformed or its runs don't cover the image.
+pub fn decode_mask(mask: &serde_json::Value) -> Option<(UVec2, Vec<u32>)> {
+    let size = mask.get("size")?.as_array()?;
+    let height = u32::try_from(size.first()?.as_u64()?).ok()?;
+    let width = u32::try_from(size.get(1)?.as_u64()?).ok()?;
+
+    let runs = match mask.get("counts")? {
+        serde_json::Value::String(counts) => decode_rle_counts(counts).ok()?,
+        serde_json::Value::Array(counts) => counts
+            .iter()
+            .map(|count| u32::try_from(count.as_u64()?).ok())
+            .collect::<Option<Vec<u32>>>()?,
+        _ => return None,
+    };
+
+    let pixels: u64 = runs.iter().map(|&run| run as u64).sum();
+    (width > 0 && height > 0 && pixels == width as u64 * height as u64).then_some((UVec2::new(width, height), runs))
+}
+
+/// One texture of the image size with the pixels of every mask in its color, transparent
+/// elsewhere, later masks drawn over earlier ones. The runs go down the columns and start with
+/// the background. Sprites showing it set the opacity.
+pub fn masks_image<'a>(size: UVec2, masks: impl IntoIterator<Item = (&'a [u32], Color)>) -> Image {
+    let (width, height) = (size.x as usize, size.y as usize);
+    let mut data = vec![0u8; width * height * 4];
+
+    for (runs, color) in masks {
+        let pixel = mask_pixel(color);
+        let mut position = 0;
+        for (index, &run) in runs.iter().enumerate() {
+            let run = run as usize;
+            if index % 2 == 1 {
+                for mask_pixel in position..position + run {
+                    let offset = ((mask_pixel % height) * width + mask_pixel / height) * 4;
+                    data[offset..offset + 4].copy_from_slice(&pixel);
+                }
+            }
+            position += run;
+        }
+    }
+
+    texture(size, data)
+}
+
+fn mask_pixel(color: Color) -> [u8; 4] {
+    let [red, green, blue, _] = color.to_srgba().to_u8_array();
+    [red, green, blue, 255]
+}
+
+fn texture(size: UVec2, data: Vec<u8>) -> Image {
+    Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
@@ -1,4 +1,13 @@
 pub mod camera_controls;
 pub mod commands;
+pub mod coordinates;
+pub mod drawing_aids;
 pub mod interactions;
-pub mod rectangle;
\ No newline at end of file
+pub mod labels;
+pub mod layers;
+pub mod masks;
+pub mod outlines;
+pub mod rectangle;
+pub mod shortcuts;
+pub mod spatial_index;
+pub mod touch;
\ No newline at end of file
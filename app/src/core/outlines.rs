@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use bevy::asset::RenderAssetUsages;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use crate::core::rectangle::{Rectangle, rect_color};
+
+/// Outlines sit above the masks and below the box labels
+pub const OUTLINE_Z: f32 = 2.0;
+/// Line width of the box outlines in screen pixels, the same for the gizmos of boxes being drawn
+pub const OUTLINE_WIDTH: f32 = 3.0;
+
+/// Corners and color of a box outline in the batched mesh
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxOutline {
+    corners: [Vec2; 4],
+    color: [f32; 4],
+}
+
+impl BoxOutline {
+    pub fn of(rect: &Rectangle) -> Self {
+        let (pos1, pos2) = rect.position;
+        let (min, max) = (pos1.min(pos2), pos1.max(pos2));
+        let color: Color = rect_color(rect.class).into();
+        Self {
+            corners: [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)].map(|corner| rect.to_world(corner)),
+            color: color.to_linear().to_f32_array(),
+        }
+    }
+}
+
+/// One mesh with the outlines of all `outlines`, every edge a quad `width` wide in world units.
+/// The colors are vertex colors, so a single draw call covers every box.
+pub fn outline_mesh(outlines: &[BoxOutline], width: f32) -> Mesh {
+    let half = width / 2.0;
+    let mut positions: Vec<[f32; 3]> = Vec::with_capacity(outlines.len() * 16);
+    let mut colors: Vec<[f32; 4]> = Vec::with_capacity(outlines.len() * 16);
+    let mut indices: Vec<u32> = Vec::with_capacity(outlines.len() * 24);
+
+    for outline in outlines {
+        for (index, &start) in outline.corners.iter().enumerate() {
+            let end = outline.corners[(index + 1) % 4];
+            // Edges run past their corners by half the width, to close the corners
+            let along = (end - start).normalize_or_zero() * half;
+            let across = along.perp();
+
+            let first = positions.len() as u32;
+            for corner in [start - along - across, start - along + across, end + along + across, end + along - across] {
+                positions.push(corner.extend(0.0).to_array());
+                colors.push(outline.color);
+            }
+            indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+        }
+    }
+
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+        .with_inserted_indices(Indices::U32(indices))
+}
@@ -1,10 +1,27 @@
 use bevy::prelude::*;
 use bevy::color::palettes::css::*;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
 
-#[derive(Debug, Clone)]
+/// Distance between the top edge of a rectangle and its rotation handle
+pub const ROTATION_HANDLE_OFFSET: f32 = 25.0;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Rectangle {
     pub class: usize,
     pub position: (Vec2, Vec2),
+    /// Model confidence while the box is an unaccepted suggestion
+    pub suggestion_score: Option<f32>,
+    /// Custom attribute values, keyed by the category's attribute names
+    pub attributes: serde_json::Map<String, serde_json::Value>,
+    /// Counter-clockwise rotation in radians around the center, `position` holds the unrotated box
+    pub rotation: f32,
+    /// Object followed across the frames of a video task, or the tasks of an image sequence
+    pub track_id: Option<uuid::Uuid>,
+    /// Generated between two keyframes by the server; editing the box turns it into a keyframe
+    pub interpolated: bool,
+    /// Pixel mask of the object as the server stores it, a COCO RLE of the whole image. Painting
+    /// it fits the box to it, other edits of the box leave it where it was.
+    pub mask: Option<serde_json::Value>,
 }
 
 impl Rectangle {
@@ -12,11 +29,27 @@ impl Rectangle {
         let mut rect = Self {
             class,
             position: (start, end),
+            suggestion_score: None,
+            attributes: serde_json::Map::new(),
+            rotation: 0.0,
+            track_id: None,
+            interpolated: false,
+            mask: None,
         };
         rect.normalize_position();
         rect
     }
 
+    pub fn new_suggestion(class: usize, start: Vec2, end: Vec2, score: f32) -> Self {
+        let mut rect = Self::new(class, start, end);
+        rect.suggestion_score = Some(score);
+        rect
+    }
+
+    pub fn is_suggestion(&self) -> bool {
+        self.suggestion_score.is_some()
+    }
+
     pub fn normalize_position(&mut self) {
         let (pos1, pos2) = &mut self.position;
         let min_x = pos1.x.min(pos2.x);
@@ -38,7 +71,51 @@ impl Rectangle {
         end - start
     }
 
+    /// Placement for gizmos: the center plus the rotation
+    pub fn isometry(&self) -> Isometry2d {
+        Isometry2d::new(self.center(), Rot2::radians(self.rotation))
+    }
+
+    /// Maps a world point into the unrotated frame of `position`
+    pub fn to_local(&self, point: Vec2) -> Vec2 {
+        let center = self.center();
+        center + Vec2::from_angle(-self.rotation).rotate(point - center)
+    }
+
+    /// Maps a point from the unrotated frame of `position` back into world space
+    pub fn to_world(&self, point: Vec2) -> Vec2 {
+        let center = self.center();
+        center + Vec2::from_angle(self.rotation).rotate(point - center)
+    }
+
+    /// Axis-aligned bounds of the rotated box in world space
+    pub fn world_bounds(&self) -> Rect {
+        let (pos1, pos2) = self.position;
+        let (min, max) = (pos1.min(pos2), pos1.max(pos2));
+        [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)]
+            .into_iter()
+            .map(|corner| self.to_world(corner))
+            .fold(Rect::from_corners(self.center(), self.center()), |bounds, corner| bounds.union_point(corner))
+    }
+
+    pub fn rotation_handle(&self) -> Vec2 {
+        let (pos1, pos2) = self.position;
+        let top = Vec2::new(self.center().x, pos1.y.max(pos2.y) + ROTATION_HANDLE_OFFSET);
+        self.to_world(top)
+    }
+
+    /// Turns the rectangle so its rotation handle points at `point`
+    pub fn rotate_towards(&mut self, point: Vec2) {
+        let direction = point - self.center();
+        if direction.length_squared() > f32::EPSILON {
+            let rotation = direction.y.atan2(direction.x) - FRAC_PI_2;
+            self.rotation = if rotation < -PI { rotation + TAU } else { rotation };
+            self.interpolated = false;
+        }
+    }
+
     pub fn contains_point(&self, point: Vec2, margin: f32) -> bool {
+        let point = self.to_local(point);
         let (pos1, pos2) = self.position;
         let min_x = pos1.x.min(pos2.x);
         let max_x = pos1.x.max(pos2.x);
@@ -58,6 +135,7 @@ impl Rectangle {
     }
 
     pub fn get_corner_at_point(&self, point: Vec2, margin: f32) -> Option<Corner> {
+        let point = self.to_local(point);
         let (pos1, pos2) = self.position;
         let min_x = pos1.x.min(pos2.x);
         let max_x = pos1.x.max(pos2.x);
@@ -85,9 +163,20 @@ impl Rectangle {
     pub fn move_by(&mut self, delta: Vec2) {
         self.position.0 += delta;
         self.position.1 += delta;
+        self.interpolated = false;
     }
 
     pub fn resize_corner(&mut self, corner: Corner, new_position: Vec2) {
+        let new_position = self.to_local(new_position);
+        let (start, end) = self.position;
+        let opposite = match corner {
+            Corner::BottomLeft => end,
+            Corner::BottomRight => Vec2::new(start.x, end.y),
+            Corner::TopLeft => Vec2::new(end.x, start.y),
+            Corner::TopRight => start,
+        };
+        let opposite_before = self.to_world(opposite);
+
         let (start_pos, end_pos) = &mut self.position;
         match corner {
             Corner::BottomLeft => *start_pos = new_position,
@@ -101,6 +190,13 @@ impl Rectangle {
             }
             Corner::TopRight => *end_pos = new_position,
         }
+        self.interpolated = false;
+
+        // Rotating around the new center would move the opposite corner, so shift it back
+        if self.rotation != 0.0 {
+            let drift = opposite_before - self.to_world(opposite);
+            self.move_by(drift);
+        }
     }
 }
 
@@ -113,6 +209,8 @@ pub enum Corner {
 }
 
 pub fn rect_color(class: usize) -> impl Into<Color> {
+    // Classes past the digit keys reuse the palette
+    let class = if class > 9 { (class - 1) % 9 + 1 } else { class };
     match class {
         1 => RED,
         2 => BLUE,
@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+use crate::api::categories::AnnotationCategory;
+
+/// Keys a category can be bound to, with the name stored on the server and the label shown
+/// to the user. Keys used by other tools (M, Z, `,`, `.`, Enter, F1, ...) are left out.
+pub const ASSIGNABLE_KEYS: [(KeyCode, &str, &str); 45] = [
+    (KeyCode::Digit1, "Digit1", "1"),
+    (KeyCode::Digit2, "Digit2", "2"),
+    (KeyCode::Digit3, "Digit3", "3"),
+    (KeyCode::Digit4, "Digit4", "4"),
+    (KeyCode::Digit5, "Digit5", "5"),
+    (KeyCode::Digit6, "Digit6", "6"),
+    (KeyCode::Digit7, "Digit7", "7"),
+    (KeyCode::Digit8, "Digit8", "8"),
+    (KeyCode::Digit9, "Digit9", "9"),
+    (KeyCode::Digit0, "Digit0", "0"),
+    (KeyCode::KeyA, "KeyA", "A"),
+    (KeyCode::KeyB, "KeyB", "B"),
+    (KeyCode::KeyC, "KeyC", "C"),
+    (KeyCode::KeyD, "KeyD", "D"),
+    (KeyCode::KeyE, "KeyE", "E"),
+    (KeyCode::KeyF, "KeyF", "F"),
+    (KeyCode::KeyG, "KeyG", "G"),
+    (KeyCode::KeyH, "KeyH", "H"),
+    (KeyCode::KeyI, "KeyI", "I"),
+    (KeyCode::KeyJ, "KeyJ", "J"),
+    (KeyCode::KeyK, "KeyK", "K"),
+    (KeyCode::KeyL, "KeyL", "L"),
+    (KeyCode::KeyN, "KeyN", "N"),
+    (KeyCode::KeyO, "KeyO", "O"),
+    (KeyCode::KeyP, "KeyP", "P"),
+    (KeyCode::KeyQ, "KeyQ", "Q"),
+    (KeyCode::KeyR, "KeyR", "R"),
+    (KeyCode::KeyS, "KeyS", "S"),
+    (KeyCode::KeyT, "KeyT", "T"),
+    (KeyCode::KeyU, "KeyU", "U"),
+    (KeyCode::KeyV, "KeyV", "V"),
+    (KeyCode::KeyW, "KeyW", "W"),
+    (KeyCode::KeyX, "KeyX", "X"),
+    (KeyCode::KeyY, "KeyY", "Y"),
+    (KeyCode::F2, "F2", "F2"),
+    (KeyCode::F3, "F3", "F3"),
+    (KeyCode::F4, "F4", "F4"),
+    (KeyCode::F5, "F5", "F5"),
+    (KeyCode::F6, "F6", "F6"),
+    (KeyCode::F7, "F7", "F7"),
+    (KeyCode::F8, "F8", "F8"),
+    (KeyCode::F9, "F9", "F9"),
+    (KeyCode::F10, "F10", "F10"),
+    (KeyCode::F11, "F11", "F11"),
+    (KeyCode::F12, "F12", "F12"),
+];
+
+/// Keys of the detail page that are not bound to categories, with the message ID of what they
+/// do, for the cheat sheet
+pub const FIXED_SHORTCUTS: [(&str, &str); 19] = [
+    ("F1", "shortcut-cheat-sheet"),
+    ("Ctrl/Cmd + Z", "shortcut-undo"),
+    ("Ctrl/Cmd + Shift + Z", "shortcut-redo"),
+    ("Backspace", "shortcut-delete"),
+    ("Arrows", "shortcut-nudge"),
+    ("Shift + Arrows", "shortcut-nudge-far"),
+    ("Shift + Click", "shortcut-toggle-selection"),
+    ("Shift + Drag", "shortcut-select-frame"),
+    ("Esc", "shortcut-cancel"),
+    ("M", "shortcut-magic-select"),
+    (", / .", "shortcut-frames"),
+    ("Ctrl/Cmd + P", "shortcut-propagate"),
+    ("Enter", "shortcut-save-next"),
+    ("Mouse wheel", "shortcut-zoom"),
+    ("Home", "shortcut-fit"),
+    ("=", "shortcut-actual-size"),
+    ("/", "shortcut-zoom-selection"),
+    ("Right drag", "shortcut-pan"),
+    ("Two fingers", "shortcut-touch"),
+];
+
+/// Annotation modes of the detail page, with the message IDs of their name and of how to use them
+pub const ANNOTATION_MODES: [(&str, &str); 7] = [
+    ("mode-draw", "mode-draw-how"),
+    ("mode-move", "mode-move-how"),
+    ("mode-resize", "mode-resize-how"),
+    ("mode-rotate", "mode-rotate-how"),
+    ("mode-select", "mode-select-how"),
+    ("mode-magic-select", "mode-magic-select-how"),
+    ("mode-paint-mask", "mode-paint-mask-how"),
+];
+
+pub fn key_code(name: &str) -> Option<KeyCode> {
+    ASSIGNABLE_KEYS.iter().find(|(_, key_name, _)| *key_name == name).map(|(key, _, _)| *key)
+}
+
+pub fn key_name(key: KeyCode) -> Option<&'static str> {
+    ASSIGNABLE_KEYS.iter().find(|(code, _, _)| *code == key).map(|(_, name, _)| *name)
+}
+
+/// Display label of a stored key name, the name itself for keys this version doesn't know.
+pub fn key_label(name: &str) -> &str {
+    ASSIGNABLE_KEYS.iter().find(|(_, key_name, _)| *key_name == name).map_or(name, |(_, _, label)| *label)
+}
+
+/// Keys of the project's classes (category index + 1). Until any category has a shortcut,
+/// the digit keys pick the first nine categories.
+pub fn class_bindings(categories: &[AnnotationCategory]) -> Vec<(KeyCode, usize)> {
+    let configured: Vec<(KeyCode, usize)> = categories
+        .iter()
+        .enumerate()
+        .filter_map(|(index, category)| Some((key_code(category.hotkey.as_deref()?)?, index + 1)))
+        .collect();
+    if !configured.is_empty() {
+        return configured;
+    }
+
+    ASSIGNABLE_KEYS[..9]
+        .iter()
+        .zip(1..=categories.len())
+        .map(|((key, _, _), class)| (*key, class))
+        .collect()
+}
+
+/// Label of the key that picks `class`, if any
+pub fn class_key_label(categories: &[AnnotationCategory], class: usize) -> Option<&'static str> {
+    class_bindings(categories)
+        .into_iter()
+        .find(|(_, bound_class)| *bound_class == class)
+        .and_then(|(key, _)| ASSIGNABLE_KEYS.iter().find(|(code, _, _)| *code == key))
+        .map(|(_, _, label)| *label)
+}
+
+pub fn pressed_class(keyboard: &ButtonInput<KeyCode>, categories: &[AnnotationCategory]) -> Option<usize> {
+    class_bindings(categories)
+        .into_iter()
+        .find(|(key, _)| keyboard.pressed(*key))
+        .map(|(_, class)| class)
+}
+
+/// Like `pressed_class`, but only for the frame the key went down, for toggles
+pub fn just_pressed_class(keyboard: &ButtonInput<KeyCode>, categories: &[AnnotationCategory]) -> Option<usize> {
+    class_bindings(categories)
+        .into_iter()
+        .find(|(key, _)| keyboard.just_pressed(*key))
+        .map(|(_, class)| class)
+}
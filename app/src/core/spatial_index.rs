@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+use crate::core::rectangle::Rectangle;
+
+/// Cells are about the size of an average box, within these bounds in world units
+const MIN_CELL_SIZE: f32 = 16.0;
+const MAX_CELL_SIZE: f32 = 512.0;
+/// Boxes covering more cells than this, such as one around the whole image, are kept aside and
+/// always returned instead of being put into every cell
+const MAX_CELLS_PER_BOX: usize = 64;
+
+/// Uniform grid over the world bounds of the boxes, so hit-testing a point only looks at the
+/// boxes around it instead of every box of the image. `sync` rebuilds it when a box moved,
+/// changed size or rotation, or boxes came and went, so it is called before hit-testing
+/// whatever may have changed the boxes since the last call.
+#[derive(Debug, Default)]
+pub struct SpatialIndex {
+    /// Placement of every box when the grid was built, to notice changes
+    placements: Vec<((Vec2, Vec2), f32)>,
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+    oversized: Vec<usize>,
+}
+
+impl SpatialIndex {
+    /// Rebuilds the grid if the boxes changed since it was built
+    pub fn sync(&mut self, rectangles: &[Rectangle]) {
+        let unchanged = self.placements.len() == rectangles.len()
+            && self.placements.iter().zip(rectangles).all(|(placement, rect)| *placement == (rect.position, rect.rotation));
+        if !unchanged {
+            self.rebuild(rectangles);
+        }
+    }
+
+    fn rebuild(&mut self, rectangles: &[Rectangle]) {
+        self.placements = rectangles.iter().map(|rect| (rect.position, rect.rotation)).collect();
+        self.cells.clear();
+        self.oversized.clear();
+
+        let bounds: Vec<Rect> = rectangles.iter().map(Rectangle::world_bounds).collect();
+        let average_extent = bounds.iter().map(|bounds| bounds.size().max_element()).sum::<f32>() / bounds.len().max(1) as f32;
+        self.cell_size = average_extent.clamp(MIN_CELL_SIZE, MAX_CELL_SIZE);
+
+        for (index, bounds) in bounds.iter().enumerate() {
+            let (min, max) = self.cell_range(*bounds);
+            let count = (max.0 - min.0 + 1) as usize * (max.1 - min.1 + 1) as usize;
+            if count > MAX_CELLS_PER_BOX {
+                self.oversized.push(index);
+                continue;
+            }
+            for x in min.0..=max.0 {
+                for y in min.1..=max.1 {
+                    self.cells.entry((x, y)).or_default().push(index);
+                }
+            }
+        }
+    }
+
+    fn cell_range(&self, area: Rect) -> ((i32, i32), (i32, i32)) {
+        let cell = |point: Vec2| {
+            let cell = (point / self.cell_size).floor();
+            (cell.x as i32, cell.y as i32)
+        };
+        (cell(area.min), cell(area.max))
+    }
+
+    /// Indices of the boxes whose bounds may overlap `area`, in ascending order. Callers still
+    /// test each one, the grid only rules out the boxes far away.
+    pub fn query(&self, area: Rect) -> Vec<usize> {
+        if self.placements.is_empty() {
+            return Vec::new();
+        }
+
+        let (min, max) = self.cell_range(area);
+        let mut indices = self.oversized.clone();
+        // A huge area, such as a selection frame around the whole image, would visit more cells
+        // than there are boxes
+        if (max.0 - min.0 + 1) as usize * (max.1 - min.1 + 1) as usize > self.cells.len() {
+            indices.extend(self.cells.values().flatten());
+        } else {
+            for x in min.0..=max.0 {
+                for y in min.1..=max.1 {
+                    indices.extend(self.cells.get(&(x, y)).into_iter().flatten());
+                }
+            }
+        }
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+
+    /// Indices of the boxes that may lie within `margin` of `point`, in ascending order
+    pub fn near(&self, point: Vec2, margin: f32) -> Vec<usize> {
+        self.query(Rect::from_center_half_size(point, Vec2::splat(margin)))
+    }
+}
@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+use bevy::input::ButtonState;
+use bevy::input::mouse::MouseButtonInput;
+use bevy::input::touch::{ForceTouch, TouchInput, TouchPhase};
+
+/// A finger or pen on a touchscreen or tablet, acting as the left mouse button for the box
+/// tools. A second finger turns the touch into a pan and pinch gesture of the view instead, until
+/// every finger is lifted. A pen hovering over the screen moves the mouse cursor, so the crosshair
+/// and the cursor icons of the handles follow it like a mouse.
+#[derive(Resource, Debug, Default)]
+pub struct TouchState {
+    /// Touch acting as the left button
+    primary: Option<u64>,
+    /// A gesture ended the primary touch, the fingers left don't start a new one
+    gesturing: bool,
+    /// Window position of the primary touch, kept for the frame it is lifted in
+    pub position: Option<Vec2>,
+    /// Pen pressure of the primary touch from 0 to 1, `None` for fingers and pens without it
+    pub pressure: Option<f32>,
+    /// Presses and releases of the primary touch this frame
+    pub button_events: Vec<ButtonState>,
+}
+
+impl TouchState {
+    /// Takes in the touch events of a frame, with how many fingers are down after them
+    pub fn process<'a>(&mut self, events: impl Iterator<Item = &'a TouchInput>, touch_count: usize) {
+        self.button_events.clear();
+        if self.primary.is_none() {
+            self.position = None;
+            self.pressure = None;
+        }
+
+        for event in events {
+            match event.phase {
+                TouchPhase::Started if self.primary.is_none() && !self.gesturing => {
+                    self.primary = Some(event.id);
+                    self.position = Some(event.position);
+                    self.pressure = event.force.map(normalized_force);
+                    self.button_events.push(ButtonState::Pressed);
+                }
+                TouchPhase::Moved if self.primary == Some(event.id) => {
+                    self.position = Some(event.position);
+                    self.pressure = event.force.map(normalized_force);
+                }
+                TouchPhase::Ended | TouchPhase::Canceled if self.primary == Some(event.id) => {
+                    self.position = Some(event.position);
+                    self.primary = None;
+                    self.button_events.push(ButtonState::Released);
+                }
+                _ => {}
+            }
+        }
+
+        if touch_count > 1 && self.primary.take().is_some() {
+            self.button_events.push(ButtonState::Released);
+        }
+        self.gesturing = match touch_count {
+            0 => false,
+            1 => self.gesturing,
+            _ => true,
+        };
+    }
+
+    pub fn pressed(&self) -> bool {
+        self.primary.is_some()
+    }
+
+    pub fn just_pressed(&self) -> bool {
+        self.button_events.contains(&ButtonState::Pressed)
+    }
+
+    /// The presses and releases as left button events, for the handlers that take mouse events.
+    /// None of them looks at the window of an event.
+    pub fn mouse_events(&self) -> impl Iterator<Item = MouseButtonInput> + '_ {
+        self.button_events.iter().map(|&state| MouseButtonInput {
+            button: MouseButton::Left,
+            state,
+            window: Entity::PLACEHOLDER,
+        })
+    }
+}
+
+fn normalized_force(force: ForceTouch) -> f32 {
+    let force = match force {
+        ForceTouch::Calibrated { force, max_possible_force, .. } if max_possible_force > 0.0 => force / max_possible_force,
+        ForceTouch::Calibrated { .. } => 1.0,
+        ForceTouch::Normalized(force) => force,
+    };
+    (force as f32).clamp(0.0, 1.0)
+}
@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+use uuid::Uuid;
+use crate::api::tasks::TasksApi;
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use crate::app::state::AppState;
+use crate::auth::AuthState;
+use crate::notifications::Notify;
+use crate::pages::{detail, tasks};
+use crate::platform;
+
+/// Scheme of the links to a task, `fasttag://project/{id}/task/{id}`
+pub const URL_SCHEME: &str = "fasttag";
+
+/// Links to a task, as pasted in chats and issue trackers. The OS starts the app with the link
+/// as argument, which opens the task in the editor once the user is logged in.
+pub struct DeepLinkPlugin;
+
+impl Plugin for DeepLinkPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins(ApiTaskPlugin::<LinkedTask>::default())
+            .insert_resource(PendingDeepLink(launch_link()))
+            .add_systems(Startup, register_scheme_system)
+            .add_systems(Update, (
+                open_deep_link_system.run_if(in_state(AppState::Projects)),
+                process_deep_link_results,
+            ));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeepLink {
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+}
+
+impl DeepLink {
+    /// `fasttag://project/{id}/task/{id}`, with or without a trailing slash
+    pub fn parse(url: &str) -> Option<Self> {
+        let path = url.trim().strip_prefix(URL_SCHEME)?.strip_prefix("://")?;
+        match path.trim_end_matches('/').split('/').collect::<Vec<_>>()[..] {
+            ["project", project_id, "task", task_id] => Some(Self {
+                project_id: Uuid::parse_str(project_id).ok()?,
+                task_id: Uuid::parse_str(task_id).ok()?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// The link the app was started with, opened once the user is logged in
+#[derive(Resource, Default)]
+pub struct PendingDeepLink(pub Option<DeepLink>);
+
+/// Image of the linked task, the editor needs it to open the task
+pub struct LinkedTask {
+    link: DeepLink,
+    url: String,
+}
+
+fn launch_link() -> Option<DeepLink> {
+    let argument = std::env::args().skip(1).find(|argument| argument.starts_with(URL_SCHEME))?;
+    let link = DeepLink::parse(&argument);
+    if link.is_none() {
+        warn!("Ignoring malformed link {}", argument);
+    }
+    link
+}
+
+/// Makes the OS start this build of the app for the links, every launch, so the links follow
+/// the app when it moves
+fn register_scheme_system() {
+    if let Err(error) = platform::register_url_scheme(URL_SCHEME) {
+        warn!("Failed to register the {} links: {}", URL_SCHEME, error);
+    }
+}
+
+/// Login ends on the project list, where the link takes over
+fn open_deep_link_system(
+    mut pending: ResMut<PendingDeepLink>,
+    auth_state: Res<AuthState>,
+    link_tasks: Res<ApiTasks<LinkedTask>>,
+) {
+    let Some(jwt) = auth_state.jwt.clone() else {
+        return;
+    };
+    let Some(link) = pending.0.take() else {
+        return;
+    };
+
+    info!("Opening the linked task {} of project {}", link.task_id, link.project_id);
+    link_tasks.spawn(async move {
+        let url = TasksApi::new()
+            .get_task_image_url(&jwt, &link.project_id.to_string(), &link.task_id.to_string())
+            .await
+            .map_err(|e| t!("deep-link-open-failed", error = e.to_string()))?;
+        Ok(LinkedTask { link, url })
+    });
+}
+
+fn process_deep_link_results(
+    mut commands: Commands,
+    mut succeeded: EventReader<ApiTaskSucceeded<LinkedTask>>,
+    mut failed: EventReader<ApiTaskFailed<LinkedTask>>,
+    mut notify: EventWriter<Notify>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for ApiTaskSucceeded(LinkedTask { link, url }) in succeeded.read() {
+        // The task list of the project is where the editor goes back to
+        commands.insert_resource(tasks::Parameters { project_id: link.project_id.to_string() });
+        commands.insert_resource(detail::Parameters {
+            url: url.clone(),
+            task_id: Some(link.task_id),
+            project_id: Some(link.project_id),
+        });
+        next_state.set(AppState::Detail);
+    }
+
+    for failure in failed.read() {
+        notify.write(Notify::error(failure.error.clone()));
+    }
+}
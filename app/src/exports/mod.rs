@@ -0,0 +1,188 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::api::export::{ExportApi, ExportJob, ExportOptions};
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use crate::notifications::Notify;
+
+/// Seconds between two looks at the exports still running
+const POLL_INTERVAL_SECS: f64 = 3.0;
+
+/// Exports the server writes to storage in the background. The ones started from the app are
+/// followed until they finish, wherever the user went meanwhile, and end in a toast.
+pub struct ExportJobsPlugin;
+
+impl Plugin for ExportJobsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins(ApiTaskPlugin::<ExportJob>::default())
+            .add_plugins(ApiTaskPlugin::<ProjectExports>::default())
+            .init_resource::<ExportJobsState>()
+            .add_event::<ExportJobRequestEvent>()
+            .add_systems(Update, (
+                handle_export_job_requests,
+                poll_export_jobs,
+                process_export_job_results,
+            ));
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ExportJobsState {
+    /// Latest list of exports of every project looked at, newest first
+    pub exports: HashMap<Uuid, Vec<ExportJob>>,
+    /// Jobs started from the app that haven't finished, by project
+    followed: Vec<(Uuid, Uuid)>,
+    /// Projects whose list is wanted without waiting for the next poll
+    refresh: Vec<Uuid>,
+    token: Option<String>,
+    /// A list request is on its way
+    is_polling: bool,
+    last_poll: f64,
+}
+
+impl ExportJobsState {
+    /// Reads the exports of `project_id` again
+    pub fn refresh(&mut self, project_id: Uuid, token: &str) {
+        self.token = Some(token.to_string());
+        if !self.refresh.contains(&project_id) {
+            self.refresh.push(project_id);
+        }
+    }
+
+    pub fn is_following(&self, project_id: Uuid) -> bool {
+        self.followed.iter().any(|(project, _)| *project == project_id)
+    }
+}
+
+/// Starts a COCO export of `project_id` into the `destination` folder of its storage
+#[derive(Event)]
+pub struct ExportJobRequestEvent {
+    pub project_id: Uuid,
+    pub token: String,
+    pub options: ExportOptions,
+    pub destination: String,
+}
+
+/// What a page needs to start exports and show how they went
+#[derive(SystemParam)]
+pub struct ExportJobs<'w> {
+    pub state: ResMut<'w, ExportJobsState>,
+    pub requests: EventWriter<'w, ExportJobRequestEvent>,
+}
+
+/// Exports of every project that was polled
+pub struct ProjectExports(Vec<(Uuid, Vec<ExportJob>)>);
+
+fn handle_export_job_requests(
+    mut requests: EventReader<ExportJobRequestEvent>,
+    mut state: ResMut<ExportJobsState>,
+    job_tasks: Res<ApiTasks<ExportJob>>,
+) {
+    for request in requests.read() {
+        state.token = Some(request.token.clone());
+        let project_id = request.project_id;
+        let token = request.token.clone();
+        let options = request.options.clone();
+        let destination = request.destination.clone();
+        job_tasks.spawn(async move {
+            ExportApi::new()
+                .start_coco_export_job(&token, project_id, &options, &destination)
+                .await
+                .map_err(|e| e.to_string())
+        });
+    }
+}
+
+fn poll_export_jobs(
+    time: Res<Time>,
+    mut state: ResMut<ExportJobsState>,
+    list_tasks: Res<ApiTasks<ProjectExports>>,
+) {
+    if state.is_polling {
+        return;
+    }
+    let now = time.elapsed_secs_f64();
+    let mut projects = std::mem::take(&mut state.refresh);
+    if !state.followed.is_empty() && now - state.last_poll >= POLL_INTERVAL_SECS {
+        for (project_id, _) in &state.followed {
+            if !projects.contains(project_id) {
+                projects.push(*project_id);
+            }
+        }
+    }
+    let Some(token) = state.token.clone() else {
+        return;
+    };
+    if projects.is_empty() {
+        return;
+    }
+
+    state.is_polling = true;
+    state.last_poll = now;
+    list_tasks.spawn(async move {
+        let export_api = ExportApi::new();
+        let mut lists = Vec::new();
+        for project_id in projects {
+            let exports = export_api.list_exports(&token, project_id).await.map_err(|e| e.to_string())?;
+            lists.push((project_id, exports));
+        }
+        Ok(ProjectExports(lists))
+    });
+}
+
+fn process_export_job_results(
+    mut started: EventReader<ApiTaskSucceeded<ExportJob>>,
+    mut start_failures: EventReader<ApiTaskFailed<ExportJob>>,
+    mut polled: EventReader<ApiTaskSucceeded<ProjectExports>>,
+    mut list_failures: EventReader<ApiTaskFailed<ProjectExports>>,
+    mut state: ResMut<ExportJobsState>,
+    mut notify: EventWriter<Notify>,
+) {
+    for ApiTaskSucceeded(job) in started.read() {
+        notify.write(Notify::info(t!("exports-started")));
+        state.followed.push((job.project_id, job.id));
+        state.exports.entry(job.project_id).or_default().insert(0, job.clone());
+    }
+    for failure in start_failures.read() {
+        notify.write(Notify::error(t!("exports-start-failed", error = failure.error.as_str())));
+    }
+
+    for ApiTaskSucceeded(ProjectExports(lists)) in polled.read() {
+        state.is_polling = false;
+        let finished: Vec<ExportJob> = lists
+            .iter()
+            .flat_map(|(_, exports)| exports)
+            .filter(|job| !job.is_running() && state.followed.iter().any(|(_, job_id)| *job_id == job.id))
+            .cloned()
+            .collect();
+        for (project_id, exports) in lists {
+            state.exports.insert(*project_id, exports.clone());
+        }
+
+        for job in finished {
+            state.followed.retain(|(_, job_id)| *job_id != job.id);
+            match job.status.as_str() {
+                "completed" => notify.write(Notify::success(t!(
+                    "exports-completed",
+                    size = megabytes(job.size_bytes.unwrap_or(0)),
+                    location = job.location.as_deref().or(job.storage_key.as_deref()).unwrap_or_default(),
+                ))),
+                _ => notify.write(Notify::error(t!(
+                    "exports-failed",
+                    error = job.error.as_deref().unwrap_or_default(),
+                ))),
+            };
+        }
+    }
+    for failure in list_failures.read() {
+        state.is_polling = false;
+        warn!("Failed to fetch the exports: {}", failure.error);
+    }
+}
+
+/// Size of an export as shown to the user, e.g. `12.5`
+pub fn megabytes(bytes: i64) -> String {
+    format!("{:.1}", bytes as f64 / (1024.0 * 1024.0))
+}
@@ -0,0 +1,150 @@
+//! UI strings in the user's language, from the Fluent files in `app/locales`. Pages look strings
+//! up with `t!("message-id")`, or `t!("message-id", name = value)` for messages with arguments.
+//! A message missing from a language falls back to English, then to its ID.
+
+use crate::io::preferences::Preferences;
+use bevy::prelude::*;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use std::sync::{OnceLock, RwLock};
+use unic_langid::LanguageIdentifier;
+
+/// Looks up a UI string in the selected language
+macro_rules! t {
+    ($id:expr) => {
+        $crate::i18n::tr($id, None)
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::tr($id, Some(&args))
+    }};
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Language {
+    English,
+    Japanese,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Japanese];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Japanese => "ja",
+        }
+    }
+
+    /// Name in the language itself, so users find theirs whichever language is selected
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Japanese => "日本語",
+        }
+    }
+
+    fn messages(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../../locales/en/main.ftl"),
+            Language::Japanese => include_str!("../../locales/ja/main.ftl"),
+        }
+    }
+
+    /// Matches codes with a region or encoding too, such as `ja_JP.UTF-8`
+    fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|language| code.starts_with(language.code()))
+    }
+}
+
+struct Localizer {
+    language: Language,
+    bundles: Vec<(Language, FluentBundle<FluentResource>)>,
+}
+
+impl Localizer {
+    fn format(&self, language: Language, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+        let (_, bundle) = self.bundles.iter().find(|(bundle_language, _)| *bundle_language == language)?;
+        let pattern = bundle.get_message(id)?.value()?;
+        let mut errors = Vec::new();
+        let text = bundle.format_pattern(pattern, args, &mut errors);
+        if !errors.is_empty() {
+            warn!("Failed to format message {}: {:?}", id, errors);
+        }
+        Some(text.into_owned())
+    }
+}
+
+fn load_bundle(language: Language) -> FluentBundle<FluentResource> {
+    let id: LanguageIdentifier = language.code().parse().expect("language codes are valid");
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    // egui draws the Unicode isolation marks around arguments as boxes
+    bundle.set_use_isolating(false);
+
+    let resource = FluentResource::try_new(language.messages().to_string()).unwrap_or_else(|(resource, errors)| {
+        error!("Invalid messages for {}: {:?}", language.code(), errors);
+        resource
+    });
+    if let Err(errors) = bundle.add_resource(resource) {
+        error!("Duplicate messages for {}: {:?}", language.code(), errors);
+    }
+    bundle
+}
+
+/// The language chosen in the settings, or else the one of the system
+fn initial_language() -> Language {
+    if let Some(language) = Preferences::load().language.as_deref().and_then(Language::from_code) {
+        return language;
+    }
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find_map(|code| Language::from_code(&code))
+        .unwrap_or(Language::English)
+}
+
+fn localizer() -> &'static RwLock<Localizer> {
+    static LOCALIZER: OnceLock<RwLock<Localizer>> = OnceLock::new();
+    LOCALIZER.get_or_init(|| {
+        RwLock::new(Localizer {
+            language: initial_language(),
+            bundles: Language::ALL.into_iter().map(|language| (language, load_bundle(language))).collect(),
+        })
+    })
+}
+
+pub fn language() -> Language {
+    localizer().read().map(|localizer| localizer.language).unwrap_or(Language::English)
+}
+
+/// Switches the UI language and remembers it for the next start
+pub fn set_language(language: Language) {
+    if let Ok(mut localizer) = localizer().write() {
+        localizer.language = language;
+    }
+
+    let mut preferences = Preferences::load();
+    preferences.language = Some(language.code().to_string());
+    if let Err(error) = preferences.save() {
+        warn!("Failed to save the language: {}", error);
+    }
+}
+
+fn lookup(id: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let localizer = localizer().read().ok()?;
+    localizer
+        .format(localizer.language, id, args)
+        .or_else(|| localizer.format(Language::English, id, args))
+}
+
+/// Formats a message, use `t!` instead
+pub fn tr(id: &str, args: Option<&FluentArgs>) -> String {
+    lookup(id, args).unwrap_or_else(|| id.to_string())
+}
+
+/// Label of a code the API client names in English, such as a split or a flag reason, from the
+/// `<kind>-<code>` message of the selected language when there is one
+pub fn code_label(kind: &str, code: &str, english: &str) -> String {
+    lookup(&format!("{}-{}", kind, code.replace('_', "-")), None).unwrap_or_else(|| english.to_string())
+}
@@ -0,0 +1,277 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::future::Future;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use crate::api::ApiError;
+use crate::api::resources::ResourcesApi;
+use crate::api::tasks::TasksApi;
+use crate::platform::{self, Task};
+
+/// Encoded image bytes kept in memory
+const MEMORY_BUDGET_BYTES: usize = 256 * 1024 * 1024;
+/// Encoded image bytes kept on disk, the least recently used files go first
+const DISK_BUDGET_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+/// Downloads running ahead of the annotator at the same time
+const MAX_PREFETCHES: usize = 4;
+/// Frames or tasks downloaded ahead of the one being annotated
+pub const PREFETCH_AHEAD: usize = 3;
+
+/// Storage location of an image: the URL without its query, which for presigned URLs only holds
+/// the signature and changes every time the server resolves it.
+pub fn cache_key(url: &str) -> &str {
+    url.split('?').next().unwrap_or(url)
+}
+
+#[derive(Default)]
+struct MemoryCache {
+    entries: HashMap<String, Arc<Vec<u8>>>,
+    /// Keys from least to most recently used
+    order: VecDeque<String>,
+    size: usize,
+}
+
+impl MemoryCache {
+    fn get(&mut self, key: &str) -> Option<Arc<Vec<u8>>> {
+        let bytes = self.entries.get(key)?.clone();
+        self.touch(key);
+        Some(bytes)
+    }
+
+    fn insert(&mut self, key: String, bytes: Arc<Vec<u8>>) {
+        self.size += bytes.len();
+        if let Some(replaced) = self.entries.insert(key.clone(), bytes) {
+            self.size -= replaced.len();
+        }
+        self.touch(&key);
+
+        // The newest image stays even when it is larger than the whole budget
+        while self.size > MEMORY_BUDGET_BYTES && self.order.len() > 1 {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            if let Some(evicted) = self.entries.remove(&oldest) {
+                self.size -= evicted.len();
+            }
+        }
+    }
+
+    fn touch(&mut self, key: &str) {
+        self.order.retain(|existing| existing != key);
+        self.order.push_back(key.to_string());
+    }
+}
+
+/// What is stored next to the image bytes on disk
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    etag: Option<String>,
+}
+
+/// Images in the user's cache directory, one `.img` file with a `.json` file describing it per
+/// image. The modification time of the `.img` file tells when it was last used. The browser
+/// has no cache directory, there images are only kept in memory.
+struct DiskCache {
+    dir: Option<PathBuf>,
+}
+
+impl DiskCache {
+    fn paths(&self, key: &str) -> Option<(PathBuf, PathBuf)> {
+        let dir = self.dir.as_ref()?;
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let name = format!("{:016x}", hasher.finish());
+        Some((dir.join(format!("{}.img", name)), dir.join(format!("{}.json", name))))
+    }
+
+    fn read(&self, key: &str) -> Option<(Vec<u8>, Option<String>)> {
+        let (data_path, entry_path) = self.paths(key)?;
+        let entry: DiskEntry = serde_json::from_slice(&fs::read(entry_path).ok()?).ok()?;
+        // Another key with the same hash
+        if entry.key != key {
+            return None;
+        }
+        let bytes = fs::read(&data_path).ok()?;
+
+        if let Ok(file) = fs::File::options().write(true).open(&data_path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some((bytes, entry.etag))
+    }
+
+    fn write(&self, key: &str, bytes: &[u8], etag: Option<&str>) {
+        let (Some(dir), Some((data_path, entry_path))) = (self.dir.as_ref(), self.paths(key)) else {
+            return;
+        };
+        let entry = DiskEntry { key: key.to_string(), etag: etag.map(str::to_string) };
+
+        let result = fs::create_dir_all(dir)
+            .and_then(|_| fs::write(&data_path, bytes))
+            .and_then(|_| fs::write(&entry_path, serde_json::to_vec(&entry).unwrap_or_default()));
+        if let Err(error) = result {
+            warn!("Failed to write image cache entry {}: {}", data_path.display(), error);
+            return;
+        }
+        self.evict();
+    }
+
+    fn evict(&self) {
+        let Some(Ok(read_dir)) = self.dir.as_ref().map(fs::read_dir) else {
+            return;
+        };
+        let mut files: Vec<(SystemTime, u64, PathBuf)> = read_dir
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "img"))
+            .filter_map(|path| {
+                let metadata = fs::metadata(&path).ok()?;
+                Some((metadata.modified().ok()?, metadata.len(), path))
+            })
+            .collect();
+
+        let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+        files.sort_by_key(|(modified, _, _)| *modified);
+        for (_, len, path) in files {
+            if total <= DISK_BUDGET_BYTES {
+                break;
+            }
+            let _ = fs::remove_file(&path);
+            let _ = fs::remove_file(path.with_extension("json"));
+            total -= len;
+        }
+    }
+}
+
+/// Task an image belongs to, where a fresh URL comes from once its presigned URL has expired
+#[derive(Clone)]
+pub struct ImageSource {
+    pub project_id: String,
+    pub task_id: String,
+    pub token: String,
+}
+
+/// Downloaded images kept in memory and on disk, so opening a task again doesn't download its
+/// image again. Copies on disk are checked against the server by their ETag before use, copies
+/// in memory are trusted for the rest of the session.
+#[derive(Resource)]
+pub struct ImageCache {
+    memory: Arc<Mutex<MemoryCache>>,
+    disk: Arc<DiskCache>,
+    /// Prefetches by cache key
+    prefetches: Mutex<HashMap<String, Task>>,
+    /// Tasks of the images by cache key
+    sources: Arc<Mutex<HashMap<String, ImageSource>>>,
+}
+
+impl Default for ImageCache {
+    fn default() -> Self {
+        Self {
+            memory: Arc::new(Mutex::new(MemoryCache::default())),
+            disk: Arc::new(DiskCache {
+                dir: dirs::cache_dir().map(|dir| dir.join("fast-tag").join("images")),
+            }),
+            prefetches: Mutex::new(HashMap::new()),
+            sources: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl ImageCache {
+    /// Bytes of the image at `url`. A prefetch of the same image is waited for rather than
+    /// downloading it a second time. The future doesn't borrow the cache, so it can run in the
+    /// background.
+    pub fn get(&self, url: &str) -> impl Future<Output = Result<Arc<Vec<u8>>, String>> + 'static {
+        let prefetch = self.prefetches.lock().unwrap().remove(cache_key(url));
+        let (memory, disk, sources, url) = (self.memory.clone(), self.disk.clone(), self.sources.clone(), url.to_string());
+        async move {
+            if let Some(prefetch) = prefetch {
+                prefetch.join().await;
+            }
+            fetch(memory, disk, sources, url).await
+        }
+    }
+
+    /// Remembers the task of the image at `url`, so downloading it can recover from an expired
+    /// presigned URL by asking the server for a new one.
+    pub fn set_source(&self, url: &str, source: ImageSource) {
+        self.sources.lock().unwrap().insert(cache_key(url).to_string(), source);
+    }
+
+    /// Starts downloading images the annotator is likely to open next, in the background.
+    /// Images in memory or already on their way are skipped.
+    pub fn prefetch(&self, urls: impl IntoIterator<Item = String>) {
+        let mut prefetches = self.prefetches.lock().unwrap();
+        prefetches.retain(|_, handle| !handle.is_finished());
+
+        for url in urls {
+            if prefetches.len() >= MAX_PREFETCHES {
+                break;
+            }
+            let key = cache_key(&url).to_string();
+            if prefetches.contains_key(&key) || self.memory.lock().unwrap().entries.contains_key(&key) {
+                continue;
+            }
+
+            let (memory, disk, sources) = (self.memory.clone(), self.disk.clone(), self.sources.clone());
+            let handle = platform::spawn(async move {
+                if let Err(error) = fetch(memory, disk, sources, url).await {
+                    warn!("Failed to prefetch image: {}", error);
+                }
+            });
+            prefetches.insert(key, handle);
+        }
+    }
+}
+
+async fn fetch(
+    memory: Arc<Mutex<MemoryCache>>,
+    disk: Arc<DiskCache>,
+    sources: Arc<Mutex<HashMap<String, ImageSource>>>,
+    url: String,
+) -> Result<Arc<Vec<u8>>, String> {
+    let key = cache_key(&url).to_string();
+    let in_memory = memory.lock().unwrap().get(&key);
+    if let Some(bytes) = in_memory {
+        return Ok(bytes);
+    }
+
+    let on_disk = disk.read(&key);
+    let etag = on_disk.as_ref().and_then(|(_, etag)| etag.as_deref());
+    let mut downloaded = ResourcesApi::new().download_image_if_changed(&url, etag).await;
+
+    // Presigned URLs expire while a session goes on, ask the server for a new one once
+    let source = sources.lock().unwrap().get(&key).cloned();
+    if let (Err(ApiError::Forbidden(_)), Some(source)) = (&downloaded, source) {
+        match TasksApi::new().get_task_image_url(&source.token, &source.project_id, &source.task_id).await {
+            Ok(fresh_url) => {
+                info!("URL of {} was rejected, retrying with a fresh one", key);
+                downloaded = ResourcesApi::new().download_image_if_changed(&fresh_url, etag).await;
+            }
+            Err(error) => warn!("Failed to refresh the URL of {}: {}", key, error),
+        }
+    }
+
+    let bytes = match (downloaded, on_disk) {
+        (Ok(Some(image)), _) => {
+            disk.write(&key, &image.bytes, image.etag.as_deref());
+            image.bytes
+        }
+        (Ok(None), Some((bytes, _))) => bytes,
+        // Offline, or the presigned URL has expired: the copy on disk beats no image
+        (Err(error), Some((bytes, _))) => {
+            warn!("Using cached copy of {}: {}", key, error);
+            bytes
+        }
+        (Ok(None), None) => return Err(format!("No cached copy of {}", key)),
+        (Err(error), None) => return Err(error.to_string()),
+    };
+
+    let bytes = Arc::new(bytes);
+    memory.lock().unwrap().insert(key, bytes.clone());
+    Ok(bytes)
+}
@@ -1,24 +1,18 @@
 use bevy::prelude::*;
 use bevy::asset::RenderAssetUsages;
-use crate::api::resources::ResourcesApi;
+use std::future::Future;
+use std::sync::Arc;
+use crate::io::image_cache::ImageCache;
 
-pub async fn download_image_bytes(url: &str) -> Option<Vec<u8>> {
-    println!("Downloading from URL: {}", url);
-    
-    let resources_api = ResourcesApi::new();
-    match resources_api.download_image(url).await {
-        Ok(bytes) => {
-            println!("Successfully downloaded {} bytes", bytes.len());
-            Some(bytes)
-        },
-        Err(e) => {
-            eprintln!("Image download error: {}", e);
-            None
-        }
-    }
+/// Downloads and decodes the image at `url`. Only the lookup touches `image_cache`, the returned
+/// future runs on its own.
+pub fn load_image_from_url(url: &str, image_cache: &ImageCache) -> impl Future<Output = Result<image::DynamicImage, image::ImageError>> + 'static {
+    let download = image_cache.get(url);
+    let url = url.to_string();
+    async move { decode_downloaded_image(&url, download.await) }
 }
 
-pub fn load_image_from_url(url: &str) -> Result<image::DynamicImage, image::ImageError> {
+fn decode_downloaded_image(url: &str, downloaded: Result<Arc<Vec<u8>>, String>) -> Result<image::DynamicImage, image::ImageError> {
     println!("Attempting to load image from URL: {}", url);
     
     // Check if URL is empty or invalid
@@ -32,8 +26,13 @@ pub fn load_image_from_url(url: &str) -> Result<image::DynamicImage, image::Imag
         ));
     }
     
-    let rt = tokio::runtime::Runtime::new().unwrap();
-    let image_bytes = rt.block_on(download_image_bytes(url));
+    let image_bytes = match downloaded {
+        Ok(bytes) => Some(bytes),
+        Err(e) => {
+            eprintln!("Image download error: {}", e);
+            None
+        }
+    };
 
     if let Some(bytes) = image_bytes {
         println!("Downloaded {} bytes from URL", bytes.len());
@@ -86,22 +85,47 @@ pub fn load_image_from_url(url: &str) -> Result<image::DynamicImage, image::Imag
     }
 }
 
+/// Lookup table that maps the window `level - width / 2 ..= level + width / 2` onto the full
+/// 0..=255 range, as the window/level controls of medical viewers do.
+pub fn window_level_lut(width: f32, level: f32) -> [u8; 256] {
+    let low = level - width / 2.0;
+    let width = width.max(1.0);
+    std::array::from_fn(|value| ((value as f32 - low) / width * 255.0).clamp(0.0, 255.0).round() as u8)
+}
+
+/// Lookup table for the view adjustments of the detail page. Brightness shifts by a fraction of
+/// the full range, contrast scales around mid-gray and gamma above 1 lightens the shadows.
+pub fn adjustment_lut(brightness: f32, contrast: f32, gamma: f32) -> [u8; 256] {
+    let gamma = gamma.max(0.01);
+    std::array::from_fn(|value| {
+        let value = ((value as f32 / 255.0 - 0.5) * contrast + 0.5 + brightness).clamp(0.0, 1.0);
+        (value.powf(1.0 / gamma) * 255.0).round() as u8
+    })
+}
+
 pub fn create_bevy_image_from_dynamic(dynamic_image: image::DynamicImage) -> Image {
     Image::from_dynamic(dynamic_image, true, RenderAssetUsages::default())
 }
 
+/// Image decoded in the background, ready to be shown with `spawn_image_sprite`
+pub struct LoadedImage {
+    pub image: Image,
+    pub dimensions: Vec2,
+}
+
+impl LoadedImage {
+    pub fn from_dynamic(dynamic_image: image::DynamicImage) -> Self {
+        let dimensions = Vec2::new(dynamic_image.width() as f32, dynamic_image.height() as f32);
+        Self { image: create_bevy_image_from_dynamic(dynamic_image), dimensions }
+    }
+}
+
 pub fn spawn_image_sprite(
     commands: &mut Commands,
     images: &mut ResMut<Assets<Image>>,
-    url: &str,
-) -> Result<(Entity, Vec2), image::ImageError> {
-    let dynamic_image = load_image_from_url(url)?;
-    let width = dynamic_image.width() as f32;
-    let height = dynamic_image.height() as f32;
-    let dimensions = Vec2::new(width, height);
-    
-    let image = create_bevy_image_from_dynamic(dynamic_image);
-    let image_handle = images.add(image);
+    loaded: &LoadedImage,
+) -> (Entity, Vec2) {
+    let image_handle = images.add(loaded.image.clone());
     let image_entity = commands.spawn(Sprite::from_image(image_handle)).id();
-    Ok((image_entity, dimensions))
+    (image_entity, loaded.dimensions)
 }
\ No newline at end of file
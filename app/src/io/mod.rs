@@ -1 +1,7 @@
-pub mod image_loader;
\ No newline at end of file
+pub mod image_cache;
+pub mod image_loader;
+pub mod tile_loader;
+pub mod offline_store;
+pub mod session_store;
+pub mod server_profiles;
+pub mod preferences;
@@ -0,0 +1,238 @@
+use bevy::prelude::*;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use uuid::Uuid;
+use crate::api::annotations::{AnnotationWithCategory, BoundingBox};
+use crate::api::categories::AnnotationCategory;
+use crate::api::projects::Project;
+use crate::api::tasks::{TaskFilter, TaskWithResolvedUrl};
+use crate::api::ApiResult;
+use crate::platform::LocalDb;
+
+/// Boxes saved while the server couldn't be reached, sent again once it can be
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedSave {
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub bounding_boxes: Vec<BoundingBox>,
+    /// Latest annotation of the task on the server when the offline edits started from it.
+    /// Anything else on the server by the time the save is sent is a conflict.
+    pub base_annotation_id: Option<Uuid>,
+    pub queued_at: DateTime<Utc>,
+    /// Annotation found on the server instead of `base_annotation_id`, until the user decides
+    /// which version to keep
+    pub conflicting_annotation_id: Option<Uuid>,
+}
+
+impl QueuedSave {
+    pub fn has_conflict(&self) -> bool {
+        self.conflicting_annotation_id.is_some()
+    }
+
+    /// The queued boxes in the shape the server returns them, to show them again offline
+    pub fn annotations(&self) -> Vec<AnnotationWithCategory> {
+        self.bounding_boxes
+            .iter()
+            .map(|bounding_box| AnnotationWithCategory {
+                id: Uuid::new_v4(),
+                task_id: self.task_id,
+                metadata: serde_json::Value::Null,
+                annotated_by: None,
+                annotated_at: self.queued_at,
+                annotation_id: Uuid::nil(),
+                category_id: Some(bounding_box.category_id),
+                bbox: bounding_box.bbox.clone(),
+                area: bounding_box.area,
+                iscrowd: bounding_box.iscrowd.unwrap_or(false),
+                image_metadata: serde_json::Value::Null,
+                is_prediction: bounding_box.is_prediction.unwrap_or(false),
+                confidence: bounding_box.confidence,
+                attributes: bounding_box.attributes.clone().unwrap_or_default(),
+                rotation: bounding_box.rotation.unwrap_or(0.0),
+                frame_index: bounding_box.frame_index,
+                track_id: bounding_box.track_id,
+                is_interpolated: bounding_box.is_interpolated.unwrap_or(false),
+                mask: bounding_box.mask.clone(),
+                created_at: self.queued_at,
+                updated_at: self.queued_at,
+                category_name: String::new(),
+                category_color: None,
+            })
+            .collect()
+    }
+}
+
+/// Local copy of what the annotator needs to keep working without a connection: projects, task
+/// lists, categories and the latest annotations of each task, plus the saves waiting to be sent.
+/// Images are kept by `ImageCache`. It is shared by the API helpers, which run in the background
+/// outside of systems, so it lives for the whole process instead of in a resource.
+pub struct OfflineStore {
+    db: Option<LocalDb>,
+}
+
+pub fn store() -> &'static OfflineStore {
+    static STORE: OnceLock<OfflineStore> = OnceLock::new();
+    STORE.get_or_init(OfflineStore::open)
+}
+
+impl OfflineStore {
+    fn open() -> Self {
+        Self { db: LocalDb::open("offline") }
+    }
+
+    fn read<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let value = self.db.as_ref()?.get(key)?;
+        serde_json::from_slice(&value).ok()
+    }
+
+    fn write<T: Serialize>(&self, key: &str, value: &T) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        let result = serde_json::to_vec(value)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| db.insert(key, bytes));
+        if let Err(error) = result {
+            warn!("Failed to write {} to the offline store: {}", key, error);
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        if let Some(db) = &self.db {
+            db.remove(key);
+        }
+    }
+
+    /// Keeps what the server answered, or answers with the last copy when it couldn't be reached.
+    fn remember<T: Serialize + DeserializeOwned>(&self, key: &str, result: ApiResult<T>) -> ApiResult<T> {
+        match result {
+            Ok(value) => {
+                self.write(key, &value);
+                Ok(value)
+            }
+            Err(error) if error.is_network_error() => self.read(key).ok_or(error),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn projects(&self, result: ApiResult<Vec<Project>>) -> ApiResult<Vec<Project>> {
+        self.remember("projects", result)
+    }
+
+    /// Task list of a project, once per filter
+    pub fn tasks(
+        &self,
+        project_id: &str,
+        filter: &TaskFilter,
+        result: ApiResult<Vec<TaskWithResolvedUrl>>,
+    ) -> ApiResult<Vec<TaskWithResolvedUrl>> {
+        let mut key = format!("tasks/{}/{}", project_id, filter.flag.as_deref().unwrap_or("all"));
+        if let Some(has_annotations) = filter.has_annotations {
+            key.push_str(if has_annotations { "/annotated" } else { "/unannotated" });
+        }
+        if let Some(category_id) = filter.contains_category {
+            key.push_str(&format!("/category/{}", category_id));
+        }
+        if let Some(stale) = filter.stale {
+            key.push_str(if stale { "/stale" } else { "/unchanged" });
+        }
+        if let Some(overdue) = filter.overdue {
+            key.push_str(if overdue { "/overdue" } else { "/in-time" });
+        }
+        self.remember(&key, result)
+    }
+
+    pub fn categories(&self, project_id: Uuid, result: ApiResult<Vec<AnnotationCategory>>) -> ApiResult<Vec<AnnotationCategory>> {
+        self.remember(&format!("categories/{}", project_id), result)
+    }
+
+    /// Latest annotations of a task on the server
+    pub fn annotations(&self, task_id: Uuid, result: ApiResult<Vec<AnnotationWithCategory>>) -> ApiResult<Vec<AnnotationWithCategory>> {
+        self.remember(&format!("annotations/{}", task_id), result)
+    }
+
+    pub fn set_annotations(&self, task_id: Uuid, annotations: &[AnnotationWithCategory]) {
+        self.write(&format!("annotations/{}", task_id), &annotations);
+    }
+
+    /// Latest annotations of a task on the server as far as this computer knows, which edits
+    /// in the editor start from
+    pub fn known_annotations(&self, task_id: Uuid) -> Option<Vec<AnnotationWithCategory>> {
+        self.read(&format!("annotations/{}", task_id))
+    }
+
+    /// Latest annotation of a task on the server as far as this computer knows
+    fn known_annotation_id(&self, task_id: Uuid) -> Option<Uuid> {
+        latest_annotation_id(&self.known_annotations(task_id)?)
+    }
+
+    /// Queues the boxes of a task to be sent later. A newer save of the same task replaces the
+    /// queued one but keeps what it was based on.
+    pub fn queue_save(&self, project_id: Uuid, task_id: Uuid, bounding_boxes: Vec<BoundingBox>) {
+        let previous = self.queued_save(task_id);
+        let save = QueuedSave {
+            project_id,
+            task_id,
+            bounding_boxes,
+            base_annotation_id: match &previous {
+                Some(previous) => previous.base_annotation_id,
+                None => self.known_annotation_id(task_id),
+            },
+            queued_at: Utc::now(),
+            conflicting_annotation_id: previous.and_then(|previous| previous.conflicting_annotation_id),
+        };
+        self.write(&format!("queue/{}", task_id), &save);
+    }
+
+    pub fn queued_save(&self, task_id: Uuid) -> Option<QueuedSave> {
+        self.read(&format!("queue/{}", task_id))
+    }
+
+    /// Queued saves, oldest first
+    pub fn queued_saves(&self) -> Vec<QueuedSave> {
+        let Some(db) = &self.db else {
+            return Vec::new();
+        };
+        let mut saves: Vec<QueuedSave> = db
+            .scan_prefix("queue/")
+            .into_iter()
+            .filter_map(|value| serde_json::from_slice(&value).ok())
+            .collect();
+        saves.sort_by_key(|save| save.queued_at);
+        saves
+    }
+
+    pub fn remove_queued_save(&self, task_id: Uuid) {
+        self.remove(&format!("queue/{}", task_id));
+    }
+
+    /// Drops a queued save once it reached the server, unless a newer one replaced it meanwhile
+    pub fn remove_sent_save(&self, sent: &QueuedSave) {
+        if self.queued_save(sent.task_id).is_some_and(|queued| queued.queued_at == sent.queued_at) {
+            self.remove_queued_save(sent.task_id);
+        }
+    }
+
+    pub fn mark_conflict(&self, task_id: Uuid, server_annotation_id: Option<Uuid>) {
+        if let Some(mut save) = self.queued_save(task_id) {
+            // A task without annotations on the server still conflicts with a base that has some
+            save.conflicting_annotation_id = Some(server_annotation_id.unwrap_or(Uuid::nil()));
+            self.write(&format!("queue/{}", task_id), &save);
+        }
+    }
+
+    /// Sends the queued boxes over whatever is on the server now
+    pub fn keep_queued_save(&self, task_id: Uuid) {
+        if let Some(mut save) = self.queued_save(task_id) {
+            save.base_annotation_id = save.conflicting_annotation_id.take().filter(|id| !id.is_nil());
+            self.write(&format!("queue/{}", task_id), &save);
+        }
+    }
+}
+
+/// Server's latest annotation of a task, to compare with `QueuedSave::base_annotation_id`
+pub fn latest_annotation_id(annotations: &[AnnotationWithCategory]) -> Option<Uuid> {
+    annotations.first().map(|annotation| annotation.annotation_id)
+}
@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::core::input_settings::InputSettings;
+use crate::platform;
+
+/// Settings of the app itself rather than of a project, in the user's config directory (the
+/// page's local storage in the browser)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Preferences {
+    /// Code of the UI language, the system language when not chosen yet
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Whether the tour of the detail page was taken or skipped, so it only starts by itself once
+    #[serde(default)]
+    pub tour_completed: bool,
+    /// Release the user chose not to be told about again
+    #[serde(default)]
+    pub skipped_update: Option<String>,
+    /// Mouse and trackpad settings of the editor
+    #[serde(default)]
+    pub input: InputSettings,
+}
+
+impl Preferences {
+    const FILE_NAME: &str = "preferences.json";
+
+    pub fn load() -> Self {
+        let Some(bytes) = platform::read_setting(Self::FILE_NAME) else {
+            return Self::default();
+        };
+        serde_json::from_slice(&bytes).unwrap_or_else(|error| {
+            warn!("Ignoring unreadable preferences in {}: {}", Self::FILE_NAME, error);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let bytes = serde_json::to_vec_pretty(self).map_err(|e| e.to_string())?;
+        platform::write_setting(Self::FILE_NAME, &bytes)
+    }
+}
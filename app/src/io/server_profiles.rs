@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::platform;
+
+/// A server the login page can point the app at, e.g. staging or a self-hosted instance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerProfile {
+    pub name: String,
+    pub url: String,
+}
+
+/// Saved servers and the one used last, in the user's config directory (the page's local
+/// storage in the browser)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServerProfiles {
+    pub profiles: Vec<ServerProfile>,
+    #[serde(default)]
+    pub selected_url: Option<String>,
+}
+
+impl ServerProfiles {
+    const FILE_NAME: &str = "servers.json";
+
+    pub fn load() -> Self {
+        let Some(bytes) = platform::read_setting(Self::FILE_NAME) else {
+            return Self::default();
+        };
+        serde_json::from_slice(&bytes).unwrap_or_else(|error| {
+            warn!("Ignoring unreadable server profiles in {}: {}", Self::FILE_NAME, error);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let bytes = serde_json::to_vec_pretty(self).map_err(|e| e.to_string())?;
+        platform::write_setting(Self::FILE_NAME, &bytes)
+    }
+
+    pub fn find(&self, url: &str) -> Option<&ServerProfile> {
+        self.profiles.iter().find(|profile| profile.url == url)
+    }
+
+    /// Adds a profile, or renames the one with the same URL
+    pub fn upsert(&mut self, name: &str, url: &str) {
+        match self.profiles.iter_mut().find(|profile| profile.url == url) {
+            Some(profile) => profile.name = name.to_string(),
+            None => self.profiles.push(ServerProfile { name: name.to_string(), url: url.to_string() }),
+        }
+    }
+
+    pub fn remove(&mut self, url: &str) {
+        self.profiles.retain(|profile| profile.url != url);
+    }
+}
@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::api::ApiConfig;
+use crate::platform;
+
+/// Login kept in the OS keyring between launches when the user asked to be remembered, in the
+/// page's local storage for the browser build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredSession {
+    pub jwt: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+}
+
+/// One entry per server, so sessions of different servers don't replace each other
+fn account() -> String {
+    ApiConfig::default().base_url
+}
+
+pub fn load() -> Option<StoredSession> {
+    match platform::read_secret(&account()) {
+        Ok(Some(secret)) => match serde_json::from_str(&secret) {
+            Ok(session) => Some(session),
+            Err(error) => {
+                warn!("Ignoring unreadable stored session: {}", error);
+                None
+            }
+        },
+        Ok(None) => None,
+        Err(error) => {
+            warn!("Failed to read the stored session: {}", error);
+            None
+        }
+    }
+}
+
+pub fn save(session: &StoredSession) -> Result<(), String> {
+    let secret = serde_json::to_string(session).map_err(|e| e.to_string())?;
+    platform::write_secret(&account(), &secret)
+}
+
+pub fn clear() {
+    if let Err(error) = platform::delete_secret(&account()) {
+        warn!("Failed to remove the stored session: {}", error);
+    }
+}
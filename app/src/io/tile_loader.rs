@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use std::collections::{HashMap, HashSet};
+use crate::api::tasks::{TasksApi, TileInfo};
+use crate::io::image_loader;
+use crate::platform::{self, Pending};
+
+/// Tiles downloaded at the same time, more only queue up behind the ones on screen
+const MAX_PENDING_TILES: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct TileKey {
+    level: u32,
+    col: u32,
+    row: u32,
+}
+
+struct TileSource {
+    root: Entity,
+    project_id: String,
+    task_id: String,
+    token: String,
+    info: TileInfo,
+}
+
+/// Streams the tiles of a large image instead of loading it whole. Tiles are children of the
+/// image entity, so they go away with it.
+#[derive(Resource, Default)]
+pub struct TileState {
+    source: Option<TileSource>,
+    api: Option<TasksApi>,
+    tiles: HashMap<TileKey, Entity>,
+    pending: HashMap<TileKey, Pending<Result<image::DynamicImage, String>>>,
+    failed: HashSet<TileKey>,
+}
+
+impl TileState {
+    /// Starts streaming a task's pyramid into `root`, dropping whatever the previous task left.
+    pub fn start(&mut self, root: Entity, project_id: String, task_id: String, token: String, info: TileInfo) {
+        self.stop();
+        self.source = Some(TileSource { root, project_id, task_id, token, info });
+    }
+
+    pub fn stop(&mut self) {
+        self.source = None;
+        self.tiles.clear();
+        for (_, handle) in self.pending.drain() {
+            handle.abort();
+        }
+        self.failed.clear();
+    }
+}
+
+/// Spawns the empty entity the tiles of an image are attached to. It sits where the sprite of
+/// a whole image would, so boxes line up the same way.
+pub fn spawn_tile_root(commands: &mut Commands, info: &TileInfo) -> (Entity, Vec2) {
+    let entity = commands.spawn((Transform::default(), Visibility::default())).id();
+    (entity, Vec2::new(info.width as f32, info.height as f32))
+}
+
+/// Finest level worth loading when one screen pixel covers `scale` image pixels.
+fn level_for_scale(info: &TileInfo, scale: f32) -> u32 {
+    let halvings = scale.max(f32::EPSILON).log2().floor().max(0.0) as u32;
+    info.max_level.saturating_sub(halvings).max(info.min_level)
+}
+
+/// Size of a level's tiles in pixels of the full image
+fn tile_span(info: &TileInfo, level: u32) -> f32 {
+    (info.tile_size << (info.max_level - level)) as f32
+}
+
+/// Tiles of `level` overlapping the area between `min` and `max`, in image pixels.
+fn visible_tiles(info: &TileInfo, level: u32, min: Vec2, max: Vec2) -> Vec<TileKey> {
+    let span = tile_span(info, level);
+    let cols = (info.width as f32 / span).ceil() as i64;
+    let rows = (info.height as f32 / span).ceil() as i64;
+
+    let first_col = ((min.x / span).floor() as i64).max(0);
+    let last_col = ((max.x / span).floor() as i64).min(cols - 1);
+    let first_row = ((min.y / span).floor() as i64).max(0);
+    let last_row = ((max.y / span).floor() as i64).min(rows - 1);
+
+    let mut tiles = Vec::new();
+    for row in first_row..=last_row {
+        for col in first_col..=last_col {
+            tiles.push(TileKey { level, col: col as u32, row: row as u32 });
+        }
+    }
+    tiles
+}
+
+/// Area of the full image a tile covers. Edge tiles are cut off at the image border.
+fn tile_rect(info: &TileInfo, key: TileKey) -> Rect {
+    let span = tile_span(info, key.level);
+    let min = Vec2::new(key.col as f32 * span, key.row as f32 * span);
+    let max = (min + span).min(Vec2::new(info.width as f32, info.height as f32));
+    Rect::from_corners(min, max)
+}
+
+/// Loads the tiles in view at the level matching the zoom and drops the ones that left it.
+/// The single-tile overview stays loaded underneath, so panning never shows empty canvas.
+pub fn tile_streaming_system(
+    mut commands: Commands,
+    mut tile_state: ResMut<TileState>,
+    mut images: ResMut<Assets<Image>>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<&Transform, With<Camera>>,
+) {
+    let tile_state = &mut *tile_state;
+    let Some(source) = &tile_state.source else {
+        return;
+    };
+    let info = source.info;
+    let image_size = Vec2::new(info.width as f32, info.height as f32);
+
+    // Place the tiles that finished downloading
+    let finished: Vec<(TileKey, Result<image::DynamicImage, String>)> = tile_state.pending.iter()
+        .filter_map(|(key, pending)| Some((*key, pending.take()?)))
+        .collect();
+    for (key, result) in finished {
+        tile_state.pending.remove(&key);
+
+        match result {
+            Ok(tile) => {
+                let rect = tile_rect(&info, key);
+                let center = rect.center();
+                let sprite = Sprite {
+                    image: images.add(image_loader::create_bevy_image_from_dynamic(tile)),
+                    custom_size: Some(rect.size()),
+                    ..default()
+                };
+                // Finer levels cover the coarser ones below them
+                let z = (key.level - info.min_level) as f32 * 0.001;
+                let entity = commands.spawn((
+                    sprite,
+                    Transform::from_xyz(center.x - image_size.x / 2.0, image_size.y / 2.0 - center.y, z),
+                    ChildOf(source.root),
+                )).id();
+                tile_state.tiles.insert(key, entity);
+            }
+            Err(e) => {
+                error!("Failed to load tile {:?}: {}", key, e);
+                tile_state.failed.insert(key);
+            }
+        }
+    }
+
+    let (Ok(window), Ok(camera)) = (q_window.single(), cameras.single()) else {
+        return;
+    };
+
+    // Viewport in image pixels, whose y axis points down
+    let scale = camera.scale.x;
+    let half_view = Vec2::new(window.width(), window.height()) / 2.0 * scale;
+    let view_center = Vec2::new(camera.translation.x, -camera.translation.y) + image_size / 2.0;
+    let level = level_for_scale(&info, scale);
+
+    let overview = TileKey { level: info.min_level, col: 0, row: 0 };
+    let mut wanted = vec![overview];
+    if level != info.min_level {
+        wanted.extend(visible_tiles(&info, level, view_center - half_view, view_center + half_view));
+    }
+    let wanted_set: HashSet<TileKey> = wanted.iter().copied().collect();
+
+    tile_state.tiles.retain(|key, entity| {
+        let keep = wanted_set.contains(key);
+        if !keep {
+            commands.entity(*entity).despawn();
+        }
+        keep
+    });
+    tile_state.pending.retain(|key, handle| {
+        let keep = wanted_set.contains(key);
+        if !keep {
+            handle.abort();
+        }
+        keep
+    });
+
+    let api = tile_state.api.get_or_insert_with(TasksApi::new);
+    for key in wanted {
+        if tile_state.pending.len() >= MAX_PENDING_TILES {
+            break;
+        }
+        if tile_state.tiles.contains_key(&key) || tile_state.pending.contains_key(&key) || tile_state.failed.contains(&key) {
+            continue;
+        }
+
+        let api = api.clone();
+        let (project_id, task_id, token) = (source.project_id.clone(), source.task_id.clone(), source.token.clone());
+        let pending = platform::spawn_pending(async move {
+            let bytes = api.get_tile(&token, &project_id, &task_id, key.level, key.col, key.row).await
+                .map_err(|e| e.to_string())?;
+            image::load_from_memory(&bytes).map_err(|e| e.to_string())
+        });
+        tile_state.pending.insert(key, pending);
+    }
+}
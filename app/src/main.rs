@@ -1,13 +1,24 @@
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
 
+#[macro_use]
+mod i18n;
 mod api;
 mod app;
 mod auth;
 mod core;
+mod deep_link;
+mod exports;
 mod io;
+mod notifications;
+mod offline;
+mod onboarding;
+mod platform;
+mod progress;
 mod sync;
 mod ui;
+mod updates;
+mod upload;
+mod workspace;
 use app::state::AppState;
 use auth::{AuthState, ProjectsState, UserState};
 use bevy_egui::{EguiContexts, EguiPlugin, egui};
@@ -15,16 +26,26 @@ use bevy_egui::{EguiContexts, EguiPlugin, egui};
 mod pages {
     pub mod detail;
     pub mod login;
+    pub mod profile;
     pub mod project_settings;
     pub mod projects;
+    pub mod reports;
+    pub mod review;
+    pub mod stats;
+    pub mod storage_browser;
     pub mod tasks;
 }
 
 use pages::{
-    detail::DetailPlugin, login::LoginPlugin, project_settings::ProjectSettingsPlugin,
-    projects::ProjectsPlugin, tasks::TasksPlugin,
+    detail::DetailPlugin, login::LoginPlugin, profile::ProfilePlugin,
+    project_settings::ProjectSettingsPlugin, projects::ProjectsPlugin, reports::ReportsPlugin,
+    review::ReviewPlugin, stats::StatsPlugin, storage_browser::StorageBrowserPlugin,
+    tasks::TasksPlugin,
 };
 
+#[cfg(all(target_arch = "wasm32", feature = "file-dialogs"))]
+compile_error!("The browser has no file dialogs, build it with `--no-default-features`");
+
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
@@ -35,13 +56,28 @@ fn main() {
         .init_resource::<AuthState>()
         .init_resource::<UserState>()
         .init_resource::<ProjectsState>()
-        .add_systems(Startup, (setup, setup_fonts, maximize_window))
+        .init_resource::<io::image_cache::ImageCache>()
+        .add_systems(Startup, (setup, setup_fonts))
+        .add_plugins(notifications::NotificationsPlugin)
         .add_plugins(sync::SyncPlugin)
+        .add_plugins(offline::OfflinePlugin)
+        .add_plugins(progress::ProgressPlugin)
+        .add_plugins(onboarding::OnboardingPlugin)
+        .add_plugins(upload::UploadPlugin)
+        .add_plugins(deep_link::DeepLinkPlugin)
+        .add_plugins(workspace::WorkspacePlugin)
+        .add_plugins(updates::UpdatesPlugin)
+        .add_plugins(exports::ExportJobsPlugin)
         .add_plugins(LoginPlugin)
         .add_plugins(TasksPlugin)
         .add_plugins(ProjectsPlugin)
         .add_plugins(ProjectSettingsPlugin)
         .add_plugins(DetailPlugin)
+        .add_plugins(ReportsPlugin)
+        .add_plugins(ReviewPlugin)
+        .add_plugins(StatsPlugin)
+        .add_plugins(StorageBrowserPlugin)
+        .add_plugins(ProfilePlugin)
         .run();
 }
 
@@ -71,9 +107,3 @@ fn setup_fonts(mut contexts: EguiContexts) {
 
     contexts.ctx_mut().set_fonts(fonts);
 }
-
-fn maximize_window(mut windows: Query<&mut Window, With<PrimaryWindow>>) {
-    for mut window in windows.iter_mut() {
-        window.set_maximized(true);
-    }
-}
@@ -0,0 +1,219 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiContextPass, egui};
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+
+/// Seconds a success or info toast stays on screen
+const TOAST_SECS: f64 = 4.0;
+/// Seconds an error toast stays on screen, long enough to read the details
+const ERROR_TOAST_SECS: f64 = 10.0;
+/// Toasts on screen at once, older ones stay in the history only
+const MAX_VISIBLE_TOASTS: usize = 5;
+/// Notifications kept for the history panel
+const HISTORY_LIMIT: usize = 100;
+
+/// Toasts in the top right corner for the outcome of what the user started, written by any page
+/// through the `Notify` event. Every notification also goes to a history panel behind the bell
+/// in the bottom right corner, so messages that were missed can be read again.
+pub struct NotificationsPlugin;
+
+impl Plugin for NotificationsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<Notify>()
+            .init_resource::<Notifications>()
+            .add_systems(Update, collect_notifications_system)
+            .add_systems(EguiContextPass, notifications_ui_system);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NotifyLevel {
+    Success,
+    Info,
+    Error,
+}
+
+impl NotifyLevel {
+    fn icon(self) -> &'static str {
+        match self {
+            NotifyLevel::Success => "✔",
+            NotifyLevel::Info => "ℹ",
+            NotifyLevel::Error => "✖",
+        }
+    }
+
+    fn color(self) -> egui::Color32 {
+        match self {
+            NotifyLevel::Success => egui::Color32::from_rgb(80, 200, 120),
+            NotifyLevel::Info => egui::Color32::from_rgb(100, 160, 255),
+            NotifyLevel::Error => egui::Color32::from_rgb(255, 90, 90),
+        }
+    }
+
+    fn duration_secs(self) -> f64 {
+        match self {
+            NotifyLevel::Error => ERROR_TOAST_SECS,
+            NotifyLevel::Success | NotifyLevel::Info => TOAST_SECS,
+        }
+    }
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct Notify {
+    pub level: NotifyLevel,
+    pub message: String,
+}
+
+impl Notify {
+    pub fn success(message: impl Into<String>) -> Self {
+        Self { level: NotifyLevel::Success, message: message.into() }
+    }
+
+    pub fn info(message: impl Into<String>) -> Self {
+        Self { level: NotifyLevel::Info, message: message.into() }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self { level: NotifyLevel::Error, message: message.into() }
+    }
+}
+
+struct Notification {
+    level: NotifyLevel,
+    message: String,
+    received_at: DateTime<Local>,
+    /// App time when the toast goes away
+    expires_at: f64,
+    dismissed: bool,
+}
+
+#[derive(Resource, Default)]
+pub struct Notifications {
+    /// Oldest first
+    history: VecDeque<Notification>,
+    /// Notifications received since the history panel was last opened
+    unread: usize,
+    show_history: bool,
+}
+
+fn collect_notifications_system(
+    time: Res<Time>,
+    mut events: EventReader<Notify>,
+    mut notifications: ResMut<Notifications>,
+) {
+    let now = time.elapsed_secs_f64();
+    for event in events.read() {
+        if notifications.history.len() == HISTORY_LIMIT {
+            notifications.history.pop_front();
+        }
+        notifications.history.push_back(Notification {
+            level: event.level,
+            message: event.message.clone(),
+            received_at: Local::now(),
+            expires_at: now + event.level.duration_secs(),
+            dismissed: false,
+        });
+        if !notifications.show_history {
+            notifications.unread += 1;
+        }
+    }
+}
+
+fn notifications_ui_system(
+    mut contexts: EguiContexts,
+    time: Res<Time>,
+    mut notifications: ResMut<Notifications>,
+) {
+    let ctx = contexts.ctx_mut();
+    let now = time.elapsed_secs_f64();
+    let notifications = &mut *notifications;
+
+    // Newest on top
+    let visible: Vec<usize> = (0..notifications.history.len())
+        .rev()
+        .filter(|&i| {
+            let notification = &notifications.history[i];
+            !notification.dismissed && notification.expires_at > now
+        })
+        .take(MAX_VISIBLE_TOASTS)
+        .collect();
+
+    if !visible.is_empty() {
+        // Keep repainting so toasts go away on time without input
+        ctx.request_repaint_after(std::time::Duration::from_millis(250));
+
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_TOP, [-8.0, 36.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.set_max_width(360.0);
+                for i in visible {
+                    let notification = &mut notifications.history[i];
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.colored_label(notification.level.color(), notification.level.icon());
+                            ui.add(egui::Label::new(&notification.message).wrap());
+                            if ui.small_button("✕").on_hover_text(t!("notifications-dismiss")).clicked() {
+                                notification.dismissed = true;
+                            }
+                        });
+                    });
+                    ui.add_space(4.0);
+                }
+            });
+    }
+
+    let bell = if notifications.unread > 0 {
+        format!("🔔 {}", notifications.unread)
+    } else {
+        "🔔".to_string()
+    };
+    egui::Area::new(egui::Id::new("notifications_bell"))
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-8.0, -8.0])
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            if ui.button(bell).on_hover_text(t!("notifications-title")).clicked() {
+                notifications.show_history = !notifications.show_history;
+            }
+        });
+
+    if !notifications.show_history {
+        return;
+    }
+    notifications.unread = 0;
+
+    let mut open = true;
+    let mut clear = false;
+    egui::Window::new(t!("notifications-title"))
+        .open(&mut open)
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-8.0, -40.0])
+        .default_width(380.0)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            if notifications.history.is_empty() {
+                ui.weak(t!("notifications-empty"));
+                return;
+            }
+            if ui.button(t!("notifications-clear")).clicked() {
+                clear = true;
+            }
+            ui.separator();
+            egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                for notification in notifications.history.iter().rev() {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(notification.level.color(), notification.level.icon());
+                        ui.weak(notification.received_at.format("%H:%M:%S").to_string());
+                        ui.add(egui::Label::new(&notification.message).wrap());
+                    });
+                }
+            });
+        });
+
+    if clear {
+        notifications.history.clear();
+    }
+    if !open {
+        notifications.show_history = false;
+    }
+}
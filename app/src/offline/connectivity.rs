@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiContextPass, egui};
+use crate::api::connectivity;
+use crate::api::health::HealthApi;
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+
+/// Seconds between checks of the server while it can't be reached
+const CHECK_INTERVAL_SECS: f64 = 10.0;
+
+/// Follows whether the server can be reached and shows a banner while it can't. Requests of
+/// every page report their outcome, and while offline the server is checked regularly so the
+/// banner goes away even when nothing else is requested.
+pub struct ConnectivityPlugin;
+
+impl Plugin for ConnectivityPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins(ApiTaskPlugin::<ServerHealthy>::default())
+            .init_resource::<ConnectivityMonitor>()
+            .add_systems(Update, (
+                monitor_connectivity_system,
+                process_health_checks,
+            ))
+            .add_systems(EguiContextPass, offline_banner_ui_system);
+    }
+}
+
+#[derive(Resource)]
+pub struct ConnectivityMonitor {
+    pub online: bool,
+    /// App time when the connection was lost
+    offline_since: f64,
+    last_check: f64,
+    is_checking: bool,
+    check_requested: bool,
+}
+
+impl Default for ConnectivityMonitor {
+    fn default() -> Self {
+        Self {
+            online: true,
+            offline_since: 0.0,
+            last_check: 0.0,
+            is_checking: false,
+            check_requested: false,
+        }
+    }
+}
+
+impl ConnectivityMonitor {
+    pub fn is_offline(&self) -> bool {
+        !self.online
+    }
+}
+
+/// The health endpoint answered
+pub struct ServerHealthy;
+
+fn monitor_connectivity_system(
+    time: Res<Time>,
+    health_tasks: Res<ApiTasks<ServerHealthy>>,
+    mut monitor: ResMut<ConnectivityMonitor>,
+) {
+    let now = time.elapsed_secs_f64();
+    let online = connectivity::is_reachable();
+    if online != monitor.online {
+        monitor.online = online;
+        monitor.offline_since = now;
+        monitor.last_check = now;
+    }
+
+    if monitor.online || monitor.is_checking {
+        return;
+    }
+    if !monitor.check_requested && now - monitor.last_check < CHECK_INTERVAL_SECS {
+        return;
+    }
+
+    monitor.is_checking = true;
+    monitor.check_requested = false;
+    monitor.last_check = now;
+    // The answer, or the lack of one, reaches `connectivity` like that of any other request
+    health_tasks.spawn(async {
+        HealthApi::new().check().await.map(|_| ServerHealthy).map_err(|e| e.to_string())
+    });
+}
+
+fn process_health_checks(
+    mut succeeded: EventReader<ApiTaskSucceeded<ServerHealthy>>,
+    mut failed: EventReader<ApiTaskFailed<ServerHealthy>>,
+    mut monitor: ResMut<ConnectivityMonitor>,
+) {
+    for _ in succeeded.read() {
+        monitor.is_checking = false;
+    }
+    for failure in failed.read() {
+        debug!("Server still unreachable: {}", failure.error);
+        monitor.is_checking = false;
+    }
+}
+
+fn offline_banner_ui_system(
+    mut contexts: EguiContexts,
+    time: Res<Time>,
+    mut monitor: ResMut<ConnectivityMonitor>,
+) {
+    if monitor.online {
+        return;
+    }
+
+    let now = time.elapsed_secs_f64();
+    let offline_secs = (now - monitor.offline_since).max(0.0) as u64;
+    let next_check_secs = (CHECK_INTERVAL_SECS - (now - monitor.last_check)).max(0.0).ceil() as u64;
+    let mut retry = false;
+
+    egui::Area::new(egui::Id::new("offline_banner"))
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 8.0])
+        .order(egui::Order::Foreground)
+        .show(contexts.ctx_mut(), |ui| {
+            egui::Frame::popup(ui.style())
+                .fill(egui::Color32::from_rgb(90, 70, 0))
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            t!(
+                                "offline-banner",
+                                duration = format!("{}:{:02}", offline_secs / 60, offline_secs % 60),
+                            ),
+                        );
+                        if monitor.is_checking {
+                            ui.add(egui::Spinner::new());
+                        } else {
+                            ui.weak(t!("offline-next-check", seconds = next_check_secs));
+                            if ui.small_button(t!("offline-retry-now")).clicked() {
+                                retry = true;
+                            }
+                        }
+                    });
+                });
+        });
+
+    if retry {
+        monitor.check_requested = true;
+    }
+}
@@ -0,0 +1,226 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiContextPass, egui};
+use uuid::Uuid;
+use crate::api::annotations::AnnotationsApi;
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use crate::auth::AuthState;
+use crate::io::offline_store::{self, QueuedSave, latest_annotation_id};
+use crate::pages::tasks::TasksState;
+
+pub mod connectivity;
+
+pub use connectivity::ConnectivityMonitor;
+
+/// Seconds between attempts to send the saves queued while offline
+const REPLAY_INTERVAL_SECS: f64 = 15.0;
+/// Seconds between reads of the queue for the status window
+const STATUS_REFRESH_SECS: f64 = 1.0;
+
+/// Sends the annotations saved while the server couldn't be reached once it can be again, and
+/// lets the user settle the ones that conflict with changes made on the server meanwhile.
+pub struct OfflinePlugin;
+
+impl Plugin for OfflinePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins(connectivity::ConnectivityPlugin)
+            .add_plugins(ApiTaskPlugin::<ReplaySummary>::default())
+            .init_resource::<OfflineState>()
+            .add_systems(Update, (
+                replay_queue_system,
+                process_replay_results,
+            ))
+            .add_systems(EguiContextPass, offline_status_ui_system);
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct OfflineState {
+    /// Queued saves as of the last refresh
+    pub queued: Vec<QueuedSave>,
+    pub is_replaying: bool,
+    retry_requested: bool,
+    last_replay: f64,
+    last_refresh: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct ReplaySummary {
+    pub sent: usize,
+    pub conflicts: usize,
+}
+
+enum ConflictChoice {
+    KeepMine(Uuid),
+    KeepServer(Uuid),
+}
+
+fn replay_queue_system(
+    time: Res<Time>,
+    auth_state: Res<AuthState>,
+    connectivity: Res<ConnectivityMonitor>,
+    replay_tasks: Res<ApiTasks<ReplaySummary>>,
+    mut offline_state: ResMut<OfflineState>,
+) {
+    let now = time.elapsed_secs_f64();
+    if now - offline_state.last_refresh >= STATUS_REFRESH_SECS {
+        offline_state.queued = offline_store::store().queued_saves();
+        offline_state.last_refresh = now;
+    }
+
+    if offline_state.is_replaying
+        || (!offline_state.retry_requested && now - offline_state.last_replay < REPLAY_INTERVAL_SECS)
+    {
+        return;
+    }
+    if !offline_state.queued.iter().any(|save| !save.has_conflict()) {
+        return;
+    }
+    // The monitor notices when the server is back, sending before that would only fail
+    if connectivity.is_offline() && !offline_state.retry_requested {
+        return;
+    }
+    let Some(token) = auth_state.get_jwt() else {
+        return;
+    };
+
+    offline_state.is_replaying = true;
+    offline_state.retry_requested = false;
+    offline_state.last_replay = now;
+    replay_tasks.spawn(replay_queue(token.clone()));
+}
+
+/// Sends the queued saves without a conflict, oldest first. A task whose latest annotation on
+/// the server is no longer the one the offline edits started from is marked as a conflict
+/// instead of being overwritten.
+async fn replay_queue(token: String) -> Result<ReplaySummary, String> {
+    let store = offline_store::store();
+    let annotations_api = AnnotationsApi::new();
+    let mut summary = ReplaySummary::default();
+
+    for save in store.queued_saves().into_iter().filter(|save| !save.has_conflict()) {
+        let server_annotations = match annotations_api
+            .list_annotations_with_options(&token, save.project_id, save.task_id, true)
+            .await
+        {
+            Ok(annotations) => annotations,
+            Err(error) if error.is_network_error() => return Err(error.to_string()),
+            Err(error) => {
+                warn!("Failed to check task {} before sending its queued annotations: {}", save.task_id, error);
+                continue;
+            }
+        };
+
+        let server_annotation_id = latest_annotation_id(&server_annotations);
+        if server_annotation_id != save.base_annotation_id {
+            warn!("Task {} was changed on the server while offline", save.task_id);
+            store.set_annotations(save.task_id, &server_annotations);
+            store.mark_conflict(save.task_id, server_annotation_id);
+            summary.conflicts += 1;
+            continue;
+        }
+
+        match annotations_api.save_annotations(&token, save.project_id, save.task_id, &save.bounding_boxes).await {
+            Ok(saved) => {
+                if !saved.is_empty() {
+                    store.set_annotations(save.task_id, &saved);
+                }
+                store.remove_sent_save(&save);
+                summary.sent += 1;
+            }
+            Err(error) if error.is_network_error() => return Err(error.to_string()),
+            Err(error) => {
+                warn!("Failed to send queued annotations of task {}: {}", save.task_id, error);
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+fn process_replay_results(
+    mut succeeded: EventReader<ApiTaskSucceeded<ReplaySummary>>,
+    mut failed: EventReader<ApiTaskFailed<ReplaySummary>>,
+    mut offline_state: ResMut<OfflineState>,
+) {
+    for ApiTaskSucceeded(summary) in succeeded.read() {
+        info!("Sent {} queued saves, {} conflicts", summary.sent, summary.conflicts);
+        offline_state.is_replaying = false;
+        offline_state.queued = offline_store::store().queued_saves();
+    }
+
+    for failure in failed.read() {
+        info!("Server still unreachable: {}", failure.error);
+        offline_state.is_replaying = false;
+    }
+}
+
+/// The queued saves in the bottom left corner, with a choice for every task that conflicts with
+/// the server. Being offline is told by the banner of `ConnectivityPlugin`.
+fn offline_status_ui_system(
+    mut contexts: EguiContexts,
+    mut offline_state: ResMut<OfflineState>,
+    tasks_state: Option<Res<TasksState>>,
+) {
+    let store = offline_store::store();
+    if offline_state.queued.is_empty() {
+        return;
+    }
+
+    let task_name = |task_id: Uuid| {
+        tasks_state
+            .as_ref()
+            .and_then(|tasks_state| tasks_state.tasks.iter().find(|task| task.task.id == task_id.to_string()))
+            .map_or_else(|| task_id.to_string(), |task| task.task.name.clone())
+    };
+    let waiting = offline_state.queued.iter().filter(|save| !save.has_conflict()).count();
+    let mut choice = None;
+    let mut retry = false;
+
+    egui::Window::new(t!("offline-status-title"))
+        .title_bar(false)
+        .resizable(false)
+        .anchor(egui::Align2::LEFT_BOTTOM, [8.0, -8.0])
+        .show(contexts.ctx_mut(), |ui| {
+            if waiting > 0 {
+                ui.horizontal(|ui| {
+                    ui.label(t!("offline-waiting", count = waiting));
+                    if offline_state.is_replaying {
+                        ui.add(egui::Spinner::new());
+                    } else if ui.small_button(t!("offline-retry-now")).clicked() {
+                        retry = true;
+                    }
+                });
+            }
+
+            for save in offline_state.queued.iter().filter(|save| save.has_conflict()) {
+                ui.separator();
+                ui.colored_label(
+                    egui::Color32::RED,
+                    t!("offline-conflict", task = task_name(save.task_id)),
+                );
+                ui.horizontal(|ui| {
+                    if ui.button(t!("offline-keep-mine")).clicked() {
+                        choice = Some(ConflictChoice::KeepMine(save.task_id));
+                    }
+                    if ui.button(t!("offline-keep-server")).clicked() {
+                        choice = Some(ConflictChoice::KeepServer(save.task_id));
+                    }
+                });
+            }
+        });
+
+    if let Some(choice) = choice {
+        match choice {
+            ConflictChoice::KeepMine(task_id) => {
+                store.keep_queued_save(task_id);
+                retry = true;
+            }
+            ConflictChoice::KeepServer(task_id) => store.remove_queued_save(task_id),
+        }
+        offline_state.queued = store.queued_saves();
+    }
+    if retry {
+        offline_state.retry_requested = true;
+    }
+}
@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiContextPass, egui};
+use crate::app::state::AppState;
+use crate::io::preferences::Preferences;
+use crate::pages::detail;
+
+/// Guided tour of the detail page on the first visit: a bubble per step pointing at the part of
+/// the page it explains, with the widget outlined. It can be taken again from the F1 cheat sheet.
+pub struct OnboardingPlugin;
+
+impl Plugin for OnboardingPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Tour>()
+            .add_systems(OnEnter(AppState::Detail), start_first_tour_system)
+            .add_systems(
+                EguiContextPass,
+                tour_ui_system
+                    .after(detail::ui_system)
+                    .after(detail::shortcuts_ui_system)
+                    .run_if(in_state(AppState::Detail)),
+            );
+    }
+}
+
+/// Parts of the page the tour points at. Pages mark where they drew them with `mark_target`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TourTarget {
+    Toolbar,
+    ClassPicker,
+    SaveButton,
+}
+
+struct TourStep {
+    /// Steps without a target are shown in the middle of the screen
+    target: Option<TourTarget>,
+    title: &'static str,
+    text: &'static str,
+}
+
+const TOUR_STEPS: [TourStep; 6] = [
+    TourStep { target: None, title: "tour-welcome-title", text: "tour-welcome" },
+    TourStep { target: Some(TourTarget::Toolbar), title: "tour-toolbar-title", text: "tour-toolbar" },
+    TourStep { target: Some(TourTarget::ClassPicker), title: "tour-classes-title", text: "tour-classes" },
+    TourStep { target: None, title: "tour-drawing-title", text: "tour-drawing" },
+    TourStep { target: Some(TourTarget::SaveButton), title: "tour-save-title", text: "tour-save" },
+    TourStep { target: None, title: "tour-help-title", text: "tour-help" },
+];
+
+#[derive(Resource, Default)]
+pub struct Tour {
+    /// Index in `TOUR_STEPS` of the step shown, `None` while there is no tour
+    step: Option<usize>,
+}
+
+impl Tour {
+    pub fn start(&mut self) {
+        self.step = Some(0);
+    }
+
+    /// Ends the tour, skipped or done, so it doesn't start by itself again
+    fn finish(&mut self) {
+        self.step = None;
+        let mut preferences = Preferences::load();
+        if preferences.tour_completed {
+            return;
+        }
+        preferences.tour_completed = true;
+        if let Err(error) = preferences.save() {
+            warn!("Failed to remember the finished tour: {}", error);
+        }
+    }
+}
+
+/// Remembers where a widget the tour points at was drawn in this pass
+pub fn mark_target(ctx: &egui::Context, target: TourTarget, rect: egui::Rect) {
+    let pass = ctx.cumulative_pass_nr();
+    ctx.data_mut(|data| data.insert_temp(egui::Id::new(("tour_target", target)), (pass, rect)));
+}
+
+/// Where the target was drawn, unless it wasn't drawn in this pass, as for the box tools in
+/// classification projects
+fn target_rect(ctx: &egui::Context, target: TourTarget) -> Option<egui::Rect> {
+    let pass = ctx.cumulative_pass_nr();
+    ctx.data(|data| data.get_temp::<(u64, egui::Rect)>(egui::Id::new(("tour_target", target))))
+        .filter(|(marked_pass, _)| *marked_pass == pass)
+        .map(|(_, rect)| rect)
+}
+
+fn start_first_tour_system(mut tour: ResMut<Tour>) {
+    tour.step = (!Preferences::load().tour_completed).then_some(0);
+}
+
+fn tour_ui_system(mut contexts: EguiContexts, mut tour: ResMut<Tour>) {
+    let Some(index) = tour.step else {
+        return;
+    };
+    let Some(step) = TOUR_STEPS.get(index) else {
+        tour.finish();
+        return;
+    };
+
+    let ctx = contexts.ctx_mut();
+    let target = step.target.and_then(|target| target_rect(ctx, target));
+    let mut area = egui::Area::new(egui::Id::new("tour_bubble")).order(egui::Order::Foreground);
+    area = match target {
+        Some(rect) => {
+            ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("tour_highlight")))
+                .rect_stroke(
+                    rect.expand(4.0),
+                    4.0,
+                    egui::Stroke::new(3.0, egui::Color32::from_rgb(255, 200, 0)),
+                    egui::StrokeKind::Outside,
+                );
+            // Below the target when it fits, otherwise above it
+            let screen = ctx.screen_rect();
+            let x = rect.left().clamp(screen.left() + 8.0, (screen.right() - 340.0).max(screen.left()));
+            if rect.bottom() + 200.0 < screen.bottom() {
+                area.pivot(egui::Align2::LEFT_TOP).fixed_pos(egui::pos2(x, rect.bottom() + 12.0))
+            } else {
+                area.pivot(egui::Align2::LEFT_BOTTOM).fixed_pos(egui::pos2(x, rect.top() - 12.0))
+            }
+        }
+        None => area.anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0]),
+    };
+
+    let mut next_step = Some(index);
+    area.show(ctx, |ui| {
+        egui::Frame::popup(ui.style()).show(ui, |ui| {
+            ui.set_max_width(320.0);
+            ui.strong(t!(step.title));
+            ui.add(egui::Label::new(t!(step.text)).wrap());
+            ui.add_space(4.0);
+            ui.weak(t!("tour-step", step = index + 1, count = TOUR_STEPS.len()));
+            ui.horizontal(|ui| {
+                if ui.add_enabled(index > 0, egui::Button::new(t!("tour-back"))).clicked() {
+                    next_step = Some(index - 1);
+                }
+                let last = index + 1 == TOUR_STEPS.len();
+                if ui.button(if last { t!("tour-done") } else { t!("tour-next") }).clicked() {
+                    next_step = Some(index + 1);
+                }
+                if !last && ui.button(t!("tour-skip")).clicked() {
+                    next_step = None;
+                }
+            });
+        });
+    });
+
+    match next_step {
+        Some(step) if step < TOUR_STEPS.len() => tour.step = Some(step),
+        _ => tour.finish(),
+    }
+}
@@ -1,22 +1,51 @@
 use crate::app::state::AppState;
 use crate::core::camera_controls::CameraController;
 use crate::core::commands::{Command, CommandHistory};
+use crate::core::coordinates;
+use crate::core::drawing_aids::{self, DrawingAids, Snap};
+use crate::core::input_settings::InputSettings;
 use crate::core::interactions::{
-    DrawingHandler, GrabbingHandler, InteractionMode, ResizingHandler, key_code_to_class,
+    DrawingHandler, GrabbingHandler, GroupHandler, InteractionMode, ResizingHandler, RotatingHandler,
 };
+use crate::core::labels;
+use crate::core::layers::LayerState;
+use crate::core::masks;
+use crate::core::outlines::{self, BoxOutline};
 use crate::core::rectangle::{Rectangle, rect_color};
-use crate::io::image_loader;
+use crate::core::shortcuts;
+use crate::core::spatial_index::SpatialIndex;
+use crate::core::touch::TouchState;
+use crate::io::image_cache::{ImageCache, ImageSource, PREFETCH_AHEAD};
+use crate::io::image_loader::{self, LoadedImage};
+use crate::io::offline_store;
+use crate::io::tile_loader::{self, TileState};
+use crate::notifications::Notify;
 use crate::ui::components::egui_common;
-use crate::ui::detail_ui;
-use crate::api::categories::CategoriesApi;
-use crate::api::annotations::AnnotationsApi;
+use crate::ui::detail_ui::{self, MergeConflictChoice, NextTask, UnsavedChangesChoice};
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use crate::api::categories::{CategoriesApi, CategoryHotkey};
+use crate::api::annotations::{AnnotationConflict, AnnotationsApi};
+use crate::api::labeling_rules::{self, LabelingRules, LabelingRulesApi, RuleViolation};
+use crate::api::merge::{self, BoxMerge};
+use crate::api::classifications::{ClassificationsApi, TaskClassification};
+use crate::api::segmentation::{PromptPoint, SegmentRequest, SegmentationApi};
+use crate::api::comments::{CommentsApi, CreateCommentRequest, UpdateCommentRequest};
+use crate::api::tasks::{TaskFrame, TaskFramesResponse, TaskWithResolvedUrl, TasksApi, TileInfo};
+pub use crate::api::comments::Comment;
 pub use crate::api::categories::AnnotationCategory;
 pub use crate::api::annotations::{AnnotationWithCategory, BoundingBox};
+use bevy::ecs::system::SystemParam;
+use bevy::input::ButtonState;
+use bevy::input::gestures::PinchGesture;
+use bevy::input::keyboard::KeyboardInput;
 use bevy::input::mouse::{MouseButtonInput, MouseWheel};
+use bevy::input::touch::{TouchInput, Touches};
 use bevy::prelude::*;
+use bevy::sprite::Anchor;
 use bevy::text::Text2d;
 use bevy::window::PrimaryWindow;
 use bevy_egui::{EguiContexts, EguiContextPass};
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
 #[derive(Resource, Default)]
@@ -32,8 +61,21 @@ pub struct DetailData {
     pub image_dimensions: Vec2,
     cursor_position: Option<Vec2>,
     selected_class: usize,
+    /// Search text of the class picker
+    class_filter: String,
     camera_controller: CameraController,
     text_entities: Vec<Entity>,
+    /// Overlay sprite with all masks in `shown_masks`, with the class they are tinted for
+    mask_entity: Option<Entity>,
+    shown_masks: Vec<(serde_json::Value, usize)>,
+    /// Mask stroke in progress in mask mode
+    mask_stroke: Option<MaskStroke>,
+    /// Mesh with the outlines of the boxes that aren't being edited, and what it was built from:
+    /// the outlines and the camera scale
+    outline_mesh: Option<(Entity, Handle<Mesh>)>,
+    shown_outlines: (Vec<BoxOutline>, f32),
+    /// Shown boxes and their labels, back to everything shown whenever the page opens
+    layers: LayerState,
 }
 
 #[derive(Resource, Default)]
@@ -45,227 +87,853 @@ pub struct SelectedRectangleIndex(pub Option<usize>);
 #[derive(Resource, Default)]
 pub struct InteractionState {
     mode: InteractionMode,
+    /// When enabled, a click asks the segmentation server for a tight box instead of drawing
+    pub magic_select: bool,
+    pub magic_select_error: Option<String>,
+    /// A magic select request is on its way, further clicks wait for it
+    magic_select_running: bool,
+    /// Classification projects label whole images, so drawing and editing boxes is disabled
+    pub labels_only: bool,
+    /// Set by the propagate button and handled by `propagate_box_system`
+    pub propagate_requested: bool,
+    /// Brush or eraser of the mask mode, handled by `mask_paint_system`
+    pub mask_painting: masks::MaskPainting,
+}
+
+/// Mask being painted from a mouse press to its release, drawn by its own sprite until then
+struct MaskStroke {
+    /// Box whose mask is painted, `None` for a brush stroke that starts a new box
+    index: Option<usize>,
+    class: usize,
+    bitmap: masks::MaskBitmap,
+    entity: Entity,
+    image: Handle<Image>,
+    /// Last point of the stroke in mask pixels
+    last_point: Vec2,
+}
+
+/// Discussion thread of the current task, shown in the comments side panel
+#[derive(Resource, Default)]
+pub struct CommentsState {
+    pub comments: Vec<Comment>,
+    pub draft: String,
+    /// Anchor the next comment on the selected box
+    pub attach_to_selected: bool,
+    pub reply_to: Option<Uuid>,
+    /// A comment is on its way, posting again waits for it
+    pub posting: bool,
+    pub loaded_task_id: Option<Uuid>,
+    pub error: Option<String>,
+}
+
+/// Whole-image labels of the current task, used instead of boxes in classification projects
+#[derive(Resource, Default)]
+pub struct ClassificationState {
+    /// Category IDs toggled on for the current task
+    pub labels: Vec<Uuid>,
+    pub loaded_task_id: Option<Uuid>,
+    /// Set by the Enter hotkey and handled by the labels window
+    pub save_and_next_requested: bool,
+    pub status: Option<String>,
+    pub error: Option<String>,
+}
+
+impl ClassificationState {
+    pub fn toggle(&mut self, category_id: Uuid) {
+        if let Some(index) = self.labels.iter().position(|id| *id == category_id) {
+            self.labels.remove(index);
+        } else {
+            self.labels.push(category_id);
+        }
+    }
+}
+
+/// Cheat sheet and shortcut editor of the detail page
+#[derive(Resource, Default)]
+pub struct ShortcutState {
+    pub show_cheat_sheet: bool,
+    pub editor_open: bool,
+    /// Key names being edited by category, saved all at once
+    pub draft: HashMap<Uuid, String>,
+    /// Category in the editor waiting for a key press
+    pub capturing: Option<Uuid>,
+    /// The keys are on their way to the server
+    pub saving: bool,
+    pub error: Option<String>,
+}
+
+impl ShortcutState {
+    /// Opens the editor with the keys currently in effect, including the digit defaults.
+    pub fn open_editor(&mut self, categories: &[AnnotationCategory]) {
+        self.draft = shortcuts::class_bindings(categories)
+            .into_iter()
+            .filter_map(|(key, class)| Some((categories.get(class - 1)?.id, shortcuts::key_name(key)?.to_string())))
+            .collect();
+        self.capturing = None;
+        self.error = None;
+        self.editor_open = true;
+    }
+}
+
+/// Seconds without changes before unsaved boxes are saved automatically
+const AUTO_SAVE_DELAY_SECS: f64 = 3.0;
+/// Comparing every box with the saved copy is too much work for every frame
+const UNSAVED_CHECK_INTERVAL_SECS: f64 = 0.5;
+
+/// Boxes of every frame (`None` for images) without their track IDs, which are only handed out on saving
+pub type AnnotationSnapshot = BTreeMap<Option<i32>, Vec<Rectangle>>;
+
+pub fn annotation_snapshot(rectangles: &[Rectangle], video_state: &VideoState) -> AnnotationSnapshot {
+    let untracked = |rects: &[Rectangle]| -> Vec<Rectangle> {
+        rects.iter().map(|rect| Rectangle { track_id: None, ..rect.clone() }).collect()
+    };
+
+    let mut snapshot: AnnotationSnapshot = video_state.frame_rectangles.iter()
+        .filter(|(_, rects)| !rects.is_empty())
+        .map(|(index, rects)| (Some(*index), untracked(rects)))
+        .collect();
+    if !rectangles.is_empty() {
+        snapshot.insert(video_state.current_frame_index(), untracked(rectangles));
+    }
+    snapshot
+}
+
+/// Asks for the boxes of the current task to be saved
+#[derive(Event)]
+pub struct SaveAnnotationsEvent;
+
+/// Auto-save of the detail page and the question about unsaved boxes before leaving it
+#[derive(Resource)]
+pub struct AutoSaveState {
+    pub enabled: bool,
+    /// Whether the boxes differ from `AnnotationState::saved_boxes`, as of the last check
+    pub unsaved: bool,
+    /// Page the user asked for while there were unsaved changes, waiting for an answer
+    pub leave_to: Option<AppState>,
+    pub error: Option<String>,
+    task_id: Option<Uuid>,
+    /// Boxes at the last check, a save waits until they stop changing
+    last_snapshot: Option<AnnotationSnapshot>,
+    last_change: f64,
+    last_check: f64,
+}
+
+impl Default for AutoSaveState {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            unsaved: false,
+            leave_to: None,
+            error: None,
+            task_id: None,
+            last_snapshot: None,
+            last_change: 0.0,
+            last_check: 0.0,
+        }
+    }
+}
+
+/// Default window of the window/level controls, which leaves the pixels as they are
+pub const DEFAULT_WINDOW_WIDTH: f32 = 255.0;
+pub const DEFAULT_WINDOW_LEVEL: f32 = 127.5;
+
+/// How the shown image is drawn, kept from task to task. Only the pixels on screen change.
+#[derive(Resource, Clone, Copy, PartialEq)]
+pub struct ViewAdjustments {
+    /// Added to every channel, as a fraction of the full range
+    pub brightness: f32,
+    /// Scale around mid-gray
+    pub contrast: f32,
+    pub gamma: f32,
+    pub grayscale: bool,
+}
+
+impl Default for ViewAdjustments {
+    fn default() -> Self {
+        Self {
+            brightness: 0.0,
+            contrast: 1.0,
+            gamma: 1.0,
+            grayscale: false,
+        }
+    }
+}
+
+impl ViewAdjustments {
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Frames of a video task or slices of a volume. `Rectangles` holds the boxes of the current frame,
+/// the boxes of every other frame wait in `frame_rectangles` until it is shown again.
+#[derive(Resource)]
+pub struct VideoState {
+    pub frames: Vec<TaskFrame>,
+    /// `None` for volumes, whose frames are slices rather than points in time
+    pub frame_rate: Option<f64>,
+    /// Position in `frames` of the shown frame
+    pub current_frame: usize,
+    /// Slider position of the frame scrubber, applied when the drag ends
+    pub scrub_frame: usize,
+    /// Frame to show on the next update
+    pub pending_frame: Option<usize>,
+    /// Boxes of the frames that are not shown, keyed by frame index
+    pub frame_rectangles: HashMap<i32, Vec<Rectangle>>,
+    /// Copy the boxes of the frame that was left onto frames without boxes, to continue their tracks
+    pub carry_boxes: bool,
+    /// Box to put on the frame shown next in place of the box of its track there
+    pub propagated_box: Option<Rectangle>,
+    /// Annotations to split per frame on the next frame change, loaded with the frames or after interpolating
+    pub pending_annotations: Option<Vec<AnnotationWithCategory>>,
+    /// The frames or the image of a frame are on their way
+    pub loading: bool,
+    /// Window/level of volume slices, in 8-bit pixel values
+    pub window_width: f32,
+    pub window_level: f32,
+    /// Unadjusted pixels of the shown image or slice, with the sprite they belong to
+    original_pixels: Option<(Entity, Vec<u8>)>,
+    /// Sprite, width, level and view adjustments last written to the image
+    applied_view: Option<(Entity, f32, f32, ViewAdjustments)>,
+    pub loaded_task_id: Option<Uuid>,
+    pub error: Option<String>,
+}
+
+impl Default for VideoState {
+    fn default() -> Self {
+        Self {
+            frames: Vec::new(),
+            frame_rate: None,
+            current_frame: 0,
+            scrub_frame: 0,
+            pending_frame: None,
+            frame_rectangles: HashMap::new(),
+            carry_boxes: false,
+            propagated_box: None,
+            pending_annotations: None,
+            loading: false,
+            window_width: DEFAULT_WINDOW_WIDTH,
+            window_level: DEFAULT_WINDOW_LEVEL,
+            original_pixels: None,
+            applied_view: None,
+            loaded_task_id: None,
+            error: None,
+        }
+    }
+}
+
+impl VideoState {
+    pub fn is_video(&self) -> bool {
+        !self.frames.is_empty()
+    }
+
+    /// Slice stacks (multi-page TIFF, DICOM) are multi-frame tasks without a frame rate
+    pub fn is_volume(&self) -> bool {
+        self.is_video() && self.frame_rate.is_none()
+    }
+
+    pub fn current_frame_index(&self) -> Option<i32> {
+        self.frames.get(self.current_frame).map(|frame| frame.frame_index)
+    }
+}
+
+/// Issue flag of the current task, edited from the tools window
+#[derive(Resource, Default)]
+pub struct TaskFlagState {
+    pub current_reason: Option<String>,
+    /// Index into `FLAG_REASONS` of the reason picker
+    pub selected_reason: usize,
+    pub note: String,
+    pub loaded_task_id: Option<Uuid>,
+    pub error: Option<String>,
 }
 
 #[derive(Resource, Default)]
 pub struct InteractionHandlers {
+    rotating: RotatingHandler,
     resizing: ResizingHandler,
     grabbing: GrabbingHandler,
     drawing: DrawingHandler,
+    group: GroupHandler,
+    /// Where the boxes are, for the hit-testing of the handlers. Synced at the start of `update`.
+    spatial_index: SpatialIndex,
 }
 
 #[derive(Default, Reflect, GizmoConfigGroup)]
 pub struct SelectedRect;
 
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct SuggestionRect;
+
+/// Image of a task, ready to be spawned
+pub enum TaskImage {
+    /// Too large to load whole, the tiles in view are streamed by `tile_loader`
+    Tiles {
+        info: TileInfo,
+        project_id: Uuid,
+        task_id: Uuid,
+        token: String,
+    },
+    Whole(LoadedImage),
+    /// The image couldn't be loaded, a placeholder keeps the page usable
+    Missing,
+}
+
+/// Categories, labeling rules and boxes of the task the page opened on
+pub struct TaskBoxes {
+    categories: Vec<AnnotationCategory>,
+    labeling_rules: LabelingRules,
+    annotations: Vec<AnnotationWithCategory>,
+}
+
+/// Task loaded by `start_task_load`, shown by `apply_loaded_task_system`
+pub struct LoadedTask {
+    image: TaskImage,
+    /// `None` when moving on to the next task, or when the categories couldn't be loaded
+    boxes: Option<TaskBoxes>,
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn setup(
     mut commands: Commands,
     params: Res<Parameters>,
-    mut images: ResMut<Assets<Image>>,
     mut config_store: ResMut<GizmoConfigStore>,
     mut annotation_state: ResMut<AnnotationState>,
     auth_state: Res<crate::auth::AuthState>,
-    q_window: Query<&Window, With<PrimaryWindow>>,
-    mut camera_transforms: Query<&mut Transform, With<Camera>>,
+    projects_state: Res<crate::auth::ProjectsState>,
+    image_cache: Res<ImageCache>,
+    task_loads: Res<ApiTasks<LoadedTask>>,
 ) {
     println!("detail setup");
 
-    // load image
+    // The editor opens once the image is there, see apply_loaded_task_system
     println!("url {:?}", params.url);
-    let (image_entity, image_dimensions) =
-        match image_loader::spawn_image_sprite(&mut commands, &mut images, &params.url) {
-            Ok((entity, dimensions)) => {
-                println!("Image loaded successfully with dimensions: {:?}", dimensions);
-                (entity, dimensions)
-            },
-            Err(e) => {
-                eprintln!("load_image error: {}", e);
-                eprintln!("Failed to load image from URL: {}", params.url);
-                // Create a placeholder entity even when image loading fails
-                // This prevents the DetailData resource from not being created
-                (commands.spawn(Sprite::default()).id(), Vec2::new(100.0, 100.0))
-            }
-        };
+    if params.project_id.is_some() && auth_state.get_jwt().is_none() {
+        warn!("No JWT token available for loading categories");
+    }
+    start_task_load(&task_loads, &image_cache, &params.url, params.project_id, params.task_id, auth_state.get_jwt(), true);
 
     // gizmo config
     let (config, _) = config_store.config_mut::<DefaultGizmoConfigGroup>();
-    config.line.width = 3.;
+    config.line.width = outlines::OUTLINE_WIDTH;
     let (selected_rect_config, _) = config_store.config_mut::<SelectedRect>();
     selected_rect_config.line.width = 5.;
     selected_rect_config.line.style = GizmoLineStyle::Dashed {
         gap_scale: 3.0,
         line_scale: 3.0,
     };
-
-    // Calculate initial zoom to fit image in window
-    let mut camera_controller = CameraController::default();
-    
-    if let Ok(window) = q_window.single() {
-        let window_size = Vec2::new(window.width(), window.height());
-        
-        // Calculate zoom level to fit image with 30% padding on each side
-        let padding_factor = 0.4; // 40% of window size (30% padding on each side)
-        let zoom_x = (window_size.x * padding_factor) / image_dimensions.x;
-        let zoom_y = (window_size.y * padding_factor) / image_dimensions.y;
-        let initial_zoom = zoom_x.min(zoom_y).clamp(0.1, 10.0);
-        
-        // Set initial zoom level
-        camera_controller.zoom_level = initial_zoom;
-        
-        // Apply initial zoom to camera
-        if let Ok(mut camera_transform) = camera_transforms.single_mut() {
-            camera_transform.scale = Vec3::splat(1.0 / initial_zoom);
-        }
-    }
-    
-    // add resource - always create this even if image loading failed
-    commands.insert_resource(DetailData {
-        image_entity,
-        image_dimensions,
-        selected_class: 1,
-        cursor_position: None,
-        camera_controller,
-        text_entities: Vec::new(),
-    });
+    let (suggestion_config, _) = config_store.config_mut::<SuggestionRect>();
+    suggestion_config.line.width = 2.;
+    suggestion_config.line.style = GizmoLineStyle::Dashed {
+        gap_scale: 2.0,
+        line_scale: 2.0,
+    };
 
     commands.insert_resource(Rectangles::default());
     commands.insert_resource(SelectedRectangleIndex::default());
-    commands.insert_resource(InteractionState::default());
+    let labels_only = params.project_id.is_some_and(|project_id| {
+        projects_state
+            .projects
+            .iter()
+            .any(|project| project.id == project_id.to_string() && project.is_classification())
+    });
+    commands.insert_resource(InteractionState {
+        labels_only,
+        ..Default::default()
+    });
     commands.insert_resource(InteractionHandlers::default());
     commands.insert_resource(CommandHistory::default());
     
+    // Taken again once the boxes of the task are loaded
+    annotation_state.saved_boxes = None;
+    annotation_state.pending_merge = None;
+    annotation_state.merged_annotations = None;
+    // Requests of an earlier visit which came back after the page was left
+    annotation_state.is_saving = false;
+    annotation_state.is_loading_next_task = false;
+    annotation_state.save_requested = None;
+    annotation_state.reload_requested = false;
+
     // Set current task and project IDs for annotation system
     if let Some(task_id) = params.task_id {
         annotation_state.current_task_id = Some(task_id);
     }
     if let Some(project_id) = params.project_id {
         annotation_state.current_project_id = Some(project_id);
-        
-        // Load categories for this project
-        if let Some(token) = auth_state.get_jwt() {
-            let categories_api = CategoriesApi::new();
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            match rt.block_on(categories_api.list_categories(token, project_id)) {
-                Ok(categories) => {
-                    annotation_state.categories = categories.clone();
-                    info!("Loaded categories for project: {}", project_id);
-                    
-                    // Automatically load existing annotations
-                    if let Some(task_id) = params.task_id {
-                        match annotation_client::load_annotations(project_id, task_id, token.to_string(), true) {
-                            Ok(annotations) => {
-                                info!("Automatically loaded {} annotations", annotations.len());
-                                info!("Auto-loaded annotations JSON: {}", serde_json::to_string_pretty(&annotations).unwrap_or_else(|_| "Failed to serialize".to_string()));
-                                
-                                // Convert loaded annotations to rectangles
-                                let mut loaded_rectangles = Vec::new();
-                                for annotation in annotations {
-                                    let x = annotation.bbox[0] as f32;
-                                    let y = annotation.bbox[1] as f32;
-                                    let width = annotation.bbox[2] as f32;
-                                    let height = annotation.bbox[3] as f32;
-                                    
-                                    // Convert from COCO format (top-left origin) to Bevy format (center origin)
-                                    let center_x = x + width / 2.0 - image_dimensions.x / 2.0;
-                                    let center_y = image_dimensions.y / 2.0 - (y + height / 2.0);
-                                    
-                                    // Calculate corner positions for Rectangle
-                                    let half_width = width / 2.0;
-                                    let half_height = height / 2.0;
-                                    let start = Vec2::new(center_x - half_width, center_y - half_height);
-                                    let end = Vec2::new(center_x + half_width, center_y + half_height);
-                                    
-                                    // Find the class index from category_id
-                                    let class = if let Some(cat_id) = annotation.category_id {
-                                        if let Some(category_index) = categories.iter().position(|cat| cat.id == cat_id) {
-                                            (category_index % 9) + 1  // Convert 0-based index to 1-based class (1-9)
-                                        } else {
-                                            1  // Default to class 1 if category not found
-                                        }
-                                    } else {
-                                        1  // Default to class 1 if no category
-                                    };
-                                    
-                                    let rect = Rectangle::new(class, start, end);
-                                    loaded_rectangles.push(rect);
-                                }
-                                
-                                // Update rectangles resource
-                                commands.insert_resource(Rectangles(loaded_rectangles.clone()));
-                                info!("Converted and loaded {} rectangles", loaded_rectangles.len());
-                            }
-                            Err(error) => {
-                                // Don't treat this as fatal - annotations might not exist yet
-                                info!("No existing annotations found or failed to load: {}", error);
-                            }
-                        }
-                    }
+    }
+}
+
+/// Loads the image of a task in the background: the tile pyramid's layout when the server has
+/// one for it, the whole image otherwise. With `load_boxes` the categories and labeling rules
+/// of the project and the boxes of the task come along. Only the last load started is shown.
+fn start_task_load(
+    task_loads: &ApiTasks<LoadedTask>,
+    image_cache: &ImageCache,
+    url: &str,
+    project_id: Option<Uuid>,
+    task_id: Option<Uuid>,
+    token: Option<&String>,
+    load_boxes: bool,
+) {
+    if let (Some(project_id), Some(task_id), Some(token)) = (project_id, task_id, token) {
+        image_cache.set_source(url, ImageSource {
+            project_id: project_id.to_string(),
+            task_id: task_id.to_string(),
+            token: token.clone(),
+        });
+    }
+    // Only downloads once awaited, which tiled images never are
+    let whole_image = image_loader::load_image_from_url(url, image_cache);
+    let (url, token) = (url.to_string(), token.cloned());
+
+    task_loads.cancel_all();
+    task_loads.spawn(async move {
+        let mut tiles = None;
+        if let (Some(project_id), Some(task_id), Some(token)) = (project_id, task_id, &token) {
+            match annotation_client::load_tile_info(project_id, task_id, token.clone()).await {
+                Ok(info) => tiles = info.map(|info| TaskImage::Tiles { info, project_id, task_id, token: token.clone() }),
+                Err(error) => error!("Failed to load tile info: {}", error),
+            }
+        }
+        let image = match tiles {
+            Some(tiles) => tiles,
+            None => match whole_image.await {
+                Ok(image) => {
+                    println!("Image loaded successfully with dimensions: {}x{}", image.width(), image.height());
+                    TaskImage::Whole(LoadedImage::from_dynamic(image))
                 }
-                Err(error) => {
-                    error!("Failed to load categories: {}", error);
+                Err(e) => {
+                    eprintln!("load_image error: {}", e);
+                    eprintln!("Failed to load image from URL: {}", url);
+                    TaskImage::Missing
                 }
+            },
+        };
+
+        let boxes = match (load_boxes, project_id, token) {
+            (true, Some(project_id), Some(token)) => load_task_boxes(project_id, task_id, token).await,
+            _ => None,
+        };
+        Ok(LoadedTask { image, boxes })
+    });
+}
+
+async fn load_task_boxes(project_id: Uuid, task_id: Option<Uuid>, token: String) -> Option<TaskBoxes> {
+    let result = CategoriesApi::new().list_categories(&token, project_id).await;
+    let categories = match offline_store::store().categories(project_id, result) {
+        Ok(categories) => {
+            info!("Loaded categories for project: {}", project_id);
+            categories
+        }
+        Err(error) => {
+            error!("Failed to load categories: {}", error);
+            return None;
+        }
+    };
+
+    // Without the rules boxes are only checked by the server
+    let labeling_rules = LabelingRulesApi::new().get_rules(&token, project_id).await
+        .unwrap_or_else(|error| {
+            warn!("Failed to load labeling rules: {}", error);
+            LabelingRules::default()
+        });
+
+    // Automatically load existing annotations
+    let mut annotations = Vec::new();
+    if let Some(task_id) = task_id {
+        match annotation_client::load_annotations(project_id, task_id, token, true).await {
+            Ok(loaded) => {
+                info!("Automatically loaded {} annotations", loaded.len());
+                info!("Auto-loaded annotations JSON: {}", serde_json::to_string_pretty(&loaded).unwrap_or_else(|_| "Failed to serialize".to_string()));
+                annotations = loaded;
             }
-        } else {
-            warn!("No JWT token available for loading categories");
+            Err(error) => {
+                // Don't treat this as fatal - annotations might not exist yet
+                info!("No existing annotations found or failed to load: {}", error);
+            }
+        }
+    }
+    Some(TaskBoxes { categories, labeling_rules, annotations })
+}
+
+/// Zoom that fits an image into the window with 30% padding on each side
+fn fit_zoom(q_window: &Query<&Window, With<PrimaryWindow>>, image_dimensions: Vec2) -> Option<f32> {
+    let window = q_window.single().ok()?;
+    let window_size = Vec2::new(window.width(), window.height());
+
+    let padding_factor = 0.4; // 40% of window size (30% padding on each side)
+    let zoom_x = (window_size.x * padding_factor) / image_dimensions.x;
+    let zoom_y = (window_size.y * padding_factor) / image_dimensions.y;
+    Some(zoom_x.min(zoom_y).clamp(0.1, 10.0))
+}
+
+/// Shows the task `start_task_load` loaded: spawns its image in place of the last one, fits the
+/// camera to it and puts up its boxes. The first task of the page creates `DetailData`, which
+/// the editor waits for.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_loaded_task_system(
+    mut commands: Commands,
+    mut loaded_tasks: EventReader<ApiTaskSucceeded<LoadedTask>>,
+    detail_data: Option<ResMut<DetailData>>,
+    mut annotation_state: ResMut<AnnotationState>,
+    mut rectangles: ResMut<Rectangles>,
+    mut images: ResMut<Assets<Image>>,
+    mut tile_state: ResMut<TileState>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut camera_transforms: Query<&mut Transform, With<Camera>>,
+) {
+    let Some(ApiTaskSucceeded(loaded)) = loaded_tasks.read().last() else {
+        return;
+    };
+
+    let (image_entity, image_dimensions) = match &loaded.image {
+        TaskImage::Tiles { info, project_id, task_id, token } => {
+            info!("Streaming {}x{} image as tiles", info.width, info.height);
+            let (entity, dimensions) = tile_loader::spawn_tile_root(&mut commands, info);
+            tile_state.start(entity, project_id.to_string(), task_id.to_string(), token.clone(), *info);
+            (entity, dimensions)
+        }
+        TaskImage::Whole(image) => {
+            tile_state.stop();
+            image_loader::spawn_image_sprite(&mut commands, &mut images, image)
+        }
+        TaskImage::Missing => {
+            tile_state.stop();
+            (commands.spawn(Sprite::default()).id(), Vec2::new(100.0, 100.0))
+        }
+    };
+
+    let zoom = fit_zoom(&q_window, image_dimensions);
+    if let (Some(zoom), Ok(mut camera_transform)) = (zoom, camera_transforms.single_mut()) {
+        camera_transform.scale = Vec3::splat(1.0 / zoom);
+    }
+
+    match detail_data {
+        Some(mut detail_data) => {
+            commands.entity(detail_data.image_entity).despawn();
+            detail_data.image_entity = image_entity;
+            detail_data.image_dimensions = image_dimensions;
+            if let Some(zoom) = zoom {
+                detail_data.camera_controller.zoom_level = zoom;
+            }
+            info!("Successfully switched to next task");
+        }
+        None => {
+            let mut camera_controller = CameraController::default();
+            if let Some(zoom) = zoom {
+                camera_controller.zoom_level = zoom;
+            }
+            commands.insert_resource(DetailData {
+                image_entity,
+                image_dimensions,
+                selected_class: 1,
+                class_filter: String::new(),
+                cursor_position: None,
+                camera_controller,
+                text_entities: Vec::new(),
+                mask_entity: None,
+                shown_masks: Vec::new(),
+                mask_stroke: None,
+                outline_mesh: None,
+                shown_outlines: (Vec::new(), 0.0),
+                layers: LayerState::default(),
+            });
         }
     }
+
+    if let Some(boxes) = &loaded.boxes {
+        annotation_state.categories = boxes.categories.clone();
+        annotation_state.labeling_rules = boxes.labeling_rules.clone();
+        annotation_state.rule_violations.clear();
+        rectangles.0 = boxes.annotations
+            .iter()
+            .filter_map(|annotation| annotation_to_rectangle(annotation, &boxes.categories, image_dimensions))
+            .collect();
+        info!("Converted and loaded {} rectangles", rectangles.0.len());
+    }
+    // Taken again from the boxes just put up
+    annotation_state.saved_boxes = None;
+    annotation_state.is_loading_next_task = false;
+}
+
+/// Page shown until the first image of the editor is there
+pub fn loading_ui_system(
+    mut contexts: EguiContexts,
+    current_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    egui_common::ui_top_panel(&mut contexts, current_state, &mut next_state);
+    detail_ui::render_task_loading(&mut contexts);
+}
+
+/// Converts a loaded COCO box into a Bevy-space rectangle of the matching class. `None` when the
+/// box doesn't have four values.
+fn annotation_to_rectangle(
+    annotation: &AnnotationWithCategory,
+    categories: &[AnnotationCategory],
+    image_dimensions: Vec2,
+) -> Option<Rectangle> {
+    let Ok(bbox) = <&[f64; 4]>::try_from(annotation.bbox.as_slice()) else {
+        warn!("Skipping box {} with {} values", annotation.id, annotation.bbox.len());
+        return None;
+    };
+
+    // Find the class index from category_id
+    let class = if let Some(cat_id) = annotation.category_id {
+        if let Some(category_index) = categories.iter().position(|cat| cat.id == cat_id) {
+            category_index + 1  // Convert 0-based index to 1-based class
+        } else {
+            1  // Default to class 1 if category not found
+        }
+    } else {
+        1  // Default to class 1 if no category
+    };
+    
+    let (start, end) = coordinates::bbox_to_rectangle(bbox, class, image_dimensions).position;
+    let mut rect = if annotation.is_prediction {
+        Rectangle::new_suggestion(class, start, end, annotation.confidence.unwrap_or(0.0) as f32)
+    } else {
+        Rectangle::new(class, start, end)
+    };
+    rect.attributes = annotation.attributes.as_object().cloned().unwrap_or_default();
+    // The API rotates clockwise in image space (y down), Bevy counter-clockwise (y up)
+    rect.rotation = -(annotation.rotation as f32).to_radians();
+    rect.track_id = annotation.track_id;
+    rect.interpolated = annotation.is_interpolated;
+    rect.mask = annotation.mask.clone();
+    Some(rect)
 }
 
+/// Draws the selected boxes, which change from frame to frame while they are edited. The other
+/// boxes are drawn by `box_outlines_system`.
 fn draw_rectangles(
     rectangles: &Rectangles,
     selected_index: &SelectedRectangleIndex,
-    gizmos: &mut Gizmos,
+    group: &[usize],
+    layers: &LayerState,
     selected_rect_gizmos: &mut Gizmos<SelectedRect>,
 ) {
     let current_selected = selected_index.0;
-    for (index, rect) in rectangles.0.iter().enumerate() {
+    for (index, rect) in rectangles.0.iter().enumerate().filter(|(_, rect)| layers.is_visible(rect)) {
         let is_selected = current_selected == Some(index);
         let color = rect_color(rect.class);
         
-        // Draw rectangle
+        // Draw rectangle (unselected suggestions are drawn by draw_suggestions)
+        if is_selected || group.contains(&index) {
+            selected_rect_gizmos.rect_2d(rect.isometry(), rect.size(), color);
+        }
         if is_selected {
-            selected_rect_gizmos.rect_2d(rect.center(), rect.size(), color);
-        } else {
-            gizmos.rect_2d(rect.center(), rect.size(), color);
+            // Rotation handle above the top edge
+            let (pos1, pos2) = rect.position;
+            let top_center = rect.to_world(Vec2::new(rect.center().x, pos1.y.max(pos2.y)));
+            let handle = rect.rotation_handle();
+            selected_rect_gizmos.line_2d(top_center, handle, color);
+            selected_rect_gizmos.circle_2d(handle, 5.0, color);
         }
     }
 }
 
-fn update_text_entities(
-    commands: &mut Commands,
-    detail_data: &mut DetailData,
-    rectangles: &Rectangles,
+/// Draws the boxes that are neither selected nor suggestions as one mesh, which is only rebuilt
+/// when they or the zoom change. Redrawing hundreds of them with gizmos every frame costs too
+/// much on dense scenes.
+pub fn box_outlines_system(
+    mut commands: Commands,
+    mut detail_data: ResMut<DetailData>,
+    rectangles: Res<Rectangles>,
+    selected_index: Res<SelectedRectangleIndex>,
+    handlers: Res<InteractionHandlers>,
+    cameras: Query<&Transform, With<Camera>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let camera_scale = cameras.single().map_or(1.0, |transform| transform.scale.x);
+    let layers = &detail_data.layers;
+    let idle: Vec<BoxOutline> = rectangles.0.iter()
+        .enumerate()
+        .filter(|(index, rect)| {
+            layers.is_visible(rect)
+                && !rect.is_suggestion()
+                && selected_index.0 != Some(*index)
+                && !handlers.group.indices.contains(index)
+        })
+        .map(|(_, rect)| BoxOutline::of(rect))
+        .collect();
+    if detail_data.shown_outlines.0 == idle && detail_data.shown_outlines.1 == camera_scale {
+        return;
+    }
+
+    let mesh = outlines::outline_mesh(&idle, outlines::OUTLINE_WIDTH * camera_scale);
+    match &detail_data.outline_mesh {
+        Some((_, handle)) => {
+            if let Some(existing) = meshes.get_mut(handle) {
+                *existing = mesh;
+            }
+        }
+        None => {
+            let handle = meshes.add(mesh);
+            let entity = commands.spawn((
+                Mesh2d(handle.clone()),
+                MeshMaterial2d(materials.add(ColorMaterial::default())),
+                Transform::from_xyz(0.0, 0.0, outlines::OUTLINE_Z),
+            )).id();
+            detail_data.outline_mesh = Some((entity, handle));
+        }
+    }
+    detail_data.shown_outlines = (idle, camera_scale);
+}
+
+pub fn draw_suggestions(
+    rectangles: Res<Rectangles>,
+    selected_index: Res<SelectedRectangleIndex>,
+    handlers: Res<InteractionHandlers>,
+    detail_data: Res<DetailData>,
+    mut suggestion_gizmos: Gizmos<SuggestionRect>,
+) {
+    for (index, rect) in rectangles.0.iter().enumerate().filter(|(_, rect)| detail_data.layers.is_visible(rect)) {
+        if rect.is_suggestion() && selected_index.0 != Some(index) && !handlers.group.indices.contains(&index) {
+            suggestion_gizmos.rect_2d(rect.isometry(), rect.size(), rect_color(rect.class));
+        }
+    }
+}
+
+/// Draws the masks of the visible boxes over the image, tinted with the color of their class.
+/// All masks share one overlay, only rebuilt when the shown masks change. The mask being painted
+/// is drawn by its stroke instead.
+pub fn mask_overlay_system(
+    mut commands: Commands,
+    mut detail_data: ResMut<DetailData>,
+    rectangles: Res<Rectangles>,
+    mut images: ResMut<Assets<Image>>,
+    mut sprites: Query<&mut Sprite>,
 ) {
-    // Clear existing text entities
-    for entity in detail_data.text_entities.drain(..) {
+    let opacity = Color::WHITE.with_alpha(detail_data.layers.mask_opacity);
+    for entity in detail_data.mask_entity.iter().chain(detail_data.mask_stroke.as_ref().map(|stroke| &stroke.entity)) {
+        if let Ok(mut sprite) = sprites.get_mut(*entity) {
+            if sprite.color != opacity {
+                sprite.color = opacity;
+            }
+        }
+    }
+
+    let layers = &detail_data.layers;
+    let painted = detail_data.mask_stroke.as_ref().and_then(|stroke| stroke.index);
+    let visible_masks = || rectangles.0.iter()
+        .enumerate()
+        .filter(|(index, rect)| layers.is_visible(rect) && Some(*index) != painted)
+        .filter_map(|(_, rect)| Some((rect.mask.as_ref()?, rect.class)));
+    if visible_masks().eq(detail_data.shown_masks.iter().map(|(mask, class)| (mask, *class))) {
+        return;
+    }
+    let shown: Vec<(serde_json::Value, usize)> = visible_masks().map(|(mask, class)| (mask.clone(), class)).collect();
+
+    if let Some(entity) = detail_data.mask_entity.take() {
         commands.entity(entity).despawn();
     }
-    
-    // Create new text entities for each rectangle
-    for (index, rect) in rectangles.0.iter().enumerate() {
-        let top_left = Vec2::new(
-            rect.position.0.x.min(rect.position.1.x),
-            rect.position.0.y.max(rect.position.1.y)
-        );
-        let text_position = top_left + Vec2::new(15.0, -15.0);
-        
-        let text_entity = commands.spawn((
-            Text2d::new(index.to_string()),
+    let mut decoded: Vec<(UVec2, Vec<u32>, Color)> = Vec::new();
+    for (mask, class) in &shown {
+        match masks::decode_mask(mask) {
+            Some((size, runs)) => decoded.push((size, runs, rect_color(*class).into())),
+            None => warn!("Skipping malformed mask"),
+        }
+    }
+    // Masks are as large as the image, so they all go in the texture of the first
+    if let Some(&(size, _, _)) = decoded.first() {
+        let image = images.add(masks::masks_image(
+            size,
+            decoded.iter()
+                .filter(|(mask_size, _, _)| *mask_size == size)
+                .map(|(_, runs, color)| (runs.as_slice(), *color)),
+        ));
+        // The overlay covers the whole image, which is centered on the origin
+        let entity = commands.spawn((
+            Sprite {
+                image,
+                color: opacity,
+                custom_size: Some(detail_data.image_dimensions),
+                ..default()
+            },
+            Transform::from_xyz(0.0, 0.0, masks::MASK_Z),
+        )).id();
+        detail_data.mask_entity = Some(entity);
+    }
+    detail_data.shown_masks = shown;
+}
+
+/// Keeps one text tag per box next to it: spawns and despawns tags as boxes come and go,
+/// writes their text, keeps them the same size on screen at every zoom and moves them out of
+/// each other's way.
+pub fn box_labels_system(
+    mut commands: Commands,
+    mut detail_data: ResMut<DetailData>,
+    rectangles: Res<Rectangles>,
+    annotation_state: Res<AnnotationState>,
+    cameras: Query<&Transform, (With<Camera>, Without<Text2d>)>,
+    mut tags: Query<(&mut Text2d, &mut TextColor, &mut Transform, &mut Visibility), Without<Camera>>,
+) {
+    let count = rectangles.0.len();
+    if detail_data.text_entities.len() > count {
+        for entity in detail_data.text_entities.split_off(count) {
+            commands.entity(entity).despawn();
+        }
+    }
+    while detail_data.text_entities.len() < count {
+        let entity = commands.spawn((
+            Text2d::default(),
             TextFont {
-                font_size: 20.0,
+                font_size: labels::LABEL_FONT_SIZE,
                 ..default()
             },
-            TextColor(Color::WHITE),
-            Transform::from_translation(text_position.extend(10.0)),
+            Anchor::BottomLeft,
+            Visibility::Hidden,
         )).id();
-        
-        detail_data.text_entities.push(text_entity);
+        detail_data.text_entities.push(entity);
+    }
+
+    let camera_scale = cameras.single().map_or(1.0, |transform| transform.scale.x);
+    let layers = &detail_data.layers;
+    let shown = layers.label_opacity > 0.0;
+    let texts: Vec<Option<String>> = rectangles.0.iter()
+        .enumerate()
+        .map(|(index, rect)| {
+            (shown && layers.is_visible(rect)).then(|| {
+                let category_name = rect.class
+                    .checked_sub(1)
+                    .and_then(|category_index| annotation_state.categories.get(category_index))
+                    .map(|category| category.name.as_str());
+                labels::label_text(index, rect, category_name, layers.show_attributes)
+            })
+        })
+        .collect();
+    let sizes: Vec<Option<(Rect, Vec2)>> = rectangles.0.iter()
+        .zip(&texts)
+        .map(|(rect, text)| Some((rect.world_bounds(), labels::label_size(text.as_ref()?, camera_scale))))
+        .collect();
+    let positions = labels::place_labels(&sizes, camera_scale);
+
+    for (((rect, text), position), entity) in rectangles.0.iter().zip(texts).zip(positions).zip(&detail_data.text_entities) {
+        // Tags spawned this frame exist from the next one on
+        let Ok((mut label, mut color, mut transform, mut visibility)) = tags.get_mut(*entity) else {
+            continue;
+        };
+        let (Some(text), Some(position)) = (text, position) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+
+        if label.0 != text {
+            label.0 = text;
+        }
+        let tag_color: Color = rect_color(rect.class).into();
+        color.set_if_neq(TextColor(tag_color.with_alpha(layers.label_opacity)));
+        transform.set_if_neq(
+            Transform::from_translation(position.extend(10.0)).with_scale(Vec3::splat(camera_scale)),
+        );
+        visibility.set_if_neq(Visibility::Inherited);
     }
 }
 
 #[allow(clippy::too_many_arguments)]
 pub fn update(
-    mut commands: Commands,
     cameras: Query<(&Camera, &GlobalTransform)>,
     q_window: Query<&Window, With<PrimaryWindow>>,
     mut gizmos: Gizmos,
@@ -277,25 +945,29 @@ pub fn update(
     mut interaction_state: ResMut<InteractionState>,
     mut handlers: ResMut<InteractionHandlers>,
     mut command_history: ResMut<CommandHistory>,
+    drawing_aids: Res<DrawingAids>,
+    input_settings: Res<InputSettings>,
+    touch_state: Res<TouchState>,
     mut egui_contexts: EguiContexts,
     mut mouse_button_input_events: EventReader<MouseButtonInput>,
-    mut mouse_wheel_events: EventReader<MouseWheel>,
-    mut camera_transforms: Query<&mut Transform, With<Camera>>,
 ) {
     let egui_input_use = egui_contexts.ctx_mut().wants_pointer_input();
 
     // Get cursor position in world coordinates
     let (camera, camera_transform) = cameras.single().unwrap();
     let window = q_window.single().unwrap();
-    let cursor_position = window
-        .cursor_position()
+    let cursor_position = touch_state.position
+        .or_else(|| window.cursor_position())
         .and_then(|pos| camera.viewport_to_world_2d(camera_transform, pos).ok());
 
     if cursor_position.is_some() {
         detail_data.cursor_position = cursor_position;
     }
 
-    let mouse_events: Vec<MouseButtonInput> = mouse_button_input_events.read().cloned().collect();
+    let mouse_events: Vec<MouseButtonInput> = mouse_button_input_events.read()
+        .cloned()
+        .chain(touch_state.mouse_events())
+        .collect();
 
     // Process interactions
     let cursor_pos = detail_data.cursor_position;
@@ -304,69 +976,112 @@ pub fn update(
     // Track the number of rectangles before processing
     let rect_count_before = rectangles.0.len();
 
-    handlers.resizing.process(
-        &mut rectangles.0,
-        cursor_pos,
-        &mouse_events,
-        &mut interaction_state.mode,
-        &mut selected_index.0,
-        &mut egui_contexts,
-        &mut command_history,
-    );
+    // Boxes hidden from the layers panel can't stay selected, or keys would edit what can't be seen
+    let layers = &detail_data.layers;
+    if selected_index.0.and_then(|index| rectangles.0.get(index)).is_some_and(|rect| !layers.is_visible(rect)) {
+        selected_index.0 = None;
+    }
+    handlers.group.indices.retain(|&index| rectangles.0.get(index).is_some_and(|rect| layers.is_visible(rect)));
 
-    handlers.grabbing.process(
-        &mut rectangles.0,
-        cursor_pos,
-        &mouse_events,
-        &mut interaction_state.mode,
-        &mut selected_index.0,
-        &mut egui_contexts,
-        &mut command_history,
-    );
+    let handlers = &mut *handlers;
+    handlers.spatial_index.sync(&rectangles.0);
 
-    handlers.drawing.process(
-        &mut rectangles.0,
-        cursor_pos,
-        &mouse_events,
-        &mut interaction_state.mode,
-        selected_class,
-        egui_input_use,
-        &mut gizmos,
-        &mut command_history,
-    );
+    // Hiding every box hides the tools that edit them too, and strokes in mask mode paint instead
+    if !interaction_state.labels_only && layers.show_boxes && interaction_state.mask_painting.tool.is_none() {
+        // Runs first so a shift-click or a drag on a multi-box selection isn't also taken
+        // as the start of a single-box edit
+        handlers.group.process(
+            &mut rectangles.0,
+            layers,
+            &handlers.spatial_index,
+            input_settings.grab_radius,
+            cursor_pos,
+            &mouse_events,
+            input_settings.select_modifier.is_pressed(&keyboard),
+            &mut interaction_state.mode,
+            &mut selected_index.0,
+            egui_input_use,
+            &mut egui_contexts,
+            &mut gizmos,
+            &mut command_history,
+        );
+
+        // Each handler can move boxes, so the ones after it hit-test the moved boxes
+        handlers.spatial_index.sync(&rectangles.0);
+        handlers.rotating.process(
+            &mut rectangles.0,
+            cursor_pos,
+            &mouse_events,
+            &mut interaction_state.mode,
+            &mut selected_index.0,
+            &mut egui_contexts,
+            &mut command_history,
+        );
+
+        handlers.spatial_index.sync(&rectangles.0);
+        handlers.resizing.process(
+            &mut rectangles.0,
+            layers,
+            &handlers.spatial_index,
+            input_settings.grab_radius,
+            cursor_pos,
+            &mouse_events,
+            &mut interaction_state.mode,
+            &mut selected_index.0,
+            &mut egui_contexts,
+            &mut command_history,
+        );
+
+        handlers.spatial_index.sync(&rectangles.0);
+        handlers.grabbing.process(
+            &mut rectangles.0,
+            layers,
+            &handlers.spatial_index,
+            input_settings.grab_radius,
+            cursor_pos,
+            &mouse_events,
+            &mut interaction_state.mode,
+            &mut selected_index.0,
+            &mut egui_contexts,
+            &mut command_history,
+        );
+
+        if !interaction_state.magic_select {
+            let draw_cursor = match cursor_pos {
+                Some(pos) if drawing_aids.snapping => Some(snap_cursor(pos, &rectangles, &detail_data).point),
+                other => other,
+            };
+            handlers.drawing.process(
+                &mut rectangles.0,
+                draw_cursor,
+                &mouse_events,
+                &mut interaction_state.mode,
+                selected_class,
+                egui_input_use,
+                &mut gizmos,
+                &mut command_history,
+            );
+        }
+    }
     
-    // Update text entities if rectangles have changed
-    let rect_count_after = rectangles.0.len();
-    if rect_count_before != rect_count_after || detail_data.text_entities.len() != rect_count_after {
-        update_text_entities(&mut commands, &mut detail_data, &rectangles);
+    // A box drawn in a hidden class shows its class again
+    if rectangles.0.len() > rect_count_before {
+        if let Some(class) = rectangles.0.last().map(|rect| rect.class) {
+            detail_data.layers.set_class_visible(class, true);
+        }
     }
 
     draw_rectangles(
         &rectangles,
         &selected_index,
-        &mut gizmos,
+        &handlers.group.indices,
+        &detail_data.layers,
         &mut selected_rect_gizmos,
     );
 
-    // Camera controls
-    detail_data.camera_controller.process_zoom(
-        &mut mouse_wheel_events,
-        &mut camera_transforms,
-        egui_input_use,
-    );
-    detail_data.camera_controller.process_panning(
-        &mouse_events,
-        &mut camera_transforms,
-        q_window,
-        egui_input_use,
-    );
-
-    // Handle keyboard input
-    if let Some(class) = key_code_to_class(&keyboard) {
-        detail_data.selected_class = class;
-    }
-
-    if keyboard.pressed(KeyCode::Backspace) {
+    if keyboard.pressed(KeyCode::Backspace) && handlers.group.indices.len() > 1 {
+        handlers.group.delete(&mut rectangles.0, &mut selected_index.0, &mut command_history);
+    } else if keyboard.pressed(KeyCode::Backspace) {
         if let Some(idx) = selected_index.0 {
             if idx < rectangles.0.len() {
                 let rectangle = rectangles.0[idx].clone();
@@ -377,7 +1092,6 @@ pub fn update(
                 command.execute(&mut rectangles.0);
                 command_history.push(command);
                 selected_index.0 = None;
-                update_text_entities(&mut commands, &mut detail_data, &rectangles);
             }
         }
     }
@@ -394,13 +1108,11 @@ pub fn update(
             // Redo with Cmd+Shift+Z (macOS) or Ctrl+Shift+Z (Windows/Linux)
             if command_history.redo(&mut rectangles.0) {
                 selected_index.0 = None;
-                update_text_entities(&mut commands, &mut detail_data, &rectangles);
             }
         } else {
             // Undo with Cmd+Z (macOS) or Ctrl+Z (Windows/Linux)
             if command_history.undo(&mut rectangles.0) {
                 selected_index.0 = None;
-                update_text_entities(&mut commands, &mut detail_data, &rectangles);
             }
         }
     }
@@ -408,16 +1120,420 @@ pub fn update(
     if keyboard.pressed(KeyCode::Escape) {
         selected_index.0 = None;
         interaction_state.mode = InteractionMode::Default;
+        handlers.rotating.clear();
         handlers.resizing.clear();
         handlers.grabbing.clear();
         handlers.drawing.clear();
+        handlers.group.clear();
         detail_data.camera_controller.reset_panning();
     }
 }
 
+/// Zooms and pans the view with the mouse or trackpad, the way the input settings say, and with
+/// two fingers on touchscreens
 #[allow(clippy::too_many_arguments)]
-pub fn ui_system(
+pub fn camera_control_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    input_settings: Res<InputSettings>,
+    mut detail_data: ResMut<DetailData>,
+    mut egui_contexts: EguiContexts,
+    mut mouse_button_input_events: EventReader<MouseButtonInput>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut pinch_events: EventReader<PinchGesture>,
+    touches: Res<Touches>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut camera_transforms: Query<&mut Transform, With<Camera>>,
+) {
+    let egui_input_use = egui_contexts.ctx_mut().wants_pointer_input();
+    let zoom_modifier = if cfg!(target_os = "macos") {
+        keyboard.pressed(KeyCode::SuperLeft) || keyboard.pressed(KeyCode::SuperRight)
+    } else {
+        keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight)
+    };
+    let mouse_events: Vec<MouseButtonInput> = mouse_button_input_events.read().cloned().collect();
+
+    detail_data.camera_controller.process_zoom(
+        &mut mouse_wheel_events,
+        &mut pinch_events,
+        &mut camera_transforms,
+        &input_settings,
+        zoom_modifier,
+        egui_input_use,
+    );
+    detail_data.camera_controller.process_panning(
+        &mouse_events,
+        &mut camera_transforms,
+        q_window,
+        input_settings.pan_button.mouse_button(),
+        egui_input_use,
+    );
+    detail_data.camera_controller.process_touch_gesture(&touches, &mut camera_transforms);
+}
+
+/// Turns the touches of a touchscreen or pen tablet into the button presses and cursor of the
+/// box tools
+pub fn touch_input_system(
+    mut touch_state: ResMut<TouchState>,
+    mut touch_events: EventReader<TouchInput>,
+    touches: Res<Touches>,
+) {
+    touch_state.process(touch_events.read(), touches.iter().count());
+}
+
+/// Saves the input settings as soon as they are changed in their window
+pub fn input_settings_ui_system(mut contexts: EguiContexts, mut input_settings: ResMut<InputSettings>) {
+    if detail_ui::render_input_settings_window(&mut contexts, &mut input_settings) {
+        if let Err(error) = input_settings.save() {
+            warn!("Failed to save the input settings: {}", error);
+        }
+    }
+}
+
+/// Arrow keys move the selected boxes by one pixel, ten with Shift. Held keys repeat, and every
+/// step can be undone on its own.
+pub fn nudge_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    interaction_state: Res<InteractionState>,
+    handlers: Res<InteractionHandlers>,
+    selected_index: Res<SelectedRectangleIndex>,
+    mut rectangles: ResMut<Rectangles>,
+    mut command_history: ResMut<CommandHistory>,
+    mut egui_contexts: EguiContexts,
+) {
+    let direction: Vec2 = keyboard_events
+        .read()
+        .filter(|event| event.state == ButtonState::Pressed)
+        .map(|event| match event.key_code {
+            KeyCode::ArrowLeft => Vec2::NEG_X,
+            KeyCode::ArrowRight => Vec2::X,
+            KeyCode::ArrowUp => Vec2::Y,
+            KeyCode::ArrowDown => Vec2::NEG_Y,
+            _ => Vec2::ZERO,
+        })
+        .sum();
+
+    if direction == Vec2::ZERO
+        || interaction_state.labels_only
+        || interaction_state.mode != InteractionMode::Default
+        || egui_contexts.ctx_mut().wants_keyboard_input()
+    {
+        return;
+    }
+
+    let step = if keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight) {
+        10.0
+    } else {
+        1.0
+    };
+    let mut moves: Vec<Command> = handlers.group.targets(selected_index.0)
+        .into_iter()
+        .filter_map(|index| {
+            let rectangle = rectangles.0.get_mut(index)?;
+            let old_center = rectangle.center();
+            rectangle.move_by(direction * step);
+            let new_center = rectangle.center();
+            Some(Command::MoveRectangle {
+                index,
+                old_position: (old_center.x, old_center.y),
+                new_position: (new_center.x, new_center.y),
+            })
+        })
+        .collect();
+
+    match moves.len() {
+        0 => {}
+        1 => command_history.push(moves.remove(0)),
+        _ => command_history.push(Command::Batch { commands: moves }),
+    }
+}
+
+/// Box the segmentation server found around a magic select click
+pub struct SegmentedBox {
+    task_id: Uuid,
+    /// Class selected when the image was clicked
+    class: usize,
+    bbox: [f64; 4],
+}
+
+/// Turns a click on the image into a box using the segmentation server ("magic select"). The
+/// box is added by `magic_select_result_system` once the server answered.
+#[allow(clippy::too_many_arguments)]
+pub fn magic_select_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    detail_data: Res<DetailData>,
+    annotation_state: Res<AnnotationState>,
+    auth_state: Res<crate::auth::AuthState>,
+    rectangles: Res<Rectangles>,
+    mut interaction_state: ResMut<InteractionState>,
+    mut handlers: ResMut<InteractionHandlers>,
+    input_settings: Res<InputSettings>,
+    touch_state: Res<TouchState>,
+    segment_tasks: Res<ApiTasks<SegmentedBox>>,
+    mut egui_contexts: EguiContexts,
+    mut mouse_button_input_events: EventReader<MouseButtonInput>,
+) {
+    if interaction_state.labels_only {
+        return;
+    }
+
+    if keyboard.just_pressed(KeyCode::KeyM) && !egui_contexts.ctx_mut().wants_keyboard_input() {
+        interaction_state.magic_select = !interaction_state.magic_select;
+    }
+
+    let clicked = mouse_button_input_events
+        .read()
+        .any(|event| event.button == MouseButton::Left && event.state == ButtonState::Pressed)
+        || touch_state.just_pressed();
+
+    if !interaction_state.magic_select
+        || !clicked
+        || interaction_state.magic_select_running
+        || interaction_state.mode != InteractionMode::Default
+        || interaction_state.mask_painting.tool.is_some()
+        || egui_contexts.ctx_mut().wants_pointer_input()
+    {
+        return;
+    }
+
+    let Some(cursor) = detail_data.cursor_position else {
+        return;
+    };
+
+    // Boxes may have changed since the editor's update synced the index
+    handlers.spatial_index.sync(&rectangles.0);
+
+    // Clicks on existing boxes are left to the grab/resize handlers
+    let margin = input_settings.grab_radius;
+    let on_box = handlers.spatial_index.near(cursor, margin)
+        .into_iter()
+        .any(|index| rectangles.0.get(index).is_some_and(|rect| rect.contains_point(cursor, margin)));
+    if on_box {
+        return;
+    }
+
+    let dimensions = detail_data.image_dimensions;
+    let image_point = coordinates::world_to_image(cursor, dimensions);
+    if !coordinates::is_on_image(image_point, dimensions) {
+        return;
+    }
+
+    let (Some(project_id), Some(task_id), Some(token)) = (
+        annotation_state.current_project_id,
+        annotation_state.current_task_id,
+        auth_state.get_jwt(),
+    ) else {
+        interaction_state.magic_select_error = Some(t!("detail-magic-select-login"));
+        return;
+    };
+
+    interaction_state.magic_select_running = true;
+    let class = detail_data.selected_class;
+    let token = token.clone();
+    segment_tasks.spawn(async move {
+        let bbox = annotation_client::segment_at_point(project_id, task_id, image_point.x, image_point.y, token).await?;
+        Ok(SegmentedBox { task_id, class, bbox })
+    });
+}
+
+/// Adds the box of a magic select as one step to undo. Boxes of a task the editor has moved on
+/// from are dropped.
+pub fn magic_select_result_system(
+    mut segmented: EventReader<ApiTaskSucceeded<SegmentedBox>>,
+    mut segment_failed: EventReader<ApiTaskFailed<SegmentedBox>>,
+    detail_data: Res<DetailData>,
+    annotation_state: Res<AnnotationState>,
+    mut rectangles: ResMut<Rectangles>,
+    mut selected_index: ResMut<SelectedRectangleIndex>,
+    mut interaction_state: ResMut<InteractionState>,
+    mut command_history: ResMut<CommandHistory>,
+) {
+    for ApiTaskSucceeded(segmented_box) in segmented.read() {
+        interaction_state.magic_select_running = false;
+        if annotation_state.current_task_id != Some(segmented_box.task_id) {
+            continue;
+        }
+        let rectangle = coordinates::bbox_to_rectangle(&segmented_box.bbox, segmented_box.class, detail_data.image_dimensions);
+        let command = Command::AddRectangle { rectangle };
+        command.execute(&mut rectangles.0);
+        command_history.push(command);
+        selected_index.0 = Some(rectangles.0.len() - 1);
+        interaction_state.magic_select_error = None;
+    }
+
+    for failure in segment_failed.read() {
+        error!("Magic select failed: {}", failure.error);
+        interaction_state.magic_select_running = false;
+        interaction_state.magic_select_error = Some(failure.error.clone());
+    }
+}
+
+/// Fraction of the brush size painted by the lightest pen touch
+const MIN_PEN_PRESSURE: f32 = 0.1;
+
+/// Paints the mask of the selected box with the brush or eraser of the mask mode. A brush stroke
+/// without a selected box starts a new box of the current class. Releasing the mouse stores the
+/// mask and fits the box to it, as one step to undo.
+#[allow(clippy::too_many_arguments)]
+pub fn mask_paint_system(
     mut commands: Commands,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    touch_state: Res<TouchState>,
+    mut detail_data: ResMut<DetailData>,
+    mut rectangles: ResMut<Rectangles>,
+    mut selected_index: ResMut<SelectedRectangleIndex>,
+    interaction_state: Res<InteractionState>,
+    mut command_history: ResMut<CommandHistory>,
+    mut images: ResMut<Assets<Image>>,
+    mut egui_contexts: EguiContexts,
+) {
+    let painting = &interaction_state.mask_painting;
+    let dimensions = detail_data.image_dimensions;
+
+    if detail_data.mask_stroke.is_none() {
+        let Some(tool) = painting.tool else {
+            return;
+        };
+        if interaction_state.labels_only
+            || !detail_data.layers.show_boxes
+            || !(mouse_buttons.just_pressed(MouseButton::Left) || touch_state.just_pressed())
+            || egui_contexts.ctx_mut().wants_pointer_input()
+        {
+            return;
+        }
+        let Some(cursor) = detail_data.cursor_position else {
+            return;
+        };
+        let image_point = coordinates::world_to_image(cursor, dimensions);
+        if !coordinates::is_on_image(image_point, dimensions) {
+            return;
+        }
+
+        let selected = selected_index.0
+            .filter(|&index| rectangles.0.get(index).is_some_and(|rect| detail_data.layers.is_visible(rect)));
+        let (index, class, bitmap) = match selected {
+            Some(index) => {
+                let rect = &rectangles.0[index];
+                let bitmap = match rect.mask.as_ref().and_then(masks::decode_mask) {
+                    Some((size, runs)) => masks::MaskBitmap::from_runs(size, &runs),
+                    None => masks::MaskBitmap::new(dimensions.round().as_uvec2()),
+                };
+                (Some(index), rect.class, bitmap)
+            }
+            // The eraser has nothing to erase without a box
+            None if tool == masks::MaskTool::Brush => {
+                (None, detail_data.selected_class, masks::MaskBitmap::new(dimensions.round().as_uvec2()))
+            }
+            None => return,
+        };
+        if bitmap.size.cmpeq(UVec2::ZERO).any() {
+            return;
+        }
+
+        let color: Color = rect_color(class).into();
+        let image = images.add(bitmap.to_image(color));
+        let entity = commands.spawn((
+            Sprite {
+                image: image.clone(),
+                color: Color::WHITE.with_alpha(detail_data.layers.mask_opacity),
+                custom_size: Some(dimensions),
+                ..default()
+            },
+            Transform::from_xyz(0.0, 0.0, masks::PAINTED_MASK_Z),
+        )).id();
+        let last_point = image_point * bitmap.size.as_vec2() / dimensions;
+        detail_data.mask_stroke = Some(MaskStroke { index, class, bitmap, entity, image, last_point });
+    }
+
+    let cursor = detail_data.cursor_position;
+    let Some(stroke) = detail_data.mask_stroke.as_mut() else {
+        return;
+    };
+
+    if let Some(cursor) = cursor {
+        let scale = stroke.bitmap.size.as_vec2() / dimensions;
+        let point = coordinates::world_to_image(cursor, dimensions) * scale;
+        // Pens paint thinner the lighter they press
+        let pressure = touch_state.pressure.map_or(1.0, |pressure| pressure.max(MIN_PEN_PRESSURE));
+        let radius = painting.brush_size / 2.0 * pressure * scale.max_element();
+        let paint = painting.tool != Some(masks::MaskTool::Eraser);
+        let changed = stroke.bitmap.paint_line(stroke.last_point, point, radius, paint);
+        if !changed.is_empty() {
+            if let Some(image) = images.get_mut(&stroke.image) {
+                masks::update_image(image, &changed, paint, rect_color(stroke.class).into());
+            }
+        }
+        stroke.last_point = point;
+    }
+
+    if (mouse_buttons.pressed(MouseButton::Left) || touch_state.pressed()) && painting.tool.is_some() {
+        return;
+    }
+
+    // The stroke ended, or mask mode was left in the middle of it
+    let Some(stroke) = detail_data.mask_stroke.take() else {
+        return;
+    };
+    commands.entity(stroke.entity).despawn();
+
+    let fitted = stroke.bitmap.bounds().map(|[x, y, width, height]| {
+        let scale = dimensions / stroke.bitmap.size.as_vec2();
+        let bbox = [
+            x as f64 * scale.x as f64,
+            y as f64 * scale.y as f64,
+            width as f64 * scale.x as f64,
+            height as f64 * scale.y as f64,
+        ];
+        coordinates::bbox_to_rectangle(&bbox, stroke.class, dimensions)
+    });
+
+    let command = match (stroke.index, fitted) {
+        (Some(index), fitted) => {
+            let Some(old_rect) = rectangles.0.get(index).cloned() else {
+                return;
+            };
+            let new_rect = match fitted {
+                Some(fitted) => Rectangle {
+                    position: fitted.position,
+                    rotation: 0.0,
+                    interpolated: false,
+                    mask: Some(stroke.bitmap.to_rle()),
+                    ..old_rect.clone()
+                },
+                // Erasing the whole mask leaves the box as it is
+                None => Rectangle { mask: None, ..old_rect.clone() },
+            };
+            if new_rect == old_rect {
+                return;
+            }
+            Command::ResizeRectangle { index, old_rect, new_rect }
+        }
+        (None, Some(mut rectangle)) => {
+            rectangle.mask = Some(stroke.bitmap.to_rle());
+            Command::AddRectangle { rectangle }
+        }
+        (None, None) => return,
+    };
+
+    command.execute(&mut rectangles.0);
+    if matches!(command, Command::AddRectangle { .. }) {
+        selected_index.0 = Some(rectangles.0.len() - 1);
+        detail_data.layers.set_class_visible(stroke.class, true);
+    }
+    command_history.push(command);
+}
+
+/// Requests the editor's windows start, which `ui_system` has no room left for
+#[derive(SystemParam)]
+pub struct EditorRequests<'w> {
+    pub flags: Res<'w, ApiTasks<TaskFlag>>,
+    pub labels: Res<'w, ApiTasks<TaskLabels>>,
+    pub comments: Res<'w, ApiTasks<CommentsChange>>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn ui_system(
+    requests: EditorRequests,
     mut contexts: EguiContexts,
     current_state: Res<State<AppState>>,
     mut next_state: ResMut<NextState<AppState>>,
@@ -425,162 +1541,2079 @@ pub fn ui_system(
     mut selected_index: ResMut<SelectedRectangleIndex>,
     mut detail_data: ResMut<DetailData>,
     mut annotation_state: ResMut<AnnotationState>,
+    mut command_history: ResMut<CommandHistory>,
+    mut interaction_state: ResMut<InteractionState>,
+    mut comments_state: ResMut<CommentsState>,
+    mut flag_state: ResMut<TaskFlagState>,
+    mut classification_state: ResMut<ClassificationState>,
+    mut video_state: ResMut<VideoState>,
     auth_state: Res<crate::auth::AuthState>,
     user_state: Res<crate::auth::UserState>,
-    projects_state: Res<crate::auth::ProjectsState>,
 ) {
     egui_common::ui_top_panel(&mut contexts, current_state, &mut next_state);
 
-    let rect_count_before = rectangles.0.len();
+    if interaction_state.labels_only {
+        detail_ui::render_classification_window(
+            &mut contexts,
+            &requests,
+            &mut classification_state,
+            &mut flag_state,
+            &mut annotation_state,
+            &auth_state,
+        );
+        detail_ui::render_comments_panel(
+            &mut contexts,
+            &requests.comments,
+            &mut comments_state,
+            &rectangles.0,
+            &mut selected_index.0,
+            &annotation_state,
+            &auth_state,
+            &user_state,
+            detail_data.image_dimensions,
+        );
+        return;
+    }
+
+    detail_ui::render_frame_scrubber(&mut contexts, &mut video_state);
+
     detail_ui::render_side_panels_with_annotations(
         &mut contexts, 
         &mut rectangles.0, 
         &mut selected_index.0,
         &mut annotation_state,
+        &user_state,
+        &video_state,
+    );
+
+    detail_ui::render_comments_panel(
+        &mut contexts,
+        &requests.comments,
+        &mut comments_state,
+        &rectangles.0,
+        &mut selected_index.0,
+        &annotation_state,
         &auth_state,
         &user_state,
-        &projects_state,
         detail_data.image_dimensions,
-        Some(&mut commands),
-        Some(&mut next_state),
     );
     
-    // Check if rectangles were sorted (order might have changed)
-    if rect_count_before == rectangles.0.len() && rect_count_before > 0 {
-        // Update text entities to reflect new order
-        update_text_entities(&mut commands, &mut detail_data, &rectangles);
-    }
+    detail_ui::render_rectangle_editor_window(
+        &mut contexts,
+        &mut rectangles.0,
+        &mut selected_index.0,
+        &mut command_history,
+        &annotation_state.categories,
+        detail_data.image_dimensions,
+        &mut interaction_state.propagate_requested,
+    );
+
+    let DetailData { selected_class, class_filter, .. } = &mut *detail_data;
+    detail_ui::render_class_picker_window(&mut contexts, &annotation_state.categories, selected_class, class_filter);
 
-    detail_ui::render_rectangle_editor_window(&mut contexts, &mut rectangles.0, selected_index.0);
+    let InteractionState { magic_select, magic_select_error, mask_painting, .. } = &mut *interaction_state;
+    detail_ui::render_tools_window(
+        &mut contexts,
+        magic_select,
+        magic_select_error.as_deref(),
+        mask_painting,
+        &requests.flags,
+        &mut flag_state,
+        &annotation_state,
+        &auth_state,
+    );
 }
 
 
-pub fn cleanup(mut commands: Commands, detail_data: Res<DetailData>) {
+/// Loads of the editor, dropped when the page closes
+#[derive(SystemParam)]
+pub struct EditorLoads<'w> {
+    tasks: Res<'w, ApiTasks<LoadedTask>>,
+    frames: Res<'w, ApiTasks<LoadedFrames>>,
+    frame_images: Res<'w, ApiTasks<LoadedFrame>>,
+    reloads: Res<'w, ApiTasks<ReloadedAnnotations>>,
+    next_tasks: Res<'w, ApiTasks<NextTask>>,
+    segments: Res<'w, ApiTasks<SegmentedBox>>,
+}
+
+pub fn cleanup(mut commands: Commands, detail_data: Option<Res<DetailData>>, loads: EditorLoads) {
     println!("detail cleanup");
-    commands.entity(detail_data.image_entity).despawn();
-    
-    // Clean up text entities
-    for entity in &detail_data.text_entities {
-        commands.entity(*entity).despawn();
+    loads.tasks.cancel_all();
+    loads.frames.cancel_all();
+    loads.frame_images.cancel_all();
+    loads.reloads.cancel_all();
+    loads.next_tasks.cancel_all();
+    loads.segments.cancel_all();
+
+    // Left before the image of the task was there
+    if let Some(detail_data) = detail_data {
+        commands.entity(detail_data.image_entity).despawn();
+
+        // Clean up text entities, mask overlays and box outlines
+        let stroke_entity = detail_data.mask_stroke.as_ref().map(|stroke| &stroke.entity);
+        let outline_entity = detail_data.outline_mesh.as_ref().map(|(entity, _)| entity);
+        for entity in detail_data.text_entities.iter().chain(&detail_data.mask_entity).chain(stroke_entity).chain(outline_entity) {
+            commands.entity(*entity).despawn();
+        }
+        commands.remove_resource::<DetailData>();
     }
     
     commands.remove_resource::<CommandHistory>();
+    commands.insert_resource(CommentsState::default());
+    commands.insert_resource(TaskFlagState::default());
+    commands.insert_resource(ClassificationState::default());
+    commands.insert_resource(VideoState::default());
+    commands.insert_resource(TileState::default());
+    commands.insert_resource(ShortcutState::default());
 }
 
-#[allow(clippy::too_many_arguments)]
-pub fn check_next_task_system(
-    mut commands: Commands,
-    next_task_marker: Option<Res<crate::ui::detail_ui::NextTaskMarker>>,
-    mut rectangles: ResMut<Rectangles>,
-    mut annotation_state: ResMut<AnnotationState>,
-    mut detail_data: ResMut<DetailData>,
-    mut images: ResMut<Assets<Image>>,
-    q_window: Query<&Window, With<PrimaryWindow>>,
-    mut camera_transforms: Query<&mut Transform, With<Camera>>,
+/// Frames of a video or volume task, with its boxes to split up over them
+pub struct LoadedFrames {
+    task_id: Uuid,
+    frame_rate: Option<f64>,
+    frames: Vec<TaskFrame>,
+    annotations: Option<Vec<AnnotationWithCategory>>,
+}
+
+/// Fetches the frames of the current task whenever the task changes, once its image is shown.
+/// Image tasks have none.
+pub fn load_video_frames_system(
+    annotation_state: Res<AnnotationState>,
+    auth_state: Res<crate::auth::AuthState>,
+    mut video_state: ResMut<VideoState>,
+    frame_loads: Res<ApiTasks<LoadedFrames>>,
 ) {
-    if let Some(marker) = next_task_marker {
-        info!("Processing next task marker");
-        
-        // Load new image
-        match crate::io::image_loader::spawn_image_sprite(&mut commands, &mut images, &marker.url) {
-            Ok((new_image_entity, new_image_dimensions)) => {
-                info!("New image loaded with dimensions: {:?}", new_image_dimensions);
-                
-                // Despawn old image
-                commands.entity(detail_data.image_entity).despawn();
-                
-                // Update detail data
-                detail_data.image_entity = new_image_entity;
-                detail_data.image_dimensions = new_image_dimensions;
-                
-                // Calculate and apply zoom for new image
-                if let Ok(window) = q_window.single() {
-                    let window_size = Vec2::new(window.width(), window.height());
-                    
-                    // Calculate zoom level to fit image with 30% padding on each side
-                    let padding_factor = 0.4; // 40% of window size (30% padding on each side)
-                    let zoom_x = (window_size.x * padding_factor) / new_image_dimensions.x;
-                    let zoom_y = (window_size.y * padding_factor) / new_image_dimensions.y;
-                    let initial_zoom = zoom_x.min(zoom_y).clamp(0.1, 10.0);
-                    
-                    // Update camera controller zoom level
-                    detail_data.camera_controller.zoom_level = initial_zoom;
-                    
-                    // Apply zoom to camera
-                    if let Ok(mut camera_transform) = camera_transforms.single_mut() {
-                        camera_transform.scale = Vec3::splat(1.0 / initial_zoom);
-                    }
-                }
-                
-                // Update annotation state
-                annotation_state.current_task_id = marker.task_id;
-                annotation_state.current_project_id = Some(marker.project_id);
-                annotation_state.is_loading_next_task = false;
-                
-                // Clear rectangles for new task
-                rectangles.0.clear();
-                
-                info!("Successfully switched to next task");
-            }
-            Err(e) => {
-                error!("Failed to load new image: {}", e);
-                annotation_state.is_loading_next_task = false;
+    if video_state.loaded_task_id == annotation_state.current_task_id || annotation_state.is_loading_next_task {
+        return;
+    }
+
+    *video_state = VideoState {
+        loaded_task_id: annotation_state.current_task_id,
+        ..Default::default()
+    };
+
+    let (Some(project_id), Some(task_id), Some(token)) = (
+        annotation_state.current_project_id,
+        annotation_state.current_task_id,
+        auth_state.get_jwt(),
+    ) else {
+        return;
+    };
+
+    video_state.loading = true;
+    let token = token.clone();
+    frame_loads.cancel_all();
+    frame_loads.spawn(async move {
+        let TaskFramesResponse { frame_rate, frames } = annotation_client::load_frames(project_id, task_id, token.clone()).await?;
+        if frames.is_empty() {
+            return Ok(LoadedFrames { task_id, frame_rate, frames, annotations: None });
+        }
+
+        // The boxes loaded on entering the page ignore frames, so load them again to split them up
+        let annotations = match annotation_client::load_annotations(project_id, task_id, token, true).await {
+            Ok(annotations) => Some(annotations),
+            Err(error) => {
+                info!("No existing annotations found or failed to load: {}", error);
+                None
             }
+        };
+        Ok(LoadedFrames { task_id, frame_rate, frames, annotations })
+    });
+}
+
+/// Puts up the frames `load_video_frames_system` fetched, starting with the first one.
+pub fn apply_video_frames_system(
+    mut loaded_frames: EventReader<ApiTaskSucceeded<LoadedFrames>>,
+    mut frames_failed: EventReader<ApiTaskFailed<LoadedFrames>>,
+    annotation_state: Res<AnnotationState>,
+    mut video_state: ResMut<VideoState>,
+) {
+    for ApiTaskSucceeded(loaded) in loaded_frames.read() {
+        // The editor moved on to another task meanwhile
+        if video_state.loaded_task_id != Some(loaded.task_id) || annotation_state.current_task_id != Some(loaded.task_id) {
+            continue;
         }
-        
-        // Remove the marker
-        commands.remove_resource::<crate::ui::detail_ui::NextTaskMarker>();
+        video_state.loading = false;
+        if loaded.frames.is_empty() {
+            continue;
+        }
+
+        info!("Loaded {} video frames", loaded.frames.len());
+        video_state.frames = loaded.frames.clone();
+        video_state.frame_rate = loaded.frame_rate;
+        video_state.pending_annotations = loaded.annotations.clone();
+        video_state.pending_frame = Some(0);
     }
-}
 
-// Annotation types and structures
-#[derive(Resource, Default)]
-pub struct AnnotationState {
-    pub is_saving: bool,
-    pub is_loading_next_task: bool,
-    pub categories: Vec<AnnotationCategory>,
-    pub current_task_id: Option<Uuid>,
-    pub current_project_id: Option<Uuid>,
-    pub current_task_name: Option<String>,
-    pub image_url: Option<String>,
+    for failure in frames_failed.read() {
+        error!("Failed to load video frames: {}", failure.error);
+        video_state.loading = false;
+        video_state.error = Some(failure.error.clone());
+    }
 }
 
-// API types are now re-exported at the top of the file
+/// Applies the window/level of volume tasks and the view adjustments to the shown image. The
+/// original pixels are kept so every change starts from them, and saved annotations never see
+/// the adjusted ones.
+pub fn view_adjustment_system(
+    mut video_state: ResMut<VideoState>,
+    detail_data: Res<DetailData>,
+    adjustments: Res<ViewAdjustments>,
+    sprites: Query<&Sprite>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let entity = detail_data.image_entity;
+    let is_volume = video_state.is_volume();
+    let (width, level) = (video_state.window_width, video_state.window_level);
+    let view = (entity, width, level, *adjustments);
+    if video_state.applied_view == Some(view) {
+        return;
+    }
+    // Untouched images are left alone rather than copied
+    let touched = video_state.original_pixels.as_ref().is_some_and(|(original_entity, _)| *original_entity == entity);
+    if !is_volume && adjustments.is_identity() && !touched {
+        return;
+    }
 
-// Adapter functions to maintain existing interface while using new API modules
-pub mod annotation_client {
-    use super::*;
+    // The sprite of a new slice exists once its spawn command has run
+    let Ok(sprite) = sprites.get(entity) else {
+        return;
+    };
+    let Some(image) = images.get_mut(&sprite.image) else {
+        return;
+    };
+    let Some(pixels) = image.data.as_mut() else {
+        return;
+    };
 
-    pub fn save_annotations(
-        project_id: Uuid,
-        task_id: Uuid,
-        bounding_boxes: Vec<BoundingBox>,
-        token: String,
-    ) -> Result<Vec<AnnotationWithCategory>, String> {
-        let annotations_api = AnnotationsApi::new();
-        let runtime = tokio::runtime::Runtime::new()
-            .map_err(|e| format!("Failed to create runtime: {}", e))?;
-        
-        runtime.block_on(async {
-            annotations_api.save_annotations(&token, project_id, task_id, &bounding_boxes).await
-                .map_err(|e| e.to_string())
-        })
+    if video_state.original_pixels.as_ref().is_none_or(|(original_entity, _)| *original_entity != entity) {
+        video_state.original_pixels = Some((entity, pixels.clone()));
     }
 
-    pub fn load_annotations(
-        project_id: Uuid,
-        task_id: Uuid,
-        token: String,
-        latest_only: bool,
+    if let Some((_, original)) = &video_state.original_pixels {
+        let window: [u8; 256] = if is_volume {
+            image_loader::window_level_lut(width, level)
+        } else {
+            std::array::from_fn(|value| value as u8)
+        };
+        let adjust = image_loader::adjustment_lut(adjustments.brightness, adjustments.contrast, adjustments.gamma);
+        let lut: [u8; 256] = std::array::from_fn(|value| adjust[window[value] as usize]);
+
+        // Sprites hold RGBA8 pixels, the alpha channel stays untouched
+        for (pixel, original) in pixels.chunks_exact_mut(4).zip(original.chunks_exact(4)) {
+            if adjustments.grayscale {
+                let luma = 0.299 * window[original[0] as usize] as f32
+                    + 0.587 * window[original[1] as usize] as f32
+                    + 0.114 * window[original[2] as usize] as f32;
+                let value = adjust[(luma.round() as usize).min(255)];
+                pixel[..3].fill(value);
+            } else {
+                for channel in 0..3 {
+                    pixel[channel] = lut[original[channel] as usize];
+                }
+            }
+        }
+    }
+    video_state.applied_view = Some(view);
+}
+
+/// Brightness, contrast, gamma and grayscale switches of the viewer
+pub fn view_adjustments_ui_system(
+    mut contexts: EguiContexts,
+    mut adjustments: ResMut<ViewAdjustments>,
+) {
+    detail_ui::render_view_adjustments_window(&mut contexts, &mut adjustments);
+}
+
+/// Snaps `position` to the visible boxes and the image borders, within the same distance on
+/// screen at every zoom
+fn snap_cursor(position: Vec2, rectangles: &Rectangles, detail_data: &DetailData) -> Snap {
+    let visible: Vec<&Rectangle> = rectangles.0.iter().filter(|rect| detail_data.layers.is_visible(rect)).collect();
+    let image_bounds = Rect::from_center_size(Vec2::ZERO, detail_data.image_dimensions);
+    let max_distance = drawing_aids::SNAP_DISTANCE / detail_data.camera_controller.zoom_level;
+    drawing_aids::snap_point(position, &visible, image_bounds, max_distance)
+}
+
+/// Whether the cursor is free to start or continue a box, which is when the drawing aids show
+fn is_drawing(interaction_state: &InteractionState) -> bool {
+    !interaction_state.labels_only
+        && matches!(interaction_state.mode, InteractionMode::Default | InteractionMode::Drawing)
+}
+
+/// Crosshair through the cursor and the edges it snaps to
+#[allow(clippy::too_many_arguments)]
+pub fn drawing_aids_system(
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    detail_data: Res<DetailData>,
+    rectangles: Res<Rectangles>,
+    interaction_state: Res<InteractionState>,
+    drawing_aids: Res<DrawingAids>,
+    mut egui_contexts: EguiContexts,
+    mut gizmos: Gizmos,
+) {
+    if !is_drawing(&interaction_state) || egui_contexts.ctx_mut().wants_pointer_input() {
+        return;
+    }
+    let (Ok((camera, camera_transform)), Ok(window)) = (cameras.single(), q_window.single()) else {
+        return;
+    };
+    let Some(cursor) = window
+        .cursor_position()
+        .and_then(|pos| camera.viewport_to_world_2d(camera_transform, pos).ok())
+    else {
+        return;
+    };
+    let (Ok(corner1), Ok(corner2)) = (
+        camera.viewport_to_world_2d(camera_transform, Vec2::ZERO),
+        camera.viewport_to_world_2d(camera_transform, Vec2::new(window.width(), window.height())),
+    ) else {
+        return;
+    };
+    let view = Rect::from_corners(corner1, corner2);
+
+    let snap = if drawing_aids.snapping && !interaction_state.magic_select {
+        snap_cursor(cursor, &rectangles, &detail_data)
+    } else {
+        Snap { point: cursor, x: None, y: None }
+    };
+
+    if drawing_aids.crosshair {
+        let color = Color::srgba(1.0, 1.0, 1.0, 0.6);
+        gizmos.line_2d(Vec2::new(view.min.x, snap.point.y), Vec2::new(view.max.x, snap.point.y), color);
+        gizmos.line_2d(Vec2::new(snap.point.x, view.min.y), Vec2::new(snap.point.x, view.max.y), color);
+    }
+    // The edges the corner lines up with, across the whole view
+    let guide_color = Color::srgb(0.0, 1.0, 1.0);
+    if let Some(x) = snap.x {
+        gizmos.line_2d(Vec2::new(x, view.min.y), Vec2::new(x, view.max.y), guide_color);
+    }
+    if let Some(y) = snap.y {
+        gizmos.line_2d(Vec2::new(view.min.x, y), Vec2::new(view.max.x, y), guide_color);
+    }
+}
+
+/// Settings of the drawing aids and the loupe that follows the cursor
+#[allow(clippy::too_many_arguments)]
+pub fn drawing_aids_ui_system(
+    mut contexts: EguiContexts,
+    mut drawing_aids: ResMut<DrawingAids>,
+    detail_data: Res<DetailData>,
+    rectangles: Res<Rectangles>,
+    interaction_state: Res<InteractionState>,
+    sprites: Query<&Sprite>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+) {
+    if interaction_state.labels_only {
+        return;
+    }
+    detail_ui::render_drawing_aids_window(&mut contexts, &mut drawing_aids);
+
+    if !drawing_aids.loupe || !is_drawing(&interaction_state) || contexts.ctx_mut().is_pointer_over_area() {
+        return;
+    }
+    let (Ok((camera, camera_transform)), Ok(window)) = (cameras.single(), q_window.single()) else {
+        return;
+    };
+    let Some(screen_position) = window.cursor_position() else {
+        return;
+    };
+    let Ok(cursor) = camera.viewport_to_world_2d(camera_transform, screen_position) else {
+        return;
+    };
+    // Tiled images have no single texture to magnify
+    let Ok(sprite) = sprites.get(detail_data.image_entity) else {
+        return;
+    };
+    let texture = contexts.add_image(sprite.image.clone_weak());
+
+    let center = if drawing_aids.snapping && !interaction_state.magic_select {
+        snap_cursor(cursor, &rectangles, &detail_data).point
+    } else {
+        cursor
+    };
+    let loupe_position = coordinates::window_to_egui(screen_position, window, contexts.ctx_mut());
+    detail_ui::render_loupe(
+        &mut contexts,
+        texture,
+        loupe_position,
+        center,
+        detail_data.image_dimensions,
+        detail_data.camera_controller.zoom_level,
+    );
+}
+
+/// Share of the window the image takes after "fit to window" and a box after "zoom to selection"
+const FIT_FRACTION: f32 = 0.9;
+const SELECTION_FRACTION: f32 = 0.5;
+
+/// Home fits the image to the window, `=` shows it at 100% and `/` zooms to the selected boxes.
+#[allow(clippy::too_many_arguments)]
+pub fn zoom_shortcuts_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut egui_contexts: EguiContexts,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut detail_data: ResMut<DetailData>,
+    rectangles: Res<Rectangles>,
+    selected_index: Res<SelectedRectangleIndex>,
+    handlers: Res<InteractionHandlers>,
+    mut camera_transforms: Query<&mut Transform, With<Camera>>,
+) {
+    let pressed = [KeyCode::Home, KeyCode::Equal, KeyCode::Slash]
+        .into_iter()
+        .find(|key| keyboard.just_pressed(*key));
+    let Some(key) = pressed else {
+        return;
+    };
+    if egui_contexts.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let window_size = Vec2::new(window.width(), window.height());
+    let controller = &detail_data.camera_controller;
+
+    let (center, zoom) = match key {
+        KeyCode::Home => (Vec2::ZERO, controller.zoom_to_fit(window_size, detail_data.image_dimensions, FIT_FRACTION)),
+        KeyCode::Equal => {
+            let center = camera_transforms.single().map_or(Vec2::ZERO, |transform| transform.translation.truncate());
+            (center, 1.0)
+        }
+        _ => {
+            let bounds = handlers.group.targets(selected_index.0)
+                .into_iter()
+                .filter_map(|index| rectangles.0.get(index))
+                .map(|rect| rect.world_bounds())
+                .reduce(|bounds, other| bounds.union(other));
+            let Some(bounds) = bounds else {
+                return;
+            };
+            (bounds.center(), controller.zoom_to_fit(window_size, bounds.size(), SELECTION_FRACTION))
+        }
+    };
+    detail_data.camera_controller.look_at(center, zoom, &mut camera_transforms);
+}
+
+/// Overview of the whole image in the corner, with the part in view framed. Clicking or dragging
+/// on it moves the view there.
+pub fn minimap_ui_system(
+    mut contexts: EguiContexts,
+    mut detail_data: ResMut<DetailData>,
+    rectangles: Res<Rectangles>,
+    sprites: Query<&Sprite>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut camera_transforms: Query<&mut Transform, With<Camera>>,
+) {
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+    let Ok(camera_transform) = camera_transforms.single() else {
+        return;
+    };
+    let view_size = Vec2::new(window.width(), window.height()) * camera_transform.scale.truncate();
+    let view = Rect::from_center_size(camera_transform.translation.truncate(), view_size);
+
+    // Tiled images have no single texture, the minimap then shows the image outline only
+    let texture = sprites
+        .get(detail_data.image_entity)
+        .ok()
+        .map(|sprite| contexts.add_image(sprite.image.clone_weak()));
+
+    let target = detail_ui::render_minimap_window(
+        &mut contexts,
+        texture,
+        detail_data.image_dimensions,
+        &rectangles.0,
+        &detail_data.layers,
+        view,
+    );
+    if let Some(center) = target {
+        let zoom = detail_data.camera_controller.zoom_level;
+        detail_data.camera_controller.look_at(center, zoom, &mut camera_transforms);
+    }
+}
+
+/// Layers panel: show or hide all boxes or the boxes of a category, and fade their labels
+pub fn layers_ui_system(
+    mut contexts: EguiContexts,
+    mut detail_data: ResMut<DetailData>,
+    annotation_state: Res<AnnotationState>,
+    interaction_state: Res<InteractionState>,
+) {
+    if interaction_state.labels_only {
+        return;
+    }
+    detail_ui::render_layers_window(&mut contexts, &mut detail_data.layers, &annotation_state.categories);
+}
+
+/// Image of a video frame, loaded by `load_video_frame_system`
+pub struct LoadedFrame {
+    task_id: Uuid,
+    /// Position of the frame in `VideoState::frames`
+    target: usize,
+    image: LoadedImage,
+    /// Box to put on the frame in place of the box of its track there
+    propagated: Option<Rectangle>,
+}
+
+/// Steps through video frames with `,` and `.` and starts loading the image of the frame to
+/// show. `video_frame_system` swaps it in. Only the last frame asked for is shown.
+pub fn load_video_frame_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut egui_contexts: EguiContexts,
+    mut video_state: ResMut<VideoState>,
+    image_cache: Res<ImageCache>,
+    frame_image_loads: Res<ApiTasks<LoadedFrame>>,
+) {
+    if !video_state.is_video() {
+        return;
+    }
+
+    if !egui_contexts.ctx_mut().wants_keyboard_input() {
+        let current = video_state.current_frame;
+        if keyboard.just_pressed(KeyCode::Comma) && current > 0 {
+            video_state.pending_frame = Some(current - 1);
+        } else if keyboard.just_pressed(KeyCode::Period) && current + 1 < video_state.frames.len() {
+            video_state.pending_frame = Some(current + 1);
+        }
+    }
+
+    let Some(target) = video_state.pending_frame.take() else {
+        return;
+    };
+    let propagated = video_state.propagated_box.take();
+    let Some(url) = video_state.frames.get(target).and_then(|frame| frame.resolved_resource_url.clone()) else {
+        video_state.error = Some(t!("detail-frame-no-url", frame = target));
+        return;
+    };
+    let Some(task_id) = video_state.loaded_task_id else {
+        return;
+    };
+
+    video_state.loading = true;
+    let image = image_loader::load_image_from_url(&url, &image_cache);
+    frame_image_loads.cancel_all();
+    frame_image_loads.spawn(async move {
+        let image = image.await
+            .map_err(|error| t!("detail-frame-failed", frame = target, error = error.to_string()))?;
+        Ok(LoadedFrame { task_id, target, image: LoadedImage::from_dynamic(image), propagated })
+    });
+}
+
+/// Swaps in the image and boxes of the frame `load_video_frame_system` loaded.
+#[allow(clippy::too_many_arguments)]
+pub fn video_frame_system(
+    mut commands: Commands,
+    mut loaded_frames: EventReader<ApiTaskSucceeded<LoadedFrame>>,
+    mut frame_failed: EventReader<ApiTaskFailed<LoadedFrame>>,
+    mut video_state: ResMut<VideoState>,
+    mut rectangles: ResMut<Rectangles>,
+    mut selected_index: ResMut<SelectedRectangleIndex>,
+    mut command_history: ResMut<CommandHistory>,
+    mut detail_data: ResMut<DetailData>,
+    mut images: ResMut<Assets<Image>>,
+    annotation_state: Res<AnnotationState>,
+) {
+    for failure in frame_failed.read() {
+        error!("{}", failure.error);
+        video_state.loading = false;
+        video_state.error = Some(failure.error.clone());
+    }
+
+    let Some(ApiTaskSucceeded(frame)) = loaded_frames.read().last() else {
+        return;
+    };
+    // The editor moved on to another task meanwhile
+    if video_state.loaded_task_id != Some(frame.task_id) || annotation_state.current_task_id != Some(frame.task_id) {
+        return;
+    }
+    let target = frame.target;
+    video_state.loading = false;
+
+    let (image_entity, image_dimensions) = image_loader::spawn_image_sprite(&mut commands, &mut images, &frame.image);
+    commands.entity(detail_data.image_entity).despawn();
+    detail_data.image_entity = image_entity;
+    detail_data.image_dimensions = image_dimensions;
+
+    if let Some(annotations) = video_state.pending_annotations.take() {
+        let mut frame_rectangles: HashMap<i32, Vec<Rectangle>> = HashMap::new();
+        for annotation in &annotations {
+            let Some(rectangle) = annotation_to_rectangle(annotation, &annotation_state.categories, detail_data.image_dimensions) else {
+                continue;
+            };
+            frame_rectangles
+                .entry(annotation.frame_index.unwrap_or(0))
+                .or_default()
+                .push(rectangle);
+        }
+        video_state.frame_rectangles = frame_rectangles;
+    } else if let Some(frame_index) = video_state.current_frame_index() {
+        let mut shown = std::mem::take(&mut rectangles.0);
+        // Boxes get their track when they leave the screen, so copies made from them share it
+        for rect in &mut shown {
+            rect.track_id.get_or_insert_with(Uuid::new_v4);
+        }
+        video_state.frame_rectangles.insert(frame_index, shown);
+    }
+
+    let left_frame_index = video_state.current_frame_index();
+    video_state.current_frame = target;
+    video_state.scrub_frame = target;
+    video_state.error = None;
+    let frame_index = video_state.frames[target].frame_index;
+    rectangles.0 = video_state.frame_rectangles.remove(&frame_index).unwrap_or_default();
+
+    if rectangles.0.is_empty() && video_state.carry_boxes {
+        if let Some(left) = left_frame_index.and_then(|index| video_state.frame_rectangles.get(&index)) {
+            rectangles.0 = left.iter()
+                .map(|rect| Rectangle { interpolated: false, mask: None, ..rect.clone() })
+                .collect();
+        }
+    }
+
+    // Undo history refers to positions in the frame that was left
+    selected_index.0 = None;
+    *command_history = CommandHistory::default();
+
+    // Selected, so it can be adjusted and propagated on right away
+    if let Some(propagated) = frame.propagated.clone() {
+        rectangles.0.retain(|rect| rect.track_id != propagated.track_id);
+        rectangles.0.push(propagated);
+        selected_index.0 = Some(rectangles.0.len() - 1);
+    }
+}
+
+/// Copies the selected box onto the next frame, with Ctrl/Cmd + P or the button of the box window.
+/// On videos the copy continues the box's track on the next frame, which is shown. Image tasks
+/// are the frames of a sequence in name order, so the copy is saved onto the next image task
+/// after this one is saved, see `SavePurpose::Propagate`.
+#[allow(clippy::too_many_arguments)]
+pub fn propagate_box_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut egui_contexts: EguiContexts,
+    mut interaction_state: ResMut<InteractionState>,
+    mut rectangles: ResMut<Rectangles>,
+    selected_index: Res<SelectedRectangleIndex>,
+    mut video_state: ResMut<VideoState>,
+    mut annotation_state: ResMut<AnnotationState>,
+    detail_data: Res<DetailData>,
+    mut notify: EventWriter<Notify>,
+) {
+    let modifier_pressed = if cfg!(target_os = "macos") {
+        keyboard.pressed(KeyCode::SuperLeft) || keyboard.pressed(KeyCode::SuperRight)
+    } else {
+        keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight)
+    };
+    if modifier_pressed && keyboard.just_pressed(KeyCode::KeyP) && !egui_contexts.ctx_mut().wants_keyboard_input() {
+        interaction_state.propagate_requested = true;
+    }
+    if !std::mem::take(&mut interaction_state.propagate_requested) || interaction_state.labels_only {
+        return;
+    }
+
+    let Some(rect) = selected_index.0.and_then(|index| rectangles.0.get_mut(index)) else {
+        return;
+    };
+    // The copy shares the track of the box it was made from
+    rect.track_id.get_or_insert_with(Uuid::new_v4);
+    // Masks belong to the image they were made on
+    let propagated = Rectangle { interpolated: false, mask: None, ..rect.clone() };
+
+    if video_state.is_video() {
+        if video_state.current_frame + 1 >= video_state.frames.len() {
+            notify.write(Notify::info(t!("detail-propagate-last-frame")));
+            return;
+        }
+        video_state.propagated_box = Some(propagated);
+        video_state.pending_frame = Some(video_state.current_frame + 1);
+        return;
+    }
+
+    let mut propagated_boxes = detail_ui::collect_bounding_boxes(
+        std::slice::from_ref(&propagated),
+        &video_state,
+        &annotation_state.categories,
+        detail_data.image_dimensions,
+    );
+    // The images are only linked once this one is saved with the track too
+    if let Some(bounding_box) = propagated_boxes.pop() {
+        annotation_state.save_requested = Some(SavePurpose::Propagate(bounding_box));
+    }
+}
+
+/// Downloads the next frames of a video and the next pending tasks of the task list in the
+/// background, so moving on to them doesn't wait for the network.
+pub fn prefetch_upcoming_system(
+    image_cache: Res<ImageCache>,
+    annotation_state: Res<AnnotationState>,
+    video_state: Res<VideoState>,
+    tasks_state: Res<crate::pages::tasks::TasksState>,
+    mut last_position: Local<Option<(Option<Uuid>, usize, usize)>>,
+) {
+    // Frames arrive after the task opened, so their count is part of the position
+    let position = (annotation_state.current_task_id, video_state.current_frame, video_state.frames.len());
+    if *last_position == Some(position) {
+        return;
+    }
+    *last_position = Some(position);
+
+    let frames = video_state.frames.iter()
+        .skip(video_state.current_frame + 1)
+        .take(PREFETCH_AHEAD)
+        .filter_map(|frame| frame.resolved_resource_url.clone());
+
+    let current_task_id = annotation_state.current_task_id.map(|id| id.to_string());
+    let tasks = tasks_state.tasks.iter()
+        .skip_while(|task_with_url| Some(&task_with_url.task.id) != current_task_id.as_ref())
+        .skip(1)
+        .filter(|task_with_url| task_with_url.task.status == "pending" && task_with_url.task.is_image())
+        .take(PREFETCH_AHEAD)
+        .filter_map(|task_with_url| task_with_url.resolved_resource_url.clone());
+
+    image_cache.prefetch(frames.chain(tasks));
+}
+
+/// Flag reason of a task, as loaded or as flagging or clearing it left it
+pub struct TaskFlag {
+    pub task_id: Uuid,
+    pub reason: Option<String>,
+}
+
+/// Fetches the flag of the current task whenever the task changes.
+pub fn load_task_flag_system(
+    annotation_state: Res<AnnotationState>,
+    auth_state: Res<crate::auth::AuthState>,
+    mut flag_state: ResMut<TaskFlagState>,
+    flag_tasks: Res<ApiTasks<TaskFlag>>,
+) {
+    if flag_state.loaded_task_id == annotation_state.current_task_id {
+        return;
+    }
+
+    *flag_state = TaskFlagState {
+        loaded_task_id: annotation_state.current_task_id,
+        ..Default::default()
+    };
+
+    let (Some(project_id), Some(task_id), Some(token)) = (
+        annotation_state.current_project_id,
+        annotation_state.current_task_id,
+        auth_state.get_jwt(),
+    ) else {
+        return;
+    };
+
+    let token = token.clone();
+    flag_tasks.spawn(async move {
+        let reason = annotation_client::load_task_flag(project_id, task_id, token).await?;
+        Ok(TaskFlag { task_id, reason })
+    });
+}
+
+/// Shows the flag of the current task once it is loaded, flagged or cleared.
+pub fn task_flag_result_system(
+    mut flag_results: EventReader<ApiTaskSucceeded<TaskFlag>>,
+    mut flag_failed: EventReader<ApiTaskFailed<TaskFlag>>,
+    mut flag_state: ResMut<TaskFlagState>,
+) {
+    for ApiTaskSucceeded(flag) in flag_results.read() {
+        if flag_state.loaded_task_id != Some(flag.task_id) {
+            continue;
+        }
+        if flag.reason.is_some() {
+            flag_state.note.clear();
+        }
+        flag_state.current_reason = flag.reason.clone();
+        flag_state.error = None;
+    }
+
+    for failure in flag_failed.read() {
+        error!("Task flag request failed: {}", failure.error);
+        flag_state.error = Some(failure.error.clone());
+    }
+}
+
+/// Labels of a task in a classification project, as loaded or saved
+pub enum TaskLabels {
+    Loaded(TaskClassification),
+    /// Saved from the labels window, save & next opens another task after
+    Saved {
+        classification: TaskClassification,
+        project_id: Uuid,
+        open_next: bool,
+    },
+}
+
+/// Fetches the labels of the current task whenever the task changes, in classification projects.
+pub fn load_classification_system(
+    annotation_state: Res<AnnotationState>,
+    auth_state: Res<crate::auth::AuthState>,
+    interaction_state: Res<InteractionState>,
+    mut classification_state: ResMut<ClassificationState>,
+    label_tasks: Res<ApiTasks<TaskLabels>>,
+) {
+    if !interaction_state.labels_only || classification_state.loaded_task_id == annotation_state.current_task_id {
+        return;
+    }
+
+    *classification_state = ClassificationState {
+        loaded_task_id: annotation_state.current_task_id,
+        ..Default::default()
+    };
+
+    let (Some(project_id), Some(task_id), Some(token)) = (
+        annotation_state.current_project_id,
+        annotation_state.current_task_id,
+        auth_state.get_jwt(),
+    ) else {
+        return;
+    };
+
+    let token = token.clone();
+    label_tasks.spawn(async move {
+        annotation_client::load_classification(project_id, task_id, token).await.map(TaskLabels::Loaded)
+    });
+}
+
+/// Shows the labels of the current task once they are loaded, and moves on after save & next.
+pub fn labels_result_system(
+    mut label_results: EventReader<ApiTaskSucceeded<TaskLabels>>,
+    mut labels_failed: EventReader<ApiTaskFailed<TaskLabels>>,
+    mut classification_state: ResMut<ClassificationState>,
+    mut annotation_state: ResMut<AnnotationState>,
+    auth_state: Res<crate::auth::AuthState>,
+    next_tasks: Res<ApiTasks<NextTask>>,
+) {
+    for ApiTaskSucceeded(labels) in label_results.read() {
+        match labels {
+            TaskLabels::Loaded(classification) => {
+                if classification_state.loaded_task_id == Some(classification.task_id) {
+                    classification_state.labels = classification.labels.iter().map(|label| label.category_id).collect();
+                }
+            }
+            TaskLabels::Saved { classification, project_id, open_next } => {
+                info!("Labels saved successfully: {} labels", classification.labels.len());
+                annotation_state.is_saving = false;
+                classification_state.status = Some(t!("detail-labels-saved", count = classification.labels.len()));
+                classification_state.error = None;
+                if let (true, Some(token)) = (*open_next, auth_state.get_jwt()) {
+                    detail_ui::open_next_task(&next_tasks, &mut annotation_state, token, *project_id);
+                }
+            }
+        }
+    }
+
+    for failure in labels_failed.read() {
+        error!("Labels request failed: {}", failure.error);
+        annotation_state.is_saving = false;
+        classification_state.error = Some(failure.error.clone());
+    }
+}
+
+/// Class shortcuts pick the class of new boxes. Keys typed into text fields or meant for the
+/// shortcut editor don't switch classes (in classification projects they toggle labels instead,
+/// see classification_hotkeys_system).
+pub fn class_hotkeys_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    annotation_state: Res<AnnotationState>,
+    interaction_state: Res<InteractionState>,
+    shortcut_state: Res<ShortcutState>,
+    mut detail_data: ResMut<DetailData>,
+    mut egui_contexts: EguiContexts,
+) {
+    if interaction_state.labels_only
+        || shortcut_state.capturing.is_some()
+        || egui_contexts.ctx_mut().wants_keyboard_input()
+    {
+        return;
+    }
+
+    if let Some(class) = shortcuts::pressed_class(&keyboard, &annotation_state.categories) {
+        detail_data.selected_class = class;
+    }
+}
+
+/// Class shortcuts toggle the matching category and Enter asks for save & next, in classification projects.
+pub fn classification_hotkeys_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    annotation_state: Res<AnnotationState>,
+    interaction_state: Res<InteractionState>,
+    shortcut_state: Res<ShortcutState>,
+    mut classification_state: ResMut<ClassificationState>,
+    mut egui_contexts: EguiContexts,
+) {
+    if !interaction_state.labels_only
+        || shortcut_state.capturing.is_some()
+        || egui_contexts.ctx_mut().wants_keyboard_input()
+    {
+        return;
+    }
+
+    if let Some(class) = shortcuts::just_pressed_class(&keyboard, &annotation_state.categories) {
+        if let Some(category) = annotation_state.categories.get(class - 1) {
+            classification_state.toggle(category.id);
+        }
+    }
+
+    if keyboard.just_pressed(KeyCode::Enter) && !annotation_state.is_loading_next_task {
+        classification_state.save_and_next_requested = true;
+    }
+}
+
+/// Toggles the cheat sheet with F1 and hands the next key press to the shortcut editor while
+/// it waits for one. Esc cancels the capture.
+pub fn shortcut_keys_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut shortcut_state: ResMut<ShortcutState>,
+    mut egui_contexts: EguiContexts,
+) {
+    if let Some(category_id) = shortcut_state.capturing {
+        for key in keyboard.get_just_pressed() {
+            if *key == KeyCode::Escape {
+                shortcut_state.capturing = None;
+                break;
+            }
+            if let Some(name) = shortcuts::key_name(*key) {
+                // A key picks a single category, so it moves over from the one that had it
+                shortcut_state.draft.retain(|_, bound| bound != name);
+                shortcut_state.draft.insert(category_id, name.to_string());
+                shortcut_state.capturing = None;
+                break;
+            }
+        }
+        return;
+    }
+
+    if keyboard.just_pressed(KeyCode::F1) && !egui_contexts.ctx_mut().wants_keyboard_input() {
+        shortcut_state.show_cheat_sheet = !shortcut_state.show_cheat_sheet;
+    }
+}
+
+/// Windows of the shortcut cheat sheet and editor, shown for box and classification projects alike.
+/// Keeps track of unsaved boxes and saves them once they have been left alone for a few seconds.
+pub fn auto_save_system(
+    time: Res<Time>,
+    rectangles: Res<Rectangles>,
+    video_state: Res<VideoState>,
+    interaction_state: Res<InteractionState>,
+    mut annotation_state: ResMut<AnnotationState>,
+    mut auto_save: ResMut<AutoSaveState>,
+    mut save_events: EventWriter<SaveAnnotationsEvent>,
+) {
+    if auto_save.task_id != annotation_state.current_task_id {
+        auto_save.task_id = annotation_state.current_task_id;
+        auto_save.unsaved = false;
+        auto_save.last_snapshot = None;
+        auto_save.error = None;
+        annotation_state.saved_boxes = None;
+    }
+    // Saving again would only run into the same conflicts. The boxes of a task still loading
+    // aren't its boxes yet.
+    if interaction_state.labels_only
+        || annotation_state.current_task_id.is_none()
+        || annotation_state.pending_merge.is_some()
+        || annotation_state.is_loading_next_task
+        || annotation_state.is_saving
+    {
+        return;
+    }
+
+    let now = time.elapsed_secs_f64();
+    if now - auto_save.last_check < UNSAVED_CHECK_INTERVAL_SECS {
+        return;
+    }
+    auto_save.last_check = now;
+
+    let snapshot = annotation_snapshot(&rectangles.0, &video_state);
+    if annotation_state.saved_boxes.is_none() {
+        // Video boxes are loaded once they have been split up over the frames
+        let loaded = video_state.loaded_task_id == annotation_state.current_task_id
+            && !video_state.loading
+            && video_state.pending_annotations.is_none()
+            && video_state.pending_frame.is_none();
+        if loaded {
+            annotation_state.saved_boxes = Some(snapshot);
+        }
+        return;
+    }
+
+    auto_save.unsaved = annotation_state.saved_boxes.as_ref() != Some(&snapshot);
+    if !auto_save.unsaved {
+        auto_save.last_snapshot = None;
+        return;
+    }
+    if auto_save.last_snapshot.as_ref() != Some(&snapshot) {
+        auto_save.last_snapshot = Some(snapshot);
+        auto_save.last_change = now;
+        return;
+    }
+
+    // Boxes in the middle of a drag are left alone
+    if auto_save.enabled
+        && interaction_state.mode == InteractionMode::Default
+        && now - auto_save.last_change >= AUTO_SAVE_DELAY_SECS
+    {
+        // A failed save is tried again after another delay
+        auto_save.last_change = now;
+        save_events.write(SaveAnnotationsEvent);
+    }
+}
+
+/// What a save of the boxes is for, which decides what happens once it is done
+#[derive(Clone)]
+pub enum SavePurpose {
+    /// Save button
+    Manual,
+    /// Auto-save and the unsaved changes dialog, the page the user was leaving for opens after
+    Auto,
+    /// Save & next, a random unannotated task opens after
+    NextTask,
+    /// Save & interpolate, the tracks of the video are filled between their key frames after
+    Interpolate,
+    /// The box is saved onto the next image task after, which opens
+    Propagate(BoundingBox),
+    /// Boxes picked in the merge conflict dialog, which comes back when the save fails
+    Merge(PendingMerge),
+}
+
+/// Result of a save started by `start_save`
+pub struct SavedBoxes {
+    project_id: Uuid,
+    task_id: Uuid,
+    purpose: SavePurpose,
+    /// Boxes in the editor when the save started
+    snapshot: Option<AnnotationSnapshot>,
+    outcome: Result<SaveOutcome, String>,
+    follow_up: FollowUp,
+}
+
+/// What the request following a save came back with
+pub enum FollowUp {
+    None,
+    /// Boxes of every frame once the tracks were filled
+    Interpolated(Result<Vec<AnnotationWithCategory>, String>),
+    /// Task the box was propagated onto, `None` after the last image
+    Propagated(Result<Option<TaskWithResolvedUrl>, String>),
+}
+
+/// Saves `bounding_boxes` in the background, followed by the request `purpose` asks for once
+/// the boxes reached the server.
+fn start_save(
+    save_tasks: &ApiTasks<SavedBoxes>,
+    project_id: Uuid,
+    task_id: Uuid,
+    bounding_boxes: Vec<BoundingBox>,
+    snapshot: Option<AnnotationSnapshot>,
+    purpose: SavePurpose,
+    token: String,
+) {
+    save_tasks.spawn(async move {
+        let outcome = annotation_client::save_annotations(project_id, task_id, bounding_boxes, token.clone()).await;
+        let saved = matches!(outcome, Ok(SaveOutcome::Saved(_) | SaveOutcome::Merged(_)));
+        let follow_up = match &purpose {
+            SavePurpose::Interpolate if saved => {
+                let interpolated = async {
+                    annotation_client::interpolate(project_id, task_id, token.clone()).await?;
+                    annotation_client::load_annotations(project_id, task_id, token, true).await
+                };
+                FollowUp::Interpolated(interpolated.await)
+            }
+            SavePurpose::Propagate(bounding_box) if saved => {
+                FollowUp::Propagated(annotation_client::propagate_to_next_task(project_id, task_id, bounding_box.clone(), token).await)
+            }
+            _ => FollowUp::None,
+        };
+        Ok(SavedBoxes { project_id, task_id, purpose, snapshot, outcome, follow_up })
+    });
+}
+
+/// Starts the saves asked for by the buttons, propagation, auto-save and the unsaved changes
+/// dialog, one at a time. `save_result_system` carries on once a save is done.
+#[allow(clippy::too_many_arguments)]
+pub fn save_annotations_system(
+    mut save_events: EventReader<SaveAnnotationsEvent>,
+    rectangles: Res<Rectangles>,
+    video_state: Res<VideoState>,
+    detail_data: Res<DetailData>,
+    auth_state: Res<crate::auth::AuthState>,
+    mut annotation_state: ResMut<AnnotationState>,
+    mut auto_save: ResMut<AutoSaveState>,
+    save_tasks: Res<ApiTasks<SavedBoxes>>,
+    mut notify: EventWriter<Notify>,
+) {
+    let auto_save_requested = save_events.read().count() > 0;
+    let Some(purpose) = annotation_state.save_requested.take().or(auto_save_requested.then_some(SavePurpose::Auto)) else {
+        return;
+    };
+    // A failed auto-save is tried again after another delay
+    if annotation_state.is_saving {
+        return;
+    }
+    let (Some(project_id), Some(task_id), Some(token)) = (
+        annotation_state.current_project_id,
+        annotation_state.current_task_id,
+        auth_state.get_jwt(),
+    ) else {
+        return;
+    };
+
+    let bounding_boxes = detail_ui::collect_bounding_boxes(
+        &rectangles.0,
+        &video_state,
+        &annotation_state.categories,
+        detail_data.image_dimensions,
+    );
+    // The annotation controls list the broken rules
+    if !follows_labeling_rules(&mut annotation_state, &bounding_boxes) {
+        match purpose {
+            SavePurpose::Auto => auto_save.error = Some(t!("detail-rules-broken")),
+            SavePurpose::Propagate(_) => {
+                notify.write(Notify::error(t!("detail-rules-broken")));
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    annotation_state.is_saving = true;
+    let snapshot = annotation_snapshot(&rectangles.0, &video_state);
+    start_save(&save_tasks, project_id, task_id, bounding_boxes, Some(snapshot), purpose, token.clone());
+}
+
+/// Settles a save started by `start_save`: keeps what was saved as the boxes without unsaved
+/// changes, leaves merges and conflicts for the editor to show, then carries on with what the
+/// save was for.
+#[allow(clippy::too_many_arguments)]
+pub fn save_result_system(
+    mut commands: Commands,
+    mut saved_events: EventReader<ApiTaskSucceeded<SavedBoxes>>,
+    mut annotation_state: ResMut<AnnotationState>,
+    mut auto_save: ResMut<AutoSaveState>,
+    mut video_state: ResMut<VideoState>,
+    mut next_state: ResMut<NextState<AppState>>,
+    auth_state: Res<crate::auth::AuthState>,
+    next_tasks: Res<ApiTasks<NextTask>>,
+    mut notify: EventWriter<Notify>,
+) {
+    for ApiTaskSucceeded(saved) in saved_events.read() {
+        annotation_state.is_saving = false;
+        let task_id = saved.task_id;
+
+        if let SavePurpose::Merge(pending) = &saved.purpose {
+            match &saved.outcome {
+                Ok(SaveOutcome::Saved(annotations) | SaveOutcome::Merged(annotations)) => {
+                    notify.write(Notify::success(t!("detail-merge-saved")));
+                    annotation_state.merged_annotations = Some((task_id, annotations.clone()));
+                }
+                // Someone saved again in the meantime
+                Ok(SaveOutcome::Conflicts(next)) => annotation_state.pending_merge = Some(next.clone()),
+                Err(error) => {
+                    notify.write(Notify::error(t!("detail-save-failed", error = error.as_str())));
+                    annotation_state.pending_merge = Some(pending.clone());
+                }
+            }
+            continue;
+        }
+
+        let outcome = match &saved.outcome {
+            Ok(outcome) => outcome.clone(),
+            Err(error) => {
+                error!("Failed to save annotations: {}", error);
+                match saved.purpose {
+                    SavePurpose::Auto => auto_save.error = Some(t!("detail-save-failed", error = error.as_str())),
+                    SavePurpose::Interpolate => video_state.error = Some(t!("detail-interpolate-failed", error = error.as_str())),
+                    SavePurpose::Propagate(_) => {
+                        notify.write(Notify::error(t!("detail-save-failed", error = error.as_str())));
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+        };
+        if !annotation_state.settle_save(task_id, outcome) {
+            // The page is left once the conflicts are resolved and saved
+            auto_save.leave_to = None;
+            continue;
+        }
+        let current = annotation_state.current_task_id == Some(task_id);
+        if current {
+            annotation_state.saved_boxes = saved.snapshot.clone();
+        }
+
+        match (&saved.purpose, &saved.follow_up) {
+            (SavePurpose::Auto, _) => {
+                info!("Auto-saved annotations of task {}", task_id);
+                auto_save.unsaved = false;
+                auto_save.error = None;
+                if let Some(target) = auto_save.leave_to.take() {
+                    next_state.set(target);
+                }
+            }
+            (SavePurpose::NextTask, _) => {
+                info!("Annotations saved successfully");
+                if let Some(token) = auth_state.get_jwt() {
+                    detail_ui::open_next_task(&next_tasks, &mut annotation_state, token, saved.project_id);
+                }
+            }
+            (_, FollowUp::Interpolated(Ok(annotations))) => {
+                info!("Interpolated annotations loaded: {} boxes", annotations.len());
+                if current {
+                    // Redistribute the boxes over the frames and show the current one again
+                    video_state.pending_annotations = Some(annotations.clone());
+                    video_state.pending_frame = Some(video_state.current_frame);
+                    annotation_state.saved_boxes = None;
+                    // Merged boxes are among the loaded ones
+                    annotation_state.merged_annotations = None;
+                }
+            }
+            (_, FollowUp::Interpolated(Err(error))) => {
+                error!("Failed to interpolate annotations: {}", error);
+                video_state.error = Some(t!("detail-interpolate-failed", error = error.as_str()));
+            }
+            (_, FollowUp::Propagated(Ok(Some(next_task)))) => {
+                notify.write(Notify::success(t!("detail-propagated", task = next_task.task.name.as_str())));
+                detail_ui::open_task(&mut commands, &mut annotation_state, saved.project_id, next_task.clone());
+            }
+            (_, FollowUp::Propagated(Ok(None))) => {
+                notify.write(Notify::info(t!("detail-propagate-last-task")));
+            }
+            (_, FollowUp::Propagated(Err(error))) => {
+                notify.write(Notify::error(t!("detail-propagate-failed", error = error.as_str())));
+            }
+            _ => info!("Annotations saved successfully"),
+        }
+    }
+}
+
+/// Shows the boxes a save merged with someone else's in place of the edited ones.
+pub fn apply_merged_annotations_system(
+    mut annotation_state: ResMut<AnnotationState>,
+    mut rectangles: ResMut<Rectangles>,
+    mut selected_index: ResMut<SelectedRectangleIndex>,
+    mut video_state: ResMut<VideoState>,
+    detail_data: Res<DetailData>,
+) {
+    let Some((task_id, annotations)) = annotation_state.merged_annotations.take() else {
+        return;
+    };
+    // The editor moved on to another task meanwhile
+    if annotation_state.current_task_id != Some(task_id) {
+        return;
+    }
+
+    if video_state.is_video() {
+        // Redistribute the boxes over the frames and show the current one again
+        video_state.pending_annotations = Some(annotations);
+        video_state.pending_frame = Some(video_state.current_frame);
+    } else {
+        rectangles.0 = annotations
+            .iter()
+            .filter_map(|annotation| annotation_to_rectangle(annotation, &annotation_state.categories, detail_data.image_dimensions))
+            .collect();
+    }
+    selected_index.0 = None;
+    annotation_state.saved_boxes = None;
+}
+
+/// Asks which version to keep of each box a save found changed by someone else too, then
+/// saves the merge.
+pub fn merge_conflict_ui_system(
+    mut contexts: EguiContexts,
+    mut annotation_state: ResMut<AnnotationState>,
+    auth_state: Res<crate::auth::AuthState>,
+    save_tasks: Res<ApiTasks<SavedBoxes>>,
+) {
+    let Some(mut pending) = annotation_state.pending_merge.take() else {
+        return;
+    };
+
+    match detail_ui::render_merge_conflict_dialog(&mut contexts, &mut pending, &annotation_state.categories) {
+        Some(MergeConflictChoice::Save) => {
+            let Some(token) = auth_state.get_jwt() else {
+                annotation_state.pending_merge = Some(pending);
+                return;
+            };
+            let bounding_boxes = pending.merge.resolve(&pending.keep_mine);
+            annotation_state.is_saving = true;
+            let (project_id, task_id) = (pending.project_id, pending.task_id);
+            start_save(&save_tasks, project_id, task_id, bounding_boxes, None, SavePurpose::Merge(pending), token.clone());
+        }
+        Some(MergeConflictChoice::KeepTheirs) => {
+            annotation_state.merged_annotations = Some((pending.task_id, pending.theirs));
+        }
+        None => annotation_state.pending_merge = Some(pending),
+    }
+}
+
+/// Holds back page changes while there are unsaved boxes and asks whether to save them first.
+#[allow(clippy::too_many_arguments)]
+pub fn unsaved_changes_guard_system(
+    mut contexts: EguiContexts,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut auto_save: ResMut<AutoSaveState>,
+    mut annotation_state: ResMut<AnnotationState>,
+    rectangles: Res<Rectangles>,
+    video_state: Res<VideoState>,
+    interaction_state: Res<InteractionState>,
+    mut save_events: EventWriter<SaveAnnotationsEvent>,
+) {
+    if interaction_state.labels_only {
+        return;
+    }
+
+    if let NextState::Pending(target) = *next_state {
+        let unsaved = annotation_state.saved_boxes.as_ref()
+            .is_some_and(|saved| *saved != annotation_snapshot(&rectangles.0, &video_state));
+        if unsaved {
+            auto_save.leave_to = Some(target);
+            next_state.reset();
+        }
+    }
+
+    let AutoSaveState { enabled, unsaved, error, .. } = &mut *auto_save;
+    detail_ui::render_save_status(&mut contexts, enabled, *unsaved, error.as_deref());
+
+    let Some(target) = auto_save.leave_to else {
+        return;
+    };
+    match detail_ui::render_unsaved_changes_dialog(&mut contexts, auto_save.error.as_deref()) {
+        Some(UnsavedChangesChoice::Save) => {
+            save_events.write(SaveAnnotationsEvent);
+        }
+        Some(UnsavedChangesChoice::Discard) => {
+            annotation_state.saved_boxes = None;
+            auto_save.leave_to = None;
+            next_state.set(target);
+        }
+        Some(UnsavedChangesChoice::Cancel) => {
+            auto_save.leave_to = None;
+        }
+        None => {}
+    }
+}
+
+/// Window of the group operations while several boxes are selected
+pub fn group_ui_system(
+    mut contexts: EguiContexts,
+    mut rectangles: ResMut<Rectangles>,
+    mut selected_index: ResMut<SelectedRectangleIndex>,
+    mut handlers: ResMut<InteractionHandlers>,
+    mut command_history: ResMut<CommandHistory>,
+    annotation_state: Res<AnnotationState>,
+    interaction_state: Res<InteractionState>,
+    input_settings: Res<InputSettings>,
+) {
+    if interaction_state.labels_only {
+        return;
+    }
+
+    detail_ui::render_group_window(
+        &mut contexts,
+        &mut handlers.group,
+        &mut rectangles.0,
+        &mut selected_index.0,
+        &mut command_history,
+        &annotation_state.categories,
+        input_settings.select_modifier.name(),
+    );
+}
+
+/// Categories of the project after its class shortcuts were saved
+pub struct SavedHotkeys(pub Vec<AnnotationCategory>);
+
+pub fn shortcuts_ui_system(
+    mut contexts: EguiContexts,
+    mut shortcut_state: ResMut<ShortcutState>,
+    annotation_state: Res<AnnotationState>,
+    mut tour: ResMut<crate::onboarding::Tour>,
+    auth_state: Res<crate::auth::AuthState>,
+    input_settings: Res<InputSettings>,
+    hotkey_tasks: Res<ApiTasks<SavedHotkeys>>,
+) {
+    if detail_ui::render_shortcuts_cheat_sheet(&mut contexts, &mut shortcut_state, &annotation_state.categories, &input_settings) {
+        tour.start();
+    }
+    detail_ui::render_shortcut_editor_window(&mut contexts, &hotkey_tasks, &mut shortcut_state, &annotation_state, &auth_state);
+}
+
+/// Closes the shortcut editor once its keys are saved.
+pub fn hotkeys_result_system(
+    mut saved_hotkeys: EventReader<ApiTaskSucceeded<SavedHotkeys>>,
+    mut hotkeys_failed: EventReader<ApiTaskFailed<SavedHotkeys>>,
+    mut shortcut_state: ResMut<ShortcutState>,
+    mut annotation_state: ResMut<AnnotationState>,
+) {
+    for ApiTaskSucceeded(SavedHotkeys(categories)) in saved_hotkeys.read() {
+        info!("Saved class shortcuts for {} categories", categories.len());
+        annotation_state.categories = categories.clone();
+        shortcut_state.editor_open = false;
+        shortcut_state.capturing = None;
+        shortcut_state.saving = false;
+    }
+
+    for failure in hotkeys_failed.read() {
+        error!("Failed to save class shortcuts: {}", failure.error);
+        shortcut_state.saving = false;
+        shortcut_state.error = Some(failure.error.clone());
+    }
+}
+
+/// What came back from a request of the comments panel
+pub enum CommentsChange {
+    Loaded { task_id: Uuid, comments: Vec<Comment> },
+    Posted(Comment),
+    Updated(Comment),
+    Deleted { task_id: Uuid, comment_id: Uuid },
+}
+
+/// Reloads the comment thread whenever the current task changes.
+pub fn load_comments_system(
+    annotation_state: Res<AnnotationState>,
+    auth_state: Res<crate::auth::AuthState>,
+    mut comments_state: ResMut<CommentsState>,
+    comment_tasks: Res<ApiTasks<CommentsChange>>,
+) {
+    if comments_state.loaded_task_id == annotation_state.current_task_id {
+        return;
+    }
+
+    comments_state.loaded_task_id = annotation_state.current_task_id;
+    comments_state.comments.clear();
+    comments_state.reply_to = None;
+    comments_state.error = None;
+
+    let (Some(project_id), Some(task_id), Some(token)) = (
+        annotation_state.current_project_id,
+        annotation_state.current_task_id,
+        auth_state.get_jwt(),
+    ) else {
+        return;
+    };
+
+    let token = token.clone();
+    comment_tasks.spawn(async move {
+        let comments = annotation_client::load_comments(project_id, task_id, token).await?;
+        Ok(CommentsChange::Loaded { task_id, comments })
+    });
+}
+
+/// Applies what the comments panel's requests came back with to the thread of the current task.
+pub fn comments_result_system(
+    mut comment_results: EventReader<ApiTaskSucceeded<CommentsChange>>,
+    mut comments_failed: EventReader<ApiTaskFailed<CommentsChange>>,
+    mut comments_state: ResMut<CommentsState>,
+) {
+    for ApiTaskSucceeded(change) in comment_results.read() {
+        let task_id = match change {
+            CommentsChange::Loaded { task_id, .. } | CommentsChange::Deleted { task_id, .. } => *task_id,
+            CommentsChange::Posted(comment) | CommentsChange::Updated(comment) => comment.task_id,
+        };
+        if let CommentsChange::Posted(_) = change {
+            comments_state.posting = false;
+            comments_state.draft.clear();
+            comments_state.reply_to = None;
+        }
+        if comments_state.loaded_task_id != Some(task_id) {
+            continue;
+        }
+
+        match change {
+            CommentsChange::Loaded { comments, .. } => comments_state.comments = comments.clone(),
+            CommentsChange::Posted(comment) => comments_state.comments.push(comment.clone()),
+            CommentsChange::Updated(updated) => {
+                if let Some(comment) = comments_state.comments.iter_mut().find(|comment| comment.id == updated.id) {
+                    *comment = updated.clone();
+                }
+            }
+            CommentsChange::Deleted { comment_id, .. } => {
+                let comment_id = *comment_id;
+                comments_state.comments.retain(|comment| comment.id != comment_id && comment.parent_id != Some(comment_id));
+                if comments_state.reply_to == Some(comment_id) {
+                    comments_state.reply_to = None;
+                }
+            }
+        }
+        comments_state.error = None;
+    }
+
+    for failure in comments_failed.read() {
+        error!("Comments request failed: {}", failure.error);
+        comments_state.posting = false;
+        comments_state.error = Some(failure.error.clone());
+    }
+}
+
+/// Starts loading the task `detail_ui::open_task` switched to, `apply_loaded_task_system`
+/// shows it. The boxes of the task that was left go right away, so they are neither shown on
+/// the new task nor saved onto it.
+pub fn check_next_task_system(
+    mut commands: Commands,
+    next_task_marker: Option<Res<crate::ui::detail_ui::NextTaskMarker>>,
+    mut rectangles: ResMut<Rectangles>,
+    image_cache: Res<ImageCache>,
+    task_loads: Res<ApiTasks<LoadedTask>>,
+    auth_state: Res<crate::auth::AuthState>,
+) {
+    if let Some(marker) = next_task_marker {
+        info!("Processing next task marker");
+        rectangles.0.clear();
+        start_task_load(&task_loads, &image_cache, &marker.url, Some(marker.project_id), marker.task_id, auth_state.get_jwt(), false);
+        
+        // Remove the marker
+        commands.remove_resource::<crate::ui::detail_ui::NextTaskMarker>();
+    }
+}
+
+/// Switches to the task `detail_ui::open_next_task` found, if any was left.
+pub fn next_task_system(
+    mut commands: Commands,
+    mut next_tasks: EventReader<ApiTaskSucceeded<NextTask>>,
+    mut next_task_failed: EventReader<ApiTaskFailed<NextTask>>,
+    mut annotation_state: ResMut<AnnotationState>,
+) {
+    for ApiTaskSucceeded(next) in next_tasks.read() {
+        match &next.task {
+            Some(next_task) => {
+                info!("Found next task: {}", next_task.task.name);
+                detail_ui::open_task(&mut commands, &mut annotation_state, next.project_id, next_task.clone());
+            }
+            None => {
+                info!("No more unannotated tasks available");
+                annotation_state.is_loading_next_task = false;
+            }
+        }
+    }
+
+    for failure in next_task_failed.read() {
+        error!("Failed to get next task: {}", failure.error);
+        annotation_state.is_loading_next_task = false;
+    }
+}
+
+/// Boxes of the current task as the reload button fetched them
+pub struct ReloadedAnnotations {
+    task_id: Uuid,
+    annotations: Vec<AnnotationWithCategory>,
+}
+
+/// Fetches the boxes of the current task again when the reload button asks for it.
+pub fn reload_annotations_system(
+    mut annotation_state: ResMut<AnnotationState>,
+    auth_state: Res<crate::auth::AuthState>,
+    reload_tasks: Res<ApiTasks<ReloadedAnnotations>>,
+) {
+    if !std::mem::take(&mut annotation_state.reload_requested) {
+        return;
+    }
+    let (Some(project_id), Some(task_id), Some(token)) = (
+        annotation_state.current_project_id,
+        annotation_state.current_task_id,
+        auth_state.get_jwt(),
+    ) else {
+        return;
+    };
+
+    let token = token.clone();
+    reload_tasks.spawn(async move {
+        let annotations = annotation_client::load_annotations(project_id, task_id, token, true).await?;
+        Ok(ReloadedAnnotations { task_id, annotations })
+    });
+}
+
+/// Replaces the boxes in the editor with the reloaded ones.
+pub fn reloaded_annotations_system(
+    mut reloaded: EventReader<ApiTaskSucceeded<ReloadedAnnotations>>,
+    mut reload_failed: EventReader<ApiTaskFailed<ReloadedAnnotations>>,
+    mut annotation_state: ResMut<AnnotationState>,
+    mut rectangles: ResMut<Rectangles>,
+    detail_data: Res<DetailData>,
+) {
+    for failure in reload_failed.read() {
+        error!("Failed to load annotations: {}", failure.error);
+    }
+
+    let Some(ApiTaskSucceeded(reloaded)) = reloaded.read().last() else {
+        return;
+    };
+    // The editor moved on to another task meanwhile
+    if annotation_state.current_task_id != Some(reloaded.task_id) {
+        return;
+    }
+    let annotations = &reloaded.annotations;
+    let image_dimensions = detail_data.image_dimensions;
+    info!("Annotations loaded: {} annotations", annotations.len());
+    info!("Loaded annotations JSON: {}", serde_json::to_string_pretty(annotations).unwrap_or_else(|_| "Failed to serialize".to_string()));
+
+    // Convert loaded annotations back to rectangles
+    rectangles.0.clear();
+    for annotation_with_category in annotations {
+        if let Ok(bbox) = <&[f64; 4]>::try_from(annotation_with_category.bbox.as_slice()) {
+            let (pos1, pos2) = coordinates::bbox_to_rectangle(bbox, 1, image_dimensions).position;
+
+            // Extract class from metadata if available
+            let class = if let Some(class_value) = annotation_with_category.metadata.get("class") {
+                class_value.as_u64().unwrap_or(1) as usize
+            } else {
+                // Map category back to class (inverse of save mapping)
+                if let Some(cat_id) = annotation_with_category.category_id {
+                    if let Some(category_index) = annotation_state.categories.iter().position(|c| c.id == cat_id) {
+                        category_index + 1
+                    } else {
+                        1 // Default to class 1 if category not found
+                    }
+                } else {
+                    1 // Default to class 1 if no category
+                }
+            };
+
+            let rectangle = Rectangle {
+                position: (pos1, pos2),
+                class,
+                suggestion_score: annotation_with_category.is_prediction
+                    .then(|| annotation_with_category.confidence.unwrap_or(0.0) as f32),
+                attributes: annotation_with_category.attributes.as_object().cloned().unwrap_or_default(),
+                rotation: -(annotation_with_category.rotation as f32).to_radians(),
+                track_id: annotation_with_category.track_id,
+                interpolated: annotation_with_category.is_interpolated,
+                mask: annotation_with_category.mask.clone(),
+            };
+
+            rectangles.0.push(rectangle);
+        }
+    }
+
+    info!("Converted {} annotations to rectangles", rectangles.0.len());
+    annotation_state.saved_boxes = None;
+}
+
+// Annotation types and structures
+#[derive(Resource, Default)]
+pub struct AnnotationState {
+    pub is_saving: bool,
+    pub is_loading_next_task: bool,
+    pub categories: Vec<AnnotationCategory>,
+    pub current_task_id: Option<Uuid>,
+    pub current_project_id: Option<Uuid>,
+    pub current_task_name: Option<String>,
+    pub image_url: Option<String>,
+    /// Boxes as last loaded or saved, to tell whether there are unsaved changes. `None` until
+    /// the boxes of the current task have been loaded.
+    pub saved_boxes: Option<AnnotationSnapshot>,
+    /// Labeling rules of the project, checked before boxes are saved
+    pub labeling_rules: LabelingRules,
+    /// Rules the boxes of the last save broke, which held the save back
+    pub rule_violations: Vec<String>,
+    /// Boxes both the user and someone else changed since they were loaded, until the user
+    /// picks a version of each
+    pub pending_merge: Option<PendingMerge>,
+    /// Boxes of a task as a save merged them with someone else's, for the editor to show
+    pub merged_annotations: Option<(Uuid, Vec<AnnotationWithCategory>)>,
+    /// Save asked for by the buttons and by propagation, started by `save_annotations_system`
+    pub save_requested: Option<SavePurpose>,
+    /// Set by the reload button and handled by `reload_annotations_system`
+    pub reload_requested: bool,
+}
+
+impl AnnotationState {
+    /// Keeps what a save leaves for the editor to do. True when the boxes reached the server,
+    /// merged with someone else's or not, false when the user has to resolve conflicts first.
+    pub fn settle_save(&mut self, task_id: Uuid, outcome: SaveOutcome) -> bool {
+        match outcome {
+            SaveOutcome::Saved(_) => true,
+            SaveOutcome::Merged(annotations) => {
+                self.merged_annotations = Some((task_id, annotations));
+                true
+            }
+            SaveOutcome::Conflicts(pending) => {
+                self.pending_merge = Some(pending);
+                false
+            }
+        }
+    }
+}
+
+/// What became of the boxes handed to `annotation_client::save_annotations`
+#[derive(Clone)]
+pub enum SaveOutcome {
+    /// Saved as they are in the editor, or queued while the server can't be reached
+    Saved(Vec<AnnotationWithCategory>),
+    /// Someone saved the task since the boxes were loaded, and changed other boxes. Their
+    /// changes were merged in and saved along.
+    Merged(Vec<AnnotationWithCategory>),
+    /// Someone changed the same boxes since they were loaded, nothing was saved
+    Conflicts(PendingMerge),
+}
+
+/// A save that ran into boxes someone else changed too
+#[derive(Clone)]
+pub struct PendingMerge {
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub merge: BoxMerge,
+    /// Version kept of each conflict, the user's where true
+    pub keep_mine: Vec<bool>,
+    /// Latest boxes on the server, shown when the user drops their changes
+    pub theirs: Vec<AnnotationWithCategory>,
+}
+
+/// Checks the boxes against the project's labeling rules before they are saved. Broken rules
+/// are kept on the state for the editor to show.
+pub fn follows_labeling_rules(annotation_state: &mut AnnotationState, bounding_boxes: &[BoundingBox]) -> bool {
+    let violations = labeling_rules::check(&annotation_state.labeling_rules, bounding_boxes);
+    annotation_state.rule_violations = violations
+        .iter()
+        .map(|violation| describe_rule_violation(violation, annotation_state))
+        .collect();
+    annotation_state.rule_violations.is_empty()
+}
+
+fn describe_rule_violation(violation: &RuleViolation, annotation_state: &AnnotationState) -> String {
+    let max = annotation_state.labeling_rules.max_boxes_per_image.unwrap_or_default();
+    match violation {
+        RuleViolation::BoxTooSmall { index, width, height } => t!(
+            "detail-rule-box-too-small",
+            number = index + 1,
+            width = width.round(),
+            height = height.round()
+        ),
+        RuleViolation::TooManyBoxes { frame_index: Some(frame), count } => {
+            t!("detail-rule-too-many-boxes-frame", frame = frame + 1, count = *count, max = max)
+        }
+        RuleViolation::TooManyBoxes { frame_index: None, count } => {
+            t!("detail-rule-too-many-boxes", count = *count, max = max)
+        }
+        RuleViolation::MissingCategory { category_id } => {
+            let category = annotation_state.categories.iter()
+                .find(|category| category.id == *category_id)
+                .map_or_else(|| category_id.to_string(), |category| category.name.clone());
+            t!("detail-rule-missing-category", category = category)
+        }
+    }
+}
+
+// API types are now re-exported at the top of the file
+
+// Adapter functions to maintain existing interface while using new API modules
+pub mod annotation_client {
+    use super::*;
+
+    /// Saves the boxes over the latest annotations the editor loaded. When someone saved the
+    /// task meanwhile their boxes are merged with these, and only boxes both changed are left
+    /// for the user to decide about.
+    pub async fn save_annotations(
+        project_id: Uuid,
+        task_id: Uuid,
+        bounding_boxes: Vec<BoundingBox>,
+        token: String,
+    ) -> Result<SaveOutcome, String> {
+        let annotations_api = AnnotationsApi::new();
+        
+        let store = offline_store::store();
+        // Without a cached copy there is nothing to tell someone else's changes by
+        let base = store.known_annotations(task_id);
+        let base_annotation_id = base.as_deref()
+            .map(|base| offline_store::latest_annotation_id(base).unwrap_or(Uuid::nil()));
+        let conflict = match annotations_api.save_annotations_based_on(&token, project_id, task_id, &bounding_boxes, base_annotation_id).await {
+            Ok(saved) => {
+                remember_save(task_id, &saved);
+                return Ok(SaveOutcome::Saved(saved));
+            }
+            Err(error) if error.is_network_error() => {
+                warn!("Server unreachable, keeping the annotations until it is back: {}", error);
+                store.queue_save(project_id, task_id, bounding_boxes);
+                crate::progress::record_save(task_id);
+                let queued = store.queued_save(task_id).map(|queued| queued.annotations());
+                return Ok(SaveOutcome::Saved(queued.unwrap_or_default()));
+            }
+            Err(error) => AnnotationConflict::from_error(&error).ok_or_else(|| error.to_string())?,
+        };
+
+        // Their boxes are what the next save of this task starts from
+        store.set_annotations(task_id, &conflict.annotations);
+        let boxes_of = |annotations: &[AnnotationWithCategory]| -> Vec<BoundingBox> {
+            annotations.iter().filter_map(BoundingBox::from_annotation).collect()
+        };
+        let merge = merge::merge(
+            &boxes_of(&base.unwrap_or_default()),
+            &bounding_boxes,
+            &boxes_of(&conflict.annotations),
+        );
+        info!(
+            "Task {} was saved meanwhile: {} boxes merged, {} conflicts",
+            task_id,
+            merge.merged.len(),
+            merge.conflicts.len()
+        );
+        if !merge.conflicts.is_empty() {
+            return Ok(SaveOutcome::Conflicts(PendingMerge {
+                project_id,
+                task_id,
+                keep_mine: vec![true; merge.conflicts.len()],
+                merge,
+                theirs: conflict.annotations,
+            }));
+        }
+
+        let base_annotation_id = conflict.latest_annotation_id.unwrap_or(Uuid::nil());
+        let saved = annotations_api.save_annotations_based_on(&token, project_id, task_id, &merge.merged, Some(base_annotation_id)).await
+            .map_err(|e| e.to_string())?;
+        remember_save(task_id, &saved);
+        Ok(SaveOutcome::Merged(saved))
+    }
+
+    /// Keeps a save that reached the server as the latest annotations of the task
+    fn remember_save(task_id: Uuid, saved: &[AnnotationWithCategory]) {
+        let store = offline_store::store();
+        crate::progress::record_save(task_id);
+        // This save supersedes whatever was still waiting to be sent
+        store.remove_queued_save(task_id);
+        // Saving no boxes leaves the server untouched
+        if !saved.is_empty() {
+            store.set_annotations(task_id, saved);
+        }
+    }
+
+    pub async fn load_annotations(
+        project_id: Uuid,
+        task_id: Uuid,
+        token: String,
+        latest_only: bool,
     ) -> Result<Vec<AnnotationWithCategory>, String> {
         let annotations_api = AnnotationsApi::new();
-        let runtime = tokio::runtime::Runtime::new()
-            .map_err(|e| format!("Failed to create runtime: {}", e))?;
         
-        runtime.block_on(async {
-            annotations_api.list_annotations_with_options(&token, project_id, task_id, latest_only).await
-                .map_err(|e| e.to_string())
-        })
+        let result = annotations_api.list_annotations_with_options(&token, project_id, task_id, latest_only).await;
+        if !latest_only {
+            return result.map_err(|e| e.to_string());
+        }
+
+        let store = offline_store::store();
+        let annotations = store.annotations(task_id, result).map_err(|e| e.to_string())?;
+        // Boxes saved while offline are newer than anything the server has
+        match store.queued_save(task_id) {
+            Some(queued) => Ok(queued.annotations()),
+            None => Ok(annotations),
+        }
+    }
+
+    /// Puts `bounding_box` on the image task after `task_id` in name order, the next frame of an
+    /// image sequence, in place of the box its track has there. `None` after the last image.
+    pub async fn propagate_to_next_task(
+        project_id: Uuid,
+        task_id: Uuid,
+        bounding_box: BoundingBox,
+        token: String,
+    ) -> Result<Option<TaskWithResolvedUrl>, String> {
+        let tasks = TasksApi::new().list_tasks(&token, &project_id.to_string()).await
+            .map_err(|e| e.to_string())?;
+        let mut images: Vec<TaskWithResolvedUrl> = tasks.into_iter().filter(|task| task.task.is_image()).collect();
+        images.sort_by(|a, b| a.task.name.cmp(&b.task.name));
+        let next_task = images
+            .iter()
+            .position(|task| task.task.id == task_id.to_string())
+            .and_then(|position| images.get(position + 1).cloned());
+        let Some(next_task) = next_task else {
+            return Ok(None);
+        };
+        let next_task_id = Uuid::parse_str(&next_task.task.id).map_err(|e| e.to_string())?;
+
+        let mut bounding_boxes: Vec<BoundingBox> = load_annotations(project_id, next_task_id, token.clone(), true).await?
+            .iter()
+            .filter_map(BoundingBox::from_annotation)
+            .filter(|existing| existing.track_id != bounding_box.track_id)
+            .collect();
+        bounding_boxes.push(bounding_box);
+        match save_annotations(project_id, next_task_id, bounding_boxes, token).await? {
+            SaveOutcome::Conflicts(_) => Err(t!("detail-propagate-conflict")),
+            SaveOutcome::Saved(_) | SaveOutcome::Merged(_) => Ok(Some(next_task)),
+        }
+    }
+
+    /// Sends a single foreground click to the segmentation server and returns the tight COCO box.
+    pub async fn segment_at_point(
+        project_id: Uuid,
+        task_id: Uuid,
+        x: f32,
+        y: f32,
+        token: String,
+    ) -> Result<[f64; 4], String> {
+        let segmentation_api = SegmentationApi::new();
+
+        let request = SegmentRequest {
+            points: vec![PromptPoint { x: x as f64, y: y as f64, label: 1 }],
+            prompt_box: None,
+        };
+
+        let response = segmentation_api.segment(&token, project_id, task_id, &request).await
+            .map_err(|e| e.to_string())?;
+
+        if let Some(score) = response.score {
+            info!("Magic select mask score: {:.2}", score);
+        }
+
+        match <[f64; 4]>::try_from(response.bbox.as_slice()) {
+            Ok(bbox) if bbox[2] > 0.0 && bbox[3] > 0.0 => Ok(bbox),
+            _ => Err("Segmentation returned an empty box".to_string()),
+        }
+    }
+
+    pub async fn load_task_flag(
+        project_id: Uuid,
+        task_id: Uuid,
+        token: String,
+    ) -> Result<Option<String>, String> {
+        let tasks_api = TasksApi::new();
+
+        tasks_api.get_task(&token, &project_id.to_string(), &task_id.to_string()).await
+            .map(|task| task.flag_reason)
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn flag_task(
+        project_id: Uuid,
+        task_id: Uuid,
+        reason: String,
+        note: Option<String>,
+        token: String,
+    ) -> Result<(), String> {
+        let tasks_api = TasksApi::new();
+
+        tasks_api.flag_task(&token, &project_id.to_string(), &task_id.to_string(), &reason, note.as_deref()).await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn unflag_task(
+        project_id: Uuid,
+        task_id: Uuid,
+        token: String,
+    ) -> Result<(), String> {
+        let tasks_api = TasksApi::new();
+
+        tasks_api.unflag_task(&token, &project_id.to_string(), &task_id.to_string()).await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn load_classification(
+        project_id: Uuid,
+        task_id: Uuid,
+        token: String,
+    ) -> Result<TaskClassification, String> {
+        let classifications_api = ClassificationsApi::new();
+
+        classifications_api.get_classification(&token, project_id, task_id).await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn save_classification(
+        project_id: Uuid,
+        task_id: Uuid,
+        category_ids: Vec<Uuid>,
+        token: String,
+    ) -> Result<TaskClassification, String> {
+        let classifications_api = ClassificationsApi::new();
+
+        classifications_api.set_classification(&token, project_id, task_id, category_ids).await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn save_hotkeys(
+        project_id: Uuid,
+        hotkeys: Vec<CategoryHotkey>,
+        token: String,
+    ) -> Result<Vec<AnnotationCategory>, String> {
+        let categories_api = CategoriesApi::new();
+
+        categories_api.update_hotkeys(&token, project_id, hotkeys).await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn load_frames(
+        project_id: Uuid,
+        task_id: Uuid,
+        token: String,
+    ) -> Result<TaskFramesResponse, String> {
+        let tasks_api = TasksApi::new();
+
+        tasks_api.list_frames(&token, &project_id.to_string(), &task_id.to_string()).await
+            .map_err(|e| e.to_string())
+    }
+
+    /// `None` when the task's image is small enough to be loaded whole.
+    pub async fn load_tile_info(
+        project_id: Uuid,
+        task_id: Uuid,
+        token: String,
+    ) -> Result<Option<TileInfo>, String> {
+        let tasks_api = TasksApi::new();
+
+        match tasks_api.get_tile_info(&token, &project_id.to_string(), &task_id.to_string()).await {
+            Ok(info) => Ok(Some(info)),
+            Err(crate::api::ApiError::NotFound(_)) => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    pub async fn interpolate(
+        project_id: Uuid,
+        task_id: Uuid,
+        token: String,
+    ) -> Result<usize, String> {
+        let annotations_api = AnnotationsApi::new();
+
+        annotations_api.interpolate(&token, project_id, task_id).await
+            .map(|response| response.interpolated_count)
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn load_comments(
+        project_id: Uuid,
+        task_id: Uuid,
+        token: String,
+    ) -> Result<Vec<Comment>, String> {
+        let comments_api = CommentsApi::new();
+
+        comments_api.list_comments(&token, project_id, task_id).await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn post_comment(
+        project_id: Uuid,
+        task_id: Uuid,
+        request: CreateCommentRequest,
+        token: String,
+    ) -> Result<Comment, String> {
+        let comments_api = CommentsApi::new();
+
+        comments_api.create_comment(&token, project_id, task_id, &request).await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn set_comment_resolved(
+        project_id: Uuid,
+        task_id: Uuid,
+        comment_id: Uuid,
+        resolved: bool,
+        token: String,
+    ) -> Result<Comment, String> {
+        let comments_api = CommentsApi::new();
+
+        let request = UpdateCommentRequest { body: None, resolved: Some(resolved) };
+        comments_api.update_comment(&token, project_id, task_id, comment_id, &request).await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn delete_comment(
+        project_id: Uuid,
+        task_id: Uuid,
+        comment_id: Uuid,
+        token: String,
+    ) -> Result<(), String> {
+        let comments_api = CommentsApi::new();
+
+        comments_api.delete_comment(&token, project_id, task_id, comment_id).await
+            .map_err(|e| e.to_string())
     }
 
 }
@@ -591,18 +3624,49 @@ pub struct DetailPlugin;
 
 impl Plugin for DetailPlugin {
     fn build(&self, app: &mut App) {
-        app.init_gizmo_group::<SelectedRect>()
+        app.add_plugins((
+               ApiTaskPlugin::<SegmentedBox>::default(),
+               ApiTaskPlugin::<LoadedTask>::default(),
+               ApiTaskPlugin::<NextTask>::default(),
+               ApiTaskPlugin::<LoadedFrames>::default(),
+               ApiTaskPlugin::<LoadedFrame>::default(),
+               ApiTaskPlugin::<SavedBoxes>::default(),
+               ApiTaskPlugin::<ReloadedAnnotations>::default(),
+               ApiTaskPlugin::<TaskFlag>::default(),
+               ApiTaskPlugin::<TaskLabels>::default(),
+               ApiTaskPlugin::<SavedHotkeys>::default(),
+               ApiTaskPlugin::<CommentsChange>::default(),
+           ))
+           .init_gizmo_group::<SelectedRect>()
+           .init_gizmo_group::<SuggestionRect>()
            .init_resource::<Parameters>()
            .init_resource::<Rectangles>()
            .init_resource::<SelectedRectangleIndex>()
            .init_resource::<InteractionState>()
            .init_resource::<InteractionHandlers>()
            .init_resource::<AnnotationState>()
+           .init_resource::<CommentsState>()
+           .init_resource::<TaskFlagState>()
+           .init_resource::<ClassificationState>()
+           .init_resource::<VideoState>()
+           .init_resource::<TileState>()
+           .init_resource::<ShortcutState>()
+           .init_resource::<AutoSaveState>()
+           .init_resource::<ViewAdjustments>()
+           .init_resource::<DrawingAids>()
+           .insert_resource(InputSettings::load())
+           .init_resource::<TouchState>()
+           .add_event::<SaveAnnotationsEvent>()
            .add_systems(OnEnter(AppState::Detail), setup)
-           .add_systems(Update, (update, check_next_task_system).run_if(in_state(AppState::Detail)))
+           // The editor needs the image of the task, which is loaded in the background
+           .add_systems(Update, (apply_loaded_task_system, (check_next_task_system, next_task_system).before(apply_loaded_task_system), (save_result_system, task_flag_result_system, labels_result_system, hotkeys_result_system, comments_result_system)).run_if(in_state(AppState::Detail)))
+           .add_systems(Update, ((update, (magic_select_system, magic_select_result_system).after(update), nudge_system.after(update), (draw_suggestions, box_labels_system.after(update).after(video_frame_system), mask_overlay_system.after(update).after(video_frame_system).after(mask_paint_system), mask_paint_system.after(update), box_outlines_system.after(update).after(video_frame_system), camera_control_system.after(update), touch_input_system.before(update).before(magic_select_system).before(mask_paint_system), drawing_aids_system.after(update)), load_comments_system, load_task_flag_system, load_classification_system, shortcut_keys_system, class_hotkeys_system.before(shortcut_keys_system).before(update), classification_hotkeys_system.before(shortcut_keys_system)), (load_video_frames_system, apply_video_frames_system.after(load_video_frames_system), (propagate_box_system, load_video_frame_system, video_frame_system).chain().after(apply_video_frames_system), view_adjustment_system.after(video_frame_system), tile_loader::tile_streaming_system, auto_save_system.after(video_frame_system).after(check_next_task_system), (save_annotations_system.after(auto_save_system).before(save_result_system), apply_merged_annotations_system.after(save_annotations_system).after(save_result_system)), (reload_annotations_system, reloaded_annotations_system).chain(), prefetch_upcoming_system, zoom_shortcuts_system)).run_if(in_state(AppState::Detail)).run_if(resource_exists::<DetailData>))
            .add_systems(
                EguiContextPass,
-               ui_system.run_if(in_state(AppState::Detail)),
+               (
+                   loading_ui_system.run_if(not(resource_exists::<DetailData>)),
+                   (ui_system, group_ui_system.after(ui_system), shortcuts_ui_system.after(ui_system), unsaved_changes_guard_system.after(ui_system), merge_conflict_ui_system.after(ui_system), view_adjustments_ui_system.after(ui_system), layers_ui_system.after(ui_system), minimap_ui_system.after(ui_system), drawing_aids_ui_system.after(ui_system), input_settings_ui_system.after(ui_system)).run_if(resource_exists::<DetailData>),
+               ).run_if(in_state(AppState::Detail)),
            )
            .add_systems(OnExit(AppState::Detail), cleanup);
     }
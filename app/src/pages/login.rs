@@ -1,50 +1,233 @@
-use crate::api::auth::AuthApi;
+use crate::api::ApiConfig;
+use crate::api::auth::{AuthApi, User};
+use crate::api::health::HealthApi;
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
 use crate::app::state::AppState;
-use crate::auth::AuthState;
+use crate::auth::{AuthState, UserState};
+use crate::io::server_profiles::ServerProfiles;
+use crate::io::session_store::{self, StoredSession};
+use crate::platform;
+use bevy::platform::time::Instant;
 use bevy::prelude::*;
 use bevy_egui::{EguiContexts, EguiContextPass, egui};
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 
 #[derive(Debug, Clone, PartialEq, Default)]
 enum LoginState {
     #[default]
     Idle,
+    /// The remembered session is being checked with the server
+    Restoring,
+    /// Asking the server for the sign-in page of a provider
+    Starting,
     WaitingForAuth {
         poll_token: String,
+        /// Sign-in page of the provider, linked in case the browser didn't open it
+        auth_url: String,
         start_time: Instant,
     },
     Success(String), // JWT token
     Error(String),
 }
 
+/// Outcome of a request of the login page. Failures are shown as `LoginState::Error`.
+pub enum LoginStep {
+    /// The remembered session, with `user` left out when the server couldn't be reached
+    Restored { session: StoredSession, user: Option<User> },
+    /// The remembered session was rejected and is forgotten
+    Expired,
+    Started { poll_token: String, auth_url: String },
+    /// The session once the sign-in in the browser is complete, `None` until then
+    Polled(Option<StoredSession>),
+}
+
+/// Outcome of a connection check, as shown below the server
+pub struct HealthCheck(Result<String, String>);
+
 #[derive(Resource, Default)]
 pub struct LoginResource {
     state: LoginState,
     last_poll_time: Option<Instant>,
+    /// A poll for the sign-in is on its way
+    is_polling: bool,
+    /// Keep the session in the OS keyring for the next launch
+    remember_me: bool,
+    profiles: ServerProfiles,
+    server_url: String,
+    profile_name: String,
+    /// Outcome of the last connection check of `server_url`
+    health: Option<Result<String, String>>,
+    is_checking_health: bool,
 }
 
-pub fn setup(mut commands: Commands) {
-    commands.insert_resource(LoginResource::default());
+impl LoginResource {
+    /// Makes the typed server the one every request goes to, and the one picked next launch
+    fn apply_server(&mut self) {
+        ApiConfig::set_base_url(&self.server_url);
+        self.profiles.selected_url = Some(ApiConfig::default().base_url);
+        if let Err(error) = self.profiles.save() {
+            warn!("Failed to save server profiles: {}", error);
+        }
+    }
+}
+
+pub fn setup(
+    mut commands: Commands,
+    mut auth_state: ResMut<AuthState>,
+    mut user_state: ResMut<UserState>,
+    login_tasks: Res<ApiTasks<LoginStep>>,
+) {
     println!("login setup");
+
+    // The server used last, before a remembered session of it is looked up
+    let profiles = ServerProfiles::load();
+    if let Some(url) = &profiles.selected_url {
+        ApiConfig::set_base_url(url);
+    }
+    let server_url = ApiConfig::default().base_url;
+    let mut login_resource = LoginResource {
+        profile_name: profiles.find(&server_url).map(|profile| profile.name.clone()).unwrap_or_default(),
+        server_url,
+        profiles,
+        ..default()
+    };
+
+    // Coming back here while logged in is logging out
+    if auth_state.is_authenticated() {
+        auth_state.clear();
+        user_state.clear();
+        session_store::clear();
+    } else if let Some(session) = session_store::load() {
+        login_resource.state = LoginState::Restoring;
+        login_tasks.spawn(restore_session(session));
+    }
+    commands.insert_resource(login_resource);
+}
+
+/// Checks the remembered session with the server before it is used
+async fn restore_session(mut session: StoredSession) -> Result<LoginStep, String> {
+    let mut user_info = AuthApi::new().get_user_info(&session.jwt).await;
+    // An expired JWT is swapped for a new one while the session is still signed in
+    if let (Err(error), Some(refresh_token)) = (&user_info, &session.refresh_token) {
+        if !error.is_network_error() {
+            match refresh_session(refresh_token).await {
+                Ok((refreshed, user)) => {
+                    if let Err(error) = session_store::save(&refreshed) {
+                        warn!("Failed to save the refreshed session: {}", error);
+                    }
+                    session = refreshed;
+                    user_info = Ok(user);
+                }
+                Err(error) => info!("Failed to refresh the stored session: {}", error),
+            }
+        }
+    }
+    match user_info {
+        Ok(user) => {
+            info!("Restored the session of {}", user.name);
+            Ok(LoginStep::Restored { session, user: Some(user) })
+        }
+        // Offline the stored token is all there is to go on
+        Err(error) if error.is_network_error() => {
+            info!("Restored the session without checking it: {}", error);
+            Ok(LoginStep::Restored { session, user: None })
+        }
+        Err(error) => {
+            info!("Stored session is no longer valid: {}", error);
+            session_store::clear();
+            Ok(LoginStep::Expired)
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn process_login_results(
+    mut succeeded: EventReader<ApiTaskSucceeded<LoginStep>>,
+    mut failed: EventReader<ApiTaskFailed<LoginStep>>,
+    mut health_checks: EventReader<ApiTaskSucceeded<HealthCheck>>,
+    mut login_resource: ResMut<LoginResource>,
+    mut auth_state: ResMut<AuthState>,
+    mut user_state: ResMut<UserState>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for ApiTaskSucceeded(step) in succeeded.read() {
+        match step {
+            LoginStep::Restored { session, user } => {
+                if let Some(user) = user {
+                    user_state.set_user(user.clone());
+                }
+                login_resource.state = LoginState::Idle;
+                auth_state.set_jwt(session.jwt.clone());
+                next_state.set(AppState::Projects);
+            }
+            LoginStep::Expired => login_resource.state = LoginState::Idle,
+            LoginStep::Started { poll_token, auth_url } => {
+                // The page is linked too, in case no browser opens
+                if let Err(error) = platform::open_url(auth_url) {
+                    login_resource.state = LoginState::Error(t!("login-open-browser-failed", error = error));
+                    continue;
+                }
+                login_resource.state = LoginState::WaitingForAuth {
+                    poll_token: poll_token.clone(),
+                    auth_url: auth_url.clone(),
+                    start_time: Instant::now(),
+                };
+                login_resource.last_poll_time = None;
+            }
+            LoginStep::Polled(session) => {
+                login_resource.is_polling = false;
+                // Cancelled meanwhile
+                if !matches!(login_resource.state, LoginState::WaitingForAuth { .. }) {
+                    continue;
+                }
+                let Some(session) = session else {
+                    continue;
+                };
+                if login_resource.remember_me {
+                    if let Err(error) = session_store::save(session) {
+                        warn!("Failed to remember the session: {}", error);
+                    }
+                } else {
+                    session_store::clear();
+                }
+                login_resource.state = LoginState::Success(session.jwt.clone());
+                auth_state.set_jwt(session.jwt.clone());
+                next_state.set(AppState::Projects);
+            }
+        }
+    }
+
+    for failure in failed.read() {
+        login_resource.is_polling = false;
+        login_resource.state = LoginState::Error(failure.error.clone());
+    }
+
+    for ApiTaskSucceeded(HealthCheck(health)) in health_checks.read() {
+        login_resource.is_checking_health = false;
+        login_resource.health = Some(health.clone());
+    }
 }
 
 pub fn update(
     mut login_resource: ResMut<LoginResource>,
     mut next_state: ResMut<NextState<AppState>>,
-    mut auth_state: ResMut<AuthState>,
+    login_tasks: Res<ApiTasks<LoginStep>>,
 ) {
     let now = Instant::now();
     
     match &login_resource.state {
-        LoginState::WaitingForAuth { poll_token, start_time } => {
+        LoginState::WaitingForAuth { poll_token, start_time, .. } => {
             // Timeout check (5 minutes)
             if now.duration_since(*start_time) > Duration::from_secs(300) {
-                login_resource.state = LoginState::Error("Authentication timeout".to_string());
+                login_resource.state = LoginState::Error(t!("login-timeout"));
                 return;
             }
             
             // Polling interval check (2 second intervals)
+            if login_resource.is_polling {
+                return;
+            }
             if let Some(last_poll) = login_resource.last_poll_time {
                 if now.duration_since(last_poll) < Duration::from_secs(2) {
                     return;
@@ -53,22 +236,9 @@ pub fn update(
             
             // Execute polling to retrieve JWT
             let poll_token = poll_token.clone();
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            
-            match rt.block_on(poll_for_jwt(&poll_token)) {
-                Ok(Some(jwt)) => {
-                    login_resource.state = LoginState::Success(jwt.clone());
-                    auth_state.set_jwt(jwt);
-                    next_state.set(AppState::Projects);
-                }
-                Ok(None) => {
-                    // Authentication not yet completed
-                    login_resource.last_poll_time = Some(now);
-                }
-                Err(err) => {
-                    login_resource.state = LoginState::Error(err);
-                }
-            }
+            login_tasks.spawn(async move { poll_for_jwt(&poll_token).await.map(LoginStep::Polled) });
+            login_resource.is_polling = true;
+            login_resource.last_poll_time = Some(now);
         }
         LoginState::Success(_) => {
             // Already logged in successfully, transition to Projects screen
@@ -83,6 +253,8 @@ pub fn ui_system(
     _current_state: Res<State<AppState>>,
     mut next_state: ResMut<NextState<AppState>>,
     mut login_resource: ResMut<LoginResource>,
+    login_tasks: Res<ApiTasks<LoginStep>>,
+    health_tasks: Res<ApiTasks<HealthCheck>>,
 ) {
     // Temporarily skip top panel and implement login UI first
     
@@ -90,107 +262,208 @@ pub fn ui_system(
         ui.vertical_centered(|ui| {
             ui.add_space(100.0);
             
-            ui.heading("Login");
+            ui.heading(t!("login-title"));
             ui.add_space(50.0);
             
             match &login_resource.state {
                 LoginState::Idle => {
+                    render_server_settings(ui, &mut login_resource, &health_tasks);
+                    ui.add_space(20.0);
+
                     // GitHub login button
-                    if ui.button("🚀 Login with GitHub").clicked() {
-                        start_oauth_login("github", &mut login_resource);
+                    if ui.button(t!("login-with-github")).clicked() {
+                        login_resource.apply_server();
+                        start_oauth_login("github", &mut login_resource, &login_tasks);
                     }
                     
                     ui.add_space(10.0);
                     
                     // Google login button
-                    if ui.button("🔍 Login with Google").clicked() {
-                        start_oauth_login("google", &mut login_resource);
+                    if ui.button(t!("login-with-google")).clicked() {
+                        login_resource.apply_server();
+                        start_oauth_login("google", &mut login_resource, &login_tasks);
                     }
+
+                    ui.add_space(10.0);
+                    ui.checkbox(&mut login_resource.remember_me, t!("login-remember-me"));
+                }
+                LoginState::Restoring => {
+                    ui.add(egui::Spinner::new());
+                    ui.label(t!("login-restoring"));
                 }
-                LoginState::WaitingForAuth { .. } => {
-                    ui.label("🔄 Waiting for authentication...");
-                    ui.label("Please complete the authentication in your browser.");
+                LoginState::Starting => {
+                    ui.add(egui::Spinner::new());
+                    ui.label(t!("login-waiting"));
+                }
+                LoginState::WaitingForAuth { auth_url, .. } => {
+                    ui.label(t!("login-waiting"));
+                    ui.label(t!("login-complete-in-browser"));
+                    ui.hyperlink_to(t!("login-open-sign-in-page"), auth_url);
                     
-                    if ui.button("Cancel").clicked() {
+                    if ui.button(t!("common-cancel")).clicked() {
+                        login_tasks.cancel_all();
                         login_resource.state = LoginState::Idle;
+                        login_resource.is_polling = false;
                     }
                 }
                 LoginState::Error(error) => {
-                    ui.colored_label(egui::Color32::RED, format!("❌ Error: {}", error));
+                    ui.colored_label(egui::Color32::RED, t!("login-error", error = error.as_str()));
                     
-                    if ui.button("Try Again").clicked() {
+                    if ui.button(t!("login-try-again")).clicked() {
                         login_resource.state = LoginState::Idle;
                     }
                 }
                 LoginState::Success(_) => {
-                    ui.label("✅ Login successful! Redirecting...");
+                    ui.label(t!("login-success"));
                 }
             }
             
             ui.add_space(20.0);
             
             // Development skip button
-            if ui.button("Skip (Development)").clicked() {
+            if ui.button(t!("login-skip-development")).clicked() {
+                login_resource.apply_server();
                 next_state.set(AppState::Projects);
             }
         });
     });
 }
 
-fn start_oauth_login(provider: &str, login_resource: &mut LoginResource) {
-    let auth_api = AuthApi::new();
-    
-    let rt = tokio::runtime::Runtime::new().unwrap();
-    match rt.block_on(async {
-        auth_api.start_oauth(provider).await
-    }) {
-        Ok(auth_response) => {
-            // Open authentication URL in browser
-            if let Err(e) = open::that(&auth_response.auth_url) {
-                login_resource.state = LoginState::Error(format!("Failed to open browser: {}", e));
-                return;
-            }
-            
-            // Start polling
-            login_resource.state = LoginState::WaitingForAuth {
-                poll_token: auth_response.poll_token,
-                start_time: Instant::now(),
-            };
-            login_resource.last_poll_time = None;
+/// Server URL with the saved profiles and a connection check
+fn render_server_settings(ui: &mut egui::Ui, login_resource: &mut LoginResource, health_tasks: &ApiTasks<HealthCheck>) {
+    let LoginResource { profiles, server_url, profile_name, health, is_checking_health, .. } = login_resource;
+    let mut profiles_changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label(t!("login-server"));
+        let selected_text = profiles.find(server_url.trim()).map_or_else(|| t!("login-custom-server"), |profile| profile.name.clone());
+        egui::ComboBox::from_id_salt("server_profile")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                for profile in &profiles.profiles {
+                    if ui.selectable_label(profile.url == server_url.trim(), &profile.name).clicked() {
+                        *server_url = profile.url.clone();
+                        *profile_name = profile.name.clone();
+                        *health = None;
+                    }
+                }
+            });
+        if ui.add(egui::TextEdit::singleline(server_url).hint_text("https://fast-tag.example.com")).changed() {
+            *health = None;
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(profile_name).hint_text(t!("login-profile-name")).desired_width(140.0));
+        let url = server_url.trim().trim_end_matches('/').to_string();
+        if ui.add_enabled(!profile_name.trim().is_empty() && !url.is_empty(), egui::Button::new(t!("login-save-profile"))).clicked() {
+            profiles.upsert(profile_name.trim(), &url);
+            *server_url = url.clone();
+            profiles_changed = true;
+        }
+        if profiles.find(&url).is_some() && ui.button(t!("login-delete-profile")).clicked() {
+            profiles.remove(&url);
+            profiles_changed = true;
+        }
+        if ui.add_enabled(!*is_checking_health, egui::Button::new(t!("login-check-connection"))).clicked() {
+            *health = None;
+            *is_checking_health = true;
+            health_tasks.spawn(async move {
+                Ok(HealthCheck(match HealthApi::for_server(&url).check().await {
+                    Ok(response) if response.status == "ok" => {
+                        Ok(t!("login-connected", service = response.service.as_str(), database = response.database.as_str()))
+                    }
+                    Ok(response) => Err(t!("login-server-status", status = response.status.as_str(), database = response.database.as_str())),
+                    Err(error) => Err(error.to_string()),
+                }))
+            });
+        }
+        if *is_checking_health {
+            ui.add(egui::Spinner::new());
+        }
+    });
+
+    match health {
+        Some(Ok(message)) => {
+            ui.colored_label(egui::Color32::GREEN, format!("✅ {}", message));
         }
-        Err(e) => {
-            login_resource.state = LoginState::Error(format!("Failed to call auth API: {}", e));
+        Some(Err(error)) => {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
         }
+        None => {}
     }
+
+    if profiles_changed {
+        if let Err(error) = profiles.save() {
+            warn!("Failed to save server profiles: {}", error);
+        }
+    }
+}
+
+/// Trades the refresh token for a new session, and loads the user with it
+async fn refresh_session(refresh_token: &str) -> Result<(StoredSession, crate::api::auth::User), crate::api::ApiError> {
+    let auth_api = AuthApi::new();
+    let refreshed = auth_api.refresh(refresh_token).await?;
+    let user = auth_api.get_user_info(&refreshed.token).await?;
+    Ok((
+        StoredSession { jwt: refreshed.token, refresh_token: Some(refreshed.refresh_token) },
+        user,
+    ))
+}
+
+/// Asks the server for the sign-in page of `provider`, which is opened in the browser and
+/// polled for the session once it is back
+fn start_oauth_login(provider: &str, login_resource: &mut LoginResource, login_tasks: &ApiTasks<LoginStep>) {
+    let provider = provider.to_string();
+    // Shown in the session list of the profile page
+    let device = platform::device_name();
+
+    login_resource.state = LoginState::Starting;
+    login_tasks.spawn(async move {
+        let auth_response = AuthApi::new()
+            .start_oauth(&provider, Some(&device))
+            .await
+            .map_err(|e| t!("login-start-failed", error = e.to_string()))?;
+        Ok(LoginStep::Started { poll_token: auth_response.poll_token, auth_url: auth_response.auth_url })
+    });
 }
 
-async fn poll_for_jwt(poll_token: &str) -> Result<Option<String>, String> {
+async fn poll_for_jwt(poll_token: &str) -> Result<Option<StoredSession>, String> {
     let auth_api = AuthApi::new();
     
     match auth_api.poll_auth(poll_token).await {
         Ok(poll_response) => {
             match poll_response.status.as_str() {
-                "completed" => Ok(poll_response.jwt),
+                "completed" => Ok(poll_response.jwt.map(|jwt| StoredSession {
+                    jwt,
+                    refresh_token: poll_response.refresh_token,
+                })),
                 "pending" => Ok(None),
-                "expired" => Err("Authentication session expired".to_string()),
-                "failed" => Err("Authentication failed".to_string()),
-                _ => Err(format!("Unknown status: {}", poll_response.status)),
+                "expired" => Err(t!("login-expired")),
+                "failed" => Err(t!("login-failed")),
+                _ => Err(t!("login-unknown-status", status = poll_response.status.as_str())),
             }
         }
-        Err(e) => Err(format!("Failed to poll for JWT: {}", e)),
+        Err(e) => Err(t!("login-poll-failed", error = e.to_string())),
     }
 }
 
-pub fn cleanup(_commands: Commands) {
+pub fn cleanup(login_tasks: Res<ApiTasks<LoginStep>>, health_tasks: Res<ApiTasks<HealthCheck>>) {
     println!("login cleanup");
+    login_tasks.cancel_all();
+    health_tasks.cancel_all();
 }
 
 pub struct LoginPlugin;
 
 impl Plugin for LoginPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Login), setup)
-           .add_systems(Update, update.run_if(in_state(AppState::Login)))
+        app.add_plugins((
+               ApiTaskPlugin::<LoginStep>::default(),
+               ApiTaskPlugin::<HealthCheck>::default(),
+           ))
+           .add_systems(OnEnter(AppState::Login), setup)
+           .add_systems(Update, (update, process_login_results).run_if(in_state(AppState::Login)))
            .add_systems(
                EguiContextPass,
                ui_system.run_if(in_state(AppState::Login)),
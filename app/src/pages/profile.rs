@@ -0,0 +1,544 @@
+use crate::ui::components::egui_common;
+use crate::app::state::AppState;
+use crate::auth::{AuthState, User, UserState};
+use crate::api::account::{AccountApi, AccountDeletion, DeleteAccountRequest, LinkedProvidersResponse, OwnershipTransfer, Session, SharedProject};
+use crate::api::auth::AuthApi;
+use crate::api::projects::{ProjectMember, ProjectsApi};
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use crate::io::session_store::{self, StoredSession};
+use crate::notifications::Notify;
+use crate::platform::{self, dialogs};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiContextPass, egui};
+use chrono::Local;
+use bevy::platform::time::Instant;
+use std::time::Duration;
+use uuid::Uuid;
+
+const AVATAR_SIZE: f32 = 96.0;
+/// Same pace and patience as the login page
+const LINK_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const LINK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// What the page shows besides the account itself, loaded when it opens
+pub struct LoadedProfile {
+    providers: LinkedProvidersResponse,
+    sessions: Vec<Session>,
+    avatar: Option<egui::ColorImage>,
+}
+
+/// Outcome of a change made on the page
+pub enum ProfileChange {
+    Renamed { user: User, token: String },
+    /// New avatar, `None` once it was removed
+    AvatarChanged(Option<egui::ColorImage>),
+    /// The file dialog was closed without picking an image
+    AvatarUnchanged,
+    Linked(String),
+    Unlinked(String),
+    SessionRevoked(Uuid),
+    /// Owned projects that need a new owner, with the members who can take them over
+    DeletionBlocked(Vec<(SharedProject, Vec<ProjectMember>)>),
+    Deleted,
+}
+
+struct BlockedProject {
+    project: SharedProject,
+    members: Vec<ProjectMember>,
+    new_owner_id: Option<String>,
+}
+
+#[derive(Resource, Default)]
+pub struct ProfilePageData {
+    name: String,
+    providers: Option<LinkedProvidersResponse>,
+    sessions: Vec<Session>,
+    avatar: Option<egui::TextureHandle>,
+    /// Loaded avatar waiting to become a texture
+    pending_avatar: Option<egui::ColorImage>,
+    error: Option<String>,
+    is_loading: bool,
+    /// A change is on its way to the server
+    is_saving: bool,
+    /// Provider waiting for the sign-in in the browser
+    linking: Option<String>,
+    confirm_email: String,
+    blocked_projects: Vec<BlockedProject>,
+}
+
+fn request_profile(
+    page_data: &mut ProfilePageData,
+    load_tasks: &ApiTasks<LoadedProfile>,
+    auth_state: &AuthState,
+    user_state: &UserState,
+) {
+    let Some(jwt) = auth_state.get_jwt() else {
+        return;
+    };
+
+    page_data.is_loading = true;
+    page_data.error = None;
+    load_tasks.cancel_all();
+    let jwt = jwt.clone();
+    let user_id = user_state.user.as_ref().map(|user| user.id.clone());
+    load_tasks.spawn(async move {
+        let account_api = AccountApi::new();
+        let providers = account_api.list_providers(&jwt).await.map_err(|e| e.to_string())?;
+        let sessions = account_api.list_sessions(&jwt).await.map_err(|e| e.to_string())?;
+        // Accounts without a picture, or with one the provider no longer serves, show none
+        let avatar = match user_id {
+            Some(user_id) => account_api.get_avatar(&jwt, &user_id).await.ok().and_then(|bytes| decode_avatar(&bytes).ok()),
+            None => None,
+        };
+        Ok(LoadedProfile { providers, sessions, avatar })
+    });
+}
+
+fn decode_avatar(bytes: &[u8]) -> Result<egui::ColorImage, String> {
+    let image = image::load_from_memory(bytes).map_err(|e| e.to_string())?;
+    let rgba = image.thumbnail(AVATAR_SIZE as u32 * 2, AVATAR_SIZE as u32 * 2).to_rgba8();
+    Ok(egui::ColorImage::from_rgba_unmultiplied([rgba.width() as usize, rgba.height() as usize], rgba.as_raw()))
+}
+
+pub fn setup(
+    mut commands: Commands,
+    auth_state: Res<AuthState>,
+    user_state: Res<UserState>,
+    load_tasks: Res<ApiTasks<LoadedProfile>>,
+) {
+    println!("profile setup");
+
+    let mut page_data = ProfilePageData {
+        name: user_state.user.as_ref().map(|user| user.name.clone()).unwrap_or_default(),
+        ..default()
+    };
+    request_profile(&mut page_data, &load_tasks, &auth_state, &user_state);
+    commands.insert_resource(page_data);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn process_profile_results(
+    mut loaded: EventReader<ApiTaskSucceeded<LoadedProfile>>,
+    mut load_failed: EventReader<ApiTaskFailed<LoadedProfile>>,
+    mut changed: EventReader<ApiTaskSucceeded<ProfileChange>>,
+    mut change_failed: EventReader<ApiTaskFailed<ProfileChange>>,
+    mut notify: EventWriter<Notify>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut auth_state: ResMut<AuthState>,
+    mut user_state: ResMut<UserState>,
+    load_tasks: Res<ApiTasks<LoadedProfile>>,
+    page_data: Option<ResMut<ProfilePageData>>,
+) {
+    let Some(mut page_data) = page_data else {
+        return;
+    };
+
+    for ApiTaskSucceeded(profile) in loaded.read() {
+        page_data.is_loading = false;
+        page_data.providers = Some(profile.providers.clone());
+        page_data.sessions = profile.sessions.clone();
+        page_data.pending_avatar = profile.avatar.clone();
+    }
+
+    for failure in load_failed.read() {
+        page_data.is_loading = false;
+        page_data.error = Some(failure.error.clone());
+    }
+
+    let mut reload = false;
+    for ApiTaskSucceeded(change) in changed.read() {
+        page_data.is_saving = false;
+        match change {
+            ProfileChange::Renamed { user, token } => {
+                // The old token still carries the previous name
+                if let Some(session) = session_store::load() {
+                    let session = StoredSession { jwt: token.clone(), ..session };
+                    if let Err(error) = session_store::save(&session) {
+                        warn!("Failed to remember the renewed session: {}", error);
+                    }
+                }
+                auth_state.set_jwt(token.clone());
+                user_state.set_user(user.clone());
+                page_data.name = user.name.clone();
+                notify.write(Notify::success(t!("profile-saved")));
+            }
+            ProfileChange::AvatarChanged(avatar) => {
+                page_data.avatar = None;
+                page_data.pending_avatar = avatar.clone();
+                notify.write(Notify::success(t!("profile-avatar-saved")));
+            }
+            ProfileChange::AvatarUnchanged => {}
+            ProfileChange::Linked(provider) => {
+                page_data.linking = None;
+                notify.write(Notify::success(t!("profile-linked", provider = provider_label(provider))));
+                reload = true;
+            }
+            ProfileChange::Unlinked(provider) => {
+                notify.write(Notify::success(t!("profile-unlinked", provider = provider_label(provider))));
+                reload = true;
+            }
+            ProfileChange::SessionRevoked(session_id) => {
+                page_data.sessions.retain(|session| session.id != *session_id);
+                notify.write(Notify::success(t!("profile-session-revoked")));
+            }
+            ProfileChange::DeletionBlocked(projects) => {
+                page_data.blocked_projects = projects
+                    .iter()
+                    .map(|(project, members)| BlockedProject {
+                        project: project.clone(),
+                        members: members.clone(),
+                        new_owner_id: None,
+                    })
+                    .collect();
+            }
+            ProfileChange::Deleted => {
+                notify.write(Notify::success(t!("profile-deleted")));
+                // The login page ends the session and forgets the remembered one
+                next_state.set(AppState::Login);
+            }
+        }
+    }
+
+    for failure in change_failed.read() {
+        page_data.is_saving = false;
+        page_data.linking = None;
+        notify.write(Notify::error(t!("common-error", error = failure.error.as_str())));
+    }
+
+    if reload {
+        request_profile(&mut page_data, &load_tasks, &auth_state, &user_state);
+    }
+}
+
+fn provider_label(provider: &str) -> String {
+    match provider {
+        "google" => "Google".to_string(),
+        "github" => "GitHub".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn rename(change_tasks: &ApiTasks<ProfileChange>, jwt: String, name: String) {
+    change_tasks.spawn(async move {
+        let profile = AccountApi::new().update_profile(&jwt, &name).await.map_err(|e| e.to_string())?;
+        Ok(ProfileChange::Renamed { user: profile.user, token: profile.token })
+    });
+}
+
+fn change_avatar(change_tasks: &ApiTasks<ProfileChange>, jwt: String) {
+    change_tasks.spawn(async move {
+        let file_path = platform::unblock(|| {
+            dialogs::pick_file("Images", &["png", "jpg", "jpeg", "webp", "gif"])
+        }).await?;
+        let Some(file_path) = file_path else {
+            return Ok(ProfileChange::AvatarUnchanged);
+        };
+
+        let data = std::fs::read(&file_path).map_err(|e| t!("profile-read-failed", error = e.to_string()))?;
+        let avatar = decode_avatar(&data)?;
+        AccountApi::new().upload_avatar(&jwt, data).await.map_err(|e| e.to_string())?;
+        Ok(ProfileChange::AvatarChanged(Some(avatar)))
+    });
+}
+
+fn remove_avatar(change_tasks: &ApiTasks<ProfileChange>, jwt: String) {
+    change_tasks.spawn(async move {
+        AccountApi::new().delete_avatar(&jwt).await.map_err(|e| e.to_string())?;
+        Ok(ProfileChange::AvatarChanged(None))
+    });
+}
+
+/// Signs in to `provider` in the browser and waits until the server linked that account
+fn link(change_tasks: &ApiTasks<ProfileChange>, jwt: String, provider: String) {
+    change_tasks.spawn(async move {
+        let auth_response = AccountApi::new().link_provider(&jwt, &provider).await.map_err(|e| e.to_string())?;
+        platform::open_url(&auth_response.auth_url).map_err(|e| t!("login-open-browser-failed", error = e))?;
+
+        let auth_api = AuthApi::new();
+        let start_time = Instant::now();
+        while start_time.elapsed() < LINK_TIMEOUT {
+            platform::sleep(LINK_POLL_INTERVAL).await;
+            let poll_response = auth_api.poll_auth(&auth_response.poll_token).await.map_err(|e| e.to_string())?;
+            if poll_response.status == "completed" {
+                return Ok(ProfileChange::Linked(provider));
+            }
+        }
+        Err(t!("profile-link-timeout"))
+    });
+}
+
+fn unlink(change_tasks: &ApiTasks<ProfileChange>, jwt: String, provider: String) {
+    change_tasks.spawn(async move {
+        AccountApi::new().unlink_provider(&jwt, &provider).await.map_err(|e| e.to_string())?;
+        Ok(ProfileChange::Unlinked(provider))
+    });
+}
+
+fn revoke_session(change_tasks: &ApiTasks<ProfileChange>, jwt: String, session_id: Uuid) {
+    change_tasks.spawn(async move {
+        AccountApi::new().revoke_session(&jwt, session_id).await.map_err(|e| e.to_string())?;
+        Ok(ProfileChange::SessionRevoked(session_id))
+    });
+}
+
+fn delete_account(change_tasks: &ApiTasks<ProfileChange>, jwt: String, user_id: String, request: DeleteAccountRequest) {
+    change_tasks.spawn(async move {
+        match AccountApi::new().delete_account(&jwt, &request).await.map_err(|e| e.to_string())? {
+            AccountDeletion::Deleted => Ok(ProfileChange::Deleted),
+            AccountDeletion::Blocked(projects) => {
+                let projects_api = ProjectsApi::new();
+                let mut blocked = Vec::new();
+                for project in projects {
+                    let members = projects_api
+                        .list_members(&jwt, &project.id.to_string())
+                        .await
+                        .map_err(|e| e.to_string())?
+                        .into_iter()
+                        .filter(|member| member.user_id != user_id)
+                        .collect();
+                    blocked.push((project, members));
+                }
+                Ok(ProfileChange::DeletionBlocked(blocked))
+            }
+        }
+    });
+}
+
+pub fn ui_system(
+    mut contexts: EguiContexts,
+    current_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut page_data: ResMut<ProfilePageData>,
+    auth_state: Res<AuthState>,
+    user_state: Res<UserState>,
+    change_tasks: Res<ApiTasks<ProfileChange>>,
+) {
+    egui_common::ui_top_panel(&mut contexts, current_state, &mut next_state);
+
+    if let Some(avatar) = page_data.pending_avatar.take() {
+        page_data.avatar = Some(contexts.ctx_mut().load_texture("profile_avatar", avatar, egui::TextureOptions::LINEAR));
+    }
+
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        ui.vertical_centered(|ui| {
+            ui.heading(t!("profile-title"));
+            ui.add_space(10.0);
+        });
+
+        let (Some(jwt), Some(user)) = (auth_state.get_jwt().cloned(), user_state.user.clone()) else {
+            ui.vertical_centered(|ui| {
+                ui.add_space(50.0);
+                ui.label(t!("profile-unavailable"));
+            });
+            return;
+        };
+
+        if let Some(error) = &page_data.error {
+            ui.colored_label(egui::Color32::RED, t!("common-error", error = error.as_str()));
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            let busy = page_data.is_saving;
+
+            ui.horizontal(|ui| {
+                let (rect, _) = ui.allocate_exact_size(egui::vec2(AVATAR_SIZE, AVATAR_SIZE), egui::Sense::hover());
+                match &page_data.avatar {
+                    Some(texture) => {
+                        egui::Image::new(texture).corner_radius(AVATAR_SIZE / 2.0).paint_at(ui, rect);
+                    }
+                    None => {
+                        ui.painter().circle_filled(rect.center(), AVATAR_SIZE / 2.0, ui.visuals().faint_bg_color);
+                        let initial = user.name.chars().next().unwrap_or('?').to_uppercase().to_string();
+                        ui.painter().text(rect.center(), egui::Align2::CENTER_CENTER, initial, egui::FontId::proportional(40.0), ui.visuals().text_color());
+                    }
+                }
+
+                ui.vertical(|ui| {
+                    ui.strong(&user.name);
+                    ui.label(&user.email);
+                    ui.horizontal(|ui| {
+                        if dialogs::AVAILABLE && ui.add_enabled(!busy, egui::Button::new(t!("profile-avatar-upload"))).clicked() {
+                            page_data.is_saving = true;
+                            change_avatar(&change_tasks, jwt.clone());
+                        }
+                        if ui.add_enabled(!busy && page_data.avatar.is_some(), egui::Button::new(t!("profile-avatar-remove"))).clicked() {
+                            page_data.is_saving = true;
+                            remove_avatar(&change_tasks, jwt.clone());
+                        }
+                    });
+                    ui.weak(t!("profile-avatar-hint"));
+                });
+            });
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                ui.label(t!("profile-name"));
+                ui.add(egui::TextEdit::singleline(&mut page_data.name).desired_width(250.0));
+                let name = page_data.name.trim().to_string();
+                let can_save = !busy && !name.is_empty() && name != user.name;
+                if ui.add_enabled(can_save, egui::Button::new(t!("common-save"))).clicked() {
+                    page_data.is_saving = true;
+                    rename(&change_tasks, jwt.clone(), name);
+                }
+                if busy {
+                    ui.add(egui::Spinner::new());
+                }
+            });
+            ui.separator();
+
+            ui.strong(t!("profile-providers"));
+            ui.weak(t!("profile-providers-hint"));
+            match page_data.providers.clone() {
+                Some(providers) => {
+                    egui::Grid::new("linked_providers").num_columns(3).spacing([20.0, 6.0]).show(ui, |ui| {
+                        for provider in &providers.available {
+                            ui.label(provider_label(provider));
+                            match providers.providers.iter().find(|linked| &linked.provider == provider) {
+                                Some(linked) => {
+                                    ui.label(t!("profile-linked-as", email = linked.email.as_str()));
+                                    let can_unlink = !busy && providers.providers.len() > 1;
+                                    if ui
+                                        .add_enabled(can_unlink, egui::Button::new(t!("profile-unlink")))
+                                        .on_disabled_hover_text(t!("profile-unlink-last"))
+                                        .clicked()
+                                    {
+                                        page_data.is_saving = true;
+                                        unlink(&change_tasks, jwt.clone(), provider.clone());
+                                    }
+                                }
+                                None if page_data.linking.as_ref() == Some(provider) => {
+                                    ui.label(t!("profile-link-waiting"));
+                                    ui.add(egui::Spinner::new());
+                                }
+                                None => {
+                                    ui.weak(t!("profile-not-linked"));
+                                    if ui.add_enabled(!busy, egui::Button::new(t!("profile-link"))).clicked() {
+                                        page_data.is_saving = true;
+                                        page_data.linking = Some(provider.clone());
+                                        link(&change_tasks, jwt.clone(), provider.clone());
+                                    }
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                }
+                None if page_data.is_loading => {
+                    ui.add(egui::Spinner::new());
+                }
+                None => {}
+            }
+            ui.separator();
+
+            ui.strong(t!("profile-sessions"));
+            ui.weak(t!("profile-sessions-hint"));
+            let sessions = page_data.sessions.clone();
+            egui::Grid::new("sessions").num_columns(4).spacing([20.0, 6.0]).show(ui, |ui| {
+                for session in &sessions {
+                    let device = session.device_name.clone().unwrap_or_else(|| t!("profile-session-unknown-device"));
+                    let label = ui.label(device);
+                    if let Some(user_agent) = &session.user_agent {
+                        label.on_hover_text(user_agent);
+                    }
+                    ui.weak(session.ip_address.clone().unwrap_or_default());
+                    ui.label(t!(
+                        "profile-session-last-used",
+                        time = session.last_used_at.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+                    ));
+                    if session.current {
+                        // Logging out ends this one
+                        ui.weak(t!("profile-session-current"));
+                    } else if ui.add_enabled(!busy, egui::Button::new(t!("profile-session-revoke"))).clicked() {
+                        page_data.is_saving = true;
+                        revoke_session(&change_tasks, jwt.clone(), session.id);
+                    }
+                    ui.end_row();
+                }
+            });
+            ui.separator();
+
+            egui::CollapsingHeader::new(egui::RichText::new(t!("profile-delete-title")).color(egui::Color32::RED))
+                .id_salt("delete_account")
+                .show(ui, |ui| {
+                    ui.label(t!("profile-delete-hint"));
+
+                    if !page_data.blocked_projects.is_empty() {
+                        ui.add_space(5.0);
+                        ui.label(t!("profile-delete-transfer"));
+                        for blocked in &mut page_data.blocked_projects {
+                            ui.horizontal(|ui| {
+                                ui.label(t!("profile-delete-project", name = blocked.project.name.as_str(), members = blocked.project.other_members));
+                                let selected = blocked
+                                    .members
+                                    .iter()
+                                    .find(|member| Some(&member.user_id) == blocked.new_owner_id.as_ref())
+                                    .map(|member| member.name.clone())
+                                    .unwrap_or_else(|| t!("profile-delete-pick-owner"));
+                                egui::ComboBox::from_id_salt(("new_owner", blocked.project.id))
+                                    .selected_text(selected)
+                                    .show_ui(ui, |ui| {
+                                        for member in &blocked.members {
+                                            ui.selectable_value(
+                                                &mut blocked.new_owner_id,
+                                                Some(member.user_id.clone()),
+                                                format!("{} ({})", member.name, member.email),
+                                            );
+                                        }
+                                    });
+                            });
+                        }
+                    }
+
+                    ui.add_space(5.0);
+                    ui.horizontal(|ui| {
+                        ui.label(t!("profile-delete-confirm", email = user.email.as_str()));
+                        ui.add(egui::TextEdit::singleline(&mut page_data.confirm_email).desired_width(250.0));
+                    });
+
+                    let transfers: Option<Vec<OwnershipTransfer>> = page_data
+                        .blocked_projects
+                        .iter()
+                        .map(|blocked| {
+                            let new_owner_id = Uuid::parse_str(blocked.new_owner_id.as_deref()?).ok()?;
+                            Some(OwnershipTransfer { project_id: blocked.project.id, new_owner_id })
+                        })
+                        .collect();
+                    let confirmed = page_data.confirm_email.trim().eq_ignore_ascii_case(&user.email);
+                    let can_delete = !busy && confirmed && transfers.is_some();
+                    if ui
+                        .add_enabled(can_delete, egui::Button::new(egui::RichText::new(t!("profile-delete")).color(egui::Color32::RED)))
+                        .clicked()
+                    {
+                        page_data.is_saving = true;
+                        let request = DeleteAccountRequest {
+                            confirm_email: page_data.confirm_email.trim().to_string(),
+                            transfers: transfers.unwrap_or_default(),
+                        };
+                        delete_account(&change_tasks, jwt.clone(), user.id.clone(), request);
+                    }
+                });
+        });
+    });
+}
+
+pub fn cleanup(mut commands: Commands, load_tasks: Res<ApiTasks<LoadedProfile>>) {
+    println!("profile cleanup");
+    load_tasks.cancel_all();
+    commands.remove_resource::<ProfilePageData>();
+}
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ApiTaskPlugin::<LoadedProfile>::default())
+           .add_plugins(ApiTaskPlugin::<ProfileChange>::default())
+           .add_systems(OnEnter(AppState::Profile), setup)
+           .add_systems(Update, process_profile_results.run_if(in_state(AppState::Profile)))
+           .add_systems(
+               EguiContextPass,
+               ui_system.run_if(in_state(AppState::Profile)),
+           )
+           .add_systems(OnExit(AppState::Profile), cleanup);
+    }
+}
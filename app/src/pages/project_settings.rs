@@ -1,14 +1,29 @@
 use crate::ui::components::egui_common;
 use crate::app::state::AppState;
 use crate::auth::{AuthState, ProjectsState};
-use crate::sync::{SyncState, SyncRequestEvent, SyncRequest, SyncCompletedEvent, SyncErrorEvent};
-use crate::api::categories::{CategoriesApi, AnnotationCategory, CreateCategoryRequest};
+use crate::sync::{SYNC_FILE_EXTENSIONS, SyncState, SyncRequestEvent, SyncRequest, SyncCompletedEvent, SyncErrorEvent};
+use crate::api::categories::{BulkCategoryImportResult, CategoriesApi, AnnotationCategory, CreateCategoryRequest, flatten_category_tree};
+use crate::api::sync::{SyncApi, SyncHistory, SyncRun};
+use crate::api::export::{ExportApi, ExportJob, ExportOptions, ExportPreset, ExportPresetRequest};
+use crate::api::projects::{CloneProjectResponse, Project, ProjectsApi};
+use crate::api::labeling_rules::{LabelingRules, LabelingRulesApi};
+use crate::api::deadlines::{DeadlineSettings, DeadlinesApi};
+use crate::api::tasks::{SPLITS, split_label};
+use crate::api::ApiConfig;
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use crate::exports::{self, ExportJobRequestEvent, ExportJobs};
+use crate::i18n::{self, Language};
+use crate::notifications::Notify;
+use crate::platform::{self, dialogs};
 use bevy::prelude::*;
 use bevy_egui::{EguiContexts, EguiContextPass, egui};
-use rfd::FileDialog;
+use chrono::Local;
 use uuid::Uuid;
-use std::sync::mpsc::{channel, Receiver, Sender};
-use std::sync::Mutex;
+
+use super::tasks;
+
+/// Sync runs per page of the history
+const SYNC_HISTORY_PER_PAGE: i64 = 10;
 
 #[derive(Resource, Default)]
 pub struct Parameters {
@@ -27,6 +42,18 @@ pub struct DeleteProjectTask {
     pub project_id: String,
 }
 
+#[derive(Component)]
+pub struct CloneProjectTask {
+    pub project_id: String,
+    pub request: crate::api::projects::CloneProjectRequest,
+}
+
+#[derive(Component)]
+pub struct ImportCategoriesTask {
+    pub project_id: String,
+    pub on_duplicate: String,
+}
+
 #[derive(Component)]
 pub struct SaveStorageConfigTask {
     pub project_id: String,
@@ -40,11 +67,53 @@ pub struct DownloadCocoExportTask {
     pub file_path: Option<String>,
 }
 
+/// What the export section downloads into a file
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum ExportFormat {
+    Coco,
+    /// Object tracks as MOTChallenge sequences
+    #[default]
+    Mot,
+    /// KITTI label files, tracking ones for videos
+    Kitti,
+}
+
+/// Label file formats offered next to COCO for detection projects
+const LABEL_EXPORT_FORMATS: [ExportFormat; 2] = [ExportFormat::Mot, ExportFormat::Kitti];
+
+impl ExportFormat {
+    fn label(self) -> String {
+        match self {
+            ExportFormat::Coco => "COCO".to_string(),
+            ExportFormat::Mot => t!("settings-export-format-mot"),
+            ExportFormat::Kitti => t!("settings-export-format-kitti"),
+        }
+    }
+
+    fn hint(self) -> String {
+        match self {
+            ExportFormat::Coco => t!("settings-export-coco-hint"),
+            ExportFormat::Mot => t!("settings-export-mot-hint"),
+            ExportFormat::Kitti => t!("settings-export-kitti-hint"),
+        }
+    }
+
+    fn file_prefix(self) -> &'static str {
+        match self {
+            ExportFormat::Coco => "coco_export",
+            ExportFormat::Mot => "mot_export",
+            ExportFormat::Kitti => "kitti_export",
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct SelectFilePathTask {
     pub project_id: String,
     pub token: String,
     pub filename: String,
+    pub options: ExportOptions,
+    pub format: ExportFormat,
 }
 
 #[derive(Component)]
@@ -52,6 +121,7 @@ pub struct ImportCocoTask {
     pub project_id: String,
     pub token: String,
     pub file_path: String,
+    pub fetch_images: bool,
 }
 
 #[derive(Component)]
@@ -66,13 +136,13 @@ pub struct ProjectSettingsPageData {
     pub project_name: String,
     pub project_description: String,
     pub is_editing: bool,
-    pub save_error: Option<String>,
     pub is_saving: bool,
     pub is_deleting: bool,
     pub delete_error: Option<String>,
     pub show_delete_confirmation: bool,
-    pub sync_status_message: Option<String>,
-    pub sync_error_message: Option<String>,
+    pub sync_skip_duplicates: bool,
+    pub sync_history: Option<SyncHistory>,
+    pub is_loading_sync_history: bool,
     // Storage configuration fields
     pub is_editing_storage: bool,
     pub storage_provider: String,
@@ -88,22 +158,48 @@ pub struct ProjectSettingsPageData {
     pub storage_gcs_project_id: String,
     pub storage_gcs_service_account_key: String,
     pub storage_local_base_path: String,
-    pub storage_save_error: Option<String>,
     pub is_saving_storage: bool,
+    pub is_saving_strict_bounds: bool,
+    // Labeling rule fields, empty when the rule is not checked
+    pub rule_min_box_width: String,
+    pub rule_min_box_height: String,
+    pub rule_max_boxes_per_image: String,
+    pub rule_required_category_ids: Vec<Uuid>,
+    pub is_saving_labeling_rules: bool,
+    /// Last day of the labeling campaign, `YYYY-MM-DD`
+    pub deadline_date: String,
+    pub default_due_days: String,
+    pub is_saving_deadlines: bool,
     // Category management fields
     pub new_category_name: String,
     pub new_category_color: [f32; 3],
     pub new_category_description: String,
+    pub new_category_parent_id: Option<Uuid>,
     pub is_creating_category: bool,
     pub category_error: Option<String>,
+    pub category_import_update_existing: bool,
+    pub is_importing_categories: bool,
     // Export fields
     pub is_exporting_coco: bool,
-    pub export_error: Option<String>,
-    pub export_success_message: Option<String>,
+    pub export_options: ExportOptions,
+    /// Format of the label files download, MOT by default
+    pub label_export_format: ExportFormat,
+    pub export_presets: Vec<ExportPreset>,
+    pub selected_export_preset: Option<Uuid>,
+    pub export_preset_name: String,
+    pub export_preset_default: bool,
+    pub is_saving_export_preset: bool,
+    /// Folder of the project's storage that exports to storage are written into
+    pub export_destination: String,
     // Import fields
     pub is_importing_coco: bool,
-    pub import_error: Option<String>,
-    pub import_success_message: Option<String>,
+    pub import_fetch_images: bool,
+    // Duplicate fields
+    pub clone_name: String,
+    pub clone_include_tasks: bool,
+    pub clone_include_annotations: bool,
+    pub clone_include_credentials: bool,
+    pub is_cloning: bool,
 }
 
 // Category management structures
@@ -113,40 +209,29 @@ pub struct CategoryState {
     pub current_project_id: Option<Uuid>,
 }
 
-#[derive(Resource)]
-struct CategoryChannelSender(Mutex<Sender<CategoryResult>>);
-
-#[derive(Resource)]
-struct CategoryChannelReceiver(Mutex<Receiver<CategoryResult>>);
-
-#[derive(Resource)]
-pub struct ImportChannelSender(Mutex<Sender<ImportResult>>);
-
-#[derive(Resource)]
-pub struct ImportChannelReceiver(Mutex<Receiver<ImportResult>>);
-
-#[derive(Resource)]
-pub struct ExportChannelSender(Mutex<Sender<ExportResult>>);
-
-#[derive(Resource)]
-pub struct ExportChannelReceiver(Mutex<Receiver<ExportResult>>);
-
-enum ImportResult {
+pub enum ImportResult {
     FileSelected { project_id: String, token: String, file_path: String },
+    /// The picked file was imported into the project
+    Imported { project_id: Uuid, result: crate::api::import::ImportResult },
     Cancelled,
 }
 
-enum ExportResult {
+pub enum ExportResult {
     Success { file_path: String },
-    Error { error: String },
+    Cancelled,
+}
+
+pub enum ExportPresetResult {
+    Loaded(Vec<ExportPreset>),
+    Saved(ExportPreset),
+    Deleted(Uuid),
 }
 
 // Types are now imported from API modules
 
-enum CategoryResult {
+pub enum CategoryResult {
     CategoriesLoaded { categories: Vec<AnnotationCategory> },
     CategoryCreated { category: AnnotationCategory },
-    Error { error: String },
 }
 
 #[derive(Event)]
@@ -179,11 +264,16 @@ pub fn setup(
     mut category_state: ResMut<CategoryState>,
     mut load_categories_events: EventWriter<LoadCategoriesEvent>,
     auth_state: Res<AuthState>,
+    sync_history_tasks: Res<ApiTasks<SyncHistory>>,
+    export_preset_tasks: Res<ApiTasks<ExportPresetResult>>,
+    box_rules_tasks: Res<ApiTasks<BoxRulesResult>>,
+    mut export_jobs: ExportJobs,
 ) {
     println!("project_settings setup");
     
     let mut page_data = ProjectSettingsPageData {
         new_category_color: [1.0, 0.0, 0.0], // Default to red
+        export_destination: "exports".to_string(),
         ..Default::default()
     };
     
@@ -227,12 +317,508 @@ pub fn setup(
                 project_id: project_uuid,
                 token: token.clone(),
             });
+            export_jobs.state.refresh(project_uuid, token);
         }
     }
-    
+
+    request_sync_history(&mut page_data, &sync_history_tasks, &auth_state, 1);
+    request_export_presets(&page_data, &export_preset_tasks, &auth_state);
+    request_labeling_rules(&page_data, &box_rules_tasks, &auth_state);
+    request_deadlines(&page_data, &box_rules_tasks, &auth_state);
     commands.insert_resource(page_data);
 }
 
+/// Reads the export presets of the selected project
+fn request_export_presets(
+    page_data: &ProjectSettingsPageData,
+    export_preset_tasks: &ApiTasks<ExportPresetResult>,
+    auth_state: &AuthState,
+) {
+    let Some(jwt) = auth_state.get_jwt() else {
+        return;
+    };
+    let Some(project_id) = page_data.selected_project_id.as_deref().and_then(|id| Uuid::parse_str(id).ok()) else {
+        return;
+    };
+
+    let jwt = jwt.clone();
+    export_preset_tasks.spawn(async move {
+        ExportApi::new()
+            .list_export_presets(&jwt, project_id)
+            .await
+            .map(ExportPresetResult::Loaded)
+            .map_err(|e| e.to_string())
+    });
+}
+
+/// Makes the preset the current one and takes over its settings
+fn select_export_preset(page_data: &mut ProjectSettingsPageData, preset: &ExportPreset) {
+    page_data.selected_export_preset = Some(preset.id);
+    page_data.export_options = preset.options.clone();
+    page_data.export_preset_name = preset.name.clone();
+    page_data.export_preset_default = preset.is_default;
+}
+
+pub fn process_export_preset_results(
+    mut succeeded: EventReader<ApiTaskSucceeded<ExportPresetResult>>,
+    mut failed: EventReader<ApiTaskFailed<ExportPresetResult>>,
+    page_data: Option<ResMut<ProjectSettingsPageData>>,
+    mut notify: EventWriter<Notify>,
+) {
+    let Some(mut page_data) = page_data else {
+        return;
+    };
+
+    for ApiTaskSucceeded(result) in succeeded.read() {
+        match result {
+            ExportPresetResult::Loaded(presets) => {
+                // The export section only writes COCO files
+                page_data.export_presets = presets.iter().filter(|preset| preset.format == "coco").cloned().collect();
+                if let Some(preset) = page_data.export_presets.iter().find(|preset| preset.is_default).cloned() {
+                    select_export_preset(&mut page_data, &preset);
+                }
+            }
+            ExportPresetResult::Saved(saved) => {
+                page_data.is_saving_export_preset = false;
+                for preset in page_data.export_presets.iter_mut() {
+                    preset.is_default &= !saved.is_default;
+                }
+                page_data.export_presets.retain(|preset| preset.id != saved.id);
+                page_data.export_presets.push(saved.clone());
+                page_data.export_presets.sort_by(|a, b| a.name.cmp(&b.name));
+                select_export_preset(&mut page_data, saved);
+                notify.write(Notify::success(t!("settings-export-preset-saved", name = saved.name.as_str())));
+            }
+            ExportPresetResult::Deleted(preset_id) => {
+                page_data.export_presets.retain(|preset| preset.id != *preset_id);
+                if page_data.selected_export_preset == Some(*preset_id) {
+                    page_data.selected_export_preset = None;
+                    page_data.export_preset_default = false;
+                }
+            }
+        }
+    }
+    for failure in failed.read() {
+        page_data.is_saving_export_preset = false;
+        notify.write(Notify::error(t!("settings-export-preset-failed", error = failure.error.as_str())));
+    }
+}
+
+/// Reads a page of the sync history of the selected project, from 1
+fn request_sync_history(
+    page_data: &mut ProjectSettingsPageData,
+    sync_history_tasks: &ApiTasks<SyncHistory>,
+    auth_state: &AuthState,
+    page: i64,
+) {
+    let Some(jwt) = auth_state.get_jwt() else {
+        return;
+    };
+    let Some(project_id) = page_data.selected_project_id.as_deref().and_then(|id| Uuid::parse_str(id).ok()) else {
+        return;
+    };
+
+    page_data.is_loading_sync_history = true;
+    // A page still on its way was asked for before
+    sync_history_tasks.cancel_all();
+    let jwt = jwt.clone();
+    sync_history_tasks.spawn(async move {
+        SyncApi::new()
+            .list_sync_history(&jwt, project_id, page, SYNC_HISTORY_PER_PAGE)
+            .await
+            .map_err(|e| e.to_string())
+    });
+}
+
+pub fn process_sync_history_results(
+    mut succeeded: EventReader<ApiTaskSucceeded<SyncHistory>>,
+    mut failed: EventReader<ApiTaskFailed<SyncHistory>>,
+    page_data: Option<ResMut<ProjectSettingsPageData>>,
+    mut sync_state: ResMut<SyncState>,
+) {
+    let Some(mut page_data) = page_data else {
+        return;
+    };
+
+    for ApiTaskSucceeded(history) in succeeded.read() {
+        page_data.is_loading_sync_history = false;
+        page_data.sync_history = Some(history.clone());
+
+        // A run started elsewhere, like from the CLI, shows its progress here too
+        let project_id = page_data.selected_project_id.as_deref().and_then(|id| Uuid::parse_str(id).ok());
+        if let (Some(project_id), Some(newest)) = (project_id, history.syncs.first().filter(|_| history.page == 1)) {
+            sync_state.follow(project_id, newest);
+        }
+    }
+    for failure in failed.read() {
+        page_data.is_loading_sync_history = false;
+        warn!("Failed to fetch the sync history: {}", failure.error);
+    }
+}
+
+/// Checks boxes are held to when they are saved, and when their tasks are due
+pub enum BoxRulesResult {
+    /// Project after its bounds check was switched
+    StrictBoundsSaved(Project),
+    LabelingRulesLoaded(LabelingRules),
+    LabelingRulesSaved(LabelingRules),
+    DeadlinesLoaded(DeadlineSettings),
+    DeadlinesSaved(DeadlineSettings),
+}
+
+fn save_strict_bounds(
+    page_data: &mut ProjectSettingsPageData,
+    box_rules_tasks: &ApiTasks<BoxRulesResult>,
+    auth_state: &AuthState,
+    project_id: String,
+    strict_bounds: bool,
+) {
+    let Some(jwt) = auth_state.get_jwt().cloned() else {
+        return;
+    };
+
+    page_data.is_saving_strict_bounds = true;
+    box_rules_tasks.spawn(async move {
+        ProjectsApi::new()
+            .update_strict_bounds(&jwt, &project_id, strict_bounds)
+            .await
+            .map(BoxRulesResult::StrictBoundsSaved)
+            .map_err(|e| e.to_string())
+    });
+}
+
+/// Reads the labeling rules of the selected project
+fn request_labeling_rules(
+    page_data: &ProjectSettingsPageData,
+    box_rules_tasks: &ApiTasks<BoxRulesResult>,
+    auth_state: &AuthState,
+) {
+    let Some(jwt) = auth_state.get_jwt().cloned() else {
+        return;
+    };
+    let Some(project_id) = page_data.selected_project_id.as_deref().and_then(|id| Uuid::parse_str(id).ok()) else {
+        return;
+    };
+
+    box_rules_tasks.spawn(async move {
+        LabelingRulesApi::new()
+            .get_rules(&jwt, project_id)
+            .await
+            .map(BoxRulesResult::LabelingRulesLoaded)
+            .map_err(|e| e.to_string())
+    });
+}
+
+fn save_labeling_rules(
+    page_data: &mut ProjectSettingsPageData,
+    box_rules_tasks: &ApiTasks<BoxRulesResult>,
+    auth_state: &AuthState,
+    project_id: Uuid,
+    rules: LabelingRules,
+) {
+    let Some(jwt) = auth_state.get_jwt().cloned() else {
+        return;
+    };
+
+    page_data.is_saving_labeling_rules = true;
+    box_rules_tasks.spawn(async move {
+        LabelingRulesApi::new()
+            .update_rules(&jwt, project_id, &rules)
+            .await
+            .map(BoxRulesResult::LabelingRulesSaved)
+            .map_err(|e| e.to_string())
+    });
+}
+
+/// Fills the rule fields from the saved rules
+fn parse_labeling_rules(page_data: &mut ProjectSettingsPageData, rules: &LabelingRules) {
+    page_data.rule_min_box_width = rules.min_box_width.map(|value| value.to_string()).unwrap_or_default();
+    page_data.rule_min_box_height = rules.min_box_height.map(|value| value.to_string()).unwrap_or_default();
+    page_data.rule_max_boxes_per_image = rules.max_boxes_per_image.map(|value| value.to_string()).unwrap_or_default();
+    page_data.rule_required_category_ids = rules.required_category_ids.clone();
+}
+
+/// Reads the rules back from the fields, `None` when a field holds no valid limit
+fn build_labeling_rules(page_data: &ProjectSettingsPageData) -> Option<LabelingRules> {
+    fn limit<T: std::str::FromStr>(field: &str) -> Option<Option<T>> {
+        let field = field.trim();
+        if field.is_empty() {
+            return Some(None);
+        }
+        field.parse().ok().map(Some)
+    }
+
+    let rules = LabelingRules {
+        min_box_width: limit(&page_data.rule_min_box_width)?,
+        min_box_height: limit(&page_data.rule_min_box_height)?,
+        max_boxes_per_image: limit(&page_data.rule_max_boxes_per_image)?,
+        required_category_ids: page_data.rule_required_category_ids.clone(),
+    };
+    let positive = |value: Option<f64>| value.is_none_or(|value| value.is_finite() && value > 0.0);
+    (positive(rules.min_box_width) && positive(rules.min_box_height) && rules.max_boxes_per_image != Some(0))
+        .then_some(rules)
+}
+
+/// Reads the deadline settings of the selected project
+fn request_deadlines(
+    page_data: &ProjectSettingsPageData,
+    box_rules_tasks: &ApiTasks<BoxRulesResult>,
+    auth_state: &AuthState,
+) {
+    let Some(jwt) = auth_state.get_jwt().cloned() else {
+        return;
+    };
+    let Some(project_id) = page_data.selected_project_id.as_deref().and_then(|id| Uuid::parse_str(id).ok()) else {
+        return;
+    };
+
+    box_rules_tasks.spawn(async move {
+        DeadlinesApi::new()
+            .get_settings(&jwt, project_id)
+            .await
+            .map(BoxRulesResult::DeadlinesLoaded)
+            .map_err(|e| e.to_string())
+    });
+}
+
+fn save_deadlines(
+    page_data: &mut ProjectSettingsPageData,
+    box_rules_tasks: &ApiTasks<BoxRulesResult>,
+    auth_state: &AuthState,
+    project_id: Uuid,
+    settings: DeadlineSettings,
+) {
+    let Some(jwt) = auth_state.get_jwt().cloned() else {
+        return;
+    };
+
+    page_data.is_saving_deadlines = true;
+    box_rules_tasks.spawn(async move {
+        DeadlinesApi::new()
+            .update_settings(&jwt, project_id, &settings)
+            .await
+            .map(BoxRulesResult::DeadlinesSaved)
+            .map_err(|e| e.to_string())
+    });
+}
+
+/// Fills the deadline fields from the saved settings
+fn parse_deadlines(page_data: &mut ProjectSettingsPageData, settings: &DeadlineSettings) {
+    page_data.deadline_date = settings.deadline.as_ref().map(tasks::format_due_date).unwrap_or_default();
+    page_data.default_due_days = settings.default_due_days.map(|days| days.to_string()).unwrap_or_default();
+}
+
+/// Reads the settings back from the fields, `None` when a field holds no valid day or number of days
+fn build_deadlines(page_data: &ProjectSettingsPageData) -> Option<DeadlineSettings> {
+    let deadline = match page_data.deadline_date.trim() {
+        "" => None,
+        day => Some(tasks::end_of_day(day.parse().ok()?)?),
+    };
+    let default_due_days = match page_data.default_due_days.trim() {
+        "" => None,
+        days => Some(days.parse::<u32>().ok().filter(|days| *days > 0)?),
+    };
+    Some(DeadlineSettings { deadline, default_due_days })
+}
+
+pub fn process_box_rules_results(
+    mut succeeded: EventReader<ApiTaskSucceeded<BoxRulesResult>>,
+    mut failed: EventReader<ApiTaskFailed<BoxRulesResult>>,
+    mut projects_state: ResMut<ProjectsState>,
+    mut notify: EventWriter<Notify>,
+    page_data: Option<ResMut<ProjectSettingsPageData>>,
+) {
+    let Some(mut page_data) = page_data else {
+        return;
+    };
+
+    for ApiTaskSucceeded(result) in succeeded.read() {
+        match result {
+            BoxRulesResult::StrictBoundsSaved(updated) => {
+                page_data.is_saving_strict_bounds = false;
+                if let Some(project) = projects_state.projects.iter_mut().find(|p| p.id == updated.id) {
+                    *project = updated.clone();
+                }
+                notify.write(Notify::success(t!("settings-strict-bounds-saved")));
+            }
+            BoxRulesResult::LabelingRulesLoaded(rules) => parse_labeling_rules(&mut page_data, rules),
+            BoxRulesResult::LabelingRulesSaved(rules) => {
+                page_data.is_saving_labeling_rules = false;
+                parse_labeling_rules(&mut page_data, rules);
+                notify.write(Notify::success(t!("settings-rules-saved")));
+            }
+            BoxRulesResult::DeadlinesLoaded(settings) => parse_deadlines(&mut page_data, settings),
+            BoxRulesResult::DeadlinesSaved(settings) => {
+                page_data.is_saving_deadlines = false;
+                parse_deadlines(&mut page_data, settings);
+                notify.write(Notify::success(t!("settings-deadlines-saved")));
+            }
+        }
+    }
+    for failure in failed.read() {
+        page_data.is_saving_strict_bounds = false;
+        page_data.is_saving_labeling_rules = false;
+        page_data.is_saving_deadlines = false;
+        notify.write(Notify::error(t!("common-error", error = failure.error.as_str())));
+    }
+}
+
+fn sync_status_label(status: &str) -> (egui::Color32, String) {
+    match status {
+        "running" => (egui::Color32::LIGHT_BLUE, t!("settings-sync-status-running")),
+        "completed" => (egui::Color32::GREEN, t!("settings-sync-status-completed")),
+        "completed_with_errors" => (egui::Color32::YELLOW, t!("settings-sync-status-completed-with-errors")),
+        "failed" => (egui::Color32::RED, t!("settings-sync-status-failed")),
+        other => (egui::Color32::GRAY, other.to_string()),
+    }
+}
+
+/// Preset picker of the export section, and the fields to save the current settings as a preset
+fn render_export_presets(
+    ui: &mut egui::Ui,
+    page_data: &mut ProjectSettingsPageData,
+    auth_state: &AuthState,
+    export_preset_tasks: &ApiTasks<ExportPresetResult>,
+) {
+    let Some(jwt) = auth_state.get_jwt().cloned() else {
+        return;
+    };
+    let Some(project_id) = page_data.selected_project_id.as_deref().and_then(|id| Uuid::parse_str(id).ok()) else {
+        return;
+    };
+
+    ui.horizontal(|ui| {
+        ui.label(t!("settings-export-preset"));
+        let selected_name = page_data
+            .selected_export_preset
+            .and_then(|id| page_data.export_presets.iter().find(|preset| preset.id == id))
+            .map(|preset| preset.name.clone())
+            .unwrap_or_else(|| t!("settings-export-preset-none"));
+
+        let mut chosen = None;
+        egui::ComboBox::from_id_salt("export_preset")
+            .selected_text(selected_name)
+            .show_ui(ui, |ui| {
+                if ui.selectable_label(page_data.selected_export_preset.is_none(), t!("settings-export-preset-none")).clicked() {
+                    chosen = Some(None);
+                }
+                for preset in &page_data.export_presets {
+                    let label = if preset.is_default {
+                        t!("settings-export-preset-default-name", name = preset.name.as_str())
+                    } else {
+                        preset.name.clone()
+                    };
+                    if ui.selectable_label(page_data.selected_export_preset == Some(preset.id), label).clicked() {
+                        chosen = Some(Some(preset.clone()));
+                    }
+                }
+            });
+        match chosen {
+            Some(Some(preset)) => select_export_preset(page_data, &preset),
+            Some(None) => {
+                page_data.selected_export_preset = None;
+                page_data.export_preset_default = false;
+            }
+            None => {}
+        }
+
+        if let Some(preset_id) = page_data.selected_export_preset {
+            if ui.button(t!("settings-export-preset-delete")).clicked() {
+                let jwt = jwt.clone();
+                export_preset_tasks.spawn(async move {
+                    ExportApi::new()
+                        .delete_export_preset(&jwt, project_id, preset_id)
+                        .await
+                        .map(|()| ExportPresetResult::Deleted(preset_id))
+                        .map_err(|e| e.to_string())
+                });
+            }
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut page_data.export_preset_name)
+                .hint_text(t!("settings-export-preset-name"))
+                .desired_width(200.0),
+        );
+        ui.checkbox(&mut page_data.export_preset_default, t!("settings-export-preset-make-default"));
+
+        let name = page_data.export_preset_name.trim().to_string();
+        let can_save = !name.is_empty() && !page_data.is_saving_export_preset;
+        if ui.add_enabled(can_save, egui::Button::new(t!("settings-export-preset-save"))).clicked() {
+            // Saving under the name of a preset overwrites it
+            let existing = page_data.export_presets.iter().find(|preset| preset.name == name).map(|preset| preset.id);
+            let request = ExportPresetRequest {
+                name,
+                format: "coco".to_string(),
+                options: page_data.export_options.clone(),
+                is_default: page_data.export_preset_default,
+            };
+            page_data.is_saving_export_preset = true;
+            export_preset_tasks.spawn(async move {
+                let export_api = ExportApi::new();
+                let saved = match existing {
+                    Some(preset_id) => export_api.update_export_preset(&jwt, project_id, preset_id, &request).await,
+                    None => export_api.create_export_preset(&jwt, project_id, &request).await,
+                };
+                saved.map(ExportPresetResult::Saved).map_err(|e| e.to_string())
+            });
+        }
+        if page_data.is_saving_export_preset {
+            ui.add(egui::Spinner::new());
+        }
+    });
+}
+
+fn render_sync_run(ui: &mut egui::Ui, run: &SyncRun) {
+    ui.horizontal(|ui| {
+        let (color, status) = sync_status_label(&run.status);
+        ui.colored_label(color, status);
+        ui.label(run.started_at.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string());
+        if let Some(completed_at) = run.completed_at {
+            let seconds = (completed_at - run.started_at).num_seconds().max(0);
+            ui.weak(format!("{}:{:02}", seconds / 60, seconds % 60));
+        }
+        ui.label(t!(
+            "settings-sync-run-counts",
+            files = run.total_files,
+            created = run.tasks_created,
+            skipped = run.tasks_skipped,
+        ));
+    });
+    if !run.errors.is_empty() {
+        egui::CollapsingHeader::new(t!("settings-sync-run-errors", count = run.errors.len()))
+            .id_salt(("sync_errors", run.sync_id))
+            .show(ui, |ui| {
+                for error in &run.errors {
+                    ui.colored_label(egui::Color32::LIGHT_RED, error);
+                }
+            });
+    }
+}
+
+fn render_export_job(ui: &mut egui::Ui, job: &ExportJob) {
+    ui.horizontal(|ui| {
+        let (color, status) = sync_status_label(&job.status);
+        ui.colored_label(color, status);
+        ui.label(job.created_at.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string());
+        ui.label(job.format.to_uppercase());
+        if let Some(size_bytes) = job.size_bytes {
+            ui.label(t!("settings-export-size", size = exports::megabytes(size_bytes)));
+        }
+        if let Some(location) = job.location.as_deref().or(job.storage_key.as_deref()) {
+            ui.weak(location);
+        }
+        if let Some(download_url) = &job.download_url {
+            ui.hyperlink_to(t!("settings-export-download"), download_url);
+        }
+    });
+    if let Some(error) = &job.error {
+        ui.colored_label(egui::Color32::LIGHT_RED, error);
+    }
+}
+
 fn build_storage_config(page_data: &ProjectSettingsPageData) -> Option<serde_json::Value> {
     use serde_json::json;
     
@@ -372,6 +958,11 @@ pub fn ui_system(
     category_state: Res<CategoryState>,
     mut sync_request_events: EventWriter<SyncRequestEvent>,
     mut create_category_events: EventWriter<CreateCategoryEvent>,
+    mut notify: EventWriter<Notify>,
+    sync_history_tasks: Res<ApiTasks<SyncHistory>>,
+    export_preset_tasks: Res<ApiTasks<ExportPresetResult>>,
+    box_rules_tasks: Res<ApiTasks<BoxRulesResult>>,
+    mut export_jobs: ExportJobs,
 ) {
     egui_common::ui_top_panel(&mut contexts, current_state, &mut next_state);
 
@@ -379,17 +970,33 @@ pub fn ui_system(
 
     egui::CentralPanel::default().show(ctx, |ui| {
         ui.vertical_centered(|ui| {
-            ui.heading("Project Settings");
+            ui.heading(t!("settings-title"));
             ui.add_space(10.0);
         });
         
         egui::ScrollArea::vertical().show(ui, |ui| {
 
+        // The language is of the app rather than of a project, so it can be picked without one
+        ui.horizontal(|ui| {
+            ui.label(t!("settings-language"));
+            let current = i18n::language();
+            egui::ComboBox::from_id_salt("ui_language")
+                .selected_text(current.native_name())
+                .show_ui(ui, |ui| {
+                    for language in Language::ALL {
+                        if ui.selectable_label(language == current, language.native_name()).clicked() {
+                            i18n::set_language(language);
+                        }
+                    }
+                });
+        });
+        ui.add_space(10.0);
+
         if projects_state.projects.is_empty() {
             ui.vertical_centered(|ui| {
                 ui.add_space(50.0);
-                ui.label("No projects available");
-                ui.label("Go to Projects page to create a project first.");
+                ui.label(t!("settings-no-projects"));
+                ui.label(t!("settings-no-projects-hint"));
             });
             return;
         }
@@ -401,15 +1008,14 @@ pub fn ui_system(
                 ui.group(|ui| {
                     ui.vertical(|ui| {
                         ui.horizontal(|ui| {
-                            ui.strong("Project Details");
+                            ui.strong(t!("settings-project-details"));
                             
                             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                                 if page_data.is_editing {
                                     let can_save = !page_data.project_name.trim().is_empty() && !page_data.is_saving;
                                     
-                                    if ui.add_enabled(can_save, egui::Button::new("💾 Save")).clicked() {
+                                    if ui.add_enabled(can_save, egui::Button::new(t!("common-save"))).clicked() {
                                         page_data.is_saving = true;
-                                        page_data.save_error = None;
                                         
                                         // Spawn task to save project
                                         commands.spawn(SaveProjectTask {
@@ -423,17 +1029,16 @@ pub fn ui_system(
                                         });
                                     }
                                     
-                                    if ui.button("❌ Cancel").clicked() {
+                                    if ui.button(t!("settings-cancel-editing")).clicked() {
                                         page_data.project_name = project.name.clone();
                                         page_data.project_description = project.description.clone().unwrap_or_default();
                                         page_data.is_editing = false;
-                                        page_data.save_error = None;
                                     }
                                     
                                     if page_data.is_saving {
                                         ui.add(egui::Spinner::new());
                                     }
-                                } else if ui.button("Edit").clicked() {
+                                } else if ui.button(t!("common-edit")).clicked() {
                                     page_data.is_editing = true;
                                 }
                             });
@@ -445,7 +1050,7 @@ pub fn ui_system(
                         
                         // Project name
                         ui.horizontal(|ui| {
-                            ui.label("Name:");
+                            ui.label(t!("settings-name"));
                             if page_data.is_editing {
                                 ui.text_edit_singleline(&mut page_data.project_name);
                             } else {
@@ -457,13 +1062,13 @@ pub fn ui_system(
                         
                         // Project description
                         ui.horizontal(|ui| {
-                            ui.label("Description:");
+                            ui.label(t!("settings-description"));
                             if page_data.is_editing {
                                 ui.vertical(|ui| {
                                     ui.text_edit_multiline(&mut page_data.project_description);
                                 });
                             } else {
-                                ui.label(project.description.as_deref().unwrap_or("No description"));
+                                ui.label(project.description.clone().unwrap_or_else(|| t!("projects-no-description")));
                             }
                         });
                         
@@ -471,95 +1076,231 @@ pub fn ui_system(
                         
                         // Project metadata
                         ui.horizontal(|ui| {
-                            ui.label("Created:");
+                            ui.label(t!("settings-created"));
                             ui.label(format_date(&project.created_at));
                         });
-                        
-                        // Show save error
-                        if let Some(error) = &page_data.save_error {
-                            ui.add_space(10.0);
-                            ui.colored_label(egui::Color32::RED, format!("Error: {}", error));
-                        }
+
+                        ui.add_space(5.0);
+
+                        // Boxes drawn past the edge of the image
+                        ui.horizontal(|ui| {
+                            let mut strict_bounds = project.strict_bounds;
+                            let checkbox = egui::Checkbox::new(&mut strict_bounds, t!("settings-strict-bounds"));
+                            if ui
+                                .add_enabled(!page_data.is_saving_strict_bounds, checkbox)
+                                .on_hover_text(t!("settings-strict-bounds-hint"))
+                                .changed()
+                            {
+                                save_strict_bounds(&mut page_data, &box_rules_tasks, &auth_state, project_id.clone(), strict_bounds);
+                            }
+                            if page_data.is_saving_strict_bounds {
+                                ui.add(egui::Spinner::new());
+                            }
+                        });
                     });
                 });
                 
+                ui.add_space(20.0);
+
+                // Labeling rules, checked by the editor and the server whenever boxes are saved
+                if !project.is_classification() {
+                    ui.group(|ui| {
+                        ui.vertical(|ui| {
+                            ui.strong(t!("settings-rules-title"));
+                            ui.separator();
+                            ui.label(t!("settings-rules-description"));
+
+                            egui::Grid::new("labeling_rules").num_columns(2).show(ui, |ui| {
+                                let data = &mut *page_data;
+                                let fields = [
+                                    (t!("settings-rules-min-width"), &mut data.rule_min_box_width),
+                                    (t!("settings-rules-min-height"), &mut data.rule_min_box_height),
+                                    (t!("settings-rules-max-boxes"), &mut data.rule_max_boxes_per_image),
+                                ];
+                                for (label, value) in fields {
+                                    ui.label(label);
+                                    ui.add(egui::TextEdit::singleline(value).hint_text(t!("settings-rules-no-limit")).desired_width(80.0));
+                                    ui.end_row();
+                                }
+                            });
+
+                            ui.label(t!("settings-rules-required-categories"));
+                            for category in &category_state.categories {
+                                let mut required = page_data.rule_required_category_ids.contains(&category.id);
+                                if ui.checkbox(&mut required, &category.name).changed() {
+                                    if required {
+                                        page_data.rule_required_category_ids.push(category.id);
+                                    } else {
+                                        page_data.rule_required_category_ids.retain(|id| *id != category.id);
+                                    }
+                                }
+                            }
+
+                            ui.horizontal(|ui| {
+                                if ui.add_enabled(!page_data.is_saving_labeling_rules, egui::Button::new(t!("common-save"))).clicked() {
+                                    match (build_labeling_rules(&page_data), Uuid::parse_str(&project_id)) {
+                                        (Some(rules), Ok(project_uuid)) => {
+                                            save_labeling_rules(&mut page_data, &box_rules_tasks, &auth_state, project_uuid, rules);
+                                        }
+                                        (None, _) => {
+                                            notify.write(Notify::error(t!("settings-rules-invalid")));
+                                        }
+                                        (_, Err(_)) => {
+                                            notify.write(Notify::error(t!("common-invalid-project-id")));
+                                        }
+                                    }
+                                }
+                                if page_data.is_saving_labeling_rules {
+                                    ui.add(egui::Spinner::new());
+                                }
+                            });
+                        });
+                    });
+
+                    ui.add_space(20.0);
+                }
+
+                // Deadlines of the labeling campaign, tasks with their own due date keep it
+                ui.group(|ui| {
+                    ui.vertical(|ui| {
+                        ui.strong(t!("settings-deadlines-title"));
+                        ui.separator();
+                        ui.label(t!("settings-deadlines-description"));
+
+                        egui::Grid::new("deadlines").num_columns(2).show(ui, |ui| {
+                            ui.label(t!("settings-deadlines-deadline"));
+                            ui.add(egui::TextEdit::singleline(&mut page_data.deadline_date).hint_text("YYYY-MM-DD").desired_width(90.0));
+                            ui.end_row();
+                            ui.label(t!("settings-deadlines-due-days"));
+                            ui.add(egui::TextEdit::singleline(&mut page_data.default_due_days).hint_text(t!("settings-deadlines-none")).desired_width(90.0));
+                            ui.end_row();
+                        });
+
+                        ui.horizontal(|ui| {
+                            if ui.add_enabled(!page_data.is_saving_deadlines, egui::Button::new(t!("common-save"))).clicked() {
+                                match (build_deadlines(&page_data), Uuid::parse_str(&project_id)) {
+                                    (Some(settings), Ok(project_uuid)) => {
+                                        save_deadlines(&mut page_data, &box_rules_tasks, &auth_state, project_uuid, settings);
+                                    }
+                                    (None, _) => {
+                                        notify.write(Notify::error(t!("settings-deadlines-invalid")));
+                                    }
+                                    (_, Err(_)) => {
+                                        notify.write(Notify::error(t!("common-invalid-project-id")));
+                                    }
+                                }
+                            }
+                            if page_data.is_saving_deadlines {
+                                ui.add(egui::Spinner::new());
+                            }
+                        });
+                    });
+                });
+
                 ui.add_space(20.0);
                 
                 // Storage Sync section
                 ui.group(|ui| {
                     ui.vertical(|ui| {
-                        ui.strong("Storage Sync");
+                        ui.strong(t!("settings-sync-title"));
                         ui.separator();
                         
                         ui.horizontal(|ui| {
-                            ui.label("Sync files from storage to create annotation tasks.");
+                            ui.label(t!("settings-sync-description"));
                         });
                         
+                        ui.checkbox(&mut page_data.sync_skip_duplicates, t!("settings-sync-skip-duplicates"));
+
                         ui.add_space(10.0);
                         
                         ui.horizontal(|ui| {
                             let is_syncing = sync_state.is_syncing;
                             
-                            if ui.add_enabled(!is_syncing, egui::Button::new("🔄 Start Sync")).clicked() {
+                            if ui.add_enabled(!is_syncing, egui::Button::new(t!("settings-sync-start"))).clicked() {
                                 if let Ok(project_uuid) = Uuid::parse_str(&project_id) {
                                     if let Some(jwt) = auth_state.get_jwt() {
                                         sync_request_events.write(SyncRequestEvent {
                                             project_id: project_uuid,
                                             request: SyncRequest {
                                                 prefix: None,
-                                                file_extensions: Some(vec!["jpg".to_string(), "jpeg".to_string(), "png".to_string()]),
+                                                file_extensions: Some(SYNC_FILE_EXTENSIONS.iter().map(|ext| ext.to_string()).collect()),
                                                 overwrite_existing: Some(false),
+                                                skip_duplicates: Some(page_data.sync_skip_duplicates),
                                             },
                                             token: jwt.clone(),
                                         });
                                     }
-                                    page_data.sync_status_message = Some("Starting sync...".to_string());
-                                    page_data.sync_error_message = None;
+                                    notify.write(Notify::info(t!("settings-sync-starting")));
                                 }
                             }
                             
                             if is_syncing {
                                 ui.add(egui::Spinner::new());
-                                ui.label("Syncing...");
+                                ui.label(t!("settings-syncing"));
                                 
                                 if let Some(progress) = &sync_state.progress {
-                                    ui.label(format!(
-                                        "Progress: {} / {} files",
-                                        progress.processed_files,
-                                        progress.total_files
+                                    ui.label(t!(
+                                        "settings-sync-progress",
+                                        processed = progress.processed_files,
+                                        total = progress.total_files,
                                     ));
                                 }
                             }
                         });
-                        
-                        if let Some(msg) = &page_data.sync_status_message {
-                            ui.add_space(5.0);
-                            ui.colored_label(egui::Color32::GREEN, msg);
-                        }
-                        
-                        if let Some(err) = &page_data.sync_error_message {
-                            ui.add_space(5.0);
-                            ui.colored_label(egui::Color32::RED, err);
-                        }
-                    });
-                });
-                
-                ui.add_space(20.0);
-                
-                // Storage Configuration section
-                ui.group(|ui| {
+
+                        ui.add_space(10.0);
+                        let mut requested_page = None;
+                        ui.horizontal(|ui| {
+                            ui.strong(t!("settings-sync-history"));
+                            if page_data.is_loading_sync_history {
+                                ui.add(egui::Spinner::new());
+                            } else if ui.small_button(t!("projects-refresh")).clicked() {
+                                requested_page = Some(page_data.sync_history.as_ref().map_or(1, |history| history.page));
+                            }
+                        });
+                        match &page_data.sync_history {
+                            Some(history) if history.syncs.is_empty() => {
+                                ui.weak(t!("settings-sync-history-empty"));
+                            }
+                            Some(history) => {
+                                for run in &history.syncs {
+                                    render_sync_run(ui, run);
+                                }
+                                if history.page_count() > 1 {
+                                    ui.horizontal(|ui| {
+                                        if ui.add_enabled(history.page > 1, egui::Button::new("◀")).clicked() {
+                                            requested_page = Some(history.page - 1);
+                                        }
+                                        ui.label(t!("settings-sync-history-page", page = history.page, pages = history.page_count()));
+                                        if ui.add_enabled(history.page < history.page_count(), egui::Button::new("▶")).clicked() {
+                                            requested_page = Some(history.page + 1);
+                                        }
+                                    });
+                                }
+                            }
+                            None => {}
+                        }
+                        if let Some(page) = requested_page {
+                            request_sync_history(&mut page_data, &sync_history_tasks, &auth_state, page);
+                        }
+                    });
+                });
+                
+                ui.add_space(20.0);
+                
+                // Storage Configuration section
+                ui.group(|ui| {
                     ui.vertical(|ui| {
                         ui.horizontal(|ui| {
-                            ui.strong("Storage Configuration");
+                            ui.strong(t!("settings-storage-title"));
                             
                             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                                 if page_data.is_editing_storage {
                                     let can_save = !page_data.is_saving_storage;
                                     
-                                    if ui.add_enabled(can_save, egui::Button::new("💾 Save")).clicked() {
+                                    if ui.add_enabled(can_save, egui::Button::new(t!("common-save"))).clicked() {
                                         if let Some(storage_config) = build_storage_config(&page_data) {
                                             page_data.is_saving_storage = true;
-                                            page_data.storage_save_error = None;
                                             
                                             // Spawn task to save storage config
                                             commands.spawn(SaveStorageConfigTask {
@@ -567,11 +1308,11 @@ pub fn ui_system(
                                                 storage_config,
                                             });
                                         } else {
-                                            page_data.storage_save_error = Some("Invalid storage configuration".to_string());
+                                            notify.write(Notify::error(t!("settings-storage-invalid")));
                                         }
                                     }
                                     
-                                    if ui.button("❌ Cancel").clicked() {
+                                    if ui.button(t!("settings-cancel-editing")).clicked() {
                                         // Reset fields from project's current storage config
                                         if let Some(storage_config) = &project.storage_config {
                                             parse_storage_config(&mut page_data, storage_config);
@@ -592,13 +1333,12 @@ pub fn ui_system(
                                             page_data.storage_local_base_path = String::new();
                                         }
                                         page_data.is_editing_storage = false;
-                                        page_data.storage_save_error = None;
                                     }
                                     
                                     if page_data.is_saving_storage {
                                         ui.add(egui::Spinner::new());
                                     }
-                                } else if ui.button("Configure").clicked() {
+                                } else if ui.button(t!("settings-storage-configure")).clicked() {
                                     page_data.is_editing_storage = true;
                                 }
                             });
@@ -610,18 +1350,18 @@ pub fn ui_system(
                         if page_data.is_editing_storage {
                             // Provider selection
                             ui.horizontal(|ui| {
-                                ui.label("Provider:");
+                                ui.label(t!("settings-storage-provider"));
                                 egui::ComboBox::from_label("")
                                     .selected_text(if page_data.storage_provider.is_empty() { 
-                                        "Select provider" 
+                                        t!("settings-storage-select-provider-short")
                                     } else { 
-                                        &page_data.storage_provider 
+                                        page_data.storage_provider.clone()
                                     })
                                     .show_ui(ui, |ui| {
                                         ui.selectable_value(&mut page_data.storage_provider, "s3".to_string(), "Amazon S3");
                                         ui.selectable_value(&mut page_data.storage_provider, "azure".to_string(), "Azure Blob Storage");
                                         ui.selectable_value(&mut page_data.storage_provider, "gcs".to_string(), "Google Cloud Storage");
-                                        ui.selectable_value(&mut page_data.storage_provider, "local".to_string(), "Local Storage");
+                                        ui.selectable_value(&mut page_data.storage_provider, "local".to_string(), t!("settings-storage-local"));
                                     });
                             });
                             
@@ -631,62 +1371,62 @@ pub fn ui_system(
                             match page_data.storage_provider.as_str() {
                                 "s3" => {
                                     ui.horizontal(|ui| {
-                                        ui.label("Bucket:");
+                                        ui.label(t!("settings-storage-bucket"));
                                         ui.text_edit_singleline(&mut page_data.storage_s3_bucket);
                                     });
                                     ui.horizontal(|ui| {
-                                        ui.label("Region:");
+                                        ui.label(t!("settings-storage-region"));
                                         ui.text_edit_singleline(&mut page_data.storage_s3_region);
                                     });
                                     ui.horizontal(|ui| {
-                                        ui.label("Access Key:");
+                                        ui.label(t!("settings-storage-access-key"));
                                         ui.text_edit_singleline(&mut page_data.storage_s3_access_key);
                                     });
                                     ui.horizontal(|ui| {
-                                        ui.label("Secret Key:");
+                                        ui.label(t!("settings-storage-secret-key"));
                                         ui.add(egui::TextEdit::singleline(&mut page_data.storage_s3_secret_key).password(true));
                                     });
                                     ui.horizontal(|ui| {
-                                        ui.label("Endpoint (optional):");
+                                        ui.label(t!("settings-storage-endpoint"));
                                         ui.text_edit_singleline(&mut page_data.storage_s3_endpoint);
                                     });
                                 }
                                 "azure" => {
                                     ui.horizontal(|ui| {
-                                        ui.label("Account Name:");
+                                        ui.label(t!("settings-storage-account-name"));
                                         ui.text_edit_singleline(&mut page_data.storage_azure_account_name);
                                     });
                                     ui.horizontal(|ui| {
-                                        ui.label("Account Key:");
+                                        ui.label(t!("settings-storage-account-key"));
                                         ui.add(egui::TextEdit::singleline(&mut page_data.storage_azure_account_key).password(true));
                                     });
                                     ui.horizontal(|ui| {
-                                        ui.label("Container Name:");
+                                        ui.label(t!("settings-storage-container-name"));
                                         ui.text_edit_singleline(&mut page_data.storage_azure_container_name);
                                     });
                                 }
                                 "gcs" => {
                                     ui.horizontal(|ui| {
-                                        ui.label("Bucket:");
+                                        ui.label(t!("settings-storage-bucket"));
                                         ui.text_edit_singleline(&mut page_data.storage_gcs_bucket);
                                     });
                                     ui.horizontal(|ui| {
-                                        ui.label("Project ID:");
+                                        ui.label(t!("settings-storage-project-id"));
                                         ui.text_edit_singleline(&mut page_data.storage_gcs_project_id);
                                     });
                                     ui.vertical(|ui| {
-                                        ui.label("Service Account Key (JSON):");
+                                        ui.label(t!("settings-storage-service-account-key"));
                                         ui.text_edit_multiline(&mut page_data.storage_gcs_service_account_key);
                                     });
                                 }
                                 "local" => {
                                     ui.horizontal(|ui| {
-                                        ui.label("Base Path:");
+                                        ui.label(t!("settings-storage-base-path"));
                                         ui.text_edit_singleline(&mut page_data.storage_local_base_path);
                                     });
                                 }
                                 _ => {
-                                    ui.label("Please select a storage provider.");
+                                    ui.label(t!("settings-storage-select-provider"));
                                 }
                             }
                         } else {
@@ -694,13 +1434,13 @@ pub fn ui_system(
                             if let Some(storage_config) = &project.storage_config {
                                 if let Some(provider_type) = storage_config.get("type").and_then(|v| v.as_str()) {
                                     ui.horizontal(|ui| {
-                                        ui.label("Provider:");
+                                        ui.label(t!("settings-storage-provider"));
                                         ui.label(match provider_type {
-                                            "s3" => "Amazon S3",
-                                            "azure" => "Azure Blob Storage",
-                                            "gcs" => "Google Cloud Storage",
-                                            "local" => "Local Storage",
-                                            _ => provider_type,
+                                            "s3" => "Amazon S3".to_string(),
+                                            "azure" => "Azure Blob Storage".to_string(),
+                                            "gcs" => "Google Cloud Storage".to_string(),
+                                            "local" => t!("settings-storage-local"),
+                                            _ => provider_type.to_string(),
                                         });
                                     });
                                     
@@ -708,13 +1448,13 @@ pub fn ui_system(
                                         "s3" => {
                                             if let Some(bucket) = storage_config.get("bucket").and_then(|v| v.as_str()) {
                                                 ui.horizontal(|ui| {
-                                                    ui.label("Bucket:");
+                                                    ui.label(t!("settings-storage-bucket"));
                                                     ui.label(bucket);
                                                 });
                                             }
                                             if let Some(region) = storage_config.get("region").and_then(|v| v.as_str()) {
                                                 ui.horizontal(|ui| {
-                                                    ui.label("Region:");
+                                                    ui.label(t!("settings-storage-region"));
                                                     ui.label(region);
                                                 });
                                             }
@@ -722,13 +1462,13 @@ pub fn ui_system(
                                         "azure" => {
                                             if let Some(account_name) = storage_config.get("account_name").and_then(|v| v.as_str()) {
                                                 ui.horizontal(|ui| {
-                                                    ui.label("Account Name:");
+                                                    ui.label(t!("settings-storage-account-name"));
                                                     ui.label(account_name);
                                                 });
                                             }
                                             if let Some(container_name) = storage_config.get("container_name").and_then(|v| v.as_str()) {
                                                 ui.horizontal(|ui| {
-                                                    ui.label("Container:");
+                                                    ui.label(t!("settings-storage-container"));
                                                     ui.label(container_name);
                                                 });
                                             }
@@ -736,13 +1476,13 @@ pub fn ui_system(
                                         "gcs" => {
                                             if let Some(bucket) = storage_config.get("bucket").and_then(|v| v.as_str()) {
                                                 ui.horizontal(|ui| {
-                                                    ui.label("Bucket:");
+                                                    ui.label(t!("settings-storage-bucket"));
                                                     ui.label(bucket);
                                                 });
                                             }
                                             if let Some(project_id) = storage_config.get("project_id").and_then(|v| v.as_str()) {
                                                 ui.horizontal(|ui| {
-                                                    ui.label("Project ID:");
+                                                    ui.label(t!("settings-storage-project-id"));
                                                     ui.label(project_id);
                                                 });
                                             }
@@ -750,7 +1490,7 @@ pub fn ui_system(
                                         "local" => {
                                             if let Some(base_path) = storage_config.get("base_path").and_then(|v| v.as_str()) {
                                                 ui.horizontal(|ui| {
-                                                    ui.label("Base Path:");
+                                                    ui.label(t!("settings-storage-base-path"));
                                                     ui.label(base_path);
                                                 });
                                             }
@@ -758,18 +1498,12 @@ pub fn ui_system(
                                         _ => {}
                                     }
                                 } else {
-                                    ui.label("No storage configuration set.");
+                                    ui.label(t!("settings-storage-none"));
                                 }
                             } else {
-                                ui.label("No storage configuration set.");
+                                ui.label(t!("settings-storage-none"));
                             }
                         }
-                        
-                        // Show save error
-                        if let Some(error) = &page_data.storage_save_error {
-                            ui.add_space(10.0);
-                            ui.colored_label(egui::Color32::RED, format!("Error: {}", error));
-                        }
                     });
                 });
                 
@@ -778,19 +1512,21 @@ pub fn ui_system(
                 // Category Management section
                 ui.group(|ui| {
                     ui.vertical(|ui| {
-                        ui.strong("Annotation Categories");
+                        ui.strong(t!("settings-categories-title"));
                         ui.separator();
                         ui.add_space(10.0);
                         
                         // Show existing categories
-                        ui.label("Existing Categories:");
+                        ui.label(t!("settings-categories-existing"));
                         ui.add_space(5.0);
                         
                         if category_state.categories.is_empty() {
-                            ui.colored_label(egui::Color32::GRAY, "No categories created yet.");
+                            ui.colored_label(egui::Color32::GRAY, t!("settings-categories-empty"));
                         } else {
-                            for category in &category_state.categories {
+                            for (depth, category) in flatten_category_tree(&category_state.categories) {
                                 ui.horizontal(|ui| {
+                                    ui.add_space(depth as f32 * 16.0);
+
                                     // Color indicator
                                     if let Some(color) = &category.color {
                                         if let Ok(hex) = u32::from_str_radix(&color[1..], 16) {
@@ -824,23 +1560,41 @@ pub fn ui_system(
                         ui.add_space(10.0);
                         
                         // Create new category form
-                        ui.label("Create New Category:");
+                        ui.label(t!("settings-categories-create-title"));
                         ui.add_space(5.0);
                         
                         ui.horizontal(|ui| {
-                            ui.label("Name:");
+                            ui.label(t!("settings-name"));
                             ui.text_edit_singleline(&mut page_data.new_category_name);
                         });
                         
                         ui.horizontal(|ui| {
-                            ui.label("Color:");
+                            ui.label(t!("settings-categories-color"));
                             egui::color_picker::color_edit_button_rgb(ui, &mut page_data.new_category_color);
                         });
                         
                         ui.horizontal(|ui| {
-                            ui.label("Description:");
+                            ui.label(t!("settings-description"));
                             ui.text_edit_singleline(&mut page_data.new_category_description);
                         });
+
+                        ui.horizontal(|ui| {
+                            ui.label(t!("settings-categories-parent"));
+                            let selected_parent = page_data
+                                .new_category_parent_id
+                                .and_then(|id| category_state.categories.iter().find(|c| c.id == id))
+                                .map(|c| c.name.clone())
+                                .unwrap_or_else(|| t!("settings-categories-top-level"));
+                            egui::ComboBox::from_id_salt("new_category_parent")
+                                .selected_text(selected_parent)
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut page_data.new_category_parent_id, None, t!("settings-categories-top-level"));
+                                    for (depth, category) in flatten_category_tree(&category_state.categories) {
+                                        let label = format!("{}{}", "  ".repeat(depth), category.name);
+                                        ui.selectable_value(&mut page_data.new_category_parent_id, Some(category.id), label);
+                                    }
+                                });
+                        });
                         
                         ui.add_space(10.0);
                         
@@ -848,7 +1602,7 @@ pub fn ui_system(
                             let can_create = !page_data.new_category_name.trim().is_empty() && 
                                            !page_data.is_creating_category;
                             
-                            if ui.add_enabled(can_create, egui::Button::new("➕ Create Category")).clicked() {
+                            if ui.add_enabled(can_create, egui::Button::new(t!("settings-categories-create"))).clicked() {
                                 if let Some(token) = auth_state.get_jwt() {
                                     if let Some(project_id_str) = &page_data.selected_project_id {
                                         if let Ok(project_uuid) = Uuid::parse_str(project_id_str) {
@@ -873,6 +1627,7 @@ pub fn ui_system(
                                                     Some(page_data.new_category_description.clone())
                                                 },
                                                 coco_id: None,
+                                                parent_id: page_data.new_category_parent_id,
                                             };
                                             
                                             create_category_events.write(CreateCategoryEvent {
@@ -887,15 +1642,44 @@ pub fn ui_system(
                             
                             if page_data.is_creating_category {
                                 ui.add(egui::Spinner::new());
-                                ui.label("Creating...");
+                                ui.label(t!("settings-creating"));
                             }
                         });
                         
                         // Show category error
                         if let Some(error) = &page_data.category_error {
                             ui.add_space(10.0);
-                            ui.colored_label(egui::Color32::RED, format!("Error: {}", error));
+                            ui.colored_label(egui::Color32::RED, t!("common-error", error = error.as_str()));
                         }
+
+                        ui.add_space(15.0);
+                        ui.separator();
+                        ui.add_space(10.0);
+
+                        // Bulk import
+                        ui.label(t!("settings-categories-import-title"));
+                        ui.weak(t!("settings-categories-import-formats"));
+                        ui.add_space(5.0);
+
+                        ui.checkbox(&mut page_data.category_import_update_existing, t!("settings-categories-import-update"));
+
+                        ui.horizontal(|ui| {
+                            if ui.add_enabled(dialogs::AVAILABLE && !page_data.is_importing_categories, egui::Button::new(t!("settings-categories-import"))).clicked() {
+                                if let Some(project_id) = page_data.selected_project_id.clone() {
+                                    let on_duplicate = if page_data.category_import_update_existing { "update" } else { "skip" };
+                                    commands.spawn(ImportCategoriesTask {
+                                        project_id,
+                                        on_duplicate: on_duplicate.to_string(),
+                                    });
+                                    page_data.is_importing_categories = true;
+                                }
+                            }
+
+                            if page_data.is_importing_categories {
+                                ui.add(egui::Spinner::new());
+                                ui.label(t!("settings-importing"));
+                            }
+                        });
                     });
                 });
                 
@@ -904,29 +1688,33 @@ pub fn ui_system(
                 // Export section
                 ui.group(|ui| {
                     ui.vertical(|ui| {
-                        ui.strong("Export Data");
+                        ui.strong(t!("settings-export-title"));
                         ui.separator();
                         ui.add_space(10.0);
                         
-                        ui.label("Download annotation data in various formats:");
+                        ui.label(t!("settings-export-description"));
+                        ui.add_space(5.0);
+
+                        render_export_presets(ui, &mut page_data, &auth_state, &export_preset_tasks);
                         ui.add_space(5.0);
                         
                         ui.horizontal(|ui| {
-                            let can_export = !page_data.is_exporting_coco;
-                            if ui.add_enabled(can_export, egui::Button::new("📥 Download COCO Format")).clicked() {
+                            let can_export = dialogs::AVAILABLE && !page_data.is_exporting_coco;
+                            if ui.add_enabled(can_export, egui::Button::new(t!("settings-export-coco"))).clicked() {
                                 // Trigger file dialog for COCO export
                                 if let Some(token) = auth_state.get_jwt() {
                                     if let Some(project_id_str) = page_data.selected_project_id.clone() {
                                         page_data.is_exporting_coco = true;
-                                        page_data.export_error = None;
-                                        page_data.export_success_message = None;
                                         
                                         // Spawn the file dialog task
-                                        let filename = format!("coco_export_{}.json", chrono::Utc::now().format("%Y%m%d_%H%M%S"));
+                                        let extension = if page_data.export_options.include_images { "zip" } else { "json" };
+                                        let filename = format!("{}_{}.{}", ExportFormat::Coco.file_prefix(), chrono::Utc::now().format("%Y%m%d_%H%M%S"), extension);
                                         commands.spawn(SelectFilePathTask {
                                             project_id: project_id_str,
                                             token: token.clone(),
                                             filename,
+                                            options: page_data.export_options.clone(),
+                                            format: ExportFormat::Coco,
                                         });
                                     }
                                 }
@@ -934,22 +1722,110 @@ pub fn ui_system(
                             
                             if page_data.is_exporting_coco {
                                 ui.add(egui::Spinner::new());
-                                ui.label("Downloading...");
+                                ui.label(t!("settings-downloading"));
                             } else {
-                                ui.label("Export annotations in COCO format (JSON)");
+                                ui.label(ExportFormat::Coco.hint());
                             }
                         });
-                        
-                        // Show export success message
-                        if let Some(message) = &page_data.export_success_message {
-                            ui.add_space(5.0);
-                            ui.colored_label(egui::Color32::GREEN, message);
+                        ui.checkbox(&mut page_data.export_options.include_metadata, t!("settings-export-include-metadata"))
+                            .on_hover_text(t!("settings-export-include-metadata-hint"));
+                        ui.checkbox(&mut page_data.export_options.include_images, t!("settings-export-include-images"))
+                            .on_hover_text(t!("settings-export-include-images-hint"));
+                        ui.horizontal(|ui| {
+                            ui.label(t!("settings-export-splits"));
+                            for (code, _) in SPLITS {
+                                let mut included = page_data.export_options.splits.iter().any(|split| split == code);
+                                if ui.checkbox(&mut included, i18n::code_label("split", code, split_label(code)).as_str()).changed() {
+                                    page_data.export_options.splits.retain(|split| split != code);
+                                    if included {
+                                        page_data.export_options.splits.push(code.to_string());
+                                    }
+                                }
+                            }
+                        })
+                        .response
+                        .on_hover_text(t!("settings-export-splits-hint"));
+
+                        // Box label files only fit detection projects
+                        if !project.is_classification() {
+                            ui.horizontal(|ui| {
+                                ui.label(t!("settings-export-labels"));
+                                let selected = page_data.label_export_format;
+                                egui::ComboBox::from_id_salt("label_export_format")
+                                    .selected_text(selected.label())
+                                    .show_ui(ui, |ui| {
+                                        for format in LABEL_EXPORT_FORMATS {
+                                            ui.selectable_value(&mut page_data.label_export_format, format, format.label());
+                                        }
+                                    });
+
+                                let format = page_data.label_export_format;
+                                let can_export = dialogs::AVAILABLE && !page_data.is_exporting_coco;
+                                if ui.add_enabled(can_export, egui::Button::new(t!("settings-export-labels-download"))).clicked() {
+                                    if let (Some(token), Some(project_id)) = (auth_state.get_jwt(), page_data.selected_project_id.clone()) {
+                                        page_data.is_exporting_coco = true;
+                                        commands.spawn(SelectFilePathTask {
+                                            project_id,
+                                            token: token.clone(),
+                                            filename: format!("{}_{}.zip", format.file_prefix(), chrono::Utc::now().format("%Y%m%d_%H%M%S")),
+                                            options: page_data.export_options.clone(),
+                                            format,
+                                        });
+                                    }
+                                }
+                                ui.label(format.hint());
+                            });
                         }
-                        
-                        // Show export error
-                        if let Some(error) = &page_data.export_error {
-                            ui.add_space(5.0);
-                            ui.colored_label(egui::Color32::RED, format!("Error: {}", error));
+
+                        // Exports written to the project's storage by the server, in the background
+                        ui.add_space(10.0);
+                        let project_uuid = page_data.selected_project_id.as_deref().and_then(|id| Uuid::parse_str(id).ok());
+                        let has_storage = projects_state
+                            .projects
+                            .iter()
+                            .find(|project| Some(&project.id) == page_data.selected_project_id.as_ref())
+                            .is_some_and(|project| project.storage_config.is_some());
+                        let is_running = project_uuid.is_some_and(|id| export_jobs.state.is_following(id));
+                        ui.horizontal(|ui| {
+                            ui.label(t!("settings-export-destination"));
+                            ui.text_edit_singleline(&mut page_data.export_destination);
+                            let can_start = has_storage && !is_running && !page_data.export_destination.trim().is_empty();
+                            let button = ui
+                                .add_enabled(can_start, egui::Button::new(t!("settings-export-to-storage")))
+                                .on_disabled_hover_text(t!("settings-export-to-storage-hint"));
+                            if button.clicked() {
+                                if let (Some(project_id), Some(token)) = (project_uuid, auth_state.get_jwt()) {
+                                    export_jobs.requests.write(ExportJobRequestEvent {
+                                        project_id,
+                                        token: token.clone(),
+                                        options: page_data.export_options.clone(),
+                                        destination: page_data.export_destination.trim().to_string(),
+                                    });
+                                }
+                            }
+                            if is_running {
+                                ui.add(egui::Spinner::new());
+                            }
+                        });
+
+                        ui.add_space(5.0);
+                        ui.horizontal(|ui| {
+                            ui.strong(t!("settings-export-recent"));
+                            if let (Some(project_id), Some(token)) = (project_uuid, auth_state.get_jwt()) {
+                                if ui.small_button(t!("projects-refresh")).clicked() {
+                                    export_jobs.state.refresh(project_id, token);
+                                }
+                            }
+                        });
+                        match project_uuid.and_then(|id| export_jobs.state.exports.get(&id)) {
+                            Some(jobs) if !jobs.is_empty() => {
+                                for job in jobs {
+                                    render_export_job(ui, job);
+                                }
+                            }
+                            _ => {
+                                ui.weak(t!("settings-export-recent-empty"));
+                            }
                         }
                     });
                 });
@@ -959,22 +1835,20 @@ pub fn ui_system(
                 // Import section
                 ui.group(|ui| {
                     ui.vertical(|ui| {
-                        ui.strong("Import Data");
+                        ui.strong(t!("settings-import-title"));
                         ui.separator();
                         ui.add_space(10.0);
                         
-                        ui.label("Import annotation data from various formats:");
+                        ui.label(t!("settings-import-description"));
                         ui.add_space(5.0);
                         
                         ui.horizontal(|ui| {
-                            let can_import = !page_data.is_importing_coco;
-                            if ui.add_enabled(can_import, egui::Button::new("📁 Import COCO Format")).clicked() {
+                            let can_import = dialogs::AVAILABLE && !page_data.is_importing_coco;
+                            if ui.add_enabled(can_import, egui::Button::new(t!("settings-import-coco"))).clicked() {
                                 // Trigger file dialog for COCO import
                                 if let Some(token) = auth_state.get_jwt() {
                                     if let Some(project_id_str) = page_data.selected_project_id.clone() {
                                         page_data.is_importing_coco = true;
-                                        page_data.import_error = None;
-                                        page_data.import_success_message = None;
                                         
                                         // Spawn task to open file dialog
                                         commands.spawn(OpenImportDialogTask {
@@ -987,39 +1861,77 @@ pub fn ui_system(
                             
                             if page_data.is_importing_coco {
                                 ui.add(egui::Spinner::new());
-                                ui.label("Importing...");
+                                ui.label(t!("settings-importing"));
                             } else {
-                                ui.label("Import categories, tasks, and annotations from COCO format (JSON)");
+                                ui.label(t!("settings-import-coco-hint"));
                             }
                         });
-                        
-                        // Show import success message
-                        if let Some(message) = &page_data.import_success_message {
-                            ui.add_space(5.0);
-                            ui.colored_label(egui::Color32::GREEN, message);
-                        }
-                        
-                        // Show import error
-                        if let Some(error) = &page_data.import_error {
-                            ui.add_space(5.0);
-                            ui.colored_label(egui::Color32::RED, format!("Error: {}", error));
-                        }
+                        ui.checkbox(&mut page_data.import_fetch_images, t!("settings-import-fetch-images"))
+                            .on_hover_text(t!("settings-import-fetch-images-hint"));
                     });
                 });
                 
+                ui.add_space(20.0);
+
+                // Duplicate project
+                ui.group(|ui| {
+                    ui.vertical(|ui| {
+                        ui.strong(t!("settings-duplicate-title"));
+                        ui.label(t!("settings-duplicate-description"));
+                        ui.separator();
+
+                        ui.horizontal(|ui| {
+                            ui.label(t!("settings-name"));
+                            let hint = t!("settings-duplicate-default-name", name = page_data.project_name.as_str());
+                            ui.add(egui::TextEdit::singleline(&mut page_data.clone_name).hint_text(hint));
+                        });
+                        ui.checkbox(&mut page_data.clone_include_tasks, t!("settings-duplicate-include-tasks"));
+                        ui.add_enabled_ui(page_data.clone_include_tasks, |ui| {
+                            ui.checkbox(&mut page_data.clone_include_annotations, t!("settings-duplicate-include-annotations"));
+                        });
+                        ui.checkbox(&mut page_data.clone_include_credentials, t!("settings-duplicate-include-credentials"))
+                            .on_hover_text(t!("settings-duplicate-include-credentials-hint"));
+
+                        ui.horizontal(|ui| {
+                            if ui.add_enabled(!page_data.is_cloning, egui::Button::new(t!("settings-duplicate"))).clicked() {
+                                if let Some(project_id) = page_data.selected_project_id.clone() {
+                                    let name = page_data.clone_name.trim();
+                                    commands.spawn(CloneProjectTask {
+                                        project_id,
+                                        request: crate::api::projects::CloneProjectRequest {
+                                            name: (!name.is_empty()).then(|| name.to_string()),
+                                            include_tasks: Some(page_data.clone_include_tasks),
+                                            include_annotations: Some(page_data.clone_include_tasks && page_data.clone_include_annotations),
+                                            include_storage_config: Some(true),
+                                            include_credentials: Some(page_data.clone_include_credentials),
+                                            ..Default::default()
+                                        },
+                                    });
+                                    page_data.is_cloning = true;
+                                }
+                            }
+
+                            if page_data.is_cloning {
+                                ui.add(egui::Spinner::new());
+                                ui.label(t!("settings-duplicating"));
+                            }
+                        });
+                    });
+                });
+
                 ui.add_space(20.0);
                 
                 // Danger zone
                 ui.group(|ui| {
                     ui.vertical(|ui| {
-                        ui.colored_label(egui::Color32::RED, "⚡ Danger Zone");
+                        ui.colored_label(egui::Color32::RED, t!("settings-danger-zone"));
                         ui.separator();
                         
                         ui.horizontal(|ui| {
-                            if ui.button("❌ Delete Project").clicked() {
+                            if ui.button(t!("settings-delete-project")).clicked() {
                                 page_data.show_delete_confirmation = true;
                             }
-                            ui.label("This action cannot be undone.");
+                            ui.label(t!("settings-delete-irreversible"));
                         });
                     });
                 });
@@ -1030,34 +1942,34 @@ pub fn ui_system(
     
     // Delete confirmation dialog
     if page_data.show_delete_confirmation {
-        egui::Window::new("⚠️ Confirm Delete")
+        egui::Window::new(t!("settings-delete-confirm-title"))
             .collapsible(false)
             .resizable(false)
             .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
             .show(ctx, |ui| {
                 ui.vertical(|ui| {
-                    ui.label("Are you sure you want to delete this project?");
+                    ui.label(t!("settings-delete-confirm"));
                     ui.add_space(5.0);
-                    ui.colored_label(egui::Color32::RED, "This action cannot be undone!");
+                    ui.colored_label(egui::Color32::RED, t!("settings-delete-irreversible"));
                     
                     if let Some(project_id) = &page_data.selected_project_id {
                         if let Some(project) = projects_state.projects.iter().find(|p| &p.id == project_id) {
                             ui.add_space(10.0);
-                            ui.strong(format!("Project: {}", project.name));
+                            ui.strong(t!("settings-delete-project-name", name = project.name.as_str()));
                         }
                     }
                     
                     ui.add_space(15.0);
                     
                     ui.horizontal(|ui| {
-                        if ui.button("Cancel").clicked() {
+                        if ui.button(t!("common-cancel")).clicked() {
                             page_data.show_delete_confirmation = false;
                         }
                         
                         ui.add_space(10.0);
                         
                         let can_delete = !page_data.is_deleting;
-                        if ui.add_enabled(can_delete, egui::Button::new("❌ Delete").fill(egui::Color32::from_rgb(220, 53, 69))).clicked() {
+                        if ui.add_enabled(can_delete, egui::Button::new(t!("settings-delete")).fill(egui::Color32::from_rgb(220, 53, 69))).clicked() {
                             page_data.is_deleting = true;
                             page_data.delete_error = None;
                             page_data.show_delete_confirmation = false;
@@ -1079,17 +1991,17 @@ pub fn ui_system(
     
     // Show delete error
     if let Some(error) = page_data.delete_error.clone() {
-        egui::Window::new("❌ Delete Error")
+        egui::Window::new(t!("settings-delete-error-title"))
             .collapsible(false)
             .resizable(false)
             .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
             .show(ctx, |ui| {
                 ui.vertical(|ui| {
-                    ui.colored_label(egui::Color32::RED, format!("Failed to delete project: {}", error));
+                    ui.colored_label(egui::Color32::RED, t!("settings-delete-failed", error = error.as_str()));
                     
                     ui.add_space(10.0);
                     
-                    if ui.button("OK").clicked() {
+                    if ui.button(t!("common-ok")).clicked() {
                         page_data.delete_error = None;
                     }
                 });
@@ -1106,50 +2018,56 @@ fn format_date(date_str: &str) -> String {
     }
 }
 
-pub fn cleanup(mut commands: Commands) {
+pub fn cleanup(
+    mut commands: Commands,
+    sync_history_tasks: Res<ApiTasks<SyncHistory>>,
+    export_preset_tasks: Res<ApiTasks<ExportPresetResult>>,
+) {
     println!("project_settings cleanup");
+    sync_history_tasks.cancel_all();
+    export_preset_tasks.cancel_all();
     commands.remove_resource::<ProjectSettingsPageData>();
 }
 
+/// Name and description of a project after they were saved
+pub struct SavedProject(Project);
+
+/// Project after its storage settings were saved
+pub struct SavedStorageConfig(Project);
+
+pub struct DeletedProject {
+    project_id: String,
+}
+
+pub struct ClonedProject(CloneProjectResponse);
+
+pub enum CategoryImportResult {
+    Imported { project_id: Uuid, token: String, result: BulkCategoryImportResult },
+    Cancelled,
+}
+
 pub fn handle_save_project_task(
     mut commands: Commands,
     mut page_data: ResMut<ProjectSettingsPageData>,
-    mut projects_state: ResMut<ProjectsState>,
     auth_state: Res<AuthState>,
-    mut save_tasks: Query<(Entity, &SaveProjectTask)>,
+    save_tasks: Query<(Entity, &SaveProjectTask)>,
+    project_tasks: Res<ApiTasks<SavedProject>>,
+    mut notify: EventWriter<Notify>,
 ) {
-    for (entity, task) in save_tasks.iter_mut() {
+    for (entity, task) in save_tasks.iter() {
         if let Some(jwt) = auth_state.get_jwt() {
             let jwt = jwt.clone();
             let project_id = task.project_id.clone();
             let name = task.name.clone();
             let description = task.description.clone();
             
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            match rt.block_on(crate::auth::update_project(&jwt, &project_id, &name, description.as_deref())) {
-                Ok(updated_project) => {
-                    // Update the project in projects_state
-                    if let Some(project) = projects_state.projects.iter_mut().find(|p| p.id == updated_project.id) {
-                        *project = updated_project.clone();
-                        
-                        // Update page data to reflect the changes
-                        if Some(&project.id) == page_data.selected_project_id.as_ref() {
-                            page_data.project_name = updated_project.name;
-                            page_data.project_description = updated_project.description.unwrap_or_default();
-                        }
-                    }
-                    
-                    page_data.is_saving = false;
-                    page_data.is_editing = false;
-                    page_data.save_error = None;
-                }
-                Err(error) => {
-                    page_data.save_error = Some(error);
-                    page_data.is_saving = false;
-                }
-            }
+            project_tasks.spawn(async move {
+                crate::auth::update_project(&jwt, &project_id, &name, description.as_deref()).await
+                    .map(SavedProject)
+                    .map_err(|error| t!("settings-project-save-failed", error = error))
+            });
         } else {
-            page_data.save_error = Some("Not authenticated".to_string());
+            notify.write(Notify::error(t!("common-not-authenticated")));
             page_data.is_saving = false;
         }
         commands.entity(entity).despawn();
@@ -1169,6 +2087,7 @@ pub fn handle_legacy_category_events(
         page_data.new_category_name.clear();
         page_data.new_category_color = [1.0, 0.0, 0.0];
         page_data.new_category_description.clear();
+        page_data.new_category_parent_id = None;
     }
     
     for event in category_error_events.read() {
@@ -1179,190 +2098,348 @@ pub fn handle_legacy_category_events(
 }
 
 pub fn handle_sync_events(
-    mut page_data: ResMut<ProjectSettingsPageData>,
+    mut notify: EventWriter<Notify>,
     mut sync_completed_events: EventReader<SyncCompletedEvent>,
     mut sync_error_events: EventReader<SyncErrorEvent>,
+    page_data: Option<ResMut<ProjectSettingsPageData>>,
+    auth_state: Res<AuthState>,
+    sync_history_tasks: Res<ApiTasks<SyncHistory>>,
 ) {
+    let mut finished = false;
     for event in sync_completed_events.read() {
-        page_data.sync_status_message = Some(format!(
-            "Sync completed! Created {} tasks, skipped {} tasks.",
-            event.response.tasks_created,
-            event.response.tasks_skipped
-        ));
-        page_data.sync_error_message = None;
+        finished = true;
+        notify.write(Notify::success(t!(
+            "settings-sync-done",
+            created = event.response.tasks_created,
+            skipped = event.response.tasks_skipped,
+        )));
         
         if !event.response.errors.is_empty() {
-            page_data.sync_error_message = Some(format!(
-                "Completed with {} errors: {}",
-                event.response.errors.len(),
-                event.response.errors.join(", ")
-            ));
+            notify.write(Notify::error(t!(
+                "settings-sync-errors",
+                count = event.response.errors.len(),
+                errors = event.response.errors.join(", "),
+            )));
         }
     }
     
     for event in sync_error_events.read() {
-        page_data.sync_error_message = Some(format!("Sync failed: {}", event.error));
-        page_data.sync_status_message = None;
+        finished = true;
+        notify.write(Notify::error(t!("settings-sync-failed", error = event.error.as_str())));
+    }
+
+    // The run just finished goes on top of the history
+    if let (true, Some(mut page_data)) = (finished, page_data) {
+        request_sync_history(&mut page_data, &sync_history_tasks, &auth_state, 1);
     }
 }
 
 pub fn handle_delete_project_task(
     mut commands: Commands,
     mut page_data: ResMut<ProjectSettingsPageData>,
-    mut projects_state: ResMut<ProjectsState>,
-    mut next_state: ResMut<NextState<AppState>>,
     auth_state: Res<AuthState>,
-    mut delete_tasks: Query<(Entity, &DeleteProjectTask)>,
+    delete_tasks: Query<(Entity, &DeleteProjectTask)>,
+    project_tasks: Res<ApiTasks<DeletedProject>>,
 ) {
-    for (entity, task) in delete_tasks.iter_mut() {
+    for (entity, task) in delete_tasks.iter() {
         if let Some(jwt) = auth_state.get_jwt() {
             let jwt = jwt.clone();
             let project_id = task.project_id.clone();
             
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            match rt.block_on(crate::auth::delete_project(&jwt, &project_id)) {
-                Ok(()) => {
-                    // Remove the project from projects_state
-                    if let Some(project_id) = &page_data.selected_project_id {
-                        projects_state.projects.retain(|p| &p.id != project_id);
-                    }
-                    
-                    page_data.is_deleting = false;
-                    page_data.delete_error = None;
-                    
-                    // Navigate back to projects list
-                    next_state.set(AppState::Projects);
-                }
-                Err(error) => {
-                    page_data.delete_error = Some(error);
-                    page_data.is_deleting = false;
-                }
-            }
+            project_tasks.spawn(async move {
+                crate::auth::delete_project(&jwt, &project_id).await?;
+                Ok(DeletedProject { project_id })
+            });
         } else {
-            page_data.delete_error = Some("Not authenticated".to_string());
+            page_data.delete_error = Some(t!("common-not-authenticated"));
             page_data.is_deleting = false;
         }
         commands.entity(entity).despawn();
     }
 }
 
+pub fn handle_clone_project_task(
+    mut commands: Commands,
+    mut page_data: ResMut<ProjectSettingsPageData>,
+    auth_state: Res<AuthState>,
+    clone_tasks: Query<(Entity, &CloneProjectTask)>,
+    project_tasks: Res<ApiTasks<ClonedProject>>,
+    mut notify: EventWriter<Notify>,
+) {
+    for (entity, task) in clone_tasks.iter() {
+        if let Some(jwt) = auth_state.get_jwt() {
+            let (jwt, project_id, request) = (jwt.clone(), task.project_id.clone(), task.request.clone());
+            project_tasks.spawn(async move {
+                crate::auth::clone_project(&jwt, &project_id, &request).await
+                    .map(ClonedProject)
+                    .map_err(|error| t!("settings-duplicate-failed", error = error))
+            });
+        } else {
+            notify.write(Notify::error(t!("common-not-authenticated")));
+            page_data.is_cloning = false;
+        }
+        commands.entity(entity).despawn();
+    }
+}
+
+pub fn handle_import_categories_task(
+    mut commands: Commands,
+    mut page_data: ResMut<ProjectSettingsPageData>,
+    auth_state: Res<AuthState>,
+    import_tasks: Query<(Entity, &ImportCategoriesTask)>,
+    category_import_tasks: Res<ApiTasks<CategoryImportResult>>,
+    mut notify: EventWriter<Notify>,
+) {
+    for (entity, task) in import_tasks.iter() {
+        commands.entity(entity).despawn();
+
+        let Some(jwt) = auth_state.get_jwt() else {
+            page_data.is_importing_categories = false;
+            notify.write(Notify::error(t!("common-not-authenticated")));
+            continue;
+        };
+        let Ok(project_uuid) = Uuid::parse_str(&task.project_id) else {
+            page_data.is_importing_categories = false;
+            notify.write(Notify::error(t!("common-invalid-project-id")));
+            continue;
+        };
+
+        let (token, on_duplicate) = (jwt.clone(), task.on_duplicate.clone());
+        category_import_tasks.spawn(async move {
+            let path = platform::unblock(|| dialogs::pick_file("Categories", &["json", "csv"])).await?;
+            let Some(path) = path else {
+                return Ok(CategoryImportResult::Cancelled);
+            };
+
+            let content = std::fs::read(&path)
+                .map_err(|e| t!("settings-read-file-failed", error = e.to_string()))?;
+            let file_name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("categories")
+                .to_string();
+
+            let result = CategoriesApi::new().import_categories_file(&token, project_uuid, &file_name, content, &on_duplicate).await
+                .map_err(|e| t!("settings-categories-import-failed", error = e.to_string()))?;
+            Ok(CategoryImportResult::Imported { project_id: project_uuid, token, result })
+        });
+    }
+}
+
 pub fn handle_save_storage_config_task(
     mut commands: Commands,
     mut page_data: ResMut<ProjectSettingsPageData>,
-    mut projects_state: ResMut<ProjectsState>,
     auth_state: Res<AuthState>,
-    mut save_tasks: Query<(Entity, &SaveStorageConfigTask)>,
+    save_tasks: Query<(Entity, &SaveStorageConfigTask)>,
+    storage_tasks: Res<ApiTasks<SavedStorageConfig>>,
+    mut notify: EventWriter<Notify>,
 ) {
-    for (entity, task) in save_tasks.iter_mut() {
+    for (entity, task) in save_tasks.iter() {
         if let Some(jwt) = auth_state.get_jwt() {
             let jwt = jwt.clone();
             let project_id = task.project_id.clone();
             let storage_config = task.storage_config.clone();
             
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            match rt.block_on(crate::auth::update_project_storage_config(&jwt, &project_id, storage_config)) {
-                Ok(updated_project) => {
-                    // Update the project in projects_state
-                    if let Some(project) = projects_state.projects.iter_mut().find(|p| p.id == updated_project.id) {
-                        *project = updated_project.clone();
-                        
-                        // Update page data to reflect the changes
-                        if Some(&project.id) == page_data.selected_project_id.as_ref() {
-                            if let Some(storage_config) = &updated_project.storage_config {
-                                parse_storage_config(&mut page_data, storage_config);
-                            }
-                        }
-                    }
-                    
-                    page_data.is_saving_storage = false;
-                    page_data.is_editing_storage = false;
-                    page_data.storage_save_error = None;
-                }
-                Err(error) => {
-                    page_data.storage_save_error = Some(error);
-                    page_data.is_saving_storage = false;
-                }
-            }
+            storage_tasks.spawn(async move {
+                crate::auth::update_project_storage_config(&jwt, &project_id, storage_config).await
+                    .map(SavedStorageConfig)
+                    .map_err(|error| t!("settings-storage-save-failed", error = error))
+            });
         } else {
-            page_data.storage_save_error = Some("Not authenticated".to_string());
+            notify.write(Notify::error(t!("common-not-authenticated")));
             page_data.is_saving_storage = false;
         }
         commands.entity(entity).despawn();
     }
 }
 
+/// Puts up what the project section's saves, deletions and duplications came back with. Runs
+/// outside of the page too, so the project list stays right when it was left meanwhile.
+#[allow(clippy::too_many_arguments)]
+pub fn process_project_results(
+    mut saved: EventReader<ApiTaskSucceeded<SavedProject>>,
+    mut save_failed: EventReader<ApiTaskFailed<SavedProject>>,
+    mut storage_saved: EventReader<ApiTaskSucceeded<SavedStorageConfig>>,
+    mut storage_failed: EventReader<ApiTaskFailed<SavedStorageConfig>>,
+    mut deleted: EventReader<ApiTaskSucceeded<DeletedProject>>,
+    mut delete_failed: EventReader<ApiTaskFailed<DeletedProject>>,
+    mut cloned: EventReader<ApiTaskSucceeded<ClonedProject>>,
+    mut clone_failed: EventReader<ApiTaskFailed<ClonedProject>>,
+    mut page_data: Option<ResMut<ProjectSettingsPageData>>,
+    mut projects_state: ResMut<ProjectsState>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut notify: EventWriter<Notify>,
+) {
+    for ApiTaskSucceeded(SavedProject(updated_project)) in saved.read() {
+        // Update the project in projects_state
+        if let Some(project) = projects_state.projects.iter_mut().find(|p| p.id == updated_project.id) {
+            *project = updated_project.clone();
+        }
+        if let Some(page_data) = &mut page_data {
+            // Update page data to reflect the changes
+            if Some(&updated_project.id) == page_data.selected_project_id.as_ref() {
+                page_data.project_name = updated_project.name.clone();
+                page_data.project_description = updated_project.description.clone().unwrap_or_default();
+            }
+            page_data.is_saving = false;
+            page_data.is_editing = false;
+        }
+        notify.write(Notify::success(t!("settings-project-saved")));
+    }
+    for failure in save_failed.read() {
+        if let Some(page_data) = &mut page_data {
+            page_data.is_saving = false;
+        }
+        notify.write(Notify::error(failure.error.clone()));
+    }
+
+    for ApiTaskSucceeded(SavedStorageConfig(updated_project)) in storage_saved.read() {
+        if let Some(project) = projects_state.projects.iter_mut().find(|p| p.id == updated_project.id) {
+            *project = updated_project.clone();
+        }
+        if let Some(page_data) = &mut page_data {
+            if Some(&updated_project.id) == page_data.selected_project_id.as_ref() {
+                if let Some(storage_config) = &updated_project.storage_config {
+                    parse_storage_config(page_data, storage_config);
+                }
+            }
+            page_data.is_saving_storage = false;
+            page_data.is_editing_storage = false;
+        }
+        notify.write(Notify::success(t!("settings-storage-saved")));
+    }
+    for failure in storage_failed.read() {
+        if let Some(page_data) = &mut page_data {
+            page_data.is_saving_storage = false;
+        }
+        notify.write(Notify::error(failure.error.clone()));
+    }
+
+    for ApiTaskSucceeded(DeletedProject { project_id }) in deleted.read() {
+        projects_state.projects.retain(|p| &p.id != project_id);
+        if let Some(page_data) = &mut page_data {
+            page_data.is_deleting = false;
+            page_data.delete_error = None;
+            // Navigate back to projects list
+            if page_data.selected_project_id.as_ref() == Some(project_id) {
+                next_state.set(AppState::Projects);
+            }
+        }
+    }
+    for failure in delete_failed.read() {
+        match &mut page_data {
+            Some(page_data) => {
+                page_data.delete_error = Some(failure.error.clone());
+                page_data.is_deleting = false;
+            }
+            None => {
+                notify.write(Notify::error(failure.error.clone()));
+            }
+        }
+    }
+
+    for ApiTaskSucceeded(ClonedProject(response)) in cloned.read() {
+        notify.write(Notify::success(t!(
+            "settings-duplicate-done",
+            name = response.project.name.as_str(),
+            categories = response.categories_copied,
+            tasks = response.tasks_copied,
+            annotations = response.annotations_copied,
+        )));
+        if let Some(page_data) = &mut page_data {
+            page_data.clone_name.clear();
+            page_data.is_cloning = false;
+        }
+        projects_state.projects.insert(0, response.project.clone());
+    }
+    for failure in clone_failed.read() {
+        if let Some(page_data) = &mut page_data {
+            page_data.is_cloning = false;
+        }
+        notify.write(Notify::error(failure.error.clone()));
+    }
+}
+
+/// Reports how the categories file went and reads the categories again
+pub fn process_category_import_results(
+    mut succeeded: EventReader<ApiTaskSucceeded<CategoryImportResult>>,
+    mut failed: EventReader<ApiTaskFailed<CategoryImportResult>>,
+    mut page_data: ResMut<ProjectSettingsPageData>,
+    mut load_categories_events: EventWriter<LoadCategoriesEvent>,
+    mut notify: EventWriter<Notify>,
+) {
+    for ApiTaskSucceeded(imported) in succeeded.read() {
+        page_data.is_importing_categories = false;
+        let CategoryImportResult::Imported { project_id, token, result } = imported else {
+            continue;
+        };
+        notify.write(Notify::success(t!(
+            "settings-categories-imported",
+            created = result.created,
+            updated = result.updated,
+            skipped = result.skipped.len(),
+        )));
+        load_categories_events.write(LoadCategoriesEvent {
+            project_id: *project_id,
+            token: token.clone(),
+        });
+    }
+
+    for failure in failed.read() {
+        page_data.is_importing_categories = false;
+        notify.write(Notify::error(failure.error.clone()));
+    }
+}
+
 pub fn handle_download_coco_export_task(
     mut commands: Commands,
     mut page_data: ResMut<ProjectSettingsPageData>,
-    mut download_tasks: Query<(Entity, &DownloadCocoExportTask)>,
+    download_tasks: Query<(Entity, &DownloadCocoExportTask)>,
+    export_tasks: Res<ApiTasks<ExportResult>>,
+    mut notify: EventWriter<Notify>,
 ) {
-    use crate::api::export::ExportApi;
-    
-    for (entity, task) in download_tasks.iter_mut() {
+    for (entity, task) in download_tasks.iter() {
+        commands.entity(entity).despawn();
         info!("Starting COCO export download for project: {}", task.project_id);
-        let project_id = task.project_id.clone();
         let token = task.token.clone();
+        let file_path = task.file_path.clone();
         
         // Parse project ID
-        if let Ok(project_uuid) = Uuid::parse_str(&project_id) {
-            info!("Parsed project UUID: {}", project_uuid);
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            info!("Created Tokio runtime for COCO export download");
-            
-            match rt.block_on(async {
-                let export_api = ExportApi::new();
-                export_api.download_coco_export(&token, project_uuid).await
-            }) {
-                Ok(data) => {
-                    info!("COCO export download completed successfully, data size: {} bytes", data.len());
-                    
-                    // Create a filename
+        let Ok(project_uuid) = Uuid::parse_str(&task.project_id) else {
+            error!("Failed to parse project ID as UUID: {}", task.project_id);
+            page_data.is_exporting_coco = false;
+            notify.write(Notify::error(t!("common-invalid-project-id")));
+            continue;
+        };
+        info!("Parsed project UUID: {}", project_uuid);
+
+        export_tasks.spawn(async move {
+            let data = ExportApi::new().download_coco_export(&token, project_uuid, &ExportOptions::default()).await
+                .map_err(|e| {
+                    error!("Failed to download COCO export for project {}: {}", project_uuid, e);
+                    t!("settings-download-failed", error = e.to_string())
+                })?;
+            info!("COCO export download completed successfully, data size: {} bytes", data.len());
+
+            // Save to the Downloads folder unless a file path was picked
+            let save_path = match file_path {
+                Some(file_path) => std::path::PathBuf::from(file_path),
+                None => {
                     let filename = format!("coco_export_{}.json", chrono::Utc::now().format("%Y%m%d_%H%M%S"));
-                    info!("Generated filename for COCO export: {}", filename);
-                    
-                    // Save to Downloads folder (fallback since native dialog is problematic)
-                    info!("Saving COCO export to Downloads folder");
-                    
-                    let downloads_path = dirs::download_dir()
+                    dirs::download_dir()
                         .unwrap_or_else(|| std::path::PathBuf::from("."))
-                        .join(&filename);
-                    
-                    info!("Saving to path: {:?}", downloads_path);
-                    
-                    // Check if a custom file path was provided
-                    let save_path = if let Some(file_path) = &task.file_path {
-                        std::path::PathBuf::from(file_path)
-                    } else {
-                        downloads_path
-                    };
-                    
-                    match std::fs::write(&save_path, &data) {
-                        Ok(_) => {
-                            info!("COCO export saved successfully to: {:?}", save_path);
-                            page_data.is_exporting_coco = false;
-                            page_data.export_error = None;
-                            page_data.export_success_message = Some(format!("Export completed! File saved to: {}", save_path.display()));
-                        }
-                        Err(e) => {
-                            error!("Failed to save COCO export file to {:?}: {}", save_path, e);
-                            page_data.is_exporting_coco = false;
-                            page_data.export_error = Some(format!("Failed to save file: {}", e));
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!("Failed to download COCO export for project {}: {}", project_uuid, e);
-                    page_data.is_exporting_coco = false;
-                    page_data.export_error = Some(format!("Failed to download: {}", e));
+                        .join(filename)
                 }
-            }
-        } else {
-            error!("Failed to parse project ID as UUID: {}", project_id);
-            page_data.is_exporting_coco = false;
-            page_data.export_error = Some("Invalid project ID".to_string());
-        }
-        
-        commands.entity(entity).despawn();
+            };
+            info!("Saving to path: {:?}", save_path);
+
+            std::fs::write(&save_path, &data).map_err(|e| {
+                error!("Failed to save COCO export file to {:?}: {}", save_path, e);
+                t!("settings-save-file-failed", error = e.to_string())
+            })?;
+            info!("COCO export saved successfully to: {:?}", save_path);
+            Ok(ExportResult::Success { file_path: save_path.display().to_string() })
+        });
     }
 }
 
@@ -1395,240 +2472,153 @@ mod category_client {
 fn handle_category_requests(
     mut load_categories_events: EventReader<LoadCategoriesEvent>,
     mut create_category_events: EventReader<CreateCategoryEvent>,
-    sender: Res<CategoryChannelSender>,
+    category_tasks: Res<ApiTasks<CategoryResult>>,
 ) {
     for load_categories_event in load_categories_events.read() {
         let project_id = load_categories_event.project_id;
         let token = load_categories_event.token.clone();
-        let api_base_url = std::env::var("API_BASE_URL")
-            .unwrap_or_else(|_| "http://localhost:8080".to_string());
+        let api_base_url = ApiConfig::default().base_url;
         
-        if let Ok(tx) = sender.0.lock() {
-            let tx = tx.clone();
-            
-            std::thread::spawn(move || {
-                let runtime = tokio::runtime::Runtime::new().unwrap();
-                runtime.block_on(async {
-                    let result = category_client::load_categories(project_id, token, api_base_url).await;
-                    match result {
-                        Ok(categories) => {
-                            let _ = tx.send(CategoryResult::CategoriesLoaded { categories });
-                        }
-                        Err(error) => {
-                            let _ = tx.send(CategoryResult::Error { error });
-                        }
-                    }
-                });
-            });
-        }
+        category_tasks.spawn(async move {
+            let categories = category_client::load_categories(project_id, token, api_base_url).await?;
+            Ok(CategoryResult::CategoriesLoaded { categories })
+        });
     }
 
     for create_event in create_category_events.read() {
         let project_id = create_event.project_id;
         let request = create_event.request.clone();
         let token = create_event.token.clone();
-        let api_base_url = std::env::var("API_BASE_URL")
-            .unwrap_or_else(|_| "http://localhost:8080".to_string());
+        let api_base_url = ApiConfig::default().base_url;
         
-        if let Ok(tx) = sender.0.lock() {
-            let tx = tx.clone();
-            
-            std::thread::spawn(move || {
-                let runtime = tokio::runtime::Runtime::new().unwrap();
-                runtime.block_on(async {
-                    let result = category_client::create_category(project_id, request, token, api_base_url).await;
-                    match result {
-                        Ok(category) => {
-                            let _ = tx.send(CategoryResult::CategoryCreated { category });
-                        }
-                        Err(error) => {
-                            let _ = tx.send(CategoryResult::Error { error });
-                        }
-                    }
-                });
-            });
-        }
+        category_tasks.spawn(async move {
+            let category = category_client::create_category(project_id, request, token, api_base_url).await?;
+            Ok(CategoryResult::CategoryCreated { category })
+        });
     }
 }
 
 fn process_category_results(
-    receiver: Res<CategoryChannelReceiver>,
+    mut succeeded: EventReader<ApiTaskSucceeded<CategoryResult>>,
+    mut failed: EventReader<ApiTaskFailed<CategoryResult>>,
     mut category_state: ResMut<CategoryState>,
     mut category_created_events: EventWriter<CategoryCreatedEvent>,
     mut error_events: EventWriter<CategoryErrorEvent>,
     mut page_data: ResMut<ProjectSettingsPageData>,
 ) {
-    if let Ok(rx) = receiver.0.lock() {
-        while let Ok(result) = rx.try_recv() {
-            match result {
-                CategoryResult::CategoriesLoaded { categories } => {
-                    category_state.categories = categories;
-                }
-                CategoryResult::CategoryCreated { category } => {
-                    category_state.categories.push(category.clone());
-                    category_created_events.write(CategoryCreatedEvent { category });
-                    // Reset the form
-                    page_data.new_category_name.clear();
-                    page_data.new_category_description.clear();
-                    page_data.new_category_color = [1.0, 0.0, 0.0];
-                    page_data.is_creating_category = false;
-                    page_data.category_error = None;
-                }
-                CategoryResult::Error { error } => {
-                    error_events.write(CategoryErrorEvent { error: error.clone() });
-                    page_data.category_error = Some(error);
-                    page_data.is_creating_category = false;
-                }
+    for ApiTaskSucceeded(result) in succeeded.read() {
+        match result {
+            CategoryResult::CategoriesLoaded { categories } => {
+                category_state.categories = categories.clone();
+            }
+            CategoryResult::CategoryCreated { category } => {
+                category_state.categories.push(category.clone());
+                category_created_events.write(CategoryCreatedEvent { category: category.clone() });
+                // Reset the form
+                page_data.new_category_name.clear();
+                page_data.new_category_description.clear();
+                page_data.new_category_color = [1.0, 0.0, 0.0];
+                page_data.new_category_parent_id = None;
+                page_data.is_creating_category = false;
+                page_data.category_error = None;
             }
         }
     }
+
+    for failure in failed.read() {
+        error_events.write(CategoryErrorEvent { error: failure.error.clone() });
+        page_data.category_error = Some(failure.error.clone());
+        page_data.is_creating_category = false;
+    }
 }
 
 
 pub fn handle_import_coco_task(
     mut commands: Commands,
     mut page_data: ResMut<ProjectSettingsPageData>,
-    mut import_tasks: Query<(Entity, &ImportCocoTask)>,
-    mut load_categories_events: EventWriter<LoadCategoriesEvent>,
-    auth_state: Res<AuthState>,
+    import_tasks: Query<(Entity, &ImportCocoTask)>,
+    import_results: Res<ApiTasks<ImportResult>>,
+    mut notify: EventWriter<Notify>,
 ) {
     use crate::api::import::ImportApi;
     
-    for (entity, task) in import_tasks.iter_mut() {
+    for (entity, task) in import_tasks.iter() {
+        commands.entity(entity).despawn();
         info!("Starting COCO import for project: {}", task.project_id);
-        let project_id = task.project_id.clone();
         let token = task.token.clone();
         let file_path = task.file_path.clone();
+        let fetch_images = task.fetch_images;
         
         // Parse project ID
-        if let Ok(project_uuid) = Uuid::parse_str(&project_id) {
-            info!("Parsed project UUID: {}", project_uuid);
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            info!("Created Tokio runtime for COCO import");
-            
-            match rt.block_on(async {
-                let import_api = ImportApi::new();
-                import_api.import_coco_file(&token, project_uuid, &file_path).await
-            }) {
-                Ok(result) => {
-                    info!("COCO import completed successfully: {}", result.message);
-                    page_data.is_importing_coco = false;
-                    page_data.import_error = None;
-                    
-                    let stats_msg = format!(
-                        "Import completed! Created {} categories, {} tasks, {} annotations. Updated {} categories.", 
-                        result.stats.categories_created,
-                        result.stats.tasks_created,
-                        result.stats.annotations_created,
-                        result.stats.categories_updated
-                    );
-                    
-                    if !result.stats.errors.is_empty() {
-                        page_data.import_success_message = Some(format!(
-                            "{} Note: {} errors occurred during import.",
-                            stats_msg,
-                            result.stats.errors.len()
-                        ));
-                    } else {
-                        page_data.import_success_message = Some(stats_msg);
-                    }
-                    
-                    // Reload categories if any were created or updated
-                    if result.stats.categories_created > 0 || result.stats.categories_updated > 0 {
-                        if let Some(token) = auth_state.get_jwt() {
-                            load_categories_events.write(LoadCategoriesEvent {
-                                project_id: project_uuid,
-                                token: token.clone(),
-                            });
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!("Failed to import COCO file for project {}: {}", project_uuid, e);
-                    page_data.is_importing_coco = false;
-                    page_data.import_error = Some(format!("Failed to import: {}", e));
-                    page_data.import_success_message = None;
-                }
-            }
-        } else {
-            error!("Failed to parse project ID as UUID: {}", project_id);
+        let Ok(project_uuid) = Uuid::parse_str(&task.project_id) else {
+            error!("Failed to parse project ID as UUID: {}", task.project_id);
             page_data.is_importing_coco = false;
-            page_data.import_error = Some("Invalid project ID".to_string());
-            page_data.import_success_message = None;
-        }
-        
-        commands.entity(entity).despawn();
+            notify.write(Notify::error(t!("common-invalid-project-id")));
+            continue;
+        };
+        info!("Parsed project UUID: {}", project_uuid);
+
+        import_results.spawn(async move {
+            let result = ImportApi::new().import_coco_file(&token, project_uuid, &file_path, fetch_images).await
+                .map_err(|e| {
+                    error!("Failed to import COCO file for project {}: {}", project_uuid, e);
+                    t!("settings-import-failed", error = e.to_string())
+                })?;
+            Ok(ImportResult::Imported { project_id: project_uuid, result })
+        });
     }
 }
 
 pub fn handle_select_file_path_task(
     mut commands: Commands,
     mut select_tasks: Query<(Entity, &SelectFilePathTask)>,
-    sender: Res<ExportChannelSender>,
+    export_tasks: Res<ApiTasks<ExportResult>>,
 ) {
     for (entity, task) in select_tasks.iter_mut() {
         // Open the file save dialog
         let project_id = task.project_id.clone();
         let token = task.token.clone();
         let filename = task.filename.clone();
+        let options = task.options.clone();
+        let format = task.format;
         
-        if let Ok(tx) = sender.0.lock() {
-            let tx = tx.clone();
-            
-            // Use a non-blocking approach by spawning a thread
-            std::thread::spawn(move || {
-                let file_path = FileDialog::new()
-                    .set_file_name(&filename)
-                    .add_filter("JSON", &["json"])
-                    .save_file();
-                
-                if let Some(path) = file_path {
-                    let path_str = path.to_str().unwrap_or("").to_string();
-                    info!("User selected file path: {}", path_str);
-                    
-                    // We need to communicate back to the main thread
-                    let rt = tokio::runtime::Runtime::new().unwrap();
-                    rt.block_on(async {
-                        use crate::api::export::ExportApi;
-                        
-                        if let Ok(project_uuid) = Uuid::parse_str(&project_id) {
-                            let export_api = ExportApi::new();
-                            match export_api.download_coco_export(&token, project_uuid).await {
-                                Ok(data) => {
-                                    match std::fs::write(&path, &data) {
-                                        Ok(_) => {
-                                            info!("COCO export saved successfully to: {:?}", path);
-                                            let _ = tx.send(ExportResult::Success { 
-                                                file_path: path_str.clone() 
-                                            });
-                                        }
-                                        Err(e) => {
-                                            error!("Failed to save COCO export file: {}", e);
-                                            let _ = tx.send(ExportResult::Error { 
-                                                error: format!("Failed to save file: {}", e) 
-                                            });
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    error!("Failed to download COCO export: {}", e);
-                                    let _ = tx.send(ExportResult::Error { 
-                                        error: format!("Failed to download: {}", e) 
-                                    });
-                                }
-                            }
-                        } else {
-                            let _ = tx.send(ExportResult::Error { 
-                                error: "Invalid project ID".to_string() 
-                            });
-                        }
-                    });
-                } else {
-                    info!("File save dialog canceled");
-                    // Don't send any result for cancellation, just let the UI reset
-                }
-            });
-        }
+        export_tasks.spawn(async move {
+            let (filter_name, extension) = if options.include_images || format != ExportFormat::Coco {
+                ("ZIP", "zip")
+            } else {
+                ("JSON", "json")
+            };
+            let file_path = platform::unblock(move || {
+                dialogs::save_file(&filename, filter_name, &[extension])
+            }).await?;
+
+            let Some(path) = file_path else {
+                info!("File save dialog canceled");
+                return Ok(ExportResult::Cancelled);
+            };
+            let path_str = path.to_str().unwrap_or("").to_string();
+            info!("User selected file path: {}", path_str);
+
+            let project_uuid = Uuid::parse_str(&project_id)
+                .map_err(|_| t!("common-invalid-project-id"))?;
+            let export_api = ExportApi::new();
+            let data = match format {
+                ExportFormat::Coco => export_api.download_coco_export(&token, project_uuid, &options).await,
+                ExportFormat::Mot => export_api.download_mot_export(&token, project_uuid).await,
+                ExportFormat::Kitti => export_api.download_kitti_export(&token, project_uuid).await,
+            }
+            .map_err(|e| {
+                error!("Failed to download export: {}", e);
+                t!("settings-download-failed", error = e.to_string())
+            })?;
+            std::fs::write(&path, &data).map_err(|e| {
+                error!("Failed to save export file: {}", e);
+                t!("settings-save-file-failed", error = e.to_string())
+            })?;
+
+            info!("Export saved successfully to: {:?}", path);
+            Ok(ExportResult::Success { file_path: path_str })
+        });
         
         commands.entity(entity).despawn();
     }
@@ -1637,38 +2627,32 @@ pub fn handle_select_file_path_task(
 pub fn handle_open_import_dialog_task(
     mut commands: Commands,
     mut open_dialog_tasks: Query<(Entity, &OpenImportDialogTask)>,
-    sender: Res<ImportChannelSender>,
+    import_tasks: Res<ApiTasks<ImportResult>>,
 ) {
     for (entity, task) in open_dialog_tasks.iter_mut() {
         let project_id = task.project_id.clone();
         let token = task.token.clone();
         
-        if let Ok(tx) = sender.0.lock() {
-            let tx = tx.clone();
-            
-            // Open file dialog in a separate thread
-            std::thread::spawn(move || {
-                if let Some(file_path) = FileDialog::new()
-                    .add_filter("JSON", &["json"])
-                    .pick_file() 
-                {
-                    if let Some(path_str) = file_path.to_str() {
-                        info!("User selected file for COCO import: {}", path_str);
-                        let _ = tx.send(ImportResult::FileSelected {
-                            project_id,
-                            token,
-                            file_path: path_str.to_string(),
-                        });
-                    } else {
-                        warn!("File path could not be converted to string");
-                        let _ = tx.send(ImportResult::Cancelled);
-                    }
-                } else {
-                    info!("File dialog cancelled by user");
-                    let _ = tx.send(ImportResult::Cancelled);
-                }
-            });
-        }
+        // Open file dialog off the main thread
+        import_tasks.spawn(async move {
+            let file_path = platform::unblock(|| dialogs::pick_file("JSON", &["json"])).await?;
+
+            let Some(file_path) = file_path else {
+                info!("File dialog cancelled by user");
+                return Ok(ImportResult::Cancelled);
+            };
+            let Some(path_str) = file_path.to_str() else {
+                warn!("File path could not be converted to string");
+                return Ok(ImportResult::Cancelled);
+            };
+
+            info!("User selected file for COCO import: {}", path_str);
+            Ok(ImportResult::FileSelected {
+                project_id,
+                token,
+                file_path: path_str.to_string(),
+            })
+        });
         
         commands.entity(entity).despawn();
     }
@@ -1676,66 +2660,109 @@ pub fn handle_open_import_dialog_task(
 
 fn process_import_results(
     mut commands: Commands,
-    receiver: Res<ImportChannelReceiver>,
+    mut succeeded: EventReader<ApiTaskSucceeded<ImportResult>>,
+    mut failed: EventReader<ApiTaskFailed<ImportResult>>,
     mut page_data: ResMut<ProjectSettingsPageData>,
+    mut load_categories_events: EventWriter<LoadCategoriesEvent>,
+    auth_state: Res<AuthState>,
+    mut notify: EventWriter<Notify>,
 ) {
-    if let Ok(rx) = receiver.0.lock() {
-        while let Ok(result) = rx.try_recv() {
-            match result {
-                ImportResult::FileSelected { project_id, token, file_path } => {
-                    // Spawn the import task
-                    commands.spawn(ImportCocoTask {
-                        project_id,
-                        token,
-                        file_path,
-                    });
+    for ApiTaskSucceeded(result) in succeeded.read() {
+        match result {
+            ImportResult::FileSelected { project_id, token, file_path } => {
+                // Spawn the import task
+                commands.spawn(ImportCocoTask {
+                    project_id: project_id.clone(),
+                    token: token.clone(),
+                    file_path: file_path.clone(),
+                    fetch_images: page_data.import_fetch_images,
+                });
+            }
+            ImportResult::Imported { project_id, result } => {
+                info!("COCO import completed successfully: {}", result.message);
+                page_data.is_importing_coco = false;
+
+                let stats_msg = t!(
+                    "settings-import-done",
+                    categories = result.stats.categories_created,
+                    tasks = result.stats.tasks_created,
+                    annotations = result.stats.annotations_created,
+                    updated = result.stats.categories_updated,
+                );
+
+                if !result.stats.errors.is_empty() {
+                    notify.write(Notify::error(t!(
+                        "settings-import-done-with-errors",
+                        summary = stats_msg,
+                        count = result.stats.errors.len(),
+                    )));
+                } else {
+                    notify.write(Notify::success(stats_msg));
                 }
-                ImportResult::Cancelled => {
-                    // Reset import state
-                    page_data.is_importing_coco = false;
+                if result.stats.images_fetched > 0 {
+                    notify.write(Notify::info(t!("settings-import-images-fetched", count = result.stats.images_fetched)));
+                }
+
+                // Reload categories if any were created or updated
+                if result.stats.categories_created > 0 || result.stats.categories_updated > 0 {
+                    if let Some(token) = auth_state.get_jwt() {
+                        load_categories_events.write(LoadCategoriesEvent {
+                            project_id: *project_id,
+                            token: token.clone(),
+                        });
+                    }
                 }
             }
+            ImportResult::Cancelled => {
+                // Reset import state
+                page_data.is_importing_coco = false;
+            }
         }
     }
+
+    for failure in failed.read() {
+        page_data.is_importing_coco = false;
+        notify.write(Notify::error(failure.error.clone()));
+    }
 }
 
 fn process_export_results(
-    receiver: Res<ExportChannelReceiver>,
+    mut succeeded: EventReader<ApiTaskSucceeded<ExportResult>>,
+    mut failed: EventReader<ApiTaskFailed<ExportResult>>,
     mut page_data: ResMut<ProjectSettingsPageData>,
+    mut notify: EventWriter<Notify>,
 ) {
-    if let Ok(rx) = receiver.0.lock() {
-        while let Ok(result) = rx.try_recv() {
-            match result {
-                ExportResult::Success { file_path } => {
-                    page_data.is_exporting_coco = false;
-                    page_data.export_error = None;
-                    page_data.export_success_message = Some(format!("Export completed! File saved to: {}", file_path));
-                }
-                ExportResult::Error { error } => {
-                    page_data.is_exporting_coco = false;
-                    page_data.export_error = Some(error);
-                    page_data.export_success_message = None;
-                }
-            }
+    for ApiTaskSucceeded(result) in succeeded.read() {
+        page_data.is_exporting_coco = false;
+        if let ExportResult::Success { file_path } = result {
+            notify.write(Notify::success(t!("settings-export-done", path = file_path.as_str())));
         }
     }
+
+    for failure in failed.read() {
+        page_data.is_exporting_coco = false;
+        notify.write(Notify::error(failure.error.clone()));
+    }
 }
 
 pub struct ProjectSettingsPlugin;
 
 impl Plugin for ProjectSettingsPlugin {
     fn build(&self, app: &mut App) {
-        let (tx, rx) = channel::<CategoryResult>();
-        let (import_tx, import_rx) = channel::<ImportResult>();
-        let (export_tx, export_rx) = channel::<ExportResult>();
-        
-        app.init_resource::<CategoryState>()
-           .insert_resource(CategoryChannelSender(Mutex::new(tx)))
-           .insert_resource(CategoryChannelReceiver(Mutex::new(rx)))
-           .insert_resource(ImportChannelSender(Mutex::new(import_tx)))
-           .insert_resource(ImportChannelReceiver(Mutex::new(import_rx)))
-           .insert_resource(ExportChannelSender(Mutex::new(export_tx)))
-           .insert_resource(ExportChannelReceiver(Mutex::new(export_rx)))
+        app.add_plugins((
+               ApiTaskPlugin::<CategoryResult>::default(),
+               ApiTaskPlugin::<ImportResult>::default(),
+               ApiTaskPlugin::<ExportResult>::default(),
+               ApiTaskPlugin::<SyncHistory>::default(),
+               ApiTaskPlugin::<ExportPresetResult>::default(),
+               ApiTaskPlugin::<BoxRulesResult>::default(),
+               ApiTaskPlugin::<SavedProject>::default(),
+               ApiTaskPlugin::<SavedStorageConfig>::default(),
+               ApiTaskPlugin::<DeletedProject>::default(),
+               ApiTaskPlugin::<ClonedProject>::default(),
+               ApiTaskPlugin::<CategoryImportResult>::default(),
+           ))
+           .init_resource::<CategoryState>()
            .add_event::<LoadCategoriesEvent>()
            .add_event::<CreateCategoryEvent>()
            .add_event::<CategoryCreatedEvent>()
@@ -1745,6 +2772,8 @@ impl Plugin for ProjectSettingsPlugin {
                update,
                handle_save_project_task,
                handle_delete_project_task,
+               handle_clone_project_task,
+               handle_import_categories_task,
                handle_save_storage_config_task,
                handle_select_file_path_task,
                handle_download_coco_export_task,
@@ -1756,11 +2785,17 @@ impl Plugin for ProjectSettingsPlugin {
                process_category_results,
                process_import_results,
                process_export_results,
+               process_category_import_results,
+               process_sync_history_results,
+               process_export_preset_results,
+               process_box_rules_results,
            ).run_if(in_state(AppState::ProjectSettings)))
            .add_systems(
                EguiContextPass,
                ui_system.run_if(in_state(AppState::ProjectSettings)),
            )
+           // Outside of the page too, see process_project_results
+           .add_systems(Update, process_project_results)
            .add_systems(OnExit(AppState::ProjectSettings), cleanup);
     }
 }
\ No newline at end of file
@@ -1,6 +1,13 @@
 use crate::ui::components::egui_common;
 use crate::app::state::AppState;
-use crate::auth::{AuthState, ProjectsState, fetch_projects, create_project};
+use crate::deep_link::{DeepLink, PendingDeepLink};
+use crate::workspace::Workspace;
+use crate::auth::{AuthState, ProjectsState, fetch_projects, create_project, fetch_templates, set_project_archived};
+use crate::api::projects::{Project, TASK_TYPE_CLASSIFICATION};
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use crate::api::templates::ProjectTemplate;
+use crate::platform;
+use crate::upload::UploadState;
 use bevy::prelude::*;
 use bevy_egui::{EguiContexts, EguiContextPass, egui};
 
@@ -11,12 +18,43 @@ pub struct ProjectsPageData {
     pub new_project_description: String,
     pub create_error: Option<String>,
     pub is_creating: bool,
+    pub templates: Vec<ProjectTemplate>,
+    pub selected_template_id: Option<String>,
+    /// Create a classification project (whole-image labels) instead of a detection one
+    pub classification: bool,
+    /// Why archiving or unarchiving a project failed
+    pub archive_error: Option<String>,
+}
+
+/// Projects of the user, loaded when the page opens or is refreshed
+pub struct LoadedProjects(Vec<Project>);
+
+/// Templates offered in the create dialog
+pub struct LoadedTemplates(Vec<ProjectTemplate>);
+
+pub struct CreatedProject(Project);
+
+/// A project was archived, or unarchived when `archived_at` is `None`
+pub struct ArchiveChanged {
+    project_id: String,
+    archived_at: Option<String>,
+}
+
+fn request_projects(projects_state: &mut ProjectsState, project_tasks: &ApiTasks<LoadedProjects>, auth_state: &AuthState) {
+    let Some(jwt) = auth_state.get_jwt() else {
+        return;
+    };
+
+    projects_state.start_fetching();
+    let jwt = jwt.clone();
+    project_tasks.spawn(async move { fetch_projects(&jwt).await.map(LoadedProjects) });
 }
 
 pub fn setup(
     mut commands: Commands,
     auth_state: Res<AuthState>,
     mut projects_state: ResMut<ProjectsState>,
+    project_tasks: Res<ApiTasks<LoadedProjects>>,
 ) {
     println!("projects setup");
     
@@ -24,27 +62,72 @@ pub fn setup(
     
     // Fetch projects if authenticated and not already fetching
     if auth_state.is_authenticated() && !projects_state.is_fetching {
-        if let Some(jwt) = auth_state.get_jwt() {
-            let jwt = jwt.clone();
-            projects_state.start_fetching();
-            
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            match rt.block_on(fetch_projects(&jwt)) {
-                Ok(projects) => {
-                    projects_state.set_projects(projects);
-                }
-                Err(error) => {
-                    projects_state.set_error(error);
-                }
-            }
+        request_projects(&mut projects_state, &project_tasks, &auth_state);
+    }
+}
+
+/// Projects are kept for other pages too, so their results are read on every page and a load
+/// started here isn't lost when the user moves on before it is back
+#[allow(clippy::too_many_arguments)]
+pub fn process_project_results(
+    mut loaded: EventReader<ApiTaskSucceeded<LoadedProjects>>,
+    mut load_failed: EventReader<ApiTaskFailed<LoadedProjects>>,
+    mut templates_loaded: EventReader<ApiTaskSucceeded<LoadedTemplates>>,
+    mut templates_failed: EventReader<ApiTaskFailed<LoadedTemplates>>,
+    mut created: EventReader<ApiTaskSucceeded<CreatedProject>>,
+    mut create_failed: EventReader<ApiTaskFailed<CreatedProject>>,
+    mut archive_changed: EventReader<ApiTaskSucceeded<ArchiveChanged>>,
+    mut archive_failed: EventReader<ApiTaskFailed<ArchiveChanged>>,
+    mut projects_state: ResMut<ProjectsState>,
+    mut page_data: Option<ResMut<ProjectsPageData>>,
+) {
+    for ApiTaskSucceeded(LoadedProjects(projects)) in loaded.read() {
+        projects_state.set_projects(projects.clone());
+    }
+    for failure in load_failed.read() {
+        projects_state.set_error(failure.error.clone());
+    }
+    for ApiTaskSucceeded(changed) in archive_changed.read() {
+        if let Some(project) = projects_state.projects.iter_mut().find(|p| p.id == changed.project_id) {
+            project.archived_at = changed.archived_at.clone();
+        }
+    }
+
+    for ApiTaskSucceeded(CreatedProject(project)) in created.read() {
+        projects_state.add_project(project.clone());
+        if let Some(page_data) = page_data.as_deref_mut() {
+            page_data.show_create_dialog = false;
+            page_data.new_project_name.clear();
+            page_data.new_project_description.clear();
+            page_data.selected_template_id = None;
+            page_data.classification = false;
+            page_data.is_creating = false;
         }
     }
+
+    let Some(page_data) = page_data.as_deref_mut() else {
+        return;
+    };
+    for failure in create_failed.read() {
+        page_data.create_error = Some(failure.error.clone());
+        page_data.is_creating = false;
+    }
+    for failure in archive_failed.read() {
+        page_data.archive_error = Some(failure.error.clone());
+    }
+    for ApiTaskSucceeded(LoadedTemplates(templates)) in templates_loaded.read() {
+        page_data.templates = templates.clone();
+    }
+    for failure in templates_failed.read() {
+        page_data.create_error = Some(t!("projects-templates-failed", error = failure.error.as_str()));
+    }
 }
 
 pub fn update() {
     // No update logic needed for projects page currently
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn ui_system(
     mut commands: Commands,
     mut contexts: EguiContexts,
@@ -52,38 +135,39 @@ pub fn ui_system(
     mut next_state: ResMut<NextState<AppState>>,
     mut projects_state: ResMut<ProjectsState>,
     mut page_data: ResMut<ProjectsPageData>,
+    mut upload_state: ResMut<UploadState>,
+    mut pending_link: ResMut<PendingDeepLink>,
+    workspace: Res<Workspace>,
     auth_state: Res<AuthState>,
+    project_tasks: Res<ApiTasks<LoadedProjects>>,
+    template_tasks: Res<ApiTasks<LoadedTemplates>>,
+    create_tasks: Res<ApiTasks<CreatedProject>>,
+    archive_tasks: Res<ApiTasks<ArchiveChanged>>,
 ) {
     egui_common::ui_top_panel(&mut contexts, current_state, &mut next_state);
 
     egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
         ui.vertical_centered(|ui| {
-            ui.heading("Projects");
+            ui.heading(t!("projects-title"));
             ui.add_space(10.0);
         });
 
         // Create new project button
         ui.horizontal(|ui| {
-            if ui.button("➕ New Project").clicked() {
+            if ui.button(t!("projects-new")).clicked() {
                 page_data.show_create_dialog = true;
+
+                // Load templates for the picker
+                if let Some(jwt) = auth_state.get_jwt() {
+                    let jwt = jwt.clone();
+                    template_tasks.cancel_all();
+                    template_tasks.spawn(async move { fetch_templates(&jwt).await.map(LoadedTemplates) });
+                }
             }
             
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                if ui.button("🔄 Refresh").clicked() && !projects_state.is_fetching {
-                    if let Some(jwt) = auth_state.get_jwt() {
-                        let jwt = jwt.clone();
-                        projects_state.start_fetching();
-                        
-                        let rt = tokio::runtime::Runtime::new().unwrap();
-                        match rt.block_on(fetch_projects(&jwt)) {
-                            Ok(projects) => {
-                                projects_state.set_projects(projects);
-                            }
-                            Err(error) => {
-                                projects_state.set_error(error);
-                            }
-                        }
-                    }
+                if ui.button(t!("projects-refresh")).clicked() && !projects_state.is_fetching {
+                    request_projects(&mut projects_state, &project_tasks, &auth_state);
                 }
             });
         });
@@ -94,27 +178,37 @@ pub fn ui_system(
         if projects_state.is_fetching {
             ui.vertical_centered(|ui| {
                 ui.add(egui::Spinner::new());
-                ui.label("Loading projects...");
+                ui.label(t!("projects-loading"));
             });
             return;
         }
 
         // Show error state
         if let Some(error) = &projects_state.fetch_error {
-            ui.colored_label(egui::Color32::RED, format!("Error: {}", error));
+            ui.colored_label(egui::Color32::RED, t!("common-error", error = error.as_str()));
+            ui.separator();
+        }
+
+        if let Some(error) = &page_data.archive_error {
+            ui.colored_label(egui::Color32::RED, t!("projects-archive-failed", error = error.as_str()));
             ui.separator();
         }
 
         // Projects list
         egui::ScrollArea::vertical().show(ui, |ui| {
-            if projects_state.projects.is_empty() {
+            render_recent_projects(ui, &projects_state, &workspace, &mut commands, &mut next_state, &mut pending_link);
+
+            let (archived, active): (Vec<&Project>, Vec<&Project>) =
+                projects_state.projects.iter().partition(|project| project.is_archived());
+
+            if active.is_empty() {
                 ui.vertical_centered(|ui| {
                     ui.add_space(50.0);
-                    ui.label("No projects found");
-                    ui.label("Create your first project to get started!");
+                    ui.label(t!("projects-empty"));
+                    ui.label(t!("projects-empty-hint"));
                 });
             } else {
-                for project in &projects_state.projects {
+                for project in active {
                     ui.group(|ui| {
                         ui.horizontal(|ui| {
                             ui.vertical(|ui| {
@@ -122,13 +216,16 @@ pub fn ui_system(
                                 if let Some(description) = &project.description {
                                     ui.label(description);
                                 } else {
-                                    ui.weak("No description");
+                                    ui.weak(t!("projects-no-description"));
                                 }
-                                ui.weak(format!("Created: {}", format_date(&project.created_at)));
+                                if project.is_classification() {
+                                    ui.weak(t!("projects-classification"));
+                                }
+                                ui.weak(t!("projects-created", date = format_date(&project.created_at)));
                             });
                             
                             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                if ui.button("Open").clicked() {
+                                if ui.button(t!("projects-open")).clicked() {
                                     println!("Opening project: {}", project.name);
                                     // Set project ID parameter for Tasks page
                                     commands.insert_resource(crate::pages::tasks::Parameters {
@@ -137,7 +234,39 @@ pub fn ui_system(
                                     next_state.set(AppState::Tasks);
                                 }
                                 
-                                if ui.button("🔧 Settings").clicked() {
+                                if platform::DESKTOP && ui.button(t!("projects-upload")).clicked() {
+                                    upload_state.open(project.id.clone(), project.name.clone());
+                                }
+
+                                if ui.button(t!("nav-reports")).clicked() {
+                                    commands.insert_resource(crate::pages::reports::Parameters {
+                                        project_id: project.id.clone(),
+                                    });
+                                    next_state.set(AppState::Reports);
+                                }
+
+                                if ui.button(t!("nav-stats")).clicked() {
+                                    commands.insert_resource(crate::pages::stats::Parameters {
+                                        project_id: project.id.clone(),
+                                    });
+                                    next_state.set(AppState::Stats);
+                                }
+
+                                if ui.button(t!("nav-storage")).clicked() {
+                                    commands.insert_resource(crate::pages::storage_browser::Parameters {
+                                        project_id: project.id.clone(),
+                                    });
+                                    next_state.set(AppState::StorageBrowser);
+                                }
+
+                                if ui.button(t!("nav-review")).clicked() {
+                                    commands.insert_resource(crate::pages::review::Parameters {
+                                        project_id: project.id.clone(),
+                                    });
+                                    next_state.set(AppState::Review);
+                                }
+
+                                if ui.button(t!("nav-settings")).clicked() {
                                     // Navigate to project settings page
                                     println!("Opening settings for project: {}", project.name);
                                     // Set project ID parameter for ProjectSettings page
@@ -146,86 +275,205 @@ pub fn ui_system(
                                     });
                                     next_state.set(AppState::ProjectSettings);
                                 }
+
+                                if ui.button(t!("projects-archive")).on_hover_text(t!("projects-archive-hint")).clicked() {
+                                    request_archive(&archive_tasks, &auth_state, &mut page_data, &project.id, true);
+                                }
                             });
                         });
                     });
                     ui.add_space(5.0);
                 }
             }
+
+            if !archived.is_empty() {
+                ui.add_space(10.0);
+                egui::CollapsingHeader::new(t!("projects-archived", count = archived.len()))
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        for project in archived {
+                            ui.group(|ui| {
+                                ui.horizontal(|ui| {
+                                    ui.vertical(|ui| {
+                                        ui.strong(&project.name);
+                                        if let Some(description) = &project.description {
+                                            ui.label(description);
+                                        }
+                                        if let Some(archived_at) = &project.archived_at {
+                                            ui.weak(t!("projects-archived-on", date = format_date(archived_at)));
+                                        }
+                                    });
+
+                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                        if ui.button(t!("projects-unarchive")).clicked() {
+                                            request_archive(&archive_tasks, &auth_state, &mut page_data, &project.id, false);
+                                        }
+
+                                        if ui.button(t!("nav-reports")).clicked() {
+                                            commands.insert_resource(crate::pages::reports::Parameters {
+                                                project_id: project.id.clone(),
+                                            });
+                                            next_state.set(AppState::Reports);
+                                        }
+
+                                        if ui.button(t!("nav-stats")).clicked() {
+                                            commands.insert_resource(crate::pages::stats::Parameters {
+                                                project_id: project.id.clone(),
+                                            });
+                                            next_state.set(AppState::Stats);
+                                        }
+                                    });
+                                });
+                            });
+                            ui.add_space(5.0);
+                        }
+                    });
+            }
         });
 
         // Create project dialog
-        show_create_project_dialog(ui, &mut page_data, &mut projects_state, &auth_state);
+        show_create_project_dialog(ui, &mut page_data, &create_tasks, &auth_state);
+    });
+}
+
+/// Recent projects of the workspace that are still there, to go back to their task list or
+/// straight to the task last opened in the editor.
+fn render_recent_projects(
+    ui: &mut egui::Ui,
+    projects_state: &ProjectsState,
+    workspace: &Workspace,
+    commands: &mut Commands,
+    next_state: &mut NextState<AppState>,
+    pending_link: &mut PendingDeepLink,
+) {
+    let recent: Vec<_> = workspace
+        .recent_projects()
+        .filter_map(|recent| {
+            let project = projects_state.projects.iter().find(|project| project.id == recent.project_id && !project.is_archived())?;
+            Some((project, recent.last_task_id))
+        })
+        .collect();
+    if recent.is_empty() {
+        return;
+    }
+
+    ui.strong(t!("projects-recent"));
+    for (project, last_task_id) in recent {
+        ui.horizontal(|ui| {
+            ui.label(&project.name);
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button(t!("projects-open")).clicked() {
+                    commands.insert_resource(crate::pages::tasks::Parameters {
+                        project_id: project.id.clone(),
+                    });
+                    next_state.set(AppState::Tasks);
+                }
+
+                let link = last_task_id.zip(uuid::Uuid::parse_str(&project.id).ok())
+                    .map(|(task_id, project_id)| DeepLink { project_id, task_id });
+                if let Some(link) = link {
+                    // Opened like a link, which looks up the image of the task
+                    if ui.button(t!("projects-resume")).on_hover_text(t!("projects-resume-hint")).clicked() {
+                        pending_link.0 = Some(link);
+                    }
+                }
+            });
+        });
+    }
+    ui.separator();
+}
+
+fn request_archive(
+    archive_tasks: &ApiTasks<ArchiveChanged>,
+    auth_state: &AuthState,
+    page_data: &mut ProjectsPageData,
+    project_id: &str,
+    archived: bool,
+) {
+    let Some(jwt) = auth_state.get_jwt() else {
+        return;
+    };
+
+    page_data.archive_error = None;
+    let jwt = jwt.clone();
+    let project_id = project_id.to_string();
+    archive_tasks.spawn(async move {
+        set_project_archived(&jwt, &project_id, archived)
+            .await
+            .map(|archived_at| ArchiveChanged { project_id, archived_at })
     });
 }
 
 fn show_create_project_dialog(
     ui: &mut egui::Ui,
     page_data: &mut ProjectsPageData,
-    projects_state: &mut ProjectsState,
+    create_tasks: &ApiTasks<CreatedProject>,
     auth_state: &AuthState,
 ) {
     if !page_data.show_create_dialog {
         return;
     }
 
-    egui::Window::new("Create New Project")
+    egui::Window::new(t!("projects-create-title"))
         .collapsible(false)
         .resizable(false)
         .show(ui.ctx(), |ui| {
             ui.vertical(|ui| {
-                ui.label("Project Name:");
+                ui.label(t!("projects-name"));
                 ui.text_edit_singleline(&mut page_data.new_project_name);
                 
                 ui.add_space(10.0);
                 
-                ui.label("Description (optional):");
+                ui.label(t!("projects-description"));
                 ui.text_edit_multiline(&mut page_data.new_project_description);
                 
+                ui.add_space(10.0);
+
+                show_template_picker(ui, page_data);
+
+                ui.add_space(10.0);
+
+                ui.checkbox(&mut page_data.classification, t!("projects-classification-option"));
+
                 ui.add_space(10.0);
                 
                 // Show create error
                 if let Some(error) = &page_data.create_error {
-                    ui.colored_label(egui::Color32::RED, format!("Error: {}", error));
+                    ui.colored_label(egui::Color32::RED, t!("common-error", error = error.as_str()));
                 }
                 
                 ui.horizontal(|ui| {
-                    if ui.button("Cancel").clicked() {
+                    if ui.button(t!("common-cancel")).clicked() {
                         page_data.show_create_dialog = false;
                         page_data.new_project_name.clear();
                         page_data.new_project_description.clear();
+                        page_data.selected_template_id = None;
+                        page_data.classification = false;
                         page_data.create_error = None;
                     }
                     
                     let can_create = !page_data.new_project_name.trim().is_empty() && !page_data.is_creating;
                     
-                    if ui.add_enabled(can_create, egui::Button::new("Create")).clicked() {
+                    if ui.add_enabled(can_create, egui::Button::new(t!("projects-create"))).clicked() {
                         if let Some(jwt) = auth_state.get_jwt() {
                             let jwt = jwt.clone();
                             let name = page_data.new_project_name.trim().to_string();
                             let description = if page_data.new_project_description.trim().is_empty() {
                                 None
                             } else {
-                                Some(page_data.new_project_description.trim())
+                                Some(page_data.new_project_description.trim().to_string())
                             };
+                            let template_id = page_data.selected_template_id.clone();
+                            let task_type = page_data.classification.then_some(TASK_TYPE_CLASSIFICATION);
                             
                             page_data.is_creating = true;
                             page_data.create_error = None;
                             
-                            let rt = tokio::runtime::Runtime::new().unwrap();
-                            match rt.block_on(create_project(&jwt, &name, description)) {
-                                Ok(project) => {
-                                    projects_state.add_project(project);
-                                    page_data.show_create_dialog = false;
-                                    page_data.new_project_name.clear();
-                                    page_data.new_project_description.clear();
-                                    page_data.is_creating = false;
-                                }
-                                Err(error) => {
-                                    page_data.create_error = Some(error);
-                                    page_data.is_creating = false;
-                                }
-                            }
+                            create_tasks.spawn(async move {
+                                create_project(&jwt, &name, description.as_deref(), template_id.as_deref(), task_type)
+                                    .await
+                                    .map(CreatedProject)
+                            });
                         }
                     }
                     
@@ -237,6 +485,49 @@ fn show_create_project_dialog(
         });
 }
 
+fn show_template_picker(ui: &mut egui::Ui, page_data: &mut ProjectsPageData) {
+    ui.label(t!("projects-template"));
+
+    let selected_text = page_data
+        .selected_template_id
+        .as_ref()
+        .and_then(|id| page_data.templates.iter().find(|t| &t.id == id))
+        .map(|t| t.name.clone())
+        .unwrap_or_else(|| t!("projects-blank-template"));
+
+    egui::ComboBox::from_id_salt("project_template")
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut page_data.selected_template_id, None, t!("projects-blank-template"));
+            for template in &page_data.templates {
+                let label = if template.created_by.is_some() {
+                    t!("projects-custom-template", name = template.name.as_str(), count = template.categories.len())
+                } else {
+                    t!("projects-builtin-template", name = template.name.as_str(), count = template.categories.len())
+                };
+                ui.selectable_value(&mut page_data.selected_template_id, Some(template.id.clone()), label);
+            }
+        });
+
+    // Preview the selected template
+    if let Some(template) = page_data
+        .selected_template_id
+        .as_ref()
+        .and_then(|id| page_data.templates.iter().find(|t| &t.id == id))
+    {
+        if let Some(description) = &template.description {
+            ui.weak(description);
+        }
+        let names: Vec<&str> = template.categories.iter().take(8).map(|c| c.name.as_str()).collect();
+        let more = template.categories.len().saturating_sub(names.len());
+        if more > 0 {
+            ui.weak(t!("projects-template-more", names = names.join(", "), count = more));
+        } else {
+            ui.weak(names.join(", "));
+        }
+    }
+}
+
 fn format_date(date_str: &str) -> String {
     // Simple date formatting - just return the first 10 characters (YYYY-MM-DD)
     if date_str.len() >= 10 {
@@ -255,8 +546,14 @@ pub struct ProjectsPlugin;
 
 impl Plugin for ProjectsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Projects), setup)
-           .add_systems(Update, update.run_if(in_state(AppState::Projects)))
+        app.add_plugins((
+               ApiTaskPlugin::<LoadedProjects>::default(),
+               ApiTaskPlugin::<LoadedTemplates>::default(),
+               ApiTaskPlugin::<CreatedProject>::default(),
+               ApiTaskPlugin::<ArchiveChanged>::default(),
+           ))
+           .add_systems(OnEnter(AppState::Projects), setup)
+           .add_systems(Update, (update.run_if(in_state(AppState::Projects)), process_project_results))
            .add_systems(
                EguiContextPass,
                ui_system.run_if(in_state(AppState::Projects)),
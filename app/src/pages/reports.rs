@@ -0,0 +1,336 @@
+use crate::ui::components::egui_common;
+use crate::app::state::AppState;
+use crate::auth::AuthState;
+use crate::api::reports::{AnnotatorReport, AnnotatorsReport, ReportsApi};
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiContextPass, egui};
+use chrono::{Duration, NaiveDate, Utc};
+
+/// Days of the report when the page opens
+const DEFAULT_DAYS: i64 = 30;
+const CHART_HEIGHT: f32 = 220.0;
+
+#[derive(Resource, Default)]
+pub struct Parameters {
+    pub project_id: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ChartMetric {
+    Boxes,
+    Annotations,
+}
+
+impl ChartMetric {
+    fn value(self, annotations: i64, boxes: i64) -> i64 {
+        match self {
+            ChartMetric::Boxes => boxes,
+            ChartMetric::Annotations => annotations,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct ReportsPageData {
+    /// First and last day of the report as typed, `YYYY-MM-DD`
+    pub from: String,
+    pub to: String,
+    pub report: Option<AnnotatorsReport>,
+    pub error: Option<String>,
+    pub is_loading: bool,
+    pub metric: ChartMetric,
+}
+
+impl Default for ReportsPageData {
+    fn default() -> Self {
+        let (from, to) = last_days(DEFAULT_DAYS);
+        Self {
+            from: from.to_string(),
+            to: to.to_string(),
+            report: None,
+            error: None,
+            is_loading: false,
+            metric: ChartMetric::Boxes,
+        }
+    }
+}
+
+/// The last `days` UTC days up to today, the days the server groups saves by
+fn last_days(days: i64) -> (NaiveDate, NaiveDate) {
+    let today = Utc::now().date_naive();
+    (today - Duration::days(days - 1), today)
+}
+
+fn request_report(
+    page_data: &mut ReportsPageData,
+    report_tasks: &ApiTasks<AnnotatorsReport>,
+    auth_state: &AuthState,
+    parameters: Option<&Parameters>,
+) {
+    let (Some(jwt), Some(params)) = (auth_state.get_jwt(), parameters) else {
+        return;
+    };
+    let (Ok(from), Ok(to)) = (page_data.from.parse::<NaiveDate>(), page_data.to.parse::<NaiveDate>()) else {
+        page_data.error = Some(t!("reports-invalid-date"));
+        return;
+    };
+
+    page_data.is_loading = true;
+    page_data.error = None;
+    // A report still on its way is for the dates picked before
+    report_tasks.cancel_all();
+    let (jwt, project_id) = (jwt.clone(), params.project_id.clone());
+    report_tasks.spawn(async move {
+        ReportsApi::new()
+            .get_annotators_report(&jwt, &project_id, from, to)
+            .await
+            .map_err(|e| e.to_string())
+    });
+}
+
+pub fn setup(
+    mut commands: Commands,
+    auth_state: Res<AuthState>,
+    parameters: Option<Res<Parameters>>,
+    report_tasks: Res<ApiTasks<AnnotatorsReport>>,
+) {
+    println!("reports setup");
+
+    let mut page_data = ReportsPageData::default();
+    request_report(&mut page_data, &report_tasks, &auth_state, parameters.as_deref());
+    commands.insert_resource(page_data);
+}
+
+pub fn process_report_results(
+    mut succeeded: EventReader<ApiTaskSucceeded<AnnotatorsReport>>,
+    mut failed: EventReader<ApiTaskFailed<AnnotatorsReport>>,
+    page_data: Option<ResMut<ReportsPageData>>,
+) {
+    let Some(mut page_data) = page_data else {
+        return;
+    };
+
+    for ApiTaskSucceeded(report) in succeeded.read() {
+        page_data.is_loading = false;
+        page_data.report = Some(report.clone());
+    }
+
+    for failure in failed.read() {
+        page_data.is_loading = false;
+        page_data.error = Some(failure.error.clone());
+    }
+}
+
+pub fn ui_system(
+    mut contexts: EguiContexts,
+    current_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut page_data: ResMut<ReportsPageData>,
+    auth_state: Res<AuthState>,
+    parameters: Option<Res<Parameters>>,
+    report_tasks: Res<ApiTasks<AnnotatorsReport>>,
+) {
+    egui_common::ui_top_panel(&mut contexts, current_state, &mut next_state);
+
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        ui.vertical_centered(|ui| {
+            ui.heading(t!("reports-title"));
+            ui.add_space(10.0);
+        });
+
+        if parameters.is_none() {
+            ui.vertical_centered(|ui| {
+                ui.add_space(50.0);
+                ui.label(t!("reports-no-project"));
+            });
+            return;
+        }
+
+        let mut refresh = false;
+        ui.horizontal(|ui| {
+            ui.label(t!("reports-from"));
+            ui.add(egui::TextEdit::singleline(&mut page_data.from).desired_width(90.0));
+            ui.label(t!("reports-to"));
+            ui.add(egui::TextEdit::singleline(&mut page_data.to).desired_width(90.0));
+            for days in [7, 30, 90] {
+                if ui.button(t!("reports-last-days", days = days)).clicked() {
+                    let (from, to) = last_days(days);
+                    page_data.from = from.to_string();
+                    page_data.to = to.to_string();
+                    refresh = true;
+                }
+            }
+            if ui.add_enabled(!page_data.is_loading, egui::Button::new(t!("projects-refresh"))).clicked() {
+                refresh = true;
+            }
+            if page_data.is_loading {
+                ui.add(egui::Spinner::new());
+            }
+        });
+        if refresh {
+            request_report(&mut page_data, &report_tasks, &auth_state, parameters.as_deref());
+        }
+
+        if let Some(error) = &page_data.error {
+            ui.colored_label(egui::Color32::RED, t!("common-error", error = error.as_str()));
+        }
+        ui.separator();
+
+        let Some(report) = page_data.report.clone() else {
+            return;
+        };
+        if report.annotators.is_empty() {
+            ui.vertical_centered(|ui| {
+                ui.add_space(50.0);
+                ui.label(t!("reports-empty", from = report.from.to_string(), to = report.to.to_string()));
+            });
+            return;
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            render_summary_table(ui, &report);
+            ui.add_space(15.0);
+
+            ui.horizontal(|ui| {
+                ui.strong(t!("reports-per-day"));
+                ui.selectable_value(&mut page_data.metric, ChartMetric::Boxes, t!("reports-boxes"));
+                ui.selectable_value(&mut page_data.metric, ChartMetric::Annotations, t!("reports-annotations"));
+            });
+            render_daily_chart(ui, &report, page_data.metric);
+        });
+    });
+}
+
+/// Color of an annotator in the chart, spread around the hue circle
+fn annotator_color(index: usize) -> egui::Color32 {
+    let hue = (index as f32 * 0.618_034).fract();
+    egui::ecolor::Hsva::new(hue, 0.6, 0.85, 1.0).into()
+}
+
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.round() as i64;
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
+
+fn render_summary_table(ui: &mut egui::Ui, report: &AnnotatorsReport) {
+    let days = (report.to - report.from).num_days() + 1;
+
+    egui::Grid::new("annotators_report")
+        .striped(true)
+        .num_columns(6)
+        .spacing([20.0, 6.0])
+        .show(ui, |ui| {
+            ui.strong(t!("reports-annotator"));
+            ui.strong(t!("reports-tasks"));
+            ui.strong(t!("reports-annotations"));
+            ui.strong(t!("reports-boxes"));
+            ui.strong(t!("reports-time-per-task"));
+            ui.strong(t!("reports-boxes-per-day"));
+            ui.end_row();
+
+            for (index, annotator) in report.annotators.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    let (rect, _) = ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
+                    ui.painter().rect_filled(rect, 2.0, annotator_color(index));
+                    ui.label(&annotator.name);
+                });
+                ui.label(annotator.tasks.to_string());
+                ui.label(annotator.annotations.to_string());
+                ui.label(annotator.boxes.to_string());
+                match annotator.average_seconds_per_task {
+                    Some(seconds) => ui.label(format_duration(seconds)),
+                    None => ui.weak("–"),
+                };
+                ui.label(format!("{:.1}", annotator.boxes as f64 / days as f64));
+                ui.end_row();
+            }
+        });
+}
+
+/// Stacked bars of every day of the report, one color per annotator
+fn render_daily_chart(ui: &mut egui::Ui, report: &AnnotatorsReport, metric: ChartMetric) {
+    let days: Vec<NaiveDate> = report.from.iter_days().take_while(|day| *day <= report.to).collect();
+    let value = |annotator: &AnnotatorReport, day: NaiveDate| {
+        annotator
+            .days
+            .iter()
+            .find(|annotator_day| annotator_day.date == day)
+            .map_or(0, |annotator_day| metric.value(annotator_day.annotations, annotator_day.boxes))
+    };
+    let totals: Vec<i64> = days
+        .iter()
+        .map(|day| report.annotators.iter().map(|annotator| value(annotator, *day)).sum())
+        .collect();
+    let max = totals.iter().copied().max().unwrap_or(0).max(1);
+
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(ui.available_width(), CHART_HEIGHT), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let text_color = ui.visuals().weak_text_color();
+    let font = egui::FontId::proportional(11.0);
+    let plot = egui::Rect::from_min_max(rect.min + egui::vec2(0.0, 14.0), rect.max - egui::vec2(0.0, 14.0));
+    let bar_width = plot.width() / days.len() as f32;
+
+    painter.line_segment([plot.left_bottom(), plot.right_bottom()], ui.visuals().widgets.noninteractive.bg_stroke);
+    painter.text(rect.left_top(), egui::Align2::LEFT_TOP, max.to_string(), font.clone(), text_color);
+    painter.text(rect.left_bottom(), egui::Align2::LEFT_BOTTOM, report.from.to_string(), font.clone(), text_color);
+    painter.text(rect.right_bottom(), egui::Align2::RIGHT_BOTTOM, report.to.to_string(), font, text_color);
+
+    for (day_index, day) in days.iter().enumerate() {
+        let left = plot.left() + day_index as f32 * bar_width;
+        let mut bottom = plot.bottom();
+        for (index, annotator) in report.annotators.iter().enumerate() {
+            let height = value(annotator, *day) as f32 / max as f32 * plot.height();
+            if height <= 0.0 {
+                continue;
+            }
+            let bar = egui::Rect::from_min_max(
+                egui::pos2(left + bar_width * 0.1, bottom - height),
+                egui::pos2(left + bar_width * 0.9, bottom),
+            );
+            painter.rect_filled(bar, 1.0, annotator_color(index));
+            bottom -= height;
+        }
+    }
+
+    // Values of the day under the pointer
+    if let Some(pointer) = response.hover_pos() {
+        let day_index = (((pointer.x - plot.left()) / bar_width) as usize).min(days.len() - 1);
+        let day = days[day_index];
+        response.on_hover_ui_at_pointer(|ui| {
+            ui.strong(day.to_string());
+            for annotator in &report.annotators {
+                let count = value(annotator, day);
+                if count > 0 {
+                    ui.label(format!("{}: {}", annotator.name, count));
+                }
+            }
+            ui.label(t!("reports-total", count = totals[day_index]));
+        });
+    }
+}
+
+pub fn cleanup(mut commands: Commands, report_tasks: Res<ApiTasks<AnnotatorsReport>>) {
+    println!("reports cleanup");
+    report_tasks.cancel_all();
+    commands.remove_resource::<ReportsPageData>();
+}
+
+pub struct ReportsPlugin;
+
+impl Plugin for ReportsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ApiTaskPlugin::<AnnotatorsReport>::default())
+           .add_systems(OnEnter(AppState::Reports), setup)
+           .add_systems(Update, process_report_results.run_if(in_state(AppState::Reports)))
+           .add_systems(
+               EguiContextPass,
+               ui_system.run_if(in_state(AppState::Reports)),
+           )
+           .add_systems(OnExit(AppState::Reports), cleanup);
+    }
+}
@@ -0,0 +1,575 @@
+use crate::ui::components::egui_common;
+use crate::app::state::AppState;
+use crate::auth::AuthState;
+use crate::api::annotations::{AnnotationWithCategory, AnnotationsApi};
+use crate::api::comments::{CommentsApi, CreateCommentRequest};
+use crate::api::resources::ResourcesApi;
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use crate::api::tasks::{TaskWithResolvedUrl, TasksApi};
+use crate::notifications::Notify;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiContextPass, egui};
+use uuid::Uuid;
+use super::detail;
+
+/// Longest side of the images shown, larger ones are scaled down before they become textures
+const MAX_TEXTURE_SIZE: u32 = 4096;
+const VERSION_HEIGHT: f32 = 480.0;
+
+#[derive(Resource, Default)]
+pub struct Parameters {
+    pub project_id: String,
+}
+
+/// Tasks a quality-control sample marked for review
+pub struct ReviewQueue(Vec<TaskWithResolvedUrl>);
+
+/// Image of a task under review with the boxes of its last two saves
+pub struct ReviewedTask {
+    task_id: String,
+    /// `None` for video and volume tasks, which are reviewed in the editor
+    image: Option<Result<(egui::ColorImage, egui::Vec2), String>>,
+    before: Vec<AnnotationWithCategory>,
+    after: Vec<AnnotationWithCategory>,
+}
+
+pub struct ReviewDecision {
+    task_id: String,
+    task_name: String,
+    approved: bool,
+}
+
+/// What is shown of the selected task
+pub struct ShownTask {
+    task_id: String,
+    texture: Option<egui::TextureHandle>,
+    /// Size of the original image, which the boxes are in
+    image_size: egui::Vec2,
+    image_error: Option<String>,
+    /// Boxes before the last save, empty when the task was saved once
+    before: Vec<AnnotationWithCategory>,
+    after: Vec<AnnotationWithCategory>,
+}
+
+#[derive(Resource, Default)]
+pub struct ReviewPageData {
+    pub tasks: Vec<TaskWithResolvedUrl>,
+    pub selected: usize,
+    pub shown: Option<ShownTask>,
+    /// Task whose image and boxes were asked for last
+    pub requested_task: Option<String>,
+    /// Reviewer comment posted with the decision
+    pub comment: String,
+    pub error: Option<String>,
+    pub is_loading_queue: bool,
+    pub is_loading_task: bool,
+    /// Task a decision is on its way for, it stays in the queue until the server took it
+    pub deciding: Option<String>,
+}
+
+impl ReviewPageData {
+    fn select(&mut self, index: usize) {
+        if index < self.tasks.len() && index != self.selected {
+            self.selected = index;
+            self.comment.clear();
+            self.error = None;
+        }
+    }
+}
+
+fn request_queue(
+    page_data: &mut ReviewPageData,
+    queue_tasks: &ApiTasks<ReviewQueue>,
+    auth_state: &AuthState,
+    parameters: Option<&Parameters>,
+) {
+    let (Some(jwt), Some(params)) = (auth_state.get_jwt(), parameters) else {
+        return;
+    };
+
+    page_data.is_loading_queue = true;
+    page_data.error = None;
+    let (jwt, project_id) = (jwt.clone(), params.project_id.clone());
+    queue_tasks.spawn(async move {
+        TasksApi::new()
+            .list_tasks_for_review(&jwt, &project_id)
+            .await
+            .map(ReviewQueue)
+            .map_err(|e| e.to_string())
+    });
+}
+
+/// Starts loading the selected task unless it is shown already or on its way
+fn load_selected_task(
+    page_data: &mut ReviewPageData,
+    task_loads: &ApiTasks<ReviewedTask>,
+    auth_state: &AuthState,
+    parameters: Option<&Parameters>,
+) {
+    let (Some(jwt), Some(params)) = (auth_state.get_jwt(), parameters) else {
+        return;
+    };
+    let Some(task) = page_data.tasks.get(page_data.selected).cloned() else {
+        page_data.shown = None;
+        page_data.requested_task = None;
+        return;
+    };
+    if page_data.requested_task.as_deref() == Some(task.task.id.as_str()) {
+        return;
+    }
+
+    // The task selected before is no longer of interest
+    task_loads.cancel_all();
+    page_data.requested_task = Some(task.task.id.clone());
+    page_data.is_loading_task = true;
+    let (jwt, project_id) = (jwt.clone(), params.project_id.clone());
+    task_loads.spawn(async move {
+        let project_uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
+        let task_uuid = Uuid::parse_str(&task.task.id).map_err(|e| e.to_string())?;
+        let annotations = AnnotationsApi::new()
+            .list_annotations(&jwt, project_uuid, task_uuid)
+            .await
+            .map_err(|e| e.to_string())?;
+        let image = if task.task.is_image() {
+            Some(download_image(&jwt, &project_id, &task).await)
+        } else {
+            None
+        };
+        let (before, after) = last_two_saves(annotations);
+        Ok(ReviewedTask { task_id: task.task.id, image, before, after })
+    });
+}
+
+/// The image as a texture no larger than `MAX_TEXTURE_SIZE`, with the size of the original
+async fn download_image(jwt: &str, project_id: &str, task: &TaskWithResolvedUrl) -> Result<(egui::ColorImage, egui::Vec2), String> {
+    let url = match &task.resolved_resource_url {
+        Some(url) => url.clone(),
+        None => TasksApi::new().get_task_image_url(jwt, project_id, &task.task.id).await.map_err(|e| e.to_string())?,
+    };
+    let bytes = ResourcesApi::new().download_image(&url).await.map_err(|e| e.to_string())?;
+    let image = image::load_from_memory(&bytes).map_err(|e| e.to_string())?;
+    let size = egui::vec2(image.width() as f32, image.height() as f32);
+    let image = if image.width().max(image.height()) > MAX_TEXTURE_SIZE {
+        image.thumbnail(MAX_TEXTURE_SIZE, MAX_TEXTURE_SIZE)
+    } else {
+        image
+    };
+    let rgba = image.to_rgba8();
+    let color_image = egui::ColorImage::from_rgba_unmultiplied([rgba.width() as usize, rgba.height() as usize], rgba.as_raw());
+    Ok((color_image, size))
+}
+
+/// Boxes of the save before the last one and of the last one. Every save is a new annotation,
+/// and the server lists their boxes oldest annotation first.
+fn last_two_saves(annotations: Vec<AnnotationWithCategory>) -> (Vec<AnnotationWithCategory>, Vec<AnnotationWithCategory>) {
+    let mut saves: Vec<Vec<AnnotationWithCategory>> = Vec::new();
+    for annotation in annotations {
+        match saves.last_mut() {
+            Some(save) if save[0].annotation_id == annotation.annotation_id => save.push(annotation),
+            _ => saves.push(vec![annotation]),
+        }
+    }
+    let after = saves.pop().unwrap_or_default();
+    let before = saves.pop().unwrap_or_default();
+    (before, after)
+}
+
+/// Approving completes the task, rejecting sends it back to the annotators with the comment
+/// saying what to fix. Either way it leaves the review queue.
+fn decide(
+    page_data: &mut ReviewPageData,
+    decision_tasks: &ApiTasks<ReviewDecision>,
+    auth_state: &AuthState,
+    parameters: Option<&Parameters>,
+    approved: bool,
+) {
+    if page_data.deciding.is_some() {
+        return;
+    }
+    let (Some(jwt), Some(params), Some(task)) = (auth_state.get_jwt(), parameters, page_data.tasks.get(page_data.selected)) else {
+        return;
+    };
+    let (task_id, task_name) = (task.task.id.clone(), task.task.name.clone());
+    let comment = page_data.comment.trim().to_string();
+    if !approved && comment.is_empty() {
+        page_data.error = Some(t!("review-reject-needs-comment"));
+        return;
+    }
+
+    page_data.error = None;
+    page_data.deciding = Some(task_id.clone());
+    let (jwt, project_id) = (jwt.clone(), params.project_id.clone());
+    decision_tasks.spawn(async move {
+        let tasks_api = TasksApi::new();
+        if !comment.is_empty() {
+            let project_uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
+            let task_uuid = Uuid::parse_str(&task_id).map_err(|e| e.to_string())?;
+            let request = CreateCommentRequest { body: comment, parent_id: None, anchor_bbox: None };
+            CommentsApi::new().create_comment(&jwt, project_uuid, task_uuid, &request).await.map_err(|e| e.to_string())?;
+        }
+        let status = if approved { "completed" } else { "in_progress" };
+        tasks_api
+            .batch_set_status(&jwt, &project_id, std::slice::from_ref(&task_id), status)
+            .await
+            .map_err(|e| e.to_string())?;
+        tasks_api.complete_review(&jwt, &project_id, &task_id).await.map_err(|e| e.to_string())?;
+        Ok(ReviewDecision { task_id, task_name, approved })
+    });
+}
+
+pub fn setup(
+    mut commands: Commands,
+    auth_state: Res<AuthState>,
+    parameters: Option<Res<Parameters>>,
+    queue_tasks: Res<ApiTasks<ReviewQueue>>,
+) {
+    println!("review setup");
+
+    let mut page_data = ReviewPageData::default();
+    request_queue(&mut page_data, &queue_tasks, &auth_state, parameters.as_deref());
+    commands.insert_resource(page_data);
+}
+
+pub fn process_queue_results(
+    mut succeeded: EventReader<ApiTaskSucceeded<ReviewQueue>>,
+    mut failed: EventReader<ApiTaskFailed<ReviewQueue>>,
+    page_data: Option<ResMut<ReviewPageData>>,
+) {
+    let Some(mut page_data) = page_data else {
+        return;
+    };
+
+    for ApiTaskSucceeded(ReviewQueue(tasks)) in succeeded.read() {
+        page_data.is_loading_queue = false;
+        page_data.tasks = tasks.clone();
+        page_data.selected = page_data.selected.min(page_data.tasks.len().saturating_sub(1));
+        // The selected task may have changed on the server, so it is loaded again
+        page_data.requested_task = None;
+    }
+
+    for failure in failed.read() {
+        page_data.is_loading_queue = false;
+        page_data.error = Some(failure.error.clone());
+    }
+}
+
+pub fn process_task_results(
+    mut contexts: EguiContexts,
+    mut succeeded: EventReader<ApiTaskSucceeded<ReviewedTask>>,
+    mut failed: EventReader<ApiTaskFailed<ReviewedTask>>,
+    page_data: Option<ResMut<ReviewPageData>>,
+) {
+    let Some(mut page_data) = page_data else {
+        return;
+    };
+
+    for ApiTaskSucceeded(loaded) in succeeded.read() {
+        page_data.is_loading_task = false;
+        let (texture, image_size, image_error) = match &loaded.image {
+            Some(Ok((image, size))) => {
+                let texture = contexts.ctx_mut().load_texture(
+                    format!("review-{}", loaded.task_id),
+                    image.clone(),
+                    egui::TextureOptions::LINEAR,
+                );
+                (Some(texture), *size, None)
+            }
+            Some(Err(error)) => (None, egui::Vec2::ZERO, Some(error.clone())),
+            None => (None, egui::Vec2::ZERO, None),
+        };
+        page_data.shown = Some(ShownTask {
+            task_id: loaded.task_id.clone(),
+            texture,
+            image_size,
+            image_error,
+            before: loaded.before.clone(),
+            after: loaded.after.clone(),
+        });
+    }
+
+    for failure in failed.read() {
+        page_data.is_loading_task = false;
+        page_data.error = Some(failure.error.clone());
+    }
+}
+
+pub fn process_decision_results(
+    mut succeeded: EventReader<ApiTaskSucceeded<ReviewDecision>>,
+    mut failed: EventReader<ApiTaskFailed<ReviewDecision>>,
+    mut notify: EventWriter<Notify>,
+    page_data: Option<ResMut<ReviewPageData>>,
+) {
+    let Some(mut page_data) = page_data else {
+        return;
+    };
+
+    for ApiTaskSucceeded(decision) in succeeded.read() {
+        page_data.deciding = None;
+        if let Some(index) = page_data.tasks.iter().position(|task| task.task.id == decision.task_id) {
+            page_data.tasks.remove(index);
+            if index == page_data.selected {
+                page_data.comment.clear();
+            } else if index < page_data.selected {
+                page_data.selected -= 1;
+            }
+            page_data.selected = page_data.selected.min(page_data.tasks.len().saturating_sub(1));
+        }
+        let message = if decision.approved {
+            t!("review-approved", task = decision.task_name.as_str())
+        } else {
+            t!("review-rejected", task = decision.task_name.as_str())
+        };
+        notify.write(Notify::success(message));
+    }
+
+    for failure in failed.read() {
+        page_data.deciding = None;
+        notify.write(Notify::error(t!("review-decision-failed", error = failure.error.as_str())));
+    }
+}
+
+/// Color of a category from its `#rrggbb` code
+fn category_color(annotation: &AnnotationWithCategory) -> egui::Color32 {
+    annotation
+        .category_color
+        .as_deref()
+        .and_then(|color| color.strip_prefix('#'))
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .map_or(egui::Color32::YELLOW, |hex| {
+            egui::Color32::from_rgb((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
+        })
+}
+
+/// The image with the boxes of one save drawn over it, rotated boxes turned around their center
+fn render_save(
+    ui: &mut egui::Ui,
+    title: String,
+    texture: &egui::TextureHandle,
+    image_size: egui::Vec2,
+    boxes: &[AnnotationWithCategory],
+) {
+    ui.horizontal(|ui| {
+        ui.strong(title);
+        ui.weak(t!("review-box-count", count = boxes.len()));
+    });
+
+    let scale = (ui.available_width() / image_size.x).min(VERSION_HEIGHT / image_size.y);
+    let (rect, _) = ui.allocate_exact_size(image_size * scale, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.image(
+        texture.id(),
+        rect,
+        egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+        egui::Color32::WHITE,
+    );
+
+    for annotation in boxes {
+        let [x, y, width, height] = annotation.bbox[..] else {
+            continue;
+        };
+        let (x, y, width, height) = (x as f32, y as f32, width as f32, height as f32);
+        let color = category_color(annotation);
+        let center = rect.min + egui::vec2(x + width / 2.0, y + height / 2.0) * scale;
+        let (sin, cos) = (annotation.rotation as f32).to_radians().sin_cos();
+        let corners: Vec<egui::Pos2> = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+            .into_iter()
+            .map(|(dx, dy): (f32, f32)| {
+                let (dx, dy) = (dx * width / 2.0, dy * height / 2.0);
+                center + egui::vec2(dx * cos - dy * sin, dx * sin + dy * cos) * scale
+            })
+            .collect();
+        painter.text(corners[0], egui::Align2::LEFT_BOTTOM, &annotation.category_name, egui::FontId::proportional(12.0), color);
+        painter.add(egui::Shape::closed_line(corners, egui::Stroke::new(2.0, color)));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn ui_system(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    current_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut page_data: ResMut<ReviewPageData>,
+    auth_state: Res<AuthState>,
+    parameters: Option<Res<Parameters>>,
+    queue_tasks: Res<ApiTasks<ReviewQueue>>,
+    task_loads: Res<ApiTasks<ReviewedTask>>,
+    decision_tasks: Res<ApiTasks<ReviewDecision>>,
+) {
+    egui_common::ui_top_panel(&mut contexts, current_state, &mut next_state);
+    let page_data = &mut *page_data;
+
+    if parameters.is_none() {
+        egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(50.0);
+                ui.label(t!("review-no-project"));
+            });
+        });
+        return;
+    }
+
+    // A for approve, R for reject and the arrows through the queue, unless a text field has the keys
+    let ctx = contexts.ctx_mut();
+    if !ctx.wants_keyboard_input() {
+        let (approve, reject, previous, next) = ctx.input(|input| {
+            (
+                input.key_pressed(egui::Key::A),
+                input.key_pressed(egui::Key::R),
+                input.key_pressed(egui::Key::ArrowUp),
+                input.key_pressed(egui::Key::ArrowDown),
+            )
+        });
+        if approve || reject {
+            decide(page_data, &decision_tasks, &auth_state, parameters.as_deref(), approve);
+        }
+        if previous {
+            page_data.select(page_data.selected.saturating_sub(1));
+        }
+        if next {
+            page_data.select(page_data.selected + 1);
+        }
+    }
+
+    let mut refresh = false;
+    egui::SidePanel::left("review_queue")
+        .resizable(true)
+        .default_width(240.0)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.heading(t!("review-queue", count = page_data.tasks.len()));
+                if page_data.is_loading_queue {
+                    ui.add(egui::Spinner::new());
+                } else if ui.small_button("🔄").on_hover_text(t!("projects-refresh")).clicked() {
+                    refresh = true;
+                }
+            });
+            ui.separator();
+
+            if page_data.tasks.is_empty() && !page_data.is_loading_queue {
+                ui.weak(t!("review-queue-empty"));
+            }
+            let mut clicked = None;
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (index, task) in page_data.tasks.iter().enumerate() {
+                    let label = t!("review-queue-item", task = task.task.name.as_str(), count = task.annotation_count);
+                    if ui.selectable_label(index == page_data.selected, label).clicked() {
+                        clicked = Some(index);
+                    }
+                }
+            });
+            if let Some(index) = clicked {
+                page_data.select(index);
+            }
+        });
+    if refresh {
+        request_queue(page_data, &queue_tasks, &auth_state, parameters.as_deref());
+    }
+
+    load_selected_task(page_data, &task_loads, &auth_state, parameters.as_deref());
+
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        if let Some(error) = &page_data.error {
+            ui.colored_label(egui::Color32::RED, t!("common-error", error = error.as_str()));
+        }
+
+        let Some(task) = page_data.tasks.get(page_data.selected).cloned() else {
+            return;
+        };
+        ui.heading(&task.task.name);
+        ui.separator();
+
+        match page_data.shown.as_ref().filter(|shown| shown.task_id == task.task.id) {
+            Some(shown) => match (&shown.texture, &shown.image_error) {
+                (Some(texture), _) => {
+                    ui.columns(2, |columns| {
+                        render_save(&mut columns[0], t!("review-before"), texture, shown.image_size, &shown.before);
+                        render_save(&mut columns[1], t!("review-after"), texture, shown.image_size, &shown.after);
+                    });
+                }
+                (None, Some(error)) => {
+                    ui.colored_label(egui::Color32::RED, t!("review-image-failed", error = error.as_str()));
+                }
+                (None, None) => {
+                    ui.weak(t!("review-frames-in-editor"));
+                }
+            },
+            None if page_data.is_loading_task => {
+                ui.add(egui::Spinner::new());
+            }
+            None => {}
+        }
+
+        ui.separator();
+        ui.label(t!("review-comment"));
+        ui.add(
+            egui::TextEdit::multiline(&mut page_data.comment)
+                .hint_text(t!("review-comment-hint"))
+                .desired_rows(3)
+                .desired_width(f32::INFINITY),
+        );
+
+        let deciding = page_data.deciding.is_some();
+        let mut decision = None;
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!deciding, egui::Button::new(t!("review-approve"))).clicked() {
+                decision = Some(true);
+            }
+            if ui.add_enabled(!deciding, egui::Button::new(t!("review-reject"))).clicked() {
+                decision = Some(false);
+            }
+            if deciding {
+                ui.add(egui::Spinner::new());
+            }
+            if ui.button(t!("review-open-in-editor")).clicked() {
+                commands.insert_resource(detail::Parameters {
+                    url: task.resolved_resource_url.clone().unwrap_or_default(),
+                    task_id: Uuid::parse_str(&task.task.id).ok(),
+                    project_id: parameters.as_deref().and_then(|params| Uuid::parse_str(&params.project_id).ok()),
+                });
+                next_state.set(AppState::Detail);
+            }
+        });
+        ui.weak(t!("review-keys-hint"));
+
+        if let Some(approved) = decision {
+            decide(page_data, &decision_tasks, &auth_state, parameters.as_deref(), approved);
+        }
+    });
+}
+
+pub fn cleanup(
+    mut commands: Commands,
+    queue_tasks: Res<ApiTasks<ReviewQueue>>,
+    task_loads: Res<ApiTasks<ReviewedTask>>,
+) {
+    println!("review cleanup");
+    // Decisions change the server, so they are left to finish
+    queue_tasks.cancel_all();
+    task_loads.cancel_all();
+    commands.remove_resource::<ReviewPageData>();
+}
+
+pub struct ReviewPlugin;
+
+impl Plugin for ReviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+               ApiTaskPlugin::<ReviewQueue>::default(),
+               ApiTaskPlugin::<ReviewedTask>::default(),
+               ApiTaskPlugin::<ReviewDecision>::default(),
+           ))
+           .add_systems(OnEnter(AppState::Review), setup)
+           .add_systems(Update, (
+               process_queue_results,
+               process_task_results,
+           ).run_if(in_state(AppState::Review)))
+           // Decisions still on their way when the page closes report their outcome anyway
+           .add_systems(Update, process_decision_results)
+           .add_systems(
+               EguiContextPass,
+               ui_system.run_if(in_state(AppState::Review)),
+           )
+           .add_systems(OnExit(AppState::Review), cleanup);
+    }
+}
@@ -0,0 +1,351 @@
+use crate::ui::components::egui_common;
+use crate::app::state::AppState;
+use crate::auth::AuthState;
+use crate::api::stats::{AnnotationStats, StatsApi};
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiContextPass, egui};
+use egui_plot::{Bar, BarChart, Legend, Plot};
+
+const HEATMAP_SIZE: f32 = 300.0;
+const CHART_HEIGHT: f32 = 300.0;
+/// Height of a row of the category chart
+const CATEGORY_ROW_HEIGHT: f32 = 24.0;
+
+#[derive(Resource, Default)]
+pub struct Parameters {
+    pub project_id: String,
+}
+
+#[derive(Resource, Default)]
+pub struct StatsPageData {
+    pub stats: Option<AnnotationStats>,
+    pub error: Option<String>,
+    pub is_loading: bool,
+}
+
+fn request_stats(
+    page_data: &mut StatsPageData,
+    stats_tasks: &ApiTasks<AnnotationStats>,
+    auth_state: &AuthState,
+    parameters: Option<&Parameters>,
+) {
+    let (Some(jwt), Some(params)) = (auth_state.get_jwt(), parameters) else {
+        return;
+    };
+
+    page_data.is_loading = true;
+    page_data.error = None;
+    stats_tasks.cancel_all();
+    let (jwt, project_id) = (jwt.clone(), params.project_id.clone());
+    stats_tasks.spawn(async move {
+        StatsApi::new()
+            .get_annotation_stats(&jwt, &project_id)
+            .await
+            .map_err(|e| e.to_string())
+    });
+}
+
+pub fn setup(
+    mut commands: Commands,
+    auth_state: Res<AuthState>,
+    parameters: Option<Res<Parameters>>,
+    stats_tasks: Res<ApiTasks<AnnotationStats>>,
+) {
+    println!("stats setup");
+
+    let mut page_data = StatsPageData::default();
+    request_stats(&mut page_data, &stats_tasks, &auth_state, parameters.as_deref());
+    commands.insert_resource(page_data);
+}
+
+pub fn process_stats_results(
+    mut succeeded: EventReader<ApiTaskSucceeded<AnnotationStats>>,
+    mut failed: EventReader<ApiTaskFailed<AnnotationStats>>,
+    page_data: Option<ResMut<StatsPageData>>,
+) {
+    let Some(mut page_data) = page_data else {
+        return;
+    };
+
+    for ApiTaskSucceeded(stats) in succeeded.read() {
+        page_data.is_loading = false;
+        page_data.stats = Some(stats.clone());
+    }
+
+    for failure in failed.read() {
+        page_data.is_loading = false;
+        page_data.error = Some(failure.error.clone());
+    }
+}
+
+pub fn ui_system(
+    mut contexts: EguiContexts,
+    current_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut page_data: ResMut<StatsPageData>,
+    auth_state: Res<AuthState>,
+    parameters: Option<Res<Parameters>>,
+    stats_tasks: Res<ApiTasks<AnnotationStats>>,
+) {
+    egui_common::ui_top_panel(&mut contexts, current_state, &mut next_state);
+
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        ui.vertical_centered(|ui| {
+            ui.heading(t!("stats-title"));
+            ui.add_space(10.0);
+        });
+
+        if parameters.is_none() {
+            ui.vertical_centered(|ui| {
+                ui.add_space(50.0);
+                ui.label(t!("stats-no-project"));
+            });
+            return;
+        }
+
+        let mut refresh = false;
+        ui.horizontal(|ui| {
+            if let Some(stats) = &page_data.stats {
+                ui.label(t!("stats-summary", tasks = stats.annotated_tasks, boxes = stats.total_boxes));
+            }
+            if ui.add_enabled(!page_data.is_loading, egui::Button::new(t!("projects-refresh"))).clicked() {
+                refresh = true;
+            }
+            if page_data.is_loading {
+                ui.add(egui::Spinner::new());
+            }
+        });
+        if refresh {
+            request_stats(&mut page_data, &stats_tasks, &auth_state, parameters.as_deref());
+        }
+
+        if let Some(error) = &page_data.error {
+            ui.colored_label(egui::Color32::RED, t!("common-error", error = error.as_str()));
+        }
+        ui.separator();
+
+        let Some(stats) = &page_data.stats else {
+            return;
+        };
+        if stats.total_boxes == 0 {
+            ui.vertical_centered(|ui| {
+                ui.add_space(50.0);
+                ui.label(t!("stats-empty"));
+            });
+            return;
+        }
+        if stats.boxes_without_dimensions > 0 {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                t!("stats-without-dimensions", count = stats.boxes_without_dimensions),
+            );
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.horizontal_top(|ui| {
+                ui.vertical(|ui| {
+                    ui.strong(t!("stats-centers"));
+                    render_center_heatmap(ui, stats);
+                });
+                ui.add_space(20.0);
+                ui.vertical(|ui| {
+                    ui.strong(t!("stats-sizes"));
+                    render_size_histogram(ui, stats);
+                });
+            });
+            ui.add_space(15.0);
+
+            ui.strong(t!("stats-categories"));
+            render_category_chart(ui, stats);
+            ui.add_space(10.0);
+            render_category_table(ui, stats);
+        });
+    });
+}
+
+/// Hex color of a category, gray for categories without one
+fn category_color(color: Option<&str>) -> egui::Color32 {
+    color
+        .and_then(|color| u32::from_str_radix(color.trim_start_matches('#'), 16).ok())
+        .map(|hex| egui::Color32::from_rgb((hex >> 16) as u8, (hex >> 8) as u8, hex as u8))
+        .unwrap_or(egui::Color32::GRAY)
+}
+
+/// Box centers over the image, a cell per bin of the server. The square root of the count sets
+/// the color so that sparse areas still show next to a crowded one.
+fn render_center_heatmap(ui: &mut egui::Ui, stats: &AnnotationStats) {
+    let cells = stats.heatmap_cells.max(1) as usize;
+    let max = stats.center_heatmap.iter().copied().max().unwrap_or(0).max(1);
+
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(HEATMAP_SIZE, HEATMAP_SIZE), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let cell_size = HEATMAP_SIZE / cells as f32;
+
+    for (index, &count) in stats.center_heatmap.iter().enumerate() {
+        let (row, column) = (index / cells, index % cells);
+        let intensity = (count as f32 / max as f32).sqrt();
+        // From dark blue for empty cells to red for the most crowded one
+        let color: egui::Color32 = egui::ecolor::Hsva::new(0.66 * (1.0 - intensity), 0.8, 0.2 + 0.8 * intensity, 1.0).into();
+        let cell = egui::Rect::from_min_size(
+            rect.min + egui::vec2(column as f32 * cell_size, row as f32 * cell_size),
+            egui::vec2(cell_size, cell_size),
+        );
+        painter.rect_filled(cell, 0.0, color);
+    }
+    painter.rect_stroke(rect, 0.0, ui.visuals().widgets.noninteractive.bg_stroke, egui::StrokeKind::Inside);
+
+    // Count of the cell under the pointer
+    if let Some(pointer) = response.hover_pos() {
+        let column = (((pointer.x - rect.left()) / cell_size) as usize).min(cells - 1);
+        let row = (((pointer.y - rect.top()) / cell_size) as usize).min(cells - 1);
+        let count = stats.center_heatmap.get(row * cells + column).copied().unwrap_or(0);
+        let percent = |cell: usize| cell * 100 / cells;
+        response.on_hover_ui_at_pointer(|ui| {
+            ui.label(t!(
+                "stats-cell",
+                x = format!("{}–{}%", percent(column), percent(column + 1)),
+                y = format!("{}–{}%", percent(row), percent(row + 1)),
+            ));
+            ui.strong(t!("stats-boxes", count = count));
+        });
+    }
+}
+
+/// Box widths and heights relative to the image, side by side in each bin
+fn render_size_histogram(ui: &mut egui::Ui, stats: &AnnotationStats) {
+    let bin_width = 1.0 / stats.width_histogram.len().max(1) as f64;
+    let bars = |histogram: &[i64], offset: f64| -> Vec<Bar> {
+        histogram
+            .iter()
+            .enumerate()
+            .map(|(bin, &count)| {
+                Bar::new((bin as f64 + 0.5 + offset) * bin_width, count as f64).width(bin_width * 0.4)
+            })
+            .collect()
+    };
+    let widths = BarChart::new(bars(&stats.width_histogram, -0.2))
+        .name(t!("stats-width"))
+        .color(egui::Color32::from_rgb(100, 160, 255));
+    let heights = BarChart::new(bars(&stats.height_histogram, 0.2))
+        .name(t!("stats-height"))
+        .color(egui::Color32::from_rgb(255, 160, 80));
+
+    Plot::new("stats_size_histogram")
+        .height(CHART_HEIGHT)
+        .legend(Legend::default())
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .allow_boxed_zoom(false)
+        .include_x(0.0)
+        .include_x(1.0)
+        .include_y(0.0)
+        .x_axis_formatter(|mark, _range| format!("{:.0}%", mark.value * 100.0))
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(widths);
+            plot_ui.bar_chart(heights);
+        });
+}
+
+/// Boxes of every category, most used on top, in the color of the category
+fn render_category_chart(ui: &mut egui::Ui, stats: &AnnotationStats) {
+    let mut rows: Vec<(String, i64, egui::Color32)> = stats
+        .categories
+        .iter()
+        .map(|category| (category.name.clone(), category.boxes, category_color(category.color.as_deref())))
+        .collect();
+    if stats.uncategorized_boxes > 0 {
+        rows.push((t!("stats-uncategorized"), stats.uncategorized_boxes, egui::Color32::DARK_GRAY));
+    }
+
+    // Bars go up from 0, so the first row gets the highest position
+    let bars: Vec<Bar> = rows
+        .iter()
+        .enumerate()
+        .map(|(index, (name, boxes, color))| {
+            Bar::new((rows.len() - 1 - index) as f64, *boxes as f64)
+                .name(name)
+                .fill(*color)
+                .width(0.7)
+        })
+        .collect();
+    let names: Vec<String> = rows.iter().rev().map(|(name, _, _)| name.clone()).collect();
+
+    Plot::new("stats_categories")
+        .height((rows.len() as f32 * CATEGORY_ROW_HEIGHT).max(120.0))
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .allow_boxed_zoom(false)
+        .include_x(0.0)
+        .show_grid([true, false])
+        .y_axis_formatter(move |mark, _range| {
+            if mark.value.fract() != 0.0 || mark.value < 0.0 {
+                return String::new();
+            }
+            names.get(mark.value as usize).cloned().unwrap_or_default()
+        })
+        .show(ui, |plot_ui| plot_ui.bar_chart(BarChart::new(bars).horizontal()));
+}
+
+fn render_category_table(ui: &mut egui::Ui, stats: &AnnotationStats) {
+    let share = |boxes: i64| format!("{:.1}%", boxes as f64 * 100.0 / stats.total_boxes.max(1) as f64);
+
+    egui::Grid::new("stats_category_table")
+        .striped(true)
+        .num_columns(4)
+        .spacing([20.0, 6.0])
+        .show(ui, |ui| {
+            ui.strong(t!("stats-category"));
+            ui.strong(t!("reports-boxes"));
+            ui.strong(t!("stats-share"));
+            ui.strong(t!("reports-tasks"));
+            ui.end_row();
+
+            for category in &stats.categories {
+                ui.horizontal(|ui| {
+                    let (rect, _) = ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
+                    ui.painter().rect_filled(rect, 2.0, category_color(category.color.as_deref()));
+                    ui.label(&category.name);
+                });
+                ui.label(category.boxes.to_string());
+                ui.label(share(category.boxes));
+                if category.boxes == 0 {
+                    ui.colored_label(egui::Color32::YELLOW, t!("stats-unused"));
+                } else {
+                    ui.label(category.tasks.to_string());
+                }
+                ui.end_row();
+            }
+
+            if stats.uncategorized_boxes > 0 {
+                ui.weak(t!("stats-uncategorized"));
+                ui.label(stats.uncategorized_boxes.to_string());
+                ui.label(share(stats.uncategorized_boxes));
+                ui.weak("–");
+                ui.end_row();
+            }
+        });
+}
+
+pub fn cleanup(mut commands: Commands, stats_tasks: Res<ApiTasks<AnnotationStats>>) {
+    println!("stats cleanup");
+    stats_tasks.cancel_all();
+    commands.remove_resource::<StatsPageData>();
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ApiTaskPlugin::<AnnotationStats>::default())
+           .add_systems(OnEnter(AppState::Stats), setup)
+           .add_systems(Update, process_stats_results.run_if(in_state(AppState::Stats)))
+           .add_systems(
+               EguiContextPass,
+               ui_system.run_if(in_state(AppState::Stats)),
+           )
+           .add_systems(OnExit(AppState::Stats), cleanup);
+    }
+}
@@ -0,0 +1,422 @@
+use crate::ui::components::egui_common;
+use crate::app::state::AppState;
+use crate::auth::AuthState;
+use crate::api::storage::{StorageApi, StorageFolder};
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use crate::notifications::Notify;
+use crate::platform;
+use crate::sync::{SYNC_FILE_EXTENSIONS, SyncCompletedEvent, SyncErrorEvent, SyncRequest, SyncRequestEvent, SyncState};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiContextPass, egui};
+use uuid::Uuid;
+
+/// Longest side of a preview, larger images are scaled down before they become a texture
+const PREVIEW_SIZE: u32 = 1024;
+const PREVIEW_PANEL_WIDTH: f32 = 420.0;
+/// Files the preview can decode
+const PREVIEW_EXTENSIONS: [&str; 8] = ["jpg", "jpeg", "png", "gif", "bmp", "webp", "tif", "tiff"];
+
+#[derive(Resource, Default)]
+pub struct Parameters {
+    pub project_id: String,
+}
+
+/// A file of the folder, decoded for the preview
+#[derive(Clone)]
+pub struct ObjectPreview {
+    key: String,
+    image: egui::ColorImage,
+}
+
+#[derive(Resource, Default)]
+pub struct StorageBrowserPageData {
+    /// Folder shown, empty for the root of the bucket, otherwise ending with `/`
+    prefix: String,
+    folder: Option<StorageFolder>,
+    error: Option<String>,
+    is_loading: bool,
+    /// File picked in the folder
+    selected: Option<String>,
+    preview: Option<(String, egui::TextureHandle)>,
+    /// Loaded preview waiting to become a texture
+    pending_preview: Option<ObjectPreview>,
+    preview_error: Option<String>,
+    is_loading_preview: bool,
+    sync_skip_duplicates: bool,
+}
+
+fn is_previewable(key: &str) -> bool {
+    std::path::Path::new(key)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| PREVIEW_EXTENSIONS.iter().any(|allowed| allowed.eq_ignore_ascii_case(ext)))
+}
+
+/// Folders from the root down to `prefix`, each with the prefix that opens it
+fn breadcrumbs(prefix: &str) -> Vec<(&str, &str)> {
+    prefix
+        .match_indices('/')
+        .scan(0, |start, (end, _)| {
+            let crumb = (&prefix[*start..end], &prefix[..end + 1]);
+            *start = end + 1;
+            Some(crumb)
+        })
+        .collect()
+}
+
+/// Folder above `prefix`, the root for a folder of the root
+fn parent_prefix(prefix: &str) -> &str {
+    let trimmed = prefix.trim_end_matches('/');
+    trimmed.rfind('/').map_or("", |end| &prefix[..end + 1])
+}
+
+fn open_folder(
+    page_data: &mut StorageBrowserPageData,
+    folder_tasks: &ApiTasks<StorageFolder>,
+    auth_state: &AuthState,
+    parameters: Option<&Parameters>,
+    prefix: String,
+) {
+    let (Some(jwt), Some(params)) = (auth_state.get_jwt(), parameters) else {
+        return;
+    };
+
+    page_data.prefix = prefix.clone();
+    page_data.is_loading = true;
+    page_data.error = None;
+    // The folder that was being opened before is no longer wanted
+    folder_tasks.cancel_all();
+    let (jwt, project_id) = (jwt.clone(), params.project_id.clone());
+    folder_tasks.spawn(async move {
+        StorageApi::new()
+            .list_folder(&jwt, &project_id, &prefix)
+            .await
+            .map_err(|e| e.to_string())
+    });
+}
+
+fn request_preview(
+    page_data: &mut StorageBrowserPageData,
+    preview_tasks: &ApiTasks<ObjectPreview>,
+    auth_state: &AuthState,
+    parameters: Option<&Parameters>,
+    key: String,
+) {
+    page_data.selected = Some(key.clone());
+    page_data.preview_error = None;
+    if !is_previewable(&key) {
+        return;
+    }
+    let (Some(jwt), Some(params)) = (auth_state.get_jwt(), parameters) else {
+        return;
+    };
+
+    page_data.is_loading_preview = true;
+    preview_tasks.cancel_all();
+    let (jwt, project_id) = (jwt.clone(), params.project_id.clone());
+    preview_tasks.spawn(async move {
+        let bytes = StorageApi::new()
+            .download_object(&jwt, &project_id, &key)
+            .await
+            .map_err(|e| e.to_string())?;
+        let image = platform::unblock(move || {
+            let image = image::load_from_memory(&bytes).map_err(|e| e.to_string())?;
+            let rgba = image.thumbnail(PREVIEW_SIZE, PREVIEW_SIZE).to_rgba8();
+            Ok::<_, String>(egui::ColorImage::from_rgba_unmultiplied(
+                [rgba.width() as usize, rgba.height() as usize],
+                rgba.as_raw(),
+            ))
+        })
+        .await??;
+        Ok(ObjectPreview { key, image })
+    });
+}
+
+pub fn setup(
+    mut commands: Commands,
+    auth_state: Res<AuthState>,
+    parameters: Option<Res<Parameters>>,
+    folder_tasks: Res<ApiTasks<StorageFolder>>,
+) {
+    println!("storage browser setup");
+
+    let mut page_data = StorageBrowserPageData::default();
+    open_folder(&mut page_data, &folder_tasks, &auth_state, parameters.as_deref(), String::new());
+    commands.insert_resource(page_data);
+}
+
+pub fn process_browser_results(
+    mut folders: EventReader<ApiTaskSucceeded<StorageFolder>>,
+    mut folder_failed: EventReader<ApiTaskFailed<StorageFolder>>,
+    mut previews: EventReader<ApiTaskSucceeded<ObjectPreview>>,
+    mut preview_failed: EventReader<ApiTaskFailed<ObjectPreview>>,
+    page_data: Option<ResMut<StorageBrowserPageData>>,
+) {
+    let Some(mut page_data) = page_data else {
+        return;
+    };
+
+    for ApiTaskSucceeded(folder) in folders.read() {
+        page_data.is_loading = false;
+        page_data.folder = Some(folder.clone());
+    }
+    for failure in folder_failed.read() {
+        page_data.is_loading = false;
+        page_data.folder = None;
+        page_data.error = Some(failure.error.clone());
+    }
+
+    for ApiTaskSucceeded(preview) in previews.read() {
+        page_data.is_loading_preview = false;
+        page_data.pending_preview = Some(preview.clone());
+    }
+    for failure in preview_failed.read() {
+        page_data.is_loading_preview = false;
+        page_data.preview_error = Some(failure.error.clone());
+    }
+}
+
+/// Tells how a sync started from the browser went, and shows the new state of the folder
+pub fn handle_sync_events(
+    mut notify: EventWriter<Notify>,
+    mut sync_completed_events: EventReader<SyncCompletedEvent>,
+    mut sync_error_events: EventReader<SyncErrorEvent>,
+    page_data: Option<ResMut<StorageBrowserPageData>>,
+    auth_state: Res<AuthState>,
+    parameters: Option<Res<Parameters>>,
+    folder_tasks: Res<ApiTasks<StorageFolder>>,
+) {
+    let mut finished = false;
+    for event in sync_completed_events.read() {
+        finished = true;
+        notify.write(Notify::success(t!(
+            "settings-sync-done",
+            created = event.response.tasks_created,
+            skipped = event.response.tasks_skipped,
+        )));
+        if !event.response.errors.is_empty() {
+            notify.write(Notify::error(t!(
+                "settings-sync-errors",
+                count = event.response.errors.len(),
+                errors = event.response.errors.join(", "),
+            )));
+        }
+    }
+    for event in sync_error_events.read() {
+        finished = true;
+        notify.write(Notify::error(t!("settings-sync-failed", error = event.error.as_str())));
+    }
+
+    // Videos got their frames written next to them
+    if let (true, Some(mut page_data)) = (finished, page_data) {
+        let prefix = page_data.prefix.clone();
+        open_folder(&mut page_data, &folder_tasks, &auth_state, parameters.as_deref(), prefix);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn ui_system(
+    mut contexts: EguiContexts,
+    current_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut page_data: ResMut<StorageBrowserPageData>,
+    auth_state: Res<AuthState>,
+    parameters: Option<Res<Parameters>>,
+    sync_state: Res<SyncState>,
+    folder_tasks: Res<ApiTasks<StorageFolder>>,
+    preview_tasks: Res<ApiTasks<ObjectPreview>>,
+    mut sync_request_events: EventWriter<SyncRequestEvent>,
+    mut notify: EventWriter<Notify>,
+) {
+    egui_common::ui_top_panel(&mut contexts, current_state, &mut next_state);
+
+    if let Some(preview) = page_data.pending_preview.take() {
+        let texture = contexts.ctx_mut().load_texture("storage_preview", preview.image, egui::TextureOptions::LINEAR);
+        page_data.preview = Some((preview.key, texture));
+    }
+
+    let Some(parameters) = parameters else {
+        egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(50.0);
+                ui.label(t!("storage-no-project"));
+            });
+        });
+        return;
+    };
+
+    let mut open_prefix = None;
+    let mut select_key = None;
+
+    egui::SidePanel::right("storage_preview_panel")
+        .default_width(PREVIEW_PANEL_WIDTH)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.strong(t!("storage-preview"));
+            ui.separator();
+            let Some(selected) = page_data.selected.clone() else {
+                ui.weak(t!("storage-preview-hint"));
+                return;
+            };
+            ui.label(&selected);
+            if !is_previewable(&selected) {
+                ui.weak(t!("storage-preview-unsupported"));
+            } else if page_data.is_loading_preview {
+                ui.add(egui::Spinner::new());
+            } else if let Some(error) = &page_data.preview_error {
+                ui.colored_label(egui::Color32::RED, t!("common-error", error = error.as_str()));
+            } else if let Some((_, texture)) = page_data.preview.as_ref().filter(|(key, _)| *key == selected) {
+                let size = texture.size_vec2();
+                let scale = (ui.available_width() / size.x).min(1.0);
+                ui.image((texture.id(), size * scale));
+            }
+        });
+
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        ui.vertical_centered(|ui| {
+            ui.heading(t!("storage-title"));
+            ui.add_space(10.0);
+        });
+
+        // Where in the bucket the folder is, every part of the path opens its folder
+        ui.horizontal_wrapped(|ui| {
+            if ui.selectable_label(page_data.prefix.is_empty(), t!("storage-root")).clicked() {
+                open_prefix = Some(String::new());
+            }
+            for (name, prefix) in breadcrumbs(&page_data.prefix) {
+                ui.label("/");
+                if ui.selectable_label(prefix == page_data.prefix, name).clicked() {
+                    open_prefix = Some(prefix.to_string());
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!page_data.prefix.is_empty(), egui::Button::new(t!("storage-up"))).clicked() {
+                open_prefix = Some(parent_prefix(&page_data.prefix).to_string());
+            }
+            if ui.add_enabled(!page_data.is_loading, egui::Button::new(t!("projects-refresh"))).clicked() {
+                open_prefix = Some(page_data.prefix.clone());
+            }
+            if page_data.is_loading {
+                ui.add(egui::Spinner::new());
+            }
+        });
+
+        ui.add_space(5.0);
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                let folder_name = if page_data.prefix.is_empty() {
+                    t!("storage-root")
+                } else {
+                    page_data.prefix.clone()
+                };
+                ui.label(t!("storage-sync-folder-description", folder = folder_name.as_str()));
+            });
+            ui.checkbox(&mut page_data.sync_skip_duplicates, t!("settings-sync-skip-duplicates"));
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!sync_state.is_syncing, egui::Button::new(t!("storage-sync-folder"))).clicked() {
+                    match (Uuid::parse_str(&parameters.project_id), auth_state.get_jwt()) {
+                        (Ok(project_id), Some(jwt)) => {
+                            sync_request_events.write(SyncRequestEvent {
+                                project_id,
+                                request: SyncRequest {
+                                    prefix: (!page_data.prefix.is_empty()).then(|| page_data.prefix.clone()),
+                                    file_extensions: Some(SYNC_FILE_EXTENSIONS.iter().map(|ext| ext.to_string()).collect()),
+                                    overwrite_existing: Some(false),
+                                    skip_duplicates: Some(page_data.sync_skip_duplicates),
+                                },
+                                token: jwt.clone(),
+                            });
+                            notify.write(Notify::info(t!("settings-sync-starting")));
+                        }
+                        (Err(_), _) => notify.write(Notify::error(t!("common-invalid-project-id"))),
+                        (_, None) => notify.write(Notify::error(t!("common-not-authenticated"))),
+                    }
+                }
+                if sync_state.is_syncing {
+                    ui.add(egui::Spinner::new());
+                    ui.label(t!("settings-syncing"));
+                    if let Some(progress) = &sync_state.progress {
+                        ui.label(t!(
+                            "settings-sync-progress",
+                            processed = progress.processed_files,
+                            total = progress.total_files,
+                        ));
+                    }
+                }
+            });
+        });
+
+        if let Some(error) = &page_data.error {
+            ui.colored_label(egui::Color32::RED, t!("common-error", error = error.as_str()));
+        }
+        ui.separator();
+
+        let Some(folder) = &page_data.folder else {
+            return;
+        };
+        if folder.prefixes.is_empty() && folder.objects.is_empty() {
+            ui.vertical_centered(|ui| {
+                ui.add_space(30.0);
+                ui.weak(t!("storage-empty"));
+            });
+            return;
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for prefix in &folder.prefixes {
+                let name = prefix.strip_prefix(page_data.prefix.as_str()).unwrap_or(prefix);
+                if ui.selectable_label(false, format!("📁 {}", name)).clicked() {
+                    open_prefix = Some(prefix.clone());
+                }
+            }
+            for key in &folder.objects {
+                let name = key.strip_prefix(page_data.prefix.as_str()).unwrap_or(key);
+                let icon = if is_previewable(key) { "🖼" } else { "📄" };
+                let selected = page_data.selected.as_deref() == Some(key.as_str());
+                if ui.selectable_label(selected, format!("{} {}", icon, name)).clicked() {
+                    select_key = Some(key.clone());
+                }
+            }
+        });
+    });
+
+    if let Some(prefix) = open_prefix {
+        open_folder(&mut page_data, &folder_tasks, &auth_state, Some(&*parameters), prefix);
+    }
+    if let Some(key) = select_key {
+        request_preview(&mut page_data, &preview_tasks, &auth_state, Some(&*parameters), key);
+    }
+}
+
+pub fn cleanup(
+    mut commands: Commands,
+    folder_tasks: Res<ApiTasks<StorageFolder>>,
+    preview_tasks: Res<ApiTasks<ObjectPreview>>,
+) {
+    println!("storage browser cleanup");
+    folder_tasks.cancel_all();
+    preview_tasks.cancel_all();
+    commands.remove_resource::<StorageBrowserPageData>();
+}
+
+pub struct StorageBrowserPlugin;
+
+impl Plugin for StorageBrowserPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+               ApiTaskPlugin::<StorageFolder>::default(),
+               ApiTaskPlugin::<ObjectPreview>::default(),
+           ))
+           .add_systems(OnEnter(AppState::StorageBrowser), setup)
+           .add_systems(Update, (
+               process_browser_results,
+               handle_sync_events,
+           ).run_if(in_state(AppState::StorageBrowser)))
+           .add_systems(
+               EguiContextPass,
+               ui_system.run_if(in_state(AppState::StorageBrowser)),
+           )
+           .add_systems(OnExit(AppState::StorageBrowser), cleanup);
+    }
+}
@@ -1,10 +1,23 @@
 use crate::ui::components::egui_common;
 use crate::app::state::AppState;
 use crate::auth::{AuthState, TaskWithResolvedUrl};
-use crate::api::tasks::TasksApi;
+use crate::api::{ApiError, ApiResult};
+use crate::api::categories::{AnnotationCategory, CategoriesApi};
+use crate::api::projects::{ProjectMember, ProjectsApi};
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use crate::api::tasks::{TaskFilter, TasksApi, FLAG_REASONS, SPLITS, flag_reason_label, split_label};
+use crate::i18n;
+use crate::io::image_cache::{ImageCache, PREFETCH_AHEAD};
+use crate::io::offline_store;
+use crate::notifications::Notify;
+use crate::platform;
 use bevy::prelude::*;
 use bevy::ui::Interaction;
 use bevy_egui::{EguiContexts, EguiContextPass, egui};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
 use uuid;
 
 use super::detail;
@@ -25,8 +38,154 @@ pub struct TasksPageData {
     pub create_error: Option<String>,
     #[allow(dead_code)]
     pub is_creating: bool,
+    /// Filters of the list
+    pub filter: TaskFilter,
+    /// Categories of the project, for the category filter
+    pub categories: Vec<AnnotationCategory>,
+    /// IDs of the tasks picked for bulk operations
+    pub selected: HashSet<String>,
+    /// Members of the project, who the selected tasks can be assigned to
+    pub members: Vec<ProjectMember>,
+    /// User IDs ticked in the assign menu
+    pub batch_assignees: HashSet<String>,
+    pub confirm_batch_delete: bool,
+    pub is_applying_batch: bool,
+    /// Day typed in the due date menu, `YYYY-MM-DD`
+    pub batch_due_date: String,
+    /// The paste button was clicked, handled like Ctrl+V
+    pub paste_requested: bool,
+    pub is_pasting: bool,
+    /// A random unannotated task is being looked for, to annotate next
+    pub is_starting_annotation: bool,
 }
 
+const TASK_STATUSES: [&str; 4] = ["pending", "in_progress", "completed", "cancelled"];
+
+/// Change applied to every selected task at once
+#[derive(Debug, Clone)]
+pub enum BatchOperation {
+    Delete,
+    SetStatus(&'static str),
+    /// Replaces the assignees, an empty list unassigns the tasks
+    Assign(Vec<String>),
+    /// Puts the tasks in a split, or takes them out of theirs with `None`
+    SetSplit(Option<&'static str>),
+    /// Makes the tasks due at the given time, or leaves them to the deadline settings with `None`
+    SetDue(Option<DateTime<Utc>>),
+    /// Compares the files in storage with the ones the tasks were made from
+    CheckSources,
+}
+
+impl BatchOperation {
+    fn describe(&self, affected: u64) -> String {
+        match self {
+            BatchOperation::Delete => t!("tasks-batch-deleted", count = affected),
+            BatchOperation::SetStatus(status) => t!("tasks-batch-status-set", count = affected, status = format_status(status)),
+            BatchOperation::Assign(user_ids) if user_ids.is_empty() => t!("tasks-batch-unassigned", count = affected),
+            BatchOperation::Assign(user_ids) => t!("tasks-batch-assigned", count = affected, annotators = user_ids.len()),
+            BatchOperation::SetSplit(Some(split)) => t!("tasks-batch-split-set", count = affected, split = split_name(split)),
+            BatchOperation::SetSplit(None) => t!("tasks-batch-split-removed", count = affected),
+            BatchOperation::SetDue(Some(due_at)) => t!("tasks-batch-due-set", count = affected, date = format_due_date(due_at)),
+            BatchOperation::SetDue(None) => t!("tasks-batch-due-cleared", count = affected),
+            BatchOperation::CheckSources => t!("tasks-batch-sources-checked", count = affected),
+        }
+    }
+}
+
+pub struct BatchResult {
+    operation: BatchOperation,
+    affected: u64,
+}
+
+async fn apply_batch(jwt: String, project_id: String, task_ids: Vec<String>, operation: BatchOperation) -> Result<BatchResult, String> {
+    let tasks_api = TasksApi::new();
+    let affected = match &operation {
+        BatchOperation::Delete => tasks_api.batch_delete(&jwt, &project_id, &task_ids).await,
+        BatchOperation::SetStatus(status) => tasks_api.batch_set_status(&jwt, &project_id, &task_ids, status).await,
+        BatchOperation::Assign(user_ids) => tasks_api.batch_assign(&jwt, &project_id, &task_ids, user_ids).await,
+        BatchOperation::SetSplit(split) => tasks_api.batch_set_split(&jwt, &project_id, &task_ids, *split).await,
+        BatchOperation::SetDue(due_at) => tasks_api.batch_set_due(&jwt, &project_id, &task_ids, *due_at).await,
+        BatchOperation::CheckSources => check_sources(&tasks_api, &jwt, &project_id, &task_ids).await,
+    }
+    .map_err(|e| e.to_string())?;
+    Ok(BatchResult { operation, affected })
+}
+
+/// Refreshes the tasks one by one and counts the ones whose file changed. Tasks with no file in
+/// the project storage are passed over.
+async fn check_sources(tasks_api: &TasksApi, jwt: &str, project_id: &str, task_ids: &[String]) -> ApiResult<u64> {
+    let mut changed = 0;
+    for task_id in task_ids {
+        match tasks_api.refresh_task(jwt, project_id, task_id, false).await {
+            Ok(response) if response.changed => changed += 1,
+            Ok(_) | Err(ApiError::BadRequest(_)) => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(changed)
+}
+
+/// Border and due date of the cards of tasks past due
+const OVERDUE_COLOR: egui::Color32 = egui::Color32::from_rgb(220, 60, 60);
+
+/// Thumbnails downloaded at the same time, more wait until they are scrolled to again
+const MAX_THUMBNAIL_REQUESTS: usize = 6;
+/// Size of a card in the task grid, in points
+const CARD_WIDTH: f32 = 200.0;
+const CARD_HEIGHT: f32 = 250.0;
+const THUMBNAIL_HEIGHT: f32 = 140.0;
+
+pub enum Thumbnail {
+    Loading,
+    Loaded(egui::TextureHandle),
+    /// No thumbnail for this task, such as images outside of the project storage
+    Unavailable,
+}
+
+/// Thumbnails of the task grid by task ID, requested as their cards scroll into view and kept
+/// for the rest of the session
+#[derive(Resource, Default)]
+pub struct ThumbnailState {
+    thumbnails: HashMap<String, Thumbnail>,
+    loading: usize,
+}
+
+pub struct LoadedThumbnail {
+    task_id: String,
+    image: Result<egui::ColorImage, String>,
+}
+
+impl ThumbnailState {
+    /// Starts downloading the thumbnail of a task unless it is known or too many are on the way
+    fn request(&mut self, tasks: &ApiTasks<LoadedThumbnail>, jwt: &str, project_id: &str, task_id: &str) {
+        if self.thumbnails.contains_key(task_id) || self.loading >= MAX_THUMBNAIL_REQUESTS {
+            return;
+        }
+        self.thumbnails.insert(task_id.to_string(), Thumbnail::Loading);
+        self.loading += 1;
+
+        let (jwt, project_id, task_id) = (jwt.to_string(), project_id.to_string(), task_id.to_string());
+        tasks.spawn(async move {
+            let image = match TasksApi::new().get_thumbnail(&jwt, &project_id, &task_id).await {
+                Ok(bytes) => image::load_from_memory(&bytes)
+                    .map(|image| {
+                        let rgba = image.to_rgba8();
+                        egui::ColorImage::from_rgba_unmultiplied([rgba.width() as usize, rgba.height() as usize], rgba.as_raw())
+                    })
+                    .map_err(|e| e.to_string()),
+                Err(error) => Err(error.to_string()),
+            };
+            Ok(LoadedThumbnail { task_id, image })
+        });
+    }
+}
+
+/// Task list as the server (or the offline copy) returned it
+pub struct LoadedTasks(Vec<TaskWithResolvedUrl>);
+
+/// Random unannotated task of the project, `None` once every task is annotated
+pub struct RandomTask(Option<TaskWithResolvedUrl>);
+
 #[derive(Resource, Default)]
 pub struct TasksState {
     pub tasks: Vec<TaskWithResolvedUrl>,
@@ -70,29 +229,33 @@ pub fn setup(
     auth_state: Res<AuthState>,
     mut tasks_state: ResMut<TasksState>,
     parameters: Option<Res<Parameters>>,
+    task_loads: Res<ApiTasks<LoadedTasks>>,
+    member_tasks: Res<ApiTasks<Vec<ProjectMember>>>,
+    category_tasks: Res<ApiTasks<Vec<AnnotationCategory>>>,
 ) {
     println!("tasks setup");
     
     commands.init_resource::<TasksPageData>();
+
+    // Members are only needed by the assign menu and categories by the category filter, so they
+    // can arrive after the list
+    if let (Some(jwt), Some(params)) = (auth_state.get_jwt(), &parameters) {
+        let (jwt, project_id) = (jwt.clone(), params.project_id.clone());
+        let (categories_jwt, categories_project_id) = (jwt.clone(), project_id.clone());
+        member_tasks.spawn(async move {
+            ProjectsApi::new().list_members(&jwt, &project_id).await.map_err(|e| e.to_string())
+        });
+        category_tasks.spawn(async move {
+            let project_id = uuid::Uuid::parse_str(&categories_project_id).map_err(|e| e.to_string())?;
+            CategoriesApi::new().list_categories(&categories_jwt, project_id).await.map_err(|e| e.to_string())
+        });
+    }
     
     // Fetch tasks if authenticated and we have a project ID
     if let Some(params) = parameters {
-        if auth_state.is_authenticated() && !tasks_state.is_fetching {
+        if auth_state.is_authenticated() {
             if let Some(jwt) = auth_state.get_jwt() {
-                let jwt = jwt.clone();
-                let project_id = params.project_id.clone();
-                tasks_state.start_fetching();
-                
-                let tasks_api = TasksApi::new();
-                let rt = tokio::runtime::Runtime::new().unwrap();
-                match rt.block_on(tasks_api.list_tasks(&jwt, &project_id)) {
-                    Ok(tasks) => {
-                        tasks_state.set_tasks(tasks);
-                    }
-                    Err(error) => {
-                        tasks_state.set_error(error.to_string());
-                    }
-                }
+                reload_tasks(&mut tasks_state, &task_loads, &TaskFilter::default(), jwt, &params.project_id);
             }
         }
     }
@@ -120,90 +283,128 @@ pub fn ui_system(
     mut page_data: ResMut<TasksPageData>,
     auth_state: Res<AuthState>,
     parameters: Option<Res<Parameters>>,
+    mut thumbnails: ResMut<ThumbnailState>,
+    thumbnail_tasks: Res<ApiTasks<LoadedThumbnail>>,
+    batch_tasks: Res<ApiTasks<BatchResult>>,
+    task_loads: Res<ApiTasks<LoadedTasks>>,
+    random_tasks: Res<ApiTasks<RandomTask>>,
 ) {
     egui_common::ui_top_panel(&mut contexts, current_state, &mut next_state);
 
     egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
         ui.vertical_centered(|ui| {
-            ui.heading("Tasks");
+            ui.heading(t!("tasks-title"));
             ui.add_space(10.0);
         });
 
         // Create new task button, start annotation button, and refresh
         ui.horizontal(|ui| {
-            if ui.button("➕ New Task").clicked() {
+            if ui.button(t!("tasks-new")).clicked() {
                 page_data.show_create_dialog = true;
             }
 
-            if ui.button("🎯 Start Annotation").clicked() && !tasks_state.is_fetching {
+            let paste_shortcut = if cfg!(target_os = "macos") { "Cmd+V" } else { "Ctrl+V" };
+            if ui.add_enabled(!page_data.is_pasting, egui::Button::new(t!("tasks-paste-image")))
+                .on_hover_text(t!("tasks-paste-image-hint", shortcut = paste_shortcut))
+                .clicked()
+            {
+                page_data.paste_requested = true;
+            }
+
+            let can_start = !tasks_state.is_fetching && !page_data.is_starting_annotation;
+            if ui.add_enabled(can_start, egui::Button::new(t!("tasks-start-annotation"))).clicked() {
                 if let (Some(jwt), Some(params)) = (auth_state.get_jwt(), &parameters) {
-                    let jwt = jwt.clone();
-                    let project_id = params.project_id.clone();
-                    
-                    let tasks_api = TasksApi::new();
-                    let rt = tokio::runtime::Runtime::new().unwrap();
-                    match rt.block_on(tasks_api.get_next_random_unannotated_task(&jwt, &project_id)) {
-                        Ok(Some(task_with_url)) => {
-                            println!("Found random unannotated task: {}", task_with_url.task.name);
-                            
-                            // Use resolved_resource_url if available, fallback to original resource_url
-                            let url_to_use = task_with_url.resolved_resource_url.as_ref()
-                                .or(task_with_url.task.resource_url.as_ref());
-                            
-                            if let Some(url) = url_to_use {
-                                let url = url.clone();
-                                
-                                // Validate URL
-                                if !url.is_empty() {
-                                    // Parse project_id and task_id
-                                    let project_id = uuid::Uuid::parse_str(&params.project_id).ok();
-                                    let task_id = uuid::Uuid::parse_str(&task_with_url.task.id).ok();
-                                    
-                                    // Set parameters for Detail page and navigate
-                                    commands.insert_resource(detail::Parameters {
-                                        url,
-                                        task_id,
-                                        project_id,
-                                    });
-                                    next_state.set(AppState::Detail);
-                                } else {
-                                    tasks_state.set_error("Task has no valid resource URL".to_string());
-                                }
-                            } else {
-                                tasks_state.set_error("Task has no resource URL".to_string());
-                            }
-                        }
-                        Ok(None) => {
-                            tasks_state.set_error("No unannotated tasks available".to_string());
-                        }
-                        Err(error) => {
-                            tasks_state.set_error(format!("Failed to fetch next task: {}", error));
-                        }
-                    }
+                    let (jwt, project_id) = (jwt.clone(), params.project_id.clone());
+                    page_data.is_starting_annotation = true;
+                    random_tasks.spawn(async move {
+                        TasksApi::new().get_next_random_unannotated_task(&jwt, &project_id).await
+                            .map(RandomTask)
+                            .map_err(|error| t!("tasks-next-failed", error = error.to_string()))
+                    });
                 }
             }
             
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                if ui.button("🔄 Refresh").clicked() && !tasks_state.is_fetching {
-                    if let (Some(jwt), Some(params)) = (auth_state.get_jwt(), &parameters) {
-                        let jwt = jwt.clone();
-                        let project_id = params.project_id.clone();
-                        tasks_state.start_fetching();
-                        
-                        let tasks_api = TasksApi::new();
-                        let rt = tokio::runtime::Runtime::new().unwrap();
-                        match rt.block_on(tasks_api.list_tasks(&jwt, &project_id)) {
-                            Ok(tasks) => {
-                                tasks_state.set_tasks(tasks);
-                            }
-                            Err(error) => {
-                                tasks_state.set_error(error.to_string());
-                            }
+                let previous_filter = page_data.filter.clone();
+                let page_data = &mut *page_data;
+                let filter_label = match page_data.filter.flag.as_deref() {
+                    None => t!("tasks-filter-all"),
+                    Some("any") => t!("tasks-filter-flagged"),
+                    Some("none") => t!("tasks-filter-not-flagged"),
+                    Some(reason) => flag_reason_name(reason),
+                };
+                egui::ComboBox::from_id_salt("flag_filter")
+                    .selected_text(filter_label)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut page_data.filter.flag, None, t!("tasks-filter-all"));
+                        ui.selectable_value(&mut page_data.filter.flag, Some("any".to_string()), t!("tasks-filter-flagged"));
+                        ui.selectable_value(&mut page_data.filter.flag, Some("none".to_string()), t!("tasks-filter-not-flagged"));
+                        for (code, _) in FLAG_REASONS {
+                            ui.selectable_value(&mut page_data.filter.flag, Some(code.to_string()), flag_reason_name(code));
                         }
+                    });
+
+                let annotation_label = match page_data.filter.has_annotations {
+                    None => t!("tasks-filter-any-annotation"),
+                    Some(true) => t!("tasks-filter-annotated"),
+                    Some(false) => t!("tasks-filter-unannotated"),
+                };
+                egui::ComboBox::from_id_salt("annotation_filter")
+                    .selected_text(annotation_label)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut page_data.filter.has_annotations, None, t!("tasks-filter-any-annotation"));
+                        ui.selectable_value(&mut page_data.filter.has_annotations, Some(true), t!("tasks-filter-annotated"));
+                        ui.selectable_value(&mut page_data.filter.has_annotations, Some(false), t!("tasks-filter-unannotated"));
+                    });
+
+                let category_label = page_data.filter.contains_category
+                    .and_then(|id| page_data.categories.iter().find(|category| category.id == id))
+                    .map(|category| t!("tasks-filter-category", category = category.name.as_str()))
+                    .unwrap_or_else(|| t!("tasks-filter-any-category"));
+                egui::ComboBox::from_id_salt("category_filter")
+                    .selected_text(category_label)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut page_data.filter.contains_category, None, t!("tasks-filter-any-category"));
+                        for category in &page_data.categories {
+                            ui.selectable_value(&mut page_data.filter.contains_category, Some(category.id), &category.name);
+                        }
+                    });
+
+                let source_label = match page_data.filter.stale {
+                    None => t!("tasks-filter-any-source"),
+                    Some(true) => t!("tasks-filter-stale"),
+                    Some(false) => t!("tasks-filter-not-stale"),
+                };
+                egui::ComboBox::from_id_salt("stale_filter")
+                    .selected_text(source_label)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut page_data.filter.stale, None, t!("tasks-filter-any-source"));
+                        ui.selectable_value(&mut page_data.filter.stale, Some(true), t!("tasks-filter-stale"));
+                        ui.selectable_value(&mut page_data.filter.stale, Some(false), t!("tasks-filter-not-stale"));
+                    });
+
+                let due_label = match page_data.filter.overdue {
+                    None => t!("tasks-filter-any-due"),
+                    Some(true) => t!("tasks-filter-overdue"),
+                    Some(false) => t!("tasks-filter-in-time"),
+                };
+                egui::ComboBox::from_id_salt("overdue_filter")
+                    .selected_text(due_label)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut page_data.filter.overdue, None, t!("tasks-filter-any-due"));
+                        ui.selectable_value(&mut page_data.filter.overdue, Some(true), t!("tasks-filter-overdue"));
+                        ui.selectable_value(&mut page_data.filter.overdue, Some(false), t!("tasks-filter-in-time"));
+                    });
+                let filter_changed = page_data.filter != previous_filter;
+
+                // A changed filter replaces the list still on its way
+                if (ui.button(t!("projects-refresh")).clicked() && !tasks_state.is_fetching) || filter_changed {
+                    if let (Some(jwt), Some(params)) = (auth_state.get_jwt(), &parameters) {
+                        reload_tasks(&mut tasks_state, &task_loads, &page_data.filter, jwt, &params.project_id);
                     }
                 }
                 
-                if ui.button("← Back to Projects").clicked() {
+                if ui.button(t!("tasks-back-to-projects")).clicked() {
                     next_state.set(AppState::Projects);
                 }
             });
@@ -215,103 +416,620 @@ pub fn ui_system(
         if tasks_state.is_fetching {
             ui.vertical_centered(|ui| {
                 ui.add(egui::Spinner::new());
-                ui.label("Loading tasks...");
+                ui.label(t!("tasks-loading"));
             });
             return;
         }
 
         // Show error state
         if let Some(error) = &tasks_state.fetch_error {
-            ui.colored_label(egui::Color32::RED, format!("Error: {}", error));
+            ui.colored_label(egui::Color32::RED, t!("common-error", error = error.as_str()));
             ui.separator();
         }
 
-        // Tasks list
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            if tasks_state.tasks.is_empty() {
-                ui.vertical_centered(|ui| {
-                    ui.add_space(50.0);
-                    ui.label("No tasks found");
-                    ui.label("Create your first task to get started!");
-                });
-            } else {
-                for task_with_url in &tasks_state.tasks {
-                    ui.group(|ui| {
+        // Task grid, only the rows in view are drawn and fetch their thumbnails
+        if tasks_state.tasks.is_empty() {
+            ui.vertical_centered(|ui| {
+                ui.add_space(50.0);
+                ui.label(t!("tasks-empty"));
+                ui.label(t!("tasks-empty-hint"));
+            });
+        } else {
+            let mut operation = None;
+            ui.horizontal(|ui| {
+                ui.label(t!("tasks-selected", count = page_data.selected.len()));
+                if ui.small_button(t!("tasks-select-all")).clicked() {
+                    page_data.selected = tasks_state.tasks.iter().map(|task_with_url| task_with_url.task.id.clone()).collect();
+                }
+                if ui.add_enabled(!page_data.selected.is_empty(), egui::Button::new(t!("tasks-clear-selection")).small()).clicked() {
+                    page_data.selected.clear();
+                }
+
+                if !page_data.selected.is_empty() {
+                    ui.separator();
+                    let enabled = !page_data.is_applying_batch;
+                    ui.add_enabled_ui(enabled, |ui| {
+                        operation = render_batch_actions(ui, &mut page_data);
+                    });
+                    if page_data.is_applying_batch {
+                        ui.add(egui::Spinner::new());
+                    }
+                }
+            });
+            ui.add_space(5.0);
+
+            if page_data.confirm_batch_delete {
+                let mut open = true;
+                egui::Window::new(t!("tasks-delete-title"))
+                    .collapsible(false)
+                    .resizable(false)
+                    .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                    .open(&mut open)
+                    .show(ui.ctx(), |ui| {
+                        ui.label(t!("tasks-delete-confirm", count = page_data.selected.len()));
                         ui.horizontal(|ui| {
-                            ui.vertical(|ui| {
-                                ui.strong(&task_with_url.task.name);
-                                ui.label(format!("Status: {}", format_status(&task_with_url.task.status)));
-                                if let Some(url) = &task_with_url.task.resource_url {
-                                    ui.weak(format!("Resource: {}", url));
-                                }
-                                ui.weak(format!("Created: {}", format_date(&task_with_url.task.created_at)));
-                            });
-                            
-                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                // Use resolved_resource_url if available, fallback to original resource_url
-                                let url_to_use = task_with_url.resolved_resource_url.as_ref()
-                                    .or(task_with_url.task.resource_url.as_ref());
-                                
-                                if ui.button("Open").clicked() && url_to_use.is_some() {
-                                    let url = url_to_use.cloned().unwrap_or_default();
-                                    println!("Opening task: {}", task_with_url.task.name);
-                                    println!("Task original resource URL: '{:?}'", task_with_url.task.resource_url);
-                                    println!("Task resolved resource URL: '{:?}'", task_with_url.resolved_resource_url);
-                                    println!("Using URL: '{}'", url);
-                                    
-                                    // Validate URL before transitioning
-                                    if url.is_empty() {
-                                        eprintln!("Error: Task resource URL is empty for task '{}'", task_with_url.task.name);
-                                    } else if !url.starts_with("http://") && !url.starts_with("https://") && !url.starts_with("file://") {
-                                        eprintln!("Warning: Task resource URL might not be a valid URL: '{}'", url);
-                                    }
-                                    
-                                    // Parse project_id from parameters
-                                    let project_id = if let Some(params) = &parameters {
-                                        uuid::Uuid::parse_str(&params.project_id).ok()
-                                    } else {
-                                        None
-                                    };
-                                    
-                                    // Parse task_id from string
-                                    let task_id = uuid::Uuid::parse_str(&task_with_url.task.id).ok();
-                                    
-                                    // Set task resource URL parameter for Detail page
-                                    commands.insert_resource(detail::Parameters {
-                                        url,
-                                        task_id,
-                                        project_id,
-                                    });
-                                    next_state.set(AppState::Detail);
-                                }
-                            });
+                            if ui.button(t!("tasks-delete")).clicked() {
+                                operation = Some(BatchOperation::Delete);
+                            }
+                            if ui.button(t!("common-cancel")).clicked() {
+                                page_data.confirm_batch_delete = false;
+                            }
                         });
                     });
-                    ui.add_space(5.0);
+                if !open || operation.is_some() {
+                    page_data.confirm_batch_delete = false;
                 }
             }
-        });
+
+            if let (Some(operation), Some(jwt), Some(params)) = (operation, auth_state.get_jwt(), &parameters) {
+                page_data.is_applying_batch = true;
+                let task_ids = page_data.selected.iter().cloned().collect();
+                batch_tasks.spawn(apply_batch(jwt.clone(), params.project_id.clone(), task_ids, operation));
+            }
+
+            let spacing = ui.spacing().item_spacing;
+            let columns = ((ui.available_width() + spacing.x) / (CARD_WIDTH + spacing.x)).floor().max(1.0) as usize;
+            let rows = tasks_state.tasks.len().div_ceil(columns);
+            let mut opened = None;
+
+            egui::ScrollArea::vertical().show_rows(ui, CARD_HEIGHT, rows, |ui, row_range| {
+                for row in row_range {
+                    ui.horizontal(|ui| {
+                        for task_with_url in tasks_state.tasks.iter().skip(row * columns).take(columns) {
+                            let task_id = &task_with_url.task.id;
+                            if let (Some(jwt), Some(params)) = (auth_state.get_jwt(), &parameters) {
+                                thumbnails.request(&thumbnail_tasks, jwt, &params.project_id, task_id);
+                            }
+                            let mut selected = page_data.selected.contains(task_id);
+                            if render_task_card(ui, task_with_url, thumbnails.thumbnails.get(task_id), &mut selected) {
+                                opened = Some(task_with_url.clone());
+                            }
+                            if selected {
+                                page_data.selected.insert(task_id.clone());
+                            } else {
+                                page_data.selected.remove(task_id);
+                            }
+                        }
+                    });
+                }
+            });
+
+            if let Some(task_with_url) = opened {
+                open_task(&mut commands, &mut next_state, &task_with_url, parameters.as_deref());
+            }
+        }
 
         // Create task dialog would go here if needed
         // show_create_task_dialog(ui, &mut page_data, &mut tasks_state, &auth_state, &parameters);
     });
 }
 
-pub fn cleanup(mut commands: Commands) {
+/// One card of the task grid: thumbnail, name and badges for status, box count, assignees and
+/// flags, with a checkbox for bulk operations. Returns whether the card was clicked to open it.
+fn render_task_card(
+    ui: &mut egui::Ui,
+    task_with_url: &TaskWithResolvedUrl,
+    thumbnail: Option<&Thumbnail>,
+    selected: &mut bool,
+) -> bool {
+    let task = &task_with_url.task;
+    let mut open = false;
+
+    let frame = egui::Frame::group(ui.style()).stroke(if *selected {
+        ui.visuals().selection.stroke
+    } else if task_with_url.overdue {
+        egui::Stroke::new(1.5, OVERDUE_COLOR)
+    } else {
+        ui.visuals().widgets.noninteractive.bg_stroke
+    });
+    frame.show(ui, |ui| {
+        ui.set_width(CARD_WIDTH - 14.0);
+        ui.set_height(CARD_HEIGHT - 14.0);
+        ui.vertical(|ui| {
+            let size = egui::vec2(ui.available_width(), THUMBNAIL_HEIGHT);
+            let response = match thumbnail {
+                Some(Thumbnail::Loaded(texture)) => {
+                    let image_size = texture.size_vec2();
+                    let scale = (size.x / image_size.x).min(size.y / image_size.y);
+                    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+                    let image_rect = egui::Rect::from_center_size(rect.center(), image_size * scale);
+                    ui.painter().rect_filled(rect, 2.0, egui::Color32::from_gray(30));
+                    ui.painter().image(
+                        texture.id(),
+                        image_rect,
+                        egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                        egui::Color32::WHITE,
+                    );
+                    response
+                }
+                other => {
+                    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+                    ui.painter().rect_filled(rect, 2.0, egui::Color32::from_gray(30));
+                    if matches!(other, Some(Thumbnail::Loading) | None) {
+                        ui.put(egui::Rect::from_center_size(rect.center(), egui::vec2(24.0, 24.0)), egui::Spinner::new());
+                    } else {
+                        let icon = if task.is_image() { "🖼" } else { "🎞" };
+                        ui.painter().text(rect.center(), egui::Align2::CENTER_CENTER, icon, egui::FontId::proportional(32.0), egui::Color32::GRAY);
+                    }
+                    response
+                }
+            };
+            if response.on_hover_cursor(egui::CursorIcon::PointingHand).clicked() {
+                open = true;
+            }
+
+            ui.horizontal(|ui| {
+                ui.checkbox(selected, "");
+                ui.add(egui::Label::new(egui::RichText::new(&task.name).strong()).truncate());
+            });
+            ui.horizontal_wrapped(|ui| {
+                ui.label(format_status(&task.status));
+                ui.label(format!("🔲 {}", task_with_url.annotation_count))
+                    .on_hover_text(t!("tasks-box-count-hint"));
+                if !task.is_image() {
+                    ui.label(format!("🎞 {}", task.frame_count.unwrap_or(0)));
+                }
+                if let Some(split) = &task.split {
+                    ui.label(format!("🏷 {}", split_name(split)));
+                }
+                if let Some(reason) = &task.flag_reason {
+                    ui.colored_label(egui::Color32::from_rgb(220, 120, 40), format!("🚩 {}", flag_reason_name(reason)))
+                        .on_hover_text(task.flag_note.as_deref().unwrap_or(""));
+                }
+                if task.is_stale() {
+                    ui.colored_label(egui::Color32::from_rgb(200, 160, 40), t!("tasks-stale"))
+                        .on_hover_text(t!("tasks-stale-hint", key = task.source_key.as_deref().unwrap_or("")));
+                }
+                if let Some(due_date) = &task_with_url.due_date {
+                    let label = format!("📅 {}", format_due_date(due_date));
+                    if task_with_url.overdue {
+                        ui.colored_label(OVERDUE_COLOR, label).on_hover_text(t!("tasks-overdue-hint"));
+                    } else {
+                        ui.label(label).on_hover_text(t!("tasks-due-hint"));
+                    }
+                }
+            });
+            if !task_with_url.assignees.is_empty() {
+                ui.add(egui::Label::new(format!("👤 {}", task_with_url.assignees.join(", "))).truncate());
+            }
+            if let Some(priority) = task.priority {
+                ui.weak(t!("tasks-priority", priority = format!("{:.3}", priority)));
+            }
+            ui.weak(t!("projects-created", date = format_date(&task.created_at)));
+        });
+    });
+    ui.add_space(4.0);
+
+    open
+}
+
+/// Menus of the bulk operations on the selected tasks. Deleting asks for confirmation first, so
+/// it only opens the confirmation window.
+fn render_batch_actions(ui: &mut egui::Ui, page_data: &mut TasksPageData) -> Option<BatchOperation> {
+    let mut operation = None;
+
+    ui.menu_button(t!("tasks-set-status"), |ui| {
+        for status in TASK_STATUSES {
+            if ui.button(format_status(status)).clicked() {
+                operation = Some(BatchOperation::SetStatus(status));
+                ui.close_menu();
+            }
+        }
+    });
+
+    ui.menu_button(t!("tasks-add-to-split"), |ui| {
+        for (code, _) in SPLITS {
+            if ui.button(split_name(code)).clicked() {
+                operation = Some(BatchOperation::SetSplit(Some(code)));
+                ui.close_menu();
+            }
+        }
+        ui.separator();
+        if ui.button(t!("tasks-remove-from-split")).clicked() {
+            operation = Some(BatchOperation::SetSplit(None));
+            ui.close_menu();
+        }
+    });
+
+    ui.menu_button(t!("tasks-set-due"), |ui| {
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut page_data.batch_due_date).hint_text("YYYY-MM-DD").desired_width(90.0));
+            let due_at = page_data.batch_due_date.trim().parse::<NaiveDate>().ok().and_then(end_of_day);
+            if ui.add_enabled(due_at.is_some(), egui::Button::new(t!("tasks-set-due-apply"))).clicked() {
+                operation = Some(BatchOperation::SetDue(due_at));
+                ui.close_menu();
+            }
+        });
+        ui.separator();
+        if ui.button(t!("tasks-clear-due")).on_hover_text(t!("tasks-clear-due-hint")).clicked() {
+            operation = Some(BatchOperation::SetDue(None));
+            ui.close_menu();
+        }
+    });
+
+    ui.menu_button(t!("tasks-assign"), |ui| {
+        if page_data.members.is_empty() {
+            ui.weak(t!("tasks-no-members"));
+        }
+        for member in &page_data.members {
+            let mut assigned = page_data.batch_assignees.contains(&member.user_id);
+            if ui.checkbox(&mut assigned, &member.name).on_hover_text(&member.email).changed() {
+                if assigned {
+                    page_data.batch_assignees.insert(member.user_id.clone());
+                } else {
+                    page_data.batch_assignees.remove(&member.user_id);
+                }
+            }
+        }
+        ui.separator();
+        let label = if page_data.batch_assignees.is_empty() { t!("tasks-unassign") } else { t!("tasks-assign") };
+        if ui.button(label).on_hover_text(t!("tasks-assign-hint")).clicked() {
+            operation = Some(BatchOperation::Assign(page_data.batch_assignees.iter().cloned().collect()));
+            ui.close_menu();
+        }
+    });
+
+    if ui.button(t!("tasks-check-sources")).on_hover_text(t!("tasks-check-sources-hint")).clicked() {
+        operation = Some(BatchOperation::CheckSources);
+    }
+
+    if ui.button(t!("tasks-delete")).clicked() {
+        page_data.confirm_batch_delete = true;
+    }
+
+    operation
+}
+
+/// Reads the task list again with `filter` in the background, in place of any read still on
+/// its way. `process_task_results` puts it up.
+fn reload_tasks(tasks_state: &mut TasksState, task_loads: &ApiTasks<LoadedTasks>, filter: &TaskFilter, jwt: &str, project_id: &str) {
+    tasks_state.start_fetching();
+
+    let (jwt, project_id, filter) = (jwt.to_string(), project_id.to_string(), filter.clone());
+    task_loads.cancel_all();
+    task_loads.spawn(async move {
+        let result = TasksApi::new().list_tasks_filtered(&jwt, &project_id, &filter).await;
+        offline_store::store().tasks(&project_id, &filter, result)
+            .map(LoadedTasks)
+            .map_err(|e| e.to_string())
+    });
+}
+
+/// Shows the task list `reload_tasks` read, and gets the images of the first pending tasks
+/// ready while it is looked through.
+pub fn process_task_results(
+    mut succeeded: EventReader<ApiTaskSucceeded<LoadedTasks>>,
+    mut failed: EventReader<ApiTaskFailed<LoadedTasks>>,
+    mut tasks_state: ResMut<TasksState>,
+    mut page_data: Option<ResMut<TasksPageData>>,
+    image_cache: Res<ImageCache>,
+) {
+    for ApiTaskSucceeded(LoadedTasks(tasks)) in succeeded.read() {
+        image_cache.prefetch(
+            tasks.iter()
+                .filter(|task_with_url| task_with_url.task.status == "pending" && task_with_url.task.is_image())
+                .take(PREFETCH_AHEAD)
+                .filter_map(|task_with_url| task_with_url.resolved_resource_url.clone()),
+        );
+        tasks_state.set_tasks(tasks.clone());
+        if let Some(page_data) = &mut page_data {
+            page_data.selected.retain(|id| tasks_state.tasks.iter().any(|task_with_url| &task_with_url.task.id == id));
+        }
+    }
+    for failure in failed.read() {
+        tasks_state.set_error(failure.error.clone());
+    }
+}
+
+/// Opens the random unannotated task the start annotation button asked for
+pub fn process_random_task_results(
+    mut commands: Commands,
+    mut succeeded: EventReader<ApiTaskSucceeded<RandomTask>>,
+    mut failed: EventReader<ApiTaskFailed<RandomTask>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut tasks_state: ResMut<TasksState>,
+    page_data: Option<ResMut<TasksPageData>>,
+    parameters: Option<Res<Parameters>>,
+) {
+    let Some(mut page_data) = page_data else {
+        return;
+    };
+
+    for ApiTaskSucceeded(RandomTask(task_with_url)) in succeeded.read() {
+        page_data.is_starting_annotation = false;
+        let Some(task_with_url) = task_with_url else {
+            tasks_state.set_error(t!("tasks-none-unannotated"));
+            continue;
+        };
+        println!("Found random unannotated task: {}", task_with_url.task.name);
+
+        // Use resolved_resource_url if available, fallback to original resource_url
+        let Some(url) = task_with_url.resolved_resource_url.as_ref().or(task_with_url.task.resource_url.as_ref()) else {
+            tasks_state.set_error(t!("tasks-no-resource-url"));
+            continue;
+        };
+        if url.is_empty() {
+            tasks_state.set_error(t!("tasks-invalid-resource-url"));
+            continue;
+        }
+
+        // Set parameters for Detail page and navigate
+        commands.insert_resource(detail::Parameters {
+            url: url.clone(),
+            task_id: uuid::Uuid::parse_str(&task_with_url.task.id).ok(),
+            project_id: parameters.as_ref().and_then(|params| uuid::Uuid::parse_str(&params.project_id).ok()),
+        });
+        next_state.set(AppState::Detail);
+    }
+    for failure in failed.read() {
+        page_data.is_starting_annotation = false;
+        tasks_state.set_error(failure.error.clone());
+    }
+}
+
+/// Shows how a bulk operation went and reads the changed tasks again
+#[allow(clippy::too_many_arguments)]
+pub fn process_batch_results(
+    mut batch_succeeded: EventReader<ApiTaskSucceeded<BatchResult>>,
+    mut batch_failed: EventReader<ApiTaskFailed<BatchResult>>,
+    mut members_succeeded: EventReader<ApiTaskSucceeded<Vec<ProjectMember>>>,
+    mut members_failed: EventReader<ApiTaskFailed<Vec<ProjectMember>>>,
+    mut categories_succeeded: EventReader<ApiTaskSucceeded<Vec<AnnotationCategory>>>,
+    mut categories_failed: EventReader<ApiTaskFailed<Vec<AnnotationCategory>>>,
+    mut tasks_state: ResMut<TasksState>,
+    page_data: Option<ResMut<TasksPageData>>,
+    auth_state: Res<AuthState>,
+    parameters: Option<Res<Parameters>>,
+    task_loads: Res<ApiTasks<LoadedTasks>>,
+    mut notify: EventWriter<Notify>,
+) {
+    let Some(mut page_data) = page_data else {
+        return;
+    };
+
+    for ApiTaskSucceeded(members) in members_succeeded.read() {
+        page_data.members = members.clone();
+    }
+    for failure in members_failed.read() {
+        warn!("Failed to fetch project members: {}", failure.error);
+    }
+
+    for ApiTaskSucceeded(categories) in categories_succeeded.read() {
+        page_data.categories = categories.clone();
+    }
+    for failure in categories_failed.read() {
+        warn!("Failed to fetch categories for the filter: {}", failure.error);
+    }
+
+    let mut reload = false;
+    for ApiTaskSucceeded(result) in batch_succeeded.read() {
+        page_data.is_applying_batch = false;
+        notify.write(Notify::success(result.operation.describe(result.affected)));
+        reload = true;
+    }
+    for failure in batch_failed.read() {
+        page_data.is_applying_batch = false;
+        notify.write(Notify::error(t!("tasks-batch-failed", error = failure.error.as_str())));
+    }
+
+    if reload {
+        if let (Some(jwt), Some(params)) = (auth_state.get_jwt(), &parameters) {
+            reload_tasks(&mut tasks_state, &task_loads, &page_data.filter, jwt, &params.project_id);
+        }
+    }
+}
+
+/// Opens a task on the detail page
+fn open_task(
+    commands: &mut Commands,
+    next_state: &mut NextState<AppState>,
+    task_with_url: &TaskWithResolvedUrl,
+    parameters: Option<&Parameters>,
+) {
+    // Use resolved_resource_url if available, fallback to original resource_url
+    let url = task_with_url.resolved_resource_url.as_ref()
+        .or(task_with_url.task.resource_url.as_ref())
+        .cloned()
+        .unwrap_or_default();
+    println!("Opening task: {}", task_with_url.task.name);
+    println!("Using URL: '{}'", url);
+
+    // Validate URL before transitioning
+    if url.is_empty() {
+        eprintln!("Error: Task resource URL is empty for task '{}'", task_with_url.task.name);
+        return;
+    } else if !url.starts_with("http://") && !url.starts_with("https://") && !url.starts_with("file://") {
+        eprintln!("Warning: Task resource URL might not be a valid URL: '{}'", url);
+    }
+
+    commands.insert_resource(detail::Parameters {
+        url,
+        task_id: uuid::Uuid::parse_str(&task_with_url.task.id).ok(),
+        project_id: parameters.and_then(|params| uuid::Uuid::parse_str(&params.project_id).ok()),
+    });
+    next_state.set(AppState::Detail);
+}
+
+/// Task created from the clipboard
+pub struct PastedImage {
+    name: String,
+}
+
+/// Image in the OS clipboard, such as a screenshot, encoded as PNG
+fn clipboard_png() -> Result<Vec<u8>, String> {
+    let rgba = platform::clipboard_image()?;
+    let mut png = Vec::new();
+    image::DynamicImage::ImageRgba8(rgba)
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| t!("tasks-clipboard-encode-failed", error = e.to_string()))?;
+    Ok(png)
+}
+
+/// Uploads the image in the clipboard as a new task on Ctrl+V (Cmd+V on macOS), or when the
+/// paste button was clicked
+pub fn paste_image_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut egui_contexts: EguiContexts,
+    auth_state: Res<AuthState>,
+    parameters: Option<Res<Parameters>>,
+    page_data: Option<ResMut<TasksPageData>>,
+    paste_tasks: Res<ApiTasks<PastedImage>>,
+    mut notify: EventWriter<Notify>,
+) {
+    let Some(mut page_data) = page_data else {
+        return;
+    };
+
+    let modifier_pressed = if cfg!(target_os = "macos") {
+        keyboard.pressed(KeyCode::SuperLeft) || keyboard.pressed(KeyCode::SuperRight)
+    } else {
+        keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight)
+    };
+    // Pasting into a text field is left to the field
+    let shortcut = modifier_pressed
+        && keyboard.just_pressed(KeyCode::KeyV)
+        && !egui_contexts.ctx_mut().wants_keyboard_input();
+    if !std::mem::take(&mut page_data.paste_requested) && !shortcut {
+        return;
+    }
+    if page_data.is_pasting {
+        return;
+    }
+    let (Some(jwt), Some(params)) = (auth_state.get_jwt(), &parameters) else {
+        return;
+    };
+
+    let png = match clipboard_png() {
+        Ok(png) => png,
+        Err(error) => {
+            notify.write(Notify::error(error));
+            return;
+        }
+    };
+
+    let name = format!("pasted_{}.png", chrono::Local::now().format("%Y%m%d_%H%M%S"));
+    page_data.is_pasting = true;
+    let (jwt, project_id) = (jwt.clone(), params.project_id.clone());
+    paste_tasks.spawn(async move {
+        crate::upload::upload_image(&jwt, &project_id, &name, png, "image/png", Arc::new(AtomicU64::new(0))).await?;
+        Ok(PastedImage { name })
+    });
+}
+
+/// Shows the task created from the clipboard in the list
+pub fn process_paste_results(
+    mut succeeded: EventReader<ApiTaskSucceeded<PastedImage>>,
+    mut failed: EventReader<ApiTaskFailed<PastedImage>>,
+    mut tasks_state: ResMut<TasksState>,
+    page_data: Option<ResMut<TasksPageData>>,
+    auth_state: Res<AuthState>,
+    parameters: Option<Res<Parameters>>,
+    task_loads: Res<ApiTasks<LoadedTasks>>,
+    mut notify: EventWriter<Notify>,
+) {
+    let Some(mut page_data) = page_data else {
+        return;
+    };
+
+    let mut reload = false;
+    for ApiTaskSucceeded(pasted) in succeeded.read() {
+        page_data.is_pasting = false;
+        notify.write(Notify::success(t!("tasks-pasted", name = pasted.name.as_str())));
+        reload = true;
+    }
+    for failure in failed.read() {
+        page_data.is_pasting = false;
+        notify.write(Notify::error(t!("tasks-paste-failed", error = failure.error.as_str())));
+    }
+
+    if reload {
+        if let (Some(jwt), Some(params)) = (auth_state.get_jwt(), &parameters) {
+            reload_tasks(&mut tasks_state, &task_loads, &page_data.filter, jwt, &params.project_id);
+        }
+    }
+}
+
+/// Turns downloaded thumbnails into textures for the grid
+pub fn process_thumbnail_results(
+    mut contexts: EguiContexts,
+    mut succeeded: EventReader<ApiTaskSucceeded<LoadedThumbnail>>,
+    mut thumbnails: ResMut<ThumbnailState>,
+) {
+    for ApiTaskSucceeded(loaded) in succeeded.read() {
+        thumbnails.loading = thumbnails.loading.saturating_sub(1);
+        let thumbnail = match &loaded.image {
+            Ok(image) => Thumbnail::Loaded(contexts.ctx_mut().load_texture(
+                format!("thumbnail-{}", loaded.task_id),
+                image.clone(),
+                egui::TextureOptions::LINEAR,
+            )),
+            Err(error) => {
+                debug!("No thumbnail for task {}: {}", loaded.task_id, error);
+                Thumbnail::Unavailable
+            }
+        };
+        thumbnails.thumbnails.insert(loaded.task_id.clone(), thumbnail);
+    }
+}
+
+pub fn cleanup(
+    mut commands: Commands,
+    member_tasks: Res<ApiTasks<Vec<ProjectMember>>>,
+    category_tasks: Res<ApiTasks<Vec<AnnotationCategory>>>,
+    task_loads: Res<ApiTasks<LoadedTasks>>,
+    random_tasks: Res<ApiTasks<RandomTask>>,
+    mut tasks_state: ResMut<TasksState>,
+) {
     println!("tasks cleanup");
+    // Members, categories and tasks of this project would end up on the page of the next one
+    // opened, and a random task found late would open on another page. Thumbnails are kept by
+    // task ID and bulk operations change the server either way, so both go on.
+    member_tasks.cancel_all();
+    category_tasks.cancel_all();
+    task_loads.cancel_all();
+    random_tasks.cancel_all();
+    tasks_state.is_fetching = false;
     commands.remove_resource::<TasksPageData>();
 }
 
 fn format_status(status: &str) -> String {
     match status {
-        "pending" => "📋 Pending".to_string(),
-        "in_progress" => "🔄 In Progress".to_string(),
-        "completed" => "✅ Completed".to_string(),
-        "cancelled" => "❌ Cancelled".to_string(),
+        "pending" => t!("status-pending"),
+        "in_progress" => t!("status-in-progress"),
+        "completed" => t!("status-completed"),
+        "cancelled" => t!("status-cancelled"),
         _ => status.to_string(),
     }
 }
 
+fn split_name(split: &str) -> String {
+    i18n::code_label("split", split, split_label(split))
+}
+
+fn flag_reason_name(reason: &str) -> String {
+    i18n::code_label("flag-reason", reason, flag_reason_label(reason))
+}
+
 fn format_date(date_str: &str) -> String {
     // Simple date formatting - just return the first 10 characters (YYYY-MM-DD)
     if date_str.len() >= 10 {
@@ -321,17 +1039,38 @@ fn format_date(date_str: &str) -> String {
     }
 }
 
+/// Last moment of `day` in the user's time zone, what a due date typed as a day means
+pub fn end_of_day(day: NaiveDate) -> Option<DateTime<Utc>> {
+    let end = day.and_hms_opt(23, 59, 59)?;
+    Local.from_local_datetime(&end).earliest().map(|end| end.with_timezone(&Utc))
+}
+
+/// Day a task is due, in the user's time zone
+pub fn format_due_date(due_at: &DateTime<Utc>) -> String {
+    due_at.with_timezone(&Local).format("%Y-%m-%d").to_string()
+}
+
 pub struct TasksPlugin;
 
 impl Plugin for TasksPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<TasksState>()
+        app.add_plugins(ApiTaskPlugin::<LoadedThumbnail>::default())
+           .add_plugins(ApiTaskPlugin::<BatchResult>::default())
+           .add_plugins(ApiTaskPlugin::<Vec<ProjectMember>>::default())
+           .add_plugins(ApiTaskPlugin::<Vec<AnnotationCategory>>::default())
+           .add_plugins(ApiTaskPlugin::<PastedImage>::default())
+           .add_plugins(ApiTaskPlugin::<LoadedTasks>::default())
+           .add_plugins(ApiTaskPlugin::<RandomTask>::default())
+           .init_resource::<TasksState>()
+           .init_resource::<ThumbnailState>()
            .add_systems(OnEnter(AppState::Tasks), setup)
-           .add_systems(Update, update.run_if(in_state(AppState::Tasks)))
+           .add_systems(Update, (update, process_task_results, process_random_task_results, process_batch_results, paste_image_system, process_paste_results).run_if(in_state(AppState::Tasks)))
            .add_systems(
                EguiContextPass,
                ui_system.run_if(in_state(AppState::Tasks)),
            )
+           // Outside of the page too, so thumbnails still on the way when it closes are not lost
+           .add_systems(EguiContextPass, process_thumbnail_results.before(ui_system))
            .add_systems(OnExit(AppState::Tasks), cleanup);
     }
 }
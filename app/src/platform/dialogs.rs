@@ -0,0 +1,40 @@
+//! The system's file dialogs, in builds with the `file-dialogs` feature. Without it nothing can
+//! be picked, the dialogs return as if they had been cancelled, and pages leave out the buttons
+//! that would open one (see `AVAILABLE`). They block until closed, so they are opened inside
+//! `platform::unblock` or from a system that may stall.
+
+use std::path::PathBuf;
+
+pub const AVAILABLE: bool = cfg!(feature = "file-dialogs");
+
+/// A file with one of `extensions`, described as `filter` in the dialog
+#[cfg(feature = "file-dialogs")]
+pub fn pick_file(filter: &str, extensions: &[&str]) -> Option<PathBuf> {
+    rfd::FileDialog::new().add_filter(filter, extensions).pick_file()
+}
+
+#[cfg(not(feature = "file-dialogs"))]
+pub fn pick_file(_filter: &str, _extensions: &[&str]) -> Option<PathBuf> {
+    None
+}
+
+#[cfg(feature = "file-dialogs")]
+pub fn pick_files(filter: &str, extensions: &[&str]) -> Option<Vec<PathBuf>> {
+    rfd::FileDialog::new().add_filter(filter, extensions).pick_files()
+}
+
+#[cfg(not(feature = "file-dialogs"))]
+pub fn pick_files(_filter: &str, _extensions: &[&str]) -> Option<Vec<PathBuf>> {
+    None
+}
+
+/// Where to save a file, suggesting `file_name`
+#[cfg(feature = "file-dialogs")]
+pub fn save_file(file_name: &str, filter: &str, extensions: &[&str]) -> Option<PathBuf> {
+    rfd::FileDialog::new().set_file_name(file_name).add_filter(filter, extensions).save_file()
+}
+
+#[cfg(not(feature = "file-dialogs"))]
+pub fn save_file(_file_name: &str, _filter: &str, _extensions: &[&str]) -> Option<PathBuf> {
+    None
+}
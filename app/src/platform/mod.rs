@@ -0,0 +1,57 @@
+//! What differs between the desktop app and the browser build (`wasm32`): running requests,
+//! where settings, sessions and the offline copy are kept, file dialogs and opening links.
+//! The rest of the app goes through this module instead of reaching for tokio, the file system
+//! or the OS directly.
+//!
+//! A browser page has a single thread that must never wait, so requests only run in the
+//! background, through `spawn` or `ApiTasks`, and systems pick up their results. Uploads from
+//! disk are left out of the browser build, see `DESKTOP`.
+
+pub mod dialogs;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::*;
+
+#[cfg(target_arch = "wasm32")]
+mod web;
+#[cfg(target_arch = "wasm32")]
+pub use web::*;
+
+use bevy::tasks::ConditionalSendFuture;
+use std::sync::{Arc, Mutex};
+
+/// Whether this is the desktop app rather than the browser build. Uploads of files from disk,
+/// installer updates and the window layout are only handled there.
+pub const DESKTOP: bool = cfg!(not(target_arch = "wasm32"));
+
+/// Result of a future running in the background, picked up by a system once it is there
+pub struct Pending<T> {
+    result: Arc<Mutex<Option<T>>>,
+    task: Task,
+}
+
+impl<T: Send + 'static> Pending<T> {
+    /// The result, once. `None` while the future is still running.
+    pub fn take(&self) -> Option<T> {
+        self.result.lock().ok()?.take()
+    }
+
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+}
+
+/// Runs `future` in the background like `spawn`, keeping its result for `Pending::take`
+pub fn spawn_pending<T: Send + 'static>(future: impl ConditionalSendFuture<Output = T> + 'static) -> Pending<T> {
+    let result = Arc::new(Mutex::new(None));
+    let slot = result.clone();
+    let task = spawn(async move {
+        let value = future.await;
+        if let Ok(mut slot) = slot.lock() {
+            *slot = Some(value);
+        }
+    });
+    Pending { result, task }
+}
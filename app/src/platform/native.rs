@@ -0,0 +1,190 @@
+use bevy::prelude::*;
+use bevy::tasks::ConditionalSendFuture;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use tokio::task::JoinHandle;
+
+const KEYRING_SERVICE: &str = "fast-tag";
+
+/// Runtime shared by every request of the app
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| Runtime::new().unwrap())
+}
+
+/// A future started with `spawn`
+pub struct Task(JoinHandle<()>);
+
+impl Task {
+    pub fn is_finished(&self) -> bool {
+        self.0.is_finished()
+    }
+
+    /// Stops the future where it is, a download in flight is dropped
+    pub fn abort(&self) {
+        self.0.abort();
+    }
+
+    /// Waits until the future finished or was aborted
+    pub async fn join(self) {
+        let _ = self.0.await;
+    }
+}
+
+/// Runs `future` in the background, on the runtime shared by every request
+pub fn spawn(future: impl ConditionalSendFuture<Output = ()> + 'static) -> Task {
+    Task(runtime().spawn(future))
+}
+
+pub async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+/// Runs blocking work, like a file dialog, off the threads requests run on
+pub async fn unblock<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> Result<T, String> {
+    tokio::task::spawn_blocking(work).await.map_err(|e| e.to_string())
+}
+
+/// Opens a page in the default browser
+pub fn open_url(url: &str) -> Result<(), String> {
+    open::that(url).map_err(|e| e.to_string())
+}
+
+/// How sessions of the app are listed on the profile page
+pub fn device_name() -> String {
+    format!("fast-tag desktop ({})", std::env::consts::OS)
+}
+
+/// Makes the OS start this executable with links of `scheme` as argument. On macOS the scheme is
+/// declared by the app bundle instead (`CFBundleURLTypes` in its `Info.plist`).
+pub fn register_url_scheme(scheme: &str) -> Result<(), String> {
+    let executable = std::env::current_exe().map_err(|e| e.to_string())?;
+    register_url_handler(scheme, &executable.to_string_lossy())
+}
+
+#[cfg(target_os = "linux")]
+fn register_url_handler(scheme: &str, executable: &str) -> Result<(), String> {
+    let file_name = format!("fast-tag-{}.desktop", scheme);
+    let dir = dirs::data_dir().ok_or("No data directory")?.join("applications");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName=fast-tag\nExec=\"{}\" %u\nTerminal=false\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+        executable, scheme
+    );
+    fs::write(dir.join(&file_name), entry).map_err(|e| e.to_string())?;
+    run("xdg-mime", &["default", &file_name, &format!("x-scheme-handler/{}", scheme)])
+}
+
+#[cfg(target_os = "windows")]
+fn register_url_handler(scheme: &str, executable: &str) -> Result<(), String> {
+    let key = format!("HKCU\\Software\\Classes\\{}", scheme);
+    run("reg", &["add", &key, "/ve", "/d", "URL:fast-tag", "/f"])?;
+    run("reg", &["add", &key, "/v", "URL Protocol", "/d", "", "/f"])?;
+    let command = format!("\"{}\" \"%1\"", executable);
+    run("reg", &["add", &format!("{}\\shell\\open\\command", key), "/ve", "/d", &command, "/f"])
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn register_url_handler(_scheme: &str, _executable: &str) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+fn run(program: &str, arguments: &[&str]) -> Result<(), String> {
+    let status = std::process::Command::new(program)
+        .args(arguments)
+        .status()
+        .map_err(|e| format!("{}: {}", program, e))?;
+    if status.success() { Ok(()) } else { Err(format!("{} exited with {}", program, status)) }
+}
+
+fn setting_path(name: &str) -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("fast-tag").join(name))
+}
+
+/// Contents of a settings file of the app in the user's config directory, `None` when there
+/// is none yet
+pub fn read_setting(name: &str) -> Option<Vec<u8>> {
+    fs::read(setting_path(name)?).ok()
+}
+
+pub fn write_setting(name: &str, bytes: &[u8]) -> Result<(), String> {
+    let path = setting_path(name).ok_or("No config directory")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    fs::write(&path, bytes).map_err(|e| e.to_string())
+}
+
+/// Secret kept in the OS keyring under `account`, `None` when there is none
+pub fn read_secret(account: &str) -> Result<Option<String>, String> {
+    match keyring::Entry::new(KEYRING_SERVICE, account).and_then(|entry| entry.get_password()) {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+pub fn write_secret(account: &str, secret: &str) -> Result<(), String> {
+    keyring::Entry::new(KEYRING_SERVICE, account)
+        .and_then(|entry| entry.set_password(secret))
+        .map_err(|e| e.to_string())
+}
+
+pub fn delete_secret(account: &str) -> Result<(), String> {
+    match keyring::Entry::new(KEYRING_SERVICE, account).and_then(|entry| entry.delete_credential()) {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+/// Key-value database in the user's data directory, every write is flushed to disk
+pub struct LocalDb(sled::Db);
+
+impl LocalDb {
+    /// `None` when the database can't be opened, e.g. while another instance of the app holds it
+    pub fn open(name: &str) -> Option<Self> {
+        let path = dirs::data_dir()?.join("fast-tag").join(name);
+        match sled::open(&path) {
+            Ok(db) => Some(Self(db)),
+            Err(error) => {
+                warn!("Local database at {} unavailable: {}", path.display(), error);
+                None
+            }
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        Some(self.0.get(key).ok()??.to_vec())
+    }
+
+    pub fn insert(&self, key: &str, value: Vec<u8>) -> Result<(), String> {
+        self.0.insert(key, value).map_err(|e| e.to_string())?;
+        self.0.flush().map(|_| ()).map_err(|e| e.to_string())
+    }
+
+    pub fn remove(&self, key: &str) {
+        let _ = self.0.remove(key);
+        let _ = self.0.flush();
+    }
+
+    /// Values of every key starting with `prefix`
+    pub fn scan_prefix(&self, prefix: &str) -> Vec<Vec<u8>> {
+        self.0
+            .scan_prefix(prefix)
+            .filter_map(|entry| entry.ok())
+            .map(|(_, value)| value.to_vec())
+            .collect()
+    }
+}
+
+/// Image in the OS clipboard, such as a screenshot
+pub fn clipboard_image() -> Result<image::RgbaImage, String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| t!("tasks-clipboard-open-failed", error = e.to_string()))?;
+    let image = clipboard.get_image().map_err(|_| t!("tasks-clipboard-no-image"))?;
+    image::RgbaImage::from_raw(image.width as u32, image.height as u32, image.bytes.into_owned())
+        .ok_or_else(|| t!("tasks-clipboard-malformed"))
+}
@@ -0,0 +1,195 @@
+use bevy::tasks::ConditionalSendFuture;
+use std::future::{Future, poll_fn};
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+use std::time::Duration;
+
+/// Prefix of everything the app keeps in the page's local storage
+const STORAGE_PREFIX: &str = "fast-tag/";
+
+#[derive(Default)]
+struct TaskState {
+    finished: AtomicBool,
+    aborted: AtomicBool,
+    /// Woken once the future finished, for `Task::join`
+    waker: Mutex<Option<Waker>>,
+}
+
+impl TaskState {
+    fn finish(&self) {
+        self.finished.store(true, Ordering::Relaxed);
+        if let Some(waker) = self.waker.lock().ok().and_then(|mut waker| waker.take()) {
+            waker.wake();
+        }
+    }
+}
+
+/// A future started with `spawn`
+pub struct Task(Arc<TaskState>);
+
+impl Task {
+    pub fn is_finished(&self) -> bool {
+        self.0.finished.load(Ordering::Relaxed)
+    }
+
+    /// Stops polling the future. Nothing wakes it for that, so a request in flight still
+    /// completes, its result just goes nowhere.
+    pub fn abort(&self) {
+        self.0.aborted.store(true, Ordering::Relaxed);
+    }
+
+    /// Waits until the future finished or was aborted
+    pub async fn join(self) {
+        poll_fn(|context| {
+            if self.is_finished() || self.0.aborted.load(Ordering::Relaxed) {
+                return Poll::Ready(());
+            }
+            if let Ok(mut waker) = self.0.waker.lock() {
+                *waker = Some(context.waker().clone());
+            }
+            Poll::Pending
+        })
+        .await;
+    }
+}
+
+/// Runs `future` on the page's event loop, between frames
+pub fn spawn(future: impl ConditionalSendFuture<Output = ()> + 'static) -> Task {
+    let state = Arc::new(TaskState::default());
+    let task_state = state.clone();
+    wasm_bindgen_futures::spawn_local(async move {
+        let mut future = pin!(future);
+        poll_fn(|context| {
+            if task_state.aborted.load(Ordering::Relaxed) {
+                return Poll::Ready(());
+            }
+            future.as_mut().poll(context)
+        })
+        .await;
+        task_state.finish();
+    });
+    Task(state)
+}
+
+pub async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await;
+}
+
+/// The page has no other thread, the work runs right away
+pub async fn unblock<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> Result<T, String> {
+    Ok(work())
+}
+
+/// Opens a page in a new tab
+pub fn open_url(url: &str) -> Result<(), String> {
+    let window = web_sys::window().ok_or("No browser window")?;
+    window
+        .open_with_url_and_target(url, "_blank")
+        .map(|_| ())
+        .map_err(|error| format!("{:?}", error))
+}
+
+/// How sessions of the app are listed on the profile page
+pub fn device_name() -> String {
+    "fast-tag browser".to_string()
+}
+
+/// Links of the desktop app aren't meant for the page
+pub fn register_url_scheme(_scheme: &str) -> Result<(), String> {
+    Ok(())
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+fn read_item(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok()?
+}
+
+fn write_item(key: &str, value: &str) -> Result<(), String> {
+    local_storage()
+        .ok_or("No local storage")?
+        .set_item(key, value)
+        // Mostly the storage quota of the site being used up
+        .map_err(|error| format!("{:?}", error))
+}
+
+fn remove_item(key: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(key);
+    }
+}
+
+/// Contents of a settings file of the app, kept in the page's local storage
+pub fn read_setting(name: &str) -> Option<Vec<u8>> {
+    read_item(&format!("{}{}", STORAGE_PREFIX, name)).map(String::into_bytes)
+}
+
+pub fn write_setting(name: &str, bytes: &[u8]) -> Result<(), String> {
+    let value = std::str::from_utf8(bytes).map_err(|e| e.to_string())?;
+    write_item(&format!("{}{}", STORAGE_PREFIX, name), value)
+}
+
+/// The browser has no keyring, secrets are kept in the page's local storage like the sessions
+/// of other web apps
+pub fn read_secret(account: &str) -> Result<Option<String>, String> {
+    Ok(read_item(&format!("{}secrets/{}", STORAGE_PREFIX, account)))
+}
+
+pub fn write_secret(account: &str, secret: &str) -> Result<(), String> {
+    write_item(&format!("{}secrets/{}", STORAGE_PREFIX, account), secret)
+}
+
+pub fn delete_secret(account: &str) -> Result<(), String> {
+    remove_item(&format!("{}secrets/{}", STORAGE_PREFIX, account));
+    Ok(())
+}
+
+/// Key-value database in the page's local storage. Values have to be UTF-8, the app only
+/// keeps JSON in it.
+pub struct LocalDb {
+    prefix: String,
+}
+
+impl LocalDb {
+    pub fn open(name: &str) -> Option<Self> {
+        local_storage()?;
+        Some(Self { prefix: format!("{}{}/", STORAGE_PREFIX, name) })
+    }
+
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        read_item(&format!("{}{}", self.prefix, key)).map(String::into_bytes)
+    }
+
+    pub fn insert(&self, key: &str, value: Vec<u8>) -> Result<(), String> {
+        let value = String::from_utf8(value).map_err(|e| e.to_string())?;
+        write_item(&format!("{}{}", self.prefix, key), &value)
+    }
+
+    pub fn remove(&self, key: &str) {
+        remove_item(&format!("{}{}", self.prefix, key));
+    }
+
+    /// Values of every key starting with `prefix`
+    pub fn scan_prefix(&self, prefix: &str) -> Vec<Vec<u8>> {
+        let Some(storage) = local_storage() else {
+            return Vec::new();
+        };
+        let prefix = format!("{}{}", self.prefix, prefix);
+        let length = storage.length().unwrap_or(0);
+        (0..length)
+            .filter_map(|index| storage.key(index).ok()?)
+            .filter(|key| key.starts_with(&prefix))
+            .filter_map(|key| storage.get_item(&key).ok()?)
+            .map(String::into_bytes)
+            .collect()
+    }
+}
+
+/// Pasting images needs the clipboard permission of the page, which the browser build doesn't ask for
+pub fn clipboard_image() -> Result<image::RgbaImage, String> {
+    Err(t!("tasks-clipboard-no-image"))
+}
@@ -0,0 +1,181 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiContextPass, egui};
+use chrono::{DateTime, Local, Utc};
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+use uuid::Uuid;
+use crate::api::stats::{ProjectStats, StatsApi};
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use crate::app::state::AppState;
+use crate::auth::AuthState;
+use crate::pages::detail::{self, AnnotationState};
+
+/// Seconds between reads of the project stats while annotating, so saves of others show up too
+const REFRESH_INTERVAL_SECS: f64 = 60.0;
+
+/// Completion of the project in the top bar of the detail page: the task being worked on out
+/// of all tasks, what was annotated today and how many tasks were saved in this session.
+pub struct ProgressPlugin;
+
+impl Plugin for ProgressPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins(ApiTaskPlugin::<ProjectStats>::default())
+            .init_resource::<ProgressState>()
+            .add_systems(Update, (
+                refresh_progress_system,
+                process_progress_results,
+            ).run_if(in_state(AppState::Detail)))
+            .add_systems(
+                EguiContextPass,
+                progress_hud_ui_system.after(detail::ui_system).run_if(in_state(AppState::Detail)),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ProgressState {
+    pub stats: Option<ProjectStats>,
+    /// Project the stats are of
+    project_id: Option<Uuid>,
+    is_fetching: bool,
+    last_fetch: f64,
+    /// Session saves as of the last fetch, a new save refreshes the stats
+    fetched_session_saves: usize,
+}
+
+/// Tasks saved since the app started. Saves happen in blocking helpers outside of systems, so
+/// the set lives for the whole process instead of in a resource.
+fn session_saves() -> &'static Mutex<HashSet<Uuid>> {
+    static SESSION_SAVES: OnceLock<Mutex<HashSet<Uuid>>> = OnceLock::new();
+    SESSION_SAVES.get_or_init(Default::default)
+}
+
+/// Counts a task towards the session, saving it again doesn't count twice
+pub fn record_save(task_id: Uuid) {
+    if let Ok(mut saves) = session_saves().lock() {
+        saves.insert(task_id);
+    }
+}
+
+pub fn session_save_count() -> usize {
+    session_saves().lock().map(|saves| saves.len()).unwrap_or(0)
+}
+
+/// Local midnight, where "today" of the daily counts starts
+fn start_of_today() -> DateTime<Utc> {
+    let midnight = Local::now().date_naive().and_hms_opt(0, 0, 0).unwrap();
+    midnight
+        .and_local_timezone(Local)
+        .earliest()
+        .map_or_else(Utc::now, |midnight| midnight.with_timezone(&Utc))
+}
+
+fn refresh_progress_system(
+    time: Res<Time>,
+    auth_state: Res<AuthState>,
+    annotation_state: Res<AnnotationState>,
+    stats_tasks: Res<ApiTasks<ProjectStats>>,
+    mut progress: ResMut<ProgressState>,
+) {
+    let Some(project_id) = annotation_state.current_project_id else {
+        return;
+    };
+    if progress.project_id != Some(project_id) {
+        progress.project_id = Some(project_id);
+        progress.stats = None;
+        progress.last_fetch = f64::NEG_INFINITY;
+    }
+
+    let now = time.elapsed_secs_f64();
+    let saves = session_save_count();
+    if progress.is_fetching
+        || (saves == progress.fetched_session_saves && now - progress.last_fetch < REFRESH_INTERVAL_SECS)
+    {
+        return;
+    }
+    let Some(token) = auth_state.get_jwt() else {
+        return;
+    };
+
+    progress.is_fetching = true;
+    progress.last_fetch = now;
+    progress.fetched_session_saves = saves;
+    let token = token.clone();
+    stats_tasks.spawn(async move {
+        StatsApi::new()
+            .get_project_stats(&token, project_id, start_of_today())
+            .await
+            .map_err(|e| e.to_string())
+    });
+}
+
+fn process_progress_results(
+    mut succeeded: EventReader<ApiTaskSucceeded<ProjectStats>>,
+    mut failed: EventReader<ApiTaskFailed<ProjectStats>>,
+    mut progress: ResMut<ProgressState>,
+) {
+    for ApiTaskSucceeded(stats) in succeeded.read() {
+        progress.is_fetching = false;
+        progress.stats = Some(stats.clone());
+    }
+
+    for failure in failed.read() {
+        // Keep showing the last stats, the next refresh tries again
+        debug!("Failed to fetch project stats: {}", failure.error);
+        progress.is_fetching = false;
+    }
+}
+
+/// 1800 as "1,800"
+fn format_count(count: i64) -> String {
+    let digits = count.unsigned_abs().to_string();
+    let mut formatted = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    if count < 0 {
+        formatted.insert(0, '-');
+    }
+    formatted
+}
+
+/// Drawn over the middle of the top bar, which the page links and log out button leave free
+fn progress_hud_ui_system(mut contexts: EguiContexts, progress: Res<ProgressState>) {
+    let Some(stats) = &progress.stats else {
+        return;
+    };
+    if stats.total_tasks == 0 {
+        return;
+    }
+
+    let current = (stats.annotated_tasks + 1).min(stats.total_tasks);
+    let today_percent = stats.annotated_today as f64 / stats.total_tasks as f64 * 100.0;
+    let session = session_save_count();
+
+    egui::Area::new(egui::Id::new("progress_hud"))
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 4.0])
+        .order(egui::Order::Foreground)
+        .interactable(true)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(t!(
+                    "progress-task",
+                    current = format_count(current),
+                    total = format_count(stats.total_tasks),
+                    percent = format!("{:.1}", today_percent),
+                ))
+                .on_hover_text(t!(
+                    "progress-details",
+                    annotated = format_count(stats.annotated_tasks),
+                    completed = format_count(stats.completed_tasks),
+                    today = format_count(stats.annotated_today),
+                    mine = format_count(stats.annotated_today_by_me),
+                ));
+                ui.weak(t!("progress-session", count = session));
+            });
+        });
+}
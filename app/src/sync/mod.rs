@@ -1,21 +1,23 @@
 use bevy::prelude::*;
-use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use serde::Serialize;
 use uuid::Uuid;
-use std::sync::mpsc::{channel, Receiver, Sender};
-use std::sync::Mutex;
-use crate::api::sync::{SyncApi, SyncRequest as ApiSyncRequest};
+use crate::api::sync::{SyncApi, SyncRequest as ApiSyncRequest, SyncRun};
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use crate::auth::AuthState;
+
+/// Seconds between two looks at the progress of the running sync
+const POLL_INTERVAL_SECS: f64 = 1.0;
+
+/// Files the app syncs into tasks
+pub const SYNC_FILE_EXTENSIONS: [&str; 3] = ["jpg", "jpeg", "png"];
 
 pub struct SyncPlugin;
 
 impl Plugin for SyncPlugin {
     fn build(&self, app: &mut App) {
-        let (tx, rx) = channel::<SyncResult>();
-        
         app
+            .add_plugins(ApiTaskPlugin::<SyncRun>::default())
             .init_resource::<SyncState>()
-            .insert_resource(SyncChannelSender(Mutex::new(tx)))
-            .insert_resource(SyncChannelReceiver(Mutex::new(rx)))
             .add_event::<SyncRequestEvent>()
             .add_event::<SyncStartedEvent>()
             .add_event::<SyncProgressEvent>()
@@ -23,16 +25,36 @@ impl Plugin for SyncPlugin {
             .add_event::<SyncErrorEvent>()
             .add_systems(Update, (
                 handle_sync_requests,
+                poll_sync_status,
                 process_sync_results,
             ));
     }
 }
 
+/// The sync started from the app. It runs on the server, which is asked for its progress
+/// every `POLL_INTERVAL_SECS` until it finishes.
 #[derive(Resource, Default)]
 pub struct SyncState {
     pub is_syncing: bool,
     pub current_sync_id: Option<Uuid>,
     pub progress: Option<SyncProgress>,
+    project_id: Option<Uuid>,
+    /// A status request is on its way
+    is_polling: bool,
+    last_poll: f64,
+}
+
+impl SyncState {
+    /// Follows a run that was already going, like one started before the app was opened
+    pub fn follow(&mut self, project_id: Uuid, run: &SyncRun) {
+        if self.is_syncing || !run.is_running() {
+            return;
+        }
+        self.is_syncing = true;
+        self.project_id = Some(project_id);
+        self.current_sync_id = Some(run.sync_id);
+        self.progress = Some(SyncProgress::from(run));
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -44,18 +66,15 @@ pub struct SyncProgress {
     pub tasks_skipped: usize,
 }
 
-#[derive(Resource)]
-pub struct SyncChannelSender(Mutex<Sender<SyncResult>>);
-
-#[derive(Resource)]
-pub struct SyncChannelReceiver(Mutex<Receiver<SyncResult>>);
-
-#[allow(dead_code)]
-enum SyncResult {
-    Started { sync_id: Uuid },
-    Progress { sync_id: Uuid, progress: SyncProgress },
-    Completed { response: SyncResponse },
-    Error { error: String },
+impl From<&SyncRun> for SyncProgress {
+    fn from(run: &SyncRun) -> Self {
+        Self {
+            total_files: run.total_files,
+            processed_files: run.processed_files,
+            tasks_created: run.tasks_created,
+            tasks_skipped: run.tasks_skipped,
+        }
+    }
 }
 
 #[derive(Event)]
@@ -82,7 +101,7 @@ pub struct SyncProgressEvent {
 #[allow(dead_code)]
 pub struct SyncCompletedEvent {
     pub sync_id: Uuid,
-    pub response: SyncResponse,
+    pub response: SyncRun,
 }
 
 #[derive(Event)]
@@ -95,62 +114,32 @@ pub struct SyncRequest {
     pub prefix: Option<String>,
     pub file_extensions: Option<Vec<String>>,
     pub overwrite_existing: Option<bool>,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-#[allow(dead_code)]
-pub struct SyncResponse {
-    pub sync_id: Uuid,
-    pub total_files: usize,
-    pub tasks_created: usize,
-    pub tasks_skipped: usize,
-    pub errors: Vec<String>,
-    pub started_at: DateTime<Utc>,
-    pub completed_at: DateTime<Utc>,
+    pub skip_duplicates: Option<bool>,
 }
 
 
 fn handle_sync_requests(
     mut sync_requests: EventReader<SyncRequestEvent>,
-    sender: Res<SyncChannelSender>,
+    sync_tasks: Res<ApiTasks<SyncRun>>,
     mut sync_state: ResMut<SyncState>,
+    mut error_events: EventWriter<SyncErrorEvent>,
 ) {
     for request_event in sync_requests.read() {
         if sync_state.is_syncing {
-            if let Ok(tx) = sender.0.lock() {
-                let _ = tx.send(SyncResult::Error {
-                    error: "Sync already in progress".to_string(),
-                });
-            }
+            error_events.write(SyncErrorEvent {
+                error: "Sync already in progress".to_string(),
+            });
             continue;
         }
-        
+
         sync_state.is_syncing = true;
-        
+        sync_state.project_id = Some(request_event.project_id);
+
         let project_id = request_event.project_id;
         let request = request_event.request.clone();
         let token = request_event.token.clone();
-        let api_base_url = std::env::var("API_BASE_URL")
-            .unwrap_or_else(|_| "http://localhost:8080".to_string());
-        
-        if let Ok(tx) = sender.0.lock() {
-            let tx = tx.clone();
-            
-            std::thread::spawn(move || {
-                let runtime = tokio::runtime::Runtime::new().unwrap();
-                runtime.block_on(async {
-                    let result = execute_sync(project_id, request, token, api_base_url).await;
-                    match result {
-                        Ok(response) => {
-                            let _ = tx.send(SyncResult::Completed { response });
-                        }
-                        Err(error) => {
-                            let _ = tx.send(SyncResult::Error { error });
-                        }
-                    }
-                });
-            });
-        }
+
+        sync_tasks.spawn(execute_sync(project_id, request, token));
     }
 }
 
@@ -158,70 +147,87 @@ async fn execute_sync(
     project_id: Uuid,
     request: SyncRequest,
     token: String,
-    _api_base_url: String,
-) -> Result<SyncResponse, String> {
+) -> Result<SyncRun, String> {
     let sync_api = SyncApi::new();
-    
+
     // Convert from local SyncRequest to API SyncRequest
     let api_request = ApiSyncRequest {
         prefix: request.prefix,
         file_extensions: request.file_extensions,
         overwrite_existing: request.overwrite_existing,
+        skip_duplicates: request.skip_duplicates,
     };
-    
-    let api_response = sync_api.start_sync(&token, project_id, &api_request).await
-        .map_err(|e| e.to_string())?;
-    
-    // Convert from API SyncResponse to local SyncResponse
-    let local_response = SyncResponse {
-        sync_id: api_response.sync_id,
-        total_files: api_response.total_files,
-        tasks_created: api_response.tasks_created,
-        tasks_skipped: api_response.tasks_skipped,
-        errors: api_response.errors,
-        started_at: api_response.started_at,
-        completed_at: api_response.completed_at,
-    };
-    
-    Ok(local_response)
+
+    sync_api.start_sync(&token, project_id, &api_request).await
+        .map_err(|e| e.to_string())
 }
 
+fn poll_sync_status(
+    time: Res<Time>,
+    auth_state: Res<AuthState>,
+    sync_tasks: Res<ApiTasks<SyncRun>>,
+    mut sync_state: ResMut<SyncState>,
+) {
+    let (Some(project_id), Some(sync_id)) = (sync_state.project_id, sync_state.current_sync_id) else {
+        return;
+    };
+    let now = time.elapsed_secs_f64();
+    if sync_state.is_polling || now - sync_state.last_poll < POLL_INTERVAL_SECS {
+        return;
+    }
+    let Some(jwt) = auth_state.get_jwt() else {
+        return;
+    };
+
+    sync_state.is_polling = true;
+    sync_state.last_poll = now;
+    let jwt = jwt.clone();
+    sync_tasks.spawn(async move {
+        SyncApi::new()
+            .get_sync_status(&jwt, project_id, sync_id)
+            .await
+            .map_err(|e| e.to_string())
+    });
+}
 
 fn process_sync_results(
-    receiver: Res<SyncChannelReceiver>,
+    time: Res<Time>,
+    mut succeeded: EventReader<ApiTaskSucceeded<SyncRun>>,
+    mut failed: EventReader<ApiTaskFailed<SyncRun>>,
     mut sync_state: ResMut<SyncState>,
     mut started_events: EventWriter<SyncStartedEvent>,
     mut progress_events: EventWriter<SyncProgressEvent>,
     mut completed_events: EventWriter<SyncCompletedEvent>,
     mut error_events: EventWriter<SyncErrorEvent>,
 ) {
-    if let Ok(rx) = receiver.0.lock() {
-        while let Ok(result) = rx.try_recv() {
-            match result {
-                SyncResult::Started { sync_id } => {
-                    sync_state.current_sync_id = Some(sync_id);
-                    started_events.write(SyncStartedEvent { sync_id });
-                }
-                SyncResult::Progress { sync_id, progress } => {
-                    sync_state.progress = Some(progress.clone());
-                    progress_events.write(SyncProgressEvent { sync_id, progress });
-                }
-                SyncResult::Completed { response } => {
-                    sync_state.is_syncing = false;
-                    sync_state.current_sync_id = None;
-                    sync_state.progress = None;
-                    completed_events.write(SyncCompletedEvent {
-                        sync_id: response.sync_id,
-                        response,
-                    });
-                }
-                SyncResult::Error { error } => {
-                    sync_state.is_syncing = false;
-                    sync_state.current_sync_id = None;
-                    sync_state.progress = None;
-                    error_events.write(SyncErrorEvent { error });
-                }
+    for ApiTaskSucceeded(run) in succeeded.read() {
+        sync_state.is_polling = false;
+
+        if run.is_running() {
+            if sync_state.current_sync_id.is_none() {
+                sync_state.current_sync_id = Some(run.sync_id);
+                sync_state.last_poll = time.elapsed_secs_f64();
+                started_events.write(SyncStartedEvent { sync_id: run.sync_id });
             }
+            let progress = SyncProgress::from(run);
+            sync_state.progress = Some(progress.clone());
+            progress_events.write(SyncProgressEvent { sync_id: run.sync_id, progress });
+            continue;
+        }
+
+        *sync_state = SyncState::default();
+        if run.status == "failed" {
+            error_events.write(SyncErrorEvent { error: run.errors.join(", ") });
+        } else {
+            completed_events.write(SyncCompletedEvent {
+                sync_id: run.sync_id,
+                response: run.clone(),
+            });
         }
     }
-}
\ No newline at end of file
+
+    for failure in failed.read() {
+        *sync_state = SyncState::default();
+        error_events.write(SyncErrorEvent { error: failure.error.clone() });
+    }
+}
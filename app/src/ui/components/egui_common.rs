@@ -1,4 +1,5 @@
 use crate::app::state::AppState;
+use crate::onboarding::{self, TourTarget};
 use bevy::prelude::*;
 use bevy_egui::{EguiContexts, egui};
 
@@ -7,39 +8,75 @@ pub fn ui_top_panel(
     current_state: Res<State<AppState>>,
     next_state: &mut ResMut<NextState<AppState>>,
 ) {
-    egui::TopBottomPanel::top("top_panel").show(contexts.ctx_mut(), |ui| {
+    let panel = egui::TopBottomPanel::top("top_panel").show(contexts.ctx_mut(), |ui| {
         egui::menu::bar(ui, |ui| {
             egui::widgets::global_theme_preference_switch(ui);
 
             ui.separator();
 
             if ui
-                .selectable_label(*current_state == AppState::Projects, "📁 Projects")
+                .selectable_label(*current_state == AppState::Projects, t!("nav-projects"))
                 .clicked()
             {
                 next_state.set(AppState::Projects)
             }
 
             if ui
-                .selectable_label(*current_state == AppState::ProjectSettings, "🔧 Settings")
+                .selectable_label(*current_state == AppState::ProjectSettings, t!("nav-settings"))
                 .clicked()
             {
                 next_state.set(AppState::ProjectSettings)
             }
 
             if ui
-                .selectable_label(*current_state == AppState::Tasks, "✨ Tasks")
+                .selectable_label(*current_state == AppState::Tasks, t!("nav-tasks"))
                 .clicked()
             {
                 next_state.set(AppState::Tasks)
             }
 
             if ui
-                .selectable_label(*current_state == AppState::Detail, "🕑 Detail")
+                .selectable_label(*current_state == AppState::Detail, t!("nav-detail"))
                 .clicked()
             {
                 next_state.set(AppState::Detail)
             }
+
+            if ui
+                .selectable_label(*current_state == AppState::Reports, t!("nav-reports"))
+                .clicked()
+            {
+                next_state.set(AppState::Reports)
+            }
+
+            if ui
+                .selectable_label(*current_state == AppState::Stats, t!("nav-stats"))
+                .clicked()
+            {
+                next_state.set(AppState::Stats)
+            }
+
+            if ui
+                .selectable_label(*current_state == AppState::Review, t!("nav-review"))
+                .clicked()
+            {
+                next_state.set(AppState::Review)
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                // The login page ends the session and forgets the remembered one
+                if ui.button(t!("nav-log-out")).clicked() {
+                    next_state.set(AppState::Login)
+                }
+
+                if ui
+                    .selectable_label(*current_state == AppState::Profile, t!("nav-profile"))
+                    .clicked()
+                {
+                    next_state.set(AppState::Profile)
+                }
+            });
         });
     });
+    onboarding::mark_target(contexts.ctx_mut(), TourTarget::Toolbar, panel.response.rect);
 }
@@ -2,10 +2,26 @@ use bevy::prelude::*;
 use bevy_egui::egui::scroll_area::ScrollBarVisibility;
 use bevy_egui::{EguiContexts, egui};
 use crate::core::rectangle::{Rectangle, rect_color};
+use crate::core::commands::{Command, CommandHistory};
+use crate::core::coordinates;
+use crate::core::drawing_aids::DrawingAids;
+use crate::core::input_settings::{self, InputSettings, PanButton, SelectModifier};
+use crate::core::interactions::GroupHandler;
+use crate::core::layers::LayerState;
+use crate::core::masks::{self, MaskPainting, MaskTool};
+use crate::core::shortcuts::{self, ANNOTATION_MODES, FIXED_SHORTCUTS};
 use crate::pages::detail::{
-    AnnotationState, AnnotationCategory, annotation_client, BoundingBox,
+    AnnotationState, AnnotationCategory, annotation_client, BoundingBox, ClassificationState, Comment, CommentsChange,
+    CommentsState, EditorRequests, PendingMerge, SavePurpose, SavedHotkeys, ShortcutState, TaskFlag, TaskFlagState, TaskLabels,
+    VideoState, ViewAdjustments, DEFAULT_WINDOW_LEVEL, DEFAULT_WINDOW_WIDTH,
 };
-use crate::auth::{AuthState, UserState, ProjectsState};
+use crate::api::comments::CreateCommentRequest;
+use crate::api::task::ApiTasks;
+use crate::api::tasks::{FLAG_REASONS, TaskWithResolvedUrl, TasksApi, flag_reason_label};
+use crate::i18n;
+use crate::onboarding::{self, TourTarget};
+use crate::api::categories::{AttributeType, CategoryHotkey, child_categories};
+use crate::auth::{AuthState, UserState};
 use uuid;
 
 #[derive(Resource)]
@@ -15,6 +31,72 @@ pub struct NextTaskMarker {
     pub project_id: uuid::Uuid,
 }
 
+/// Switches the detail page to `next_task` of the project
+pub fn open_task(
+    commands: &mut Commands,
+    annotation_state: &mut AnnotationState,
+    project_id: uuid::Uuid,
+    next_task: TaskWithResolvedUrl,
+) {
+    // Update Parameters resource and trigger page reload
+    if let Some(url) = next_task.resolved_resource_url {
+        info!("Setting up next task with URL: {}", url);
+        let task_id = uuid::Uuid::parse_str(&next_task.task.id).ok();
+        commands.insert_resource(crate::pages::detail::Parameters {
+            url: url.clone(),
+            task_id,
+            project_id: Some(project_id),
+        });
+        info!("Setting next task marker for reload");
+        // Set a marker to reload on next frame
+        annotation_state.current_task_id = task_id;
+        annotation_state.current_project_id = Some(project_id);
+        annotation_state.current_task_name = Some(next_task.task.name.clone());
+        annotation_state.image_url = Some(url.clone());
+
+        // Use a temporary transition to force reload
+        commands.insert_resource(NextTaskMarker { url, task_id, project_id });
+        annotation_state.is_loading_next_task = true;
+
+        info!("Marked for next task reload");
+    } else {
+        info!("Next task has no resolved_resource_url");
+        annotation_state.is_loading_next_task = false;
+    }
+}
+
+/// Random unannotated task of a project, `None` once none is left
+pub struct NextTask {
+    pub project_id: uuid::Uuid,
+    pub task: Option<TaskWithResolvedUrl>,
+}
+
+/// Looks for a random unannotated task of the project, which `detail::next_task_system` switches
+/// the detail page to.
+pub fn open_next_task(
+    next_tasks: &ApiTasks<NextTask>,
+    annotation_state: &mut AnnotationState,
+    token: &str,
+    project_id: uuid::Uuid,
+) {
+    annotation_state.is_loading_next_task = true;
+    let token = token.to_string();
+    next_tasks.spawn(async move {
+        let task = TasksApi::new().get_next_random_unannotated_task(&token, &project_id.to_string()).await
+            .map_err(|e| e.to_string())?;
+        Ok(NextTask { project_id, task })
+    });
+}
+
+/// Stands in for the editor until the image of its task is there
+pub fn render_task_loading(contexts: &mut EguiContexts) {
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        ui.centered_and_justified(|ui| {
+            ui.label(t!("detail-loading-task"));
+        });
+    });
+}
+
 pub fn render_rectangle_list(
     ui: &mut egui::Ui,
     rectangles: &mut Vec<Rectangle>,
@@ -24,29 +106,29 @@ pub fn render_rectangle_list(
     
     // Add sorting buttons
     ui.horizontal(|ui| {
-        ui.label("Sort by:");
-        if ui.button("X Asc").clicked() {
+        ui.label(t!("detail-sort-by"));
+        if ui.button(t!("detail-sort-x-asc")).clicked() {
             rectangles.sort_by(|a, b| {
                 let a_x = a.position.0.x.min(a.position.1.x);
                 let b_x = b.position.0.x.min(b.position.1.x);
                 a_x.partial_cmp(&b_x).unwrap()
             });
         }
-        if ui.button("X Desc").clicked() {
+        if ui.button(t!("detail-sort-x-desc")).clicked() {
             rectangles.sort_by(|a, b| {
                 let a_x = a.position.0.x.min(a.position.1.x);
                 let b_x = b.position.0.x.min(b.position.1.x);
                 b_x.partial_cmp(&a_x).unwrap()
             });
         }
-        if ui.button("Y Asc").clicked() {
+        if ui.button(t!("detail-sort-y-asc")).clicked() {
             rectangles.sort_by(|a, b| {
                 let a_y = a.position.0.y.min(a.position.1.y);
                 let b_y = b.position.0.y.min(b.position.1.y);
                 a_y.partial_cmp(&b_y).unwrap()
             });
         }
-        if ui.button("Y Desc").clicked() {
+        if ui.button(t!("detail-sort-y-desc")).clicked() {
             rectangles.sort_by(|a, b| {
                 let a_y = a.position.0.y.min(a.position.1.y);
                 let b_y = b.position.0.y.min(b.position.1.y);
@@ -95,7 +177,10 @@ pub fn render_rectangle_list(
                                 );
                                 ui.add_space(8.0);
                                 
-                                let item = format!("element {index}");
+                                let item = match rect.suggestion_score {
+                                    Some(score) => t!("detail-element-suggestion", index = index, percent = (score * 100.0).round() as i64),
+                                    None => t!("detail-element", index = index),
+                                };
                                 if ui.selectable_label(is_selected, item).clicked() {
                                     new_selected = Some(index);
                                 }
@@ -169,98 +254,95 @@ pub fn render_rectangle_list(
     new_selected
 }
 
+/// Decimals of the box inspector, the sub-pixel precision boxes are saved with
+const EDITOR_DECIMALS: usize = 3;
+
 pub fn render_rectangle_editor(
     ui: &mut egui::Ui,
     rectangles: &mut [Rectangle],
     selected_index: Option<usize>,
+    image_dimensions: Vec2,
 ) {
     if let Some(index) = selected_index {
         if let Some(rectangle) = rectangles.get_mut(index) {
-            ui.label(format!("element {}", index));
-            ui.label(format!("Class: {}", rectangle.class));
+            ui.label(t!("detail-element", index = index));
+            ui.label(t!("detail-class", class = rectangle.class));
 
-            let (bottom_left, top_right) = &mut rectangle.position;
             ui.separator();
 
+            // Image pixels from the top left corner, the same values the COCO export writes
+            let [mut x, mut y, mut width, mut height] = coordinates::rectangle_to_bbox(rectangle, image_dimensions);
             let mut changed = false;
 
-            ui.label("Max Position:");
-            let mut x = top_right.x;
-            let mut y = top_right.y;
-
-            ui.horizontal(|ui| {
+            egui::Grid::new("box_inspector").num_columns(4).show(ui, |ui| {
                 ui.label("X:");
-                if ui.add(egui::DragValue::new(&mut x).speed(1.0)).changed() {
-                    top_right.x = x;
-                    changed = true;
-                }
-            });
-
-            ui.horizontal(|ui| {
+                changed |= ui.add(egui::DragValue::new(&mut x).speed(1.0).max_decimals(EDITOR_DECIMALS)).changed();
                 ui.label("Y:");
-                if ui.add(egui::DragValue::new(&mut y).speed(1.0)).changed() {
-                    top_right.y = y;
-                    changed = true;
-                }
+                changed |= ui.add(egui::DragValue::new(&mut y).speed(1.0).max_decimals(EDITOR_DECIMALS)).changed();
+                ui.end_row();
+
+                ui.label("W:");
+                changed |= ui.add(egui::DragValue::new(&mut width).speed(1.0).max_decimals(EDITOR_DECIMALS).range(1.0..=f64::MAX)).changed();
+                ui.label("H:");
+                changed |= ui.add(egui::DragValue::new(&mut height).speed(1.0).max_decimals(EDITOR_DECIMALS).range(1.0..=f64::MAX)).changed();
+                ui.end_row();
             });
+            ui.weak(t!("detail-nudge-hint"));
+            if rectangle.rotation != 0.0 {
+                ui.weak(t!("detail-rotation-hint"));
+            }
 
-            ui.separator();
+            if changed {
+                rectangle.position = coordinates::bbox_to_rectangle(&[x, y, width, height], rectangle.class, image_dimensions).position;
+            }
 
-            ui.label("Min Position:");
-            let mut x = bottom_left.x;
-            let mut y = bottom_left.y;
+            ui.separator();
 
             ui.horizontal(|ui| {
-                ui.label("X:");
-                if ui.add(egui::DragValue::new(&mut x).speed(1.0)).changed() {
-                    bottom_left.x = x;
-                    changed = true;
+                ui.label(t!("detail-rotation"));
+                let mut degrees = rectangle.rotation.to_degrees();
+                if ui.add(egui::DragValue::new(&mut degrees).speed(1.0).range(-180.0..=180.0).suffix("°")).changed() {
+                    rectangle.rotation = degrees.to_radians();
+                    rectangle.interpolated = false;
                 }
             });
 
-            ui.horizontal(|ui| {
-                ui.label("Y:");
-                if ui.add(egui::DragValue::new(&mut y).speed(1.0)).changed() {
-                    bottom_left.y = y;
-                    changed = true;
-                }
-            });
+            if rectangle.interpolated {
+                ui.weak(t!("detail-interpolated-hint"));
+            }
 
             if changed {
                 rectangle.normalize_position();
+                rectangle.interpolated = false;
             }
         } else {
-            ui.label("Selected rectangle not found");
+            ui.label(t!("detail-selected-not-found"));
         }
     } else {
-        ui.label("No rectangle selected");
+        ui.label(t!("detail-nothing-selected"));
     }
 }
 
 
-#[allow(clippy::too_many_arguments)]
+/// Categories of the task and the save buttons. Saves and reloads are only asked for here,
+/// `detail::save_annotations_system` and `detail::reload_annotations_system` start them.
 pub fn render_annotation_controls(
     ui: &mut egui::Ui,
-    rectangles: &mut Vec<Rectangle>,
     annotation_state: &mut AnnotationState,
-    auth_state: &AuthState,
     _user_state: &UserState,
-    _projects_state: &ProjectsState,
-    image_dimensions: Vec2,
-    commands: Option<&mut Commands>,
-    next_state: Option<&mut NextState<crate::app::state::AppState>>,
+    video_state: &VideoState,
 ) {
     ui.group(|ui| {
         ui.vertical_centered(|ui| {
-            ui.heading("Annotations");
+            ui.heading(t!("detail-annotations"));
         });
         
         ui.separator();
         
         // Category management
-        ui.collapsing("Categories", |ui| {
+        ui.collapsing(t!("detail-categories"), |ui| {
             // Show existing categories
-            ui.label("Available Categories:");
+            ui.label(t!("detail-available-categories"));
             for category in &annotation_state.categories {
                 ui.horizontal(|ui| {
                     if let Some(color) = &category.color {
@@ -290,15 +372,15 @@ pub fn render_annotation_controls(
         
         // Show class to category mapping
         if !annotation_state.categories.is_empty() {
-            ui.collapsing("Class → Category Mapping", |ui| {
-                ui.label("Rectangle classes will map to categories as follows:");
+            ui.collapsing(t!("detail-class-mapping"), |ui| {
+                ui.label(t!("detail-class-mapping-hint"));
                 for class in 1..=9 {
                     if !annotation_state.categories.is_empty() {
                         let category_index = (class - 1) % annotation_state.categories.len();
                         let category = &annotation_state.categories[category_index];
                         
                         ui.horizontal(|ui| {
-                            ui.label(format!("Class {}: ", class));
+                            ui.label(t!("detail-class-number", class = class));
                             
                             // Show category color if available
                             if let Some(color) = &category.color {
@@ -330,185 +412,81 @@ pub fn render_annotation_controls(
         
         // Save/Load buttons
         ui.horizontal(|ui| {
-            if ui.button("💾 Save Annotations").clicked() {
-                if let Some(token) = &auth_state.jwt {
-                    if let (Some(project_id), Some(task_id)) = (annotation_state.current_project_id, annotation_state.current_task_id) {
-                        annotation_state.is_saving = true;
-                        let bounding_boxes = convert_rectangles_to_annotations(rectangles, &annotation_state.categories, image_dimensions);
-                        match annotation_client::save_annotations(project_id, task_id, bounding_boxes, token.clone()) {
-                            Ok(saved_annotations) => {
-                                annotation_state.is_saving = false;
-                                info!("Annotations saved successfully: {} annotations", saved_annotations.len());
-                            }
-                            Err(error) => {
-                                annotation_state.is_saving = false;
-                                error!("Failed to save annotations: {}", error);
-                            }
-                        }
-                    }
-                }
+            let idle = !annotation_state.is_saving && !annotation_state.is_loading_next_task;
+            let save_button = ui.add_enabled(idle, egui::Button::new(t!("detail-save-annotations")));
+            onboarding::mark_target(ui.ctx(), TourTarget::SaveButton, save_button.rect);
+            if save_button.clicked() {
+                annotation_state.save_requested = Some(SavePurpose::Manual);
             }
             
-            if ui.button("💾️ Save & Next Task").clicked() {
-                if let Some(token) = &auth_state.jwt {
-                    if let (Some(project_id), Some(task_id)) = (annotation_state.current_project_id, annotation_state.current_task_id) {
-                        annotation_state.is_saving = true;
-                        let bounding_boxes = convert_rectangles_to_annotations(rectangles, &annotation_state.categories, image_dimensions);
-                        match annotation_client::save_annotations(project_id, task_id, bounding_boxes, token.clone()) {
-                            Ok(saved_annotations) => {
-                                info!("Annotations saved successfully: {} annotations", saved_annotations.len());
-                                
-                                // Try to get next unannotated task
-                                annotation_state.is_loading_next_task = true;
-                                let tasks_api = crate::api::tasks::TasksApi::new();
-                                let rt = tokio::runtime::Runtime::new().unwrap();
-                                match rt.block_on(tasks_api.get_next_random_unannotated_task(token, &project_id.to_string())) {
-                                    Ok(Some(next_task)) => {
-                                        info!("Found next task: {}", next_task.task.name);
-                                        
-                                        // Update Parameters resource and trigger page reload
-                                        if let (Some(commands), Some(_next_state)) = (commands, next_state) {
-                                            info!("Commands and next_state are available");
-                                            if let Some(url) = next_task.resolved_resource_url {
-                                                info!("Setting up next task with URL: {}", url);
-                                                let task_id = uuid::Uuid::parse_str(&next_task.task.id).ok();
-                                                commands.insert_resource(crate::pages::detail::Parameters {
-                                                    url: url.clone(),
-                                                    task_id,
-                                                    project_id: Some(project_id),
-                                                });
-                                                info!("Setting next task marker for reload");
-                                                // Set a marker to reload on next frame
-                                                annotation_state.is_loading_next_task = false;
-                                                annotation_state.current_task_id = task_id;
-                                                annotation_state.current_project_id = Some(project_id);
-                                                annotation_state.current_task_name = Some(next_task.task.name.clone());
-                                                annotation_state.image_url = Some(url.clone());
-                                                
-                                                // Use a temporary transition to force reload
-                                                commands.insert_resource(NextTaskMarker { url, task_id, project_id });
-                                                
-                                                info!("Marked for next task reload");
-                                            } else {
-                                                info!("Next task has no resolved_resource_url");
-                                            }
-                                        } else {
-                                            info!("Commands or next_state not available");
-                                        }
-                                    }
-                                    Ok(None) => {
-                                        info!("No more unannotated tasks available");
-                                    }
-                                    Err(error) => {
-                                        error!("Failed to get next task: {}", error);
-                                    }
-                                }
-                                annotation_state.is_loading_next_task = false;
-                                annotation_state.is_saving = false;
-                            }
-                            Err(error) => {
-                                annotation_state.is_saving = false;
-                                error!("Failed to save annotations: {}", error);
-                            }
-                        }
-                    }
-                }
+            if ui.add_enabled(idle, egui::Button::new(t!("detail-save-next-task"))).clicked() {
+                annotation_state.save_requested = Some(SavePurpose::NextTask);
             }
             
-            if ui.button("🔄 Reload Annotations").clicked() {
-                if let Some(token) = &auth_state.jwt {
-                    if let (Some(project_id), Some(task_id)) = (annotation_state.current_project_id, annotation_state.current_task_id) {
-                        match annotation_client::load_annotations(project_id, task_id, token.clone(), true) {
-                            Ok(annotations) => {
-                                info!("Annotations loaded: {} annotations", annotations.len());
-                                info!("Loaded annotations JSON: {}", serde_json::to_string_pretty(&annotations).unwrap_or_else(|_| "Failed to serialize".to_string()));
-                                
-                                // Convert loaded annotations back to rectangles
-                                rectangles.clear();
-                                for annotation_with_category in &annotations {
-                                    // Convert MS COCO bbox [x, y, width, height] back to rectangle
-                                    if annotation_with_category.bbox.len() >= 4 {
-                                        let coco_x = annotation_with_category.bbox[0] as f32;
-                                        let coco_y = annotation_with_category.bbox[1] as f32;
-                                        let width = annotation_with_category.bbox[2] as f32;
-                                        let height = annotation_with_category.bbox[3] as f32;
-                                        
-                                        // Transform from COCO coordinates (top-left origin) to Bevy coordinates (center-origin)
-                                        let img_width = image_dimensions.x;
-                                        let img_height = image_dimensions.y;
-                                        
-                                        // Transform X: subtract half image width to shift origin from left edge to center
-                                        let bevy_min_x = coco_x - (img_width / 2.0);
-                                        let bevy_max_x = (coco_x + width) - (img_width / 2.0);
-                                        
-                                        // Transform Y: flip Y axis and shift origin from top edge to center
-                                        // COCO Y increases downward, Bevy Y increases upward
-                                        let bevy_max_y = (img_height / 2.0) - coco_y;  // coco_y (top) becomes max_y in Bevy
-                                        let bevy_min_y = (img_height / 2.0) - (coco_y + height);  // coco_y + height (bottom) becomes min_y in Bevy
-                                        
-                                        let pos1 = Vec2::new(bevy_min_x, bevy_min_y);
-                                        let pos2 = Vec2::new(bevy_max_x, bevy_max_y);
-                                        
-                                        // Extract class from metadata if available
-                                        let class = if let Some(class_value) = annotation_with_category.metadata.get("class") {
-                                            class_value.as_u64().unwrap_or(1) as usize
-                                        } else {
-                                            // Map category back to class (inverse of save mapping)
-                                            if let Some(cat_id) = annotation_with_category.category_id {
-                                                if let Some(category_index) = annotation_state.categories.iter().position(|c| c.id == cat_id) {
-                                                    (category_index % 9) + 1
-                                                } else {
-                                                    1 // Default to class 1 if category not found
-                                                }
-                                            } else {
-                                                1 // Default to class 1 if no category
-                                            }
-                                        };
-                                        
-                                        let rectangle = Rectangle {
-                                            position: (pos1, pos2),
-                                            class,
-                                        };
-                                        
-                                        rectangles.push(rectangle);
-                                    }
-                                }
-                                
-                                info!("Converted {} annotations to rectangles", rectangles.len());
-                            }
-                            Err(error) => {
-                                error!("Failed to load annotations: {}", error);
-                            }
-                        }
-                    }
-                }
+            if video_state.is_video() && ui.add_enabled(idle, egui::Button::new(t!("detail-save-interpolate"))).clicked() {
+                annotation_state.save_requested = Some(SavePurpose::Interpolate);
+            }
+
+            // Reloading would mix the boxes of all frames into the shown one
+            if ui.add_enabled(!video_state.is_video(), egui::Button::new(t!("detail-reload-annotations"))).clicked() {
+                annotation_state.reload_requested = true;
             }
         });
         
+        // Shown until a save follows the rules again
+        if !annotation_state.rule_violations.is_empty() {
+            ui.colored_label(egui::Color32::RED, t!("detail-rules-broken"));
+            for violation in &annotation_state.rule_violations {
+                ui.colored_label(egui::Color32::RED, format!("• {}", violation));
+            }
+        }
+
         if annotation_state.is_saving {
-            ui.label("⏳ Saving annotations...");
+            ui.label(t!("detail-saving"));
         }
         
         if annotation_state.is_loading_next_task {
-            ui.label("🔍 Loading next task...");
+            ui.label(t!("detail-loading-next-task"));
         }
     });
 }
 
+/// Boxes of the whole task. For videos the shown frame and every stashed frame are saved
+/// together, each box tagged with its frame index.
+pub fn collect_bounding_boxes(
+    rectangles: &[Rectangle],
+    video_state: &VideoState,
+    categories: &[AnnotationCategory],
+    image_dimensions: Vec2,
+) -> Vec<BoundingBox> {
+    let Some(current_frame_index) = video_state.current_frame_index() else {
+        return convert_rectangles_to_annotations(rectangles, categories, image_dimensions);
+    };
+
+    let frames = std::iter::once((current_frame_index, rectangles))
+        .chain(video_state.frame_rectangles.iter().map(|(index, rects)| (*index, rects.as_slice())));
+    let mut bounding_boxes = Vec::new();
+    for (frame_index, frame_rectangles) in frames {
+        for mut bounding_box in convert_rectangles_to_annotations(frame_rectangles, categories, image_dimensions) {
+            bounding_box.frame_index = Some(frame_index);
+            // Boxes drawn on the shown frame start a track of their own
+            bounding_box.track_id.get_or_insert_with(uuid::Uuid::new_v4);
+            bounding_boxes.push(bounding_box);
+        }
+    }
+    bounding_boxes
+}
+
 fn convert_rectangles_to_annotations(rectangles: &[Rectangle], categories: &[AnnotationCategory], image_dimensions: Vec2) -> Vec<BoundingBox> {
     let mut annotations = Vec::new();
     
-    // Image dimensions
-    let img_width = image_dimensions.x;
-    let img_height = image_dimensions.y;
-    
     for rect in rectangles {
         // Map class (1-9) to category
         // For now, use modulo to cycle through available categories
         // Or map class 1 -> category 0, class 2 -> category 1, etc.
-        let category_id = if !categories.is_empty() {
+        let category = if !categories.is_empty() {
             let category_index = (rect.class - 1) % categories.len();
-            categories[category_index].id
+            &categories[category_index]
         } else {
             // If no categories exist, we need to skip this annotation
             // or use a default UUID (this should ideally not happen)
@@ -516,29 +494,8 @@ fn convert_rectangles_to_annotations(rectangles: &[Rectangle], categories: &[Ann
             continue;
         };
         
-        // Get rectangle bounds in Bevy coordinates (center-origin)
-        let (pos1, pos2) = rect.position;
-        let min_x = pos1.x.min(pos2.x);
-        let min_y = pos1.y.min(pos2.y);
-        let max_x = pos1.x.max(pos2.x);
-        let max_y = pos1.y.max(pos2.y);
-        
-        // Transform from Bevy coordinates (center-origin) to COCO coordinates (top-left origin)
-        // In Bevy: (0,0) is at center, +Y is up, +X is right
-        // In COCO: (0,0) is at top-left, +Y is down, +X is right
-        
-        // Transform X: add half image width to shift origin from center to left edge
-        let coco_min_x = min_x + (img_width / 2.0);
-        let coco_max_x = max_x + (img_width / 2.0);
-        
-        // Transform Y: flip Y axis and shift origin from center to top edge
-        // Bevy Y increases upward, COCO Y increases downward
-        let coco_min_y = (img_height / 2.0) - max_y;  // max_y in Bevy becomes min_y in COCO
-        let coco_max_y = (img_height / 2.0) - min_y;  // min_y in Bevy becomes max_y in COCO
-        
-        // Calculate width and height
-        let width = coco_max_x - coco_min_x;
-        let height = coco_max_y - coco_min_y;
+        // COCO coordinates (top-left origin, +Y down) with sub-pixel precision
+        let [coco_min_x, coco_min_y, width, height] = coordinates::rectangle_to_bbox(rect, image_dimensions);
         
         // Ensure coordinates are non-negative
         if coco_min_x < 0.0 || coco_min_y < 0.0 {
@@ -546,31 +503,41 @@ fn convert_rectangles_to_annotations(rectangles: &[Rectangle], categories: &[Ann
             continue;
         }
         
-        let area = width * height;
+        let area = coordinates::subpixel(width * height);
+
+        // Drop values left over from a previous class whose schema no longer applies
+        let attributes: serde_json::Map<String, serde_json::Value> = rect.attributes.iter()
+            .filter(|(name, _)| category.attribute_schema.iter().any(|definition| &definition.name == *name))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
         
         annotations.push(BoundingBox {
-            category_id,
-            bbox: vec![coco_min_x as f64, coco_min_y as f64, width as f64, height as f64],
-            area: Some(area as f64),
+            category_id: category.id,
+            bbox: vec![coco_min_x, coco_min_y, width, height],
+            area: Some(area),
             iscrowd: Some(false),
+            is_prediction: rect.is_suggestion().then_some(true),
+            confidence: rect.suggestion_score.map(|score| score as f64),
+            attributes: Some(serde_json::Value::Object(attributes)),
+            // Bevy rotates counter-clockwise with +Y up, the API clockwise in image coordinates
+            rotation: (rect.rotation != 0.0).then(|| -(rect.rotation.to_degrees() as f64)),
+            frame_index: None,
+            track_id: rect.track_id,
+            is_interpolated: rect.interpolated.then_some(true),
+            mask: rect.mask.clone(),
         });
     }
     
     annotations
 }
 
-#[allow(clippy::too_many_arguments)]
 pub fn render_side_panels_with_annotations(
     contexts: &mut EguiContexts,
     rectangles: &mut Vec<Rectangle>,
     selected_index: &mut Option<usize>,
     annotation_state: &mut AnnotationState,
-    auth_state: &AuthState,
     user_state: &UserState,
-    projects_state: &ProjectsState,
-    image_dimensions: Vec2,
-    commands: Option<&mut Commands>,
-    next_state: Option<&mut NextState<crate::app::state::AppState>>,
+    video_state: &VideoState,
 ) {
     egui::SidePanel::left("left_panel")
         .resizable(true)
@@ -579,14 +546,9 @@ pub fn render_side_panels_with_annotations(
         .show(contexts.ctx_mut(), |ui| {
             render_annotation_controls(
                 ui,
-                rectangles,
                 annotation_state,
-                auth_state,
                 user_state,
-                projects_state,
-                image_dimensions,
-                commands,
-                next_state,
+                video_state,
             )
         });
 
@@ -596,7 +558,7 @@ pub fn render_side_panels_with_annotations(
         .width_range(80.0..=500.0)
         .show(contexts.ctx_mut(), |ui| {
             ui.vertical_centered(|ui| {
-                ui.heading("Right Panel");
+                ui.heading(t!("detail-right-panel"));
             });
 
             ui.vertical(|ui| {
@@ -613,14 +575,1303 @@ pub fn render_side_panels_with_annotations(
         });
 }
 
+/// Bottom bar of video and volume tasks for stepping through frames, with window/level for slices.
+/// The slider only jumps once it is released, since every frame change downloads an image.
+pub fn render_frame_scrubber(contexts: &mut EguiContexts, video_state: &mut VideoState) {
+    if !video_state.is_video() {
+        return;
+    }
+
+    egui::TopBottomPanel::bottom("frame_scrubber").show(contexts.ctx_mut(), |ui| {
+        let last = video_state.frames.len() - 1;
+        let current = video_state.current_frame;
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(current > 0, egui::Button::new("⏮")).clicked() {
+                video_state.pending_frame = Some(0);
+            }
+            if ui.add_enabled(current > 0, egui::Button::new(t!("detail-previous-frame"))).clicked() {
+                video_state.pending_frame = Some(current - 1);
+            }
+
+            let slider = ui.add(egui::Slider::new(&mut video_state.scrub_frame, 0..=last).show_value(false));
+            if slider.drag_stopped() || (slider.changed() && !slider.dragged()) {
+                video_state.pending_frame = Some(video_state.scrub_frame);
+            }
+
+            if ui.add_enabled(current < last, egui::Button::new(t!("detail-next-frame"))).clicked() {
+                video_state.pending_frame = Some(current + 1);
+            }
+
+            ui.checkbox(&mut video_state.carry_boxes, t!("detail-carry-boxes"))
+                .on_hover_text(t!("detail-carry-boxes-hint"));
+
+            let frame = &video_state.frames[current];
+            if video_state.frame_rate.is_some() {
+                ui.label(t!(
+                    "detail-frame",
+                    frame = current + 1,
+                    count = last + 1,
+                    seconds = format!("{:.1}", frame.timestamp_ms as f64 / 1000.0),
+                ));
+            } else {
+                ui.label(t!("detail-slice", slice = current + 1, count = last + 1));
+            }
+        });
+
+        if video_state.is_volume() {
+            ui.horizontal(|ui| {
+                ui.label(t!("detail-window"));
+                ui.add(egui::Slider::new(&mut video_state.window_width, 1.0..=255.0));
+                ui.label(t!("detail-level"));
+                ui.add(egui::Slider::new(&mut video_state.window_level, 0.0..=255.0));
+                if ui.button(t!("common-reset")).clicked() {
+                    video_state.window_width = DEFAULT_WINDOW_WIDTH;
+                    video_state.window_level = DEFAULT_WINDOW_LEVEL;
+                }
+            });
+        }
+
+        if let Some(error) = &video_state.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    });
+}
+
+pub fn render_suggestion_controls(
+    ui: &mut egui::Ui,
+    rectangles: &mut Vec<Rectangle>,
+    selected_index: &mut Option<usize>,
+    command_history: &mut CommandHistory,
+) {
+    let Some(index) = *selected_index else {
+        return;
+    };
+    let Some(score) = rectangles.get(index).and_then(|rect| rect.suggestion_score) else {
+        return;
+    };
+
+    ui.separator();
+    ui.label(t!("detail-suggestion", percent = (score * 100.0).round() as i64));
+    ui.horizontal(|ui| {
+        if ui.button(t!("detail-accept")).clicked() {
+            let old_rect = rectangles[index].clone();
+            let mut new_rect = old_rect.clone();
+            new_rect.suggestion_score = None;
+            let command = Command::ResizeRectangle { index, old_rect, new_rect };
+            command.execute(rectangles);
+            command_history.push(command);
+        }
+        if ui.button(t!("detail-delete")).clicked() {
+            let command = Command::DeleteRectangle { index, rectangle: rectangles[index].clone() };
+            command.execute(rectangles);
+            command_history.push(command);
+            *selected_index = None;
+        }
+    });
+}
+
+/// Widgets for the custom attributes defined by the selected rectangle's category
+pub fn render_attribute_editor(
+    ui: &mut egui::Ui,
+    rectangles: &mut [Rectangle],
+    selected_index: Option<usize>,
+    categories: &[AnnotationCategory],
+) {
+    let Some(rectangle) = selected_index.and_then(|index| rectangles.get_mut(index)) else {
+        return;
+    };
+    if categories.is_empty() {
+        return;
+    }
+    let category = &categories[rectangle.class.saturating_sub(1) % categories.len()];
+    if category.attribute_schema.is_empty() {
+        return;
+    }
+
+    ui.separator();
+    ui.label(t!("detail-attributes", category = category.name.as_str()));
+
+    for definition in &category.attribute_schema {
+        let current = rectangle.attributes.get(&definition.name).cloned()
+            .or_else(|| definition.default.clone());
+        let label = if definition.required {
+            format!("{} *", definition.name)
+        } else {
+            definition.name.clone()
+        };
+        let mut new_value = None;
+
+        ui.horizontal(|ui| {
+            ui.label(label);
+            match definition.attribute_type {
+                AttributeType::Bool => {
+                    let mut checked = current.as_ref().and_then(|value| value.as_bool()).unwrap_or(false);
+                    if ui.checkbox(&mut checked, "").changed() {
+                        new_value = Some(serde_json::Value::Bool(checked));
+                    }
+                }
+                AttributeType::Enum => {
+                    let selected = current.as_ref().and_then(|value| value.as_str()).unwrap_or("");
+                    egui::ComboBox::from_id_salt(("attribute", &definition.name))
+                        .selected_text(if selected.is_empty() { "—" } else { selected })
+                        .show_ui(ui, |ui| {
+                            for option in &definition.options {
+                                if ui.selectable_label(selected == option.as_str(), option).clicked() {
+                                    new_value = Some(serde_json::Value::String(option.clone()));
+                                }
+                            }
+                        });
+                }
+                AttributeType::String => {
+                    let mut text = current.as_ref().and_then(|value| value.as_str()).unwrap_or("").to_string();
+                    if ui.text_edit_singleline(&mut text).changed() {
+                        new_value = Some(serde_json::Value::String(text));
+                    }
+                }
+                AttributeType::Number => {
+                    let mut number = current.as_ref().and_then(|value| value.as_f64()).unwrap_or(0.0);
+                    if ui.add(egui::DragValue::new(&mut number).speed(0.1)).changed() {
+                        new_value = serde_json::Number::from_f64(number).map(serde_json::Value::Number);
+                    }
+                }
+            }
+        });
+
+        if let Some(value) = new_value {
+            rectangle.attributes.insert(definition.name.clone(), value);
+        }
+    }
+}
 
 #[allow(clippy::ptr_arg)]
 pub fn render_rectangle_editor_window(
     contexts: &mut EguiContexts,
     rectangles: &mut Vec<Rectangle>,
-    selected_index: Option<usize>,
+    selected_index: &mut Option<usize>,
+    command_history: &mut CommandHistory,
+    categories: &[AnnotationCategory],
+    image_dimensions: Vec2,
+    propagate_requested: &mut bool,
+) {
+    egui::Window::new(t!("detail-selected")).show(contexts.ctx_mut(), |ui| {
+        render_rectangle_editor(ui, rectangles, *selected_index, image_dimensions);
+        render_attribute_editor(ui, rectangles, *selected_index, categories);
+        render_suggestion_controls(ui, rectangles, selected_index, command_history);
+
+        if selected_index.is_some_and(|index| index < rectangles.len()) {
+            ui.separator();
+            if ui.button(t!("detail-propagate")).on_hover_text(t!("detail-propagate-hint")).clicked() {
+                *propagate_requested = true;
+            }
+        }
+    });
+}
+/// What to do with unsaved boxes when leaving the detail page
+pub enum UnsavedChangesChoice {
+    Save,
+    Discard,
+    Cancel,
+}
+
+/// Asks what to do with unsaved boxes before the page the user asked for is opened.
+pub fn render_unsaved_changes_dialog(contexts: &mut EguiContexts, error: Option<&str>) -> Option<UnsavedChangesChoice> {
+    let mut choice = None;
+    egui::Window::new(t!("detail-unsaved-title"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(t!("detail-unsaved-question"));
+            if let Some(error) = error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+            ui.horizontal(|ui| {
+                if ui.button(t!("detail-save-and-leave")).clicked() {
+                    choice = Some(UnsavedChangesChoice::Save);
+                }
+                if ui.button(t!("detail-discard-changes")).clicked() {
+                    choice = Some(UnsavedChangesChoice::Discard);
+                }
+                if ui.button(t!("common-cancel")).clicked() {
+                    choice = Some(UnsavedChangesChoice::Cancel);
+                }
+            });
+        });
+    choice
+}
+
+/// What to do with boxes both the user and someone else changed
+pub enum MergeConflictChoice {
+    /// Save the merge with the version picked of each box
+    Save,
+    /// Drop the user's changes and show the boxes on the server
+    KeepTheirs,
+}
+
+/// Lists the boxes someone else changed too since they were loaded, with both versions of each
+/// to pick from. Changes to other boxes are already merged.
+pub fn render_merge_conflict_dialog(
+    contexts: &mut EguiContexts,
+    pending: &mut PendingMerge,
+    categories: &[AnnotationCategory],
+) -> Option<MergeConflictChoice> {
+    let mut choice = None;
+    egui::Window::new(t!("detail-merge-title"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(t!("detail-merge-question", merged = pending.merge.merged.len(), conflicts = pending.merge.conflicts.len()));
+            egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                for (index, (conflict, keep_mine)) in pending.merge.conflicts.iter().zip(pending.keep_mine.iter_mut()).enumerate() {
+                    ui.separator();
+                    ui.strong(t!("detail-merge-conflict", number = index + 1));
+                    ui.radio_value(keep_mine, true, t!("detail-merge-mine", version = describe_merged_box(conflict.mine.as_ref(), categories).as_str()));
+                    ui.radio_value(keep_mine, false, t!("detail-merge-theirs", version = describe_merged_box(conflict.theirs.as_ref(), categories).as_str()));
+                }
+            });
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button(t!("detail-merge-save")).clicked() {
+                    choice = Some(MergeConflictChoice::Save);
+                }
+                if ui.button(t!("detail-merge-keep-theirs")).clicked() {
+                    choice = Some(MergeConflictChoice::KeepTheirs);
+                }
+            });
+        });
+    choice
+}
+
+/// One version of a conflicting box: its class, frame and place, or that it was deleted
+fn describe_merged_box(bounding_box: Option<&BoundingBox>, categories: &[AnnotationCategory]) -> String {
+    let Some(bounding_box) = bounding_box else {
+        return t!("detail-merge-deleted");
+    };
+    let category = categories.iter()
+        .find(|category| category.id == bounding_box.category_id)
+        .map_or("?", |category| category.name.as_str());
+    let [x, y, width, height] = bounding_box.bbox[..] else {
+        return category.to_string();
+    };
+    let described = t!("detail-merge-box", category = category, x = x.round(), y = y.round(), width = width.round(), height = height.round());
+    match bounding_box.frame_index {
+        Some(frame) => t!("detail-merge-box-on-frame", version = described.as_str(), frame = frame + 1),
+        None => described,
+    }
+}
+
+/// Auto-save switch with whether the boxes are saved, in the bottom right corner of the canvas
+pub fn render_save_status(contexts: &mut EguiContexts, enabled: &mut bool, unsaved: bool, error: Option<&str>) {
+    egui::Window::new("Save status")
+        .title_bar(false)
+        .resizable(false)
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-8.0, -8.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(enabled, t!("detail-auto-save"));
+                if let Some(error) = error {
+                    ui.colored_label(egui::Color32::RED, error);
+                } else if unsaved {
+                    ui.label(t!("detail-unsaved-changes"));
+                } else {
+                    ui.weak(t!("detail-all-saved"));
+                }
+            });
+        });
+}
+
+/// Class change and deletion for every box of a multi-box selection
+#[allow(clippy::ptr_arg)]
+pub fn render_group_window(
+    contexts: &mut EguiContexts,
+    group: &mut GroupHandler,
+    rectangles: &mut Vec<Rectangle>,
+    selected_index: &mut Option<usize>,
+    command_history: &mut CommandHistory,
+    categories: &[AnnotationCategory],
+    select_key: &str,
+) {
+    if group.indices.len() < 2 {
+        return;
+    }
+
+    egui::Window::new(t!("detail-selection")).show(contexts.ctx_mut(), |ui| {
+        ui.label(t!("detail-boxes-selected", count = group.indices.len()));
+        ui.weak(t!("detail-selection-hint", key = select_key));
+        ui.separator();
+
+        if !categories.is_empty() {
+            let classes: Vec<usize> = group.indices.iter()
+                .filter_map(|&index| rectangles.get(index).map(|rect| rect.class))
+                .collect();
+            let shared_class = classes.first().copied().filter(|first| classes.iter().all(|class| class == first));
+            let current = shared_class
+                .and_then(|class| categories.get(class.saturating_sub(1) % categories.len()))
+                .map_or(t!("detail-mixed"), |category| category.name.clone());
+
+            let mut new_class = None;
+            ui.horizontal(|ui| {
+                ui.label(t!("detail-class-field"));
+                egui::ComboBox::from_id_salt("group_class")
+                    .selected_text(current)
+                    .show_ui(ui, |ui| {
+                        for (index, category) in categories.iter().enumerate() {
+                            if ui.selectable_label(shared_class == Some(index + 1), &category.name).clicked() {
+                                new_class = Some(index + 1);
+                            }
+                        }
+                    });
+            });
+            if let Some(class) = new_class {
+                group.set_class(rectangles, *selected_index, class, command_history);
+            }
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button(t!("detail-delete-all")).clicked() {
+                group.delete(rectangles, selected_index, command_history);
+            }
+            if ui.button(t!("detail-clear-selection")).clicked() {
+                group.clear();
+            }
+        });
+    });
+}
+
+/// Class picker for projects with many categories: roots are grouped by supercategory,
+/// children nest under their parent, and a search box flattens the list.
+pub fn render_class_picker_window(
+    contexts: &mut EguiContexts,
+    categories: &[AnnotationCategory],
+    selected_class: &mut usize,
+    filter: &mut String,
 ) {
-    egui::Window::new("Selected").show(contexts.ctx_mut(), |ui| {
-        render_rectangle_editor(ui, rectangles, selected_index);
+    if categories.is_empty() {
+        return;
+    }
+
+    let window = egui::Window::new(t!("detail-classes")).default_width(220.0).show(contexts.ctx_mut(), |ui| {
+        if let Some(current) = categories.get((*selected_class).saturating_sub(1) % categories.len()) {
+            ui.label(t!("detail-current-class", class = current.name.as_str()));
+        }
+        ui.weak(t!("detail-shortcuts-hint"));
+        ui.horizontal(|ui| {
+            ui.label("🔍");
+            ui.text_edit_singleline(filter);
+        });
+        ui.separator();
+
+        egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+            let query = filter.trim().to_lowercase();
+            if !query.is_empty() {
+                for category in categories {
+                    let matches = category.name.to_lowercase().contains(&query)
+                        || category.supercategory.as_deref().is_some_and(|s| s.to_lowercase().contains(&query));
+                    if matches {
+                        render_class_button(ui, categories, category, selected_class);
+                    }
+                }
+                return;
+            }
+
+            let mut groups: Vec<(Option<&str>, Vec<&AnnotationCategory>)> = Vec::new();
+            for root in child_categories(categories, None) {
+                let group = root.supercategory.as_deref().filter(|s| !s.is_empty());
+                match groups.iter_mut().find(|(name, _)| *name == group) {
+                    Some((_, members)) => members.push(root),
+                    None => groups.push((group, vec![root])),
+                }
+            }
+
+            if groups.len() == 1 {
+                for root in &groups[0].1 {
+                    render_class_node(ui, categories, root, selected_class, 0);
+                }
+                return;
+            }
+
+            for (group, members) in &groups {
+                egui::CollapsingHeader::new(format!("{} ({})", group.map_or_else(|| t!("detail-other-group"), str::to_string), members.len()))
+                    .id_salt(("class_group", *group))
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        for root in members {
+                            render_class_node(ui, categories, root, selected_class, 0);
+                        }
+                    });
+            }
+        });
     });
-}
\ No newline at end of file
+    if let Some(window) = window {
+        onboarding::mark_target(contexts.ctx_mut(), TourTarget::ClassPicker, window.response.rect);
+    }
+}
+
+fn render_class_node(
+    ui: &mut egui::Ui,
+    categories: &[AnnotationCategory],
+    category: &AnnotationCategory,
+    selected_class: &mut usize,
+    depth: usize,
+) {
+    let children = child_categories(categories, Some(category.id));
+    // Cycles can't come from the API, but stop rather than recurse forever
+    if children.is_empty() || depth >= categories.len() {
+        render_class_button(ui, categories, category, selected_class);
+        return;
+    }
+
+    let id = ui.make_persistent_id(("class_node", category.id));
+    egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, false)
+        .show_header(ui, |ui| {
+            render_class_button(ui, categories, category, selected_class);
+        })
+        .body(|ui| {
+            for child in children {
+                render_class_node(ui, categories, child, selected_class, depth + 1);
+            }
+        });
+}
+
+fn render_class_button(
+    ui: &mut egui::Ui,
+    categories: &[AnnotationCategory],
+    category: &AnnotationCategory,
+    selected_class: &mut usize,
+) {
+    let Some(index) = categories.iter().position(|c| c.id == category.id) else {
+        return;
+    };
+    let class = index + 1;
+
+    ui.horizontal(|ui| {
+        let color: Color = rect_color(class).into();
+        let egui_color = egui::Color32::from_rgb(
+            (color.to_srgba().red * 255.0) as u8,
+            (color.to_srgba().green * 255.0) as u8,
+            (color.to_srgba().blue * 255.0) as u8,
+        );
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+        ui.painter().rect_filled(rect, 2.0, egui_color);
+
+        let label = match shortcuts::class_key_label(categories, class) {
+            Some(key) => format!("[{}] {}", key, category.name),
+            None => category.name.clone(),
+        };
+        if ui.selectable_label(*selected_class == class, label).clicked() {
+            *selected_class = class;
+        }
+    });
+}
+
+/// View adjustments of the shown image, collapsed until needed
+pub fn render_view_adjustments_window(contexts: &mut EguiContexts, adjustments: &mut ViewAdjustments) {
+    egui::Window::new(t!("detail-view"))
+        .default_open(false)
+        .resizable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            egui::Grid::new("view_adjustments").num_columns(2).show(ui, |ui| {
+                ui.label(t!("detail-brightness"));
+                ui.add(egui::Slider::new(&mut adjustments.brightness, -1.0..=1.0));
+                ui.end_row();
+
+                ui.label(t!("detail-contrast"));
+                ui.add(egui::Slider::new(&mut adjustments.contrast, 0.1..=4.0).logarithmic(true));
+                ui.end_row();
+
+                ui.label(t!("detail-gamma"));
+                ui.add(egui::Slider::new(&mut adjustments.gamma, 0.2..=5.0).logarithmic(true));
+                ui.end_row();
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut adjustments.grayscale, t!("detail-grayscale"));
+                if ui.add_enabled(!adjustments.is_identity(), egui::Button::new(t!("common-reset"))).clicked() {
+                    *adjustments = ViewAdjustments::default();
+                }
+            });
+            ui.weak(t!("detail-view-hint"));
+        });
+}
+
+/// Side of the loupe, in points, and how much more it magnifies than the canvas
+const LOUPE_SIZE: f32 = 140.0;
+const LOUPE_MAGNIFICATION: f32 = 4.0;
+
+/// Crosshair, snapping and loupe switches, collapsed until needed
+pub fn render_drawing_aids_window(contexts: &mut EguiContexts, drawing_aids: &mut DrawingAids) {
+    egui::Window::new(t!("detail-drawing-aids"))
+        .default_open(false)
+        .resizable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.checkbox(&mut drawing_aids.crosshair, t!("detail-crosshair"));
+            ui.checkbox(&mut drawing_aids.snapping, t!("detail-snapping"));
+            ui.checkbox(&mut drawing_aids.loupe, t!("detail-loupe"));
+        });
+}
+
+/// Mouse and trackpad settings, collapsed until needed. Returns whether they changed and are
+/// ready to be saved, which for sliders is once they are let go.
+pub fn render_input_settings_window(contexts: &mut EguiContexts, settings: &mut InputSettings) -> bool {
+    let mut changed = false;
+    egui::Window::new(t!("input-settings"))
+        .default_open(false)
+        .resizable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(t!("input-grab-radius"));
+                let slider = ui.add(egui::Slider::new(&mut settings.grab_radius, input_settings::GRAB_RADII).suffix(" px"));
+                changed |= slider.drag_stopped() || (slider.changed() && !slider.dragged());
+            });
+            ui.horizontal(|ui| {
+                ui.label(t!("input-zoom-sensitivity"));
+                let slider = ui.add(egui::Slider::new(&mut settings.zoom_sensitivity, input_settings::ZOOM_SENSITIVITIES));
+                changed |= slider.drag_stopped() || (slider.changed() && !slider.dragged());
+            });
+            ui.horizontal(|ui| {
+                ui.label(t!("input-pan-button"));
+                for button in PanButton::ALL {
+                    changed |= ui.radio_value(&mut settings.pan_button, button, t!(button.label_key())).changed();
+                }
+            });
+            changed |= ui.checkbox(&mut settings.scroll_pans, t!("input-scroll-pans"))
+                .on_hover_text(t!("input-scroll-pans-hint"))
+                .changed();
+            ui.horizontal(|ui| {
+                ui.label(t!("input-select-modifier"));
+                for modifier in SelectModifier::ALL {
+                    changed |= ui.radio_value(&mut settings.select_modifier, modifier, modifier.name()).changed();
+                }
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button(t!("input-mouse-defaults")).clicked() {
+                    *settings = InputSettings::default();
+                    changed = true;
+                }
+                if ui.button(t!("input-trackpad-defaults")).clicked() {
+                    *settings = InputSettings::trackpad();
+                    changed = true;
+                }
+            });
+        });
+    changed
+}
+
+/// Magnified image around `center` (world coordinates), shown next to the cursor at
+/// `screen_position` (egui points) with a cross on the point a click would place
+pub fn render_loupe(
+    contexts: &mut EguiContexts,
+    texture: egui::TextureId,
+    screen_position: egui::Pos2,
+    center: Vec2,
+    image_dimensions: Vec2,
+    zoom_level: f32,
+) {
+    let half_extent = LOUPE_SIZE / 2.0 / (zoom_level * LOUPE_MAGNIFICATION);
+    let to_uv = |point: Vec2| {
+        let uv = coordinates::world_to_image(point, image_dimensions) / image_dimensions;
+        egui::pos2(uv.x, uv.y)
+    };
+    let uv = egui::Rect::from_two_pos(
+        to_uv(center - Vec2::splat(half_extent)),
+        to_uv(center + Vec2::splat(half_extent)),
+    );
+
+    egui::Area::new(egui::Id::new("loupe"))
+        .fixed_pos(screen_position + egui::vec2(24.0, 24.0))
+        .order(egui::Order::Tooltip)
+        .interactable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            let (area, _) = ui.allocate_exact_size(egui::vec2(LOUPE_SIZE, LOUPE_SIZE), egui::Sense::hover());
+            let painter = ui.painter();
+            painter.rect_filled(area, 0.0, egui::Color32::BLACK);
+            // Parts of the loupe past the image edges stay black
+            let visible_uv = uv.intersect(egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)));
+            if visible_uv.is_positive() {
+                let to_area = |uv_point: egui::Pos2| area.min + (uv_point - uv.min) / uv.size() * area.size();
+                painter.image(
+                    texture,
+                    egui::Rect::from_min_max(to_area(visible_uv.min), to_area(visible_uv.max)),
+                    visible_uv,
+                    egui::Color32::WHITE,
+                );
+            }
+
+            let stroke = egui::Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(255, 255, 255, 180));
+            painter.hline(area.x_range(), area.center().y, stroke);
+            painter.vline(area.center().x, area.y_range(), stroke);
+            painter.rect_stroke(area, 0.0, egui::Stroke::new(1.0, egui::Color32::GRAY), egui::StrokeKind::Inside);
+        });
+}
+
+/// Longest side of the minimap, in points
+const MINIMAP_SIZE: f32 = 180.0;
+
+/// Minimap of the whole image with its boxes and the part in `view` framed. Returns the world
+/// point clicked or dragged to, to center the view on.
+pub fn render_minimap_window(
+    contexts: &mut EguiContexts,
+    texture: Option<egui::TextureId>,
+    image_dimensions: Vec2,
+    rectangles: &[Rectangle],
+    layers: &LayerState,
+    view: Rect,
+) -> Option<Vec2> {
+    let scale = MINIMAP_SIZE / image_dimensions.max_element().max(1.0);
+    let size = egui::vec2(image_dimensions.x * scale, image_dimensions.y * scale);
+    let mut target = None;
+
+    egui::Window::new(t!("detail-minimap"))
+        .resizable(false)
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-8.0, -8.0])
+        .show(contexts.ctx_mut(), |ui| {
+            let (response, painter) = ui.allocate_painter(size, egui::Sense::click_and_drag());
+            let area = response.rect;
+            // World y grows upwards, screen y downwards, and the image is centered on the origin
+            let to_screen = |point: Vec2| {
+                area.center() + egui::vec2(point.x * scale, -point.y * scale)
+            };
+            let to_screen_rect = |rect: Rect| egui::Rect::from_two_pos(to_screen(rect.min), to_screen(rect.max));
+
+            match texture {
+                Some(texture) => painter.image(
+                    texture,
+                    area,
+                    egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                    egui::Color32::WHITE,
+                ),
+                None => painter.rect_filled(area, 0.0, egui::Color32::from_gray(60)),
+            }
+
+            for rect in rectangles.iter().filter(|rect| layers.is_visible(rect)) {
+                let color: Color = rect_color(rect.class).into();
+                let color = color.to_srgba();
+                painter.rect_stroke(
+                    to_screen_rect(rect.world_bounds()),
+                    0.0,
+                    egui::Stroke::new(1.0, egui::Color32::from_rgb(
+                        (color.red * 255.0) as u8,
+                        (color.green * 255.0) as u8,
+                        (color.blue * 255.0) as u8,
+                    )),
+                    egui::StrokeKind::Inside,
+                );
+            }
+
+            let shown = to_screen_rect(view).intersect(area.expand(1.0));
+            if shown.is_positive() {
+                painter.rect_stroke(shown, 0.0, egui::Stroke::new(1.5, egui::Color32::YELLOW), egui::StrokeKind::Inside);
+            }
+
+            if response.clicked() || response.dragged() {
+                if let Some(pointer) = response.interact_pointer_pos() {
+                    let offset = (pointer - area.center()) / scale;
+                    target = Some(Vec2::new(offset.x, -offset.y));
+                }
+            }
+        });
+
+    target
+}
+
+/// Shown boxes per category and label and mask opacity, collapsed until needed
+pub fn render_layers_window(contexts: &mut EguiContexts, layers: &mut LayerState, categories: &[AnnotationCategory]) {
+    egui::Window::new(t!("detail-layers"))
+        .default_open(false)
+        .resizable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.checkbox(&mut layers.show_boxes, t!("detail-show-boxes"));
+
+            ui.add_enabled_ui(layers.show_boxes, |ui| {
+                egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                    for (index, category) in categories.iter().enumerate() {
+                        let class = index + 1;
+                        let color: Color = rect_color(class).into();
+                        let color = color.to_srgba();
+                        let mut visible = !layers.hidden_classes.contains(&class);
+                        ui.horizontal(|ui| {
+                            let (rect, _) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+                            ui.painter().rect_filled(
+                                rect,
+                                2.0,
+                                egui::Color32::from_rgb(
+                                    (color.red * 255.0) as u8,
+                                    (color.green * 255.0) as u8,
+                                    (color.blue * 255.0) as u8,
+                                ),
+                            );
+                            if ui.checkbox(&mut visible, &category.name).changed() {
+                                layers.set_class_visible(class, visible);
+                            }
+                        });
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui.small_button(t!("detail-show-all")).clicked() {
+                        layers.hidden_classes.clear();
+                    }
+                    if ui.small_button(t!("detail-hide-all")).clicked() {
+                        layers.hidden_classes = (1..=categories.len()).collect();
+                    }
+                });
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label(t!("detail-label-opacity"));
+                ui.add(egui::Slider::new(&mut layers.label_opacity, 0.0..=1.0));
+            });
+            ui.horizontal(|ui| {
+                ui.label(t!("detail-mask-opacity"));
+                ui.add(egui::Slider::new(&mut layers.mask_opacity, 0.0..=1.0));
+            });
+            ui.checkbox(&mut layers.show_attributes, t!("detail-attributes-in-labels"));
+            if ui.add_enabled(!layers.is_default(), egui::Button::new(t!("common-reset"))).clicked() {
+                *layers = LayerState::default();
+            }
+            ui.weak(t!("detail-layers-hint"));
+        });
+}
+
+pub fn render_tools_window(
+    contexts: &mut EguiContexts,
+    magic_select: &mut bool,
+    magic_select_error: Option<&str>,
+    mask_painting: &mut MaskPainting,
+    flag_tasks: &ApiTasks<TaskFlag>,
+    flag_state: &mut TaskFlagState,
+    annotation_state: &AnnotationState,
+    auth_state: &AuthState,
+) {
+    egui::Window::new(t!("detail-tools")).show(contexts.ctx_mut(), |ui| {
+        ui.checkbox(magic_select, t!("detail-magic-select"))
+            .on_hover_text(t!("detail-magic-select-hint"));
+        if let Some(error) = magic_select_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(t!("detail-mask"));
+            ui.radio_value(&mut mask_painting.tool, None, t!("detail-mask-off"));
+            ui.radio_value(&mut mask_painting.tool, Some(MaskTool::Brush), t!("detail-mask-brush"));
+            ui.radio_value(&mut mask_painting.tool, Some(MaskTool::Eraser), t!("detail-mask-eraser"));
+        });
+        if mask_painting.tool.is_some() {
+            ui.horizontal(|ui| {
+                ui.label(t!("detail-brush-size"));
+                ui.add(egui::Slider::new(&mut mask_painting.brush_size, masks::BRUSH_SIZES).suffix(" px"));
+            });
+            ui.weak(t!("detail-mask-hint"));
+        }
+
+        ui.separator();
+        render_flag_controls(ui, flag_tasks, flag_state, annotation_state, auth_state);
+    });
+}
+
+/// Labeling window of classification projects, replacing the box tools: class shortcuts or the
+/// checkboxes toggle labels, Enter saves and opens the next unannotated task.
+pub fn render_classification_window(
+    contexts: &mut EguiContexts,
+    requests: &EditorRequests,
+    classification_state: &mut ClassificationState,
+    flag_state: &mut TaskFlagState,
+    annotation_state: &mut AnnotationState,
+    auth_state: &AuthState,
+) {
+    let mut save = false;
+    let mut save_and_next = std::mem::take(&mut classification_state.save_and_next_requested);
+
+    let window = egui::Window::new(t!("detail-labels")).default_width(240.0).show(contexts.ctx_mut(), |ui| {
+        if annotation_state.categories.is_empty() {
+            ui.label(t!("detail-no-categories"));
+        } else {
+            ui.weak(t!("detail-labels-hint"));
+            ui.separator();
+
+            egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                for (index, category) in annotation_state.categories.iter().enumerate() {
+                    let mut checked = classification_state.labels.contains(&category.id);
+                    let label = match shortcuts::class_key_label(&annotation_state.categories, index + 1) {
+                        Some(key) => format!("[{}] {}", key, category.name),
+                        None => category.name.clone(),
+                    };
+                    if ui.checkbox(&mut checked, label).changed() {
+                        classification_state.toggle(category.id);
+                    }
+                }
+            });
+
+            ui.separator();
+            let can_save = !classification_state.labels.is_empty() && !annotation_state.is_saving;
+            ui.horizontal(|ui| {
+                let save_button = ui.add_enabled(can_save, egui::Button::new(t!("common-save")));
+                onboarding::mark_target(ui.ctx(), TourTarget::SaveButton, save_button.rect);
+                save = save_button.clicked();
+                save_and_next |= ui.add_enabled(can_save, egui::Button::new(t!("detail-save-next"))).clicked();
+            });
+        }
+
+        if let Some(status) = &classification_state.status {
+            ui.weak(status);
+        }
+        if let Some(error) = &classification_state.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        ui.separator();
+        render_flag_controls(ui, &requests.flags, flag_state, annotation_state, auth_state);
+    });
+    if let Some(window) = window {
+        onboarding::mark_target(contexts.ctx_mut(), TourTarget::ClassPicker, window.response.rect);
+    }
+
+    if (!save && !save_and_next) || annotation_state.is_saving {
+        return;
+    }
+    if classification_state.labels.is_empty() {
+        classification_state.error = Some(t!("detail-pick-label"));
+        return;
+    }
+    let (Some(project_id), Some(task_id), Some(token)) = (
+        annotation_state.current_project_id,
+        annotation_state.current_task_id,
+        auth_state.get_jwt(),
+    ) else {
+        return;
+    };
+
+    annotation_state.is_saving = true;
+    let (labels, token) = (classification_state.labels.clone(), token.clone());
+    requests.labels.spawn(async move {
+        let classification = annotation_client::save_classification(project_id, task_id, labels, token).await?;
+        Ok(TaskLabels::Saved { classification, project_id, open_next: save_and_next })
+    });
+}
+
+/// Overview of the keys and annotation modes of the detail page, including the project's class
+/// shortcuts. Returns whether the user asked for the tour.
+pub fn render_shortcuts_cheat_sheet(
+    contexts: &mut EguiContexts,
+    shortcut_state: &mut ShortcutState,
+    categories: &[AnnotationCategory],
+    input_settings: &InputSettings,
+) -> bool {
+    if !shortcut_state.show_cheat_sheet {
+        return false;
+    }
+
+    let mut open = true;
+    let mut start_tour = false;
+    egui::Window::new(t!("shortcuts-title"))
+        .open(&mut open)
+        .collapsible(false)
+        .default_width(320.0)
+        .show(contexts.ctx_mut(), |ui| {
+            egui::Grid::new("fixed_shortcuts").striped(true).show(ui, |ui| {
+                for (keys, action) in FIXED_SHORTCUTS {
+                    ui.strong(input_settings.shortcut_keys(keys));
+                    ui.label(t!(action));
+                    ui.end_row();
+                }
+            });
+
+            ui.separator();
+            ui.strong(t!("shortcuts-modes"));
+            egui::Grid::new("annotation_modes").striped(true).show(ui, |ui| {
+                for (mode, how) in ANNOTATION_MODES {
+                    ui.strong(t!(mode));
+                    ui.label(t!(how, key = input_settings.select_modifier.name()));
+                    ui.end_row();
+                }
+            });
+
+            ui.separator();
+            ui.strong(t!("shortcuts-classes"));
+            egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                let mut bindings = shortcuts::class_bindings(categories);
+                bindings.sort_by_key(|(_, class)| *class);
+                if bindings.is_empty() {
+                    ui.weak(t!("shortcuts-no-class-shortcuts"));
+                }
+                egui::Grid::new("class_shortcuts").striped(true).show(ui, |ui| {
+                    for (key, class) in bindings {
+                        let (Some(name), Some(category)) = (shortcuts::key_name(key), categories.get(class - 1)) else {
+                            continue;
+                        };
+                        ui.strong(shortcuts::key_label(name));
+                        ui.label(&category.name);
+                        ui.end_row();
+                    }
+                });
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!categories.is_empty(), egui::Button::new(t!("shortcuts-edit-classes"))).clicked() {
+                    shortcut_state.open_editor(categories);
+                }
+                start_tour = ui.button(t!("shortcuts-take-tour")).clicked();
+            });
+        });
+
+    if !open || start_tour {
+        shortcut_state.show_cheat_sheet = false;
+    }
+    start_tour
+}
+
+/// Editor of the project's class shortcuts: click a category's key, then press the new one.
+/// The keys are saved for the whole project at once.
+pub fn render_shortcut_editor_window(
+    contexts: &mut EguiContexts,
+    hotkey_tasks: &ApiTasks<SavedHotkeys>,
+    shortcut_state: &mut ShortcutState,
+    annotation_state: &AnnotationState,
+    auth_state: &AuthState,
+) {
+    if !shortcut_state.editor_open {
+        return;
+    }
+
+    let mut open = true;
+    let mut save = false;
+    egui::Window::new(t!("shortcuts-editor-title"))
+        .open(&mut open)
+        .default_width(300.0)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.weak(t!("shortcuts-editor-hint"));
+            ui.separator();
+
+            egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                egui::Grid::new("shortcut_editor").striped(true).show(ui, |ui| {
+                    for category in &annotation_state.categories {
+                        ui.label(&category.name);
+
+                        let capturing = shortcut_state.capturing == Some(category.id);
+                        let text = if capturing {
+                            t!("shortcuts-press-key")
+                        } else {
+                            shortcut_state.draft.get(&category.id).map_or("—".to_string(), |name| shortcuts::key_label(name).to_string())
+                        };
+                        if ui.selectable_label(capturing, text).clicked() {
+                            shortcut_state.capturing = if capturing { None } else { Some(category.id) };
+                        }
+
+                        let has_key = shortcut_state.draft.contains_key(&category.id);
+                        if ui.add_enabled(has_key, egui::Button::new("✖")).on_hover_text(t!("shortcuts-remove")).clicked() {
+                            shortcut_state.draft.remove(&category.id);
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                save = ui.add_enabled(!shortcut_state.saving, egui::Button::new(t!("common-save"))).clicked();
+                if ui.button(t!("shortcuts-clear-all"))
+                    .on_hover_text(t!("shortcuts-clear-all-hint"))
+                    .clicked()
+                {
+                    shortcut_state.draft.clear();
+                    shortcut_state.capturing = None;
+                }
+            });
+
+            if let Some(error) = &shortcut_state.error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        });
+
+    if !open {
+        shortcut_state.editor_open = false;
+        shortcut_state.capturing = None;
+        return;
+    }
+    if !save {
+        return;
+    }
+    let (Some(project_id), Some(token)) = (annotation_state.current_project_id, auth_state.get_jwt()) else {
+        return;
+    };
+
+    let hotkeys = annotation_state.categories
+        .iter()
+        .map(|category| CategoryHotkey {
+            category_id: category.id,
+            hotkey: shortcut_state.draft.get(&category.id).cloned(),
+        })
+        .collect();
+    shortcut_state.saving = true;
+    let token = token.clone();
+    hotkey_tasks.spawn(async move {
+        annotation_client::save_hotkeys(project_id, hotkeys, token).await.map(SavedHotkeys)
+    });
+}
+
+fn render_flag_controls(
+    ui: &mut egui::Ui,
+    flag_tasks: &ApiTasks<TaskFlag>,
+    flag_state: &mut TaskFlagState,
+    annotation_state: &AnnotationState,
+    auth_state: &AuthState,
+) {
+    let (Some(project_id), Some(task_id), Some(token)) = (
+        annotation_state.current_project_id,
+        annotation_state.current_task_id,
+        auth_state.get_jwt(),
+    ) else {
+        return;
+    };
+
+    if let Some(reason) = flag_state.current_reason.clone() {
+        ui.horizontal(|ui| {
+            ui.colored_label(egui::Color32::from_rgb(220, 120, 40), t!("detail-flagged", reason = flag_reason_name(&reason)));
+            if ui.button(t!("detail-clear-flag")).clicked() {
+                let token = token.clone();
+                flag_tasks.spawn(async move {
+                    annotation_client::unflag_task(project_id, task_id, token).await?;
+                    Ok(TaskFlag { task_id, reason: None })
+                });
+            }
+        });
+    } else {
+        ui.horizontal(|ui| {
+            let selected = FLAG_REASONS.get(flag_state.selected_reason).map(|(reason, _)| flag_reason_name(reason)).unwrap_or_default();
+            egui::ComboBox::from_id_salt("flag_reason")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (index, (reason, _)) in FLAG_REASONS.iter().enumerate() {
+                        ui.selectable_value(&mut flag_state.selected_reason, index, flag_reason_name(reason));
+                    }
+                });
+
+            if ui.button(t!("detail-flag-image")).clicked() {
+                let (reason, _) = FLAG_REASONS[flag_state.selected_reason.min(FLAG_REASONS.len() - 1)];
+                let reason = reason.to_string();
+                let note = Some(flag_state.note.trim().to_string()).filter(|note| !note.is_empty());
+                let token = token.clone();
+                flag_tasks.spawn(async move {
+                    annotation_client::flag_task(project_id, task_id, reason.clone(), note, token).await?;
+                    Ok(TaskFlag { task_id, reason: Some(reason) })
+                });
+            }
+        });
+        ui.add(egui::TextEdit::singleline(&mut flag_state.note).hint_text(t!("detail-flag-note")));
+    }
+
+    if let Some(error) = &flag_state.error {
+        ui.colored_label(egui::Color32::RED, error);
+    }
+}
+
+enum CommentAction {
+    ShowAnchor(Vec<f64>),
+    Reply(uuid::Uuid),
+    SetResolved(uuid::Uuid, bool),
+    Delete(uuid::Uuid),
+}
+
+fn bbox_iou(a: &[f64; 4], b: &[f64; 4]) -> f64 {
+    let width = ((a[0] + a[2]).min(b[0] + b[2]) - a[0].max(b[0])).max(0.0);
+    let height = ((a[1] + a[3]).min(b[1] + b[3]) - a[1].max(b[1])).max(0.0);
+    let intersection = width * height;
+    let union = a[2] * a[3] + b[2] * b[3] - intersection;
+    if union <= 0.0 { 0.0 } else { intersection / union }
+}
+
+/// Finds the rectangle overlapping a comment anchor the most. Anchors are stored by value,
+/// so the box may have moved a little since the comment was written.
+fn find_anchored_rectangle(rectangles: &[Rectangle], anchor: &[f64], image_dimensions: Vec2) -> Option<usize> {
+    if anchor.len() != 4 {
+        return None;
+    }
+    let anchor = [anchor[0], anchor[1], anchor[2], anchor[3]];
+
+    rectangles
+        .iter()
+        .enumerate()
+        .map(|(index, rect)| (index, bbox_iou(&coordinates::rectangle_to_bbox(rect, image_dimensions), &anchor)))
+        .filter(|(_, overlap)| *overlap > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+fn render_comment(
+    ui: &mut egui::Ui,
+    comment: &Comment,
+    current_user_id: Option<&str>,
+    action: &mut Option<CommentAction>,
+) {
+    ui.horizontal(|ui| {
+        ui.strong(&comment.author_name);
+        ui.weak(comment.created_at.format("%Y-%m-%d %H:%M").to_string());
+    });
+
+    if comment.resolved {
+        ui.weak(&comment.body);
+    } else {
+        ui.label(&comment.body);
+    }
+
+    ui.horizontal(|ui| {
+        if let Some(anchor) = &comment.anchor_bbox {
+            if ui.small_button(t!("comments-show-box")).clicked() {
+                *action = Some(CommentAction::ShowAnchor(anchor.clone()));
+            }
+        }
+        if ui.small_button(t!("comments-reply")).clicked() {
+            // Replies always attach to the thread root
+            *action = Some(CommentAction::Reply(comment.parent_id.unwrap_or(comment.id)));
+        }
+        if comment.parent_id.is_none() {
+            let label = if comment.resolved { t!("comments-reopen") } else { t!("comments-resolve") };
+            if ui.small_button(label).clicked() {
+                *action = Some(CommentAction::SetResolved(comment.id, !comment.resolved));
+            }
+        }
+        if current_user_id == Some(comment.author_id.to_string().as_str()) && ui.small_button("🗑").clicked() {
+            *action = Some(CommentAction::Delete(comment.id));
+        }
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn render_comments_panel(
+    contexts: &mut EguiContexts,
+    comment_tasks: &ApiTasks<CommentsChange>,
+    comments_state: &mut CommentsState,
+    rectangles: &[Rectangle],
+    selected_index: &mut Option<usize>,
+    annotation_state: &AnnotationState,
+    auth_state: &AuthState,
+    user_state: &UserState,
+    image_dimensions: Vec2,
+) {
+    egui::SidePanel::right("comments_panel")
+        .resizable(true)
+        .default_width(250.0)
+        .width_range(80.0..=500.0)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.vertical_centered(|ui| {
+                ui.heading(t!("comments-title"));
+            });
+            ui.separator();
+
+            let (Some(project_id), Some(task_id), Some(token)) = (
+                annotation_state.current_project_id,
+                annotation_state.current_task_id,
+                auth_state.get_jwt(),
+            ) else {
+                ui.label(t!("comments-login"));
+                return;
+            };
+
+            let current_user_id = user_state.user.as_ref().map(|user| user.id.clone());
+            let mut action = None;
+
+            egui::ScrollArea::vertical()
+                .max_height(ui.available_height() * 0.6)
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    if comments_state.comments.is_empty() {
+                        ui.label(t!("comments-empty"));
+                    }
+
+                    for comment in comments_state.comments.iter().filter(|comment| comment.parent_id.is_none()) {
+                        render_comment(ui, comment, current_user_id.as_deref(), &mut action);
+                        for reply in comments_state.comments.iter().filter(|reply| reply.parent_id == Some(comment.id)) {
+                            ui.indent(reply.id, |ui| {
+                                render_comment(ui, reply, current_user_id.as_deref(), &mut action);
+                            });
+                        }
+                        ui.separator();
+                    }
+                });
+
+            match action {
+                Some(CommentAction::ShowAnchor(anchor)) => {
+                    match find_anchored_rectangle(rectangles, &anchor, image_dimensions) {
+                        Some(index) => *selected_index = Some(index),
+                        None => comments_state.error = Some(t!("comments-box-gone")),
+                    }
+                }
+                Some(CommentAction::Reply(parent_id)) => {
+                    comments_state.reply_to = Some(parent_id);
+                }
+                Some(CommentAction::SetResolved(comment_id, resolved)) => {
+                    let token = token.clone();
+                    comment_tasks.spawn(async move {
+                        annotation_client::set_comment_resolved(project_id, task_id, comment_id, resolved, token).await
+                            .map(CommentsChange::Updated)
+                    });
+                }
+                Some(CommentAction::Delete(comment_id)) => {
+                    let token = token.clone();
+                    comment_tasks.spawn(async move {
+                        annotation_client::delete_comment(project_id, task_id, comment_id, token).await?;
+                        Ok(CommentsChange::Deleted { task_id, comment_id })
+                    });
+                }
+                None => {}
+            }
+
+            ui.separator();
+
+            if comments_state.reply_to.is_some() {
+                ui.horizontal(|ui| {
+                    ui.label(t!("comments-replying"));
+                    if ui.small_button("✖").clicked() {
+                        comments_state.reply_to = None;
+                    }
+                });
+            }
+
+            ui.add(
+                egui::TextEdit::multiline(&mut comments_state.draft)
+                    .hint_text(t!("comments-hint"))
+                    .desired_rows(3)
+                    .desired_width(f32::INFINITY),
+            );
+            ui.add_enabled(
+                selected_index.is_some(),
+                egui::Checkbox::new(&mut comments_state.attach_to_selected, t!("comments-attach")),
+            );
+
+            let can_post = !comments_state.draft.trim().is_empty() && !comments_state.posting;
+            if ui.add_enabled(can_post, egui::Button::new(t!("comments-post"))).clicked() {
+                let anchor_bbox = if comments_state.attach_to_selected {
+                    selected_index
+                        .and_then(|index| rectangles.get(index))
+                        .map(|rect| coordinates::rectangle_to_bbox(rect, image_dimensions).iter().map(|value| value.max(0.0)).collect())
+                } else {
+                    None
+                };
+
+                let request = CreateCommentRequest {
+                    body: comments_state.draft.trim().to_string(),
+                    parent_id: comments_state.reply_to,
+                    anchor_bbox,
+                };
+
+                comments_state.posting = true;
+                let token = token.clone();
+                comment_tasks.spawn(async move {
+                    annotation_client::post_comment(project_id, task_id, request, token).await.map(CommentsChange::Posted)
+                });
+            }
+
+            if let Some(error) = &comments_state.error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        });
+}
+
+fn flag_reason_name(reason: &str) -> String {
+    i18n::code_label("flag-reason", reason, flag_reason_label(reason))
+}
@@ -0,0 +1,199 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiContextPass, egui};
+use std::path::PathBuf;
+use crate::api::task::{ApiTaskFailed, ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use crate::api::updates::{ReleaseDownload, ReleaseManifest, UpdatesApi};
+use crate::io::preferences::Preferences;
+use crate::notifications::Notify;
+use crate::platform;
+
+/// Seconds between two looks at the release manifest, for apps left open for days
+const CHECK_INTERVAL_SECS: f64 = 6.0 * 60.0 * 60.0;
+
+/// Looks for a newer release of the desktop app in the release manifest at
+/// `FAST_TAG_UPDATE_URL` (read when the app starts, or baked in when it is built) and offers
+/// its changelog and installer. Builds without a manifest URL never check.
+pub struct UpdatesPlugin;
+
+impl Plugin for UpdatesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins(ApiTaskPlugin::<ReleaseManifest>::default())
+            .add_plugins(ApiTaskPlugin::<DownloadedInstaller>::default())
+            .init_resource::<UpdateState>()
+            .add_systems(Update, (
+                check_for_updates_system,
+                process_update_results,
+            ))
+            .add_systems(EguiContextPass, update_dialog_ui_system);
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct UpdateState {
+    /// The newer release, once found
+    pub available: Option<ReleaseManifest>,
+    show_dialog: bool,
+    is_downloading: bool,
+    last_check: Option<f64>,
+}
+
+/// Where the installer of a release was saved
+pub struct DownloadedInstaller(PathBuf);
+
+fn manifest_url() -> Option<String> {
+    std::env::var("FAST_TAG_UPDATE_URL")
+        .ok()
+        .or_else(|| option_env!("FAST_TAG_UPDATE_URL").map(str::to_string))
+        .filter(|url| !url.trim().is_empty())
+}
+
+fn check_for_updates_system(
+    time: Res<Time>,
+    mut update_state: ResMut<UpdateState>,
+    manifest_tasks: Res<ApiTasks<ReleaseManifest>>,
+) {
+    // The browser build is always the one the server hands out
+    if !platform::DESKTOP {
+        return;
+    }
+    let now = time.elapsed_secs_f64();
+    if update_state.last_check.is_some_and(|last| now - last < CHECK_INTERVAL_SECS) {
+        return;
+    }
+    update_state.last_check = Some(now);
+
+    let Some(url) = manifest_url() else {
+        return;
+    };
+    manifest_tasks.spawn(async move {
+        UpdatesApi::new().fetch_manifest(&url).await.map_err(|e| e.to_string())
+    });
+}
+
+fn process_update_results(
+    mut manifests: EventReader<ApiTaskSucceeded<ReleaseManifest>>,
+    mut manifest_failures: EventReader<ApiTaskFailed<ReleaseManifest>>,
+    mut downloads: EventReader<ApiTaskSucceeded<DownloadedInstaller>>,
+    mut download_failures: EventReader<ApiTaskFailed<DownloadedInstaller>>,
+    mut update_state: ResMut<UpdateState>,
+    mut notify: EventWriter<Notify>,
+) {
+    for ApiTaskSucceeded(manifest) in manifests.read() {
+        if !manifest.is_newer_than(env!("CARGO_PKG_VERSION")) {
+            continue;
+        }
+        let skipped = Preferences::load().skipped_update.is_some_and(|version| version == manifest.version);
+        // Already offered, the dialog isn't opened again by every check
+        let known = update_state.available.as_ref().is_some_and(|available| available.version == manifest.version);
+        if !skipped && !known {
+            info!("Version {} of the app is available", manifest.version);
+            update_state.available = Some(manifest.clone());
+            update_state.show_dialog = true;
+        }
+    }
+
+    // Checking is a courtesy, a manifest that can't be reached isn't worth a toast
+    for failure in manifest_failures.read() {
+        warn!("Failed to check for updates: {}", failure.error);
+    }
+
+    for ApiTaskSucceeded(DownloadedInstaller(path)) in downloads.read() {
+        update_state.is_downloading = false;
+        update_state.show_dialog = false;
+        notify.write(Notify::success(t!("updates-downloaded", path = path.display().to_string())));
+        if let Err(error) = platform::open_url(&path.to_string_lossy()) {
+            notify.write(Notify::error(t!("updates-open-failed", error = error)));
+        }
+    }
+
+    for failure in download_failures.read() {
+        update_state.is_downloading = false;
+        notify.write(Notify::error(t!("updates-download-failed", error = failure.error.as_str())));
+    }
+}
+
+/// Saves the installer to the downloads folder, under the file name of its URL
+async fn download_installer(download: ReleaseDownload) -> Result<DownloadedInstaller, String> {
+    let file_name = download
+        .file_name()
+        .ok_or_else(|| format!("{} doesn't name an installer file", download.url))?;
+    let bytes = UpdatesApi::new().download_installer(&download).await.map_err(|e| e.to_string())?;
+    let path = dirs::download_dir().unwrap_or_else(|| PathBuf::from(".")).join(file_name);
+    platform::unblock(move || std::fs::write(&path, bytes).map(|_| DownloadedInstaller(path)))
+        .await?
+        .map_err(|e| e.to_string())
+}
+
+fn update_dialog_ui_system(
+    mut contexts: EguiContexts,
+    mut update_state: ResMut<UpdateState>,
+    download_tasks: Res<ApiTasks<DownloadedInstaller>>,
+) {
+    if !update_state.show_dialog {
+        return;
+    }
+    let Some(release) = update_state.available.clone() else {
+        return;
+    };
+
+    let mut close = false;
+    let mut skip = false;
+    let mut download = None;
+    egui::Window::new(t!("updates-title"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(t!("updates-available", version = release.version.as_str(), current = env!("CARGO_PKG_VERSION")));
+            if let Some(published_at) = &release.published_at {
+                ui.weak(t!("updates-published", date = published_at.as_str()));
+            }
+            if !release.changelog.is_empty() {
+                ui.add_space(8.0);
+                ui.strong(t!("updates-changelog"));
+                egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                    ui.label(&release.changelog);
+                });
+            }
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                match release.download_for_this_os() {
+                    Some(_) if update_state.is_downloading => {
+                        ui.add_enabled(false, egui::Button::new(t!("updates-download")));
+                        ui.add(egui::Spinner::new());
+                    }
+                    Some(installer) => {
+                        if ui.button(t!("updates-download")).clicked() {
+                            download = Some(installer.clone());
+                        }
+                    }
+                    None => {
+                        ui.weak(t!("updates-no-installer"));
+                    }
+                }
+                if ui.button(t!("updates-later")).clicked() {
+                    close = true;
+                }
+                if ui.button(t!("updates-skip")).clicked() {
+                    skip = true;
+                }
+            });
+        });
+
+    if let Some(installer) = download {
+        update_state.is_downloading = true;
+        download_tasks.spawn(download_installer(installer));
+    }
+    if skip {
+        let mut preferences = Preferences::load();
+        preferences.skipped_update = Some(release.version.clone());
+        if let Err(error) = preferences.save() {
+            warn!("Failed to remember the skipped update: {}", error);
+        }
+    }
+    if close || skip {
+        update_state.show_dialog = false;
+    }
+}
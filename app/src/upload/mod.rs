@@ -0,0 +1,322 @@
+use bevy::prelude::*;
+use bevy::window::FileDragAndDrop;
+use bevy_egui::{EguiContexts, EguiContextPass, egui};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::Semaphore;
+use crate::api::storage::StorageApi;
+use crate::api::task::{ApiTaskPlugin, ApiTaskSucceeded, ApiTasks};
+use crate::api::tasks::TasksApi;
+use crate::app::state::AppState;
+use crate::auth::AuthState;
+use crate::platform::{self, dialogs};
+
+/// Files sent to the server at the same time
+const MAX_PARALLEL_UPLOADS: usize = 3;
+/// Extensions of the files that are uploaded, with their content types
+const IMAGE_TYPES: [(&str, &str); 8] = [
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("png", "image/png"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("bmp", "image/bmp"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+];
+
+/// Uploads images from this computer into a project's storage and creates a task for each,
+/// so images don't have to be put into the bucket by hand and synced. Files are picked in a
+/// dialog or dropped onto the window while the upload window of a project is open.
+pub struct UploadPlugin;
+
+impl Plugin for UploadPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins(ApiTaskPlugin::<UploadedFile>::default())
+            .init_resource::<UploadState>()
+            // Results are picked up on every page, uploads go on while the user moves on
+            .add_systems(Update, (
+                dropped_files_system.run_if(in_state(AppState::Projects)),
+                process_upload_results,
+            ))
+            .add_systems(
+                EguiContextPass,
+                upload_ui_system
+                    .after(crate::pages::projects::ui_system)
+                    .run_if(in_state(AppState::Projects)),
+            );
+    }
+}
+
+#[derive(Resource)]
+pub struct UploadState {
+    /// ID and name of the project files are uploaded to, the window is closed when `None`
+    project: Option<(String, String)>,
+    files: Vec<UploadFile>,
+    /// Files picked or dropped since the last frame, started by the next run of the UI system
+    pending: Vec<PathBuf>,
+    permits: Arc<Semaphore>,
+}
+
+impl Default for UploadState {
+    fn default() -> Self {
+        Self {
+            project: None,
+            files: Vec::new(),
+            pending: Vec::new(),
+            permits: Arc::new(Semaphore::new(MAX_PARALLEL_UPLOADS)),
+        }
+    }
+}
+
+impl UploadState {
+    /// Opens the upload window for a project. Uploads still running keep the current one.
+    pub fn open(&mut self, project_id: String, project_name: String) {
+        if self.is_uploading() {
+            return;
+        }
+        self.files.clear();
+        self.project = Some((project_id, project_name));
+    }
+
+    fn is_uploading(&self) -> bool {
+        self.files.iter().any(|file| matches!(file.status, UploadStatus::Uploading))
+    }
+}
+
+struct UploadFile {
+    name: String,
+    size: u64,
+    /// Bytes handed to the connection so far
+    sent: Arc<AtomicU64>,
+    status: UploadStatus,
+}
+
+enum UploadStatus {
+    Uploading,
+    Done,
+    Failed(String),
+}
+
+/// Outcome of one file, by its position in `UploadState::files`
+pub struct UploadedFile {
+    index: usize,
+    result: Result<(), String>,
+}
+
+fn content_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    IMAGE_TYPES
+        .iter()
+        .find(|(image_extension, _)| *image_extension == extension)
+        .map(|(_, content_type)| *content_type)
+}
+
+fn dropped_files_system(
+    mut drag_and_drop: EventReader<FileDragAndDrop>,
+    mut upload_state: ResMut<UploadState>,
+) {
+    for event in drag_and_drop.read() {
+        // Drops only mean something while a project to upload to is chosen
+        if let FileDragAndDrop::DroppedFile { path_buf, .. } = event {
+            if upload_state.project.is_some() {
+                upload_state.pending.push(path_buf.clone());
+            }
+        }
+    }
+}
+
+/// Uploads a file and creates its task. The storage key is the file name, the same key a sync
+/// of the bucket would create the task from, so a later sync doesn't add it a second time.
+async fn upload_file(
+    token: String,
+    project_id: String,
+    path: PathBuf,
+    name: String,
+    content_type: &'static str,
+    sent: Arc<AtomicU64>,
+    permits: Arc<Semaphore>,
+) -> Result<(), String> {
+    let _permit = permits.acquire_owned().await.map_err(|e| e.to_string())?;
+
+    let data = platform::unblock(move || std::fs::read(&path))
+        .await?
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    upload_image(&token, &project_id, &name, data, content_type, sent).await
+}
+
+/// Puts image bytes into the project's storage under `name` and creates a task showing them
+pub async fn upload_image(
+    token: &str,
+    project_id: &str,
+    name: &str,
+    data: Vec<u8>,
+    content_type: &str,
+    sent: Arc<AtomicU64>,
+) -> Result<(), String> {
+    let uploaded = StorageApi::new()
+        .upload_file(token, project_id, name, data, content_type, sent)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let resource_url = format!("storage://{}", uploaded.key);
+    TasksApi::new()
+        .create_task(token, project_id, name, Some(&resource_url))
+        .await
+        .map_err(|e| format!("Uploaded, but failed to create the task: {}", e))?;
+    Ok(())
+}
+
+fn start_uploads(upload_state: &mut UploadState, upload_tasks: &ApiTasks<UploadedFile>, token: &str) {
+    let Some((project_id, _)) = upload_state.project.clone() else {
+        upload_state.pending.clear();
+        return;
+    };
+
+    for path in std::mem::take(&mut upload_state.pending) {
+        // Folders are dropped as a single path, their images are uploaded instead
+        let paths = if path.is_dir() {
+            let mut paths: Vec<PathBuf> = std::fs::read_dir(&path)
+                .map(|entries| entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect())
+                .unwrap_or_default();
+            paths.sort();
+            paths
+        } else {
+            vec![path]
+        };
+
+        for path in paths {
+            let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            let size = std::fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+            let sent = Arc::new(AtomicU64::new(0));
+
+            let status = match content_type(&path) {
+                Some(content_type) => {
+                    let index = upload_state.files.len();
+                    let upload = upload_file(
+                        token.to_string(),
+                        project_id.clone(),
+                        path.clone(),
+                        name.clone(),
+                        content_type,
+                        sent.clone(),
+                        upload_state.permits.clone(),
+                    );
+                    upload_tasks.spawn(async move { Ok(UploadedFile { index, result: upload.await }) });
+                    UploadStatus::Uploading
+                }
+                None => UploadStatus::Failed(t!("upload-not-an-image")),
+            };
+            upload_state.files.push(UploadFile { name, size, sent, status });
+        }
+    }
+}
+
+fn process_upload_results(
+    mut succeeded: EventReader<ApiTaskSucceeded<UploadedFile>>,
+    mut upload_state: ResMut<UploadState>,
+) {
+    for ApiTaskSucceeded(uploaded) in succeeded.read() {
+        let Some(file) = upload_state.files.get_mut(uploaded.index) else {
+            continue;
+        };
+        file.status = match &uploaded.result {
+            Ok(()) => UploadStatus::Done,
+            Err(error) => UploadStatus::Failed(error.clone()),
+        };
+    }
+}
+
+fn upload_ui_system(
+    mut contexts: EguiContexts,
+    auth_state: Res<AuthState>,
+    upload_tasks: Res<ApiTasks<UploadedFile>>,
+    mut upload_state: ResMut<UploadState>,
+) {
+    let Some((_, project_name)) = upload_state.project.clone() else {
+        return;
+    };
+
+    if !upload_state.pending.is_empty() {
+        match auth_state.get_jwt() {
+            Some(token) => start_uploads(&mut upload_state, &upload_tasks, token),
+            None => upload_state.pending.clear(),
+        }
+    }
+
+    let uploading = upload_state.is_uploading();
+    let mut close = false;
+    let mut picked = None;
+
+    egui::Window::new(t!("upload-title", project = project_name.as_str()))
+        .collapsible(false)
+        .resizable(true)
+        .default_width(480.0)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                if dialogs::AVAILABLE && ui.button(t!("upload-choose-files")).clicked() {
+                    let extensions: Vec<&str> = IMAGE_TYPES.iter().map(|(extension, _)| *extension).collect();
+                    picked = dialogs::pick_files(&t!("upload-images-filter"), &extensions);
+                }
+                ui.weak(t!("upload-drop-hint"));
+            });
+            ui.separator();
+
+            if upload_state.files.is_empty() {
+                ui.weak(t!("upload-empty-hint"));
+            }
+
+            egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                for file in &upload_state.files {
+                    ui.horizontal(|ui| {
+                        ui.label(&file.name);
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            match &file.status {
+                                UploadStatus::Uploading => {
+                                    let sent = file.sent.load(Ordering::Relaxed);
+                                    let fraction = if file.size > 0 { sent as f32 / file.size as f32 } else { 0.0 };
+                                    let text = if sent == 0 { t!("upload-waiting") } else { format!("{:.0}%", fraction * 100.0) };
+                                    ui.add(egui::ProgressBar::new(fraction.min(1.0)).desired_width(160.0).text(text));
+                                }
+                                UploadStatus::Done => {
+                                    ui.colored_label(egui::Color32::GREEN, t!("upload-done"));
+                                }
+                                UploadStatus::Failed(error) => {
+                                    ui.colored_label(egui::Color32::RED, t!("upload-failed")).on_hover_text(error.as_str());
+                                }
+                            }
+                        });
+                    });
+                }
+            });
+
+            if !upload_state.files.is_empty() {
+                ui.separator();
+                let done = upload_state.files.iter().filter(|file| matches!(file.status, UploadStatus::Done)).count();
+                let failed = upload_state.files.iter().filter(|file| matches!(file.status, UploadStatus::Failed(_))).count();
+                if uploading {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::Spinner::new());
+                        ui.label(t!("upload-progress", finished = done + failed, total = upload_state.files.len()));
+                    });
+                } else {
+                    ui.label(t!("upload-summary", created = done, failed = failed));
+                }
+            }
+
+            ui.add_space(5.0);
+            if ui.add_enabled(!uploading, egui::Button::new(t!("upload-close"))).clicked() {
+                close = true;
+            }
+        });
+
+    if let Some(paths) = picked {
+        upload_state.pending.extend(paths);
+    }
+    if close {
+        upload_state.project = None;
+        upload_state.files.clear();
+    }
+}
@@ -0,0 +1,214 @@
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowPosition};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::api::ApiConfig;
+use crate::app::state::AppState;
+use crate::deep_link::{DeepLink, PendingDeepLink};
+use crate::pages::{detail, tasks};
+use crate::platform;
+
+/// Recent projects kept per server
+pub const MAX_RECENT_PROJECTS: usize = 5;
+/// Smaller windows, such as minimized ones, aren't worth coming back to
+const MIN_WINDOW_SIZE: f32 = 320.0;
+
+/// Where the user was in the app, kept in the user's config directory (the page's local storage
+/// in the browser) so the next launch continues from there: the window as it was, and after the
+/// login the task the editor had open. The project list offers the recent projects again.
+pub struct WorkspacePlugin;
+
+impl Plugin for WorkspacePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(Workspace::load())
+            .add_systems(Startup, restore_window_system)
+            .add_systems(OnEnter(AppState::Projects), resume_system)
+            .add_systems(OnEnter(AppState::Tasks), record_project_system)
+            .add_systems(OnExit(AppState::Detail), leave_editor_system)
+            .add_systems(Update, (
+                record_task_system.run_if(in_state(AppState::Detail).and(resource_exists_and_changed::<detail::Parameters>)),
+                track_window_system,
+            ))
+            .add_systems(Last, save_on_exit_system);
+    }
+}
+
+/// A project opened lately and the task of it last opened in the editor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentProject {
+    /// Server the project is on, other servers have other projects
+    pub server_url: String,
+    pub project_id: String,
+    #[serde(default)]
+    pub last_task_id: Option<Uuid>,
+}
+
+/// Position in physical pixels and size in logical pixels of the app window
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowLayout {
+    pub position: Option<[i32; 2]>,
+    pub size: [f32; 2],
+}
+
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Workspace {
+    /// Most recently opened first
+    #[serde(default)]
+    pub recent_projects: Vec<RecentProject>,
+    /// The app was closed in the editor, on the last task of the first recent project
+    #[serde(default)]
+    pub editor_open: bool,
+    /// The window when the app was closed, maximized the first time
+    #[serde(default)]
+    pub window: Option<WindowLayout>,
+}
+
+impl Workspace {
+    const FILE_NAME: &str = "workspace.json";
+
+    pub fn load() -> Self {
+        let Some(bytes) = platform::read_setting(Self::FILE_NAME) else {
+            return Self::default();
+        };
+        serde_json::from_slice(&bytes).unwrap_or_else(|error| {
+            warn!("Ignoring unreadable workspace in {}: {}", Self::FILE_NAME, error);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let bytes = serde_json::to_vec_pretty(self).map_err(|e| e.to_string())?;
+        platform::write_setting(Self::FILE_NAME, &bytes)
+    }
+
+    /// Recent projects on the server the app is logged in to, most recent first
+    pub fn recent_projects(&self) -> impl Iterator<Item = &RecentProject> {
+        let server_url = ApiConfig::default().base_url;
+        self.recent_projects.iter().filter(move |project| project.server_url == server_url)
+    }
+
+    /// Moves the project to the front of the recent projects, keeping its last task
+    pub fn open_project(&mut self, project_id: &str) -> &mut RecentProject {
+        let server_url = ApiConfig::default().base_url;
+        let position = self.recent_projects
+            .iter()
+            .position(|project| project.server_url == server_url && project.project_id == project_id);
+        let project = match position {
+            Some(position) => self.recent_projects.remove(position),
+            None => RecentProject { server_url: server_url.clone(), project_id: project_id.to_string(), last_task_id: None },
+        };
+        self.recent_projects.insert(0, project);
+
+        let mut kept = 0;
+        self.recent_projects.retain(|project| {
+            if project.server_url != server_url {
+                return true;
+            }
+            kept += 1;
+            kept <= MAX_RECENT_PROJECTS
+        });
+        &mut self.recent_projects[0]
+    }
+
+    /// The task to open again after the login, when the app was closed in the editor
+    pub fn resume_link(&self) -> Option<DeepLink> {
+        if !self.editor_open {
+            return None;
+        }
+        let project = self.recent_projects().next()?;
+        Some(DeepLink {
+            project_id: Uuid::parse_str(&project.project_id).ok()?,
+            task_id: project.last_task_id?,
+        })
+    }
+}
+
+fn save(workspace: &Workspace) {
+    if let Err(error) = workspace.save() {
+        warn!("Failed to save the workspace: {}", error);
+    }
+}
+
+/// The browser build leaves the window to the page
+fn restore_window_system(mut windows: Query<&mut Window, With<PrimaryWindow>>, workspace: Res<Workspace>) {
+    if !platform::DESKTOP {
+        return;
+    }
+    for mut window in windows.iter_mut() {
+        match workspace.window {
+            Some(layout) => {
+                window.resolution.set(layout.size[0], layout.size[1]);
+                if let Some([x, y]) = layout.position {
+                    window.position = WindowPosition::At(IVec2::new(x, y));
+                }
+            }
+            None => window.set_maximized(true),
+        }
+    }
+}
+
+/// Only the first time the project list is shown, after the login. A link the app was started
+/// with takes precedence.
+fn resume_system(mut resumed: Local<bool>, mut pending: ResMut<PendingDeepLink>, workspace: Res<Workspace>) {
+    if std::mem::replace(&mut *resumed, true) || pending.0.is_some() {
+        return;
+    }
+    pending.0 = workspace.resume_link();
+    if let Some(link) = pending.0 {
+        info!("Resuming task {} of project {}", link.task_id, link.project_id);
+    }
+}
+
+fn record_project_system(parameters: Option<Res<tasks::Parameters>>, mut workspace: ResMut<Workspace>) {
+    let Some(parameters) = parameters else {
+        return;
+    };
+    workspace.open_project(&parameters.project_id);
+    save(&workspace);
+}
+
+/// Runs on every task the editor opens, also when it moves on to the next task in place
+fn record_task_system(parameters: Res<detail::Parameters>, mut workspace: ResMut<Workspace>) {
+    let (Some(project_id), Some(task_id)) = (parameters.project_id, parameters.task_id) else {
+        return;
+    };
+    workspace.open_project(&project_id.to_string()).last_task_id = Some(task_id);
+    workspace.editor_open = true;
+    save(&workspace);
+}
+
+fn leave_editor_system(mut workspace: ResMut<Workspace>) {
+    workspace.editor_open = false;
+    save(&workspace);
+}
+
+/// Keeps the layout up to date in memory, it is saved when the app exits
+fn track_window_system(
+    windows: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut workspace: ResMut<Workspace>,
+) {
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    if !platform::DESKTOP || window.width() < MIN_WINDOW_SIZE || window.height() < MIN_WINDOW_SIZE {
+        return;
+    }
+    let layout = WindowLayout {
+        position: match window.position {
+            WindowPosition::At(position) => Some(position.to_array()),
+            _ => None,
+        },
+        size: [window.width(), window.height()],
+    };
+    if workspace.window != Some(layout) {
+        workspace.window = Some(layout);
+    }
+}
+
+fn save_on_exit_system(mut exits: EventReader<AppExit>, workspace: Res<Workspace>) {
+    if !exits.is_empty() {
+        exits.clear();
+        save(&workspace);
+    }
+}
@@ -0,0 +1,405 @@
+use crate::{session, upload, yolo};
+use chrono::{DateTime, Local, Utc};
+use fast_tag_client::auth::AuthApi;
+use fast_tag_client::export::{ExportApi, ExportOptions};
+use fast_tag_client::import::{ImportApi, ImportDryRun, ImportResult};
+use fast_tag_client::projects::{CleanupRequest, ProjectsApi};
+use fast_tag_client::stats::StatsApi;
+use fast_tag_client::sync::{SyncApi, SyncRequest};
+use fast_tag_formats::coco::CocoImport;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+/// How often the server is asked whether the login in the browser is done
+const LOGIN_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Time the user has to finish the login in the browser
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+/// How often the progress of a running sync is checked
+const SYNC_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub async fn login(provider: &str) -> Result<(), String> {
+    let auth_api = AuthApi::new();
+    let auth = auth_api
+        .start_oauth(provider, Some(&format!("fast-tag CLI ({})", std::env::consts::OS)))
+        .await
+        .map_err(|e| format!("Failed to start the login: {}", e))?;
+
+    println!("Open this URL to sign in:\n\n    {}\n", auth.auth_url);
+    // Machines without a browser show the URL above, which can be opened anywhere else
+    let _ = open::that(&auth.auth_url);
+    println!("Waiting for the login to complete...");
+
+    let started = Instant::now();
+    while started.elapsed() < LOGIN_TIMEOUT {
+        tokio::time::sleep(LOGIN_POLL_INTERVAL).await;
+        let poll = auth_api
+            .poll_auth(&auth.poll_token)
+            .await
+            .map_err(|e| format!("Failed to poll for the login: {}", e))?;
+
+        match poll.status.as_str() {
+            "pending" => {}
+            "completed" => {
+                let jwt = poll.jwt.ok_or("The login completed without a token")?;
+                let user = auth_api
+                    .get_user_info(&jwt)
+                    .await
+                    .map_err(|e| format!("Failed to load the user: {}", e))?;
+                session::save(&jwt)?;
+                println!("Logged in as {} <{}>", user.name, user.email);
+                return Ok(());
+            }
+            "expired" => return Err("Authentication session expired".to_string()),
+            "failed" => return Err("Authentication failed".to_string()),
+            status => return Err(format!("Unknown status: {}", status)),
+        }
+    }
+    Err("Timed out waiting for the login".to_string())
+}
+
+pub fn logout() -> Result<(), String> {
+    if session::remove()? {
+        println!("Logged out");
+    } else {
+        println!("Not logged in");
+    }
+    Ok(())
+}
+
+pub async fn list_projects(token: &str) -> Result<(), String> {
+    let projects = ProjectsApi::new().list_projects(token).await.map_err(|e| e.to_string())?;
+    for project in projects {
+        println!("{}\t{}\t{}", project.id, project.name, project.task_type);
+    }
+    Ok(())
+}
+
+pub async fn create_project(
+    token: &str,
+    name: &str,
+    description: Option<&str>,
+    template: Option<&str>,
+    task_type: Option<&str>,
+) -> Result<(), String> {
+    let project = ProjectsApi::new()
+        .create_project(token, name, description, template, task_type)
+        .await
+        .map_err(|e| e.to_string())?;
+    println!("{}", project.id);
+    Ok(())
+}
+
+pub async fn sync(
+    token: &str,
+    project_id: Uuid,
+    prefix: Option<String>,
+    overwrite: bool,
+    skip_duplicates: bool,
+) -> Result<(), String> {
+    let request = SyncRequest {
+        prefix,
+        file_extensions: None,
+        overwrite_existing: Some(overwrite),
+        skip_duplicates: Some(skip_duplicates),
+    };
+    let sync_api = SyncApi::new();
+    let mut response = sync_api
+        .start_sync(token, project_id, &request)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // The server syncs in the background, its progress goes to stderr so stdout keeps the summary
+    let mut reported = None;
+    while response.is_running() {
+        if response.total_files > 0 && reported != Some(response.processed_files) {
+            eprintln!("{}/{} file(s)", response.processed_files, response.total_files);
+            reported = Some(response.processed_files);
+        }
+        tokio::time::sleep(SYNC_POLL_INTERVAL).await;
+        response = sync_api
+            .get_sync_status(token, project_id, response.sync_id)
+            .await
+            .map_err(|e| format!("Failed to check the sync: {}", e))?;
+    }
+    if response.status == "failed" {
+        return Err(format!("Sync failed: {}", response.errors.join(", ")));
+    }
+
+    println!(
+        "{} file(s) synced: {} task(s) created, {} skipped",
+        response.total_files, response.tasks_created, response.tasks_skipped
+    );
+    for error in &response.errors {
+        eprintln!("  {}", error);
+    }
+    if response.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} file(s) failed to sync", response.errors.len()))
+    }
+}
+
+fn report_import(result: ImportResult) -> Result<(), String> {
+    let stats = &result.stats;
+    println!(
+        "{} categories created, {} updated, {} task(s) created, {} annotation(s) created",
+        stats.categories_created, stats.categories_updated, stats.tasks_created, stats.annotations_created
+    );
+    if stats.images_fetched > 0 {
+        println!("{} image(s) downloaded into the project storage", stats.images_fetched);
+    }
+    for error in &stats.errors {
+        eprintln!("  {}", error);
+    }
+    if result.success { Ok(()) } else { Err(result.message) }
+}
+
+fn report_dry_run(dry_run: ImportDryRun) -> Result<(), String> {
+    let validation = &dry_run.validation;
+    println!(
+        "{} categories, {} image(s), {} annotation(s)",
+        validation.categories, validation.images, validation.annotations
+    );
+    println!(
+        "Would create {} categories and update {}, create {} task(s) and update {}, annotate {} task(s)",
+        dry_run.categories_to_create,
+        dry_run.categories_to_update,
+        dry_run.tasks_to_create,
+        dry_run.tasks_to_update,
+        dry_run.tasks_to_annotate
+    );
+    if dry_run.images_to_fetch > 0 {
+        println!("{} image(s) can be downloaded with --fetch-images", dry_run.images_to_fetch);
+    }
+    for conflict in &dry_run.coco_id_conflicts {
+        eprintln!("  {}", conflict);
+    }
+    for issue in &validation.issues {
+        eprintln!("  {}", issue.message);
+    }
+    let listed = validation.issues.len();
+    let total: usize = validation.issue_counts.values().sum();
+    if total > listed {
+        eprintln!("  ... and {} more", total - listed);
+    }
+    if dry_run.valid { Ok(()) } else { Err(format!("{} problem(s) in the file", total)) }
+}
+
+pub async fn import_coco(
+    token: &str,
+    project_id: Uuid,
+    path: &Path,
+    dry_run: bool,
+    fetch_images: bool,
+) -> Result<(), String> {
+    if dry_run {
+        let content = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let result = ImportApi::new()
+            .check_coco_import(token, project_id, content)
+            .await
+            .map_err(|e| e.to_string())?;
+        return report_dry_run(result);
+    }
+    let result = ImportApi::new()
+        .import_coco_file(token, project_id, &path.to_string_lossy(), fetch_images)
+        .await
+        .map_err(|e| e.to_string())?;
+    report_import(result)
+}
+
+pub async fn import_yolo(
+    token: &str,
+    project_id: Uuid,
+    dataset: &Path,
+    classes: Option<&Path>,
+    dry_run: bool,
+) -> Result<(), String> {
+    let coco = yolo::read_dataset(dataset, classes)?;
+    let content = serde_json::to_vec(&coco).map_err(|e| e.to_string())?;
+    if dry_run {
+        let result = ImportApi::new()
+            .check_coco_import(token, project_id, content)
+            .await
+            .map_err(|e| e.to_string())?;
+        return report_dry_run(result);
+    }
+    let result = ImportApi::new()
+        .import_coco(token, project_id, content, false)
+        .await
+        .map_err(|e| e.to_string())?;
+    report_import(result)
+}
+
+pub async fn export_coco(token: &str, project_id: Uuid, output: &Path, with_metadata: bool) -> Result<(), String> {
+    let data = ExportApi::new()
+        .download_coco_export(token, project_id, &ExportOptions { include_metadata: with_metadata, ..Default::default() })
+        .await
+        .map_err(|e| e.to_string())?;
+    std::fs::write(output, &data).map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+    println!("Wrote {}", output.display());
+    Ok(())
+}
+
+pub async fn deliver_coco(
+    token: &str,
+    project_id: Uuid,
+    folder: &str,
+    bucket: Option<&str>,
+    with_metadata: bool,
+    with_images: bool,
+) -> Result<(), String> {
+    let options = ExportOptions { include_images: with_images, include_metadata: with_metadata, ..Default::default() };
+    let delivery = ExportApi::new()
+        .deliver_coco_export(token, project_id, &options, folder, bucket)
+        .await
+        .map_err(|e| e.to_string())?;
+    eprintln!("Wrote {} bytes to {}", delivery.size_bytes, delivery.location);
+    // The key alone on stdout, for scripts
+    println!("{}", delivery.key);
+    Ok(())
+}
+
+pub async fn export_yolo(token: &str, project_id: Uuid, output: &Path) -> Result<(), String> {
+    let data = ExportApi::new()
+        .download_coco_export(token, project_id, &ExportOptions::default())
+        .await
+        .map_err(|e| e.to_string())?;
+    let coco: CocoImport = serde_json::from_slice(&data).map_err(|e| format!("Invalid COCO export: {}", e))?;
+    let label_files = yolo::write_dataset(&coco, output)?;
+    println!("Wrote {} label file(s) to {}", label_files, output.display());
+    Ok(())
+}
+
+pub async fn export_mot(token: &str, project_id: Uuid, output: &Path) -> Result<(), String> {
+    let data = ExportApi::new()
+        .download_mot_export(token, project_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    std::fs::write(output, &data).map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+    println!("Wrote {}", output.display());
+    Ok(())
+}
+
+pub async fn export_kitti(token: &str, project_id: Uuid, output: &Path) -> Result<(), String> {
+    let data = ExportApi::new()
+        .download_kitti_export(token, project_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    std::fs::write(output, &data).map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+    println!("Wrote {}", output.display());
+    Ok(())
+}
+
+pub async fn upload(token: &str, project_id: Uuid, paths: &[PathBuf], parallel: usize) -> Result<(), String> {
+    let permits = Arc::new(Semaphore::new(parallel.max(1)));
+    let mut uploads = JoinSet::new();
+    let mut failed = 0;
+
+    for path in upload::expand_folders(paths) {
+        let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let Some(content_type) = upload::content_type(&path) else {
+            eprintln!("✖ {}: Not an image", name);
+            failed += 1;
+            continue;
+        };
+
+        let token = token.to_string();
+        let project_id = project_id.to_string();
+        let permits = permits.clone();
+        uploads.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let result = upload::upload_image(&token, &project_id, &path, &name, content_type).await;
+            (name, result)
+        });
+    }
+
+    let mut created = 0;
+    while let Some(finished) = uploads.join_next().await {
+        let (name, result) = finished.map_err(|e| e.to_string())?;
+        match result {
+            Ok(()) => {
+                created += 1;
+                println!("✔ {}", name);
+            }
+            Err(error) => {
+                failed += 1;
+                eprintln!("✖ {}: {}", name, error);
+            }
+        }
+    }
+
+    println!("{} task(s) created, {} file(s) failed", created, failed);
+    if failed == 0 { Ok(()) } else { Err(format!("{} file(s) failed to upload", failed)) }
+}
+
+/// Local midnight, where "today" of the daily counts starts
+fn start_of_today() -> DateTime<Utc> {
+    let midnight = Local::now().date_naive().and_hms_opt(0, 0, 0).unwrap();
+    midnight
+        .and_local_timezone(Local)
+        .earliest()
+        .map_or_else(Utc::now, |midnight| midnight.with_timezone(&Utc))
+}
+
+pub async fn stats(token: &str, project_id: Uuid) -> Result<(), String> {
+    let stats = StatsApi::new()
+        .get_project_stats(token, project_id, start_of_today())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let percent = |count: i64| {
+        if stats.total_tasks > 0 { count as f64 * 100.0 / stats.total_tasks as f64 } else { 0.0 }
+    };
+    println!("Tasks:           {}", stats.total_tasks);
+    println!("Annotated:       {} ({:.1}%)", stats.annotated_tasks, percent(stats.annotated_tasks));
+    println!("Completed:       {} ({:.1}%)", stats.completed_tasks, percent(stats.completed_tasks));
+    println!("Annotated today: {} ({} by you)", stats.annotated_today, stats.annotated_today_by_me);
+    Ok(())
+}
+
+pub async fn cleanup(
+    token: &str,
+    project_id: Uuid,
+    apply: bool,
+    storage: bool,
+    prefix: Option<String>,
+) -> Result<(), String> {
+    let request = CleanupRequest {
+        dry_run: Some(!apply),
+        include_storage: Some(storage),
+        prefix,
+    };
+    let report = ProjectsApi::new()
+        .cleanup(token, &project_id.to_string(), &request)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let verb = if report.dry_run { "Would delete" } else { "Deleted" };
+    println!(
+        "{} {} empty annotation(s) and {} box(es) without a category",
+        verb,
+        report.empty_annotations.len(),
+        report.uncategorized_boxes.len()
+    );
+    if storage {
+        println!("{} {} unused storage file(s)", verb, report.unused_objects.len());
+        for key in &report.unused_objects {
+            println!("  {}", key);
+        }
+    }
+    if report.dry_run {
+        println!("Nothing was deleted, run again with --apply to delete");
+    }
+    for error in &report.errors {
+        eprintln!("  {}", error);
+    }
+    if report.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} file(s) could not be deleted", report.errors.len()))
+    }
+}
@@ -0,0 +1,222 @@
+//! `fast-tag-cli`: the project operations of the app without the GUI, for scripts and CI jobs.
+//! Commands talk to the same API through `fast-tag-client`; the login is kept per server in the
+//! user's config directory, or taken from `FAST_TAG_TOKEN`.
+
+mod commands;
+mod session;
+mod upload;
+mod yolo;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use fast_tag_client::ApiConfig;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use uuid::Uuid;
+
+#[derive(Parser)]
+#[command(name = "fast-tag-cli", version, about = "Work with fast-tag projects from the command line")]
+struct Cli {
+    /// API server to talk to, `API_BASE_URL` or http://localhost:8080 when not given
+    #[arg(long, global = true)]
+    server: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Sign in through the browser and keep the token for the following commands
+    Login {
+        /// OAuth provider to sign in with
+        #[arg(long, default_value = "github")]
+        provider: String,
+    },
+    /// Forget the token kept for the server
+    Logout,
+    /// List or create projects
+    #[command(subcommand)]
+    Projects(ProjectsCommand),
+    /// Create tasks for the images in the project's storage bucket
+    Sync {
+        project_id: Uuid,
+        /// Only sync the files under this prefix
+        #[arg(long)]
+        prefix: Option<String>,
+        /// Recreate tasks that already exist for a file
+        #[arg(long)]
+        overwrite: bool,
+        /// Skip images whose content matches a task the project already has
+        #[arg(long)]
+        skip_duplicates: bool,
+    },
+    /// Import annotations into a project, creating its categories and tasks as needed
+    Import {
+        project_id: Uuid,
+        /// COCO annotation file, or the folder of a YOLO dataset
+        path: PathBuf,
+        #[arg(long, value_enum, default_value_t = ImportFormat::Coco)]
+        format: ImportFormat,
+        /// Class names of a YOLO dataset, `classes.txt` in its folder when not given
+        #[arg(long)]
+        classes: Option<PathBuf>,
+        /// Only check the file and show what would be imported, without changing the project
+        #[arg(long)]
+        dry_run: bool,
+        /// Download the images the COCO file links to into the project's storage
+        #[arg(long)]
+        fetch_images: bool,
+    },
+    /// Export the annotations of a project
+    Export {
+        project_id: Uuid,
+        /// File to write the COCO annotations or MOT/KITTI archive to, or folder for the YOLO dataset
+        #[arg(required_unless_present = "to_storage")]
+        output: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = ExportFormat::Coco)]
+        format: ExportFormat,
+        /// Add the annotator, timestamps and review state of each annotation under `x-fasttag`,
+        /// COCO only
+        #[arg(long)]
+        with_metadata: bool,
+        /// Have the server write the export into this folder of the project storage and print
+        /// its key, instead of downloading it. COCO only.
+        #[arg(long, value_name = "FOLDER", conflicts_with = "output")]
+        to_storage: Option<String>,
+        /// Bucket to write to with `--to-storage`, when not the project's own
+        #[arg(long, requires = "to_storage")]
+        bucket: Option<String>,
+        /// Package the images with the annotations as a ZIP, with `--to-storage`
+        #[arg(long, requires = "to_storage")]
+        with_images: bool,
+    },
+    /// Upload images into the project's storage and create a task for each
+    Upload {
+        project_id: Uuid,
+        /// Images, or folders whose images are uploaded
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Files sent to the server at the same time
+        #[arg(long, default_value_t = 3)]
+        parallel: usize,
+    },
+    /// Show how far the annotation of a project is
+    Stats {
+        project_id: Uuid,
+    },
+    /// Report annotations and storage files nothing uses anymore, and delete them with `--apply`
+    Cleanup {
+        project_id: Uuid,
+        /// Delete what is found instead of only listing it
+        #[arg(long)]
+        apply: bool,
+        /// Also look for images and videos in the project storage that no task uses
+        #[arg(long)]
+        storage: bool,
+        /// Only look at the storage files under this prefix
+        #[arg(long, requires = "storage")]
+        prefix: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProjectsCommand {
+    /// List the projects the user has access to, one `id<TAB>name<TAB>task type` per line
+    List,
+    /// Create a project and print its ID
+    Create {
+        name: String,
+        #[arg(long)]
+        description: Option<String>,
+        /// Template whose categories the project starts with
+        #[arg(long)]
+        template: Option<String>,
+        /// `detection` (bounding boxes) or `classification` (whole-image labels)
+        #[arg(long)]
+        task_type: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ImportFormat {
+    Coco,
+    Yolo,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Coco,
+    Yolo,
+    /// Object tracks as MOTChallenge sequences, in a ZIP
+    Mot,
+    /// KITTI label files, tracking files for videos, in a ZIP
+    Kitti,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Some(server) = &cli.server {
+        ApiConfig::set_base_url(server);
+    }
+
+    match run(cli.command).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(command: Command) -> Result<(), String> {
+    match command {
+        Command::Login { provider } => commands::login(&provider).await,
+        Command::Logout => commands::logout(),
+        Command::Projects(ProjectsCommand::List) => commands::list_projects(&session::token()?).await,
+        Command::Projects(ProjectsCommand::Create { name, description, template, task_type }) => {
+            commands::create_project(
+                &session::token()?,
+                &name,
+                description.as_deref(),
+                template.as_deref(),
+                task_type.as_deref(),
+            )
+            .await
+        }
+        Command::Sync { project_id, prefix, overwrite, skip_duplicates } => {
+            commands::sync(&session::token()?, project_id, prefix, overwrite, skip_duplicates).await
+        }
+        Command::Import { project_id, path, format, classes, dry_run, fetch_images } => {
+            let token = session::token()?;
+            match format {
+                ImportFormat::Coco => commands::import_coco(&token, project_id, &path, dry_run, fetch_images).await,
+                ImportFormat::Yolo => commands::import_yolo(&token, project_id, &path, classes.as_deref(), dry_run).await,
+            }
+        }
+        Command::Export { project_id, output, format, with_metadata, to_storage, bucket, with_images } => {
+            let token = session::token()?;
+            match (format, to_storage, output) {
+                (ExportFormat::Coco, Some(folder), _) => {
+                    commands::deliver_coco(&token, project_id, &folder, bucket.as_deref(), with_metadata, with_images).await
+                }
+                (ExportFormat::Yolo, Some(_), _) => Err("YOLO datasets are converted on this machine, use --format coco with --to-storage".to_string()),
+                (ExportFormat::Mot | ExportFormat::Kitti, Some(_), _) => {
+                    Err("MOT and KITTI archives are downloaded, use --format coco with --to-storage".to_string())
+                }
+                (ExportFormat::Coco, None, Some(output)) => commands::export_coco(&token, project_id, &output, with_metadata).await,
+                (ExportFormat::Yolo, None, Some(output)) => commands::export_yolo(&token, project_id, &output).await,
+                (ExportFormat::Mot, None, Some(output)) => commands::export_mot(&token, project_id, &output).await,
+                (ExportFormat::Kitti, None, Some(output)) => commands::export_kitti(&token, project_id, &output).await,
+                (_, None, None) => Err("An output path or --to-storage is required".to_string()),
+            }
+        }
+        Command::Upload { project_id, paths, parallel } => {
+            commands::upload(&session::token()?, project_id, &paths, parallel).await
+        }
+        Command::Stats { project_id } => commands::stats(&session::token()?, project_id).await,
+        Command::Cleanup { project_id, apply, storage, prefix } => {
+            commands::cleanup(&session::token()?, project_id, apply, storage, prefix).await
+        }
+    }
+}
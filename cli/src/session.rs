@@ -0,0 +1,69 @@
+use fast_tag_client::ApiConfig;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Token used instead of the stored login, for CI jobs that can't open a browser
+const TOKEN_VARIABLE: &str = "FAST_TAG_TOKEN";
+
+/// Tokens by server URL, so logins to different servers don't replace each other
+fn sessions_path() -> Result<PathBuf, String> {
+    dirs::config_dir()
+        .map(|dir| dir.join("fast-tag").join("cli-sessions.json"))
+        .ok_or_else(|| "No config directory to keep the login in".to_string())
+}
+
+fn load_sessions() -> HashMap<String, String> {
+    sessions_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_sessions(sessions: &HashMap<String, String>) -> Result<(), String> {
+    let path = sessions_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let content = serde_json::to_string_pretty(sessions).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    // The tokens grant the user's access, other users of the machine shouldn't read them
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Token for the selected server, from `FAST_TAG_TOKEN` or the last `login`
+pub fn token() -> Result<String, String> {
+    let variable = std::env::var(TOKEN_VARIABLE).ok();
+    if let Some(token) = variable.as_deref().map(str::trim).filter(|token| !token.is_empty()) {
+        return Ok(token.to_string());
+    }
+
+    let server = ApiConfig::default().base_url;
+    load_sessions().remove(&server).ok_or_else(|| {
+        format!("Not logged in to {}, run `fast-tag-cli login` or set {}", server, TOKEN_VARIABLE)
+    })
+}
+
+pub fn save(jwt: &str) -> Result<(), String> {
+    let mut sessions = load_sessions();
+    sessions.insert(ApiConfig::default().base_url, jwt.to_string());
+    save_sessions(&sessions)
+}
+
+/// Removes the token of the selected server, telling whether there was one
+pub fn remove() -> Result<bool, String> {
+    let mut sessions = load_sessions();
+    if sessions.remove(&ApiConfig::default().base_url).is_none() {
+        return Ok(false);
+    }
+    save_sessions(&sessions)?;
+    Ok(true)
+}
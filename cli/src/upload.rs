@@ -0,0 +1,72 @@
+use fast_tag_client::storage::StorageApi;
+use fast_tag_client::tasks::TasksApi;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+
+/// Extensions of the files that are uploaded, with their content types
+const IMAGE_TYPES: [(&str, &str); 8] = [
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("png", "image/png"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("bmp", "image/bmp"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+];
+
+pub fn content_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    IMAGE_TYPES
+        .iter()
+        .find(|(image_extension, _)| *image_extension == extension)
+        .map(|(_, content_type)| *content_type)
+}
+
+/// The given files, with the images directly inside folders in place of the folders
+pub fn expand_folders(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            files.extend(images_in(path));
+        } else {
+            files.push(path.clone());
+        }
+    }
+    files
+}
+
+/// Images directly inside a folder, sorted by name
+pub fn images_in(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    paths.retain(|path| path.is_file() && content_type(path).is_some());
+    paths.sort();
+    paths
+}
+
+/// Puts an image into the project's storage under its file name and creates a task showing it.
+/// The key is the one a sync of the bucket would create the task from, so a later sync doesn't
+/// add it a second time.
+pub async fn upload_image(
+    token: &str,
+    project_id: &str,
+    path: &Path,
+    name: &str,
+    content_type: &str,
+) -> Result<(), String> {
+    let data = tokio::fs::read(path).await.map_err(|e| format!("Failed to read file: {}", e))?;
+    let uploaded = StorageApi::new()
+        .upload_file(token, project_id, name, data, content_type, Arc::new(AtomicU64::new(0)))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let resource_url = format!("storage://{}", uploaded.key);
+    TasksApi::new()
+        .create_task(token, project_id, name, Some(&resource_url))
+        .await
+        .map_err(|e| format!("Uploaded, but failed to create the task: {}", e))?;
+    Ok(())
+}
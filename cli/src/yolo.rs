@@ -0,0 +1,68 @@
+//! YOLO datasets on disk: `classes.txt` next to `images/` and `labels/`, converted with
+//! `fast_tag_formats::yolo`
+
+use crate::upload::images_in;
+use fast_tag_formats::coco::CocoImport;
+use fast_tag_formats::yolo::{self, CLASSES_FILE, YoloDataset, YoloImage};
+use std::fs;
+use std::path::Path;
+
+const LABELS_DIR: &str = "labels";
+const IMAGES_DIR: &str = "images";
+
+fn write_file(path: &Path, content: &str) -> Result<(), String> {
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Writes the boxes of a COCO export as a YOLO dataset into `output`, returning the number of
+/// label files
+pub fn write_dataset(coco: &CocoImport, output: &Path) -> Result<usize, String> {
+    let dataset = yolo::from_coco(coco);
+    let labels_dir = output.join(LABELS_DIR);
+    fs::create_dir_all(&labels_dir).map_err(|e| format!("Failed to create {}: {}", labels_dir.display(), e))?;
+
+    let class_names: String = dataset.classes.iter().map(|name| format!("{}\n", name)).collect();
+    write_file(&output.join(CLASSES_FILE), &class_names)?;
+    for image in &dataset.images {
+        write_file(&labels_dir.join(yolo::label_file_name(&image.file_name)), &image.labels)?;
+    }
+    Ok(dataset.images.len())
+}
+
+/// Reads a YOLO dataset as COCO, so the server can import it. Images are taken from `images/`
+/// (or the dataset folder itself) for their size and name: each becomes the task of that name,
+/// the one `upload` or a bucket sync creates for the same file.
+pub fn read_dataset(dataset: &Path, classes_file: Option<&Path>) -> Result<CocoImport, String> {
+    let classes_path = classes_file.map_or_else(|| dataset.join(CLASSES_FILE), Path::to_path_buf);
+    let classes: Vec<String> = fs::read_to_string(&classes_path)
+        .map_err(|e| format!("Failed to read {}: {}", classes_path.display(), e))?
+        .lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect();
+    if classes.is_empty() {
+        return Err(format!("No classes in {}", classes_path.display()));
+    }
+
+    let subdir = |name: &str| {
+        let dir = dataset.join(name);
+        if dir.is_dir() { dir } else { dataset.to_path_buf() }
+    };
+    let (images_dir, labels_dir) = (subdir(IMAGES_DIR), subdir(LABELS_DIR));
+
+    let mut images = Vec::new();
+    for path in images_in(&images_dir) {
+        let (width, height) = image::image_dimensions(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        // Images without a label file have no boxes
+        let labels = fs::read_to_string(labels_dir.join(yolo::label_file_name(&file_name))).unwrap_or_default();
+        images.push(YoloImage { file_name, width, height, labels });
+    }
+    if images.is_empty() {
+        return Err(format!("No images in {}", images_dir.display()));
+    }
+
+    yolo::to_coco(&YoloDataset { classes, images })
+}
@@ -0,0 +1,213 @@
+use crate::auth::{AuthResponse, User, UserInfoResponse};
+use crate::{ApiClient, ApiError, ApiResult, ApiConfig};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+pub struct UpdateProfileRequest {
+    pub name: String,
+}
+
+/// The updated account, with a token carrying the new name
+#[derive(Debug, Deserialize)]
+pub struct ProfileResponse {
+    pub user: User,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinkedProvider {
+    pub provider: String,
+    /// Email of the provider account
+    pub email: String,
+    #[allow(dead_code)]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinkedProvidersResponse {
+    pub providers: Vec<LinkedProvider>,
+    /// Every provider accounts can be linked from
+    pub available: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnershipTransfer {
+    pub project_id: Uuid,
+    /// A member of the project who becomes its owner
+    pub new_owner_id: Uuid,
+}
+
+/// A signed-in device
+#[derive(Debug, Clone, Deserialize)]
+pub struct Session {
+    pub id: Uuid,
+    pub device_name: Option<String>,
+    /// Browser the login was completed in
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    #[allow(dead_code)]
+    pub created_at: DateTime<Utc>,
+    /// Last sign-in or refresh
+    pub last_used_at: DateTime<Utc>,
+    #[allow(dead_code)]
+    pub expires_at: DateTime<Utc>,
+    /// The session of the token the list was requested with
+    #[serde(default)]
+    pub current: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionsResponse {
+    pub sessions: Vec<Session>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteAccountRequest {
+    /// Email of the account, typed again to confirm the deletion
+    pub confirm_email: String,
+    pub transfers: Vec<OwnershipTransfer>,
+}
+
+/// Owned project other members work in
+#[derive(Debug, Clone, Deserialize)]
+pub struct SharedProject {
+    pub id: Uuid,
+    pub name: String,
+    pub other_members: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountDeletionBlocked {
+    projects: Vec<SharedProject>,
+}
+
+pub enum AccountDeletion {
+    Deleted,
+    /// These projects need a new owner before the account can go
+    Blocked(Vec<SharedProject>),
+}
+
+pub struct AccountApi {
+    client: reqwest::Client,
+    api_client: ApiClient,
+    config: ApiConfig,
+}
+
+impl AccountApi {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_client: ApiClient::new(),
+            config: ApiConfig::default(),
+        }
+    }
+
+    /// Renames the account. The returned token replaces the current one, which still carries the
+    /// old name.
+    pub async fn update_profile(&self, jwt: &str, name: &str) -> ApiResult<ProfileResponse> {
+        let request = UpdateProfileRequest { name: name.to_string() };
+        self.api_client.put("/me", &request, Some(jwt)).await
+    }
+
+    /// Uploads a PNG, JPEG, WebP or GIF image of at most 2 MiB as the avatar
+    pub async fn upload_avatar(&self, jwt: &str, data: Vec<u8>) -> ApiResult<User> {
+        let url = format!("{}/me/avatar", self.config.base_url);
+        let response = self.client
+            .put(&url)
+            .header("Authorization", format!("Bearer {}", jwt))
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .body(data)
+            .send()
+            .await?;
+
+        match response.status() {
+            reqwest::StatusCode::OK => {
+                let info: UserInfoResponse = response.json().await?;
+                Ok(info.user)
+            }
+            reqwest::StatusCode::BAD_REQUEST => {
+                let error_text = response.text().await.unwrap_or_default();
+                Err(ApiError::BadRequest(error_text))
+            }
+            reqwest::StatusCode::UNAUTHORIZED => Err(ApiError::AuthenticationError("Unauthorized".to_string())),
+            status => {
+                let error_text = response.text().await.unwrap_or_else(|_| status.to_string());
+                Err(ApiError::ServerError(format!("Server error: {}", error_text)))
+            }
+        }
+    }
+
+    pub async fn delete_avatar(&self, jwt: &str) -> ApiResult<()> {
+        self.api_client.delete("/me/avatar", Some(jwt)).await
+    }
+
+    /// Avatar image of a user, uploaded or from their sign-in provider
+    pub async fn get_avatar(&self, jwt: &str, user_id: &str) -> ApiResult<Vec<u8>> {
+        let endpoint = format!("/users/{}/avatar", user_id);
+        self.api_client.get_endpoint_bytes(&endpoint, Some(jwt)).await
+    }
+
+    pub async fn list_providers(&self, jwt: &str) -> ApiResult<LinkedProvidersResponse> {
+        self.api_client.get("/me/providers", Some(jwt)).await
+    }
+
+    /// Starts a login to `provider` that links the account signed in to rather than signing in
+    /// with it. Poll it with [`crate::auth::AuthApi::poll_auth`] like a login.
+    pub async fn link_provider(&self, jwt: &str, provider: &str) -> ApiResult<AuthResponse> {
+        let endpoint = format!("/me/providers/{}", provider);
+        self.api_client.post(&endpoint, &(), Some(jwt)).await
+    }
+
+    pub async fn unlink_provider(&self, jwt: &str, provider: &str) -> ApiResult<()> {
+        let endpoint = format!("/me/providers/{}", provider);
+        self.api_client.delete(&endpoint, Some(jwt)).await
+    }
+
+    /// Devices signed in to the account, most recently used first
+    pub async fn list_sessions(&self, jwt: &str) -> ApiResult<Vec<Session>> {
+        let response: SessionsResponse = self.api_client.get("/me/sessions", Some(jwt)).await?;
+        Ok(response.sessions)
+    }
+
+    /// Signs a device out, its tokens stop working right away
+    pub async fn revoke_session(&self, jwt: &str, session_id: Uuid) -> ApiResult<()> {
+        let endpoint = format!("/me/sessions/{}", session_id);
+        self.api_client.delete(&endpoint, Some(jwt)).await
+    }
+
+    /// Deletes the account, handing the shared projects over as `request.transfers` says
+    pub async fn delete_account(&self, jwt: &str, request: &DeleteAccountRequest) -> ApiResult<AccountDeletion> {
+        let url = format!("{}/me", self.config.base_url);
+        let response = self.client
+            .delete(&url)
+            .header("Authorization", format!("Bearer {}", jwt))
+            .json(request)
+            .send()
+            .await?;
+
+        match response.status() {
+            reqwest::StatusCode::NO_CONTENT => Ok(AccountDeletion::Deleted),
+            reqwest::StatusCode::CONFLICT => {
+                let blocked: AccountDeletionBlocked = response.json().await?;
+                Ok(AccountDeletion::Blocked(blocked.projects))
+            }
+            reqwest::StatusCode::BAD_REQUEST => {
+                let error_text = response.text().await.unwrap_or_default();
+                Err(ApiError::BadRequest(error_text))
+            }
+            reqwest::StatusCode::UNAUTHORIZED => Err(ApiError::AuthenticationError("Unauthorized".to_string())),
+            status => {
+                let error_text = response.text().await.unwrap_or_else(|_| status.to_string());
+                Err(ApiError::ServerError(format!("Server error: {}", error_text)))
+            }
+        }
+    }
+}
+
+impl Default for AccountApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
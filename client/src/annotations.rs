@@ -0,0 +1,333 @@
+use super::{ApiClient, ApiError, ApiResult};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Annotation {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub metadata: serde_json::Value,
+    pub annotated_by: Option<Uuid>,
+    pub annotated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImageAnnotation {
+    #[serde(rename = "id")]
+    pub image_id: Uuid,
+    pub annotation_id: Uuid,
+    pub category_id: Option<Uuid>,
+    pub bbox: Vec<f64>,
+    pub area: Option<f64>,
+    pub iscrowd: bool,
+    pub image_metadata: serde_json::Value,
+    #[serde(default)]
+    pub is_prediction: bool,
+    #[serde(default)]
+    pub confidence: Option<f64>,
+    #[serde(default)]
+    pub attributes: serde_json::Value,
+    #[serde(default)]
+    pub rotation: f64,
+    #[serde(rename = "created_at")]
+    pub image_created_at: DateTime<Utc>,
+    #[serde(rename = "updated_at")]
+    pub image_updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnnotationWithCategory {
+    // When flattened, ImageAnnotation fields overwrite Annotation fields with same name
+    // So 'id' will be the ImageAnnotation.id, not Annotation.id
+    pub id: Uuid, // This is actually ImageAnnotation.id
+    pub task_id: Uuid, // From Annotation
+    pub metadata: serde_json::Value, // From Annotation
+    pub annotated_by: Option<Uuid>, // From Annotation
+    pub annotated_at: DateTime<Utc>, // From Annotation
+    pub annotation_id: Uuid, // From ImageAnnotation
+    pub category_id: Option<Uuid>, // From ImageAnnotation
+    pub bbox: Vec<f64>, // From ImageAnnotation
+    pub area: Option<f64>, // From ImageAnnotation
+    pub iscrowd: bool, // From ImageAnnotation
+    pub image_metadata: serde_json::Value, // From ImageAnnotation
+    #[serde(default)]
+    pub is_prediction: bool, // From ImageAnnotation, true for unreviewed model suggestions
+    #[serde(default)]
+    pub confidence: Option<f64>, // From ImageAnnotation
+    #[serde(default)]
+    pub attributes: serde_json::Value, // From ImageAnnotation, values for the category's attribute schema
+    #[serde(default)]
+    pub rotation: f64, // From ImageAnnotation, clockwise degrees around the box center
+    #[serde(default)]
+    pub frame_index: Option<i32>, // From ImageAnnotation, frame of a video task
+    #[serde(default)]
+    pub track_id: Option<Uuid>, // From ImageAnnotation, object followed across video frames or image tasks
+    #[serde(default)]
+    pub is_interpolated: bool, // From ImageAnnotation, generated between two keyframes
+    #[serde(default)]
+    pub mask: Option<serde_json::Value>, // From ImageAnnotation, COCO RLE pixel mask with compressed counts
+    pub created_at: DateTime<Utc>, // This is actually ImageAnnotation.created_at
+    pub updated_at: DateTime<Utc>, // This is actually ImageAnnotation.updated_at
+    // Category fields
+    pub category_name: String,
+    pub category_color: Option<String>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BoundingBox {
+    pub category_id: Uuid,
+    pub bbox: Vec<f64>,
+    pub area: Option<f64>,
+    pub iscrowd: Option<bool>,
+    pub is_prediction: Option<bool>,
+    pub confidence: Option<f64>,
+    pub attributes: Option<serde_json::Value>,
+    pub rotation: Option<f64>,
+    pub frame_index: Option<i32>,
+    pub track_id: Option<Uuid>,
+    pub is_interpolated: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mask: Option<serde_json::Value>,
+}
+
+impl BoundingBox {
+    /// The box of a saved annotation, to save it again. None when its category was deleted.
+    pub fn from_annotation(annotation: &AnnotationWithCategory) -> Option<Self> {
+        Some(Self {
+            category_id: annotation.category_id?,
+            bbox: annotation.bbox.clone(),
+            area: annotation.area,
+            iscrowd: Some(annotation.iscrowd),
+            is_prediction: Some(annotation.is_prediction),
+            confidence: annotation.confidence,
+            attributes: Some(annotation.attributes.clone()),
+            rotation: Some(annotation.rotation),
+            frame_index: annotation.frame_index,
+            track_id: annotation.track_id,
+            is_interpolated: Some(annotation.is_interpolated),
+            mask: annotation.mask.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CreateAnnotationRequest {
+    pub bboxes: Vec<BoundingBox>,
+    pub metadata: Option<serde_json::Value>,
+    /// Latest annotation the boxes were edited from, the nil UUID when the task had none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_annotation_id: Option<Uuid>,
+}
+
+/// Body of the 409 a save gets when someone saved the task since its base annotation
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnnotationConflict {
+    /// None when the task has no annotations anymore
+    pub latest_annotation_id: Option<Uuid>,
+    pub annotations: Vec<AnnotationWithCategory>,
+}
+
+impl AnnotationConflict {
+    /// The conflict a save failed with, if that is why it failed
+    pub fn from_error(error: &ApiError) -> Option<Self> {
+        match error {
+            ApiError::Conflict(body) => serde_json::from_str(body).ok(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnnotationResponse {
+    pub annotations: Vec<AnnotationWithCategory>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnnotationsListResponse {
+    pub annotations: Vec<AnnotationWithCategory>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct InterpolationResponse {
+    pub annotation_id: Uuid,
+    pub track_count: usize,
+    pub interpolated_count: usize,
+}
+
+/// One box of a track in the latest annotation of its task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedBox {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub task_name: String,
+    /// Frame of a video task, `None` for images
+    pub frame_index: Option<i32>,
+    pub category_id: Option<Uuid>,
+    pub bbox: Vec<f64>,
+    pub rotation: f64,
+    pub is_interpolated: bool,
+}
+
+/// Boxes of a track across the frames and tasks of a project, in task name and frame order
+#[derive(Debug, Clone, Deserialize)]
+pub struct TrackResponse {
+    pub track_id: Uuid,
+    pub boxes: Vec<TrackedBox>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LinkTrackRequest {
+    pub box_ids: Vec<Uuid>,
+    /// Track to add the boxes to, a new one when `None`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_id: Option<Uuid>,
+}
+
+pub struct AnnotationsApi {
+    client: ApiClient,
+}
+
+impl AnnotationsApi {
+    pub fn new() -> Self {
+        Self {
+            client: ApiClient::new(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub async fn list_annotations(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        task_id: Uuid,
+    ) -> ApiResult<Vec<AnnotationWithCategory>> {
+        self.list_annotations_with_options(jwt, project_id, task_id, false).await
+    }
+
+    pub async fn list_annotations_with_options(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        task_id: Uuid,
+        latest_only: bool,
+    ) -> ApiResult<Vec<AnnotationWithCategory>> {
+        let mut endpoint = format!("/projects/{}/tasks/{}/annotations", project_id, task_id);
+        if latest_only {
+            endpoint.push_str("?latest_only=true");
+        }
+        let response: AnnotationsListResponse = self.client.get(&endpoint, Some(jwt)).await?;
+        Ok(response.annotations)
+    }
+
+    pub async fn create_annotation(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        task_id: Uuid,
+        request: &CreateAnnotationRequest,
+    ) -> ApiResult<Vec<AnnotationWithCategory>> {
+        let endpoint = format!("/projects/{}/tasks/{}/annotations", project_id, task_id);
+        let response: AnnotationResponse = self.client.post(&endpoint, request, Some(jwt)).await?;
+        Ok(response.annotations)
+    }
+
+    #[allow(dead_code)]
+    pub async fn delete_annotation(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        task_id: Uuid,
+        annotation_id: Uuid,
+    ) -> ApiResult<()> {
+        let endpoint = format!("/projects/{}/tasks/{}/annotations/{}", project_id, task_id, annotation_id);
+        self.client.delete(&endpoint, Some(jwt)).await
+    }
+
+    /// Fills the frames between the keyframes of each track in the latest annotation of a video task.
+    pub async fn interpolate(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        task_id: Uuid,
+    ) -> ApiResult<InterpolationResponse> {
+        let endpoint = format!("/projects/{}/tasks/{}/interpolate", project_id, task_id);
+        self.client.post(&endpoint, &(), Some(jwt)).await
+    }
+
+    pub async fn get_track(&self, jwt: &str, project_id: Uuid, track_id: Uuid) -> ApiResult<TrackResponse> {
+        let endpoint = format!("/projects/{}/tracks/{}", project_id, track_id);
+        self.client.get(&endpoint, Some(jwt)).await
+    }
+
+    /// Puts saved boxes of consecutive frames or image tasks on one track, at most one per
+    /// task and frame. Without `track_id` they start a new track.
+    pub async fn link_track(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        box_ids: &[Uuid],
+        track_id: Option<Uuid>,
+    ) -> ApiResult<TrackResponse> {
+        let endpoint = format!("/projects/{}/tracks", project_id);
+        let request = LinkTrackRequest { box_ids: box_ids.to_vec(), track_id };
+        self.client.post(&endpoint, &request, Some(jwt)).await
+    }
+
+    pub async fn unlink_track_box(&self, jwt: &str, project_id: Uuid, track_id: Uuid, box_id: Uuid) -> ApiResult<()> {
+        let endpoint = format!("/projects/{}/tracks/{}/boxes/{}", project_id, track_id, box_id);
+        self.client.delete(&endpoint, Some(jwt)).await
+    }
+
+    pub async fn save_annotations(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        task_id: Uuid,
+        bounding_boxes: &[BoundingBox],
+    ) -> ApiResult<Vec<AnnotationWithCategory>> {
+        self.save_annotations_based_on(jwt, project_id, task_id, bounding_boxes, None).await
+    }
+
+    /// Saves the boxes unless someone saved the task since `base_annotation_id`, the nil UUID
+    /// for a task that had no annotations. Those saves fail with `ApiError::Conflict`, which
+    /// [`AnnotationConflict::from_error`] reads the server's boxes from. Without a base the
+    /// boxes are saved over whatever is there.
+    pub async fn save_annotations_based_on(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        task_id: Uuid,
+        bounding_boxes: &[BoundingBox],
+        base_annotation_id: Option<Uuid>,
+    ) -> ApiResult<Vec<AnnotationWithCategory>> {
+        // Create new annotations without deleting existing ones to preserve history
+        if bounding_boxes.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let request = CreateAnnotationRequest {
+            bboxes: bounding_boxes.to_vec(),
+            metadata: None,
+            base_annotation_id,
+        };
+        
+        match self.create_annotation(jwt, project_id, task_id, &request).await {
+            Ok(saved) => Ok(saved),
+            Err(e) => {
+                println!("Failed to create annotations: {}", e);
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Default for AnnotationsApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
\ No newline at end of file
@@ -1,5 +1,5 @@
 use super::{ApiClient, ApiResult};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
 pub struct AuthResponse {
@@ -11,6 +11,21 @@ pub struct AuthResponse {
 pub struct PollResponse {
     pub status: String,
     pub jwt: Option<String>,
+    /// Only sent by servers that issue refresh tokens
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+/// A new JWT and the refresh token to use next time, the one sent no longer works
+#[derive(Debug, Deserialize)]
+pub struct RefreshResponse {
+    pub token: String,
+    pub refresh_token: String,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -40,9 +55,11 @@ impl AuthApi {
         }
     }
 
-    pub async fn start_oauth(&self, provider: &str) -> ApiResult<AuthResponse> {
+    /// Starts a login, `device` names the session it opens in the list of sessions
+    pub async fn start_oauth(&self, provider: &str, device: Option<&str>) -> ApiResult<AuthResponse> {
         let endpoint = format!("/auth/{}", provider);
-        self.client.get(&endpoint, None).await
+        let query: Vec<(&str, &str)> = device.map(|device| ("device", device)).into_iter().collect();
+        self.client.get_with_query(&endpoint, &query, None).await
     }
 
     pub async fn poll_auth(&self, poll_token: &str) -> ApiResult<PollResponse> {
@@ -50,6 +67,12 @@ impl AuthApi {
         self.client.get(&endpoint, None).await
     }
 
+    /// Trades the refresh token of a session for a new JWT
+    pub async fn refresh(&self, refresh_token: &str) -> ApiResult<RefreshResponse> {
+        let request = RefreshRequest { refresh_token: refresh_token.to_string() };
+        self.client.post("/auth/refresh", &request, None).await
+    }
+
     pub async fn get_user_info(&self, jwt: &str) -> ApiResult<User> {
         let response: UserInfoResponse = self.client.get("/me", Some(jwt)).await?;
         Ok(response.user)
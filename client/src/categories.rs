@@ -0,0 +1,225 @@
+use super::{ApiClient, ApiResult};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnnotationCategory {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub supercategory: Option<String>,
+    pub color: Option<String>,
+    pub description: Option<String>,
+    pub coco_id: Option<i32>,
+    pub parent_id: Option<Uuid>,
+    #[serde(default)]
+    pub attribute_schema: Vec<AttributeDefinition>,
+    /// Key code name of the project's shortcut for the category, e.g. `Digit1`
+    #[serde(default)]
+    pub hotkey: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeType {
+    Bool,
+    Enum,
+    String,
+    Number,
+}
+
+/// A custom attribute annotations of a category carry, e.g. `occluded: bool`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AttributeDefinition {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub attribute_type: AttributeType,
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub default: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CreateCategoryRequest {
+    pub name: String,
+    pub supercategory: Option<String>,
+    pub color: Option<String>,
+    pub description: Option<String>,
+    pub coco_id: Option<i32>,
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UpdateCategoryRequest {
+    pub name: String,
+    pub supercategory: Option<String>,
+    pub color: Option<String>,
+    pub description: Option<String>,
+    pub coco_id: Option<i32>,
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CategoryHotkey {
+    pub category_id: Uuid,
+    pub hotkey: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpdateHotkeysRequest {
+    pub hotkeys: Vec<CategoryHotkey>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CategoryResponse {
+    pub category: AnnotationCategory,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CategoriesListResponse {
+    pub categories: Vec<AnnotationCategory>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkCategoryImportResult {
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: Vec<String>,
+}
+
+pub struct CategoriesApi {
+    client: ApiClient,
+}
+
+impl CategoriesApi {
+    pub fn new() -> Self {
+        Self {
+            client: ApiClient::new(),
+        }
+    }
+
+    pub async fn list_categories(&self, jwt: &str, project_id: Uuid) -> ApiResult<Vec<AnnotationCategory>> {
+        let endpoint = format!("/projects/{}/image-annotation-categories", project_id);
+        let response: CategoriesListResponse = self.client.get(&endpoint, Some(jwt)).await?;
+        Ok(response.categories)
+    }
+
+    pub async fn create_category(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        request: &CreateCategoryRequest,
+    ) -> ApiResult<AnnotationCategory> {
+        let endpoint = format!("/projects/{}/image-annotation-categories", project_id);
+        let response: CategoryResponse = self.client.post(&endpoint, request, Some(jwt)).await?;
+        Ok(response.category)
+    }
+
+    /// Uploads a JSON array or CSV file of categories. `on_duplicate` is `skip`, `update` or `error`.
+    pub async fn import_categories_file(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        file_name: &str,
+        content: Vec<u8>,
+        on_duplicate: &str,
+    ) -> ApiResult<BulkCategoryImportResult> {
+        let form = reqwest::multipart::Form::new()
+            .part("file", reqwest::multipart::Part::bytes(content).file_name(file_name.to_string()));
+        let endpoint = format!("/projects/{}/image-annotation-categories/bulk?on_duplicate={}", project_id, on_duplicate);
+        self.client.post_multipart(&endpoint, form, Some(jwt)).await
+    }
+
+    /// Replaces the shortcuts of all categories of the project and returns the updated list.
+    pub async fn update_hotkeys(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        hotkeys: Vec<CategoryHotkey>,
+    ) -> ApiResult<Vec<AnnotationCategory>> {
+        let endpoint = format!("/projects/{}/image-annotation-categories/hotkeys", project_id);
+        let response: CategoriesListResponse = self.client.put(&endpoint, &UpdateHotkeysRequest { hotkeys }, Some(jwt)).await?;
+        Ok(response.categories)
+    }
+
+    #[allow(dead_code)]
+    pub async fn update_category(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        category_id: Uuid,
+        request: &UpdateCategoryRequest,
+    ) -> ApiResult<AnnotationCategory> {
+        let endpoint = format!("/projects/{}/image-annotation-categories/{}", project_id, category_id);
+        let response: CategoryResponse = self.client.put(&endpoint, request, Some(jwt)).await?;
+        Ok(response.category)
+    }
+
+    #[allow(dead_code)]
+    pub async fn delete_category(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        category_id: Uuid,
+        reassign_to: Option<Uuid>,
+        force: bool,
+    ) -> ApiResult<()> {
+        // Categories still used by annotations need a reassignment target or `force`
+        let mut endpoint = format!("/projects/{}/image-annotation-categories/{}", project_id, category_id);
+        if let Some(target_id) = reassign_to {
+            endpoint.push_str(&format!("?reassign_to={}", target_id));
+        } else if force {
+            endpoint.push_str("?force=true");
+        }
+        self.client.delete(&endpoint, Some(jwt)).await
+    }
+}
+
+/// Children of `parent` (roots for `None`) in list order. Categories whose parent is not
+/// in the list are treated as roots.
+pub fn child_categories(categories: &[AnnotationCategory], parent: Option<Uuid>) -> Vec<&AnnotationCategory> {
+    categories
+        .iter()
+        .filter(|category| {
+            let effective_parent = category
+                .parent_id
+                .filter(|id| *id != category.id && categories.iter().any(|c| c.id == *id));
+            effective_parent == parent
+        })
+        .collect()
+}
+
+/// Depth-first listing of the hierarchy as `(depth, category)` pairs.
+pub fn flatten_category_tree(categories: &[AnnotationCategory]) -> Vec<(usize, &AnnotationCategory)> {
+    fn visit<'a>(
+        categories: &'a [AnnotationCategory],
+        parent: Option<Uuid>,
+        depth: usize,
+        out: &mut Vec<(usize, &'a AnnotationCategory)>,
+    ) {
+        for category in child_categories(categories, parent) {
+            // Guards against cycles in data that bypassed API validation
+            if out.iter().any(|(_, seen)| seen.id == category.id) {
+                continue;
+            }
+            out.push((depth, category));
+            visit(categories, Some(category.id), depth + 1, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    visit(categories, None, 0, &mut out);
+    out
+}
+
+impl Default for CategoriesApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,69 @@
+use super::{ApiClient, ApiResult};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ClassificationLabel {
+    pub category_id: Uuid,
+    #[allow(dead_code)]
+    pub category_name: String,
+    #[allow(dead_code)]
+    pub category_color: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct TaskClassification {
+    pub task_id: Uuid,
+    pub annotation_id: Option<Uuid>,
+    pub annotated_by: Option<Uuid>,
+    pub annotated_at: Option<DateTime<Utc>>,
+    pub labels: Vec<ClassificationLabel>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SetClassificationRequest {
+    pub category_ids: Vec<Uuid>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+pub struct ClassificationsApi {
+    client: ApiClient,
+}
+
+impl ClassificationsApi {
+    pub fn new() -> Self {
+        Self {
+            client: ApiClient::new(),
+        }
+    }
+
+    pub async fn get_classification(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        task_id: Uuid,
+    ) -> ApiResult<TaskClassification> {
+        let endpoint = format!("/projects/{}/tasks/{}/classification", project_id, task_id);
+        self.client.get(&endpoint, Some(jwt)).await
+    }
+
+    pub async fn set_classification(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        task_id: Uuid,
+        category_ids: Vec<Uuid>,
+    ) -> ApiResult<TaskClassification> {
+        let endpoint = format!("/projects/{}/tasks/{}/classification", project_id, task_id);
+        let request = SetClassificationRequest { category_ids, metadata: None };
+        self.client.put(&endpoint, &request, Some(jwt)).await
+    }
+}
+
+impl Default for ClassificationsApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
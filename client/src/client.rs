@@ -0,0 +1,406 @@
+use super::{ApiError, ApiResult, ApiConfig, RetryPolicy, Timeouts, connectivity};
+use reqwest::{Client, RequestBuilder, Response};
+use serde::{de::DeserializeOwned, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Clone)]
+pub struct ApiClient {
+    client: Client,
+    config: ApiConfig,
+    retry: RetryPolicy,
+    timeouts: Timeouts,
+    /// Whether requests tell `connectivity` if the server could be reached, only done for the
+    /// selected server
+    reports_connectivity: bool,
+}
+
+fn build_client(timeouts: &Timeouts) -> Client {
+    let builder = Client::builder();
+    // The browser connects on its own terms, only the whole request can be limited there
+    #[cfg(not(target_arch = "wasm32"))]
+    let builder = builder.connect_timeout(timeouts.connect);
+    #[cfg(target_arch = "wasm32")]
+    let _ = timeouts;
+    builder.build().unwrap_or_default()
+}
+
+impl ApiClient {
+    pub fn new() -> Self {
+        let timeouts = Timeouts::default();
+        Self {
+            client: build_client(&timeouts),
+            config: ApiConfig::default(),
+            retry: RetryPolicy::default(),
+            timeouts,
+            reports_connectivity: true,
+        }
+    }
+
+    /// Client for a server other than the selected one
+    pub fn with_base_url(base_url: &str) -> Self {
+        let timeouts = Timeouts::default();
+        Self {
+            client: build_client(&timeouts),
+            config: ApiConfig { base_url: base_url.trim().trim_end_matches('/').to_string() },
+            retry: RetryPolicy::default(),
+            timeouts,
+            reports_connectivity: false,
+        }
+    }
+
+    /// Replaces how failed idempotent requests are retried
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Replaces how long requests may take
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.client = build_client(&timeouts);
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Sends a request to the server, noting whether it could be reached
+    async fn send(&self, request: RequestBuilder) -> ApiResult<Response> {
+        let result = request.send().await.map_err(ApiError::from);
+        if self.reports_connectivity {
+            connectivity::record(&result);
+        }
+        result
+    }
+
+
+    async fn handle_response<T: DeserializeOwned>(response: Response) -> ApiResult<T> {
+        let status = response.status();
+        
+        if status.is_success() {
+            let body = response.text().await
+                .map_err(|e| ApiError::NetworkError(format!("Failed to read response body: {}", e)))?;
+            
+            serde_json::from_str::<T>(&body).map_err(|e| {
+                ApiError::ParseError(format!("Failed to parse response: {}. Response body: {}", e, body))
+            })
+        } else {
+            let error_text = response.text().await.unwrap_or_default();
+            match status.as_u16() {
+                401 => Err(ApiError::AuthenticationError(error_text)),
+                400 => Err(ApiError::BadRequest(error_text)),
+                404 => Err(ApiError::NotFound(error_text)),
+                409 => Err(ApiError::Conflict(error_text)),
+                500..=599 => Err(ApiError::ServerError(error_text)),
+                _ => Err(ApiError::Unknown(format!("HTTP {}: {}", status, error_text))),
+            }
+        }
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, endpoint: &str, token: Option<&str>) -> ApiResult<T> {
+        let url = &format!("{}{}", self.config.base_url, endpoint);
+        self.retry.run(move || async move {
+            let mut request = self.client.get(url).timeout(self.timeouts.request);
+            
+            if let Some(token) = token {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+
+            let response = self.send(request).await?;
+            Self::handle_response(response).await
+        }).await
+    }
+
+    /// GET with query parameters, encoded by the HTTP client
+    pub async fn get_with_query<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        query: &[(&str, &str)],
+        token: Option<&str>,
+    ) -> ApiResult<T> {
+        let url = &format!("{}{}", self.config.base_url, endpoint);
+        self.retry.run(move || async move {
+            let mut request = self.client.get(url).query(query).timeout(self.timeouts.request);
+
+            if let Some(token) = token {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+
+            let response = self.send(request).await?;
+            Self::handle_response(response).await
+        }).await
+    }
+
+    pub async fn post<T: DeserializeOwned, R: Serialize>(
+        &self,
+        endpoint: &str,
+        body: &R,
+        token: Option<&str>,
+    ) -> ApiResult<T> {
+        let url = format!("{}{}", self.config.base_url, endpoint);
+        let mut request = self.client.post(&url).timeout(self.timeouts.request);
+        
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+
+        let response = self.send(request.json(body)).await?;
+        Self::handle_response(response).await
+    }
+
+    pub async fn post_multipart<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        form: reqwest::multipart::Form,
+        token: Option<&str>,
+    ) -> ApiResult<T> {
+        let url = format!("{}{}", self.config.base_url, endpoint);
+        let mut request = self.client.post(&url);
+        
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+
+        let response = self.send(request.multipart(form)).await?;
+        Self::handle_response(response).await
+    }
+
+    /// Posts raw bytes in chunks, adding the size of every chunk handed to the connection to
+    /// `sent` so callers can show how far the upload got. The browser takes the body whole, so
+    /// there `sent` jumps to the full size once the request is on its way.
+    pub async fn post_bytes_with_progress<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        query: &[(&str, &str)],
+        data: Vec<u8>,
+        sent: Arc<AtomicU64>,
+        token: Option<&str>,
+    ) -> ApiResult<T> {
+        const CHUNK_BYTES: usize = 64 * 1024;
+
+        let url = format!("{}{}", self.config.base_url, endpoint);
+        let mut request = self.client.post(&url)
+            .query(query)
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .header(reqwest::header::CONTENT_LENGTH, data.len());
+
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let body = {
+            let chunks: Vec<Vec<u8>> = data.chunks(CHUNK_BYTES).map(<[u8]>::to_vec).collect();
+            reqwest::Body::wrap_stream(futures_lite::stream::iter(chunks.into_iter().map(move |chunk| {
+                sent.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+                Ok::<_, std::io::Error>(chunk)
+            })))
+        };
+        #[cfg(target_arch = "wasm32")]
+        let body = {
+            let _ = CHUNK_BYTES;
+            sent.fetch_add(data.len() as u64, Ordering::Relaxed);
+            reqwest::Body::from(data)
+        };
+
+        let response = self.send(request.body(body)).await?;
+        Self::handle_response(response).await
+    }
+
+    pub async fn put<T: DeserializeOwned, R: Serialize>(
+        &self,
+        endpoint: &str,
+        body: &R,
+        token: Option<&str>,
+    ) -> ApiResult<T> {
+        let url = &format!("{}{}", self.config.base_url, endpoint);
+        self.retry.run(move || async move {
+            let mut request = self.client.put(url).timeout(self.timeouts.request);
+            
+            if let Some(token) = token {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+
+            let response = self.send(request.json(body)).await?;
+            Self::handle_response(response).await
+        }).await
+    }
+
+    pub async fn delete(&self, endpoint: &str, token: Option<&str>) -> ApiResult<()> {
+        let url = &format!("{}{}", self.config.base_url, endpoint);
+        self.retry.run(move || async move {
+            let mut request = self.client.delete(url).timeout(self.timeouts.request);
+            
+            if let Some(token) = token {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+
+            let response = self.send(request).await?;
+            
+            let status = response.status();
+            if status.is_success() {
+                Ok(())
+            } else {
+                let error_text = response.text().await.unwrap_or_default();
+                match status.as_u16() {
+                    401 => Err(ApiError::AuthenticationError(error_text)),
+                    400 => Err(ApiError::BadRequest(error_text)),
+                    404 => Err(ApiError::NotFound(error_text)),
+                    409 => Err(ApiError::Conflict(error_text)),
+                    500..=599 => Err(ApiError::ServerError(error_text)),
+                    _ => Err(ApiError::Unknown(format!("HTTP {}: {}", status, error_text))),
+                }
+            }
+        }).await
+    }
+
+    /// Raw body of an API endpoint, for binary responses such as image tiles.
+    pub async fn get_endpoint_bytes(&self, endpoint: &str, token: Option<&str>) -> ApiResult<Vec<u8>> {
+        let url = &format!("{}{}", self.config.base_url, endpoint);
+        self.retry.run(move || async move {
+            let mut request = self.client.get(url);
+            
+            if let Some(token) = token {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+
+            let response = self.send(request).await?;
+            
+            let status = response.status();
+            if status.is_success() {
+                response.bytes().await
+                    .map(|bytes| bytes.to_vec())
+                    .map_err(|e| ApiError::NetworkError(format!("Failed to read bytes: {}", e)))
+            } else {
+                let error_text = response.text().await.unwrap_or_default();
+                match status.as_u16() {
+                    401 => Err(ApiError::AuthenticationError(error_text)),
+                    400 => Err(ApiError::BadRequest(error_text)),
+                    404 => Err(ApiError::NotFound(error_text)),
+                    409 => Err(ApiError::Conflict(error_text)),
+                    500..=599 => Err(ApiError::ServerError(error_text)),
+                    _ => Err(ApiError::Unknown(format!("HTTP {}: {}", status, error_text))),
+                }
+            }
+        }).await
+    }
+
+    /// Downloads `url` unless the server still has it under `etag`, in which case `Ok(None)`
+    /// is returned. The new ETag comes with the bytes.
+    pub async fn get_bytes_if_changed(&self, url: &str, etag: Option<&str>) -> ApiResult<Option<(Vec<u8>, Option<String>)>> {
+        let mut request = self.client.get(url);
+        
+        if let Some(etag) = etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+
+        let response = request.send().await?;
+        
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_MODIFIED {
+            Ok(None)
+        } else if status.is_success() {
+            let etag = response.headers()
+                .get(reqwest::header::ETAG)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            let bytes = response.bytes().await
+                .map_err(|e| ApiError::NetworkError(format!("Failed to read bytes: {}", e)))?;
+            Ok(Some((bytes.to_vec(), etag)))
+        } else if status == reqwest::StatusCode::FORBIDDEN {
+            let error_text = response.text().await.unwrap_or_default();
+            Err(ApiError::Forbidden(error_text))
+        } else {
+            let error_text = response.text().await.unwrap_or_default();
+            Err(ApiError::ServerError(format!("HTTP {}: {}", status, error_text)))
+        }
+    }
+
+    /// Target of an endpoint that answers with a redirect, without following it
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn get_redirect_location(&self, endpoint: &str, token: Option<&str>) -> ApiResult<String> {
+        let url = format!("{}{}", self.config.base_url, endpoint);
+        let client = Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .connect_timeout(self.timeouts.connect)
+            .build()?;
+        let mut request = client.get(&url).timeout(self.timeouts.request);
+
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+
+        let response = self.send(request).await?;
+
+        let status = response.status();
+        if status.is_redirection() {
+            response.headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+                .ok_or_else(|| ApiError::ParseError("Redirect without a Location header".to_string()))
+        } else {
+            let error_text = response.text().await.unwrap_or_default();
+            match status.as_u16() {
+                401 => Err(ApiError::AuthenticationError(error_text)),
+                400 => Err(ApiError::BadRequest(error_text)),
+                404 => Err(ApiError::NotFound(error_text)),
+                409 => Err(ApiError::Conflict(error_text)),
+                500..=599 => Err(ApiError::ServerError(error_text)),
+                _ => Err(ApiError::Unknown(format!("HTTP {}: {}", status, error_text))),
+            }
+        }
+    }
+
+    /// Target of an endpoint that answers with a redirect. The browser always follows
+    /// redirects, so a HEAD request is sent and the address it ended up at is the target.
+    #[cfg(target_arch = "wasm32")]
+    pub async fn get_redirect_location(&self, endpoint: &str, token: Option<&str>) -> ApiResult<String> {
+        let url = format!("{}{}", self.config.base_url, endpoint);
+        let mut request = self.client.head(&url).timeout(self.timeouts.request);
+
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+
+        let response = self.send(request).await?;
+        let status = response.status();
+        if status.is_success() && response.url().as_str() != url {
+            Ok(response.url().to_string())
+        } else if status.is_success() {
+            Err(ApiError::ParseError("The endpoint didn't redirect".to_string()))
+        } else {
+            match status.as_u16() {
+                401 => Err(ApiError::AuthenticationError(String::new())),
+                404 => Err(ApiError::NotFound(String::new())),
+                500..=599 => Err(ApiError::ServerError(format!("HTTP {}", status))),
+                _ => Err(ApiError::Unknown(format!("HTTP {}", status))),
+            }
+        }
+    }
+
+    pub async fn get_bytes(&self, url: &str) -> ApiResult<Vec<u8>> {
+        self.retry.run(move || async move {
+            let response = self.client.get(url).send().await?;
+            
+            if response.status().is_success() {
+                response.bytes().await
+                    .map(|bytes| bytes.to_vec())
+                    .map_err(|e| ApiError::NetworkError(format!("Failed to read bytes: {}", e)))
+            } else {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                // Only failures of the server are worth another attempt
+                if status.is_server_error() {
+                    Err(ApiError::ServerError(format!("HTTP {}: {}", status, error_text)))
+                } else {
+                    Err(ApiError::Unknown(format!("HTTP {}: {}", status, error_text)))
+                }
+            }
+        }).await
+    }
+}
+
+impl Default for ApiClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,104 @@
+use super::{ApiClient, ApiResult};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub author_id: Uuid,
+    pub author_name: String,
+    #[allow(dead_code)]
+    pub author_email: String,
+    pub body: String,
+    pub anchor_bbox: Option<Vec<f64>>, // COCO [x, y, width, height]
+    #[allow(dead_code)]
+    pub mentions: Vec<Uuid>,
+    pub resolved: bool,
+    pub created_at: DateTime<Utc>,
+    #[allow(dead_code)]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CreateCommentRequest {
+    pub body: String,
+    pub parent_id: Option<Uuid>,
+    pub anchor_bbox: Option<Vec<f64>>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UpdateCommentRequest {
+    pub body: Option<String>,
+    pub resolved: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommentsListResponse {
+    pub comments: Vec<Comment>,
+}
+
+pub struct CommentsApi {
+    client: ApiClient,
+}
+
+impl CommentsApi {
+    pub fn new() -> Self {
+        Self {
+            client: ApiClient::new(),
+        }
+    }
+
+    pub async fn list_comments(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        task_id: Uuid,
+    ) -> ApiResult<Vec<Comment>> {
+        let endpoint = format!("/projects/{}/tasks/{}/comments", project_id, task_id);
+        let response: CommentsListResponse = self.client.get(&endpoint, Some(jwt)).await?;
+        Ok(response.comments)
+    }
+
+    pub async fn create_comment(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        task_id: Uuid,
+        request: &CreateCommentRequest,
+    ) -> ApiResult<Comment> {
+        let endpoint = format!("/projects/{}/tasks/{}/comments", project_id, task_id);
+        self.client.post(&endpoint, request, Some(jwt)).await
+    }
+
+    pub async fn update_comment(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        task_id: Uuid,
+        comment_id: Uuid,
+        request: &UpdateCommentRequest,
+    ) -> ApiResult<Comment> {
+        let endpoint = format!("/projects/{}/tasks/{}/comments/{}", project_id, task_id, comment_id);
+        self.client.put(&endpoint, request, Some(jwt)).await
+    }
+
+    pub async fn delete_comment(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        task_id: Uuid,
+        comment_id: Uuid,
+    ) -> ApiResult<()> {
+        let endpoint = format!("/projects/{}/tasks/{}/comments/{}", project_id, task_id, comment_id);
+        self.client.delete(&endpoint, Some(jwt)).await
+    }
+}
+
+impl Default for CommentsApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
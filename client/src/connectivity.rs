@@ -0,0 +1,20 @@
+//! Whether the selected server answered the latest request. Every `ApiClient` for it reports
+//! here, so the app can tell the user it is offline whichever request found out.
+
+use super::ApiResult;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::info;
+
+static REACHABLE: AtomicBool = AtomicBool::new(true);
+
+pub fn is_reachable() -> bool {
+    REACHABLE.load(Ordering::Relaxed)
+}
+
+/// Any answer of the server, errors included, means it can be reached
+pub(crate) fn record<T>(result: &ApiResult<T>) {
+    let reachable = !matches!(result, Err(error) if error.is_network_error());
+    if REACHABLE.swap(reachable, Ordering::Relaxed) != reachable {
+        info!("Connection to the server {}", if reachable { "restored" } else { "lost" });
+    }
+}
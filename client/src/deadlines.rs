@@ -0,0 +1,44 @@
+use super::{ApiClient, ApiResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// When the tasks of a project are due, for tasks without a due date of their own
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeadlineSettings {
+    /// End of the labeling campaign, every task is due then at the latest
+    #[serde(default)]
+    pub deadline: Option<DateTime<Utc>>,
+    /// Days a task has to be done in, counted from its creation
+    #[serde(default)]
+    pub default_due_days: Option<u32>,
+}
+
+pub struct DeadlinesApi {
+    client: ApiClient,
+}
+
+impl DeadlinesApi {
+    pub fn new() -> Self {
+        Self {
+            client: ApiClient::new(),
+        }
+    }
+
+    pub async fn get_settings(&self, jwt: &str, project_id: Uuid) -> ApiResult<DeadlineSettings> {
+        let endpoint = format!("/projects/{}/deadlines", project_id);
+        self.client.get(&endpoint, Some(jwt)).await
+    }
+
+    /// Replaces the settings, for owners and admins of the project
+    pub async fn update_settings(&self, jwt: &str, project_id: Uuid, settings: &DeadlineSettings) -> ApiResult<DeadlineSettings> {
+        let endpoint = format!("/projects/{}/deadlines", project_id);
+        self.client.put(&endpoint, settings, Some(jwt)).await
+    }
+}
+
+impl Default for DeadlinesApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
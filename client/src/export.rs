@@ -0,0 +1,286 @@
+use crate::{ApiClient, ApiError, ApiResult, ApiConfig};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use tracing::{info, warn, error};
+
+/// Settings of an export, sent as its query parameters or in the delivery request, and kept in
+/// presets
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExportOptions {
+    /// Package the images with the annotations as a ZIP
+    #[serde(default)]
+    pub include_images: bool,
+    /// Add the `x-fasttag` fields with the annotator, timestamps and review state of each
+    /// annotation
+    #[serde(default)]
+    pub include_metadata: bool,
+    /// Only export the tasks of these splits, every task when empty
+    #[serde(default)]
+    pub splits: Vec<String>,
+}
+
+impl ExportOptions {
+    /// Query parameters of the export endpoint
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if self.include_images {
+            params.push(("include_images", "true".to_string()));
+        }
+        if self.include_metadata {
+            params.push(("include_metadata", "true".to_string()));
+        }
+        if !self.splits.is_empty() {
+            params.push(("splits", self.splits.join(",")));
+        }
+        params
+    }
+}
+
+/// Body of a COCO export the server writes to storage
+#[derive(Debug, Serialize)]
+struct DeliveryRequest<'a> {
+    #[serde(flatten)]
+    options: &'a ExportOptions,
+    destination: &'a str,
+    destination_bucket: Option<&'a str>,
+    background: bool,
+}
+
+/// Export the server wrote to storage
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportDelivery {
+    /// Object key in the bucket
+    pub key: String,
+    /// Location reported by the storage provider, e.g. `s3://bucket/key`
+    pub location: String,
+    /// The other bucket written to, `None` for the project storage
+    #[allow(dead_code)]
+    pub bucket: Option<String>,
+    #[allow(dead_code)]
+    pub content_type: String,
+    pub size_bytes: u64,
+}
+
+/// An export the server writes to storage, followed while it runs in the background
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportJob {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub format: String,
+    /// `running`, `completed` or `failed`
+    pub status: String,
+    pub storage_key: Option<String>,
+    pub location: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Presigned link to the file once completed, valid for an hour
+    pub download_url: Option<String>,
+}
+
+impl ExportJob {
+    pub fn is_running(&self) -> bool {
+        self.status == "running"
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportJobsListResponse {
+    pub exports: Vec<ExportJob>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportPreset {
+    pub id: Uuid,
+    #[allow(dead_code)]
+    pub project_id: Uuid,
+    pub name: String,
+    pub format: String,
+    pub options: ExportOptions,
+    pub is_default: bool,
+    #[allow(dead_code)]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ExportPresetRequest {
+    pub name: String,
+    pub format: String,
+    pub options: ExportOptions,
+    pub is_default: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportPresetsListResponse {
+    pub presets: Vec<ExportPreset>,
+}
+
+pub struct ExportApi {
+    client: reqwest::Client,
+    api_client: ApiClient,
+    config: ApiConfig,
+}
+
+impl ExportApi {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_client: ApiClient::new(),
+            config: ApiConfig::default(),
+        }
+    }
+
+    /// COCO file of the project, or a ZIP with its images when `options.include_images` is set
+    pub async fn download_coco_export(&self, token: &str, project_id: Uuid, options: &ExportOptions) -> ApiResult<Vec<u8>> {
+        let url = format!("{}/projects/{}/export/coco", self.config.base_url, project_id);
+        info!("Starting COCO export download for project {}", project_id);
+        info!("Making request to URL: {}", url);
+        
+        let response = self.client
+            .get(&url)
+            .query(&options.query())
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await?;
+        
+        info!("Received response with status: {}", response.status());
+
+        match response.status() {
+            reqwest::StatusCode::OK => {
+                let bytes = response.bytes().await?;
+                info!("Successfully downloaded COCO export data: {} bytes", bytes.len());
+                Ok(bytes.to_vec())
+            }
+            reqwest::StatusCode::UNAUTHORIZED => {
+                warn!("COCO export failed: Unauthorized access for project {}", project_id);
+                Err(ApiError::AuthenticationError("Unauthorized".to_string()))
+            }
+            reqwest::StatusCode::NOT_FOUND => {
+                warn!("COCO export failed: Project {} not found or access denied", project_id);
+                Err(ApiError::NotFound("Project not found or access denied".to_string()))
+            }
+            status => {
+                let error_text = response.text().await.unwrap_or_else(|_| status.to_string());
+                error!("COCO export failed with status {}: {}", status, error_text);
+                Err(ApiError::ServerError(format!("Server error: {}", error_text)))
+            }
+        }
+    }
+
+    /// ZIP with the tracks of the project as MOTChallenge sequences, one per video task and one
+    /// for the image tasks in name order
+    pub async fn download_mot_export(&self, token: &str, project_id: Uuid) -> ApiResult<Vec<u8>> {
+        let endpoint = format!("/projects/{}/export/mot", project_id);
+        self.api_client.get_endpoint_bytes(&endpoint, Some(token)).await
+    }
+
+    /// ZIP with the boxes of the project as KITTI labels, one file per image task and one
+    /// tracking file per video task
+    pub async fn download_kitti_export(&self, token: &str, project_id: Uuid) -> ApiResult<Vec<u8>> {
+        let endpoint = format!("/projects/{}/export/kitti", project_id);
+        self.api_client.get_endpoint_bytes(&endpoint, Some(token)).await
+    }
+
+    /// Has the server write the COCO export into `destination`, a folder of the project storage
+    /// or of `bucket`, so large exports don't go through the client
+    pub async fn deliver_coco_export(
+        &self,
+        token: &str,
+        project_id: Uuid,
+        options: &ExportOptions,
+        destination: &str,
+        bucket: Option<&str>,
+    ) -> ApiResult<ExportDelivery> {
+        let url = format!("{}/projects/{}/export/coco", self.config.base_url, project_id);
+        let request = DeliveryRequest { options, destination, destination_bucket: bucket, background: false };
+        info!("Delivering COCO export of project {} to '{}'", project_id, destination);
+
+        // No request timeout, building a large export takes a while
+        let response = self.client
+            .post(&url)
+            .json(&request)
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await?;
+
+        match response.status() {
+            reqwest::StatusCode::CREATED => {
+                let delivery: ExportDelivery = response.json().await?;
+                info!("COCO export written to {}", delivery.location);
+                Ok(delivery)
+            }
+            reqwest::StatusCode::BAD_REQUEST => {
+                let error_text = response.text().await.unwrap_or_default();
+                Err(ApiError::BadRequest(error_text))
+            }
+            reqwest::StatusCode::UNAUTHORIZED => Err(ApiError::AuthenticationError("Unauthorized".to_string())),
+            reqwest::StatusCode::NOT_FOUND => Err(ApiError::NotFound("Project not found or access denied".to_string())),
+            status => {
+                let error_text = response.text().await.unwrap_or_else(|_| status.to_string());
+                error!("COCO export delivery failed with status {}: {}", status, error_text);
+                Err(ApiError::ServerError(format!("Server error: {}", error_text)))
+            }
+        }
+    }
+
+    /// Has the server write the COCO export to `destination`, a folder of the project storage,
+    /// in the background. The job is followed with `list_exports`.
+    pub async fn start_coco_export_job(
+        &self,
+        token: &str,
+        project_id: Uuid,
+        options: &ExportOptions,
+        destination: &str,
+    ) -> ApiResult<ExportJob> {
+        let endpoint = format!("/projects/{}/export/coco", project_id);
+        let request = DeliveryRequest { options, destination, destination_bucket: None, background: true };
+        self.api_client.post(&endpoint, &request, Some(token)).await
+    }
+
+    /// Exports of the project written to storage, newest first
+    pub async fn list_exports(&self, token: &str, project_id: Uuid) -> ApiResult<Vec<ExportJob>> {
+        let endpoint = format!("/projects/{}/exports", project_id);
+        let response: ExportJobsListResponse = self.api_client.get(&endpoint, Some(token)).await?;
+        Ok(response.exports)
+    }
+
+    /// Presets of the project by name
+    pub async fn list_export_presets(&self, token: &str, project_id: Uuid) -> ApiResult<Vec<ExportPreset>> {
+        let endpoint = format!("/projects/{}/export-presets", project_id);
+        let response: ExportPresetsListResponse = self.api_client.get(&endpoint, Some(token)).await?;
+        Ok(response.presets)
+    }
+
+    pub async fn create_export_preset(
+        &self,
+        token: &str,
+        project_id: Uuid,
+        request: &ExportPresetRequest,
+    ) -> ApiResult<ExportPreset> {
+        let endpoint = format!("/projects/{}/export-presets", project_id);
+        self.api_client.post(&endpoint, request, Some(token)).await
+    }
+
+    pub async fn update_export_preset(
+        &self,
+        token: &str,
+        project_id: Uuid,
+        preset_id: Uuid,
+        request: &ExportPresetRequest,
+    ) -> ApiResult<ExportPreset> {
+        let endpoint = format!("/projects/{}/export-presets/{}", project_id, preset_id);
+        self.api_client.put(&endpoint, request, Some(token)).await
+    }
+
+    pub async fn delete_export_preset(&self, token: &str, project_id: Uuid, preset_id: Uuid) -> ApiResult<()> {
+        let endpoint = format!("/projects/{}/export-presets/{}", project_id, preset_id);
+        self.api_client.delete(&endpoint, Some(token)).await
+    }
+}
+
+impl Default for ExportApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,39 @@
+use super::{ApiClient, ApiResult};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct HealthResponse {
+    pub status: String,
+    pub service: String,
+    pub database: String,
+}
+
+pub struct HealthApi {
+    client: ApiClient,
+}
+
+impl HealthApi {
+    pub fn new() -> Self {
+        Self {
+            client: ApiClient::new(),
+        }
+    }
+
+    /// Checks `base_url` rather than the selected server, so a server can be tried before
+    /// switching to it
+    pub fn for_server(base_url: &str) -> Self {
+        Self {
+            client: ApiClient::with_base_url(base_url),
+        }
+    }
+
+    pub async fn check(&self) -> ApiResult<HealthResponse> {
+        self.client.get("/health", None).await
+    }
+}
+
+impl Default for HealthApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,7 +1,9 @@
-use crate::api::{ApiError, ApiResult, ApiConfig};
+use crate::{ApiError, ApiResult, ApiConfig};
 use uuid::Uuid;
-use bevy::log::{info, warn, error};
+use tracing::{info, warn, error};
 use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ImportResult {
@@ -16,9 +18,43 @@ pub struct ImportStats {
     pub categories_updated: usize,
     pub tasks_created: usize,
     pub annotations_created: usize,
+    #[serde(default)]
+    pub images_fetched: usize,
     pub errors: Vec<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    /// `missing_image`, `unknown_category`, `malformed_bbox`, `duplicate_annotation_id`, ...
+    pub kind: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub categories: usize,
+    pub images: usize,
+    pub annotations: usize,
+    /// At most 100 of each kind
+    pub issues: Vec<ValidationIssue>,
+    pub issue_counts: BTreeMap<String, usize>,
+}
+
+/// What importing a COCO file would do, from a dry run that writes nothing
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportDryRun {
+    pub valid: bool,
+    pub validation: ValidationReport,
+    pub categories_to_create: usize,
+    pub categories_to_update: usize,
+    pub tasks_to_create: usize,
+    pub tasks_to_update: usize,
+    pub tasks_to_annotate: usize,
+    #[serde(default)]
+    pub images_to_fetch: usize,
+    pub coco_id_conflicts: Vec<String>,
+}
+
 pub struct ImportApi {
     client: reqwest::Client,
     config: ApiConfig,
@@ -32,10 +68,10 @@ impl ImportApi {
         }
     }
 
-    pub async fn import_coco_file(&self, token: &str, project_id: Uuid, file_path: &str) -> ApiResult<ImportResult> {
-        let url = format!("{}/projects/{}/import/coco", self.config.base_url, project_id);
+    /// `fetch_images` has the server download the images from the URLs in the file into the
+    /// project storage, so the tasks don't depend on where the dataset was hosted
+    pub async fn import_coco_file(&self, token: &str, project_id: Uuid, file_path: &str, fetch_images: bool) -> ApiResult<ImportResult> {
         info!("Starting COCO import for project {} from file: {}", project_id, file_path);
-        info!("Making request to URL: {}", url);
 
         // Read file content
         let file_content = match std::fs::read(file_path) {
@@ -46,6 +82,33 @@ impl ImportApi {
             }
         };
 
+        self.import_coco(token, project_id, file_content, fetch_images).await
+    }
+
+    /// Imports a COCO annotation file that is already in memory, e.g. one converted from another format
+    pub async fn import_coco(&self, token: &str, project_id: Uuid, file_content: Vec<u8>, fetch_images: bool) -> ApiResult<ImportResult> {
+        let query = if fetch_images { "?fetch_images=true" } else { "" };
+        let result: ImportResult = self.post_coco(token, project_id, file_content, query).await?;
+        info!("Successfully imported COCO data: {}", result.message);
+        Ok(result)
+    }
+
+    /// Validates a COCO annotation file on the server and reports what importing it would do,
+    /// without importing anything
+    pub async fn check_coco_import(&self, token: &str, project_id: Uuid, file_content: Vec<u8>) -> ApiResult<ImportDryRun> {
+        self.post_coco(token, project_id, file_content, "?dry_run=true").await
+    }
+
+    async fn post_coco<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        project_id: Uuid,
+        file_content: Vec<u8>,
+        query: &str,
+    ) -> ApiResult<T> {
+        let url = format!("{}/projects/{}/import/coco{}", self.config.base_url, project_id, query);
+        info!("Making request to URL: {}", url);
+
         // Create multipart form
         let form = reqwest::multipart::Form::new()
             .part("file", 
@@ -65,11 +128,7 @@ impl ImportApi {
         info!("Received response with status: {}", response.status());
 
         match response.status() {
-            reqwest::StatusCode::OK => {
-                let result: ImportResult = response.json().await?;
-                info!("Successfully imported COCO data: {}", result.message);
-                Ok(result)
-            }
+            reqwest::StatusCode::OK => Ok(response.json().await?),
             reqwest::StatusCode::UNAUTHORIZED => {
                 warn!("COCO import failed: Unauthorized access for project {}", project_id);
                 Err(ApiError::AuthenticationError("Unauthorized".to_string()))
@@ -90,4 +149,10 @@ impl ImportApi {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+impl Default for ImportApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,98 @@
+use super::{ApiClient, ApiResult};
+use crate::annotations::BoundingBox;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Rules every save of a task's boxes has to follow, the server checks them too
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LabelingRules {
+    /// Narrowest box in pixels, measured along the box before rotation
+    #[serde(default)]
+    pub min_box_width: Option<f64>,
+    /// Lowest box in pixels, measured along the box before rotation
+    #[serde(default)]
+    pub min_box_height: Option<f64>,
+    /// Most boxes on one image, or on one frame of a video
+    #[serde(default)]
+    pub max_boxes_per_image: Option<u32>,
+    /// Categories each task needs at least one box of
+    #[serde(default)]
+    pub required_category_ids: Vec<Uuid>,
+}
+
+/// A rule the boxes of a save break
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum RuleViolation {
+    /// The box at `index` of the save is below the minimum size
+    BoxTooSmall { index: usize, width: f64, height: f64 },
+    /// More boxes than allowed, on the frame for videos
+    TooManyBoxes { frame_index: Option<i32>, count: usize },
+    /// No box has this required category
+    MissingCategory { category_id: Uuid },
+}
+
+/// Checks boxes before they are saved, the same way the server does. Empty when the boxes
+/// follow every rule.
+pub fn check(rules: &LabelingRules, bboxes: &[BoundingBox]) -> Vec<RuleViolation> {
+    let mut violations = Vec::new();
+
+    for (index, bbox) in bboxes.iter().enumerate() {
+        let [_, _, width, height] = bbox.bbox[..] else {
+            continue;
+        };
+        let too_narrow = rules.min_box_width.is_some_and(|min| width < min);
+        let too_low = rules.min_box_height.is_some_and(|min| height < min);
+        if too_narrow || too_low {
+            violations.push(RuleViolation::BoxTooSmall { index, width, height });
+        }
+    }
+
+    if let Some(max) = rules.max_boxes_per_image {
+        let mut counts: HashMap<Option<i32>, usize> = HashMap::new();
+        for bbox in bboxes {
+            *counts.entry(bbox.frame_index).or_default() += 1;
+        }
+        let mut crowded: Vec<_> = counts.into_iter().filter(|(_, count)| *count > max as usize).collect();
+        crowded.sort();
+        violations.extend(crowded.into_iter().map(|(frame_index, count)| RuleViolation::TooManyBoxes { frame_index, count }));
+    }
+
+    for category_id in &rules.required_category_ids {
+        if !bboxes.iter().any(|bbox| bbox.category_id == *category_id) {
+            violations.push(RuleViolation::MissingCategory { category_id: *category_id });
+        }
+    }
+
+    violations
+}
+
+pub struct LabelingRulesApi {
+    client: ApiClient,
+}
+
+impl LabelingRulesApi {
+    pub fn new() -> Self {
+        Self {
+            client: ApiClient::new(),
+        }
+    }
+
+    pub async fn get_rules(&self, jwt: &str, project_id: Uuid) -> ApiResult<LabelingRules> {
+        let endpoint = format!("/projects/{}/labeling-rules", project_id);
+        self.client.get(&endpoint, Some(jwt)).await
+    }
+
+    /// Replaces the rules, for owners and admins of the project
+    pub async fn update_rules(&self, jwt: &str, project_id: Uuid, rules: &LabelingRules) -> ApiResult<LabelingRules> {
+        let endpoint = format!("/projects/{}/labeling-rules", project_id);
+        self.client.put(&endpoint, rules, Some(jwt)).await
+    }
+}
+
+impl Default for LabelingRulesApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,122 @@
+//! Client for the fast-tag API: typed models and one `XApi` struct per area of the API, all
+//! going through [`ApiClient`]. Requests go to `API_BASE_URL` (or the URL passed to
+//! [`ApiConfig::set_base_url`]) and take the JWT from the login flow in [`auth::AuthApi`].
+//!
+//! ```no_run
+//! # async fn run(token: &str) -> fast_tag_client::ApiResult<()> {
+//! let projects = fast_tag_client::projects::ProjectsApi::new().list_projects(token).await?;
+//! println!("{} projects", projects.len());
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod client;
+pub mod auth;
+pub mod account;
+pub mod projects;
+pub mod tasks;
+pub mod annotations;
+pub mod labeling_rules;
+pub mod deadlines;
+pub mod merge;
+pub mod classifications;
+pub mod categories;
+pub mod sync;
+pub mod resources;
+pub mod export;
+pub mod import;
+pub mod segmentation;
+pub mod comments;
+pub mod templates;
+pub mod health;
+pub mod stats;
+pub mod reports;
+pub mod storage;
+pub mod updates;
+pub mod retry;
+pub mod connectivity;
+
+use std::fmt;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone)]
+pub enum ApiError {
+    NetworkError(String),
+    ParseError(String),
+    AuthenticationError(String),
+    BadRequest(String),
+    ServerError(String),
+    NotFound(String),
+    /// 403, which storage services also answer expired presigned URLs with
+    Forbidden(String),
+    /// 409, the resource changed or is busy, with the server's body
+    Conflict(String),
+    Unknown(String),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::NetworkError(msg) => write!(f, "Network error: {}", msg),
+            ApiError::ParseError(msg) => write!(f, "Parse error: {}", msg),
+            ApiError::AuthenticationError(msg) => write!(f, "Authentication error: {}", msg),
+            ApiError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
+            ApiError::ServerError(msg) => write!(f, "Server error: {}", msg),
+            ApiError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            ApiError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
+            ApiError::Conflict(msg) => write!(f, "Conflict: {}", msg),
+            ApiError::Unknown(msg) => write!(f, "Unknown error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl ApiError {
+    /// The server couldn't be reached, as opposed to it answering with an error
+    pub fn is_network_error(&self) -> bool {
+        matches!(self, ApiError::NetworkError(_))
+    }
+}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(error: reqwest::Error) -> Self {
+        ApiError::NetworkError(error.to_string())
+    }
+}
+
+pub type ApiResult<T> = Result<T, ApiError>;
+
+/// Server picked on the login page, which takes precedence over `API_BASE_URL`
+static SERVER_URL: RwLock<Option<String>> = RwLock::new(None);
+
+#[derive(Debug, Clone)]
+pub struct ApiConfig {
+    pub base_url: String,
+}
+
+impl ApiConfig {
+    /// Points every request made from now on at another server
+    pub fn set_base_url(url: &str) {
+        if let Ok(mut server_url) = SERVER_URL.write() {
+            *server_url = Some(url.trim().trim_end_matches('/').to_string());
+        }
+    }
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        let selected = SERVER_URL.read().ok().and_then(|server_url| server_url.clone());
+        Self {
+            base_url: selected.unwrap_or_else(|| {
+                std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string())
+            }),
+        }
+    }
+}
+
+
+// Re-export common types and functions
+pub use client::ApiClient;
+pub use retry::{RetryPolicy, Timeouts};
+// Note: Individual API modules are re-exported as needed
\ No newline at end of file
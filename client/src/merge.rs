@@ -0,0 +1,179 @@
+//! Three-way merge of the boxes of a task, for saves that find someone else saved the task
+//! since the boxes were loaded. Boxes carry no stable id across saves, so a box of one version
+//! is the same object as a box of another when they sit on the same frame and overlap enough.
+
+use crate::annotations::BoundingBox;
+
+/// Overlap (IoU) from which two boxes on the same frame are taken for the same object
+const SAME_OBJECT_IOU: f64 = 0.5;
+
+/// Coordinates and rotations closer than this are equal, boxes go through the editor in `f32`
+const TOLERANCE: f64 = 0.01;
+
+/// A box both sides changed in different ways. The side that deleted it has `None`.
+#[derive(Debug, Clone)]
+pub struct BoxConflict {
+    pub mine: Option<BoundingBox>,
+    pub theirs: Option<BoundingBox>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BoxMerge {
+    /// Boxes only one side changed, both changed the same way, or nobody touched
+    pub merged: Vec<BoundingBox>,
+    /// Boxes only the user can decide about
+    pub conflicts: Vec<BoxConflict>,
+}
+
+impl BoxMerge {
+    /// The merged boxes plus one side of every conflict, mine where `keep_mine` is true
+    pub fn resolve(&self, keep_mine: &[bool]) -> Vec<BoundingBox> {
+        let mut boxes = self.merged.clone();
+        for (conflict, mine) in self.conflicts.iter().zip(keep_mine) {
+            let kept = if *mine { &conflict.mine } else { &conflict.theirs };
+            boxes.extend(kept.clone());
+        }
+        boxes
+    }
+}
+
+/// What one side did with a box of the base
+enum Change {
+    Kept,
+    /// Moved, resized or relabeled into the box at this index
+    Edited(usize),
+    Deleted,
+}
+
+/// Merges my boxes and theirs, both edited from `base`. Changes to different boxes are
+/// combined, boxes added on either side are kept, and a box both sides changed differently
+/// (or one changed and the other deleted) becomes a conflict.
+pub fn merge(base: &[BoundingBox], mine: &[BoundingBox], theirs: &[BoundingBox]) -> BoxMerge {
+    let (my_changes, my_additions) = changes(base, mine);
+    let (their_changes, their_additions) = changes(base, theirs);
+    let mut result = BoxMerge::default();
+
+    for (index, base_box) in base.iter().enumerate() {
+        match (&my_changes[index], &their_changes[index]) {
+            (Change::Kept, Change::Kept) => result.merged.push(base_box.clone()),
+            (Change::Kept, Change::Edited(t)) => result.merged.push(theirs[*t].clone()),
+            (Change::Edited(m), Change::Kept) => result.merged.push(mine[*m].clone()),
+            (Change::Edited(m), Change::Edited(t)) if same_box(&mine[*m], &theirs[*t]) => {
+                result.merged.push(mine[*m].clone())
+            }
+            (Change::Edited(m), Change::Edited(t)) => result.conflicts.push(BoxConflict {
+                mine: Some(mine[*m].clone()),
+                theirs: Some(theirs[*t].clone()),
+            }),
+            (Change::Edited(m), Change::Deleted) => result.conflicts.push(BoxConflict {
+                mine: Some(mine[*m].clone()),
+                theirs: None,
+            }),
+            (Change::Deleted, Change::Edited(t)) => result.conflicts.push(BoxConflict {
+                mine: None,
+                theirs: Some(theirs[*t].clone()),
+            }),
+            (Change::Deleted, _) | (_, Change::Deleted) => {}
+        }
+    }
+
+    // Both sides may have drawn the same new object
+    let mut their_additions: Vec<Option<usize>> = their_additions.into_iter().map(Some).collect();
+    for m in my_additions {
+        let twin = their_additions
+            .iter_mut()
+            .filter_map(|addition| {
+                let overlap = same_object(&mine[m], &theirs[(*addition)?])?;
+                Some((overlap, addition))
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .and_then(|(_, addition)| addition.take());
+        match twin {
+            Some(t) if !same_box(&mine[m], &theirs[t]) => result.conflicts.push(BoxConflict {
+                mine: Some(mine[m].clone()),
+                theirs: Some(theirs[t].clone()),
+            }),
+            _ => result.merged.push(mine[m].clone()),
+        }
+    }
+    result.merged.extend(their_additions.into_iter().flatten().map(|t| theirs[t].clone()));
+
+    result
+}
+
+/// What `edited` did with each box of `base`, and the indexes of the boxes it added
+fn changes(base: &[BoundingBox], edited: &[BoundingBox]) -> (Vec<Change>, Vec<usize>) {
+    let mut matched = vec![false; edited.len()];
+    let mut changes: Vec<Option<Change>> = base.iter().map(|_| None).collect();
+
+    // Untouched boxes first, so an edited box isn't paired with a neighbour's twin
+    for (index, base_box) in base.iter().enumerate() {
+        if let Some(e) = (0..edited.len()).find(|&e| !matched[e] && same_box(base_box, &edited[e])) {
+            matched[e] = true;
+            changes[index] = Some(Change::Kept);
+        }
+    }
+
+    for (index, base_box) in base.iter().enumerate() {
+        if changes[index].is_some() {
+            continue;
+        }
+        let closest = (0..edited.len())
+            .filter(|&e| !matched[e])
+            .filter_map(|e| Some((e, same_object(base_box, &edited[e])?)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        changes[index] = Some(match closest {
+            Some((e, _)) => {
+                matched[e] = true;
+                Change::Edited(e)
+            }
+            None => Change::Deleted,
+        });
+    }
+
+    let additions = (0..edited.len()).filter(|&e| !matched[e]).collect();
+    (changes.into_iter().map(|change| change.unwrap_or(Change::Deleted)).collect(), additions)
+}
+
+/// How much two boxes overlap when they are taken for the same object
+fn same_object(a: &BoundingBox, b: &BoundingBox) -> Option<f64> {
+    if a.frame_index != b.frame_index {
+        return None;
+    }
+    let overlap = iou(&a.bbox, &b.bbox);
+    (overlap >= SAME_OBJECT_IOU).then_some(overlap)
+}
+
+/// Whether two boxes are the same in everything an annotator edits
+fn same_box(a: &BoundingBox, b: &BoundingBox) -> bool {
+    let close = |x: f64, y: f64| (x - y).abs() <= TOLERANCE;
+    let attributes = |bbox: &BoundingBox| match &bbox.attributes {
+        Some(serde_json::Value::Object(values)) => values.clone(),
+        _ => serde_json::Map::new(),
+    };
+
+    a.category_id == b.category_id
+        && a.frame_index == b.frame_index
+        && a.bbox.len() == b.bbox.len()
+        && a.bbox.iter().zip(&b.bbox).all(|(x, y)| close(*x, *y))
+        && close(a.rotation.unwrap_or(0.0), b.rotation.unwrap_or(0.0))
+        && a.iscrowd.unwrap_or(false) == b.iscrowd.unwrap_or(false)
+        && a.is_prediction.unwrap_or(false) == b.is_prediction.unwrap_or(false)
+        && attributes(a) == attributes(b)
+}
+
+/// Intersection over union of two `[x, y, width, height]` boxes, rotation left aside
+fn iou(a: &[f64], b: &[f64]) -> f64 {
+    let ([ax, ay, aw, ah], [bx, by, bw, bh]) = (a, b) else {
+        return 0.0;
+    };
+    let width = ((ax + aw).min(bx + bw) - ax.max(*bx)).max(0.0);
+    let height = ((ay + ah).min(by + bh) - ay.max(*by)).max(0.0);
+    let intersection = width * height;
+    let union = aw * ah + bw * bh - intersection;
+    if union <= 0.0 {
+        0.0
+    } else {
+        intersection / union
+    }
+}
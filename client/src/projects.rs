@@ -0,0 +1,255 @@
+use super::{ApiClient, ApiResult};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Project task type whose tasks get whole-image labels instead of boxes
+pub const TASK_TYPE_CLASSIFICATION: &str = "classification";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[allow(dead_code)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub owner_id: String,
+    pub storage_config: Option<serde_json::Value>,
+    /// `detection` (bounding boxes) or `classification` (whole-image labels)
+    #[serde(default)]
+    pub task_type: String,
+    /// Boxes leaving the image are rejected instead of clipped to it
+    #[serde(default)]
+    pub strict_bounds: bool,
+    /// When the project was archived, archived projects are read-only
+    #[serde(default)]
+    pub archived_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Project {
+    pub fn is_classification(&self) -> bool {
+        self.task_type == TASK_TYPE_CLASSIFICATION
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+}
+
+/// Member of a project, who tasks can be assigned to
+#[derive(Debug, Deserialize, Clone)]
+#[allow(dead_code)]
+pub struct ProjectMember {
+    pub user_id: String,
+    pub name: String,
+    pub email: String,
+    pub role: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectMembersResponse {
+    pub members: Vec<ProjectMember>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectsListResponse {
+    pub projects: Vec<Project>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateProjectRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub template_id: Option<String>,
+    pub task_type: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpdateProjectRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpdateStorageConfigRequest {
+    pub storage_config: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpdateStrictBoundsRequest {
+    pub strict_bounds: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct CloneProjectRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub include_tasks: Option<bool>,
+    pub include_annotations: Option<bool>,
+    pub include_storage_config: Option<bool>,
+    pub include_credentials: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CloneProjectResponse {
+    pub project: Project,
+    pub categories_copied: u64,
+    pub tasks_copied: u64,
+    pub annotations_copied: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectResponse {
+    pub project: Project,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct CleanupRequest {
+    /// Only report what would be deleted, the server's default
+    pub dry_run: Option<bool>,
+    /// Also look for files in the project storage that no task uses
+    pub include_storage: Option<bool>,
+    /// Only look at the storage files under this prefix
+    pub prefix: Option<String>,
+}
+
+/// What a cleanup found, and deleted unless it was a dry run
+#[derive(Debug, Deserialize, Clone)]
+pub struct CleanupReport {
+    pub dry_run: bool,
+    /// Annotations left without boxes or labels
+    pub empty_annotations: Vec<Uuid>,
+    /// Boxes whose category was deleted
+    pub uncategorized_boxes: Vec<Uuid>,
+    /// Storage keys of media no task uses, with the frames and tiles written for them
+    pub unused_objects: Vec<String>,
+    /// Storage files that could not be deleted
+    pub errors: Vec<String>,
+}
+
+pub struct ProjectsApi {
+    client: ApiClient,
+}
+
+impl ProjectsApi {
+    pub fn new() -> Self {
+        Self {
+            client: ApiClient::new(),
+        }
+    }
+
+    pub async fn list_projects(&self, jwt: &str) -> ApiResult<Vec<Project>> {
+        let response: ProjectsListResponse = self.client.get("/projects", Some(jwt)).await?;
+        Ok(response.projects)
+    }
+
+    /// Projects of the user including the archived ones, which `list_projects` leaves out
+    pub async fn list_projects_with_archived(&self, jwt: &str) -> ApiResult<Vec<Project>> {
+        let response: ProjectsListResponse = self
+            .client
+            .get_with_query("/projects", &[("include_archived", "true")], Some(jwt))
+            .await?;
+        Ok(response.projects)
+    }
+
+    pub async fn create_project(
+        &self,
+        jwt: &str,
+        name: &str,
+        description: Option<&str>,
+        template_id: Option<&str>,
+        task_type: Option<&str>,
+    ) -> ApiResult<Project> {
+        let request = CreateProjectRequest {
+            name: name.to_string(),
+            description: description.map(|s| s.to_string()),
+            template_id: template_id.map(|s| s.to_string()),
+            task_type: task_type.map(|s| s.to_string()),
+        };
+        let response: ProjectResponse = self.client.post("/projects", &request, Some(jwt)).await?;
+        Ok(response.project)
+    }
+
+    pub async fn update_project(
+        &self,
+        jwt: &str,
+        project_id: &str,
+        name: &str,
+        description: Option<&str>,
+    ) -> ApiResult<Project> {
+        let request = UpdateProjectRequest {
+            name: name.to_string(),
+            description: description.map(|s| s.to_string()),
+        };
+        let endpoint = format!("/projects/{}", project_id);
+        let response: ProjectResponse = self.client.put(&endpoint, &request, Some(jwt)).await?;
+        Ok(response.project)
+    }
+
+    pub async fn delete_project(&self, jwt: &str, project_id: &str) -> ApiResult<()> {
+        let endpoint = format!("/projects/{}", project_id);
+        self.client.delete(&endpoint, Some(jwt)).await
+    }
+
+    pub async fn list_members(&self, jwt: &str, project_id: &str) -> ApiResult<Vec<ProjectMember>> {
+        let endpoint = format!("/projects/{}/members", project_id);
+        let response: ProjectMembersResponse = self.client.get(&endpoint, Some(jwt)).await?;
+        Ok(response.members)
+    }
+
+    pub async fn clone_project(
+        &self,
+        jwt: &str,
+        project_id: &str,
+        request: &CloneProjectRequest,
+    ) -> ApiResult<CloneProjectResponse> {
+        let endpoint = format!("/projects/{}/clone", project_id);
+        self.client.post(&endpoint, request, Some(jwt)).await
+    }
+
+    pub async fn update_storage_config(
+        &self,
+        jwt: &str,
+        project_id: &str,
+        storage_config: serde_json::Value,
+    ) -> ApiResult<Project> {
+        let request = UpdateStorageConfigRequest { storage_config };
+        let endpoint = format!("/projects/{}/storage-config", project_id);
+        let response: ProjectResponse = self.client.put(&endpoint, &request, Some(jwt)).await?;
+        Ok(response.project)
+    }
+
+    /// Looks for annotations and storage files nothing uses anymore, and deletes them unless
+    /// `request.dry_run` is left on. For owners and admins of the project.
+    pub async fn cleanup(&self, jwt: &str, project_id: &str, request: &CleanupRequest) -> ApiResult<CleanupReport> {
+        let endpoint = format!("/projects/{}/cleanup", project_id);
+        self.client.post(&endpoint, request, Some(jwt)).await
+    }
+
+    /// Makes the project read-only, every write to it fails until it is unarchived. For owners
+    /// and admins of the project.
+    pub async fn archive_project(&self, jwt: &str, project_id: &str) -> ApiResult<Project> {
+        let endpoint = format!("/projects/{}/archive", project_id);
+        let response: ProjectResponse = self.client.post(&endpoint, &(), Some(jwt)).await?;
+        Ok(response.project)
+    }
+
+    pub async fn unarchive_project(&self, jwt: &str, project_id: &str) -> ApiResult<()> {
+        let endpoint = format!("/projects/{}/archive", project_id);
+        self.client.delete(&endpoint, Some(jwt)).await
+    }
+
+    /// Whether boxes leaving the image are rejected, or clipped to it
+    pub async fn update_strict_bounds(&self, jwt: &str, project_id: &str, strict_bounds: bool) -> ApiResult<Project> {
+        let request = UpdateStrictBoundsRequest { strict_bounds };
+        let endpoint = format!("/projects/{}/strict-bounds", project_id);
+        let response: ProjectResponse = self.client.put(&endpoint, &request, Some(jwt)).await?;
+        Ok(response.project)
+    }
+}
+
+impl Default for ProjectsApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
\ No newline at end of file
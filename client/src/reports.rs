@@ -0,0 +1,57 @@
+use super::{ApiClient, ApiResult};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct AnnotatorDay {
+    /// UTC day
+    pub date: NaiveDate,
+    pub annotations: i64,
+    pub boxes: i64,
+}
+
+/// Work of one annotator over the days of a report
+#[derive(Debug, Deserialize, Clone)]
+#[allow(dead_code)]
+pub struct AnnotatorReport {
+    pub user_id: String,
+    pub name: String,
+    pub tasks: i64,
+    pub annotations: i64,
+    pub boxes: i64,
+    /// Time between saves per task, breaks left out
+    pub average_seconds_per_task: Option<f64>,
+    pub days: Vec<AnnotatorDay>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct AnnotatorsReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub annotators: Vec<AnnotatorReport>,
+}
+
+pub struct ReportsApi {
+    client: ApiClient,
+}
+
+impl ReportsApi {
+    pub fn new() -> Self {
+        Self {
+            client: ApiClient::new(),
+        }
+    }
+
+    /// Saves, boxes and time per task of every annotator of the project between two days,
+    /// both included. Only leads of the project get one.
+    pub async fn get_annotators_report(&self, jwt: &str, project_id: &str, from: NaiveDate, to: NaiveDate) -> ApiResult<AnnotatorsReport> {
+        let endpoint = format!("/projects/{}/reports/annotators?from={}&to={}", project_id, from, to);
+        self.client.get(&endpoint, Some(jwt)).await
+    }
+}
+
+impl Default for ReportsApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -17,29 +17,21 @@ impl ResourcesApi {
         }
 
         let bytes = self.client.get_bytes(url).await?;
-        
-        // Basic validation of image data
-        if bytes.len() < 16 {
-            return Err(ApiError::ParseError(format!(
-                "Downloaded data is too small ({} bytes) to be a valid image",
-                bytes.len()
-            )));
-        }
+        validate_image(&bytes)?;
+        Ok(bytes)
+    }
 
-        // Check for common image format headers
-        let is_valid_image = bytes.starts_with(b"\xFF\xD8\xFF")         // JPEG
-            || bytes.starts_with(b"\x89PNG\r\n\x1A\n")                 // PNG
-            || bytes.starts_with(b"GIF87a")                             // GIF87a
-            || bytes.starts_with(b"GIF89a")                             // GIF89a
-            || (bytes.starts_with(b"RIFF") && bytes.len() > 11 && &bytes[8..12] == b"WEBP"); // WebP
-
-        if !is_valid_image {
-            return Err(ApiError::ParseError(
-                "Downloaded data does not appear to be a valid image format".to_string()
-            ));
+    /// Like `download_image`, but returns `Ok(None)` when the copy tagged `etag` is still current.
+    pub async fn download_image_if_changed(&self, url: &str, etag: Option<&str>) -> ApiResult<Option<DownloadedImage>> {
+        if url.is_empty() {
+            return Err(ApiError::BadRequest("URL is empty".to_string()));
         }
 
-        Ok(bytes)
+        let Some((bytes, etag)) = self.client.get_bytes_if_changed(url, etag).await? else {
+            return Ok(None);
+        };
+        validate_image(&bytes)?;
+        Ok(Some(DownloadedImage { bytes, etag }))
     }
 
     #[allow(dead_code)]
@@ -79,6 +71,37 @@ impl Default for ResourcesApi {
     }
 }
 
+fn validate_image(bytes: &[u8]) -> ApiResult<()> {
+    // Basic validation of image data
+    if bytes.len() < 16 {
+        return Err(ApiError::ParseError(format!(
+            "Downloaded data is too small ({} bytes) to be a valid image",
+            bytes.len()
+        )));
+    }
+
+    // Check for common image format headers
+    let is_valid_image = bytes.starts_with(b"\xFF\xD8\xFF")         // JPEG
+        || bytes.starts_with(b"\x89PNG\r\n\x1A\n")                 // PNG
+        || bytes.starts_with(b"GIF87a")                             // GIF87a
+        || bytes.starts_with(b"GIF89a")                             // GIF89a
+        || (bytes.starts_with(b"RIFF") && bytes.len() > 11 && &bytes[8..12] == b"WEBP"); // WebP
+
+    if !is_valid_image {
+        return Err(ApiError::ParseError(
+            "Downloaded data does not appear to be a valid image format".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Image bytes with the ETag the server sent along, if any
+pub struct DownloadedImage {
+    pub bytes: Vec<u8>,
+    pub etag: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct ImageInfo {
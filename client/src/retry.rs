@@ -0,0 +1,109 @@
+use super::{ApiError, ApiResult};
+use std::future::Future;
+use std::time::Duration;
+
+/// How often idempotent requests (GET, PUT, DELETE) are repeated after the server couldn't be
+/// reached or failed with a 5xx, waiting twice as long before every further attempt.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    /// Longest wait between two attempts, however many retries came before
+    pub max_backoff: Duration,
+    /// Share of every wait that is random, from 0.0 to 1.0, so clients that lost the
+    /// connection together don't all come back at the same moment
+    pub jitter: f64,
+}
+
+impl RetryPolicy {
+    /// Every request is sent once
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            jitter: 0.0,
+        }
+    }
+
+    fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        backoff.mul_f64(1.0 - jitter * fastrand::f64())
+    }
+
+    fn should_retry(error: &ApiError) -> bool {
+        matches!(error, ApiError::NetworkError(_) | ApiError::ServerError(_))
+    }
+
+    /// Runs `request` until it succeeds, fails in a way repeating won't fix, or the retries
+    /// are used up
+    pub(crate) async fn run<T, F, Fut>(&self, mut request: F) -> ApiResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ApiResult<T>>,
+    {
+        let mut retry = 0;
+        loop {
+            match request().await {
+                Err(error) if retry < self.max_retries && Self::should_retry(&error) => {
+                    sleep(self.backoff(retry)).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await;
+}
+
+impl Default for RetryPolicy {
+    /// Two retries, or `API_MAX_RETRIES`, starting at a quarter second
+    fn default() -> Self {
+        Self {
+            max_retries: std::env::var("API_MAX_RETRIES")
+                .ok()
+                .and_then(|retries| retries.parse().ok())
+                .unwrap_or(2),
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(5),
+            jitter: 0.5,
+        }
+    }
+}
+
+/// How long requests may take before they fail as a network error, which is retried like one
+#[derive(Debug, Clone)]
+pub struct Timeouts {
+    /// Establishing the connection, for every request
+    pub connect: Duration,
+    /// Whole requests answered with JSON. Transfers of files only have the connect timeout, as
+    /// large images can rightly take much longer.
+    pub request: Duration,
+}
+
+impl Default for Timeouts {
+    /// 10 and 30 seconds, or `API_CONNECT_TIMEOUT_SECS` and `API_REQUEST_TIMEOUT_SECS`
+    fn default() -> Self {
+        let seconds = |variable: &str, default: u64| {
+            let seconds = std::env::var(variable).ok().and_then(|seconds| seconds.parse().ok());
+            Duration::from_secs(seconds.unwrap_or(default))
+        };
+        Self {
+            connect: seconds("API_CONNECT_TIMEOUT_SECS", 10),
+            request: seconds("API_REQUEST_TIMEOUT_SECS", 30),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use super::{ApiClient, ApiResult};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A click prompt in image pixels. `label` is 1 for foreground and 0 for background.
+#[derive(Debug, Serialize, Clone)]
+pub struct PromptPoint {
+    pub x: f64,
+    pub y: f64,
+    pub label: i32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SegmentRequest {
+    pub points: Vec<PromptPoint>,
+    #[serde(rename = "box")]
+    pub prompt_box: Option<Vec<f64>>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SegmentResponse {
+    #[allow(dead_code)]
+    pub polygon: Vec<[f64; 2]>,
+    pub bbox: Vec<f64>,
+    #[allow(dead_code)]
+    pub area: f64,
+    pub score: Option<f64>,
+}
+
+pub struct SegmentationApi {
+    client: ApiClient,
+}
+
+impl SegmentationApi {
+    pub fn new() -> Self {
+        Self {
+            client: ApiClient::new(),
+        }
+    }
+
+    pub async fn segment(
+        &self,
+        jwt: &str,
+        project_id: Uuid,
+        task_id: Uuid,
+        request: &SegmentRequest,
+    ) -> ApiResult<SegmentResponse> {
+        let endpoint = format!("/projects/{}/tasks/{}/segment", project_id, task_id);
+        self.client.post(&endpoint, request, Some(jwt)).await
+    }
+}
+
+impl Default for SegmentationApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,80 @@
+use super::{ApiClient, ApiResult};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Completion of a project
+#[derive(Debug, Deserialize, Clone)]
+pub struct ProjectStats {
+    pub total_tasks: i64,
+    /// Tasks with at least one annotation
+    pub annotated_tasks: i64,
+    pub completed_tasks: i64,
+    /// Tasks first annotated since the `since` of the request
+    pub annotated_today: i64,
+    /// Tasks the user saved an annotation of since the `since` of the request
+    pub annotated_today_by_me: i64,
+}
+
+/// Where boxes are and how large, and which categories they are, over the latest save of every task
+#[derive(Debug, Deserialize, Clone)]
+pub struct AnnotationStats {
+    pub annotated_tasks: i64,
+    pub total_boxes: i64,
+    /// Boxes on images whose size isn't known, left out of the heatmap and the histograms
+    pub boxes_without_dimensions: i64,
+    /// Box centers counted on a `heatmap_cells` by `heatmap_cells` grid over the image, row by row
+    /// from the top left
+    pub heatmap_cells: i32,
+    pub center_heatmap: Vec<i64>,
+    /// Boxes counted by width relative to the image width, in equal bins from 0 to 1
+    pub width_histogram: Vec<i64>,
+    /// Boxes counted by height relative to the image height, in equal bins from 0 to 1
+    pub height_histogram: Vec<i64>,
+    /// Every category of the project, most used first
+    pub categories: Vec<CategoryDistribution>,
+    pub uncategorized_boxes: i64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct CategoryDistribution {
+    pub category_id: Uuid,
+    pub name: String,
+    pub color: Option<String>,
+    pub boxes: i64,
+    /// Tasks with at least one box of the category
+    pub tasks: i64,
+}
+
+pub struct StatsApi {
+    client: ApiClient,
+}
+
+impl StatsApi {
+    pub fn new() -> Self {
+        Self {
+            client: ApiClient::new(),
+        }
+    }
+
+    /// `since` is the start of the day the daily counts cover, usually local midnight
+    pub async fn get_project_stats(&self, jwt: &str, project_id: Uuid, since: DateTime<Utc>) -> ApiResult<ProjectStats> {
+        let endpoint = format!(
+            "/projects/{}/stats?since={}",
+            project_id,
+            since.to_rfc3339_opts(SecondsFormat::Secs, true)
+        );
+        self.client.get(&endpoint, Some(jwt)).await
+    }
+
+    pub async fn get_annotation_stats(&self, jwt: &str, project_id: &str) -> ApiResult<AnnotationStats> {
+        let endpoint = format!("/projects/{}/stats/annotations", project_id);
+        self.client.get(&endpoint, Some(jwt)).await
+    }
+}
+
+impl Default for StatsApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,75 @@
+use super::{ApiClient, ApiResult};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(dead_code)]
+pub struct UploadResponse {
+    pub upload_url: String,
+    pub key: String,
+}
+
+/// One folder of the project's storage
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct StorageFolder {
+    /// Keys of the files in the folder
+    pub objects: Vec<String>,
+    /// Subfolders, each ending with `/`
+    pub prefixes: Vec<String>,
+}
+
+/// `key` as one path segment, its slashes encoded so the server doesn't split it up
+fn key_segment(key: &str) -> String {
+    let mut url = reqwest::Url::parse("http://localhost/").expect("static URL parses");
+    if let Ok(mut segments) = url.path_segments_mut() {
+        segments.push(key);
+    }
+    url.path().trim_start_matches('/').to_string()
+}
+
+pub struct StorageApi {
+    client: ApiClient,
+}
+
+impl StorageApi {
+    pub fn new() -> Self {
+        Self {
+            client: ApiClient::new(),
+        }
+    }
+
+    /// Puts a file into the project's storage under `key`, counting the bytes sent in `sent`
+    pub async fn upload_file(
+        &self,
+        jwt: &str,
+        project_id: &str,
+        key: &str,
+        data: Vec<u8>,
+        content_type: &str,
+        sent: Arc<AtomicU64>,
+    ) -> ApiResult<UploadResponse> {
+        let endpoint = format!("/projects/{}/storage/upload", project_id);
+        let query = [("key", key), ("content_type", content_type)];
+        self.client.post_bytes_with_progress(&endpoint, &query, data, sent, Some(jwt)).await
+    }
+
+    /// Files and subfolders right under `prefix`, the root of the bucket when it is empty.
+    /// Owners and admins of the project only.
+    pub async fn list_folder(&self, jwt: &str, project_id: &str, prefix: &str) -> ApiResult<StorageFolder> {
+        let endpoint = format!("/projects/{}/storage", project_id);
+        let query = [("prefix", prefix), ("delimiter", "/")];
+        self.client.get_with_query(&endpoint, &query, Some(jwt)).await
+    }
+
+    pub async fn download_object(&self, jwt: &str, project_id: &str, key: &str) -> ApiResult<Vec<u8>> {
+        let endpoint = format!("/projects/{}/storage/{}", project_id, key_segment(key));
+        self.client.get_endpoint_bytes(&endpoint, Some(jwt)).await
+    }
+}
+
+impl Default for StorageApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
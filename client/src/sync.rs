@@ -8,43 +8,44 @@ pub struct SyncRequest {
     pub prefix: Option<String>,
     pub file_extensions: Option<Vec<String>>,
     pub overwrite_existing: Option<bool>,
+    pub skip_duplicates: Option<bool>,
 }
 
+/// A sync run as the server recorded it, its counts grow while it is running
 #[derive(Debug, Deserialize, Clone)]
-pub struct SyncResponse {
+pub struct SyncRun {
     pub sync_id: Uuid,
+    /// "running", "completed", "completed_with_errors" or "failed"
+    pub status: String,
     pub total_files: usize,
+    pub processed_files: usize,
     pub tasks_created: usize,
     pub tasks_skipped: usize,
     pub errors: Vec<String>,
     pub started_at: DateTime<Utc>,
-    pub completed_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
-#[allow(dead_code)]
-pub struct SyncProgress {
-    pub total_files: usize,
-    pub processed_files: usize,
-    pub tasks_created: usize,
-    pub tasks_skipped: usize,
+impl SyncRun {
+    pub fn is_running(&self) -> bool {
+        self.status == "running"
+    }
 }
 
+/// A page of the sync runs of a project, newest first
 #[derive(Debug, Deserialize, Clone)]
-#[allow(dead_code)]
-pub struct SyncStatus {
-    pub sync_id: Uuid,
-    pub status: String, // "pending", "running", "completed", "failed"
-    pub progress: Option<SyncProgress>,
-    pub error: Option<String>,
-    pub started_at: DateTime<Utc>,
-    pub completed_at: Option<DateTime<Utc>>,
+pub struct SyncHistory {
+    pub syncs: Vec<SyncRun>,
+    /// Sync runs of the project over all pages
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
 }
 
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-pub struct SyncStatusResponse {
-    pub sync_status: SyncStatus,
+impl SyncHistory {
+    pub fn page_count(&self) -> i64 {
+        ((self.total + self.per_page - 1) / self.per_page.max(1)).max(1)
+    }
 }
 
 pub struct SyncApi {
@@ -58,26 +59,26 @@ impl SyncApi {
         }
     }
 
+    /// Starts a sync, which goes on in the background of the server. Follow it with
+    /// `get_sync_status` until it stops running.
     pub async fn start_sync(
         &self,
         jwt: &str,
         project_id: Uuid,
         request: &SyncRequest,
-    ) -> ApiResult<SyncResponse> {
+    ) -> ApiResult<SyncRun> {
         let endpoint = format!("/projects/{}/sync", project_id);
         self.client.post(&endpoint, request, Some(jwt)).await
     }
 
-    #[allow(dead_code)]
     pub async fn get_sync_status(
         &self,
         jwt: &str,
         project_id: Uuid,
         sync_id: Uuid,
-    ) -> ApiResult<SyncStatus> {
+    ) -> ApiResult<SyncRun> {
         let endpoint = format!("/projects/{}/sync/{}", project_id, sync_id);
-        let response: SyncStatusResponse = self.client.get(&endpoint, Some(jwt)).await?;
-        Ok(response.sync_status)
+        self.client.get(&endpoint, Some(jwt)).await
     }
 
     #[allow(dead_code)]
@@ -91,20 +92,16 @@ impl SyncApi {
         self.client.post(&endpoint, &(), Some(jwt)).await
     }
 
-    #[allow(dead_code)]
+    /// `page` counts from 1
     pub async fn list_sync_history(
         &self,
         jwt: &str,
         project_id: Uuid,
-    ) -> ApiResult<Vec<SyncStatus>> {
-        let endpoint = format!("/projects/{}/sync/history", project_id);
-        #[derive(Deserialize)]
-        #[allow(dead_code)]
-        struct SyncHistoryResponse {
-            sync_history: Vec<SyncStatus>,
-        }
-        let response: SyncHistoryResponse = self.client.get(&endpoint, Some(jwt)).await?;
-        Ok(response.sync_history)
+        page: i64,
+        per_page: i64,
+    ) -> ApiResult<SyncHistory> {
+        let endpoint = format!("/projects/{}/syncs?page={}&per_page={}", project_id, page, per_page);
+        self.client.get(&endpoint, Some(jwt)).await
     }
 }
 
@@ -0,0 +1,445 @@
+use super::{ApiClient, ApiResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[allow(dead_code)]
+pub struct Task {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub resource_url: Option<String>,
+    pub status: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub completed_at: Option<String>,
+    #[serde(default)]
+    pub priority: Option<f64>,
+    #[serde(default)]
+    pub flag_reason: Option<String>,
+    #[serde(default)]
+    pub flag_note: Option<String>,
+    /// `image`, or `video` and `volume` for tasks annotated frame by frame
+    #[serde(default)]
+    pub media_type: String,
+    #[serde(default)]
+    pub frame_count: Option<i32>,
+    /// Dataset split, one of the codes in `SPLITS`
+    #[serde(default)]
+    pub split: Option<String>,
+    /// Storage key of the file the task shows, for `storage://` resource URLs
+    #[serde(default)]
+    pub source_key: Option<String>,
+    /// Set once a refresh found the source file changed or gone
+    #[serde(default)]
+    pub stale_at: Option<String>,
+    /// Own due date of the task, without it the deadline settings of the project apply
+    #[serde(default)]
+    pub due_at: Option<String>,
+}
+
+impl Task {
+    /// Single image tasks, including ones from before tasks had a media type
+    pub fn is_image(&self) -> bool {
+        !matches!(self.media_type.as_str(), "video" | "volume")
+    }
+
+    /// The source file changed since the task was made from it
+    pub fn is_stale(&self) -> bool {
+        self.stale_at.is_some()
+    }
+}
+
+/// Reason codes accepted by the flag endpoint, with their display labels
+pub const FLAG_REASONS: [(&str, &str); 4] = [
+    ("corrupted", "Image corrupted"),
+    ("wrong_dataset", "Wrong dataset"),
+    ("cant_tell", "Can't tell"),
+    ("other", "Other"),
+];
+
+pub fn flag_reason_label(reason: &str) -> &str {
+    FLAG_REASONS
+        .iter()
+        .find(|(code, _)| *code == reason)
+        .map(|(_, label)| *label)
+        .unwrap_or(reason)
+}
+
+/// Dataset splits tasks can be put in, with their display labels
+pub const SPLITS: [(&str, &str); 3] = [
+    ("train", "Train"),
+    ("val", "Validation"),
+    ("test", "Test"),
+];
+
+pub fn split_label(split: &str) -> &str {
+    SPLITS
+        .iter()
+        .find(|(code, _)| *code == split)
+        .map(|(_, label)| *label)
+        .unwrap_or(split)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskWithResolvedUrl {
+    #[serde(flatten)]
+    pub task: Task,
+    pub resolved_resource_url: Option<String>,
+    /// Boxes in the latest annotation, only filled in by the task list
+    #[serde(default)]
+    pub annotation_count: i64,
+    /// Names of the assigned annotators, only filled in by the task list
+    #[serde(default)]
+    pub assignees: Vec<String>,
+    /// When the task is due, by itself or by the deadline settings, only filled in by the task list
+    #[serde(default)]
+    pub due_date: Option<DateTime<Utc>>,
+    /// Past its due date and still to be done, only filled in by the task list
+    #[serde(default)]
+    pub overdue: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TasksListResponse {
+    pub tasks: Vec<TaskWithResolvedUrl>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateTaskRequest {
+    pub name: String,
+    pub resource_url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpdateTaskRequest {
+    pub name: String,
+    pub resource_url: Option<String>,
+    pub status: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FlagTaskRequest {
+    pub reason: String,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchTasksRequest<'a> {
+    pub task_ids: &'a [String],
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchStatusRequest<'a> {
+    pub task_ids: &'a [String],
+    pub status: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchAssignRequest<'a> {
+    pub task_ids: &'a [String],
+    pub user_ids: &'a [String],
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchSplitRequest<'a> {
+    pub task_ids: &'a [String],
+    pub split: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchDueRequest<'a> {
+    pub task_ids: &'a [String],
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchTasksResponse {
+    /// Tasks the operation changed
+    pub affected: u64,
+}
+
+/// A still frame extracted from a video task
+#[derive(Debug, Deserialize, Clone)]
+pub struct TaskFrame {
+    pub frame_index: i32,
+    #[allow(dead_code)]
+    pub resource_url: String,
+    pub timestamp_ms: i64,
+    pub resolved_resource_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaskFramesResponse {
+    /// `None` for slice stacks such as DICOM series
+    pub frame_rate: Option<f64>,
+    pub frames: Vec<TaskFrame>,
+}
+
+/// Deep-zoom pyramid of a large image. Level `max_level` is the full image, every level below
+/// halves it down to `min_level`, which fits in a single tile.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub struct TileInfo {
+    pub width: u32,
+    pub height: u32,
+    pub tile_size: u32,
+    pub min_level: u32,
+    pub max_level: u32,
+}
+
+/// What a refresh found out about the source file of a task
+#[derive(Debug, Deserialize, Clone)]
+pub struct RefreshTaskResponse {
+    pub task: Task,
+    /// The file in storage differs from the one the task was made from
+    pub changed: bool,
+    /// The file is no longer in storage
+    pub missing: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct TaskResponse {
+    pub task: Task,
+    pub resolved_resource_url: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct TasksApi {
+    client: ApiClient,
+}
+
+/// Filters of the task list, unset ones let every task through
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskFilter {
+    /// A flag reason code, "any" or "none"
+    pub flag: Option<String>,
+    /// Only tasks with or without a saved annotation
+    pub has_annotations: Option<bool>,
+    /// Only tasks whose latest annotation has a box or label of this category
+    pub contains_category: Option<Uuid>,
+    /// Only tasks whose source file changed, or only the ones still matching it
+    pub stale: Option<bool>,
+    /// Only tasks past their due date, or only the ones still in time
+    pub overdue: Option<bool>,
+}
+
+impl TaskFilter {
+    fn query(&self) -> String {
+        let mut params = Vec::new();
+        if let Some(flag) = &self.flag {
+            params.push(format!("flag={}", flag));
+        }
+        if let Some(has_annotations) = self.has_annotations {
+            params.push(format!("has_annotations={}", has_annotations));
+        }
+        if let Some(category_id) = self.contains_category {
+            params.push(format!("contains_category={}", category_id));
+        }
+        if let Some(stale) = self.stale {
+            params.push(format!("stale={}", stale));
+        }
+        if let Some(overdue) = self.overdue {
+            params.push(format!("overdue={}", overdue));
+        }
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("?{}", params.join("&"))
+        }
+    }
+}
+
+impl TasksApi {
+    pub fn new() -> Self {
+        Self {
+            client: ApiClient::new(),
+        }
+    }
+
+    pub async fn list_tasks(&self, jwt: &str, project_id: &str) -> ApiResult<Vec<TaskWithResolvedUrl>> {
+        self.list_tasks_with_flag(jwt, project_id, None).await
+    }
+
+    /// Lists tasks filtered by flag: a reason code, "any" or "none".
+    pub async fn list_tasks_with_flag(&self, jwt: &str, project_id: &str, flag: Option<&str>) -> ApiResult<Vec<TaskWithResolvedUrl>> {
+        let filter = TaskFilter {
+            flag: flag.map(str::to_string),
+            ..TaskFilter::default()
+        };
+        self.list_tasks_filtered(jwt, project_id, &filter).await
+    }
+
+    pub async fn list_tasks_filtered(&self, jwt: &str, project_id: &str, filter: &TaskFilter) -> ApiResult<Vec<TaskWithResolvedUrl>> {
+        let endpoint = format!("/projects/{}/tasks{}", project_id, filter.query());
+        let response: TasksListResponse = self.client.get(&endpoint, Some(jwt)).await?;
+        Ok(response.tasks)
+    }
+
+    /// Tasks a quality-control sample marked for review and nobody has reviewed yet
+    pub async fn list_tasks_for_review(&self, jwt: &str, project_id: &str) -> ApiResult<Vec<TaskWithResolvedUrl>> {
+        let endpoint = format!("/projects/{}/tasks?review=true", project_id);
+        let response: TasksListResponse = self.client.get(&endpoint, Some(jwt)).await?;
+        Ok(response.tasks)
+    }
+
+    pub async fn get_task(&self, jwt: &str, project_id: &str, task_id: &str) -> ApiResult<Task> {
+        let endpoint = format!("/projects/{}/tasks/{}", project_id, task_id);
+        let response: TaskResponse = self.client.get(&endpoint, Some(jwt)).await?;
+        Ok(response.task)
+    }
+
+    /// Fresh presigned URL of the task's image, for when the one from the task list has expired
+    pub async fn get_task_image_url(&self, jwt: &str, project_id: &str, task_id: &str) -> ApiResult<String> {
+        let endpoint = format!("/projects/{}/tasks/{}/image?refresh=true", project_id, task_id);
+        self.client.get_redirect_location(&endpoint, Some(jwt)).await
+    }
+
+    /// Next task to label: the highest priority unannotated task, or a random one when no scores were uploaded.
+    pub async fn get_next_random_unannotated_task(&self, jwt: &str, project_id: &str) -> ApiResult<Option<TaskWithResolvedUrl>> {
+        let endpoint = format!("/projects/{}/tasks?next_unannotated=true&random=true&order=priority", project_id);
+        let response: TasksListResponse = self.client.get(&endpoint, Some(jwt)).await?;
+        Ok(response.tasks.into_iter().next())
+    }
+
+    pub async fn create_task(
+        &self,
+        jwt: &str,
+        project_id: &str,
+        name: &str,
+        resource_url: Option<&str>,
+    ) -> ApiResult<Task> {
+        let request = CreateTaskRequest {
+            name: name.to_string(),
+            resource_url: resource_url.map(|s| s.to_string()),
+        };
+        let endpoint = format!("/projects/{}/tasks", project_id);
+        let response: TaskResponse = self.client.post(&endpoint, &request, Some(jwt)).await?;
+        Ok(response.task)
+    }
+
+    #[allow(dead_code)]
+    pub async fn update_task(
+        &self,
+        jwt: &str,
+        project_id: &str,
+        task_id: &str,
+        name: &str,
+        resource_url: Option<&str>,
+        status: &str,
+    ) -> ApiResult<Task> {
+        let request = UpdateTaskRequest {
+            name: name.to_string(),
+            resource_url: resource_url.map(|s| s.to_string()),
+            status: status.to_string(),
+        };
+        let endpoint = format!("/projects/{}/tasks/{}", project_id, task_id);
+        let response: TaskResponse = self.client.put(&endpoint, &request, Some(jwt)).await?;
+        Ok(response.task)
+    }
+
+    pub async fn flag_task(
+        &self,
+        jwt: &str,
+        project_id: &str,
+        task_id: &str,
+        reason: &str,
+        note: Option<&str>,
+    ) -> ApiResult<Task> {
+        let request = FlagTaskRequest {
+            reason: reason.to_string(),
+            note: note.map(|s| s.to_string()),
+        };
+        let endpoint = format!("/projects/{}/tasks/{}/flag", project_id, task_id);
+        let response: TaskResponse = self.client.put(&endpoint, &request, Some(jwt)).await?;
+        Ok(response.task)
+    }
+
+    /// Checks the source file of the task in storage and marks the task stale when it changed.
+    /// `accept` takes the file as it is now instead.
+    pub async fn refresh_task(&self, jwt: &str, project_id: &str, task_id: &str, accept: bool) -> ApiResult<RefreshTaskResponse> {
+        let endpoint = format!("/projects/{}/tasks/{}/refresh?accept={}", project_id, task_id, accept);
+        self.client.post(&endpoint, &(), Some(jwt)).await
+    }
+
+    /// Frames of a video task in order, empty for image tasks.
+    pub async fn list_frames(&self, jwt: &str, project_id: &str, task_id: &str) -> ApiResult<TaskFramesResponse> {
+        let endpoint = format!("/projects/{}/tasks/{}/frames", project_id, task_id);
+        self.client.get(&endpoint, Some(jwt)).await
+    }
+
+    /// Tile pyramid of a large image task, `ApiError::NotFound` when the image is served whole.
+    pub async fn get_tile_info(&self, jwt: &str, project_id: &str, task_id: &str) -> ApiResult<TileInfo> {
+        let endpoint = format!("/projects/{}/tasks/{}/tiles", project_id, task_id);
+        self.client.get(&endpoint, Some(jwt)).await
+    }
+
+    /// JPEG bytes of one tile of the pyramid.
+    pub async fn get_tile(&self, jwt: &str, project_id: &str, task_id: &str, level: u32, col: u32, row: u32) -> ApiResult<Vec<u8>> {
+        let endpoint = format!("/projects/{}/tasks/{}/tiles/{}/{}/{}", project_id, task_id, level, col, row);
+        self.client.get_endpoint_bytes(&endpoint, Some(jwt)).await
+    }
+
+    /// Small JPEG of the task's image, or of the first frame of a video or volume.
+    pub async fn get_thumbnail(&self, jwt: &str, project_id: &str, task_id: &str) -> ApiResult<Vec<u8>> {
+        let endpoint = format!("/projects/{}/tasks/{}/thumbnail", project_id, task_id);
+        self.client.get_endpoint_bytes(&endpoint, Some(jwt)).await
+    }
+
+    pub async fn unflag_task(&self, jwt: &str, project_id: &str, task_id: &str) -> ApiResult<()> {
+        let endpoint = format!("/projects/{}/tasks/{}/flag", project_id, task_id);
+        self.client.delete(&endpoint, Some(jwt)).await
+    }
+
+    /// Takes the task off the review queue
+    pub async fn complete_review(&self, jwt: &str, project_id: &str, task_id: &str) -> ApiResult<()> {
+        let endpoint = format!("/projects/{}/tasks/{}/review", project_id, task_id);
+        self.client.delete(&endpoint, Some(jwt)).await
+    }
+
+    pub async fn batch_delete(&self, jwt: &str, project_id: &str, task_ids: &[String]) -> ApiResult<u64> {
+        let endpoint = format!("/projects/{}/tasks/batch/delete", project_id);
+        let response: BatchTasksResponse = self.client.post(&endpoint, &BatchTasksRequest { task_ids }, Some(jwt)).await?;
+        Ok(response.affected)
+    }
+
+    pub async fn batch_set_status(&self, jwt: &str, project_id: &str, task_ids: &[String], status: &str) -> ApiResult<u64> {
+        let endpoint = format!("/projects/{}/tasks/batch/status", project_id);
+        let response: BatchTasksResponse = self.client.post(&endpoint, &BatchStatusRequest { task_ids, status }, Some(jwt)).await?;
+        Ok(response.affected)
+    }
+
+    /// Replaces the assignees of the tasks, no `user_ids` unassigns them.
+    pub async fn batch_assign(&self, jwt: &str, project_id: &str, task_ids: &[String], user_ids: &[String]) -> ApiResult<u64> {
+        let endpoint = format!("/projects/{}/tasks/batch/assign", project_id);
+        let response: BatchTasksResponse = self.client.post(&endpoint, &BatchAssignRequest { task_ids, user_ids }, Some(jwt)).await?;
+        Ok(response.affected)
+    }
+
+    /// Puts the tasks in a split, or takes them out of theirs with `split = None`.
+    pub async fn batch_set_split(&self, jwt: &str, project_id: &str, task_ids: &[String], split: Option<&str>) -> ApiResult<u64> {
+        let endpoint = format!("/projects/{}/tasks/batch/split", project_id);
+        let response: BatchTasksResponse = self.client.post(&endpoint, &BatchSplitRequest { task_ids, split }, Some(jwt)).await?;
+        Ok(response.affected)
+    }
+
+    /// Gives the tasks a due date, or leaves them to the deadline settings with `due_at = None`.
+    pub async fn batch_set_due(&self, jwt: &str, project_id: &str, task_ids: &[String], due_at: Option<DateTime<Utc>>) -> ApiResult<u64> {
+        let endpoint = format!("/projects/{}/tasks/batch/due", project_id);
+        let response: BatchTasksResponse = self.client.post(&endpoint, &BatchDueRequest { task_ids, due_at }, Some(jwt)).await?;
+        Ok(response.affected)
+    }
+
+    #[allow(dead_code)]
+    pub async fn delete_task(&self, jwt: &str, project_id: &str, task_id: &str) -> ApiResult<()> {
+        let endpoint = format!("/projects/{}/tasks/{}", project_id, task_id);
+        self.client.delete(&endpoint, Some(jwt)).await
+    }
+}
+
+impl Default for TasksApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,49 @@
+use super::{ApiClient, ApiResult};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(dead_code)]
+pub struct TemplateCategory {
+    pub name: String,
+    pub supercategory: Option<String>,
+    pub color: Option<String>,
+    pub coco_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(dead_code)]
+pub struct ProjectTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub categories: Vec<TemplateCategory>,
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TemplatesListResponse {
+    pub templates: Vec<ProjectTemplate>,
+}
+
+pub struct TemplatesApi {
+    client: ApiClient,
+}
+
+impl TemplatesApi {
+    pub fn new() -> Self {
+        Self {
+            client: ApiClient::new(),
+        }
+    }
+
+    pub async fn list_templates(&self, jwt: &str) -> ApiResult<Vec<ProjectTemplate>> {
+        let response: TemplatesListResponse = self.client.get("/templates", Some(jwt)).await?;
+        Ok(response.templates)
+    }
+}
+
+impl Default for TemplatesApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
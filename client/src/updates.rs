@@ -0,0 +1,131 @@
+//! Release manifest the desktop app compares its version with. It is a JSON file published
+//! next to the installers, on any web server:
+//!
+//! ```json
+//! {
+//!   "version": "0.3.0",
+//!   "published_at": "2026-10-01",
+//!   "changelog": "- Faster tile loading\n- Fixed saving rotated boxes",
+//!   "downloads": {
+//!     "windows": { "url": "https://…/fast-tag-0.3.0.msi", "sha256": "9f86d0…" },
+//!     "macos": { "url": "https://…/fast-tag-0.3.0.dmg", "sha256": "2c26b4…" }
+//!   }
+//! }
+//! ```
+//!
+//! The manifest and the installers are only fetched over https, and an installer is only
+//! handed out once it matches its checksum.
+
+use super::{ApiClient, ApiError, ApiResult};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseManifest {
+    pub version: String,
+    #[serde(default)]
+    pub published_at: Option<String>,
+    /// What changed, in plain text
+    #[serde(default)]
+    pub changelog: String,
+    /// Installer per OS, keyed like `std::env::consts::OS`
+    #[serde(default)]
+    pub downloads: HashMap<String, ReleaseDownload>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseDownload {
+    pub url: String,
+    /// Hex SHA-256 of the installer
+    pub sha256: String,
+}
+
+impl ReleaseDownload {
+    /// Last segment of the URL path, `None` when it can't name a file in the downloads folder
+    pub fn file_name(&self) -> Option<String> {
+        let url = reqwest::Url::parse(&self.url).ok()?;
+        let name = url.path_segments()?.next_back()?;
+        let usable = !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\']);
+        usable.then(|| name.to_string())
+    }
+}
+
+impl ReleaseManifest {
+    /// Whether the release is newer than `version`. Versions that aren't `major.minor.patch`
+    /// are never newer.
+    pub fn is_newer_than(&self, version: &str) -> bool {
+        match (parse_version(&self.version), parse_version(version)) {
+            (Some(release), Some(current)) => compare_versions(&release, &current) == Ordering::Greater,
+            _ => false,
+        }
+    }
+
+    /// Installer for the OS the caller runs on
+    pub fn download_for_this_os(&self) -> Option<&ReleaseDownload> {
+        self.downloads.get(std::env::consts::OS)
+    }
+}
+
+/// Releases are only trusted from where nobody on the way can swap them
+fn require_https(url: &str) -> ApiResult<()> {
+    match reqwest::Url::parse(url) {
+        Ok(url) if url.scheme() == "https" => Ok(()),
+        _ => Err(ApiError::BadRequest(format!("{} is not an https URL", url))),
+    }
+}
+
+/// Numbers of `1.2.3` or `v1.2.3`, a `-beta` or `+build` suffix left aside
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let version = version.trim().trim_start_matches('v');
+    let version = version.split(['-', '+']).next()?;
+    version.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// Missing parts count as 0, so `1.2` equals `1.2.0`
+fn compare_versions(a: &[u64], b: &[u64]) -> Ordering {
+    (0..a.len().max(b.len()))
+        .map(|index| a.get(index).unwrap_or(&0).cmp(b.get(index).unwrap_or(&0)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+pub struct UpdatesApi {
+    client: ApiClient,
+}
+
+impl UpdatesApi {
+    pub fn new() -> Self {
+        Self {
+            client: ApiClient::new(),
+        }
+    }
+
+    /// The manifest at the https `url`, which needs no login
+    pub async fn fetch_manifest(&self, url: &str) -> ApiResult<ReleaseManifest> {
+        require_https(url)?;
+        let bytes = self.client.get_bytes(url).await?;
+        serde_json::from_slice(&bytes).map_err(|e| ApiError::ParseError(format!("Malformed release manifest: {}", e)))
+    }
+
+    /// The installer, whole, after checking it against its SHA-256
+    pub async fn download_installer(&self, download: &ReleaseDownload) -> ApiResult<Vec<u8>> {
+        require_https(&download.url)?;
+        let bytes = self.client.get_bytes(&download.url).await?;
+        let checksum: String = Sha256::digest(&bytes).iter().map(|byte| format!("{:02x}", byte)).collect();
+        if !checksum.eq_ignore_ascii_case(download.sha256.trim()) {
+            return Err(ApiError::ServerError(format!(
+                "The installer at {} doesn't match its SHA-256 checksum",
+                download.url
+            )));
+        }
+        Ok(bytes)
+    }
+}
+
+impl Default for UpdatesApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
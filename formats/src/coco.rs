@@ -0,0 +1,514 @@
+//! The COCO annotation format, as the server exports and imports it
+
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Problems of one kind listed in a validation report, the others are only counted
+pub const MAX_REPORTED_ISSUES: usize = 100;
+
+// COCO format data structures
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct CocoExport {
+    pub info: CocoInfo,
+    pub licenses: Vec<CocoLicense>,
+    pub images: Vec<CocoImage>,
+    pub annotations: Vec<CocoAnnotation>,
+    pub categories: Vec<CocoCategory>,
+}
+
+/// Real-world files often fill in only some of the info block, missing fields are left empty
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct CocoInfo {
+    pub year: i32,
+    pub version: String,
+    pub description: String,
+    pub contributor: String,
+    pub url: String,
+    pub date_created: String,
+    /// fast-tag extension fields, only in exports that asked for them
+    #[serde(rename = "x-fasttag", default, skip_serializing_if = "Option::is_none")]
+    pub x_fasttag: Option<FastTagInfo>,
+}
+
+/// The `x-fasttag` block of the info: which project the file was exported from
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct FastTagInfo {
+    pub project_id: String,
+    /// Version of the extension fields, raised when their meaning changes
+    pub schema_version: i32,
+}
+
+/// The `x-fasttag` block of an annotation, to trace the label back to the save it comes from,
+/// who made it and where the review of its task stands
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct FastTagAnnotation {
+    pub task_id: String,
+    /// The save of the task the box is part of
+    pub annotation_id: String,
+    /// Email of the annotator, unset when the account was deleted
+    pub annotated_by: Option<String>,
+    pub annotated_at: Option<String>,
+    /// Status of the task: `pending`, `in_progress`, `completed` or `cancelled`
+    pub task_status: String,
+    /// When quality control marked the task for review, unset when no review is pending
+    pub review_requested_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct CocoLicense {
+    pub id: i32,
+    pub name: String,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct CocoImage {
+    pub id: i64,
+    pub width: i32,
+    pub height: i32,
+    pub file_name: String,
+    /// 0 when the image names no license
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub license: i32,
+    #[serde(default)]
+    pub flickr_url: Option<String>,
+    #[serde(default)]
+    pub coco_url: Option<String>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub date_captured: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct CocoAnnotation {
+    pub id: i64,
+    pub image_id: i64,
+    pub category_id: i32,
+    #[serde(default)]
+    pub segmentation: CocoSegmentation,
+    /// Read rounded to whole pixels, files written by other tools use fractional areas
+    #[serde(default, deserialize_with = "rounded_area")]
+    pub area: i32,
+    pub bbox: Vec<f64>,
+    #[serde(default)]
+    pub iscrowd: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<serde_json::Value>,
+    /// fast-tag extension fields, only in exports that asked for them
+    #[serde(rename = "x-fasttag", default, skip_serializing_if = "Option::is_none")]
+    pub x_fasttag: Option<FastTagAnnotation>,
+}
+
+/// Outline of an object: polygons, or a run-length encoded mask as crowd annotations use
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(untagged)]
+pub enum CocoSegmentation {
+    Polygons(Vec<Vec<f64>>),
+    Rle(CocoRle),
+}
+
+impl Default for CocoSegmentation {
+    fn default() -> Self {
+        Self::Polygons(Vec::new())
+    }
+}
+
+impl CocoSegmentation {
+    pub fn polygons(&self) -> &[Vec<f64>] {
+        match self {
+            Self::Polygons(polygons) => polygons,
+            Self::Rle(_) => &[],
+        }
+    }
+
+    pub fn rle(&self) -> Option<&CocoRle> {
+        match self {
+            Self::Polygons(_) => None,
+            Self::Rle(rle) => Some(rle),
+        }
+    }
+}
+
+/// A run-length encoded mask. The runs go down the columns of the image and start with the
+/// background.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct CocoRle {
+    /// `[height, width]` of the image
+    pub size: [u32; 2],
+    pub counts: RleCounts,
+}
+
+/// Run lengths as numbers, or packed into a string the way pycocotools compresses them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(untagged)]
+pub enum RleCounts {
+    Uncompressed(Vec<u32>),
+    Compressed(String),
+}
+
+/// Unpacks run lengths compressed the way pycocotools does: every run is written as the
+/// difference to the run two before it, in 5-bit chunks stored as characters from `0`.
+pub fn decode_rle_counts(counts: &str) -> Result<Vec<u32>, String> {
+    let bytes = counts.as_bytes();
+    let mut runs: Vec<i64> = Vec::new();
+    let mut position = 0;
+
+    while position < bytes.len() {
+        let mut value = 0i64;
+        let mut shift = 0;
+        loop {
+            let Some(&byte) = bytes.get(position) else {
+                return Err("RLE counts end in the middle of a run".to_string());
+            };
+            let chunk = match byte.checked_sub(48) {
+                Some(chunk) if chunk < 64 => chunk as i64,
+                _ => return Err(format!("Invalid character in RLE counts at {}", position)),
+            };
+            if shift >= 60 {
+                return Err(format!("RLE run at {} is too long", position));
+            }
+            position += 1;
+            value |= (chunk & 0x1f) << shift;
+            shift += 5;
+            if chunk & 0x20 == 0 {
+                if chunk & 0x10 != 0 {
+                    value |= -1i64 << shift;
+                }
+                break;
+            }
+        }
+        if runs.len() > 2 {
+            value += runs[runs.len() - 2];
+        }
+        runs.push(value);
+    }
+
+    runs.into_iter()
+        .map(|run| u32::try_from(run).map_err(|_| format!("Invalid RLE run length {}", run)))
+        .collect()
+}
+
+/// Packs run lengths the way pycocotools does, see [`decode_rle_counts`]
+pub fn encode_rle_counts(counts: &[u32]) -> String {
+    let mut encoded = String::new();
+    for (index, &count) in counts.iter().enumerate() {
+        let mut value = count as i64;
+        if index > 2 {
+            value -= counts[index - 2] as i64;
+        }
+        loop {
+            let mut chunk = value & 0x1f;
+            value >>= 5;
+            let more = if chunk & 0x10 != 0 { value != -1 } else { value != 0 };
+            if more {
+                chunk |= 0x20;
+            }
+            encoded.push((chunk as u8 + 48) as char);
+            if !more {
+                break;
+            }
+        }
+    }
+    encoded
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct CocoCategory {
+    pub id: i32,
+    pub name: String,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub supercategory: String,
+}
+
+/// Reads `null` like a missing field
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+fn rounded_area<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i32, D::Error> {
+    Ok(f64::deserialize(deserializer)?.round() as i32)
+}
+
+// Import specific structures
+/// A COCO file as read for an import, where `info` and `licenses` may be left out
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CocoImport {
+    pub info: Option<CocoInfo>,
+    pub licenses: Option<Vec<CocoLicense>>,
+    pub images: Vec<CocoImage>,
+    pub annotations: Vec<CocoAnnotation>,
+    pub categories: Vec<CocoCategory>,
+}
+
+/// What is wrong in a COCO file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    NoCategories,
+    NoImages,
+    DuplicateCategoryId,
+    DuplicateImageId,
+    DuplicateAnnotationId,
+    UnknownCategory,
+    MissingImage,
+    /// Not `[x, y, width, height]`, or with a negative size or a value that isn't a number
+    MalformedBbox,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ValidationIssue {
+    pub kind: IssueKind,
+    pub message: String,
+}
+
+/// Everything that keeps a COCO file from being imported, instead of only the first problem
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ValidationReport {
+    pub categories: usize,
+    pub images: usize,
+    pub annotations: usize,
+    /// In the order they were found, at most `MAX_REPORTED_ISSUES` of each kind
+    pub issues: Vec<ValidationIssue>,
+    /// Problems of each kind, including the ones left out of `issues`
+    pub issue_counts: BTreeMap<IssueKind, usize>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.issue_counts.is_empty()
+    }
+
+    fn add(&mut self, kind: IssueKind, message: String) {
+        let count = self.issue_counts.entry(kind).or_default();
+        *count += 1;
+        if *count <= MAX_REPORTED_ISSUES {
+            self.issues.push(ValidationIssue { kind, message });
+        }
+    }
+}
+
+/// Checks that a COCO file can be imported: it has categories and images with unique IDs, and
+/// every annotation has a unique ID and refers to them with a `[x, y, width, height]` box
+pub fn validate(coco_data: &CocoImport) -> Result<(), String> {
+    match validation_report(coco_data).issues.into_iter().next() {
+        Some(issue) => Err(issue.message),
+        None => Ok(()),
+    }
+}
+
+/// Goes through the whole file and collects every problem, where `validate` stops at the first
+pub fn validation_report(coco_data: &CocoImport) -> ValidationReport {
+    let mut report = ValidationReport {
+        categories: coco_data.categories.len(),
+        images: coco_data.images.len(),
+        annotations: coco_data.annotations.len(),
+        ..Default::default()
+    };
+
+    // Check for required fields
+    if coco_data.categories.is_empty() {
+        report.add(IssueKind::NoCategories, "No categories found in COCO data".to_string());
+    }
+
+    if coco_data.images.is_empty() {
+        report.add(IssueKind::NoImages, "No images found in COCO data".to_string());
+    }
+
+    // Validate category IDs are unique
+    let mut category_ids = HashSet::new();
+    for category in &coco_data.categories {
+        if !category_ids.insert(category.id) {
+            report.add(IssueKind::DuplicateCategoryId, format!("Duplicate category ID: {}", category.id));
+        }
+    }
+
+    // Validate image IDs are unique
+    let mut image_ids = HashSet::new();
+    for image in &coco_data.images {
+        if !image_ids.insert(image.id) {
+            report.add(IssueKind::DuplicateImageId, format!("Duplicate image ID: {}", image.id));
+        }
+    }
+
+    // Validate annotations are unique and reference valid categories and images
+    let mut annotation_ids = HashSet::new();
+    for annotation in &coco_data.annotations {
+        if !annotation_ids.insert(annotation.id) {
+            report.add(IssueKind::DuplicateAnnotationId, format!("Duplicate annotation ID: {}", annotation.id));
+        }
+        if !category_ids.contains(&annotation.category_id) {
+            report.add(
+                IssueKind::UnknownCategory,
+                format!("Annotation {} references invalid category ID: {}", annotation.id, annotation.category_id),
+            );
+        }
+        if !image_ids.contains(&annotation.image_id) {
+            report.add(
+                IssueKind::MissingImage,
+                format!("Annotation {} references invalid image ID: {}", annotation.id, annotation.image_id),
+            );
+        }
+        if annotation.bbox.len() != 4 {
+            report.add(IssueKind::MalformedBbox, format!("Annotation {} has invalid bbox format", annotation.id));
+        } else if annotation.bbox.iter().any(|value| !value.is_finite())
+            || annotation.bbox[2] < 0.0
+            || annotation.bbox[3] < 0.0
+        {
+            report.add(
+                IssueKind::MalformedBbox,
+                format!("Annotation {} has a negative or non-numeric bbox: {:?}", annotation.id, annotation.bbox),
+            );
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn annotation(id: i64, image_id: i64, category_id: i32, bbox: Vec<f64>) -> CocoAnnotation {
+        CocoAnnotation {
+            id,
+            image_id,
+            category_id,
+            segmentation: CocoSegmentation::default(),
+            area: 0,
+            bbox,
+            iscrowd: 0,
+            attributes: None,
+            x_fasttag: None,
+        }
+    }
+
+    fn coco(annotations: Vec<CocoAnnotation>) -> CocoImport {
+        CocoImport {
+            info: None,
+            licenses: None,
+            images: vec![CocoImage {
+                id: 1,
+                width: 640,
+                height: 480,
+                file_name: "a.jpg".to_string(),
+                license: 0,
+                flickr_url: None,
+                coco_url: None,
+                date_captured: String::new(),
+            }],
+            annotations,
+            categories: vec![CocoCategory { id: 1, name: "cat".to_string(), supercategory: String::new() }],
+        }
+    }
+
+    #[test]
+    fn test_validation_report_lists_every_problem() {
+        let report = validation_report(&coco(vec![
+            annotation(1, 1, 1, vec![0.0, 0.0, 10.0, 10.0]),
+            annotation(1, 2, 1, vec![0.0, 0.0, 10.0, 10.0]),
+            annotation(2, 1, 7, vec![0.0, 0.0, -10.0, 10.0]),
+            annotation(3, 1, 1, vec![0.0, 0.0, 10.0]),
+        ]));
+
+        assert!(!report.is_valid());
+        assert_eq!(report.annotations, 4);
+        assert_eq!(report.issue_counts[&IssueKind::DuplicateAnnotationId], 1);
+        assert_eq!(report.issue_counts[&IssueKind::MissingImage], 1);
+        assert_eq!(report.issue_counts[&IssueKind::UnknownCategory], 1);
+        assert_eq!(report.issue_counts[&IssueKind::MalformedBbox], 2);
+        assert_eq!(report.issues.len(), 5);
+
+        // `validate` gives the first problem only
+        let error = validate(&coco(vec![annotation(1, 2, 1, vec![0.0, 0.0, 1.0, 1.0])])).unwrap_err();
+        assert_eq!(error, "Annotation 1 references invalid image ID: 2");
+    }
+
+    #[test]
+    fn test_validation_report_caps_listed_issues() {
+        let annotations = (0..MAX_REPORTED_ISSUES as i64 + 5)
+            .map(|id| annotation(id, 9, 1, vec![0.0, 0.0, 1.0, 1.0]))
+            .collect();
+        let report = validation_report(&coco(annotations));
+
+        assert_eq!(report.issues.len(), MAX_REPORTED_ISSUES);
+        assert_eq!(report.issue_counts[&IssueKind::MissingImage], MAX_REPORTED_ISSUES + 5);
+
+        // Empty boxes are fine
+        assert!(validation_report(&coco(vec![annotation(1, 1, 1, vec![0.0, 0.0, 0.0, 0.0])])).is_valid());
+    }
+
+    #[test]
+    fn test_reads_files_of_other_tools() {
+        let coco: CocoImport = serde_json::from_value(serde_json::json!({
+            "info": {"description": "only a description"},
+            "licenses": [{"id": 3, "name": "CC BY 4.0"}],
+            "images": [
+                {"id": 1, "width": 640, "height": 480, "file_name": "a.jpg", "license": null},
+                {"id": 2, "width": 640, "height": 480, "file_name": "b.jpg", "date_captured": null}
+            ],
+            "annotations": [
+                {"id": 1, "image_id": 1, "category_id": 1, "segmentation": [[0.0, 0.0, 10.0, 0.0, 10.0, 10.0]], "area": 49.6, "bbox": [0.0, 0.0, 10.0, 10.0], "iscrowd": 0},
+                {"id": 2, "image_id": 2, "category_id": 1, "segmentation": {"counts": [5, 10, 5], "size": [480, 640]}, "area": 10, "bbox": [1.0, 1.0, 2.0, 5.0], "iscrowd": 1},
+                {"id": 3, "image_id": 2, "category_id": 1, "segmentation": {"counts": "0`0Q1", "size": [480, 640]}, "bbox": [3.0, 3.0, 1.0, 1.0], "iscrowd": 1}
+            ],
+            "categories": [{"id": 1, "name": "cat", "supercategory": null}]
+        }))
+        .unwrap();
+
+        assert_eq!(coco.info.as_ref().unwrap().description, "only a description");
+        assert_eq!(coco.licenses.as_ref().unwrap()[0].url, "");
+        assert_eq!(coco.images[0].license, 0);
+        assert_eq!(coco.images[1].date_captured, "");
+        assert_eq!(coco.annotations[0].segmentation.polygons().len(), 1);
+        assert_eq!(coco.annotations[0].area, 50);
+        assert!(coco.annotations[1].segmentation.polygons().is_empty());
+        assert_eq!(
+            coco.annotations[1].segmentation.rle(),
+            Some(&CocoRle { size: [480, 640], counts: RleCounts::Uncompressed(vec![5, 10, 5]) })
+        );
+        assert_eq!(coco.annotations[2].segmentation.rle().unwrap().counts, RleCounts::Compressed("0`0Q1".to_string()));
+        assert_eq!(coco.annotations[1].iscrowd, 1);
+        assert_eq!(coco.annotations[2].area, 0);
+        assert_eq!(coco.categories[0].supercategory, "");
+        assert!(validation_report(&coco).is_valid());
+    }
+
+    #[test]
+    fn test_rle_counts_round_trip() {
+        // Differences to the run two before going negative, and runs long enough for several chunks
+        let runs = vec![0, 5, 3, 100_000, 2, 7, 1, 65_536, 40];
+        let encoded = encode_rle_counts(&runs);
+        assert_eq!(decode_rle_counts(&encoded).unwrap(), runs);
+
+        // pycocotools' encoding of a 4x4 mask with a 2x2 square in its middle
+        assert_eq!(encode_rle_counts(&[5, 2, 2, 2, 5]), "52203");
+        assert_eq!(decode_rle_counts("52203").unwrap(), vec![5, 2, 2, 2, 5]);
+        assert_eq!(decode_rle_counts("").unwrap(), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_decode_rle_counts_rejects_broken_strings() {
+        assert!(decode_rle_counts("5 2").is_err());
+        assert!(decode_rle_counts("P").is_err()); // The run goes on past the end
+        assert!(decode_rle_counts("5N").is_err()); // Negative run
+    }
+}
@@ -0,0 +1,8 @@
+//! Annotation file formats of fast-tag. The server imports and exports COCO with the types in
+//! [`coco`]; [`yolo`] and [`voc`] convert between COCO and the other common dataset formats, for
+//! the CLI and the Python bindings. The editor packs the masks it paints with the RLE codec of
+//! [`coco`].
+
+pub mod coco;
+pub mod voc;
+pub mod yolo;
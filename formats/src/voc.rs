@@ -0,0 +1,217 @@
+//! Pascal VOC annotations: an XML file per image with its size and the corners of its boxes in
+//! pixels
+
+use crate::coco::{CocoAnnotation, CocoCategory, CocoImage, CocoImport, CocoSegmentation};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "annotation")]
+pub struct VocAnnotation {
+    pub filename: String,
+    pub size: VocSize,
+    #[serde(default, rename = "object")]
+    pub objects: Vec<VocObject>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VocSize {
+    pub width: u32,
+    pub height: u32,
+    #[serde(default = "default_depth")]
+    pub depth: u32,
+}
+
+fn default_depth() -> u32 {
+    3
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VocObject {
+    /// Category name
+    pub name: String,
+    #[serde(default)]
+    pub difficult: u8,
+    pub bndbox: VocBox,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VocBox {
+    pub xmin: f64,
+    pub ymin: f64,
+    pub xmax: f64,
+    pub ymax: f64,
+}
+
+impl VocAnnotation {
+    pub fn from_xml(xml: &str) -> Result<Self, String> {
+        quick_xml::de::from_str(xml).map_err(|e| format!("Invalid VOC XML: {}", e))
+    }
+
+    pub fn to_xml(&self) -> Result<String, String> {
+        quick_xml::se::to_string(self).map_err(|e| format!("Failed to write VOC XML: {}", e))
+    }
+
+    /// Name of the XML file of the image, which only keeps the file name of task names with folders
+    pub fn xml_file_name(&self) -> String {
+        let stem = Path::new(&self.filename)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.filename.clone());
+        format!("{}.xml", stem)
+    }
+}
+
+/// Converts the boxes of a COCO file, one annotation per image including those without boxes
+pub fn from_coco(coco: &CocoImport) -> Vec<VocAnnotation> {
+    let category_names: HashMap<i32, &str> = coco
+        .categories
+        .iter()
+        .map(|category| (category.id, category.name.as_str()))
+        .collect();
+
+    let mut objects: HashMap<i64, Vec<VocObject>> = HashMap::new();
+    for annotation in &coco.annotations {
+        let Some(name) = category_names.get(&annotation.category_id) else {
+            continue;
+        };
+        let &[x, y, width, height] = annotation.bbox.as_slice() else {
+            continue;
+        };
+        objects.entry(annotation.image_id).or_default().push(VocObject {
+            name: name.to_string(),
+            difficult: 0,
+            bndbox: VocBox {
+                xmin: x,
+                ymin: y,
+                xmax: x + width,
+                ymax: y + height,
+            },
+        });
+    }
+
+    coco.images
+        .iter()
+        .map(|image| VocAnnotation {
+            filename: image.file_name.clone(),
+            size: VocSize {
+                width: image.width.max(0) as u32,
+                height: image.height.max(0) as u32,
+                depth: default_depth(),
+            },
+            objects: objects.remove(&image.id).unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Converts annotations to COCO for an import. Categories get the IDs from 1 in the order their
+/// names first appear and each image becomes the task named after its file.
+pub fn to_coco(annotations: &[VocAnnotation]) -> CocoImport {
+    let mut categories: Vec<CocoCategory> = Vec::new();
+    let mut images = Vec::new();
+    let mut coco_annotations = Vec::new();
+
+    for (index, annotation) in annotations.iter().enumerate() {
+        let image_id = index as i64 + 1;
+        for object in &annotation.objects {
+            let category_id = match categories.iter().find(|category| category.name == object.name) {
+                Some(category) => category.id,
+                None => {
+                    let id = categories.len() as i32 + 1;
+                    categories.push(CocoCategory {
+                        id,
+                        name: object.name.clone(),
+                        supercategory: String::new(),
+                    });
+                    id
+                }
+            };
+
+            let VocBox { xmin, ymin, xmax, ymax } = object.bndbox;
+            let bbox = vec![xmin, ymin, xmax - xmin, ymax - ymin];
+            coco_annotations.push(CocoAnnotation {
+                id: coco_annotations.len() as i64 + 1,
+                image_id,
+                category_id,
+                segmentation: CocoSegmentation::default(),
+                area: (bbox[2] * bbox[3]).round() as i32,
+                bbox,
+                iscrowd: 0,
+                attributes: None,
+                x_fasttag: None,
+            });
+        }
+
+        images.push(CocoImage {
+            id: image_id,
+            width: annotation.size.width as i32,
+            height: annotation.size.height as i32,
+            file_name: annotation.filename.clone(),
+            license: 0,
+            flickr_url: None,
+            coco_url: None,
+            date_captured: String::new(),
+        });
+    }
+
+    CocoImport {
+        info: None,
+        licenses: None,
+        images,
+        annotations: coco_annotations,
+        categories,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = r#"<annotation>
+        <folder>VOC2012</folder>
+        <filename>2007_000027.jpg</filename>
+        <size><width>486</width><height>500</height><depth>3</depth></size>
+        <segmented>0</segmented>
+        <object>
+            <name>person</name>
+            <pose>Unspecified</pose>
+            <truncated>0</truncated>
+            <difficult>0</difficult>
+            <bndbox><xmin>174</xmin><ymin>101</ymin><xmax>349</xmax><ymax>351</ymax></bndbox>
+        </object>
+        <object>
+            <name>dog</name>
+            <bndbox><xmin>10</xmin><ymin>20</ymin><xmax>30</xmax><ymax>60</ymax></bndbox>
+        </object>
+    </annotation>"#;
+
+    #[test]
+    fn test_from_xml_ignores_unused_elements() {
+        let annotation = VocAnnotation::from_xml(XML).unwrap();
+
+        assert_eq!(annotation.filename, "2007_000027.jpg");
+        assert_eq!(annotation.size.width, 486);
+        assert_eq!(annotation.objects.len(), 2);
+        assert_eq!(annotation.objects[1].name, "dog");
+        assert_eq!(annotation.xml_file_name(), "2007_000027.xml");
+    }
+
+    #[test]
+    fn test_to_coco_converts_corners_to_boxes() {
+        let coco = to_coco(&[VocAnnotation::from_xml(XML).unwrap()]);
+
+        assert_eq!(coco.categories.len(), 2);
+        assert_eq!(coco.annotations[0].bbox, vec![174.0, 101.0, 175.0, 250.0]);
+        assert_eq!(coco.annotations[1].category_id, 2);
+        assert!(crate::coco::validate(&coco).is_ok());
+    }
+
+    #[test]
+    fn test_round_trip_through_coco_and_xml() {
+        let original = VocAnnotation::from_xml(XML).unwrap();
+        let converted = from_coco(&to_coco(std::slice::from_ref(&original)));
+        let reparsed = VocAnnotation::from_xml(&converted[0].to_xml().unwrap()).unwrap();
+        assert_eq!(reparsed, original);
+    }
+}
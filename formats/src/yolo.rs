@@ -0,0 +1,219 @@
+//! YOLO datasets: the class names in index order, and a label file per image with one line per
+//! box holding the class index and the centre and size of the box relative to the image size
+
+use crate::coco::{CocoAnnotation, CocoCategory, CocoImage, CocoImport, CocoSegmentation};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// File of a dataset listing the class names, one per line
+pub const CLASSES_FILE: &str = "classes.txt";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct YoloDataset {
+    pub classes: Vec<String>,
+    pub images: Vec<YoloImage>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct YoloImage {
+    pub file_name: String,
+    pub width: u32,
+    pub height: u32,
+    /// Content of the label file, empty for an image without boxes
+    pub labels: String,
+}
+
+/// Label file of an image. Task names can contain the folders of the storage key, the label
+/// only keeps the file name.
+pub fn label_file_name(file_name: &str) -> String {
+    let stem = Path::new(file_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| file_name.to_string());
+    format!("{}.txt", stem)
+}
+
+/// Converts the boxes of a COCO file. Classes are numbered in the order of the category IDs;
+/// boxes of images without a size are left out, since they can't be made relative.
+pub fn from_coco(coco: &CocoImport) -> YoloDataset {
+    let mut categories: Vec<&CocoCategory> = coco.categories.iter().collect();
+    categories.sort_by_key(|category| category.id);
+    let classes: HashMap<i32, usize> = categories
+        .iter()
+        .enumerate()
+        .map(|(class, category)| (category.id, class))
+        .collect();
+    let images: HashMap<i64, &CocoImage> = coco.images.iter().map(|image| (image.id, image)).collect();
+
+    let mut labels: HashMap<i64, String> = HashMap::new();
+    for annotation in &coco.annotations {
+        let (Some(image), Some(class)) = (images.get(&annotation.image_id), classes.get(&annotation.category_id)) else {
+            continue;
+        };
+        let &[x, y, width, height] = annotation.bbox.as_slice() else {
+            continue;
+        };
+        if image.width <= 0 || image.height <= 0 {
+            continue;
+        }
+
+        let (image_width, image_height) = (image.width as f64, image.height as f64);
+        labels.entry(image.id).or_default().push_str(&format!(
+            "{} {:.6} {:.6} {:.6} {:.6}\n",
+            class,
+            (x + width / 2.0) / image_width,
+            (y + height / 2.0) / image_height,
+            width / image_width,
+            height / image_height,
+        ));
+    }
+
+    YoloDataset {
+        classes: categories.iter().map(|category| category.name.clone()).collect(),
+        images: coco
+            .images
+            .iter()
+            .map(|image| YoloImage {
+                file_name: image.file_name.clone(),
+                width: image.width.max(0) as u32,
+                height: image.height.max(0) as u32,
+                labels: labels.remove(&image.id).unwrap_or_default(),
+            })
+            .collect(),
+    }
+}
+
+/// Class index and `[x_center, y_center, width, height]` of a label line, `None` for anything
+/// but a box, such as the polygons of segmentation datasets
+fn parse_label(line: &str) -> Option<(usize, [f64; 4])> {
+    let mut fields = line.split_whitespace();
+    let class = fields.next()?.parse().ok()?;
+    let mut values = [0.0; 4];
+    for value in &mut values {
+        *value = fields.next()?.parse().ok()?;
+    }
+    if fields.next().is_some() {
+        return None;
+    }
+    Some((class, values))
+}
+
+/// Converts a dataset to COCO for an import. Categories get the IDs from 1 in class order and
+/// each image becomes the task named after its file.
+pub fn to_coco(dataset: &YoloDataset) -> Result<CocoImport, String> {
+    let categories: Vec<CocoCategory> = dataset
+        .classes
+        .iter()
+        .enumerate()
+        .map(|(class, name)| CocoCategory {
+            id: class as i32 + 1,
+            name: name.clone(),
+            supercategory: String::new(),
+        })
+        .collect();
+
+    let mut images = Vec::new();
+    let mut annotations = Vec::new();
+    for (index, image) in dataset.images.iter().enumerate() {
+        let image_id = index as i64 + 1;
+        let (width, height) = (image.width as f64, image.height as f64);
+
+        for (line_index, line) in image.labels.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let location = format!("{}, line {}", label_file_name(&image.file_name), line_index + 1);
+            let (class, [x_center, y_center, box_width, box_height]) = parse_label(line)
+                .ok_or_else(|| format!("{}: expected `class x_center y_center width height`", location))?;
+            let category = categories
+                .get(class)
+                .ok_or_else(|| format!("{}: class {} is not in the {} classes", location, class, categories.len()))?;
+
+            let bbox = vec![
+                (x_center - box_width / 2.0) * width,
+                (y_center - box_height / 2.0) * height,
+                box_width * width,
+                box_height * height,
+            ];
+            annotations.push(CocoAnnotation {
+                id: annotations.len() as i64 + 1,
+                image_id,
+                category_id: category.id,
+                segmentation: CocoSegmentation::default(),
+                area: (bbox[2] * bbox[3]).round() as i32,
+                bbox,
+                iscrowd: 0,
+                attributes: None,
+                x_fasttag: None,
+            });
+        }
+
+        images.push(CocoImage {
+            id: image_id,
+            width: image.width as i32,
+            height: image.height as i32,
+            file_name: image.file_name.clone(),
+            license: 0,
+            flickr_url: None,
+            coco_url: None,
+            date_captured: String::new(),
+        });
+    }
+
+    Ok(CocoImport {
+        info: None,
+        licenses: None,
+        images,
+        annotations,
+        categories,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dataset() -> YoloDataset {
+        YoloDataset {
+            classes: vec!["cat".to_string(), "dog".to_string()],
+            images: vec![YoloImage {
+                file_name: "photos/pet.jpg".to_string(),
+                width: 200,
+                height: 100,
+                labels: "1 0.500000 0.500000 0.200000 0.400000\n".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_to_coco_converts_relative_boxes_to_pixels() {
+        let coco = to_coco(&dataset()).unwrap();
+
+        assert_eq!(coco.categories.len(), 2);
+        assert_eq!(coco.images[0].file_name, "photos/pet.jpg");
+        assert_eq!(coco.annotations.len(), 1);
+        assert_eq!(coco.annotations[0].category_id, 2);
+        assert_eq!(coco.annotations[0].bbox, vec![80.0, 30.0, 40.0, 40.0]);
+        assert_eq!(coco.annotations[0].area, 1600);
+        assert!(crate::coco::validate(&coco).is_ok());
+    }
+
+    #[test]
+    fn test_round_trip_through_coco() {
+        let original = dataset();
+        let converted = from_coco(&to_coco(&original).unwrap());
+        assert_eq!(converted, original);
+        assert_eq!(label_file_name(&converted.images[0].file_name), "pet.txt");
+    }
+
+    #[test]
+    fn test_to_coco_rejects_unknown_classes_and_polygons() {
+        let mut unknown_class = dataset();
+        unknown_class.images[0].labels = "2 0.5 0.5 0.1 0.1\n".to_string();
+        assert!(to_coco(&unknown_class).unwrap_err().contains("class 2"));
+
+        let mut polygon = dataset();
+        polygon.images[0].labels = "0 0.1 0.1 0.2 0.1 0.2 0.2\n".to_string();
+        assert!(to_coco(&polygon).is_err());
+    }
+}
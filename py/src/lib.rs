@@ -0,0 +1,89 @@
+//! Python module `fast_tag` with the annotation formats of `fast-tag-formats`, the code the
+//! server validates imports with. COCO goes in and out as JSON text, so it works with exports
+//! straight from the API and `json.loads`/`json.dumps` on the Python side.
+//!
+//! ```python
+//! import fast_tag
+//!
+//! coco = open("export.json").read()
+//! fast_tag.validate_coco(coco)
+//! classes, labels = fast_tag.coco_to_yolo(coco)
+//! ```
+
+use fast_tag_formats::coco::{self, CocoImport};
+use fast_tag_formats::voc::{self, VocAnnotation};
+use fast_tag_formats::yolo::{self, YoloDataset, YoloImage};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+fn parse_coco(coco_json: &str) -> PyResult<CocoImport> {
+    serde_json::from_str(coco_json).map_err(|e| PyValueError::new_err(format!("Invalid COCO JSON: {}", e)))
+}
+
+fn to_json(coco: &CocoImport) -> PyResult<String> {
+    serde_json::to_string(coco).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Raises `ValueError` when the server would reject the COCO file on import
+#[pyfunction]
+fn validate_coco(coco_json: &str) -> PyResult<()> {
+    coco::validate(&parse_coco(coco_json)?).map_err(PyValueError::new_err)
+}
+
+/// Returns the class names in index order and the content of each label file by file name
+#[pyfunction]
+fn coco_to_yolo(coco_json: &str) -> PyResult<(Vec<String>, HashMap<String, String>)> {
+    let dataset = yolo::from_coco(&parse_coco(coco_json)?);
+    let labels = dataset
+        .images
+        .into_iter()
+        .map(|image| (yolo::label_file_name(&image.file_name), image.labels))
+        .collect();
+    Ok((dataset.classes, labels))
+}
+
+/// Takes the class names and `(file_name, width, height, labels)` of each image, returns COCO JSON
+#[pyfunction]
+fn yolo_to_coco(classes: Vec<String>, images: Vec<(String, u32, u32, String)>) -> PyResult<String> {
+    let dataset = YoloDataset {
+        classes,
+        images: images
+            .into_iter()
+            .map(|(file_name, width, height, labels)| YoloImage { file_name, width, height, labels })
+            .collect(),
+    };
+    to_json(&yolo::to_coco(&dataset).map_err(PyValueError::new_err)?)
+}
+
+/// Returns the Pascal VOC XML of each image by XML file name
+#[pyfunction]
+fn coco_to_voc(coco_json: &str) -> PyResult<HashMap<String, String>> {
+    voc::from_coco(&parse_coco(coco_json)?)
+        .iter()
+        .map(|annotation| {
+            let xml = annotation.to_xml().map_err(PyValueError::new_err)?;
+            Ok((annotation.xml_file_name(), xml))
+        })
+        .collect()
+}
+
+/// Takes the Pascal VOC XML documents of the images, returns COCO JSON
+#[pyfunction]
+fn voc_to_coco(documents: Vec<String>) -> PyResult<String> {
+    let annotations = documents
+        .iter()
+        .map(|xml| VocAnnotation::from_xml(xml).map_err(PyValueError::new_err))
+        .collect::<PyResult<Vec<_>>>()?;
+    to_json(&voc::to_coco(&annotations))
+}
+
+#[pymodule]
+fn fast_tag(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(validate_coco, module)?)?;
+    module.add_function(wrap_pyfunction!(coco_to_yolo, module)?)?;
+    module.add_function(wrap_pyfunction!(yolo_to_coco, module)?)?;
+    module.add_function(wrap_pyfunction!(coco_to_voc, module)?)?;
+    module.add_function(wrap_pyfunction!(voc_to_coco, module)?)?;
+    Ok(())
+}